@@ -6,15 +6,41 @@
 #![forbid(unsafe_code)]
 
 pub use wildside_core::{
-    Diagnostics, InterestProfile, PoiStore, PointOfInterest, Route, SolveError, SolveRequest,
-    SolveResponse, Solver, Theme, TravelTimeError, TravelTimeMatrix, TravelTimeProvider,
+    AccessibilityRequirements, CancellationToken, Diagnostics, Engine, InterestProfile,
+    MatrixShapeError, OpeningHours, PoiStore, PointOfInterest, RestBreak, Route, RouteLeg,
+    RouteStop, Shortfall, SolveError, SolveRequest, SolveResponse, Solver, StartChoice, Theme,
+    Timestamp, TravelTimeError, TravelTimeMatrix, TravelTimeProvider, VisitedPrefix, WarmUpCheck,
+    WarmUpReport, WarmUpStage, WeekTime, Weekday, WheelchairAccess,
+};
+
+#[cfg(feature = "async")]
+pub use wildside_core::engine::{BlockingExecutor, ThreadExecutor};
+#[cfg(feature = "async")]
+pub use wildside_core::travel_time::PrefetchedTravelTimeProvider;
+#[cfg(feature = "async")]
+pub use wildside_core::{AsyncEngine, AsyncTravelTimeProvider, SyncTravelTimeAdapter};
+
+#[cfg(feature = "routing-graph")]
+pub use wildside_core::travel_time::{
+    GraphTravelTimeProvider, PrecomputedTravelTimeProvider, PrecomputedTravelTimes, RoutingGraph,
+};
+
+#[cfg(feature = "serde")]
+pub use wildside_core::travel_time::{
+    RecordingTravelTimeProvider, ReplayTravelTimeProvider, TravelTimeRecording,
 };
 
 #[cfg(feature = "store-sqlite")]
 pub use wildside_core::{SqlitePoiStore, SqlitePoiStoreError};
 
+#[cfg(feature = "telemetry")]
+pub use wildside_core::telemetry::{PrivacyPolicy, TelemetryError, TelemetryRollup};
+
 #[cfg(feature = "solver-vrp")]
 pub use wildside_solver_vrp::VrpSolver;
 
 #[cfg(feature = "solver-ortools")]
 pub use wildside_solver_ortools::OrtoolsSolver;
+
+#[cfg(feature = "solver-greedy")]
+pub use wildside_solver_greedy::GreedySolver;
@@ -0,0 +1,296 @@
+//! Golden routes over a city-scale fixture, for every solver backend.
+//!
+//! The small golden routes pin exact answers on hand-built matrices. These
+//! cases instead solve representative requests against an extract of central
+//! Edinburgh, ingested into a `pois.db` and `pois.rstar` read through
+//! [`wildside_engine::SqlitePoiStore`]. Routes are scored by the
+//! production user relevance scorer and walked over the extract's street
+//! network. They guard perceived route quality: each route must visit the
+//! landmarks a visitor would expect, make enough stops, and land in the
+//! score band recorded for its backend without exceeding the time budget.
+//!
+//! Every case runs against each backend the enabled features build: the
+//! `vrp-core` solver, the greedy insertion solver, and the CP-SAT solver. The
+//! same expectations must hold when the travel times are replayed from a
+//! recording, which is how matrices captured from a live routing service are
+//! fed to these tests.
+
+#![cfg(all(
+    feature = "store-sqlite",
+    any(
+        feature = "solver-vrp",
+        feature = "solver-greedy",
+        feature = "solver-ortools"
+    )
+))]
+
+mod city_routes_support;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use rstest::rstest;
+use wildside_engine::{
+    RecordingTravelTimeProvider, ReplayTravelTimeProvider, SolveError, SolveRequest, SolveResponse,
+    Solver, TravelTimeProvider,
+};
+
+use city_routes_support::{
+    CityCase, city_scorer, city_travel_times, load_city_cases, open_city_store,
+};
+
+/// Cases exercised by [`city_golden_route`].
+const CASES: [&str; 5] = [
+    "old_town_history_hour",
+    "new_town_art_round_trip",
+    "holyrood_nature_point_to_point",
+    "grassmarket_museum_half_hour",
+    "meadows_to_stockbridge_mixed",
+];
+
+/// Solver backends built by the enabled features.
+#[derive(Debug, Clone, Copy)]
+enum Backend {
+    #[cfg(feature = "solver-vrp")]
+    Vrp,
+    #[cfg(feature = "solver-greedy")]
+    Greedy,
+    #[cfg(feature = "solver-ortools")]
+    Ortools,
+}
+
+impl Backend {
+    const ALL: &[Self] = &[
+        #[cfg(feature = "solver-vrp")]
+        Self::Vrp,
+        #[cfg(feature = "solver-greedy")]
+        Self::Greedy,
+        #[cfg(feature = "solver-ortools")]
+        Self::Ortools,
+    ];
+
+    /// Key of the backend's expectations in `cases.json`.
+    const fn name(self) -> &'static str {
+        match self {
+            #[cfg(feature = "solver-vrp")]
+            Self::Vrp => "vrp",
+            #[cfg(feature = "solver-greedy")]
+            Self::Greedy => "greedy",
+            #[cfg(feature = "solver-ortools")]
+            Self::Ortools => "ortools",
+        }
+    }
+
+    /// Whether repeated solves of a request must return the same route.
+    ///
+    /// CP-SAT stops at a wall-clock limit, so how far its search gets
+    /// depends on the machine's load.
+    const fn is_deterministic(self) -> bool {
+        match self {
+            #[cfg(feature = "solver-ortools")]
+            Self::Ortools => false,
+            #[cfg_attr(
+                not(any(feature = "solver-vrp", feature = "solver-greedy")),
+                expect(unreachable_patterns, reason = "only CP-SAT is enabled")
+            )]
+            _ => true,
+        }
+    }
+
+    /// Solve `request` over the city store with `travel_times`, scoring with
+    /// the city's claims and popularity.
+    fn solve<T>(self, travel_times: T, request: &SolveRequest) -> Result<SolveResponse, SolveError>
+    where
+        T: TravelTimeProvider + Send + Sync,
+    {
+        let store = open_city_store();
+        let scorer = city_scorer();
+        match self {
+            #[cfg(feature = "solver-vrp")]
+            Self::Vrp => {
+                wildside_engine::VrpSolver::new(store, travel_times, scorer).solve(request)
+            }
+            #[cfg(feature = "solver-greedy")]
+            Self::Greedy => {
+                wildside_engine::GreedySolver::new(store, travel_times, scorer).solve(request)
+            }
+            #[cfg(feature = "solver-ortools")]
+            Self::Ortools => {
+                wildside_engine::OrtoolsSolver::new(store, travel_times, scorer).solve(request)
+            }
+        }
+    }
+}
+
+fn city_case(name: &str) -> CityCase {
+    load_city_cases()
+        .into_iter()
+        .find(|case| case.name == name)
+        .unwrap_or_else(|| panic!("city case {name} is missing from cases.json"))
+}
+
+#[rstest]
+#[case("old_town_history_hour")]
+#[case("new_town_art_round_trip")]
+#[case("holyrood_nature_point_to_point")]
+#[case("grassmarket_museum_half_hour")]
+#[case("meadows_to_stockbridge_mixed")]
+fn city_golden_route(#[case] name: &str) {
+    let case = city_case(name);
+    let request = case.request();
+    for &backend in Backend::ALL {
+        let response = backend
+            .solve(city_travel_times(), &request)
+            .unwrap_or_else(|err| panic!("{name} ({backend:?}): city route should solve: {err:?}"));
+        assert_meets_expectations(&case, backend, &request, &response);
+    }
+}
+
+/// Solve a case once while recording travel times, then again from the
+/// recording alone.
+#[rstest]
+fn city_golden_route_replays_recorded_travel_times() {
+    let case = city_case("holyrood_nature_point_to_point");
+    let request = case.request();
+    for &backend in Backend::ALL {
+        let recorder = Arc::new(RecordingTravelTimeProvider::new(city_travel_times()));
+        backend
+            .solve(Arc::clone(&recorder), &request)
+            .unwrap_or_else(|err| panic!("{backend:?}: recorded city route should solve: {err:?}"));
+        let mut fixture = Vec::new();
+        recorder
+            .recording()
+            .to_writer(&mut fixture)
+            .unwrap_or_else(|err| panic!("write recording: {err}"));
+
+        let replay = ReplayTravelTimeProvider::from_reader(fixture.as_slice())
+            .unwrap_or_else(|err| panic!("read recording: {err}"));
+        let response = backend
+            .solve(replay, &request)
+            .unwrap_or_else(|err| panic!("{backend:?}: replayed city route should solve: {err:?}"));
+        assert_meets_expectations(&case, backend, &request, &response);
+    }
+}
+
+/// Solving the same request twice, even with a freshly opened store, returns
+/// the same route from every deterministic backend.
+#[rstest]
+#[case("old_town_history_hour")]
+#[case("meadows_to_stockbridge_mixed")]
+fn city_routes_are_deterministic(#[case] name: &str) {
+    let request = city_case(name).request();
+    for &backend in Backend::ALL
+        .iter()
+        .filter(|backend| backend.is_deterministic())
+    {
+        let solve = || {
+            backend
+                .solve(city_travel_times(), &request)
+                .unwrap_or_else(|err| {
+                    panic!("{name} ({backend:?}): city route should solve: {err:?}")
+                })
+        };
+
+        let first = solve();
+        let second = solve();
+
+        let ids = |response: &SolveResponse| -> Vec<u64> {
+            response.route.pois().iter().map(|poi| poi.id).collect()
+        };
+        assert_eq!(
+            ids(&first),
+            ids(&second),
+            "{name} ({backend:?}): routes differ"
+        );
+        assert_eq!(
+            first.score.to_bits(),
+            second.score.to_bits(),
+            "{name} ({backend:?}): scores differ"
+        );
+        assert_eq!(
+            first.route.legs(),
+            second.route.legs(),
+            "{name} ({backend:?}): legs differ"
+        );
+    }
+}
+
+fn assert_meets_expectations(
+    case: &CityCase,
+    backend: Backend,
+    request: &SolveRequest,
+    response: &SolveResponse,
+) {
+    let name = &case.name;
+    let expected = case.expected(backend.name());
+    let visited: HashSet<&str> = response
+        .route
+        .pois()
+        .iter()
+        .filter_map(|poi| poi.tags.get("name").map(String::as_str))
+        .collect();
+    let missing: Vec<&str> = expected
+        .must_visit
+        .iter()
+        .map(String::as_str)
+        .filter(|landmark| !visited.contains(landmark))
+        .collect();
+    assert!(
+        missing.is_empty(),
+        "{name} ({backend:?}): route skipped landmarks {missing:?} (visited {visited:?})"
+    );
+    assert!(
+        response.route.pois().len() >= expected.min_stops,
+        "{name} ({backend:?}): route made {} stops, expected at least {}",
+        response.route.pois().len(),
+        expected.min_stops
+    );
+    assert!(
+        response.score >= expected.min_score && response.score <= expected.max_score,
+        "{name} ({backend:?}): score {} outside expected range [{}, {}]",
+        response.score,
+        expected.min_score,
+        expected.max_score
+    );
+    let budget = Duration::from_mins(u64::from(request.duration_minutes));
+    assert!(
+        response.route.total_duration() <= budget,
+        "{name} ({backend:?}): route duration {:?} exceeds budget {budget:?}",
+        response.route.total_duration()
+    );
+}
+
+/// Ensure every case in `cases.json` is listed above and records
+/// expectations for every backend.
+#[rstest]
+fn all_city_cases_are_tested() {
+    let listed: HashSet<&str> = CASES.into_iter().collect();
+    let cases = load_city_cases();
+    let recorded: HashSet<&str> = cases.iter().map(|case| case.name.as_str()).collect();
+
+    let untested: Vec<_> = recorded
+        .iter()
+        .filter(|name| !listed.contains(*name))
+        .collect();
+
+    assert!(
+        untested.is_empty(),
+        "city cases exist but are not tested: {untested:?}. Add them to the #[case] list."
+    );
+    assert_eq!(
+        recorded.len(),
+        CASES.len(),
+        "cases.json has duplicate names"
+    );
+    for case in &cases {
+        let mut backends: Vec<&str> = case.expected.keys().map(String::as_str).collect();
+        backends.sort_unstable();
+        assert_eq!(
+            backends,
+            ["greedy", "ortools", "vrp"],
+            "{}: expectations must cover every backend",
+            case.name
+        );
+    }
+}
@@ -0,0 +1,313 @@
+//! City-scale fixture for golden route tests.
+//!
+//! The fixture is a small extract of central Edinburgh:
+//! `edinburgh.osm.pbf.b64` holds some fifty landmarks between Stockbridge and
+//! Arthur's Seat with the streets and paths linking them, and `wikidata.json`
+//! the Wikidata entities those landmarks link to. Only those inputs are
+//! checked in: the first test to need them runs both through the ingest
+//! pipeline and the popularity scorer, writing `pois.db`, `pois.rstar`,
+//! `links.filter`, `graph.bin`, and `popularity.bin` under Cargo's target
+//! directory. Every backend therefore reads POIs through [`SqlitePoiStore`],
+//! scores them with the production [`UserRelevanceScorer`], and walks between
+//! them over the extract's street network with [`GraphTravelTimeProvider`],
+//! exactly as it would against a deployed artefact set.
+//!
+//! `tests/golden_routes/city/generate.sh` rebuilds the inputs from
+//! OpenStreetMap and Wikidata.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use base64::{Engine as _, engine::general_purpose};
+use camino::{Utf8Path, Utf8PathBuf};
+use geo::Coord;
+use serde::Deserialize;
+use wildside_core::store::{SpatialIndexOptions, write_spatial_index_with_options};
+use wildside_core::travel_time::GraphTravelTimeProvider;
+use wildside_core::{InterestProfile, PointOfInterest, SolveRequest, SqlitePoiStore, Theme};
+use wildside_data::wikidata::etl::{
+    ClaimPropertySet, ClaimSelection, PoiEntityLinks, stream_linked_entity_claims,
+};
+use wildside_data::wikidata::store::{
+    DEFAULT_CLAIMS_CHUNK_SIZE, finalise_artifacts, persist_claims_iter_to_path,
+    write_link_filter_for_path,
+};
+use wildside_data::{build_routing_graph, ingest_osm_pbf_report, persist_pois_to_sqlite};
+use wildside_scorer::{
+    PopularityWeights, ScoreWeights, ThemeClaimMapping, UserRelevanceScorer, write_popularity_file,
+};
+
+/// Artefacts [`ingest_city_extract`] writes, and the tests read back.
+const CITY_ARTEFACTS: [&str; 5] = [
+    "pois.db",
+    "pois.rstar",
+    "links.filter",
+    "graph.bin",
+    "popularity.bin",
+];
+
+/// Path of a file in the city fixture directory.
+fn city_fixture_path(file_name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden_routes/city")
+        .join(file_name)
+}
+
+fn utf8_fixture_path(file_name: &str) -> Utf8PathBuf {
+    Utf8PathBuf::from_path_buf(city_fixture_path(file_name))
+        .unwrap_or_else(|path| panic!("fixture path {} is not UTF-8", path.display()))
+}
+
+/// Directory holding the artefacts ingested from the extract.
+///
+/// The extract is ingested once per test run, into Cargo's target directory,
+/// so every test reads the same artefacts without any being checked in.
+///
+/// # Panics
+///
+/// Panics if ingesting the extract fails.
+fn city_artefacts() -> &'static Utf8Path {
+    static ARTEFACTS: OnceLock<Utf8PathBuf> = OnceLock::new();
+    ARTEFACTS.get_or_init(|| {
+        let dir = Utf8PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("city_golden_routes");
+        fs::create_dir_all(&dir).unwrap_or_else(|err| panic!("create {dir}: {err}"));
+        ingest_city_extract(&dir);
+        dir
+    })
+}
+
+/// Open the ingested `pois.db` and `pois.rstar` as a store.
+///
+/// The store is opened read-only, so tests never modify the artefacts.
+///
+/// # Panics
+///
+/// Panics if the artefacts cannot be written or opened.
+#[must_use]
+pub fn open_city_store() -> SqlitePoiStore {
+    let dir = city_artefacts();
+    SqlitePoiStore::open(dir.join("pois.db"), dir.join("pois.rstar"))
+        .unwrap_or_else(|err| panic!("open city store in {dir}: {err}"))
+}
+
+/// Score POIs against the ingested claims and popularity, matching themes
+/// with `theme_mapping.toml`, as `wildside solve` would.
+///
+/// # Panics
+///
+/// Panics if the artefacts or the mapping cannot be loaded.
+#[must_use]
+pub fn city_scorer() -> UserRelevanceScorer {
+    let mapping = ThemeClaimMapping::from_path(&utf8_fixture_path("theme_mapping.toml"))
+        .unwrap_or_else(|err| panic!("load city theme mapping: {err}"));
+    let dir = city_artefacts();
+    UserRelevanceScorer::from_paths(
+        &dir.join("pois.db"),
+        &dir.join("popularity.bin"),
+        mapping,
+        ScoreWeights::default(),
+    )
+    .and_then(|scorer| scorer.with_link_filter_path(&dir.join("links.filter")))
+    .unwrap_or_else(|err| panic!("open city scorer: {err}"))
+}
+
+/// Walk between POIs over the ingested street network.
+///
+/// # Panics
+///
+/// Panics if `graph.bin` cannot be loaded.
+#[must_use]
+pub fn city_travel_times() -> GraphTravelTimeProvider {
+    GraphTravelTimeProvider::load(city_artefacts().join("graph.bin").as_std_path())
+        .unwrap_or_else(|err| panic!("load city routing graph: {err}"))
+}
+
+/// Ingest the checked-in extract into `dir`, replacing any artefacts there.
+///
+/// Follows `wildside ingest` with its default claim properties, minus the
+/// step-free graph, travel-time table, and manifest the tests never read,
+/// then computes `popularity.bin` with the default weights.
+///
+/// # Panics
+///
+/// Panics if any stage of the pipeline fails.
+fn ingest_city_extract(dir: &Utf8Path) {
+    for artefact in CITY_ARTEFACTS {
+        let path = dir.join(artefact);
+        if path.exists() {
+            fs::remove_file(&path).unwrap_or_else(|err| panic!("remove stale {path}: {err}"));
+        }
+    }
+    let extract = decode_extract(dir);
+    let report = ingest_osm_pbf_report(extract.as_std_path())
+        .unwrap_or_else(|err| panic!("ingest city extract: {err}"));
+    let pois_db = dir.join("pois.db");
+    persist_pois_to_sqlite(&pois_db, &report.pois)
+        .unwrap_or_else(|err| panic!("persist city POIs: {err}"));
+    persist_city_claims(&pois_db, &report.pois);
+    finalise_artifacts(&pois_db).unwrap_or_else(|err| panic!("finalise {pois_db}: {err}"));
+    write_link_filter_for_path(&pois_db, dir.join("links.filter"))
+        .unwrap_or_else(|err| panic!("write city link filter: {err}"));
+    let index_options = SpatialIndexOptions {
+        embed_tags: false,
+        ..SpatialIndexOptions::default()
+    };
+    write_spatial_index_with_options(
+        dir.join("pois.rstar").as_std_path(),
+        &report.pois,
+        index_options,
+    )
+    .unwrap_or_else(|err| panic!("write city spatial index: {err}"));
+    build_routing_graph(extract.as_std_path())
+        .unwrap_or_else(|err| panic!("build city routing graph: {err}"))
+        .write(dir.join("graph.bin").as_std_path())
+        .unwrap_or_else(|err| panic!("write city routing graph: {err}"));
+    fs::remove_file(&extract).unwrap_or_else(|err| panic!("remove {extract}: {err}"));
+    write_popularity_file(
+        &pois_db,
+        &dir.join("popularity.bin"),
+        PopularityWeights::default(),
+    )
+    .unwrap_or_else(|err| panic!("write city popularity: {err}"));
+}
+
+/// Decode `edinburgh.osm.pbf.b64` into `dir`, returning the decoded path.
+fn decode_extract(dir: &Utf8Path) -> Utf8PathBuf {
+    let encoded = fs::read_to_string(city_fixture_path("edinburgh.osm.pbf.b64"))
+        .unwrap_or_else(|err| panic!("read city extract: {err}"));
+    let cleaned: String = encoded
+        .chars()
+        .filter(|ch| !ch.is_ascii_whitespace())
+        .collect();
+    let decoded = general_purpose::STANDARD
+        .decode(cleaned)
+        .unwrap_or_else(|err| panic!("decode city extract: {err}"));
+    let path = dir.join("edinburgh.osm.pbf");
+    fs::write(&path, decoded).unwrap_or_else(|err| panic!("write {path}: {err}"));
+    path
+}
+
+/// Stream the claims of the entities `pois` link to from `wikidata.json`
+/// into `pois_db`.
+fn persist_city_claims(pois_db: &Utf8Path, pois: &[PointOfInterest]) {
+    let links = PoiEntityLinks::from_pois(pois);
+    let filter = links.to_link_filter();
+    let properties = ClaimPropertySet::default();
+    let dump = File::open(city_fixture_path("wikidata.json"))
+        .unwrap_or_else(|err| panic!("open city Wikidata dump: {err}"));
+    let claims = stream_linked_entity_claims(
+        BufReader::new(dump),
+        &links,
+        &filter,
+        ClaimSelection::new(&properties),
+    )
+    .map(|claims| claims.unwrap_or_else(|err| panic!("read city Wikidata dump: {err}")));
+    persist_claims_iter_to_path(pois_db, claims, DEFAULT_CLAIMS_CHUNK_SIZE)
+        .unwrap_or_else(|err| panic!("persist city claims: {err}"));
+}
+
+/// A representative request against the city fixture.
+#[derive(Debug, Deserialize, Clone)]
+pub struct CityCase {
+    /// Name of the case (used in error messages and `#[case]` lists).
+    pub name: String,
+    /// What a visitor issuing this request would expect.
+    #[expect(dead_code, reason = "documents the case in the JSON file")]
+    pub description: String,
+    /// Request parameters.
+    pub request: RequestSpec,
+    /// Expected route properties, by backend name.
+    pub expected: HashMap<String, CityExpectation>,
+}
+
+impl CityCase {
+    /// Build the request this case describes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the case names an unknown theme.
+    #[must_use]
+    pub fn request(&self) -> SolveRequest {
+        let spec = &self.request;
+        let mut interests = InterestProfile::new();
+        for (name, weight) in &spec.interests {
+            let theme: Theme = name
+                .parse()
+                .unwrap_or_else(|_| panic!("{}: unknown theme {name}", self.name));
+            interests.set_weight(theme, *weight);
+        }
+        SolveRequest {
+            start: spec.start,
+            end: spec.end,
+            duration_minutes: spec.duration_minutes,
+            interests,
+            seed: spec.seed,
+            max_nodes: spec.max_nodes,
+            ..Default::default()
+        }
+    }
+
+    /// Expectations the route `backend` returns must meet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the case records none for `backend`.
+    #[must_use]
+    pub fn expected(&self, backend: &str) -> &CityExpectation {
+        self.expected
+            .get(backend)
+            .unwrap_or_else(|| panic!("{}: no expectations recorded for {backend}", self.name))
+    }
+}
+
+/// Request parameters of a [`CityCase`].
+#[derive(Debug, Deserialize, Clone)]
+pub struct RequestSpec {
+    /// Starting coordinate.
+    pub start: Coord<f64>,
+    /// Ending coordinate of point-to-point walks.
+    pub end: Option<Coord<f64>>,
+    /// Time budget in minutes.
+    pub duration_minutes: u16,
+    /// Interest weights by theme.
+    pub interests: HashMap<String, f32>,
+    /// Random seed for the solver.
+    pub seed: u64,
+    /// Optional limit on candidates to consider.
+    pub max_nodes: Option<u16>,
+}
+
+/// Route properties a good answer to a [`CityCase`] must have.
+///
+/// Heuristic backends search stochastically or myopically, so exact routes
+/// vary between backends and, for `vrp-core`, between runs. The expectation
+/// pins the landmarks any good route includes and the score band it lands in
+/// instead.
+#[derive(Debug, Deserialize, Clone)]
+pub struct CityExpectation {
+    /// Names of the landmarks every acceptable route visits.
+    pub must_visit: Vec<String>,
+    /// Minimum number of stops.
+    pub min_stops: usize,
+    /// Minimum acceptable score.
+    pub min_score: f32,
+    /// Maximum acceptable score.
+    pub max_score: f32,
+}
+
+/// Load every case from `cases.json`.
+///
+/// # Panics
+///
+/// Panics if the file cannot be read or parsed.
+#[must_use]
+pub fn load_city_cases() -> Vec<CityCase> {
+    let path = city_fixture_path("cases.json");
+    let content = fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("failed to read {}: {err}", path.display()));
+    serde_json::from_str(&content)
+        .unwrap_or_else(|err| panic!("failed to parse {}: {err}", path.display()))
+}
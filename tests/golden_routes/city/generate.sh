@@ -0,0 +1,49 @@
+#!/usr/bin/env bash
+# Rebuild the city fixture inputs from OpenStreetMap and Wikidata.
+#
+# Usage: tests/golden_routes/city/generate.sh scotland-latest.osm.pbf
+#
+# Cuts central Edinburgh from a regional extract, such as Geofabrik's
+# scotland-latest.osm.pbf, keeping the landmarks and the ways linking them.
+# Then fetches the Wikidata entities those landmarks link to from the
+# wbgetentities API, written one per line as in a dump. Requires osmium-tool,
+# curl, and jq.
+set -euo pipefail
+
+here="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+source_pbf="${1:?usage: $0 scotland-latest.osm.pbf}"
+api="${WIKIDATA_API:-https://www.wikidata.org/w/api.php}"
+work="$(mktemp -d)"
+trap 'rm -rf "$work"' EXIT
+
+osmium extract --bbox -3.235,55.935,-3.155,55.970 "$source_pbf" \
+  -o "$work/central.osm.pbf"
+osmium tags-filter "$work/central.osm.pbf" nwr/historic nwr/tourism w/highway \
+  -o "$work/edinburgh.osm.pbf"
+base64 --wrap=76 "$work/edinburgh.osm.pbf" > "$here/edinburgh.osm.pbf.b64"
+
+# Only plain `wikidata` tags link POIs; `brand:wikidata` and the like do not.
+osmium cat "$work/edinburgh.osm.pbf" -f opl \
+  | grep -oE '[T,]wikidata=Q[0-9]+' | cut -d= -f2 | sort -u > "$work/ids"
+
+# Keep the claims ingest reads by default, plus images and sitelinks, and
+# drop qualifiers, references, and labels to keep the slice small.
+entity='{type, id,
+  claims: (.claims | {P31, P1435, P18} | with_entries(select(.value != null))
+    | map_values(map({mainsnak, rank}))),
+  sitelinks: (.sitelinks // {} | map_values({site}))}'
+
+{
+  echo '['
+  xargs -n 50 < "$work/ids" | tr ' ' '|' | while read -r batch; do
+    curl --fail --silent --show-error --get "$api" \
+      --data-urlencode action=wbgetentities \
+      --data-urlencode "ids=$batch" \
+      --data-urlencode 'props=claims|sitelinks' \
+      --data-urlencode format=json \
+      | jq -c ".entities[] | select(.missing | not) | $entity"
+  done | sed '$!s/$/,/'
+  echo ']'
+} > "$here/wikidata.json"
+
+echo "Wrote $(wc -l < "$work/ids") linked entities; review cases.json next." >&2
@@ -0,0 +1,82 @@
+//! Bundle and unbundle commands: move an artefact set as one `.wildside`
+//! file.
+//!
+//! `bundle` packs the artefacts an ingest wrote into a single container, and
+//! `unbundle` verifies and extracts one on the receiving device, so the
+//! directory it produces can be passed straight to `solve --artefacts-dir`.
+
+use camino::Utf8PathBuf;
+use clap::Parser;
+use ortho_config::{OrthoConfig, SubcmdConfigMerge};
+use serde::{Deserialize, Serialize};
+use wildside_data::bundle::{BundleEntry, OpenedBundle, open_bundle, write_bundle};
+
+use crate::{
+    ARG_BUNDLE_OUTPUT, ARG_OUTPUT_DIR, ARG_SOLVE_ARTEFACTS_DIR, ARG_UNBUNDLE_BUNDLE, CliError,
+    ENV_BUNDLE_OUTPUT, ENV_UNBUNDLE_BUNDLE,
+};
+
+/// CLI arguments for the `bundle` subcommand.
+#[derive(Debug, Clone, Parser, Deserialize, Serialize, OrthoConfig, Default)]
+#[command(
+    long_about = "Pack pois.db, pois.rstar, and any popularity.bin, \
+                 links.filter, graph.bin, graph-step-free.bin, \
+                 travel_times.bin, priors.json, and manifest.json found in the artefact directory into one \
+                 .wildside file.",
+    about = "Pack an artefact directory into a single-file bundle"
+)]
+#[ortho_config(prefix = "WILDSIDE")]
+pub(crate) struct BundleArgs {
+    /// Path of the `.wildside` bundle to write.
+    #[arg(value_name = "path")]
+    #[serde(default)]
+    pub(crate) output: Option<Utf8PathBuf>,
+    /// Directory containing the artefacts to pack (defaults to `.`).
+    #[arg(long = ARG_SOLVE_ARTEFACTS_DIR, value_name = "dir")]
+    #[serde(default)]
+    pub(crate) artefacts_dir: Option<Utf8PathBuf>,
+}
+
+/// CLI arguments for the `unbundle` subcommand.
+#[derive(Debug, Clone, Parser, Deserialize, Serialize, OrthoConfig, Default)]
+#[command(
+    long_about = "Verify a .wildside bundle's checksums and manifest, then \
+                 extract its artefacts into a directory usable by solve.",
+    about = "Extract a single-file bundle into an artefact directory"
+)]
+#[ortho_config(prefix = "WILDSIDE")]
+pub(crate) struct UnbundleArgs {
+    /// Path of the `.wildside` bundle to extract.
+    #[arg(value_name = "path")]
+    #[serde(default)]
+    pub(crate) bundle: Option<Utf8PathBuf>,
+    /// Directory to extract the artefacts into (defaults to `.`).
+    #[arg(long = ARG_OUTPUT_DIR, value_name = "dir")]
+    #[serde(default)]
+    pub(crate) output_dir: Option<Utf8PathBuf>,
+}
+
+pub(super) fn run_bundle(args: BundleArgs) -> Result<Vec<BundleEntry>, CliError> {
+    let merged = args.load_and_merge().map_err(CliError::Configuration)?;
+    let output = merged.output.ok_or(CliError::MissingArgument {
+        field: ARG_BUNDLE_OUTPUT,
+        env: ENV_BUNDLE_OUTPUT,
+    })?;
+    let artefacts_dir = merged
+        .artefacts_dir
+        .unwrap_or_else(|| Utf8PathBuf::from("."));
+    Ok(write_bundle(&artefacts_dir, &output)?)
+}
+
+pub(super) fn run_unbundle(args: UnbundleArgs) -> Result<OpenedBundle, CliError> {
+    let merged = args.load_and_merge().map_err(CliError::Configuration)?;
+    let bundle = merged.bundle.ok_or(CliError::MissingArgument {
+        field: ARG_UNBUNDLE_BUNDLE,
+        env: ENV_UNBUNDLE_BUNDLE,
+    })?;
+    let output_dir = merged.output_dir.unwrap_or_else(|| Utf8PathBuf::from("."));
+    if output_dir.exists() && !output_dir.is_dir() {
+        return Err(CliError::OutputDirectoryNotDirectory { path: output_dir });
+    }
+    Ok(open_bundle(&bundle, &output_dir)?)
+}
@@ -9,13 +9,21 @@ use camino::Utf8PathBuf;
 use thiserror::Error;
 use wildside_core::SolveError;
 use wildside_core::SolveRequestValidationError;
+use wildside_core::priors::PriorsError;
 #[cfg(feature = "store-sqlite")]
 use wildside_core::store::SpatialIndexWriteError;
+use wildside_core::telemetry::TelemetryError;
+use wildside_core::travel_time::{PrecomputedTravelTimesError, RoutingGraphError};
+use wildside_data::bundle::BundleError;
+use wildside_data::integrity::VerifyError;
+use wildside_data::manifest::ManifestError;
 use wildside_data::routing::ProviderBuildError;
-use wildside_data::wikidata::etl::WikidataEtlError;
-use wildside_data::wikidata::store::PersistClaimsError;
+use wildside_data::wikidata::etl::{
+    DecompressionError, LanguageCodeError, PropertyIdError, WikidataApiError, WikidataEtlError,
+};
+use wildside_data::wikidata::store::{ClaimsQueryError, PersistClaimsError};
 use wildside_data::{OsmIngestError, PersistPoisError};
-use wildside_scorer::UserRelevanceError;
+use wildside_scorer::{ThemeMappingError, UserRelevanceError};
 
 /// Errors emitted by the Wildside CLI.
 #[derive(Debug, Error)]
@@ -66,6 +74,27 @@ pub enum CliError {
         #[source]
         source: std::io::Error,
     },
+    /// The configured Wikidata claim properties could not be parsed.
+    #[error("invalid --claim-properties value: {source}")]
+    InvalidClaimProperties {
+        #[source]
+        source: PropertyIdError,
+    },
+    /// The configured designation label languages could not be parsed.
+    #[error("invalid --designation-labels value: {source}")]
+    InvalidLabelLanguages {
+        #[source]
+        source: LanguageCodeError,
+    },
+    /// The configured dump decompression mode could not be parsed.
+    #[error("invalid --decompression value: {source}")]
+    InvalidDecompression {
+        #[source]
+        source: DecompressionError,
+    },
+    /// The precomputed travel-time settings are inconsistent.
+    #[error("invalid travel-time precompute options: {reason}")]
+    InvalidTravelTimeOptions { reason: String },
     /// The output directory exists but is not a directory.
     #[error("output directory {path:?} is not a directory")]
     OutputDirectoryNotDirectory { path: Utf8PathBuf },
@@ -89,6 +118,12 @@ pub enum CliError {
     /// Extracting linked claims from the Wikidata dump failed.
     #[error("failed to extract Wikidata claims: {0}")]
     WikidataEtl(#[from] WikidataEtlError),
+    /// Fetching linked claims from the Wikidata API failed.
+    #[error("failed to fetch Wikidata claims from the API: {0}")]
+    WikidataApi(#[from] WikidataApiError),
+    /// Both a Wikidata dump and the Wikidata API were selected.
+    #[error("--wikidata-dump and --wikidata-api are mutually exclusive; choose one source")]
+    ConflictingWikidataSources,
     /// Persisting Wikidata claims to SQLite failed.
     #[error("failed to persist Wikidata claims into {path:?}: {source}")]
     PersistClaims {
@@ -96,6 +131,34 @@ pub enum CliError {
         #[source]
         source: PersistClaimsError,
     },
+    /// Reading the stored heritage designations failed.
+    #[error("failed to read heritage designations from {path:?}: {source}")]
+    ReadDesignations {
+        path: Utf8PathBuf,
+        #[source]
+        source: ClaimsQueryError,
+    },
+    /// Persisting designation labels to SQLite failed.
+    #[error("failed to persist designation labels into {path:?}: {source}")]
+    PersistLabels {
+        path: Utf8PathBuf,
+        #[source]
+        source: PersistClaimsError,
+    },
+    /// Indexing and compacting `pois.db` after ingest failed.
+    #[error("failed to finalise artefacts in {path:?}: {source}")]
+    FinaliseArtifacts {
+        path: Utf8PathBuf,
+        #[source]
+        source: PersistClaimsError,
+    },
+    /// Writing the link membership filter artefact failed.
+    #[error("failed to write link filter to {path:?}: {source}")]
+    WriteLinkFilter {
+        path: Utf8PathBuf,
+        #[source]
+        source: PersistClaimsError,
+    },
     /// Writing the spatial index artefact failed.
     #[cfg(feature = "store-sqlite")]
     #[error("failed to write spatial index to {path:?}: {source}")]
@@ -104,6 +167,26 @@ pub enum CliError {
         #[source]
         source: SpatialIndexWriteError,
     },
+    /// Writing the pedestrian routing graph artefact failed.
+    #[error("failed to write routing graph to {path:?}: {source}")]
+    WriteRoutingGraph {
+        path: Utf8PathBuf,
+        #[source]
+        source: RoutingGraphError,
+    },
+    /// Writing the precomputed travel-time table artefact failed.
+    #[error("failed to write travel-time table to {path:?}: {source}")]
+    WriteTravelTimes {
+        path: Utf8PathBuf,
+        #[source]
+        source: PrecomputedTravelTimesError,
+    },
+    /// Writing, reading, or validating `manifest.json` failed.
+    #[error(transparent)]
+    ArtefactManifest(#[from] ManifestError),
+    /// Writing or extracting a `.wildside` bundle failed.
+    #[error(transparent)]
+    Bundle(#[from] BundleError),
     /// Opening the solve request file failed.
     #[error("failed to open solve request at {path:?}: {source}")]
     OpenSolveRequest {
@@ -125,13 +208,51 @@ pub enum CliError {
         #[source]
         source: SolveRequestValidationError,
     },
+    /// Opening the interest priors artefact failed.
+    #[error("failed to open interest priors at {path:?}: {source}")]
+    OpenInterestPriors {
+        path: Utf8PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    /// The interest priors artefact could not be decoded.
+    #[error("failed to load interest priors from {path:?}: {source}")]
+    ParseInterestPriors {
+        path: Utf8PathBuf,
+        #[source]
+        source: PriorsError,
+    },
     /// Opening the POI store artefacts failed.
     #[cfg(feature = "store-sqlite")]
     #[error(transparent)]
     OpenPoiStore(#[from] wildside_core::SqlitePoiStoreError),
+    /// Querying the opened POI store failed.
+    #[error(transparent)]
+    QueryPoiStore(#[from] wildside_core::PoiStoreError),
     /// Constructing the user relevance scorer failed.
     #[error(transparent)]
     BuildScorer(#[from] UserRelevanceError),
+    /// Loading the theme-to-claim mapping file failed.
+    #[error("failed to load theme mapping from {path:?}: {source}")]
+    LoadThemeMapping {
+        path: Utf8PathBuf,
+        #[source]
+        source: ThemeMappingError,
+    },
+    /// Loading the pedestrian routing graph beside `pois.db` failed.
+    #[error("failed to load routing graph from {path:?}: {source}")]
+    LoadRoutingGraph {
+        path: Utf8PathBuf,
+        #[source]
+        source: RoutingGraphError,
+    },
+    /// Loading the precomputed travel-time table beside `pois.db` failed.
+    #[error("failed to load travel-time table from {path:?}: {source}")]
+    LoadTravelTimes {
+        path: Utf8PathBuf,
+        #[source]
+        source: PrecomputedTravelTimesError,
+    },
     /// Constructing the travel time provider failed.
     #[error("failed to build travel time provider for {base_url:?}: {source}")]
     BuildTravelTimeProvider {
@@ -148,4 +269,32 @@ pub enum CliError {
     /// Writing the solve output failed.
     #[error("failed to write solve output: {0}")]
     WriteSolveOutput(#[source] std::io::Error),
+    /// The simulation parameters are inconsistent.
+    #[error("invalid simulation plan: {reason}")]
+    InvalidSimulationPlan { reason: String },
+    /// The artefacts contain no POIs to sample requests around.
+    #[error("no POIs found in the artefacts indexed by {path:?}")]
+    EmptySimulationArtefacts { path: Utf8PathBuf },
+    /// Serializing the simulation report failed.
+    #[error("failed to serialize simulation report: {0}")]
+    SerializeSimulationReport(#[source] serde_json::Error),
+    /// The `serve` listener could not be bound.
+    #[error("failed to listen on {address}")]
+    BindServer {
+        address: String,
+        #[source]
+        source: std::io::Error,
+    },
+    /// The `serve` telemetry privacy settings are invalid.
+    #[error("invalid telemetry privacy policy: {0}")]
+    InvalidTelemetryPolicy(#[source] TelemetryError),
+    /// The artefacts could not be read for validation.
+    #[error(transparent)]
+    VerifyArtefacts(#[from] VerifyError),
+    /// Serializing the validation report failed.
+    #[error("failed to serialize validation report: {0}")]
+    SerializeValidationReport(#[source] serde_json::Error),
+    /// Validation found inconsistencies between the artefacts.
+    #[error("artefacts in {dir:?} failed validation with {issues} issue(s)")]
+    InconsistentArtefacts { dir: Utf8PathBuf, issues: usize },
 }
@@ -0,0 +1,260 @@
+//! Arguments of the `ingest` command and the configuration resolved from them.
+
+#[cfg(feature = "store-sqlite")]
+use camino::Utf8Path;
+use camino::Utf8PathBuf;
+use clap::Parser;
+use ortho_config::OrthoConfig;
+#[cfg(feature = "store-sqlite")]
+use ortho_config::SubcmdConfigMerge;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "store-sqlite")]
+use std::time::Duration;
+#[cfg(feature = "store-sqlite")]
+use wildside_core::travel_time::DEFAULT_MAX_TRAVEL_TIME;
+#[cfg(feature = "store-sqlite")]
+use wildside_data::wikidata::etl::{
+    ClaimPropertySet, Decompression, DumpCompression, DumpDecoder, LabelLanguages, RankFilter,
+};
+use wildside_data::wikidata::etl::{DEFAULT_API_URL, DEFAULT_LABEL_LANGUAGE};
+
+use crate::{
+    ARG_CLAIM_PROPERTIES, ARG_DECOMPRESSION, ARG_DESIGNATION_LABELS, ARG_NO_TRAVEL_TIMES,
+    ARG_OSM_PBF, ARG_OUTPUT_DIR, ARG_TRAVEL_TIME_HORIZON, ARG_TRUTHY_CLAIMS, ARG_WIKIDATA_API,
+    ARG_WIKIDATA_DUMP,
+};
+#[cfg(feature = "store-sqlite")]
+use crate::{CliError, ENV_OSM_PBF, ENV_WIKIDATA_DUMP};
+
+/// CLI arguments for the `ingest` subcommand.
+#[derive(Debug, Clone, Parser, Deserialize, Serialize, OrthoConfig, Default)]
+#[command(
+    long_about = "Define the artefact inputs for ingestion. Paths can come \
+                 from CLI flags, configuration files, or environment \
+                 variables.",
+    about = "Describe the OSM and Wikidata inputs for ingestion"
+)]
+#[ortho_config(prefix = "WILDSIDE")]
+pub(crate) struct IngestArgs {
+    /// Path to the OpenStreetMap PBF file.
+    #[arg(long = ARG_OSM_PBF, value_name = "path")]
+    #[serde(default)]
+    pub(crate) osm_pbf: Option<Utf8PathBuf>,
+    /// Path to the Wikidata dump file (JSON/BZ2).
+    #[arg(long = ARG_WIKIDATA_DUMP, value_name = "path")]
+    #[serde(default)]
+    pub(crate) wikidata_dump: Option<Utf8PathBuf>,
+    /// Fetch linked entities from the Wikidata API instead of a dump. Suits
+    /// small regions; defaults to the public wikidata.org endpoint.
+    #[arg(
+        long = ARG_WIKIDATA_API,
+        value_name = "url",
+        num_args = 0..=1,
+        default_missing_value = DEFAULT_API_URL
+    )]
+    #[serde(default)]
+    pub(crate) wikidata_api: Option<String>,
+    /// Directory to write the generated artefacts.
+    #[arg(long = ARG_OUTPUT_DIR, value_name = "dir")]
+    #[serde(default)]
+    pub(crate) output_dir: Option<Utf8PathBuf>,
+    /// Comma-separated Wikidata property ids to extract (e.g. `P31,P1435`).
+    #[arg(long = ARG_CLAIM_PROPERTIES, value_name = "ids")]
+    #[serde(default)]
+    pub(crate) claim_properties: Option<String>,
+    /// Keep only truthy claims: preferred statements, or normal ones when a
+    /// property has no preferred statement. Deprecated claims are dropped.
+    #[arg(long = ARG_TRUTHY_CLAIMS, num_args = 0, default_missing_value = "true")]
+    #[serde(default)]
+    pub(crate) truthy_claims: Option<bool>,
+    /// Resolve heritage designation labels into `wikidata_entity_labels`, in
+    /// the given comma-separated languages (defaults to `en`).
+    #[arg(
+        long = ARG_DESIGNATION_LABELS,
+        value_name = "languages",
+        num_args = 0..=1,
+        default_missing_value = DEFAULT_LABEL_LANGUAGE
+    )]
+    #[serde(default)]
+    pub(crate) designation_labels: Option<String>,
+    /// Keep precomputed travel times only between POIs at most this many
+    /// minutes apart (defaults to 120).
+    #[arg(long = ARG_TRAVEL_TIME_HORIZON, value_name = "minutes")]
+    #[serde(default)]
+    pub(crate) travel_time_horizon: Option<u16>,
+    /// Skip precomputing `travel_times.bin`; solves then route over
+    /// `graph.bin` instead.
+    #[arg(long = ARG_NO_TRAVEL_TIMES, num_args = 0, default_missing_value = "true")]
+    #[serde(default)]
+    pub(crate) no_travel_times: Option<bool>,
+    /// How to decompress a bzip2 Wikidata dump: `parallel` (the default)
+    /// decodes its streams on every core, `sequential` on one thread.
+    #[arg(long = ARG_DECOMPRESSION, value_name = "mode")]
+    #[serde(default)]
+    pub(crate) decompression: Option<String>,
+}
+
+impl IngestArgs {
+    #[cfg(feature = "store-sqlite")]
+    pub(crate) fn into_config(self) -> Result<IngestConfig, CliError> {
+        let merged = self.load_and_merge().map_err(CliError::Configuration)?;
+        IngestConfig::try_from(merged)
+    }
+}
+
+/// Where ingestion reads Wikidata entities from.
+#[cfg(feature = "store-sqlite")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum WikidataInput {
+    /// A local JSON or bzip2-compressed dump.
+    Dump(Utf8PathBuf),
+    /// A MediaWiki Action API endpoint queried with `wbgetentities`.
+    Api(String),
+}
+
+#[cfg(feature = "store-sqlite")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct IngestConfig {
+    pub(crate) osm_pbf: Utf8PathBuf,
+    pub(crate) wikidata: WikidataInput,
+    pub(crate) output_dir: Utf8PathBuf,
+    pub(crate) claim_properties: ClaimPropertySet,
+    pub(crate) claim_ranks: RankFilter,
+    pub(crate) designation_labels: Option<LabelLanguages>,
+    /// Longest travel time kept in `travel_times.bin`, or `None` to skip
+    /// writing the table.
+    pub(crate) travel_time_horizon: Option<Duration>,
+    pub(crate) decompression: Decompression,
+}
+
+#[cfg(feature = "store-sqlite")]
+impl IngestConfig {
+    /// The decoder ingest wraps around the Wikidata dump at `path`.
+    pub(crate) fn dump_decoder(&self, path: &Utf8Path) -> DumpDecoder {
+        DumpCompression::from_path(path).decoder(self.decompression)
+    }
+
+    /// The decoder wrapped around the configured Wikidata dump, or `None`
+    /// when entities come from the API.
+    pub(crate) fn wikidata_decoder(&self) -> Option<DumpDecoder> {
+        match &self.wikidata {
+            WikidataInput::Dump(path) => Some(self.dump_decoder(path)),
+            WikidataInput::Api(_) => None,
+        }
+    }
+
+    pub(crate) fn validate_sources(&self) -> Result<(), CliError> {
+        Self::require_existing(&self.osm_pbf, ARG_OSM_PBF)?;
+        if let WikidataInput::Dump(path) = &self.wikidata {
+            Self::require_existing(path, ARG_WIKIDATA_DUMP)?;
+        }
+        if self.output_dir.exists() && !self.output_dir.is_dir() {
+            return Err(CliError::OutputDirectoryNotDirectory {
+                path: self.output_dir.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    fn require_existing(path: &Utf8Path, field: &'static str) -> Result<(), CliError> {
+        match wildside_fs::file_is_file(path) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(CliError::MissingSourceFile {
+                field,
+                path: path.to_path_buf(),
+            }),
+            Err(source) if source.kind() == std::io::ErrorKind::NotFound => {
+                Err(CliError::MissingSourceFile {
+                    field,
+                    path: path.to_path_buf(),
+                })
+            }
+            Err(source) => Err(CliError::SourceFileAccess {
+                field,
+                path: path.to_path_buf(),
+                source,
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "store-sqlite")]
+impl TryFrom<IngestArgs> for IngestConfig {
+    type Error = CliError;
+
+    fn try_from(args: IngestArgs) -> Result<Self, Self::Error> {
+        let osm_pbf = args.osm_pbf.ok_or(CliError::MissingArgument {
+            field: ARG_OSM_PBF,
+            env: ENV_OSM_PBF,
+        })?;
+        let wikidata = match (args.wikidata_dump, args.wikidata_api) {
+            (Some(_), Some(_)) => return Err(CliError::ConflictingWikidataSources),
+            (Some(path), None) => WikidataInput::Dump(path),
+            (None, Some(endpoint)) => WikidataInput::Api(endpoint),
+            (None, None) => {
+                return Err(CliError::MissingArgument {
+                    field: ARG_WIKIDATA_DUMP,
+                    env: ENV_WIKIDATA_DUMP,
+                });
+            }
+        };
+        let output_dir = args.output_dir.unwrap_or_else(|| Utf8PathBuf::from("."));
+        let claim_properties = args
+            .claim_properties
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .map_err(|source| CliError::InvalidClaimProperties { source })?
+            .unwrap_or_default();
+        let designation_labels = args
+            .designation_labels
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .map_err(|source| CliError::InvalidLabelLanguages { source })?;
+        let claim_ranks = if args.truthy_claims.unwrap_or(false) {
+            RankFilter::Truthy
+        } else {
+            RankFilter::All
+        };
+        let travel_time_horizon =
+            resolve_travel_time_horizon(args.travel_time_horizon, args.no_travel_times)?;
+        let decompression = args
+            .decompression
+            .as_deref()
+            .map(str::parse)
+            .transpose()
+            .map_err(|source| CliError::InvalidDecompression { source })?
+            .unwrap_or_default();
+        Ok(Self {
+            osm_pbf,
+            wikidata,
+            output_dir,
+            claim_properties,
+            claim_ranks,
+            designation_labels,
+            travel_time_horizon,
+            decompression,
+        })
+    }
+}
+
+/// Resolve the precomputed travel-time horizon, or `None` when the table is
+/// switched off.
+#[cfg(feature = "store-sqlite")]
+fn resolve_travel_time_horizon(
+    minutes: Option<u16>,
+    disabled: Option<bool>,
+) -> Result<Option<Duration>, CliError> {
+    match (minutes, disabled.unwrap_or(false)) {
+        (Some(_), true) => Err(CliError::InvalidTravelTimeOptions {
+            reason: format!("--{ARG_TRAVEL_TIME_HORIZON} conflicts with --{ARG_NO_TRAVEL_TIMES}"),
+        }),
+        (Some(0), false) => Err(CliError::InvalidTravelTimeOptions {
+            reason: format!("--{ARG_TRAVEL_TIME_HORIZON} must be positive"),
+        }),
+        (Some(minutes), false) => Ok(Some(Duration::from_secs(u64::from(minutes) * 60))),
+        (None, false) => Ok(Some(DEFAULT_MAX_TRAVEL_TIME)),
+        (None, true) => Ok(None),
+    }
+}
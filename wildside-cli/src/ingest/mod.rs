@@ -0,0 +1,346 @@
+//! Build artefacts from OSM and Wikidata inputs for the `ingest` command.
+
+#[cfg(feature = "store-sqlite")]
+use camino::{Utf8Path, Utf8PathBuf};
+#[cfg(feature = "store-sqlite")]
+use std::{io::BufReader, time::Duration};
+#[cfg(feature = "store-sqlite")]
+use wildside_core::formats::{
+    CLAIMS_SCHEMA, LINK_FILTER, POIS_SCHEMA, ROUTING_GRAPH, SPATIAL_INDEX, TRAVEL_TIMES,
+};
+#[cfg(feature = "store-sqlite")]
+use wildside_core::{
+    PointOfInterest,
+    store::{SpatialIndexOptions, write_spatial_index_with_options},
+    travel_time::{
+        PrecomputeOptions, PrecomputedTravelTimes, PrecomputedTravelTimesError, RoutingGraph,
+    },
+};
+#[cfg(feature = "store-sqlite")]
+use wildside_data::OsmIngestSummary;
+#[cfg(feature = "store-sqlite")]
+use wildside_data::manifest::{ArtefactManifest, MANIFEST_FILE_NAME};
+#[cfg(feature = "store-sqlite")]
+use wildside_data::wikidata::etl::{
+    ClaimSelection, DumpDecoder, EntityClaims, LabelLanguages, PoiEntityLinks, WikidataApiSource,
+    extract_entity_labels, stream_linked_entity_claims,
+};
+#[cfg(feature = "store-sqlite")]
+use wildside_data::wikidata::store::{
+    DEFAULT_CLAIMS_CHUNK_SIZE, designation_entity_ids_for_path, finalise_artifacts,
+    persist_claims_iter_to_path, persist_entity_labels_to_path, write_link_filter_for_path,
+};
+#[cfg(feature = "store-sqlite")]
+use wildside_data::{
+    build_routing_graph, build_step_free_routing_graph, ingest_osm_pbf_report,
+    persist_pois_to_sqlite,
+};
+#[cfg(feature = "store-sqlite")]
+use wildside_fs::open_utf8_file;
+
+use crate::CliError;
+#[cfg(feature = "store-sqlite")]
+use crate::{
+    ARG_OSM_PBF, ARG_WIKIDATA_API, ARG_WIKIDATA_DUMP, LINK_FILTER_FILE_NAME,
+    ROUTING_GRAPH_FILE_NAME, STEP_FREE_GRAPH_FILE_NAME, TRAVEL_TIMES_FILE_NAME,
+};
+
+mod config;
+
+pub(crate) use config::IngestArgs;
+#[cfg(feature = "store-sqlite")]
+pub(crate) use config::{IngestConfig, WikidataInput};
+
+pub(crate) fn run_ingest(args: IngestArgs) -> Result<IngestOutcome, CliError> {
+    #[cfg(not(feature = "store-sqlite"))]
+    {
+        drop(args);
+        Err(CliError::MissingFeature {
+            feature: "store-sqlite",
+            action: "ingest",
+        })
+    }
+    #[cfg(feature = "store-sqlite")]
+    {
+        let config = resolve_ingest_config(args)?;
+        execute_ingest(&config)
+    }
+}
+
+#[cfg(feature = "store-sqlite")]
+pub(crate) fn resolve_ingest_config(args: IngestArgs) -> Result<IngestConfig, CliError> {
+    let config = args.into_config()?;
+    config.validate_sources()?;
+    Ok(config)
+}
+
+#[cfg(feature = "store-sqlite")]
+fn execute_ingest(config: &IngestConfig) -> Result<IngestOutcome, CliError> {
+    let pois_db = config.output_dir.join("pois.db");
+    let spatial_index = config.output_dir.join("pois.rstar");
+    let link_filter = config.output_dir.join(LINK_FILTER_FILE_NAME);
+    let routing_graph = config.output_dir.join(ROUTING_GRAPH_FILE_NAME);
+    let step_free_graph = config.output_dir.join(STEP_FREE_GRAPH_FILE_NAME);
+    let travel_times = config.output_dir.join(TRAVEL_TIMES_FILE_NAME);
+    let manifest = config.output_dir.join(MANIFEST_FILE_NAME);
+    let report = ingest_osm_pbf_report(config.osm_pbf.as_std_path())?;
+
+    persist_pois_to_sqlite(&pois_db, &report.pois).map_err(|source| CliError::PersistPois {
+        path: pois_db.clone(),
+        source,
+    })?;
+
+    let claims_count = persist_wikidata_claims(config, &report.pois, &pois_db)?;
+    let labels_count = match &config.designation_labels {
+        Some(languages) => persist_designation_labels(config, languages, &pois_db)?,
+        None => 0,
+    };
+    finalise_artifacts(pois_db.as_std_path()).map_err(|source| CliError::FinaliseArtifacts {
+        path: pois_db.clone(),
+        source,
+    })?;
+    write_link_filter_for_path(pois_db.as_std_path(), link_filter.as_std_path()).map_err(
+        |source| CliError::WriteLinkFilter {
+            path: link_filter.clone(),
+            source,
+        },
+    )?;
+
+    // `pois.db` already holds every tag map, so the index keeps only ids and
+    // locations.
+    let index_options = SpatialIndexOptions {
+        embed_tags: false,
+        ..SpatialIndexOptions::default()
+    };
+    write_spatial_index_with_options(spatial_index.as_std_path(), &report.pois, index_options)
+        .map_err(|source| CliError::WriteSpatialIndex {
+            path: spatial_index.clone(),
+            source,
+        })?;
+    let graph = build_routing_graph(config.osm_pbf.as_std_path())?;
+    graph
+        .write(routing_graph.as_std_path())
+        .map_err(|source| CliError::WriteRoutingGraph {
+            path: routing_graph.clone(),
+            source,
+        })?;
+    build_step_free_routing_graph(config.osm_pbf.as_std_path())?
+        .write(step_free_graph.as_std_path())
+        .map_err(|source| CliError::WriteRoutingGraph {
+            path: step_free_graph.clone(),
+            source,
+        })?;
+    let travel_times = match config.travel_time_horizon {
+        Some(horizon) => {
+            write_travel_times(&graph, &report.pois, horizon, &travel_times)?;
+            Some(travel_times)
+        }
+        None => {
+            remove_stale_travel_times(&travel_times)?;
+            None
+        }
+    };
+    write_artefact_manifest(config)?;
+
+    Ok(IngestOutcome {
+        pois_db,
+        spatial_index,
+        link_filter,
+        routing_graph,
+        step_free_graph,
+        travel_times,
+        manifest,
+        poi_count: report.pois.len(),
+        claims_count,
+        labels_count,
+        wikidata_decoder: config.wikidata_decoder(),
+        summary: report.summary,
+    })
+}
+
+/// Route between the POIs over `graph` and write the travel times within
+/// `horizon` of each other to `path`.
+#[cfg(feature = "store-sqlite")]
+fn write_travel_times(
+    graph: &RoutingGraph,
+    pois: &[PointOfInterest],
+    horizon: Duration,
+    path: &Utf8Path,
+) -> Result<(), CliError> {
+    let options = PrecomputeOptions::default().with_max_travel_time(horizon);
+    PrecomputedTravelTimes::from_graph(graph, pois, &options)
+        .and_then(|table| table.write(path.as_std_path()))
+        .map_err(|source| CliError::WriteTravelTimes {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+/// Remove a `travel_times.bin` left by an earlier ingest, so solves do not
+/// read a table built for other POIs.
+#[cfg(feature = "store-sqlite")]
+fn remove_stale_travel_times(path: &Utf8Path) -> Result<(), CliError> {
+    match std::fs::remove_file(path) {
+        Err(source) if source.kind() != std::io::ErrorKind::NotFound => {
+            Err(CliError::WriteTravelTimes {
+                path: path.to_path_buf(),
+                source: PrecomputedTravelTimesError::Io {
+                    path: path.as_std_path().to_path_buf(),
+                    source,
+                },
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Record the formats written by this ingest and the inputs they came from
+/// in `manifest.json`.
+///
+/// Input files are hashed in full, so a large Wikidata dump adds one
+/// sequential read to the ingest.
+#[cfg(feature = "store-sqlite")]
+fn write_artefact_manifest(config: &IngestConfig) -> Result<(), CliError> {
+    let mut artefacts = ArtefactManifest::new(env!("CARGO_PKG_VERSION"))
+        .with_artefact("pois.db", POIS_SCHEMA)
+        .with_artefact("pois.db", CLAIMS_SCHEMA)
+        .with_artefact("pois.rstar", SPATIAL_INDEX)
+        .with_artefact(LINK_FILTER_FILE_NAME, LINK_FILTER)
+        .with_artefact(ROUTING_GRAPH_FILE_NAME, ROUTING_GRAPH)
+        .with_artefact(STEP_FREE_GRAPH_FILE_NAME, ROUTING_GRAPH);
+    if config.travel_time_horizon.is_some() {
+        artefacts = artefacts.with_artefact(TRAVEL_TIMES_FILE_NAME, TRAVEL_TIMES);
+    }
+    let artefacts = artefacts.with_input_file(ARG_OSM_PBF, &config.osm_pbf)?;
+    let manifest = match &config.wikidata {
+        WikidataInput::Dump(path) => artefacts.with_input_file(ARG_WIKIDATA_DUMP, path)?,
+        WikidataInput::Api(endpoint) => artefacts.with_remote_input(ARG_WIKIDATA_API, endpoint),
+    };
+    manifest.write(&config.output_dir)?;
+    Ok(())
+}
+
+/// Extract claims for linked entities and stream them into `pois_db`,
+/// returning the number of entities persisted.
+///
+/// Dump extraction feeds persistence lazily, so memory stays bounded by the
+/// persistence chunk size however many entities the dump links. Dump lines
+/// are parsed on one worker per CPU and persisted in dump order.
+#[cfg(feature = "store-sqlite")]
+pub(crate) fn persist_wikidata_claims(
+    config: &IngestConfig,
+    pois: &[PointOfInterest],
+    pois_db: &Utf8Path,
+) -> Result<usize, CliError> {
+    let links = PoiEntityLinks::from_pois(pois.iter());
+    if links.is_empty() {
+        return persist_claim_stream(pois_db, std::iter::empty());
+    }
+    let selection = ClaimSelection::new(&config.claim_properties).with_ranks(config.claim_ranks);
+    match &config.wikidata {
+        WikidataInput::Dump(path) => {
+            let reader = open_wikidata_dump(path, config.dump_decoder(path))?;
+            let filter = links.to_link_filter();
+            let mut failure = None;
+            let claims = stream_linked_entity_claims(reader, &links, &filter, selection)
+                .parallel()
+                .map_while(|claims| claims.map_err(|err| failure = Some(err)).ok());
+            let persisted = persist_claim_stream(pois_db, claims)?;
+            failure.map_or(Ok(persisted), |err| Err(err.into()))
+        }
+        WikidataInput::Api(endpoint) => {
+            let claims = WikidataApiSource::new(endpoint.as_str())?
+                .extract_linked_entity_claims(&links, selection)?;
+            persist_claim_stream(pois_db, claims)
+        }
+    }
+}
+
+/// Resolve the labels of the heritage designations stored in `pois_db` and
+/// persist them, returning the number of labels written.
+///
+/// Dump input costs a second pass over the dump, which ends as soon as every
+/// designation has been seen.
+#[cfg(feature = "store-sqlite")]
+pub(crate) fn persist_designation_labels(
+    config: &IngestConfig,
+    languages: &LabelLanguages,
+    pois_db: &Utf8Path,
+) -> Result<usize, CliError> {
+    let designations =
+        designation_entity_ids_for_path(pois_db.as_std_path()).map_err(|source| {
+            CliError::ReadDesignations {
+                path: pois_db.to_path_buf(),
+                source,
+            }
+        })?;
+    if designations.is_empty() {
+        return Ok(0);
+    }
+    let labels = match &config.wikidata {
+        WikidataInput::Dump(path) => extract_entity_labels(
+            open_wikidata_dump(path, config.dump_decoder(path))?,
+            &designations,
+            languages,
+        )?,
+        WikidataInput::Api(endpoint) => WikidataApiSource::new(endpoint.as_str())?
+            .extract_entity_labels(&designations, languages)?,
+    };
+    persist_entity_labels_to_path(pois_db.as_std_path(), &labels).map_err(|source| {
+        CliError::PersistLabels {
+            path: pois_db.to_path_buf(),
+            source,
+        }
+    })
+}
+
+#[cfg(feature = "store-sqlite")]
+fn persist_claim_stream<I>(pois_db: &Utf8Path, claims: I) -> Result<usize, CliError>
+where
+    I: IntoIterator<Item = EntityClaims>,
+{
+    persist_claims_iter_to_path(pois_db.as_std_path(), claims, DEFAULT_CLAIMS_CHUNK_SIZE).map_err(
+        |source| CliError::PersistClaims {
+            path: pois_db.to_path_buf(),
+            source,
+        },
+    )
+}
+
+#[cfg(feature = "store-sqlite")]
+fn open_wikidata_dump(
+    path: &Utf8Path,
+    decoder: DumpDecoder,
+) -> Result<Box<dyn std::io::Read + Send>, CliError> {
+    let file = open_utf8_file(path).map_err(|source| CliError::OpenWikidataDump {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let decoded = decoder.wrap(file);
+    Ok(Box::new(BufReader::new(decoded)))
+}
+
+#[cfg(feature = "store-sqlite")]
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct IngestOutcome {
+    pub pois_db: Utf8PathBuf,
+    pub spatial_index: Utf8PathBuf,
+    pub link_filter: Utf8PathBuf,
+    pub routing_graph: Utf8PathBuf,
+    pub step_free_graph: Utf8PathBuf,
+    /// `None` when the travel-time table was switched off.
+    pub travel_times: Option<Utf8PathBuf>,
+    pub manifest: Utf8PathBuf,
+    pub poi_count: usize,
+    pub claims_count: usize,
+    pub labels_count: usize,
+    /// Decoder wrapped around the Wikidata dump; `None` for API input.
+    pub wikidata_decoder: Option<DumpDecoder>,
+    pub summary: OsmIngestSummary,
+}
+
+#[cfg(not(feature = "store-sqlite"))]
+/// When `store-sqlite` is disabled, `IngestOutcome` is a unit type because
+/// ingestion always fails with `MissingFeature`. This preserves the function
+/// signature across feature permutations.
+pub(crate) type IngestOutcome = ();
@@ -1,46 +1,56 @@
 //! Command-line interface for Wildside's offline tooling.
 #![forbid(unsafe_code)]
 
-#[cfg(feature = "store-sqlite")]
-use bzip2::read::MultiBzDecoder;
-#[cfg(feature = "store-sqlite")]
-use camino::Utf8Path;
-use camino::Utf8PathBuf;
 use clap::{Parser, Subcommand};
-use ortho_config::OrthoConfig;
-#[cfg(feature = "store-sqlite")]
-use ortho_config::SubcmdConfigMerge;
-use serde::{Deserialize, Serialize};
-#[cfg(feature = "store-sqlite")]
-use std::io::BufReader;
-#[cfg(feature = "store-sqlite")]
-use wildside_core::{PointOfInterest, store::write_spatial_index};
-#[cfg(feature = "store-sqlite")]
-use wildside_data::OsmIngestSummary;
-#[cfg(feature = "store-sqlite")]
-use wildside_data::wikidata::etl::{EntityClaims, PoiEntityLinks, extract_linked_entity_claims};
-#[cfg(feature = "store-sqlite")]
-use wildside_data::wikidata::store::persist_claims_to_path;
-#[cfg(feature = "store-sqlite")]
-use wildside_data::{ingest_osm_pbf_report, persist_pois_to_sqlite};
-#[cfg(feature = "store-sqlite")]
-use wildside_fs::open_utf8_file;
 
+mod bundle;
 mod error;
+mod ingest;
+mod serve;
+mod simulate;
 mod solve;
+mod validate;
 /// Errors emitted by the Wildside CLI.
 pub use error::CliError;
 
+use bundle::{BundleArgs, UnbundleArgs};
+#[cfg(test)]
+use ingest::IngestOutcome;
+use ingest::{IngestArgs, run_ingest};
+#[cfg(all(test, feature = "store-sqlite"))]
+use ingest::{IngestConfig, WikidataInput, resolve_ingest_config};
+use serve::ServeArgs;
+#[cfg(all(test, feature = "store-sqlite"))]
+use serve::canary_request;
+#[cfg(test)]
+use serve::{
+    EngineService, MAX_BODY_BYTES, MAX_HEAD_BYTES, MAX_HEADERS, Response, ServeConfig, Service,
+    handle_connection, route, warm_up_until_ready,
+};
+use simulate::SimulateArgs;
+#[cfg(test)]
+use simulate::{
+    SimulateConfig, SimulationBuilder, SimulationDependencies, SimulationPlan, candidate_bounds,
+    generate_requests, run_simulate_with, run_simulation, top_poi_ids,
+};
 use solve::SolveArgs;
 #[cfg(test)]
 use solve::{
-    SolveConfig, SolveSolverBuilder, config_from_layers_for_test, load_solve_request,
-    run_solve_with,
+    SolveConfig, SolveSolverBuilder, config_from_layers_for_test, load_interest_priors,
+    load_solve_request, run_solve_with,
 };
+use validate::ValidateArgs;
 
 const ARG_OSM_PBF: &str = "osm-pbf";
 const ARG_WIKIDATA_DUMP: &str = "wikidata-dump";
+const ARG_WIKIDATA_API: &str = "wikidata-api";
 const ARG_OUTPUT_DIR: &str = "output-dir";
+const ARG_CLAIM_PROPERTIES: &str = "claim-properties";
+const ARG_TRUTHY_CLAIMS: &str = "truthy-claims";
+const ARG_DESIGNATION_LABELS: &str = "designation-labels";
+const ARG_TRAVEL_TIME_HORIZON: &str = "travel-time-horizon";
+const ARG_NO_TRAVEL_TIMES: &str = "no-travel-times";
+const ARG_DECOMPRESSION: &str = "decompression";
 #[cfg(feature = "store-sqlite")]
 const ENV_OSM_PBF: &str = "WILDSIDE_CMDS_INGEST_OSM_PBF";
 #[cfg(feature = "store-sqlite")]
@@ -50,112 +60,63 @@ const ARG_SOLVE_ARTEFACTS_DIR: &str = "artefacts-dir";
 const ARG_SOLVE_POIS_DB: &str = "pois-db";
 const ARG_SOLVE_SPATIAL_INDEX: &str = "spatial-index";
 const ARG_SOLVE_POPULARITY: &str = "popularity";
+const ARG_SOLVE_PRIORS: &str = "priors";
+const ARG_SOLVE_THEME_MAPPING: &str = "theme-mapping";
 const ARG_SOLVE_OSRM_BASE_URL: &str = "osrm-base-url";
+const ARG_SERVE_LISTEN: &str = "listen";
+const ARG_SERVE_TELEMETRY_MIN_REQUESTS: &str = "telemetry-min-requests";
+const ARG_SERVE_TELEMETRY_EPSILON: &str = "telemetry-epsilon";
+const ARG_SERVE_TELEMETRY_SEED: &str = "telemetry-seed";
+const ARG_SIMULATE_REQUESTS: &str = "requests";
+const ARG_SIMULATE_SEED: &str = "seed";
+const ARG_SIMULATE_MIN_DURATION: &str = "min-duration";
+const ARG_SIMULATE_MAX_DURATION: &str = "max-duration";
+const ARG_SIMULATE_TOP_POIS: &str = "top-pois";
 const ENV_SOLVE_REQUEST: &str = "WILDSIDE_CMDS_SOLVE_REQUEST_PATH";
+const ARG_BUNDLE_OUTPUT: &str = "output";
+const ARG_UNBUNDLE_BUNDLE: &str = "bundle";
+const ENV_BUNDLE_OUTPUT: &str = "WILDSIDE_CMDS_BUNDLE_OUTPUT";
+const ENV_UNBUNDLE_BUNDLE: &str = "WILDSIDE_CMDS_UNBUNDLE_BUNDLE";
+/// File name of the link membership filter written next to `pois.db`.
+#[cfg(feature = "store-sqlite")]
+const LINK_FILTER_FILE_NAME: &str = "links.filter";
+/// File name of the pedestrian routing graph written next to `pois.db`.
+#[cfg(feature = "store-sqlite")]
+const ROUTING_GRAPH_FILE_NAME: &str = "graph.bin";
+/// File name of the routing graph without stairs written next to `pois.db`.
+#[cfg(feature = "store-sqlite")]
+const STEP_FREE_GRAPH_FILE_NAME: &str = "graph-step-free.bin";
+/// File name of the precomputed travel-time table written next to `pois.db`.
+#[cfg(feature = "store-sqlite")]
+const TRAVEL_TIMES_FILE_NAME: &str = "travel_times.bin";
 
 /// Run the Wildside CLI with the current process arguments and environment.
 pub fn run() -> Result<(), CliError> {
     let cli = Cli::try_parse().map_err(CliError::from)?;
     match cli.command {
         Command::Ingest(args) => {
-            let _outcome = run_ingest(args)?;
+            run_ingest(args)?;
         }
         Command::Solve(args) => {
             solve::run_solve(args)?;
         }
-    }
-    Ok(())
-}
-
-fn run_ingest(args: IngestArgs) -> Result<IngestOutcome, CliError> {
-    #[cfg(not(feature = "store-sqlite"))]
-    {
-        drop(args);
-        return Err(CliError::MissingFeature {
-            feature: "store-sqlite",
-            action: "ingest",
-        });
-    }
-    #[cfg(feature = "store-sqlite")]
-    {
-        let config = resolve_ingest_config(args)?;
-        execute_ingest(&config)
-    }
-}
-
-#[cfg(feature = "store-sqlite")]
-fn resolve_ingest_config(args: IngestArgs) -> Result<IngestConfig, CliError> {
-    let config = args.into_config()?;
-    config.validate_sources()?;
-    Ok(config)
-}
-
-#[cfg(feature = "store-sqlite")]
-fn execute_ingest(config: &IngestConfig) -> Result<IngestOutcome, CliError> {
-    let pois_db = config.output_dir.join("pois.db");
-    let spatial_index = config.output_dir.join("pois.rstar");
-    let report = ingest_osm_pbf_report(config.osm_pbf.as_std_path())?;
-
-    persist_pois_to_sqlite(&pois_db, &report.pois).map_err(|source| CliError::PersistPois {
-        path: pois_db.clone(),
-        source,
-    })?;
-
-    let claims = ingest_wikidata_claims(config, &report.pois)?;
-    persist_claims_to_path(pois_db.as_std_path(), &claims).map_err(|source| {
-        CliError::PersistClaims {
-            path: pois_db.clone(),
-            source,
+        Command::Simulate(args) => {
+            simulate::run_simulate(args)?;
         }
-    })?;
-
-    write_spatial_index(spatial_index.as_std_path(), &report.pois).map_err(|source| {
-        CliError::WriteSpatialIndex {
-            path: spatial_index.clone(),
-            source,
+        Command::Serve(args) => {
+            serve::run_serve(args)?;
+        }
+        Command::Bundle(args) => {
+            let _entries = bundle::run_bundle(args)?;
+        }
+        Command::Unbundle(args) => {
+            let _opened = bundle::run_unbundle(args)?;
+        }
+        Command::Validate(args) => {
+            validate::run_validate(args)?;
         }
-    })?;
-
-    Ok(IngestOutcome {
-        pois_db,
-        spatial_index,
-        poi_count: report.pois.len(),
-        claims_count: claims.len(),
-        summary: report.summary,
-    })
-}
-
-#[cfg(feature = "store-sqlite")]
-fn ingest_wikidata_claims(
-    config: &IngestConfig,
-    pois: &[PointOfInterest],
-) -> Result<Vec<EntityClaims>, CliError> {
-    let links = PoiEntityLinks::from_pois(pois.iter());
-    if links.is_empty() {
-        return Ok(Vec::new());
-    }
-    let reader = open_wikidata_dump(&config.wikidata_dump)?;
-    extract_linked_entity_claims(reader, &links).map_err(CliError::from)
-}
-
-#[cfg(feature = "store-sqlite")]
-fn open_wikidata_dump(path: &Utf8Path) -> Result<Box<dyn std::io::Read>, CliError> {
-    let file = open_utf8_file(path).map_err(|source| CliError::OpenWikidataDump {
-        path: path.to_path_buf(),
-        source,
-    })?;
-    if is_bz2(path) {
-        Ok(Box::new(BufReader::new(MultiBzDecoder::new(file))))
-    } else {
-        Ok(Box::new(BufReader::new(file)))
     }
-}
-
-#[cfg(feature = "store-sqlite")]
-fn is_bz2(path: &Utf8Path) -> bool {
-    path.extension()
-        .map(|ext| ext.eq_ignore_ascii_case("bz2"))
-        .unwrap_or(false)
+    Ok(())
 }
 
 #[derive(Debug, Parser)]
@@ -175,120 +136,17 @@ enum Command {
     Ingest(IngestArgs),
     /// Solve a tour request using pre-built artefacts.
     Solve(SolveArgs),
+    /// Evaluate artefacts by solving a synthetic population of requests.
+    Simulate(SimulateArgs),
+    /// Answer solve requests over HTTP from warm, pre-loaded artefacts.
+    Serve(ServeArgs),
+    /// Pack an artefact directory into a single `.wildside` file.
+    Bundle(BundleArgs),
+    /// Verify and extract a `.wildside` file into an artefact directory.
+    Unbundle(UnbundleArgs),
+    /// Cross-check pre-built artefacts for inconsistencies.
+    Validate(ValidateArgs),
 }
 
-/// CLI arguments for the `ingest` subcommand.
-#[derive(Debug, Clone, Parser, Deserialize, Serialize, OrthoConfig, Default)]
-#[command(
-    long_about = "Define the artefact inputs for ingestion. Paths can come \
-                 from CLI flags, configuration files, or environment \
-                 variables.",
-    about = "Describe the OSM and Wikidata inputs for ingestion"
-)]
-#[ortho_config(prefix = "WILDSIDE")]
-struct IngestArgs {
-    /// Path to the OpenStreetMap PBF file.
-    #[arg(long = ARG_OSM_PBF, value_name = "path")]
-    #[serde(default)]
-    osm_pbf: Option<Utf8PathBuf>,
-    /// Path to the Wikidata dump file (JSON/BZ2).
-    #[arg(long = ARG_WIKIDATA_DUMP, value_name = "path")]
-    #[serde(default)]
-    wikidata_dump: Option<Utf8PathBuf>,
-    /// Directory to write the generated artefacts.
-    #[arg(long = ARG_OUTPUT_DIR, value_name = "dir")]
-    #[serde(default)]
-    output_dir: Option<Utf8PathBuf>,
-}
-
-impl IngestArgs {
-    #[cfg(feature = "store-sqlite")]
-    fn into_config(self) -> Result<IngestConfig, CliError> {
-        let merged = self.load_and_merge().map_err(CliError::Configuration)?;
-        IngestConfig::try_from(merged)
-    }
-}
-
-#[cfg(feature = "store-sqlite")]
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct IngestConfig {
-    osm_pbf: Utf8PathBuf,
-    wikidata_dump: Utf8PathBuf,
-    output_dir: Utf8PathBuf,
-}
-
-#[cfg(feature = "store-sqlite")]
-impl IngestConfig {
-    fn validate_sources(&self) -> Result<(), CliError> {
-        Self::require_existing(&self.osm_pbf, ARG_OSM_PBF)?;
-        Self::require_existing(&self.wikidata_dump, ARG_WIKIDATA_DUMP)?;
-        if self.output_dir.exists() && !self.output_dir.is_dir() {
-            return Err(CliError::OutputDirectoryNotDirectory {
-                path: self.output_dir.clone(),
-            });
-        }
-        Ok(())
-    }
-
-    fn require_existing(path: &Utf8Path, field: &'static str) -> Result<(), CliError> {
-        match wildside_fs::file_is_file(path) {
-            Ok(true) => Ok(()),
-            Ok(false) => Err(CliError::MissingSourceFile {
-                field,
-                path: path.to_path_buf(),
-            }),
-            Err(source) if source.kind() == std::io::ErrorKind::NotFound => {
-                Err(CliError::MissingSourceFile {
-                    field,
-                    path: path.to_path_buf(),
-                })
-            }
-            Err(source) => Err(CliError::SourceFileAccess {
-                field,
-                path: path.to_path_buf(),
-                source,
-            }),
-        }
-    }
-}
-
-#[cfg(feature = "store-sqlite")]
-impl TryFrom<IngestArgs> for IngestConfig {
-    type Error = CliError;
-
-    fn try_from(args: IngestArgs) -> Result<Self, Self::Error> {
-        let osm_pbf = args.osm_pbf.ok_or(CliError::MissingArgument {
-            field: ARG_OSM_PBF,
-            env: ENV_OSM_PBF,
-        })?;
-        let wikidata_dump = args.wikidata_dump.ok_or(CliError::MissingArgument {
-            field: ARG_WIKIDATA_DUMP,
-            env: ENV_WIKIDATA_DUMP,
-        })?;
-        let output_dir = args.output_dir.unwrap_or_else(|| Utf8PathBuf::from("."));
-        Ok(Self {
-            osm_pbf,
-            wikidata_dump,
-            output_dir,
-        })
-    }
-}
-
-#[cfg(feature = "store-sqlite")]
-#[derive(Debug, Clone, PartialEq)]
-struct IngestOutcome {
-    pub pois_db: Utf8PathBuf,
-    pub spatial_index: Utf8PathBuf,
-    pub poi_count: usize,
-    pub claims_count: usize,
-    pub summary: OsmIngestSummary,
-}
-
-#[cfg(not(feature = "store-sqlite"))]
-/// When `store-sqlite` is disabled, `IngestOutcome` is a unit type because
-/// ingestion always fails with `MissingFeature`. This preserves the function
-/// signature across feature permutations.
-type IngestOutcome = ();
-
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,329 @@
+//! Minimal HTTP/1.1 front end for `wildside serve`.
+//!
+//! Each connection carries one request and is closed after the response, so
+//! the parser only needs the request line, `Content-Length`, and the body.
+//! The request line and headers are read within a fixed byte budget, so a
+//! client cannot make a worker buffer an endless line.
+//! A fixed pool of workers answers connections; when every worker is busy
+//! and the queue is full, further connections are turned away with `503`
+//! rather than each claiming a thread.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, TrySendError};
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use serde::Serialize;
+use wildside_core::telemetry::ExportedCell;
+use wildside_core::{SolveError, SolveRequest, SolveResponse, WarmUpCheck, WarmUpReport};
+
+/// Largest request body accepted, in bytes.
+pub(crate) const MAX_BODY_BYTES: usize = 1 << 20;
+
+/// Largest request line and header block accepted, in bytes.
+pub(crate) const MAX_HEAD_BYTES: u64 = 16 * 1024;
+
+/// Most header fields accepted in one request.
+pub(crate) const MAX_HEADERS: usize = 64;
+
+/// Connections answered at once.
+const WORKERS: usize = 8;
+
+/// Accepted connections that may wait for a worker before new ones are
+/// turned away.
+const QUEUE_DEPTH: usize = 64;
+
+/// How long a client may take to send its request or read the response.
+const SOCKET_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Delay before the first warm-up retry; each further retry doubles it up
+/// to [`MAX_WARM_UP_RETRY_DELAY`].
+const WARM_UP_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Longest wait between warm-up attempts.
+const MAX_WARM_UP_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// What the front end asks of the engine it serves.
+pub(crate) trait Service: Sync {
+    /// Exercise every component so later requests find them warm.
+    fn warm_up(&self) -> WarmUpReport;
+
+    /// Return the report of the latest warm-up, or `None` while the first is
+    /// still running.
+    fn readiness(&self) -> Option<WarmUpReport>;
+
+    /// Solve `request`.
+    fn solve(&self, request: SolveRequest) -> Result<SolveResponse, SolveError>;
+
+    /// Export the usage rollup under the configured privacy policy.
+    fn telemetry(&self) -> Vec<ExportedCell>;
+}
+
+/// Status line and JSON body sent back to a client.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Response {
+    pub(crate) status: u16,
+    pub(crate) body: String,
+}
+
+/// Body of every error response.
+#[derive(Serialize)]
+struct ErrorBody<'a> {
+    error: &'a str,
+}
+
+/// Body of a `/ready` response.
+#[derive(Serialize)]
+struct ReadyBody {
+    ready: bool,
+    checks: Vec<WarmUpCheck>,
+}
+
+impl Response {
+    fn json<T: Serialize>(status: u16, body: &T) -> Self {
+        match serde_json::to_string(body) {
+            Ok(encoded) => Self {
+                status,
+                body: encoded,
+            },
+            Err(err) => Self::error(500, &err.to_string()),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: serde_json::to_string(&ErrorBody { error: message })
+                .unwrap_or_else(|_| String::from("{}")),
+        }
+    }
+
+    const fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            411 => "Length Required",
+            413 => "Content Too Large",
+            422 => "Unprocessable Content",
+            431 => "Request Header Fields Too Large",
+            503 => "Service Unavailable",
+            _ => "Internal Server Error",
+        }
+    }
+}
+
+/// Warm `service` up in the background while a pool of workers answers
+/// connections from `listener`.
+///
+/// Returns only if the listener stops yielding connections.
+pub(crate) fn serve(listener: &TcpListener, service: &dyn Service) {
+    let (sender, receiver) = mpsc::sync_channel::<TcpStream>(QUEUE_DEPTH);
+    let receiver = Mutex::new(receiver);
+    thread::scope(|scope| {
+        scope.spawn(|| warm_up_until_ready(service, WARM_UP_RETRY_DELAY));
+        for _ in 0..WORKERS {
+            scope.spawn(|| work(service, &receiver));
+        }
+        for stream in listener.incoming().flatten() {
+            if limit_socket(&stream).is_err() {
+                continue;
+            }
+            if let Err(TrySendError::Full(stream)) = sender.try_send(stream) {
+                // A client that hangs up mid-exchange affects only itself.
+                drop(write_response(
+                    &stream,
+                    &Response::error(503, "server busy"),
+                ));
+            }
+        }
+        drop(sender);
+    });
+}
+
+/// Answer queued connections until the acceptor stops.
+fn work(service: &dyn Service, receiver: &Mutex<Receiver<TcpStream>>) {
+    loop {
+        let next = receiver
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .recv();
+        let Ok(stream) = next else {
+            return;
+        };
+        // A client that hangs up mid-exchange affects only itself.
+        drop(handle_connection(service, BufReader::new(&stream), &stream));
+    }
+}
+
+/// Keep a slow or silent client from holding a worker indefinitely.
+fn limit_socket(stream: &TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(SOCKET_TIMEOUT))?;
+    stream.set_write_timeout(Some(SOCKET_TIMEOUT))
+}
+
+/// Warm `service` up until a warm-up passes every check.
+///
+/// `/ready` keeps answering `503` in the meantime. After a failed warm-up the
+/// next waits `delay`, and each further retry waits twice as long as the last,
+/// up to [`MAX_WARM_UP_RETRY_DELAY`], so a routing service that starts after
+/// the engine is picked up without a restart.
+pub(crate) fn warm_up_until_ready(service: &dyn Service, mut delay: Duration) {
+    while !service.warm_up().is_ready() {
+        thread::sleep(delay);
+        delay = delay.saturating_mul(2).min(MAX_WARM_UP_RETRY_DELAY);
+    }
+}
+
+/// Read one request from `reader`, answer it on `writer`, and flush.
+pub(crate) fn handle_connection<R: BufRead, W: Write>(
+    service: &dyn Service,
+    mut reader: R,
+    writer: W,
+) -> io::Result<()> {
+    let response = match read_request(&mut reader)? {
+        Ok((method, path, body)) => route(service, &method, &path, &body),
+        Err(response) => response,
+    };
+    write_response(writer, &response)
+}
+
+/// Send `response` on `writer` and flush.
+pub(crate) fn write_response<W: Write>(mut writer: W, response: &Response) -> io::Result<()> {
+    write!(
+        writer,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        response.status,
+        response.reason(),
+        response.body.len(),
+        response.body
+    )?;
+    writer.flush()
+}
+
+type ParsedRequest = (String, String, Vec<u8>);
+
+/// Request line and the headers the server acts on.
+struct RequestHead {
+    method: String,
+    path: String,
+    content_length: usize,
+}
+
+/// Parse the request line, headers, and body, or return the response that
+/// rejects a malformed request.
+fn read_request<R: BufRead>(reader: &mut R) -> io::Result<Result<ParsedRequest, Response>> {
+    let head = match read_head(reader.by_ref())? {
+        Ok(head) => head,
+        Err(response) => return Ok(Err(response)),
+    };
+    if head.content_length > MAX_BODY_BYTES {
+        return Ok(Err(Response::error(413, "request body too large")));
+    }
+    let mut body = vec![0; head.content_length];
+    reader.read_exact(&mut body)?;
+    Ok(Ok((head.method, head.path, body)))
+}
+
+/// Parse the request line and headers, reading at most [`MAX_HEAD_BYTES`]
+/// and [`MAX_HEADERS`] fields.
+fn read_head<R: BufRead>(reader: R) -> io::Result<Result<RequestHead, Response>> {
+    let mut limited = reader.take(MAX_HEAD_BYTES);
+    let Some(line) = read_head_line(&mut limited)? else {
+        return Ok(Err(head_too_large()));
+    };
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Ok(Err(Response::error(400, "malformed request line")));
+    };
+    let mut head = RequestHead {
+        method: method.to_owned(),
+        path: path.to_owned(),
+        content_length: 0,
+    };
+    // One pass more than the field limit reads the blank line ending them.
+    for _ in 0..=MAX_HEADERS {
+        let Some(header) = read_head_line(&mut limited)? else {
+            return Ok(Err(head_too_large()));
+        };
+        if header.trim().is_empty() {
+            return Ok(Ok(head));
+        }
+        if let Err(response) = apply_header(&mut head, &header) {
+            return Ok(Err(response));
+        }
+    }
+    Ok(Err(Response::error(431, "too many header fields")))
+}
+
+/// Read one line of the request head, or `None` when the byte budget ran
+/// out before the line ended.
+fn read_head_line<R: BufRead>(limited: &mut io::Take<R>) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    limited.read_line(&mut line)?;
+    let truncated = !line.ends_with('\n') && limited.limit() == 0;
+    Ok((!truncated).then_some(line))
+}
+
+/// Record the `Content-Length` in `header`, rejecting transfer codings the
+/// server cannot decode.
+fn apply_header(head: &mut RequestHead, header: &str) -> Result<(), Response> {
+    let Some((field, value)) = header.split_once(':') else {
+        return Ok(());
+    };
+    let name = field.trim();
+    if name.eq_ignore_ascii_case("transfer-encoding") {
+        return Err(Response::error(
+            411,
+            "Transfer-Encoding is not supported; send Content-Length",
+        ));
+    }
+    if name.eq_ignore_ascii_case("content-length") {
+        head.content_length = value
+            .trim()
+            .parse()
+            .map_err(|_| Response::error(400, "invalid Content-Length"))?;
+    }
+    Ok(())
+}
+
+fn head_too_large() -> Response {
+    Response::error(431, "request head too large")
+}
+
+/// Dispatch a parsed request to `service`.
+pub(crate) fn route(service: &dyn Service, method: &str, path: &str, body: &[u8]) -> Response {
+    match (method, path) {
+        ("GET", "/ready") => ready(service),
+        ("POST", "/solve") => solve(service, body),
+        ("GET", "/telemetry") => Response::json(200, &service.telemetry()),
+        (_, "/ready" | "/solve" | "/telemetry") => Response::error(405, "method not allowed"),
+        _ => Response::error(404, "not found"),
+    }
+}
+
+/// Answer `200 OK` once the latest warm-up passed every check, otherwise
+/// `503 Service Unavailable`; the body lists each check either way.
+fn ready(service: &dyn Service) -> Response {
+    let report = service.readiness();
+    let ready = report.as_ref().is_some_and(WarmUpReport::is_ready);
+    let checks = report.map(|found| found.checks).unwrap_or_default();
+    let status = if ready { 200 } else { 503 };
+    Response::json(status, &ReadyBody { ready, checks })
+}
+
+fn solve(service: &dyn Service, body: &[u8]) -> Response {
+    let request: SolveRequest = match serde_json::from_slice(body) {
+        Ok(request) => request,
+        Err(err) => return Response::error(400, &err.to_string()),
+    };
+    match service.solve(request) {
+        Ok(response) => Response::json(200, &response),
+        Err(err @ SolveError::InvalidRequest) => Response::error(400, &err.to_string()),
+        Err(err) => Response::error(422, &err.to_string()),
+    }
+}
@@ -0,0 +1,318 @@
+//! Serve command: answer solve requests over HTTP from one long-lived engine.
+//!
+//! The artefacts are opened once, then the listener is bound and the engine
+//! warms up in the background, retrying until it succeeds. `GET /ready`
+//! answers `503 Service Unavailable` until every warm-up check has passed,
+//! so orchestrators hold traffic back from cold instances; `POST /solve`
+//! accepts a JSON `SolveRequest` and returns the `SolveResponse`. Every solve
+//! is folded into a telemetry rollup that `GET /telemetry` exports under the
+//! configured privacy policy.
+
+mod http;
+
+use std::net::TcpListener;
+use std::sync::{Mutex, PoisonError};
+use std::time::SystemTime;
+
+use camino::Utf8PathBuf;
+use clap::Parser;
+#[cfg(feature = "store-sqlite")]
+use geo::{Coord, Rect};
+use ortho_config::{OrthoConfig, SubcmdConfigMerge};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "store-sqlite")]
+use std::sync::Arc;
+use wildside_core::telemetry::{ExportedCell, PrivacyPolicy, TelemetryRollup};
+use wildside_core::{
+    Engine, InterestPriors, PoiStore, Scorer, SolveError, SolveRequest, SolveResponse, Solver,
+    WarmUpReport,
+};
+#[cfg(feature = "store-sqlite")]
+use wildside_core::{InterestProfile, Theme};
+use wildside_data::routing::HttpTravelTimeProviderConfig;
+
+use crate::solve::SolveConfig;
+#[cfg(feature = "store-sqlite")]
+use crate::solve::{ArtefactPaths, load_interest_priors, make_store_and_deps, select_solver};
+use crate::{
+    ARG_SERVE_LISTEN, ARG_SERVE_TELEMETRY_EPSILON, ARG_SERVE_TELEMETRY_MIN_REQUESTS,
+    ARG_SERVE_TELEMETRY_SEED, ARG_SOLVE_ARTEFACTS_DIR, ARG_SOLVE_OSRM_BASE_URL, ARG_SOLVE_POIS_DB,
+    ARG_SOLVE_POPULARITY, ARG_SOLVE_PRIORS, ARG_SOLVE_SPATIAL_INDEX, ARG_SOLVE_THEME_MAPPING,
+    CliError,
+};
+pub(crate) use http::Service;
+#[cfg(test)]
+pub(crate) use http::{
+    MAX_BODY_BYTES, MAX_HEAD_BYTES, MAX_HEADERS, Response, handle_connection, route, serve,
+    warm_up_until_ready,
+};
+
+/// Address the server listens on when `--listen` is not given.
+const DEFAULT_LISTEN: &str = "127.0.0.1:8080";
+
+/// Visit budget of the canary request solved while warming up, in minutes.
+#[cfg(feature = "store-sqlite")]
+const CANARY_DURATION_MINUTES: u16 = 15;
+
+/// CLI arguments for the `serve` subcommand.
+#[derive(Debug, Clone, Parser, Deserialize, Serialize, OrthoConfig, Default)]
+#[command(
+    long_about = "Load prepared artefacts once and answer solve requests over \
+                 HTTP. POST /solve takes a JSON-encoded SolveRequest; GET \
+                 /ready reports the engine's warm-up checks and answers 503 \
+                 until they all pass. GET /telemetry exports hourly usage \
+                 rollups, optionally withholding sparse cells and adding \
+                 Laplace noise to counts.",
+    about = "Serve solve requests over HTTP"
+)]
+#[ortho_config(prefix = "WILDSIDE")]
+pub(crate) struct ServeArgs {
+    /// Directory containing the default artefact filenames.
+    #[arg(long = ARG_SOLVE_ARTEFACTS_DIR, value_name = "dir")]
+    #[serde(default)]
+    pub(crate) artefacts_dir: Option<Utf8PathBuf>,
+    /// Override the path to the SQLite POI store (`pois.db`).
+    #[arg(long = ARG_SOLVE_POIS_DB, value_name = "path")]
+    #[serde(default)]
+    pub(crate) pois_db: Option<Utf8PathBuf>,
+    /// Override the path to the persisted spatial index (`pois.rstar`).
+    #[arg(long = ARG_SOLVE_SPATIAL_INDEX, value_name = "path")]
+    #[serde(default)]
+    pub(crate) spatial_index: Option<Utf8PathBuf>,
+    /// Override the path to pre-computed popularity scores (`popularity.bin`).
+    #[arg(long = ARG_SOLVE_POPULARITY, value_name = "path")]
+    #[serde(default)]
+    pub(crate) popularity: Option<Utf8PathBuf>,
+    /// Override the path to per-region default interests (`priors.json`).
+    #[arg(long = ARG_SOLVE_PRIORS, value_name = "path")]
+    #[serde(default)]
+    pub(crate) priors: Option<Utf8PathBuf>,
+    /// TOML or JSON file mapping themes to Wikidata claims, replacing the
+    /// built-in mapping.
+    #[arg(long = ARG_SOLVE_THEME_MAPPING, value_name = "path")]
+    #[serde(default)]
+    pub(crate) theme_mapping: Option<Utf8PathBuf>,
+    /// Base URL for the OSRM server (e.g. "http://localhost:5000").
+    #[arg(long = ARG_SOLVE_OSRM_BASE_URL, value_name = "url")]
+    #[serde(default)]
+    pub(crate) osrm_base_url: Option<String>,
+    /// Socket address to listen on (default 127.0.0.1:8080).
+    #[arg(long = ARG_SERVE_LISTEN, value_name = "address")]
+    #[serde(default)]
+    pub(crate) listen: Option<String>,
+    /// Withhold telemetry cells with fewer than this many requests.
+    #[arg(long = ARG_SERVE_TELEMETRY_MIN_REQUESTS, value_name = "k")]
+    #[serde(default)]
+    pub(crate) telemetry_min_requests: Option<u64>,
+    /// Add Laplace noise spending this privacy budget per telemetry export.
+    #[arg(long = ARG_SERVE_TELEMETRY_EPSILON, value_name = "epsilon")]
+    #[serde(default)]
+    pub(crate) telemetry_epsilon: Option<f64>,
+    /// Seed the telemetry noise for reproducible exports (default: random
+    /// per process).
+    #[arg(long = ARG_SERVE_TELEMETRY_SEED, value_name = "seed")]
+    #[serde(default)]
+    pub(crate) telemetry_seed: Option<u64>,
+}
+
+impl ServeArgs {
+    fn into_config(self) -> Result<ServeConfig, CliError> {
+        let merged = self.load_and_merge().map_err(CliError::Configuration)?;
+        ServeConfig::try_from(merged)
+    }
+}
+
+/// Resolved `serve` command configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ServeConfig {
+    pub(crate) pois_db: Utf8PathBuf,
+    pub(crate) spatial_index: Utf8PathBuf,
+    pub(crate) popularity: Utf8PathBuf,
+    pub(crate) priors: Utf8PathBuf,
+    pub(crate) theme_mapping: Option<Utf8PathBuf>,
+    pub(crate) osrm_base_url: String,
+    pub(crate) listen: String,
+    pub(crate) privacy: PrivacyPolicy,
+}
+
+impl ServeConfig {
+    #[cfg(feature = "store-sqlite")]
+    fn artefacts(&self) -> ArtefactPaths<'_> {
+        ArtefactPaths {
+            pois_db: &self.pois_db,
+            spatial_index: &self.spatial_index,
+            popularity: &self.popularity,
+            theme_mapping: self.theme_mapping.as_deref(),
+            osrm_base_url: &self.osrm_base_url,
+        }
+    }
+
+    fn validate_sources(&self) -> Result<(), CliError> {
+        SolveConfig::require_existing(&self.pois_db, ARG_SOLVE_POIS_DB)?;
+        SolveConfig::require_existing(&self.spatial_index, ARG_SOLVE_SPATIAL_INDEX)?;
+        SolveConfig::allow_missing(&self.popularity, ARG_SOLVE_POPULARITY)?;
+        SolveConfig::allow_missing(&self.priors, ARG_SOLVE_PRIORS)?;
+        if let Some(theme_mapping) = &self.theme_mapping {
+            SolveConfig::require_existing(theme_mapping, ARG_SOLVE_THEME_MAPPING)?;
+        }
+        Ok(())
+    }
+}
+
+impl TryFrom<ServeArgs> for ServeConfig {
+    type Error = CliError;
+
+    fn try_from(args: ServeArgs) -> Result<Self, Self::Error> {
+        let artefacts_dir = args.artefacts_dir.unwrap_or_else(|| Utf8PathBuf::from("."));
+        let mut privacy =
+            PrivacyPolicy::none().with_min_requests(args.telemetry_min_requests.unwrap_or(0));
+        if let Some(epsilon) = args.telemetry_epsilon {
+            privacy = privacy
+                .with_laplace_noise(epsilon)
+                .map_err(CliError::InvalidTelemetryPolicy)?;
+        }
+        // A fixed seed keeps repeated exports from averaging the noise away.
+        privacy = privacy.with_noise_seed(args.telemetry_seed.unwrap_or_else(rand::random));
+        Ok(Self {
+            pois_db: args
+                .pois_db
+                .unwrap_or_else(|| artefacts_dir.join("pois.db")),
+            spatial_index: args
+                .spatial_index
+                .unwrap_or_else(|| artefacts_dir.join("pois.rstar")),
+            popularity: args
+                .popularity
+                .unwrap_or_else(|| artefacts_dir.join("popularity.bin")),
+            priors: args
+                .priors
+                .unwrap_or_else(|| artefacts_dir.join("priors.json")),
+            theme_mapping: args.theme_mapping,
+            osrm_base_url: args
+                .osrm_base_url
+                .unwrap_or_else(|| HttpTravelTimeProviderConfig::default().base_url),
+            listen: args.listen.unwrap_or_else(|| DEFAULT_LISTEN.to_owned()),
+            privacy,
+        })
+    }
+}
+
+/// An [`Engine`] answering HTTP requests, blending regional priors into each
+/// request's interests as `wildside solve` does and recording every solve in
+/// a telemetry rollup.
+pub(crate) struct EngineService<S, C, V> {
+    engine: Engine<S, C, V>,
+    priors: Option<InterestPriors>,
+    privacy: PrivacyPolicy,
+    telemetry: Mutex<TelemetryRollup>,
+}
+
+impl<S, C, V> EngineService<S, C, V> {
+    #[cfg_attr(
+        all(not(feature = "store-sqlite"), not(test)),
+        expect(dead_code, reason = "engines are only built over the SQLite store")
+    )]
+    pub(crate) fn new(
+        engine: Engine<S, C, V>,
+        priors: Option<InterestPriors>,
+        privacy: PrivacyPolicy,
+    ) -> Self {
+        Self {
+            engine,
+            priors,
+            privacy,
+            telemetry: Mutex::new(TelemetryRollup::default()),
+        }
+    }
+}
+
+impl<S, C, V> Service for EngineService<S, C, V>
+where
+    S: PoiStore + Sync,
+    C: Scorer,
+    V: Solver,
+{
+    fn warm_up(&self) -> WarmUpReport {
+        self.engine.warm_up()
+    }
+
+    fn readiness(&self) -> Option<WarmUpReport> {
+        self.engine.readiness()
+    }
+
+    fn solve(&self, mut request: SolveRequest) -> Result<SolveResponse, SolveError> {
+        request.validate()?;
+        if let Some(priors) = &self.priors {
+            request.interests = priors.blend(request.start, &request.interests);
+        }
+        let outcome = self.engine.solve(&request);
+        self.telemetry
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .record(SystemTime::now(), &request, outcome.as_ref());
+        outcome
+    }
+
+    fn telemetry(&self) -> Vec<ExportedCell> {
+        self.telemetry
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .export(&self.privacy)
+    }
+}
+
+pub(super) fn run_serve(args: ServeArgs) -> Result<(), CliError> {
+    let config = args.into_config()?;
+    config.validate_sources()?;
+    let service = build_service(&config)?;
+    let listener = TcpListener::bind(&config.listen).map_err(|source| CliError::BindServer {
+        address: config.listen.clone(),
+        source,
+    })?;
+    http::serve(&listener, service.as_ref());
+    Ok(())
+}
+
+/// Open the artefacts and assemble an engine whose solver shares the
+/// engine's store and scorer, so warming up the engine warms the solver.
+#[cfg(feature = "store-sqlite")]
+fn build_service(config: &ServeConfig) -> Result<Box<dyn Service>, CliError> {
+    let (store, provider, scorer) = make_store_and_deps(config.artefacts())?;
+    let store = Arc::new(store);
+    let scorer = Arc::new(scorer);
+    let canary = canary_request(store.bounds());
+    let solver = select_solver(Arc::clone(&store), provider, Arc::clone(&scorer))?;
+    Ok(Box::new(EngineService::new(
+        Engine::new(store, scorer, solver, canary),
+        load_interest_priors(&config.priors)?,
+        config.privacy,
+    )))
+}
+
+#[cfg(not(feature = "store-sqlite"))]
+fn build_service(config: &ServeConfig) -> Result<Box<dyn Service>, CliError> {
+    let _ = config;
+    Err(CliError::MissingFeature {
+        feature: "store-sqlite",
+        action: "serve",
+    })
+}
+
+/// Build a short request from the centre of `bounds` that weights every
+/// theme, so the canary solve scores and routes real POIs.
+///
+/// Artefacts without POIs have no bounds; the canary then starts at the
+/// origin and warming up only proves the components respond.
+#[cfg(feature = "store-sqlite")]
+pub(crate) fn canary_request(bounds: Option<Rect<f64>>) -> SolveRequest {
+    let interests = Theme::ALL
+        .into_iter()
+        .fold(InterestProfile::new(), |profile, theme| {
+            profile.with_weight(theme, 1.0)
+        });
+    SolveRequest {
+        start: bounds.map_or(Coord { x: 0.0, y: 0.0 }, |found| found.center()),
+        duration_minutes: CANARY_DURATION_MINUTES,
+        interests,
+        ..Default::default()
+    }
+}
@@ -0,0 +1,170 @@
+//! Arguments of the `simulate` command and the configuration resolved from
+//! them.
+
+use camino::Utf8PathBuf;
+use clap::Parser;
+use ortho_config::{OrthoConfig, SubcmdConfigMerge};
+use serde::{Deserialize, Serialize};
+use wildside_data::routing::HttpTravelTimeProviderConfig;
+
+#[cfg(feature = "store-sqlite")]
+use crate::solve::ArtefactPaths;
+use crate::solve::SolveConfig;
+use crate::{
+    ARG_SIMULATE_MAX_DURATION, ARG_SIMULATE_MIN_DURATION, ARG_SIMULATE_REQUESTS, ARG_SIMULATE_SEED,
+    ARG_SIMULATE_TOP_POIS, ARG_SOLVE_ARTEFACTS_DIR, ARG_SOLVE_OSRM_BASE_URL, ARG_SOLVE_POIS_DB,
+    ARG_SOLVE_POPULARITY, ARG_SOLVE_SPATIAL_INDEX, CliError,
+};
+
+const DEFAULT_REQUESTS: u32 = 100;
+const DEFAULT_MIN_DURATION_MINUTES: u16 = 30;
+const DEFAULT_MAX_DURATION_MINUTES: u16 = 180;
+const DEFAULT_TOP_POIS: usize = 20;
+
+/// CLI arguments for the `simulate` subcommand.
+#[derive(Debug, Clone, Parser, Deserialize, Serialize, OrthoConfig, Default)]
+#[command(
+    long_about = "Generate a seeded population of synthetic solve requests \
+                 within the artefact bounds, solve each one against the \
+                 prepared artefacts, and print aggregate quality metrics \
+                 (top-POI coverage, mean score, duration utilisation) as JSON.",
+    about = "Evaluate artefacts against synthetic requests"
+)]
+#[ortho_config(prefix = "WILDSIDE")]
+pub(crate) struct SimulateArgs {
+    /// Directory containing the default artefact filenames.
+    #[arg(long = ARG_SOLVE_ARTEFACTS_DIR, value_name = "dir")]
+    #[serde(default)]
+    pub(crate) artefacts_dir: Option<Utf8PathBuf>,
+    /// Override the path to the SQLite POI store (`pois.db`).
+    #[arg(long = ARG_SOLVE_POIS_DB, value_name = "path")]
+    #[serde(default)]
+    pub(crate) pois_db: Option<Utf8PathBuf>,
+    /// Override the path to the persisted spatial index (`pois.rstar`).
+    #[arg(long = ARG_SOLVE_SPATIAL_INDEX, value_name = "path")]
+    #[serde(default)]
+    pub(crate) spatial_index: Option<Utf8PathBuf>,
+    /// Override the path to pre-computed popularity scores (`popularity.bin`).
+    #[arg(long = ARG_SOLVE_POPULARITY, value_name = "path")]
+    #[serde(default)]
+    pub(crate) popularity: Option<Utf8PathBuf>,
+    /// Base URL for the OSRM server (e.g. "http://localhost:5000").
+    #[arg(long = ARG_SOLVE_OSRM_BASE_URL, value_name = "url")]
+    #[serde(default)]
+    pub(crate) osrm_base_url: Option<String>,
+    /// Number of synthetic requests to generate (default 100).
+    #[arg(long = ARG_SIMULATE_REQUESTS, value_name = "count")]
+    #[serde(default)]
+    pub(crate) requests: Option<u32>,
+    /// Seed for the request generator (default 0).
+    #[arg(long = ARG_SIMULATE_SEED, value_name = "seed")]
+    #[serde(default)]
+    pub(crate) seed: Option<u64>,
+    /// Shortest sampled visit budget in minutes (default 30).
+    #[arg(long = ARG_SIMULATE_MIN_DURATION, value_name = "minutes")]
+    #[serde(default)]
+    pub(crate) min_duration: Option<u16>,
+    /// Longest sampled visit budget in minutes (default 180).
+    #[arg(long = ARG_SIMULATE_MAX_DURATION, value_name = "minutes")]
+    #[serde(default)]
+    pub(crate) max_duration: Option<u16>,
+    /// Number of top-ranked POIs used for the coverage metric (default 20).
+    #[arg(long = ARG_SIMULATE_TOP_POIS, value_name = "count")]
+    #[serde(default)]
+    pub(crate) top_pois: Option<usize>,
+}
+
+impl SimulateArgs {
+    pub(super) fn into_config(self) -> Result<SimulateConfig, CliError> {
+        let merged = self.load_and_merge().map_err(CliError::Configuration)?;
+        SimulateConfig::try_from(merged)
+    }
+}
+
+/// Parameters controlling the synthetic request population.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SimulationPlan {
+    pub(crate) requests: u32,
+    pub(crate) seed: u64,
+    pub(crate) min_duration_minutes: u16,
+    pub(crate) max_duration_minutes: u16,
+    pub(crate) top_pois: usize,
+}
+
+/// Resolved `simulate` command configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SimulateConfig {
+    pub(crate) pois_db: Utf8PathBuf,
+    pub(crate) spatial_index: Utf8PathBuf,
+    pub(crate) popularity: Utf8PathBuf,
+    pub(crate) osrm_base_url: String,
+    pub(crate) plan: SimulationPlan,
+}
+
+impl SimulateConfig {
+    #[cfg(feature = "store-sqlite")]
+    pub(crate) fn artefacts(&self) -> ArtefactPaths<'_> {
+        ArtefactPaths {
+            pois_db: &self.pois_db,
+            spatial_index: &self.spatial_index,
+            popularity: &self.popularity,
+            theme_mapping: None,
+            osrm_base_url: &self.osrm_base_url,
+        }
+    }
+
+    pub(super) fn validate_sources(&self) -> Result<(), CliError> {
+        SolveConfig::require_existing(&self.pois_db, ARG_SOLVE_POIS_DB)?;
+        SolveConfig::require_existing(&self.spatial_index, ARG_SOLVE_SPATIAL_INDEX)?;
+        SolveConfig::allow_missing(&self.popularity, ARG_SOLVE_POPULARITY)
+    }
+}
+
+impl TryFrom<SimulateArgs> for SimulateConfig {
+    type Error = CliError;
+
+    fn try_from(args: SimulateArgs) -> Result<Self, Self::Error> {
+        let artefacts_dir = args.artefacts_dir.unwrap_or_else(|| Utf8PathBuf::from("."));
+        let plan = SimulationPlan {
+            requests: args.requests.unwrap_or(DEFAULT_REQUESTS),
+            seed: args.seed.unwrap_or_default(),
+            min_duration_minutes: args.min_duration.unwrap_or(DEFAULT_MIN_DURATION_MINUTES),
+            max_duration_minutes: args.max_duration.unwrap_or(DEFAULT_MAX_DURATION_MINUTES),
+            top_pois: args.top_pois.unwrap_or(DEFAULT_TOP_POIS),
+        };
+        validate_plan(&plan)?;
+
+        Ok(Self {
+            pois_db: args
+                .pois_db
+                .unwrap_or_else(|| artefacts_dir.join("pois.db")),
+            spatial_index: args
+                .spatial_index
+                .unwrap_or_else(|| artefacts_dir.join("pois.rstar")),
+            popularity: args
+                .popularity
+                .unwrap_or_else(|| artefacts_dir.join("popularity.bin")),
+            osrm_base_url: args
+                .osrm_base_url
+                .unwrap_or_else(|| HttpTravelTimeProviderConfig::default().base_url),
+            plan,
+        })
+    }
+}
+
+fn validate_plan(plan: &SimulationPlan) -> Result<(), CliError> {
+    let reason = if plan.requests == 0 {
+        Some(format!("--{ARG_SIMULATE_REQUESTS} must be positive"))
+    } else if plan.min_duration_minutes == 0 {
+        Some(format!("--{ARG_SIMULATE_MIN_DURATION} must be positive"))
+    } else if plan.min_duration_minutes > plan.max_duration_minutes {
+        Some(format!(
+            "--{ARG_SIMULATE_MIN_DURATION} exceeds --{ARG_SIMULATE_MAX_DURATION}"
+        ))
+    } else {
+        None
+    };
+    reason.map_or(Ok(()), |reason| {
+        Err(CliError::InvalidSimulationPlan { reason })
+    })
+}
@@ -0,0 +1,262 @@
+//! Simulate command: solve a synthetic population of requests and report
+//! aggregate route quality.
+//!
+//! The harness samples start points within the artefact bounds, random
+//! interest profiles, and visit budgets from a seeded generator, so two runs
+//! with the same seed and artefacts produce identical populations. Comparing
+//! reports before and after an artefact or scorer change shows its effect at
+//! scale without hand-written golden requests.
+
+use std::{collections::HashSet, io::Write};
+
+use geo::{Coord, Rect};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::Serialize;
+#[cfg(feature = "store-sqlite")]
+use wildside_core::PoiStore;
+use wildside_core::{InterestProfile, PointOfInterest, SolveRequest, Solver, Theme};
+
+use crate::CliError;
+#[cfg(feature = "store-sqlite")]
+use crate::solve::{build_solver_with_features, make_store_and_deps};
+
+mod config;
+
+pub(crate) use config::{SimulateArgs, SimulateConfig, SimulationPlan};
+
+/// Probability that a sampled profile includes any given theme.
+const THEME_INCLUSION_PROBABILITY: f64 = 0.5;
+
+/// Dependencies assembled for a simulation run.
+pub(crate) struct SimulationDependencies {
+    /// Solver used for every synthetic request.
+    pub(crate) solver: Box<dyn Solver>,
+    /// Every POI in the artefacts; request starts are sampled within their
+    /// bounds.
+    pub(crate) candidates: Vec<PointOfInterest>,
+    /// Identifiers of the top-ranked POIs used for the coverage metric.
+    pub(crate) top_pois: HashSet<u64>,
+}
+
+/// Builds the solver and supporting data for a simulation run.
+pub(super) trait SimulationBuilder {
+    fn build(&self, config: &SimulateConfig) -> Result<SimulationDependencies, CliError>;
+}
+
+pub(super) struct DefaultSimulationBuilder;
+
+impl SimulationBuilder for DefaultSimulationBuilder {
+    fn build(&self, config: &SimulateConfig) -> Result<SimulationDependencies, CliError> {
+        #[cfg(feature = "store-sqlite")]
+        {
+            let (store, provider, scorer) = make_store_and_deps(config.artefacts())?;
+            let candidates: Vec<PointOfInterest> = match store.bounds() {
+                Some(bounds) => store.get_pois_in_bbox(&bounds)?.collect(),
+                None => Vec::new(),
+            };
+            let top_pois = top_poi_ids(&candidates, &scorer, config.plan.top_pois);
+            let solver = build_solver_with_features((store, provider, scorer))?;
+            Ok(SimulationDependencies {
+                solver,
+                candidates,
+                top_pois,
+            })
+        }
+        #[cfg(not(feature = "store-sqlite"))]
+        {
+            let _ = config;
+            Err(CliError::MissingFeature {
+                feature: "store-sqlite",
+                action: "simulate",
+            })
+        }
+    }
+}
+
+/// Aggregate quality metrics for a simulated population.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct SimulationReport {
+    /// Number of generated requests.
+    pub(crate) requests: u32,
+    /// Requests the solver answered successfully.
+    pub(crate) solved: u32,
+    /// Requests the solver rejected or failed to answer.
+    pub(crate) failed: u32,
+    /// Mean `SolveResponse::score` across solved requests.
+    pub(crate) mean_score: f32,
+    /// Mean ratio of route duration to the requested budget.
+    pub(crate) mean_duration_utilisation: f32,
+    /// Mean number of POIs per solved route.
+    pub(crate) mean_route_pois: f32,
+    /// Size of the top-ranked POI set used for coverage.
+    pub(crate) top_pois: usize,
+    /// Fraction of top-ranked POIs visited by at least one route.
+    pub(crate) top_poi_coverage: f32,
+}
+
+/// Sample `plan.requests` synthetic requests inside `bounds`.
+pub(crate) fn generate_requests(bounds: Rect<f64>, plan: &SimulationPlan) -> Vec<SolveRequest> {
+    let mut rng = ChaCha8Rng::seed_from_u64(plan.seed);
+    (0..plan.requests)
+        .map(|_| SolveRequest {
+            start: Coord {
+                x: sample_between(&mut rng, bounds.min().x, bounds.max().x),
+                y: sample_between(&mut rng, bounds.min().y, bounds.max().y),
+            },
+            duration_minutes: rng.gen_range(plan.min_duration_minutes..=plan.max_duration_minutes),
+            interests: sample_profile(&mut rng),
+            seed: rng.r#gen(),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn sample_between(rng: &mut ChaCha8Rng, min: f64, max: f64) -> f64 {
+    if min < max {
+        rng.gen_range(min..=max)
+    } else {
+        min
+    }
+}
+
+fn sample_profile(rng: &mut ChaCha8Rng) -> InterestProfile {
+    let mut profile = InterestProfile::new();
+    for theme in Theme::ALL {
+        if rng.gen_bool(THEME_INCLUSION_PROBABILITY) {
+            profile.set_weight(theme, rng.gen_range(0.0..=1.0));
+        }
+    }
+    profile
+}
+
+/// Return the bounding rectangle of `pois`, or `None` when empty.
+pub(crate) fn candidate_bounds(pois: &[PointOfInterest]) -> Option<Rect<f64>> {
+    let first = pois.first()?.location;
+    let (min, max) = pois.iter().fold((first, first), |(min, max), poi| {
+        (
+            Coord {
+                x: min.x.min(poi.location.x),
+                y: min.y.min(poi.location.y),
+            },
+            Coord {
+                x: max.x.max(poi.location.x),
+                y: max.y.max(poi.location.y),
+            },
+        )
+    });
+    Some(Rect::new(min, max))
+}
+
+/// Rank candidates under a profile that weights every theme fully and return
+/// the ids of the best `count`, breaking ties by id.
+#[cfg(any(test, feature = "store-sqlite"))]
+pub(crate) fn top_poi_ids<S: wildside_core::Scorer>(
+    candidates: &[PointOfInterest],
+    scorer: &S,
+    count: usize,
+) -> HashSet<u64> {
+    let profile = Theme::ALL
+        .into_iter()
+        .fold(InterestProfile::new(), |profile, theme| {
+            profile.with_weight(theme, 1.0)
+        });
+    let mut ranked: Vec<(f32, u64)> = scorer
+        .score_many(candidates, &profile)
+        .into_iter()
+        .zip(candidates.iter().map(|poi| poi.id))
+        .collect();
+    ranked.sort_unstable_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+    ranked.into_iter().take(count).map(|(_, id)| id).collect()
+}
+
+/// Solve every request and aggregate the outcomes.
+pub(crate) fn run_simulation(
+    solver: &dyn Solver,
+    requests: &[SolveRequest],
+    top_pois: &HashSet<u64>,
+) -> SimulationReport {
+    let mut solved = 0_u32;
+    let mut score_sum = 0.0_f32;
+    let mut utilisation_sum = 0.0_f32;
+    let mut poi_sum = 0_usize;
+    let mut covered = HashSet::new();
+
+    for request in requests {
+        let Ok(response) = solver.solve(request) else {
+            continue;
+        };
+        solved += 1;
+        score_sum += response.score;
+        let budget_secs = f32::from(request.duration_minutes) * 60.0;
+        utilisation_sum += response.route.total_duration().as_secs_f32() / budget_secs;
+        poi_sum += response.route.pois().len();
+        covered.extend(
+            response
+                .route
+                .pois()
+                .iter()
+                .map(|poi| poi.id)
+                .filter(|id| top_pois.contains(id)),
+        );
+    }
+
+    let requested = u32::try_from(requests.len()).unwrap_or(u32::MAX);
+    SimulationReport {
+        requests: requested,
+        solved,
+        failed: requested - solved,
+        mean_score: mean(score_sum, solved),
+        mean_duration_utilisation: mean(utilisation_sum, solved),
+        mean_route_pois: mean(poi_sum as f32, solved),
+        top_pois: top_pois.len(),
+        top_poi_coverage: if top_pois.is_empty() {
+            0.0
+        } else {
+            covered.len() as f32 / top_pois.len() as f32
+        },
+    }
+}
+
+fn mean(sum: f32, count: u32) -> f32 {
+    if count == 0 { 0.0 } else { sum / count as f32 }
+}
+
+pub(super) fn run_simulate(args: SimulateArgs) -> Result<(), CliError> {
+    let mut stdout = std::io::stdout().lock();
+    run_simulate_with(args, &DefaultSimulationBuilder, &mut stdout)
+}
+
+pub(super) fn run_simulate_with(
+    args: SimulateArgs,
+    builder: &dyn SimulationBuilder,
+    writer: &mut dyn Write,
+) -> Result<(), CliError> {
+    let config = args.into_config()?;
+    config.validate_sources()?;
+    let report = execute_simulation(&config, builder)?;
+    write_report(writer, &report)
+}
+
+fn execute_simulation(
+    config: &SimulateConfig,
+    builder: &dyn SimulationBuilder,
+) -> Result<SimulationReport, CliError> {
+    let deps = builder.build(config)?;
+    let bounds =
+        candidate_bounds(&deps.candidates).ok_or_else(|| CliError::EmptySimulationArtefacts {
+            path: config.spatial_index.clone(),
+        })?;
+    let requests = generate_requests(bounds, &config.plan);
+    Ok(run_simulation(
+        deps.solver.as_ref(),
+        &requests,
+        &deps.top_pois,
+    ))
+}
+
+fn write_report(writer: &mut dyn Write, report: &SimulationReport) -> Result<(), CliError> {
+    let payload =
+        serde_json::to_string_pretty(report).map_err(CliError::SerializeSimulationReport)?;
+    writeln!(writer, "{payload}").map_err(CliError::WriteSolveOutput)
+}
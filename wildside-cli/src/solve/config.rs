@@ -0,0 +1,201 @@
+//! Arguments of the `solve` command and the configuration resolved from them.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::Parser;
+use ortho_config::{OrthoConfig, SubcmdConfigMerge};
+use serde::{Deserialize, Serialize};
+use wildside_data::routing::HttpTravelTimeProviderConfig;
+
+use crate::{
+    ARG_SOLVE_ARTEFACTS_DIR, ARG_SOLVE_OSRM_BASE_URL, ARG_SOLVE_POIS_DB, ARG_SOLVE_POPULARITY,
+    ARG_SOLVE_PRIORS, ARG_SOLVE_REQUEST, ARG_SOLVE_SPATIAL_INDEX, ARG_SOLVE_THEME_MAPPING,
+    CliError, ENV_SOLVE_REQUEST,
+};
+
+/// CLI arguments for the `solve` subcommand.
+#[derive(Debug, Clone, Parser, Deserialize, Serialize, OrthoConfig, Default)]
+#[command(
+    long_about = "Solve a tour request by loading prepared artefacts \
+                 (pois.db, pois.rstar, popularity.bin, priors.json) and \
+                 reading travel times from travel_times.bin or routing over \
+                 graph.bin when present, otherwise querying an OSRM instance \
+                 for travel time matrices. Step-free requests route over \
+                 graph-step-free.bin. The request itself is \
+                 provided as a JSON-encoded SolveRequest.",
+    about = "Solve an orienteering request"
+)]
+#[ortho_config(prefix = "WILDSIDE")]
+pub(crate) struct SolveArgs {
+    /// Path to a JSON file containing a SolveRequest.
+    #[arg(value_name = "path")]
+    #[serde(default)]
+    pub(crate) request_path: Option<Utf8PathBuf>,
+    /// Directory containing the default artefact filenames.
+    #[arg(long = ARG_SOLVE_ARTEFACTS_DIR, value_name = "dir")]
+    #[serde(default)]
+    pub(crate) artefacts_dir: Option<Utf8PathBuf>,
+    /// Override the path to the SQLite POI store (`pois.db`).
+    #[arg(long = ARG_SOLVE_POIS_DB, value_name = "path")]
+    #[serde(default)]
+    pub(crate) pois_db: Option<Utf8PathBuf>,
+    /// Override the path to the persisted spatial index (`pois.rstar`).
+    #[arg(long = ARG_SOLVE_SPATIAL_INDEX, value_name = "path")]
+    #[serde(default)]
+    pub(crate) spatial_index: Option<Utf8PathBuf>,
+    /// Override the path to pre-computed popularity scores (`popularity.bin`).
+    #[arg(long = ARG_SOLVE_POPULARITY, value_name = "path")]
+    #[serde(default)]
+    pub(crate) popularity: Option<Utf8PathBuf>,
+    /// Override the path to per-region default interests (`priors.json`).
+    #[arg(long = ARG_SOLVE_PRIORS, value_name = "path")]
+    #[serde(default)]
+    pub(crate) priors: Option<Utf8PathBuf>,
+    /// TOML or JSON file mapping themes to Wikidata claims, replacing the
+    /// built-in mapping.
+    #[arg(long = ARG_SOLVE_THEME_MAPPING, value_name = "path")]
+    #[serde(default)]
+    pub(crate) theme_mapping: Option<Utf8PathBuf>,
+    /// Base URL for the OSRM server (e.g. "http://localhost:5000").
+    #[arg(long = ARG_SOLVE_OSRM_BASE_URL, value_name = "url")]
+    #[serde(default)]
+    pub(crate) osrm_base_url: Option<String>,
+}
+
+impl SolveArgs {
+    pub(crate) fn into_config(self) -> Result<SolveConfig, CliError> {
+        let merged = self.load_and_merge().map_err(CliError::Configuration)?;
+        SolveConfig::try_from(merged)
+    }
+}
+
+/// Resolved `solve` command configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SolveConfig {
+    /// Path to the JSON request file.
+    pub(crate) request_path: Utf8PathBuf,
+    /// Path to `pois.db` SQLite database.
+    pub(crate) pois_db: Utf8PathBuf,
+    /// Path to `pois.rstar` persisted spatial index.
+    pub(crate) spatial_index: Utf8PathBuf,
+    /// Path to `popularity.bin` popularity scores.
+    pub(crate) popularity: Utf8PathBuf,
+    /// Path to `priors.json` per-region default interests.
+    pub(crate) priors: Utf8PathBuf,
+    /// Path to a theme-to-claim mapping file, when one was supplied.
+    pub(crate) theme_mapping: Option<Utf8PathBuf>,
+    /// Base URL for the OSRM table service.
+    pub(crate) osrm_base_url: String,
+}
+
+/// Artefact locations needed to assemble a store, scorer, and solver.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(
+    not(feature = "store-sqlite"),
+    expect(dead_code, reason = "artefacts are only opened with the SQLite store")
+)]
+pub(crate) struct ArtefactPaths<'a> {
+    pub(crate) pois_db: &'a Utf8Path,
+    pub(crate) spatial_index: &'a Utf8Path,
+    pub(crate) popularity: &'a Utf8Path,
+    pub(crate) theme_mapping: Option<&'a Utf8Path>,
+    pub(crate) osrm_base_url: &'a str,
+}
+
+impl SolveConfig {
+    pub(crate) fn artefacts(&self) -> ArtefactPaths<'_> {
+        ArtefactPaths {
+            pois_db: &self.pois_db,
+            spatial_index: &self.spatial_index,
+            popularity: &self.popularity,
+            theme_mapping: self.theme_mapping.as_deref(),
+            osrm_base_url: &self.osrm_base_url,
+        }
+    }
+
+    pub(crate) fn validate_sources(&self) -> Result<(), CliError> {
+        Self::require_existing(&self.request_path, ARG_SOLVE_REQUEST)?;
+        Self::require_existing(&self.pois_db, ARG_SOLVE_POIS_DB)?;
+        Self::require_existing(&self.spatial_index, ARG_SOLVE_SPATIAL_INDEX)?;
+        Self::allow_missing(&self.popularity, ARG_SOLVE_POPULARITY)?;
+        Self::allow_missing(&self.priors, ARG_SOLVE_PRIORS)?;
+        if let Some(theme_mapping) = &self.theme_mapping {
+            Self::require_existing(theme_mapping, ARG_SOLVE_THEME_MAPPING)?;
+        }
+        Ok(())
+    }
+
+    /// Accept an absent optional artefact but reject paths that are not files.
+    pub(crate) fn allow_missing(path: &Utf8Path, field: &'static str) -> Result<(), CliError> {
+        match Self::require_existing(path, field) {
+            Err(CliError::MissingSourceFile { .. }) => Ok(()),
+            other => other,
+        }
+    }
+
+    pub(crate) fn require_existing(path: &Utf8Path, field: &'static str) -> Result<(), CliError> {
+        match wildside_fs::file_is_file(path) {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(CliError::SourcePathNotFile {
+                field,
+                path: path.to_path_buf(),
+            }),
+            Err(source) if source.kind() == std::io::ErrorKind::NotFound => {
+                Err(CliError::MissingSourceFile {
+                    field,
+                    path: path.to_path_buf(),
+                })
+            }
+            Err(source) => Err(CliError::InspectSourcePath {
+                field,
+                path: path.to_path_buf(),
+                source,
+            }),
+        }
+    }
+}
+
+impl TryFrom<SolveArgs> for SolveConfig {
+    type Error = CliError;
+
+    fn try_from(args: SolveArgs) -> Result<Self, Self::Error> {
+        let request_path = args.request_path.ok_or(CliError::MissingArgument {
+            field: ARG_SOLVE_REQUEST,
+            env: ENV_SOLVE_REQUEST,
+        })?;
+
+        let artefacts_dir = args.artefacts_dir.unwrap_or_else(|| Utf8PathBuf::from("."));
+        let pois_db = args
+            .pois_db
+            .unwrap_or_else(|| artefacts_dir.join("pois.db"));
+        let spatial_index = args
+            .spatial_index
+            .unwrap_or_else(|| artefacts_dir.join("pois.rstar"));
+        let popularity = args
+            .popularity
+            .unwrap_or_else(|| artefacts_dir.join("popularity.bin"));
+        let priors = args
+            .priors
+            .unwrap_or_else(|| artefacts_dir.join("priors.json"));
+
+        let default_base_url = HttpTravelTimeProviderConfig::default().base_url;
+        let osrm_base_url = args.osrm_base_url.unwrap_or(default_base_url);
+
+        Ok(Self {
+            request_path,
+            pois_db,
+            spatial_index,
+            popularity,
+            priors,
+            theme_mapping: args.theme_mapping,
+            osrm_base_url,
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn config_from_layers_for_test(
+    layers: Vec<ortho_config::MergeLayer<'static>>,
+) -> Result<SolveConfig, CliError> {
+    let merged = SolveArgs::merge_from_layers(layers).map_err(CliError::from)?;
+    SolveConfig::try_from(merged)
+}
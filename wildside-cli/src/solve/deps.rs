@@ -0,0 +1,275 @@
+//! Opening the artefacts and building the solver the enabled features select.
+
+#[cfg(feature = "store-sqlite")]
+use camino::Utf8Path;
+use wildside_core::Solver;
+#[cfg(feature = "store-sqlite")]
+use wildside_core::travel_time::{
+    GraphTravelTimeProvider, PrecomputedTravelTimeProvider, StepFreeTravelTimeProvider,
+};
+#[cfg(feature = "store-sqlite")]
+use wildside_core::{PoiStore, Scorer, SqlitePoiStore, TravelTimeProvider};
+#[cfg(feature = "store-sqlite")]
+use wildside_data::manifest::load_manifest;
+#[cfg(feature = "store-sqlite")]
+use wildside_data::routing::HttpTravelTimeProvider;
+#[cfg(feature = "store-sqlite")]
+use wildside_scorer::{PopularitySource, ScoreWeights, ThemeClaimMapping, UserRelevanceScorer};
+#[cfg(all(
+    feature = "store-sqlite",
+    feature = "solver-ortools",
+    not(feature = "solver-vrp")
+))]
+use wildside_solver_ortools::OrtoolsSolver;
+#[cfg(all(feature = "store-sqlite", feature = "solver-vrp"))]
+use wildside_solver_vrp::VrpSolver;
+
+use super::ArtefactPaths;
+use crate::CliError;
+#[cfg(feature = "store-sqlite")]
+use crate::{
+    LINK_FILTER_FILE_NAME, ROUTING_GRAPH_FILE_NAME, STEP_FREE_GRAPH_FILE_NAME,
+    TRAVEL_TIMES_FILE_NAME,
+};
+
+#[cfg(test)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum SelectedSolverKind {
+    Vrp,
+    Ortools,
+    Missing,
+}
+
+/// Travel-time provider chosen at run time: the offline routing graph when
+/// ingest wrote one, otherwise OSRM.
+#[cfg(feature = "store-sqlite")]
+type SelectedTravelTimeProvider = Box<dyn TravelTimeProvider + Send + Sync>;
+
+#[cfg(all(feature = "store-sqlite", feature = "solver-vrp"))]
+type SelectedSolver<S, C> = VrpSolver<S, SelectedTravelTimeProvider, C>;
+#[cfg(all(feature = "store-sqlite", feature = "solver-vrp", test))]
+const SELECTED_SOLVER_KIND: SelectedSolverKind = SelectedSolverKind::Vrp;
+
+#[cfg(all(
+    feature = "store-sqlite",
+    not(feature = "solver-vrp"),
+    feature = "solver-ortools"
+))]
+type SelectedSolver<S, C> = OrtoolsSolver<S, SelectedTravelTimeProvider, C>;
+#[cfg(all(
+    feature = "store-sqlite",
+    not(feature = "solver-vrp"),
+    feature = "solver-ortools",
+    test
+))]
+const SELECTED_SOLVER_KIND: SelectedSolverKind = SelectedSolverKind::Ortools;
+
+#[cfg(all(
+    test,
+    any(
+        not(feature = "store-sqlite"),
+        all(not(feature = "solver-vrp"), not(feature = "solver-ortools"))
+    )
+))]
+const SELECTED_SOLVER_KIND: SelectedSolverKind = SelectedSolverKind::Missing;
+
+#[cfg(feature = "store-sqlite")]
+pub(crate) type StoreDependencies = (
+    SqlitePoiStore,
+    SelectedTravelTimeProvider,
+    UserRelevanceScorer,
+);
+
+#[cfg(not(feature = "store-sqlite"))]
+pub(crate) type StoreDependencies = ();
+
+pub(crate) fn make_store_and_deps(
+    artefacts: ArtefactPaths<'_>,
+) -> Result<StoreDependencies, CliError> {
+    #[cfg(feature = "store-sqlite")]
+    {
+        check_artefact_manifest(artefacts.pois_db)?;
+        let store = SqlitePoiStore::open(
+            artefacts.pois_db.as_std_path(),
+            artefacts.spatial_index.as_std_path(),
+        )?;
+        // A missing popularity.bin degrades to interest-only scoring.
+        let scorer = UserRelevanceScorer::from_source(
+            artefacts.pois_db,
+            PopularitySource::Path(artefacts.popularity.to_path_buf()),
+            load_theme_mapping(artefacts.theme_mapping)?,
+            ScoreWeights::default(),
+        )?;
+        let scorer = attach_link_filter(scorer, artefacts.pois_db)?;
+        let provider = select_travel_time_provider(artefacts.pois_db, artefacts.osrm_base_url)?;
+        let provider = attach_step_free_graph(provider, artefacts.pois_db)?;
+        Ok((store, provider, scorer))
+    }
+    #[cfg(not(feature = "store-sqlite"))]
+    {
+        let _ = artefacts;
+        Err(CliError::MissingFeature {
+            feature: "store-sqlite",
+            action: "solve",
+        })
+    }
+}
+
+/// Reject artefact sets whose `manifest.json`, stored beside `pois.db`,
+/// records formats this build cannot read.
+///
+/// Artefacts from older ingests lack the manifest; each artefact's own
+/// version check still runs when it is opened.
+#[cfg(feature = "store-sqlite")]
+fn check_artefact_manifest(pois_db: &Utf8Path) -> Result<(), CliError> {
+    let dir = pois_db.parent().unwrap_or_else(|| Utf8Path::new("."));
+    load_manifest(dir)?;
+    Ok(())
+}
+
+/// Load the theme-to-claim mapping file, defaulting to the built-in mapping
+/// when none was supplied.
+#[cfg(feature = "store-sqlite")]
+fn load_theme_mapping(path: Option<&Utf8Path>) -> Result<ThemeClaimMapping, CliError> {
+    path.map_or_else(
+        || Ok(ThemeClaimMapping::default()),
+        |path| {
+            ThemeClaimMapping::from_path(path).map_err(|source| CliError::LoadThemeMapping {
+                path: path.to_path_buf(),
+                source,
+            })
+        },
+    )
+}
+
+/// Attach the optional `links.filter` artefact stored beside `pois.db`.
+///
+/// Artefacts from older ingests lack the filter; the scorer then falls back
+/// to querying SQLite for every POI.
+#[cfg(feature = "store-sqlite")]
+fn attach_link_filter(
+    scorer: UserRelevanceScorer,
+    pois_db: &Utf8Path,
+) -> Result<UserRelevanceScorer, CliError> {
+    let path = pois_db.with_file_name(LINK_FILTER_FILE_NAME);
+    if wildside_fs::file_is_file(&path).unwrap_or(false) {
+        Ok(scorer.with_link_filter_path(&path)?)
+    } else {
+        Ok(scorer)
+    }
+}
+
+/// Serve travel times from the optional `travel_times.bin` table stored
+/// beside `pois.db`, else route over the optional `graph.bin`, falling back to
+/// the OSRM service at `osrm_base_url`.
+///
+/// Artefacts from older ingests lack these files and keep using OSRM.
+#[cfg(feature = "store-sqlite")]
+fn select_travel_time_provider(
+    pois_db: &Utf8Path,
+    osrm_base_url: &str,
+) -> Result<SelectedTravelTimeProvider, CliError> {
+    let path = pois_db.with_file_name(TRAVEL_TIMES_FILE_NAME);
+    if wildside_fs::file_is_file(&path).unwrap_or(false) {
+        let provider = PrecomputedTravelTimeProvider::load(path.as_std_path())
+            .map_err(|source| CliError::LoadTravelTimes { path, source })?;
+        return Ok(Box::new(provider));
+    }
+    let path = pois_db.with_file_name(ROUTING_GRAPH_FILE_NAME);
+    if wildside_fs::file_is_file(&path).unwrap_or(false) {
+        let provider = GraphTravelTimeProvider::load(path.as_std_path())
+            .map_err(|source| CliError::LoadRoutingGraph { path, source })?;
+        return Ok(Box::new(provider));
+    }
+    let provider = HttpTravelTimeProvider::new(osrm_base_url.to_owned()).map_err(|source| {
+        CliError::BuildTravelTimeProvider {
+            base_url: osrm_base_url.to_owned(),
+            source,
+        }
+    })?;
+    Ok(Box::new(provider))
+}
+
+/// Serve step-free solves by routing over the optional
+/// `graph-step-free.bin` stored beside `pois.db`.
+///
+/// Without it, step-free requests fail while every other request is
+/// unaffected.
+#[cfg(feature = "store-sqlite")]
+fn attach_step_free_graph(
+    provider: SelectedTravelTimeProvider,
+    pois_db: &Utf8Path,
+) -> Result<SelectedTravelTimeProvider, CliError> {
+    let path = pois_db.with_file_name(STEP_FREE_GRAPH_FILE_NAME);
+    if !wildside_fs::file_is_file(&path).unwrap_or(false) {
+        return Ok(provider);
+    }
+    let step_free = GraphTravelTimeProvider::load(path.as_std_path())
+        .map_err(|source| CliError::LoadRoutingGraph { path, source })?;
+    Ok(Box::new(StepFreeTravelTimeProvider::new(
+        provider, step_free,
+    )))
+}
+
+pub(crate) fn build_solver_with_features(
+    deps: StoreDependencies,
+) -> Result<Box<dyn Solver>, CliError> {
+    #[cfg(feature = "store-sqlite")]
+    {
+        let (store, provider, scorer) = deps;
+        select_solver(store, provider, scorer)
+    }
+    #[cfg(not(feature = "store-sqlite"))]
+    {
+        let () = deps;
+        Err(CliError::MissingFeature {
+            feature: "store-sqlite",
+            action: "solve",
+        })
+    }
+}
+
+/// Build the solver chosen by the enabled features over `store` and
+/// `scorer`, which may be shared with other components.
+#[cfg(feature = "store-sqlite")]
+pub(crate) fn select_solver<S, C>(
+    store: S,
+    provider: SelectedTravelTimeProvider,
+    scorer: C,
+) -> Result<Box<dyn Solver>, CliError>
+where
+    S: PoiStore + Send + Sync + 'static,
+    C: Scorer + 'static,
+{
+    #[cfg(any(feature = "solver-vrp", feature = "solver-ortools"))]
+    {
+        Ok(Box::new(SelectedSolver::new(store, provider, scorer)))
+    }
+    #[cfg(all(not(feature = "solver-vrp"), not(feature = "solver-ortools")))]
+    {
+        let _ = (store, provider, scorer);
+        Err(CliError::MissingFeature {
+            feature: "solver-vrp or solver-ortools",
+            action: "solve",
+        })
+    }
+}
+
+#[cfg(test)]
+mod feature_flag_tests {
+    use super::{SELECTED_SOLVER_KIND, SelectedSolverKind};
+    use rstest::rstest;
+
+    #[rstest]
+    fn solver_selection_matches_features() {
+        let expected = if cfg!(feature = "store-sqlite") && cfg!(feature = "solver-vrp") {
+            SelectedSolverKind::Vrp
+        } else if cfg!(feature = "store-sqlite") && cfg!(feature = "solver-ortools") {
+            SelectedSolverKind::Ortools
+        } else {
+            SelectedSolverKind::Missing
+        };
+
+        assert_eq!(SELECTED_SOLVER_KIND, expected);
+    }
+}
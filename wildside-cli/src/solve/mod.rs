@@ -0,0 +1,118 @@
+//! Solve command implementation for the Wildside CLI.
+
+use camino::Utf8Path;
+use std::io::{BufReader, Write};
+use wildside_core::{InterestPriors, SolveRequest, SolveResponse, Solver};
+use wildside_fs::open_utf8_file;
+
+use crate::CliError;
+
+mod config;
+mod deps;
+
+#[cfg(test)]
+pub(crate) use config::config_from_layers_for_test;
+pub(crate) use config::{ArtefactPaths, SolveArgs, SolveConfig};
+#[cfg(feature = "store-sqlite")]
+pub(crate) use deps::select_solver;
+pub(crate) use deps::{build_solver_with_features, make_store_and_deps};
+
+/// Builds a solver instance for the current solve invocation.
+pub(super) trait SolveSolverBuilder {
+    fn build(&self, config: &SolveConfig) -> Result<Box<dyn Solver>, CliError>;
+}
+
+pub(super) struct DefaultSolveSolverBuilder;
+
+impl SolveSolverBuilder for DefaultSolveSolverBuilder {
+    fn build(&self, config: &SolveConfig) -> Result<Box<dyn Solver>, CliError> {
+        build_solver_with_features(make_store_and_deps(config.artefacts())?)
+    }
+}
+
+pub(super) fn run_solve(args: SolveArgs) -> Result<(), CliError> {
+    let mut stdout = std::io::stdout().lock();
+    let builder = DefaultSolveSolverBuilder;
+    run_solve_with(args, &builder, &mut stdout)
+}
+
+pub(super) fn run_solve_with(
+    args: SolveArgs,
+    builder: &dyn SolveSolverBuilder,
+    writer: &mut dyn Write,
+) -> Result<(), CliError> {
+    let response = execute_solve(args, builder)?;
+    write_solve_response(writer, &response)
+}
+
+fn execute_solve(
+    args: SolveArgs,
+    builder: &dyn SolveSolverBuilder,
+) -> Result<SolveResponse, CliError> {
+    let config = resolve_solve_config(args)?;
+    let mut request = load_solve_request(&config.request_path)?;
+    request
+        .validate_detailed()
+        .map_err(|source| CliError::InvalidSolveRequest {
+            path: config.request_path.clone(),
+            source,
+        })?;
+    if let Some(priors) = load_interest_priors(&config.priors)? {
+        request.interests = priors.blend(request.start, &request.interests);
+    }
+    let solver = builder.build(&config)?;
+    solver
+        .solve(&request)
+        .map_err(|source| CliError::Solve { source })
+}
+
+fn resolve_solve_config(args: SolveArgs) -> Result<SolveConfig, CliError> {
+    let config = args.into_config()?;
+    config.validate_sources()?;
+    Ok(config)
+}
+
+/// Loads a JSON-encoded [`SolveRequest`] from disk.
+pub(super) fn load_solve_request(path: &Utf8Path) -> Result<SolveRequest, CliError> {
+    let file = open_utf8_file(path).map_err(|source| CliError::OpenSolveRequest {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let reader = BufReader::new(file);
+    serde_json::from_reader(reader).map_err(|source| CliError::ParseSolveRequest {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Loads the optional per-region interest priors artefact.
+///
+/// Returns `Ok(None)` when the file is absent so cold-start blending is
+/// skipped for artefact sets built without priors.
+pub(super) fn load_interest_priors(path: &Utf8Path) -> Result<Option<InterestPriors>, CliError> {
+    if !wildside_fs::file_is_file(path).unwrap_or(false) {
+        return Ok(None);
+    }
+    let file = open_utf8_file(path).map_err(|source| CliError::OpenInterestPriors {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    InterestPriors::from_reader(BufReader::new(file))
+        .map(Some)
+        .map_err(|source| CliError::ParseInterestPriors {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+fn write_solve_response(writer: &mut dyn Write, response: &SolveResponse) -> Result<(), CliError> {
+    let payload =
+        serde_json::to_string_pretty(response).map_err(CliError::SerializeSolveResponse)?;
+    writer
+        .write_all(payload.as_bytes())
+        .map_err(CliError::WriteSolveOutput)?;
+    writer
+        .write_all(b"\n")
+        .map_err(CliError::WriteSolveOutput)?;
+    Ok(())
+}
@@ -0,0 +1,63 @@
+//! Unit tests for the bundle and unbundle commands.
+
+use super::helpers::write_utf8;
+use super::*;
+use camino::Utf8PathBuf;
+use rstest::{fixture, rstest};
+use tempfile::TempDir;
+
+#[fixture]
+fn workspace() -> (TempDir, Utf8PathBuf) {
+    let tmp = TempDir::new().expect("tempdir");
+    let root = Utf8PathBuf::from_path_buf(tmp.path().to_path_buf()).expect("utf-8 workspace path");
+    (tmp, root)
+}
+
+#[rstest]
+fn bundle_round_trips_through_unbundle(workspace: (TempDir, Utf8PathBuf)) {
+    let (_tmp, root) = workspace;
+    write_utf8(&root.join("pois.db"), b"db");
+    write_utf8(&root.join("pois.rstar"), b"index");
+    let output = root.join("region.wildside");
+
+    let written = bundle::run_bundle(BundleArgs {
+        output: Some(output.clone()),
+        artefacts_dir: Some(root.clone()),
+    })
+    .expect("bundle artefacts");
+    let opened = bundle::run_unbundle(UnbundleArgs {
+        bundle: Some(output),
+        output_dir: Some(root.join("device")),
+    })
+    .expect("unbundle artefacts");
+
+    assert_eq!(opened.entries, written);
+    assert!(opened.dir.join("pois.rstar").is_file());
+    assert!(opened.manifest.is_none());
+}
+
+#[rstest]
+fn bundle_requires_an_output_path() {
+    let err = bundle::run_bundle(BundleArgs::default()).expect_err("missing output should error");
+    assert!(matches!(
+        err,
+        CliError::MissingArgument {
+            field: ARG_BUNDLE_OUTPUT,
+            env: ENV_BUNDLE_OUTPUT,
+        }
+    ));
+}
+
+#[rstest]
+fn unbundle_rejects_file_output_dir(workspace: (TempDir, Utf8PathBuf)) {
+    let (_tmp, root) = workspace;
+    let occupied = root.join("occupied");
+    write_utf8(&occupied, b"not a directory");
+
+    let err = bundle::run_unbundle(UnbundleArgs {
+        bundle: Some(root.join("region.wildside")),
+        output_dir: Some(occupied),
+    })
+    .expect_err("file output dir should error");
+    assert!(matches!(err, CliError::OutputDirectoryNotDirectory { .. }));
+}
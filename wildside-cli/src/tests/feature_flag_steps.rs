@@ -74,6 +74,13 @@ fn run_ingest_command(#[from(feature_flag_world)] world: &FeatureFlagWorld) {
         osm_pbf: Some(world.osm_path()),
         wikidata_dump: Some(world.wikidata_path()),
         output_dir: Some(world.output_dir.clone()),
+        claim_properties: None,
+        truthy_claims: None,
+        travel_time_horizon: None,
+        no_travel_times: None,
+        decompression: None,
+        designation_labels: None,
+        wikidata_api: None,
     };
     let outcome = run_ingest(args);
     world.outcome.replace(Some(outcome));
@@ -18,6 +18,13 @@ fn ingest_requires_store_sqlite() {
         osm_pbf: Some(osm_path),
         wikidata_dump: Some(wikidata_path),
         output_dir: Some(root.join("artefacts")),
+        claim_properties: None,
+        truthy_claims: None,
+        travel_time_horizon: None,
+        no_travel_times: None,
+        decompression: None,
+        designation_labels: None,
+        wikidata_api: None,
     };
 
     let err = run_ingest(args).expect_err("missing feature should error");
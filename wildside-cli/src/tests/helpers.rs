@@ -147,10 +147,22 @@ pub(super) fn fixtures_dir() -> Utf8PathBuf {
     Utf8PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../wildside-data/tests/fixtures")
 }
 
+/// Inputs of the central Edinburgh extract behind the city golden routes.
+#[cfg(feature = "store-sqlite")]
+pub(super) fn city_fixture_dir() -> Utf8PathBuf {
+    Utf8PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../tests/golden_routes/city")
+}
+
 #[cfg(feature = "store-sqlite")]
 pub(super) fn decode_pbf_fixture(dest_dir: &Utf8Path, stem: &str) -> Utf8PathBuf {
     let encoded_path = fixtures_dir().join(format!("{stem}.osm.pbf.b64"));
-    let encoded = read_utf8(&encoded_path);
+    decode_pbf(&encoded_path, dest_dir, stem)
+}
+
+/// Decode the Base64 PBF at `encoded_path` into `dest_dir/{stem}.osm.pbf`.
+#[cfg(feature = "store-sqlite")]
+pub(super) fn decode_pbf(encoded_path: &Utf8Path, dest_dir: &Utf8Path, stem: &str) -> Utf8PathBuf {
+    let encoded = read_utf8(encoded_path);
     let cleaned: String = encoded
         .chars()
         .filter(|ch| !ch.is_ascii_whitespace())
@@ -168,7 +180,8 @@ pub(super) fn write_wikidata_dump(dir: &Utf8Path) -> Utf8PathBuf {
     let dump_path = dir.join("wikidata.json");
     let payload = r#"[
 {"id":"Q64","claims":{"P1435":[{"mainsnak":{"snaktype":"value","datavalue":{"type":"wikibase-entityid","value":{"id":"Q9259"}}}}]}},
-{"id":"Q42","claims":{}}
+{"id":"Q42","claims":{}},
+{"id":"Q9259","labels":{"en":{"language":"en","value":"World Heritage Site"}},"claims":{}}
 ]"#;
     write_utf8(&dump_path, payload);
     dump_path
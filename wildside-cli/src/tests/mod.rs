@@ -6,12 +6,17 @@
 
 use super::*;
 
+mod bundle_unit;
 mod feature_flag_steps;
 mod feature_flags;
 mod helpers;
 mod pipeline;
 mod pipeline_steps;
+mod serve_unit;
+mod simulate_unit;
+mod solve_input_unit;
 mod solve_steps;
 mod solve_unit;
 mod steps;
 mod unit;
+mod validate_unit;
@@ -2,18 +2,26 @@
 
 #![cfg(feature = "store-sqlite")]
 
-use super::helpers::{decode_pbf_fixture, write_wikidata_dump};
+use super::helpers::{city_fixture_dir, decode_pbf, decode_pbf_fixture, write_wikidata_dump};
 use super::*;
-use crate::is_bz2;
 use bzip2::{Compression, write::BzEncoder};
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
+use flate2::write::GzEncoder;
 use geo::{Coord, Rect};
 use rstest::rstest;
 use rusqlite::Connection;
 use std::fs;
 use std::io::Write;
 use tempfile::TempDir;
-use wildside_core::{PoiStore, SqlitePoiStore, Tags};
+use wildside_core::travel_time::{PrecomputedTravelTimes, RoutingGraph};
+use wildside_core::{PoiStore, PointOfInterest, SqlitePoiStore, Tags, store::LinkFilter};
+use wildside_data::manifest::{MANIFEST_FILE_NAME, load_manifest};
+use wildside_data::persist_pois_to_sqlite;
+use wildside_data::wikidata::etl::{
+    ClaimPropertySet, Decompression, DumpDecoder, LabelLanguages, RankFilter,
+};
+
+use crate::ingest::{persist_designation_labels, persist_wikidata_claims};
 
 #[rstest]
 fn ingest_pipeline_creates_artefacts() {
@@ -28,6 +36,13 @@ fn ingest_pipeline_creates_artefacts() {
         osm_pbf: Some(osm_path),
         wikidata_dump: Some(wikidata_path),
         output_dir: Some(output_dir.clone()),
+        claim_properties: None,
+        truthy_claims: None,
+        travel_time_horizon: None,
+        no_travel_times: None,
+        decompression: None,
+        designation_labels: None,
+        wikidata_api: None,
     };
 
     let outcome = run_ingest(args).expect("pipeline should succeed");
@@ -37,6 +52,31 @@ fn ingest_pipeline_creates_artefacts() {
         "expected pois.rstar artefact"
     );
     assert!(outcome.poi_count > 0);
+    RoutingGraph::load(outcome.routing_graph.as_std_path()).expect("load graph.bin artefact");
+    let step_free = RoutingGraph::load(outcome.step_free_graph.as_std_path())
+        .expect("load graph-step-free.bin artefact");
+    assert!(step_free.node_count() > 0);
+    let travel_times = PrecomputedTravelTimes::load(
+        outcome
+            .travel_times
+            .as_ref()
+            .expect("ingest should write travel_times.bin")
+            .as_std_path(),
+    )
+    .expect("load travel_times.bin artefact");
+    assert_eq!(travel_times.len(), outcome.poi_count);
+    let manifest = load_manifest(&output_dir)
+        .expect("load manifest.json")
+        .expect("ingest should write manifest.json");
+    assert_eq!(outcome.manifest, output_dir.join(MANIFEST_FILE_NAME));
+    assert_eq!(manifest.tool_version, env!("CARGO_PKG_VERSION"));
+    assert!(
+        manifest
+            .inputs
+            .iter()
+            .all(|input| input.sha256.as_ref().is_some_and(|hash| hash.len() == 64)),
+        "every input file should be hashed"
+    );
 
     let store = SqlitePoiStore::open(
         outcome.pois_db.as_std_path(),
@@ -50,7 +90,10 @@ fn ingest_pipeline_creates_artefacts() {
         },
         Coord { x: 180.0, y: 90.0 },
     );
-    let pois: Vec<_> = store.get_pois_in_bbox(&bbox).collect();
+    let pois: Vec<_> = store
+        .get_pois_in_bbox(&bbox)
+        .expect("query store")
+        .collect();
     assert_eq!(pois.len(), outcome.poi_count);
 
     let conn = Connection::open(outcome.pois_db.as_std_path()).expect("open pois.db");
@@ -63,6 +106,60 @@ fn ingest_pipeline_creates_artefacts() {
         persisted_claims as usize, outcome.claims_count,
         "claims_count should reflect persisted claims"
     );
+
+    let filter =
+        LinkFilter::load(outcome.link_filter.as_std_path()).expect("load links.filter artefact");
+    let mut links = conn
+        .prepare("SELECT poi_id FROM poi_wikidata_links")
+        .expect("prepare link query");
+    let linked: Vec<i64> = links
+        .query_map([], |row| row.get(0))
+        .expect("query links")
+        .collect::<Result<_, _>>()
+        .expect("collect links");
+    assert!(
+        linked.iter().all(|id| filter.may_contain_poi(*id as u64)),
+        "every linked POI should pass the filter"
+    );
+}
+
+#[rstest]
+fn ingest_can_skip_the_travel_time_table() {
+    let working = TempDir::new().expect("temp dir");
+    let workspace =
+        Utf8PathBuf::from_path_buf(working.path().to_path_buf()).expect("utf-8 workspace path");
+    let osm_path = decode_pbf_fixture(&workspace, "poi_tags");
+    let wikidata_path = write_wikidata_dump(&workspace);
+    let output_dir = workspace.join("artefacts");
+    let stale = output_dir.join(TRAVEL_TIMES_FILE_NAME);
+    fs::create_dir_all(&output_dir).expect("create output dir");
+    fs::write(&stale, b"stale table").expect("write stale travel_times.bin");
+
+    let args = IngestArgs {
+        osm_pbf: Some(osm_path),
+        wikidata_dump: Some(wikidata_path),
+        output_dir: Some(output_dir.clone()),
+        no_travel_times: Some(true),
+        decompression: None,
+        ..IngestArgs::default()
+    };
+
+    let outcome = run_ingest(args).expect("pipeline should succeed");
+    assert_eq!(outcome.travel_times, None);
+    assert!(
+        !stale.exists(),
+        "a stale travel_times.bin should be removed"
+    );
+    let manifest = load_manifest(&output_dir)
+        .expect("load manifest.json")
+        .expect("ingest should write manifest.json");
+    assert!(
+        manifest
+            .artefacts
+            .iter()
+            .all(|artefact| artefact.file != TRAVEL_TIMES_FILE_NAME),
+        "the manifest should not list a skipped travel-time table"
+    );
 }
 
 #[rstest]
@@ -77,6 +174,13 @@ fn ingest_errors_when_wikidata_missing() {
         osm_pbf: Some(osm_path),
         wikidata_dump: Some(missing_wikidata),
         output_dir: Some(workspace.join("artefacts")),
+        claim_properties: None,
+        truthy_claims: None,
+        travel_time_horizon: None,
+        no_travel_times: None,
+        decompression: None,
+        designation_labels: None,
+        wikidata_api: None,
     };
 
     let err = run_ingest(args).expect_err("missing dump should fail");
@@ -107,6 +211,13 @@ fn ingest_pipeline_creates_artefacts_with_bz2_wikidata() {
         osm_pbf: Some(osm_path),
         wikidata_dump: Some(bz2_path),
         output_dir: Some(output_dir.clone()),
+        claim_properties: None,
+        truthy_claims: None,
+        travel_time_horizon: None,
+        no_travel_times: None,
+        decompression: None,
+        designation_labels: None,
+        wikidata_api: None,
     };
 
     let outcome = run_ingest(args).expect("pipeline should succeed");
@@ -129,10 +240,94 @@ fn ingest_pipeline_creates_artefacts_with_bz2_wikidata() {
         },
         Coord { x: 180.0, y: 90.0 },
     );
-    let pois: Vec<_> = store.get_pois_in_bbox(&bbox).collect();
+    let pois: Vec<_> = store
+        .get_pois_in_bbox(&bbox)
+        .expect("query store")
+        .collect();
     assert_eq!(pois.len(), outcome.poi_count);
 }
 
+/// Compress `plain` as one bzip2 stream per line, the way the parallel
+/// compressors behind Wikidata's dumps lay out `latest-all.json.bz2`.
+fn write_multistream_bz2(path: &Utf8Path, plain: &[u8]) {
+    let mut archive = Vec::new();
+    for line in plain.split_inclusive(|byte| *byte == b'\n') {
+        let mut encoder = BzEncoder::new(Vec::new(), Compression::fast());
+        encoder.write_all(line).expect("compress wikidata line");
+        archive.extend(encoder.finish().expect("finish stream"));
+    }
+    fs::write(path, archive).expect("write multistream dump");
+}
+
+#[rstest]
+#[case::default_mode(None, DumpDecoder::ParallelBzip2)]
+#[case::sequential(Some("sequential"), DumpDecoder::Bzip2)]
+fn ingest_decodes_multistream_all_dumps(
+    #[case] decompression: Option<&str>,
+    #[case] expected: DumpDecoder,
+) {
+    let working = TempDir::new().expect("temp dir");
+    let workspace =
+        Utf8PathBuf::from_path_buf(working.path().to_path_buf()).expect("utf-8 workspace path");
+    let city = city_fixture_dir();
+    let osm_path = decode_pbf(&city.join("edinburgh.osm.pbf.b64"), &workspace, "edinburgh");
+    let plain = fs::read(city.join("wikidata.json")).expect("read city wikidata dump");
+    let entities = plain
+        .split(|byte| *byte == b'\n')
+        .filter(|line| line.starts_with(b"{"))
+        .count();
+    let dump_path = workspace.join("latest-all.json.bz2");
+    write_multistream_bz2(&dump_path, &plain);
+
+    let args = IngestArgs {
+        osm_pbf: Some(osm_path),
+        wikidata_dump: Some(dump_path),
+        output_dir: Some(workspace.join("artefacts")),
+        no_travel_times: Some(true),
+        decompression: decompression.map(str::to_owned),
+        ..IngestArgs::default()
+    };
+
+    let outcome = run_ingest(args).expect("pipeline should succeed");
+    assert_eq!(outcome.wikidata_decoder, Some(expected));
+    assert!(entities > 1, "expected several streams in the dump");
+    assert_eq!(
+        outcome.claims_count, entities,
+        "every linked entity should be decoded and persisted"
+    );
+}
+
+fn poi_with_link() -> PointOfInterest {
+    PointOfInterest::new(
+        7,
+        Coord { x: 1.0, y: 2.0 },
+        Tags::from([("wikidata".into(), "Q64".into())]),
+    )
+}
+
+fn pois_database(workspace: &Utf8Path, pois: &[PointOfInterest]) -> Utf8PathBuf {
+    let pois_db = workspace.join("pois.db");
+    persist_pois_to_sqlite(&pois_db, pois).expect("persist POIs");
+    pois_db
+}
+
+fn stored_claims(pois_db: &Utf8Path) -> Vec<(i64, String, String, String)> {
+    let connection = Connection::open(pois_db.as_std_path()).expect("open pois.db");
+    let mut statement = connection
+        .prepare(
+            "SELECT poi_id, entity_id, property_id, value_entity_id FROM poi_wikidata_claims \
+             ORDER BY poi_id, property_id",
+        )
+        .expect("prepare claims query");
+    statement
+        .query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })
+        .expect("query claims")
+        .collect::<Result<_, _>>()
+        .expect("read claims")
+}
+
 #[rstest]
 fn wikidata_claims_are_extracted_for_linked_entities() {
     let working = TempDir::new().expect("temp dir");
@@ -141,20 +336,23 @@ fn wikidata_claims_are_extracted_for_linked_entities() {
     let wikidata_path = write_wikidata_dump(&workspace);
     let config = IngestConfig {
         osm_pbf: workspace.join("dummy.osm.pbf"),
-        wikidata_dump: wikidata_path,
+        wikidata: WikidataInput::Dump(wikidata_path),
         output_dir: workspace.clone(),
+        claim_properties: ClaimPropertySet::default(),
+        claim_ranks: RankFilter::All,
+        designation_labels: None,
+        travel_time_horizon: None,
+        decompression: Decompression::Parallel,
     };
-    let poi = PointOfInterest::new(
-        7,
-        Coord { x: 1.0, y: 2.0 },
-        Tags::from([("wikidata".into(), "Q64".into())]),
-    );
+    let poi = poi_with_link();
+    let pois_db = pois_database(&workspace, std::slice::from_ref(&poi));
 
-    let claims = ingest_wikidata_claims(&config, &[poi]).expect("extract claims");
-    assert_eq!(claims.len(), 1, "expected one linked entity");
-    assert_eq!(claims[0].entity_id, "Q64");
-    assert_eq!(claims[0].linked_poi_ids, vec![7]);
-    assert_eq!(claims[0].heritage_designations, vec!["Q9259".to_string()]);
+    let persisted = persist_wikidata_claims(&config, &[poi], &pois_db).expect("persist claims");
+    assert_eq!(persisted, 1, "expected one linked entity");
+    assert_eq!(
+        stored_claims(&pois_db),
+        vec![(7, "Q64".to_owned(), "P1435".to_owned(), "Q9259".to_owned())]
+    );
 }
 
 #[rstest]
@@ -165,29 +363,90 @@ fn wikidata_claims_are_empty_when_no_linked_entities() {
     let wikidata_path = write_wikidata_dump(&workspace);
     let config = IngestConfig {
         osm_pbf: workspace.join("dummy.osm.pbf"),
-        wikidata_dump: wikidata_path,
+        wikidata: WikidataInput::Dump(wikidata_path),
         output_dir: workspace.clone(),
+        claim_properties: ClaimPropertySet::default(),
+        claim_ranks: RankFilter::All,
+        designation_labels: None,
+        travel_time_horizon: None,
+        decompression: Decompression::Parallel,
     };
 
-    let claims = ingest_wikidata_claims(&config, &[]).expect("extract claims without links");
-    assert!(
-        claims.is_empty(),
+    let pois_db = pois_database(&workspace, &[]);
+
+    let persisted =
+        persist_wikidata_claims(&config, &[], &pois_db).expect("persist claims without links");
+    assert_eq!(
+        persisted, 0,
         "expected no claims when POIs contain no wikidata tags"
     );
+    assert!(stored_claims(&pois_db).is_empty());
 }
 
-#[test]
-fn is_bz2_handles_case_insensitive_extensions() {
-    let cases = [
-        ("dump.bz2", true),
-        ("dump.BZ2", true),
-        ("dump.json.bz2", true),
-        ("dump.json", false),
-        ("dumpbz2", false),
-    ];
-
-    for (name, expected) in cases {
-        let path = Utf8PathBuf::from(name);
-        assert_eq!(is_bz2(&path), expected, "is_bz2({name})");
-    }
+#[rstest]
+fn wikidata_claims_are_extracted_from_gzip_dump() {
+    let working = TempDir::new().expect("temp dir");
+    let workspace =
+        Utf8PathBuf::from_path_buf(working.path().to_path_buf()).expect("utf-8 workspace path");
+    let plain = fs::read(write_wikidata_dump(&workspace)).expect("read wikidata dump");
+    let gz_path = workspace.join("wikidata.json.gz");
+    let file = fs::File::create(&gz_path).expect("create gz file");
+    let mut encoder = GzEncoder::new(file, flate2::Compression::default());
+    encoder.write_all(&plain).expect("compress wikidata");
+    encoder.finish().expect("finish compression");
+    let config = IngestConfig {
+        osm_pbf: workspace.join("dummy.osm.pbf"),
+        wikidata: WikidataInput::Dump(gz_path),
+        output_dir: workspace.clone(),
+        claim_properties: ClaimPropertySet::default(),
+        claim_ranks: RankFilter::All,
+        designation_labels: None,
+        travel_time_horizon: None,
+        decompression: Decompression::Parallel,
+    };
+    let poi = poi_with_link();
+    let pois_db = pois_database(&workspace, std::slice::from_ref(&poi));
+
+    let persisted = persist_wikidata_claims(&config, &[poi], &pois_db).expect("persist claims");
+    assert_eq!(persisted, 1, "expected one linked entity");
+    assert_eq!(
+        stored_claims(&pois_db),
+        vec![(7, "Q64".to_owned(), "P1435".to_owned(), "Q9259".to_owned())]
+    );
+}
+
+#[rstest]
+fn designation_labels_are_resolved_from_the_dump() {
+    let working = TempDir::new().expect("temp dir");
+    let workspace =
+        Utf8PathBuf::from_path_buf(working.path().to_path_buf()).expect("utf-8 workspace path");
+    let wikidata_path = write_wikidata_dump(&workspace);
+    let languages = LabelLanguages::default();
+    let config = IngestConfig {
+        osm_pbf: workspace.join("dummy.osm.pbf"),
+        wikidata: WikidataInput::Dump(wikidata_path),
+        output_dir: workspace.clone(),
+        claim_properties: ClaimPropertySet::default(),
+        claim_ranks: RankFilter::All,
+        designation_labels: Some(languages.clone()),
+        travel_time_horizon: None,
+        decompression: Decompression::Parallel,
+    };
+    let poi = poi_with_link();
+    let pois_db = pois_database(&workspace, std::slice::from_ref(&poi));
+    persist_wikidata_claims(&config, &[poi], &pois_db).expect("persist claims");
+
+    let written =
+        persist_designation_labels(&config, &languages, &pois_db).expect("persist labels");
+
+    assert_eq!(written, 1);
+    let connection = Connection::open(pois_db.as_std_path()).expect("open pois.db");
+    let label: String = connection
+        .query_row(
+            "SELECT label FROM poi_wikidata_claim_labels WHERE poi_id = 7 AND language = 'en'",
+            [],
+            |row| row.get(0),
+        )
+        .expect("read designation label");
+    assert_eq!(label, "World Heritage Site");
 }
@@ -84,6 +84,13 @@ fn run_pipeline(#[from(pipeline_world)] world: &PipelineWorld) {
         osm_pbf: Some(world.osm_path()),
         wikidata_dump: Some(world.wikidata_path()),
         output_dir: Some(world.output_dir.clone()),
+        claim_properties: None,
+        truthy_claims: None,
+        travel_time_horizon: None,
+        no_travel_times: None,
+        decompression: None,
+        designation_labels: None,
+        wikidata_api: None,
     };
     let outcome = run_ingest(args);
     world.outcome.replace(Some(outcome));
@@ -121,7 +128,10 @@ fn index_matches_pois(#[from(pipeline_world)] world: &PipelineWorld) {
         },
         Coord { x: 180.0, y: 90.0 },
     );
-    let pois: Vec<_> = store.get_pois_in_bbox(&bbox).collect();
+    let pois: Vec<_> = store
+        .get_pois_in_bbox(&bbox)
+        .expect("query store")
+        .collect();
     assert_eq!(pois.len(), outcome.poi_count);
 }
 
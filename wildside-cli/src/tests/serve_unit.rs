@@ -0,0 +1,404 @@
+//! Unit tests for the serve command's routing and readiness probe.
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use super::*;
+use camino::Utf8PathBuf;
+use geo::{Coord, Rect};
+use rstest::{fixture, rstest};
+use wildside_core::telemetry::{ExportedCell, PrivacyPolicy};
+use wildside_core::{
+    Diagnostics, Engine, InterestProfile, PoiIter, PoiStore, PoiStoreError, PointOfInterest, Route,
+    Scorer, SolveError, SolveRequest, SolveResponse, Solver, WarmUpCheck, WarmUpReport,
+    WarmUpStage,
+};
+
+fn empty_response() -> SolveResponse {
+    SolveResponse {
+        route: Route::empty(),
+        score: 0.0,
+        diagnostics: Diagnostics::default(),
+        geometry: None,
+    }
+}
+
+/// Service whose warm-up installs a canned report, after failing the given
+/// number of times.
+struct StubService {
+    warm: WarmUpReport,
+    failures: Mutex<u32>,
+    readiness: Mutex<Option<WarmUpReport>>,
+}
+
+impl StubService {
+    fn new(warm: WarmUpReport) -> Self {
+        Self {
+            warm,
+            failures: Mutex::new(0),
+            readiness: Mutex::new(None),
+        }
+    }
+}
+
+impl Service for StubService {
+    fn warm_up(&self) -> WarmUpReport {
+        let mut failures = self.failures.lock().expect("failures lock");
+        let report = if *failures > 0 {
+            *failures -= 1;
+            report(Some("routing service down"))
+        } else {
+            self.warm.clone()
+        };
+        *self.readiness.lock().expect("readiness lock") = Some(report.clone());
+        report
+    }
+
+    fn readiness(&self) -> Option<WarmUpReport> {
+        self.readiness.lock().expect("readiness lock").clone()
+    }
+
+    fn solve(&self, request: SolveRequest) -> Result<SolveResponse, SolveError> {
+        if request.duration_minutes > 120 {
+            return Err(SolveError::NotImplemented);
+        }
+        Ok(empty_response())
+    }
+
+    fn telemetry(&self) -> Vec<ExportedCell> {
+        Vec::new()
+    }
+}
+
+fn report(error: Option<&str>) -> WarmUpReport {
+    WarmUpReport {
+        checks: vec![WarmUpCheck {
+            stage: WarmUpStage::CanarySolve,
+            elapsed: Duration::ZERO,
+            error: error.map(str::to_owned),
+        }],
+    }
+}
+
+#[fixture]
+fn healthy() -> StubService {
+    StubService::new(report(None))
+}
+
+fn request(duration_minutes: u16) -> SolveRequest {
+    SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes,
+        ..Default::default()
+    }
+}
+
+fn request_body(duration_minutes: u16) -> String {
+    let request = request(duration_minutes);
+    serde_json::to_string(&request).expect("request serialises")
+}
+
+fn body_json(response: &Response) -> serde_json::Value {
+    serde_json::from_str(&response.body).expect("response body is JSON")
+}
+
+#[rstest]
+fn ready_is_unavailable_before_warm_up(healthy: StubService) {
+    let response = route(&healthy, "GET", "/ready", &[]);
+
+    assert_eq!(response.status, 503);
+    assert_eq!(body_json(&response)["ready"], false);
+}
+
+#[rstest]
+fn ready_reports_checks_after_warm_up(healthy: StubService) {
+    healthy.warm_up();
+    let response = route(&healthy, "GET", "/ready", &[]);
+
+    assert_eq!(response.status, 200);
+    let body = body_json(&response);
+    assert_eq!(body["ready"], true);
+    assert_eq!(body["checks"][0]["stage"], "canary_solve");
+}
+
+#[rstest]
+fn ready_stays_unavailable_after_failed_warm_up() {
+    let service = StubService::new(report(Some("disk gone")));
+    service.warm_up();
+    let response = route(&service, "GET", "/ready", &[]);
+
+    assert_eq!(response.status, 503);
+    assert_eq!(body_json(&response)["checks"][0]["error"], "disk gone");
+}
+
+#[rstest]
+#[case::solved(request_body(30), 200)]
+#[case::unsolvable(request_body(180), 422)]
+#[case::malformed(String::from("{"), 400)]
+fn solve_maps_outcomes_to_statuses(
+    healthy: StubService,
+    #[case] body: String,
+    #[case] status: u16,
+) {
+    let response = route(&healthy, "POST", "/solve", body.as_bytes());
+
+    assert_eq!(response.status, status);
+}
+
+#[rstest]
+#[case::wrong_method("POST", "/ready", 405)]
+#[case::unknown_path("GET", "/", 404)]
+fn unknown_routes_are_rejected(
+    healthy: StubService,
+    #[case] method: &str,
+    #[case] path: &str,
+    #[case] status: u16,
+) {
+    assert_eq!(route(&healthy, method, path, &[]).status, status);
+}
+
+#[rstest]
+fn connection_reads_body_and_writes_status_line(healthy: StubService) {
+    let body = request_body(30);
+    let request = format!(
+        "POST /solve HTTP/1.1\r\nHost: localhost\r\ncontent-length: {}\r\n\r\n{body}",
+        body.len()
+    );
+    let mut written = Vec::new();
+
+    handle_connection(&healthy, request.as_bytes(), &mut written).expect("exchange completes");
+
+    let reply = String::from_utf8(written).expect("reply is UTF-8");
+    assert!(reply.starts_with("HTTP/1.1 200 OK\r\n"), "{reply}");
+    assert!(reply.contains("\"score\":0.0"), "{reply}");
+}
+
+#[rstest]
+fn connection_rejects_oversized_bodies(healthy: StubService) {
+    let request = format!(
+        "POST /solve HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+        MAX_BODY_BYTES + 1
+    );
+    let mut written = Vec::new();
+
+    handle_connection(&healthy, request.as_bytes(), &mut written).expect("exchange completes");
+
+    let reply = String::from_utf8(written).expect("reply is UTF-8");
+    assert!(reply.starts_with("HTTP/1.1 413 "), "{reply}");
+}
+
+fn exchange(service: &StubService, request: &[u8]) -> String {
+    let mut written = Vec::new();
+    handle_connection(service, request, &mut written).expect("exchange completes");
+    String::from_utf8(written).expect("reply is UTF-8")
+}
+
+fn endless_line() -> String {
+    "a".repeat(usize::try_from(MAX_HEAD_BYTES).expect("budget fits in memory") + 1)
+}
+
+#[rstest]
+#[case::request_line(format!("GET /{}", endless_line()))]
+#[case::header(format!("GET /ready HTTP/1.1\r\nX-Filler: {}", endless_line()))]
+#[case::header_count(format!(
+    "GET /ready HTTP/1.1\r\n{}\r\n",
+    "X-Filler: a\r\n".repeat(MAX_HEADERS + 1)
+))]
+fn connection_rejects_oversized_heads(healthy: StubService, #[case] request: String) {
+    let reply = exchange(&healthy, request.as_bytes());
+
+    assert!(reply.starts_with("HTTP/1.1 431 "), "{reply}");
+}
+
+#[rstest]
+fn connection_accepts_heads_within_the_limits(healthy: StubService) {
+    let request = format!(
+        "GET /ready HTTP/1.1\r\n{}\r\n",
+        "X-Filler: a\r\n".repeat(MAX_HEADERS)
+    );
+    healthy.warm_up();
+
+    let reply = exchange(&healthy, request.as_bytes());
+
+    assert!(reply.starts_with("HTTP/1.1 200 "), "{reply}");
+}
+
+#[rstest]
+fn connection_rejects_chunked_bodies(healthy: StubService) {
+    let body = request_body(30);
+    let request = format!(
+        "POST /solve HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n{:x}\r\n{body}\r\n0\r\n\r\n",
+        body.len()
+    );
+
+    let reply = exchange(&healthy, request.as_bytes());
+
+    assert!(reply.starts_with("HTTP/1.1 411 "), "{reply}");
+}
+
+#[rstest]
+fn warm_up_is_retried_until_ready(healthy: StubService) {
+    *healthy.failures.lock().expect("failures lock") = 2;
+
+    warm_up_until_ready(&healthy, Duration::ZERO);
+
+    assert_eq!(*healthy.failures.lock().expect("failures lock"), 0);
+    assert_eq!(route(&healthy, "GET", "/ready", &[]).status, 200);
+}
+
+#[rstest]
+fn server_answers_connections_with_its_workers(healthy: StubService) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind an ephemeral port");
+    let address = listener.local_addr().expect("listener address");
+    let service: &'static StubService = Box::leak(Box::new(healthy));
+    thread::spawn(move || serve::serve(&listener, service));
+
+    let replies: Vec<String> = (0..3)
+        .map(|_| {
+            let mut stream = TcpStream::connect(address).expect("connect to the server");
+            stream
+                .write_all(b"GET /telemetry HTTP/1.1\r\n\r\n")
+                .expect("send the request");
+            let mut reply = String::new();
+            stream.read_to_string(&mut reply).expect("read the reply");
+            reply
+        })
+        .collect();
+
+    for reply in replies {
+        assert!(reply.starts_with("HTTP/1.1 200 OK\r\n"), "{reply}");
+        assert!(reply.ends_with("[]"), "{reply}");
+    }
+}
+
+struct EmptyStore;
+
+impl PoiStore for EmptyStore {
+    fn get_pois_in_bbox(&self, _bbox: &Rect<f64>) -> Result<PoiIter<'_>, PoiStoreError> {
+        Ok(Box::new(std::iter::empty()))
+    }
+}
+
+struct FlatScorer;
+
+impl Scorer for FlatScorer {
+    fn score(&self, _poi: &PointOfInterest, _profile: &InterestProfile) -> f32 {
+        0.5
+    }
+}
+
+struct EmptySolver;
+
+impl Solver for EmptySolver {
+    fn solve(&self, _request: &SolveRequest) -> Result<SolveResponse, SolveError> {
+        Ok(empty_response())
+    }
+}
+
+#[rstest]
+fn engine_service_becomes_ready_once_warmed_up() {
+    let service = EngineService::new(
+        Engine::new(EmptyStore, FlatScorer, EmptySolver, request(10)),
+        None,
+        PrivacyPolicy::none(),
+    );
+
+    assert_eq!(route(&service, "GET", "/ready", &[]).status, 503);
+    assert!(service.warm_up().is_ready());
+    assert_eq!(route(&service, "GET", "/ready", &[]).status, 200);
+}
+
+#[rstest]
+fn engine_service_rejects_invalid_requests() {
+    let service = EngineService::new(
+        Engine::new(EmptyStore, FlatScorer, EmptySolver, request(10)),
+        None,
+        PrivacyPolicy::none(),
+    );
+    let body = request_body(0);
+
+    assert_eq!(
+        route(&service, "POST", "/solve", body.as_bytes()).status,
+        400
+    );
+}
+
+#[rstest]
+fn serve_config_defaults_to_local_listener() {
+    let config = ServeConfig::try_from(ServeArgs::default()).expect("defaults are valid");
+
+    assert_eq!(config.listen, "127.0.0.1:8080");
+    assert_eq!(config.pois_db, Utf8PathBuf::from("./pois.db"));
+    assert_eq!(config.privacy.epsilon(), None);
+    assert!(config.privacy.noise_seed().is_some());
+}
+
+#[rstest]
+fn serve_config_reads_the_privacy_policy() {
+    let args = ServeArgs {
+        telemetry_min_requests: Some(10),
+        telemetry_epsilon: Some(0.5),
+        telemetry_seed: Some(7),
+        ..ServeArgs::default()
+    };
+    let config = ServeConfig::try_from(args).expect("policy is valid");
+
+    assert_eq!(config.privacy.min_requests(), 10);
+    assert_eq!(config.privacy.epsilon(), Some(0.5));
+    assert_eq!(config.privacy.noise_seed(), Some(7));
+}
+
+#[rstest]
+fn serve_config_rejects_a_non_positive_budget() {
+    let args = ServeArgs {
+        telemetry_epsilon: Some(0.0),
+        ..ServeArgs::default()
+    };
+
+    assert!(matches!(
+        ServeConfig::try_from(args),
+        Err(CliError::InvalidTelemetryPolicy(_))
+    ));
+}
+
+#[rstest]
+#[case::exact(0, 1)]
+#[case::withheld(3, 0)]
+fn telemetry_exports_recorded_solves(#[case] min_requests: u64, #[case] cells: usize) {
+    let service = EngineService::new(
+        Engine::new(EmptyStore, FlatScorer, EmptySolver, request(10)),
+        None,
+        PrivacyPolicy::none().with_min_requests(min_requests),
+    );
+    for _ in 0..2 {
+        let body = request_body(30);
+        assert_eq!(
+            route(&service, "POST", "/solve", body.as_bytes()).status,
+            200
+        );
+    }
+
+    let response = route(&service, "GET", "/telemetry", &[]);
+
+    assert_eq!(response.status, 200);
+    let exported = body_json(&response);
+    let exported = exported.as_array().expect("cells are an array");
+    assert_eq!(exported.len(), cells);
+    if let Some(cell) = exported.first() {
+        assert_eq!(cell["requests"], 2);
+        assert_eq!(cell["solved"], 2);
+    }
+}
+
+#[cfg(feature = "store-sqlite")]
+#[rstest]
+fn canary_starts_at_the_centre_of_the_artefacts() {
+    let bounds = Rect::new(Coord { x: 0.0, y: 0.0 }, Coord { x: 2.0, y: 4.0 });
+    let canary = canary_request(Some(bounds));
+
+    assert_eq!(canary.start, Coord { x: 1.0, y: 2.0 });
+    assert!(canary.validate().is_ok());
+}
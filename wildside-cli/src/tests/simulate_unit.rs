@@ -0,0 +1,255 @@
+//! Unit tests for the simulate command's request generation and metrics.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use super::helpers::write_utf8;
+use super::*;
+use camino::Utf8PathBuf;
+use geo::{Coord, Rect};
+use rstest::{fixture, rstest};
+use tempfile::TempDir;
+use wildside_core::{
+    Diagnostics, InterestProfile, PointOfInterest, Route, Scorer, SolveError, SolveRequest,
+    SolveResponse, Solver,
+};
+
+/// Visits the first `visits` candidates in a route lasting half the budget.
+struct HalfBudgetSolver {
+    candidates: Vec<PointOfInterest>,
+    visits: usize,
+}
+
+impl Solver for HalfBudgetSolver {
+    fn solve(&self, request: &SolveRequest) -> Result<SolveResponse, SolveError> {
+        if request.duration_minutes > 120 {
+            return Err(SolveError::InvalidRequest);
+        }
+        let pois = self.candidates.iter().take(self.visits).cloned().collect();
+        let budget = u64::from(request.duration_minutes) * 60;
+        Ok(SolveResponse {
+            route: Route::new(pois, Duration::from_secs(budget / 2)),
+            score: 2.0,
+            diagnostics: Diagnostics {
+                solve_time: Duration::ZERO,
+                candidates_evaluated: 0,
+                candidates_pruned: 0,
+            },
+            geometry: None,
+        })
+    }
+}
+
+/// Scores POIs by identifier so ranking is predictable.
+struct IdScorer;
+
+impl Scorer for IdScorer {
+    fn score(&self, poi: &PointOfInterest, _profile: &InterestProfile) -> f32 {
+        poi.id as f32
+    }
+}
+
+struct StubSimulationBuilder {
+    candidates: Vec<PointOfInterest>,
+}
+
+impl SimulationBuilder for StubSimulationBuilder {
+    fn build(&self, config: &SimulateConfig) -> Result<SimulationDependencies, CliError> {
+        Ok(SimulationDependencies {
+            solver: Box::new(HalfBudgetSolver {
+                candidates: self.candidates.clone(),
+                visits: 1,
+            }),
+            candidates: self.candidates.clone(),
+            top_pois: top_poi_ids(&self.candidates, &IdScorer, config.plan.top_pois),
+        })
+    }
+}
+
+#[fixture]
+fn candidates() -> Vec<PointOfInterest> {
+    vec![
+        PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 }),
+        PointOfInterest::with_empty_tags(2, Coord { x: 1.0, y: 2.0 }),
+        PointOfInterest::with_empty_tags(3, Coord { x: 0.5, y: 1.0 }),
+    ]
+}
+
+#[fixture]
+fn plan() -> SimulationPlan {
+    SimulationPlan {
+        requests: 25,
+        seed: 7,
+        min_duration_minutes: 30,
+        max_duration_minutes: 90,
+        top_pois: 2,
+    }
+}
+
+#[rstest]
+fn generated_requests_are_deterministic_and_bounded(plan: SimulationPlan) {
+    let bounds = Rect::new(Coord { x: 0.0, y: 0.0 }, Coord { x: 1.0, y: 2.0 });
+
+    let first = generate_requests(bounds, &plan);
+    let second = generate_requests(bounds, &plan);
+
+    assert_eq!(first, second);
+    assert_eq!(first.len(), 25);
+    for request in &first {
+        assert!((0.0..=1.0).contains(&request.start.x));
+        assert!((0.0..=2.0).contains(&request.start.y));
+        assert!((30..=90).contains(&request.duration_minutes));
+        assert!(request.end.is_none());
+        request
+            .validate()
+            .expect("generated request should validate");
+    }
+}
+
+#[rstest]
+fn different_seeds_produce_different_populations(plan: SimulationPlan) {
+    let bounds = Rect::new(Coord { x: 0.0, y: 0.0 }, Coord { x: 1.0, y: 1.0 });
+    let other = SimulationPlan { seed: 8, ..plan };
+
+    assert_ne!(
+        generate_requests(bounds, &plan),
+        generate_requests(bounds, &other)
+    );
+}
+
+#[rstest]
+fn candidate_bounds_spans_all_pois(candidates: Vec<PointOfInterest>) {
+    let bounds = candidate_bounds(&candidates).expect("bounds for non-empty candidates");
+    assert_eq!(bounds.min(), Coord { x: 0.0, y: 0.0 });
+    assert_eq!(bounds.max(), Coord { x: 1.0, y: 2.0 });
+    assert!(candidate_bounds(&[]).is_none());
+}
+
+#[rstest]
+fn top_poi_ids_prefers_highest_scores(candidates: Vec<PointOfInterest>) {
+    let top = top_poi_ids(&candidates, &IdScorer, 2);
+    assert_eq!(top, HashSet::from([2, 3]));
+}
+
+#[rstest]
+fn run_simulation_aggregates_metrics(candidates: Vec<PointOfInterest>) {
+    let solver = HalfBudgetSolver {
+        candidates: candidates.clone(),
+        visits: 2,
+    };
+    let request = |duration_minutes| SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes,
+        interests: InterestProfile::new(),
+        seed: 0,
+        ..Default::default()
+    };
+    let requests = [request(60), request(90), request(180)];
+    let top_pois = HashSet::from([2, 3]);
+
+    let report = run_simulation(&solver, &requests, &top_pois);
+
+    assert_eq!(report.requests, 3);
+    assert_eq!(report.solved, 2);
+    assert_eq!(report.failed, 1);
+    assert!((report.mean_score - 2.0).abs() < f32::EPSILON);
+    assert!((report.mean_duration_utilisation - 0.5).abs() < f32::EPSILON);
+    assert!((report.mean_route_pois - 2.0).abs() < f32::EPSILON);
+    assert_eq!(report.top_pois, 2);
+    assert!((report.top_poi_coverage - 0.5).abs() < f32::EPSILON);
+}
+
+#[rstest]
+fn simulate_config_applies_defaults() {
+    let tmp = TempDir::new().expect("tempdir");
+    let root = Utf8PathBuf::from_path_buf(tmp.path().to_path_buf()).expect("utf-8 workspace");
+    let args = SimulateArgs {
+        artefacts_dir: Some(root.clone()),
+        ..SimulateArgs::default()
+    };
+
+    let config = SimulateConfig::try_from(args).expect("config should build");
+    assert_eq!(config.pois_db, root.join("pois.db"));
+    assert_eq!(config.spatial_index, root.join("pois.rstar"));
+    assert_eq!(config.popularity, root.join("popularity.bin"));
+    assert_eq!(
+        config.plan,
+        SimulationPlan {
+            requests: 100,
+            seed: 0,
+            min_duration_minutes: 30,
+            max_duration_minutes: 180,
+            top_pois: 20,
+        }
+    );
+}
+
+#[rstest]
+#[case::no_requests(Some(0), None, None)]
+#[case::zero_minimum(None, Some(0), None)]
+#[case::inverted_range(None, Some(120), Some(60))]
+fn simulate_config_rejects_invalid_plans(
+    #[case] requests: Option<u32>,
+    #[case] min_duration: Option<u16>,
+    #[case] max_duration: Option<u16>,
+) {
+    let args = SimulateArgs {
+        requests,
+        min_duration,
+        max_duration,
+        ..SimulateArgs::default()
+    };
+
+    let err = SimulateConfig::try_from(args).expect_err("invalid plan should error");
+    assert!(
+        matches!(err, CliError::InvalidSimulationPlan { .. }),
+        "expected InvalidSimulationPlan, found {err:?}"
+    );
+}
+
+#[rstest]
+fn run_simulate_with_writes_json_report(candidates: Vec<PointOfInterest>) {
+    let tmp = TempDir::new().expect("tempdir");
+    let root = Utf8PathBuf::from_path_buf(tmp.path().to_path_buf()).expect("utf-8 workspace");
+    write_utf8(&root.join("pois.db"), b"db");
+    write_utf8(&root.join("pois.rstar"), b"index");
+    let args = SimulateArgs {
+        artefacts_dir: Some(root),
+        requests: Some(10),
+        max_duration: Some(120),
+        top_pois: Some(1),
+        ..SimulateArgs::default()
+    };
+    let builder = StubSimulationBuilder { candidates };
+    let mut output = Vec::new();
+
+    run_simulate_with(args, &builder, &mut output).expect("simulation should succeed");
+
+    let report: serde_json::Value = serde_json::from_slice(&output).expect("JSON report");
+    assert_eq!(report["requests"], 10);
+    assert_eq!(report["solved"], 10);
+    assert_eq!(report["top_pois"], 1);
+    assert_eq!(report["top_poi_coverage"], 0.0);
+}
+
+#[rstest]
+fn run_simulate_with_rejects_empty_artefacts() {
+    let tmp = TempDir::new().expect("tempdir");
+    let root = Utf8PathBuf::from_path_buf(tmp.path().to_path_buf()).expect("utf-8 workspace");
+    write_utf8(&root.join("pois.db"), b"db");
+    write_utf8(&root.join("pois.rstar"), b"index");
+    let args = SimulateArgs {
+        artefacts_dir: Some(root),
+        ..SimulateArgs::default()
+    };
+    let builder = StubSimulationBuilder {
+        candidates: Vec::new(),
+    };
+
+    let err = run_simulate_with(args, &builder, &mut Vec::new())
+        .expect_err("empty artefacts should error");
+    assert!(
+        matches!(err, CliError::EmptySimulationArtefacts { .. }),
+        "expected EmptySimulationArtefacts, found {err:?}"
+    );
+}
@@ -0,0 +1,106 @@
+//! Focused unit tests covering loading solve requests and interest priors.
+
+use super::helpers::write_utf8;
+use super::*;
+use camino::Utf8PathBuf;
+use geo::Coord;
+use rstest::rstest;
+use tempfile::TempDir;
+use wildside_core::{InterestProfile, SolveRequest, Theme};
+
+#[rstest]
+fn load_solve_request_decodes_json() {
+    let tmp = TempDir::new().expect("tempdir");
+    let root = Utf8PathBuf::from_path_buf(tmp.path().to_path_buf()).expect("utf-8 workspace");
+    let request_path = root.join("request.json");
+
+    let request = SolveRequest {
+        start: Coord { x: 0.1, y: 51.5 },
+        duration_minutes: 30,
+        interests: InterestProfile::new(),
+        seed: 42,
+        max_nodes: Some(10),
+        ..Default::default()
+    };
+    let payload = serde_json::to_string_pretty(&request).expect("serialize request");
+    write_utf8(&request_path, payload.as_bytes());
+
+    let decoded = load_solve_request(&request_path).expect("request should decode");
+    assert_eq!(decoded, request);
+}
+
+/// Helper to set up a temporary directory and request path for load_solve_request tests.
+fn setup_request_test() -> (TempDir, Utf8PathBuf) {
+    let tmp = TempDir::new().expect("tempdir");
+    let root = Utf8PathBuf::from_path_buf(tmp.path().to_path_buf()).expect("utf-8 workspace");
+    let request_path = root.join("request.json");
+    (tmp, request_path)
+}
+
+#[rstest]
+fn load_solve_request_rejects_invalid_json() {
+    let (_tmp, request_path) = setup_request_test();
+    write_utf8(&request_path, b"{ not valid json");
+
+    let err = load_solve_request(&request_path).expect_err("invalid json should error");
+    match err {
+        CliError::ParseSolveRequest { path, .. } => assert_eq!(path, request_path),
+        other => panic!("unexpected error {other:?}"),
+    }
+}
+
+#[rstest]
+fn load_solve_request_io_error_returns_open_error() {
+    let (_tmp, request_path) = setup_request_test();
+    // Deliberately don't write the file to trigger IO error
+
+    let err = load_solve_request(&request_path).expect_err("missing request should error");
+    match err {
+        CliError::OpenSolveRequest { path, .. } => assert_eq!(path, request_path),
+        other => panic!("expected OpenSolveRequest, found {other:?}"),
+    }
+}
+
+#[rstest]
+fn load_interest_priors_skips_missing_artefact() {
+    let tmp = TempDir::new().expect("tempdir");
+    let root = Utf8PathBuf::from_path_buf(tmp.path().to_path_buf()).expect("utf-8 workspace");
+
+    let priors = load_interest_priors(&root.join("priors.json")).expect("missing priors are fine");
+    assert!(priors.is_none());
+}
+
+#[rstest]
+fn load_interest_priors_blends_empty_profiles() {
+    let tmp = TempDir::new().expect("tempdir");
+    let root = Utf8PathBuf::from_path_buf(tmp.path().to_path_buf()).expect("utf-8 workspace");
+    let priors_path = root.join("priors.json");
+    write_utf8(
+        &priors_path,
+        br#"{"regions": [{
+            "name": "london",
+            "bounds": {"min": {"x": -0.5, "y": 51.3}, "max": {"x": 0.3, "y": 51.7}},
+            "weights": {"History": 0.9}
+        }]}"#,
+    );
+
+    let priors = load_interest_priors(&priors_path)
+        .expect("priors should load")
+        .expect("priors present");
+    let blended = priors.blend(Coord { x: 0.1, y: 51.5 }, &InterestProfile::new());
+    assert_eq!(blended.weight(&Theme::History), Some(0.9));
+}
+
+#[rstest]
+fn load_interest_priors_rejects_invalid_weights() {
+    let tmp = TempDir::new().expect("tempdir");
+    let root = Utf8PathBuf::from_path_buf(tmp.path().to_path_buf()).expect("utf-8 workspace");
+    let priors_path = root.join("priors.json");
+    write_utf8(&priors_path, br#"{"fallback": {"Art": 2.0}}"#);
+
+    let err = load_interest_priors(&priors_path).expect_err("invalid weight should error");
+    match err {
+        CliError::ParseInterestPriors { path, .. } => assert_eq!(path, priors_path),
+        other => panic!("expected ParseInterestPriors, found {other:?}"),
+    }
+}
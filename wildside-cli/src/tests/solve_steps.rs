@@ -2,6 +2,7 @@
 
 use super::helpers::write_utf8;
 use super::*;
+use camino::Utf8PathBuf;
 use geo::Coord;
 use rstest::fixture;
 use rstest_bdd_macros::{given, scenario, then, when};
@@ -105,11 +106,11 @@ fn valid_solve_request_exists(#[from(world)] world: &SolveWorld) {
     let interests = InterestProfile::new().with_weight(Theme::History, 0.8);
     let request = SolveRequest {
         start: Coord { x: -0.1, y: 51.5 },
-        end: None,
         duration_minutes: 30,
         interests,
         seed: 1,
         max_nodes: Some(20),
+        ..Default::default()
     };
     let payload = serde_json::to_string_pretty(&request).expect("serialize request");
     write_utf8(&world.request_path, payload.as_bytes());
@@ -124,11 +125,10 @@ fn solve_request_contains_invalid_json(#[from(world)] world: &SolveWorld) {
 fn solve_request_contains_invalid_parameters(#[from(world)] world: &SolveWorld) {
     let request = SolveRequest {
         start: Coord { x: -0.1, y: 51.5 },
-        end: None,
         duration_minutes: 0,
         interests: InterestProfile::new(),
         seed: 1,
-        max_nodes: None,
+        ..Default::default()
     };
     let payload = serde_json::to_string_pretty(&request).expect("serialize request");
     write_utf8(&world.request_path, payload.as_bytes());
@@ -146,13 +146,22 @@ fn run_solve_command(#[from(world)] world: &SolveWorld) {
                 diagnostics: Diagnostics {
                     solve_time: Duration::from_secs(0),
                     candidates_evaluated: 0,
+                    candidates_pruned: 0,
                 },
+                geometry: None,
             };
             let builder = StubSolveSolverBuilder { response };
             let mut buffer = world.stdout.borrow_mut();
             run_solve_with(args, &builder, &mut *buffer)
         }
-        Command::Ingest(_) => panic!("expected solve command"),
+        Command::Ingest(_)
+        | Command::Simulate(_)
+        | Command::Serve(_)
+        | Command::Bundle(_)
+        | Command::Unbundle(_)
+        | Command::Validate(_) => {
+            panic!("expected solve command")
+        }
     });
 
     world.result.replace(Some(outcome));
@@ -1,19 +1,16 @@
-//! Focused unit tests covering solve CLI configuration and request parsing.
+//! Focused unit tests covering solve CLI configuration and artefact validation.
 
 use super::helpers::write_utf8;
 use super::*;
 use camino::Utf8PathBuf;
-use geo::Coord;
 use rstest::rstest;
 use tempfile::TempDir;
-use wildside_core::{InterestProfile, SolveRequest};
 
 #[derive(Debug, Copy, Clone)]
 enum MissingArtefact {
     Request,
     PoisDb,
     SpatialIndex,
-    Popularity,
 }
 
 #[rstest]
@@ -54,6 +51,8 @@ fn solve_config_derives_default_artefact_paths() {
         pois_db: None,
         spatial_index: None,
         popularity: None,
+        priors: None,
+        theme_mapping: None,
         osrm_base_url: None,
     };
 
@@ -62,14 +61,41 @@ fn solve_config_derives_default_artefact_paths() {
     assert_eq!(config.pois_db, db_path);
     assert_eq!(config.spatial_index, index_path);
     assert_eq!(config.popularity, popularity_path);
+    assert_eq!(config.priors, root.join("priors.json"));
     assert_eq!(config.osrm_base_url, "http://localhost:5000");
 }
 
+#[rstest]
+fn validate_sources_requires_a_supplied_theme_mapping() {
+    let tmp = TempDir::new().expect("tempdir");
+    let root = Utf8PathBuf::from_path_buf(tmp.path().to_path_buf()).expect("utf-8 workspace");
+    let request_path = root.join("request.json");
+    write_utf8(&request_path, b"{}");
+    write_utf8(&root.join("pois.db"), b"db");
+    write_utf8(&root.join("pois.rstar"), b"index");
+
+    let args = SolveArgs {
+        request_path: Some(request_path),
+        artefacts_dir: Some(root.clone()),
+        theme_mapping: Some(root.join("themes.toml")),
+        ..SolveArgs::default()
+    };
+    let config = SolveConfig::try_from(args).expect("config should build");
+    assert_eq!(config.theme_mapping, Some(root.join("themes.toml")));
+
+    let err = config
+        .validate_sources()
+        .expect_err("missing mapping should error");
+    match err {
+        CliError::MissingSourceFile { field, .. } => assert_eq!(field, ARG_SOLVE_THEME_MAPPING),
+        other => panic!("expected MissingSourceFile, found {other:?}"),
+    }
+}
+
 #[rstest]
 #[case::missing_request(ARG_SOLVE_REQUEST, MissingArtefact::Request)]
 #[case::missing_db(ARG_SOLVE_POIS_DB, MissingArtefact::PoisDb)]
 #[case::missing_index(ARG_SOLVE_SPATIAL_INDEX, MissingArtefact::SpatialIndex)]
-#[case::missing_popularity(ARG_SOLVE_POPULARITY, MissingArtefact::Popularity)]
 fn validate_sources_reports_missing_artefacts(
     #[case] expected_field: &'static str,
     #[case] missing: MissingArtefact,
@@ -91,15 +117,15 @@ fn validate_sources_reports_missing_artefacts(
     if !matches!(missing, MissingArtefact::SpatialIndex) {
         write_utf8(&index_path, b"index");
     }
-    if !matches!(missing, MissingArtefact::Popularity) {
-        write_utf8(&popularity_path, b"popularity");
-    }
+    write_utf8(&popularity_path, b"popularity");
 
     let config = SolveConfig {
         request_path,
         pois_db: db_path,
         spatial_index: index_path,
         popularity: popularity_path,
+        priors: root.join("priors.json"),
+        theme_mapping: None,
         osrm_base_url: "http://localhost:5000".to_string(),
     };
 
@@ -111,83 +137,120 @@ fn validate_sources_reports_missing_artefacts(
 }
 
 #[rstest]
-fn validate_sources_reports_not_file() {
+fn validate_sources_allows_missing_popularity() {
     let tmp = TempDir::new().expect("tempdir");
     let root = Utf8PathBuf::from_path_buf(tmp.path().to_path_buf()).expect("utf-8 workspace");
 
     let request_path = root.join("request.json");
-    std::fs::create_dir(&request_path).expect("request directory");
+    let db_path = root.join("pois.db");
+    let index_path = root.join("pois.rstar");
+    write_utf8(&request_path, b"{}");
+    write_utf8(&db_path, b"db");
+    write_utf8(&index_path, b"index");
 
     let config = SolveConfig {
-        request_path: request_path.clone(),
-        pois_db: root.join("pois.db"),
-        spatial_index: root.join("pois.rstar"),
+        request_path,
+        pois_db: db_path,
+        spatial_index: index_path,
         popularity: root.join("popularity.bin"),
+        priors: root.join("priors.json"),
+        theme_mapping: None,
         osrm_base_url: "http://localhost:5000".to_string(),
     };
 
-    let err = config
+    config
         .validate_sources()
-        .expect_err("expected directory path to fail validation");
-    match err {
-        CliError::SourcePathNotFile { field, path } => {
-            assert_eq!(field, ARG_SOLVE_REQUEST);
-            assert_eq!(path, request_path);
-        }
-        other => panic!("expected SourcePathNotFile, found {other:?}"),
-    }
+        .expect("missing popularity should degrade rather than fail");
 }
 
 #[rstest]
-fn load_solve_request_decodes_json() {
+fn validate_sources_rejects_popularity_directory() {
     let tmp = TempDir::new().expect("tempdir");
     let root = Utf8PathBuf::from_path_buf(tmp.path().to_path_buf()).expect("utf-8 workspace");
+
     let request_path = root.join("request.json");
+    let db_path = root.join("pois.db");
+    let index_path = root.join("pois.rstar");
+    let popularity_path = root.join("popularity.bin");
+    write_utf8(&request_path, b"{}");
+    write_utf8(&db_path, b"db");
+    write_utf8(&index_path, b"index");
+    std::fs::create_dir(&popularity_path).expect("popularity directory");
 
-    let request = SolveRequest {
-        start: Coord { x: 0.1, y: 51.5 },
-        end: None,
-        duration_minutes: 30,
-        interests: InterestProfile::new(),
-        seed: 42,
-        max_nodes: Some(10),
+    let config = SolveConfig {
+        request_path,
+        pois_db: db_path,
+        spatial_index: index_path,
+        popularity: popularity_path,
+        priors: root.join("priors.json"),
+        theme_mapping: None,
+        osrm_base_url: "http://localhost:5000".to_string(),
     };
-    let payload = serde_json::to_string_pretty(&request).expect("serialize request");
-    write_utf8(&request_path, payload.as_bytes());
 
-    let decoded = load_solve_request(&request_path).expect("request should decode");
-    assert_eq!(decoded, request);
+    let err = config
+        .validate_sources()
+        .expect_err("directory popularity path should fail");
+    match err {
+        CliError::SourcePathNotFile { field, .. } => assert_eq!(field, ARG_SOLVE_POPULARITY),
+        other => panic!("expected SourcePathNotFile, found {other:?}"),
+    }
 }
 
-/// Helper to set up a temporary directory and request path for load_solve_request tests.
-fn setup_request_test() -> (TempDir, Utf8PathBuf) {
+#[rstest]
+fn validate_sources_reports_not_file() {
     let tmp = TempDir::new().expect("tempdir");
     let root = Utf8PathBuf::from_path_buf(tmp.path().to_path_buf()).expect("utf-8 workspace");
+
     let request_path = root.join("request.json");
-    (tmp, request_path)
-}
+    std::fs::create_dir(&request_path).expect("request directory");
 
-#[rstest]
-fn load_solve_request_rejects_invalid_json() {
-    let (_tmp, request_path) = setup_request_test();
-    write_utf8(&request_path, b"{ not valid json");
+    let config = SolveConfig {
+        request_path: request_path.clone(),
+        pois_db: root.join("pois.db"),
+        spatial_index: root.join("pois.rstar"),
+        popularity: root.join("popularity.bin"),
+        priors: root.join("priors.json"),
+        theme_mapping: None,
+        osrm_base_url: "http://localhost:5000".to_string(),
+    };
 
-    let err = load_solve_request(&request_path).expect_err("invalid json should error");
+    let err = config
+        .validate_sources()
+        .expect_err("expected directory path to fail validation");
     match err {
-        CliError::ParseSolveRequest { path, .. } => assert_eq!(path, request_path),
-        other => panic!("unexpected error {other:?}"),
+        CliError::SourcePathNotFile { field, path } => {
+            assert_eq!(field, ARG_SOLVE_REQUEST);
+            assert_eq!(path, request_path);
+        }
+        other => panic!("expected SourcePathNotFile, found {other:?}"),
     }
 }
 
+#[cfg(feature = "store-sqlite")]
 #[rstest]
-fn load_solve_request_io_error_returns_open_error() {
-    let (_tmp, request_path) = setup_request_test();
-    // Deliberately don't write the file to trigger IO error
+fn make_store_and_deps_rejects_incompatible_manifest() {
+    use wildside_data::manifest::{ArtefactManifest, ManifestError};
 
-    let err = load_solve_request(&request_path).expect_err("missing request should error");
-    match err {
-        CliError::OpenSolveRequest { path, .. } => assert_eq!(path, request_path),
-        other => panic!("expected OpenSolveRequest, found {other:?}"),
+    let tmp = TempDir::new().expect("tempdir");
+    let root = Utf8PathBuf::from_path_buf(tmp.path().to_path_buf()).expect("utf-8 workspace");
+    let mut manifest = ArtefactManifest::new("99.0.0");
+    manifest.bundle_version = wildside_core::formats::BUNDLE.current + 1;
+    manifest.write(&root).expect("write manifest");
+
+    let config = SolveConfig {
+        request_path: root.join("request.json"),
+        pois_db: root.join("pois.db"),
+        spatial_index: root.join("pois.rstar"),
+        popularity: root.join("popularity.bin"),
+        priors: root.join("priors.json"),
+        theme_mapping: None,
+        osrm_base_url: "http://localhost:5000".to_string(),
+    };
+
+    match crate::solve::make_store_and_deps(config.artefacts()) {
+        Err(CliError::ArtefactManifest(ManifestError::Incompatible { .. })) => {}
+        Err(other) => panic!("expected incompatible manifest, found {other:?}"),
+        Ok(_) => panic!("a newer bundle should be rejected before opening artefacts"),
     }
 }
 
@@ -239,5 +302,6 @@ fn merge_layers_honours_precedence_and_defaults_paths() {
     assert_eq!(config.pois_db, cli_dir.join("pois.db"));
     assert_eq!(config.spatial_index, cli_dir.join("pois.rstar"));
     assert_eq!(config.popularity, cli_dir.join("popularity.bin"));
+    assert_eq!(config.priors, cli_dir.join("priors.json"));
     assert_eq!(config.osrm_base_url, "http://from-file:5000");
 }
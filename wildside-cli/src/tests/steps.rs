@@ -133,7 +133,14 @@ fn configure_ingest(#[from(world)] world: &IngestWorld) {
                     resolve_ingest_config(cmd)
                 }
             }
-            Command::Solve(_) => panic!("expected ingest command"),
+            Command::Solve(_)
+            | Command::Simulate(_)
+            | Command::Serve(_)
+            | Command::Bundle(_)
+            | Command::Unbundle(_)
+            | Command::Validate(_) => {
+                panic!("expected ingest command")
+            }
         });
     world.cli_result().replace(Some(outcome));
 }
@@ -148,8 +155,8 @@ fn plan_uses_cli_paths(#[from(world)] world: &IngestWorld) {
         .expect("expected success");
     assert_eq!(config.osm_pbf, world.dataset_files().osm().to_path_buf());
     assert_eq!(
-        config.wikidata_dump,
-        world.dataset_files().wikidata().to_path_buf()
+        config.wikidata,
+        WikidataInput::Dump(world.dataset_files().wikidata().to_path_buf())
     );
 }
 
@@ -177,8 +184,8 @@ fn precedence_holds(#[from(world)] world: &IngestWorld) {
         .expect("expected success");
     assert_eq!(config.osm_pbf, world.dataset_files().osm().to_path_buf());
     assert_eq!(
-        config.wikidata_dump,
-        world.dataset_files().env_wikidata().to_path_buf()
+        config.wikidata,
+        WikidataInput::Dump(world.dataset_files().env_wikidata().to_path_buf())
     );
 }
 
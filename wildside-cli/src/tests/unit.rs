@@ -6,7 +6,12 @@ use super::helpers::write_utf8;
 use super::*;
 use camino::Utf8PathBuf;
 use rstest::rstest;
+use std::time::Duration;
 use tempfile::TempDir;
+use wildside_core::travel_time::DEFAULT_MAX_TRAVEL_TIME;
+use wildside_data::wikidata::etl::{
+    ClaimPropertySet, DEFAULT_API_URL, Decompression, DumpDecoder, RankFilter,
+};
 
 #[rstest]
 #[case(
@@ -52,8 +57,13 @@ fn validate_sources_reports_missing_files() {
         Utf8PathBuf::from_path_buf(tmp.path().to_path_buf()).expect("utf-8 workspace path");
     let config = IngestConfig {
         osm_pbf: workspace.join("missing-osm"),
-        wikidata_dump: workspace.join("missing-wiki"),
+        wikidata: WikidataInput::Dump(workspace.join("missing-wiki")),
         output_dir: workspace,
+        claim_properties: ClaimPropertySet::default(),
+        claim_ranks: RankFilter::All,
+        designation_labels: None,
+        travel_time_horizon: None,
+        decompression: Decompression::Parallel,
     };
     let err = config.validate_sources().expect_err("expected failure");
     match err {
@@ -72,8 +82,13 @@ fn validate_sources_rejects_directories() {
     write_utf8(&file_path, b"{}\n");
     let config = IngestConfig {
         osm_pbf: root.clone(),
-        wikidata_dump: file_path,
+        wikidata: WikidataInput::Dump(file_path),
         output_dir: root.clone(),
+        claim_properties: ClaimPropertySet::default(),
+        claim_ranks: RankFilter::All,
+        designation_labels: None,
+        travel_time_horizon: None,
+        decompression: Decompression::Parallel,
     };
     let err = config
         .validate_sources()
@@ -97,8 +112,13 @@ fn validate_sources_rejects_output_file() {
 
     let config = IngestConfig {
         osm_pbf: osm_path,
-        wikidata_dump: wikidata_path,
+        wikidata: WikidataInput::Dump(wikidata_path),
         output_dir: output_file,
+        claim_properties: ClaimPropertySet::default(),
+        claim_ranks: RankFilter::All,
+        designation_labels: None,
+        travel_time_horizon: None,
+        decompression: Decompression::Parallel,
     };
 
     let err = config
@@ -124,6 +144,13 @@ fn ingest_config_uses_default_output_dir_when_none() {
         osm_pbf: Some(osm_pbf_path),
         wikidata_dump: Some(wikidata_dump_path),
         output_dir: None,
+        claim_properties: None,
+        truthy_claims: None,
+        travel_time_horizon: None,
+        no_travel_times: None,
+        decompression: None,
+        designation_labels: None,
+        wikidata_api: None,
     };
 
     let config: IngestConfig = IngestConfig::try_from(args).expect("config should build");
@@ -137,3 +164,196 @@ fn ingest_config_uses_default_output_dir_when_none() {
         .validate_sources()
         .expect("validation should succeed for valid defaults");
 }
+
+#[rstest]
+fn converting_parses_claim_properties() {
+    let args = IngestArgs {
+        osm_pbf: Some(Utf8PathBuf::from("planet.osm.pbf")),
+        wikidata_dump: Some(Utf8PathBuf::from("wikidata.json")),
+        claim_properties: Some("P31, P571".into()),
+        ..IngestArgs::default()
+    };
+
+    let config = IngestConfig::try_from(args).expect("config should build");
+    assert_eq!(config.claim_properties.to_string(), "P31,P571");
+}
+
+#[rstest]
+#[case::flag_only(&["--designation-labels"], Some("en"))]
+#[case::languages(&["--designation-labels", "de,EN"], Some("de,en"))]
+#[case::flag_absent(&[], None)]
+fn designation_labels_flag_selects_languages(
+    #[case] extra: &[&str],
+    #[case] expected: Option<&str>,
+) {
+    let invocation = ["wildside", "ingest", "--osm-pbf", "planet.osm.pbf"]
+        .into_iter()
+        .chain(["--wikidata-dump", "wikidata.json"])
+        .chain(extra.iter().copied());
+    let cli = Cli::try_parse_from(invocation).expect("arguments should parse");
+    let Command::Ingest(args) = cli.command else {
+        panic!("expected ingest command");
+    };
+
+    let config = IngestConfig::try_from(args).expect("config should build");
+    assert_eq!(
+        config
+            .designation_labels
+            .map(|languages| languages.to_string())
+            .as_deref(),
+        expected
+    );
+}
+
+#[rstest]
+#[case::flag_set(&["--truthy-claims"], RankFilter::Truthy)]
+#[case::flag_absent(&[], RankFilter::All)]
+fn truthy_claims_flag_selects_rank_filter(#[case] extra: &[&str], #[case] expected: RankFilter) {
+    let invocation = ["wildside", "ingest", "--osm-pbf", "planet.osm.pbf"]
+        .into_iter()
+        .chain(["--wikidata-dump", "wikidata.json"])
+        .chain(extra.iter().copied());
+    let cli = Cli::try_parse_from(invocation).expect("arguments should parse");
+    let Command::Ingest(args) = cli.command else {
+        panic!("expected ingest command");
+    };
+
+    let config = IngestConfig::try_from(args).expect("config should build");
+    assert_eq!(config.claim_ranks, expected);
+}
+
+#[rstest]
+#[case::default_mode(&[], "latest-all.json.bz2", DumpDecoder::ParallelBzip2)]
+#[case::sequential(
+    &["--decompression", "sequential"],
+    "latest-all.json.bz2",
+    DumpDecoder::Bzip2
+)]
+#[case::gzip(&["--decompression", "sequential"], "latest-all.json.gz", DumpDecoder::Gzip)]
+fn decompression_flag_selects_the_dump_decoder(
+    #[case] extra: &[&str],
+    #[case] dump: &str,
+    #[case] expected: DumpDecoder,
+) {
+    let invocation = ["wildside", "ingest", "--osm-pbf", "planet.osm.pbf"]
+        .into_iter()
+        .chain(["--wikidata-dump", dump])
+        .chain(extra.iter().copied());
+    let cli = Cli::try_parse_from(invocation).expect("arguments should parse");
+    let Command::Ingest(args) = cli.command else {
+        panic!("expected ingest command");
+    };
+
+    let config = IngestConfig::try_from(args).expect("config should build");
+    assert_eq!(config.wikidata_decoder(), Some(expected));
+}
+
+#[rstest]
+fn converting_rejects_unknown_decompression_modes() {
+    let args = IngestArgs {
+        osm_pbf: Some(Utf8PathBuf::from("planet.osm.pbf")),
+        wikidata_dump: Some(Utf8PathBuf::from("wikidata.json.bz2")),
+        decompression: Some("threaded".into()),
+        ..IngestArgs::default()
+    };
+
+    let err = IngestConfig::try_from(args).expect_err("unknown mode should error");
+    assert!(
+        matches!(err, CliError::InvalidDecompression { .. }),
+        "expected InvalidDecompression, found {err:?}"
+    );
+}
+
+#[rstest]
+#[case::default_horizon(&[], Some(DEFAULT_MAX_TRAVEL_TIME))]
+#[case::custom_horizon(&["--travel-time-horizon", "30"], Some(Duration::from_secs(30 * 60)))]
+#[case::opted_out(&["--no-travel-times"], None)]
+fn travel_time_flags_select_the_horizon(
+    #[case] extra: &[&str],
+    #[case] expected: Option<Duration>,
+) {
+    let invocation = ["wildside", "ingest", "--osm-pbf", "planet.osm.pbf"]
+        .into_iter()
+        .chain(["--wikidata-dump", "wikidata.json"])
+        .chain(extra.iter().copied());
+    let cli = Cli::try_parse_from(invocation).expect("arguments should parse");
+    let Command::Ingest(args) = cli.command else {
+        panic!("expected ingest command");
+    };
+
+    let config = IngestConfig::try_from(args).expect("config should build");
+    assert_eq!(config.travel_time_horizon, expected);
+}
+
+#[rstest]
+#[case::zero_horizon(Some(0), None)]
+#[case::horizon_and_opt_out(Some(30), Some(true))]
+fn converting_rejects_invalid_travel_time_options(
+    #[case] travel_time_horizon: Option<u16>,
+    #[case] no_travel_times: Option<bool>,
+) {
+    let args = IngestArgs {
+        osm_pbf: Some(Utf8PathBuf::from("planet.osm.pbf")),
+        wikidata_dump: Some(Utf8PathBuf::from("wikidata.json")),
+        travel_time_horizon,
+        no_travel_times,
+        ..IngestArgs::default()
+    };
+
+    let err = IngestConfig::try_from(args).expect_err("invalid options should error");
+    assert!(
+        matches!(err, CliError::InvalidTravelTimeOptions { .. }),
+        "expected InvalidTravelTimeOptions, found {err:?}"
+    );
+}
+
+#[rstest]
+#[case::default_endpoint(&["--wikidata-api"], DEFAULT_API_URL)]
+#[case::custom_endpoint(
+    &["--wikidata-api", "http://localhost:8080/w/api.php"],
+    "http://localhost:8080/w/api.php"
+)]
+fn wikidata_api_flag_selects_api_source(#[case] extra: &[&str], #[case] expected: &str) {
+    let invocation = ["wildside", "ingest", "--osm-pbf", "planet.osm.pbf"]
+        .into_iter()
+        .chain(extra.iter().copied());
+    let cli = Cli::try_parse_from(invocation).expect("arguments should parse");
+    let Command::Ingest(args) = cli.command else {
+        panic!("expected ingest command");
+    };
+
+    let config = IngestConfig::try_from(args).expect("config should build");
+    assert_eq!(config.wikidata, WikidataInput::Api(expected.to_owned()));
+}
+
+#[rstest]
+fn converting_rejects_dump_and_api_together() {
+    let args = IngestArgs {
+        osm_pbf: Some(Utf8PathBuf::from("planet.osm.pbf")),
+        wikidata_dump: Some(Utf8PathBuf::from("wikidata.json")),
+        wikidata_api: Some(DEFAULT_API_URL.to_owned()),
+        ..IngestArgs::default()
+    };
+
+    let err = IngestConfig::try_from(args).expect_err("conflicting sources should error");
+    assert!(
+        matches!(err, CliError::ConflictingWikidataSources),
+        "expected ConflictingWikidataSources, found {err:?}"
+    );
+}
+
+#[rstest]
+fn converting_rejects_malformed_claim_properties() {
+    let args = IngestArgs {
+        osm_pbf: Some(Utf8PathBuf::from("planet.osm.pbf")),
+        wikidata_dump: Some(Utf8PathBuf::from("wikidata.json")),
+        claim_properties: Some("P31,Q5".into()),
+        ..IngestArgs::default()
+    };
+
+    let err = IngestConfig::try_from(args).expect_err("malformed property should error");
+    assert!(
+        matches!(err, CliError::InvalidClaimProperties { .. }),
+        "expected InvalidClaimProperties, found {err:?}"
+    );
+}
@@ -0,0 +1,77 @@
+//! Unit tests for the validate command.
+
+#![cfg(feature = "store-sqlite")]
+
+use super::*;
+use camino::Utf8PathBuf;
+use geo::Coord;
+use rstest::rstest;
+use tempfile::TempDir;
+use wildside_core::PointOfInterest;
+use wildside_core::store::write_spatial_index;
+
+fn artefacts(indexed: &[u64]) -> (TempDir, Utf8PathBuf) {
+    let tmp = TempDir::new().expect("tempdir");
+    let root = Utf8PathBuf::from_path_buf(tmp.path().to_path_buf()).expect("utf-8 workspace path");
+    rusqlite::Connection::open(root.join("pois.db").as_std_path())
+        .expect("open pois.db")
+        .execute_batch(
+            "CREATE TABLE pois (id INTEGER PRIMARY KEY, lon REAL, lat REAL, tags TEXT);
+             INSERT INTO pois VALUES (1, 0.0, 0.0, '{}'), (2, 1.0, 1.0, '{}');",
+        )
+        .expect("seed pois.db");
+    let pois: Vec<PointOfInterest> = indexed
+        .iter()
+        .map(|&id| {
+            let offset = if id == 1 { 0.0 } else { 1.0 };
+            PointOfInterest::with_empty_tags(
+                id,
+                Coord {
+                    x: offset,
+                    y: offset,
+                },
+            )
+        })
+        .collect();
+    write_spatial_index(root.join("pois.rstar").as_std_path(), &pois).expect("write index");
+    (tmp, root)
+}
+
+fn run(root: Utf8PathBuf) -> (Result<(), CliError>, serde_json::Value) {
+    let mut output = Vec::new();
+    let result = validate::run_validate_with(
+        ValidateArgs {
+            artefacts_dir: Some(root),
+            popularity: None,
+        },
+        &mut output,
+    );
+    let report = serde_json::from_slice(&output).expect("report is JSON");
+    (result, report)
+}
+
+#[rstest]
+fn consistent_artefacts_pass() {
+    let (_tmp, root) = artefacts(&[1, 2]);
+
+    let (result, report) = run(root);
+
+    result.expect("validation should pass");
+    assert_eq!(report["pois"], 2);
+    assert_eq!(report["popularity_scores"], serde_json::Value::Null);
+    assert_eq!(report["issues"], serde_json::json!([]));
+}
+
+#[rstest]
+fn unindexed_pois_fail_validation() {
+    let (_tmp, root) = artefacts(&[1]);
+
+    let (result, report) = run(root);
+
+    assert!(matches!(
+        result,
+        Err(CliError::InconsistentArtefacts { issues: 1, .. })
+    ));
+    assert_eq!(report["issues"][0]["kind"], "unindexed_pois");
+    assert_eq!(report["issues"][0]["sample_ids"], serde_json::json!([2]));
+}
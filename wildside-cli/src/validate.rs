@@ -0,0 +1,109 @@
+//! Validate command: cross-check an artefact set and report inconsistencies.
+//!
+//! Opening a store only samples the spatial index against `pois.db`, so a
+//! damaged artefact set can pass that check and still fail on individual
+//! POIs. `validate` checks every index entry, every foreign key, and the
+//! popularity coverage, printing the report as JSON and failing when any
+//! check does.
+
+use std::io::Write;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use clap::Parser;
+use ortho_config::{OrthoConfig, SubcmdConfigMerge};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "store-sqlite")]
+use wildside_data::integrity::{IntegrityReport, VerifyOptions, verify_artifacts_with_options};
+#[cfg(feature = "store-sqlite")]
+use wildside_data::manifest::load_manifest;
+#[cfg(feature = "store-sqlite")]
+use wildside_scorer::load_popularity;
+
+use crate::{ARG_SOLVE_ARTEFACTS_DIR, ARG_SOLVE_POPULARITY, CliError, solve::SolveConfig};
+
+/// CLI arguments for the `validate` subcommand.
+#[derive(Debug, Clone, Parser, Deserialize, Serialize, OrthoConfig, Default)]
+#[command(
+    long_about = "Check that pois.rstar indexes exactly the POIs in pois.db, \
+                 that every foreign key in pois.db resolves, and that \
+                 popularity.bin scores every stored POI. Prints the report \
+                 as JSON and exits with an error if any check fails.",
+    about = "Cross-check prepared artefacts for inconsistencies"
+)]
+#[ortho_config(prefix = "WILDSIDE")]
+pub(crate) struct ValidateArgs {
+    /// Directory containing the artefacts to check (defaults to `.`).
+    #[arg(long = ARG_SOLVE_ARTEFACTS_DIR, value_name = "dir")]
+    #[serde(default)]
+    pub(crate) artefacts_dir: Option<Utf8PathBuf>,
+    /// Override the path to pre-computed popularity scores (`popularity.bin`).
+    #[arg(long = ARG_SOLVE_POPULARITY, value_name = "path")]
+    #[serde(default)]
+    pub(crate) popularity: Option<Utf8PathBuf>,
+}
+
+pub(super) fn run_validate(args: ValidateArgs) -> Result<(), CliError> {
+    let mut stdout = std::io::stdout().lock();
+    run_validate_with(args, &mut stdout)
+}
+
+pub(super) fn run_validate_with(
+    args: ValidateArgs,
+    writer: &mut dyn Write,
+) -> Result<(), CliError> {
+    let merged = args.load_and_merge().map_err(CliError::Configuration)?;
+    let artefacts_dir = merged
+        .artefacts_dir
+        .unwrap_or_else(|| Utf8PathBuf::from("."));
+    let popularity = merged
+        .popularity
+        .unwrap_or_else(|| artefacts_dir.join("popularity.bin"));
+    SolveConfig::allow_missing(&popularity, ARG_SOLVE_POPULARITY)?;
+    validate_artefacts(&artefacts_dir, &popularity, writer)
+}
+
+#[cfg(feature = "store-sqlite")]
+fn validate_artefacts(
+    artefacts_dir: &Utf8Path,
+    popularity: &Utf8Path,
+    writer: &mut dyn Write,
+) -> Result<(), CliError> {
+    load_manifest(artefacts_dir)?;
+    // Without popularity.bin scoring falls back to interests alone, so there
+    // is no coverage to check.
+    let options = if popularity.is_file() {
+        let scores = load_popularity(popularity)?;
+        VerifyOptions::default().with_popularity_ids(scores.into_inner().into_keys())
+    } else {
+        VerifyOptions::default()
+    };
+    let report = verify_artifacts_with_options(artefacts_dir, &options)?;
+    write_report(writer, &report)?;
+    if report.is_consistent() {
+        Ok(())
+    } else {
+        Err(CliError::InconsistentArtefacts {
+            dir: artefacts_dir.to_path_buf(),
+            issues: report.issues.len(),
+        })
+    }
+}
+
+#[cfg(not(feature = "store-sqlite"))]
+fn validate_artefacts(
+    _artefacts_dir: &Utf8Path,
+    _popularity: &Utf8Path,
+    _writer: &mut dyn Write,
+) -> Result<(), CliError> {
+    Err(CliError::MissingFeature {
+        feature: "store-sqlite",
+        action: "validate",
+    })
+}
+
+#[cfg(feature = "store-sqlite")]
+fn write_report(writer: &mut dyn Write, report: &IntegrityReport) -> Result<(), CliError> {
+    let payload =
+        serde_json::to_string_pretty(report).map_err(CliError::SerializeValidationReport)?;
+    writeln!(writer, "{payload}").map_err(CliError::WriteSolveOutput)
+}
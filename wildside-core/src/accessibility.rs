@@ -0,0 +1,198 @@
+//! Accessibility needs a tour must meet.
+//!
+//! [`AccessibilityRequirements`] travel on a
+//! [`SolveRequest`](crate::SolveRequest) and act on both halves of a solve:
+//!
+//! - candidates are limited to POIs whose OpenStreetMap `wheelchair` tag
+//!   offers at least the requested [`WheelchairAccess`]; and
+//! - step-free requests route with the travel-time provider's
+//!   [`step_free`](TravelTimeProvider::step_free) profile, so the legs between
+//!   stops avoid stairs.
+//!
+//! Untagged POIs are treated as inaccessible when wheelchair access is
+//! required, since nothing is known about their entrances.
+//!
+//! # Examples
+//! ```rust
+//! use geo::Coord;
+//! use wildside_core::{AccessibilityRequirements, PointOfInterest, Tags, WheelchairAccess};
+//!
+//! let requirements = AccessibilityRequirements {
+//!     wheelchair: Some(WheelchairAccess::Limited),
+//!     step_free: false,
+//! };
+//! let tags = Tags::from([("wheelchair".into(), "limited".into())]);
+//! let ramped = PointOfInterest::new(1, Coord { x: 0.0, y: 0.0 }, tags);
+//! let untagged = PointOfInterest::with_empty_tags(2, Coord { x: 0.0, y: 0.0 });
+//! assert!(requirements.admits(&ramped));
+//! assert!(!requirements.admits(&untagged));
+//! ```
+
+use crate::{PointOfInterest, Tags, TravelTimeError, TravelTimeProvider};
+
+/// Tag key describing how well a POI suits wheelchair users.
+pub const WHEELCHAIR_TAG: &str = "wheelchair";
+
+/// Degree of wheelchair access a POI offers, from least to most.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WheelchairAccess {
+    /// Partly accessible, e.g. a ramped entrance but some rooms up steps
+    /// (`wheelchair=limited`).
+    Limited,
+    /// Fully accessible (`wheelchair=yes` or `wheelchair=designated`).
+    Full,
+}
+
+impl WheelchairAccess {
+    /// Parse a `wheelchair` tag value, or `None` for `no` and values that
+    /// promise nothing.
+    #[must_use]
+    pub fn from_tag(value: &str) -> Option<Self> {
+        match value {
+            "yes" | "designated" => Some(Self::Full),
+            "limited" => Some(Self::Limited),
+            _ => None,
+        }
+    }
+
+    /// Read the [`WHEELCHAIR_TAG`] of `tags`.
+    #[must_use]
+    pub fn from_tags(tags: &Tags) -> Option<Self> {
+        tags.get(WHEELCHAIR_TAG)
+            .and_then(|value| Self::from_tag(value))
+    }
+}
+
+/// Accessibility needs of the visitor a tour is planned for.
+///
+/// The default asks for nothing, so every POI is a candidate and routes use
+/// the provider's usual profile.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct AccessibilityRequirements {
+    /// Least wheelchair access a POI must offer to be visited.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub wheelchair: Option<WheelchairAccess>,
+    /// Whether the walk between stops must avoid stairs.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub step_free: bool,
+}
+
+impl AccessibilityRequirements {
+    /// Requirements for a wheelchair user: fully accessible POIs joined by
+    /// step-free legs.
+    #[must_use]
+    pub const fn wheelchair_user() -> Self {
+        Self {
+            wheelchair: Some(WheelchairAccess::Full),
+            step_free: true,
+        }
+    }
+
+    /// Whether the requirements ask for nothing.
+    #[must_use]
+    pub const fn is_unrestricted(&self) -> bool {
+        self.wheelchair.is_none() && !self.step_free
+    }
+
+    /// Whether `poi` offers the wheelchair access required.
+    #[must_use]
+    pub fn admits(&self, poi: &PointOfInterest) -> bool {
+        self.wheelchair.is_none_or(|required| {
+            WheelchairAccess::from_tags(&poi.tags).is_some_and(|offered| offered >= required)
+        })
+    }
+
+    /// The travel-time provider to route with: `provider` itself, or its
+    /// step-free profile when one is required.
+    ///
+    /// # Errors
+    /// Returns [`TravelTimeError::StepFreeUnavailable`] when step-free routing
+    /// is required but `provider` cannot plan it.
+    pub fn provider<'a, P: TravelTimeProvider>(
+        &self,
+        provider: &'a P,
+    ) -> Result<&'a dyn TravelTimeProvider, TravelTimeError> {
+        if self.step_free {
+            provider
+                .step_free()
+                .ok_or(TravelTimeError::StepFreeUnavailable)
+        } else {
+            Ok(provider)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for accessibility requirements.
+
+    use super::*;
+    use geo::Coord;
+    use rstest::rstest;
+
+    use crate::test_support::UnitTravelTimeProvider;
+
+    fn poi(wheelchair: Option<&str>) -> PointOfInterest {
+        let tags = wheelchair
+            .map(|value| Tags::from([(WHEELCHAIR_TAG.to_owned(), value.to_owned())]))
+            .unwrap_or_default();
+        PointOfInterest::new(1, Coord { x: 0.0, y: 0.0 }, tags)
+    }
+
+    #[rstest]
+    #[case("yes", Some(WheelchairAccess::Full))]
+    #[case("designated", Some(WheelchairAccess::Full))]
+    #[case("limited", Some(WheelchairAccess::Limited))]
+    #[case("no", None)]
+    #[case("unknown", None)]
+    fn parses_wheelchair_tags(#[case] value: &str, #[case] expected: Option<WheelchairAccess>) {
+        assert_eq!(WheelchairAccess::from_tag(value), expected);
+    }
+
+    #[rstest]
+    #[case(None, None, true)]
+    #[case(None, Some("no"), true)]
+    #[case(Some(WheelchairAccess::Limited), Some("limited"), true)]
+    #[case(Some(WheelchairAccess::Limited), Some("yes"), true)]
+    #[case(Some(WheelchairAccess::Limited), Some("no"), false)]
+    #[case(Some(WheelchairAccess::Limited), None, false)]
+    #[case(Some(WheelchairAccess::Full), Some("limited"), false)]
+    #[case(Some(WheelchairAccess::Full), Some("designated"), true)]
+    fn admits_pois_offering_enough_access(
+        #[case] wheelchair: Option<WheelchairAccess>,
+        #[case] tag: Option<&str>,
+        #[case] expected: bool,
+    ) {
+        let requirements = AccessibilityRequirements {
+            wheelchair,
+            step_free: false,
+        };
+        assert_eq!(requirements.admits(&poi(tag)), expected);
+    }
+
+    #[rstest]
+    fn step_free_routing_needs_a_step_free_profile() {
+        let requirements = AccessibilityRequirements {
+            wheelchair: None,
+            step_free: true,
+        };
+        let Err(err) = requirements.provider(&UnitTravelTimeProvider) else {
+            panic!("unit provider has no step-free profile");
+        };
+        assert_eq!(err, TravelTimeError::StepFreeUnavailable);
+        assert!(
+            AccessibilityRequirements::default()
+                .provider(&UnitTravelTimeProvider)
+                .is_ok()
+        );
+    }
+}
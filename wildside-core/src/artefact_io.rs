@@ -0,0 +1,35 @@
+//! File access shared by the artefact readers and writers.
+//!
+//! Ambient authority is confined to [`open_parent_dir`]; the artefact modules
+//! perform file I/O through the returned `Dir` handle, keeping the capability
+//! surface explicit.
+
+use std::{
+    ffi::OsStr,
+    io::{self, Read},
+    path::Path,
+};
+
+use cap_std::{ambient_authority, fs::Dir};
+
+/// Open the parent directory of `path` as a capability handle.
+///
+/// Returns the handle together with the file name to open within it.
+pub(crate) fn open_parent_dir(path: &Path) -> io::Result<(Dir, &OsStr)> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let dir = Dir::open_ambient_dir(parent, ambient_authority())?;
+    Ok((dir, file_name))
+}
+
+/// Read exactly `N` bytes, such as a fixed-width header field.
+pub(crate) fn read_array<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut buffer = [0_u8; N];
+    reader.read_exact(&mut buffer)?;
+    Ok(buffer)
+}
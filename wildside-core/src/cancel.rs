@@ -0,0 +1,68 @@
+//! Cooperative cancellation of in-flight solves.
+//!
+//! A web service solving on behalf of a client has no use for the answer once
+//! the client disconnects. [`CancellationToken`] lets it say so: the service
+//! keeps one clone, passes another to [`Solver::solve_with_cancel`], and calls
+//! [`CancellationToken::cancel`] when the connection drops. Solvers check the
+//! token at their checkpoints and return [`SolveError::Cancelled`].
+//!
+//! [`Solver::solve_with_cancel`]: crate::Solver::solve_with_cancel
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::SolveError;
+
+/// Shared flag asking a solve to stop and return [`SolveError::Cancelled`].
+///
+/// Clones share the same flag, so cancelling any clone cancels them all.
+/// Cancellation cannot be undone.
+///
+/// # Examples
+/// ```rust
+/// use wildside_core::{CancellationToken, SolveError};
+///
+/// let token = CancellationToken::new();
+/// let worker_token = token.clone();
+/// assert!(worker_token.check().is_ok());
+///
+/// token.cancel();
+/// assert!(worker_token.is_cancelled());
+/// assert!(matches!(worker_token.check(), Err(SolveError::Cancelled)));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Create a token that has not been cancelled.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every solve holding a clone of this token to stop.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Report whether [`CancellationToken::cancel`] has been called.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Return [`SolveError::Cancelled`] once the token has been cancelled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::Cancelled`] after [`CancellationToken::cancel`].
+    pub fn check(&self) -> Result<(), SolveError> {
+        if self.is_cancelled() {
+            Err(SolveError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
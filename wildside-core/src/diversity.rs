@@ -0,0 +1,150 @@
+//! Penalties that keep routes from repeating a single category.
+//!
+//! Scoring alone favours whichever theme best matches the visitor, so a route
+//! can degenerate into five churches in a row. [`DiversityPolicy`] charges a
+//! penalty, in score units, for each stop that repeats a theme already on the
+//! route and for each pair of consecutive stops sharing a theme.
+
+use crate::{PointOfInterest, Theme};
+
+/// Penalties for repeating themes along a route.
+///
+/// Each POI's category is its first theme in [`Theme::ALL`] whose OSM tags it
+/// carries; POIs matching no theme are never penalised. Penalties are in the
+/// same units as POI scores and are subtracted from the objective, not from
+/// the reported route score. The default policy applies no penalty.
+///
+/// # Examples
+///
+/// ```rust
+/// use wildside_core::{DiversityPolicy, Theme};
+///
+/// let policy = DiversityPolicy::new(0.2, 0.5);
+/// let art = Some(&Theme::Art);
+/// // The second stop both repeats the first's theme and follows it.
+/// assert!((policy.route_penalty([art, art]) - 0.7).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DiversityPolicy {
+    /// Charged for each stop whose theme already appears earlier on the
+    /// route.
+    pub repeat_penalty: f64,
+    /// Charged for each stop sharing its theme with the stop just before it.
+    pub consecutive_penalty: f64,
+}
+
+impl DiversityPolicy {
+    /// Charge `repeat_penalty` per repeated theme and `consecutive_penalty`
+    /// per back-to-back pair.
+    #[must_use]
+    pub const fn new(repeat_penalty: f64, consecutive_penalty: f64) -> Self {
+        Self {
+            repeat_penalty,
+            consecutive_penalty,
+        }
+    }
+
+    /// Report whether the policy penalises anything.
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        self.repeat_penalty > 0.0 || self.consecutive_penalty > 0.0
+    }
+
+    /// Total penalty for stops with the given themes, in route order.
+    #[must_use]
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "penalties accumulate in floating-point score units"
+    )]
+    pub fn route_penalty<'a>(&self, themes: impl IntoIterator<Item = Option<&'a Theme>>) -> f64 {
+        let mut seen: Vec<&Theme> = Vec::new();
+        let mut previous: Option<&Theme> = None;
+        let mut penalty = 0.0;
+        for theme in themes {
+            let before = previous;
+            previous = theme;
+            let Some(current) = theme else {
+                continue;
+            };
+            if seen.contains(&current) {
+                penalty += self.repeat_penalty;
+            } else {
+                seen.push(current);
+            }
+            if before == Some(current) {
+                penalty += self.consecutive_penalty;
+            }
+        }
+        penalty
+    }
+
+    /// The category the policy assigns to `poi`.
+    #[must_use]
+    pub fn category(poi: &PointOfInterest) -> Option<Theme> {
+        Theme::primary_for(&poi.tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for theme diversity penalties.
+
+    use super::*;
+    use crate::Tags;
+    use geo::Coord;
+    use rstest::rstest;
+
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "penalties are compared within a tolerance"
+    )]
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[rstest]
+    fn default_policy_is_disabled() {
+        let policy = DiversityPolicy::default();
+        assert!(!policy.is_enabled());
+        assert_close(
+            policy.route_penalty([Some(&Theme::Art), Some(&Theme::Art)]),
+            0.0,
+        );
+    }
+
+    #[rstest]
+    #[case::distinct(vec![Some(Theme::Art), Some(Theme::Food)], 0.0)]
+    #[case::back_to_back(vec![Some(Theme::Art), Some(Theme::Art)], 1.5)]
+    #[case::separated(vec![Some(Theme::Art), Some(Theme::Food), Some(Theme::Art)], 1.0)]
+    #[case::uncategorised_breaks_runs(vec![Some(Theme::Art), None, Some(Theme::Art)], 1.0)]
+    #[case::uncategorised_never_repeats(vec![None, None], 0.0)]
+    fn penalises_repeated_and_consecutive_themes(
+        #[case] themes: Vec<Option<Theme>>,
+        #[case] expected: f64,
+    ) {
+        let policy = DiversityPolicy::new(1.0, 0.5);
+        assert_close(
+            policy.route_penalty(themes.iter().map(Option::as_ref)),
+            expected,
+        );
+    }
+
+    #[rstest]
+    #[case(&[("tourism", "museum")], Some(Theme::Culture))]
+    #[case(&[("historic", "castle")], Some(Theme::History))]
+    #[case(&[("amenity", "bench")], None)]
+    fn categories_follow_declaration_order(
+        #[case] pairs: &[(&str, &str)],
+        #[case] expected: Option<Theme>,
+    ) {
+        let tags: Tags = pairs
+            .iter()
+            .map(|&(key, value)| (key.to_owned(), value.to_owned()))
+            .collect();
+        let poi = PointOfInterest::new(1, Coord { x: 0.0, y: 0.0 }, tags);
+        assert_eq!(DiversityPolicy::category(&poi), expected);
+    }
+}
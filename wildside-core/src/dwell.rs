@@ -0,0 +1,158 @@
+//! Time spent visiting each POI on a route.
+//!
+//! A walking tour is mostly stops: a museum can take an hour while a
+//! viewpoint takes a few minutes. [`DwellTimePolicy`] assigns each candidate
+//! a visit duration so the route's duration budget covers the time spent at
+//! each stop as well as the walking between them.
+
+use std::time::Duration;
+
+use crate::{PointOfInterest, TagFilter, Theme};
+
+/// Which POIs a [`DwellTimeRule`] applies to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DwellSelector {
+    /// POIs whose tags satisfy the filter, e.g. `tourism=museum`.
+    Tag(TagFilter),
+    /// POIs belonging to the theme, as judged by [`Theme::matches_tags`].
+    Theme(Theme),
+}
+
+impl DwellSelector {
+    /// Whether `poi` is selected.
+    #[must_use]
+    pub fn matches(&self, poi: &PointOfInterest) -> bool {
+        match self {
+            Self::Tag(filter) => filter.matches(&poi.tags),
+            Self::Theme(theme) => theme.matches_tags(&poi.tags),
+        }
+    }
+}
+
+/// A visit duration for the POIs a selector picks out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DwellTimeRule {
+    /// POIs the rule applies to.
+    pub selector: DwellSelector,
+    /// Time spent at each of them.
+    pub duration: Duration,
+}
+
+/// Visit durations for route candidates.
+///
+/// Rules are tried in the order they were added and the first that matches a
+/// POI sets its visit duration; POIs no rule matches get `default`. Put
+/// specific tag rules before broad theme rules. The default policy spends no
+/// time at any stop.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+/// use geo::Coord;
+/// use wildside_core::{DwellTimePolicy, PointOfInterest, TagFilter, Tags, Theme};
+///
+/// let policy = DwellTimePolicy::new(Duration::from_mins(5))
+///     .with_tag(TagFilter::key_value("tourism", "museum"), Duration::from_mins(60))
+///     .with_theme(Theme::Food, Duration::from_mins(30));
+///
+/// let museum = PointOfInterest::new(
+///     1,
+///     Coord { x: 0.0, y: 0.0 },
+///     Tags::from([("tourism".into(), "museum".into())]),
+/// );
+/// let bench = PointOfInterest::with_empty_tags(2, Coord { x: 0.0, y: 0.0 });
+/// assert_eq!(policy.dwell_time(&museum), Duration::from_mins(60));
+/// assert_eq!(policy.dwell_time(&bench), Duration::from_mins(5));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DwellTimePolicy {
+    /// Visit duration for POIs no rule matches.
+    pub default: Duration,
+    /// Overrides, tried in order.
+    pub rules: Vec<DwellTimeRule>,
+}
+
+impl DwellTimePolicy {
+    /// Spend `default` at every POI until rules are added.
+    #[must_use]
+    pub const fn new(default: Duration) -> Self {
+        Self {
+            default,
+            rules: Vec::new(),
+        }
+    }
+
+    /// Spend `duration` at POIs whose tags satisfy `filter`, while consuming
+    /// `self`.
+    #[must_use]
+    pub fn with_tag(self, filter: TagFilter, duration: Duration) -> Self {
+        self.with_rule(DwellSelector::Tag(filter), duration)
+    }
+
+    /// Spend `duration` at POIs belonging to `theme`, while consuming `self`.
+    #[must_use]
+    pub fn with_theme(self, theme: Theme, duration: Duration) -> Self {
+        self.with_rule(DwellSelector::Theme(theme), duration)
+    }
+
+    /// Append a rule for `selector`, while consuming `self`.
+    #[must_use]
+    pub fn with_rule(mut self, selector: DwellSelector, duration: Duration) -> Self {
+        self.rules.push(DwellTimeRule { selector, duration });
+        self
+    }
+
+    /// Time spent visiting `poi`.
+    #[must_use]
+    pub fn dwell_time(&self, poi: &PointOfInterest) -> Duration {
+        self.rules
+            .iter()
+            .find(|rule| rule.selector.matches(poi))
+            .map_or(self.default, |rule| rule.duration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for visit-duration rules.
+
+    use super::*;
+    use crate::Tags;
+    use geo::Coord;
+    use rstest::rstest;
+
+    fn tagged(key: &str, value: &str) -> PointOfInterest {
+        PointOfInterest::new(
+            1,
+            Coord { x: 0.0, y: 0.0 },
+            Tags::from([(key.to_owned(), value.to_owned())]),
+        )
+    }
+
+    #[rstest]
+    fn default_policy_spends_no_time() {
+        assert_eq!(
+            DwellTimePolicy::default().dwell_time(&tagged("tourism", "museum")),
+            Duration::ZERO
+        );
+    }
+
+    #[rstest]
+    #[case(tagged("tourism", "museum"), Duration::from_mins(45))]
+    #[case(tagged("tourism", "viewpoint"), Duration::from_mins(5))]
+    #[case(tagged("historic", "memorial"), Duration::from_mins(10))]
+    #[case(tagged("amenity", "bench"), Duration::from_mins(2))]
+    fn first_matching_rule_wins(#[case] poi: PointOfInterest, #[case] expected: Duration) {
+        let policy = DwellTimePolicy::new(Duration::from_mins(2))
+            .with_tag(
+                TagFilter::key_value("tourism", "museum"),
+                Duration::from_mins(45),
+            )
+            .with_theme(Theme::Nature, Duration::from_mins(5))
+            .with_tag(TagFilter::key("historic"), Duration::from_mins(10))
+            .with_theme(Theme::Culture, Duration::from_mins(90));
+
+        assert_eq!(policy.dwell_time(&poi), expected);
+    }
+}
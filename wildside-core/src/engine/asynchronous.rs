@@ -0,0 +1,440 @@
+//! Solving with travel times awaited through an async provider.
+
+use std::sync::Arc;
+
+use super::executor::{BlockingExecutor, ThreadExecutor};
+use crate::travel_time::{
+    AsyncTravelTimeProvider, DEFAULT_WALKING_SPEED_KMH, PrefetchedTravelTimeProvider,
+};
+use crate::{PoiStore, PointOfInterest, Scorer, SolveError, SolveRequest, SolveResponse, Solver};
+
+/// Solves whose travel times come from an [`AsyncTravelTimeProvider`].
+///
+/// Solvers ask for travel times synchronously, so a network provider would
+/// otherwise have to block its caller's runtime. For each request the async
+/// engine instead selects the candidates within walking reach, as
+/// [`SolveRequest::walking_candidates`] does, awaits one travel-time matrix
+/// over them and the endpoints, and runs a solver built over a
+/// [`PrefetchedTravelTimeProvider`] holding that matrix. The solver must draw
+/// its candidates from the same store, scorer, and walking speed: any matrix
+/// it asks for beyond the fetched locations fails with
+/// [`TravelTimeError::NotPrefetched`](crate::TravelTimeError::NotPrefetched).
+///
+/// Candidate selection and the search each run once, on the engine's
+/// [`BlockingExecutor`], so the task awaiting a solve is never blocked by
+/// it. Responses carry no route geometry, and step-free requests fail with
+/// [`TravelTimeError::StepFreeUnavailable`](crate::TravelTimeError::StepFreeUnavailable),
+/// as the prefetched provider offers neither.
+///
+/// # Examples
+/// ```rust
+/// use std::sync::Arc;
+///
+/// use geo::{Coord, Rect};
+/// use wildside_core::travel_time::{
+///     HaversineTravelTimeProvider, PrefetchedTravelTimeProvider, SyncTravelTimeAdapter,
+/// };
+/// use wildside_core::{
+///     AsyncEngine, Diagnostics, InterestProfile, PoiIter, PoiStore, PoiStoreError,
+///     PointOfInterest, Route, Scorer, SolveError, SolveRequest, SolveResponse, Solver,
+///     TravelTimeProvider,
+/// };
+///
+/// /// A single landmark north of the origin.
+/// struct Landmark;
+///
+/// impl PoiStore for Landmark {
+///     fn get_pois_in_bbox(&self, _bbox: &Rect<f64>) -> Result<PoiIter<'_>, PoiStoreError> {
+///         let landmark = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.01 });
+///         Ok(Box::new(std::iter::once(landmark)))
+///     }
+/// }
+///
+/// struct Flat;
+///
+/// impl Scorer for Flat {
+///     fn score(&self, _poi: &PointOfInterest, _profile: &InterestProfile) -> f32 {
+///         1.0
+///     }
+/// }
+///
+/// /// Walks to every candidate in turn and back.
+/// struct Tour {
+///     store: Arc<Landmark>,
+///     scorer: Arc<Flat>,
+///     travel_times: PrefetchedTravelTimeProvider,
+/// }
+///
+/// impl Solver for Tour {
+///     fn solve(&self, request: &SolveRequest) -> Result<SolveResponse, SolveError> {
+///         let candidates: Vec<_> = request
+///             .walking_candidates(&*self.store, &*self.scorer, 5.0)?
+///             .into_iter()
+///             .map(|(poi, _)| poi)
+///             .collect();
+///         let stops = request.matrix_locations(&candidates);
+///         let matrix = self.travel_times.get_travel_time_matrix(&stops)?;
+///         let walk = (0..stops.len())
+///             .map(|from| matrix[from][(from + 1) % stops.len()])
+///             .sum();
+///         Ok(SolveResponse {
+///             route: Route::new(candidates, walk),
+///             score: 1.0,
+///             diagnostics: Diagnostics::default(),
+///             geometry: None,
+///         })
+///     }
+/// }
+///
+/// # async fn run() -> Result<(), SolveError> {
+/// let engine = AsyncEngine::new(
+///     Landmark,
+///     Flat,
+///     SyncTravelTimeAdapter::new(HaversineTravelTimeProvider::default()),
+///     |store, scorer, travel_times| Tour {
+///         store,
+///         scorer,
+///         travel_times,
+///     },
+/// );
+/// let request = SolveRequest {
+///     start: Coord { x: 0.0, y: 0.0 },
+///     duration_minutes: 60,
+///     ..Default::default()
+/// };
+/// let response = engine.solve(&request).await?;
+/// assert_eq!(response.route.pois().len(), 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct AsyncEngine<S, C, P, B, E = ThreadExecutor> {
+    store: Arc<S>,
+    scorer: Arc<C>,
+    provider: P,
+    build: B,
+    executor: E,
+    speed_kmh: f64,
+}
+
+impl<S, C, P, B> AsyncEngine<S, C, P, B> {
+    /// Select candidates from `store` scored by `scorer`, await their travel
+    /// times through `provider`, and solve with the solvers `build` makes
+    /// over the store, the scorer, and the fetched matrix.
+    ///
+    /// Blocking work runs on a [`ThreadExecutor`], and candidates are those
+    /// within reach at [`DEFAULT_WALKING_SPEED_KMH`].
+    pub fn new(store: S, scorer: C, provider: P, build: B) -> Self {
+        Self {
+            store: Arc::new(store),
+            scorer: Arc::new(scorer),
+            provider,
+            build,
+            executor: ThreadExecutor,
+            speed_kmh: DEFAULT_WALKING_SPEED_KMH,
+        }
+    }
+}
+
+impl<S, C, P, B, E> AsyncEngine<S, C, P, B, E> {
+    /// Run blocking work on `executor` instead.
+    pub fn with_executor<X>(self, executor: X) -> AsyncEngine<S, C, P, B, X> {
+        AsyncEngine {
+            store: self.store,
+            scorer: self.scorer,
+            provider: self.provider,
+            build: self.build,
+            executor,
+            speed_kmh: self.speed_kmh,
+        }
+    }
+
+    /// Select the candidates within reach at `speed_kmh`, matching the
+    /// speed the built solvers are configured with.
+    #[must_use]
+    pub const fn with_walking_speed(mut self, speed_kmh: f64) -> Self {
+        self.speed_kmh = speed_kmh;
+        self
+    }
+
+    /// Return the engine's travel-time provider.
+    pub const fn provider(&self) -> &P {
+        &self.provider
+    }
+}
+
+impl<S, C, P, B, E, V> AsyncEngine<S, C, P, B, E>
+where
+    S: PoiStore + Send + Sync + 'static,
+    C: Scorer + 'static,
+    P: AsyncTravelTimeProvider,
+    B: Fn(Arc<S>, Arc<C>, PrefetchedTravelTimeProvider) -> V,
+    E: BlockingExecutor,
+    V: Solver + Send + 'static,
+{
+    /// Solve `request`, awaiting the travel times between its candidates
+    /// before running the search.
+    ///
+    /// # Errors
+    /// Returns [`SolveError::InvalidRequest`] for an invalid request,
+    /// [`SolveError::Store`] when selecting candidates fails,
+    /// [`SolveError::TravelTime`] when the provider fails, and otherwise the
+    /// [`SolveError`] raised by the solver.
+    pub async fn solve(&self, request: &SolveRequest) -> Result<SolveResponse, SolveError> {
+        request.validate()?;
+        let locations = self
+            .executor
+            .spawn_blocking({
+                let store = Arc::clone(&self.store);
+                let scorer = Arc::clone(&self.scorer);
+                let request = request.clone();
+                let speed_kmh = self.speed_kmh;
+                move || candidate_locations(&*store, &*scorer, &request, speed_kmh)
+            })
+            .await?;
+        let travel_times = PrefetchedTravelTimeProvider::new();
+        travel_times.fetch(&self.provider, &locations).await?;
+        let solver = (self.build)(
+            Arc::clone(&self.store),
+            Arc::clone(&self.scorer),
+            travel_times,
+        );
+        let request = request.clone();
+        self.executor
+            .spawn_blocking(move || solver.solve(&request))
+            .await
+    }
+}
+
+/// The endpoints and candidates of `request`, laid out as its travel-time
+/// matrix.
+fn candidate_locations<S, C>(
+    store: &S,
+    scorer: &C,
+    request: &SolveRequest,
+    speed_kmh: f64,
+) -> Result<Vec<PointOfInterest>, SolveError>
+where
+    S: PoiStore + ?Sized,
+    C: Scorer + ?Sized,
+{
+    let candidates: Vec<PointOfInterest> = request
+        .walking_candidates(store, scorer, speed_kmh)?
+        .into_iter()
+        .map(|(poi, _)| poi)
+        .collect();
+    Ok(request.matrix_locations(&candidates))
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for solving through an async travel-time provider.
+
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread::{self, ThreadId};
+    use std::time::Duration;
+
+    use geo::Coord;
+    use rstest::rstest;
+
+    use super::super::executor::block_on;
+    use super::*;
+    use crate::test_support::{MemoryStore, TagScorer, UnitTravelTimeProvider};
+    use crate::travel_time::SyncTravelTimeAdapter;
+    use crate::{
+        Diagnostics, InterestProfile, Route, Tags, Theme, TravelTimeError, TravelTimeMatrix,
+        TravelTimeProvider,
+    };
+
+    /// Async provider counting its requests.
+    #[derive(Default)]
+    struct Counting(AtomicUsize);
+
+    impl AsyncTravelTimeProvider for Counting {
+        async fn get_travel_time_matrix_async(
+            &self,
+            pois: &[PointOfInterest],
+        ) -> Result<TravelTimeMatrix, TravelTimeError> {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            UnitTravelTimeProvider.get_travel_time_matrix(pois)
+        }
+    }
+
+    /// What the solvers built by an engine saw.
+    #[derive(Default)]
+    struct Witness {
+        solves: AtomicUsize,
+        threads: Mutex<Vec<ThreadId>>,
+    }
+
+    /// Solver walking its candidates in rank order, optionally asking for
+    /// one more location than the engine fetched.
+    struct Tour {
+        store: Arc<MemoryStore>,
+        scorer: Arc<TagScorer>,
+        travel_times: PrefetchedTravelTimeProvider,
+        witness: Arc<Witness>,
+        detour: Option<Coord<f64>>,
+    }
+
+    impl Solver for Tour {
+        fn solve(&self, request: &SolveRequest) -> Result<SolveResponse, SolveError> {
+            self.witness.solves.fetch_add(1, Ordering::Relaxed);
+            self.witness
+                .threads
+                .lock()
+                .expect("threads lock")
+                .push(thread::current().id());
+            let mut locations = candidate_locations(
+                &*self.store,
+                &*self.scorer,
+                request,
+                DEFAULT_WALKING_SPEED_KMH,
+            )?;
+            if let Some(detour) = self.detour {
+                locations.push(PointOfInterest::with_empty_tags(99, detour));
+            }
+            let matrix = self.travel_times.get_travel_time_matrix(&locations)?;
+            let walked = (1..locations.len())
+                .map(|stop| matrix[stop - 1][stop])
+                .sum();
+            Ok(SolveResponse {
+                route: Route::new(locations[1..].to_vec(), walked),
+                score: 0.0,
+                diagnostics: Diagnostics::default(),
+                geometry: None,
+            })
+        }
+    }
+
+    fn store() -> MemoryStore {
+        MemoryStore::with_pois((1..=3).map(|id| {
+            PointOfInterest::new(
+                id,
+                Coord {
+                    x: 0.0,
+                    y: 0.001 * id as f64,
+                },
+                Tags::from([(String::from("history"), String::new())]),
+            )
+        }))
+    }
+
+    fn request() -> SolveRequest {
+        SolveRequest {
+            start: Coord { x: 0.0, y: 0.0 },
+            duration_minutes: 30,
+            interests: InterestProfile::new().with_weight(Theme::History, 1.0),
+            ..Default::default()
+        }
+    }
+
+    /// Builds the [`Tour`] solvers of a test engine.
+    type BuildTour =
+        Box<dyn Fn(Arc<MemoryStore>, Arc<TagScorer>, PrefetchedTravelTimeProvider) -> Tour>;
+
+    fn engine<P: AsyncTravelTimeProvider>(
+        provider: P,
+        witness: &Arc<Witness>,
+        detour: Option<Coord<f64>>,
+    ) -> AsyncEngine<MemoryStore, TagScorer, P, BuildTour> {
+        let witness = Arc::clone(witness);
+        let build: BuildTour = Box::new(move |store, scorer, travel_times| Tour {
+            store,
+            scorer,
+            travel_times,
+            witness: Arc::clone(&witness),
+            detour,
+        });
+        AsyncEngine::new(store(), TagScorer, provider, build)
+    }
+
+    #[rstest]
+    fn candidate_matrix_is_fetched_once_and_searched_once() {
+        let witness = Arc::default();
+        let engine = engine(Counting::default(), &witness, None);
+
+        let response = block_on(engine.solve(&request())).expect("solve");
+
+        assert_eq!(response.route.pois().len(), 3);
+        assert_eq!(response.route.total_duration(), Duration::from_secs(3));
+        assert_eq!(engine.provider().0.load(Ordering::Relaxed), 1);
+        assert_eq!(witness.solves.load(Ordering::Relaxed), 1);
+    }
+
+    #[rstest]
+    fn searches_run_off_the_awaiting_thread() {
+        let witness = Arc::default();
+        let engine = engine(Counting::default(), &witness, None);
+
+        block_on(engine.solve(&request())).expect("solve");
+
+        let threads = witness.threads.lock().expect("threads lock");
+        assert!(!threads.contains(&thread::current().id()));
+    }
+
+    #[rstest]
+    fn matrices_beyond_the_candidates_are_not_fetched() {
+        let witness = Arc::default();
+        let engine = engine(
+            SyncTravelTimeAdapter::new(UnitTravelTimeProvider),
+            &witness,
+            Some(Coord { x: 1.0, y: 1.0 }),
+        );
+
+        let result = block_on(engine.solve(&request()));
+
+        assert!(matches!(
+            result,
+            Err(SolveError::TravelTime(
+                TravelTimeError::NotPrefetched { .. }
+            ))
+        ));
+        assert_eq!(witness.solves.load(Ordering::Relaxed), 1);
+    }
+
+    /// Async provider whose every request fails.
+    struct Offline;
+
+    impl AsyncTravelTimeProvider for Offline {
+        async fn get_travel_time_matrix_async(
+            &self,
+            _pois: &[PointOfInterest],
+        ) -> Result<TravelTimeMatrix, TravelTimeError> {
+            Err(TravelTimeError::NetworkError {
+                url: "http://routing.invalid".into(),
+                message: "offline".into(),
+            })
+        }
+    }
+
+    #[rstest]
+    fn provider_failures_surface_as_travel_time_errors() {
+        let witness = Arc::default();
+        let engine = engine(Offline, &witness, None);
+
+        let result = block_on(engine.solve(&request()));
+
+        assert!(matches!(
+            result,
+            Err(SolveError::TravelTime(TravelTimeError::NetworkError { .. }))
+        ));
+        assert_eq!(witness.solves.load(Ordering::Relaxed), 0);
+    }
+
+    #[rstest]
+    fn invalid_requests_are_rejected_before_fetching() {
+        let witness = Arc::default();
+        let engine = engine(Counting::default(), &witness, None);
+        let invalid = SolveRequest {
+            duration_minutes: 0,
+            ..request()
+        };
+
+        let result = block_on(engine.solve(&invalid));
+
+        assert!(matches!(result, Err(SolveError::InvalidRequest)));
+        assert_eq!(engine.provider().0.load(Ordering::Relaxed), 0);
+    }
+}
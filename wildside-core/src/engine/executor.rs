@@ -0,0 +1,154 @@
+//! Running blocking work without holding up an async executor.
+
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+/// Run blocking work away from the threads polling async tasks.
+///
+/// [`AsyncEngine`](super::AsyncEngine) hands candidate selection and the
+/// search itself to an executor, so a solve never occupies the task that
+/// awaits it. [`ThreadExecutor`] needs no runtime; services on Tokio can
+/// forward to `tokio::task::spawn_blocking` instead to share its thread pool.
+///
+/// # Examples
+/// ```rust
+/// use wildside_core::engine::{BlockingExecutor, ThreadExecutor};
+///
+/// # async fn run() {
+/// let sum = ThreadExecutor.spawn_blocking(|| (1..=10).sum::<u32>()).await;
+/// assert_eq!(sum, 55);
+/// # }
+/// ```
+pub trait BlockingExecutor {
+    /// Run `work` where it may block, resolving to its result.
+    ///
+    /// A panic in `work` is resumed on the task awaiting the result.
+    fn spawn_blocking<F, T>(&self, work: F) -> impl Future<Output = T> + Send
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static;
+}
+
+/// Run each piece of blocking work on a thread of its own.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ThreadExecutor;
+
+impl BlockingExecutor for ThreadExecutor {
+    fn spawn_blocking<F, T>(&self, work: F) -> impl Future<Output = T> + Send
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let handoff = Arc::new(Handoff::default());
+        let worker = Arc::clone(&handoff);
+        thread::spawn(move || {
+            let outcome = panic::catch_unwind(AssertUnwindSafe(work));
+            let mut state = worker.lock();
+            state.outcome = Some(outcome);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+        Joined(handoff)
+    }
+}
+
+/// Result of a worker thread and the task waiting for it.
+struct Handoff<T> {
+    state: Mutex<HandoffState<T>>,
+}
+
+struct HandoffState<T> {
+    outcome: Option<thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+impl<T> Default for Handoff<T> {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(HandoffState {
+                outcome: None,
+                waker: None,
+            }),
+        }
+    }
+}
+
+impl<T> Handoff<T> {
+    fn lock(&self) -> MutexGuard<'_, HandoffState<T>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Future resolving once a [`ThreadExecutor`] worker finishes.
+struct Joined<T>(Arc<Handoff<T>>);
+
+impl<T> Future for Joined<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.0.lock();
+        match state.outcome.take() {
+            Some(Ok(value)) => Poll::Ready(value),
+            Some(Err(payload)) => {
+                drop(state);
+                panic::resume_unwind(payload)
+            }
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Drive `future` to completion on the current thread, parking between
+/// polls.
+#[cfg(test)]
+pub(super) fn block_on<F: Future>(future: F) -> F::Output {
+    use std::task::Wake;
+
+    struct Unpark(thread::Thread);
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = std::pin::pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for running blocking work on worker threads.
+
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    fn work_runs_on_another_thread() {
+        let caller = thread::current().id();
+        let worker = block_on(ThreadExecutor.spawn_blocking(|| thread::current().id()));
+        assert_ne!(worker, caller);
+    }
+
+    #[rstest]
+    #[should_panic(expected = "search failed")]
+    fn panics_reach_the_awaiting_task() {
+        block_on(ThreadExecutor.spawn_blocking(|| panic!("search failed")));
+    }
+}
@@ -0,0 +1,268 @@
+//! Long-lived engines and their readiness.
+//!
+//! A process that serves many solves builds one [`Engine`] from its store,
+//! scorer, and solver, then calls [`Engine::warm_up`] before taking traffic.
+//! Warming up exercises each component the way a real request would: reading
+//! the POIs nearest a canary request's start pulls the spatial index and any
+//! lazily loaded tags into memory, scoring them prepares the scorer's
+//! database statements, and solving the canary runs the whole pipeline once.
+//! The resulting [`WarmUpReport`] backs a readiness probe, so orchestrators
+//! route traffic only to instances that are warm.
+//!
+//! With the `async` feature, [`AsyncEngine`] solves requests whose travel
+//! times are awaited through an
+//! [`AsyncTravelTimeProvider`](crate::AsyncTravelTimeProvider) instead.
+
+use std::fmt;
+use std::sync::{PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::{PoiStore, Scorer, SolveError, SolveRequest, SolveResponse, Solver};
+
+#[cfg(feature = "async")]
+mod asynchronous;
+#[cfg(feature = "async")]
+mod executor;
+
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub use asynchronous::AsyncEngine;
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub use executor::{BlockingExecutor, ThreadExecutor};
+
+/// Number of POIs near the canary start read while warming up.
+pub const CANARY_POIS: usize = 32;
+
+/// Component exercised by one warm-up check.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarmUpStage {
+    /// Reading the POIs nearest the canary start from the store.
+    SpatialIndex,
+    /// Scoring those POIs against the canary interests.
+    Scorer,
+    /// Solving the canary request.
+    CanarySolve,
+}
+
+/// Outcome of one warm-up check.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WarmUpCheck {
+    /// Component the check exercised.
+    pub stage: WarmUpStage,
+    /// Time the check took.
+    pub elapsed: Duration,
+    /// Why the check failed, or `None` when it passed.
+    pub error: Option<String>,
+}
+
+/// Readiness diagnostics recorded by [`Engine::warm_up`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WarmUpReport {
+    /// Checks in the order they ran.
+    pub checks: Vec<WarmUpCheck>,
+}
+
+impl WarmUpReport {
+    /// Report whether every check passed.
+    #[must_use]
+    pub fn is_ready(&self) -> bool {
+        self.checks.iter().all(|check| check.error.is_none())
+    }
+
+    /// Total time spent warming up.
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        self.checks.iter().map(|check| check.elapsed).sum()
+    }
+}
+
+/// A store, scorer, and solver serving solves together.
+///
+/// The solver usually shares the store and scorer with the engine through an
+/// [`Arc`](std::sync::Arc), which implements both [`PoiStore`] and [`Scorer`].
+///
+/// # Examples
+/// ```rust
+/// use geo::{Coord, Rect};
+/// use wildside_core::{
+///     Diagnostics, Engine, InterestProfile, PoiIter, PoiStore, PoiStoreError, PointOfInterest,
+///     Route, Scorer, SolveError, SolveRequest, SolveResponse, Solver,
+/// };
+///
+/// struct Empty;
+/// impl PoiStore for Empty {
+///     fn get_pois_in_bbox(&self, _bbox: &Rect<f64>) -> Result<PoiIter<'_>, PoiStoreError> {
+///         Ok(Box::new(std::iter::empty()))
+///     }
+/// }
+///
+/// struct Flat;
+/// impl Scorer for Flat {
+///     fn score(&self, _poi: &PointOfInterest, _profile: &InterestProfile) -> f32 {
+///         0.5
+///     }
+/// }
+///
+/// struct Stay;
+/// impl Solver for Stay {
+///     fn solve(&self, _request: &SolveRequest) -> Result<SolveResponse, SolveError> {
+///         Ok(SolveResponse {
+///             route: Route::empty(),
+///             score: 0.0,
+///             diagnostics: Diagnostics::default(),
+///             geometry: None,
+///         })
+///     }
+/// }
+///
+/// let canary = SolveRequest {
+///     start: Coord { x: 0.0, y: 0.0 },
+///     duration_minutes: 10,
+///     ..Default::default()
+/// };
+/// let engine = Engine::new(Empty, Flat, Stay, canary);
+/// assert!(!engine.is_ready());
+/// assert!(engine.warm_up().is_ready());
+/// assert!(engine.is_ready());
+/// ```
+#[derive(Debug)]
+pub struct Engine<S, C, V> {
+    store: S,
+    scorer: C,
+    solver: V,
+    canary: SolveRequest,
+    readiness: RwLock<Option<WarmUpReport>>,
+}
+
+impl<S, C, V> Engine<S, C, V>
+where
+    S: PoiStore,
+    C: Scorer,
+    V: Solver,
+{
+    /// Combine `store`, `scorer`, and `solver`, warming up with `canary`.
+    ///
+    /// `canary` should be a short request inside the area the store covers,
+    /// so that warming up reads real POIs without waiting on a long search.
+    pub const fn new(store: S, scorer: C, solver: V, canary: SolveRequest) -> Self {
+        Self {
+            store,
+            scorer,
+            solver,
+            canary,
+            readiness: RwLock::new(None),
+        }
+    }
+
+    /// Return the engine's POI store.
+    pub const fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Return the engine's scorer.
+    pub const fn scorer(&self) -> &C {
+        &self.scorer
+    }
+
+    /// Return the engine's solver.
+    pub const fn solver(&self) -> &V {
+        &self.solver
+    }
+
+    /// Return the request solved while warming up.
+    pub const fn canary(&self) -> &SolveRequest {
+        &self.canary
+    }
+
+    /// Solve `request` with the engine's solver.
+    ///
+    /// # Errors
+    /// Returns the [`SolveError`] raised by the solver.
+    pub fn solve(&self, request: &SolveRequest) -> Result<SolveResponse, SolveError> {
+        self.solver.solve(request)
+    }
+
+    /// Exercise the store, scorer, and solver with the canary request and
+    /// record how each fared.
+    ///
+    /// Every check runs even when an earlier one fails, so the report names
+    /// each broken component. A canary solve ending in
+    /// [`SolveError::Infeasible`] passes, because the solver ran end to end
+    /// and only the canary's targets went unmet. The report is kept for
+    /// [`Self::readiness`]; warming up again replaces it, so an instance whose
+    /// dependencies recover becomes ready.
+    pub fn warm_up(&self) -> WarmUpReport {
+        let (index, nearby) = check(WarmUpStage::SpatialIndex, || {
+            self.store
+                .get_nearest_pois(self.canary.start, CANARY_POIS)
+                .map(Iterator::collect::<Vec<_>>)
+        });
+        let (scorer, _) = check(WarmUpStage::Scorer, || {
+            let nearby = nearby.unwrap_or_default();
+            Ok::<_, SolveError>(self.scorer.score_many(&nearby, &self.canary.interests))
+        });
+        let (solve, _) = check(WarmUpStage::CanarySolve, || {
+            match self.solver.solve(&self.canary) {
+                Err(SolveError::Infeasible(_)) => Ok(()),
+                outcome => outcome.map(drop),
+            }
+        });
+        let report = WarmUpReport {
+            checks: vec![index, scorer, solve],
+        };
+        *self
+            .readiness
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = Some(report.clone());
+        report
+    }
+
+    /// Return the report of the latest warm-up, or `None` before the first.
+    #[must_use]
+    pub fn readiness(&self) -> Option<WarmUpReport> {
+        self.readiness
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Report whether the latest warm-up passed every check.
+    #[must_use]
+    pub fn is_ready(&self) -> bool {
+        self.readiness
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+            .is_some_and(WarmUpReport::is_ready)
+    }
+}
+
+/// Time `run` and record its outcome as a check for `stage`.
+fn check<T, E: fmt::Display>(
+    stage: WarmUpStage,
+    run: impl FnOnce() -> Result<T, E>,
+) -> (WarmUpCheck, Option<T>) {
+    let started = Instant::now();
+    let outcome = run();
+    let elapsed = started.elapsed();
+    let (value, error) = match outcome {
+        Ok(value) => (Some(value), None),
+        Err(error) => (None, Some(error.to_string())),
+    };
+    (
+        WarmUpCheck {
+            stage,
+            elapsed,
+            error,
+        },
+        value,
+    )
+}
+
+#[cfg(test)]
+mod tests;
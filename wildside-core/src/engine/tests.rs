@@ -0,0 +1,160 @@
+//! Tests for engine warm-up and readiness.
+
+use super::*;
+use crate::test_support::{MemoryStore, TagScorer};
+use crate::{
+    Diagnostics, InterestProfile, PoiIter, PoiStoreError, PointOfInterest, Route, Shortfall, Tags,
+    Theme,
+};
+use geo::{Coord, Rect};
+use rstest::{fixture, rstest};
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Store whose every query fails.
+struct BrokenStore;
+
+impl PoiStore for BrokenStore {
+    fn get_pois_in_bbox(&self, _bbox: &Rect<f64>) -> Result<PoiIter<'_>, PoiStoreError> {
+        Err(PoiStoreError::backend(io::Error::other("disk gone")))
+    }
+}
+
+/// Solver returning a fixed outcome and counting its calls.
+#[derive(Default)]
+struct CannedSolver {
+    outcome: Option<SolveError>,
+    calls: AtomicUsize,
+}
+
+impl Solver for CannedSolver {
+    fn solve(&self, _request: &SolveRequest) -> Result<SolveResponse, SolveError> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        match &self.outcome {
+            None => Ok(SolveResponse {
+                route: Route::empty(),
+                score: 0.0,
+                diagnostics: Diagnostics::default(),
+                geometry: None,
+            }),
+            Some(SolveError::Infeasible(shortfall)) => {
+                Err(SolveError::Infeasible(shortfall.clone()))
+            }
+            Some(_) => Err(SolveError::Timeout),
+        }
+    }
+}
+
+#[fixture]
+fn canary() -> SolveRequest {
+    SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes: 10,
+        interests: InterestProfile::new().with_weight(Theme::History, 1.0),
+        ..Default::default()
+    }
+}
+
+fn store() -> MemoryStore {
+    MemoryStore::with_poi(PointOfInterest::new(
+        1,
+        Coord { x: 0.001, y: 0.0 },
+        Tags::from([(String::from("history"), String::new())]),
+    ))
+}
+
+fn stages(report: &WarmUpReport) -> Vec<WarmUpStage> {
+    report.checks.iter().map(|check| check.stage).collect()
+}
+
+#[rstest]
+fn warm_engines_are_ready(canary: SolveRequest) {
+    let engine = Engine::new(store(), TagScorer, CannedSolver::default(), canary);
+    assert!(engine.readiness().is_none());
+    assert!(!engine.is_ready());
+
+    let report = engine.warm_up();
+
+    assert!(report.is_ready());
+    assert_eq!(
+        stages(&report),
+        [
+            WarmUpStage::SpatialIndex,
+            WarmUpStage::Scorer,
+            WarmUpStage::CanarySolve
+        ]
+    );
+    assert_eq!(engine.readiness(), Some(report));
+    assert!(engine.is_ready());
+    assert_eq!(engine.solver().calls.load(Ordering::Relaxed), 1);
+}
+
+#[rstest]
+fn failing_stores_are_reported_and_later_checks_still_run(canary: SolveRequest) {
+    let engine = Engine::new(BrokenStore, TagScorer, CannedSolver::default(), canary);
+
+    let report = engine.warm_up();
+
+    assert!(!report.is_ready());
+    let errors: Vec<_> = report
+        .checks
+        .iter()
+        .map(|check| check.error.as_deref())
+        .collect();
+    assert_eq!(errors.len(), 3);
+    assert!(
+        errors
+            .first()
+            .copied()
+            .flatten()
+            .is_some_and(|error| error.contains("disk gone"))
+    );
+    assert_eq!(errors.get(1..), Some(&[None, None][..]));
+    assert_eq!(engine.solver().calls.load(Ordering::Relaxed), 1);
+    assert!(!engine.is_ready());
+}
+
+#[rstest]
+fn infeasible_canaries_still_count_as_warm(canary: SolveRequest) {
+    let solver = CannedSolver {
+        outcome: Some(SolveError::Infeasible(Shortfall::default())),
+        ..CannedSolver::default()
+    };
+    let engine = Engine::new(store(), TagScorer, solver, canary);
+
+    assert!(engine.warm_up().is_ready());
+}
+
+#[rstest]
+fn failed_canary_solves_leave_the_engine_unready(canary: SolveRequest) {
+    let solver = CannedSolver {
+        outcome: Some(SolveError::Timeout),
+        ..CannedSolver::default()
+    };
+    let engine = Engine::new(store(), TagScorer, solver, canary);
+
+    let report = engine.warm_up();
+
+    assert!(!report.is_ready());
+    let failed = report.checks.last().expect("canary check");
+    assert_eq!(failed.stage, WarmUpStage::CanarySolve);
+    assert_eq!(
+        failed.error.as_deref(),
+        Some(SolveError::Timeout.to_string().as_str())
+    );
+    assert!(!engine.is_ready());
+}
+
+#[rstest]
+fn warming_up_again_replaces_the_report(canary: SolveRequest) {
+    let engine = Engine::new(store(), TagScorer, CannedSolver::default(), canary);
+
+    engine.warm_up();
+    engine.warm_up();
+
+    assert_eq!(engine.solver().calls.load(Ordering::Relaxed), 2);
+    assert_eq!(
+        engine.readiness().map(|report| report.checks.len()),
+        Some(3)
+    );
+}
@@ -0,0 +1,324 @@
+//! Versioned artefact formats and their compatibility matrix.
+//!
+//! Every artefact the engine persists carries a format version. This module is
+//! the single place those versions are declared: writers stamp
+//! [`ArtefactFormat::current`], and readers pass the version they find to
+//! [`ArtefactFormat::negotiate`], which either accepts it or reports a
+//! [`FormatVersionError`]. Bumping a format therefore means editing its entry
+//! here, and the compile-time checks below reject an inconsistent matrix.
+//!
+//! # Examples
+//! ```
+//! use wildside_core::formats::{Negotiated, SPATIAL_INDEX};
+//!
+//! let current = i64::from(SPATIAL_INDEX.current);
+//! assert_eq!(SPATIAL_INDEX.negotiate(current), Ok(Negotiated::Current));
+//! assert!(SPATIAL_INDEX.negotiate(current + 1).is_err());
+//! ```
+
+use std::fmt;
+
+use thiserror::Error;
+
+/// Artefacts whose on-disk format is versioned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum ArtefactKind {
+    /// The `pois` table in `pois.db`, versioned through `PRAGMA user_version`.
+    PoisSchema,
+    /// The persisted R\*-tree in `pois.rstar`.
+    SpatialIndex,
+    /// The Bloom filter of POIs with Wikidata links.
+    LinkFilter,
+    /// Global popularity scores in `popularity.bin`.
+    Popularity,
+    /// The Wikidata claims tables stored alongside the POIs.
+    ClaimsSchema,
+    /// The pedestrian routing graph in `graph.bin`.
+    RoutingGraph,
+    /// Precomputed travel times between POIs in `travel_times.bin`.
+    TravelTimes,
+    /// The artefact set produced together by one ingest run.
+    Bundle,
+}
+
+impl ArtefactKind {
+    /// Human-readable name used in diagnostics.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::PoisSchema => "POI schema",
+            Self::SpatialIndex => "spatial index",
+            Self::LinkFilter => "link filter",
+            Self::Popularity => "popularity",
+            Self::ClaimsSchema => "Wikidata claims schema",
+            Self::RoutingGraph => "routing graph",
+            Self::TravelTimes => "travel-time table",
+            Self::Bundle => "artefact bundle",
+        }
+    }
+}
+
+impl fmt::Display for ArtefactKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Versions of one artefact format understood by this build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArtefactFormat {
+    /// Artefact the versions apply to.
+    pub kind: ArtefactKind,
+    /// Version stamped by writers.
+    pub current: u16,
+    /// Oldest version readers still accept.
+    pub oldest_readable: u16,
+}
+
+/// Outcome of a successful version negotiation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Negotiated {
+    /// The artefact is at the current version.
+    Current,
+    /// The artefact predates the current version but remains readable.
+    Legacy {
+        /// Version found in the artefact.
+        found: u16,
+    },
+}
+
+/// Error raised when an artefact's format version is outside the supported
+/// range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error(
+    "unsupported {kind} version {found}; this build reads versions {oldest_readable} to {current}"
+)]
+pub struct FormatVersionError {
+    /// Artefact whose version was rejected.
+    pub kind: ArtefactKind,
+    /// Version recorded in the artefact.
+    pub found: i64,
+    /// Oldest version this build reads.
+    pub oldest_readable: u16,
+    /// Version this build writes.
+    pub current: u16,
+}
+
+impl ArtefactFormat {
+    /// Report whether `version` falls within the readable range.
+    #[must_use]
+    pub const fn reads(&self, version: i64) -> bool {
+        version >= self.oldest_readable as i64 && version <= self.current as i64
+    }
+
+    /// Accept or reject a version read from an artefact.
+    ///
+    /// Versions are taken as `i64` so values stored in SQLite integer columns
+    /// and pragmas can be checked without a lossy conversion; header readers
+    /// widen their `u16` with [`i64::from`].
+    ///
+    /// # Errors
+    /// Returns [`FormatVersionError`] when `found` is older than
+    /// [`Self::oldest_readable`] or newer than [`Self::current`].
+    pub fn negotiate(&self, found: i64) -> Result<Negotiated, FormatVersionError> {
+        if !self.reads(found) {
+            return Err(FormatVersionError {
+                kind: self.kind,
+                found,
+                oldest_readable: self.oldest_readable,
+                current: self.current,
+            });
+        }
+        // Readable versions lie within `u16`, so the conversion only fails
+        // for values already rejected above.
+        Ok(match u16::try_from(found) {
+            Ok(version) if version != self.current => Negotiated::Legacy { found: version },
+            _ => Negotiated::Current,
+        })
+    }
+}
+
+/// `pois` table layout. Version 0 marks databases written before the schema
+/// was versioned; their layout is identical to version 1. Version 2 adds the
+/// `poi_names` full-text table; older databases stay readable but cannot be
+/// searched by name until a writer upgrades them.
+pub const POIS_SCHEMA: ArtefactFormat = ArtefactFormat {
+    kind: ArtefactKind::PoisSchema,
+    current: 2,
+    oldest_readable: 0,
+};
+
+/// Persisted R\*-tree. Version 1 stored bare coordinates and is no longer read.
+pub const SPATIAL_INDEX: ArtefactFormat = ArtefactFormat {
+    kind: ArtefactKind::SpatialIndex,
+    current: 3,
+    oldest_readable: 2,
+};
+
+/// Wikidata link membership filter.
+pub const LINK_FILTER: ArtefactFormat = ArtefactFormat {
+    kind: ArtefactKind::LinkFilter,
+    current: 1,
+    oldest_readable: 1,
+};
+
+/// Popularity scores. Version 1 files carry no header and are still read;
+/// version 3 headers record how the scores were normalized, version 4
+/// headers when and from which `pois.db` they were computed, and version 5
+/// headers whether they were normalized within POI categories.
+pub const POPULARITY: ArtefactFormat = ArtefactFormat {
+    kind: ArtefactKind::Popularity,
+    current: 5,
+    oldest_readable: 1,
+};
+
+/// Wikidata claims schema. Every migration so far is additive, so any older
+/// schema is upgraded in place.
+pub const CLAIMS_SCHEMA: ArtefactFormat = ArtefactFormat {
+    kind: ArtefactKind::ClaimsSchema,
+    current: 6,
+    oldest_readable: 1,
+};
+
+/// Pedestrian routing graph used for offline travel times.
+pub const ROUTING_GRAPH: ArtefactFormat = ArtefactFormat {
+    kind: ArtefactKind::RoutingGraph,
+    current: 1,
+    oldest_readable: 1,
+};
+
+/// Travel times precomputed between the POIs of a region.
+pub const TRAVEL_TIMES: ArtefactFormat = ArtefactFormat {
+    kind: ArtefactKind::TravelTimes,
+    current: 1,
+    oldest_readable: 1,
+};
+
+/// Artefact set written by one ingest run, recorded in its `manifest.json`
+/// and in the header of `.wildside` bundles. The version is bumped whenever a component's
+/// [`ArtefactFormat::oldest_readable`] advances, so equal bundle versions
+/// identify mutually readable artefact sets.
+pub const BUNDLE: ArtefactFormat = ArtefactFormat {
+    kind: ArtefactKind::Bundle,
+    current: 1,
+    oldest_readable: 1,
+};
+
+/// Every versioned artefact format, in declaration order.
+pub const COMPATIBILITY_MATRIX: [ArtefactFormat; 8] = [
+    POIS_SCHEMA,
+    SPATIAL_INDEX,
+    LINK_FILTER,
+    POPULARITY,
+    CLAIMS_SCHEMA,
+    ROUTING_GRAPH,
+    TRAVEL_TIMES,
+    BUNDLE,
+];
+
+const _: () = check_matrix(&COMPATIBILITY_MATRIX);
+
+/// Reject matrices with inverted ranges, unstamped formats, or duplicate
+/// entries at compile time.
+const fn check_matrix(matrix: &[ArtefactFormat]) {
+    let mut i = 0;
+    while i < matrix.len() {
+        let format = &matrix[i];
+        assert!(format.current > 0, "formats must be stamped with a version");
+        assert!(
+            format.oldest_readable <= format.current,
+            "oldest readable version must not exceed the current version"
+        );
+        let mut j = i + 1;
+        while j < matrix.len() {
+            assert!(
+                format.kind as u8 != matrix[j].kind as u8,
+                "each artefact kind must appear once"
+            );
+            j += 1;
+        }
+        i += 1;
+    }
+}
+
+/// Look up the format declared for `kind`.
+#[must_use]
+pub const fn format_of(kind: ArtefactKind) -> ArtefactFormat {
+    match kind {
+        ArtefactKind::PoisSchema => POIS_SCHEMA,
+        ArtefactKind::SpatialIndex => SPATIAL_INDEX,
+        ArtefactKind::LinkFilter => LINK_FILTER,
+        ArtefactKind::Popularity => POPULARITY,
+        ArtefactKind::ClaimsSchema => CLAIMS_SCHEMA,
+        ArtefactKind::RoutingGraph => ROUTING_GRAPH,
+        ArtefactKind::TravelTimes => TRAVEL_TIMES,
+        ArtefactKind::Bundle => BUNDLE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for version negotiation and the compatibility matrix.
+
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    fn matrix_lists_every_kind() {
+        for format in COMPATIBILITY_MATRIX {
+            assert_eq!(format_of(format.kind), format);
+        }
+    }
+
+    #[rstest]
+    fn current_version_negotiates_as_current() {
+        for format in COMPATIBILITY_MATRIX {
+            assert_eq!(
+                format.negotiate(i64::from(format.current)),
+                Ok(Negotiated::Current)
+            );
+        }
+    }
+
+    #[rstest]
+    fn older_readable_version_negotiates_as_legacy() {
+        assert_eq!(POPULARITY.negotiate(1), Ok(Negotiated::Legacy { found: 1 }));
+        assert_eq!(
+            SPATIAL_INDEX.negotiate(2),
+            Ok(Negotiated::Legacy { found: 2 })
+        );
+    }
+
+    #[rstest]
+    #[case(SPATIAL_INDEX, 1)]
+    #[case(SPATIAL_INDEX, 4)]
+    #[case(CLAIMS_SCHEMA, 0)]
+    #[case(CLAIMS_SCHEMA, 7)]
+    #[case(LINK_FILTER, -1)]
+    #[case(POIS_SCHEMA, i64::from(u16::MAX) + 1)]
+    fn out_of_range_versions_are_rejected(#[case] format: ArtefactFormat, #[case] found: i64) {
+        let error = format
+            .negotiate(found)
+            .expect_err("version should be rejected");
+        assert_eq!(error.kind, format.kind);
+        assert_eq!(error.found, found);
+        assert_eq!(error.current, format.current);
+        assert_eq!(error.oldest_readable, format.oldest_readable);
+    }
+
+    #[rstest]
+    fn error_names_the_artefact_and_range() {
+        let error = SPATIAL_INDEX
+            .negotiate(7)
+            .expect_err("version 7 is unknown");
+        assert_eq!(
+            error.to_string(),
+            "unsupported spatial index version 7; this build reads versions 2 to 3"
+        );
+    }
+}
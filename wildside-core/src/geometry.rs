@@ -0,0 +1,79 @@
+//! Spherical geometry shared by store queries and solver candidate regions.
+//!
+//! Distances are great-circle metres on a spherical Earth.
+
+use std::f64::consts::FRAC_PI_2;
+
+use geo::{Coord, Distance, Haversine, Point, Rect};
+
+/// Great-circle distance in metres between two WGS84 coordinates.
+#[must_use]
+pub fn great_circle_m(from: Coord<f64>, to: Coord<f64>) -> f64 {
+    Haversine.distance(Point::from(from), Point::from(to))
+}
+
+/// Bounding rectangle of a spherical cap of `radius_m` around `centre`.
+///
+/// Caps reaching a pole span every longitude. Longitudes are clamped to
+/// ±180° rather than wrapped across the antimeridian.
+#[must_use]
+pub fn cap_bounds(centre: Coord<f64>, radius_m: f64) -> Rect<f64> {
+    let angular = radius_m.max(0.0) / Haversine.radius();
+    let latitude = centre.y.to_radians();
+    let south = latitude - angular;
+    let north = latitude + angular;
+    let (west, east) = if south <= -FRAC_PI_2 || north >= FRAC_PI_2 {
+        (-180.0, 180.0)
+    } else {
+        let span = (angular.sin() / latitude.cos()).asin().to_degrees();
+        ((centre.x - span).max(-180.0), (centre.x + span).min(180.0))
+    };
+    Rect::new(
+        Coord {
+            x: west,
+            y: south.to_degrees().max(-90.0),
+        },
+        Coord {
+            x: east,
+            y: north.to_degrees().min(90.0),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for spherical cap bounds.
+
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0.0)]
+    #[case(55.95)]
+    #[case(-33.9)]
+    fn cap_bounds_enclose_the_circle(#[case] latitude: f64) {
+        let centre = Coord {
+            x: 10.0,
+            y: latitude,
+        };
+        let bounds = cap_bounds(centre, 1_000.0);
+        let east = Coord {
+            x: bounds.max().x,
+            y: latitude,
+        };
+        let north = Coord {
+            x: 10.0,
+            y: bounds.max().y,
+        };
+        assert!(great_circle_m(centre, east) >= 999.0);
+        assert!(great_circle_m(centre, north) >= 999.0);
+    }
+
+    #[rstest]
+    fn cap_bounds_span_all_longitudes_at_the_pole() {
+        let bounds = cap_bounds(Coord { x: 0.0, y: 89.99 }, 5_000.0);
+        assert_eq!(bounds.min().x, -180.0);
+        assert_eq!(bounds.max().x, 180.0);
+        assert_eq!(bounds.max().y, 90.0);
+    }
+}
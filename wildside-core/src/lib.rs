@@ -2,27 +2,65 @@
 
 //! Core domain types for the Wildside engine.
 
+pub mod accessibility;
+#[cfg(any(feature = "store-sqlite", feature = "routing-graph"))]
+mod artefact_io;
+pub mod cancel;
+pub mod diversity;
+pub mod dwell;
+pub mod engine;
+pub mod formats;
+pub mod geometry;
+pub mod opening_hours;
+pub mod overlap;
 pub mod poi;
+pub mod priors;
 pub mod profile;
+pub mod reach;
+pub mod rest_break;
 pub mod route;
 pub mod scorer;
+pub mod solve_handle;
 pub mod solver;
 pub mod store;
+#[cfg(feature = "telemetry")]
+#[cfg_attr(docsrs, doc(cfg(feature = "telemetry")))]
+pub mod telemetry;
 pub mod theme;
+pub mod timestamp;
 pub mod travel_time;
 
+pub use accessibility::{AccessibilityRequirements, WheelchairAccess};
+pub use cancel::CancellationToken;
+pub use diversity::DiversityPolicy;
+pub use dwell::{DwellSelector, DwellTimePolicy, DwellTimeRule};
+#[cfg(feature = "async")]
+pub use engine::AsyncEngine;
+pub use engine::{Engine, WarmUpCheck, WarmUpReport, WarmUpStage};
+pub use formats::{ArtefactFormat, ArtefactKind, FormatVersionError, Negotiated};
+pub use opening_hours::{OpeningHours, OpeningHoursError, WeekTime, Weekday};
+pub use overlap::OverlapPolicy;
 pub use poi::{PointOfInterest, SpatialIndex, Tags, build_spatial_index};
+pub use priors::{InterestPriors, PriorsError, RegionPrior};
 pub use profile::InterestProfile;
-pub use route::Route;
-pub use scorer::Scorer;
+pub use reach::WalkingReach;
+pub use rest_break::{REST_BREAK_AMENITIES, RestBreak};
+pub use route::{Route, RouteLeg, RouteStop};
+pub use scorer::{CompositeScorer, CompositeWeightError, Scorer};
+pub use solve_handle::SolveHandle;
 pub use solver::{
-    Diagnostics, SolveError, SolveRequest, SolveRequestValidationError, SolveResponse, Solver,
+    Diagnostics, END_POI_ID, ProgressObserver, START_POI_ID, Shortfall, SolveError, SolveProgress,
+    SolveRequest, SolveRequestValidationError, SolveResponse, Solver, StartChoice, VisitedPrefix,
+    pareto_frontier,
 };
-pub use store::PoiStore;
+pub use store::{PoiIter, PoiQuery, PoiStore, PoiStoreError, PoiWriter, TagFilter};
 #[cfg(feature = "store-sqlite")]
-pub use store::{SqlitePoiStore, SqlitePoiStoreError};
+pub use store::{SqlitePoiStore, SqlitePoiStoreError, SqlitePoiWriter, SqlitePoiWriterError};
 pub use theme::Theme;
-pub use travel_time::{TravelTimeError, TravelTimeMatrix, TravelTimeProvider};
+pub use timestamp::{Timestamp, TimestampError};
+#[cfg(feature = "async")]
+pub use travel_time::{AsyncTravelTimeProvider, SyncTravelTimeAdapter};
+pub use travel_time::{MatrixShapeError, TravelTimeError, TravelTimeMatrix, TravelTimeProvider};
 
 #[cfg(any(test, feature = "test-support"))]
 #[cfg_attr(all(not(test), docsrs), doc(cfg(feature = "test-support")))]
@@ -0,0 +1,220 @@
+//! Weekly opening hours parsed from OpenStreetMap `opening_hours` tags.
+//!
+//! The full `opening_hours` grammar covers holidays, months, week numbers,
+//! sunrise offsets, and free-text comments. This module understands the
+//! weekly subset that covers most tagged POIs:
+//!
+//! - `24/7`;
+//! - rules separated by `;`, where later rules override earlier ones for the
+//!   days they name;
+//! - weekday lists and ranges such as `Mo-Fr`, `Sa,Su`, or `Fr-Mo`;
+//! - comma-separated time spans such as `09:00-12:30,13:30-17:00`, including
+//!   spans past midnight such as `18:00-02:00`;
+//! - `off` and `closed` to mark days as closed, and a redundant `open`.
+//!
+//! Rules for public or school holidays (`PH`, `SH`) are skipped because the
+//! calendar is unknown. Anything else fails with
+//! [`OpeningHoursError::Unsupported`], and callers should then treat the POI
+//! as unconstrained rather than closed.
+//!
+//! # Examples
+//! ```rust
+//! use std::time::Duration;
+//! use wildside_core::opening_hours::{OpeningHours, WeekTime, Weekday};
+//!
+//! let hours = OpeningHours::parse("Mo-Fr 09:00-17:00; Sa 10:00-14:00")?;
+//! let saturday_morning = WeekTime::new(Weekday::Saturday, 9, 0)?;
+//! let windows = hours.windows_from(saturday_morning, Duration::from_hours(8));
+//! assert_eq!(
+//!     windows,
+//!     [Duration::from_hours(1)..Duration::from_hours(5)]
+//! );
+//! # Ok::<(), wildside_core::opening_hours::OpeningHoursError>(())
+//! ```
+
+use std::ops::Range;
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::Tags;
+
+mod parse;
+mod week;
+
+use parse::parse_rule;
+pub use week::{WeekTime, Weekday};
+
+/// Tag key holding a POI's opening hours.
+pub const OPENING_HOURS_TAG: &str = "opening_hours";
+
+pub(crate) const MINUTES_PER_DAY: u16 = 24 * 60;
+const MINUTES_PER_WEEK: u64 = 7 * 24 * 60;
+
+/// Errors raised while parsing opening hours or a time of the week.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum OpeningHoursError {
+    /// The value held no rules.
+    #[error("opening hours are empty")]
+    Empty,
+    /// A rule uses syntax outside the supported subset.
+    #[error("unsupported opening hours rule {rule:?}")]
+    Unsupported {
+        /// The rule as written.
+        rule: String,
+    },
+    /// A weekday or time of day could not be parsed or is out of range.
+    #[error("invalid time of the week {value:?}; expected e.g. \"Sa 10:30\"")]
+    InvalidTime {
+        /// The value as written.
+        value: String,
+    },
+}
+
+/// Weekly opening hours.
+///
+/// Each day holds the spans, in minutes since midnight, during which the POI
+/// is open; a span may end after midnight and then continues into the next
+/// day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpeningHours {
+    days: [Vec<(u16, u16)>; 7],
+}
+
+impl OpeningHours {
+    /// Opening hours that never close.
+    pub fn always_open() -> Self {
+        Self {
+            days: std::array::from_fn(|_| vec![(0, MINUTES_PER_DAY)]),
+        }
+    }
+
+    /// Opening hours with the same span, in minutes since midnight, every
+    /// day.
+    pub(crate) fn daily(open: u16, close: u16) -> Self {
+        Self {
+            days: std::array::from_fn(|_| vec![(open, close)]),
+        }
+    }
+
+    /// Parse an `opening_hours` value in the supported subset.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OpeningHoursError::Empty`] when the value holds no rules and
+    /// [`OpeningHoursError::Unsupported`] for syntax outside the subset
+    /// described in the [module documentation](crate::opening_hours).
+    pub fn parse(value: &str) -> Result<Self, OpeningHoursError> {
+        let mut hours = Self {
+            days: Default::default(),
+        };
+        let mut rules = 0_usize;
+        for rule in value
+            .split(';')
+            .map(str::trim)
+            .filter(|rule| !rule.is_empty())
+        {
+            rules += 1;
+            let Some((days, spans)) = parse_rule(rule)? else {
+                continue;
+            };
+            for (day, _) in hours
+                .days
+                .iter_mut()
+                .zip(days)
+                .filter(|(_, selected)| *selected)
+            {
+                day.clone_from(&spans);
+            }
+        }
+        if rules == 0 {
+            return Err(OpeningHoursError::Empty);
+        }
+        Ok(hours)
+    }
+
+    /// Parse the [`OPENING_HOURS_TAG`] of `tags`, or `None` when it is absent.
+    pub fn from_tags(tags: &Tags) -> Option<Result<Self, OpeningHoursError>> {
+        tags.get(OPENING_HOURS_TAG).map(|value| Self::parse(value))
+    }
+
+    /// Open spans on `weekday`, in minutes since midnight.
+    ///
+    /// Spans that run past midnight end after `1440`.
+    pub fn spans(&self, weekday: Weekday) -> &[(u16, u16)] {
+        self.days
+            .get(weekday.index())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Periods during which the POI is open, as offsets from `start` and
+    /// clipped to `horizon`.
+    ///
+    /// The periods are sorted and disjoint. An empty result means the POI is
+    /// closed throughout.
+    pub fn windows_from(&self, start: WeekTime, horizon: Duration) -> Vec<Range<Duration>> {
+        let horizon_minutes = horizon.as_secs().div_ceil(60);
+        let start_minute = i64::from(start.minute_of_day());
+        let horizon_end = i64::try_from(horizon_minutes).unwrap_or(i64::MAX);
+        let days_ahead = horizon_end.saturating_add(start_minute) / i64::from(MINUTES_PER_DAY);
+
+        // Spans from the previous day can run past midnight into the start day.
+        let mut windows: Vec<(i64, i64)> = Vec::new();
+        for offset in -1..=days_ahead {
+            let day_index = (start.weekday().index() as i64 + offset).rem_euclid(7) as usize;
+            let day_start = offset * i64::from(MINUTES_PER_DAY) - start_minute;
+            let Some(spans) = self.days.get(day_index) else {
+                continue;
+            };
+            windows.extend(
+                spans
+                    .iter()
+                    .map(|&(open, close)| {
+                        let from = (day_start + i64::from(open)).max(0);
+                        let to = (day_start + i64::from(close)).min(horizon_end);
+                        (from, to)
+                    })
+                    .filter(|(from, to)| from < to),
+            );
+        }
+        windows.sort_unstable();
+
+        let mut merged: Vec<(i64, i64)> = Vec::with_capacity(windows.len());
+        for (from, to) in windows {
+            match merged.last_mut() {
+                Some(last) if from <= last.1 => last.1 = last.1.max(to),
+                _ => merged.push((from, to)),
+            }
+        }
+        let minutes = |value: i64| Duration::from_secs(u64::try_from(value).unwrap_or(0) * 60);
+        merged
+            .into_iter()
+            .map(|(from, to)| minutes(from).min(horizon)..minutes(to).min(horizon))
+            .collect()
+    }
+
+    /// Offsets from `start` at which a visit lasting `dwell` may begin and
+    /// still end before closing, within `horizon`.
+    ///
+    /// Each window of [`OpeningHours::windows_from`] is shortened by `dwell`;
+    /// windows too short for a visit are dropped, so an empty result means
+    /// the POI cannot be visited.
+    pub fn visit_windows(
+        &self,
+        start: WeekTime,
+        horizon: Duration,
+        dwell: Duration,
+    ) -> Vec<Range<Duration>> {
+        self.windows_from(start, horizon)
+            .into_iter()
+            .filter_map(|window| {
+                let latest_start = window.end.checked_sub(dwell)?;
+                (window.start <= latest_start).then_some(window.start..latest_start)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests;
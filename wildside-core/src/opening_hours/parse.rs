@@ -0,0 +1,108 @@
+//! Parsing the weekly subset of the `opening_hours` grammar.
+
+use super::{MINUTES_PER_DAY, OpeningHoursError, Weekday};
+
+/// Days selected by a rule, indexed from Monday, and their open spans.
+type Rule = ([bool; 7], Vec<(u16, u16)>);
+
+/// Parse one rule into the selected days and their spans, or `None` for a
+/// holiday rule that cannot be applied.
+pub(super) fn parse_rule(rule: &str) -> Result<Option<Rule>, OpeningHoursError> {
+    let unsupported = || OpeningHoursError::Unsupported {
+        rule: rule.to_owned(),
+    };
+    if rule == "24/7" {
+        return Ok(Some(([true; 7], vec![(0, MINUTES_PER_DAY)])));
+    }
+
+    let (selector, rest) = match rule.split_once(char::is_whitespace) {
+        Some((head, tail)) if is_day_selector(head) => (Some(head), tail.trim()),
+        None if is_day_selector(rule) => (Some(rule), ""),
+        _ => (None, rule),
+    };
+    let days = match selector {
+        Some(selector) => match parse_days(selector).ok_or_else(unsupported)? {
+            Some(days) => days,
+            None => return Ok(None),
+        },
+        None => [true; 7],
+    };
+
+    let rest = rest.strip_suffix("open").map_or(rest, str::trim_end);
+    let spans = match rest {
+        "" | "24/7" | "00:00-24:00" => vec![(0, MINUTES_PER_DAY)],
+        "off" | "closed" => Vec::new(),
+        spans => spans
+            .split(',')
+            .map(|span| parse_span(span.trim()))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(unsupported)?,
+    };
+    Ok(Some((days, spans)))
+}
+
+/// Whether `token` looks like a weekday or holiday selector.
+fn is_day_selector(token: &str) -> bool {
+    token
+        .split([',', '-'])
+        .all(|part| Weekday::from_abbreviation(part).is_some() || matches!(part, "PH" | "SH"))
+}
+
+/// Parse a day selector such as `Mo-Fr,Su`.
+///
+/// Returns `Some(None)` when the selector names only holidays, and `None`
+/// when it is malformed.
+fn parse_days(selector: &str) -> Option<Option<[bool; 7]>> {
+    let mut days = [false; 7];
+    let mut any_weekday = false;
+    for part in selector.split(',') {
+        if matches!(part, "PH" | "SH") {
+            continue;
+        }
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (
+                Weekday::from_abbreviation(first)?,
+                Weekday::from_abbreviation(last)?,
+            ),
+            None => {
+                let day = Weekday::from_abbreviation(part)?;
+                (day, day)
+            }
+        };
+        let span = (last.index() + 7 - first.index()) % 7;
+        for step in 0..=span {
+            if let Some(day) = days.get_mut((first.index() + step) % 7) {
+                *day = true;
+            }
+        }
+        any_weekday = true;
+    }
+    Some(any_weekday.then_some(days))
+}
+
+/// Parse `HH:MM-HH:MM`; a close at or before the open runs past midnight.
+fn parse_span(span: &str) -> Option<(u16, u16)> {
+    let (open, close) = span.split_once('-')?;
+    let open = parse_clock(open)?;
+    let close = parse_clock(close)?;
+    if open >= MINUTES_PER_DAY || close > 2 * MINUTES_PER_DAY {
+        return None;
+    }
+    let close = if close <= open {
+        close + MINUTES_PER_DAY
+    } else {
+        close
+    };
+    Some((open, close))
+}
+
+/// Parse `HH:MM` into minutes, allowing hours up to 48 for extended spans.
+pub(super) fn parse_clock(value: &str) -> Option<u16> {
+    let (hour, minute) = value.split_once(':')?;
+    if hour.is_empty() || hour.len() > 2 || minute.len() != 2 {
+        return None;
+    }
+    let hour: u16 = hour.parse().ok()?;
+    let minute: u16 = minute.parse().ok()?;
+    (hour <= 48 && minute < 60).then_some(hour * 60 + minute)
+}
@@ -0,0 +1,139 @@
+//! Tests for opening-hours parsing and window computation.
+
+use super::*;
+use rstest::rstest;
+
+fn at(weekday: Weekday, hour: u8, minute: u8) -> WeekTime {
+    WeekTime::new(weekday, hour, minute).expect("valid time")
+}
+
+fn hours(value: u64) -> Duration {
+    Duration::from_hours(value)
+}
+
+#[rstest]
+fn parses_weekday_ranges_and_overrides() {
+    let parsed = OpeningHours::parse("Mo-Sa 10:00-18:00; Th 10:00-21:00; Sa off").expect("parse");
+    assert_eq!(parsed.spans(Weekday::Monday), [(600, 1080)]);
+    assert_eq!(parsed.spans(Weekday::Thursday), [(600, 1260)]);
+    assert!(parsed.spans(Weekday::Saturday).is_empty());
+    assert!(parsed.spans(Weekday::Sunday).is_empty());
+}
+
+#[rstest]
+#[case("24/7")]
+#[case("Mo-Su 00:00-24:00")]
+#[case("00:00-24:00")]
+fn recognises_always_open(#[case] value: &str) {
+    let parsed = OpeningHours::parse(value).expect("parse");
+    assert_eq!(parsed, OpeningHours::always_open());
+}
+
+#[rstest]
+fn wraps_day_ranges_past_sunday() {
+    let parsed = OpeningHours::parse("Fr-Mo 12:00-14:00,18:00-22:00").expect("parse");
+    assert_eq!(parsed.spans(Weekday::Sunday), [(720, 840), (1080, 1320)]);
+    assert!(parsed.spans(Weekday::Tuesday).is_empty());
+}
+
+#[rstest]
+fn skips_holiday_rules() {
+    let parsed = OpeningHours::parse("Mo-Fr 09:00-17:00; PH off").expect("parse");
+    assert_eq!(parsed.spans(Weekday::Friday), [(540, 1020)]);
+}
+
+#[rstest]
+#[case("")]
+#[case(" ; ")]
+fn rejects_empty_values(#[case] value: &str) {
+    assert_eq!(OpeningHours::parse(value), Err(OpeningHoursError::Empty));
+}
+
+#[rstest]
+#[case("Jan-Mar Mo-Fr 09:00-17:00")]
+#[case("Mo-Fr sunrise-sunset")]
+#[case("Mo-Fr 09:00-17:00 || \"by appointment\"")]
+#[case("Mo 25:00-26:00")]
+fn rejects_unsupported_rules(#[case] value: &str) {
+    assert!(matches!(
+        OpeningHours::parse(value),
+        Err(OpeningHoursError::Unsupported { .. })
+    ));
+}
+
+#[rstest]
+fn windows_are_relative_to_the_start() {
+    let parsed = OpeningHours::parse("Mo-Fr 09:00-12:00,13:00-17:00").expect("parse");
+    let windows = parsed.windows_from(at(Weekday::Monday, 10, 0), hours(5));
+    assert_eq!(windows, [Duration::ZERO..hours(2), hours(3)..hours(5)]);
+}
+
+#[rstest]
+fn windows_follow_spans_past_midnight() {
+    let parsed = OpeningHours::parse("Fr 18:00-02:00").expect("parse");
+    let windows = parsed.windows_from(at(Weekday::Saturday, 1, 0), hours(3));
+    assert_eq!(windows, [Duration::ZERO..hours(1)]);
+}
+
+#[rstest]
+fn windows_span_several_days() {
+    let parsed = OpeningHours::parse("Sa,Su 10:00-11:00").expect("parse");
+    let windows = parsed.windows_from(at(Weekday::Saturday, 12, 0), hours(24));
+    assert_eq!(windows, [hours(22)..hours(23)]);
+}
+
+#[rstest]
+fn closed_days_have_no_windows() {
+    let parsed = OpeningHours::parse("Mo-Fr 09:00-17:00").expect("parse");
+    assert!(
+        parsed
+            .windows_from(at(Weekday::Sunday, 9, 0), hours(8))
+            .is_empty()
+    );
+}
+
+#[rstest]
+fn always_open_spans_the_whole_horizon() {
+    let windows = OpeningHours::always_open().windows_from(at(Weekday::Wednesday, 23, 0), hours(3));
+    assert_eq!(windows, [Duration::ZERO..hours(3)]);
+}
+
+#[rstest]
+fn visit_windows_leave_room_to_finish() {
+    let parsed = OpeningHours::parse("Mo 09:00-10:00,11:00-11:10").expect("parse");
+    let windows =
+        parsed.visit_windows(at(Weekday::Monday, 8, 0), hours(4), Duration::from_mins(20));
+    assert_eq!(windows, [hours(1)..Duration::from_mins(100)]);
+}
+
+#[rstest]
+#[case(at(Weekday::Monday, 9, 0), 90, at(Weekday::Monday, 10, 30))]
+#[case(at(Weekday::Sunday, 23, 30), 60, at(Weekday::Monday, 0, 30))]
+#[case(at(Weekday::Friday, 12, 0), 7 * 24 * 60, at(Weekday::Friday, 12, 0))]
+fn week_times_wrap_around_the_week(
+    #[case] start: WeekTime,
+    #[case] minutes: u64,
+    #[case] expected: WeekTime,
+) {
+    assert_eq!(start.after(Duration::from_mins(minutes)), expected);
+}
+
+#[rstest]
+#[case("Sa 10:30", Weekday::Saturday, 630)]
+#[case("mo 00:00", Weekday::Monday, 0)]
+fn parses_week_times(#[case] value: &str, #[case] weekday: Weekday, #[case] minute: u16) {
+    let parsed: WeekTime = value.parse().expect("parse");
+    assert_eq!(parsed.weekday(), weekday);
+    assert_eq!(parsed.minute_of_day(), minute);
+}
+
+#[rstest]
+#[case("Saturday 10:30")]
+#[case("Sa 24:00")]
+#[case("Sa")]
+fn rejects_invalid_week_times(#[case] value: &str) {
+    assert!(matches!(
+        value.parse::<WeekTime>(),
+        Err(OpeningHoursError::InvalidTime { .. })
+    ));
+}
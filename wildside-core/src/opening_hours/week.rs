@@ -0,0 +1,217 @@
+//! Days of the week and times within a week.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use super::parse::parse_clock;
+use super::{MINUTES_PER_DAY, MINUTES_PER_WEEK, OpeningHoursError};
+
+/// Day of the week, starting on Monday as in `opening_hours` ranges.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Weekday {
+    /// Monday (`Mo`).
+    Monday,
+    /// Tuesday (`Tu`).
+    Tuesday,
+    /// Wednesday (`We`).
+    Wednesday,
+    /// Thursday (`Th`).
+    Thursday,
+    /// Friday (`Fr`).
+    Friday,
+    /// Saturday (`Sa`).
+    Saturday,
+    /// Sunday (`Su`).
+    Sunday,
+}
+
+impl Weekday {
+    /// Every weekday, from Monday.
+    pub const ALL: [Self; 7] = [
+        Self::Monday,
+        Self::Tuesday,
+        Self::Wednesday,
+        Self::Thursday,
+        Self::Friday,
+        Self::Saturday,
+        Self::Sunday,
+    ];
+
+    /// Two-letter abbreviation used by `opening_hours`, e.g. `Mo`.
+    pub const fn abbreviation(self) -> &'static str {
+        match self {
+            Self::Monday => "Mo",
+            Self::Tuesday => "Tu",
+            Self::Wednesday => "We",
+            Self::Thursday => "Th",
+            Self::Friday => "Fr",
+            Self::Saturday => "Sa",
+            Self::Sunday => "Su",
+        }
+    }
+
+    /// Days since Monday.
+    pub(super) const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The day `days` after Monday, wrapping around the week.
+    pub(crate) const fn from_index(days: u64) -> Self {
+        match days % 7 {
+            0 => Self::Monday,
+            1 => Self::Tuesday,
+            2 => Self::Wednesday,
+            3 => Self::Thursday,
+            4 => Self::Friday,
+            5 => Self::Saturday,
+            _ => Self::Sunday,
+        }
+    }
+
+    pub(super) fn from_abbreviation(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|day| day.abbreviation().eq_ignore_ascii_case(value))
+    }
+}
+
+impl fmt::Display for Weekday {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.abbreviation())
+    }
+}
+
+/// A local time within the week, such as Saturday 10:30.
+///
+/// Opening hours are written in local time, so a route's start is given the
+/// same way. Parses from and displays as `"Sa 10:30"`.
+///
+/// # Examples
+/// ```rust
+/// use wildside_core::opening_hours::{WeekTime, Weekday};
+///
+/// let start: WeekTime = "Sa 10:30".parse()?;
+/// assert_eq!(start.weekday(), Weekday::Saturday);
+/// assert_eq!(start.minute_of_day(), 630);
+/// assert_eq!(start.to_string(), "Sa 10:30");
+/// # Ok::<(), wildside_core::opening_hours::OpeningHoursError>(())
+/// ```
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WeekTime {
+    weekday: Weekday,
+    minute_of_day: u16,
+}
+
+impl WeekTime {
+    /// Build the time `hour:minute` on `weekday`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OpeningHoursError::InvalidTime`] when `hour` exceeds 23 or
+    /// `minute` exceeds 59.
+    pub fn new(weekday: Weekday, hour: u8, minute: u8) -> Result<Self, OpeningHoursError> {
+        if hour > 23 || minute > 59 {
+            return Err(OpeningHoursError::InvalidTime {
+                value: format!("{weekday} {hour:02}:{minute:02}"),
+            });
+        }
+        Ok(Self {
+            weekday,
+            minute_of_day: u16::from(hour) * 60 + u16::from(minute),
+        })
+    }
+
+    /// Day of the week.
+    pub const fn weekday(&self) -> Weekday {
+        self.weekday
+    }
+
+    /// Minutes since local midnight.
+    pub const fn minute_of_day(&self) -> u16 {
+        self.minute_of_day
+    }
+
+    /// The time `elapsed` later, wrapping past Sunday midnight.
+    ///
+    /// Seconds short of a whole minute are dropped.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::time::Duration;
+    /// use wildside_core::opening_hours::WeekTime;
+    ///
+    /// let late: WeekTime = "Su 23:30".parse()?;
+    /// assert_eq!(late.after(Duration::from_hours(1)).to_string(), "Mo 00:30");
+    /// # Ok::<(), wildside_core::opening_hours::OpeningHoursError>(())
+    /// ```
+    #[must_use]
+    pub const fn after(self, elapsed: Duration) -> Self {
+        let minute_of_week = (self.weekday.index() as u64 * MINUTES_PER_DAY as u64
+            + self.minute_of_day as u64
+            + elapsed.as_secs() / 60 % MINUTES_PER_WEEK)
+            % MINUTES_PER_WEEK;
+        Self::from_minute_of_week(minute_of_week)
+    }
+
+    /// The time `minute` minutes after Monday midnight, wrapping around the
+    /// week.
+    pub(crate) const fn from_minute_of_week(minute: u64) -> Self {
+        let minute = minute % MINUTES_PER_WEEK;
+        Self {
+            weekday: Weekday::from_index(minute / MINUTES_PER_DAY as u64),
+            minute_of_day: (minute % MINUTES_PER_DAY as u64) as u16,
+        }
+    }
+}
+
+impl fmt::Display for WeekTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {:02}:{:02}",
+            self.weekday,
+            self.minute_of_day / 60,
+            self.minute_of_day % 60
+        )
+    }
+}
+
+impl FromStr for WeekTime {
+    type Err = OpeningHoursError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || OpeningHoursError::InvalidTime {
+            value: value.to_owned(),
+        };
+        let (day, time) = value.trim().split_once(' ').ok_or_else(invalid)?;
+        let weekday = Weekday::from_abbreviation(day).ok_or_else(invalid)?;
+        let minutes = parse_clock(time.trim())
+            .filter(|minutes| *minutes < MINUTES_PER_DAY)
+            .ok_or_else(invalid)?;
+        Ok(Self {
+            weekday,
+            minute_of_day: minutes,
+        })
+    }
+}
+
+impl TryFrom<String> for WeekTime {
+    type Error = OpeningHoursError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<WeekTime> for String {
+    fn from(value: WeekTime) -> Self {
+        value.to_string()
+    }
+}
@@ -0,0 +1,169 @@
+//! Penalties that keep routes from doubling back on themselves.
+//!
+//! A route that walks out to a POI and straight back along the same street
+//! scores as well as a loop through the same stops, but is less pleasant to
+//! walk. [`OverlapPolicy`] approximates how much consecutive legs overlap from
+//! their bearings and charges a penalty, in score units, for each turn that
+//! sends the walker back the way they came.
+
+use geo::Coord;
+
+/// Penalty for consecutive legs that retrace one another.
+///
+/// Legs are approximated as straight lines between stops. At each stop the
+/// route charges `backtrack_penalty` scaled by how sharply the next leg
+/// doubles back on the last: nothing for turns of up to 90 degrees, rising to
+/// the full penalty for a U-turn. Penalties are in the same units as POI
+/// scores and are subtracted from the objective, not from the reported route
+/// score. The default policy applies no penalty.
+///
+/// # Examples
+///
+/// ```rust
+/// use geo::Coord;
+/// use wildside_core::OverlapPolicy;
+///
+/// let policy = OverlapPolicy::new(0.3);
+/// let (home, shop) = (Coord { x: 0.0, y: 0.0 }, Coord { x: 0.001, y: 0.0 });
+/// // Walking out and straight back turns around at the shop.
+/// assert!((policy.route_penalty([home, shop, home]) - 0.3).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OverlapPolicy {
+    /// Charged for each stop where the route turns straight back, and in
+    /// proportion for sharp turns short of that.
+    pub backtrack_penalty: f64,
+}
+
+impl OverlapPolicy {
+    /// Charge up to `backtrack_penalty` per turn that doubles back.
+    #[must_use]
+    pub const fn new(backtrack_penalty: f64) -> Self {
+        Self { backtrack_penalty }
+    }
+
+    /// Report whether the policy penalises anything.
+    #[must_use]
+    pub const fn is_enabled(&self) -> bool {
+        self.backtrack_penalty > 0.0
+    }
+
+    /// Penalty for turning at `via` when walking from `from` on to `to`.
+    #[must_use]
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "penalties scale with a floating-point overlap"
+    )]
+    pub fn turn_penalty(self, from: Coord<f64>, via: Coord<f64>, to: Coord<f64>) -> f64 {
+        self.backtrack_penalty * backtrack(from, via, to)
+    }
+
+    /// Total penalty for a route through `points`, in order.
+    ///
+    /// Consecutive repeats of a point, such as a rest break taken at the POI
+    /// just visited, are walked as one stop.
+    #[must_use]
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "penalties accumulate in floating-point score units"
+    )]
+    pub fn route_penalty(self, points: impl IntoIterator<Item = Coord<f64>>) -> f64 {
+        let mut from: Option<Coord<f64>> = None;
+        let mut via: Option<Coord<f64>> = None;
+        let mut penalty = 0.0;
+        for point in points {
+            if via == Some(point) {
+                continue;
+            }
+            if let Some((start, turn)) = from.zip(via) {
+                penalty += self.turn_penalty(start, turn, point);
+            }
+            from = via;
+            via = Some(point);
+        }
+        penalty
+    }
+}
+
+/// How far the leg from `via` to `to` doubles back on the leg from `from` to
+/// `via`: zero for turns of up to 90 degrees, one for a U-turn.
+///
+/// Bearings come from an equirectangular projection around `via`, which is
+/// accurate over walking distances.
+#[expect(
+    clippy::float_arithmetic,
+    reason = "bearings are compared with floating-point vector arithmetic"
+)]
+fn backtrack(from: Coord<f64>, via: Coord<f64>, to: Coord<f64>) -> f64 {
+    let scale = via.y.to_radians().cos();
+    let (in_x, in_y) = ((via.x - from.x) * scale, via.y - from.y);
+    let (out_x, out_y) = ((to.x - via.x) * scale, to.y - via.y);
+    let lengths = in_x.hypot(in_y) * out_x.hypot(out_y);
+    if lengths <= 0.0 {
+        return 0.0;
+    }
+    let cosine = (in_x * out_x + in_y * out_y) / lengths;
+    (-cosine).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for backtracking penalties.
+
+    use super::*;
+    use rstest::rstest;
+
+    const fn at(x: f64, y: f64) -> Coord<f64> {
+        Coord { x, y }
+    }
+
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "penalties are compared within a tolerance"
+    )]
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[rstest]
+    fn default_policy_is_disabled() {
+        let policy = OverlapPolicy::default();
+        assert!(!policy.is_enabled());
+        assert_close(
+            policy.route_penalty([at(0.0, 0.0), at(0.001, 0.0), at(0.0, 0.0)]),
+            0.0,
+        );
+    }
+
+    #[rstest]
+    #[case::straight_on(at(0.002, 0.0), 0.0)]
+    #[case::right_angle(at(0.001, 0.001), 0.0)]
+    #[case::u_turn(at(0.0, 0.0), 2.0)]
+    #[case::sharp_turn(at(0.0, 0.001), std::f64::consts::SQRT_2)]
+    fn penalises_turns_that_double_back(#[case] to: Coord<f64>, #[case] expected: f64) {
+        let penalty = OverlapPolicy::new(2.0).turn_penalty(at(0.0, 0.0), at(0.001, 0.0), to);
+        assert_close(penalty, expected);
+    }
+
+    #[rstest]
+    fn out_and_back_costs_more_than_a_loop() {
+        let policy = OverlapPolicy::new(1.0);
+        let depot = at(0.0, 0.0);
+        let out_and_back = policy.route_penalty([depot, at(0.001, 0.0), at(0.002, 0.0), depot]);
+        let a_loop = policy.route_penalty([depot, at(0.001, 0.0), at(0.001, 0.001), depot]);
+        assert_close(out_and_back, 1.0);
+        assert!(a_loop < out_and_back);
+    }
+
+    #[rstest]
+    fn repeated_points_are_one_stop() {
+        let policy = OverlapPolicy::new(1.0);
+        let depot = at(0.0, 0.0);
+        let stop = at(0.001, 0.0);
+        let rested = policy.route_penalty([depot, stop, stop, depot]);
+        assert_close(rested, policy.route_penalty([depot, stop, depot]));
+    }
+}
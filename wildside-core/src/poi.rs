@@ -7,7 +7,7 @@
 use std::collections::HashMap;
 
 use geo::Coord;
-use rstar::{AABB, RTree, RTreeObject};
+use rstar::{AABB, PointDistance, RTree, RTreeObject};
 
 /// Map of tag key/value pairs (typically OSM-like).
 pub type Tags = HashMap<String, String>;
@@ -48,6 +48,17 @@ impl RTreeObject for PointOfInterest {
     }
 }
 
+/// Measure squared distances in lon/lat degree space so R\*-trees can answer
+/// nearest-neighbour queries over POIs.
+impl PointDistance for PointOfInterest {
+    fn distance_2(&self, point: &[f64; 2]) -> f64 {
+        let [x, y] = *point;
+        let dx = self.location.x - x;
+        let dy = self.location.y - y;
+        dx * dx + dy * dy
+    }
+}
+
 /// A spatial index for locating [`PointOfInterest`] values.
 #[derive(Clone, Debug)]
 pub struct SpatialIndex {
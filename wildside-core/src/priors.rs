@@ -0,0 +1,304 @@
+//! Per-region default interest weights for cold-start personalization.
+//!
+//! Visitors who leave their [`InterestProfile`] empty or nearly empty give the
+//! scorer little to work with. [`InterestPriors`] holds an optional artefact of
+//! typical theme weights per region (for example, "visitors to this city enjoy
+//! history and food") and blends the matching prior into sparse profiles at
+//! solve time. Themes the user set explicitly are never overridden.
+
+#[cfg(feature = "serde")]
+use std::collections::HashMap;
+
+use geo::{Coord, Intersects, Rect};
+use thiserror::Error;
+
+use crate::{InterestProfile, Theme, profile::WeightError};
+
+/// Profiles with fewer explicit themes than this are considered sparse.
+pub const SPARSE_THEME_COUNT: usize = 3;
+
+/// Scale applied to prior weights when filling gaps in a sparse profile.
+///
+/// Empty profiles receive the prior unscaled.
+pub const SPARSE_PRIOR_SCALE: f32 = 0.5;
+
+/// Default theme weights for a geographic region.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionPrior {
+    /// Human-readable region label, such as a city name.
+    pub name: String,
+    /// WGS84 bounds (`x = longitude`, `y = latitude`); boundaries are inclusive.
+    pub bounds: Rect<f64>,
+    /// Typical theme weights for visitors to the region.
+    pub weights: InterestProfile,
+}
+
+/// Collection of regional priors with an optional global fallback.
+///
+/// # Examples
+/// ```rust
+/// use geo::{Coord, Rect};
+/// use wildside_core::{InterestProfile, Theme};
+/// use wildside_core::priors::{InterestPriors, RegionPrior};
+///
+/// let priors = InterestPriors::new(vec![RegionPrior {
+///     name: "berlin".into(),
+///     bounds: Rect::new(Coord { x: 13.0, y: 52.3 }, Coord { x: 13.8, y: 52.7 }),
+///     weights: InterestProfile::new().with_weight(Theme::History, 0.9),
+/// }]);
+///
+/// let blended = priors.blend(Coord { x: 13.4, y: 52.5 }, &InterestProfile::new());
+/// assert_eq!(blended.weight(&Theme::History), Some(0.9));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InterestPriors {
+    regions: Vec<RegionPrior>,
+    fallback: Option<InterestProfile>,
+}
+
+/// Errors raised when decoding a priors artefact.
+#[derive(Debug, Error)]
+pub enum PriorsError {
+    /// The artefact was not valid JSON or did not match the expected shape.
+    #[cfg(feature = "serde")]
+    #[error("failed to parse interest priors: {0}")]
+    Parse(#[from] serde_json::Error),
+    /// A weight in the artefact was outside `-1.0..=1.0` or not finite.
+    #[error("invalid weight for theme {theme} in region {region}: {source}")]
+    InvalidWeight {
+        /// Region containing the invalid weight, or `fallback`.
+        region: String,
+        /// Theme whose weight was rejected.
+        theme: Theme,
+        /// Validation failure.
+        #[source]
+        source: WeightError,
+    },
+}
+
+impl InterestPriors {
+    /// Build priors from regions, checked in order.
+    #[must_use]
+    pub fn new(regions: Vec<RegionPrior>) -> Self {
+        Self {
+            regions,
+            fallback: None,
+        }
+    }
+
+    /// Use `fallback` for locations outside every region.
+    #[must_use]
+    pub fn with_fallback(mut self, fallback: InterestProfile) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+
+    /// Decode priors from a JSON artefact.
+    ///
+    /// The document holds a `regions` array of objects with `name`, `bounds`
+    /// (`{"min": {"x", "y"}, "max": {"x", "y"}}`), and a `weights` map from
+    /// theme to weight, plus an optional `fallback` weights map.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use geo::Coord;
+    /// use wildside_core::Theme;
+    /// use wildside_core::priors::InterestPriors;
+    ///
+    /// let json = r#"{
+    ///     "regions": [],
+    ///     "fallback": {"Food": 0.4}
+    /// }"#;
+    /// let priors = InterestPriors::from_reader(json.as_bytes())?;
+    /// let prior = priors.prior_for(Coord { x: 0.0, y: 0.0 }).expect("fallback prior");
+    /// assert_eq!(prior.weight(&Theme::Food), Some(0.4));
+    /// # Ok::<(), wildside_core::priors::PriorsError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`PriorsError::Parse`] for malformed documents and
+    /// [`PriorsError::InvalidWeight`] for out-of-range weights.
+    #[cfg(feature = "serde")]
+    pub fn from_reader<R: std::io::Read>(reader: R) -> Result<Self, PriorsError> {
+        let raw: RawPriors = serde_json::from_reader(reader)?;
+        let regions = raw
+            .regions
+            .into_iter()
+            .map(|region| {
+                let weights = validated_profile(&region.name, region.weights)?;
+                Ok(RegionPrior {
+                    name: region.name,
+                    bounds: region.bounds,
+                    weights,
+                })
+            })
+            .collect::<Result<_, PriorsError>>()?;
+        let fallback = raw
+            .fallback
+            .map(|weights| validated_profile("fallback", weights))
+            .transpose()?;
+        Ok(Self { regions, fallback })
+    }
+
+    /// Return the prior for `location`: the first containing region, else the
+    /// fallback.
+    #[must_use]
+    pub fn prior_for(&self, location: Coord<f64>) -> Option<&InterestProfile> {
+        self.regions
+            .iter()
+            .find(|region| region.bounds.intersects(&location))
+            .map(|region| &region.weights)
+            .or(self.fallback.as_ref())
+    }
+
+    /// Blend the prior for `location` into `profile`.
+    ///
+    /// Empty profiles adopt the prior wholesale. Profiles with fewer than
+    /// [`SPARSE_THEME_COUNT`] themes keep their explicit weights and gain the
+    /// remaining prior themes scaled by [`SPARSE_PRIOR_SCALE`]. Fuller profiles,
+    /// and locations without a prior, are returned unchanged.
+    #[must_use]
+    pub fn blend(&self, location: Coord<f64>, profile: &InterestProfile) -> InterestProfile {
+        let Some(prior) = self.prior_for(location) else {
+            return profile.clone();
+        };
+        let scale = match profile.len() {
+            0 => 1.0,
+            n if n < SPARSE_THEME_COUNT => SPARSE_PRIOR_SCALE,
+            _ => return profile.clone(),
+        };
+        let mut blended = profile.clone();
+        for (theme, weight) in prior.iter() {
+            if blended.weight(theme).is_none() {
+                // Prior weights were validated and scale is within 0..=1.
+                let _ignored = blended.try_set_weight(theme.clone(), weight * scale);
+            }
+        }
+        blended
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawPriors {
+    #[serde(default)]
+    regions: Vec<RawRegionPrior>,
+    #[serde(default)]
+    fallback: Option<HashMap<Theme, f32>>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawRegionPrior {
+    name: String,
+    bounds: Rect<f64>,
+    weights: HashMap<Theme, f32>,
+}
+
+#[cfg(feature = "serde")]
+fn validated_profile(
+    region: &str,
+    weights: HashMap<Theme, f32>,
+) -> Result<InterestProfile, PriorsError> {
+    let mut profile = InterestProfile::new();
+    for (theme, weight) in weights {
+        profile
+            .try_set_weight(theme.clone(), weight)
+            .map_err(|source| PriorsError::InvalidWeight {
+                region: region.to_owned(),
+                theme,
+                source,
+            })?;
+    }
+    Ok(profile)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for regional prior lookup and profile blending.
+
+    use super::*;
+    use rstest::{fixture, rstest};
+
+    #[fixture]
+    fn priors() -> InterestPriors {
+        InterestPriors::new(vec![RegionPrior {
+            name: "berlin".into(),
+            bounds: Rect::new(Coord { x: 13.0, y: 52.3 }, Coord { x: 13.8, y: 52.7 }),
+            weights: InterestProfile::new()
+                .with_weight(Theme::History, 0.8)
+                .with_weight(Theme::Food, 0.6),
+        }])
+    }
+
+    const BERLIN: Coord<f64> = Coord { x: 13.4, y: 52.5 };
+    const ELSEWHERE: Coord<f64> = Coord { x: 0.0, y: 0.0 };
+
+    #[rstest]
+    fn empty_profile_adopts_prior(priors: InterestPriors) {
+        let blended = priors.blend(BERLIN, &InterestProfile::new());
+        assert_eq!(blended.weight(&Theme::History), Some(0.8));
+        assert_eq!(blended.weight(&Theme::Food), Some(0.6));
+    }
+
+    #[rstest]
+    fn sparse_profile_keeps_explicit_weights(priors: InterestPriors) {
+        let profile = InterestProfile::new().with_weight(Theme::History, 0.1);
+        let blended = priors.blend(BERLIN, &profile);
+        assert_eq!(blended.weight(&Theme::History), Some(0.1));
+        assert_eq!(blended.weight(&Theme::Food), Some(0.3));
+    }
+
+    #[rstest]
+    fn full_profile_is_unchanged(priors: InterestPriors) {
+        let profile = InterestProfile::new()
+            .with_weight(Theme::Art, 0.5)
+            .with_weight(Theme::Nature, 0.5)
+            .with_weight(Theme::Culture, 0.5);
+        assert_eq!(priors.blend(BERLIN, &profile), profile);
+    }
+
+    #[rstest]
+    fn locations_outside_regions_use_fallback(priors: InterestPriors) {
+        assert_eq!(
+            priors.blend(ELSEWHERE, &InterestProfile::new()),
+            InterestProfile::new()
+        );
+
+        let with_fallback =
+            priors.with_fallback(InterestProfile::new().with_weight(Theme::Nature, 0.7));
+        let blended = with_fallback.blend(ELSEWHERE, &InterestProfile::new());
+        assert_eq!(blended.weight(&Theme::Nature), Some(0.7));
+    }
+
+    #[cfg(feature = "serde")]
+    #[rstest]
+    fn decodes_json_artefact() {
+        let json = r#"{
+            "regions": [{
+                "name": "berlin",
+                "bounds": {"min": {"x": 13.0, "y": 52.3}, "max": {"x": 13.8, "y": 52.7}},
+                "weights": {"History": 0.8}
+            }]
+        }"#;
+        let priors = InterestPriors::from_reader(json.as_bytes()).expect("decode priors");
+        let prior = priors.prior_for(BERLIN).expect("berlin prior");
+        assert_eq!(prior.weight(&Theme::History), Some(0.8));
+        assert!(priors.prior_for(ELSEWHERE).is_none());
+    }
+
+    #[cfg(feature = "serde")]
+    #[rstest]
+    fn rejects_out_of_range_weights() {
+        let json = r#"{"regions": [], "fallback": {"Art": 1.5}}"#;
+        let err = InterestPriors::from_reader(json.as_bytes()).expect_err("weight out of range");
+        assert!(matches!(
+            err,
+            PriorsError::InvalidWeight {
+                theme: Theme::Art,
+                source: WeightError::OutOfRange,
+                ..
+            }
+        ));
+    }
+}
@@ -1,7 +1,8 @@
-//! Interest profiles: per-theme user preference weights in `[0.0, 1.0]`.
+//! Interest profiles: per-theme user preference weights in `[-1.0, 1.0]`.
 //!
-//! Provides an API to set, get, and chain theme weights. Prefer the
-//! non-panicking `try_*` methods for validation in library code.
+//! Positive weights express interest in a theme and negative weights a
+//! dislike of it. Provides an API to set, get, and chain theme weights. Prefer
+//! the non-panicking `try_*` methods for validation in library code.
 
 use std::collections::HashMap;
 use thiserror::Error;
@@ -10,6 +11,9 @@ use crate::Theme;
 
 /// User interest weighting across themes.
 ///
+/// A negative weight marks a theme the visitor wants to avoid; scorers lower
+/// the score of POIs matching it.
+///
 /// # Examples
 /// ```rust
 /// use wildside_core::{InterestProfile, Theme};
@@ -28,8 +32,8 @@ pub struct InterestProfile {
 /// Errors from [`InterestProfile::try_set_weight`].
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
 pub enum WeightError {
-    /// Weight is not within the `0.0..=1.0` range.
-    #[error("weight must be within 0.0..=1.0")]
+    /// Weight is not within the `-1.0..=1.0` range.
+    #[error("weight must be within -1.0..=1.0")]
     OutOfRange,
     /// Weight is not finite.
     #[error("weight must be finite")]
@@ -64,12 +68,36 @@ impl InterestProfile {
         self.weights.get(theme).copied()
     }
 
+    /// Iterate over the explicitly weighted themes in arbitrary order.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use wildside_core::{InterestProfile, Theme};
+    ///
+    /// let profile = InterestProfile::new().with_weight(Theme::Food, 0.4);
+    /// let themes: Vec<_> = profile.iter().map(|(theme, _)| theme.clone()).collect();
+    /// assert_eq!(themes, vec![Theme::Food]);
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = (&Theme, f32)> {
+        self.weights.iter().map(|(theme, weight)| (theme, *weight))
+    }
+
+    /// Return the number of explicitly weighted themes.
+    pub fn len(&self) -> usize {
+        self.weights.len()
+    }
+
+    /// Return `true` when no theme has been weighted.
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
     /// Insert or update a theme weight.
     ///
     /// Delegates to [`Self::try_set_weight`] and panics on error.
     ///
     /// # Panics
-    /// Panics if `weight` is outside `0.0..=1.0` or not finite (NaN/∞).
+    /// Panics if `weight` is outside `-1.0..=1.0` or not finite (NaN/∞).
     ///
     /// # Examples
     /// ```rust
@@ -85,7 +113,7 @@ impl InterestProfile {
         // wrapper documents its panic contract, and fallible callers should
         // use `try_set_weight` to propagate the error instead.
         if let Err(error) = self.try_set_weight(theme, weight) {
-            panic!("weight must be finite and within 0.0..=1.0: {error}");
+            panic!("weight must be finite and within -1.0..=1.0: {error}");
         }
     }
 
@@ -93,13 +121,13 @@ impl InterestProfile {
     ///
     /// # Errors
     /// Returns [`WeightError::OutOfRange`] if `weight` is outside
-    /// `0.0..=1.0`.
+    /// `-1.0..=1.0`.
     /// Returns [`WeightError::NonFinite`] if `weight` is `NaN` or infinite.
     pub fn try_set_weight(&mut self, theme: Theme, weight: f32) -> Result<(), WeightError> {
         if !weight.is_finite() {
             return Err(WeightError::NonFinite);
         }
-        if !(0.0..=1.0).contains(&weight) {
+        if !(-1.0..=1.0).contains(&weight) {
             return Err(WeightError::OutOfRange);
         }
         self.weights.insert(theme, weight);
@@ -109,14 +137,16 @@ impl InterestProfile {
     /// Add a theme weight while returning `self` for chaining.
     ///
     /// # Panics
-    /// Panics if `weight` is outside `0.0..=1.0` or not finite (NaN/∞).
+    /// Panics if `weight` is outside `-1.0..=1.0` or not finite (NaN/∞).
     ///
     /// # Examples
     /// ```rust
     /// use wildside_core::{InterestProfile, Theme};
     ///
-    /// let profile = InterestProfile::new().with_weight(Theme::History, 0.8);
-    /// assert_eq!(profile.weight(&Theme::History), Some(0.8));
+    /// let profile = InterestProfile::new()
+    ///     .with_weight(Theme::History, 0.8)
+    ///     .with_weight(Theme::Shopping, -1.0);
+    /// assert_eq!(profile.weight(&Theme::Shopping), Some(-1.0));
     /// ```
     #[must_use]
     pub fn with_weight(mut self, theme: Theme, weight: f32) -> Self {
@@ -186,11 +216,18 @@ mod tests {
             Err(WeightError::OutOfRange)
         );
         assert_eq!(
-            profile.try_set_weight(Theme::Art, -0.5),
+            profile.try_set_weight(Theme::Art, -1.5),
             Err(WeightError::OutOfRange)
         );
     }
 
+    #[test]
+    fn try_set_weight_accepts_dislikes() {
+        let mut profile = InterestProfile::new();
+        assert_eq!(profile.try_set_weight(Theme::Shopping, -0.5), Ok(()));
+        assert_eq!(profile.weight(&Theme::Shopping), Some(-0.5));
+    }
+
     #[test]
     fn try_set_weight_rejects_non_finite() {
         let mut profile = InterestProfile::new();
@@ -216,7 +253,7 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "0.0..=1.0")]
+    #[should_panic(expected = "-1.0..=1.0")]
     fn set_weight_panics_on_out_of_range() {
         let mut profile = InterestProfile::new();
         profile.set_weight(Theme::History, 1.5);
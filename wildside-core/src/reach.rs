@@ -0,0 +1,140 @@
+//! Walking reach around a request's endpoints.
+//!
+//! Solvers without a candidate region model of their own consider the POIs a
+//! visitor could walk to within the whole budget. [`WalkingReach`] describes
+//! that area as two circles, around the start and the end, and bounds them by
+//! a rectangle suitable for a [`PoiStore`](crate::PoiStore) query. Distances
+//! are great-circle metres on a spherical Earth.
+
+use geo::{Coord, Rect};
+
+use crate::SolveRequest;
+use crate::geometry::{cap_bounds, great_circle_m};
+
+/// Circles around a request's start and end whose radius is the distance
+/// walkable within the budget.
+///
+/// # Examples
+/// ```rust
+/// use geo::Coord;
+/// use wildside_core::{InterestProfile, SolveRequest, WalkingReach};
+///
+/// let request = SolveRequest {
+///     start: Coord { x: 0.0, y: 0.0 },
+///     duration_minutes: 60,
+///     interests: InterestProfile::new(),
+///     seed: 1,
+///     ..Default::default()
+/// };
+/// let reach = WalkingReach::new(&request, 5.0);
+/// assert!(reach.contains(Coord { x: 0.04, y: 0.0 }));
+/// assert!(!reach.contains(Coord { x: 0.05, y: 0.0 }));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WalkingReach {
+    start: Coord<f64>,
+    end: Coord<f64>,
+    radius_m: f64,
+}
+
+impl WalkingReach {
+    /// Resolve the reach of `request` at `speed_kmh`.
+    ///
+    /// Round trips have a single circle around the start.
+    #[must_use]
+    pub fn new(request: &SolveRequest, speed_kmh: f64) -> Self {
+        Self {
+            start: request.start,
+            end: request.end.unwrap_or(request.start),
+            radius_m: f64::from(request.duration_minutes) / 60.0 * speed_kmh * 1000.0,
+        }
+    }
+
+    /// Radius of each circle in metres.
+    #[must_use]
+    pub const fn radius_m(&self) -> f64 {
+        self.radius_m
+    }
+
+    /// Rectangle, in degrees, enclosing both circles.
+    ///
+    /// Circles reaching a pole span every longitude. Longitudes are clamped
+    /// to ±180° rather than wrapped across the antimeridian.
+    #[must_use]
+    pub fn bounds(&self) -> Rect<f64> {
+        let start = cap_bounds(self.start, self.radius_m);
+        let end = cap_bounds(self.end, self.radius_m);
+        Rect::new(
+            Coord {
+                x: start.min().x.min(end.min().x),
+                y: start.min().y.min(end.min().y),
+            },
+            Coord {
+                x: start.max().x.max(end.max().x),
+                y: start.max().y.max(end.max().y),
+            },
+        )
+    }
+
+    /// Report whether `location` lies within either circle.
+    #[must_use]
+    pub fn contains(&self, location: Coord<f64>) -> bool {
+        let from_start = great_circle_m(self.start, location);
+        let to_end = great_circle_m(location, self.end);
+        from_start.min(to_end) <= self.radius_m
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for walking reach geometry.
+
+    use super::*;
+    use crate::InterestProfile;
+    use rstest::rstest;
+
+    fn request(start: Coord<f64>, end: Option<Coord<f64>>) -> SolveRequest {
+        SolveRequest {
+            start,
+            end,
+            duration_minutes: 60,
+            interests: InterestProfile::new(),
+            seed: 1,
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    fn radius_is_the_distance_walkable_in_the_budget() {
+        let reach = WalkingReach::new(&request(Coord { x: 0.0, y: 0.0 }, None), 4.0);
+        assert!((reach.radius_m() - 4000.0).abs() < 1e-9);
+    }
+
+    #[rstest]
+    fn bounds_cover_both_endpoints() {
+        let start = Coord { x: 0.0, y: 0.0 };
+        let end = Coord { x: 1.0, y: 0.5 };
+        let bounds = WalkingReach::new(&request(start, Some(end)), 5.0).bounds();
+        assert!(bounds.min().x < start.x && bounds.max().x > end.x);
+        assert!(bounds.min().y < start.y && bounds.max().y > end.y);
+    }
+
+    #[rstest]
+    fn points_near_either_endpoint_are_within_reach() {
+        let reach = WalkingReach::new(
+            &request(Coord { x: 0.0, y: 0.0 }, Some(Coord { x: 1.0, y: 0.0 })),
+            5.0,
+        );
+        assert!(reach.contains(Coord { x: 0.01, y: 0.0 }));
+        assert!(reach.contains(Coord { x: 0.99, y: 0.0 }));
+        assert!(!reach.contains(Coord { x: 0.5, y: 0.0 }));
+    }
+
+    #[rstest]
+    fn caps_reaching_a_pole_span_every_longitude() {
+        let reach = WalkingReach::new(&request(Coord { x: 10.0, y: 89.99 }, None), 5.0);
+        let bounds = reach.bounds();
+        assert!((bounds.min().x + 180.0).abs() < 1e-9);
+        assert!((bounds.max().x - 180.0).abs() < 1e-9);
+    }
+}
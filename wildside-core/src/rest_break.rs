@@ -0,0 +1,215 @@
+//! Rest breaks reserved during a walk.
+//!
+//! A [`RestBreak`] asks for a pause of a set length that begins within a
+//! window of local time, such as half an hour for lunch around midday.
+//! Solvers that support breaks take it at a café, restaurant, or similar
+//! venue (see [`RestBreak::is_venue`]) while the venue is open, counting the
+//! break as time spent there.
+//!
+//! # Examples
+//! ```rust
+//! use std::time::Duration;
+//! use wildside_core::{RestBreak, WeekTime, Weekday};
+//!
+//! let lunch = RestBreak::lunch();
+//! let start = WeekTime::new(Weekday::Saturday, 10, 0)?;
+//! assert_eq!(
+//!     lunch.start_windows(start, Duration::from_hours(4)),
+//!     vec![Duration::from_mins(90)..Duration::from_mins(210)],
+//! );
+//! # Ok::<(), wildside_core::OpeningHoursError>(())
+//! ```
+
+use std::ops::Range;
+use std::time::Duration;
+
+use crate::PointOfInterest;
+use crate::opening_hours::{MINUTES_PER_DAY, OpeningHours, WeekTime};
+
+/// Values of the OpenStreetMap `amenity` tag marking somewhere to take a
+/// break.
+pub const REST_BREAK_AMENITIES: &[&str] = &[
+    "cafe",
+    "restaurant",
+    "fast_food",
+    "food_court",
+    "pub",
+    "biergarten",
+    "ice_cream",
+];
+
+/// A pause to reserve within a walk.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RestBreak {
+    /// Length of the break, in minutes.
+    pub duration_minutes: u16,
+    /// Earliest local time at which the break may begin, in minutes after
+    /// midnight.
+    pub earliest_minute: u16,
+    /// Latest local time at which the break may begin, in minutes after
+    /// midnight.
+    pub latest_minute: u16,
+}
+
+impl RestBreak {
+    /// A 30-minute lunch beginning between 11:30 and 13:30.
+    #[must_use]
+    pub const fn lunch() -> Self {
+        Self {
+            duration_minutes: 30,
+            earliest_minute: 11 * 60 + 30,
+            latest_minute: 13 * 60 + 30,
+        }
+    }
+
+    /// Length of the break.
+    #[must_use]
+    pub fn duration(&self) -> Duration {
+        Duration::from_mins(u64::from(self.duration_minutes))
+    }
+
+    /// Whether the break lasts between a minute and a day and its window
+    /// lies within one day, earliest first.
+    #[must_use]
+    pub const fn is_valid(&self) -> bool {
+        self.duration_minutes > 0
+            && self.duration_minutes <= MINUTES_PER_DAY
+            && self.earliest_minute <= self.latest_minute
+            && self.latest_minute < MINUTES_PER_DAY
+    }
+
+    /// Whether `poi` is somewhere to take a break, according to its
+    /// `amenity` tag.
+    #[must_use]
+    pub fn is_venue(poi: &PointOfInterest) -> bool {
+        poi.tags
+            .get("amenity")
+            .is_some_and(|amenity| REST_BREAK_AMENITIES.contains(&amenity.as_str()))
+    }
+
+    /// Offsets from `start` at which the break may begin, within `horizon`.
+    ///
+    /// The window recurs daily, so a walk starting after it has closed may
+    /// only break the next day. A walk starting within the window may break
+    /// at once.
+    #[must_use]
+    pub fn start_windows(&self, start: WeekTime, horizon: Duration) -> Vec<Range<Duration>> {
+        let close = self.latest_minute.saturating_add(self.duration_minutes);
+        OpeningHours::daily(self.earliest_minute, close).visit_windows(
+            start,
+            horizon,
+            self.duration(),
+        )
+    }
+
+    /// Offsets from `start` at which the break may begin at `poi`, within
+    /// `horizon`.
+    ///
+    /// These are the [`RestBreak::start_windows`] during which the venue
+    /// stays open for the whole break. Venues without usable opening hours
+    /// are assumed to be open.
+    #[must_use]
+    pub fn venue_windows(
+        &self,
+        poi: &PointOfInterest,
+        start: WeekTime,
+        horizon: Duration,
+    ) -> Vec<Range<Duration>> {
+        let windows = self.start_windows(start, horizon);
+        match OpeningHours::from_tags(&poi.tags) {
+            Some(Ok(hours)) => intersect(
+                &windows,
+                &hours.visit_windows(start, horizon, self.duration()),
+            ),
+            _ => windows,
+        }
+    }
+}
+
+/// Periods common to two sorted lists of disjoint windows.
+fn intersect(lhs: &[Range<Duration>], rhs: &[Range<Duration>]) -> Vec<Range<Duration>> {
+    lhs.iter()
+        .flat_map(|left| {
+            rhs.iter().filter_map(move |right| {
+                let from = left.start.max(right.start);
+                let to = left.end.min(right.end);
+                (from <= to).then_some(from..to)
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for rest-break windows and venues.
+
+    use super::*;
+    use crate::{Tags, Weekday};
+    use geo::Coord;
+    use rstest::rstest;
+
+    fn saturday_at(hour: u8, minute: u8) -> WeekTime {
+        WeekTime::new(Weekday::Saturday, hour, minute).expect("valid time")
+    }
+
+    fn venue(tags: &[(&str, &str)]) -> PointOfInterest {
+        let tags: Tags = tags
+            .iter()
+            .map(|&(key, value)| (key.to_owned(), value.to_owned()))
+            .collect();
+        PointOfInterest::new(1, Coord { x: 0.0, y: 0.0 }, tags)
+    }
+
+    #[rstest]
+    #[case(RestBreak::lunch(), true)]
+    #[case(RestBreak { duration_minutes: 0, ..RestBreak::lunch() }, false)]
+    #[case(RestBreak { earliest_minute: 900, ..RestBreak::lunch() }, false)]
+    #[case(RestBreak { latest_minute: 1440, ..RestBreak::lunch() }, false)]
+    fn validates_breaks(#[case] rest: RestBreak, #[case] expected: bool) {
+        assert_eq!(rest.is_valid(), expected);
+    }
+
+    #[rstest]
+    #[case(&[("amenity", "cafe")], true)]
+    #[case(&[("amenity", "restaurant")], true)]
+    #[case(&[("amenity", "bench")], false)]
+    #[case(&[("tourism", "museum")], false)]
+    fn recognises_venues(#[case] tags: &[(&str, &str)], #[case] expected: bool) {
+        assert_eq!(RestBreak::is_venue(&venue(tags)), expected);
+    }
+
+    #[rstest]
+    fn walks_starting_within_the_window_may_break_at_once() {
+        let windows = RestBreak::lunch().start_windows(saturday_at(12, 0), Duration::from_hours(3));
+        assert_eq!(windows, vec![Duration::ZERO..Duration::from_mins(90)]);
+    }
+
+    #[rstest]
+    fn walks_starting_after_the_window_cannot_break_the_same_day() {
+        let windows = RestBreak::lunch().start_windows(saturday_at(14, 0), Duration::from_hours(3));
+        assert!(windows.is_empty());
+    }
+
+    #[rstest]
+    fn venues_must_stay_open_for_the_whole_break() {
+        let cafe = venue(&[("amenity", "cafe"), ("opening_hours", "Sa 12:00-12:45")]);
+        let windows =
+            RestBreak::lunch().venue_windows(&cafe, saturday_at(10, 0), Duration::from_hours(4));
+        assert_eq!(
+            windows,
+            vec![Duration::from_mins(120)..Duration::from_mins(135)]
+        );
+    }
+
+    #[rstest]
+    fn venues_without_hours_are_assumed_open() {
+        let cafe = venue(&[("amenity", "cafe")]);
+        let windows =
+            RestBreak::lunch().venue_windows(&cafe, saturday_at(10, 0), Duration::from_hours(4));
+        assert_eq!(
+            windows,
+            vec![Duration::from_mins(90)..Duration::from_mins(210)]
+        );
+    }
+}
@@ -0,0 +1,348 @@
+//! Routes: ordered paths through points of interest with a caller-supplied duration.
+//!
+//! This module defines [`Route`], representing an ordered sequence of points of
+//! interest along with an overall duration. The duration is not computed from
+//! POIs; supply it from your planning logic. Solvers may also attach a
+//! [`RouteLeg`] breakdown so clients can render step-by-step itineraries.
+
+use std::collections::HashSet;
+use std::iter;
+use std::time::Duration;
+
+use geo::{Coord, LineString};
+
+use crate::{PointOfInterest, Timestamp, TravelTimeProvider};
+
+/// One end of a [`RouteLeg`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum RouteStop {
+    /// The route's start coordinate.
+    Start,
+    /// A visited point of interest, by identifier.
+    Poi(u64),
+    /// The route's end coordinate.
+    End,
+}
+
+/// One step of a route: walking to the next stop, then visiting it.
+///
+/// # Examples
+/// ```rust
+/// use std::time::Duration;
+/// use wildside_core::{RouteLeg, RouteStop};
+///
+/// let leg = RouteLeg {
+///     from: RouteStop::Start,
+///     to: RouteStop::Poi(7),
+///     travel_duration: Duration::from_mins(4),
+///     dwell: Some(Duration::from_mins(20)),
+///     cumulative_duration: Duration::from_mins(24),
+///     arrival_at: None,
+/// };
+/// assert_eq!(leg.arrival(), Duration::from_mins(4));
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteLeg {
+    /// Where the leg starts.
+    pub from: RouteStop,
+    /// Where the leg ends.
+    pub to: RouteStop,
+    /// Walking time from `from` to `to`.
+    pub travel_duration: Duration,
+    /// Time spent at `to`, when the solver models visits, including any wait
+    /// for it to open.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub dwell: Option<Duration>,
+    /// Time from the start of the route to the end of this leg, including the
+    /// visit to `to`.
+    pub cumulative_duration: Duration,
+    /// When `to` is reached, for routes stamped with a start time by
+    /// [`Route::with_start_at`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub arrival_at: Option<Timestamp>,
+}
+
+impl RouteLeg {
+    /// Time from the start of the route until `to` is reached.
+    #[must_use]
+    pub fn arrival(&self) -> Duration {
+        self.cumulative_duration
+            .saturating_sub(self.dwell.unwrap_or_default())
+    }
+}
+
+/// An ordered path through points of interest with an overall duration.
+///
+/// A route represents a path from a start coordinate, through zero or more
+/// points of interest, to an end coordinate. The start and end may be the
+/// same location (round-trip) or different (point-to-point).
+///
+/// # Examples
+/// ```rust
+/// use geo::Coord;
+/// use std::time::Duration;
+/// use wildside_core::{PointOfInterest, Route};
+///
+/// let start = Coord { x: 0.0, y: 0.0 };
+/// let end = Coord { x: 1.0, y: 1.0 };
+/// let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.5, y: 0.5 });
+/// let route = Route::with_endpoints(start, end, vec![poi], Duration::from_mins(1));
+///
+/// assert_eq!(route.start(), start);
+/// assert_eq!(route.end(), end);
+/// assert_eq!(route.pois().len(), 1);
+/// assert_eq!(route.total_duration().as_secs(), 60);
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+pub struct Route {
+    /// Starting coordinate of the route.
+    start: Coord<f64>,
+    /// Ending coordinate of the route.
+    end: Coord<f64>,
+    /// Points of interest visited in order.
+    pois: Vec<PointOfInterest>,
+    /// Total duration of the route.
+    total_duration: Duration,
+    /// Step-by-step breakdown, empty when the solver did not provide one.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    legs: Vec<RouteLeg>,
+}
+
+impl Default for Route {
+    fn default() -> Self {
+        Self {
+            start: Coord { x: 0.0, y: 0.0 },
+            end: Coord { x: 0.0, y: 0.0 },
+            pois: Vec::new(),
+            total_duration: Duration::ZERO,
+            legs: Vec::new(),
+        }
+    }
+}
+
+impl Route {
+    /// Construct a route with explicit start and end coordinates.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use geo::Coord;
+    /// use std::time::Duration;
+    /// use wildside_core::{PointOfInterest, Route};
+    ///
+    /// let start = Coord { x: 0.0, y: 0.0 };
+    /// let end = Coord { x: 1.0, y: 1.0 };
+    /// let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.5, y: 0.5 });
+    /// let route = Route::with_endpoints(start, end, vec![poi.clone()], Duration::from_secs(30));
+    /// assert_eq!(route.start(), start);
+    /// assert_eq!(route.end(), end);
+    /// assert_eq!(route.pois(), &[poi]);
+    /// ```
+    pub fn with_endpoints(
+        start: Coord<f64>,
+        end: Coord<f64>,
+        pois: Vec<PointOfInterest>,
+        total_duration: Duration,
+    ) -> Self {
+        Self {
+            start,
+            end,
+            pois,
+            total_duration,
+            legs: Vec::new(),
+        }
+    }
+
+    /// Construct a route from points and total duration.
+    ///
+    /// The start and end coordinates default to the origin. Use
+    /// [`Route::with_endpoints`] to specify explicit start/end coordinates.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use geo::Coord;
+    /// use std::time::Duration;
+    /// use wildside_core::{PointOfInterest, Route};
+    ///
+    /// let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+    /// let route = Route::new(vec![poi.clone()], Duration::from_secs(30));
+    /// assert_eq!(route.pois(), &[poi]);
+    /// ```
+    pub fn new(pois: Vec<PointOfInterest>, total_duration: Duration) -> Self {
+        Self {
+            start: Coord { x: 0.0, y: 0.0 },
+            end: Coord { x: 0.0, y: 0.0 },
+            pois,
+            total_duration,
+            legs: Vec::new(),
+        }
+    }
+
+    /// Construct an empty route.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use wildside_core::Route;
+    ///
+    /// let route = Route::empty();
+    /// assert!(route.pois().is_empty());
+    /// assert_eq!(route.total_duration().as_secs(), 0);
+    /// ```
+    #[rustfmt::skip]
+    pub fn empty() -> Self { Self::default() }
+
+    /// Attach a step-by-step breakdown, while consuming `self`.
+    ///
+    /// Legs run in route order from [`RouteStop::Start`], through each POI,
+    /// to [`RouteStop::End`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use geo::Coord;
+    /// use std::time::Duration;
+    /// use wildside_core::{PointOfInterest, Route, RouteLeg, RouteStop};
+    ///
+    /// let origin = Coord { x: 0.0, y: 0.0 };
+    /// let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.5, y: 0.5 });
+    /// let legs = vec![
+    ///     RouteLeg {
+    ///         from: RouteStop::Start,
+    ///         to: RouteStop::Poi(1),
+    ///         travel_duration: Duration::from_mins(5),
+    ///         dwell: None,
+    ///         cumulative_duration: Duration::from_mins(5),
+    ///         arrival_at: None,
+    ///     },
+    ///     RouteLeg {
+    ///         from: RouteStop::Poi(1),
+    ///         to: RouteStop::End,
+    ///         travel_duration: Duration::from_mins(5),
+    ///         dwell: None,
+    ///         cumulative_duration: Duration::from_mins(10),
+    ///         arrival_at: None,
+    ///     },
+    /// ];
+    /// let route = Route::with_endpoints(origin, origin, vec![poi], Duration::from_mins(10))
+    ///     .with_legs(legs);
+    /// assert_eq!(route.legs().len(), 2);
+    /// ```
+    pub fn with_legs(mut self, legs: Vec<RouteLeg>) -> Self {
+        self.legs = legs;
+        self
+    }
+
+    /// Stamp each leg with the time its stop is reached on a walk starting
+    /// at `start`, while consuming `self`.
+    ///
+    /// Arrivals that fall outside the years a [`Timestamp`] supports are left
+    /// unset.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::time::Duration;
+    /// use wildside_core::{Route, RouteLeg, RouteStop, Timestamp};
+    ///
+    /// let leg = RouteLeg {
+    ///     from: RouteStop::Start,
+    ///     to: RouteStop::End,
+    ///     travel_duration: Duration::from_mins(20),
+    ///     dwell: None,
+    ///     cumulative_duration: Duration::from_mins(20),
+    ///     arrival_at: None,
+    /// };
+    /// let start: Timestamp = "2026-10-17T10:30:00+02:00".parse()?;
+    /// let route = Route::new(Vec::new(), Duration::from_mins(20))
+    ///     .with_legs(vec![leg])
+    ///     .with_start_at(start);
+    /// let arrival = route.legs()[0].arrival_at.expect("stamped");
+    /// assert_eq!(arrival.to_string(), "2026-10-17T10:50:00+02:00");
+    /// # Ok::<(), wildside_core::TimestampError>(())
+    /// ```
+    pub fn with_start_at(mut self, start: Timestamp) -> Self {
+        for leg in &mut self.legs {
+            leg.arrival_at = start.checked_add(leg.arrival());
+        }
+        self
+    }
+
+    /// Starting coordinate of the route.
+    #[rustfmt::skip]
+    pub fn start(&self) -> Coord<f64> { self.start }
+
+    /// Ending coordinate of the route.
+    #[rustfmt::skip]
+    pub fn end(&self) -> Coord<f64> { self.end }
+
+    /// Points of interest in order.
+    #[rustfmt::skip]
+    pub fn pois(&self) -> &[PointOfInterest] { &self.pois }
+
+    /// Total duration of the route.
+    #[rustfmt::skip]
+    pub fn total_duration(&self) -> Duration { self.total_duration }
+
+    /// Step-by-step breakdown of the route, if the solver provided one.
+    #[rustfmt::skip]
+    pub fn legs(&self) -> &[RouteLeg] { &self.legs }
+
+    /// Walking path of the route as traced by `provider`, from the start
+    /// through each POI to the end.
+    ///
+    /// Geometry is decoration for display, so a provider failure is logged
+    /// and the route is left without it.
+    #[must_use]
+    pub fn trace(&self, provider: &dyn TravelTimeProvider) -> Option<LineString<f64>> {
+        let stops: Vec<Coord<f64>> = iter::once(self.start)
+            .chain(self.pois.iter().map(|poi| poi.location))
+            .chain(iter::once(self.end))
+            .collect();
+        provider
+            .route_geometry(&stops)
+            .inspect_err(|error| log::warn!("Returning route without geometry: {error}"))
+            .ok()
+            .flatten()
+    }
+
+    /// Share of visited POIs two routes have in common, from `0.0` for
+    /// disjoint routes to `1.0` for the same set of POIs.
+    ///
+    /// This is the Jaccard index of the two sets of POI identifiers; visiting
+    /// order is ignored. Two routes visiting nothing count as identical.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::time::Duration;
+    /// use geo::Coord;
+    /// use wildside_core::{PointOfInterest, Route};
+    ///
+    /// let poi = |id| PointOfInterest::with_empty_tags(id, Coord { x: 0.0, y: 0.0 });
+    /// let first = Route::new(vec![poi(1), poi(2), poi(3)], Duration::from_mins(30));
+    /// let second = Route::new(vec![poi(3), poi(2), poi(4)], Duration::from_mins(30));
+    /// assert_eq!(first.overlap(&second), 0.5);
+    /// ```
+    #[must_use]
+    pub fn overlap(&self, other: &Self) -> f64 {
+        let ours: HashSet<u64> = self.pois.iter().map(|poi| poi.id).collect();
+        let theirs: HashSet<u64> = other.pois.iter().map(|poi| poi.id).collect();
+        let union = ours.union(&theirs).count();
+        if union == 0 {
+            return 1.0;
+        }
+        ours.intersection(&theirs).count() as f64 / union as f64
+    }
+}
+
+#[cfg(test)]
+mod tests;
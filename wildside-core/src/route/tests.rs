@@ -0,0 +1,97 @@
+//! Tests for route construction and endpoint preservation.
+
+use super::*;
+
+#[test]
+fn route_preserves_order() {
+    let poi1 = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+    let poi2 = PointOfInterest::with_empty_tags(2, Coord { x: 1.0, y: 1.0 });
+    let route = Route::new(vec![poi1.clone(), poi2.clone()], Duration::from_mins(2));
+    assert_eq!(route.pois(), &[poi1, poi2]);
+    assert_eq!(route.total_duration().as_secs(), 120);
+}
+
+#[test]
+fn empty_route_has_zero_duration() {
+    let route = Route::empty();
+    assert!(route.pois().is_empty());
+    assert_eq!(route.total_duration().as_secs(), 0);
+}
+
+#[test]
+fn routes_have_no_legs_until_attached() {
+    let leg = RouteLeg {
+        from: RouteStop::Start,
+        to: RouteStop::End,
+        travel_duration: Duration::from_mins(3),
+        dwell: None,
+        cumulative_duration: Duration::from_mins(3),
+        arrival_at: None,
+    };
+    let route = Route::new(Vec::new(), Duration::from_mins(3));
+    assert!(route.legs().is_empty());
+    assert_eq!(route.with_legs(vec![leg.clone()]).legs(), &[leg]);
+}
+
+#[test]
+fn arrival_excludes_the_visit() {
+    let leg = RouteLeg {
+        from: RouteStop::Poi(1),
+        to: RouteStop::Poi(2),
+        travel_duration: Duration::from_mins(3),
+        dwell: Some(Duration::from_mins(10)),
+        cumulative_duration: Duration::from_mins(30),
+        arrival_at: None,
+    };
+    assert_eq!(leg.arrival(), Duration::from_mins(20));
+}
+
+#[test]
+fn start_times_stamp_every_arrival() {
+    let leg = |to, dwell: Option<u64>, cumulative| RouteLeg {
+        from: RouteStop::Start,
+        to,
+        travel_duration: Duration::from_mins(5),
+        dwell: dwell.map(Duration::from_mins),
+        cumulative_duration: Duration::from_mins(cumulative),
+        arrival_at: None,
+    };
+    let start: Timestamp = "2026-10-17T09:00:00Z".parse().expect("parse");
+    let route = Route::new(Vec::new(), Duration::from_mins(35))
+        .with_legs(vec![
+            leg(RouteStop::Poi(1), Some(20), 25),
+            leg(RouteStop::End, None, 35),
+        ])
+        .with_start_at(start);
+    let arrivals: Vec<String> = route
+        .legs()
+        .iter()
+        .filter_map(|leg| leg.arrival_at.map(|at| at.to_string()))
+        .collect();
+    assert_eq!(arrivals, ["2026-10-17T09:05:00Z", "2026-10-17T09:35:00Z"]);
+}
+
+#[test]
+fn overlap_compares_visited_sets() {
+    let poi = |id| PointOfInterest::with_empty_tags(id, Coord { x: 0.0, y: 0.0 });
+    let route = |ids: &[u64]| {
+        Route::new(
+            ids.iter().copied().map(poi).collect(),
+            Duration::from_mins(1),
+        )
+    };
+    assert_eq!(route(&[1, 2]).overlap(&route(&[2, 1])), 1.0);
+    assert_eq!(route(&[1, 2]).overlap(&route(&[3, 4])), 0.0);
+    assert_eq!(route(&[]).overlap(&route(&[])), 1.0);
+    assert_eq!(route(&[1]).overlap(&route(&[])), 0.0);
+}
+
+#[test]
+fn route_with_endpoints_stores_coordinates() {
+    let start = Coord { x: 1.0, y: 2.0 };
+    let end = Coord { x: 3.0, y: 4.0 };
+    let poi = PointOfInterest::with_empty_tags(1, Coord { x: 2.0, y: 3.0 });
+    let route = Route::with_endpoints(start, end, vec![poi], Duration::from_mins(1));
+    assert_eq!(route.start(), start);
+    assert_eq!(route.end(), end);
+}
@@ -2,7 +2,13 @@
 //!
 //! The `Scorer` trait assigns a relevance score to a
 //! [`PointOfInterest`](crate::PointOfInterest) given a visitor's
-//! [`InterestProfile`](crate::InterestProfile).
+//! [`InterestProfile`](crate::InterestProfile). [`CompositeScorer`] blends
+//! several scorers into one.
+
+use std::fmt;
+use std::sync::Arc;
+
+use thiserror::Error;
 
 use crate::{InterestProfile, PointOfInterest};
 
@@ -45,12 +51,46 @@ pub trait Scorer: Send + Sync {
     /// Return a score for `poi` according to `profile`.
     fn score(&self, poi: &PointOfInterest, profile: &InterestProfile) -> f32;
 
+    /// Return a score for each of `pois` according to `profile`, in order.
+    ///
+    /// Each score must equal what [`Scorer::score`] returns for that POI. The
+    /// default calls [`Scorer::score`] once per POI; scorers backed by a
+    /// database override it to fetch what every POI needs in one query, so
+    /// solvers rank candidates with this method.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use geo::Coord;
+    /// use wildside_core::{InterestProfile, PointOfInterest, Scorer};
+    ///
+    /// struct IdScorer;
+    ///
+    /// impl Scorer for IdScorer {
+    ///     fn score(&self, poi: &PointOfInterest, _profile: &InterestProfile) -> f32 {
+    ///         if poi.id == 1 { 1.0 } else { 0.0 }
+    ///     }
+    /// }
+    ///
+    /// let pois = [
+    ///     PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 }),
+    ///     PointOfInterest::with_empty_tags(2, Coord { x: 0.0, y: 0.0 }),
+    /// ];
+    /// assert_eq!(IdScorer.score_many(&pois, &InterestProfile::new()), [1.0, 0.0]);
+    /// ```
+    fn score_many(&self, pois: &[PointOfInterest], profile: &InterestProfile) -> Vec<f32> {
+        pois.iter().map(|poi| self.score(poi, profile)).collect()
+    }
+
     /// Clamp and validate a raw score.
     ///
     /// Returns `0.0` for non-finite values and clamps to `0.0..=1.0`.
     #[must_use]
     #[inline]
-    fn sanitise(score: f32) -> f32 {
+    fn sanitise(score: f32) -> f32
+    where
+        Self: Sized,
+    {
         if !score.is_finite() {
             0.0
         } else {
@@ -58,3 +98,158 @@ pub trait Scorer: Send + Sync {
         }
     }
 }
+
+impl<C: Scorer + ?Sized> Scorer for Arc<C> {
+    fn score(&self, poi: &PointOfInterest, profile: &InterestProfile) -> f32 {
+        (**self).score(poi, profile)
+    }
+
+    fn score_many(&self, pois: &[PointOfInterest], profile: &InterestProfile) -> Vec<f32> {
+        (**self).score_many(pois, profile)
+    }
+}
+
+/// Errors from [`CompositeScorer::try_push`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CompositeWeightError {
+    /// Weight is below zero.
+    #[error("scorer weight must not be negative")]
+    Negative,
+    /// Weight is not finite.
+    #[error("scorer weight must be finite")]
+    NonFinite,
+}
+
+/// Weighted mean of several scorers.
+///
+/// Each component score is sanitised before blending, so a misbehaving
+/// scorer cannot push the result outside `0.0..=1.0`. A composite without
+/// components, or whose weights are all zero, scores every POI `0.0`.
+///
+/// # Examples
+///
+/// ```rust
+/// use geo::Coord;
+/// use wildside_core::{CompositeScorer, InterestProfile, PointOfInterest, Scorer};
+///
+/// struct Fixed(f32);
+///
+/// impl Scorer for Fixed {
+///     fn score(&self, _poi: &PointOfInterest, _profile: &InterestProfile) -> f32 {
+///         self.0
+///     }
+/// }
+///
+/// let scorer = CompositeScorer::new()
+///     .with_scorer(Fixed(1.0), 3.0)
+///     .with_scorer(Fixed(0.0), 1.0);
+/// let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+/// assert_eq!(scorer.score(&poi, &InterestProfile::new()), 0.75);
+/// ```
+#[derive(Default)]
+pub struct CompositeScorer {
+    components: Vec<(Box<dyn Scorer>, f32)>,
+}
+
+impl CompositeScorer {
+    /// Construct a composite without components.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate `weight` and add `scorer` as a component.
+    ///
+    /// # Errors
+    /// Returns [`CompositeWeightError::NonFinite`] if `weight` is `NaN` or
+    /// infinite, and [`CompositeWeightError::Negative`] if it is below zero.
+    pub fn try_push(
+        &mut self,
+        scorer: impl Scorer + 'static,
+        weight: f32,
+    ) -> Result<(), CompositeWeightError> {
+        if !weight.is_finite() {
+            return Err(CompositeWeightError::NonFinite);
+        }
+        if weight < 0.0 {
+            return Err(CompositeWeightError::Negative);
+        }
+        self.components.push((Box::new(scorer), weight));
+        Ok(())
+    }
+
+    /// Add a component while returning `self` for chaining.
+    ///
+    /// # Panics
+    /// Panics if `weight` is negative or not finite (NaN/∞).
+    #[must_use]
+    #[track_caller]
+    pub fn with_scorer(mut self, scorer: impl Scorer + 'static, weight: f32) -> Self {
+        // Panic explicitly, as `InterestProfile::set_weight` does; fallible
+        // callers should use `try_push` to propagate the error instead.
+        if let Err(error) = self.try_push(scorer, weight) {
+            panic!("scorer weight must be finite and non-negative: {error}");
+        }
+        self
+    }
+
+    /// Return the number of components.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.components.len()
+    }
+
+    /// Return `true` when the composite has no components.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.components.is_empty()
+    }
+
+    fn total_weight(&self) -> f32 {
+        self.components.iter().map(|(_, weight)| weight).sum()
+    }
+}
+
+impl fmt::Debug for CompositeScorer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let weights: Vec<f32> = self.components.iter().map(|(_, weight)| *weight).collect();
+        f.debug_struct("CompositeScorer")
+            .field("weights", &weights)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Scorer for CompositeScorer {
+    fn score(&self, poi: &PointOfInterest, profile: &InterestProfile) -> f32 {
+        let total = self.total_weight();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        let blended: f32 = self
+            .components
+            .iter()
+            .map(|(scorer, weight)| Self::sanitise(scorer.score(poi, profile)) * weight)
+            .sum();
+        Self::sanitise(blended / total)
+    }
+
+    /// Score `pois` with each component's own [`Scorer::score_many`], so
+    /// batching components keep their single-query lookups.
+    fn score_many(&self, pois: &[PointOfInterest], profile: &InterestProfile) -> Vec<f32> {
+        let total = self.total_weight();
+        if total <= 0.0 {
+            return vec![0.0; pois.len()];
+        }
+        let mut blended = vec![0.0_f32; pois.len()];
+        for (scorer, weight) in &self.components {
+            let scores = scorer.score_many(pois, profile);
+            for (sum, score) in blended.iter_mut().zip(scores) {
+                *sum += Self::sanitise(score) * weight;
+            }
+        }
+        blended
+            .into_iter()
+            .map(|sum| Self::sanitise(sum / total))
+            .collect()
+    }
+}
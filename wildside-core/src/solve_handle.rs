@@ -0,0 +1,259 @@
+//! Background solves that can be inspected and cut short.
+//!
+//! [`SolveHandle`] runs [`Solver::solve_with_progress`] on a worker thread and
+//! records the latest [`SolveProgress`]. Interactive callers poll
+//! [`SolveHandle::best_so_far`] to drive an "improving route…" indicator and
+//! call [`SolveHandle::finish_now`] to accept the current best route instead
+//! of waiting for the search to run its course.
+
+use std::ops::ControlFlow;
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::{self, JoinHandle};
+
+use crate::{SolveError, SolveProgress, SolveRequest, SolveResponse, Solver};
+
+/// State shared between a [`SolveHandle`] and its worker thread.
+#[derive(Debug, Default)]
+struct Shared {
+    latest: Mutex<Option<SolveProgress>>,
+    stop: AtomicBool,
+}
+
+impl Shared {
+    /// Store `progress` and tell the solver whether to keep searching.
+    fn record(&self, progress: &SolveProgress) -> ControlFlow<()> {
+        *self.latest.lock().unwrap_or_else(PoisonError::into_inner) = Some(*progress);
+        if self.stop.load(Ordering::Relaxed) {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+}
+
+/// Handle to a solve running on a background thread.
+///
+/// Dropping the handle asks the solver to stop but does not wait for it.
+///
+/// # Examples
+/// ```rust
+/// use std::sync::Arc;
+/// use geo::Coord;
+/// use wildside_core::{
+///     Diagnostics, InterestProfile, Route, SolveError, SolveHandle, SolveRequest,
+///     SolveResponse, Solver,
+/// };
+///
+/// struct Fixed;
+/// impl Solver for Fixed {
+///     fn solve(&self, _request: &SolveRequest) -> Result<SolveResponse, SolveError> {
+///         Ok(SolveResponse {
+///             route: Route::empty(),
+///             score: 2.5,
+///             diagnostics: Diagnostics::default(),
+///             geometry: None,
+///         })
+///     }
+/// }
+///
+/// let request = SolveRequest {
+///     start: Coord { x: 0.0, y: 0.0 },
+///     duration_minutes: 30,
+///     interests: InterestProfile::new(),
+///     seed: 1,
+///     ..Default::default()
+/// };
+/// let handle = SolveHandle::spawn(Arc::new(Fixed), request);
+/// let response = handle.finish_now()?;
+/// assert_eq!(response.score, 2.5);
+/// # Ok::<(), SolveError>(())
+/// ```
+#[derive(Debug)]
+pub struct SolveHandle {
+    shared: Arc<Shared>,
+    worker: Option<JoinHandle<Result<SolveResponse, SolveError>>>,
+}
+
+impl SolveHandle {
+    /// Start solving `request` with `solver` on a new thread.
+    pub fn spawn<S>(solver: Arc<S>, request: SolveRequest) -> Self
+    where
+        S: Solver + ?Sized + 'static,
+    {
+        let shared = Arc::new(Shared::default());
+        let worker_shared = Arc::clone(&shared);
+        let worker = thread::spawn(move || {
+            solver.solve_with_progress(&request, &mut |progress| worker_shared.record(progress))
+        });
+        Self {
+            shared,
+            worker: Some(worker),
+        }
+    }
+
+    /// Return the most recent progress snapshot, if any has been reported.
+    ///
+    /// The snapshot describes the best route found so far; call
+    /// [`finish_now`](Self::finish_now) to take that route.
+    #[must_use]
+    pub fn best_so_far(&self) -> Option<SolveProgress> {
+        *self
+            .shared
+            .latest
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Report whether the solver has returned.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.worker.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    /// Ask the solver to stop and return its current best route.
+    ///
+    /// The solver honours the request at its next progress checkpoint, so
+    /// this blocks for at most one further generation.
+    ///
+    /// # Errors
+    /// Propagates the [`SolveError`] returned by the solver.
+    pub fn finish_now(mut self) -> Result<SolveResponse, SolveError> {
+        self.shared.stop.store(true, Ordering::Relaxed);
+        self.join()
+    }
+
+    /// Wait for the solver to finish without interrupting it.
+    ///
+    /// # Errors
+    /// Propagates the [`SolveError`] returned by the solver.
+    pub fn wait(mut self) -> Result<SolveResponse, SolveError> {
+        self.join()
+    }
+
+    fn join(&mut self) -> Result<SolveResponse, SolveError> {
+        let worker = self
+            .worker
+            .take()
+            .expect("SolveHandle worker is only taken by consuming methods");
+        worker
+            .join()
+            .unwrap_or_else(|payload| panic::resume_unwind(payload))
+    }
+}
+
+impl Drop for SolveHandle {
+    fn drop(&mut self) {
+        self.shared.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for background solves and early acceptance.
+
+    use std::time::Duration;
+
+    use geo::Coord;
+    use rstest::{fixture, rstest};
+
+    use super::*;
+    use crate::{Diagnostics, InterestProfile, ProgressObserver, Route};
+
+    /// Improves its score by one per generation, pausing between generations.
+    struct CountingSolver {
+        generations: usize,
+        pause: Duration,
+    }
+
+    impl Solver for CountingSolver {
+        fn solve(&self, request: &SolveRequest) -> Result<SolveResponse, SolveError> {
+            self.solve_with_progress(request, &mut |_| ControlFlow::Continue(()))
+        }
+
+        fn solve_with_progress(
+            &self,
+            _request: &SolveRequest,
+            on_progress: &mut ProgressObserver<'_>,
+        ) -> Result<SolveResponse, SolveError> {
+            let last_generation = (1..=self.generations)
+                .inspect(|_| thread::sleep(self.pause))
+                .find(|&generation| on_progress(&snapshot(generation)).is_break())
+                .unwrap_or(self.generations);
+            let best_score = last_generation as f32;
+            Ok(SolveResponse {
+                route: Route::empty(),
+                score: best_score,
+                diagnostics: Diagnostics::default(),
+                geometry: None,
+            })
+        }
+    }
+
+    fn snapshot(generation: usize) -> SolveProgress {
+        SolveProgress {
+            generation,
+            best_score: generation as f32,
+            best_stops: generation,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    #[fixture]
+    fn request() -> SolveRequest {
+        SolveRequest {
+            start: Coord { x: 0.0, y: 0.0 },
+            duration_minutes: 30,
+            interests: InterestProfile::new(),
+            seed: 1,
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    fn finish_now_returns_best_so_far(request: SolveRequest) {
+        let solver = Arc::new(CountingSolver {
+            generations: 100_000,
+            pause: Duration::from_millis(1),
+        });
+        let handle = SolveHandle::spawn(solver, request);
+
+        let progress = loop {
+            if let Some(progress) = handle.best_so_far()
+                && progress.generation >= 3
+            {
+                break progress;
+            }
+            thread::yield_now();
+        };
+        assert!(!handle.is_finished());
+
+        let response = handle.finish_now().expect("solve should succeed");
+        assert!(response.score >= progress.best_score);
+        assert!(response.score < 100_000.0);
+    }
+
+    #[rstest]
+    fn wait_runs_to_completion(request: SolveRequest) {
+        let solver = Arc::new(CountingSolver {
+            generations: 5,
+            pause: Duration::ZERO,
+        });
+        let handle = SolveHandle::spawn(solver, request);
+
+        let response = handle.wait().expect("solve should succeed");
+        assert!((response.score - 5.0).abs() < f32::EPSILON);
+    }
+
+    #[rstest]
+    fn best_so_far_is_empty_before_progress(request: SolveRequest) {
+        let solver = Arc::new(CountingSolver {
+            generations: 1,
+            pause: Duration::from_secs(60),
+        });
+        let handle = SolveHandle::spawn(solver, request);
+
+        assert!(handle.best_so_far().is_none());
+    }
+}
@@ -0,0 +1,239 @@
+//! Candidates a request may visit and how solvers lay them out.
+//!
+//! Every backend scores the POIs near a request, keeps the best of them
+//! alongside any required POIs, and asks for one travel-time matrix over the
+//! start, the candidates, and the end. These helpers keep those steps
+//! identical across backends.
+
+use std::collections::HashSet;
+use std::iter;
+
+use super::{SolveError, SolveRequest};
+use crate::{PoiStore, PointOfInterest, Scorer, WalkingReach};
+
+/// Synthetic POI ID for the start location in a travel-time matrix.
+pub const START_POI_ID: u64 = 0;
+
+/// Synthetic POI ID for the end location of point-to-point routes.
+///
+/// Stays clear of real POI IDs and of `u64::MAX`, which the SQLite
+/// persistence layer rejects.
+pub const END_POI_ID: u64 = u64::MAX - 1;
+
+impl SolveRequest {
+    /// Score the POIs within walking reach of either endpoint at
+    /// `speed_kmh`, plus any required POIs further away, best first and at
+    /// most [`SolveRequest::max_nodes`] of them besides the required ones.
+    ///
+    /// Optional POIs scoring nothing cannot improve a route and are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::Store`] when `store` fails.
+    pub fn walking_candidates<S, C>(
+        &self,
+        store: &S,
+        scorer: &C,
+        speed_kmh: f64,
+    ) -> Result<Vec<(PointOfInterest, f32)>, SolveError>
+    where
+        S: PoiStore + ?Sized,
+        C: Scorer + ?Sized,
+    {
+        let reach = WalkingReach::new(self, speed_kmh);
+        let mut pois: Vec<PointOfInterest> = store
+            .get_pois_in_bbox(&reach.bounds())?
+            .filter(|poi| reach.contains(poi.location) && !self.excludes(poi))
+            .collect();
+        self.add_required(store, &mut pois)?;
+
+        let relevance = scorer.score_many(&pois, &self.interests);
+        let scored = pois
+            .into_iter()
+            .zip(relevance)
+            .filter(|(poi, score)| *score > 0.0 || self.required_poi_ids.contains(&poi.id))
+            .collect();
+        Ok(self.rank_candidates(scored))
+    }
+
+    /// Append the required POIs missing from `pois`, as looked up in
+    /// `store`, unless the request excludes them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::Store`] when `store` fails.
+    pub fn add_required<S>(
+        &self,
+        store: &S,
+        pois: &mut Vec<PointOfInterest>,
+    ) -> Result<(), SolveError>
+    where
+        S: PoiStore + ?Sized,
+    {
+        let mut missing: Vec<u64> = self
+            .required_poi_ids
+            .iter()
+            .copied()
+            .filter(|id| !pois.iter().any(|poi| poi.id == *id))
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        missing.sort_unstable();
+        missing.dedup();
+        let found = store.get_pois_by_ids(&missing)?;
+        pois.extend(found.filter(|poi| !self.excludes(poi)));
+        Ok(())
+    }
+
+    /// Order `scored` best first, ties broken by POI ID, and keep every
+    /// required candidate and at most [`SolveRequest::max_nodes`] candidates
+    /// overall, filling the remaining slots with the best optional ones.
+    #[must_use]
+    pub fn rank_candidates(
+        &self,
+        mut scored: Vec<(PointOfInterest, f32)>,
+    ) -> Vec<(PointOfInterest, f32)> {
+        scored.sort_unstable_by(|(lhs_poi, lhs_score), (rhs_poi, rhs_score)| {
+            rhs_score
+                .total_cmp(lhs_score)
+                .then_with(|| lhs_poi.id.cmp(&rhs_poi.id))
+        });
+        let Some(max_nodes) = self.max_nodes else {
+            return scored;
+        };
+        let required = &self.required_poi_ids;
+        let required_count = scored
+            .iter()
+            .filter(|(poi, _)| required.contains(&poi.id))
+            .count();
+        let mut optional_slots = usize::from(max_nodes).saturating_sub(required_count);
+        scored.retain(|(poi, _)| {
+            if required.contains(&poi.id) {
+                return true;
+            }
+            let keep = optional_slots > 0;
+            optional_slots = optional_slots.saturating_sub(1);
+            keep
+        });
+        scored
+    }
+
+    /// Fail with [`SolveError::InfeasibleRequirement`] unless every required
+    /// POI is among `pois`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::InfeasibleRequirement`] when a required POI is
+    /// missing.
+    pub fn ensure_required<'a>(
+        &self,
+        pois: impl IntoIterator<Item = &'a PointOfInterest>,
+    ) -> Result<(), SolveError> {
+        if self.required_poi_ids.is_empty() {
+            return Ok(());
+        }
+        let present: HashSet<u64> = pois.into_iter().map(|poi| poi.id).collect();
+        let missing: Vec<u64> = self
+            .required_poi_ids
+            .iter()
+            .copied()
+            .filter(|id| !present.contains(id))
+            .collect();
+        if missing.is_empty() {
+            Ok(())
+        } else {
+            log::debug!("Required POIs {missing:?} cannot be visited");
+            Err(SolveError::InfeasibleRequirement)
+        }
+    }
+
+    /// Travel-time matrix layout for `candidates`: the start as
+    /// [`START_POI_ID`], each candidate, then the end of a point-to-point
+    /// route as [`END_POI_ID`].
+    #[must_use]
+    pub fn matrix_locations(&self, candidates: &[PointOfInterest]) -> Vec<PointOfInterest> {
+        iter::once(PointOfInterest::with_empty_tags(START_POI_ID, self.start))
+            .chain(candidates.iter().cloned())
+            .chain(
+                self.end
+                    .map(|end| PointOfInterest::with_empty_tags(END_POI_ID, end)),
+            )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for candidate ranking and matrix layout.
+
+    use geo::Coord;
+    use rstest::rstest;
+
+    use super::*;
+    use crate::InterestProfile;
+
+    fn poi(id: u64) -> PointOfInterest {
+        PointOfInterest::with_empty_tags(id, Coord { x: 0.0, y: 0.0 })
+    }
+
+    fn request() -> SolveRequest {
+        SolveRequest {
+            start: Coord { x: 0.0, y: 0.0 },
+            duration_minutes: 60,
+            interests: InterestProfile::new(),
+            seed: 1,
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    fn ranking_keeps_required_candidates_beyond_the_node_limit() {
+        let request = SolveRequest {
+            max_nodes: Some(2),
+            required_poi_ids: vec![4],
+            ..request()
+        };
+        let ranked = request.rank_candidates(vec![
+            (poi(4), 0.1),
+            (poi(2), 0.9),
+            (poi(1), 0.9),
+            (poi(3), 0.5),
+        ]);
+        let ids: Vec<u64> = ranked.iter().map(|(poi, _)| poi.id).collect();
+        assert_eq!(ids, vec![1, 4]);
+    }
+
+    #[rstest]
+    fn missing_required_pois_are_infeasible() {
+        let request = SolveRequest {
+            required_poi_ids: vec![1, 2],
+            ..request()
+        };
+        assert!(request.ensure_required(&[poi(1), poi(2)]).is_ok());
+        assert!(matches!(
+            request.ensure_required(&[poi(1)]),
+            Err(SolveError::InfeasibleRequirement)
+        ));
+    }
+
+    #[rstest]
+    fn matrix_locations_bracket_candidates_with_the_endpoints() {
+        let end = Coord { x: 1.0, y: 1.0 };
+        let round_trip: Vec<u64> = request()
+            .matrix_locations(&[poi(5)])
+            .iter()
+            .map(|poi| poi.id)
+            .collect();
+        assert_eq!(round_trip, vec![START_POI_ID, 5]);
+        let commute = SolveRequest {
+            end: Some(end),
+            ..request()
+        }
+        .matrix_locations(&[poi(5)]);
+        assert_eq!(
+            commute.last().map(|poi| (poi.id, poi.location)),
+            Some((END_POI_ID, end))
+        );
+    }
+}
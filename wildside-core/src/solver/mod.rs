@@ -0,0 +1,365 @@
+//! Solver API: request/response types, error, and trait.
+//! Implementations MUST be Send + Sync and return InvalidRequest for bad inputs.
+//! Use [`SolveRequest::validate`] to enforce basic invariants.
+
+mod candidates;
+mod request;
+mod response;
+mod validation;
+mod variants;
+
+use std::ops::ControlFlow;
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::{CancellationToken, PoiStoreError, TravelTimeError};
+pub use candidates::{END_POI_ID, START_POI_ID};
+pub use request::{SolveRequest, VisitedPrefix};
+pub use response::{Diagnostics, Shortfall, SolveResponse, StartChoice, pareto_frontier};
+pub use validation::SolveRequestValidationError;
+
+/// Errors returned by [`Solver::solve`].
+///
+/// Failures of the solver's collaborators keep their cause, available
+/// through [`std::error::Error::source`].
+#[derive(Debug, Error)]
+pub enum SolveError {
+    /// Request parameters were invalid, e.g. zero duration or non-finite coordinates.
+    #[error("invalid request")]
+    InvalidRequest,
+    /// Solver implementation is not yet available.
+    #[error("solver not implemented")]
+    NotImplemented,
+    /// The POI store could not be queried for candidates.
+    #[error("POI store query failed: {0}")]
+    Store(#[from] PoiStoreError),
+    /// The travel-time provider could not supply travel times between the
+    /// candidates.
+    #[error("travel-time provider failed: {0}")]
+    TravelTime(#[from] TravelTimeError),
+    /// The solve's time limit passed before any route was found.
+    #[error("solve timed out before finding a route")]
+    Timeout,
+    /// Some required POIs could not be fitted into a route within the budget.
+    #[error("required POIs cannot be visited within the time budget")]
+    InfeasibleRequirement,
+    /// No route satisfies the request's constraints, for example because the
+    /// end cannot be reached within the budget or the best route falls short
+    /// of [`SolveRequest::min_pois`] or [`SolveRequest::min_score`].
+    #[error(
+        "no route satisfies the request; the best found visits {} POIs scoring {}",
+        .0.pois,
+        .0.score
+    )]
+    Infeasible(Shortfall),
+    /// The caller cancelled the solve through a
+    /// [`CancellationToken`](crate::CancellationToken).
+    #[error("solve cancelled")]
+    Cancelled,
+    /// The solver failed for a reason unrelated to the request, such as an
+    /// error inside its optimization backend.
+    #[error("solver failed: {0}")]
+    Internal(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl SolveError {
+    /// Wrap an unexpected failure of the solver's backend.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::error::Error as _;
+    ///
+    /// use wildside_core::SolveError;
+    ///
+    /// let error = SolveError::internal(std::fmt::Error);
+    /// assert!(matches!(error, SolveError::Internal(_)));
+    /// assert!(error.source().is_some());
+    /// ```
+    pub fn internal<E>(source: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        Self::Internal(source.into())
+    }
+}
+
+/// Snapshot of an in-progress solve reported to progress observers.
+///
+/// Solvers that iterate (e.g. metaheuristics) report one snapshot per
+/// generation; single-shot solvers report a single final snapshot.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SolveProgress {
+    /// Number of completed search generations.
+    pub generation: usize,
+    /// Score of the best route found so far.
+    pub best_score: f32,
+    /// Number of POIs the best route found so far visits.
+    pub best_stops: usize,
+    /// Time elapsed since the solve started.
+    pub elapsed: Duration,
+}
+
+/// Observer invoked with each [`SolveProgress`] snapshot.
+///
+/// Returning [`ControlFlow::Break`] asks the solver to stop searching and
+/// return its current best route. Solvers honour the request at their next
+/// checkpoint, so a few further snapshots may still arrive.
+pub type ProgressObserver<'a> = dyn FnMut(&SolveProgress) -> ControlFlow<()> + 'a;
+
+/// Find a route satisfying the caller's preferences and constraints.
+///
+/// Implementations should return [`SolveError::InvalidRequest`] for invalid
+/// parameters rather than panicking. Placeholder solvers may return
+/// [`SolveError::NotImplemented`] until a backend is available.
+///
+/// # Thread Safety
+/// Implementations must avoid shared mutable state or use proper synchronization
+/// to ensure thread safety. Solvers must be `Send + Sync` to operate safely across threads.
+pub trait Solver: Send + Sync {
+    /// Solve a request, producing a route or an error.
+    fn solve(&self, request: &SolveRequest) -> Result<SolveResponse, SolveError>;
+
+    /// Solve a request while reporting progress to `on_progress`.
+    ///
+    /// The observer runs on the calling thread. To stream progress elsewhere,
+    /// forward each snapshot over a channel from inside the closure. The
+    /// default implementation solves normally and reports one final snapshot.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::ops::ControlFlow;
+    /// use std::sync::mpsc;
+    /// use geo::Coord;
+    /// use wildside_core::{
+    ///     Diagnostics, InterestProfile, Route, SolveError, SolveRequest, SolveResponse, Solver,
+    /// };
+    ///
+    /// struct Fixed;
+    /// impl Solver for Fixed {
+    ///     fn solve(&self, _request: &SolveRequest) -> Result<SolveResponse, SolveError> {
+    ///         Ok(SolveResponse {
+    ///             route: Route::empty(),
+    ///             score: 2.5,
+    ///             diagnostics: Diagnostics::default(),
+    ///             geometry: None,
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let request = SolveRequest {
+    ///     start: Coord { x: 0.0, y: 0.0 },
+    ///     duration_minutes: 30,
+    ///     interests: InterestProfile::new(),
+    ///     seed: 1,
+    ///     ..Default::default()
+    /// };
+    /// let (sender, receiver) = mpsc::channel();
+    /// Fixed.solve_with_progress(&request, &mut |progress| {
+    ///     let _ = sender.send(*progress);
+    ///     ControlFlow::Continue(())
+    /// })?;
+    /// assert_eq!(receiver.recv().map(|progress| progress.best_score), Ok(2.5));
+    /// # Ok::<(), SolveError>(())
+    /// ```
+    fn solve_with_progress(
+        &self,
+        request: &SolveRequest,
+        on_progress: &mut ProgressObserver<'_>,
+    ) -> Result<SolveResponse, SolveError> {
+        let response = self.solve(request)?;
+        let _ = on_progress(&SolveProgress {
+            generation: 0,
+            best_score: response.score,
+            best_stops: response.route.pois().len(),
+            elapsed: response.diagnostics.solve_time,
+        });
+        Ok(response)
+    }
+
+    /// Solve a request for up to `count` distinct routes, best first.
+    ///
+    /// Solvers that can search for alternatives return routes that differ in
+    /// the POIs they visit, so a user interface can offer a choice of walks.
+    /// Fewer than `count` routes come back when no more distinct ones are
+    /// found. The default implementation returns the single route from
+    /// [`Solver::solve`], or none when `count` is zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`Solver::solve`].
+    fn solve_alternatives(
+        &self,
+        request: &SolveRequest,
+        count: usize,
+    ) -> Result<Vec<SolveResponse>, SolveError> {
+        if count == 0 {
+            request.validate()?;
+            return Ok(Vec::new());
+        }
+        Ok(vec![self.solve(request)?])
+    }
+
+    /// Solve a request once per duration budget and return the
+    /// [`pareto_frontier`] of the results.
+    ///
+    /// Each budget in minutes replaces [`SolveRequest::duration_minutes`], so
+    /// one call can offer, say, 60, 90, and 120-minute variants of a walk.
+    /// Budgets whose best route is no better than a shorter one's are dropped,
+    /// and no budgets means no routes.
+    /// The default implementation calls [`Solver::solve`] for every budget;
+    /// solvers can share work such as candidate selection between them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::InvalidRequest`] when any budget is zero, and the
+    /// same errors as [`Solver::solve`] otherwise.
+    fn solve_frontier(
+        &self,
+        request: &SolveRequest,
+        budgets: &[u16],
+    ) -> Result<Vec<SolveResponse>, SolveError> {
+        let responses = request
+            .with_budgets(budgets)?
+            .iter()
+            .map(|budgeted| self.solve(budgeted))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(pareto_frontier(responses))
+    }
+
+    /// Solve a request from whichever of `starts` gives the best route.
+    ///
+    /// Each start replaces [`SolveRequest::start`], so a walk can begin at
+    /// whichever of several nearby transit stops suits it best; round trips
+    /// return to the start chosen. The best route is picked as by
+    /// [`StartChoice::best`]. The default implementation calls
+    /// [`Solver::solve`] for every start; solvers can share work such as
+    /// travel-time requests between them.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::InvalidRequest`] when `starts` is empty or any
+    /// start is not finite, an infeasibility error when no start has a
+    /// feasible route, and the same errors as [`Solver::solve`] otherwise.
+    fn solve_from_starts(
+        &self,
+        request: &SolveRequest,
+        starts: &[geo::Coord<f64>],
+    ) -> Result<StartChoice, SolveError> {
+        let requests = request.with_starts(starts)?;
+        StartChoice::best(requests.iter().map(|moved| self.solve(moved)))
+    }
+
+    /// Solve a request unless `cancel` is cancelled first.
+    ///
+    /// Once the token is cancelled the solve stops at its next checkpoint and
+    /// returns [`SolveError::Cancelled`] rather than a partial route. The
+    /// default implementation checks the token before solving, at every
+    /// [`solve_with_progress`](Solver::solve_with_progress) snapshot, and
+    /// after solving, so single-shot solvers only notice cancellation once
+    /// they finish.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::Cancelled`] when the token is cancelled before
+    /// the solve completes, and the solver's own errors otherwise.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use geo::Coord;
+    /// use wildside_core::{
+    ///     CancellationToken, Diagnostics, InterestProfile, Route, SolveError, SolveRequest,
+    ///     SolveResponse, Solver,
+    /// };
+    ///
+    /// struct Fixed;
+    /// impl Solver for Fixed {
+    ///     fn solve(&self, _request: &SolveRequest) -> Result<SolveResponse, SolveError> {
+    ///         Ok(SolveResponse {
+    ///             route: Route::empty(),
+    ///             score: 2.5,
+    ///             diagnostics: Diagnostics::default(),
+    ///             geometry: None,
+    ///         })
+    ///     }
+    /// }
+    ///
+    /// let request = SolveRequest {
+    ///     start: Coord { x: 0.0, y: 0.0 },
+    ///     duration_minutes: 30,
+    ///     interests: InterestProfile::new(),
+    ///     seed: 1,
+    ///     ..Default::default()
+    /// };
+    /// let token = CancellationToken::new();
+    /// assert!(Fixed.solve_with_cancel(&request, &token).is_ok());
+    ///
+    /// token.cancel();
+    /// assert!(matches!(
+    ///     Fixed.solve_with_cancel(&request, &token),
+    ///     Err(SolveError::Cancelled)
+    /// ));
+    /// ```
+    fn solve_with_cancel(
+        &self,
+        request: &SolveRequest,
+        cancel: &CancellationToken,
+    ) -> Result<SolveResponse, SolveError> {
+        cancel.check()?;
+        let response = self.solve_with_progress(request, &mut |_| {
+            if cancel.is_cancelled() {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        })?;
+        cancel.check()?;
+        Ok(response)
+    }
+}
+
+impl<V: Solver + ?Sized> Solver for Box<V> {
+    fn solve(&self, request: &SolveRequest) -> Result<SolveResponse, SolveError> {
+        (**self).solve(request)
+    }
+
+    fn solve_with_progress(
+        &self,
+        request: &SolveRequest,
+        on_progress: &mut ProgressObserver<'_>,
+    ) -> Result<SolveResponse, SolveError> {
+        (**self).solve_with_progress(request, on_progress)
+    }
+
+    fn solve_alternatives(
+        &self,
+        request: &SolveRequest,
+        count: usize,
+    ) -> Result<Vec<SolveResponse>, SolveError> {
+        (**self).solve_alternatives(request, count)
+    }
+
+    fn solve_frontier(
+        &self,
+        request: &SolveRequest,
+        budgets: &[u16],
+    ) -> Result<Vec<SolveResponse>, SolveError> {
+        (**self).solve_frontier(request, budgets)
+    }
+
+    fn solve_from_starts(
+        &self,
+        request: &SolveRequest,
+        starts: &[geo::Coord<f64>],
+    ) -> Result<StartChoice, SolveError> {
+        (**self).solve_from_starts(request, starts)
+    }
+
+    fn solve_with_cancel(
+        &self,
+        request: &SolveRequest,
+        cancel: &CancellationToken,
+    ) -> Result<SolveResponse, SolveError> {
+        (**self).solve_with_cancel(request, cancel)
+    }
+}
@@ -0,0 +1,345 @@
+//! Parameters of a solve and the questions solvers ask of them.
+
+use std::time::Duration;
+
+use geo::{Intersects, Polygon};
+
+#[cfg(doc)]
+use super::{SolveError, Solver};
+
+use crate::{
+    AccessibilityRequirements, InterestProfile, PointOfInterest, RestBreak, Timestamp, WeekTime,
+};
+
+/// Parameters for a solve request.
+///
+/// The request captures the starting point, the time budget in minutes, the
+/// caller's interests and a random seed for deterministic results. Optionally,
+/// callers can provide an end location to request point-to-point routing.
+///
+/// # Examples
+/// ```rust
+/// use geo::Coord;
+/// use wildside_core::{InterestProfile, SolveRequest};
+///
+/// let request = SolveRequest {
+///     start: Coord { x: 0.0, y: 0.0 },
+///     duration_minutes: 30,
+///     interests: InterestProfile::new(),
+///     seed: 1,
+///     max_nodes: Some(50),
+///     ..Default::default()
+/// };
+/// assert_eq!(request.duration_minutes, 30);
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SolveRequest {
+    /// Start location for the tour.
+    pub start: geo::Coord<f64>,
+    /// Optional end location for point-to-point routes.
+    ///
+    /// When set, solvers should treat the tour as starting at [`SolveRequest::start`]
+    /// and finishing at `end` rather than returning to the start location.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub end: Option<geo::Coord<f64>>,
+    /// Time budget in minutes.
+    pub duration_minutes: u16,
+    /// Visitor interest profile guiding POI selection.
+    pub interests: InterestProfile,
+    /// Seed for reproducible stochastic components.
+    pub seed: u64,
+    /// Optional upper bound on candidate POIs considered by the solver.
+    ///
+    /// This is a hint to prune expensive searches. A value of zero is
+    /// rejected by [`SolveRequest::validate`]; `None` leaves the solver free
+    /// to choose its own limits.
+    pub max_nodes: Option<u16>,
+    /// Local time at which the walk starts.
+    ///
+    /// When set, solvers only schedule visits while a POI is open according
+    /// to its `opening_hours` tag. `None` ignores opening hours unless
+    /// [`SolveRequest::start_at`] is set; the two cannot both be set.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub start_time: Option<WeekTime>,
+    /// Identifiers of POIs the route must visit.
+    ///
+    /// Required POIs are candidates wherever they lie and regardless of
+    /// `max_nodes`. Solvers return [`SolveError::InfeasibleRequirement`] when
+    /// any of them is unknown or cannot be visited within the budget.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub required_poi_ids: Vec<u64>,
+    /// Identifiers of POIs the route must not visit.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub excluded_poi_ids: Vec<u64>,
+    /// Areas to keep out of, such as construction zones.
+    ///
+    /// POIs inside an area or on its boundary are not candidates. Walking
+    /// legs between candidates may still cross an area, as travel times come
+    /// from the routing backend.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub avoid_areas: Vec<Polygon<f64>>,
+    /// Optional wall-clock limit on the solve, in milliseconds.
+    ///
+    /// Solvers that support it stop searching once the limit has passed and
+    /// return the best route found so far. Solvers may apply a tighter limit
+    /// of their own. A value of zero is rejected by
+    /// [`SolveRequest::validate`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub max_solve_ms: Option<u32>,
+    /// Optional limit on the walk between consecutive stops, in minutes.
+    ///
+    /// Visitors who need to rest often can cap each leg of the route,
+    /// including those from the start and to the end. Solvers that support
+    /// it never chain two stops further apart than this, and return
+    /// [`SolveError::Infeasible`] when no route keeps to it. Solvers may
+    /// apply a tighter limit of their own. A value of zero is rejected by
+    /// [`SolveRequest::validate`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub max_leg_minutes: Option<u16>,
+    /// Fewest POIs an acceptable route visits.
+    ///
+    /// When the best route found visits fewer, [`Solver::solve`] fails with
+    /// [`SolveError::Infeasible`] rather than returning a near-empty walk.
+    /// See [`SolveRequest::ensure_minimums`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub min_pois: Option<u16>,
+    /// Lowest score an acceptable route collects.
+    ///
+    /// When the best route found scores less, [`Solver::solve`] fails with
+    /// [`SolveError::Infeasible`]. The threshold must be finite.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub min_score: Option<f32>,
+    /// Identifiers of the POIs of a previous route, in visiting order, to
+    /// start the search from.
+    ///
+    /// When re-solving after a small change, such as a tweaked interest or a
+    /// slightly different budget, the previous route is usually close to the
+    /// new best one. Solvers that support warm starts begin from the stops of
+    /// this route that are still candidates and still fit, and search only
+    /// for improvements. The hint never constrains the result; an empty list
+    /// starts from scratch.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub initial_route: Vec<u64>,
+    /// Progress through a tour already under way, to re-plan its remainder.
+    ///
+    /// Solvers that support re-planning keep the visited POIs at the front of
+    /// the route and search only the rest of the budget, from the visitor's
+    /// current position. See [`SolveRequest::remainder`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub visited: Option<VisitedPrefix>,
+    /// Accessibility needs the tour must meet.
+    ///
+    /// POIs without the required wheelchair access are not candidates, and
+    /// step-free requests route with the travel-time provider's step-free
+    /// profile, failing with [`SolveError::TravelTime`] when it has none.
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            skip_serializing_if = "AccessibilityRequirements::is_unrestricted"
+        )
+    )]
+    pub accessibility: AccessibilityRequirements,
+    /// Date and time at which the walk starts.
+    ///
+    /// Solvers stamp each [`RouteLeg`](crate::RouteLeg) of the route with its
+    /// estimated arrival, and check opening hours against the local time it
+    /// gives. Cannot be set together with [`SolveRequest::start_time`]. See
+    /// [`SolveRequest::start_week_time`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub start_at: Option<Timestamp>,
+    /// A pause to reserve during the walk, such as lunch.
+    ///
+    /// Solvers that support breaks bias the route to pass a café or
+    /// restaurant while the break may begin, and count the break as time
+    /// spent there. Needs [`SolveRequest::start_time`] or
+    /// [`SolveRequest::start_at`] to place the break window.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub rest_break: Option<RestBreak>,
+    /// Limit on a point-to-point walk, as a multiple of the direct walk from
+    /// [`SolveRequest::start`] to [`SolveRequest::end`].
+    ///
+    /// A factor of 1.5 keeps a commute with sightseeing within half as long
+    /// again as walking straight there, however large the budget. Solvers
+    /// that support it walk within the tighter of this and
+    /// [`SolveRequest::duration_minutes`]; see
+    /// [`SolveRequest::detour_budget`]. The factor must be finite and at
+    /// least 1, and round trips are rejected by [`SolveRequest::validate`].
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub max_detour: Option<f32>,
+}
+
+/// The part of a tour already walked, which re-planning leaves unchanged.
+///
+/// # Examples
+/// ```rust
+/// use geo::Coord;
+/// use wildside_core::VisitedPrefix;
+///
+/// let visited = VisitedPrefix {
+///     poi_ids: vec![4, 9],
+///     position: Coord { x: 0.01, y: 0.0 },
+///     elapsed_minutes: 25,
+/// };
+/// assert_eq!(visited.poi_ids.last(), Some(&9));
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct VisitedPrefix {
+    /// Identifiers of the POIs visited so far, in visiting order.
+    pub poi_ids: Vec<u64>,
+    /// Where the visitor is now.
+    pub position: geo::Coord<f64>,
+    /// Minutes of [`SolveRequest::duration_minutes`] already spent.
+    pub elapsed_minutes: u16,
+}
+
+impl VisitedPrefix {
+    /// Time already spent on the tour.
+    #[must_use]
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_mins(u64::from(self.elapsed_minutes))
+    }
+}
+
+impl SolveRequest {
+    /// The wall-clock limit from [`SolveRequest::max_solve_ms`], if any.
+    #[must_use]
+    pub fn max_solve_duration(&self) -> Option<Duration> {
+        self.max_solve_ms
+            .map(|millis| Duration::from_millis(u64::from(millis)))
+    }
+
+    /// Local time of the week at which the walk starts, for opening hours:
+    /// that of [`SolveRequest::start_at`] or [`SolveRequest::start_time`],
+    /// whichever is set.
+    #[must_use]
+    pub fn start_week_time(&self) -> Option<WeekTime> {
+        self.start_at
+            .map(|start| start.week_time())
+            .or(self.start_time)
+    }
+
+    /// The walking limit per leg from [`SolveRequest::max_leg_minutes`], if
+    /// any.
+    #[must_use]
+    pub fn max_leg_duration(&self) -> Option<Duration> {
+        self.max_leg_minutes
+            .map(|minutes| Duration::from_mins(u64::from(minutes)))
+    }
+
+    /// Time the walk may take when walking directly from
+    /// [`SolveRequest::start`] to [`SolveRequest::end`] takes `direct`.
+    ///
+    /// This is [`SolveRequest::duration_minutes`], capped at
+    /// [`SolveRequest::max_detour`] times `direct` for point-to-point walks.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::time::Duration;
+    /// use geo::Coord;
+    /// use wildside_core::{InterestProfile, SolveRequest};
+    ///
+    /// let commute = SolveRequest {
+    ///     start: Coord { x: 0.0, y: 0.0 },
+    ///     end: Some(Coord { x: 0.01, y: 0.0 }),
+    ///     duration_minutes: 120,
+    ///     interests: InterestProfile::new(),
+    ///     seed: 1,
+    ///     max_detour: Some(1.5),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(
+    ///     commute.detour_budget(Duration::from_mins(20)),
+    ///     Duration::from_mins(30)
+    /// );
+    /// assert_eq!(
+    ///     commute.detour_budget(Duration::from_mins(100)),
+    ///     Duration::from_mins(120)
+    /// );
+    /// ```
+    #[must_use]
+    pub fn detour_budget(&self, direct: Duration) -> Duration {
+        let budget = Duration::from_mins(u64::from(self.duration_minutes));
+        self.max_detour
+            .filter(|_| self.end.is_some())
+            .and_then(|factor| {
+                Duration::try_from_secs_f64(direct.as_secs_f64() * f64::from(factor)).ok()
+            })
+            .map_or(budget, |limit| limit.min(budget))
+    }
+
+    /// Whether `poi` is excluded by identifier, lies in an avoid area, or
+    /// lacks the wheelchair access [`SolveRequest::accessibility`] requires.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use geo::{Coord, Rect};
+    /// use wildside_core::{InterestProfile, PointOfInterest, SolveRequest};
+    ///
+    /// let request = SolveRequest {
+    ///     start: Coord { x: 0.0, y: 0.0 },
+    ///     duration_minutes: 30,
+    ///     interests: InterestProfile::new(),
+    ///     seed: 1,
+    ///     excluded_poi_ids: vec![7],
+    ///     avoid_areas: vec![Rect::new(Coord { x: 1.0, y: 1.0 }, Coord { x: 2.0, y: 2.0 }).to_polygon()],
+    ///     ..Default::default()
+    /// };
+    /// assert!(request.excludes(&PointOfInterest::with_empty_tags(7, Coord { x: 0.0, y: 0.0 })));
+    /// assert!(request.excludes(&PointOfInterest::with_empty_tags(8, Coord { x: 1.5, y: 1.0 })));
+    /// assert!(!request.excludes(&PointOfInterest::with_empty_tags(9, Coord { x: 0.5, y: 0.5 })));
+    /// ```
+    #[must_use]
+    pub fn excludes(&self, poi: &PointOfInterest) -> bool {
+        self.excluded_poi_ids.contains(&poi.id)
+            || self
+                .avoid_areas
+                .iter()
+                .any(|area| area.intersects(&poi.location))
+            || !self.accessibility.admits(poi)
+    }
+}
@@ -0,0 +1,283 @@
+//! Results of a successful solve and how they compare.
+
+use geo::LineString;
+
+#[cfg(doc)]
+use super::Solver;
+use super::{SolveError, SolveRequest};
+use crate::Route;
+
+/// Telemetry from a solve operation.
+///
+/// Contains metrics describing solver execution, useful for performance
+/// monitoring and debugging.
+///
+/// # Examples
+/// ```rust
+/// use std::time::Duration;
+/// use wildside_core::Diagnostics;
+///
+/// let diagnostics = Diagnostics {
+///     solve_time: Duration::from_millis(42),
+///     candidates_evaluated: 150,
+///     candidates_pruned: 3,
+/// };
+/// assert_eq!(diagnostics.candidates_evaluated, 150);
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Diagnostics {
+    /// Time taken to produce the solution.
+    pub solve_time: std::time::Duration,
+    /// Number of candidate POIs evaluated by the solver.
+    pub candidates_evaluated: u64,
+    /// Number of candidate POIs dropped before the search because the
+    /// travel-time matrix reported them as unreachable.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub candidates_pruned: u64,
+}
+
+/// How the best route a solve found falls short of the request.
+///
+/// Carried by [`SolveError::Infeasible`] so callers can explain the failure,
+/// for example by suggesting a longer budget or a lower threshold.
+///
+/// # Examples
+/// ```rust
+/// use wildside_core::{Diagnostics, Shortfall};
+///
+/// let shortfall = Shortfall {
+///     pois: 2,
+///     score: 0.4,
+///     diagnostics: Diagnostics::default(),
+/// };
+/// assert_eq!(shortfall.pois, 2);
+/// ```
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Shortfall {
+    /// Number of POIs on the best route found; zero when none was found.
+    pub pois: usize,
+    /// Score of the best route found; zero when none was found.
+    pub score: f32,
+    /// Telemetry from the solve.
+    pub diagnostics: Diagnostics,
+}
+
+impl Shortfall {
+    /// The shortfall of `response`, the best route found.
+    #[must_use]
+    pub fn of(response: &SolveResponse) -> Self {
+        Self {
+            pois: response.route.pois().len(),
+            score: response.score,
+            diagnostics: response.diagnostics.clone(),
+        }
+    }
+}
+
+/// Response from a successful solve.
+///
+/// Contains the chosen [`Route`], its aggregate score, and [`Diagnostics`]
+/// describing solver execution.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct SolveResponse {
+    /// The ordered route for the visitor.
+    pub route: Route,
+    /// Total score accumulated along the route.
+    pub score: f32,
+    /// Telemetry from the solve operation.
+    pub diagnostics: Diagnostics,
+    /// Walking path through the route's stops, in route order.
+    ///
+    /// Populated when the travel-time provider can trace routes (see
+    /// [`TravelTimeProvider::route_geometry`](crate::TravelTimeProvider::route_geometry)),
+    /// so map frontends need not re-route the stops themselves.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub geometry: Option<LineString<f64>>,
+}
+
+/// The best route found from one of several candidate starts.
+///
+/// Returned by [`Solver::solve_from_starts`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StartChoice {
+    /// Position of the chosen start among those offered.
+    pub start_index: usize,
+    /// The route from the chosen start.
+    pub response: SolveResponse,
+}
+
+impl StartChoice {
+    /// Pick the best of `outcomes`, the results of solving from each
+    /// candidate start in turn.
+    ///
+    /// The highest-scoring route wins, then the shortest, then the earliest
+    /// start. Starts whose solve is infeasible are passed over; any other
+    /// error is returned at once, without consuming further outcomes.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first start's infeasibility error when every start is
+    /// infeasible, and [`SolveError::InvalidRequest`] when there are no
+    /// outcomes.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::time::Duration;
+    /// use wildside_core::{Diagnostics, Route, SolveError, SolveResponse, StartChoice};
+    ///
+    /// let response = |minutes, score| SolveResponse {
+    ///     route: Route::new(Vec::new(), Duration::from_mins(minutes)),
+    ///     score,
+    ///     diagnostics: Diagnostics::default(),
+    ///     geometry: None,
+    /// };
+    /// let choice = StartChoice::best([
+    ///     Ok(response(60, 2.0)),
+    ///     Err(SolveError::InfeasibleRequirement),
+    ///     Ok(response(45, 2.0)),
+    /// ])?;
+    /// assert_eq!(choice.start_index, 2);
+    /// # Ok::<(), SolveError>(())
+    /// ```
+    pub fn best(
+        outcomes: impl IntoIterator<Item = Result<SolveResponse, SolveError>>,
+    ) -> Result<Self, SolveError> {
+        let mut best: Option<Self> = None;
+        let mut infeasible: Option<SolveError> = None;
+        for (start_index, outcome) in outcomes.into_iter().enumerate() {
+            match outcome {
+                Ok(response)
+                    if best
+                        .as_ref()
+                        .is_none_or(|current| response.beats(&current.response)) =>
+                {
+                    best = Some(Self {
+                        start_index,
+                        response,
+                    });
+                }
+                Ok(_) => {}
+                Err(error @ (SolveError::Infeasible(_) | SolveError::InfeasibleRequirement)) => {
+                    infeasible.get_or_insert(error);
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        best.ok_or_else(|| infeasible.unwrap_or(SolveError::InvalidRequest))
+    }
+}
+
+impl SolveResponse {
+    /// Whether this response scores more than `other`, or as much over a
+    /// shorter route.
+    fn beats(&self, other: &Self) -> bool {
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| {
+                other
+                    .route
+                    .total_duration()
+                    .cmp(&self.route.total_duration())
+            })
+            .is_gt()
+    }
+}
+
+/// Keep the responses no other response beats on both duration and score.
+///
+/// The frontier is ordered by ascending route duration, and each response
+/// scores strictly more than the shorter ones before it. Of two responses with
+/// the same duration, only the higher-scoring one is kept.
+///
+/// # Examples
+/// ```rust
+/// use std::time::Duration;
+/// use wildside_core::{Diagnostics, Route, SolveResponse, pareto_frontier};
+///
+/// let response = |minutes, score| SolveResponse {
+///     route: Route::new(Vec::new(), Duration::from_mins(minutes)),
+///     score,
+///     diagnostics: Diagnostics::default(),
+///     geometry: None,
+/// };
+/// let frontier = pareto_frontier([response(90, 3.0), response(60, 3.0), response(30, 1.0)]);
+/// let scores: Vec<f32> = frontier.iter().map(|response| response.score).collect();
+/// assert_eq!(scores, vec![1.0, 3.0]);
+/// ```
+#[must_use]
+pub fn pareto_frontier(responses: impl IntoIterator<Item = SolveResponse>) -> Vec<SolveResponse> {
+    let mut sorted: Vec<SolveResponse> = responses.into_iter().collect();
+    sorted.sort_by(|lhs, rhs| {
+        lhs.route
+            .total_duration()
+            .cmp(&rhs.route.total_duration())
+            .then_with(|| rhs.score.total_cmp(&lhs.score))
+    });
+    let mut frontier: Vec<SolveResponse> = Vec::with_capacity(sorted.len());
+    for response in sorted {
+        if frontier
+            .last()
+            .is_none_or(|best| response.score > best.score)
+        {
+            frontier.push(response);
+        }
+    }
+    frontier
+}
+
+impl SolveRequest {
+    /// Pass `response` through when it meets [`SolveRequest::min_pois`] and
+    /// [`SolveRequest::min_score`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::Infeasible`] carrying the response's
+    /// [`Shortfall`] when it visits too few POIs or scores too little.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::time::Duration;
+    /// use geo::Coord;
+    /// use wildside_core::{
+    ///     Diagnostics, InterestProfile, Route, SolveError, SolveRequest, SolveResponse,
+    /// };
+    ///
+    /// let request = SolveRequest {
+    ///     start: Coord { x: 0.0, y: 0.0 },
+    ///     duration_minutes: 30,
+    ///     interests: InterestProfile::new(),
+    ///     seed: 1,
+    ///     min_pois: Some(3),
+    ///     ..Default::default()
+    /// };
+    /// let empty = SolveResponse {
+    ///     route: Route::empty(),
+    ///     score: 0.0,
+    ///     diagnostics: Diagnostics::default(),
+    ///     geometry: None,
+    /// };
+    /// let Err(SolveError::Infeasible(shortfall)) = request.ensure_minimums(empty) else {
+    ///     panic!("an empty route is too short");
+    /// };
+    /// assert_eq!(shortfall.pois, 0);
+    /// ```
+    pub fn ensure_minimums(&self, response: SolveResponse) -> Result<SolveResponse, SolveError> {
+        let too_few = self
+            .min_pois
+            .is_some_and(|min_pois| response.route.pois().len() < usize::from(min_pois));
+        let too_low = self
+            .min_score
+            .is_some_and(|min_score| response.score < min_score);
+        if too_few || too_low {
+            return Err(SolveError::Infeasible(Shortfall::of(&response)));
+        }
+        Ok(response)
+    }
+}
@@ -0,0 +1,147 @@
+//! Checks that a [`SolveRequest`] is fit to solve.
+
+use geo::CoordsIter;
+use thiserror::Error;
+
+use super::{SolveError, SolveRequest};
+
+/// Detailed validation errors for [`SolveRequest`].
+///
+/// Solvers typically map invalid inputs to [`SolveError::InvalidRequest`], but
+/// command-line tooling and callers may prefer more actionable diagnostics.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Error)]
+pub enum SolveRequestValidationError {
+    /// The request has a zero-minute time budget.
+    #[error("duration_minutes must be greater than zero")]
+    ZeroDuration,
+    /// The start coordinate contains `NaN` or infinite values.
+    #[error("start coordinate must be finite")]
+    NonFiniteStart,
+    /// The end coordinate contains `NaN` or infinite values.
+    #[error("end coordinate must be finite")]
+    NonFiniteEnd,
+    /// A provided `max_nodes` hint was zero.
+    #[error("max_nodes must be greater than zero when supplied")]
+    ZeroMaxNodes,
+    /// An avoid area has a vertex containing `NaN` or infinite values.
+    #[error("avoid_areas coordinates must be finite")]
+    NonFiniteAvoidArea,
+    /// A provided `max_solve_ms` limit was zero.
+    #[error("max_solve_ms must be greater than zero when supplied")]
+    ZeroMaxSolveTime,
+    /// A provided `max_leg_minutes` limit was zero.
+    #[error("max_leg_minutes must be greater than zero when supplied")]
+    ZeroMaxLeg,
+    /// A provided `min_score` threshold contains `NaN` or infinite values.
+    #[error("min_score must be finite when supplied")]
+    NonFiniteMinScore,
+    /// The visitor's current position contains `NaN` or infinite values.
+    #[error("visited position must be finite")]
+    NonFinitePosition,
+    /// The tour under way has already spent the whole budget.
+    #[error("visited elapsed_minutes must be less than duration_minutes")]
+    BudgetSpent,
+    /// The rest break lasts no time or more than a day, or its window is
+    /// reversed or runs past midnight.
+    #[error("rest_break must last 1 to 1440 minutes and begin within one day")]
+    InvalidRestBreak,
+    /// A rest break was requested without a time for the walk to start.
+    #[error("rest_break requires start_time or start_at")]
+    RestBreakWithoutStartTime,
+    /// A provided `max_detour` factor is below one or not finite.
+    #[error("max_detour must be a finite factor of at least 1 when supplied")]
+    InvalidMaxDetour,
+    /// A detour limit was requested for a round trip.
+    #[error("max_detour requires an end coordinate")]
+    MaxDetourWithoutEnd,
+    /// Both a local start time and a dated start were given.
+    #[error("start_time and start_at cannot both be set")]
+    ConflictingStartTimes,
+}
+
+impl SolveRequest {
+    /// Validates invariants required by solvers.
+    ///
+    /// Returns [`SolveError::InvalidRequest`] when the time budget is zero or the
+    /// start coordinates are non-finite. Provided `max_nodes`, `max_solve_ms`,
+    /// and `max_leg_minutes` values must be greater than zero. When set, `end`
+    /// and `min_score` must also be finite, as must every vertex of the avoid
+    /// areas. At most one of `start_time` and `start_at` may be set, and a
+    /// rest break must be valid and come with one of them.
+    pub fn validate(&self) -> Result<(), SolveError> {
+        self.validate_detailed()
+            .map_err(|_| SolveError::InvalidRequest)
+    }
+
+    /// Validates invariants required by solvers while returning actionable
+    /// diagnostics.
+    ///
+    /// This is a more detailed form of [`SolveRequest::validate`] which
+    /// preserves the precise reason that validation failed.
+    pub fn validate_detailed(&self) -> Result<(), SolveRequestValidationError> {
+        if self.duration_minutes == 0 {
+            return Err(SolveRequestValidationError::ZeroDuration);
+        }
+        if !is_valid_coord(&self.start) {
+            return Err(SolveRequestValidationError::NonFiniteStart);
+        }
+        if let Some(end) = self.end
+            && !is_valid_coord(&end)
+        {
+            return Err(SolveRequestValidationError::NonFiniteEnd);
+        }
+        if matches!(self.max_nodes, Some(0)) {
+            return Err(SolveRequestValidationError::ZeroMaxNodes);
+        }
+        if matches!(self.max_solve_ms, Some(0)) {
+            return Err(SolveRequestValidationError::ZeroMaxSolveTime);
+        }
+        if matches!(self.max_leg_minutes, Some(0)) {
+            return Err(SolveRequestValidationError::ZeroMaxLeg);
+        }
+        if self.min_score.is_some_and(|score| !score.is_finite()) {
+            return Err(SolveRequestValidationError::NonFiniteMinScore);
+        }
+        if let Some(visited) = &self.visited {
+            if !is_valid_coord(&visited.position) {
+                return Err(SolveRequestValidationError::NonFinitePosition);
+            }
+            if visited.elapsed_minutes >= self.duration_minutes {
+                return Err(SolveRequestValidationError::BudgetSpent);
+            }
+        }
+        if !self
+            .avoid_areas
+            .iter()
+            .flat_map(CoordsIter::coords_iter)
+            .all(|coord| is_valid_coord(&coord))
+        {
+            return Err(SolveRequestValidationError::NonFiniteAvoidArea);
+        }
+        if self.start_time.is_some() && self.start_at.is_some() {
+            return Err(SolveRequestValidationError::ConflictingStartTimes);
+        }
+        if let Some(rest_break) = &self.rest_break {
+            if !rest_break.is_valid() {
+                return Err(SolveRequestValidationError::InvalidRestBreak);
+            }
+            if self.start_week_time().is_none() {
+                return Err(SolveRequestValidationError::RestBreakWithoutStartTime);
+            }
+        }
+        if let Some(factor) = self.max_detour {
+            if !factor.is_finite() || factor < 1.0 {
+                return Err(SolveRequestValidationError::InvalidMaxDetour);
+            }
+            if self.end.is_none() {
+                return Err(SolveRequestValidationError::MaxDetourWithoutEnd);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks whether both x and y coordinates are finite.
+fn is_valid_coord(coord: &geo::Coord<f64>) -> bool {
+    coord.x.is_finite() && coord.y.is_finite()
+}
@@ -0,0 +1,202 @@
+//! Requests derived from another: the rest of a tour, and copies with
+//! other budgets or starts.
+
+use super::{SolveError, SolveRequest, SolveResponse};
+use crate::{PoiStore, PointOfInterest, Route, RouteLeg, RouteStop, Scorer};
+
+impl SolveRequest {
+    /// The request for the rest of a tour under way, or `None` when
+    /// [`SolveRequest::visited`] is unset.
+    ///
+    /// The remainder starts at the visitor's position with the unspent
+    /// budget and finishes where the whole tour would, back at `start` for a
+    /// round trip, and its start times move on by the time already spent.
+    /// Visited POIs are excluded and no longer count as required or as part
+    /// of [`SolveRequest::initial_route`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// use geo::Coord;
+    /// use wildside_core::{InterestProfile, SolveRequest, VisitedPrefix};
+    ///
+    /// let request = SolveRequest {
+    ///     start: Coord { x: 0.0, y: 0.0 },
+    ///     duration_minutes: 90,
+    ///     interests: InterestProfile::new(),
+    ///     seed: 1,
+    ///     required_poi_ids: vec![4, 7],
+    ///     visited: Some(VisitedPrefix {
+    ///         poi_ids: vec![4],
+    ///         position: Coord { x: 0.01, y: 0.0 },
+    ///         elapsed_minutes: 30,
+    ///     }),
+    ///     ..Default::default()
+    /// };
+    /// let remainder = request.remainder().expect("tour is under way");
+    /// assert_eq!(remainder.start, Coord { x: 0.01, y: 0.0 });
+    /// assert_eq!(remainder.end, Some(request.start));
+    /// assert_eq!(remainder.duration_minutes, 60);
+    /// assert_eq!(remainder.required_poi_ids, vec![7]);
+    /// assert_eq!(remainder.excluded_poi_ids, vec![4]);
+    /// ```
+    #[must_use]
+    pub fn remainder(&self) -> Option<Self> {
+        let visited = self.visited.as_ref()?;
+        let unvisited = |ids: &[u64]| -> Vec<u64> {
+            ids.iter()
+                .copied()
+                .filter(|id| !visited.poi_ids.contains(id))
+                .collect()
+        };
+        Some(Self {
+            start: visited.position,
+            end: Some(self.end.unwrap_or(self.start)),
+            duration_minutes: self
+                .duration_minutes
+                .saturating_sub(visited.elapsed_minutes),
+            required_poi_ids: unvisited(&self.required_poi_ids),
+            excluded_poi_ids: self
+                .excluded_poi_ids
+                .iter()
+                .chain(&visited.poi_ids)
+                .copied()
+                .collect(),
+            initial_route: unvisited(&self.initial_route),
+            visited: None,
+            start_time: self.start_time.map(|start| start.after(visited.elapsed())),
+            start_at: self
+                .start_at
+                .and_then(|start| start.checked_add(visited.elapsed())),
+            ..self.clone()
+        })
+    }
+
+    /// Prefix `response`, planned for the [`SolveRequest::remainder`], with
+    /// the stops already visited, as looked up in `store` and scored by
+    /// `scorer`.
+    ///
+    /// The route keeps the request's start and end, its duration and legs
+    /// count the time already spent, and its score includes the visited
+    /// POIs. The first re-planned leg departs from the last visited POI but
+    /// is timed from the visitor's current position. Visited POIs missing
+    /// from `store` are left off the route. The whole route has no geometry
+    /// until the caller traces it. Responses to requests without
+    /// [`SolveRequest::visited`] are returned unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::Store`] when `store` fails.
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "the tour's score adds the visited POIs' scores"
+    )]
+    pub fn lock_prefix<S, C>(
+        &self,
+        store: &S,
+        scorer: &C,
+        response: SolveResponse,
+    ) -> Result<SolveResponse, SolveError>
+    where
+        S: PoiStore + ?Sized,
+        C: Scorer + ?Sized,
+    {
+        let Some(visited) = &self.visited else {
+            return Ok(response);
+        };
+        let mut pois = visited_pois(store, &visited.poi_ids)?;
+        let prefix_score: f32 = scorer.score_many(&pois, &self.interests).into_iter().sum();
+        let elapsed = visited.elapsed();
+        let resumed_from = visited
+            .poi_ids
+            .last()
+            .map_or(RouteStop::Start, |id| RouteStop::Poi(*id));
+        let legs = response
+            .route
+            .legs()
+            .iter()
+            .map(|leg| RouteLeg {
+                from: match leg.from {
+                    RouteStop::Start => resumed_from,
+                    stop => stop,
+                },
+                cumulative_duration: leg.cumulative_duration + elapsed,
+                ..leg.clone()
+            })
+            .collect();
+        let total_duration = elapsed + response.route.total_duration();
+        pois.extend(response.route.pois().iter().cloned());
+        let route_end = self.end.unwrap_or(self.start);
+        let route =
+            Route::with_endpoints(self.start, route_end, pois, total_duration).with_legs(legs);
+        Ok(SolveResponse {
+            route,
+            score: prefix_score + response.score,
+            diagnostics: response.diagnostics,
+            geometry: None,
+        })
+    }
+
+    /// Copies of this request with each of `budgets` as its duration, in the
+    /// order given.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::InvalidRequest`] when any copy fails
+    /// [`SolveRequest::validate`], for example because a budget is zero.
+    pub fn with_budgets(&self, budgets: &[u16]) -> Result<Vec<Self>, SolveError> {
+        budgets
+            .iter()
+            .map(|&duration_minutes| {
+                let budgeted = Self {
+                    duration_minutes,
+                    ..self.clone()
+                };
+                budgeted.validate().map(|()| budgeted)
+            })
+            .collect()
+    }
+
+    /// Copies of this request leaving from each of `starts`, in the order
+    /// given.
+    ///
+    /// Round trips return to their own start, while point-to-point copies
+    /// keep [`SolveRequest::end`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::InvalidRequest`] when `starts` is empty or any
+    /// copy fails [`SolveRequest::validate`], for example because a start is
+    /// not finite.
+    pub fn with_starts(&self, starts: &[geo::Coord<f64>]) -> Result<Vec<Self>, SolveError> {
+        if starts.is_empty() {
+            return Err(SolveError::InvalidRequest);
+        }
+        starts
+            .iter()
+            .map(|&start| {
+                let moved = Self {
+                    start,
+                    ..self.clone()
+                };
+                moved.validate().map(|()| moved)
+            })
+            .collect()
+    }
+}
+
+/// Look up the visited POIs in `store`, in visiting order, skipping unknown
+/// ones.
+fn visited_pois<S>(store: &S, poi_ids: &[u64]) -> Result<Vec<PointOfInterest>, SolveError>
+where
+    S: PoiStore + ?Sized,
+{
+    let mut ids = poi_ids.to_vec();
+    ids.sort_unstable();
+    ids.dedup();
+    let mut pois: Vec<PointOfInterest> = store.get_pois_by_ids(&ids)?.collect();
+    if pois.len() < ids.len() {
+        log::debug!("Some visited POIs of {poi_ids:?} are unknown and left off the route");
+    }
+    pois.sort_by_key(|poi| poi_ids.iter().position(|id| *id == poi.id));
+    Ok(pois)
+}
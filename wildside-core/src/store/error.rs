@@ -0,0 +1,57 @@
+//! Errors reported by [`PoiStore`](super::PoiStore) queries.
+
+use thiserror::Error;
+
+/// Error raised when a store cannot answer a query.
+///
+/// Queries that succeed but match nothing return an empty iterator instead,
+/// so callers can tell "no POIs here" apart from "the store is broken".
+#[derive(Debug, Error)]
+pub enum PoiStoreError {
+    /// Reading persisted POI data failed.
+    #[error("failed to read POI data: {source}")]
+    Io {
+        /// Underlying I/O error.
+        #[source]
+        source: std::io::Error,
+    },
+    /// Persisted POI data could not be decoded.
+    #[error("POI data is corrupt: {reason}")]
+    Corrupt {
+        /// Description of the inconsistency that was detected.
+        reason: String,
+    },
+    /// A backend-specific failure, such as a database error.
+    #[error("POI store backend failed: {source}")]
+    Backend {
+        /// Error reported by the backend.
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+}
+
+impl PoiStoreError {
+    /// Wrap a backend-specific error.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use wildside_core::PoiStoreError;
+    ///
+    /// let error = PoiStoreError::backend(std::fmt::Error);
+    /// assert!(matches!(error, PoiStoreError::Backend { .. }));
+    /// ```
+    pub fn backend<E>(source: E) -> Self
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        Self::Backend {
+            source: Box::new(source),
+        }
+    }
+}
+
+impl From<std::io::Error> for PoiStoreError {
+    fn from(source: std::io::Error) -> Self {
+        Self::Io { source }
+    }
+}
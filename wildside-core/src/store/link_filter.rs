@@ -0,0 +1,376 @@
+//! Probabilistic membership filter for Wikidata-linked entities and POIs.
+//!
+//! Most POIs in an ingest carry no Wikidata link, so querying SQLite for their
+//! claims is wasted work. The [`LinkFilter`] is a compact Bloom filter written
+//! alongside `pois.db` during claims persistence. Readers consult it before
+//! touching the database: a negative answer is definitive, while a positive
+//! answer may be a false positive and must be confirmed against SQLite.
+
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+use crate::artefact_io::{open_parent_dir, read_array};
+use crate::formats::{self, FormatVersionError};
+
+/// File identifier for persisted link filters.
+pub(crate) const LINK_FILTER_MAGIC: [u8; 4] = *b"WSLF";
+
+/// Version stamped on newly written link filter files.
+pub(crate) const LINK_FILTER_VERSION: u16 = formats::LINK_FILTER.current;
+
+/// Target false-positive rate used by [`LinkFilter::with_capacity`].
+pub const DEFAULT_FALSE_POSITIVE_RATE: f64 = 0.01;
+
+/// Upper bound on hash functions; keeps lookups cheap for tiny filters.
+const MAX_HASH_COUNT: u32 = 16;
+
+/// Domain separators so entity and POI keys never collide.
+const ENTITY_DOMAIN: u8 = b'E';
+const POI_DOMAIN: u8 = b'P';
+
+/// Error raised when reading or writing a persisted link filter.
+#[derive(Debug, Error)]
+pub enum LinkFilterError {
+    /// The filter file could not be read or written.
+    #[error("failed to access link filter at {path}: {source}")]
+    Io {
+        /// Location of the link filter artefact.
+        path: PathBuf,
+        /// Underlying I/O error.
+        #[source]
+        source: io::Error,
+    },
+    /// The file did not contain the expected header.
+    #[error("invalid link filter magic: expected {expected:?}, found {found:?}")]
+    InvalidMagic {
+        /// Expected byte sequence identifying a link filter file.
+        expected: [u8; 4],
+        /// Sequence read from the file.
+        found: [u8; 4],
+    },
+    /// The file's format version is outside the readable range.
+    #[error(transparent)]
+    UnsupportedVersion(#[from] FormatVersionError),
+    /// The header described an impossible filter shape.
+    #[error("link filter at {path} is corrupt: {reason}")]
+    Corrupt {
+        /// Location of the link filter artefact.
+        path: PathBuf,
+        /// Description of the inconsistency.
+        reason: &'static str,
+    },
+}
+
+/// Bloom filter recording Wikidata entities and the POIs that link to them.
+///
+/// # Examples
+/// ```rust
+/// use wildside_core::store::LinkFilter;
+///
+/// let mut filter = LinkFilter::with_capacity(2);
+/// filter.insert_entity("Q64");
+/// filter.insert_poi(7);
+///
+/// assert!(filter.may_contain_entity("Q64"));
+/// assert!(filter.may_contain_poi(7));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkFilter {
+    words: Vec<u64>,
+    hash_count: u32,
+}
+
+impl LinkFilter {
+    /// Size a filter for `expected_items` keys at the default false-positive
+    /// rate.
+    #[must_use]
+    pub fn with_capacity(expected_items: usize) -> Self {
+        Self::with_false_positive_rate(expected_items, DEFAULT_FALSE_POSITIVE_RATE)
+    }
+
+    /// Size a filter for `expected_items` keys at the requested false-positive
+    /// rate.
+    ///
+    /// Rates outside `(0.0, 1.0)` fall back to
+    /// [`DEFAULT_FALSE_POSITIVE_RATE`].
+    #[must_use]
+    pub fn with_false_positive_rate(expected_items: usize, false_positive_rate: f64) -> Self {
+        let rate = if false_positive_rate > 0.0 && false_positive_rate < 1.0 {
+            false_positive_rate
+        } else {
+            DEFAULT_FALSE_POSITIVE_RATE
+        };
+        let items = expected_items.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let bits = (-(items * rate.ln()) / (ln2 * ln2)).ceil().max(64.0);
+        let word_count = (bits / 64.0).ceil() as usize;
+        let hashes = ((word_count as f64 * 64.0 / items) * ln2).round();
+        let hash_count = (hashes as u32).clamp(1, MAX_HASH_COUNT);
+        Self {
+            words: vec![0; word_count],
+            hash_count,
+        }
+    }
+
+    /// Record a Wikidata entity identifier such as `Q64`.
+    pub fn insert_entity(&mut self, entity_id: &str) {
+        self.insert(ENTITY_DOMAIN, entity_id.as_bytes());
+    }
+
+    /// Record a POI identifier that links to at least one entity.
+    pub fn insert_poi(&mut self, poi_id: u64) {
+        self.insert(POI_DOMAIN, &poi_id.to_le_bytes());
+    }
+
+    /// Report whether the entity may have been recorded.
+    ///
+    /// `false` is definitive; `true` may be a false positive.
+    #[must_use]
+    pub fn may_contain_entity(&self, entity_id: &str) -> bool {
+        self.contains(ENTITY_DOMAIN, entity_id.as_bytes())
+    }
+
+    /// Report whether the POI may have been recorded.
+    ///
+    /// `false` is definitive; `true` may be a false positive.
+    #[must_use]
+    pub fn may_contain_poi(&self, poi_id: u64) -> bool {
+        self.contains(POI_DOMAIN, &poi_id.to_le_bytes())
+    }
+
+    /// Number of bits backing the filter.
+    #[must_use]
+    pub fn bit_len(&self) -> u64 {
+        self.words.len() as u64 * 64
+    }
+
+    /// Persist the filter in the `WSLF` binary format. Existing files are
+    /// truncated.
+    pub fn write(&self, path: &Path) -> Result<(), LinkFilterError> {
+        let io_error = |source| LinkFilterError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let (dir, file_name) = open_parent_dir(path).map_err(io_error)?;
+        let mut file = io::BufWriter::new(dir.create(file_name).map_err(io_error)?);
+        file.write_all(&LINK_FILTER_MAGIC).map_err(io_error)?;
+        file.write_all(&LINK_FILTER_VERSION.to_le_bytes())
+            .map_err(io_error)?;
+        file.write_all(&self.hash_count.to_le_bytes())
+            .map_err(io_error)?;
+        file.write_all(&(self.words.len() as u64).to_le_bytes())
+            .map_err(io_error)?;
+        for word in &self.words {
+            file.write_all(&word.to_le_bytes()).map_err(io_error)?;
+        }
+        let file = file
+            .into_inner()
+            .map_err(|error| io_error(error.into_error()))?;
+        file.sync_all().map_err(io_error)
+    }
+
+    /// Load a filter previously written by [`LinkFilter::write`].
+    pub fn load(path: &Path) -> Result<Self, LinkFilterError> {
+        let io_error = |source| LinkFilterError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let (dir, file_name) = open_parent_dir(path).map_err(io_error)?;
+        let mut file = io::BufReader::new(dir.open(file_name).map_err(io_error)?);
+
+        let magic: [u8; 4] = read_array(&mut file).map_err(io_error)?;
+        if magic != LINK_FILTER_MAGIC {
+            return Err(LinkFilterError::InvalidMagic {
+                expected: LINK_FILTER_MAGIC,
+                found: magic,
+            });
+        }
+        let version = u16::from_le_bytes(read_array(&mut file).map_err(io_error)?);
+        formats::LINK_FILTER.negotiate(i64::from(version))?;
+        let hash_count = u32::from_le_bytes(read_array(&mut file).map_err(io_error)?);
+        let word_count = u64::from_le_bytes(read_array(&mut file).map_err(io_error)?);
+        let corrupt = |reason| LinkFilterError::Corrupt {
+            path: path.to_path_buf(),
+            reason,
+        };
+        if hash_count == 0 || hash_count > MAX_HASH_COUNT {
+            return Err(corrupt("hash count out of range"));
+        }
+        let word_count = usize::try_from(word_count)
+            .ok()
+            .filter(|count| *count > 0)
+            .ok_or_else(|| corrupt("word count out of range"))?;
+
+        let mut words = Vec::with_capacity(word_count.min(1 << 20));
+        for _ in 0..word_count {
+            let word = read_array(&mut file).map_err(io_error)?;
+            words.push(u64::from_le_bytes(word));
+        }
+        Ok(Self { words, hash_count })
+    }
+
+    fn insert(&mut self, domain: u8, key: &[u8]) {
+        let bit_len = self.bit_len();
+        for bit in probe_positions(domain, key, self.hash_count, bit_len) {
+            let (word, mask) = locate(bit);
+            if let Some(slot) = self.words.get_mut(word) {
+                *slot |= mask;
+            }
+        }
+    }
+
+    fn contains(&self, domain: u8, key: &[u8]) -> bool {
+        probe_positions(domain, key, self.hash_count, self.bit_len()).all(|bit| {
+            let (word, mask) = locate(bit);
+            self.words.get(word).is_some_and(|slot| slot & mask != 0)
+        })
+    }
+}
+
+/// Derive probe positions using Kirsch–Mitzenmacher double hashing.
+///
+/// FNV-1a is used rather than `std`'s hasher because the persisted filter must
+/// hash identically across processes, platforms, and Rust releases.
+fn probe_positions(
+    domain: u8,
+    key: &[u8],
+    hash_count: u32,
+    bit_len: u64,
+) -> impl Iterator<Item = u64> {
+    let primary = fnv1a(0xcbf2_9ce4_8422_2325, domain, key);
+    let secondary = fnv1a(0x8422_2325_cbf2_9ce4, domain, key) | 1;
+    (0..u64::from(hash_count))
+        .map(move |round| primary.wrapping_add(round.wrapping_mul(secondary)) % bit_len.max(1))
+}
+
+fn fnv1a(offset_basis: u64, domain: u8, key: &[u8]) -> u64 {
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    std::iter::once(&domain)
+        .chain(key)
+        .fold(offset_basis, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+        })
+}
+
+const fn locate(bit: u64) -> (usize, u64) {
+    ((bit / 64) as usize, 1 << (bit % 64))
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for link filter membership and persistence.
+
+    use super::*;
+    use rstest::{fixture, rstest};
+    use tempfile::TempDir;
+
+    #[fixture]
+    fn temp_filter_path() -> (TempDir, PathBuf) {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("links.filter");
+        (dir, path)
+    }
+
+    #[rstest]
+    fn inserted_keys_are_always_reported() {
+        let mut filter = LinkFilter::with_capacity(1_000);
+        for id in 0..1_000_u64 {
+            filter.insert_poi(id);
+            filter.insert_entity(&format!("Q{id}"));
+        }
+        assert!((0..1_000_u64).all(|id| filter.may_contain_poi(id)));
+        assert!((0..1_000_u64).all(|id| filter.may_contain_entity(&format!("Q{id}"))));
+    }
+
+    #[rstest]
+    fn false_positive_rate_stays_near_target() {
+        let mut filter = LinkFilter::with_capacity(1_000);
+        for id in 0..1_000_u64 {
+            filter.insert_poi(id);
+        }
+        let false_positives = (1_000..11_000_u64)
+            .filter(|id| filter.may_contain_poi(*id))
+            .count();
+        assert!(
+            false_positives < 300,
+            "expected roughly 1% false positives, found {false_positives} in 10000"
+        );
+    }
+
+    #[rstest]
+    fn entity_and_poi_keys_do_not_alias() {
+        let mut filter = LinkFilter::with_capacity(1);
+        filter.insert_entity("Q1");
+        assert!(!filter.may_contain_poi(1));
+    }
+
+    #[rstest]
+    fn empty_filter_reports_nothing() {
+        let filter = LinkFilter::with_capacity(0);
+        assert!(!filter.may_contain_entity("Q64"));
+        assert!(!filter.may_contain_poi(7));
+    }
+
+    #[rstest]
+    fn round_trips_through_disk(#[from(temp_filter_path)] (_dir, path): (TempDir, PathBuf)) {
+        let mut filter = LinkFilter::with_capacity(10);
+        filter.insert_entity("Q64");
+        filter.insert_poi(42);
+        filter.write(&path).expect("write filter");
+
+        let loaded = LinkFilter::load(&path).expect("load filter");
+        assert_eq!(loaded, filter);
+        assert!(loaded.may_contain_entity("Q64"));
+        assert!(loaded.may_contain_poi(42));
+    }
+
+    #[rstest]
+    fn load_rejects_invalid_magic(#[from(temp_filter_path)] (_dir, path): (TempDir, PathBuf)) {
+        std::fs::write(&path, b"BAD!\x01\x00").expect("write corrupt header");
+        let error = LinkFilter::load(&path).expect_err("invalid magic should fail");
+        assert!(matches!(error, LinkFilterError::InvalidMagic { .. }));
+    }
+
+    #[rstest]
+    fn load_rejects_unsupported_version(
+        #[from(temp_filter_path)] (_dir, path): (TempDir, PathBuf),
+    ) {
+        let mut bytes = LINK_FILTER_MAGIC.to_vec();
+        bytes.extend_from_slice(&(LINK_FILTER_VERSION + 1).to_le_bytes());
+        std::fs::write(&path, bytes).expect("write header");
+        let error = LinkFilter::load(&path).expect_err("unsupported version should fail");
+        assert!(matches!(
+            error,
+            LinkFilterError::UnsupportedVersion(FormatVersionError { found, .. })
+                if found == i64::from(LINK_FILTER_VERSION + 1)
+        ));
+    }
+
+    #[rstest]
+    fn load_rejects_truncated_payload(#[from(temp_filter_path)] (_dir, path): (TempDir, PathBuf)) {
+        let mut bytes = LINK_FILTER_MAGIC.to_vec();
+        bytes.extend_from_slice(&LINK_FILTER_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&3_u32.to_le_bytes());
+        bytes.extend_from_slice(&4_u64.to_le_bytes());
+        bytes.extend_from_slice(&0_u64.to_le_bytes());
+        std::fs::write(&path, bytes).expect("write truncated filter");
+        let error = LinkFilter::load(&path).expect_err("truncated payload should fail");
+        assert!(matches!(error, LinkFilterError::Io { .. }));
+    }
+
+    #[rstest]
+    fn load_rejects_zero_hash_count(#[from(temp_filter_path)] (_dir, path): (TempDir, PathBuf)) {
+        let mut bytes = LINK_FILTER_MAGIC.to_vec();
+        bytes.extend_from_slice(&LINK_FILTER_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&0_u32.to_le_bytes());
+        bytes.extend_from_slice(&1_u64.to_le_bytes());
+        bytes.extend_from_slice(&0_u64.to_le_bytes());
+        std::fs::write(&path, bytes).expect("write corrupt filter");
+        let error = LinkFilter::load(&path).expect_err("zero hash count should fail");
+        assert!(matches!(error, LinkFilterError::Corrupt { .. }));
+    }
+}
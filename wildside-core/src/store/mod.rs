@@ -2,21 +2,60 @@
 //!
 //! The `PoiStore` trait defines a read-only interface for retrieving
 //! [`PointOfInterest`] values. Consumers can use it to query a set of POIs
-//! within a geographic bounding box.
+//! within a geographic bounding box or polygon, within a walking distance of a
+//! point, or nearest a point. The companion `PoiWriter` trait covers
+//! incremental updates, so ingest, conflation, and test setup can write POIs
+//! without depending on a particular backend.
 
-use geo::Rect;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
-use crate::PointOfInterest;
+use geo::{BoundingRect, Coord, Intersects, Polygon, Rect};
+use rstar::PointDistance;
 
+use crate::{PointOfInterest, geometry};
+
+mod error;
+#[cfg(feature = "store-sqlite")]
+mod link_filter;
+#[cfg(feature = "store-sqlite")]
+mod name_search;
+mod pool;
+mod query;
+mod ranking;
 #[cfg(feature = "store-sqlite")]
 mod spatial_index;
 #[cfg(feature = "store-sqlite")]
 mod sqlite;
+#[cfg(feature = "store-sqlite")]
+mod tag_loader;
+#[cfg(feature = "store-sqlite")]
+mod writer;
 
+pub use error::PoiStoreError;
 #[cfg(feature = "store-sqlite")]
-pub use spatial_index::{SpatialIndexError, SpatialIndexWriteError, write_spatial_index};
+pub use link_filter::{DEFAULT_FALSE_POSITIVE_RATE, LinkFilter, LinkFilterError};
 #[cfg(feature = "store-sqlite")]
-pub use sqlite::{SqlitePoiStore, SqlitePoiStoreError};
+pub use name_search::{POI_NAMES_SINCE, install_poi_name_search};
+pub use pool::{DEFAULT_MAX_IDLE, ReadPool};
+pub use query::{PoiQuery, TagFilter};
+pub use ranking::PopularityLookup;
+#[cfg(feature = "store-sqlite")]
+pub use spatial_index::{
+    DEFAULT_COMPRESSION_LEVEL, SpatialIndexError, SpatialIndexOptions, SpatialIndexUpgradeError,
+    SpatialIndexWriteError, read_spatial_index, upgrade_spatial_index, write_spatial_index,
+    write_spatial_index_with_options,
+};
+#[cfg(feature = "store-sqlite")]
+pub use sqlite::{
+    BboxOrder, SqlitePoiStore, SqlitePoiStoreError, SqlitePoiStoreOptions, TagLoading,
+    VALIDATION_SAMPLE_SIZE, Validation,
+};
+#[cfg(feature = "store-sqlite")]
+pub use writer::{SqlitePoiWriter, SqlitePoiWriterError};
+
+/// Iterator over POIs returned by [`PoiStore`] queries.
+pub type PoiIter<'a> = Box<dyn Iterator<Item = PointOfInterest> + Send + 'a>;
 
 /// Read-only access to persisted points of interest.
 ///
@@ -24,28 +63,30 @@ pub use sqlite::{SqlitePoiStore, SqlitePoiStoreError};
 /// R\*-tree. The bounding box uses WGS84 coordinates (`x = longitude`,
 /// `y = latitude`).
 ///
+/// Every query is fallible: an empty iterator means the store holds no
+/// matching POIs, while a [`PoiStoreError`] means the store could not be
+/// read.
+///
 /// # Examples
 ///
 /// ```rust
 /// use geo::{Coord, Rect, Intersects};
-/// use wildside_core::{PointOfInterest, PoiStore};
+/// use wildside_core::{PoiIter, PoiStore, PoiStoreError, PointOfInterest};
 ///
 /// struct MemoryStore {
 ///     pois: Vec<PointOfInterest>,
 /// }
 ///
 /// impl PoiStore for MemoryStore {
-///     fn get_pois_in_bbox(
-///         &self,
-///         bbox: &Rect<f64>,
-///     ) -> Box<dyn Iterator<Item = PointOfInterest> + Send + '_> {
-///         Box::new(
+///     fn get_pois_in_bbox(&self, bbox: &Rect<f64>) -> Result<PoiIter<'_>, PoiStoreError> {
+///         let bbox = *bbox;
+///         Ok(Box::new(
 ///             self.pois
 ///                 .iter()
 ///                 // `Intersects` treats boundary points as inside the rectangle.
 ///                 .filter(move |p| bbox.intersects(&p.location))
 ///                 .cloned(),
-///         )
+///         ))
 ///     }
 /// }
 ///
@@ -53,8 +94,9 @@ pub use sqlite::{SqlitePoiStore, SqlitePoiStoreError};
 /// let store = MemoryStore { pois: vec![poi.clone()] };
 /// let bbox = Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 1.0, y: 1.0 });
 ///
-/// let found: Vec<_> = store.get_pois_in_bbox(&bbox).collect();
+/// let found: Vec<_> = store.get_pois_in_bbox(&bbox)?.collect();
 /// assert_eq!(found, vec![poi]);
+/// # Ok::<(), PoiStoreError>(())
 /// ```
 pub trait PoiStore {
     /// Return all POIs that fall within the provided bounding box.
@@ -68,61 +110,243 @@ pub trait PoiStore {
     /// two `Rect` ranges and invoke this method for each range.
     ///
     /// Containment includes boundary points.
-    fn get_pois_in_bbox(
+    fn get_pois_in_bbox(&self, bbox: &Rect<f64>) -> Result<PoiIter<'_>, PoiStoreError>;
+
+    /// Return the POI with identifier `id`, if the store holds it.
+    fn get_poi(&self, id: u64) -> Result<Option<PointOfInterest>, PoiStoreError> {
+        Ok(self.get_pois_by_ids(&[id])?.next())
+    }
+
+    /// Return the POIs with the given identifiers, in the order requested.
+    ///
+    /// Unknown identifiers are skipped and repeated identifiers yield the POI
+    /// once per occurrence. The default implementation scans the whole WGS84
+    /// extent; stores that can look POIs up by identifier should override it.
+    fn get_pois_by_ids(&self, ids: &[u64]) -> Result<PoiIter<'_>, PoiStoreError> {
+        if ids.is_empty() {
+            return Ok(Box::new(std::iter::empty()));
+        }
+        let wanted: HashSet<u64> = ids.iter().copied().collect();
+        let found: HashMap<u64, PointOfInterest> = self
+            .get_pois_in_bbox(&world())?
+            .filter(|poi| wanted.contains(&poi.id))
+            .map(|poi| (poi.id, poi))
+            .collect();
+        let pois: Vec<_> = ids.iter().filter_map(|id| found.get(id).cloned()).collect();
+        Ok(Box::new(pois.into_iter()))
+    }
+
+    /// Return the POIs matching `query`, ordered by identifier.
+    ///
+    /// The default implementation filters
+    /// [`get_pois_in_bbox`](Self::get_pois_in_bbox); stores with tag indexes
+    /// should override it to avoid visiting non-matching POIs.
+    fn query_pois(&self, query: &PoiQuery) -> Result<PoiIter<'_>, PoiStoreError> {
+        let mut pois: Vec<_> = self
+            .get_pois_in_bbox(&query.bbox)?
+            .filter(|poi| query.matches_tags(poi))
+            .collect();
+        pois.sort_unstable_by_key(|poi| poi.id);
+        pois.truncate(query.limit.unwrap_or(usize::MAX));
+        Ok(Box::new(pois.into_iter()))
+    }
+
+    /// Return the POIs inside `polygon`, such as a city boundary or an
+    /// isochrone.
+    ///
+    /// The store is queried with the polygon's bounding rectangle and each
+    /// candidate is then tested against the polygon itself. Points on the
+    /// boundary, including interior ring boundaries, count as inside; points
+    /// within a hole do not. Results keep the order of
+    /// [`get_pois_in_bbox`](Self::get_pois_in_bbox).
+    fn get_pois_in_polygon(&self, polygon: &Polygon<f64>) -> Result<PoiIter<'_>, PoiStoreError> {
+        let Some(envelope) = polygon.bounding_rect() else {
+            return Ok(Box::new(std::iter::empty()));
+        };
+        let polygon = polygon.clone();
+        Ok(Box::new(
+            self.get_pois_in_bbox(&envelope)?
+                .filter(move |poi| polygon.intersects(&poi.location)),
+        ))
+    }
+
+    /// Return the POIs within `meters` of `center` along the great circle.
+    ///
+    /// The store is queried with the bounding rectangle of the disc, so
+    /// implementations benefit from their bbox index, and POIs in the
+    /// rectangle's corners are then discarded by exact distance. Results keep
+    /// the order of [`get_pois_in_bbox`](Self::get_pois_in_bbox). Discs are
+    /// clamped at the antimeridian rather than wrapped; negative radii select
+    /// only POIs at `center`.
+    fn get_pois_within_radius(
+        &self,
+        center: Coord<f64>,
+        meters: f64,
+    ) -> Result<PoiIter<'_>, PoiStoreError> {
+        let envelope = geometry::cap_bounds(center, meters);
+        let meters = meters.max(0.0);
+        Ok(Box::new(self.get_pois_in_bbox(&envelope)?.filter(
+            move |poi| geometry::great_circle_m(center, poi.location) <= meters,
+        )))
+    }
+
+    /// Return up to `limit` POIs inside `bbox` with the highest `scores`,
+    /// best first.
+    ///
+    /// POIs without a score rank as zero and equal scores are ordered by
+    /// identifier. Containment follows
+    /// [`get_pois_in_bbox`](Self::get_pois_in_bbox). The default
+    /// implementation ranks every POI that query returns; indexed stores
+    /// should override it to rank by identifier and copy only the winners.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use std::collections::BTreeMap;
+    /// use geo::Rect;
+    /// use wildside_core::{PoiStore, PoiStoreError};
+    ///
+    /// fn best_ten<S: PoiStore>(
+    ///     store: &S,
+    ///     area: &Rect<f64>,
+    ///     scores: &BTreeMap<u64, f32>,
+    /// ) -> Result<Vec<u64>, PoiStoreError> {
+    ///     Ok(store
+    ///         .get_top_pois_in_bbox(area, 10, scores)?
+    ///         .map(|poi| poi.id)
+    ///         .collect())
+    /// }
+    /// ```
+    fn get_top_pois_in_bbox(
         &self,
         bbox: &Rect<f64>,
-    ) -> Box<dyn Iterator<Item = PointOfInterest> + Send + '_>;
+        limit: usize,
+        scores: &dyn PopularityLookup,
+    ) -> Result<PoiIter<'_>, PoiStoreError> {
+        let pois = ranking::top_by_popularity(
+            self.get_pois_in_bbox(bbox)?.map(|poi| (poi.id, poi)),
+            limit,
+            scores,
+        );
+        Ok(Box::new(pois.into_iter()))
+    }
+
+    /// Return up to `k` POIs closest to `center`, nearest first.
+    ///
+    /// Distances are planar in lon/lat degrees, matching the R\*-tree used
+    /// by indexed stores; equally distant POIs are ordered by identifier.
+    ///
+    /// The default implementation sorts every POI in the WGS84 extent, so it
+    /// suits only small stores. Indexed stores should override it.
+    fn get_nearest_pois(&self, center: Coord<f64>, k: usize) -> Result<PoiIter<'_>, PoiStoreError> {
+        if k == 0 {
+            return Ok(Box::new(std::iter::empty()));
+        }
+        let mut pois: Vec<_> = self.get_pois_in_bbox(&world())?.collect();
+        sort_by_distance(&mut pois, center);
+        pois.truncate(k);
+        Ok(Box::new(pois.into_iter()))
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    //! Tests for in-memory point-of-interest store queries.
-
-    use super::PoiStore;
-    use crate::{PointOfInterest, test_support::MemoryStore};
-    use geo::{Coord, Rect};
-    use rstest::rstest;
-
-    #[rstest]
-    fn returns_pois_inside_bbox() {
-        let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
-        let store = MemoryStore::with_poi(poi.clone());
-        let bbox = Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 1.0, y: 1.0 });
-        let found: Vec<_> = store.get_pois_in_bbox(&bbox).collect();
-        assert_eq!(found, vec![poi]);
+/// Rectangle covering every valid WGS84 coordinate.
+fn world() -> Rect<f64> {
+    Rect::new(
+        Coord {
+            x: -180.0,
+            y: -90.0,
+        },
+        Coord { x: 180.0, y: 90.0 },
+    )
+}
+
+/// Order POIs by distance from `center`, breaking ties by identifier.
+pub(crate) fn sort_by_distance(pois: &mut [PointOfInterest], center: Coord<f64>) {
+    let target = [center.x, center.y];
+    pois.sort_by(|a, b| {
+        a.distance_2(&target)
+            .total_cmp(&b.distance_2(&target))
+            .then(a.id.cmp(&b.id))
+    });
+}
+
+impl<S: PoiStore + ?Sized> PoiStore for Arc<S> {
+    fn get_pois_in_bbox(&self, bbox: &Rect<f64>) -> Result<PoiIter<'_>, PoiStoreError> {
+        (**self).get_pois_in_bbox(bbox)
+    }
+
+    fn get_poi(&self, id: u64) -> Result<Option<PointOfInterest>, PoiStoreError> {
+        (**self).get_poi(id)
     }
 
-    #[rstest]
-    fn returns_empty_when_no_pois() {
-        let store = MemoryStore::default();
-        let bbox = Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 1.0, y: 1.0 });
-        assert_eq!(store.get_pois_in_bbox(&bbox).count(), 0);
+    fn get_pois_by_ids(&self, ids: &[u64]) -> Result<PoiIter<'_>, PoiStoreError> {
+        (**self).get_pois_by_ids(ids)
     }
 
-    #[rstest]
-    #[case(Coord { x: -1.0, y: 0.0 })] // left edge
-    #[case(Coord { x: 1.0, y: 0.0 })] // right edge
-    #[case(Coord { x: 0.0, y: -1.0 })] // bottom edge
-    #[case(Coord { x: 0.0, y: 1.0 })] // top edge
-    #[case(Coord { x: -1.0, y: -1.0 })] // bottom-left corner
-    #[case(Coord { x: 1.0, y: 1.0 })] // top-right corner
-    fn includes_poi_on_bbox_boundary(#[case] location: Coord<f64>) {
-        let poi = PointOfInterest::with_empty_tags(42, location);
-        let store = MemoryStore::with_poi(poi.clone());
-        let bbox = Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 1.0, y: 1.0 });
-        let found: Vec<_> = store.get_pois_in_bbox(&bbox).collect();
-        assert_eq!(found, vec![poi]);
+    fn query_pois(&self, query: &PoiQuery) -> Result<PoiIter<'_>, PoiStoreError> {
+        (**self).query_pois(query)
+    }
+
+    fn get_pois_in_polygon(&self, polygon: &Polygon<f64>) -> Result<PoiIter<'_>, PoiStoreError> {
+        (**self).get_pois_in_polygon(polygon)
+    }
+
+    fn get_pois_within_radius(
+        &self,
+        center: Coord<f64>,
+        meters: f64,
+    ) -> Result<PoiIter<'_>, PoiStoreError> {
+        (**self).get_pois_within_radius(center, meters)
+    }
+
+    fn get_top_pois_in_bbox(
+        &self,
+        bbox: &Rect<f64>,
+        limit: usize,
+        scores: &dyn PopularityLookup,
+    ) -> Result<PoiIter<'_>, PoiStoreError> {
+        (**self).get_top_pois_in_bbox(bbox, limit, scores)
     }
 
-    #[rstest]
-    #[case(Coord { x: -1.0000001, y: 0.0 })]
-    #[case(Coord { x: 1.0000001, y: 0.0 })]
-    #[case(Coord { x: 0.0, y: -1.0000001 })]
-    #[case(Coord { x: 0.0, y: 1.0000001 })]
-    fn excludes_poi_just_outside_bbox(#[case] location: Coord<f64>) {
-        let poi = PointOfInterest::with_empty_tags(7, location);
-        let store = MemoryStore::with_poi(poi);
-        let bbox = Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 1.0, y: 1.0 });
-        assert_eq!(store.get_pois_in_bbox(&bbox).count(), 0);
+    fn get_nearest_pois(&self, center: Coord<f64>, k: usize) -> Result<PoiIter<'_>, PoiStoreError> {
+        (**self).get_nearest_pois(center, k)
     }
 }
+
+/// Write access to persisted points of interest.
+///
+/// Implementations may buffer changes; callers must invoke
+/// [`flush`](Self::flush) before readers are guaranteed to observe them.
+/// Upserting an identifier that already exists replaces the stored POI, and
+/// deleting an unknown identifier is not an error.
+///
+/// # Examples
+///
+/// ```rust
+/// use wildside_core::{PoiWriter, PointOfInterest};
+///
+/// fn replace_all<W: PoiWriter>(
+///     writer: &mut W,
+///     stale: &[u64],
+///     fresh: &[PointOfInterest],
+/// ) -> Result<(), W::Error> {
+///     writer.delete_pois(stale)?;
+///     writer.upsert_pois(fresh)?;
+///     writer.flush()
+/// }
+/// ```
+pub trait PoiWriter {
+    /// Error reported when staging or flushing changes fails.
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// Insert the provided POIs, replacing any with matching identifiers.
+    fn upsert_pois(&mut self, pois: &[PointOfInterest]) -> Result<(), Self::Error>;
+
+    /// Remove the POIs with the provided identifiers.
+    fn delete_pois(&mut self, ids: &[u64]) -> Result<(), Self::Error>;
+
+    /// Persist every staged change so subsequent reads observe it.
+    fn flush(&mut self) -> Result<(), Self::Error>;
+}
+
+#[cfg(test)]
+mod tests;
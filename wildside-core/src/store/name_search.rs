@@ -0,0 +1,140 @@
+//! Full-text search over POI names backed by an SQLite FTS5 table.
+//!
+//! `poi_names` holds one row per named POI, keyed by the POI identifier as
+//! its `rowid`. Triggers on `pois` keep it current, so ingest and
+//! [`super::SqlitePoiWriter`] only need to install it alongside the `pois`
+//! table. Diacritics are folded when indexing, so "Musée" matches a query
+//! typed without the accent.
+
+use geo::Rect;
+use rusqlite::Connection;
+
+use crate::PointOfInterest;
+
+use super::sqlite::{SqlitePoiStoreError, read_poi_row};
+
+/// `pois` schema version that introduced the `poi_names` table.
+pub const POI_NAMES_SINCE: u16 = 2;
+
+const POI_NAMES_SCHEMA: &str = "
+    CREATE VIRTUAL TABLE IF NOT EXISTS poi_names USING fts5(
+        name,
+        tokenize = 'unicode61 remove_diacritics 2'
+    );
+    -- INSERT OR REPLACE does not fire delete triggers, so clear the old row
+    -- before indexing the new one.
+    CREATE TRIGGER IF NOT EXISTS pois_index_name AFTER INSERT ON pois BEGIN
+        DELETE FROM poi_names WHERE rowid = NEW.id;
+        INSERT INTO poi_names (rowid, name)
+        SELECT NEW.id, json_extract(NEW.tags, '$.name')
+        WHERE json_extract(NEW.tags, '$.name') IS NOT NULL;
+    END;
+    CREATE TRIGGER IF NOT EXISTS pois_reindex_name AFTER UPDATE ON pois BEGIN
+        DELETE FROM poi_names WHERE rowid = OLD.id;
+        INSERT INTO poi_names (rowid, name)
+        SELECT NEW.id, json_extract(NEW.tags, '$.name')
+        WHERE json_extract(NEW.tags, '$.name') IS NOT NULL;
+    END;
+    CREATE TRIGGER IF NOT EXISTS pois_unindex_name AFTER DELETE ON pois BEGIN
+        DELETE FROM poi_names WHERE rowid = OLD.id;
+    END;
+";
+
+const POI_NAMES_BACKFILL: &str = "
+    DELETE FROM poi_names;
+    INSERT INTO poi_names (rowid, name)
+    SELECT id, json_extract(tags, '$.name') FROM pois
+    WHERE json_extract(tags, '$.name') IS NOT NULL;
+";
+
+/// Create the `poi_names` full-text table and the triggers that keep it in
+/// step with `pois`.
+///
+/// `existing_version` is the `pois` schema version found before the caller
+/// upgraded it. Databases older than [`POI_NAMES_SINCE`] have the names of
+/// their existing rows indexed here. The `pois` table must already exist.
+///
+/// # Errors
+/// Returns the SQLite error when the table, triggers, or backfill fail.
+pub fn install_poi_name_search(
+    connection: &Connection,
+    existing_version: i64,
+) -> Result<(), rusqlite::Error> {
+    connection.execute_batch(POI_NAMES_SCHEMA)?;
+    if existing_version < i64::from(POI_NAMES_SINCE) {
+        connection.execute_batch(POI_NAMES_BACKFILL)?;
+    }
+    Ok(())
+}
+
+/// Report whether the database carries the `poi_names` table.
+pub(super) fn has_poi_names(connection: &Connection) -> Result<bool, rusqlite::Error> {
+    connection.query_row(
+        "SELECT EXISTS (
+            SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'poi_names'
+        )",
+        [],
+        |row| row.get(0),
+    )
+}
+
+/// Translate free text into an FTS5 query.
+///
+/// Every word must appear, in any order, and the last word also matches as
+/// a prefix so partially typed names still find results. Words are quoted,
+/// so FTS5 operators and punctuation in `text` are matched literally.
+/// Returns `None` when `text` holds no words.
+pub(super) fn match_expression(text: &str) -> Option<String> {
+    let words: Vec<_> = text.split_whitespace().collect();
+    let (last, rest) = words.split_last()?;
+    let quote = |word: &str| format!("\"{}\"", word.replace('"', "\"\""));
+    let mut terms: Vec<String> = rest.iter().map(|word| quote(word)).collect();
+    terms.push(format!("{}*", quote(last)));
+    Some(terms.join(" "))
+}
+
+/// Find POIs inside `bbox` whose names match `expression`, best match first.
+pub(super) fn search_names(
+    connection: &Connection,
+    expression: &str,
+    bbox: &Rect<f64>,
+) -> Result<Vec<PointOfInterest>, SqlitePoiStoreError> {
+    let mut statement = connection.prepare(
+        "SELECT pois.id, pois.lon, pois.lat, pois.tags
+         FROM poi_names JOIN pois ON pois.id = poi_names.rowid
+         WHERE poi_names MATCH ?1
+           AND pois.lon BETWEEN ?2 AND ?3
+           AND pois.lat BETWEEN ?4 AND ?5
+         ORDER BY poi_names.rank, pois.id",
+    )?;
+    let mut rows = statement.query((
+        expression,
+        bbox.min().x,
+        bbox.max().x,
+        bbox.min().y,
+        bbox.max().y,
+    ))?;
+    let mut pois = Vec::new();
+    while let Some(row) = rows.next()? {
+        pois.push(read_poi_row(row)?);
+    }
+    Ok(pois)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for translating search text into FTS5 queries.
+
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("pergamon", Some("\"pergamon\"*"))]
+    #[case("  Pergamon   Museum ", Some("\"Pergamon\" \"Museum\"*"))]
+    #[case("say \"hi\"", Some("\"say\" \"\"\"hi\"\"\"*"))]
+    #[case("museum OR NOT", Some("\"museum\" \"OR\" \"NOT\"*"))]
+    #[case("   ", None)]
+    fn match_expression_quotes_words(#[case] text: &str, #[case] expected: Option<&str>) {
+        assert_eq!(match_expression(text).as_deref(), expected);
+    }
+}
@@ -0,0 +1,155 @@
+//! Small pool of read-only resources shared across threads.
+//!
+//! SQLite connections cannot be used from two threads at once, so a single
+//! shared connection serializes every reader behind one lock. A [`ReadPool`]
+//! instead lends each caller its own resource, opening another when every
+//! pooled one is in use, and keeps up to `max_idle` of them for reuse.
+
+use std::{
+    fmt,
+    sync::{Mutex, PoisonError},
+};
+
+/// Idle resources kept by [`ReadPool::new`] callers that do not choose.
+pub const DEFAULT_MAX_IDLE: usize = 8;
+
+type Opener<T, E> = Box<dyn Fn() -> Result<T, E> + Send + Sync>;
+
+/// Pool of interchangeable read-only resources, such as database connections.
+///
+/// # Examples
+/// ```rust
+/// use wildside_core::store::ReadPool;
+///
+/// let pool: ReadPool<Vec<u8>, std::io::Error> = ReadPool::new(2, || Ok(Vec::new()));
+/// let len = pool.with(|buffer| buffer.len())?;
+/// assert_eq!(len, 0);
+/// assert_eq!(pool.idle_len(), 1);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct ReadPool<T, E> {
+    idle: Mutex<Vec<T>>,
+    max_idle: usize,
+    open: Opener<T, E>,
+}
+
+impl<T, E> fmt::Debug for ReadPool<T, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadPool")
+            .field("idle", &self.idle_len())
+            .field("max_idle", &self.max_idle)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, E> ReadPool<T, E> {
+    /// Create an empty pool that opens resources with `open` and keeps at
+    /// most `max_idle` of them between uses.
+    pub fn new<F>(max_idle: usize, open: F) -> Self
+    where
+        F: Fn() -> Result<T, E> + Send + Sync + 'static,
+    {
+        Self {
+            idle: Mutex::new(Vec::new()),
+            max_idle,
+            open: Box::new(open),
+        }
+    }
+
+    /// Add an already opened resource to the idle set while consuming
+    /// `self`, so the first caller does not pay to open one.
+    #[must_use]
+    pub fn with_idle(self, resource: T) -> Self {
+        self.release(resource);
+        self
+    }
+
+    /// Run `f` with a resource to itself, opening one if none is idle.
+    ///
+    /// The resource returns to the pool afterwards unless `max_idle` are
+    /// already waiting, in which case it is dropped.
+    ///
+    /// # Errors
+    /// Returns the opener's error when no resource is idle and a new one
+    /// cannot be opened.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> Result<R, E> {
+        let pooled = self
+            .idle
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop();
+        let resource = match pooled {
+            Some(resource) => resource,
+            None => (self.open)()?,
+        };
+        let result = f(&resource);
+        self.release(resource);
+        Ok(result)
+    }
+
+    /// Number of resources waiting to be reused.
+    pub fn idle_len(&self) -> usize {
+        self.idle
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    fn release(&self, resource: T) {
+        let mut idle = self.idle.lock().unwrap_or_else(PoisonError::into_inner);
+        if idle.len() < self.max_idle {
+            idle.push(resource);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for resource reuse and the idle limit.
+
+    use super::*;
+    use rstest::rstest;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    fn counting_pool(max_idle: usize) -> (ReadPool<usize, ()>, Arc<AtomicUsize>) {
+        let opened = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&opened);
+        let pool = ReadPool::new(max_idle, move || Ok(counter.fetch_add(1, Ordering::SeqCst)));
+        (pool, opened)
+    }
+
+    #[rstest]
+    fn sequential_uses_share_one_resource() {
+        let (pool, opened) = counting_pool(2);
+        for _ in 0..3 {
+            assert_eq!(pool.with(|id| *id), Ok(0));
+        }
+        assert_eq!(opened.load(Ordering::SeqCst), 1);
+    }
+
+    #[rstest]
+    fn nested_uses_open_another_resource() {
+        let (pool, opened) = counting_pool(1);
+        let ids = pool.with(|outer| (*outer, pool.with(|inner| *inner)));
+        assert_eq!(ids, Ok((0, Ok(1))));
+        assert_eq!(opened.load(Ordering::SeqCst), 2);
+        assert_eq!(pool.idle_len(), 1);
+    }
+
+    #[rstest]
+    fn seeded_resource_is_used_first() {
+        let (pool, opened) = counting_pool(1);
+        let pool = pool.with_idle(42);
+        assert_eq!(pool.with(|id| *id), Ok(42));
+        assert_eq!(opened.load(Ordering::SeqCst), 0);
+    }
+
+    #[rstest]
+    fn open_errors_are_returned() {
+        let pool: ReadPool<usize, &str> = ReadPool::new(1, || Err("unavailable"));
+        assert_eq!(pool.with(|id| *id), Err("unavailable"));
+    }
+}
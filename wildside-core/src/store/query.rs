@@ -0,0 +1,191 @@
+//! Filtered POI queries.
+//!
+//! A [`PoiQuery`] narrows a bounding-box query to POIs carrying particular
+//! tags or belonging to particular themes, so stores can skip non-matching
+//! POIs instead of handing every candidate to the caller.
+
+use geo::{Intersects, Rect};
+
+use crate::{PointOfInterest, Tags, Theme};
+
+/// Tag constraint applied by a [`PoiQuery`].
+///
+/// # Examples
+/// ```rust
+/// use wildside_core::{Tags, TagFilter};
+///
+/// let tags = Tags::from([("tourism".into(), "museum".into())]);
+/// assert!(TagFilter::key("tourism").matches(&tags));
+/// assert!(!TagFilter::key_value("tourism", "gallery").matches(&tags));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagFilter {
+    /// The tag key must be present, with any value.
+    Key(String),
+    /// The tag key must be present with exactly this value.
+    KeyValue(String, String),
+}
+
+impl TagFilter {
+    /// Require the presence of `key`.
+    pub fn key(key: impl Into<String>) -> Self {
+        Self::Key(key.into())
+    }
+
+    /// Require `key` to carry `value`.
+    pub fn key_value(key: impl Into<String>, value: impl Into<String>) -> Self {
+        Self::KeyValue(key.into(), value.into())
+    }
+
+    /// Tag key the filter inspects.
+    pub fn tag_key(&self) -> &str {
+        match self {
+            Self::Key(key) | Self::KeyValue(key, _) => key,
+        }
+    }
+
+    /// Whether `tags` satisfy the filter.
+    pub fn matches(&self, tags: &Tags) -> bool {
+        match self {
+            Self::Key(key) => tags.contains_key(key),
+            Self::KeyValue(key, value) => tags.get(key) == Some(value),
+        }
+    }
+}
+
+/// Bounding-box query restricted by tags and themes.
+///
+/// A POI matches when it lies inside `bbox` (boundary included), satisfies
+/// every entry of `required_tags`, and belongs to at least one of `themes`
+/// as judged by [`Theme::matches_tags`]. Empty `required_tags` or `themes`
+/// impose no constraint. Results are ordered by identifier and truncated to
+/// `limit` when set.
+///
+/// # Examples
+/// ```rust
+/// use geo::{Coord, Rect};
+/// use wildside_core::{PoiQuery, TagFilter, Theme};
+///
+/// let bbox = Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 1.0, y: 1.0 });
+/// let query = PoiQuery::new(bbox)
+///     .with_required_tag(TagFilter::key("name"))
+///     .with_theme(Theme::History)
+///     .with_limit(50);
+/// assert_eq!(query.limit, Some(50));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoiQuery {
+    /// Area to search, in WGS84 longitude/latitude degrees.
+    pub bbox: Rect<f64>,
+    /// Tag constraints every returned POI must satisfy.
+    pub required_tags: Vec<TagFilter>,
+    /// Themes of which every returned POI must match at least one.
+    pub themes: Vec<Theme>,
+    /// Maximum number of POIs to return.
+    pub limit: Option<usize>,
+}
+
+impl PoiQuery {
+    /// Query every POI inside `bbox`.
+    pub fn new(bbox: Rect<f64>) -> Self {
+        Self {
+            bbox,
+            required_tags: Vec::new(),
+            themes: Vec::new(),
+            limit: None,
+        }
+    }
+
+    /// Add a tag constraint while consuming `self`, enabling chaining.
+    #[must_use]
+    pub fn with_required_tag(mut self, filter: TagFilter) -> Self {
+        self.required_tags.push(filter);
+        self
+    }
+
+    /// Add an accepted theme while consuming `self`, enabling chaining.
+    #[must_use]
+    pub fn with_theme(mut self, theme: Theme) -> Self {
+        self.themes.push(theme);
+        self
+    }
+
+    /// Cap the number of returned POIs while consuming `self`.
+    #[must_use]
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Whether `poi` satisfies the tag and theme constraints, ignoring the
+    /// bounding box.
+    pub fn matches_tags(&self, poi: &PointOfInterest) -> bool {
+        self.required_tags
+            .iter()
+            .all(|filter| filter.matches(&poi.tags))
+            && (self.themes.is_empty()
+                || self
+                    .themes
+                    .iter()
+                    .any(|theme| theme.matches_tags(&poi.tags)))
+    }
+
+    /// Whether `poi` satisfies every constraint of the query.
+    pub fn matches(&self, poi: &PointOfInterest) -> bool {
+        self.bbox.intersects(&poi.location) && self.matches_tags(poi)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for query matching rules.
+
+    use super::*;
+    use geo::Coord;
+    use rstest::rstest;
+
+    fn unit_bbox() -> Rect<f64> {
+        Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 1.0, y: 1.0 })
+    }
+
+    fn poi(tags: &[(&str, &str)]) -> PointOfInterest {
+        PointOfInterest::new(
+            1,
+            Coord { x: 0.0, y: 0.0 },
+            tags.iter()
+                .map(|(key, value)| ((*key).to_owned(), (*value).to_owned()))
+                .collect(),
+        )
+    }
+
+    #[rstest]
+    fn unconstrained_query_matches_everything_in_bbox() {
+        assert!(PoiQuery::new(unit_bbox()).matches(&poi(&[])));
+    }
+
+    #[rstest]
+    fn required_tags_must_all_match() {
+        let query = PoiQuery::new(unit_bbox())
+            .with_required_tag(TagFilter::key("name"))
+            .with_required_tag(TagFilter::key_value("tourism", "museum"));
+        assert!(query.matches(&poi(&[("name", "Hall"), ("tourism", "museum")])));
+        assert!(!query.matches(&poi(&[("tourism", "museum")])));
+        assert!(!query.matches(&poi(&[("name", "Hall"), ("tourism", "gallery")])));
+    }
+
+    #[rstest]
+    fn any_theme_may_match() {
+        let query = PoiQuery::new(unit_bbox())
+            .with_theme(Theme::Art)
+            .with_theme(Theme::History);
+        assert!(query.matches(&poi(&[("historic", "memorial")])));
+        assert!(!query.matches(&poi(&[("shop", "bakery")])));
+    }
+
+    #[rstest]
+    fn pois_outside_bbox_do_not_match() {
+        let mut outside = poi(&[]);
+        outside.location = Coord { x: 2.0, y: 0.0 };
+        assert!(!PoiQuery::new(unit_bbox()).matches(&outside));
+    }
+}
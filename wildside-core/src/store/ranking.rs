@@ -0,0 +1,102 @@
+//! Popularity-ranked POI queries.
+//!
+//! Solvers keep only the best few candidates in a search area, so ranking
+//! inside the store lets it skip copying, or reading the tags of, every POI
+//! that would be discarded. Scores come from a [`PopularityLookup`], which
+//! `wildside-scorer` implements for its `PopularityScores` and which plain
+//! maps implement for tests and ad-hoc tables.
+
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    hash::BuildHasher,
+};
+
+/// Popularity scores keyed by POI identifier.
+///
+/// # Examples
+/// ```rust
+/// use std::collections::BTreeMap;
+/// use wildside_core::store::PopularityLookup;
+///
+/// let scores = BTreeMap::from([(1_u64, 0.8_f32)]);
+/// assert_eq!(scores.popularity(1), Some(0.8));
+/// assert_eq!(scores.popularity(2), None);
+/// ```
+pub trait PopularityLookup {
+    /// Return the score of the POI with identifier `id`, if it has one.
+    fn popularity(&self, id: u64) -> Option<f32>;
+}
+
+impl PopularityLookup for BTreeMap<u64, f32> {
+    fn popularity(&self, id: u64) -> Option<f32> {
+        self.get(&id).copied()
+    }
+}
+
+impl<S: BuildHasher> PopularityLookup for HashMap<u64, f32, S> {
+    fn popularity(&self, id: u64) -> Option<f32> {
+        self.get(&id).copied()
+    }
+}
+
+impl<T: PopularityLookup + ?Sized> PopularityLookup for &T {
+    fn popularity(&self, id: u64) -> Option<f32> {
+        (**self).popularity(id)
+    }
+}
+
+/// Keep the `limit` highest-scored of `items`, best first.
+///
+/// POIs without a score rank as zero, and equal scores are ordered by
+/// identifier so the result does not depend on the input order.
+pub(crate) fn top_by_popularity<T>(
+    items: impl IntoIterator<Item = (u64, T)>,
+    limit: usize,
+    scores: &dyn PopularityLookup,
+) -> Vec<T> {
+    if limit == 0 {
+        return Vec::new();
+    }
+    let mut ranked: Vec<(f32, u64, T)> = items
+        .into_iter()
+        .map(|(id, item)| (scores.popularity(id).unwrap_or(0.0), id, item))
+        .collect();
+    let order = |lhs: &(f32, u64, T), rhs: &(f32, u64, T)| -> Ordering {
+        rhs.0.total_cmp(&lhs.0).then(lhs.1.cmp(&rhs.1))
+    };
+    if ranked.len() > limit {
+        ranked.select_nth_unstable_by(limit - 1, order);
+        ranked.truncate(limit);
+    }
+    ranked.sort_unstable_by(order);
+    ranked.into_iter().map(|(_, _, item)| item).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for popularity ranking.
+
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, vec![])]
+    #[case(2, vec![3, 1])]
+    #[case(10, vec![3, 1, 4, 2])]
+    fn keeps_highest_scores_with_identifier_tie_break(
+        #[case] limit: usize,
+        #[case] expected: Vec<u64>,
+    ) {
+        let scores: BTreeMap<u64, f32> = BTreeMap::from([(1, 0.5), (2, 0.1), (3, 0.9), (4, 0.5)]);
+        let top = top_by_popularity([4, 2, 1, 3].map(|id| (id, id)), limit, &scores);
+        assert_eq!(top, expected);
+    }
+
+    #[rstest]
+    fn unscored_items_rank_as_zero() {
+        let scores: HashMap<u64, f32> = HashMap::from([(1, 0.2), (2, -0.5)]);
+        let top = top_by_popularity([(1, 1), (2, 2), (3, 3)], 3, &scores);
+        assert_eq!(top, vec![1, 3, 2]);
+    }
+}
@@ -0,0 +1,157 @@
+//! Reading spatial index artefacts in every supported format version.
+
+use std::{
+    io::{self, Read},
+    path::Path,
+};
+
+use bincode::deserialize_from;
+
+use crate::artefact_io::open_parent_dir;
+use crate::formats::{self, FormatVersionError};
+use crate::{PointOfInterest, Tags};
+
+use super::{IndexContents, IndexEntry, SPATIAL_INDEX_MAGIC, SpatialIndexError, TAG_BLOCK_FLAG};
+
+/// Read one length-prefixed block written by [`push_block`](super::push_block), still
+/// compressed.
+pub(super) fn read_block(reader: &mut dyn Read) -> io::Result<Vec<u8>> {
+    let mut length_bytes = [0_u8; 8];
+    reader.read_exact(&mut length_bytes)?;
+    let length = u64::from_le_bytes(length_bytes);
+    let mut compressed = Vec::new();
+    Read::take(&mut *reader, length).read_to_end(&mut compressed)?;
+    if u64::try_from(compressed.len()).ok() != Some(length) {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "spatial index block is truncated",
+        ));
+    }
+    Ok(compressed)
+}
+
+/// Load POI entries from a spatial index artefact.
+pub(crate) fn load_index_entries(path: &Path) -> Result<IndexContents, SpatialIndexError> {
+    let io_error = |source| SpatialIndexError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let (dir, file_name) = open_parent_dir(path).map_err(io_error)?;
+    let mut file = dir.open(file_name).map_err(io_error)?;
+
+    let mut magic = [0_u8; 4];
+    file.read_exact(&mut magic).map_err(io_error)?;
+    if magic != SPATIAL_INDEX_MAGIC {
+        return Err(SpatialIndexError::InvalidMagic {
+            expected: SPATIAL_INDEX_MAGIC,
+            found: magic,
+        });
+    }
+
+    let mut version_bytes = [0_u8; 2];
+    file.read_exact(&mut version_bytes).map_err(io_error)?;
+    let version = u16::from_le_bytes(version_bytes);
+    formats::SPATIAL_INDEX.negotiate(i64::from(version))?;
+    // `check_decoders` guarantees a decoder for every readable version.
+    let decode = decoder_for(version).ok_or(FormatVersionError {
+        kind: formats::SPATIAL_INDEX.kind,
+        found: i64::from(version),
+        oldest_readable: formats::SPATIAL_INDEX.oldest_readable,
+        current: formats::SPATIAL_INDEX.current,
+    })?;
+    decode(&mut file, path)
+}
+
+/// Decoder for the payload following the header of one format version.
+type PayloadDecoder = fn(&mut dyn Read, &Path) -> Result<IndexContents, SpatialIndexError>;
+
+/// Payload decoders for every format version this build reads.
+///
+/// Bumping [`formats::SPATIAL_INDEX`] means adding a decoder here for the new
+/// version and keeping the old one until `oldest_readable` moves past it, so
+/// existing artefacts keep opening across releases.
+const PAYLOAD_DECODERS: [(u16, PayloadDecoder); 2] = [(2, read_v2_payload), (3, read_v3_payload)];
+
+const _: () = check_decoders(&PAYLOAD_DECODERS);
+
+/// Reject decoder tables that miss a readable version at compile time.
+const fn check_decoders(decoders: &[(u16, PayloadDecoder)]) {
+    let mut version = formats::SPATIAL_INDEX.oldest_readable;
+    while version <= formats::SPATIAL_INDEX.current {
+        let mut i = 0;
+        let mut found = false;
+        while i < decoders.len() {
+            found |= decoders[i].0 == version;
+            i += 1;
+        }
+        assert!(found, "readable spatial index version lacks a decoder");
+        version += 1;
+    }
+}
+
+fn decoder_for(version: u16) -> Option<PayloadDecoder> {
+    PAYLOAD_DECODERS
+        .iter()
+        .find(|(decoded, _)| *decoded == version)
+        .map(|(_, decode)| *decode)
+}
+
+/// Decode a version-2 payload: full POI records with their tags.
+fn read_v2_payload(reader: &mut dyn Read, path: &Path) -> Result<IndexContents, SpatialIndexError> {
+    let pois = deserialize_from(reader).map_err(|source| SpatialIndexError::Decode {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    Ok(IndexContents {
+        pois,
+        has_tags: true,
+        version: 2,
+    })
+}
+
+/// Decode a version-3 payload: the flags byte and compressed blocks.
+fn read_v3_payload(reader: &mut dyn Read, path: &Path) -> Result<IndexContents, SpatialIndexError> {
+    let io_error = |source| SpatialIndexError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let decompress_error = |source| SpatialIndexError::Decompress {
+        path: path.to_path_buf(),
+        source,
+    };
+    let decode_error = |source| SpatialIndexError::Decode {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    let mut flags = [0_u8; 1];
+    reader.read_exact(&mut flags).map_err(io_error)?;
+    let has_tags = u8::from_le_bytes(flags) & TAG_BLOCK_FLAG != 0;
+
+    let location_block = zstd::decode_all(read_block(reader).map_err(io_error)?.as_slice())
+        .map_err(decompress_error)?;
+    let locations: Vec<IndexEntry> = bincode::deserialize(&location_block).map_err(decode_error)?;
+    let mut pois: Vec<PointOfInterest> = locations.into_iter().map(PointOfInterest::from).collect();
+
+    if has_tags {
+        let tag_block = zstd::decode_all(read_block(reader).map_err(io_error)?.as_slice())
+            .map_err(decompress_error)?;
+        let tag_maps: Vec<Tags> = bincode::deserialize(&tag_block).map_err(decode_error)?;
+        if tag_maps.len() != pois.len() {
+            return Err(SpatialIndexError::TagCountMismatch {
+                path: path.to_path_buf(),
+                entries: pois.len(),
+                tags: tag_maps.len(),
+            });
+        }
+        for (poi, tags) in pois.iter_mut().zip(tag_maps) {
+            poi.tags = tags;
+        }
+    }
+
+    Ok(IndexContents {
+        pois,
+        has_tags,
+        version: 3,
+    })
+}
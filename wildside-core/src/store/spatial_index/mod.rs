@@ -0,0 +1,339 @@
+//! Persisted spatial index file format helpers.
+//!
+//! These helpers define the on-disk representation for the R\*-tree indices
+//! used by the SQLite-backed POI store.
+//!
+//! Every file starts with the `WSPI` magic and a little-endian `u16` version.
+//! Version 3 follows this with a flags byte and one or two length-prefixed
+//! zstd blocks: the `bincode` list of POI ids and locations, then, when
+//! [`TAG_BLOCK_FLAG`] is set, the `bincode` list of their tag maps in the same
+//! order. Version 2 files hold a plain `bincode` list of full
+//! [`PointOfInterest`] records and are still read.
+
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use geo::Coord;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::artefact_io::open_parent_dir;
+use crate::formats::{self, FormatVersionError};
+use crate::{PointOfInterest, Tags};
+
+mod decode;
+
+pub(crate) use decode::load_index_entries;
+
+/// File identifier for persisted spatial indices.
+pub(crate) const SPATIAL_INDEX_MAGIC: [u8; 4] = *b"WSPI";
+
+/// Version stamped on newly written spatial index files.
+pub(crate) const SPATIAL_INDEX_VERSION: u16 = formats::SPATIAL_INDEX.current;
+
+/// Header flag marking a version-3 file that carries a tag block.
+const TAG_BLOCK_FLAG: u8 = 0b0000_0001;
+
+/// zstd level used unless [`SpatialIndexOptions`] chooses another.
+pub const DEFAULT_COMPRESSION_LEVEL: i32 = 3;
+
+/// Error emitted when loading or validating the persisted spatial index.
+#[derive(Debug, Error)]
+pub enum SpatialIndexError {
+    /// The index file could not be read from disk.
+    #[error("failed to read spatial index from {path}: {source}")]
+    Io {
+        /// Location of the persisted R\*-tree artefact.
+        path: PathBuf,
+        /// Underlying I/O error.
+        #[source]
+        source: std::io::Error,
+    },
+    /// A compressed block could not be decompressed.
+    #[error("failed to decompress spatial index from {path}: {source}")]
+    Decompress {
+        /// Location of the persisted R\*-tree artefact.
+        path: PathBuf,
+        /// Error reported by the zstd decoder.
+        #[source]
+        source: std::io::Error,
+    },
+    /// The serialized R\*-tree could not be decoded.
+    #[error("failed to decode spatial index from {path}: {source}")]
+    Decode {
+        /// Location of the persisted R\*-tree artefact.
+        path: PathBuf,
+        /// Decoder error returned by `bincode`.
+        #[source]
+        source: bincode::Error,
+    },
+    /// The tag block does not line up with the indexed locations.
+    #[error("spatial index at {path} lists {entries} POIs but {tags} tag maps")]
+    TagCountMismatch {
+        /// Location of the persisted R\*-tree artefact.
+        path: PathBuf,
+        /// Number of indexed locations.
+        entries: usize,
+        /// Number of tag maps in the tag block.
+        tags: usize,
+    },
+    /// The file did not contain the expected header.
+    #[error("invalid spatial index magic: expected {expected:?}, found {found:?}")]
+    InvalidMagic {
+        /// Expected byte sequence identifying a spatial index file.
+        expected: [u8; 4],
+        /// Sequence read from the file.
+        found: [u8; 4],
+    },
+    /// The file's format version is outside the readable range.
+    #[error(transparent)]
+    UnsupportedVersion(#[from] FormatVersionError),
+}
+
+/// Error emitted when serializing a spatial index to disk.
+#[derive(Debug, Error)]
+pub enum SpatialIndexWriteError {
+    /// Writing bytes to disk failed.
+    #[error("failed to write spatial index to {path}: {source}")]
+    Io {
+        /// Destination file path.
+        path: PathBuf,
+        /// Underlying I/O error.
+        #[source]
+        source: std::io::Error,
+    },
+    /// The in-memory representation could not be encoded.
+    #[error("failed to encode spatial index for {path}: {source}")]
+    Encode {
+        /// Destination file path.
+        path: PathBuf,
+        /// Encoder failure from `bincode`.
+        #[source]
+        source: bincode::Error,
+    },
+    /// An encoded block could not be compressed.
+    #[error("failed to compress spatial index for {path}: {source}")]
+    Compress {
+        /// Destination file path.
+        path: PathBuf,
+        /// Error reported by the zstd encoder.
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// Error emitted by [`upgrade_spatial_index`].
+#[derive(Debug, Error)]
+pub enum SpatialIndexUpgradeError {
+    /// The existing artefact could not be read.
+    #[error(transparent)]
+    Read(#[from] SpatialIndexError),
+    /// The upgraded artefact could not be written.
+    #[error(transparent)]
+    Write(#[from] SpatialIndexWriteError),
+}
+
+/// Layout choices for [`write_spatial_index_with_options`].
+///
+/// # Examples
+/// ```rust
+/// use wildside_core::store::{DEFAULT_COMPRESSION_LEVEL, SpatialIndexOptions};
+///
+/// let options = SpatialIndexOptions {
+///     embed_tags: false,
+///     ..SpatialIndexOptions::default()
+/// };
+/// assert_eq!(options.compression_level, DEFAULT_COMPRESSION_LEVEL);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpatialIndexOptions {
+    /// Store each POI's tags in a compressed block after the locations.
+    ///
+    /// Without the block the file holds only ids and locations, and
+    /// `SqlitePoiStore` reads tags from `pois.db` when it opens the index.
+    pub embed_tags: bool,
+    /// zstd compression level applied to every block.
+    pub compression_level: i32,
+}
+
+impl Default for SpatialIndexOptions {
+    fn default() -> Self {
+        Self {
+            embed_tags: true,
+            compression_level: DEFAULT_COMPRESSION_LEVEL,
+        }
+    }
+}
+
+/// Id and location of one indexed POI, as stored in version-3 files.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct IndexEntry {
+    id: u64,
+    x: f64,
+    y: f64,
+}
+
+impl From<&PointOfInterest> for IndexEntry {
+    fn from(poi: &PointOfInterest) -> Self {
+        Self {
+            id: poi.id,
+            x: poi.location.x,
+            y: poi.location.y,
+        }
+    }
+}
+
+impl From<IndexEntry> for PointOfInterest {
+    fn from(entry: IndexEntry) -> Self {
+        Self::with_empty_tags(
+            entry.id,
+            Coord {
+                x: entry.x,
+                y: entry.y,
+            },
+        )
+    }
+}
+
+/// POIs read from a spatial index artefact.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct IndexContents {
+    /// Indexed POIs in file order; their tags are empty unless `has_tags`.
+    pub(crate) pois: Vec<PointOfInterest>,
+    /// Whether the artefact carried tag maps.
+    pub(crate) has_tags: bool,
+    /// Format version the artefact was written in.
+    pub(crate) version: u16,
+}
+
+/// Persist a spatial index artefact containing the provided POIs.
+///
+/// The file is written in the current `WSPI` format expected by
+/// `SqlitePoiStore`, with tags embedded and the default compression level.
+/// Existing files are truncated, so rewriting an artefact from an older
+/// release also upgrades it to the current version.
+pub fn write_spatial_index(
+    path: &Path,
+    entries: &[PointOfInterest],
+) -> Result<(), SpatialIndexWriteError> {
+    write_index(path, entries)
+}
+
+/// Persist a spatial index artefact using the provided layout options.
+///
+/// Omitting tags keeps planet-scale artefacts small when the tags already
+/// live in `pois.db`.
+pub fn write_spatial_index_with_options(
+    path: &Path,
+    entries: &[PointOfInterest],
+    options: SpatialIndexOptions,
+) -> Result<(), SpatialIndexWriteError> {
+    write_index_with(path, entries, options)
+}
+
+/// Rewrite a spatial index artefact in the current format version.
+///
+/// Any readable version is accepted, so artefacts produced by earlier
+/// releases can be brought forward without re-running ingestion. The file
+/// keeps or omits its tags as before. Returns the version the file was
+/// upgraded from, or `None` when it was already current and left untouched.
+///
+/// # Examples
+/// ```rust,no_run
+/// use std::path::Path;
+/// use wildside_core::store::upgrade_spatial_index;
+///
+/// let upgraded_from = upgrade_spatial_index(Path::new("pois.rstar"))?;
+/// assert_ne!(upgraded_from, Some(3));
+/// # Ok::<(), wildside_core::store::SpatialIndexUpgradeError>(())
+/// ```
+pub fn upgrade_spatial_index(path: &Path) -> Result<Option<u16>, SpatialIndexUpgradeError> {
+    let contents = load_index_entries(path)?;
+    if contents.version == SPATIAL_INDEX_VERSION {
+        return Ok(None);
+    }
+    let options = SpatialIndexOptions {
+        embed_tags: contents.has_tags,
+        ..SpatialIndexOptions::default()
+    };
+    write_index_with(path, &contents.pois, options)?;
+    Ok(Some(contents.version))
+}
+
+/// Read every POI recorded in a spatial index artefact, in file order.
+///
+/// Indexes written without tags yield POIs with empty tag maps, so callers
+/// such as integrity checks can compare identifiers and locations against
+/// `pois.db` without opening a store.
+///
+/// # Errors
+/// Returns [`SpatialIndexError`] when the file cannot be read, is not in a
+/// readable version, or is corrupt.
+pub fn read_spatial_index(path: &Path) -> Result<Vec<PointOfInterest>, SpatialIndexError> {
+    load_index_entries(path).map(|contents| contents.pois)
+}
+
+/// Persist a spatial index file without exposing the public wrapper signature.
+pub(crate) fn write_index(
+    path: &Path,
+    entries: &[PointOfInterest],
+) -> Result<(), SpatialIndexWriteError> {
+    write_index_with(path, entries, SpatialIndexOptions::default())
+}
+
+fn write_index_with(
+    path: &Path,
+    entries: &[PointOfInterest],
+    options: SpatialIndexOptions,
+) -> Result<(), SpatialIndexWriteError> {
+    let io_error = |source| SpatialIndexWriteError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let encode_error = |source| SpatialIndexWriteError::Encode {
+        path: path.to_path_buf(),
+        source,
+    };
+    let compress_error = |source| SpatialIndexWriteError::Compress {
+        path: path.to_path_buf(),
+        source,
+    };
+
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&SPATIAL_INDEX_MAGIC);
+    bytes.extend_from_slice(&SPATIAL_INDEX_VERSION.to_le_bytes());
+    bytes.push(if options.embed_tags {
+        TAG_BLOCK_FLAG
+    } else {
+        0
+    });
+
+    let locations: Vec<IndexEntry> = entries.iter().map(IndexEntry::from).collect();
+    let location_block = bincode::serialize(&locations).map_err(encode_error)?;
+    push_block(&mut bytes, &location_block, options.compression_level).map_err(compress_error)?;
+    if options.embed_tags {
+        let tags: Vec<&Tags> = entries.iter().map(|poi| &poi.tags).collect();
+        let tag_block = bincode::serialize(&tags).map_err(encode_error)?;
+        push_block(&mut bytes, &tag_block, options.compression_level).map_err(compress_error)?;
+    }
+
+    let (dir, file_name) = open_parent_dir(path).map_err(io_error)?;
+    let mut file = dir.create(file_name).map_err(io_error)?;
+    file.write_all(&bytes).map_err(io_error)?;
+    file.sync_all().map_err(io_error)
+}
+
+/// Compress `block` and append it to `out` behind a little-endian `u64`
+/// length.
+fn push_block(out: &mut Vec<u8>, block: &[u8], level: i32) -> io::Result<()> {
+    let compressed = zstd::encode_all(block, level)?;
+    let length = u64::try_from(compressed.len()).map_err(io::Error::other)?;
+    out.extend_from_slice(&length.to_le_bytes());
+    out.extend_from_slice(&compressed);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests;
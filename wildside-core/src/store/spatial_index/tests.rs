@@ -0,0 +1,239 @@
+//! Tests for spatial index persistence and validation.
+
+use super::decode::read_block;
+use super::*;
+use crate::{PointOfInterest, Tags};
+use bincode::serialize_into;
+use geo::Coord;
+use rstest::{fixture, rstest};
+use std::{
+    fs::File,
+    io::{Read, Write},
+    path::PathBuf,
+};
+use tempfile::TempDir;
+
+fn poi(id: u64, x: f64, y: f64, name: &str) -> PointOfInterest {
+    PointOfInterest::new(
+        id,
+        Coord { x, y },
+        Tags::from([(String::from("name"), String::from(name))]),
+    )
+}
+
+fn write_header(file: &mut File, version: u16) {
+    file.write_all(&SPATIAL_INDEX_MAGIC)
+        .expect("write magic header");
+    file.write_all(&version.to_le_bytes())
+        .expect("write version");
+}
+
+#[fixture]
+fn temp_index_path() -> (TempDir, PathBuf) {
+    let dir = TempDir::new().expect("create temp dir");
+    let index_path = dir.path().join("pois.rstar");
+    (dir, index_path)
+}
+
+#[fixture]
+fn sample_pois() -> Vec<PointOfInterest> {
+    vec![poi(1, 0.0, 0.0, "centre"), poi(2, 2.0, 2.0, "museum")]
+}
+
+#[rstest]
+fn load_index_entries_round_trips_entries(
+    #[from(temp_index_path)] (_dir, index_path): (TempDir, PathBuf),
+    sample_pois: Vec<PointOfInterest>,
+) {
+    write_index(&index_path, &sample_pois).expect("persist index");
+
+    let loaded = load_index_entries(&index_path).expect("load index");
+    assert!(loaded.has_tags);
+    assert_eq!(loaded.pois, sample_pois);
+}
+
+#[rstest]
+fn load_index_entries_omits_tags_when_not_embedded(
+    #[from(temp_index_path)] (_dir, index_path): (TempDir, PathBuf),
+    sample_pois: Vec<PointOfInterest>,
+) {
+    let options = SpatialIndexOptions {
+        embed_tags: false,
+        ..SpatialIndexOptions::default()
+    };
+    write_spatial_index_with_options(&index_path, &sample_pois, options).expect("persist index");
+
+    let loaded = load_index_entries(&index_path).expect("load index");
+    assert!(!loaded.has_tags);
+    let expected: Vec<PointOfInterest> = sample_pois
+        .into_iter()
+        .map(|poi| PointOfInterest::with_empty_tags(poi.id, poi.location))
+        .collect();
+    assert_eq!(loaded.pois, expected);
+}
+
+#[rstest]
+fn load_index_entries_reads_version_2_files(
+    #[from(temp_index_path)] (_dir, index_path): (TempDir, PathBuf),
+    sample_pois: Vec<PointOfInterest>,
+) {
+    let mut file = File::create(&index_path).expect("create index file");
+    write_header(&mut file, 2);
+    serialize_into(&mut file, &sample_pois).expect("write payload");
+    drop(file);
+
+    let loaded = load_index_entries(&index_path).expect("load legacy index");
+    assert_eq!(loaded.version, 2);
+    assert!(loaded.has_tags);
+    assert_eq!(loaded.pois, sample_pois);
+}
+
+#[rstest]
+fn upgrade_spatial_index_rewrites_legacy_files(
+    #[from(temp_index_path)] (_dir, index_path): (TempDir, PathBuf),
+    sample_pois: Vec<PointOfInterest>,
+) {
+    let mut file = File::create(&index_path).expect("create index file");
+    write_header(&mut file, 2);
+    serialize_into(&mut file, &sample_pois).expect("write payload");
+    drop(file);
+
+    let upgraded_from = upgrade_spatial_index(&index_path).expect("upgrade index");
+    assert_eq!(upgraded_from, Some(2));
+
+    let loaded = load_index_entries(&index_path).expect("load upgraded index");
+    assert_eq!(loaded.version, SPATIAL_INDEX_VERSION);
+    assert!(loaded.has_tags);
+    assert_eq!(loaded.pois, sample_pois);
+}
+
+#[rstest]
+fn upgrade_spatial_index_leaves_current_files_alone(
+    #[from(temp_index_path)] (_dir, index_path): (TempDir, PathBuf),
+    sample_pois: Vec<PointOfInterest>,
+) {
+    write_index(&index_path, &sample_pois).expect("persist index");
+    let before = std::fs::read(&index_path).expect("read index");
+
+    let upgraded_from = upgrade_spatial_index(&index_path).expect("upgrade index");
+    assert_eq!(upgraded_from, None);
+    assert_eq!(std::fs::read(&index_path).expect("reread index"), before);
+}
+
+#[rstest]
+fn load_index_entries_returns_io_error_for_missing_file() {
+    let missing_path = PathBuf::from("/non-existent/index-file");
+    let error = load_index_entries(&missing_path).expect_err("missing file should error");
+    assert!(matches!(error, SpatialIndexError::Io { .. }));
+}
+
+#[rstest]
+fn load_index_entries_errors_on_invalid_magic(
+    #[from(temp_index_path)] (_dir, index_path): (TempDir, PathBuf),
+) {
+    std::fs::write(&index_path, b"BAD!").expect("write corrupt header");
+
+    let error = load_index_entries(&index_path).expect_err("invalid magic should fail");
+    assert!(matches!(error, SpatialIndexError::InvalidMagic { .. }));
+}
+
+#[rstest]
+fn load_index_entries_errors_on_truncated_block(
+    #[from(temp_index_path)] (_dir, index_path): (TempDir, PathBuf),
+) {
+    let mut file = File::create(&index_path).expect("create index file");
+    write_header(&mut file, SPATIAL_INDEX_VERSION);
+    file.write_all(&[0]).expect("write flags");
+    file.write_all(&64_u64.to_le_bytes())
+        .expect("write block length");
+    drop(file);
+
+    let error = load_index_entries(&index_path).expect_err("truncated block should fail");
+    assert!(matches!(error, SpatialIndexError::Io { .. }));
+}
+
+#[rstest]
+fn load_index_entries_errors_on_corrupt_block(
+    #[from(temp_index_path)] (_dir, index_path): (TempDir, PathBuf),
+) {
+    let mut file = File::create(&index_path).expect("create index file");
+    write_header(&mut file, SPATIAL_INDEX_VERSION);
+    file.write_all(&[0]).expect("write flags");
+    file.write_all(&4_u64.to_le_bytes())
+        .expect("write block length");
+    file.write_all(b"junk").expect("write block");
+    drop(file);
+
+    let error = load_index_entries(&index_path).expect_err("corrupt block should fail");
+    assert!(matches!(error, SpatialIndexError::Decompress { .. }));
+}
+
+#[rstest]
+fn load_index_entries_errors_on_decode_failure(
+    #[from(temp_index_path)] (_dir, index_path): (TempDir, PathBuf),
+) {
+    let mut file = File::create(&index_path).expect("create index file");
+    write_header(&mut file, 2);
+    drop(file);
+
+    let error = load_index_entries(&index_path).expect_err("decode should fail");
+    assert!(matches!(error, SpatialIndexError::Decode { .. }));
+}
+
+#[rstest]
+fn load_index_entries_errors_on_unsupported_version(
+    #[from(temp_index_path)] (_dir, index_path): (TempDir, PathBuf),
+) {
+    let mut file = File::create(&index_path).expect("create index file");
+    write_header(&mut file, SPATIAL_INDEX_VERSION + 1);
+    serialize_into(&mut file, &Vec::<PointOfInterest>::new()).expect("write payload");
+    drop(file);
+
+    let error = load_index_entries(&index_path).expect_err("unsupported version should fail");
+    assert!(matches!(
+        error,
+        SpatialIndexError::UnsupportedVersion(FormatVersionError { found, current, .. })
+            if found == i64::from(SPATIAL_INDEX_VERSION + 1) && current == SPATIAL_INDEX_VERSION
+    ));
+}
+
+#[rstest]
+fn load_index_entries_errors_on_legacy_version(
+    #[from(temp_index_path)] (_dir, index_path): (TempDir, PathBuf),
+) {
+    let legacy = formats::SPATIAL_INDEX.oldest_readable - 1;
+    let mut file = File::create(&index_path).expect("create index file");
+    write_header(&mut file, legacy);
+    drop(file);
+
+    let error = load_index_entries(&index_path).expect_err("legacy version should fail");
+    assert!(matches!(
+        error,
+        SpatialIndexError::UnsupportedVersion(FormatVersionError { found, current, .. })
+            if found == i64::from(legacy) && current == SPATIAL_INDEX_VERSION
+    ));
+}
+
+#[rstest]
+fn write_index_persists_spatial_index_file(
+    #[from(temp_index_path)] (_dir, index_path): (TempDir, PathBuf),
+    sample_pois: Vec<PointOfInterest>,
+) {
+    write_index(&index_path, &sample_pois).expect("persist index");
+    let mut file = File::open(&index_path).expect("open index");
+    let mut magic = [0_u8; 4];
+    file.read_exact(&mut magic).expect("read magic");
+    assert_eq!(magic, SPATIAL_INDEX_MAGIC);
+    let mut version_bytes = [0_u8; 2];
+    file.read_exact(&mut version_bytes).expect("read version");
+    assert_eq!(u16::from_le_bytes(version_bytes), SPATIAL_INDEX_VERSION);
+    let mut flags = [0_u8; 1];
+    file.read_exact(&mut flags).expect("read flags");
+    assert_eq!(flags, [TAG_BLOCK_FLAG]);
+
+    let block = read_block(&mut file).expect("read location block");
+    let decompressed = zstd::decode_all(block.as_slice()).expect("decompress locations");
+    let locations: Vec<IndexEntry> = bincode::deserialize(&decompressed).expect("decode locations");
+    let expected: Vec<IndexEntry> = sample_pois.iter().map(IndexEntry::from).collect();
+    assert_eq!(locations, expected);
+}
@@ -0,0 +1,51 @@
+//! Errors raised while opening or reading the SQLite POI store.
+
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::formats::FormatVersionError;
+
+use crate::store::spatial_index::SpatialIndexError;
+
+/// Error raised when reading or validating persisted POI artefacts.
+#[derive(Debug, Error)]
+pub enum SqlitePoiStoreError {
+    /// Opening the SQLite database failed.
+    #[error("failed to open SQLite database at {path}: {source}")]
+    OpenDatabase {
+        /// Location of the SQLite database on disk.
+        path: PathBuf,
+        /// Source error returned by `rusqlite`.
+        #[source]
+        source: rusqlite::Error,
+    },
+    /// The `pois` table was written in an unsupported schema version.
+    #[error(transparent)]
+    PoisSchema(#[from] FormatVersionError),
+    /// Errors encountered while loading or validating the persisted R\*-tree.
+    #[error(transparent)]
+    SpatialIndex(#[from] SpatialIndexError),
+    /// The SQLite database did not contain a POI referenced by the index.
+    #[error("point of interest {id} listed in the index is missing from the database")]
+    MissingPoi {
+        /// Identifier of the missing POI.
+        id: u64,
+    },
+    /// The stored tag payload was not valid JSON.
+    #[error("failed to parse tags for POI {id}: {source}")]
+    InvalidTags {
+        /// Identifier of the POI whose tags failed to parse.
+        id: u64,
+        /// JSON decoding failure.
+        #[source]
+        source: serde_json::Error,
+    },
+    /// The database predates the `poi_names` full-text table, so names
+    /// cannot be searched.
+    #[error("POI database has no name index; re-run ingest to build one")]
+    MissingNameIndex,
+    /// Generic SQLite error when reading POI rows.
+    #[error(transparent)]
+    Database(#[from] rusqlite::Error),
+}
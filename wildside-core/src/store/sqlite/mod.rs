@@ -0,0 +1,195 @@
+//! SQLite-backed store implementation for persisted POIs.
+
+use std::{collections::HashMap, fmt, path::Path, sync::Arc};
+
+use geo::{Coord, Rect};
+use rstar::{RTree, primitives::GeomWithData};
+use rusqlite::{Connection, OpenFlags};
+
+use crate::formats;
+use crate::{PointOfInterest, Tags};
+
+use super::PoiStoreError;
+use super::pool::ReadPool;
+use super::spatial_index::{IndexContents, load_index_entries};
+use super::tag_loader::TagLoader;
+
+mod error;
+mod options;
+mod queries;
+mod rows;
+mod validation;
+
+pub use error::SqlitePoiStoreError;
+pub use options::{BboxOrder, SqlitePoiStoreOptions, TagLoading, Validation};
+pub(super) use rows::{load_pois_chunk, max_variable_limit, read_poi_row};
+use validation::ensure_index_pois_exist;
+
+/// SQLite limits bound parameters per statement to 999 by default. The store
+/// chunks `IN` queries to remain below that ceiling.
+const SQLITE_MAX_VARIABLE_NUMBER: usize = 999;
+
+/// Number of indexed POIs [`Validation::Sampled`] looks up in the database.
+pub const VALIDATION_SAMPLE_SIZE: usize = 1_024;
+/// R\*-tree entry pointing at a POI by its position in `SqlitePoiStore::pois`.
+type IndexedPoint = GeomWithData<[f64; 2], usize>;
+
+/// Read-only POI store backed by SQLite metadata and a persisted R\*-tree.
+///
+/// POIs are held in identifier order. The R\*-tree and the tag-key postings
+/// both refer to positions in that order, so either can drive a query and
+/// yield results already sorted by identifier. With [`TagLoading::Lazy`] the
+/// held POIs carry no tags; `tag_loader` fills them in for query results.
+/// `connections` serves the tag loader and name searches.
+pub struct SqlitePoiStore {
+    pois: Vec<PointOfInterest>,
+    index: RTree<IndexedPoint>,
+    tag_postings: HashMap<String, Vec<usize>>,
+    tag_loader: Option<TagLoader>,
+    connections: Option<Arc<ReadPool<Connection, rusqlite::Error>>>,
+}
+
+impl fmt::Debug for SqlitePoiStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqlitePoiStore")
+            .field("entries", &self.index.size())
+            .field("tag_keys", &self.tag_postings.len())
+            .field("lazy_tags", &self.tag_loader.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl SqlitePoiStore {
+    /// Open a store backed by the provided SQLite database and R\*-tree artefact.
+    ///
+    /// The index is checked against the database by sampling; use
+    /// [`Self::open_with_options`] with [`Validation::Full`] to check every
+    /// POI.
+    pub fn open<P, Q>(database_path: P, index_path: Q) -> Result<Self, SqlitePoiStoreError>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        Self::open_with_options(database_path, index_path, SqlitePoiStoreOptions::default())
+    }
+
+    /// Open a store as [`Self::open`] does, configured by `options`.
+    ///
+    /// The database connection stays open in a pool of read-only connections
+    /// that serve later queries, so concurrent queries do not wait on one
+    /// another. With [`TagLoading::Lazy`] the tags read while validating the
+    /// index only seed the tag-key postings and are then dropped.
+    pub fn open_with_options<P, Q>(
+        database_path: P,
+        index_path: Q,
+        options: SqlitePoiStoreOptions,
+    ) -> Result<Self, SqlitePoiStoreError>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        let database_path = database_path.as_ref();
+        let index_path = index_path.as_ref();
+
+        let connection =
+            Connection::open_with_flags(database_path, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(
+                |source| SqlitePoiStoreError::OpenDatabase {
+                    path: database_path.to_path_buf(),
+                    source,
+                },
+            )?;
+        let schema_version: i64 =
+            connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        formats::POIS_SCHEMA.negotiate(schema_version)?;
+
+        let IndexContents {
+            pois: mut entries,
+            has_tags,
+            ..
+        } = load_index_entries(index_path)?;
+        entries.sort_by_key(|poi| poi.id);
+        // Filling in tags reads every row, which validates them all anyway.
+        let validation = if has_tags {
+            options.validation
+        } else {
+            Validation::Full
+        };
+        ensure_index_pois_exist(&connection, &mut entries, validation, !has_tags)?;
+
+        let mut store = Self::from_sorted(entries);
+        let path = database_path.to_path_buf();
+        let connections = Arc::new(
+            ReadPool::new(options.max_idle_connections, move || {
+                Connection::open_with_flags(&path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            })
+            .with_idle(connection),
+        );
+        if let TagLoading::Lazy { cache_capacity } = options.tag_loading {
+            for poi in &mut store.pois {
+                poi.tags = Tags::new();
+            }
+            store.tag_loader = Some(TagLoader::new(Arc::clone(&connections), cache_capacity));
+        }
+        store.connections = Some(connections);
+        Ok(store)
+    }
+
+    fn from_sorted(pois: Vec<PointOfInterest>) -> Self {
+        let points = pois
+            .iter()
+            .enumerate()
+            .map(|(position, poi)| IndexedPoint::new([poi.location.x, poi.location.y], position))
+            .collect();
+        let mut tag_postings: HashMap<String, Vec<usize>> = HashMap::new();
+        for (position, poi) in pois.iter().enumerate() {
+            for key in poi.tags.keys() {
+                tag_postings.entry(key.clone()).or_default().push(position);
+            }
+        }
+        Self {
+            pois,
+            index: RTree::bulk_load(points),
+            tag_postings,
+            tag_loader: None,
+            connections: None,
+        }
+    }
+
+    /// Copy the POIs at `positions`, with their tags.
+    fn materialise(
+        &self,
+        positions: impl IntoIterator<Item = usize>,
+    ) -> Result<Vec<PointOfInterest>, PoiStoreError> {
+        let mut pois: Vec<_> = positions
+            .into_iter()
+            .filter_map(|position| self.pois.get(position).cloned())
+            .collect();
+        if let Some(loader) = &self.tag_loader {
+            loader.hydrate(&mut pois).map_err(PoiStoreError::backend)?;
+        }
+        Ok(pois)
+    }
+
+    /// Position of the POI with identifier `id`.
+    fn position_of(&self, id: u64) -> Option<usize> {
+        self.pois.binary_search_by_key(&id, |poi| poi.id).ok()
+    }
+
+    /// Return the bounding rectangle of every indexed POI, or `None` when the
+    /// store is empty.
+    pub fn bounds(&self) -> Option<Rect<f64>> {
+        if self.index.size() == 0 {
+            return None;
+        }
+        let envelope = self.index.root().envelope();
+        let [min_x, min_y] = envelope.lower();
+        let [max_x, max_y] = envelope.upper();
+        Some(Rect::new(
+            Coord { x: min_x, y: min_y },
+            Coord { x: max_x, y: max_y },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests;
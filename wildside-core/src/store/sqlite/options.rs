@@ -0,0 +1,115 @@
+//! Options controlling how the SQLite POI store opens.
+
+#[cfg(doc)]
+use super::{SqlitePoiStore, VALIDATION_SAMPLE_SIZE};
+#[cfg(doc)]
+use crate::store::PoiStore;
+use crate::store::pool::DEFAULT_MAX_IDLE;
+
+/// Order in which [`SqlitePoiStore::stream_pois_in_bbox`] yields POIs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BboxOrder {
+    /// R\*-tree traversal order. POIs are yielded as the tree is walked, so
+    /// nothing is buffered however many match.
+    Unordered,
+    /// Ascending identifier, as returned by
+    /// [`PoiStore::get_pois_in_bbox`].
+    #[default]
+    ById,
+}
+
+/// How [`SqlitePoiStore`] keeps POI tags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TagLoading {
+    /// Hold every tag map in memory, as read when the store opens.
+    #[default]
+    Eager,
+    /// Hold only identifiers and locations, reading tags from SQLite when a
+    /// [`PoiStore`] query returns a POI.
+    ///
+    /// Up to `cache_capacity` recently read tag maps are kept so repeated
+    /// queries over one area stay in memory; zero disables the cache.
+    Lazy {
+        /// Maximum number of cached tag maps.
+        cache_capacity: usize,
+    },
+}
+
+/// How thoroughly [`SqlitePoiStore`] checks the index against the database
+/// when it opens.
+///
+/// An index written without tags is always checked in full, because every
+/// row has to be read to fill in the tags anyway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Validation {
+    /// Look up every indexed POI. Opening costs one database read per POI,
+    /// so reserve this for offline checks.
+    Full,
+    /// Look up up to [`VALIDATION_SAMPLE_SIZE`] POIs spread evenly across
+    /// the identifier range, which catches an index paired with the wrong
+    /// database without reading all of it.
+    #[default]
+    Sampled,
+    /// Trust the index. A POI missing from the database surfaces only when
+    /// a lazily loaded store reads its tags.
+    Off,
+}
+
+/// Options accepted by [`SqlitePoiStore::open_with_options`].
+///
+/// # Examples
+/// ```rust
+/// use wildside_core::store::{SqlitePoiStoreOptions, TagLoading, Validation};
+///
+/// let options = SqlitePoiStoreOptions::default()
+///     .with_tag_loading(TagLoading::Lazy { cache_capacity: 10_000 })
+///     .with_validation(Validation::Full);
+/// assert_ne!(options.tag_loading, TagLoading::Eager);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqlitePoiStoreOptions {
+    /// Whether tags are held in memory or read on demand.
+    pub tag_loading: TagLoading,
+    /// How many indexed POIs are looked up in the database on open.
+    pub validation: Validation,
+    /// Read-only connections kept open between lazy tag loads and name
+    /// searches.
+    ///
+    /// Concurrent queries each borrow a connection, opening another when all
+    /// are busy, so this bounds idle connections rather than concurrency.
+    pub max_idle_connections: usize,
+}
+
+impl Default for SqlitePoiStoreOptions {
+    fn default() -> Self {
+        Self {
+            tag_loading: TagLoading::default(),
+            validation: Validation::default(),
+            max_idle_connections: DEFAULT_MAX_IDLE,
+        }
+    }
+}
+
+impl SqlitePoiStoreOptions {
+    /// Choose how tags are kept while consuming `self`.
+    #[must_use]
+    pub const fn with_tag_loading(mut self, tag_loading: TagLoading) -> Self {
+        self.tag_loading = tag_loading;
+        self
+    }
+
+    /// Choose how the index is validated while consuming `self`.
+    #[must_use]
+    pub const fn with_validation(mut self, validation: Validation) -> Self {
+        self.validation = validation;
+        self
+    }
+
+    /// Bound the idle connections kept for lazy tag loads and name searches
+    /// while consuming `self`.
+    #[must_use]
+    pub const fn with_max_idle_connections(mut self, max_idle_connections: usize) -> Self {
+        self.max_idle_connections = max_idle_connections;
+        self
+    }
+}
@@ -0,0 +1,273 @@
+//! Spatial, tag and name queries over an open SQLite POI store.
+
+use geo::{Coord, Intersects, Rect};
+use rstar::{AABB, Envelope};
+
+use crate::PointOfInterest;
+
+#[cfg(doc)]
+use super::TagLoading;
+use super::{BboxOrder, SQLITE_MAX_VARIABLE_NUMBER, SqlitePoiStore, SqlitePoiStoreError};
+use crate::store::name_search::{has_poi_names, match_expression, search_names};
+use crate::store::ranking::{PopularityLookup, top_by_popularity};
+use crate::store::{PoiIter, PoiQuery, PoiStore, PoiStoreError, sort_by_distance};
+
+impl SqlitePoiStore {
+    /// Stream the POIs inside `bbox` by reference in the requested `order`.
+    ///
+    /// [`BboxOrder::Unordered`] walks the R\*-tree lazily and allocates
+    /// nothing per match, which suits dense queries that aggregate or filter
+    /// further. [`BboxOrder::ById`] reads the identifier-ordered POI list
+    /// directly when `bbox` covers the whole store; otherwise it buffers one
+    /// position per match, not a copy of each POI, and sorts those.
+    ///
+    /// Streaming never touches SQLite, so a store opened with
+    /// [`TagLoading::Lazy`] yields POIs without tags.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use geo::{Coord, Rect};
+    /// use wildside_core::{SqlitePoiStore, store::BboxOrder};
+    ///
+    /// let store = SqlitePoiStore::open("pois.db", "pois.rstar")?;
+    /// let bbox = Rect::new(Coord { x: -3.3, y: 55.9 }, Coord { x: -3.1, y: 56.0 });
+    /// let named = store
+    ///     .stream_pois_in_bbox(&bbox, BboxOrder::Unordered)
+    ///     .filter(|poi| poi.tags.contains_key("name"))
+    ///     .count();
+    /// # let _ = named;
+    /// # Ok::<(), wildside_core::SqlitePoiStoreError>(())
+    /// ```
+    pub fn stream_pois_in_bbox(
+        &self,
+        bbox: &Rect<f64>,
+        order: BboxOrder,
+    ) -> Box<dyn Iterator<Item = &PointOfInterest> + Send + '_> {
+        let envelope = envelope_of(bbox);
+        match order {
+            BboxOrder::Unordered => Box::new(
+                self.index
+                    .locate_in_envelope_intersecting(&envelope)
+                    .filter_map(|point| self.pois.get(point.data)),
+            ),
+            BboxOrder::ById if envelope.contains_envelope(&self.index.root().envelope()) => {
+                Box::new(self.pois.iter())
+            }
+            BboxOrder::ById => Box::new(
+                self.positions_in_bbox(bbox)
+                    .into_iter()
+                    .filter_map(|position| self.pois.get(position)),
+            ),
+        }
+    }
+
+    /// Positions of POIs inside `bbox`, ascending.
+    fn positions_in_bbox(&self, bbox: &Rect<f64>) -> Vec<usize> {
+        let envelope = envelope_of(bbox);
+        let mut positions: Vec<_> = self
+            .index
+            .locate_in_envelope_intersecting(&envelope)
+            .map(|point| point.data)
+            .collect();
+        positions.sort_unstable();
+        positions
+    }
+
+    /// Smallest candidate set for `query`'s tag constraints, ascending.
+    ///
+    /// Required tags give a single posting list each, so the shortest wins.
+    /// Themes contribute the union of their selectors' postings. `None` means
+    /// the query has no tag constraints to exploit.
+    fn tag_candidates(&self, query: &PoiQuery) -> Option<Vec<usize>> {
+        let postings = |key: &str| self.tag_postings.get(key).map_or(&[][..], Vec::as_slice);
+        let required = query
+            .required_tags
+            .iter()
+            .map(|filter| postings(filter.tag_key()))
+            .min_by_key(|positions| positions.len())
+            .map(<[usize]>::to_vec);
+        let themed = (!query.themes.is_empty()).then(|| {
+            let mut positions: Vec<usize> = query
+                .themes
+                .iter()
+                .flat_map(|theme| theme.osm_tags())
+                .flat_map(|&(key, _)| postings(key).iter().copied())
+                .collect();
+            positions.sort_unstable();
+            positions.dedup();
+            positions
+        });
+        match (required, themed) {
+            (Some(required), Some(themed)) if themed.len() < required.len() => Some(themed),
+            (Some(required), _) => Some(required),
+            (None, themed) => themed,
+        }
+    }
+
+    /// Answer `query` over `positions` when tags load lazily.
+    ///
+    /// Tags are needed to filter, so hydrate one chunk at a time and stop
+    /// reading once `limit` matches are found.
+    fn query_hydrated(
+        &self,
+        query: &PoiQuery,
+        positions: Vec<usize>,
+        limit: usize,
+    ) -> Result<Vec<PointOfInterest>, PoiStoreError> {
+        let in_bbox: Vec<usize> = positions
+            .into_iter()
+            .filter(|&position| {
+                self.pois
+                    .get(position)
+                    .is_some_and(|poi| query.bbox.intersects(&poi.location))
+            })
+            .collect();
+        let mut found = Vec::new();
+        for chunk in in_bbox.chunks(SQLITE_MAX_VARIABLE_NUMBER) {
+            if found.len() >= limit {
+                break;
+            }
+            found.extend(
+                self.materialise(chunk.iter().copied())?
+                    .into_iter()
+                    .filter(|poi| query.matches_tags(poi)),
+            );
+        }
+        found.truncate(limit);
+        Ok(found)
+    }
+
+    /// Find POIs inside `bbox` whose `name` tag matches `query`, best match
+    /// first.
+    ///
+    /// Every word of `query` must appear in the name, in any order, and the
+    /// last word also matches as a prefix, so "pergamon mus" finds the
+    /// Pergamon Museum while it is still being typed. Matching ignores case
+    /// and diacritics. Results are read from SQLite, so they carry their tags
+    /// however the store was opened. A query with no words matches nothing.
+    ///
+    /// # Errors
+    /// Returns [`SqlitePoiStoreError::MissingNameIndex`] when the database
+    /// was written before names were indexed, or the SQLite error when the
+    /// search fails.
+    ///
+    /// # Examples
+    /// ```rust,no_run
+    /// use geo::{Coord, Rect};
+    /// use wildside_core::SqlitePoiStore;
+    ///
+    /// let store = SqlitePoiStore::open("pois.db", "pois.rstar")?;
+    /// let nearby = Rect::new(Coord { x: 13.38, y: 52.51 }, Coord { x: 13.41, y: 52.53 });
+    /// let found = store.search_pois("pergamon museum", &nearby)?;
+    /// # let _ = found;
+    /// # Ok::<(), wildside_core::SqlitePoiStoreError>(())
+    /// ```
+    pub fn search_pois(
+        &self,
+        query: &str,
+        bbox: &Rect<f64>,
+    ) -> Result<Vec<PointOfInterest>, SqlitePoiStoreError> {
+        let (Some(connections), Some(expression)) = (&self.connections, match_expression(query))
+        else {
+            return Ok(Vec::new());
+        };
+        connections.with(|connection| {
+            if !has_poi_names(connection)? {
+                return Err(SqlitePoiStoreError::MissingNameIndex);
+            }
+            search_names(connection, &expression, bbox)
+        })?
+    }
+}
+
+impl PoiStore for SqlitePoiStore {
+    fn get_pois_in_bbox(&self, bbox: &Rect<f64>) -> Result<PoiIter<'_>, PoiStoreError> {
+        if self.tag_loader.is_some() {
+            let pois = self.materialise(self.positions_in_bbox(bbox))?;
+            return Ok(Box::new(pois.into_iter()));
+        }
+        Ok(Box::new(
+            self.stream_pois_in_bbox(bbox, BboxOrder::ById).cloned(),
+        ))
+    }
+
+    fn get_poi(&self, id: u64) -> Result<Option<PointOfInterest>, PoiStoreError> {
+        Ok(self.materialise(self.position_of(id))?.pop())
+    }
+
+    fn get_pois_by_ids(&self, ids: &[u64]) -> Result<PoiIter<'_>, PoiStoreError> {
+        let pois = self.materialise(ids.iter().filter_map(|&id| self.position_of(id)))?;
+        Ok(Box::new(pois.into_iter()))
+    }
+
+    fn query_pois(&self, query: &PoiQuery) -> Result<PoiIter<'_>, PoiStoreError> {
+        // Walk whichever of the tag postings and the R*-tree hits is smaller;
+        // both are in identifier order, so the limit can stop the walk early.
+        let bbox_positions = self.positions_in_bbox(&query.bbox);
+        let positions = match self.tag_candidates(query) {
+            Some(tagged) if tagged.len() < bbox_positions.len() => tagged,
+            _ => bbox_positions,
+        };
+        let limit = query.limit.unwrap_or(usize::MAX);
+        if self.tag_loader.is_some() {
+            let found = self.query_hydrated(query, positions, limit)?;
+            return Ok(Box::new(found.into_iter()));
+        }
+        let query = query.clone();
+        Ok(Box::new(
+            positions
+                .into_iter()
+                .filter_map(move |position| self.pois.get(position))
+                .filter(move |poi| query.matches(poi))
+                .take(limit)
+                .cloned(),
+        ))
+    }
+
+    fn get_top_pois_in_bbox(
+        &self,
+        bbox: &Rect<f64>,
+        limit: usize,
+        scores: &dyn PopularityLookup,
+    ) -> Result<PoiIter<'_>, PoiStoreError> {
+        // Rank positions by identifier so only the winners are copied, and
+        // with lazy tags, only their tags are read.
+        let positions = self
+            .index
+            .locate_in_envelope_intersecting(&envelope_of(bbox))
+            .filter_map(|point| self.pois.get(point.data).map(|poi| (poi.id, point.data)));
+        let pois = self.materialise(top_by_popularity(positions, limit, scores))?;
+        Ok(Box::new(pois.into_iter()))
+    }
+
+    fn get_nearest_pois(&self, center: Coord<f64>, k: usize) -> Result<PoiIter<'_>, PoiStoreError> {
+        if k == 0 {
+            return Ok(Box::new(std::iter::empty()));
+        }
+
+        // Keep walking past the k-th neighbour while distances tie so the
+        // identifier tie-break does not depend on R*-tree traversal order.
+        let mut nearest: Vec<(usize, f64)> = Vec::with_capacity(k);
+        for (point, distance_2) in self
+            .index
+            .nearest_neighbor_iter_with_distance_2(&[center.x, center.y])
+        {
+            if let Some(&(_, furthest)) = nearest.last()
+                && nearest.len() >= k
+                && distance_2 > furthest
+            {
+                break;
+            }
+            nearest.push((point.data, distance_2));
+        }
+
+        let mut pois = self.materialise(nearest.into_iter().map(|(position, _)| position))?;
+        sort_by_distance(&mut pois, center);
+        pois.truncate(k);
+        Ok(Box::new(pois.into_iter()))
+    }
+}
+
+pub(super) fn envelope_of(bbox: &Rect<f64>) -> AABB<[f64; 2]> {
+    AABB::from_corners([bbox.min().x, bbox.min().y], [bbox.max().x, bbox.max().y])
+}
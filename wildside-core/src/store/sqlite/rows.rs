@@ -0,0 +1,49 @@
+//! Reading POI rows from the `pois` table.
+
+use std::collections::HashMap;
+
+use geo::Coord;
+use rusqlite::{Connection, Row, params_from_iter};
+
+use crate::PointOfInterest;
+
+use super::{SQLITE_MAX_VARIABLE_NUMBER, SqlitePoiStoreError};
+
+pub(crate) fn max_variable_limit(connection: &Connection) -> usize {
+    let _ = connection; // connection kept for symmetry with future tunables.
+    SQLITE_MAX_VARIABLE_NUMBER
+}
+
+pub(crate) fn load_pois_chunk(
+    connection: &Connection,
+    ids: &[u64],
+) -> Result<Vec<PointOfInterest>, SqlitePoiStoreError> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let placeholders = vec!["?"; ids.len()].join(", ");
+    let query = format!("SELECT id, lon, lat, tags FROM pois WHERE id IN ({placeholders})");
+    let mut statement = connection.prepare(&query)?;
+    let mut rows = statement.query(params_from_iter(ids.iter()))?;
+    let mut pois = Vec::new();
+
+    while let Some(row) = rows.next()? {
+        pois.push(read_poi_row(row)?);
+    }
+
+    pois.sort_unstable_by_key(|poi| poi.id);
+
+    Ok(pois)
+}
+
+/// Decode a `SELECT id, lon, lat, tags` row into a POI.
+pub(crate) fn read_poi_row(row: &Row<'_>) -> Result<PointOfInterest, SqlitePoiStoreError> {
+    let id: u64 = row.get(0)?;
+    let lon: f64 = row.get(1)?;
+    let lat: f64 = row.get(2)?;
+    let tags_json: String = row.get(3)?;
+    let tags: HashMap<String, String> = serde_json::from_str(&tags_json)
+        .map_err(|source| SqlitePoiStoreError::InvalidTags { id, source })?;
+    Ok(PointOfInterest::new(id, Coord { x: lon, y: lat }, tags))
+}
@@ -0,0 +1,51 @@
+//! Tests for SQLite-backed point-of-interest store loading.
+
+use super::*;
+use crate::Theme;
+use crate::formats::FormatVersionError;
+use crate::store::spatial_index::{SPATIAL_INDEX_MAGIC, SPATIAL_INDEX_VERSION, SpatialIndexError};
+use crate::store::{
+    PoiQuery, PoiStore, SpatialIndexOptions, TagFilter, write_spatial_index_with_options,
+};
+use crate::test_support::{write_sqlite_database, write_sqlite_spatial_index};
+use bincode::serialize_into;
+use geo::Coord;
+use rstest::{fixture, rstest};
+use std::{fs::File, io::Write, path::PathBuf};
+use tempfile::TempDir;
+
+mod queries;
+mod search;
+mod tag_loading;
+mod validation;
+
+fn poi(id: u64, x: f64, y: f64, name: &str) -> PointOfInterest {
+    PointOfInterest::new(
+        id,
+        Coord { x, y },
+        Tags::from([(String::from("name"), String::from(name))]),
+    )
+}
+
+#[fixture]
+fn temp_artefacts() -> (TempDir, PathBuf, PathBuf) {
+    let dir = TempDir::new().expect("create temp dir");
+    let db_path = dir.path().join("pois.db");
+    let index_path = dir.path().join("pois.rstar");
+    (dir, db_path, index_path)
+}
+
+#[fixture]
+fn sample_pois() -> Vec<PointOfInterest> {
+    vec![poi(1, 0.0, 0.0, "centre"), poi(2, 2.0, 2.0, "museum")]
+}
+
+#[fixture]
+fn sqlite_store_fixture(
+    #[from(temp_artefacts)] (dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+    sample_pois: Vec<PointOfInterest>,
+) -> (TempDir, PathBuf, PathBuf, Vec<PointOfInterest>) {
+    write_sqlite_database(&db_path, &sample_pois).expect("persist database");
+    write_sqlite_spatial_index(&index_path, &sample_pois).expect("persist index");
+    (dir, db_path, index_path, sample_pois)
+}
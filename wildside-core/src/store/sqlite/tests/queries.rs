@@ -0,0 +1,257 @@
+//! Tests for spatial, tag and identifier queries.
+
+use super::*;
+
+#[rstest]
+fn sqlite_store_returns_pois_in_bbox(
+    sqlite_store_fixture: (TempDir, PathBuf, PathBuf, Vec<PointOfInterest>),
+) {
+    let (_dir, db_path, index_path, pois) = sqlite_store_fixture;
+    let store = SqlitePoiStore::open(&db_path, &index_path).expect("open store");
+    let bbox = Rect::new(Coord { x: -0.5, y: -0.5 }, Coord { x: 0.5, y: 0.5 });
+    let found: Vec<_> = store
+        .get_pois_in_bbox(&bbox)
+        .expect("query store")
+        .collect();
+    assert_eq!(found, vec![pois[0].clone()]);
+}
+
+#[rstest]
+fn sqlite_store_returns_sorted_results(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+) {
+    let pois = vec![
+        poi(3, 3.0, 3.0, "gallery"),
+        poi(1, 0.0, 0.0, "centre"),
+        poi(2, 1.0, 1.0, "library"),
+    ];
+    write_sqlite_database(&db_path, &pois).expect("persist database");
+    write_sqlite_spatial_index(&index_path, &pois).expect("persist index");
+
+    let store = SqlitePoiStore::open(&db_path, &index_path).expect("open store");
+    let bbox = Rect::new(Coord { x: -10.0, y: -10.0 }, Coord { x: 10.0, y: 10.0 });
+    let found: Vec<_> = store
+        .get_pois_in_bbox(&bbox)
+        .expect("query store")
+        .collect();
+
+    let mut expected = pois;
+    expected.sort_unstable_by_key(|poi| poi.id);
+    assert_eq!(found, expected);
+}
+
+#[rstest]
+fn sqlite_store_returns_nearest_pois(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+) {
+    let pois = vec![
+        poi(1, 1.0, 0.0, "east"),
+        poi(2, -1.0, 0.0, "west"),
+        poi(3, 0.0, 3.0, "north"),
+        poi(4, 0.5, 0.5, "close"),
+    ];
+    write_sqlite_database(&db_path, &pois).expect("persist database");
+    write_sqlite_spatial_index(&index_path, &pois).expect("persist index");
+
+    let store = SqlitePoiStore::open(&db_path, &index_path).expect("open store");
+    let ids: Vec<_> = store
+        .get_nearest_pois(Coord { x: 0.0, y: 0.0 }, 2)
+        .expect("query store")
+        .map(|poi| poi.id)
+        .collect();
+    assert_eq!(ids, vec![4, 1]);
+    assert_eq!(
+        store
+            .get_nearest_pois(Coord { x: 0.0, y: 0.0 }, 10)
+            .expect("query store")
+            .count(),
+        4
+    );
+}
+
+#[rstest]
+fn sqlite_store_returns_pois_within_radius(
+    sqlite_store_fixture: (TempDir, PathBuf, PathBuf, Vec<PointOfInterest>),
+) {
+    let (_dir, db_path, index_path, pois) = sqlite_store_fixture;
+    let store = SqlitePoiStore::open(&db_path, &index_path).expect("open store");
+    // The museum at (2, 2) lies about 314 km from the origin.
+    let found: Vec<_> = store
+        .get_pois_within_radius(Coord { x: 0.0, y: 0.0 }, 300_000.0)
+        .expect("query store")
+        .collect();
+    assert_eq!(found, vec![pois[0].clone()]);
+    assert_eq!(
+        store
+            .get_pois_within_radius(Coord { x: 0.0, y: 0.0 }, 320_000.0)
+            .expect("query store")
+            .count(),
+        2
+    );
+}
+
+#[rstest]
+fn sqlite_store_answers_tag_and_theme_queries(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+) {
+    let tagged = |id, x, key: &str, value: &str| {
+        PointOfInterest::new(
+            id,
+            Coord { x, y: 0.0 },
+            Tags::from([(key.to_owned(), value.to_owned())]),
+        )
+    };
+    let pois = vec![
+        tagged(1, 0.0, "tourism", "museum"),
+        tagged(2, 0.5, "historic", "memorial"),
+        tagged(3, 5.0, "historic", "castle"),
+        tagged(4, 0.2, "tourism", "gallery"),
+        tagged(5, 0.3, "historic", "ruins"),
+    ];
+    write_sqlite_database(&db_path, &pois).expect("persist database");
+    write_sqlite_spatial_index(&index_path, &pois).expect("persist index");
+    let store = SqlitePoiStore::open(&db_path, &index_path).expect("open store");
+    let bbox = Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 1.0, y: 1.0 });
+    let ids = |query: PoiQuery| -> Vec<u64> {
+        store
+            .query_pois(&query)
+            .expect("query store")
+            .map(|poi| poi.id)
+            .collect()
+    };
+
+    assert_eq!(
+        ids(PoiQuery::new(bbox).with_theme(Theme::History)),
+        vec![2, 5]
+    );
+    assert_eq!(
+        ids(PoiQuery::new(bbox).with_required_tag(TagFilter::key_value("tourism", "gallery"))),
+        vec![4]
+    );
+    assert_eq!(
+        ids(PoiQuery::new(bbox)
+            .with_required_tag(TagFilter::key("historic"))
+            .with_limit(1)),
+        vec![2]
+    );
+    assert_eq!(ids(PoiQuery::new(bbox).with_limit(2)), vec![1, 2]);
+    assert!(ids(PoiQuery::new(bbox).with_required_tag(TagFilter::key("shop"))).is_empty());
+}
+
+#[rstest]
+fn sqlite_store_looks_up_pois_by_id(
+    sqlite_store_fixture: (TempDir, PathBuf, PathBuf, Vec<PointOfInterest>),
+) {
+    let (_dir, db_path, index_path, pois) = sqlite_store_fixture;
+    let store = SqlitePoiStore::open(&db_path, &index_path).expect("open store");
+
+    assert_eq!(
+        store.get_poi(2).expect("query store"),
+        Some(pois[1].clone())
+    );
+    assert_eq!(store.get_poi(42).expect("query store"), None);
+    let found: Vec<_> = store
+        .get_pois_by_ids(&[2, 42, 1])
+        .expect("query store")
+        .collect();
+    assert_eq!(found, vec![pois[1].clone(), pois[0].clone()]);
+}
+
+#[rstest]
+#[case::partial(Rect::new(Coord { x: 0.5, y: 0.5 }, Coord { x: 10.0, y: 10.0 }))]
+#[case::covering(Rect::new(Coord { x: -10.0, y: -10.0 }, Coord { x: 10.0, y: 10.0 }))]
+fn sqlite_store_streams_bbox_in_both_orders(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+    #[case] bbox: Rect<f64>,
+) {
+    let pois = vec![
+        poi(5, 4.0, 4.0, "gallery"),
+        poi(2, 0.0, 0.0, "centre"),
+        poi(9, 1.0, 1.0, "library"),
+        poi(1, 3.0, 1.0, "market"),
+    ];
+    write_sqlite_database(&db_path, &pois).expect("persist database");
+    write_sqlite_spatial_index(&index_path, &pois).expect("persist index");
+    let store = SqlitePoiStore::open(&db_path, &index_path).expect("open store");
+    let expected: Vec<u64> = {
+        let mut ids: Vec<_> = pois
+            .iter()
+            .filter(|poi| geo::Intersects::intersects(&bbox, &poi.location))
+            .map(|poi| poi.id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    };
+
+    let ordered: Vec<_> = store
+        .stream_pois_in_bbox(&bbox, BboxOrder::ById)
+        .map(|poi| poi.id)
+        .collect();
+    assert_eq!(ordered, expected);
+
+    let mut unordered: Vec<_> = store
+        .stream_pois_in_bbox(&bbox, BboxOrder::Unordered)
+        .map(|poi| poi.id)
+        .collect();
+    unordered.sort_unstable();
+    assert_eq!(unordered, expected);
+}
+
+#[rstest]
+fn sqlite_store_reports_bounds(
+    sqlite_store_fixture: (TempDir, PathBuf, PathBuf, Vec<PointOfInterest>),
+) {
+    let (_dir, db_path, index_path, _pois) = sqlite_store_fixture;
+    let store = SqlitePoiStore::open(&db_path, &index_path).expect("open store");
+    assert_eq!(
+        store.bounds(),
+        Some(Rect::new(
+            Coord { x: 0.0, y: 0.0 },
+            Coord { x: 2.0, y: 2.0 }
+        ))
+    );
+}
+
+#[rstest]
+#[case::eager(TagLoading::Eager)]
+#[case::lazy(TagLoading::Lazy { cache_capacity: 0 })]
+fn sqlite_store_returns_top_scored_pois(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+    #[case] tag_loading: TagLoading,
+) {
+    let pois = vec![
+        poi(1, 0.0, 0.0, "centre"),
+        poi(2, 1.0, 1.0, "museum"),
+        poi(3, 2.0, 2.0, "gallery"),
+        poi(4, 8.0, 8.0, "distant"),
+    ];
+    write_sqlite_database(&db_path, &pois).expect("persist database");
+    write_sqlite_spatial_index(&index_path, &pois).expect("persist index");
+    let options = SqlitePoiStoreOptions::default().with_tag_loading(tag_loading);
+    let store =
+        SqlitePoiStore::open_with_options(&db_path, &index_path, options).expect("open store");
+    let scores: HashMap<u64, f32> = HashMap::from([(1, 0.1), (3, 0.7), (4, 1.0)]);
+
+    let bbox = Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 3.0, y: 3.0 });
+    let top: Vec<_> = store
+        .get_top_pois_in_bbox(&bbox, 2, &scores)
+        .expect("query store")
+        .collect();
+    assert_eq!(top, vec![pois[2].clone(), pois[0].clone()]);
+}
+
+#[rstest]
+fn sqlite_store_returns_empty_outside_bbox(
+    sqlite_store_fixture: (TempDir, PathBuf, PathBuf, Vec<PointOfInterest>),
+) {
+    let (_dir, db_path, index_path, _pois) = sqlite_store_fixture;
+    let store = SqlitePoiStore::open(&db_path, &index_path).expect("open store");
+    let bbox = Rect::new(Coord { x: 5.0, y: 5.0 }, Coord { x: 6.0, y: 6.0 });
+    assert!(
+        store
+            .get_pois_in_bbox(&bbox)
+            .expect("query store")
+            .next()
+            .is_none()
+    );
+}
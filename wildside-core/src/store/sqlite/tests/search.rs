@@ -0,0 +1,73 @@
+//! Tests for searching POIs by name.
+
+use super::*;
+
+#[rstest]
+#[case::whole_word("museum", vec![3])]
+#[case::prefix("muse", vec![2, 3])]
+#[case::all_words("MUSEUM pergamon", vec![3])]
+#[case::folds_diacritics("musee", vec![2])]
+#[case::accented_query("Musée", vec![2])]
+#[case::no_words("  ", vec![])]
+#[case::literal_operators("museum NOT", vec![])]
+fn sqlite_store_searches_names_within_bbox(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+    #[case] query: &str,
+    #[case] expected: Vec<u64>,
+) {
+    let pois = vec![
+        poi(1, 0.0, 0.0, "centre"),
+        poi(2, 1.0, 1.0, "Musée d'Orsay"),
+        poi(3, 2.0, 2.0, "Pergamon Museum"),
+        poi(4, 8.0, 8.0, "Distant Museum"),
+    ];
+    write_sqlite_database(&db_path, &pois).expect("persist database");
+    write_sqlite_spatial_index(&index_path, &pois).expect("persist index");
+    let store = SqlitePoiStore::open(&db_path, &index_path).expect("open store");
+
+    let bbox = Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 3.0, y: 3.0 });
+    let mut found: Vec<_> = store
+        .search_pois(query, &bbox)
+        .expect("search names")
+        .into_iter()
+        .map(|poi| poi.id)
+        .collect();
+    found.sort_unstable();
+    assert_eq!(found, expected);
+}
+
+#[rstest]
+fn lazy_sqlite_store_search_returns_tags(
+    sqlite_store_fixture: (TempDir, PathBuf, PathBuf, Vec<PointOfInterest>),
+) {
+    let (_dir, db_path, index_path, pois) = sqlite_store_fixture;
+    let options =
+        SqlitePoiStoreOptions::default().with_tag_loading(TagLoading::Lazy { cache_capacity: 0 });
+    let store =
+        SqlitePoiStore::open_with_options(&db_path, &index_path, options).expect("open lazy store");
+    let bbox = Rect::new(Coord { x: -5.0, y: -5.0 }, Coord { x: 5.0, y: 5.0 });
+
+    let found = store.search_pois("museum", &bbox).expect("search names");
+    assert_eq!(found, vec![pois[1].clone()]);
+}
+
+#[rstest]
+fn sqlite_store_search_requires_name_index(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+    sample_pois: Vec<PointOfInterest>,
+) {
+    write_sqlite_database(&db_path, &sample_pois).expect("persist database");
+    write_sqlite_spatial_index(&index_path, &sample_pois).expect("persist index");
+    let connection = Connection::open(&db_path).expect("open database");
+    connection
+        .execute_batch("DROP TABLE poi_names; PRAGMA user_version = 1;")
+        .expect("downgrade database");
+    drop(connection);
+
+    let store = SqlitePoiStore::open(&db_path, &index_path).expect("open legacy store");
+    let bbox = Rect::new(Coord { x: -5.0, y: -5.0 }, Coord { x: 5.0, y: 5.0 });
+    let error = store
+        .search_pois("museum", &bbox)
+        .expect_err("search without a name index should fail");
+    assert!(matches!(error, SqlitePoiStoreError::MissingNameIndex));
+}
@@ -0,0 +1,86 @@
+//! Tests for filling in and lazily loading POI tags.
+
+use super::*;
+
+#[rstest]
+fn sqlite_store_reads_tags_from_database_when_index_omits_them(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+    sample_pois: Vec<PointOfInterest>,
+) {
+    write_sqlite_database(&db_path, &sample_pois).expect("persist database");
+    let options = SpatialIndexOptions {
+        embed_tags: false,
+        ..SpatialIndexOptions::default()
+    };
+    write_spatial_index_with_options(&index_path, &sample_pois, options).expect("persist index");
+
+    let store = SqlitePoiStore::open(&db_path, &index_path).expect("open store");
+    let found: Vec<_> = store
+        .get_pois_in_bbox(&Rect::new(
+            Coord { x: -1.0, y: -1.0 },
+            Coord { x: 3.0, y: 3.0 },
+        ))
+        .expect("query store")
+        .collect();
+    assert_eq!(found, sample_pois);
+}
+
+#[rstest]
+fn lazy_sqlite_store_hydrates_tags_on_query(
+    sqlite_store_fixture: (TempDir, PathBuf, PathBuf, Vec<PointOfInterest>),
+    #[values(0, 1, 16)] cache_capacity: usize,
+) {
+    let (_dir, db_path, index_path, pois) = sqlite_store_fixture;
+    let options =
+        SqlitePoiStoreOptions::default().with_tag_loading(TagLoading::Lazy { cache_capacity });
+    let store =
+        SqlitePoiStore::open_with_options(&db_path, &index_path, options).expect("open lazy store");
+    let everywhere = Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 3.0, y: 3.0 });
+
+    assert!(
+        store
+            .stream_pois_in_bbox(&everywhere, BboxOrder::ById)
+            .all(|poi| poi.tags.is_empty())
+    );
+    for _ in 0..2 {
+        let found: Vec<_> = store
+            .get_pois_in_bbox(&everywhere)
+            .expect("query store")
+            .collect();
+        assert_eq!(found, pois);
+    }
+    assert_eq!(
+        store.get_poi(2).expect("look up POI"),
+        Some(pois[1].clone())
+    );
+    let museums: Vec<_> = store
+        .query_pois(
+            &PoiQuery::new(everywhere).with_required_tag(TagFilter::key_value("name", "museum")),
+        )
+        .expect("query store")
+        .collect();
+    assert_eq!(museums, vec![pois[1].clone()]);
+}
+
+#[rstest]
+fn lazy_sqlite_store_serves_concurrent_queries(
+    sqlite_store_fixture: (TempDir, PathBuf, PathBuf, Vec<PointOfInterest>),
+) {
+    let (_dir, db_path, index_path, pois) = sqlite_store_fixture;
+    let options = SqlitePoiStoreOptions::default()
+        .with_tag_loading(TagLoading::Lazy { cache_capacity: 0 })
+        .with_max_idle_connections(2);
+    let store =
+        SqlitePoiStore::open_with_options(&db_path, &index_path, options).expect("open lazy store");
+
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..4)
+            .map(|_| scope.spawn(|| store.get_pois_by_ids(&[1, 2]).map(Iterator::collect)))
+            .collect();
+        for worker in workers {
+            let found: Vec<PointOfInterest> =
+                worker.join().expect("join worker").expect("query store");
+            assert_eq!(found, pois);
+        }
+    });
+}
@@ -0,0 +1,140 @@
+//! Tests for checking the index against the database on open.
+
+use super::*;
+use crate::store::sqlite::validation::sample_ids;
+
+#[rstest]
+fn sqlite_store_errors_when_index_has_unknown_poi(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+    sample_pois: Vec<PointOfInterest>,
+) {
+    write_sqlite_database(&db_path, &sample_pois).expect("persist database");
+    let mut pois = sample_pois;
+    pois.push(poi(99, 9.0, 9.0, "ghost"));
+    write_sqlite_spatial_index(&index_path, &pois).expect("persist index");
+
+    let error = SqlitePoiStore::open(&db_path, &index_path).expect_err("missing POI should fail");
+    assert!(matches!(error, SqlitePoiStoreError::MissingPoi { id: 99 }));
+}
+
+#[rstest]
+fn sqlite_store_skips_unknown_poi_without_validation(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+    sample_pois: Vec<PointOfInterest>,
+) {
+    write_sqlite_database(&db_path, &sample_pois).expect("persist database");
+    let mut pois = sample_pois;
+    pois.push(poi(99, 9.0, 9.0, "ghost"));
+    write_sqlite_spatial_index(&index_path, &pois).expect("persist index");
+
+    let options = SqlitePoiStoreOptions::default().with_validation(Validation::Off);
+    let store = SqlitePoiStore::open_with_options(&db_path, &index_path, options)
+        .expect("open unvalidated store");
+    assert!(store.get_poi(99).expect("look up POI").is_some());
+}
+
+#[rstest]
+fn sample_ids_spread_across_entries() {
+    let entries: Vec<_> = (0..5_000_u64)
+        .map(|id| PointOfInterest::with_empty_tags(id, Coord { x: 0.0, y: 0.0 }))
+        .collect();
+    let ids = sample_ids(&entries);
+    assert!(ids.len() <= VALIDATION_SAMPLE_SIZE + 1);
+    assert_eq!(ids.first(), Some(&0));
+    assert_eq!(ids.last(), Some(&4_999));
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[rstest]
+fn sqlite_store_errors_on_corrupted_magic(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+    sample_pois: Vec<PointOfInterest>,
+) {
+    write_sqlite_database(&db_path, &sample_pois).expect("persist database");
+    std::fs::write(&index_path, b"BAD!").expect("write corrupt file");
+
+    let error = SqlitePoiStore::open(&db_path, &index_path).expect_err("invalid magic should fail");
+    assert!(matches!(
+        error,
+        SqlitePoiStoreError::SpatialIndex(SpatialIndexError::InvalidMagic { .. })
+    ));
+}
+
+#[rstest]
+fn sqlite_store_errors_on_unsupported_version(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+    sample_pois: Vec<PointOfInterest>,
+) {
+    write_sqlite_database(&db_path, &sample_pois).expect("persist database");
+    {
+        let mut file = File::create(&index_path).expect("create index file");
+        file.write_all(&SPATIAL_INDEX_MAGIC)
+            .expect("write magic header");
+        file.write_all(&(SPATIAL_INDEX_VERSION + 1).to_le_bytes())
+            .expect("write version");
+        serialize_into(&mut file, &Vec::<PointOfInterest>::new())
+            .expect("write unsupported payload");
+    }
+
+    let error =
+        SqlitePoiStore::open(&db_path, &index_path).expect_err("unsupported version should fail");
+    assert!(matches!(
+        error,
+        SqlitePoiStoreError::SpatialIndex(SpatialIndexError::UnsupportedVersion(
+            FormatVersionError { found, current, .. }
+        )) if found == i64::from(SPATIAL_INDEX_VERSION + 1) && current == SPATIAL_INDEX_VERSION
+    ));
+}
+
+#[rstest]
+fn sqlite_store_errors_on_newer_pois_schema(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+    sample_pois: Vec<PointOfInterest>,
+) {
+    write_sqlite_database(&db_path, &sample_pois).expect("persist database");
+    write_sqlite_spatial_index(&index_path, &sample_pois).expect("persist index");
+    let newer = formats::POIS_SCHEMA.current + 1;
+    Connection::open(&db_path)
+        .expect("reopen SQLite database")
+        .pragma_update(None, "user_version", newer)
+        .expect("bump schema version");
+
+    let error = SqlitePoiStore::open(&db_path, &index_path).expect_err("newer schema should fail");
+    assert!(matches!(
+        error,
+        SqlitePoiStoreError::PoisSchema(FormatVersionError { found, .. })
+            if found == i64::from(newer)
+    ));
+}
+
+#[rstest]
+fn sqlite_store_errors_on_invalid_tags(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+    sample_pois: Vec<PointOfInterest>,
+) {
+    write_sqlite_spatial_index(&index_path, &sample_pois).expect("persist index");
+    let connection = Connection::open(&db_path).expect("create SQLite database");
+    connection
+        .execute(
+            "CREATE TABLE pois (
+                id INTEGER PRIMARY KEY,
+                lon REAL NOT NULL,
+                lat REAL NOT NULL,
+                tags TEXT NOT NULL
+            )",
+            [],
+        )
+        .expect("create table");
+    connection
+        .execute(
+            "INSERT INTO pois (id, lon, lat, tags) VALUES (1, 0.0, 0.0, 'not-json')",
+            [],
+        )
+        .expect("insert row");
+
+    let error = SqlitePoiStore::open(&db_path, &index_path).expect_err("invalid tags should fail");
+    assert!(matches!(
+        error,
+        SqlitePoiStoreError::InvalidTags { id: 1, .. }
+    ));
+}
@@ -0,0 +1,89 @@
+//! Checking a spatial index against the database it was built from.
+
+use rusqlite::Connection;
+
+use crate::PointOfInterest;
+
+use super::rows::{load_pois_chunk, max_variable_limit};
+use super::{SqlitePoiStoreError, VALIDATION_SAMPLE_SIZE, Validation};
+
+fn find_missing_poi_in_chunk(chunk: &[u64], pois: &[PointOfInterest]) -> Option<u64> {
+    if pois.len() == chunk.len() {
+        return None;
+    }
+
+    for id in chunk {
+        if pois.binary_search_by_key(id, |poi| poi.id).is_err() {
+            return Some(*id);
+        }
+    }
+
+    unreachable!("chunk length mismatch should reveal missing id");
+}
+
+/// Check that the indexed POIs chosen by `validation` have database rows.
+///
+/// `entries` must be sorted by identifier. When `hydrate_tags` is set, each
+/// entry takes its tags from that row, which is how indices written without a
+/// tag block are completed; `validation` must then be [`Validation::Full`].
+pub(super) fn ensure_index_pois_exist(
+    connection: &Connection,
+    entries: &mut [PointOfInterest],
+    validation: Validation,
+    hydrate_tags: bool,
+) -> Result<(), SqlitePoiStoreError> {
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let mut ids: Vec<u64> = match validation {
+        Validation::Full => entries.iter().map(|entry| entry.id).collect(),
+        Validation::Sampled => sample_ids(entries),
+        Validation::Off => return Ok(()),
+    };
+    ids.dedup();
+
+    let max_parameters = max_variable_limit(connection);
+    for chunk in ids.chunks(max_parameters) {
+        let pois = load_pois_chunk(connection, chunk)?;
+        if let Some(missing_id) = find_missing_poi_in_chunk(chunk, &pois) {
+            return Err(SqlitePoiStoreError::MissingPoi { id: missing_id });
+        }
+        if hydrate_tags {
+            copy_tags(entries, &pois);
+        }
+    }
+
+    Ok(())
+}
+
+/// Identifiers of up to [`VALIDATION_SAMPLE_SIZE`] entries spread evenly
+/// across the sorted `entries`, always including the last.
+pub(super) fn sample_ids(entries: &[PointOfInterest]) -> Vec<u64> {
+    let stride = entries.len().div_ceil(VALIDATION_SAMPLE_SIZE).max(1);
+    let mut ids: Vec<u64> = entries
+        .iter()
+        .step_by(stride)
+        .map(|entry| entry.id)
+        .collect();
+    if let Some(last) = entries.last()
+        && ids.last() != Some(&last.id)
+    {
+        ids.push(last.id);
+    }
+    ids
+}
+
+/// Give each entry the tags of the row sharing its identifier.
+fn copy_tags(entries: &mut [PointOfInterest], rows: &[PointOfInterest]) {
+    for row in rows {
+        let start = entries.partition_point(|entry| entry.id < row.id);
+        for entry in entries
+            .iter_mut()
+            .skip(start)
+            .take_while(|entry| entry.id == row.id)
+        {
+            entry.tags.clone_from(&row.tags);
+        }
+    }
+}
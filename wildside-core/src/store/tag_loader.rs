@@ -0,0 +1,177 @@
+//! On-demand tag hydration for lazily loaded SQLite stores.
+//!
+//! A [`TagLoader`] borrows read-only connections from a [`ReadPool`] and fills
+//! in the tag maps of POIs that were indexed by identifier and location alone. Recently
+//! loaded maps can be kept in a bounded least-recently-used [`TagCache`], so
+//! repeated queries over the same area do not return to SQLite.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use rusqlite::Connection;
+
+use crate::{PointOfInterest, Tags};
+
+use super::pool::ReadPool;
+use super::sqlite::{SqlitePoiStoreError, load_pois_chunk, max_variable_limit};
+
+/// Reads POI tags from SQLite when a query needs them.
+pub(crate) struct TagLoader {
+    connections: Arc<ReadPool<Connection, rusqlite::Error>>,
+    cache: Option<Mutex<TagCache>>,
+}
+
+impl TagLoader {
+    /// Load tags through `connections`, caching up to `cache_capacity` maps.
+    ///
+    /// A capacity of zero disables the cache.
+    pub(crate) fn new(
+        connections: Arc<ReadPool<Connection, rusqlite::Error>>,
+        cache_capacity: usize,
+    ) -> Self {
+        Self {
+            connections,
+            cache: (cache_capacity > 0).then(|| Mutex::new(TagCache::new(cache_capacity))),
+        }
+    }
+
+    /// Replace the tags of every POI in `pois` with those stored in SQLite.
+    pub(crate) fn hydrate(&self, pois: &mut [PointOfInterest]) -> Result<(), SqlitePoiStoreError> {
+        let mut missing: Vec<u64> = Vec::new();
+        if let Some(cache) = &self.cache {
+            let mut cache = cache.lock().unwrap_or_else(PoisonError::into_inner);
+            for poi in pois.iter_mut() {
+                match cache.get(poi.id) {
+                    Some(tags) => poi.tags = tags,
+                    None => missing.push(poi.id),
+                }
+            }
+        } else {
+            missing.extend(pois.iter().map(|poi| poi.id));
+        }
+        if missing.is_empty() {
+            return Ok(());
+        }
+        missing.sort_unstable();
+        missing.dedup();
+
+        let loaded = self.connections.with(|connection| {
+            let mut loaded: HashMap<u64, Tags> = HashMap::with_capacity(missing.len());
+            for chunk in missing.chunks(max_variable_limit(connection)) {
+                loaded.extend(
+                    load_pois_chunk(connection, chunk)?
+                        .into_iter()
+                        .map(|row| (row.id, row.tags)),
+                );
+            }
+            Ok::<_, SqlitePoiStoreError>(loaded)
+        })??;
+
+        for poi in pois
+            .iter_mut()
+            .filter(|poi| missing.binary_search(&poi.id).is_ok())
+        {
+            let tags = loaded
+                .get(&poi.id)
+                .ok_or(SqlitePoiStoreError::MissingPoi { id: poi.id })?;
+            poi.tags.clone_from(tags);
+        }
+        if let Some(cache) = &self.cache {
+            let mut cache = cache.lock().unwrap_or_else(PoisonError::into_inner);
+            for (id, tags) in loaded {
+                cache.insert(id, tags);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Least-recently-used cache of tag maps keyed by POI identifier.
+pub(crate) struct TagCache {
+    capacity: usize,
+    clock: u64,
+    entries: HashMap<u64, (u64, Tags)>,
+    recency: BTreeMap<u64, u64>,
+}
+
+impl TagCache {
+    /// Create a cache holding at most `capacity` tag maps.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            clock: 0,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+        }
+    }
+
+    /// Return a copy of the tags cached for `id`, marking them recently used.
+    pub(crate) fn get(&mut self, id: u64) -> Option<Tags> {
+        let stamp = self.tick();
+        let (used, tags) = self.entries.get_mut(&id)?;
+        self.recency.remove(&*used);
+        self.recency.insert(stamp, id);
+        *used = stamp;
+        Some(tags.clone())
+    }
+
+    /// Cache `tags` for `id`, evicting the least recently used entry when
+    /// the cache is full.
+    pub(crate) fn insert(&mut self, id: u64, tags: Tags) {
+        let stamp = self.tick();
+        if let Some((used, _)) = self.entries.insert(id, (stamp, tags)) {
+            self.recency.remove(&used);
+        }
+        self.recency.insert(stamp, id);
+        while self.entries.len() > self.capacity {
+            let Some((_, evicted)) = self.recency.pop_first() else {
+                break;
+            };
+            self.entries.remove(&evicted);
+        }
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for the tag cache's eviction order.
+
+    use super::*;
+    use rstest::rstest;
+
+    fn tags(name: &str) -> Tags {
+        Tags::from([(String::from("name"), String::from(name))])
+    }
+
+    #[rstest]
+    fn cache_evicts_least_recently_used_entry() {
+        let mut cache = TagCache::new(2);
+        cache.insert(1, tags("one"));
+        cache.insert(2, tags("two"));
+        assert_eq!(cache.get(1), Some(tags("one")));
+
+        cache.insert(3, tags("three"));
+        assert_eq!(cache.get(2), None);
+        assert_eq!(cache.get(1), Some(tags("one")));
+        assert_eq!(cache.get(3), Some(tags("three")));
+    }
+
+    #[rstest]
+    fn reinserting_refreshes_an_entry() {
+        let mut cache = TagCache::new(2);
+        cache.insert(1, tags("one"));
+        cache.insert(2, tags("two"));
+        cache.insert(1, tags("uno"));
+
+        cache.insert(3, tags("three"));
+        assert_eq!(cache.get(1), Some(tags("uno")));
+        assert_eq!(cache.get(2), None);
+    }
+}
@@ -0,0 +1,266 @@
+//! Tests for in-memory point-of-interest store queries.
+
+use super::{PoiQuery, PoiStore, PoiWriter, TagFilter};
+use crate::{PointOfInterest, Tags, Theme, test_support::MemoryStore};
+use geo::{Coord, LineString, Polygon, Rect, polygon};
+use rstest::rstest;
+use std::collections::BTreeMap;
+
+#[rstest]
+fn returns_pois_inside_bbox() {
+    let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+    let store = MemoryStore::with_poi(poi.clone());
+    let bbox = Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 1.0, y: 1.0 });
+    let found: Vec<_> = store
+        .get_pois_in_bbox(&bbox)
+        .expect("query store")
+        .collect();
+    assert_eq!(found, vec![poi]);
+}
+
+#[rstest]
+fn returns_top_scored_pois_inside_bbox() {
+    let store = MemoryStore::with_pois([
+        PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 }),
+        PointOfInterest::with_empty_tags(2, Coord { x: 0.5, y: 0.5 }),
+        PointOfInterest::with_empty_tags(3, Coord { x: -0.5, y: 0.5 }),
+        PointOfInterest::with_empty_tags(4, Coord { x: 5.0, y: 5.0 }),
+    ]);
+    let scores: BTreeMap<u64, f32> = BTreeMap::from([(1, 0.2), (2, 0.9), (4, 1.0)]);
+    let bbox = Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 1.0, y: 1.0 });
+    let top: Vec<_> = store
+        .get_top_pois_in_bbox(&bbox, 2, &scores)
+        .expect("query store")
+        .map(|poi| poi.id)
+        .collect();
+    assert_eq!(top, vec![2, 1]);
+}
+
+#[rstest]
+fn returns_empty_when_no_pois() {
+    let store = MemoryStore::default();
+    let bbox = Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 1.0, y: 1.0 });
+    assert_eq!(
+        store.get_pois_in_bbox(&bbox).expect("query store").count(),
+        0
+    );
+}
+
+#[rstest]
+#[case(Coord { x: -1.0, y: 0.0 })] // left edge
+#[case(Coord { x: 1.0, y: 0.0 })] // right edge
+#[case(Coord { x: 0.0, y: -1.0 })] // bottom edge
+#[case(Coord { x: 0.0, y: 1.0 })] // top edge
+#[case(Coord { x: -1.0, y: -1.0 })] // bottom-left corner
+#[case(Coord { x: 1.0, y: 1.0 })] // top-right corner
+fn includes_poi_on_bbox_boundary(#[case] location: Coord<f64>) {
+    let poi = PointOfInterest::with_empty_tags(42, location);
+    let store = MemoryStore::with_poi(poi.clone());
+    let bbox = Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 1.0, y: 1.0 });
+    let found: Vec<_> = store
+        .get_pois_in_bbox(&bbox)
+        .expect("query store")
+        .collect();
+    assert_eq!(found, vec![poi]);
+}
+
+#[rstest]
+#[case(Coord { x: -1.0000001, y: 0.0 })]
+#[case(Coord { x: 1.0000001, y: 0.0 })]
+#[case(Coord { x: 0.0, y: -1.0000001 })]
+#[case(Coord { x: 0.0, y: 1.0000001 })]
+fn excludes_poi_just_outside_bbox(#[case] location: Coord<f64>) {
+    let poi = PointOfInterest::with_empty_tags(7, location);
+    let store = MemoryStore::with_poi(poi);
+    let bbox = Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 1.0, y: 1.0 });
+    assert_eq!(
+        store.get_pois_in_bbox(&bbox).expect("query store").count(),
+        0
+    );
+}
+
+#[rstest]
+fn memory_store_applies_upserts_and_deletes() {
+    let original = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+    let moved = PointOfInterest::with_empty_tags(1, Coord { x: 0.5, y: 0.5 });
+    let other = PointOfInterest::with_empty_tags(2, Coord { x: 0.2, y: 0.2 });
+    let mut store = MemoryStore::with_poi(original);
+    let bbox = Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 1.0, y: 1.0 });
+
+    store
+        .upsert_pois(&[moved.clone(), other])
+        .expect("upsert POIs");
+    store.delete_pois(&[2, 99]).expect("delete POIs");
+    store.flush().expect("flush store");
+
+    let found: Vec<_> = store
+        .get_pois_in_bbox(&bbox)
+        .expect("query store")
+        .collect();
+    assert_eq!(found, vec![moved]);
+}
+
+#[rstest]
+fn nearest_pois_are_ordered_by_distance_then_id() {
+    let store = MemoryStore::with_pois([
+        PointOfInterest::with_empty_tags(3, Coord { x: 3.0, y: 0.0 }),
+        PointOfInterest::with_empty_tags(2, Coord { x: 0.0, y: -1.0 }),
+        PointOfInterest::with_empty_tags(1, Coord { x: 1.0, y: 0.0 }),
+        PointOfInterest::with_empty_tags(4, Coord { x: 0.0, y: 2.0 }),
+    ]);
+
+    let ids: Vec<_> = store
+        .get_nearest_pois(Coord { x: 0.0, y: 0.0 }, 3)
+        .expect("query store")
+        .map(|poi| poi.id)
+        .collect();
+    assert_eq!(ids, vec![1, 2, 4]);
+}
+
+#[rstest]
+#[case(0, 0)]
+#[case(5, 2)]
+fn nearest_pois_respect_k(#[case] k: usize, #[case] expected: usize) {
+    let store = MemoryStore::with_pois([
+        PointOfInterest::with_empty_tags(1, Coord { x: 1.0, y: 0.0 }),
+        PointOfInterest::with_empty_tags(2, Coord { x: 2.0, y: 0.0 }),
+    ]);
+    assert_eq!(
+        store
+            .get_nearest_pois(Coord { x: 0.0, y: 0.0 }, k)
+            .expect("query store")
+            .count(),
+        expected
+    );
+}
+
+#[rstest]
+fn radius_query_excludes_bbox_corners() {
+    // About 1.1 km north and 1.1 km east of the centre: inside the disc's
+    // bounding square, but roughly 1.57 km away diagonally.
+    let centre = Coord { x: 0.0, y: 0.0 };
+    let north = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0099 });
+    let corner = PointOfInterest::with_empty_tags(
+        2,
+        Coord {
+            x: 0.0099,
+            y: 0.0099,
+        },
+    );
+    let store = MemoryStore::with_pois([north.clone(), corner]);
+
+    let found: Vec<_> = store
+        .get_pois_within_radius(centre, 1_200.0)
+        .expect("query store")
+        .collect();
+    assert_eq!(found, vec![north]);
+}
+
+#[rstest]
+fn radius_query_with_negative_radius_keeps_only_the_centre() {
+    let centre = Coord { x: 1.0, y: 1.0 };
+    let at_centre = PointOfInterest::with_empty_tags(1, centre);
+    let nearby = PointOfInterest::with_empty_tags(2, Coord { x: 1.0001, y: 1.0 });
+    let store = MemoryStore::with_pois([at_centre.clone(), nearby]);
+
+    let found: Vec<_> = store
+        .get_pois_within_radius(centre, -5.0)
+        .expect("query store")
+        .collect();
+    assert_eq!(found, vec![at_centre]);
+}
+
+#[rstest]
+fn query_filters_by_tags_and_themes_in_id_order() {
+    let tagged = |id, key: &str, value: &str| {
+        PointOfInterest::new(
+            id,
+            Coord { x: 0.0, y: 0.0 },
+            Tags::from([(key.to_owned(), value.to_owned())]),
+        )
+    };
+    let store = MemoryStore::with_pois([
+        tagged(4, "historic", "memorial"),
+        tagged(2, "shop", "bakery"),
+        tagged(3, "tourism", "museum"),
+        tagged(1, "historic", "castle"),
+    ]);
+    let bbox = Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 1.0, y: 1.0 });
+
+    let history: Vec<_> = store
+        .query_pois(&PoiQuery::new(bbox).with_theme(Theme::History))
+        .expect("query store")
+        .map(|poi| poi.id)
+        .collect();
+    assert_eq!(history, vec![1, 4]);
+
+    let limited: Vec<_> = store
+        .query_pois(
+            &PoiQuery::new(bbox)
+                .with_required_tag(TagFilter::key("historic"))
+                .with_limit(1),
+        )
+        .expect("query store")
+        .map(|poi| poi.id)
+        .collect();
+    assert_eq!(limited, vec![1]);
+}
+
+#[rstest]
+fn polygon_query_refines_the_envelope() {
+    // A right triangle: (0.8, 0.2) lies inside, (0.2, 0.8) only inside
+    // its bounding square, and (0.5, 0.5) on the hypotenuse.
+    let triangle = polygon![(x: 0.0, y: 0.0), (x: 1.0, y: 0.0), (x: 1.0, y: 1.0)];
+    let inside = PointOfInterest::with_empty_tags(1, Coord { x: 0.8, y: 0.2 });
+    let corner = PointOfInterest::with_empty_tags(2, Coord { x: 0.2, y: 0.8 });
+    let edge = PointOfInterest::with_empty_tags(3, Coord { x: 0.5, y: 0.5 });
+    let store = MemoryStore::with_pois([inside.clone(), corner, edge.clone()]);
+
+    let found: Vec<_> = store
+        .get_pois_in_polygon(&triangle)
+        .expect("query store")
+        .collect();
+    assert_eq!(found, vec![inside, edge]);
+}
+
+#[rstest]
+fn polygon_query_excludes_holes_and_empty_polygons() {
+    let exterior = LineString::from(vec![(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+    let hole = LineString::from(vec![(1.0, 1.0), (3.0, 1.0), (3.0, 3.0), (1.0, 3.0)]);
+    let with_hole = Polygon::new(exterior, vec![hole]);
+    let store = MemoryStore::with_pois([
+        PointOfInterest::with_empty_tags(1, Coord { x: 2.0, y: 2.0 }),
+        PointOfInterest::with_empty_tags(2, Coord { x: 0.5, y: 0.5 }),
+    ]);
+
+    let ids: Vec<_> = store
+        .get_pois_in_polygon(&with_hole)
+        .expect("query store")
+        .map(|poi| poi.id)
+        .collect();
+    assert_eq!(ids, vec![2]);
+
+    let empty = Polygon::new(LineString::new(Vec::new()), Vec::new());
+    assert_eq!(
+        store
+            .get_pois_in_polygon(&empty)
+            .expect("query store")
+            .count(),
+        0
+    );
+}
+
+#[rstest]
+fn looks_up_pois_by_id_in_request_order() {
+    let first = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+    let second = PointOfInterest::with_empty_tags(2, Coord { x: 10.0, y: 10.0 });
+    let store = MemoryStore::with_pois([first.clone(), second.clone()]);
+
+    assert_eq!(store.get_poi(2).expect("query store"), Some(second.clone()));
+    assert_eq!(store.get_poi(3).expect("query store"), None);
+    let found: Vec<_> = store
+        .get_pois_by_ids(&[2, 3, 1, 2])
+        .expect("query store")
+        .collect();
+    assert_eq!(found, vec![second.clone(), first, second]);
+}
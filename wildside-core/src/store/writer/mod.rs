@@ -0,0 +1,257 @@
+//! SQLite-backed [`PoiWriter`] implementation.
+//!
+//! The writer maintains the same artefact pair that [`SqlitePoiStore`] reads:
+//! the `pois` table inside the SQLite database and the persisted R\*-tree
+//! beside it. Changes are buffered in memory and applied in one transaction on
+//! [`PoiWriter::flush`], after which the spatial index is rebuilt from the
+//! committed rows so both artefacts describe the same POIs.
+//!
+//! [`SqlitePoiStore`]: super::SqlitePoiStore
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use geo::Coord;
+use rusqlite::{Connection, Transaction};
+use thiserror::Error;
+
+use crate::PointOfInterest;
+use crate::formats::{FormatVersionError, POIS_SCHEMA};
+
+use super::PoiWriter;
+use super::name_search::install_poi_name_search;
+use super::spatial_index::{SpatialIndexWriteError, write_index};
+
+/// Error raised when writing POIs through [`SqlitePoiWriter`].
+#[derive(Debug, Error)]
+pub enum SqlitePoiWriterError {
+    /// Opening the SQLite database failed.
+    #[error("failed to open SQLite database at {path}: {source}")]
+    OpenDatabase {
+        /// Location of the SQLite database on disk.
+        path: PathBuf,
+        /// Source error returned by `rusqlite`.
+        #[source]
+        source: rusqlite::Error,
+    },
+    /// The existing `pois` table uses a schema this build cannot write.
+    #[error(transparent)]
+    PoisSchema(#[from] FormatVersionError),
+    /// A POI identifier could not be represented as an SQLite integer.
+    #[error("POI id {id} exceeds SQLite i64 range")]
+    PoiIdOutOfRange {
+        /// Identifier that failed the conversion.
+        id: u64,
+    },
+    /// Serializing POI tags to JSON failed.
+    #[error("failed to serialize tags for POI {id}: {source}")]
+    SerializeTags {
+        /// Identifier of the POI whose tags failed to serialize.
+        id: u64,
+        /// Source error produced by `serde_json`.
+        #[source]
+        source: serde_json::Error,
+    },
+    /// A stored tag payload was not valid JSON when rebuilding the index.
+    #[error("failed to parse tags for POI {id}: {source}")]
+    InvalidTags {
+        /// Identifier of the POI whose tags failed to parse.
+        id: u64,
+        /// JSON decoding failure.
+        #[source]
+        source: serde_json::Error,
+    },
+    /// Rewriting the persisted R\*-tree failed.
+    #[error(transparent)]
+    SpatialIndex(#[from] SpatialIndexWriteError),
+    /// Generic SQLite error when writing or reading POI rows.
+    #[error(transparent)]
+    Database(#[from] rusqlite::Error),
+}
+
+/// Buffered writer for the SQLite database and R\*-tree read by
+/// [`SqlitePoiStore`](super::SqlitePoiStore).
+///
+/// Upserts and deletions are recorded against the POI identifier, so the last
+/// change to an identifier before a flush wins. Nothing reaches disk until
+/// [`PoiWriter::flush`] is called; dropping the writer discards pending
+/// changes.
+///
+/// # Examples
+///
+/// ```rust
+/// use geo::{Coord, Rect};
+/// use tempfile::TempDir;
+/// use wildside_core::{PoiStore, PoiWriter, PointOfInterest, SqlitePoiStore, SqlitePoiWriter};
+///
+/// let dir = TempDir::new().expect("create temp dir");
+/// let db_path = dir.path().join("pois.db");
+/// let index_path = dir.path().join("pois.rstar");
+///
+/// let mut writer = SqlitePoiWriter::open(&db_path, &index_path).expect("open writer");
+/// let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+/// writer.upsert_pois(&[poi.clone()]).expect("stage POI");
+/// writer.flush().expect("flush POIs");
+///
+/// let store = SqlitePoiStore::open(&db_path, &index_path).expect("open store");
+/// let bbox = Rect::new(Coord { x: -1.0, y: -1.0 }, Coord { x: 1.0, y: 1.0 });
+/// let found: Vec<_> = store.get_pois_in_bbox(&bbox).expect("query store").collect();
+/// assert_eq!(found, vec![poi]);
+/// ```
+pub struct SqlitePoiWriter {
+    connection: Connection,
+    index_path: PathBuf,
+    pending: BTreeMap<u64, Option<PointOfInterest>>,
+}
+
+impl fmt::Debug for SqlitePoiWriter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SqlitePoiWriter")
+            .field("index_path", &self.index_path)
+            .field("pending", &self.pending.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl SqlitePoiWriter {
+    /// Open a writer for the provided SQLite database and R\*-tree artefact.
+    ///
+    /// The database is created when missing. An existing `pois` schema is
+    /// negotiated up front, so a database written by a newer build is
+    /// rejected before any changes are staged.
+    pub fn open<P, Q>(database_path: P, index_path: Q) -> Result<Self, SqlitePoiWriterError>
+    where
+        P: AsRef<Path>,
+        Q: AsRef<Path>,
+    {
+        let database_path = database_path.as_ref();
+        let connection = Connection::open(database_path).map_err(|source| {
+            SqlitePoiWriterError::OpenDatabase {
+                path: database_path.to_path_buf(),
+                source,
+            }
+        })?;
+        // Deleting a POI must cascade to its Wikidata links when present.
+        connection.pragma_update(None, "foreign_keys", true)?;
+        let schema_version: i64 =
+            connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        POIS_SCHEMA.negotiate(schema_version)?;
+
+        Ok(Self {
+            connection,
+            index_path: index_path.as_ref().to_path_buf(),
+            pending: BTreeMap::new(),
+        })
+    }
+
+    /// Number of POI identifiers with changes awaiting a flush.
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+impl PoiWriter for SqlitePoiWriter {
+    type Error = SqlitePoiWriterError;
+
+    fn upsert_pois(&mut self, pois: &[PointOfInterest]) -> Result<(), Self::Error> {
+        for poi in pois {
+            sqlite_id(poi.id)?;
+        }
+        for poi in pois {
+            self.pending.insert(poi.id, Some(poi.clone()));
+        }
+        Ok(())
+    }
+
+    fn delete_pois(&mut self, ids: &[u64]) -> Result<(), Self::Error> {
+        for &id in ids {
+            sqlite_id(id)?;
+        }
+        for &id in ids {
+            self.pending.insert(id, None);
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        let transaction = self.connection.transaction()?;
+        create_schema(&transaction)?;
+        apply_changes(&transaction, &self.pending)?;
+        transaction.commit()?;
+        self.pending.clear();
+
+        let entries = load_all_pois(&self.connection)?;
+        write_index(&self.index_path, &entries)?;
+        Ok(())
+    }
+}
+
+fn sqlite_id(id: u64) -> Result<i64, SqlitePoiWriterError> {
+    i64::try_from(id).map_err(|_| SqlitePoiWriterError::PoiIdOutOfRange { id })
+}
+
+fn create_schema(transaction: &Transaction<'_>) -> Result<(), SqlitePoiWriterError> {
+    let existing: i64 = transaction.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    POIS_SCHEMA.negotiate(existing)?;
+    transaction.execute(
+        "CREATE TABLE IF NOT EXISTS pois (
+            id INTEGER PRIMARY KEY,
+            lon REAL NOT NULL,
+            lat REAL NOT NULL,
+            tags TEXT NOT NULL
+        )",
+        [],
+    )?;
+    install_poi_name_search(transaction, existing)?;
+    transaction.pragma_update(None, "user_version", POIS_SCHEMA.current)?;
+    Ok(())
+}
+
+fn apply_changes(
+    transaction: &Transaction<'_>,
+    pending: &BTreeMap<u64, Option<PointOfInterest>>,
+) -> Result<(), SqlitePoiWriterError> {
+    let mut upsert = transaction
+        .prepare("INSERT OR REPLACE INTO pois (id, lon, lat, tags) VALUES (?1, ?2, ?3, ?4)")?;
+    let mut delete = transaction.prepare("DELETE FROM pois WHERE id = ?1")?;
+
+    for (&id, change) in pending {
+        let row_id = sqlite_id(id)?;
+        match change {
+            Some(poi) => {
+                let tags = serde_json::to_string(&poi.tags)
+                    .map_err(|source| SqlitePoiWriterError::SerializeTags { id, source })?;
+                upsert.execute((row_id, poi.location.x, poi.location.y, tags))?;
+            }
+            None => {
+                delete.execute([row_id])?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn load_all_pois(connection: &Connection) -> Result<Vec<PointOfInterest>, SqlitePoiWriterError> {
+    let mut statement = connection.prepare("SELECT id, lon, lat, tags FROM pois ORDER BY id")?;
+    let mut rows = statement.query([])?;
+    let mut pois = Vec::new();
+
+    while let Some(row) = rows.next()? {
+        let id: u64 = row.get(0)?;
+        let lon: f64 = row.get(1)?;
+        let lat: f64 = row.get(2)?;
+        let tags_json: String = row.get(3)?;
+        let tags: HashMap<String, String> = serde_json::from_str(&tags_json)
+            .map_err(|source| SqlitePoiWriterError::InvalidTags { id, source })?;
+        pois.push(PointOfInterest::new(id, Coord { x: lon, y: lat }, tags));
+    }
+
+    Ok(pois)
+}
+
+#[cfg(test)]
+mod tests;
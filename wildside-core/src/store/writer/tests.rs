@@ -0,0 +1,183 @@
+//! Tests for buffered SQLite point-of-interest writes.
+
+use super::*;
+use crate::Tags;
+use crate::store::{PoiStore, SqlitePoiStore};
+use crate::test_support::{write_sqlite_database, write_sqlite_spatial_index};
+use geo::Rect;
+use rstest::{fixture, rstest};
+use tempfile::TempDir;
+
+fn poi(id: u64, x: f64, y: f64, name: &str) -> PointOfInterest {
+    PointOfInterest::new(
+        id,
+        Coord { x, y },
+        Tags::from([(String::from("name"), String::from(name))]),
+    )
+}
+
+fn everything() -> Rect<f64> {
+    Rect::new(Coord { x: -10.0, y: -10.0 }, Coord { x: 10.0, y: 10.0 })
+}
+
+#[fixture]
+fn temp_artefacts() -> (TempDir, PathBuf, PathBuf) {
+    let dir = TempDir::new().expect("create temp dir");
+    let db_path = dir.path().join("pois.db");
+    let index_path = dir.path().join("pois.rstar");
+    (dir, db_path, index_path)
+}
+
+#[rstest]
+fn flushed_pois_are_readable_by_the_store(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+) {
+    let pois = vec![poi(1, 0.0, 0.0, "centre"), poi(2, 2.0, 2.0, "museum")];
+    let mut writer = SqlitePoiWriter::open(&db_path, &index_path).expect("open writer");
+    writer.upsert_pois(&pois).expect("stage POIs");
+    writer.flush().expect("flush POIs");
+
+    let store = SqlitePoiStore::open(&db_path, &index_path).expect("open store");
+    let found: Vec<_> = store
+        .get_pois_in_bbox(&everything())
+        .expect("query store")
+        .collect();
+    assert_eq!(found, pois);
+}
+
+#[rstest]
+fn upsert_replaces_and_delete_removes_existing_rows(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+) {
+    let original = vec![poi(1, 0.0, 0.0, "centre"), poi(2, 2.0, 2.0, "museum")];
+    write_sqlite_database(&db_path, &original).expect("persist database");
+    write_sqlite_spatial_index(&index_path, &original).expect("persist index");
+
+    let renamed = poi(1, 0.5, 0.5, "plaza");
+    let mut writer = SqlitePoiWriter::open(&db_path, &index_path).expect("open writer");
+    writer
+        .upsert_pois(std::slice::from_ref(&renamed))
+        .expect("stage upsert");
+    writer.delete_pois(&[2]).expect("stage delete");
+    writer.flush().expect("flush changes");
+
+    let store = SqlitePoiStore::open(&db_path, &index_path).expect("open store");
+    let found: Vec<_> = store
+        .get_pois_in_bbox(&everything())
+        .expect("query store")
+        .collect();
+    assert_eq!(found, vec![renamed]);
+}
+
+#[rstest]
+fn flush_keeps_name_search_in_step(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+) {
+    let original = vec![poi(1, 0.0, 0.0, "centre"), poi(2, 2.0, 2.0, "museum")];
+    write_sqlite_database(&db_path, &original).expect("persist database");
+    write_sqlite_spatial_index(&index_path, &original).expect("persist index");
+
+    let mut writer = SqlitePoiWriter::open(&db_path, &index_path).expect("open writer");
+    writer
+        .upsert_pois(&[poi(1, 0.0, 0.0, "old museum")])
+        .expect("stage upsert");
+    writer.delete_pois(&[2]).expect("stage delete");
+    writer.flush().expect("flush changes");
+
+    let store = SqlitePoiStore::open(&db_path, &index_path).expect("open store");
+    let found = store
+        .search_pois("museum", &everything())
+        .expect("search names");
+    assert_eq!(found, vec![poi(1, 0.0, 0.0, "old museum")]);
+    assert!(
+        store
+            .search_pois("centre", &everything())
+            .expect("search names")
+            .is_empty()
+    );
+}
+
+#[rstest]
+fn flush_indexes_names_of_legacy_databases(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+) {
+    let original = vec![poi(1, 0.0, 0.0, "centre"), poi(2, 2.0, 2.0, "museum")];
+    write_sqlite_database(&db_path, &original).expect("persist database");
+    Connection::open(&db_path)
+        .expect("open database")
+        .execute_batch(
+            "DROP TRIGGER pois_index_name;
+             DROP TRIGGER pois_reindex_name;
+             DROP TRIGGER pois_unindex_name;
+             DROP TABLE poi_names;
+             PRAGMA user_version = 1;",
+        )
+        .expect("downgrade database");
+
+    let mut writer = SqlitePoiWriter::open(&db_path, &index_path).expect("open writer");
+    writer.flush().expect("flush upgrade");
+
+    let store = SqlitePoiStore::open(&db_path, &index_path).expect("open store");
+    let found = store
+        .search_pois("museum", &everything())
+        .expect("search names");
+    assert_eq!(found, vec![original[1].clone()]);
+}
+
+#[rstest]
+fn last_change_to_an_id_wins(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+) {
+    let mut writer = SqlitePoiWriter::open(&db_path, &index_path).expect("open writer");
+    writer
+        .upsert_pois(&[poi(1, 0.0, 0.0, "centre")])
+        .expect("stage upsert");
+    writer.delete_pois(&[1]).expect("stage delete");
+    assert_eq!(writer.pending_len(), 1);
+    writer.flush().expect("flush changes");
+    assert_eq!(writer.pending_len(), 0);
+
+    let store = SqlitePoiStore::open(&db_path, &index_path).expect("open store");
+    assert_eq!(
+        store
+            .get_pois_in_bbox(&everything())
+            .expect("query store")
+            .count(),
+        0
+    );
+}
+
+#[rstest]
+fn rejects_out_of_range_ids_before_staging(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+) {
+    let mut writer = SqlitePoiWriter::open(&db_path, &index_path).expect("open writer");
+    let oversized = PointOfInterest::with_empty_tags(u64::MAX, Coord { x: 0.0, y: 0.0 });
+
+    let error = writer
+        .upsert_pois(&[poi(1, 0.0, 0.0, "centre"), oversized])
+        .expect_err("out-of-range id should fail");
+    assert!(matches!(
+        error,
+        SqlitePoiWriterError::PoiIdOutOfRange { id: u64::MAX }
+    ));
+    assert_eq!(writer.pending_len(), 0);
+}
+
+#[rstest]
+fn rejects_newer_pois_schema_on_open(
+    #[from(temp_artefacts)] (_dir, db_path, index_path): (TempDir, PathBuf, PathBuf),
+) {
+    let newer = POIS_SCHEMA.current + 1;
+    Connection::open(&db_path)
+        .expect("create database")
+        .pragma_update(None, "user_version", newer)
+        .expect("stamp newer version");
+
+    let error = SqlitePoiWriter::open(&db_path, &index_path).expect_err("newer schema should fail");
+    assert!(matches!(
+        error,
+        SqlitePoiWriterError::PoisSchema(FormatVersionError { found, .. })
+            if found == i64::from(newer)
+    ));
+}
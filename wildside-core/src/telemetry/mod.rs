@@ -0,0 +1,234 @@
+//! Usage rollups of solve telemetry, exportable under a privacy policy.
+//!
+//! [`TelemetryRollup`] folds each solve into a cell keyed by the square map
+//! tile its start falls in and the UTC hour it was made in, keeping request
+//! counts and summary statistics rather than individual requests.
+//! [`TelemetryRollup::export`] releases the cells under a [`PrivacyPolicy`],
+//! which can withhold sparsely used cells and add Laplace noise to the
+//! totals so the export says little about any one request.
+
+mod privacy;
+
+use std::collections::BTreeMap;
+use std::time::{Duration, SystemTime};
+
+use thiserror::Error;
+
+use crate::{SolveError, SolveRequest, SolveResponse};
+pub use privacy::PrivacyPolicy;
+
+/// Side of a rollup tile in degrees when none is chosen, roughly a kilometre
+/// north to south.
+pub const DEFAULT_TILE_DEGREES: f64 = 0.01;
+
+/// Most solve time one request adds to its cell, bounding how far any one
+/// request can move the total.
+pub const MAX_SOLVE_TIME_CONTRIBUTION: Duration = Duration::from_secs(30);
+
+/// Most route POIs one request adds to its cell.
+pub const MAX_ROUTE_POIS_CONTRIBUTION: u64 = 50;
+
+const SECONDS_PER_HOUR: i64 = 3600;
+
+/// Errors from configuring telemetry rollups and their privacy policy.
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum TelemetryError {
+    /// The tile size is not a positive, finite number of degrees.
+    #[error("tile size must be positive and finite, got {0}")]
+    InvalidTileSize(f64),
+    /// The privacy budget is not a positive, finite number.
+    #[error("privacy budget ε must be positive and finite, got {0}")]
+    InvalidEpsilon(f64),
+}
+
+/// A square map tile during one UTC hour.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CellKey {
+    /// Column of the tile, counting tiles east of longitude zero.
+    pub tile_x: i64,
+    /// Row of the tile, counting tiles north of the equator.
+    pub tile_y: i64,
+    /// Hours since the Unix epoch.
+    pub hour: i64,
+}
+
+/// Totals gathered for one cell.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CellStats {
+    /// Solves requested.
+    pub requests: u64,
+    /// Solves that returned a route.
+    pub solved: u64,
+    /// Solve time summed over solved requests, each counting for at most
+    /// [`MAX_SOLVE_TIME_CONTRIBUTION`].
+    pub solve_time: Duration,
+    /// Route POIs summed over solved requests, each counting for at most
+    /// [`MAX_ROUTE_POIS_CONTRIBUTION`].
+    pub route_pois: u64,
+}
+
+/// One cell as released by [`TelemetryRollup::export`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExportedCell {
+    /// Tile and hour the cell covers.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub key: CellKey,
+    /// Solves requested, noised when the policy adds noise.
+    pub requests: u64,
+    /// Solves that returned a route, noised when the policy adds noise.
+    pub solved: u64,
+    /// Mean solve time of solved requests, in milliseconds, derived from the
+    /// noised totals when the policy adds noise.
+    pub mean_solve_millis: f64,
+    /// Mean number of POIs per returned route, derived from the noised
+    /// totals when the policy adds noise.
+    pub mean_route_pois: f64,
+}
+
+/// Solve telemetry aggregated by tile and hour.
+///
+/// # Examples
+/// ```rust
+/// use geo::Coord;
+/// use wildside_core::telemetry::{PrivacyPolicy, TelemetryRollup};
+/// use std::time::{Duration, SystemTime};
+/// use wildside_core::{SolveError, SolveRequest};
+///
+/// let mut rollup = TelemetryRollup::default();
+/// let request = SolveRequest {
+///     start: Coord { x: -3.19, y: 55.95 },
+///     duration_minutes: 60,
+///     ..Default::default()
+/// };
+/// let at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+/// rollup.record(at, &request, Err(&SolveError::NotImplemented));
+///
+/// let cells = rollup.export(&PrivacyPolicy::none());
+/// assert_eq!(cells.len(), 1);
+/// assert_eq!((cells[0].requests, cells[0].solved), (1, 0));
+/// assert!(rollup.export(&PrivacyPolicy::none().with_min_requests(2)).is_empty());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetryRollup {
+    tile_degrees: f64,
+    cells: BTreeMap<CellKey, CellStats>,
+}
+
+impl Default for TelemetryRollup {
+    fn default() -> Self {
+        Self {
+            tile_degrees: DEFAULT_TILE_DEGREES,
+            cells: BTreeMap::new(),
+        }
+    }
+}
+
+impl TelemetryRollup {
+    /// Create an empty rollup whose tiles are `tile_degrees` on a side.
+    ///
+    /// # Errors
+    /// Returns [`TelemetryError::InvalidTileSize`] unless `tile_degrees` is
+    /// positive and finite.
+    pub fn new(tile_degrees: f64) -> Result<Self, TelemetryError> {
+        if !(tile_degrees.is_finite() && tile_degrees > 0.0) {
+            return Err(TelemetryError::InvalidTileSize(tile_degrees));
+        }
+        Ok(Self {
+            tile_degrees,
+            cells: BTreeMap::new(),
+        })
+    }
+
+    /// Side of each tile in degrees.
+    #[must_use]
+    pub const fn tile_degrees(&self) -> f64 {
+        self.tile_degrees
+    }
+
+    /// Fold a solve of `request` made at `at` into its cell.
+    ///
+    /// Only the request's start and the outcome's totals are kept; the
+    /// request's interests and the route's POIs are not.
+    pub fn record(
+        &mut self,
+        at: SystemTime,
+        request: &SolveRequest,
+        outcome: Result<&SolveResponse, &SolveError>,
+    ) {
+        let key = CellKey {
+            tile_x: self.tile_index(request.start.x),
+            tile_y: self.tile_index(request.start.y),
+            hour: unix_seconds(at).div_euclid(SECONDS_PER_HOUR),
+        };
+        let stats = self.cells.entry(key).or_default();
+        stats.requests += 1;
+        if let Ok(response) = outcome {
+            stats.solved += 1;
+            stats.solve_time += response
+                .diagnostics
+                .solve_time
+                .min(MAX_SOLVE_TIME_CONTRIBUTION);
+            stats.route_pois +=
+                (response.route.pois().len() as u64).min(MAX_ROUTE_POIS_CONTRIBUTION);
+        }
+    }
+
+    /// Iterate over the exact totals of every cell in key order.
+    pub fn cells(&self) -> impl Iterator<Item = (&CellKey, &CellStats)> {
+        self.cells.iter()
+    }
+
+    /// Report whether nothing has been recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Release the cells in key order under `policy`.
+    ///
+    /// When the policy adds noise, every total of each cell is perturbed
+    /// first. Cells whose released request count falls below the policy's
+    /// threshold are then left out, and the means are computed from the
+    /// released totals. Noise is keyed by each cell's state, so exporting a
+    /// seeded policy twice without recording in between gives equal results.
+    #[must_use]
+    pub fn export(&self, policy: &PrivacyPolicy) -> Vec<ExportedCell> {
+        let noise = policy.noise_source();
+        self.cells
+            .iter()
+            .map(|(key, stats)| (key, noise.release(key, stats)))
+            .filter(|(_, released)| released.requests >= policy.min_requests())
+            .map(|(key, released)| ExportedCell {
+                key: *key,
+                requests: released.requests,
+                solved: released.solved,
+                mean_solve_millis: mean(released.solve_millis, released.solved),
+                mean_route_pois: mean(released.route_pois, released.solved),
+            })
+            .collect()
+    }
+
+    fn tile_index(&self, degrees: f64) -> i64 {
+        (degrees / self.tile_degrees).floor() as i64
+    }
+}
+
+/// Whole seconds from the Unix epoch to `at`, rounded towards the past.
+fn unix_seconds(at: SystemTime) -> i64 {
+    match at.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(after) => after.as_secs() as i64,
+        Err(before) => {
+            let before = before.duration();
+            -(before.as_secs() as i64) - i64::from(before.subsec_nanos() > 0)
+        }
+    }
+}
+
+fn mean(sum: f64, count: u64) -> f64 {
+    if count == 0 { 0.0 } else { sum / count as f64 }
+}
+
+#[cfg(test)]
+mod tests;
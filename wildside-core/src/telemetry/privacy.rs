@@ -0,0 +1,193 @@
+//! Threshold and noise settings applied when exporting rollups.
+
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+
+use super::{
+    CellKey, CellStats, MAX_ROUTE_POIS_CONTRIBUTION, MAX_SOLVE_TIME_CONTRIBUTION, TelemetryError,
+};
+
+/// How much a rollup export may reveal.
+///
+/// [`PrivacyPolicy::none`] releases exact totals for every cell. Raising the
+/// threshold with [`Self::with_min_requests`] withholds cells with fewer
+/// than `k` requests. [`Self::with_laplace_noise`] splits a budget `ε`
+/// evenly across the four totals a cell releases (requests, solved requests,
+/// solve time, and route POIs) and adds Laplace noise with scale `4Δ / ε` to
+/// each, where `Δ` is the most one request can change that total: one for
+/// the counts, and [`MAX_SOLVE_TIME_CONTRIBUTION`] and
+/// [`MAX_ROUTE_POIS_CONTRIBUTION`] for the sums. A request lands in a single
+/// cell, so an export as a whole spends `ε`. The threshold is then applied
+/// to the noised request count and the means are derived from the noised
+/// totals, which reveals nothing further.
+///
+/// A cell's noise depends only on the noise seed, the cell's key, and its
+/// exact request count, so exporting an unchanged cell again repeats its
+/// noise rather than offering a fresh sample to average away. Without
+/// [`Self::with_noise_seed`] every export draws a new seed from entropy;
+/// long-running exporters should fix one for the life of the process.
+///
+/// [`MAX_SOLVE_TIME_CONTRIBUTION`]: super::MAX_SOLVE_TIME_CONTRIBUTION
+/// [`MAX_ROUTE_POIS_CONTRIBUTION`]: super::MAX_ROUTE_POIS_CONTRIBUTION
+///
+/// # Examples
+/// ```rust
+/// use wildside_core::telemetry::PrivacyPolicy;
+///
+/// let policy = PrivacyPolicy::none()
+///     .with_min_requests(10)
+///     .with_laplace_noise(1.0)
+///     .expect("ε is positive")
+///     .with_noise_seed(7);
+/// assert_eq!(policy.min_requests(), 10);
+/// assert_eq!(policy.epsilon(), Some(1.0));
+/// assert!(PrivacyPolicy::none().with_laplace_noise(0.0).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PrivacyPolicy {
+    min_requests: u64,
+    epsilon: Option<f64>,
+    seed: Option<u64>,
+}
+
+impl PrivacyPolicy {
+    /// Release exact totals for every cell.
+    #[must_use]
+    pub const fn none() -> Self {
+        Self {
+            min_requests: 0,
+            epsilon: None,
+            seed: None,
+        }
+    }
+
+    /// Withhold cells with fewer than `k` requests, after any noise is
+    /// added.
+    #[must_use]
+    pub const fn with_min_requests(mut self, k: u64) -> Self {
+        self.min_requests = k;
+        self
+    }
+
+    /// Add Laplace noise spending a budget of `epsilon` per export to the
+    /// exported totals.
+    ///
+    /// Smaller budgets add more noise and reveal less.
+    ///
+    /// # Errors
+    /// Returns [`TelemetryError::InvalidEpsilon`] unless `epsilon` is
+    /// positive and finite.
+    pub fn with_laplace_noise(mut self, epsilon: f64) -> Result<Self, TelemetryError> {
+        if !(epsilon.is_finite() && epsilon > 0.0) {
+            return Err(TelemetryError::InvalidEpsilon(epsilon));
+        }
+        self.epsilon = Some(epsilon);
+        Ok(self)
+    }
+
+    /// Draw noise from a generator seeded with `seed`, so exports of the
+    /// same rollup are reproducible.
+    #[must_use]
+    pub const fn with_noise_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Smallest request count a cell needs to be exported.
+    #[must_use]
+    pub const fn min_requests(&self) -> u64 {
+        self.min_requests
+    }
+
+    /// Privacy budget an export spends, or `None` when totals are exact.
+    #[must_use]
+    pub const fn epsilon(&self) -> Option<f64> {
+        self.epsilon
+    }
+
+    /// Seed of the noise generator, or `None` when noise uses fresh entropy.
+    #[must_use]
+    pub const fn noise_seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    pub(super) fn noise_source(&self) -> NoiseSource {
+        NoiseSource {
+            scale: self
+                .epsilon
+                .map(|epsilon| f64::from(RELEASED_TOTALS) / epsilon),
+            seed: self.seed.unwrap_or_else(rand::random),
+        }
+    }
+}
+
+/// Totals each exported cell releases, sharing the policy's budget.
+const RELEASED_TOTALS: u8 = 4;
+
+/// A cell's totals as released, noised when the policy adds noise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Released {
+    pub(super) requests: u64,
+    pub(super) solved: u64,
+    pub(super) solve_millis: f64,
+    pub(super) route_pois: f64,
+}
+
+/// Laplace noise generator for one export.
+pub(super) struct NoiseSource {
+    /// Noise scale for a total one request changes by at most one.
+    scale: Option<f64>,
+    seed: u64,
+}
+
+impl NoiseSource {
+    /// Return the totals of the cell at `key`, with noise added when the
+    /// policy adds any: counts are rounded and clamped at zero, and sums
+    /// clamped at zero.
+    ///
+    /// Every solve raises a cell's request count, so the seed, key, and
+    /// request count together identify the cell's state and select the
+    /// generator stream its noise is drawn from.
+    pub(super) fn release(&self, key: &CellKey, stats: &CellStats) -> Released {
+        let solve_millis = stats.solve_time.as_secs_f64() * 1000.0;
+        let route_pois = stats.route_pois as f64;
+        let Some(scale) = self.scale else {
+            return Released {
+                requests: stats.requests,
+                solved: stats.solved,
+                solve_millis,
+                route_pois,
+            };
+        };
+        let mut seed = [0_u8; 32];
+        let words = [self.seed as i64, key.tile_x, key.tile_y, key.hour];
+        for (chunk, word) in seed.chunks_exact_mut(8).zip(words) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        let mut rng = ChaCha20Rng::from_seed(seed);
+        rng.set_stream(stats.requests);
+        let solve_time_scale = scale * MAX_SOLVE_TIME_CONTRIBUTION.as_secs_f64() * 1000.0;
+        let route_pois_scale = scale * MAX_ROUTE_POIS_CONTRIBUTION as f64;
+        Released {
+            requests: laplace_count(&mut rng, scale, stats.requests),
+            solved: laplace_count(&mut rng, scale, stats.solved),
+            solve_millis: laplace(&mut rng, solve_time_scale, solve_millis).max(0.0),
+            route_pois: laplace(&mut rng, route_pois_scale, route_pois).max(0.0),
+        }
+    }
+}
+
+fn laplace_count(rng: &mut ChaCha20Rng, scale: f64, count: u64) -> u64 {
+    laplace(rng, scale, count as f64).round().max(0.0) as u64
+}
+
+fn laplace(rng: &mut ChaCha20Rng, scale: f64, value: f64) -> f64 {
+    // The difference of two unit exponentials is Laplace distributed.
+    value + scale * (exponential(rng) - exponential(rng))
+}
+
+fn exponential(rng: &mut ChaCha20Rng) -> f64 {
+    // `1 - u` lies in `(0, 1]`, so the logarithm stays finite.
+    let u: f64 = rng.r#gen();
+    -(1.0 - u).ln()
+}
@@ -0,0 +1,271 @@
+//! Tests for telemetry rollups and their privacy policy.
+
+use super::*;
+use crate::{Diagnostics, PointOfInterest, Route};
+use geo::Coord;
+use rstest::{fixture, rstest};
+
+fn request_at(x: f64, y: f64) -> SolveRequest {
+    SolveRequest {
+        start: Coord { x, y },
+        duration_minutes: 60,
+        ..Default::default()
+    }
+}
+
+fn response(pois: usize, solve_millis: u64) -> SolveResponse {
+    let pois = (0..pois as u64)
+        .map(|id| PointOfInterest::with_empty_tags(id, Coord { x: 0.0, y: 0.0 }))
+        .collect();
+    SolveResponse {
+        route: Route::new(pois, Duration::from_secs(600)),
+        score: 1.0,
+        diagnostics: Diagnostics {
+            solve_time: Duration::from_millis(solve_millis),
+            ..Diagnostics::default()
+        },
+        geometry: None,
+    }
+}
+
+fn hour(index: u64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(index * 3600 + 30)
+}
+
+/// Twenty requests in one cell and two in another.
+#[fixture]
+fn rollup() -> TelemetryRollup {
+    let mut rollup = TelemetryRollup::default();
+    for _ in 0..20 {
+        rollup.record(hour(0), &request_at(0.005, 0.005), Ok(&response(2, 40)));
+    }
+    rollup.record(hour(0), &request_at(0.015, 0.005), Ok(&response(4, 10)));
+    rollup.record(
+        hour(0),
+        &request_at(0.015, 0.005),
+        Err(&SolveError::NotImplemented),
+    );
+    rollup
+}
+
+#[rstest]
+fn record_groups_requests_by_tile_and_hour() {
+    let mut rollup = TelemetryRollup::new(1.0).expect("valid tile size");
+    rollup.record(
+        hour(0),
+        &request_at(0.2, 0.2),
+        Err(&SolveError::NotImplemented),
+    );
+    rollup.record(
+        hour(0),
+        &request_at(0.8, 0.9),
+        Err(&SolveError::NotImplemented),
+    );
+    rollup.record(
+        hour(1),
+        &request_at(0.8, 0.9),
+        Err(&SolveError::NotImplemented),
+    );
+    rollup.record(
+        hour(1),
+        &request_at(-0.5, 0.9),
+        Err(&SolveError::NotImplemented),
+    );
+
+    let keys: Vec<CellKey> = rollup.cells().map(|(key, _)| *key).collect();
+    assert_eq!(
+        keys,
+        vec![
+            CellKey {
+                tile_x: -1,
+                tile_y: 0,
+                hour: 1
+            },
+            CellKey {
+                tile_x: 0,
+                tile_y: 0,
+                hour: 0
+            },
+            CellKey {
+                tile_x: 0,
+                tile_y: 0,
+                hour: 1
+            },
+        ]
+    );
+}
+
+#[rstest]
+#[case::just_before(Duration::from_millis(1), -1)]
+#[case::whole_hour(Duration::from_secs(3600), -1)]
+#[case::next_hour_down(Duration::from_secs(3601), -2)]
+fn times_before_the_epoch_fall_in_negative_hours(#[case] before: Duration, #[case] hour: i64) {
+    let mut rollup = TelemetryRollup::default();
+    let at = SystemTime::UNIX_EPOCH - before;
+    rollup.record(at, &request_at(0.0, 0.0), Err(&SolveError::NotImplemented));
+
+    let keys: Vec<i64> = rollup.cells().map(|(key, _)| key.hour).collect();
+    assert_eq!(keys, vec![hour]);
+}
+
+#[rstest]
+fn exact_export_reports_counts_and_means(rollup: TelemetryRollup) {
+    let cells = rollup.export(&PrivacyPolicy::none());
+
+    assert_eq!(cells.len(), 2);
+    let busy = cells.first().expect("busy cell");
+    assert_eq!((busy.requests, busy.solved), (20, 20));
+    assert!((busy.mean_solve_millis - 40.0).abs() < 1e-9);
+    assert!((busy.mean_route_pois - 2.0).abs() < 1e-9);
+    let quiet = cells.get(1).expect("quiet cell");
+    assert_eq!((quiet.requests, quiet.solved), (2, 1));
+    assert!((quiet.mean_route_pois - 4.0).abs() < 1e-9);
+}
+
+#[rstest]
+fn threshold_withholds_sparse_cells(rollup: TelemetryRollup) {
+    let cells = rollup.export(&PrivacyPolicy::none().with_min_requests(10));
+
+    assert_eq!(cells.len(), 1);
+    assert_eq!(cells.first().map(|cell| cell.requests), Some(20));
+}
+
+#[rstest]
+fn each_request_contributes_a_bounded_amount() {
+    let mut rollup = TelemetryRollup::default();
+    rollup.record(hour(0), &request_at(0.0, 0.0), Ok(&response(80, 90_000)));
+
+    let (_, stats) = rollup.cells().next().expect("one cell");
+    assert_eq!(stats.route_pois, MAX_ROUTE_POIS_CONTRIBUTION);
+    assert_eq!(stats.solve_time, MAX_SOLVE_TIME_CONTRIBUTION);
+}
+
+#[rstest]
+fn threshold_applies_to_noised_counts() {
+    let mut rollup = TelemetryRollup::default();
+    for index in 0..100 {
+        for _ in 0..10 {
+            rollup.record(
+                hour(index),
+                &request_at(0.0, 0.0),
+                Err(&SolveError::NotImplemented),
+            );
+        }
+    }
+    let policy = PrivacyPolicy::none()
+        .with_min_requests(10)
+        .with_laplace_noise(1.0)
+        .expect("valid budget")
+        .with_noise_seed(7);
+
+    let cells = rollup.export(&policy);
+    assert!(cells.len() < 100, "every cell was released");
+    assert!(!cells.is_empty(), "every cell was withheld");
+    assert!(cells.iter().all(|cell| cell.requests >= 10));
+}
+
+#[rstest]
+fn means_come_from_noised_totals(rollup: TelemetryRollup) {
+    let policy = PrivacyPolicy::none()
+        .with_laplace_noise(1.0)
+        .expect("valid budget")
+        .with_noise_seed(11);
+
+    let cells = rollup.export(&policy);
+    let busy = cells.first().expect("busy cell");
+    assert!((busy.mean_solve_millis - 40.0).abs() > 1e-9);
+    assert!((busy.mean_route_pois - 2.0).abs() > 1e-9);
+}
+
+#[rstest]
+fn seeded_noise_is_reproducible(rollup: TelemetryRollup) {
+    let policy = PrivacyPolicy::none()
+        .with_laplace_noise(0.5)
+        .expect("valid budget")
+        .with_noise_seed(11);
+
+    assert_eq!(rollup.export(&policy), rollup.export(&policy));
+}
+
+#[rstest]
+fn noise_changes_only_for_cells_that_changed(mut rollup: TelemetryRollup) {
+    let policy = PrivacyPolicy::none()
+        .with_laplace_noise(0.5)
+        .expect("valid budget")
+        .with_noise_seed(11);
+    let before = rollup.export(&policy);
+
+    rollup.record(
+        hour(0),
+        &request_at(0.5, 0.5),
+        Err(&SolveError::NotImplemented),
+    );
+    let after = rollup.export(&policy);
+
+    assert_eq!(after.len(), before.len() + 1);
+    assert_eq!(after.first(), before.first());
+    assert_eq!(after.get(1), before.get(1));
+}
+
+#[rstest]
+fn noise_perturbs_counts_around_the_truth() {
+    let mut rollup = TelemetryRollup::new(1.0).expect("valid tile size");
+    for index in 0..200 {
+        for _ in 0..50 {
+            rollup.record(
+                hour(index),
+                &request_at(0.5, 0.5),
+                Err(&SolveError::NotImplemented),
+            );
+        }
+    }
+    let policy = PrivacyPolicy::none()
+        .with_laplace_noise(1.0)
+        .expect("valid budget")
+        .with_noise_seed(3);
+
+    let counts: Vec<u64> = rollup
+        .export(&policy)
+        .iter()
+        .map(|cell| cell.requests)
+        .collect();
+    let mean = counts.iter().sum::<u64>() as f64 / counts.len() as f64;
+    assert!(counts.iter().any(|count| *count != 50), "{counts:?}");
+    assert!((mean - 50.0).abs() < 0.5, "mean {mean}");
+}
+
+#[rstest]
+fn noise_never_produces_negative_counts() {
+    let mut rollup = TelemetryRollup::default();
+    for index in 0..100 {
+        rollup.record(
+            hour(index),
+            &request_at(0.0, 0.0),
+            Err(&SolveError::NotImplemented),
+        );
+    }
+    let policy = PrivacyPolicy::none()
+        .with_laplace_noise(0.1)
+        .expect("valid budget")
+        .with_noise_seed(5);
+
+    let cells = rollup.export(&policy);
+    assert_eq!(cells.len(), 100);
+    assert!(cells.iter().any(|cell| cell.requests == 0));
+}
+
+#[rstest]
+#[case(0.0)]
+#[case(-1.0)]
+#[case(f64::NAN)]
+#[case(f64::INFINITY)]
+fn invalid_parameters_are_rejected(#[case] value: f64) {
+    assert!(matches!(
+        TelemetryRollup::new(value),
+        Err(TelemetryError::InvalidTileSize(_))
+    ));
+    assert!(matches!(
+        PrivacyPolicy::none().with_laplace_noise(value),
+        Err(TelemetryError::InvalidEpsilon(_))
+    ));
+}
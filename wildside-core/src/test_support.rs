@@ -15,10 +15,12 @@ use rusqlite::{Connection, Error as SqliteError};
 use serde_json::to_string;
 
 #[cfg(all(any(test, feature = "test-support"), feature = "store-sqlite"))]
-use crate::store::{SpatialIndexWriteError, write_spatial_index};
+use crate::formats::POIS_SCHEMA;
+#[cfg(all(any(test, feature = "test-support"), feature = "store-sqlite"))]
+use crate::store::{SpatialIndexWriteError, install_poi_name_search, write_spatial_index};
 use crate::{
-    InterestProfile, PoiStore, PointOfInterest, TravelTimeError, TravelTimeMatrix,
-    TravelTimeProvider,
+    InterestProfile, PoiIter, PoiStore, PoiStoreError, PoiWriter, PointOfInterest, TravelTimeError,
+    TravelTimeMatrix, TravelTimeProvider,
 };
 #[cfg(any(test, feature = "test-support"))]
 use crate::{Scorer, Theme};
@@ -49,18 +51,38 @@ impl MemoryStore {
 }
 
 impl PoiStore for MemoryStore {
-    fn get_pois_in_bbox(
-        &self,
-        bbox: &Rect<f64>,
-    ) -> Box<dyn Iterator<Item = PointOfInterest> + Send + '_> {
+    fn get_pois_in_bbox(&self, bbox: &Rect<f64>) -> Result<PoiIter<'_>, PoiStoreError> {
         let bbox = *bbox;
-        Box::new(
+        Ok(Box::new(
             self.pois
                 .iter()
                 // `Intersects` treats boundary points as inside the rectangle.
                 .filter(move |p| bbox.intersects(&p.location))
                 .cloned(),
-        )
+        ))
+    }
+}
+
+impl PoiWriter for MemoryStore {
+    type Error = std::convert::Infallible;
+
+    fn upsert_pois(&mut self, pois: &[PointOfInterest]) -> Result<(), Self::Error> {
+        for poi in pois {
+            match self.pois.iter_mut().find(|existing| existing.id == poi.id) {
+                Some(existing) => *existing = poi.clone(),
+                None => self.pois.push(poi.clone()),
+            }
+        }
+        Ok(())
+    }
+
+    fn delete_pois(&mut self, ids: &[u64]) -> Result<(), Self::Error> {
+        self.pois.retain(|poi| !ids.contains(&poi.id));
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        Ok(())
     }
 }
 
@@ -78,6 +100,8 @@ pub fn write_sqlite_database(path: &Path, pois: &[PointOfInterest]) -> Result<()
         )",
         [],
     )?;
+    install_poi_name_search(&transaction, 0)?;
+    transaction.pragma_update(None, "user_version", POIS_SCHEMA.current)?;
     {
         let mut statement =
             transaction.prepare("INSERT INTO pois (id, lon, lat, tags) VALUES (?1, ?2, ?3, ?4)")?;
@@ -116,12 +140,13 @@ impl TravelTimeProvider for UnitTravelTimeProvider {
         if pois.is_empty() {
             return Err(TravelTimeError::EmptyInput);
         }
-        let n = pois.len();
-        let mut matrix = vec![vec![Duration::from_secs(1); n]; n];
-        for (i, row) in matrix.iter_mut().enumerate() {
-            row[i] = Duration::ZERO;
-        }
-        Ok(matrix)
+        Ok(TravelTimeMatrix::from_fn(pois.len(), |i, j| {
+            if i == j {
+                Duration::ZERO
+            } else {
+                Duration::from_secs(1)
+            }
+        }))
     }
 }
 
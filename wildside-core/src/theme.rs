@@ -9,6 +9,9 @@
 //! assert_eq!(Theme::History.as_str(), "history");
 //! assert_eq!(Theme::Art.to_string(), "art");
 //! ```
+
+use crate::Tags;
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Theme {
@@ -31,6 +34,25 @@ pub enum Theme {
 }
 
 impl Theme {
+    /// Every theme, in declaration order.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use wildside_core::Theme;
+    ///
+    /// assert!(Theme::ALL.contains(&Theme::Food));
+    /// ```
+    pub const ALL: [Self; 8] = [
+        Self::History,
+        Self::Art,
+        Self::Nature,
+        Self::Food,
+        Self::Architecture,
+        Self::Shopping,
+        Self::Entertainment,
+        Self::Culture,
+    ];
+
     /// Return the theme as a lowercase `&str`.
     ///
     /// # Examples
@@ -51,6 +73,99 @@ impl Theme {
             Self::Culture => "culture",
         }
     }
+
+    /// OpenStreetMap tags marking a POI as belonging to the theme.
+    ///
+    /// Each entry is a tag key and, optionally, the value it must carry; a
+    /// POI belongs to the theme when any entry matches. The table is a coarse
+    /// first pass for store queries, not a substitute for scoring.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use wildside_core::Theme;
+    ///
+    /// assert!(Theme::History.osm_tags().contains(&("historic", None)));
+    /// ```
+    pub fn osm_tags(&self) -> &'static [(&'static str, Option<&'static str>)] {
+        match self {
+            Self::History => &[("historic", None), ("heritage", None)],
+            Self::Art => &[
+                ("tourism", Some("artwork")),
+                ("tourism", Some("gallery")),
+                ("amenity", Some("arts_centre")),
+            ],
+            Self::Nature => &[
+                ("natural", None),
+                ("leisure", Some("park")),
+                ("leisure", Some("garden")),
+                ("leisure", Some("nature_reserve")),
+                ("tourism", Some("viewpoint")),
+            ],
+            Self::Food => &[
+                ("cuisine", None),
+                ("amenity", Some("restaurant")),
+                ("amenity", Some("cafe")),
+                ("amenity", Some("pub")),
+                ("amenity", Some("food_court")),
+            ],
+            Self::Architecture => &[
+                ("architect", None),
+                ("building:architecture", None),
+                ("historic", Some("castle")),
+            ],
+            Self::Shopping => &[("shop", None), ("amenity", Some("marketplace"))],
+            Self::Entertainment => &[
+                ("amenity", Some("cinema")),
+                ("amenity", Some("nightclub")),
+                ("tourism", Some("theme_park")),
+                ("tourism", Some("zoo")),
+            ],
+            Self::Culture => &[
+                ("tourism", Some("museum")),
+                ("amenity", Some("theatre")),
+                ("amenity", Some("library")),
+            ],
+        }
+    }
+
+    /// Whether `tags` match any entry of [`osm_tags`](Self::osm_tags).
+    ///
+    /// # Examples
+    /// ```rust
+    /// use wildside_core::{Tags, Theme};
+    ///
+    /// let tags = Tags::from([("tourism".into(), "museum".into())]);
+    /// assert!(Theme::Culture.matches_tags(&tags));
+    /// assert!(!Theme::Food.matches_tags(&tags));
+    /// ```
+    pub fn matches_tags(&self, tags: &Tags) -> bool {
+        self.osm_tags()
+            .iter()
+            .any(|&(key, value)| match (tags.get(key), value) {
+                (Some(_), None) => true,
+                (Some(found), Some(expected)) => found == expected,
+                (None, _) => false,
+            })
+    }
+
+    /// The first theme in [`ALL`](Self::ALL) whose OSM tags `tags` match,
+    /// used as the POI's category.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use wildside_core::{Tags, Theme};
+    ///
+    /// let tags = Tags::from([
+    ///     ("tourism".into(), "museum".into()),
+    ///     ("historic".into(), "building".into()),
+    /// ]);
+    /// assert_eq!(Theme::primary_for(&tags), Some(Theme::History));
+    /// assert_eq!(Theme::primary_for(&Tags::new()), None);
+    /// ```
+    #[must_use]
+    pub fn primary_for(tags: &Tags) -> Option<Self> {
+        Self::ALL.into_iter().find(|theme| theme.matches_tags(tags))
+    }
 }
 
 impl std::fmt::Display for Theme {
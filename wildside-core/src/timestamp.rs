@@ -0,0 +1,398 @@
+//! Absolute points in time observed at a local UTC offset.
+//!
+//! A [`Timestamp`] pins a walk to a calendar date: it records the instant as
+//! seconds since the Unix epoch together with the UTC offset of the place the
+//! walk happens in, so the local [`WeekTime`] used for opening hours follows
+//! directly. Timestamps parse from and display as RFC 3339 date-times such as
+//! `"2026-10-17T10:30:00+02:00"`; fractional seconds are accepted and dropped.
+//!
+//! # Examples
+//! ```rust
+//! use std::time::Duration;
+//! use wildside_core::{Timestamp, Weekday};
+//!
+//! let start: Timestamp = "2026-10-17T10:30:00+02:00".parse()?;
+//! assert_eq!(start.week_time().weekday(), Weekday::Saturday);
+//! assert_eq!(start.week_time().minute_of_day(), 630);
+//!
+//! let arrival = start.checked_add(Duration::from_mins(45)).expect("in range");
+//! assert_eq!(arrival.to_string(), "2026-10-17T11:15:00+02:00");
+//! # Ok::<(), wildside_core::TimestampError>(())
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::opening_hours::WeekTime;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+/// Largest UTC offset in use anywhere, in minutes.
+const MAX_OFFSET_MINUTES: i16 = 18 * 60;
+/// Days from 0000-03-01 to the Unix epoch, as used by the civil calendar
+/// conversions below.
+const EPOCH_DAYS: i64 = 719_468;
+/// Days from the Unix epoch to 0000-01-01 and to 10000-01-01, bounding the
+/// years a timestamp can display.
+const MIN_DAY: i64 = -719_528;
+const MAX_DAY: i64 = 2_932_897;
+
+/// Errors raised while parsing or building a [`Timestamp`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TimestampError {
+    /// The value is not an RFC 3339 date-time.
+    #[error("invalid timestamp {value:?}; expected e.g. \"2026-10-17T10:30:00+02:00\"")]
+    Invalid {
+        /// The value as written.
+        value: String,
+    },
+    /// The UTC offset exceeds eighteen hours either way.
+    #[error("UTC offset of {minutes} minutes is out of range")]
+    OffsetOutOfRange {
+        /// The offset in minutes east of UTC.
+        minutes: i16,
+    },
+    /// The local date falls outside the years 0000 to 9999.
+    #[error("timestamp {unix_seconds} is outside the years 0000 to 9999")]
+    OutOfRange {
+        /// Seconds since the Unix epoch.
+        unix_seconds: i64,
+    },
+}
+
+/// An instant, with the UTC offset of the place it is observed in.
+///
+/// Two timestamps for the same instant at different offsets compare unequal,
+/// since they name different local times.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "String", into = "String")
+)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Timestamp {
+    unix_seconds: i64,
+    utc_offset_minutes: i16,
+}
+
+impl Timestamp {
+    /// The instant `unix_seconds` after the Unix epoch, observed
+    /// `utc_offset_minutes` east of UTC.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TimestampError::OffsetOutOfRange`] for offsets beyond
+    /// eighteen hours, and [`TimestampError::OutOfRange`] when the local date
+    /// falls outside the years 0000 to 9999.
+    pub fn new(unix_seconds: i64, utc_offset_minutes: i16) -> Result<Self, TimestampError> {
+        if utc_offset_minutes.abs() > MAX_OFFSET_MINUTES {
+            return Err(TimestampError::OffsetOutOfRange {
+                minutes: utc_offset_minutes,
+            });
+        }
+        let timestamp = Self {
+            unix_seconds,
+            utc_offset_minutes,
+        };
+        let day = timestamp
+            .local_seconds()
+            .map(|seconds| seconds.div_euclid(SECONDS_PER_DAY));
+        match day {
+            Some(day) if (MIN_DAY..MAX_DAY).contains(&day) => Ok(timestamp),
+            _ => Err(TimestampError::OutOfRange { unix_seconds }),
+        }
+    }
+
+    /// Seconds since the Unix epoch.
+    #[must_use]
+    pub const fn unix_seconds(&self) -> i64 {
+        self.unix_seconds
+    }
+
+    /// Minutes east of UTC at which the instant is observed.
+    #[must_use]
+    pub const fn utc_offset_minutes(&self) -> i16 {
+        self.utc_offset_minutes
+    }
+
+    /// The local time of the week, for checking opening hours.
+    #[must_use]
+    pub fn week_time(&self) -> WeekTime {
+        let seconds = self.local_seconds().unwrap_or_default();
+        // The Unix epoch fell on a Thursday, three days after a Monday.
+        let since_monday = seconds.div_euclid(60) + 3 * SECONDS_PER_DAY / 60;
+        WeekTime::from_minute_of_week(since_monday.rem_euclid(7 * SECONDS_PER_DAY / 60) as u64)
+    }
+
+    /// The instant `elapsed` later at the same offset, or `None` when it
+    /// falls outside the supported years.
+    #[must_use]
+    pub fn checked_add(&self, elapsed: Duration) -> Option<Self> {
+        let seconds = i64::try_from(elapsed.as_secs()).ok()?;
+        Self::new(
+            self.unix_seconds.checked_add(seconds)?,
+            self.utc_offset_minutes,
+        )
+        .ok()
+    }
+
+    fn local_seconds(&self) -> Option<i64> {
+        self.unix_seconds
+            .checked_add(i64::from(self.utc_offset_minutes) * 60)
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self.local_seconds().unwrap_or_default();
+        let (year, month, day) = civil_from_days(seconds.div_euclid(SECONDS_PER_DAY));
+        let time = seconds.rem_euclid(SECONDS_PER_DAY);
+        write!(
+            f,
+            "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}",
+            time / 3600,
+            time % 3600 / 60,
+            time % 60
+        )?;
+        match self.utc_offset_minutes {
+            0 => f.write_str("Z"),
+            offset => {
+                let sign = if offset < 0 { '-' } else { '+' };
+                let minutes = offset.unsigned_abs();
+                write!(f, "{sign}{:02}:{:02}", minutes / 60, minutes % 60)
+            }
+        }
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = TimestampError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || TimestampError::Invalid {
+            value: value.to_owned(),
+        };
+        let trimmed = value.trim();
+        let (date, rest) = trimmed.split_once(['T', 't', ' ']).ok_or_else(invalid)?;
+        let days = parse_date(date).ok_or_else(invalid)?;
+        let (time, offset) = split_offset(rest).ok_or_else(invalid)?;
+        let seconds = parse_time(time).ok_or_else(invalid)?;
+        let offset_minutes = parse_offset(offset).ok_or_else(invalid)?;
+        let local = days * SECONDS_PER_DAY + seconds;
+        Self::new(local - i64::from(offset_minutes) * 60, offset_minutes)
+    }
+}
+
+impl TryFrom<String> for Timestamp {
+    type Error = TimestampError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Timestamp> for String {
+    fn from(value: Timestamp) -> Self {
+        value.to_string()
+    }
+}
+
+/// Parse `YYYY-MM-DD` into days since the Unix epoch.
+fn parse_date(date: &str) -> Option<i64> {
+    let mut parts = date.split('-');
+    let year = parse_digits(parts.next()?, 4)?;
+    let month = parse_digits(parts.next()?, 2)?;
+    let day = parse_digits(parts.next()?, 2)?;
+    if parts.next().is_some() || !(1..=12).contains(&month) {
+        return None;
+    }
+    (1..=days_in_month(year, month))
+        .contains(&day)
+        .then(|| days_from_civil(year, month, day))
+}
+
+/// Split `HH:MM:SS[.fff]` from its `Z` or `±HH:MM` offset.
+fn split_offset(rest: &str) -> Option<(&str, &str)> {
+    if let Some(time) = rest.strip_suffix(['Z', 'z']) {
+        return Some((time, "Z"));
+    }
+    let at = rest.rfind(['+', '-'])?;
+    Some((rest.get(..at)?, rest.get(at..)?))
+}
+
+/// Parse `HH:MM:SS`, with optional fractional seconds, into seconds since
+/// midnight.
+fn parse_time(time: &str) -> Option<i64> {
+    let whole = match time.split_once('.') {
+        Some((whole, fraction))
+            if !fraction.is_empty() && fraction.bytes().all(|byte| byte.is_ascii_digit()) =>
+        {
+            whole
+        }
+        Some(_) => return None,
+        None => time,
+    };
+    let mut parts = whole.split(':');
+    let hour = parse_digits(parts.next()?, 2)?;
+    let minute = parse_digits(parts.next()?, 2)?;
+    let second = parse_digits(parts.next()?, 2)?;
+    (parts.next().is_none() && hour < 24 && minute < 60 && second < 60)
+        .then_some(hour * 3600 + minute * 60 + second)
+}
+
+/// Parse `Z` or `±HH:MM` into minutes east of UTC.
+fn parse_offset(offset: &str) -> Option<i16> {
+    if offset == "Z" {
+        return Some(0);
+    }
+    let (sign, clock) = match offset.split_at_checked(1)? {
+        ("+", clock) => (1, clock),
+        ("-", clock) => (-1, clock),
+        _ => return None,
+    };
+    let (hour, minute) = clock.split_once(':')?;
+    let hour = parse_digits(hour, 2)?;
+    let minute = parse_digits(minute, 2)?;
+    if minute >= 60 {
+        return None;
+    }
+    i16::try_from(sign * (hour * 60 + minute)).ok()
+}
+
+/// Parse exactly `width` ASCII digits.
+fn parse_digits(value: &str, width: usize) -> Option<i64> {
+    (value.len() == width && value.bytes().all(|byte| byte.is_ascii_digit()))
+        .then(|| value.parse().ok())
+        .flatten()
+}
+
+const fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+const fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since the Unix epoch of a proleptic Gregorian date.
+///
+/// Counts from March so that leap days fall at the end of each year.
+const fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_from_march = (month + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - EPOCH_DAYS
+}
+
+/// The proleptic Gregorian date `days` after the Unix epoch.
+const fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let shifted = days + EPOCH_DAYS;
+    let era = shifted.div_euclid(146_097);
+    let day_of_era = shifted - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for timestamp parsing, display, and arithmetic.
+
+    use super::*;
+    use crate::opening_hours::Weekday;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("1970-01-01T00:00:00Z", 0, 0)]
+    #[case("2026-10-17T10:30:00+02:00", 1_792_225_800, 120)]
+    #[case("2024-02-29T23:59:59-05:30", 1_709_270_999, -330)]
+    #[case("2026-10-17 08:30:00.250Z", 1_792_225_800, 0)]
+    fn parses_rfc_3339(#[case] value: &str, #[case] unix_seconds: i64, #[case] offset: i16) {
+        let parsed: Timestamp = value.parse().expect("parse");
+        assert_eq!(parsed.unix_seconds(), unix_seconds);
+        assert_eq!(parsed.utc_offset_minutes(), offset);
+    }
+
+    #[rstest]
+    #[case("2026-10-17T10:30:00+02:00")]
+    #[case("1969-12-31T23:59:59Z")]
+    #[case("0000-01-01T00:00:00Z")]
+    #[case("9999-12-31T23:59:59-11:00")]
+    fn displays_what_it_parses(#[case] value: &str) {
+        let parsed: Timestamp = value.parse().expect("parse");
+        assert_eq!(parsed.to_string(), value);
+    }
+
+    #[rstest]
+    #[case("2026-10-17")]
+    #[case("2026-10-17T10:30+02:00")]
+    #[case("2026-13-01T00:00:00Z")]
+    #[case("2025-02-29T00:00:00Z")]
+    #[case("2026-10-17T24:00:00Z")]
+    #[case("2026-10-17T10:30:00")]
+    #[case("2026-10-17T10:30:00+2:00")]
+    #[case("2026-10-17T10:30:00.Z")]
+    fn rejects_malformed_values(#[case] value: &str) {
+        assert!(matches!(
+            value.parse::<Timestamp>(),
+            Err(TimestampError::Invalid { .. })
+        ));
+    }
+
+    #[rstest]
+    fn rejects_offsets_beyond_eighteen_hours() {
+        assert_eq!(
+            Timestamp::new(0, 19 * 60),
+            Err(TimestampError::OffsetOutOfRange { minutes: 19 * 60 })
+        );
+        assert!(matches!(
+            "2026-10-17T10:30:00+19:00".parse::<Timestamp>(),
+            Err(TimestampError::OffsetOutOfRange { .. })
+        ));
+    }
+
+    #[rstest]
+    #[case("2026-10-12T00:00:00Z", Weekday::Monday, 0)]
+    #[case("2026-10-17T10:30:00+02:00", Weekday::Saturday, 630)]
+    #[case("2026-10-17T01:00:00+02:00", Weekday::Saturday, 60)]
+    #[case("1969-12-28T23:59:00Z", Weekday::Sunday, 1439)]
+    fn week_times_are_local(
+        #[case] value: &str,
+        #[case] weekday: Weekday,
+        #[case] minute_of_day: u16,
+    ) {
+        let week_time = value.parse::<Timestamp>().expect("parse").week_time();
+        assert_eq!(week_time.weekday(), weekday);
+        assert_eq!(week_time.minute_of_day(), minute_of_day);
+    }
+
+    #[rstest]
+    fn adding_time_keeps_the_offset() {
+        let start: Timestamp = "2026-10-17T23:30:00-04:00".parse().expect("parse");
+        let later = start
+            .checked_add(Duration::from_hours(1))
+            .expect("in range");
+        assert_eq!(later.to_string(), "2026-10-18T00:30:00-04:00");
+        let last: Timestamp = "9999-12-31T23:59:59Z".parse().expect("parse");
+        assert_eq!(last.checked_add(Duration::from_secs(1)), None);
+    }
+}
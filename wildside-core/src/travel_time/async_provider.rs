@@ -0,0 +1,136 @@
+//! Asynchronous travel-time provider trait and its adapter for sync providers.
+
+use std::future::Future;
+
+use crate::PointOfInterest;
+
+use super::{TravelTimeError, TravelTimeMatrix, TravelTimeProvider};
+
+/// Fetch pairwise travel times for a set of POIs without blocking.
+///
+/// This is the asynchronous counterpart of [`TravelTimeProvider`], for
+/// providers backed by network services that would otherwise have to block
+/// on their own runtime. The contract is the same: a square `n×n` matrix
+/// where `n == pois.len()`, and [`TravelTimeError::EmptyInput`] for an empty
+/// slice. Returned futures are `Send` so they can run on multi-threaded
+/// executors.
+///
+/// Synchronous providers can be used where this trait is expected by wrapping
+/// them in [`SyncTravelTimeAdapter`].
+///
+/// # Examples
+///
+/// ```rust
+/// use geo::Coord;
+/// use wildside_core::{PointOfInterest, TravelTimeError, TravelTimeMatrix};
+/// use wildside_core::travel_time::AsyncTravelTimeProvider;
+///
+/// async fn leg_count(
+///     provider: &impl AsyncTravelTimeProvider,
+///     pois: &[PointOfInterest],
+/// ) -> Result<usize, TravelTimeError> {
+///     let matrix: TravelTimeMatrix = provider.get_travel_time_matrix_async(pois).await?;
+///     Ok(matrix.len() * matrix.len().saturating_sub(1))
+/// }
+/// ```
+pub trait AsyncTravelTimeProvider {
+    /// Return a matrix of travel times for `pois`.
+    ///
+    /// Implementations must resolve to `Err(TravelTimeError::EmptyInput)`
+    /// when `pois` is empty.
+    fn get_travel_time_matrix_async(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> impl Future<Output = Result<TravelTimeMatrix, TravelTimeError>> + Send;
+}
+
+/// Present any [`TravelTimeProvider`] as an [`AsyncTravelTimeProvider`].
+///
+/// The wrapped provider runs on the polling thread when the future is first
+/// polled, so this suits providers that compute locally, such as
+/// [`HaversineTravelTimeProvider`](super::HaversineTravelTimeProvider).
+/// Blocking network providers should implement the async trait directly.
+///
+/// # Examples
+///
+/// ```rust
+/// use wildside_core::travel_time::{
+///     AsyncTravelTimeProvider, HaversineTravelTimeProvider, SyncTravelTimeAdapter,
+/// };
+///
+/// fn assert_async(_: &impl AsyncTravelTimeProvider) {}
+///
+/// assert_async(&SyncTravelTimeAdapter::new(HaversineTravelTimeProvider::default()));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncTravelTimeAdapter<P>(P);
+
+impl<P> SyncTravelTimeAdapter<P> {
+    /// Wrap `provider`.
+    pub const fn new(provider: P) -> Self {
+        Self(provider)
+    }
+
+    /// Borrow the wrapped provider.
+    pub const fn get_ref(&self) -> &P {
+        &self.0
+    }
+
+    /// Unwrap the provider.
+    pub fn into_inner(self) -> P {
+        self.0
+    }
+}
+
+impl<P: TravelTimeProvider + Sync> AsyncTravelTimeProvider for SyncTravelTimeAdapter<P> {
+    fn get_travel_time_matrix_async(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> impl Future<Output = Result<TravelTimeMatrix, TravelTimeError>> + Send {
+        let provider = &self.0;
+        async move { provider.get_travel_time_matrix(pois) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for the synchronous provider adapter.
+
+    use super::*;
+    use crate::test_support::UnitTravelTimeProvider;
+    use geo::Coord;
+    use rstest::rstest;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+    use std::time::Duration;
+
+    /// Poll a future that is expected to complete without waiting.
+    fn poll_ready<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        match future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("adapter future should complete on first poll"),
+        }
+    }
+
+    #[rstest]
+    fn adapter_returns_wrapped_provider_matrix() {
+        let provider = SyncTravelTimeAdapter::new(UnitTravelTimeProvider);
+        let pois = [
+            PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 }),
+            PointOfInterest::with_empty_tags(2, Coord { x: 1.0, y: 1.0 }),
+        ];
+        let matrix = poll_ready(provider.get_travel_time_matrix_async(&pois)).expect("matrix");
+        assert_eq!(matrix[0][1], Duration::from_secs(1));
+    }
+
+    #[rstest]
+    fn adapter_passes_through_empty_input_error() {
+        let provider = SyncTravelTimeAdapter::new(UnitTravelTimeProvider);
+        let err = poll_ready(provider.get_travel_time_matrix_async(&[])).expect_err("empty input");
+        assert_eq!(err, TravelTimeError::EmptyInput);
+    }
+}
@@ -70,4 +70,61 @@ pub enum TravelTimeError {
         /// A human-readable error message.
         message: String,
     },
+
+    /// The routing service is temporarily being skipped.
+    ///
+    /// Recent requests failed often enough that the provider's circuit
+    /// breaker stopped contacting the service for a cooldown period.
+    #[error("routing service at {base_url} is unavailable; retrying in {retry_after_secs} seconds")]
+    CircuitOpen {
+        /// Base URL of the routing service.
+        base_url: String,
+        /// Seconds until a request will be attempted again.
+        retry_after_secs: u64,
+    },
+
+    /// Every provider in a fallback chain failed.
+    ///
+    /// Returned by
+    /// [`FallbackTravelTimeProvider`](crate::travel_time::FallbackTravelTimeProvider)
+    /// when no provider could produce a matrix.
+    #[error("every travel-time provider failed: {}", .failures.join("; "))]
+    AllProvidersFailed {
+        /// Each provider's error, in chain order, prefixed with its name.
+        failures: Vec<String>,
+    },
+
+    /// A replayed recording has no travel times for the requested POIs.
+    ///
+    /// Returned by
+    /// [`ReplayTravelTimeProvider`](crate::travel_time::ReplayTravelTimeProvider)
+    /// when no recorded matrix covers every requested location. Record the
+    /// request again against the live service to refresh the fixture.
+    #[error("no recorded travel times cover the {poi_count} requested locations")]
+    NotRecorded {
+        /// Number of POIs in the request.
+        poi_count: usize,
+    },
+
+    /// Travel times for the requested POIs have not been fetched yet.
+    ///
+    /// Returned by
+    /// [`PrefetchedTravelTimeProvider`](crate::travel_time::PrefetchedTravelTimeProvider)
+    /// for requests no fetched matrix covers; the request is noted so it can
+    /// be fetched before trying again.
+    #[error("travel times for the {poi_count} requested locations have not been fetched yet")]
+    NotPrefetched {
+        /// Number of POIs in the request.
+        poi_count: usize,
+    },
+
+    /// Step-free routing was required but the provider cannot plan it.
+    ///
+    /// Returned when a request's
+    /// [`AccessibilityRequirements`](crate::AccessibilityRequirements) ask
+    /// for step-free legs and
+    /// [`TravelTimeProvider::step_free`](crate::TravelTimeProvider::step_free)
+    /// offers no profile.
+    #[error("the travel-time provider cannot plan step-free routes")]
+    StepFreeUnavailable,
 }
@@ -0,0 +1,326 @@
+//! Ordered fallback across several travel-time providers.
+
+use std::{
+    fmt,
+    sync::{Mutex, PoisonError},
+};
+
+use geo::{Coord, LineString};
+
+use crate::PointOfInterest;
+
+use super::{TravelTimeError, TravelTimeMatrix, TravelTimeProvider};
+
+type BoxedProvider = Box<dyn TravelTimeProvider + Send + Sync>;
+
+/// A provider in a fallback chain that failed one request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderFailure {
+    /// Name the provider was registered under.
+    pub provider: String,
+    /// Error it returned.
+    pub error: TravelTimeError,
+}
+
+/// How a [`FallbackTravelTimeProvider`] answered one request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FallbackOutcome {
+    /// Name of the provider whose matrix was returned, or `None` when every
+    /// provider failed.
+    pub served_by: Option<String>,
+    /// Providers tried before it, in order, with their errors.
+    pub failures: Vec<ProviderFailure>,
+}
+
+/// Requests served and failed by one provider in a chain.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProviderUsage {
+    /// Name the provider was registered under.
+    pub provider: String,
+    /// Requests it answered.
+    pub served: u64,
+    /// Requests it failed, passing them to the next provider.
+    pub failed: u64,
+}
+
+/// Snapshot of a chain's history, from
+/// [`FallbackTravelTimeProvider::diagnostics`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FallbackDiagnostics {
+    /// Per-provider counts, in chain order.
+    pub usage: Vec<ProviderUsage>,
+    /// Outcome of the most recent request, if any was made.
+    pub last: Option<FallbackOutcome>,
+}
+
+/// Try several travel-time providers in order until one succeeds.
+///
+/// Each request goes to the first provider; when it fails, the next is tried,
+/// and so on. A request fails only when every provider does, with
+/// [`TravelTimeError::AllProvidersFailed`] listing each error. Empty input is
+/// rejected up front rather than passed down the chain.
+///
+/// Which provider served each request is recorded and can be read back with
+/// [`Self::diagnostics`], so a solve that quietly fell back to estimates can
+/// be told apart from one that used the routing service.
+///
+/// # Examples
+/// ```rust
+/// use geo::Coord;
+/// use wildside_core::{PointOfInterest, TravelTimeError, TravelTimeMatrix, TravelTimeProvider};
+/// use wildside_core::travel_time::{FallbackTravelTimeProvider, HaversineTravelTimeProvider};
+///
+/// struct Offline;
+///
+/// impl TravelTimeProvider for Offline {
+///     fn get_travel_time_matrix(
+///         &self,
+///         _pois: &[PointOfInterest],
+///     ) -> Result<TravelTimeMatrix, TravelTimeError> {
+///         Err(TravelTimeError::NetworkError {
+///             url: "http://osrm.invalid".into(),
+///             message: "connection refused".into(),
+///         })
+///     }
+/// }
+///
+/// let provider = FallbackTravelTimeProvider::new("osrm", Offline)
+///     .with_fallback("haversine", HaversineTravelTimeProvider::default());
+/// let pois = [PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 })];
+/// provider.get_travel_time_matrix(&pois)?;
+///
+/// let last = provider.diagnostics().last.expect("one request");
+/// assert_eq!(last.served_by.as_deref(), Some("haversine"));
+/// assert_eq!(last.failures[0].provider, "osrm");
+/// # Ok::<(), TravelTimeError>(())
+/// ```
+pub struct FallbackTravelTimeProvider {
+    providers: Vec<(String, BoxedProvider)>,
+    diagnostics: Mutex<FallbackDiagnostics>,
+}
+
+impl fmt::Debug for FallbackTravelTimeProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FallbackTravelTimeProvider")
+            .field(
+                "providers",
+                &self
+                    .providers
+                    .iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+impl FallbackTravelTimeProvider {
+    /// Start a chain whose first choice is `provider`, reported as `name`.
+    pub fn new<P>(name: impl Into<String>, provider: P) -> Self
+    where
+        P: TravelTimeProvider + Send + Sync + 'static,
+    {
+        Self {
+            providers: Vec::new(),
+            diagnostics: Mutex::new(FallbackDiagnostics::default()),
+        }
+        .with_fallback(name, provider)
+    }
+
+    /// Append `provider`, reported as `name`, to try after those already in
+    /// the chain, while consuming `self`.
+    #[must_use]
+    pub fn with_fallback<P>(mut self, name: impl Into<String>, provider: P) -> Self
+    where
+        P: TravelTimeProvider + Send + Sync + 'static,
+    {
+        let name = name.into();
+        self.diagnostics
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .usage
+            .push(ProviderUsage {
+                provider: name.clone(),
+                ..ProviderUsage::default()
+            });
+        self.providers.push((name, Box::new(provider)));
+        self
+    }
+
+    /// Names of the providers in the order they are tried.
+    pub fn provider_names(&self) -> impl Iterator<Item = &str> {
+        self.providers.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Return the per-provider counts and the outcome of the last request.
+    pub fn diagnostics(&self) -> FallbackDiagnostics {
+        self.diagnostics
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn record(&self, outcome: FallbackOutcome) {
+        let mut diagnostics = self
+            .diagnostics
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        // Providers are tried in chain order, so the failures are the first
+        // entries and the provider that served, if any, is the next.
+        let tried = outcome.failures.len();
+        let mut usage = diagnostics.usage.iter_mut();
+        for failed in usage.by_ref().take(tried) {
+            failed.failed += 1;
+        }
+        if outcome.served_by.is_some()
+            && let Some(served) = usage.next()
+        {
+            served.served += 1;
+        }
+        diagnostics.last = Some(outcome);
+    }
+}
+
+impl TravelTimeProvider for FallbackTravelTimeProvider {
+    fn get_travel_time_matrix(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> Result<TravelTimeMatrix, TravelTimeError> {
+        if pois.is_empty() {
+            return Err(TravelTimeError::EmptyInput);
+        }
+        let mut outcome = FallbackOutcome::default();
+        for (name, provider) in &self.providers {
+            match provider.get_travel_time_matrix(pois) {
+                Ok(matrix) => {
+                    outcome.served_by = Some(name.clone());
+                    self.record(outcome);
+                    return Ok(matrix);
+                }
+                Err(error) => outcome.failures.push(ProviderFailure {
+                    provider: name.clone(),
+                    error,
+                }),
+            }
+        }
+        let failures = outcome
+            .failures
+            .iter()
+            .map(|failure| format!("{}: {}", failure.provider, failure.error))
+            .collect();
+        self.record(outcome);
+        Err(TravelTimeError::AllProvidersFailed { failures })
+    }
+
+    /// Return the first path any provider in the chain can trace.
+    ///
+    /// Geometry is optional, so failures move on to the next provider and
+    /// are not recorded in the diagnostics.
+    fn route_geometry(
+        &self,
+        stops: &[Coord<f64>],
+    ) -> Result<Option<LineString<f64>>, TravelTimeError> {
+        Ok(self
+            .providers
+            .iter()
+            .find_map(|(_, provider)| provider.route_geometry(stops).ok().flatten()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for ordered provider fallback.
+
+    use super::*;
+    use crate::test_support::UnitTravelTimeProvider;
+    use geo::Coord;
+    use rstest::rstest;
+
+    struct Failing;
+
+    impl TravelTimeProvider for Failing {
+        fn get_travel_time_matrix(
+            &self,
+            _pois: &[PointOfInterest],
+        ) -> Result<TravelTimeMatrix, TravelTimeError> {
+            Err(TravelTimeError::ServiceError {
+                code: String::from("NoTable"),
+                message: String::from("unavailable"),
+            })
+        }
+    }
+
+    fn pois() -> Vec<PointOfInterest> {
+        vec![
+            PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 }),
+            PointOfInterest::with_empty_tags(2, Coord { x: 1.0, y: 1.0 }),
+        ]
+    }
+
+    #[rstest]
+    fn first_healthy_provider_serves() {
+        let provider = FallbackTravelTimeProvider::new("unit", UnitTravelTimeProvider)
+            .with_fallback("failing", Failing);
+        provider.get_travel_time_matrix(&pois()).expect("matrix");
+
+        let diagnostics = provider.diagnostics();
+        let last = diagnostics.last.expect("outcome");
+        assert_eq!(last.served_by.as_deref(), Some("unit"));
+        assert!(last.failures.is_empty());
+        assert_eq!(diagnostics.usage[0].served, 1);
+        assert_eq!(diagnostics.usage[1].served + diagnostics.usage[1].failed, 0);
+    }
+
+    #[rstest]
+    fn falls_back_after_failures() {
+        let provider = FallbackTravelTimeProvider::new("primary", Failing)
+            .with_fallback("secondary", Failing)
+            .with_fallback("unit", UnitTravelTimeProvider);
+        let matrix = provider.get_travel_time_matrix(&pois()).expect("matrix");
+        assert_eq!(matrix.len(), 2);
+
+        let diagnostics = provider.diagnostics();
+        let last = diagnostics.last.expect("outcome");
+        assert_eq!(last.served_by.as_deref(), Some("unit"));
+        let failed: Vec<_> = last
+            .failures
+            .iter()
+            .map(|failure| failure.provider.as_str())
+            .collect();
+        assert_eq!(failed, ["primary", "secondary"]);
+        let counts: Vec<_> = diagnostics
+            .usage
+            .iter()
+            .map(|usage| (usage.served, usage.failed))
+            .collect();
+        assert_eq!(counts, [(0, 1), (0, 1), (1, 0)]);
+    }
+
+    #[rstest]
+    fn errors_when_every_provider_fails() {
+        let provider =
+            FallbackTravelTimeProvider::new("primary", Failing).with_fallback("secondary", Failing);
+        let err = provider
+            .get_travel_time_matrix(&pois())
+            .expect_err("all providers fail");
+        let TravelTimeError::AllProvidersFailed { failures } = err else {
+            panic!("expected AllProvidersFailed, got {err:?}");
+        };
+        assert_eq!(failures.len(), 2);
+        assert!(failures[0].starts_with("primary: "));
+        assert_eq!(
+            provider.diagnostics().last.and_then(|last| last.served_by),
+            None
+        );
+    }
+
+    #[rstest]
+    fn rejects_empty_input_without_trying_providers() {
+        let provider = FallbackTravelTimeProvider::new("failing", Failing);
+        let err = provider
+            .get_travel_time_matrix(&[])
+            .expect_err("empty input");
+        assert_eq!(err, TravelTimeError::EmptyInput);
+        assert_eq!(provider.diagnostics().last, None);
+    }
+}
@@ -0,0 +1,246 @@
+//! Persistence for routing graphs.
+//!
+//! Files start with the `WSRG` magic and a little-endian `u16` version,
+//! followed by a zstd stream holding the `bincode` encoding of the graph's
+//! adjacency. The spatial index over the nodes is rebuilt on load.
+
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+use crate::artefact_io::{open_parent_dir, read_array};
+use crate::formats::{self, FormatVersionError};
+
+use super::{Adjacency, RoutingGraph};
+
+/// File identifier for persisted routing graphs.
+pub(crate) const ROUTING_GRAPH_MAGIC: [u8; 4] = *b"WSRG";
+
+/// Version stamped on newly written routing graph files.
+pub(crate) const ROUTING_GRAPH_VERSION: u16 = formats::ROUTING_GRAPH.current;
+
+/// zstd level used when writing routing graphs.
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Error raised when reading or writing a persisted routing graph.
+#[derive(Debug, Error)]
+pub enum RoutingGraphError {
+    /// The graph file could not be read or written.
+    #[error("failed to access routing graph at {path}: {source}")]
+    Io {
+        /// Location of the routing graph artefact.
+        path: PathBuf,
+        /// Underlying I/O error.
+        #[source]
+        source: io::Error,
+    },
+    /// The graph could not be encoded.
+    #[error("failed to encode routing graph for {path}: {source}")]
+    Encode {
+        /// Location of the routing graph artefact.
+        path: PathBuf,
+        /// Encoder failure from `bincode`.
+        #[source]
+        source: bincode::Error,
+    },
+    /// The graph payload could not be decoded.
+    #[error("failed to decode routing graph from {path}: {source}")]
+    Decode {
+        /// Location of the routing graph artefact.
+        path: PathBuf,
+        /// Decoder failure from `bincode`.
+        #[source]
+        source: bincode::Error,
+    },
+    /// The file did not contain the expected header.
+    #[error("invalid routing graph magic: expected {expected:?}, found {found:?}")]
+    InvalidMagic {
+        /// Expected byte sequence identifying a routing graph file.
+        expected: [u8; 4],
+        /// Sequence read from the file.
+        found: [u8; 4],
+    },
+    /// The file's format version is outside the readable range.
+    #[error(transparent)]
+    UnsupportedVersion(#[from] FormatVersionError),
+    /// The payload described an impossible graph.
+    #[error("routing graph at {path} is corrupt: {reason}")]
+    Corrupt {
+        /// Location of the routing graph artefact.
+        path: PathBuf,
+        /// Description of the inconsistency.
+        reason: &'static str,
+    },
+}
+
+impl RoutingGraph {
+    /// Persist the graph in the `WSRG` format. Existing files are truncated.
+    pub fn write(&self, path: &Path) -> Result<(), RoutingGraphError> {
+        let io_error = |source| RoutingGraphError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let (dir, file_name) = open_parent_dir(path).map_err(io_error)?;
+        let mut file = io::BufWriter::new(dir.create(file_name).map_err(io_error)?);
+        file.write_all(&ROUTING_GRAPH_MAGIC).map_err(io_error)?;
+        file.write_all(&ROUTING_GRAPH_VERSION.to_le_bytes())
+            .map_err(io_error)?;
+        let mut encoder = zstd::Encoder::new(file, COMPRESSION_LEVEL).map_err(io_error)?;
+        bincode::serialize_into(&mut encoder, &self.adjacency).map_err(|source| {
+            RoutingGraphError::Encode {
+                path: path.to_path_buf(),
+                source,
+            }
+        })?;
+        let file = encoder
+            .finish()
+            .map_err(io_error)?
+            .into_inner()
+            .map_err(|error| io_error(error.into_error()))?;
+        file.sync_all().map_err(io_error)
+    }
+
+    /// Load a graph previously written by [`RoutingGraph::write`].
+    pub fn load(path: &Path) -> Result<Self, RoutingGraphError> {
+        let io_error = |source| RoutingGraphError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let (dir, file_name) = open_parent_dir(path).map_err(io_error)?;
+        let mut file = io::BufReader::new(dir.open(file_name).map_err(io_error)?);
+
+        let magic: [u8; 4] = read_array(&mut file).map_err(io_error)?;
+        if magic != ROUTING_GRAPH_MAGIC {
+            return Err(RoutingGraphError::InvalidMagic {
+                expected: ROUTING_GRAPH_MAGIC,
+                found: magic,
+            });
+        }
+        let version = u16::from_le_bytes(read_array(&mut file).map_err(io_error)?);
+        formats::ROUTING_GRAPH.negotiate(i64::from(version))?;
+
+        let decoder = zstd::Decoder::with_buffer(file).map_err(io_error)?;
+        let adjacency: Adjacency =
+            bincode::deserialize_from(decoder).map_err(|source| RoutingGraphError::Decode {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        validate(&adjacency).map_err(|reason| RoutingGraphError::Corrupt {
+            path: path.to_path_buf(),
+            reason,
+        })?;
+        Ok(Self::from_adjacency(adjacency))
+    }
+}
+
+/// Check the invariants [`RoutingGraph`] queries rely on.
+fn validate(adjacency: &Adjacency) -> Result<(), &'static str> {
+    let Adjacency {
+        nodes,
+        offsets,
+        targets,
+        lengths,
+    } = adjacency;
+    if offsets.len() != nodes.len() + 1 {
+        return Err("offset count does not match node count");
+    }
+    if targets.len() != lengths.len() {
+        return Err("edge targets and lengths differ in count");
+    }
+    if offsets.first().is_some_and(|first| *first != 0)
+        || offsets.windows(2).any(|pair| pair[0] > pair[1])
+        || offsets.last().map(|last| *last as usize) != Some(targets.len())
+    {
+        return Err("edge offsets are not monotonic");
+    }
+    if targets.iter().any(|target| *target as usize >= nodes.len()) {
+        return Err("edge target out of range");
+    }
+    if lengths
+        .iter()
+        .any(|metres| !metres.is_finite() || *metres < 0.0)
+    {
+        return Err("edge length is negative or not finite");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for routing graph persistence.
+
+    use super::*;
+    use crate::travel_time::RoutingGraphBuilder;
+    use geo::Coord;
+    use rstest::{fixture, rstest};
+    use tempfile::TempDir;
+
+    #[fixture]
+    fn temp_graph_path() -> (TempDir, PathBuf) {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("graph.bin");
+        (dir, path)
+    }
+
+    fn write_payload(path: &Path, version: u16, adjacency: &Adjacency) {
+        let mut bytes = ROUTING_GRAPH_MAGIC.to_vec();
+        bytes.extend_from_slice(&version.to_le_bytes());
+        let payload = bincode::serialize(adjacency).expect("encode adjacency");
+        bytes.extend(zstd::encode_all(payload.as_slice(), 0).expect("compress"));
+        std::fs::write(path, bytes).expect("write graph");
+    }
+
+    #[rstest]
+    fn round_trips_through_disk(#[from(temp_graph_path)] (_dir, path): (TempDir, PathBuf)) {
+        let mut builder = RoutingGraphBuilder::default();
+        let a = builder.add_node(Coord { x: 0.0, y: 0.0 }).expect("node");
+        let b = builder.add_node(Coord { x: 0.0, y: 0.001 }).expect("node");
+        builder.add_edge(a, b);
+        let graph = builder.build();
+        graph.write(&path).expect("write graph");
+
+        let loaded = RoutingGraph::load(&path).expect("load graph");
+        assert_eq!(loaded, graph);
+        assert_eq!(
+            loaded
+                .nearest_node(Coord { x: 0.0, y: 0.0009 })
+                .map(|(node, _)| node),
+            Some(b)
+        );
+    }
+
+    #[rstest]
+    fn load_rejects_invalid_magic(#[from(temp_graph_path)] (_dir, path): (TempDir, PathBuf)) {
+        std::fs::write(&path, b"BAD!\x01\x00").expect("write corrupt header");
+        let error = RoutingGraph::load(&path).expect_err("invalid magic should fail");
+        assert!(matches!(error, RoutingGraphError::InvalidMagic { .. }));
+    }
+
+    #[rstest]
+    fn load_rejects_unsupported_version(#[from(temp_graph_path)] (_dir, path): (TempDir, PathBuf)) {
+        write_payload(&path, ROUTING_GRAPH_VERSION + 1, &Adjacency::default());
+        let error = RoutingGraph::load(&path).expect_err("unsupported version should fail");
+        assert!(matches!(error, RoutingGraphError::UnsupportedVersion(_)));
+    }
+
+    #[rstest]
+    fn load_rejects_out_of_range_targets(
+        #[from(temp_graph_path)] (_dir, path): (TempDir, PathBuf),
+    ) {
+        let adjacency = Adjacency {
+            nodes: vec![Coord { x: 0.0, y: 0.0 }],
+            offsets: vec![0, 1],
+            targets: vec![3],
+            lengths: vec![1.0],
+        };
+        write_payload(&path, ROUTING_GRAPH_VERSION, &adjacency);
+        let error = RoutingGraph::load(&path).expect_err("corrupt graph should fail");
+        assert!(matches!(
+            error,
+            RoutingGraphError::Corrupt { reason, .. } if reason == "edge target out of range"
+        ));
+    }
+}
@@ -0,0 +1,255 @@
+//! Offline routing over a pedestrian street graph.
+//!
+//! Ingest extracts the walkable ways from an OSM extract into a
+//! [`RoutingGraph`] and persists it as `graph.bin`. The
+//! [`GraphTravelTimeProvider`] answers travel-time requests from that graph
+//! alone, so a solve needs no routing service.
+//!
+//! The graph is stored in compressed sparse row form: the outgoing edges of
+//! node `n` are `targets[offsets[n]..offsets[n + 1]]`, with their lengths in
+//! metres at the same positions of `lengths`. Every way segment is stored in
+//! both directions because pedestrians may walk either way along a street.
+
+use std::{collections::BTreeSet, fmt};
+
+use geo::{Coord, Distance, Haversine, Point};
+use rstar::{RTree, primitives::GeomWithData};
+use serde::{Deserialize, Serialize};
+
+mod file;
+mod provider;
+mod search;
+
+pub use file::RoutingGraphError;
+pub use provider::{DEFAULT_MAX_SNAP_DISTANCE_M, GraphTravelTimeProvider};
+
+type NodeEntry = GeomWithData<[f64; 2], u32>;
+
+/// Adjacency of a routing graph in compressed sparse row form.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Adjacency {
+    nodes: Vec<Coord<f64>>,
+    offsets: Vec<u32>,
+    targets: Vec<u32>,
+    lengths: Vec<f32>,
+}
+
+/// Walkable street network used for offline routing.
+///
+/// Build one with [`RoutingGraphBuilder`], persist it with
+/// [`Self::write`], and read it back with [`Self::load`].
+///
+/// # Examples
+/// ```rust
+/// use geo::Coord;
+/// use wildside_core::travel_time::RoutingGraphBuilder;
+///
+/// let mut builder = RoutingGraphBuilder::default();
+/// let a = builder.add_node(Coord { x: 13.388, y: 52.516 }).expect("node");
+/// let b = builder.add_node(Coord { x: 13.389, y: 52.516 }).expect("node");
+/// builder.add_edge(a, b);
+/// let graph = builder.build();
+///
+/// let metres = graph.shortest_distance(b, a).expect("connected");
+/// assert!((60.0..75.0).contains(&metres));
+/// ```
+#[derive(Clone)]
+pub struct RoutingGraph {
+    adjacency: Adjacency,
+    index: RTree<NodeEntry>,
+}
+
+impl fmt::Debug for RoutingGraph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RoutingGraph")
+            .field("nodes", &self.node_count())
+            .field("edges", &self.edge_count())
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for RoutingGraph {
+    fn eq(&self, other: &Self) -> bool {
+        self.adjacency == other.adjacency
+    }
+}
+
+impl RoutingGraph {
+    fn from_adjacency(adjacency: Adjacency) -> Self {
+        let entries = adjacency
+            .nodes
+            .iter()
+            .zip(0_u32..)
+            .map(|(coord, node)| NodeEntry::new([coord.x, coord.y], node))
+            .collect();
+        Self {
+            adjacency,
+            index: RTree::bulk_load(entries),
+        }
+    }
+
+    /// Number of nodes in the graph.
+    #[must_use]
+    pub fn node_count(&self) -> usize {
+        self.adjacency.nodes.len()
+    }
+
+    /// Number of directed edges in the graph; each way segment counts twice.
+    #[must_use]
+    pub fn edge_count(&self) -> usize {
+        self.adjacency.targets.len()
+    }
+
+    /// Location of `node`, if it exists.
+    #[must_use]
+    pub fn coord(&self, node: u32) -> Option<Coord<f64>> {
+        self.adjacency.nodes.get(node as usize).copied()
+    }
+
+    /// Return the node closest to `location` and its distance in metres.
+    ///
+    /// Returns `None` only for an empty graph.
+    #[must_use]
+    pub fn nearest_node(&self, location: Coord<f64>) -> Option<(u32, f64)> {
+        let entry = self.index.nearest_neighbor(&[location.x, location.y])?;
+        let [x, y] = *entry.geom();
+        let metres = Haversine.distance(Point::from(location), Point::new(x, y));
+        Some((entry.data, metres))
+    }
+
+    /// Outgoing edges of `node` as `(target, metres)` pairs.
+    fn edges(&self, node: u32) -> impl Iterator<Item = (u32, f64)> + '_ {
+        let node = node as usize;
+        let range = match (
+            self.adjacency.offsets.get(node),
+            self.adjacency.offsets.get(node + 1),
+        ) {
+            (Some(&start), Some(&end)) => start as usize..end as usize,
+            _ => 0..0,
+        };
+        self.adjacency.targets[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.adjacency.lengths[range].iter().map(|&m| f64::from(m)))
+    }
+}
+
+/// Incrementally assemble a [`RoutingGraph`].
+///
+/// Nodes are numbered in insertion order. Edges may be added in any order and
+/// are sorted into place by [`Self::build`].
+#[derive(Debug, Clone, Default)]
+pub struct RoutingGraphBuilder {
+    nodes: Vec<Coord<f64>>,
+    edges: BTreeSet<(u32, u32)>,
+}
+
+impl RoutingGraphBuilder {
+    /// Add a node at `location` and return its identifier.
+    ///
+    /// Returns `None` once the graph holds `u32::MAX` nodes.
+    pub fn add_node(&mut self, location: Coord<f64>) -> Option<u32> {
+        let node = u32::try_from(self.nodes.len())
+            .ok()
+            .filter(|node| *node < u32::MAX)?;
+        self.nodes.push(location);
+        Some(node)
+    }
+
+    /// Connect `from` and `to` in both directions.
+    ///
+    /// The edge length is the great-circle distance between the nodes.
+    /// Self-loops, duplicates, and edges naming unknown nodes are ignored.
+    pub fn add_edge(&mut self, from: u32, to: u32) {
+        let known = |node: u32| (node as usize) < self.nodes.len();
+        if from == to || !known(from) || !known(to) {
+            return;
+        }
+        self.edges.insert((from, to));
+        self.edges.insert((to, from));
+    }
+
+    /// Number of nodes added so far.
+    #[must_use]
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Finish the graph.
+    #[must_use]
+    pub fn build(self) -> RoutingGraph {
+        let mut offsets = Vec::with_capacity(self.nodes.len() + 1);
+        let mut targets = Vec::with_capacity(self.edges.len());
+        let mut lengths = Vec::with_capacity(self.edges.len());
+        let mut edges = self.edges.into_iter().peekable();
+        for (node, coord) in (0_u32..).zip(&self.nodes) {
+            offsets.push(targets.len() as u32);
+            while let Some((_, to)) = edges.next_if(|(from, _)| *from == node) {
+                let metres =
+                    Haversine.distance(Point::from(*coord), Point::from(self.nodes[to as usize]));
+                targets.push(to);
+                lengths.push(metres as f32);
+            }
+        }
+        offsets.push(targets.len() as u32);
+        RoutingGraph::from_adjacency(Adjacency {
+            nodes: self.nodes,
+            offsets,
+            targets,
+            lengths,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for routing graph construction and lookup.
+
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    fn builder_stores_edges_in_both_directions() {
+        let mut builder = RoutingGraphBuilder::default();
+        let a = builder.add_node(Coord { x: 0.0, y: 0.0 }).expect("node");
+        let b = builder.add_node(Coord { x: 0.0, y: 0.001 }).expect("node");
+        builder.add_edge(a, b);
+        builder.add_edge(b, a);
+        builder.add_edge(a, a);
+        builder.add_edge(a, 7);
+        let graph = builder.build();
+
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 2);
+        let forward: Vec<_> = graph.edges(a).collect();
+        let backward: Vec<_> = graph.edges(b).collect();
+        assert_eq!(forward.len(), 1);
+        assert_eq!(forward[0].0, b);
+        assert_eq!(backward[0].0, a);
+        assert!(
+            (110.0..112.0).contains(&forward[0].1),
+            "got {}",
+            forward[0].1
+        );
+    }
+
+    #[rstest]
+    fn nearest_node_reports_snap_distance() {
+        let mut builder = RoutingGraphBuilder::default();
+        builder.add_node(Coord { x: 0.0, y: 0.0 });
+        let far = builder.add_node(Coord { x: 0.0, y: 0.01 }).expect("node");
+        let graph = builder.build();
+
+        let (node, metres) = graph
+            .nearest_node(Coord { x: 0.0, y: 0.009 })
+            .expect("non-empty graph");
+        assert_eq!(node, far);
+        assert!((110.0..112.0).contains(&metres), "got {metres}");
+    }
+
+    #[rstest]
+    fn empty_graph_has_no_nearest_node() {
+        let graph = RoutingGraphBuilder::default().build();
+        assert_eq!(graph.nearest_node(Coord { x: 0.0, y: 0.0 }), None);
+    }
+}
@@ -0,0 +1,223 @@
+//! Travel times computed from a local routing graph.
+
+use std::{collections::HashMap, path::Path, sync::Arc, time::Duration};
+
+use crate::PointOfInterest;
+
+use crate::travel_time::{
+    DEFAULT_WALKING_SPEED_KMH, TravelTimeError, TravelTimeMatrix, TravelTimeProvider,
+};
+
+use super::{RoutingGraph, RoutingGraphError};
+
+/// Furthest a POI may lie from the graph, in metres, before
+/// [`GraphTravelTimeProvider`] treats it as unreachable.
+pub const DEFAULT_MAX_SNAP_DISTANCE_M: f64 = 250.0;
+
+/// Route between POIs over a [`RoutingGraph`] without a routing service.
+///
+/// Each POI is snapped to its nearest graph node, and the walk to and from
+/// that node is added to the path length. Pairs that cannot be connected,
+/// including POIs further than the snap distance from any node, are reported
+/// as [`Duration::MAX`], matching how the OSRM provider reports unroutable
+/// pairs.
+///
+/// The graph is shared behind an [`Arc`], so clones are cheap.
+///
+/// # Examples
+/// ```rust
+/// use geo::Coord;
+/// use wildside_core::{PointOfInterest, TravelTimeProvider};
+/// use wildside_core::travel_time::{GraphTravelTimeProvider, RoutingGraphBuilder};
+///
+/// let mut builder = RoutingGraphBuilder::default();
+/// let a = builder.add_node(Coord { x: 0.0, y: 0.0 }).expect("node");
+/// let b = builder.add_node(Coord { x: 0.0, y: 0.01 }).expect("node");
+/// builder.add_edge(a, b);
+///
+/// let provider = GraphTravelTimeProvider::new(builder.build());
+/// let pois = [
+///     PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 }),
+///     PointOfInterest::with_empty_tags(2, Coord { x: 0.0, y: 0.01 }),
+/// ];
+/// let matrix = provider.get_travel_time_matrix(&pois)?;
+/// assert!(matrix[0][1].as_secs() > 0);
+/// # Ok::<(), wildside_core::TravelTimeError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct GraphTravelTimeProvider {
+    graph: Arc<RoutingGraph>,
+    speed_kmh: f64,
+    max_snap_distance_m: f64,
+}
+
+impl GraphTravelTimeProvider {
+    /// Route over `graph` at the default walking speed.
+    #[must_use]
+    pub fn new(graph: impl Into<Arc<RoutingGraph>>) -> Self {
+        Self {
+            graph: graph.into(),
+            speed_kmh: DEFAULT_WALKING_SPEED_KMH,
+            max_snap_distance_m: DEFAULT_MAX_SNAP_DISTANCE_M,
+        }
+    }
+
+    /// Load the graph persisted at `path` and route over it.
+    ///
+    /// # Errors
+    /// Returns [`RoutingGraphError`] when the file cannot be read or is not a
+    /// valid routing graph.
+    pub fn load(path: &Path) -> Result<Self, RoutingGraphError> {
+        RoutingGraph::load(path).map(Self::new)
+    }
+
+    /// Assume walking at `speed_kmh`, while consuming `self`. Non-positive or
+    /// non-finite speeds fall back to [`DEFAULT_WALKING_SPEED_KMH`].
+    #[must_use]
+    pub fn with_speed_kmh(mut self, speed_kmh: f64) -> Self {
+        self.speed_kmh = if speed_kmh.is_finite() && speed_kmh > 0.0 {
+            speed_kmh
+        } else {
+            DEFAULT_WALKING_SPEED_KMH
+        };
+        self
+    }
+
+    /// Treat POIs further than `metres` from the graph as unreachable, while
+    /// consuming `self`.
+    #[must_use]
+    pub const fn with_max_snap_distance(mut self, metres: f64) -> Self {
+        self.max_snap_distance_m = metres;
+        self
+    }
+
+    /// Graph the provider routes over.
+    #[must_use]
+    pub fn graph(&self) -> &RoutingGraph {
+        &self.graph
+    }
+
+    /// Walking speed in kilometres per hour.
+    #[must_use]
+    pub const fn speed_kmh(&self) -> f64 {
+        self.speed_kmh
+    }
+
+    /// Snap `pois` to graph nodes, yielding the node and the access walk in
+    /// metres for each POI close enough to the graph.
+    fn snap(&self, pois: &[PointOfInterest]) -> Vec<Option<(u32, f64)>> {
+        pois.iter()
+            .map(|poi| {
+                self.graph
+                    .nearest_node(poi.location)
+                    .filter(|(_, metres)| *metres <= self.max_snap_distance_m)
+            })
+            .collect()
+    }
+}
+
+impl TravelTimeProvider for GraphTravelTimeProvider {
+    fn get_travel_time_matrix(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> Result<TravelTimeMatrix, TravelTimeError> {
+        if pois.is_empty() {
+            return Err(TravelTimeError::EmptyInput);
+        }
+        let snapped = self.snap(pois);
+        let targets: Vec<u32> = snapped.iter().flatten().map(|(node, _)| *node).collect();
+        let mut rows: HashMap<u32, HashMap<u32, f64>> = HashMap::new();
+        for (node, _) in snapped.iter().flatten() {
+            rows.entry(*node)
+                .or_insert_with(|| self.graph.distances_from(*node, &targets));
+        }
+
+        let metres_per_second = self.speed_kmh * 1_000.0 / 3_600.0;
+        let travel_time = |from: Option<(u32, f64)>, to: Option<(u32, f64)>| {
+            let ((source, access), (target, egress)) = from.zip(to)?;
+            let path = rows.get(&source)?.get(&target)?;
+            Some(Duration::from_secs_f64(
+                (access + path + egress) / metres_per_second,
+            ))
+        };
+        Ok(TravelTimeMatrix::from_fn(snapped.len(), |i, j| {
+            if i == j {
+                Duration::ZERO
+            } else {
+                travel_time(snapped[i], snapped[j]).unwrap_or(Duration::MAX)
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for graph-based travel times.
+
+    use super::*;
+    use crate::travel_time::RoutingGraphBuilder;
+    use geo::Coord;
+    use rstest::{fixture, rstest};
+
+    /// Two nodes 1.11 km apart on a meridian, plus an isolated node.
+    #[fixture]
+    fn graph() -> RoutingGraph {
+        let mut builder = RoutingGraphBuilder::default();
+        let a = builder.add_node(Coord { x: 0.0, y: 0.0 }).expect("node");
+        let b = builder.add_node(Coord { x: 0.0, y: 0.01 }).expect("node");
+        builder.add_node(Coord { x: 0.1, y: 0.0 });
+        builder.add_edge(a, b);
+        builder.build()
+    }
+
+    fn poi(id: u64, x: f64, y: f64) -> PointOfInterest {
+        PointOfInterest::with_empty_tags(id, Coord { x, y })
+    }
+
+    #[rstest]
+    fn routes_between_snapped_pois(graph: RoutingGraph) {
+        let provider = GraphTravelTimeProvider::new(graph).with_speed_kmh(4.0);
+        // The first POI sits about 11 m off the graph.
+        let pois = [poi(1, 0.0, -0.0001), poi(2, 0.0, 0.01)];
+        let matrix = provider.get_travel_time_matrix(&pois).expect("matrix");
+        assert_eq!(matrix[0][0], Duration::ZERO);
+        assert_eq!(matrix[0][1], matrix[1][0]);
+        let seconds = matrix[0][1].as_secs_f64();
+        // 1.12 km at 4 km/h is about 1,010 seconds.
+        assert!((1_000.0..=1_020.0).contains(&seconds), "got {seconds}");
+    }
+
+    #[rstest]
+    fn disconnected_pois_are_unreachable(graph: RoutingGraph) {
+        let provider = GraphTravelTimeProvider::new(graph);
+        let pois = [poi(1, 0.0, 0.0), poi(2, 0.1, 0.0)];
+        let matrix = provider.get_travel_time_matrix(&pois).expect("matrix");
+        assert_eq!(matrix[0][1], Duration::MAX);
+        assert_eq!(matrix[1][1], Duration::ZERO);
+    }
+
+    #[rstest]
+    fn pois_far_from_the_graph_are_unreachable(graph: RoutingGraph) {
+        let provider = GraphTravelTimeProvider::new(graph).with_max_snap_distance(5.0);
+        let pois = [poi(1, 0.0, -0.0001), poi(2, 0.0, 0.01)];
+        let matrix = provider.get_travel_time_matrix(&pois).expect("matrix");
+        assert_eq!(matrix[0][1], Duration::MAX);
+        assert_eq!(matrix[1][0], Duration::MAX);
+    }
+
+    #[rstest]
+    fn pois_sharing_a_node_are_close(graph: RoutingGraph) {
+        let provider = GraphTravelTimeProvider::new(graph);
+        let pois = [poi(1, 0.0, 0.0), poi(2, 0.0, 0.0)];
+        let matrix = provider.get_travel_time_matrix(&pois).expect("matrix");
+        assert_eq!(matrix[0][1], Duration::ZERO);
+    }
+
+    #[rstest]
+    fn errors_on_empty_input(graph: RoutingGraph) {
+        let err = GraphTravelTimeProvider::new(graph)
+            .get_travel_time_matrix(&[])
+            .expect_err("empty input");
+        assert_eq!(err, TravelTimeError::EmptyInput);
+    }
+}
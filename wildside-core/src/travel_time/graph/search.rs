@@ -0,0 +1,224 @@
+//! Shortest-path queries over a [`RoutingGraph`].
+//!
+//! Single-pair queries use A\* guided by great-circle distance, which never
+//! overestimates because every edge is at least as long as the straight line
+//! between its ends. Matrix rows use a one-to-many Dijkstra search that stops
+//! once every requested target is settled.
+
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet, hash_map::Entry},
+};
+
+use geo::{Distance, Haversine, Point};
+
+use super::RoutingGraph;
+
+/// Frontier entry ordered so the [`BinaryHeap`] pops the lowest priority.
+#[derive(Debug, Clone, Copy)]
+struct Frontier {
+    priority: f64,
+    cost: f64,
+    node: u32,
+}
+
+impl PartialEq for Frontier {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Frontier {}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .priority
+            .total_cmp(&self.priority)
+            .then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+impl RoutingGraph {
+    /// Length in metres of the shortest path from `from` to `to`.
+    ///
+    /// Returns `None` when either node is unknown or `to` cannot be reached.
+    #[must_use]
+    pub fn shortest_distance(&self, from: u32, to: u32) -> Option<f64> {
+        let goal = Point::from(self.coord(to)?);
+        self.coord(from)?;
+        let heuristic = |node: u32| {
+            self.coord(node)
+                .map_or(0.0, |coord| Haversine.distance(Point::from(coord), goal))
+        };
+        let mut best: HashMap<u32, f64> = HashMap::from([(from, 0.0)]);
+        let mut frontier = BinaryHeap::from([Frontier {
+            priority: heuristic(from),
+            cost: 0.0,
+            node: from,
+        }]);
+        while let Some(Frontier { cost, node, .. }) = frontier.pop() {
+            if node == to {
+                return Some(cost);
+            }
+            if best.get(&node).is_some_and(|known| cost > *known) {
+                continue;
+            }
+            frontier.extend(
+                self.edges(node)
+                    .map(|(next, metres)| (next, cost + metres))
+                    .filter(|&(next, candidate)| relax(&mut best, next, candidate))
+                    .map(|(next, candidate)| Frontier {
+                        priority: candidate + heuristic(next),
+                        cost: candidate,
+                        node: next,
+                    }),
+            );
+        }
+        None
+    }
+
+    /// Lengths in metres of the shortest paths from `source` to each of
+    /// `targets` that can be reached.
+    ///
+    /// Unreachable and unknown targets are absent from the result.
+    #[must_use]
+    pub fn distances_from(&self, source: u32, targets: &[u32]) -> HashMap<u32, f64> {
+        self.distances_within(source, targets, f64::INFINITY)
+    }
+
+    /// Lengths in metres of the shortest paths from `source` to each of
+    /// `targets` no longer than `max_metres`.
+    ///
+    /// The search stops once every remaining target is further away, so a
+    /// tight limit keeps one-to-many searches on large graphs local.
+    #[must_use]
+    pub fn distances_within(
+        &self,
+        source: u32,
+        targets: &[u32],
+        max_metres: f64,
+    ) -> HashMap<u32, f64> {
+        let mut pending: HashSet<u32> = targets.iter().copied().collect();
+        let mut found = HashMap::with_capacity(pending.len());
+        if self.coord(source).is_none() {
+            return found;
+        }
+        let mut best: HashMap<u32, f64> = HashMap::from([(source, 0.0)]);
+        let mut frontier = BinaryHeap::from([Frontier {
+            priority: 0.0,
+            cost: 0.0,
+            node: source,
+        }]);
+        while let Some(Frontier { cost, node, .. }) = frontier.pop() {
+            if best.get(&node).is_some_and(|known| cost > *known) {
+                continue;
+            }
+            if cost > max_metres {
+                break;
+            }
+            if pending.remove(&node) {
+                found.insert(node, cost);
+            }
+            if pending.is_empty() {
+                break;
+            }
+            frontier.extend(
+                self.edges(node)
+                    .map(|(next, metres)| (next, cost + metres))
+                    .filter(|&(next, candidate)| relax(&mut best, next, candidate))
+                    .map(|(next, candidate)| Frontier {
+                        priority: candidate,
+                        cost: candidate,
+                        node: next,
+                    }),
+            );
+        }
+        found
+    }
+}
+
+/// Record `cost` for `node` if it improves on the best known, reporting
+/// whether it did.
+fn relax(best: &mut HashMap<u32, f64>, node: u32, cost: f64) -> bool {
+    match best.entry(node) {
+        Entry::Occupied(mut known) if cost < *known.get() => {
+            known.insert(cost);
+            true
+        }
+        Entry::Occupied(_) => false,
+        Entry::Vacant(slot) => {
+            slot.insert(cost);
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for graph shortest-path searches.
+
+    use super::*;
+    use crate::travel_time::RoutingGraphBuilder;
+    use geo::Coord;
+    use rstest::{fixture, rstest};
+
+    /// A square `a-b-c-d` with a long detour `a-e-d` and an isolated node `f`.
+    #[fixture]
+    fn graph() -> RoutingGraph {
+        let mut builder = RoutingGraphBuilder::default();
+        let coords = [
+            (0.0, 0.0),
+            (0.001, 0.0),
+            (0.001, 0.001),
+            (0.0, 0.001),
+            (-0.01, 0.0005),
+            (0.5, 0.5),
+        ];
+        for (x, y) in coords {
+            builder.add_node(Coord { x, y });
+        }
+        for (from, to) in [(0, 1), (1, 2), (2, 3), (0, 4), (4, 3)] {
+            builder.add_edge(from, to);
+        }
+        builder.build()
+    }
+
+    #[rstest]
+    fn a_star_finds_shortest_path(graph: RoutingGraph) {
+        let metres = graph.shortest_distance(0, 3).expect("reachable");
+        // Three sides of a square about 111 m across.
+        assert!((330.0..336.0).contains(&metres), "got {metres}");
+        assert_eq!(graph.shortest_distance(3, 3), Some(0.0));
+    }
+
+    #[rstest]
+    fn a_star_reports_unreachable_and_unknown_nodes(graph: RoutingGraph) {
+        assert_eq!(graph.shortest_distance(0, 5), None);
+        assert_eq!(graph.shortest_distance(0, 99), None);
+    }
+
+    #[rstest]
+    fn one_to_many_matches_pairwise_searches(graph: RoutingGraph) {
+        let distances = graph.distances_from(0, &[1, 2, 3, 5, 99]);
+        assert_eq!(distances.len(), 3);
+        for target in [1, 2, 3] {
+            let pairwise = graph.shortest_distance(0, target).expect("reachable");
+            assert!((distances[&target] - pairwise).abs() < 1e-6);
+        }
+    }
+
+    #[rstest]
+    fn bounded_search_omits_targets_beyond_the_limit(graph: RoutingGraph) {
+        let distances = graph.distances_within(0, &[1, 2, 3], 250.0);
+        let mut found: Vec<_> = distances.keys().copied().collect();
+        found.sort_unstable();
+        assert_eq!(found, [1, 2]);
+    }
+}
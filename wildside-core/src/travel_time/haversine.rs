@@ -0,0 +1,128 @@
+//! Straight-line travel times that need no routing service.
+
+use std::time::Duration;
+
+use geo::{Distance, Haversine, Point};
+
+use crate::PointOfInterest;
+
+use super::{TravelTimeError, TravelTimeMatrix, TravelTimeProvider};
+
+/// Walking speed assumed by [`HaversineTravelTimeProvider::default`], in
+/// kilometres per hour.
+pub const DEFAULT_WALKING_SPEED_KMH: f64 = 5.0;
+
+/// Estimate travel times from great-circle distance at a constant speed.
+///
+/// Real paths are longer than the straight line, so the estimates are
+/// optimistic. The provider never fails on non-empty input, which makes it a
+/// last resort behind a routing service in a
+/// [`FallbackTravelTimeProvider`](super::FallbackTravelTimeProvider).
+///
+/// # Examples
+/// ```rust
+/// use geo::Coord;
+/// use wildside_core::{PointOfInterest, TravelTimeProvider};
+/// use wildside_core::travel_time::HaversineTravelTimeProvider;
+///
+/// let pois = [
+///     PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 }),
+///     PointOfInterest::with_empty_tags(2, Coord { x: 0.0, y: 0.01 }),
+/// ];
+/// let matrix = HaversineTravelTimeProvider::default().get_travel_time_matrix(&pois)?;
+/// assert!(matrix[0][1].as_secs() > 0);
+/// # Ok::<(), wildside_core::TravelTimeError>(())
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HaversineTravelTimeProvider {
+    speed_kmh: f64,
+}
+
+impl HaversineTravelTimeProvider {
+    /// Assume travel at `speed_kmh`. Non-positive or non-finite speeds fall
+    /// back to [`DEFAULT_WALKING_SPEED_KMH`].
+    #[must_use]
+    pub fn new(speed_kmh: f64) -> Self {
+        let speed_kmh = if speed_kmh.is_finite() && speed_kmh > 0.0 {
+            speed_kmh
+        } else {
+            DEFAULT_WALKING_SPEED_KMH
+        };
+        Self { speed_kmh }
+    }
+
+    /// Speed assumed between every pair of POIs, in kilometres per hour.
+    #[must_use]
+    pub const fn speed_kmh(&self) -> f64 {
+        self.speed_kmh
+    }
+}
+
+impl Default for HaversineTravelTimeProvider {
+    fn default() -> Self {
+        Self::new(DEFAULT_WALKING_SPEED_KMH)
+    }
+}
+
+impl TravelTimeProvider for HaversineTravelTimeProvider {
+    fn get_travel_time_matrix(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> Result<TravelTimeMatrix, TravelTimeError> {
+        if pois.is_empty() {
+            return Err(TravelTimeError::EmptyInput);
+        }
+        let metres_per_second = self.speed_kmh * 1_000.0 / 3_600.0;
+        Ok(TravelTimeMatrix::from_fn(pois.len(), |from, to| {
+            let metres = Haversine.distance(
+                Point::from(pois[from].location),
+                Point::from(pois[to].location),
+            );
+            Duration::from_secs_f64(metres / metres_per_second)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for straight-line travel-time estimates.
+
+    use super::*;
+    use geo::Coord;
+    use rstest::rstest;
+
+    #[rstest]
+    fn estimates_time_from_distance_and_speed() {
+        // One hundredth of a degree of latitude is about 1.11 km.
+        let pois = [
+            PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 }),
+            PointOfInterest::with_empty_tags(2, Coord { x: 0.0, y: 0.01 }),
+        ];
+        let matrix = HaversineTravelTimeProvider::new(4.0)
+            .get_travel_time_matrix(&pois)
+            .expect("matrix");
+        assert_eq!(matrix[0][0], Duration::ZERO);
+        assert_eq!(matrix[0][1], matrix[1][0]);
+        let seconds = matrix[0][1].as_secs_f64();
+        assert!((995.0..=1_005.0).contains(&seconds), "got {seconds}");
+    }
+
+    #[rstest]
+    #[case(0.0)]
+    #[case(-3.0)]
+    #[case(f64::NAN)]
+    fn rejects_unusable_speeds(#[case] speed_kmh: f64) {
+        assert_eq!(
+            HaversineTravelTimeProvider::new(speed_kmh).speed_kmh(),
+            DEFAULT_WALKING_SPEED_KMH
+        );
+    }
+
+    #[rstest]
+    fn errors_on_empty_input() {
+        let err = HaversineTravelTimeProvider::default()
+            .get_travel_time_matrix(&[])
+            .expect_err("empty input");
+        assert_eq!(err, TravelTimeError::EmptyInput);
+    }
+}
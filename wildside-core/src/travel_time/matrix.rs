@@ -0,0 +1,258 @@
+//! Square matrices of travel times between POIs.
+
+use std::{ops::Index, time::Duration};
+
+use thiserror::Error;
+
+/// Error raised when rows passed to [`TravelTimeMatrix::try_from`] do not
+/// form a square matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error(
+    "travel-time matrix row {row} has {found} entries but there are {expected} rows; the matrix must be square"
+)]
+pub struct MatrixShapeError {
+    /// Index of the first row with the wrong length.
+    pub row: usize,
+    /// Number of entries in that row.
+    pub found: usize,
+    /// Number of rows, which every row must match.
+    pub expected: usize,
+}
+
+/// Travel times between every ordered pair of `n` POIs.
+///
+/// The matrix is always square: entry `(from, to)` is the travel time from
+/// the POI at index `from` to the POI at index `to`, in the order the POIs
+/// were passed to the provider. Unreachable pairs are [`Duration::MAX`] by
+/// convention.
+///
+/// Build a matrix with [`Self::from_fn`], or from nested rows with
+/// [`TryFrom`], which rejects ragged input with [`MatrixShapeError`]. Read it
+/// with the checked [`Self::get`] and [`Self::row`], or index it like nested
+/// slices, `matrix[from][to]`, which panics when out of range. With the
+/// `serde` feature it serializes as a list of rows.
+///
+/// # Examples
+/// ```rust
+/// use std::time::Duration;
+/// use wildside_core::TravelTimeMatrix;
+///
+/// let matrix = TravelTimeMatrix::try_from(vec![
+///     vec![Duration::ZERO, Duration::from_secs(60)],
+///     vec![Duration::from_secs(90), Duration::ZERO],
+/// ])?;
+/// assert_eq!(matrix.len(), 2);
+/// assert_eq!(matrix.get(1, 0), Some(Duration::from_secs(90)));
+/// assert_eq!(matrix.get(2, 0), None);
+/// assert!(TravelTimeMatrix::try_from(vec![vec![Duration::ZERO; 2]]).is_err());
+/// # Ok::<(), wildside_core::travel_time::MatrixShapeError>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "Vec<Vec<Duration>>", into = "Vec<Vec<Duration>>")
+)]
+pub struct TravelTimeMatrix {
+    size: usize,
+    durations: Vec<Duration>,
+}
+
+impl TravelTimeMatrix {
+    /// Build a `size × size` matrix whose entry `(from, to)` is
+    /// `duration(from, to)`.
+    pub fn from_fn(size: usize, mut duration: impl FnMut(usize, usize) -> Duration) -> Self {
+        let durations = (0..size)
+            .flat_map(|from| (0..size).map(move |to| (from, to)))
+            .map(|(from, to)| duration(from, to))
+            .collect();
+        Self { size, durations }
+    }
+
+    /// Number of POIs, which is both the row and the column count.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.size
+    }
+
+    /// Report whether the matrix covers no POIs.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Travel time from POI `from` to POI `to`, or `None` when either index
+    /// is out of range.
+    #[must_use]
+    pub fn get(&self, from: usize, to: usize) -> Option<Duration> {
+        self.row(from)?.get(to).copied()
+    }
+
+    /// Travel times from POI `from` to every POI, or `None` when `from` is
+    /// out of range.
+    #[must_use]
+    pub fn row(&self, from: usize) -> Option<&[Duration]> {
+        if from >= self.size {
+            return None;
+        }
+        let start = from * self.size;
+        self.durations.get(start..start + self.size)
+    }
+
+    /// Iterate over the rows in POI order.
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = &[Duration]> + '_ {
+        self.durations.chunks_exact(self.size.max(1))
+    }
+
+    /// Keep only the POIs for which `keep` returns `true`, removing their
+    /// rows and columns together.
+    ///
+    /// `keep` is called once per index, in order.
+    pub fn retain(&mut self, mut keep: impl FnMut(usize) -> bool) {
+        let kept: Vec<usize> = (0..self.size).filter(|index| keep(*index)).collect();
+        if kept.len() == self.size {
+            return;
+        }
+        let size = self.size;
+        let durations = kept
+            .iter()
+            .flat_map(|from| kept.iter().map(move |to| from * size + to))
+            .map(|index| self.durations[index])
+            .collect();
+        *self = Self {
+            size: kept.len(),
+            durations,
+        };
+    }
+
+    /// Convert into nested rows.
+    #[must_use]
+    pub fn into_rows(self) -> Vec<Vec<Duration>> {
+        self.rows().map(<[Duration]>::to_vec).collect()
+    }
+}
+
+impl TryFrom<Vec<Vec<Duration>>> for TravelTimeMatrix {
+    type Error = MatrixShapeError;
+
+    fn try_from(rows: Vec<Vec<Duration>>) -> Result<Self, Self::Error> {
+        let size = rows.len();
+        if let Some((row, found)) = rows
+            .iter()
+            .map(Vec::len)
+            .enumerate()
+            .find(|(_, found)| *found != size)
+        {
+            return Err(MatrixShapeError {
+                row,
+                found,
+                expected: size,
+            });
+        }
+        Ok(Self {
+            size,
+            durations: rows.into_iter().flatten().collect(),
+        })
+    }
+}
+
+impl From<TravelTimeMatrix> for Vec<Vec<Duration>> {
+    fn from(matrix: TravelTimeMatrix) -> Self {
+        matrix.into_rows()
+    }
+}
+
+impl Index<usize> for TravelTimeMatrix {
+    type Output = [Duration];
+
+    fn index(&self, from: usize) -> &[Duration] {
+        self.row(from).unwrap_or_else(|| {
+            panic!(
+                "row {from} is out of range for a travel-time matrix of {} POIs",
+                self.size
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for travel-time matrix construction and access.
+
+    use super::*;
+    use rstest::rstest;
+
+    fn secs(rows: &[&[u64]]) -> Vec<Vec<Duration>> {
+        rows.iter()
+            .map(|row| row.iter().copied().map(Duration::from_secs).collect())
+            .collect()
+    }
+
+    #[rstest]
+    fn from_fn_lays_out_rows_in_order() {
+        let matrix =
+            TravelTimeMatrix::from_fn(3, |from, to| Duration::from_secs((from * 10 + to) as u64));
+        assert_eq!(matrix.get(2, 1), Some(Duration::from_secs(21)));
+        assert_eq!(matrix[1][2], Duration::from_secs(12));
+        assert_eq!(matrix.rows().len(), 3);
+        assert_eq!(
+            matrix.into_rows(),
+            secs(&[&[0, 1, 2], &[10, 11, 12], &[20, 21, 22]])
+        );
+    }
+
+    #[rstest]
+    #[case(secs(&[&[0, 1], &[2]]), 1, 1, 2)]
+    #[case(secs(&[&[0, 1, 2], &[3, 4, 5]]), 0, 3, 2)]
+    fn rejects_ragged_rows(
+        #[case] rows: Vec<Vec<Duration>>,
+        #[case] row: usize,
+        #[case] found: usize,
+        #[case] expected: usize,
+    ) {
+        assert_eq!(
+            TravelTimeMatrix::try_from(rows),
+            Err(MatrixShapeError {
+                row,
+                found,
+                expected
+            })
+        );
+    }
+
+    #[rstest]
+    fn checked_access_rejects_out_of_range_indices() {
+        let matrix = TravelTimeMatrix::try_from(secs(&[&[0, 1], &[2, 0]])).expect("square");
+        assert_eq!(matrix.get(0, 2), None);
+        assert_eq!(matrix.row(2), None);
+        assert_eq!(matrix.row(1), Some(&secs(&[&[2, 0]])[0][..]));
+    }
+
+    #[rstest]
+    fn empty_matrix_has_no_rows() {
+        let matrix = TravelTimeMatrix::default();
+        assert!(matrix.is_empty());
+        assert_eq!(matrix.rows().len(), 0);
+        assert_eq!(matrix.get(0, 0), None);
+    }
+
+    #[rstest]
+    fn retain_removes_rows_and_columns_together() {
+        let mut matrix =
+            TravelTimeMatrix::from_fn(3, |from, to| Duration::from_secs((from * 10 + to) as u64));
+        matrix.retain(|index| index != 1);
+        assert_eq!(matrix.into_rows(), secs(&[&[0, 2], &[20, 22]]));
+    }
+
+    #[cfg(feature = "serde")]
+    #[rstest]
+    fn serde_round_trips_and_validates_shape() {
+        let matrix = TravelTimeMatrix::try_from(secs(&[&[0, 1], &[2, 0]])).expect("square");
+        let json = serde_json::to_string(&matrix).expect("serialize");
+        let decoded: TravelTimeMatrix = serde_json::from_str(&json).expect("deserialize");
+        assert_eq!(decoded, matrix);
+
+        let ragged = r#"[[{"secs":0,"nanos":0}],[]]"#;
+        assert!(serde_json::from_str::<TravelTimeMatrix>(ragged).is_err());
+    }
+}
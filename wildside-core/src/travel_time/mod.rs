@@ -2,13 +2,83 @@
 //!
 //! The `TravelTimeProvider` trait abstracts the retrieval of pairwise travel
 //! times between [`PointOfInterest`](crate::PointOfInterest) values. Callers
-//! supply a slice of POIs and receive an adjacency matrix of
+//! supply a slice of POIs and receive a square [`TravelTimeMatrix`] of
 //! [`Duration`](std::time::Duration) values.
 //!
 //! Errors are returned when inputs are invalid, e.g. an empty slice.
+//!
+//! [`HaversineTravelTimeProvider`] estimates times from straight-line
+//! distance without a routing service, and [`FallbackTravelTimeProvider`]
+//! chains providers so a solve can fall back to such estimates when the
+//! preferred provider fails.
+//!
+//! With the `routing-graph` feature, [`GraphTravelTimeProvider`] routes over
+//! a [`RoutingGraph`] built from OSM data during ingest, so travel times can
+//! be computed entirely offline. [`PrecomputedTravelTimes`] goes further and
+//! stores the travel times between a region's POIs ahead of time, which
+//! [`PrecomputedTravelTimeProvider`] serves with table lookups alone.
+//!
+//! [`StepFreeTravelTimeProvider`] pairs a provider with a second one that
+//! avoids stairs, for step-free solves.
+//!
+//! With the `serde` feature, [`RecordingTravelTimeProvider`] captures the
+//! matrices another provider returns in a [`TravelTimeRecording`], and
+//! [`ReplayTravelTimeProvider`] serves them back so tests can use realistic
+//! travel times without a live routing service.
+//!
+//! With the `async` feature, [`AsyncTravelTimeProvider`] offers the same
+//! contract without blocking, and [`SyncTravelTimeAdapter`] lets any
+//! synchronous provider stand in for it. [`PrefetchedTravelTimeProvider`]
+//! answers synchronous requests from matrices awaited through such a
+//! provider beforehand.
 
+#[cfg(feature = "async")]
+mod async_provider;
 mod error;
+mod fallback;
+#[cfg(feature = "routing-graph")]
+mod graph;
+mod haversine;
+mod matrix;
+#[cfg(feature = "routing-graph")]
+mod precomputed;
+#[cfg(feature = "async")]
+mod prefetch;
 mod provider;
+#[cfg(feature = "serde")]
+mod recording;
+mod step_free;
 
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub use async_provider::{AsyncTravelTimeProvider, SyncTravelTimeAdapter};
 pub use error::TravelTimeError;
-pub use provider::{TravelTimeMatrix, TravelTimeProvider};
+pub use fallback::{
+    FallbackDiagnostics, FallbackOutcome, FallbackTravelTimeProvider, ProviderFailure,
+    ProviderUsage,
+};
+#[cfg(feature = "routing-graph")]
+#[cfg_attr(docsrs, doc(cfg(feature = "routing-graph")))]
+pub use graph::{
+    DEFAULT_MAX_SNAP_DISTANCE_M, GraphTravelTimeProvider, RoutingGraph, RoutingGraphBuilder,
+    RoutingGraphError,
+};
+pub use haversine::{DEFAULT_WALKING_SPEED_KMH, HaversineTravelTimeProvider};
+pub use matrix::{MatrixShapeError, TravelTimeMatrix};
+#[cfg(feature = "routing-graph")]
+#[cfg_attr(docsrs, doc(cfg(feature = "routing-graph")))]
+pub use precomputed::{
+    DEFAULT_MAX_ACCESS_DISTANCE_M, DEFAULT_MAX_TRAVEL_TIME, PrecomputeOptions,
+    PrecomputedTravelTimeProvider, PrecomputedTravelTimes, PrecomputedTravelTimesError,
+};
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub use prefetch::PrefetchedTravelTimeProvider;
+pub use provider::TravelTimeProvider;
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub use recording::{
+    RecordingTravelTimeProvider, ReplayTravelTimeProvider, TravelTimeRecording,
+    TravelTimeRecordingError,
+};
+pub use step_free::StepFreeTravelTimeProvider;
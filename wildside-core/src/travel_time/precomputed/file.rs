@@ -0,0 +1,295 @@
+//! Persistence for precomputed travel-time tables.
+//!
+//! Files start with the `WSTT` magic and a little-endian `u16` version,
+//! followed by a zstd stream holding the `bincode` encoding of the table.
+//! The lookup structures over the POIs are rebuilt on load.
+
+use std::{
+    collections::HashSet,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+use crate::artefact_io::{open_parent_dir, read_array};
+use crate::formats::{self, FormatVersionError};
+
+use super::{PrecomputedTravelTimes, Table};
+
+/// File identifier for persisted travel-time tables.
+pub(crate) const TRAVEL_TIMES_MAGIC: [u8; 4] = *b"WSTT";
+
+/// Version stamped on newly written travel-time tables.
+pub(crate) const TRAVEL_TIMES_VERSION: u16 = formats::TRAVEL_TIMES.current;
+
+/// zstd level used when writing travel-time tables.
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Error raised when reading or writing a precomputed travel-time table.
+#[derive(Debug, Error)]
+pub enum PrecomputedTravelTimesError {
+    /// The table file could not be read or written.
+    #[error("failed to access travel-time table at {path}: {source}")]
+    Io {
+        /// Location of the travel-time artefact.
+        path: PathBuf,
+        /// Underlying I/O error.
+        #[source]
+        source: io::Error,
+    },
+    /// The table could not be encoded.
+    #[error("failed to encode travel-time table for {path}: {source}")]
+    Encode {
+        /// Location of the travel-time artefact.
+        path: PathBuf,
+        /// Encoder failure from `bincode`.
+        #[source]
+        source: bincode::Error,
+    },
+    /// The table payload could not be decoded.
+    #[error("failed to decode travel-time table from {path}: {source}")]
+    Decode {
+        /// Location of the travel-time artefact.
+        path: PathBuf,
+        /// Decoder failure from `bincode`.
+        #[source]
+        source: bincode::Error,
+    },
+    /// The file did not contain the expected header.
+    #[error("invalid travel-time table magic: expected {expected:?}, found {found:?}")]
+    InvalidMagic {
+        /// Expected byte sequence identifying a travel-time table.
+        expected: [u8; 4],
+        /// Sequence read from the file.
+        found: [u8; 4],
+    },
+    /// The file's format version is outside the readable range.
+    #[error(transparent)]
+    UnsupportedVersion(#[from] FormatVersionError),
+    /// The payload described an impossible table.
+    #[error("travel-time table at {path} is corrupt: {reason}")]
+    Corrupt {
+        /// Location of the travel-time artefact.
+        path: PathBuf,
+        /// Description of the inconsistency.
+        reason: &'static str,
+    },
+    /// The table holds more pairs than its `u32` row offsets can address.
+    #[error("travel-time table has {entries} entries, more than its offsets can address")]
+    TooManyEntries {
+        /// Number of pairs routed when the limit was passed.
+        entries: usize,
+    },
+}
+
+impl PrecomputedTravelTimes {
+    /// Persist the table in the `WSTT` format. Existing files are truncated.
+    pub fn write(&self, path: &Path) -> Result<(), PrecomputedTravelTimesError> {
+        let io_error = |source| PrecomputedTravelTimesError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let (dir, file_name) = open_parent_dir(path).map_err(io_error)?;
+        let mut file = io::BufWriter::new(dir.create(file_name).map_err(io_error)?);
+        file.write_all(&TRAVEL_TIMES_MAGIC).map_err(io_error)?;
+        file.write_all(&TRAVEL_TIMES_VERSION.to_le_bytes())
+            .map_err(io_error)?;
+        let mut encoder = zstd::Encoder::new(file, COMPRESSION_LEVEL).map_err(io_error)?;
+        bincode::serialize_into(&mut encoder, &self.table).map_err(|source| {
+            PrecomputedTravelTimesError::Encode {
+                path: path.to_path_buf(),
+                source,
+            }
+        })?;
+        let file = encoder
+            .finish()
+            .map_err(io_error)?
+            .into_inner()
+            .map_err(|error| io_error(error.into_error()))?;
+        file.sync_all().map_err(io_error)
+    }
+
+    /// Load a table previously written by [`PrecomputedTravelTimes::write`].
+    pub fn load(path: &Path) -> Result<Self, PrecomputedTravelTimesError> {
+        let io_error = |source| PrecomputedTravelTimesError::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        let (dir, file_name) = open_parent_dir(path).map_err(io_error)?;
+        let mut file = io::BufReader::new(dir.open(file_name).map_err(io_error)?);
+
+        let magic: [u8; 4] = read_array(&mut file).map_err(io_error)?;
+        if magic != TRAVEL_TIMES_MAGIC {
+            return Err(PrecomputedTravelTimesError::InvalidMagic {
+                expected: TRAVEL_TIMES_MAGIC,
+                found: magic,
+            });
+        }
+        let version = u16::from_le_bytes(read_array(&mut file).map_err(io_error)?);
+        formats::TRAVEL_TIMES.negotiate(i64::from(version))?;
+
+        let decoder = zstd::Decoder::with_buffer(file).map_err(io_error)?;
+        let table: Table = bincode::deserialize_from(decoder).map_err(|source| {
+            PrecomputedTravelTimesError::Decode {
+                path: path.to_path_buf(),
+                source,
+            }
+        })?;
+        validate(&table).map_err(|reason| PrecomputedTravelTimesError::Corrupt {
+            path: path.to_path_buf(),
+            reason,
+        })?;
+        Ok(Self::from_table(table))
+    }
+}
+
+/// Check the invariants [`PrecomputedTravelTimes`] lookups rely on.
+fn validate(table: &Table) -> Result<(), &'static str> {
+    let Table {
+        ids,
+        locations,
+        speed_kmh,
+        offsets,
+        targets,
+        seconds,
+    } = table;
+    if ids.len() != locations.len() {
+        return Err("POI ids and locations differ in count");
+    }
+    if ids.iter().collect::<HashSet<_>>().len() != ids.len() {
+        return Err("POI ids are not unique");
+    }
+    if !speed_kmh.is_finite() || *speed_kmh <= 0.0 {
+        return Err("walking speed is not positive");
+    }
+    if offsets.len() != ids.len() + 1 {
+        return Err("offset count does not match POI count");
+    }
+    if targets.len() != seconds.len() {
+        return Err("row targets and times differ in count");
+    }
+    if offsets.first().is_some_and(|first| *first != 0)
+        || offsets.windows(2).any(|pair| pair[0] > pair[1])
+        || offsets.last().map(|last| *last as usize) != Some(targets.len())
+    {
+        return Err("row offsets are not monotonic");
+    }
+    if targets.iter().any(|target| *target as usize >= ids.len()) {
+        return Err("row target out of range");
+    }
+    let rows_sorted = offsets.windows(2).all(|pair| {
+        targets[pair[0] as usize..pair[1] as usize]
+            .windows(2)
+            .all(|entries| entries[0] < entries[1])
+    });
+    if !rows_sorted {
+        return Err("row targets are not strictly ascending");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for travel-time table persistence.
+
+    use super::*;
+    use crate::PointOfInterest;
+    use crate::travel_time::{PrecomputeOptions, RoutingGraphBuilder};
+    use geo::Coord;
+    use rstest::{fixture, rstest};
+    use tempfile::TempDir;
+
+    #[fixture]
+    fn temp_table_path() -> (TempDir, PathBuf) {
+        let dir = TempDir::new().expect("create temp dir");
+        let path = dir.path().join("travel_times.bin");
+        (dir, path)
+    }
+
+    fn write_payload(path: &Path, version: u16, table: &Table) {
+        let mut bytes = TRAVEL_TIMES_MAGIC.to_vec();
+        bytes.extend_from_slice(&version.to_le_bytes());
+        let payload = bincode::serialize(table).expect("encode table");
+        bytes.extend(zstd::encode_all(payload.as_slice(), 0).expect("compress"));
+        std::fs::write(path, bytes).expect("write table");
+    }
+
+    fn two_poi_table() -> Table {
+        Table {
+            ids: vec![1, 2],
+            locations: vec![Coord { x: 0.0, y: 0.0 }, Coord { x: 0.0, y: 0.001 }],
+            speed_kmh: 5.0,
+            offsets: vec![0, 1, 2],
+            targets: vec![1, 0],
+            seconds: vec![80, 80],
+        }
+    }
+
+    #[rstest]
+    fn round_trips_through_disk(#[from(temp_table_path)] (_dir, path): (TempDir, PathBuf)) {
+        let mut builder = RoutingGraphBuilder::default();
+        let a = builder.add_node(Coord { x: 0.0, y: 0.0 }).expect("node");
+        let b = builder.add_node(Coord { x: 0.0, y: 0.001 }).expect("node");
+        builder.add_edge(a, b);
+        let pois = [
+            PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 }),
+            PointOfInterest::with_empty_tags(2, Coord { x: 0.0, y: 0.001 }),
+        ];
+        let table = PrecomputedTravelTimes::from_graph(
+            &builder.build(),
+            &pois,
+            &PrecomputeOptions::default(),
+        )
+        .expect("table");
+        table.write(&path).expect("write table");
+
+        let loaded = PrecomputedTravelTimes::load(&path).expect("load table");
+        assert_eq!(loaded, table);
+        assert_eq!(loaded.travel_time(2, 1), table.travel_time(2, 1));
+    }
+
+    #[rstest]
+    fn load_rejects_invalid_magic(#[from(temp_table_path)] (_dir, path): (TempDir, PathBuf)) {
+        std::fs::write(&path, b"BAD!\x01\x00").expect("write corrupt header");
+        let error = PrecomputedTravelTimes::load(&path).expect_err("invalid magic should fail");
+        assert!(matches!(
+            error,
+            PrecomputedTravelTimesError::InvalidMagic { .. }
+        ));
+    }
+
+    #[rstest]
+    fn load_rejects_unsupported_version(#[from(temp_table_path)] (_dir, path): (TempDir, PathBuf)) {
+        write_payload(&path, TRAVEL_TIMES_VERSION + 1, &two_poi_table());
+        let error =
+            PrecomputedTravelTimes::load(&path).expect_err("unsupported version should fail");
+        assert!(matches!(
+            error,
+            PrecomputedTravelTimesError::UnsupportedVersion(_)
+        ));
+    }
+
+    #[rstest]
+    #[case::target_out_of_range(
+        Table { targets: vec![1, 5], ..two_poi_table() },
+        "row target out of range"
+    )]
+    #[case::duplicate_ids(Table { ids: vec![1, 1], ..two_poi_table() }, "POI ids are not unique")]
+    #[case::unsorted_row(
+        Table { offsets: vec![0, 2, 2], targets: vec![1, 0], ..two_poi_table() },
+        "row targets are not strictly ascending"
+    )]
+    fn load_rejects_corrupt_tables(
+        #[from(temp_table_path)] (_dir, path): (TempDir, PathBuf),
+        #[case] table: Table,
+        #[case] expected: &str,
+    ) {
+        write_payload(&path, TRAVEL_TIMES_VERSION, &table);
+        let error = PrecomputedTravelTimes::load(&path).expect_err("corrupt table should fail");
+        assert!(matches!(
+            error,
+            PrecomputedTravelTimesError::Corrupt { reason, .. } if reason == expected
+        ));
+    }
+}
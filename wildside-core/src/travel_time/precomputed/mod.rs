@@ -0,0 +1,322 @@
+//! Travel times precomputed between the POIs of a region.
+//!
+//! Ingest routes between every pair of POIs over the region's
+//! [`RoutingGraph`] once and persists the results as `travel_times.bin`.
+//! [`PrecomputedTravelTimeProvider`] then answers solve-time requests with
+//! table lookups, with no routing service and no graph search.
+//!
+//! Only pairs within [`PrecomputeOptions::max_travel_time`] of each other are
+//! kept, so the table grows with POI density rather than with the square of
+//! the POI count. Rows are stored in compressed sparse row form: the entries
+//! of row `i` are `targets[offsets[i]..offsets[i + 1]]`, sorted by target,
+//! with their travel times in whole seconds at the same positions of
+//! `seconds`.
+
+use std::{collections::HashMap, fmt, time::Duration};
+
+use geo::{Coord, Distance, Haversine, Point};
+use rstar::{RTree, primitives::GeomWithData};
+use serde::{Deserialize, Serialize};
+
+use crate::PointOfInterest;
+
+use super::{DEFAULT_MAX_SNAP_DISTANCE_M, DEFAULT_WALKING_SPEED_KMH, RoutingGraph};
+
+mod file;
+mod provider;
+
+pub use file::PrecomputedTravelTimesError;
+pub use provider::{DEFAULT_MAX_ACCESS_DISTANCE_M, PrecomputedTravelTimeProvider};
+
+/// Longest travel time kept by [`PrecomputeOptions::default`].
+pub const DEFAULT_MAX_TRAVEL_TIME: Duration = Duration::from_secs(2 * 60 * 60);
+
+type PoiEntry = GeomWithData<[f64; 2], u32>;
+
+/// Settings for [`PrecomputedTravelTimes::from_graph`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PrecomputeOptions {
+    /// Walking speed in kilometres per hour.
+    pub speed_kmh: f64,
+    /// Pairs further apart than this are not stored and are reported as
+    /// unreachable.
+    pub max_travel_time: Duration,
+    /// POIs further than this many metres from the graph are left without
+    /// entries.
+    pub max_snap_distance_m: f64,
+}
+
+impl Default for PrecomputeOptions {
+    fn default() -> Self {
+        Self {
+            speed_kmh: DEFAULT_WALKING_SPEED_KMH,
+            max_travel_time: DEFAULT_MAX_TRAVEL_TIME,
+            max_snap_distance_m: DEFAULT_MAX_SNAP_DISTANCE_M,
+        }
+    }
+}
+
+impl PrecomputeOptions {
+    /// Assume walking at `speed_kmh`, while consuming `self`.
+    #[must_use]
+    pub const fn with_speed_kmh(mut self, speed_kmh: f64) -> Self {
+        self.speed_kmh = speed_kmh;
+        self
+    }
+
+    /// Keep only pairs at most `limit` apart, while consuming `self`.
+    #[must_use]
+    pub const fn with_max_travel_time(mut self, limit: Duration) -> Self {
+        self.max_travel_time = limit;
+        self
+    }
+
+    /// Leave POIs further than `metres` from the graph without entries,
+    /// while consuming `self`.
+    #[must_use]
+    pub const fn with_max_snap_distance(mut self, metres: f64) -> Self {
+        self.max_snap_distance_m = metres;
+        self
+    }
+
+    fn metres_per_second(&self) -> f64 {
+        let speed_kmh = if self.speed_kmh.is_finite() && self.speed_kmh > 0.0 {
+            self.speed_kmh
+        } else {
+            DEFAULT_WALKING_SPEED_KMH
+        };
+        speed_kmh * 1_000.0 / 3_600.0
+    }
+}
+
+/// Travel-time table of a region in compressed sparse row form.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Table {
+    ids: Vec<u64>,
+    locations: Vec<Coord<f64>>,
+    speed_kmh: f64,
+    offsets: Vec<u32>,
+    targets: Vec<u32>,
+    seconds: Vec<u32>,
+}
+
+/// Travel times between the POIs of a region, computed ahead of time.
+///
+/// Build a table with [`Self::from_graph`], persist it with [`Self::write`],
+/// and read it back with [`Self::load`].
+///
+/// # Examples
+/// ```rust
+/// use geo::Coord;
+/// use wildside_core::PointOfInterest;
+/// use wildside_core::travel_time::{
+///     PrecomputeOptions, PrecomputedTravelTimes, RoutingGraphBuilder,
+/// };
+///
+/// let mut builder = RoutingGraphBuilder::default();
+/// let a = builder.add_node(Coord { x: 0.0, y: 0.0 }).expect("node");
+/// let b = builder.add_node(Coord { x: 0.0, y: 0.01 }).expect("node");
+/// builder.add_edge(a, b);
+///
+/// let pois = [
+///     PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 }),
+///     PointOfInterest::with_empty_tags(2, Coord { x: 0.0, y: 0.01 }),
+/// ];
+/// let table =
+///     PrecomputedTravelTimes::from_graph(&builder.build(), &pois, &PrecomputeOptions::default())?;
+/// let walk = table.travel_time(1, 2).expect("connected");
+/// assert!((13..15).contains(&(walk.as_secs() / 60)));
+/// # Ok::<(), wildside_core::travel_time::PrecomputedTravelTimesError>(())
+/// ```
+#[derive(Clone)]
+pub struct PrecomputedTravelTimes {
+    table: Table,
+    positions: HashMap<u64, u32>,
+    index: RTree<PoiEntry>,
+}
+
+impl fmt::Debug for PrecomputedTravelTimes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PrecomputedTravelTimes")
+            .field("pois", &self.len())
+            .field("entries", &self.entry_count())
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for PrecomputedTravelTimes {
+    fn eq(&self, other: &Self) -> bool {
+        self.table == other.table
+    }
+}
+
+impl PrecomputedTravelTimes {
+    /// Route between every pair of `pois` over `graph`.
+    ///
+    /// Each POI is snapped to its nearest graph node, and the walks to and
+    /// from those nodes are included in the stored times. Searches stop at
+    /// [`PrecomputeOptions::max_travel_time`], so the cost of building the
+    /// table grows with the number of POIs times the size of the graph
+    /// within that limit of each one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PrecomputedTravelTimesError::TooManyEntries`] when the table
+    /// would hold more pairs than its `u32` row offsets can address.
+    pub fn from_graph(
+        graph: &RoutingGraph,
+        pois: &[PointOfInterest],
+        options: &PrecomputeOptions,
+    ) -> Result<Self, PrecomputedTravelTimesError> {
+        let metres_per_second = options.metres_per_second();
+        let max_metres = options.max_travel_time.as_secs_f64() * metres_per_second;
+        let snapped: Vec<Option<(u32, f64)>> = pois
+            .iter()
+            .map(|poi| {
+                graph
+                    .nearest_node(poi.location)
+                    .filter(|(_, metres)| *metres <= options.max_snap_distance_m)
+            })
+            .collect();
+        let mut at_node: HashMap<u32, Vec<(u32, f64)>> = HashMap::new();
+        for (position, snap) in (0_u32..).zip(&snapped) {
+            if let Some((node, egress)) = *snap {
+                at_node.entry(node).or_default().push((position, egress));
+            }
+        }
+        let nodes: Vec<u32> = at_node.keys().copied().collect();
+        // POIs reachable from one snapped to `node`, `access` metres away,
+        // with the walking distance to each.
+        let reachable = |node: u32, access: f64| -> Vec<(u32, f64)> {
+            let targets = |(target_node, path): (u32, f64)| {
+                at_node
+                    .get(&target_node)
+                    .into_iter()
+                    .flatten()
+                    .map(move |&(target, egress)| (target, access + path + egress))
+            };
+            graph
+                .distances_within(node, &nodes, max_metres - access)
+                .into_iter()
+                .flat_map(targets)
+                .collect()
+        };
+
+        let mut table = Table {
+            ids: pois.iter().map(|poi| poi.id).collect(),
+            locations: pois.iter().map(|poi| poi.location).collect(),
+            speed_kmh: metres_per_second * 3.6,
+            offsets: Vec::with_capacity(pois.len() + 1),
+            ..Table::default()
+        };
+        table.offsets.push(0);
+        for (source, snap) in (0_u32..).zip(&snapped) {
+            let mut row: Vec<(u32, u32)> = snap
+                .iter()
+                .flat_map(|&(node, access)| reachable(node, access))
+                .filter(|&(target, metres)| target != source && metres <= max_metres)
+                .map(|(target, metres)| (target, whole_seconds(metres / metres_per_second)))
+                .collect();
+            row.sort_unstable();
+            for (target, seconds) in row {
+                table.targets.push(target);
+                table.seconds.push(seconds);
+            }
+            table.offsets.push(row_end(table.targets.len())?);
+        }
+        Ok(Self::from_table(table))
+    }
+
+    fn from_table(table: Table) -> Self {
+        let positions = table.ids.iter().copied().zip(0_u32..).collect();
+        let entries = table
+            .locations
+            .iter()
+            .zip(0_u32..)
+            .map(|(location, position)| PoiEntry::new([location.x, location.y], position))
+            .collect();
+        Self {
+            table,
+            positions,
+            index: RTree::bulk_load(entries),
+        }
+    }
+
+    /// Number of POIs in the table.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.table.ids.len()
+    }
+
+    /// Report whether the table covers no POIs.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.table.ids.is_empty()
+    }
+
+    /// Number of stored POI pairs.
+    #[must_use]
+    pub fn entry_count(&self) -> usize {
+        self.table.targets.len()
+    }
+
+    /// Walking speed the table was computed for, in kilometres per hour.
+    #[must_use]
+    pub const fn speed_kmh(&self) -> f64 {
+        self.table.speed_kmh
+    }
+
+    /// Travel time from the POI with id `from` to the POI with id `to`.
+    ///
+    /// Returns `None` when either POI is not in the table, or when the pair
+    /// is unreachable or further apart than the limit the table was built
+    /// with.
+    #[must_use]
+    pub fn travel_time(&self, from: u64, to: u64) -> Option<Duration> {
+        let from = *self.positions.get(&from)?;
+        let to = *self.positions.get(&to)?;
+        self.between(from, to)
+    }
+
+    /// Travel time between two table positions.
+    fn between(&self, from: u32, to: u32) -> Option<Duration> {
+        if from == to {
+            return ((from as usize) < self.len()).then_some(Duration::ZERO);
+        }
+        let start = *self.table.offsets.get(from as usize)? as usize;
+        let end = *self.table.offsets.get(from as usize + 1)? as usize;
+        let targets = self.table.targets.get(start..end)?;
+        let found = targets.binary_search(&to).ok()?;
+        let seconds = self.table.seconds.get(start + found)?;
+        Some(Duration::from_secs(u64::from(*seconds)))
+    }
+
+    /// Table position of `poi`: the entry with the same id and location.
+    fn position_of(&self, poi: &PointOfInterest) -> Option<u32> {
+        let position = *self.positions.get(&poi.id)?;
+        (self.table.locations.get(position as usize) == Some(&poi.location)).then_some(position)
+    }
+
+    /// Nearest table POI to `location` and its distance in metres.
+    fn nearest(&self, location: Coord<f64>) -> Option<(u32, f64)> {
+        let entry = self.index.nearest_neighbor(&[location.x, location.y])?;
+        let [x, y] = *entry.geom();
+        let metres = Haversine.distance(Point::from(location), Point::new(x, y));
+        Some((entry.data, metres))
+    }
+}
+
+/// Round `seconds` to whole seconds for storage.
+fn whole_seconds(seconds: f64) -> u32 {
+    // Times are bounded by the precompute limit, far below `u32::MAX`
+    // seconds, and the float-to-int cast saturates regardless.
+    seconds.round() as u32
+}
+
+/// Offset of the end of a row that closes after `entries` stored pairs.
+fn row_end(entries: usize) -> Result<u32, PrecomputedTravelTimesError> {
+    u32::try_from(entries).map_err(|_| PrecomputedTravelTimesError::TooManyEntries { entries })
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,203 @@
+//! Travel times served from a precomputed table.
+
+use std::{path::Path, sync::Arc, time::Duration};
+
+use crate::PointOfInterest;
+
+use crate::travel_time::{TravelTimeError, TravelTimeMatrix, TravelTimeProvider};
+
+use super::{PrecomputedTravelTimes, PrecomputedTravelTimesError};
+
+/// Furthest a location outside the table may lie from the nearest tabled POI,
+/// in metres, before [`PrecomputedTravelTimeProvider`] treats it as
+/// unreachable.
+pub const DEFAULT_MAX_ACCESS_DISTANCE_M: f64 = 500.0;
+
+/// Serve travel times from a [`PrecomputedTravelTimes`] table.
+///
+/// POIs in the table, matched by id and location, are looked up directly.
+/// Other locations, such as a route's start and end, are anchored to the
+/// nearest tabled POI, and the straight-line walk to it is added at the
+/// table's walking speed. Pairs the table does not hold are reported as
+/// [`Duration::MAX`], matching how the other providers report unroutable
+/// pairs.
+///
+/// The table is shared behind an [`Arc`], so clones are cheap.
+///
+/// # Examples
+/// ```rust
+/// use geo::Coord;
+/// use wildside_core::{PointOfInterest, TravelTimeProvider};
+/// use wildside_core::travel_time::{
+///     PrecomputeOptions, PrecomputedTravelTimeProvider, PrecomputedTravelTimes,
+///     RoutingGraphBuilder,
+/// };
+///
+/// let mut builder = RoutingGraphBuilder::default();
+/// let a = builder.add_node(Coord { x: 0.0, y: 0.0 }).expect("node");
+/// let b = builder.add_node(Coord { x: 0.0, y: 0.01 }).expect("node");
+/// builder.add_edge(a, b);
+/// let pois = [
+///     PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 }),
+///     PointOfInterest::with_empty_tags(2, Coord { x: 0.0, y: 0.01 }),
+/// ];
+/// let table =
+///     PrecomputedTravelTimes::from_graph(&builder.build(), &pois, &PrecomputeOptions::default())?;
+///
+/// let provider = PrecomputedTravelTimeProvider::new(table);
+/// let matrix = provider.get_travel_time_matrix(&pois)?;
+/// assert!(matrix[0][1].as_secs() > 0);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct PrecomputedTravelTimeProvider {
+    table: Arc<PrecomputedTravelTimes>,
+    max_access_distance_m: f64,
+}
+
+impl PrecomputedTravelTimeProvider {
+    /// Serve travel times from `table`.
+    #[must_use]
+    pub fn new(table: impl Into<Arc<PrecomputedTravelTimes>>) -> Self {
+        Self {
+            table: table.into(),
+            max_access_distance_m: DEFAULT_MAX_ACCESS_DISTANCE_M,
+        }
+    }
+
+    /// Load the table persisted at `path` and serve travel times from it.
+    ///
+    /// # Errors
+    /// Returns [`PrecomputedTravelTimesError`] when the file cannot be read or
+    /// is not a valid travel-time table.
+    pub fn load(path: &Path) -> Result<Self, PrecomputedTravelTimesError> {
+        PrecomputedTravelTimes::load(path).map(Self::new)
+    }
+
+    /// Treat locations further than `metres` from every tabled POI as
+    /// unreachable, while consuming `self`.
+    #[must_use]
+    pub const fn with_max_access_distance(mut self, metres: f64) -> Self {
+        self.max_access_distance_m = metres;
+        self
+    }
+
+    /// Table the provider serves from.
+    #[must_use]
+    pub fn table(&self) -> &PrecomputedTravelTimes {
+        &self.table
+    }
+
+    /// Tabled POI standing in for `poi` and the walk to reach it.
+    fn anchor(&self, poi: &PointOfInterest) -> Option<(u32, Duration)> {
+        if let Some(position) = self.table.position_of(poi) {
+            return Some((position, Duration::ZERO));
+        }
+        let (position, metres) = self
+            .table
+            .nearest(poi.location)
+            .filter(|(_, metres)| *metres <= self.max_access_distance_m)?;
+        let metres_per_second = self.table.speed_kmh() / 3.6;
+        Some((
+            position,
+            Duration::from_secs_f64(metres / metres_per_second),
+        ))
+    }
+}
+
+impl TravelTimeProvider for PrecomputedTravelTimeProvider {
+    fn get_travel_time_matrix(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> Result<TravelTimeMatrix, TravelTimeError> {
+        if pois.is_empty() {
+            return Err(TravelTimeError::EmptyInput);
+        }
+        let anchors: Vec<_> = pois.iter().map(|poi| self.anchor(poi)).collect();
+        Ok(TravelTimeMatrix::from_fn(anchors.len(), |i, j| {
+            if i == j {
+                return Duration::ZERO;
+            }
+            anchors[i]
+                .zip(anchors[j])
+                .and_then(|((from, access), (to, egress))| {
+                    let between = self.table.between(from, to)?;
+                    Some(access + between + egress)
+                })
+                .unwrap_or(Duration::MAX)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for serving travel times from a precomputed table.
+
+    use super::*;
+    use crate::travel_time::{PrecomputeOptions, RoutingGraphBuilder};
+    use geo::Coord;
+    use rstest::{fixture, rstest};
+
+    fn poi(id: u64, x: f64, y: f64) -> PointOfInterest {
+        PointOfInterest::with_empty_tags(id, Coord { x, y })
+    }
+
+    /// Two POIs about 111 m apart on one street, and one on an island.
+    #[fixture]
+    fn provider() -> PrecomputedTravelTimeProvider {
+        let mut builder = RoutingGraphBuilder::default();
+        let a = builder.add_node(Coord { x: 0.0, y: 0.0 }).expect("node");
+        let b = builder.add_node(Coord { x: 0.0, y: 0.001 }).expect("node");
+        builder.add_node(Coord { x: 0.5, y: 0.5 });
+        builder.add_edge(a, b);
+        let pois = [poi(1, 0.0, 0.0), poi(2, 0.0, 0.001), poi(3, 0.5, 0.5)];
+        PrecomputedTravelTimeProvider::new(
+            PrecomputedTravelTimes::from_graph(
+                &builder.build(),
+                &pois,
+                &PrecomputeOptions::default(),
+            )
+            .expect("table"),
+        )
+    }
+
+    #[rstest]
+    fn serves_tabled_pairs(provider: PrecomputedTravelTimeProvider) {
+        let matrix = provider
+            .get_travel_time_matrix(&[poi(1, 0.0, 0.0), poi(2, 0.0, 0.001)])
+            .expect("matrix");
+        assert_eq!(matrix[0][0], Duration::ZERO);
+        assert_eq!(Some(matrix[0][1]), provider.table().travel_time(1, 2));
+    }
+
+    #[rstest]
+    fn anchors_other_locations_to_the_nearest_poi(provider: PrecomputedTravelTimeProvider) {
+        // A start about 11 m south of POI 1.
+        let start = poi(0, 0.0, -0.0001);
+        let matrix = provider
+            .get_travel_time_matrix(&[start, poi(2, 0.0, 0.001)])
+            .expect("matrix");
+        let tabled = provider.table().travel_time(1, 2).expect("connected");
+        let access = matrix[0][1] - tabled;
+        assert!((7..9).contains(&access.as_secs()), "got {access:?}");
+    }
+
+    #[rstest]
+    fn reports_missing_pairs_as_unreachable(provider: PrecomputedTravelTimeProvider) {
+        let far_away = poi(9, 10.0, 10.0);
+        let matrix = provider
+            .get_travel_time_matrix(&[poi(1, 0.0, 0.0), poi(3, 0.5, 0.5), far_away])
+            .expect("matrix");
+        assert_eq!(matrix[0][1], Duration::MAX);
+        assert_eq!(matrix[0][2], Duration::MAX);
+        assert_eq!(matrix[2][2], Duration::ZERO);
+    }
+
+    #[rstest]
+    fn errors_on_empty_input(provider: PrecomputedTravelTimeProvider) {
+        let err = provider
+            .get_travel_time_matrix(&[])
+            .expect_err("empty input");
+        assert_eq!(err, TravelTimeError::EmptyInput);
+    }
+}
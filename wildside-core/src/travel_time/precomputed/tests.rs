@@ -0,0 +1,86 @@
+//! Tests for building precomputed travel-time tables.
+
+use super::*;
+use crate::travel_time::RoutingGraphBuilder;
+use rstest::{fixture, rstest};
+
+/// A street running north in steps of about 111 m, and an island.
+#[fixture]
+fn graph() -> RoutingGraph {
+    let mut builder = RoutingGraphBuilder::default();
+    for step in 0..4 {
+        builder.add_node(Coord {
+            x: 0.0,
+            y: f64::from(step) * 0.001,
+        });
+    }
+    builder.add_node(Coord { x: 0.5, y: 0.5 });
+    builder.add_node(Coord { x: 0.5, y: 0.501 });
+    for (from, to) in [(0, 1), (1, 2), (2, 3), (4, 5)] {
+        builder.add_edge(from, to);
+    }
+    builder.build()
+}
+
+fn poi(id: u64, x: f64, y: f64) -> PointOfInterest {
+    PointOfInterest::with_empty_tags(id, Coord { x, y })
+}
+
+#[fixture]
+fn pois() -> Vec<PointOfInterest> {
+    vec![
+        poi(1, 0.0, 0.0),
+        poi(2, 0.0, 0.003),
+        poi(3, 0.5, 0.5),
+        poi(4, 0.0, 0.001),
+    ]
+}
+
+#[rstest]
+fn stores_times_between_connected_pois(graph: RoutingGraph, pois: Vec<PointOfInterest>) {
+    let table = PrecomputedTravelTimes::from_graph(&graph, &pois, &PrecomputeOptions::default())
+        .expect("table");
+    assert_eq!(table.len(), 4);
+    // Two connected POIs each way along the street, among three.
+    assert_eq!(table.entry_count(), 6);
+    let seconds = table.travel_time(1, 2).expect("connected").as_secs();
+    // About 333 m at 5 km/h.
+    assert!((235..245).contains(&seconds), "got {seconds}");
+    assert_eq!(table.travel_time(2, 1), table.travel_time(1, 2));
+    assert_eq!(table.travel_time(1, 1), Some(Duration::ZERO));
+}
+
+#[rstest]
+fn omits_unreachable_and_unknown_pairs(graph: RoutingGraph, pois: Vec<PointOfInterest>) {
+    let table = PrecomputedTravelTimes::from_graph(&graph, &pois, &PrecomputeOptions::default())
+        .expect("table");
+    assert_eq!(table.travel_time(1, 3), None);
+    assert_eq!(table.travel_time(1, 99), None);
+}
+
+#[rstest]
+fn drops_pairs_beyond_the_time_limit(graph: RoutingGraph, pois: Vec<PointOfInterest>) {
+    let options = PrecomputeOptions::default().with_max_travel_time(Duration::from_secs(120));
+    let table = PrecomputedTravelTimes::from_graph(&graph, &pois, &options).expect("table");
+    assert_eq!(table.travel_time(1, 2), None);
+    assert!(table.travel_time(1, 4).is_some());
+}
+
+#[rstest]
+fn leaves_distant_pois_without_entries(graph: RoutingGraph) {
+    let pois = [poi(1, 0.0, 0.0), poi(2, 0.0, 0.01)];
+    let table = PrecomputedTravelTimes::from_graph(&graph, &pois, &PrecomputeOptions::default())
+        .expect("table");
+    assert_eq!(table.entry_count(), 0);
+    assert_eq!(table.travel_time(2, 2), Some(Duration::ZERO));
+}
+
+#[rstest]
+fn rejects_tables_beyond_the_offset_range() {
+    assert_eq!(row_end(7).expect("in range"), 7);
+    let entries = usize::try_from(u64::from(u32::MAX) + 1).expect("64-bit usize");
+    assert!(matches!(
+        row_end(entries),
+        Err(PrecomputedTravelTimesError::TooManyEntries { entries: found }) if found == entries
+    ));
+}
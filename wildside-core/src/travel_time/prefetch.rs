@@ -0,0 +1,219 @@
+//! Travel times fetched asynchronously ahead of a synchronous solve.
+
+use std::mem;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::PointOfInterest;
+
+use super::{
+    AsyncTravelTimeProvider, TravelTimeError, TravelTimeMatrix, TravelTimeProvider,
+    TravelTimeRecording,
+};
+
+/// Answer travel-time requests from matrices fetched beforehand through an
+/// [`AsyncTravelTimeProvider`].
+///
+/// Matrices are fetched up front with [`Self::fetch`]. Requests no fetched
+/// matrix covers fail with [`TravelTimeError::NotPrefetched`] and are noted,
+/// so the caller can await them with [`Self::fetch_missing`] and repeat the
+/// synchronous work. As with
+/// [`ReplayTravelTimeProvider`](super::ReplayTravelTimeProvider), a fetched
+/// matrix answers any request for a subset of its locations.
+///
+/// Clones share the fetched matrices, so a solver can own one clone while
+/// the caller fetches through another. Route geometry and step-free
+/// profiles are not offered.
+///
+/// # Examples
+/// ```rust
+/// use geo::Coord;
+/// use wildside_core::{PointOfInterest, TravelTimeError, TravelTimeProvider};
+/// use wildside_core::travel_time::{
+///     HaversineTravelTimeProvider, PrefetchedTravelTimeProvider, SyncTravelTimeAdapter,
+/// };
+///
+/// # async fn run() -> Result<(), TravelTimeError> {
+/// let pois = [
+///     PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 }),
+///     PointOfInterest::with_empty_tags(2, Coord { x: 0.0, y: 0.01 }),
+/// ];
+/// let prefetched = PrefetchedTravelTimeProvider::new();
+/// assert!(prefetched.get_travel_time_matrix(&pois).is_err());
+///
+/// let live = SyncTravelTimeAdapter::new(HaversineTravelTimeProvider::default());
+/// assert_eq!(prefetched.fetch_missing(&live).await?, 1);
+/// assert!(prefetched.get_travel_time_matrix(&pois).is_ok());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PrefetchedTravelTimeProvider {
+    state: Arc<Mutex<Prefetched>>,
+}
+
+#[derive(Debug, Default)]
+struct Prefetched {
+    fetched: TravelTimeRecording,
+    missing: Vec<Vec<PointOfInterest>>,
+}
+
+impl PrefetchedTravelTimeProvider {
+    /// Create a provider with nothing fetched yet.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Await, through `provider`, the matrix over `pois`, so that it and any
+    /// subset of it can be answered without waiting.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error `provider` raises, and
+    /// [`TravelTimeError::ParseError`] when it answers with a matrix that
+    /// does not have one row per POI.
+    pub async fn fetch<P>(
+        &self,
+        provider: &P,
+        pois: &[PointOfInterest],
+    ) -> Result<(), TravelTimeError>
+    where
+        P: AsyncTravelTimeProvider + ?Sized,
+    {
+        let matrix = provider.get_travel_time_matrix_async(pois).await?;
+        self.lock()
+            .fetched
+            .insert(pois, matrix)
+            .map_err(|error| TravelTimeError::ParseError {
+                message: error.to_string(),
+            })
+    }
+
+    /// Await, through `provider`, every request this provider could not
+    /// answer since the last call, and return how many were fetched.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error raised by [`Self::fetch`]. Requests not yet
+    /// fetched when an error occurs are dropped; asking for them again notes
+    /// them afresh.
+    pub async fn fetch_missing<P>(&self, provider: &P) -> Result<usize, TravelTimeError>
+    where
+        P: AsyncTravelTimeProvider + ?Sized,
+    {
+        let missing = mem::take(&mut self.lock().missing);
+        for pois in &missing {
+            self.fetch(provider, pois).await?;
+        }
+        Ok(missing.len())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Prefetched> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl TravelTimeProvider for PrefetchedTravelTimeProvider {
+    fn get_travel_time_matrix(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> Result<TravelTimeMatrix, TravelTimeError> {
+        if pois.is_empty() {
+            return Err(TravelTimeError::EmptyInput);
+        }
+        let mut state = self.lock();
+        if let Some(matrix) = state.fetched.lookup(pois) {
+            return Ok(matrix);
+        }
+        if !state.missing.iter().any(|noted| noted.as_slice() == pois) {
+            state.missing.push(pois.to_vec());
+        }
+        Err(TravelTimeError::NotPrefetched {
+            poi_count: pois.len(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for answering requests from prefetched matrices.
+
+    use super::*;
+    use crate::test_support::UnitTravelTimeProvider;
+    use crate::travel_time::SyncTravelTimeAdapter;
+    use geo::Coord;
+    use rstest::rstest;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+    use std::time::Duration;
+
+    fn poll_ready<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        match future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("fetch should complete on first poll"),
+        }
+    }
+
+    fn pois() -> Vec<PointOfInterest> {
+        (1..=3)
+            .map(|id| {
+                PointOfInterest::with_empty_tags(
+                    id,
+                    Coord {
+                        x: 0.0,
+                        y: id as f64,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[rstest]
+    fn unanswered_requests_are_noted_once_and_fetched() {
+        let prefetched = PrefetchedTravelTimeProvider::new();
+        let pois = pois();
+        for _ in 0..2 {
+            assert_eq!(
+                prefetched.get_travel_time_matrix(&pois),
+                Err(TravelTimeError::NotPrefetched { poi_count: 3 })
+            );
+        }
+        let live = SyncTravelTimeAdapter::new(UnitTravelTimeProvider);
+        assert_eq!(poll_ready(prefetched.fetch_missing(&live)), Ok(1));
+        assert_eq!(poll_ready(prefetched.fetch_missing(&live)), Ok(0));
+        let matrix = prefetched.get_travel_time_matrix(&pois).expect("fetched");
+        assert_eq!(matrix[0][2], Duration::from_secs(1));
+    }
+
+    #[rstest]
+    fn matrices_fetched_up_front_are_answered_without_noting_them() {
+        let prefetched = PrefetchedTravelTimeProvider::new();
+        let pois = pois();
+        let live = SyncTravelTimeAdapter::new(UnitTravelTimeProvider);
+        poll_ready(prefetched.fetch(&live, &pois)).expect("fetch");
+
+        assert!(prefetched.get_travel_time_matrix(&pois[1..]).is_ok());
+        assert_eq!(poll_ready(prefetched.fetch_missing(&live)), Ok(0));
+    }
+
+    #[rstest]
+    fn fetched_matrices_answer_subsets_through_every_clone() {
+        let prefetched = PrefetchedTravelTimeProvider::new();
+        let solver_side = prefetched.clone();
+        let pois = pois();
+        assert!(solver_side.get_travel_time_matrix(&pois).is_err());
+        let live = SyncTravelTimeAdapter::new(UnitTravelTimeProvider);
+        poll_ready(prefetched.fetch_missing(&live)).expect("fetch");
+        let subset = [pois[2].clone(), pois[0].clone()];
+        assert_eq!(
+            solver_side
+                .get_travel_time_matrix(&subset)
+                .map(|matrix| matrix.len()),
+            Ok(2)
+        );
+    }
+}
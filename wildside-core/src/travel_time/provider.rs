@@ -1,13 +1,12 @@
-//! Travel-time provider trait and adjacency-matrix alias for POI pairs.
+//! Travel-time provider trait for POI pairs.
 
-use std::time::Duration;
+use std::sync::Arc;
 
-use crate::PointOfInterest;
+use geo::{Coord, LineString};
 
-use super::error::TravelTimeError;
+use crate::PointOfInterest;
 
-/// Adjacency matrix of travel times.
-pub type TravelTimeMatrix = Vec<Vec<Duration>>;
+use super::{error::TravelTimeError, matrix::TravelTimeMatrix};
 
 /// Fetch pairwise travel times for a set of POIs.
 ///
@@ -31,14 +30,9 @@ pub type TravelTimeMatrix = Vec<Vec<Duration>>;
 ///         if pois.is_empty() {
 ///             return Err(TravelTimeError::EmptyInput);
 ///         }
-///         let n = pois.len();
-///         Ok((0..n)
-///             .map(|i| {
-///                 (0..n)
-///                     .map(|j| if i == j { Duration::ZERO } else { Duration::from_secs(1) })
-///                     .collect::<Vec<_>>()
-///             })
-///             .collect())
+///         Ok(TravelTimeMatrix::from_fn(pois.len(), |i, j| {
+///             if i == j { Duration::ZERO } else { Duration::from_secs(1) }
+///         }))
 ///     }
 /// }
 ///
@@ -56,6 +50,69 @@ pub trait TravelTimeProvider {
         &self,
         pois: &[PointOfInterest],
     ) -> Result<TravelTimeMatrix, TravelTimeError>;
+
+    /// Return the walking path that visits `stops` in order, if the provider
+    /// can trace routes.
+    ///
+    /// The default returns `Ok(None)`. Providers backed by a routing engine
+    /// override it so solvers can attach the path to their response.
+    fn route_geometry(
+        &self,
+        stops: &[Coord<f64>],
+    ) -> Result<Option<LineString<f64>>, TravelTimeError> {
+        let _ = stops;
+        Ok(None)
+    }
+
+    /// Return a provider routing the same way but avoiding stairs, if this
+    /// provider can plan step-free walks.
+    ///
+    /// The default returns `None`, and step-free solves then fail with
+    /// [`TravelTimeError::StepFreeUnavailable`]. See
+    /// [`AccessibilityRequirements`](crate::AccessibilityRequirements).
+    fn step_free(&self) -> Option<&dyn TravelTimeProvider> {
+        None
+    }
+}
+
+impl<P: TravelTimeProvider + ?Sized> TravelTimeProvider for Box<P> {
+    fn get_travel_time_matrix(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> Result<TravelTimeMatrix, TravelTimeError> {
+        (**self).get_travel_time_matrix(pois)
+    }
+
+    fn route_geometry(
+        &self,
+        stops: &[Coord<f64>],
+    ) -> Result<Option<LineString<f64>>, TravelTimeError> {
+        (**self).route_geometry(stops)
+    }
+
+    fn step_free(&self) -> Option<&dyn TravelTimeProvider> {
+        (**self).step_free()
+    }
+}
+
+impl<P: TravelTimeProvider + ?Sized> TravelTimeProvider for Arc<P> {
+    fn get_travel_time_matrix(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> Result<TravelTimeMatrix, TravelTimeError> {
+        (**self).get_travel_time_matrix(pois)
+    }
+
+    fn route_geometry(
+        &self,
+        stops: &[Coord<f64>],
+    ) -> Result<Option<LineString<f64>>, TravelTimeError> {
+        (**self).route_geometry(stops)
+    }
+
+    fn step_free(&self) -> Option<&dyn TravelTimeProvider> {
+        (**self).step_free()
+    }
 }
 
 #[cfg(test)]
@@ -65,6 +122,7 @@ mod tests {
     use super::*;
     use geo::Coord;
     use rstest::rstest;
+    use std::time::Duration;
 
     use crate::test_support::UnitTravelTimeProvider;
 
@@ -83,7 +141,7 @@ mod tests {
             .get_travel_time_matrix(&pois)
             .expect("expected square matrix from UnitTravelTimeProvider");
         assert_eq!(matrix.len(), pois.len());
-        assert!(matrix.iter().all(|row| row.len() == pois.len()));
+        assert!(matrix.rows().all(|row| row.len() == pois.len()));
         assert_eq!(matrix[0][0], Duration::ZERO);
         assert_eq!(matrix[0][1], Duration::from_secs(1));
     }
@@ -96,4 +154,14 @@ mod tests {
             .expect_err("expected EmptyInput for empty slice");
         assert_eq!(err, TravelTimeError::EmptyInput);
     }
+
+    #[rstest]
+    fn geometry_is_absent_by_default() {
+        let stops: Vec<Coord<f64>> = sample_pois().iter().map(|poi| poi.location).collect();
+        let provider: Box<dyn TravelTimeProvider> = Box::new(UnitTravelTimeProvider);
+        let geometry = provider
+            .route_geometry(&stops)
+            .expect("default geometry should not fail");
+        assert_eq!(geometry, None);
+    }
 }
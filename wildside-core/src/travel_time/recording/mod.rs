@@ -0,0 +1,331 @@
+//! Record travel-time matrices from a live provider and replay them later.
+//!
+//! Golden-route tests and benchmarks want realistic travel times without a
+//! routing service in the loop. [`RecordingTravelTimeProvider`] wraps a real
+//! provider, such as an OSRM client, and keeps every matrix it returns; the
+//! resulting [`TravelTimeRecording`] is written to a JSON fixture with
+//! [`TravelTimeRecording::to_writer`]. [`ReplayTravelTimeProvider`] answers
+//! from that fixture, deterministically and offline.
+
+use std::{collections::HashMap, io, time::Duration};
+
+use geo::Coord;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::PointOfInterest;
+
+use super::TravelTimeMatrix;
+
+mod provider;
+
+pub use provider::{RecordingTravelTimeProvider, ReplayTravelTimeProvider};
+
+/// Errors raised while reading, writing, or extending a recording.
+#[derive(Debug, Error)]
+pub enum TravelTimeRecordingError {
+    /// Reading or writing the fixture failed.
+    #[error("failed to access travel-time recording: {0}")]
+    Io(#[from] io::Error),
+    /// The fixture is not a valid recording document.
+    #[error("failed to parse travel-time recording: {0}")]
+    Json(#[from] serde_json::Error),
+    /// A matrix does not have one row per recorded location.
+    #[error("recorded matrix {entry} has {rows} rows for {locations} locations")]
+    ShapeMismatch {
+        /// Index of the offending entry.
+        entry: usize,
+        /// Number of locations recorded for the entry.
+        locations: usize,
+        /// Number of rows in its matrix.
+        rows: usize,
+    },
+}
+
+/// One recorded request: the locations sent, in order, and the matrix
+/// returned for them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct RecordedMatrix {
+    locations: Vec<Coord<f64>>,
+    matrix: TravelTimeMatrix,
+}
+
+impl RecordedMatrix {
+    /// Position of each location in the entry, keyed by exact coordinates.
+    fn positions(&self) -> HashMap<[u64; 2], usize> {
+        self.locations
+            .iter()
+            .enumerate()
+            .map(|(index, location)| (location_key(*location), index))
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct RecordingDocument {
+    entries: Vec<RecordedMatrix>,
+}
+
+/// Travel-time matrices captured from a provider, keyed by the locations
+/// they were requested for.
+///
+/// A lookup succeeds when some recorded matrix covers every requested
+/// location, in any order, so one large recording can serve the smaller
+/// requests a solver makes for subsets of the same POIs. Locations are
+/// matched on their exact coordinates.
+///
+/// Recordings serialize as JSON so fixtures can be reviewed in diffs.
+///
+/// # Examples
+/// ```rust
+/// use geo::Coord;
+/// use wildside_core::{PointOfInterest, TravelTimeProvider};
+/// use wildside_core::travel_time::{
+///     HaversineTravelTimeProvider, RecordingTravelTimeProvider, ReplayTravelTimeProvider,
+///     TravelTimeRecording,
+/// };
+///
+/// let pois = [
+///     PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 }),
+///     PointOfInterest::with_empty_tags(2, Coord { x: 0.0, y: 0.01 }),
+/// ];
+/// let recorder = RecordingTravelTimeProvider::new(HaversineTravelTimeProvider::default());
+/// let live = recorder.get_travel_time_matrix(&pois)?;
+///
+/// let mut fixture = Vec::new();
+/// recorder.recording().to_writer(&mut fixture)?;
+///
+/// let replay = ReplayTravelTimeProvider::new(TravelTimeRecording::from_reader(&fixture[..])?);
+/// assert_eq!(replay.get_travel_time_matrix(&pois)?, live);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RecordingDocument", into = "RecordingDocument")]
+pub struct TravelTimeRecording {
+    entries: Vec<RecordedMatrix>,
+}
+
+impl TravelTimeRecording {
+    /// Create an empty recording.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of recorded matrices.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Report whether nothing has been recorded.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Record `matrix` as the answer for `pois`, replacing any matrix
+    /// recorded for the same locations in the same order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TravelTimeRecordingError::ShapeMismatch`] when the matrix
+    /// does not have one row per POI.
+    pub fn insert(
+        &mut self,
+        pois: &[PointOfInterest],
+        matrix: TravelTimeMatrix,
+    ) -> Result<(), TravelTimeRecordingError> {
+        let locations: Vec<_> = pois.iter().map(|poi| poi.location).collect();
+        if matrix.len() != locations.len() {
+            return Err(TravelTimeRecordingError::ShapeMismatch {
+                entry: self.entries.len(),
+                locations: locations.len(),
+                rows: matrix.len(),
+            });
+        }
+        let entry = RecordedMatrix { locations, matrix };
+        match self
+            .entries
+            .iter_mut()
+            .find(|recorded| recorded.locations == entry.locations)
+        {
+            Some(recorded) => *recorded = entry,
+            None => self.entries.push(entry),
+        }
+        Ok(())
+    }
+
+    /// Return the travel times between `pois` from the first recorded matrix
+    /// that covers all of their locations, or `None` when none does.
+    #[must_use]
+    pub fn lookup(&self, pois: &[PointOfInterest]) -> Option<TravelTimeMatrix> {
+        self.entries.iter().find_map(|entry| {
+            let positions = entry.positions();
+            let indices = pois
+                .iter()
+                .map(|poi| positions.get(&location_key(poi.location)).copied())
+                .collect::<Option<Vec<_>>>()?;
+            Some(TravelTimeMatrix::from_fn(indices.len(), |from, to| {
+                entry
+                    .matrix
+                    .get(indices[from], indices[to])
+                    .unwrap_or(Duration::MAX)
+            }))
+        })
+    }
+
+    /// Read a recording previously written with [`Self::to_writer`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TravelTimeRecordingError::Json`] when the document is
+    /// malformed, including when a matrix is not square or does not match
+    /// its locations.
+    pub fn from_reader<R: io::Read>(reader: R) -> Result<Self, TravelTimeRecordingError> {
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    /// Write the recording as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TravelTimeRecordingError::Json`] when serialization or the
+    /// underlying write fails.
+    pub fn to_writer<W: io::Write>(&self, writer: W) -> Result<(), TravelTimeRecordingError> {
+        Ok(serde_json::to_writer_pretty(writer, self)?)
+    }
+}
+
+impl TryFrom<RecordingDocument> for TravelTimeRecording {
+    type Error = TravelTimeRecordingError;
+
+    fn try_from(document: RecordingDocument) -> Result<Self, Self::Error> {
+        if let Some((entry, recorded)) = document
+            .entries
+            .iter()
+            .enumerate()
+            .find(|(_, recorded)| recorded.matrix.len() != recorded.locations.len())
+        {
+            return Err(TravelTimeRecordingError::ShapeMismatch {
+                entry,
+                locations: recorded.locations.len(),
+                rows: recorded.matrix.len(),
+            });
+        }
+        Ok(Self {
+            entries: document.entries,
+        })
+    }
+}
+
+impl From<TravelTimeRecording> for RecordingDocument {
+    fn from(recording: TravelTimeRecording) -> Self {
+        Self {
+            entries: recording.entries,
+        }
+    }
+}
+
+/// Exact-match key for a location; `-0.0` and `0.0` are treated alike.
+fn location_key(location: Coord<f64>) -> [u64; 2] {
+    [(location.x + 0.0).to_bits(), (location.y + 0.0).to_bits()]
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for storing and looking up recorded matrices.
+
+    use super::*;
+    use crate::TravelTimeProvider;
+    use crate::test_support::UnitTravelTimeProvider;
+    use rstest::{fixture, rstest};
+
+    #[fixture]
+    fn pois() -> Vec<PointOfInterest> {
+        (0..3)
+            .map(|id| {
+                PointOfInterest::with_empty_tags(
+                    id,
+                    Coord {
+                        x: id as f64,
+                        y: 0.0,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn recorded(pois: &[PointOfInterest]) -> TravelTimeRecording {
+        let recorder = RecordingTravelTimeProvider::new(UnitTravelTimeProvider);
+        recorder.get_travel_time_matrix(pois).expect("matrix");
+        recorder.recording()
+    }
+
+    #[rstest]
+    fn replays_reordered_subsets(pois: Vec<PointOfInterest>) {
+        let mut recording = TravelTimeRecording::new();
+        let matrix =
+            TravelTimeMatrix::from_fn(3, |from, to| Duration::from_secs((from * 10 + to) as u64));
+        recording.insert(&pois, matrix).expect("insert");
+
+        let subset = [pois[2].clone(), pois[0].clone()];
+        let replayed = recording.lookup(&subset).expect("covered");
+        assert_eq!(replayed.get(0, 1), Some(Duration::from_secs(20)));
+        assert_eq!(replayed.get(1, 0), Some(Duration::from_secs(2)));
+    }
+
+    #[rstest]
+    fn rerecording_the_same_request_replaces_it(pois: Vec<PointOfInterest>) {
+        let mut recording = recorded(&pois);
+        let slower = TravelTimeMatrix::from_fn(3, |_, _| Duration::from_secs(9));
+        recording.insert(&pois, slower.clone()).expect("insert");
+        assert_eq!(recording.len(), 1);
+        assert_eq!(recording.lookup(&pois), Some(slower));
+    }
+
+    #[rstest]
+    fn round_trips_through_json(pois: Vec<PointOfInterest>) {
+        let recording = recorded(&pois);
+        let mut json = Vec::new();
+        recording.to_writer(&mut json).expect("write");
+        let decoded = TravelTimeRecording::from_reader(&json[..]).expect("read");
+        assert_eq!(decoded, recording);
+    }
+
+    /// Coordinates whose shortest decimal form needs all 17 significant
+    /// digits must still match exactly once read back.
+    #[rstest]
+    fn replays_coordinates_that_need_every_digit() {
+        let pois = [PointOfInterest::with_empty_tags(
+            1,
+            Coord {
+                x: -3.201_500_000_000_000_2,
+                y: 55.952_400_000_000_004,
+            },
+        )];
+        let mut json = Vec::new();
+        recorded(&pois).to_writer(&mut json).expect("write");
+        let decoded = TravelTimeRecording::from_reader(&json[..]).expect("read");
+        assert!(decoded.lookup(&pois).is_some());
+    }
+
+    #[rstest]
+    fn rejects_matrices_that_do_not_match_their_locations(pois: Vec<PointOfInterest>) {
+        let mut recording = TravelTimeRecording::new();
+        let err = recording
+            .insert(&pois, TravelTimeMatrix::from_fn(2, |_, _| Duration::ZERO))
+            .expect_err("shape mismatch");
+        assert!(matches!(
+            err,
+            TravelTimeRecordingError::ShapeMismatch {
+                locations: 3,
+                rows: 2,
+                ..
+            }
+        ));
+
+        let json = r#"{"entries":[{"locations":[{"x":0.0,"y":0.0}],"matrix":[]}]}"#;
+        assert!(TravelTimeRecording::from_reader(json.as_bytes()).is_err());
+    }
+}
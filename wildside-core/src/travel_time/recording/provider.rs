@@ -0,0 +1,181 @@
+//! Providers that record matrices from another provider or replay them.
+
+use std::{
+    io,
+    sync::{Mutex, PoisonError},
+};
+
+use geo::{Coord, LineString};
+
+use crate::PointOfInterest;
+use crate::travel_time::{TravelTimeError, TravelTimeMatrix, TravelTimeProvider};
+
+use super::{TravelTimeRecording, TravelTimeRecordingError};
+
+/// Pass requests to another provider and record every matrix it returns.
+///
+/// Failed requests, and matrices that do not have one row per POI, are passed
+/// through without being recorded. Take the captured
+/// matrices with [`Self::recording`] and persist them with
+/// [`TravelTimeRecording::to_writer`].
+#[derive(Debug)]
+pub struct RecordingTravelTimeProvider<P> {
+    inner: P,
+    recording: Mutex<TravelTimeRecording>,
+}
+
+impl<P> RecordingTravelTimeProvider<P> {
+    /// Record the responses of `inner`.
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            recording: Mutex::new(TravelTimeRecording::new()),
+        }
+    }
+
+    /// Borrow the wrapped provider.
+    pub const fn get_ref(&self) -> &P {
+        &self.inner
+    }
+
+    /// Return a copy of everything recorded so far.
+    pub fn recording(&self) -> TravelTimeRecording {
+        self.recording
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Unwrap the provider and the recording.
+    pub fn into_parts(self) -> (P, TravelTimeRecording) {
+        let recording = self
+            .recording
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        (self.inner, recording)
+    }
+}
+
+impl<P: TravelTimeProvider> TravelTimeProvider for RecordingTravelTimeProvider<P> {
+    fn get_travel_time_matrix(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> Result<TravelTimeMatrix, TravelTimeError> {
+        let matrix = self.inner.get_travel_time_matrix(pois)?;
+        // A matrix of the wrong size breaks the provider contract and could
+        // not be replayed, so it is passed on without being recorded.
+        let _ = self
+            .recording
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(pois, matrix.clone());
+        Ok(matrix)
+    }
+
+    /// Trace the path with the inner provider; paths are not recorded.
+    fn route_geometry(
+        &self,
+        stops: &[Coord<f64>],
+    ) -> Result<Option<LineString<f64>>, TravelTimeError> {
+        self.inner.route_geometry(stops)
+    }
+}
+
+/// Answer travel-time requests from a [`TravelTimeRecording`].
+///
+/// Requests that no recorded matrix covers fail with
+/// [`TravelTimeError::NotRecorded`] rather than falling back to estimates,
+/// so a stale fixture is noticed instead of silently changing results.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ReplayTravelTimeProvider {
+    recording: TravelTimeRecording,
+}
+
+impl ReplayTravelTimeProvider {
+    /// Replay `recording`.
+    #[must_use]
+    pub const fn new(recording: TravelTimeRecording) -> Self {
+        Self { recording }
+    }
+
+    /// Replay a recording read from `reader`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error when the recording cannot be read; see
+    /// [`TravelTimeRecording::from_reader`].
+    pub fn from_reader<R: io::Read>(reader: R) -> Result<Self, TravelTimeRecordingError> {
+        TravelTimeRecording::from_reader(reader).map(Self::new)
+    }
+
+    /// Borrow the recording being replayed.
+    #[must_use]
+    pub const fn recording(&self) -> &TravelTimeRecording {
+        &self.recording
+    }
+}
+
+impl TravelTimeProvider for ReplayTravelTimeProvider {
+    fn get_travel_time_matrix(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> Result<TravelTimeMatrix, TravelTimeError> {
+        if pois.is_empty() {
+            return Err(TravelTimeError::EmptyInput);
+        }
+        self.recording
+            .lookup(pois)
+            .ok_or(TravelTimeError::NotRecorded {
+                poi_count: pois.len(),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for recording and replaying travel-time matrices.
+
+    use super::*;
+    use crate::test_support::UnitTravelTimeProvider;
+    use rstest::{fixture, rstest};
+
+    #[fixture]
+    fn pois() -> Vec<PointOfInterest> {
+        (0..3)
+            .map(|id| {
+                PointOfInterest::with_empty_tags(
+                    id,
+                    Coord {
+                        x: id as f64,
+                        y: 0.0,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn recorded(pois: &[PointOfInterest]) -> TravelTimeRecording {
+        let recorder = RecordingTravelTimeProvider::new(UnitTravelTimeProvider);
+        recorder.get_travel_time_matrix(pois).expect("matrix");
+        recorder.recording()
+    }
+
+    #[rstest]
+    fn replays_recorded_matrix(pois: Vec<PointOfInterest>) {
+        let replay = ReplayTravelTimeProvider::new(recorded(&pois));
+        let matrix = replay.get_travel_time_matrix(&pois).expect("replayed");
+        assert_eq!(
+            Some(matrix),
+            UnitTravelTimeProvider.get_travel_time_matrix(&pois).ok()
+        );
+    }
+
+    #[rstest]
+    fn reports_unrecorded_locations(pois: Vec<PointOfInterest>) {
+        let replay = ReplayTravelTimeProvider::new(recorded(&pois[..2]));
+        let err = replay
+            .get_travel_time_matrix(&pois)
+            .expect_err("third POI was never recorded");
+        assert_eq!(err, TravelTimeError::NotRecorded { poi_count: 3 });
+    }
+}
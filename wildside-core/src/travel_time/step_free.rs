@@ -0,0 +1,113 @@
+//! Pairing a provider with a second one that avoids stairs.
+
+use geo::{Coord, LineString};
+
+use crate::PointOfInterest;
+
+use super::{TravelTimeError, TravelTimeMatrix, TravelTimeProvider};
+
+/// Route with one provider, and serve step-free requests with another.
+///
+/// The second provider is typically a
+/// [`GraphTravelTimeProvider`](crate::travel_time::GraphTravelTimeProvider)
+/// over a graph without stairs, or an OSRM instance running a wheelchair
+/// profile. Solves with
+/// [`AccessibilityRequirements::step_free`](crate::AccessibilityRequirements::step_free)
+/// set route through it; every other request goes to the first provider.
+///
+/// # Examples
+/// ```rust
+/// use geo::Coord;
+/// use wildside_core::{PointOfInterest, TravelTimeProvider};
+/// use wildside_core::travel_time::{HaversineTravelTimeProvider, StepFreeTravelTimeProvider};
+///
+/// let provider = StepFreeTravelTimeProvider::new(
+///     HaversineTravelTimeProvider::default(),
+///     HaversineTravelTimeProvider::new(3.0),
+/// );
+/// let pois = [
+///     PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 }),
+///     PointOfInterest::with_empty_tags(2, Coord { x: 0.0, y: 0.01 }),
+/// ];
+/// let usual = provider.get_travel_time_matrix(&pois)?;
+/// let step_free = provider
+///     .step_free()
+///     .expect("step-free profile")
+///     .get_travel_time_matrix(&pois)?;
+/// assert!(step_free[0][1] > usual[0][1]);
+/// # Ok::<(), wildside_core::TravelTimeError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct StepFreeTravelTimeProvider<P, S> {
+    provider: P,
+    step_free: S,
+}
+
+impl<P, S> StepFreeTravelTimeProvider<P, S>
+where
+    P: TravelTimeProvider,
+    S: TravelTimeProvider,
+{
+    /// Route with `provider`, and with `step_free` when stairs must be
+    /// avoided.
+    pub const fn new(provider: P, step_free: S) -> Self {
+        Self {
+            provider,
+            step_free,
+        }
+    }
+}
+
+impl<P, S> TravelTimeProvider for StepFreeTravelTimeProvider<P, S>
+where
+    P: TravelTimeProvider,
+    S: TravelTimeProvider,
+{
+    fn get_travel_time_matrix(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> Result<TravelTimeMatrix, TravelTimeError> {
+        self.provider.get_travel_time_matrix(pois)
+    }
+
+    fn route_geometry(
+        &self,
+        stops: &[Coord<f64>],
+    ) -> Result<Option<LineString<f64>>, TravelTimeError> {
+        self.provider.route_geometry(stops)
+    }
+
+    fn step_free(&self) -> Option<&dyn TravelTimeProvider> {
+        Some(&self.step_free)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for step-free provider pairing.
+
+    use std::time::Duration;
+
+    use super::*;
+    use crate::test_support::UnitTravelTimeProvider;
+    use crate::travel_time::HaversineTravelTimeProvider;
+    use rstest::rstest;
+
+    #[rstest]
+    fn step_free_requests_use_the_second_provider() {
+        let provider = StepFreeTravelTimeProvider::new(
+            UnitTravelTimeProvider,
+            HaversineTravelTimeProvider::default(),
+        );
+        let pois = [
+            PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 }),
+            PointOfInterest::with_empty_tags(2, Coord { x: 0.0, y: 0.01 }),
+        ];
+        let usual = provider.get_travel_time_matrix(&pois).expect("matrix");
+        assert_eq!(usual[0][1], Duration::from_secs(1));
+        let step_free = provider.step_free().expect("step-free profile");
+        let matrix = step_free.get_travel_time_matrix(&pois).expect("matrix");
+        assert!(matrix[0][1] > Duration::from_mins(10));
+        assert!(step_free.step_free().is_none());
+    }
+}
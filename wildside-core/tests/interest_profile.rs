@@ -15,6 +15,7 @@ fn parse_weights(weights: &str) -> HashMap<String, f32> {
 #[case(r#"{"HiStOrY":0.8}"#, "HISTORY", Some(0.8))]
 #[case(r#"{"history":0.0}"#, "history", Some(0.0))]
 #[case(r#"{"history":1.0}"#, "history", Some(1.0))]
+#[case(r#"{"history":-0.4}"#, "history", Some(-0.4))]
 #[case(r#"{"history":0.8}"#, "art", None)]
 #[case(r#"{}"#, "history", None)]
 #[case(r#"{"history":0.8,"art":0.3}"#, "history", Some(0.8))]
@@ -41,7 +42,7 @@ fn query_weights(#[case] weights: &str, #[case] theme: &str, #[case] expected: O
 
 #[rstest]
 #[case(r#"{"history":1.5}"#, "history")]
-#[case(r#"{"history":-0.2}"#, "history")]
+#[case(r#"{"history":-1.2}"#, "history")]
 fn try_set_weight_rejects_out_of_range(#[case] weights: &str, #[case] theme: &str) {
     let map = parse_weights(weights);
     let mut profile = InterestProfile::new();
@@ -40,7 +40,10 @@ fn query_hit(
     #[from(results)] results: &RefCell<Vec<PointOfInterest>>,
 ) {
     let bbox = bbox(-1.0, -1.0, 1.0, 1.0);
-    *results.borrow_mut() = store.get_pois_in_bbox(&bbox).collect();
+    *results.borrow_mut() = store
+        .get_pois_in_bbox(&bbox)
+        .expect("query store")
+        .collect();
 }
 
 #[when("I query the bbox that excludes the origin")]
@@ -49,7 +52,10 @@ fn query_miss(
     #[from(results)] results: &RefCell<Vec<PointOfInterest>>,
 ) {
     let bbox = bbox(2.0, 2.0, 3.0, 3.0);
-    *results.borrow_mut() = store.get_pois_in_bbox(&bbox).collect();
+    *results.borrow_mut() = store
+        .get_pois_in_bbox(&bbox)
+        .expect("query store")
+        .collect();
 }
 
 #[when("I query the bbox whose edge passes through the origin")]
@@ -58,7 +64,10 @@ fn query_boundary_hit(
     #[from(results)] results: &RefCell<Vec<PointOfInterest>>,
 ) {
     let bbox = bbox(0.0, -1.0, 1.0, 1.0);
-    *results.borrow_mut() = store.get_pois_in_bbox(&bbox).collect();
+    *results.borrow_mut() = store
+        .get_pois_in_bbox(&bbox)
+        .expect("query store")
+        .collect();
 }
 
 #[when("I query the bbox defined with reversed corners but covering the origin")]
@@ -67,7 +76,10 @@ fn query_hit_reversed(
     #[from(results)] results: &RefCell<Vec<PointOfInterest>>,
 ) {
     let bbox = bbox(1.0, 1.0, -1.0, -1.0);
-    *results.borrow_mut() = store.get_pois_in_bbox(&bbox).collect();
+    *results.borrow_mut() = store
+        .get_pois_in_bbox(&bbox)
+        .expect("query store")
+        .collect();
 }
 
 #[then("one POI is returned")]
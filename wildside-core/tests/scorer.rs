@@ -3,7 +3,10 @@
 use geo::Coord;
 use rstest::rstest;
 use wildside_core::profile::test_support::InterestProfileTestExt;
-use wildside_core::{InterestProfile, PointOfInterest, Scorer, TagScorer, Theme, poi::Tags};
+use wildside_core::{
+    CompositeScorer, CompositeWeightError, InterestProfile, PointOfInterest, Scorer, TagScorer,
+    Theme, poi::Tags,
+};
 
 const TOLERANCE: f32 = 1e-6;
 
@@ -22,6 +25,8 @@ const TOLERANCE: f32 = 1e-6;
 #[case(&["art"], &[(Theme::Art, f32::MAX)], 1.0)]
 // Negative weights should not produce negative scores
 #[case(&["art"], &[(Theme::Art, -0.2)], 0.0)]
+// Disliked themes subtract from liked ones
+#[case(&["art", "history"], &[(Theme::Art, 0.7), (Theme::History, -0.3)], 0.4)]
 // Non-finite weights should yield 0.0
 #[case(&["art"], &[(Theme::Art, f32::INFINITY)], 0.0)]
 #[case(&["art"], &[(Theme::Art, f32::NAN)], 0.0)]
@@ -66,3 +71,67 @@ fn sanitize_clamps_and_filters(#[case] input: f32, #[case] expected: f32) {
     );
     assert!((result - expected).abs() <= TOLERANCE);
 }
+
+/// Scorer returning the same value for every POI.
+struct FixedScorer(f32);
+
+impl Scorer for FixedScorer {
+    fn score(&self, _poi: &PointOfInterest, _profile: &InterestProfile) -> f32 {
+        self.0
+    }
+}
+
+fn art_poi(id: u64) -> PointOfInterest {
+    let mut poi = PointOfInterest::with_empty_tags(id, Coord { x: 0.0, y: 0.0 });
+    poi.tags.insert("art".into(), String::new());
+    poi
+}
+
+#[rstest]
+// Weighted mean of the component scores
+#[case(&[(0.8, 1.0), (0.2, 1.0)], 0.5)]
+#[case(&[(1.0, 3.0), (0.0, 1.0)], 0.75)]
+// Zero-weight components do not contribute
+#[case(&[(0.4, 1.0), (1.0, 0.0)], 0.4)]
+// Out-of-range component scores are sanitised before blending
+#[case(&[(5.0, 1.0), (f32::NAN, 1.0)], 0.5)]
+// Empty and all-zero composites score nothing
+#[case(&[], 0.0)]
+#[case(&[(0.9, 0.0)], 0.0)]
+fn composite_blends_weighted_components(#[case] components: &[(f32, f32)], #[case] expected: f32) {
+    let scorer = components
+        .iter()
+        .fold(CompositeScorer::new(), |composite, &(score, weight)| {
+            composite.with_scorer(FixedScorer(score), weight)
+        });
+    let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+
+    let score = scorer.score(&poi, &InterestProfile::new());
+    assert!((score - expected).abs() <= TOLERANCE, "got {score}");
+}
+
+#[rstest]
+fn composite_score_many_matches_scoring_each_poi() {
+    let scorer = CompositeScorer::new()
+        .with_scorer(TagScorer, 2.0)
+        .with_scorer(FixedScorer(0.1), 1.0);
+    let pois = [
+        art_poi(1),
+        PointOfInterest::with_empty_tags(2, Coord { x: 0.0, y: 0.0 }),
+    ];
+    let profile = InterestProfile::new().with_weight(Theme::Art, 0.7);
+
+    let individual: Vec<f32> = pois.iter().map(|poi| scorer.score(poi, &profile)).collect();
+    assert_eq!(scorer.score_many(&pois, &profile), individual);
+    assert!(individual.first() > individual.last());
+}
+
+#[rstest]
+#[case(-0.5, CompositeWeightError::Negative)]
+#[case(f32::NAN, CompositeWeightError::NonFinite)]
+#[case(f32::INFINITY, CompositeWeightError::NonFinite)]
+fn composite_rejects_invalid_weights(#[case] weight: f32, #[case] expected: CompositeWeightError) {
+    let mut scorer = CompositeScorer::new();
+    assert_eq!(scorer.try_push(TagScorer, weight), Err(expected));
+    assert!(scorer.is_empty());
+}
@@ -0,0 +1,245 @@
+//! Tests for the helpers `SolveRequest` offers solvers: resuming a tour under
+//! way, start times, rest breaks, detour limits, and minimum route sizes.
+
+use geo::Coord;
+use rstest::{fixture, rstest};
+use std::time::Duration;
+use wildside_core::test_support::{MemoryStore, TagScorer};
+use wildside_core::{
+    Diagnostics, InterestProfile, PointOfInterest, RestBreak, Route, RouteLeg, RouteStop,
+    SolveError, SolveRequest, SolveRequestValidationError, SolveResponse, Tags, Theme,
+    VisitedPrefix,
+};
+
+#[fixture]
+fn request() -> SolveRequest {
+    SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes: 10,
+        interests: InterestProfile::new(),
+        seed: 1,
+        ..Default::default()
+    }
+}
+
+#[rstest]
+fn remainder_resumes_from_the_current_position(request: SolveRequest) {
+    let position = Coord { x: 0.5, y: 0.5 };
+    let tour = SolveRequest {
+        required_poi_ids: vec![1, 2],
+        excluded_poi_ids: vec![3],
+        initial_route: vec![1, 4],
+        visited: Some(VisitedPrefix {
+            poi_ids: vec![1],
+            position,
+            elapsed_minutes: 4,
+        }),
+        ..request
+    };
+
+    let remainder = tour.remainder().expect("tour is under way");
+
+    assert_eq!(remainder.start, position);
+    assert_eq!(remainder.end, Some(tour.start));
+    assert_eq!(remainder.duration_minutes, 6);
+    assert_eq!(remainder.required_poi_ids, vec![2]);
+    assert_eq!(remainder.excluded_poi_ids, vec![3, 1]);
+    assert_eq!(remainder.initial_route, vec![4]);
+    assert_eq!(remainder.visited, None);
+}
+
+#[rstest]
+fn remainder_starts_later(request: SolveRequest) {
+    let tour = SolveRequest {
+        start_time: Some("Sa 10:00".parse().expect("valid start time")),
+        start_at: Some("2026-10-17T10:00:00+02:00".parse().expect("valid start")),
+        visited: Some(VisitedPrefix {
+            poi_ids: vec![1],
+            position: Coord { x: 0.5, y: 0.5 },
+            elapsed_minutes: 4,
+        }),
+        ..request
+    };
+
+    let remainder = tour.remainder().expect("tour is under way");
+
+    assert_eq!(
+        remainder
+            .start_time
+            .map(|start| start.to_string())
+            .as_deref(),
+        Some("Sa 10:04")
+    );
+    assert_eq!(
+        remainder.start_at.map(|start| start.to_string()).as_deref(),
+        Some("2026-10-17T10:04:00+02:00")
+    );
+}
+
+#[rstest]
+#[expect(
+    clippy::float_cmp,
+    reason = "scores are exact sums of binary fractions"
+)]
+fn locked_prefixes_lead_the_whole_tour(request: SolveRequest) {
+    let position = Coord { x: 0.5, y: 0.0 };
+    let museum = PointOfInterest::new(1, position, Tags::from([("art".to_owned(), String::new())]));
+    let tour = SolveRequest {
+        interests: InterestProfile::new().with_weight(Theme::Art, 0.5),
+        visited: Some(VisitedPrefix {
+            poi_ids: vec![1, 9],
+            position,
+            elapsed_minutes: 4,
+        }),
+        ..request
+    };
+    let leg = |from, to, minutes| RouteLeg {
+        from,
+        to,
+        travel_duration: Duration::from_mins(1),
+        dwell: None,
+        cumulative_duration: Duration::from_mins(minutes),
+        arrival_at: None,
+    };
+    let rest = SolveResponse {
+        route: Route::with_endpoints(
+            position,
+            tour.start,
+            vec![PointOfInterest::with_empty_tags(2, position)],
+            Duration::from_mins(2),
+        )
+        .with_legs(vec![
+            leg(RouteStop::Start, RouteStop::Poi(2), 1),
+            leg(RouteStop::Poi(2), RouteStop::End, 2),
+        ]),
+        score: 0.25,
+        diagnostics: Diagnostics::default(),
+        geometry: None,
+    };
+
+    let whole = tour
+        .lock_prefix(&MemoryStore::with_poi(museum), &TagScorer, rest)
+        .expect("lock prefix");
+
+    let ids: Vec<u64> = whole.route.pois().iter().map(|poi| poi.id).collect();
+    assert_eq!(ids, vec![1, 2]);
+    assert_eq!(whole.route.start(), tour.start);
+    assert_eq!(whole.route.total_duration(), Duration::from_mins(6));
+    assert_eq!(whole.score, 0.75);
+    let first = whole.route.legs().first().expect("legs");
+    assert_eq!(first.from, RouteStop::Poi(9));
+    assert_eq!(first.cumulative_duration, Duration::from_mins(5));
+}
+
+#[rstest]
+fn absolute_start_times_give_the_week_time(request: SolveRequest) {
+    let mut tour = request;
+    tour.start_at = Some("2026-10-17T10:00:00+02:00".parse().expect("valid start"));
+    assert_eq!(
+        tour.start_week_time()
+            .map(|start| start.to_string())
+            .as_deref(),
+        Some("Sa 10:00")
+    );
+    tour.start_time = Some("Mo 09:00".parse().expect("valid start time"));
+    assert_eq!(
+        tour.validate_detailed(),
+        Err(SolveRequestValidationError::ConflictingStartTimes)
+    );
+}
+
+#[rstest]
+fn rest_breaks_need_a_start_time_and_a_valid_window(request: SolveRequest) {
+    let mut tour = request;
+    tour.rest_break = Some(RestBreak::lunch());
+    assert_eq!(
+        tour.validate_detailed(),
+        Err(SolveRequestValidationError::RestBreakWithoutStartTime)
+    );
+    tour.start_time = Some("Sa 10:00".parse().expect("valid start time"));
+    assert_eq!(tour.validate_detailed(), Ok(()));
+    tour.rest_break = Some(RestBreak {
+        earliest_minute: 900,
+        ..RestBreak::lunch()
+    });
+    assert_eq!(
+        tour.validate_detailed(),
+        Err(SolveRequestValidationError::InvalidRestBreak)
+    );
+}
+
+#[rstest]
+fn detour_limits_need_an_end_and_a_factor_of_at_least_one(request: SolveRequest) {
+    let mut tour = request;
+    tour.max_detour = Some(1.5);
+    assert_eq!(
+        tour.validate_detailed(),
+        Err(SolveRequestValidationError::MaxDetourWithoutEnd)
+    );
+    tour.end = Some(Coord { x: 0.01, y: 0.0 });
+    assert_eq!(tour.validate_detailed(), Ok(()));
+    for factor in [0.9, f32::NAN] {
+        tour.max_detour = Some(factor);
+        assert_eq!(
+            tour.validate_detailed(),
+            Err(SolveRequestValidationError::InvalidMaxDetour)
+        );
+    }
+}
+
+#[rstest]
+fn detour_budgets_only_cap_point_to_point_walks(request: SolveRequest) {
+    let mut tour = request;
+    let budget = Duration::from_mins(u64::from(tour.duration_minutes));
+    let direct = Duration::from_secs(60);
+    tour.max_detour = Some(1.5);
+    assert_eq!(tour.detour_budget(direct), budget);
+    tour.end = Some(Coord { x: 0.01, y: 0.0 });
+    assert_eq!(tour.detour_budget(direct), Duration::from_secs(90));
+    assert_eq!(tour.detour_budget(Duration::MAX), budget);
+}
+
+#[rstest]
+fn requests_without_visits_have_no_remainder(request: SolveRequest) {
+    assert_eq!(request.remainder(), None);
+}
+
+#[rstest]
+#[case::no_minimums(None, None, true)]
+#[case::enough_pois(Some(1), None, true)]
+#[case::too_few_pois(Some(2), None, false)]
+#[case::high_enough_score(None, Some(0.5), true)]
+#[case::too_low_score(None, Some(0.75), false)]
+fn minimums_hold_back_short_routes(
+    request: SolveRequest,
+    #[case] min_pois: Option<u16>,
+    #[case] min_score: Option<f32>,
+    #[case] accepted: bool,
+) {
+    let demanding = SolveRequest {
+        min_pois,
+        min_score,
+        ..request
+    };
+    let response = SolveResponse {
+        route: Route::new(
+            vec![PointOfInterest::with_empty_tags(
+                1,
+                Coord { x: 0.0, y: 0.0 },
+            )],
+            Duration::from_mins(5),
+        ),
+        score: 0.5,
+        diagnostics: Diagnostics::default(),
+        geometry: None,
+    };
+
+    match demanding.ensure_minimums(response) {
+        Ok(_) => assert!(accepted),
+        Err(SolveError::Infeasible(shortfall)) => {
+            assert!(!accepted);
+            assert_eq!(shortfall.pois, 1);
+        }
+        Err(other) => panic!("unexpected error: {other}"),
+    }
+}
@@ -0,0 +1,80 @@
+//! Tests for solve responses: diagnostics and choosing between starts.
+
+use rstest::rstest;
+use std::time::Duration;
+use wildside_core::{Diagnostics, Route, Shortfall, SolveError, SolveResponse, StartChoice};
+
+#[rstest]
+fn diagnostics_supports_clone_and_equality() {
+    let diagnostics = Diagnostics {
+        solve_time: Duration::from_millis(100),
+        candidates_evaluated: 42,
+        candidates_pruned: 2,
+    };
+
+    let cloned = diagnostics.clone();
+    assert_eq!(diagnostics, cloned);
+    assert_eq!(cloned.solve_time, Duration::from_millis(100));
+    assert_eq!(cloned.candidates_evaluated, 42);
+}
+
+#[rstest]
+fn diagnostics_debug_format() {
+    let diagnostics = Diagnostics {
+        solve_time: Duration::from_millis(50),
+        candidates_evaluated: 10,
+        candidates_pruned: 0,
+    };
+
+    let debug_str = format!("{diagnostics:?}");
+    assert!(debug_str.contains("solve_time"));
+    assert!(debug_str.contains("candidates_evaluated"));
+}
+
+#[cfg(feature = "serde")]
+#[rstest]
+fn diagnostics_serde_round_trip() {
+    let original = Diagnostics {
+        solve_time: Duration::from_millis(123),
+        candidates_evaluated: 456,
+        candidates_pruned: 7,
+    };
+
+    let json = serde_json::to_string(&original).expect("serialization should succeed");
+    let restored: Diagnostics =
+        serde_json::from_str(&json).expect("deserialization should succeed");
+
+    assert_eq!(original, restored);
+}
+
+#[cfg(feature = "serde")]
+#[rstest]
+fn diagnostics_without_pruned_count_deserialize_as_zero() {
+    let json = r#"{"solve_time":{"secs":1,"nanos":0},"candidates_evaluated":5}"#;
+    let restored: Diagnostics = serde_json::from_str(json).expect("deserialization should succeed");
+
+    assert_eq!(restored.candidates_pruned, 0);
+}
+
+#[rstest]
+fn start_choice_passes_over_infeasible_starts_only() {
+    let only_infeasible = StartChoice::best([
+        Err(SolveError::InfeasibleRequirement),
+        Err(SolveError::Infeasible(Shortfall::default())),
+    ]);
+    assert!(matches!(
+        only_infeasible,
+        Err(SolveError::InfeasibleRequirement)
+    ));
+    let cancelled = StartChoice::best([
+        Err(SolveError::InfeasibleRequirement),
+        Err(SolveError::Cancelled),
+        Ok(SolveResponse {
+            route: Route::empty(),
+            score: 1.0,
+            diagnostics: Diagnostics::default(),
+            geometry: None,
+        }),
+    ]);
+    assert!(matches!(cancelled, Err(SolveError::Cancelled)));
+}
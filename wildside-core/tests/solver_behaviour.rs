@@ -5,13 +5,14 @@
     reason = "rstest-bdd generates guard variables derived from fixture parameter names"
 )]
 
-use geo::Coord;
+use geo::{Coord, LineString, Polygon};
 use rstest::{fixture, rstest};
 use rstest_bdd_macros::{given, scenario, then, when};
 use std::cell::RefCell;
 use std::time::Duration;
 use wildside_core::{
     Diagnostics, InterestProfile, Route, SolveError, SolveRequest, SolveResponse, Solver,
+    VisitedPrefix,
 };
 
 struct DummySolver;
@@ -25,6 +26,7 @@ impl Solver for DummySolver {
             route: Route::new(Vec::new(), Duration::from_secs(0)),
             score: 0.0,
             diagnostics: Diagnostics::default(),
+            geometry: None,
         })
     }
 }
@@ -36,11 +38,10 @@ fn solver_returns_expected(#[case] duration: u16, #[case] should_succeed: bool)
     let solver = DummySolver;
     let req = SolveRequest {
         start: Coord { x: 0.0, y: 0.0 },
-        end: None,
         duration_minutes: duration,
         interests: InterestProfile::new(),
         seed: 1,
-        max_nodes: None,
+        ..Default::default()
     };
     let validation = req.validate();
     let result = solver.solve(&req);
@@ -62,19 +63,79 @@ fn solver_returns_expected(#[case] duration: u16, #[case] should_succeed: bool)
 #[rstest]
 #[case::zero_duration(SolveRequest {
     start: Coord { x: 0.0, y: 0.0 },
-    end: None,
     duration_minutes: 0,
     interests: InterestProfile::new(),
     seed: 1,
-    max_nodes: None,
+    ..Default::default()
 })]
 #[case::zero_max_nodes(SolveRequest {
     start: Coord { x: 0.0, y: 0.0 },
-    end: None,
     duration_minutes: 10,
     interests: InterestProfile::new(),
     seed: 1,
     max_nodes: Some(0),
+    ..Default::default()
+})]
+#[case::non_finite_avoid_area(SolveRequest {
+    start: Coord { x: 0.0, y: 0.0 },
+    duration_minutes: 10,
+    interests: InterestProfile::new(),
+    seed: 1,
+    avoid_areas: vec![
+        Polygon::new(
+            LineString::from(vec![(0.0, 0.0), (f64::NAN, 0.0), (0.0, 1.0)]),
+            Vec::new(),
+        ),
+    ],
+    ..Default::default()
+})]
+#[case::zero_max_solve_ms(SolveRequest {
+    start: Coord { x: 0.0, y: 0.0 },
+    duration_minutes: 10,
+    interests: InterestProfile::new(),
+    seed: 1,
+    max_solve_ms: Some(0),
+    ..Default::default()
+})]
+#[case::zero_max_leg_minutes(SolveRequest {
+    start: Coord { x: 0.0, y: 0.0 },
+    duration_minutes: 10,
+    interests: InterestProfile::new(),
+    seed: 1,
+    max_leg_minutes: Some(0),
+    ..Default::default()
+})]
+#[case::non_finite_min_score(SolveRequest {
+    start: Coord { x: 0.0, y: 0.0 },
+    duration_minutes: 10,
+    interests: InterestProfile::new(),
+    seed: 1,
+    min_score: Some(f32::NAN),
+    ..Default::default()
+})]
+#[case::non_finite_position(SolveRequest {
+    start: Coord { x: 0.0, y: 0.0 },
+    duration_minutes: 10,
+    interests: InterestProfile::new(),
+    seed: 1,
+    visited: Some(VisitedPrefix {
+        poi_ids: vec![1],
+        position: Coord { x: f64::NAN, y: 0.0 },
+        elapsed_minutes: 5,
+    }),
+    ..Default::default()
+})]
+#[case::budget_spent(SolveRequest {
+    start: Coord { x: 0.0, y: 0.0 },
+    duration_minutes: 10,
+    interests: InterestProfile::new(),
+    seed: 1,
+    visited: Some(VisitedPrefix {
+        poi_ids: vec![1],
+        position: Coord { x: 0.0, y: 0.0 },
+        elapsed_minutes: 10,
+    }),
+    ..Default::default()
 })]
 fn invalid_requests_are_rejected(#[case] req: SolveRequest) {
     let solver = DummySolver;
@@ -95,11 +156,10 @@ fn non_finite_start_is_invalid(#[case] start: Coord<f64>) {
     let solver = DummySolver;
     let req = SolveRequest {
         start,
-        end: None,
         duration_minutes: 10,
         interests: InterestProfile::new(),
         seed: 1,
-        max_nodes: None,
+        ..Default::default()
     };
 
     let err = req.validate().expect_err("expected InvalidRequest");
@@ -122,7 +182,7 @@ fn non_finite_end_is_invalid(#[case] end: Coord<f64>) {
         duration_minutes: 10,
         interests: InterestProfile::new(),
         seed: 1,
-        max_nodes: None,
+        ..Default::default()
     };
 
     let err = req.validate().expect_err("expected InvalidRequest");
@@ -137,11 +197,11 @@ fn positive_max_nodes_is_accepted() {
     let solver = DummySolver;
     let req = SolveRequest {
         start: Coord { x: 0.0, y: 0.0 },
-        end: None,
         duration_minutes: 10,
         interests: InterestProfile::new(),
         seed: 1,
         max_nodes: Some(25),
+        ..Default::default()
     };
 
     req.validate().expect("expected valid request");
@@ -150,63 +210,6 @@ fn positive_max_nodes_is_accepted() {
     assert_eq!(response.score, 0.0);
 }
 
-#[rstest]
-fn response_includes_diagnostics() {
-    let solver = DummySolver;
-    let req = SolveRequest {
-        start: Coord { x: 0.0, y: 0.0 },
-        end: None,
-        duration_minutes: 10,
-        interests: InterestProfile::new(),
-        seed: 1,
-        max_nodes: None,
-    };
-
-    let response = solver.solve(&req).expect("expected solver success");
-    assert_eq!(response.diagnostics.solve_time, Duration::from_secs(0));
-    assert_eq!(response.diagnostics.candidates_evaluated, 0);
-}
-
-#[rstest]
-fn diagnostics_supports_clone_and_equality() {
-    let diagnostics = Diagnostics {
-        solve_time: Duration::from_millis(100),
-        candidates_evaluated: 42,
-    };
-
-    let cloned = diagnostics.clone();
-    assert_eq!(diagnostics, cloned);
-    assert_eq!(cloned.solve_time, Duration::from_millis(100));
-    assert_eq!(cloned.candidates_evaluated, 42);
-}
-
-#[rstest]
-fn diagnostics_debug_format() {
-    let diagnostics = Diagnostics {
-        solve_time: Duration::from_millis(50),
-        candidates_evaluated: 10,
-    };
-
-    let debug_str = format!("{diagnostics:?}");
-    assert!(debug_str.contains("solve_time"));
-    assert!(debug_str.contains("candidates_evaluated"));
-}
-
-#[cfg(feature = "serde")]
-#[rstest]
-fn diagnostics_serde_round_trip() {
-    let original = Diagnostics {
-        solve_time: Duration::from_millis(123),
-        candidates_evaluated: 456,
-    };
-
-    let json = serde_json::to_string(&original).expect("serialization should succeed");
-    let restored: Diagnostics =
-        serde_json::from_str(&json).expect("deserialization should succeed");
-
-    assert_eq!(original, restored);
-}
-
 #[fixture]
 fn solver() -> DummySolver {
     DummySolver
@@ -216,11 +219,10 @@ fn solver() -> DummySolver {
 fn request() -> RefCell<SolveRequest> {
     RefCell::new(SolveRequest {
         start: Coord { x: 0.0, y: 0.0 },
-        end: None,
         duration_minutes: 10,
         interests: InterestProfile::new(),
         seed: 1,
-        max_nodes: None,
+        ..Default::default()
     })
 }
 
@@ -230,6 +232,7 @@ fn outcome() -> RefCell<Result<SolveResponse, SolveError>> {
         route: Route::new(Vec::new(), Duration::from_secs(0)),
         score: 0.0,
         diagnostics: Diagnostics::default(),
+        geometry: None,
     }))
 }
 
@@ -242,11 +245,11 @@ fn given_solver(#[from(solver)] _solver: &DummySolver) {
 fn given_valid_request(#[from(request)] request: &RefCell<SolveRequest>) {
     *request.borrow_mut() = SolveRequest {
         start: Coord { x: 0.0, y: 0.0 },
-        end: None,
         duration_minutes: 10,
         interests: InterestProfile::new(),
         seed: 1,
         max_nodes: Some(10),
+        ..Default::default()
     };
 }
 
@@ -254,11 +257,10 @@ fn given_valid_request(#[from(request)] request: &RefCell<SolveRequest>) {
 fn given_zero_duration_request(#[from(request)] request: &RefCell<SolveRequest>) {
     *request.borrow_mut() = SolveRequest {
         start: Coord { x: 0.0, y: 0.0 },
-        end: None,
         duration_minutes: 0,
         interests: InterestProfile::new(),
         seed: 1,
-        max_nodes: None,
+        ..Default::default()
     };
 }
 
@@ -269,11 +271,10 @@ fn given_non_finite_request(#[from(request)] request: &RefCell<SolveRequest>) {
             x: f64::NAN,
             y: 0.0,
         },
-        end: None,
         duration_minutes: 10,
         interests: InterestProfile::new(),
         seed: 1,
-        max_nodes: None,
+        ..Default::default()
     };
 }
 
@@ -281,11 +282,11 @@ fn given_non_finite_request(#[from(request)] request: &RefCell<SolveRequest>) {
 fn given_zero_max_nodes_request(#[from(request)] request: &RefCell<SolveRequest>) {
     *request.borrow_mut() = SolveRequest {
         start: Coord { x: 0.0, y: 0.0 },
-        end: None,
         duration_minutes: 10,
         interests: InterestProfile::new(),
         seed: 1,
         max_nodes: Some(0),
+        ..Default::default()
     };
 }
 
@@ -0,0 +1,166 @@
+//! Tests for solving through a minimal `Solver`: the diagnostics it reports
+//! and the trait's default cancellation, alternatives, budget frontiers, and
+//! choice between starts.
+
+use geo::Coord;
+use rstest::{fixture, rstest};
+use std::time::Duration;
+use wildside_core::{
+    CancellationToken, Diagnostics, InterestProfile, Route, SolveError, SolveRequest,
+    SolveResponse, Solver,
+};
+
+/// Returns an empty route for every valid request.
+struct DummySolver;
+
+impl Solver for DummySolver {
+    fn solve(&self, request: &SolveRequest) -> Result<SolveResponse, SolveError> {
+        request.validate()?;
+        Ok(SolveResponse {
+            route: Route::empty(),
+            score: 0.0,
+            diagnostics: Diagnostics::default(),
+            geometry: None,
+        })
+    }
+}
+
+#[fixture]
+fn request() -> SolveRequest {
+    SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes: 10,
+        interests: InterestProfile::new(),
+        seed: 1,
+        ..Default::default()
+    }
+}
+
+#[rstest]
+fn response_includes_diagnostics(request: SolveRequest) {
+    let response = DummySolver
+        .solve(&request)
+        .expect("expected solver success");
+    assert_eq!(response.diagnostics.solve_time, Duration::from_secs(0));
+    assert_eq!(response.diagnostics.candidates_evaluated, 0);
+}
+
+/// Cancels its token partway through solving, as a disconnecting client
+/// would.
+struct CancelledMidway {
+    token: CancellationToken,
+}
+
+impl Solver for CancelledMidway {
+    fn solve(&self, request: &SolveRequest) -> Result<SolveResponse, SolveError> {
+        self.token.cancel();
+        DummySolver.solve(request)
+    }
+}
+
+#[rstest]
+#[case::not_cancelled(false, false, false)]
+#[case::cancelled_before(true, false, true)]
+#[case::cancelled_midway(false, true, true)]
+fn solve_with_cancel_honours_the_token(
+    #[case] cancel_first: bool,
+    #[case] cancel_midway: bool,
+    #[case] cancelled: bool,
+    request: SolveRequest,
+) {
+    let token = CancellationToken::new();
+    if cancel_first {
+        token.cancel();
+    }
+    let solver = CancelledMidway {
+        token: if cancel_midway {
+            token.clone()
+        } else {
+            CancellationToken::new()
+        },
+    };
+    let outcome = solver.solve_with_cancel(&request, &token);
+
+    if cancelled {
+        assert!(matches!(outcome, Err(SolveError::Cancelled)));
+    } else {
+        assert!(outcome.is_ok());
+    }
+}
+
+#[rstest]
+#[case(0, 0)]
+#[case(3, 1)]
+fn default_alternatives_return_the_single_route(
+    #[case] count: usize,
+    #[case] expected: usize,
+    request: SolveRequest,
+) {
+    let responses = DummySolver
+        .solve_alternatives(&request, count)
+        .expect("expected solve success");
+    assert_eq!(responses.len(), expected);
+}
+
+#[rstest]
+fn default_alternatives_validate_the_request(request: SolveRequest) {
+    let mut invalid = request;
+    invalid.duration_minutes = 0;
+    let err = DummySolver
+        .solve_alternatives(&invalid, 0)
+        .expect_err("expected InvalidRequest");
+    assert!(matches!(err, SolveError::InvalidRequest));
+}
+
+/// Visits nothing but scores one point per minute, up to 90 minutes.
+struct CappedSolver;
+
+impl Solver for CappedSolver {
+    fn solve(&self, request: &SolveRequest) -> Result<SolveResponse, SolveError> {
+        request.validate()?;
+        let minutes = request.duration_minutes.min(90);
+        Ok(SolveResponse {
+            route: Route::new(Vec::new(), Duration::from_mins(u64::from(minutes))),
+            score: f32::from(minutes),
+            diagnostics: Diagnostics::default(),
+            geometry: None,
+        })
+    }
+}
+
+#[rstest]
+fn default_frontier_drops_budgets_that_gain_nothing(request: SolveRequest) {
+    let frontier = CappedSolver
+        .solve_frontier(&request, &[120, 30, 90, 60])
+        .expect("expected solve success");
+    let scores: Vec<f32> = frontier.iter().map(|response| response.score).collect();
+    assert_eq!(scores, vec![30.0, 60.0, 90.0]);
+}
+
+#[rstest]
+fn default_frontier_rejects_zero_budgets(request: SolveRequest) {
+    let err = CappedSolver
+        .solve_frontier(&request, &[30, 0])
+        .expect_err("expected InvalidRequest");
+    assert!(matches!(err, SolveError::InvalidRequest));
+}
+
+#[rstest]
+fn default_start_choice_keeps_the_first_of_equal_routes(request: SolveRequest) {
+    let starts = [Coord { x: 1.0, y: 0.0 }, Coord { x: 0.0, y: 0.0 }];
+    let choice = DummySolver
+        .solve_from_starts(&request, &starts)
+        .expect("both starts are valid");
+    assert_eq!(choice.start_index, 0);
+}
+
+#[rstest]
+#[case::no_starts(Vec::new())]
+#[case::non_finite_start(vec![Coord { x: 0.0, y: 0.0 }, Coord { x: f64::NAN, y: 0.0 }])]
+fn default_start_choice_rejects_invalid_starts(
+    request: SolveRequest,
+    #[case] starts: Vec<Coord<f64>>,
+) {
+    let outcome = DummySolver.solve_from_starts(&request, &starts);
+    assert!(matches!(outcome, Err(SolveError::InvalidRequest)));
+}
@@ -145,7 +145,10 @@ fn query_bbox_helper(world: &PoiStoreWorld, coords: (f64, f64, f64, f64)) {
         let store = borrowed_store
             .as_ref()
             .expect("store should be available for querying");
-        store.get_pois_in_bbox(&bbox).collect()
+        store
+            .get_pois_in_bbox(&bbox)
+            .expect("query store")
+            .collect()
     };
     world.query_results().replace(results);
 }
@@ -22,7 +22,7 @@ fn provider() -> UnitTravelTimeProvider {
 
 #[fixture]
 fn result() -> RefCell<Result<TravelTimeMatrix, TravelTimeError>> {
-    RefCell::new(Ok(Vec::new()))
+    RefCell::new(Ok(TravelTimeMatrix::default()))
 }
 
 #[given("a provider returning unit travel times")]
@@ -110,8 +110,8 @@ fn then_three(#[from(result)] result: &RefCell<Result<TravelTimeMatrix, TravelTi
 
 fn assert_unit_matrix(matrix: &TravelTimeMatrix, expected: usize) {
     assert_eq!(matrix.len(), expected);
-    assert!(matrix.iter().all(|row| row.len() == expected));
-    for (i, row) in matrix.iter().enumerate() {
+    assert!(matrix.rows().all(|row| row.len() == expected));
+    for (i, row) in matrix.rows().enumerate() {
         for (j, &cell) in row.iter().enumerate() {
             if i == j {
                 assert_eq!(cell, Duration::ZERO, "expected diagonal to be zero");
@@ -0,0 +1,157 @@
+//! Criterion benchmark for persisting Wikidata claims into `SQLite`.
+//!
+//! Generates one million entity-valued claims spread over linked entities and
+//! compares [`persist_claims`], which groups rows per table and writes them
+//! with multi-row `INSERT` statements, with a baseline that executes one
+//! `INSERT` per row in extraction order, as the store did before.
+//!
+//! Run with:
+//! ```bash
+//! cargo bench --package wildside-data --bench claims_persist
+//! ```
+
+use std::time::Duration;
+
+use criterion::{BatchSize, BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use rusqlite::Connection;
+use wildside_data::wikidata::etl::{Claim, ClaimValue, EntityClaims};
+use wildside_data::wikidata::store::{initialise_schema, persist_claims};
+
+/// Linked entities in the generated extraction.
+const ENTITY_COUNT: u64 = 100_000;
+/// Entity-valued claims per entity.
+const CLAIMS_PER_ENTITY: u64 = 10;
+
+/// Build the extraction: each entity links to one POI and carries
+/// [`CLAIMS_PER_ENTITY`] claims drawn from a small pool of values.
+fn generate_claims() -> Vec<EntityClaims> {
+    (1..=ENTITY_COUNT)
+        .map(|id| EntityClaims {
+            entity_id: format!("Q{id}"),
+            linked_poi_ids: vec![id],
+            claims: (0..CLAIMS_PER_ENTITY)
+                .map(|index| {
+                    Claim::entity(
+                        format!("P{}", 31 + index),
+                        format!("Q{}", 9_000 + (id + index) % 500),
+                    )
+                })
+                .collect(),
+            image_files: Vec::new(),
+            sitelink_count: Some(5),
+        })
+        .collect()
+}
+
+/// Open a fresh database holding every linked POI and the claims schema.
+fn seeded_connection() -> Connection {
+    let mut connection = Connection::open_in_memory().expect("open in-memory database");
+    connection
+        .execute_batch(
+            "CREATE TABLE pois (id INTEGER PRIMARY KEY, lon REAL, lat REAL, tags TEXT);
+             WITH RECURSIVE ids(id) AS (
+                 SELECT 1 UNION ALL SELECT id + 1 FROM ids WHERE id < 100000
+             )
+             INSERT INTO pois SELECT id, 13.4, 52.5, '{}' FROM ids;",
+        )
+        .expect("seed POIs");
+    initialise_schema(&mut connection).expect("initialise schema");
+    connection
+}
+
+/// Persist `claims` with one `INSERT` per row, mirroring the unbatched store.
+fn persist_row_per_insert(connection: &mut Connection, claims: &[EntityClaims]) {
+    let transaction = connection.transaction().expect("begin transaction");
+    {
+        let mut insert_entity = transaction
+            .prepare_cached(
+                "INSERT INTO wikidata_entities (entity_id) VALUES (?1) \
+                 ON CONFLICT(entity_id) DO NOTHING",
+            )
+            .expect("prepare entity insert");
+        let mut insert_claim = transaction
+            .prepare_cached(
+                "INSERT INTO wikidata_entity_claims (entity_id, property_id, value_entity_id) \
+                 VALUES (?1, ?2, ?3) \
+                 ON CONFLICT(entity_id, property_id, value_entity_id) DO NOTHING",
+            )
+            .expect("prepare claim insert");
+        let mut upsert_sitelinks = transaction
+            .prepare_cached(
+                "INSERT INTO wikidata_entity_sitelinks (entity_id, sitelink_count) \
+                 VALUES (?1, ?2) \
+                 ON CONFLICT(entity_id) DO UPDATE SET sitelink_count = excluded.sitelink_count",
+            )
+            .expect("prepare sitelink upsert");
+        let mut insert_link = transaction
+            .prepare_cached(
+                "INSERT INTO poi_wikidata_links (poi_id, entity_id) VALUES (?1, ?2) \
+                 ON CONFLICT(poi_id, entity_id) DO NOTHING",
+            )
+            .expect("prepare link insert");
+        for entity in claims {
+            let entity_id = entity.entity_id.as_str();
+            insert_entity.execute([entity_id]).expect("insert entity");
+            let entity_claims = entity.claims.iter().filter_map(|claim| match &claim.value {
+                ClaimValue::Entity(value) => Some((&claim.property_id, value)),
+                ClaimValue::Literal(_) => None,
+            });
+            for (property_id, value) in entity_claims {
+                insert_entity.execute([value]).expect("insert value entity");
+                insert_claim
+                    .execute((entity_id, property_id, value))
+                    .expect("insert claim");
+            }
+            if let Some(count) = entity.sitelink_count {
+                upsert_sitelinks
+                    .execute((entity_id, count))
+                    .expect("upsert sitelinks");
+            }
+            for poi_id in &entity.linked_poi_ids {
+                insert_link
+                    .execute((poi_id.cast_signed(), entity_id))
+                    .expect("insert link");
+            }
+        }
+    }
+    transaction.commit().expect("commit transaction");
+}
+
+fn bench_persist(c: &mut Criterion) {
+    let claims = generate_claims();
+    let claim_count = ENTITY_COUNT * CLAIMS_PER_ENTITY;
+
+    let mut group = c.benchmark_group("claims_persist");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(30));
+    group.throughput(Throughput::Elements(claim_count));
+    group.bench_with_input(
+        BenchmarkId::new("row_per_insert", claim_count),
+        &claims,
+        |b, claims| {
+            b.iter_batched(
+                seeded_connection,
+                |mut connection| persist_row_per_insert(&mut connection, claims),
+                BatchSize::PerIteration,
+            );
+        },
+    );
+    group.bench_with_input(
+        BenchmarkId::new("persist_claims", claim_count),
+        &claims,
+        |b, claims| {
+            b.iter_batched(
+                seeded_connection,
+                |mut connection| {
+                    persist_claims(&mut connection, claims)
+                        .unwrap_or_else(|err| panic!("benchmark claims should persist: {err}"));
+                },
+                BatchSize::PerIteration,
+            );
+        },
+    );
+    group.finish();
+}
+
+criterion_group!(benches, bench_persist);
+criterion_main!(benches);
@@ -0,0 +1,172 @@
+//! Criterion benchmark for Wikidata dump claim extraction.
+//!
+//! Generates a deterministic dump of entities shaped like real Wikidata
+//! records (labels, statements with qualifiers and references, sitelinks),
+//! links half of them to POIs, and measures extraction throughput in bytes.
+//!
+//! Run with:
+//! ```bash
+//! cargo bench --package wildside-data --bench etl_parse
+//! ```
+
+use std::fmt::Write as _;
+use std::io::Cursor;
+use std::time::Duration;
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use geo::Coord;
+use wildside_core::{PointOfInterest, Tags};
+use wildside_data::wikidata::etl::{
+    ClaimPropertySet, ClaimSelection, PoiEntityLinks, extract_linked_entity_claims_for,
+};
+
+/// Entities in the generated dump.
+const ENTITY_COUNT: u64 = 5_000;
+
+/// Append one statement for `property` to `out`.
+fn statement(out: &mut String, entity: u64, property: &str, datavalue: &str) {
+    let _ = write!(
+        out,
+        concat!(
+            r#"{{"mainsnak":{{"snaktype":"value","property":"{property}","datavalue":{datavalue}}},"#,
+            r#""type":"statement","id":"Q{entity}${property}-5e1d","rank":"normal","#,
+            r#""qualifiers":{{"P580":[{{"snaktype":"value","property":"P580","datavalue":"#,
+            r#"{{"value":{{"time":"+1990-01-01T00:00:00Z","precision":11}},"type":"time"}}}}]}},"#,
+            r#""references":[{{"hash":"d5847b9b6032aa8b13dae3c2dfd9ed5d114d21b3","snaks":"#,
+            r#"{{"P143":[{{"snaktype":"value","property":"P143","datavalue":{{"value":"#,
+            r#"{{"entity-type":"item","numeric-id":328,"id":"Q328"}},"type":"wikibase-entityid"}}}}]}}}}]}}"#,
+        ),
+        property = property,
+        entity = entity,
+        datavalue = datavalue,
+    );
+}
+
+fn entity_value(id: u64) -> String {
+    format!(
+        r#"{{"value":{{"entity-type":"item","numeric-id":{id},"id":"Q{id}"}},"type":"wikibase-entityid"}}"#
+    )
+}
+
+fn string_value(value: &str) -> String {
+    format!(r#"{{"value":"{value}","type":"string"}}"#)
+}
+
+/// Render one entity as a dump line.
+fn entity_line(id: u64) -> String {
+    let mut line = format!(
+        concat!(
+            r#"{{"type":"item","id":"Q{id}","labels":{{"en":{{"language":"en","value":"Landmark {id}"}},"#,
+            r#""de":{{"language":"de","value":"Wahrzeichen {id}"}}}},"#,
+            r#""descriptions":{{"en":{{"language":"en","value":"historic building number {id}"}}}},"#,
+            r#""claims":{{"#,
+        ),
+        id = id
+    );
+    let properties = [
+        ("P31", entity_value(41176 + id % 7)),
+        ("P17", entity_value(183)),
+        ("P131", entity_value(64)),
+        ("P1435", entity_value(9259 + id % 3)),
+        ("P149", entity_value(176483)),
+        ("P18", string_value(&format!("Landmark {id}.jpg"))),
+        ("P373", string_value(&format!("Landmark {id}"))),
+        (
+            "P571",
+            r#"{"value":{"time":"+1871-00-00T00:00:00Z","precision":9},"type":"time"}"#.to_owned(),
+        ),
+        (
+            "P1082",
+            r#"{"value":{"amount":"+1250","unit":"1"},"type":"quantity"}"#.to_owned(),
+        ),
+        (
+            "P625",
+            r#"{"value":{"latitude":52.5,"longitude":13.4,"precision":0.0001},"type":"globecoordinate"}"#
+                .to_owned(),
+        ),
+    ];
+    for (index, (property, datavalue)) in properties.iter().enumerate() {
+        if index > 0 {
+            line.push(',');
+        }
+        let _ = write!(line, r#""{property}":["#);
+        statement(&mut line, id, property, datavalue);
+        line.push(',');
+        statement(&mut line, id, property, datavalue);
+        line.push(']');
+    }
+    line.push_str(r#"},"sitelinks":{"#);
+    for (index, site) in ["enwiki", "dewiki", "frwiki", "eswiki", "commonswiki"]
+        .iter()
+        .enumerate()
+    {
+        if index > 0 {
+            line.push(',');
+        }
+        let _ = write!(
+            line,
+            r#""{site}":{{"site":"{site}","title":"Landmark {id}","badges":[]}}"#
+        );
+    }
+    line.push_str("}}");
+    line
+}
+
+/// Render the full dump in the array-per-line layout Wikidata publishes.
+fn generate_dump() -> Vec<u8> {
+    let mut dump = String::from("[\n");
+    for id in 1..=ENTITY_COUNT {
+        dump.push_str(&entity_line(id));
+        dump.push_str(if id == ENTITY_COUNT { "\n" } else { ",\n" });
+    }
+    dump.push_str("]\n");
+    dump.into_bytes()
+}
+
+/// Link every other entity to a POI so the filter lets half through.
+fn linked_pois() -> Vec<PointOfInterest> {
+    (1..=ENTITY_COUNT)
+        .step_by(2)
+        .map(|id| {
+            PointOfInterest::new(
+                id,
+                Coord { x: 13.4, y: 52.5 },
+                Tags::from([("wikidata".into(), format!("Q{id}"))]),
+            )
+        })
+        .collect()
+}
+
+fn bench_extract(c: &mut Criterion) {
+    let dump = generate_dump();
+    let pois = linked_pois();
+    let links = PoiEntityLinks::from_pois(pois.iter());
+    let filter = links.to_link_filter();
+    let properties = ClaimPropertySet::default();
+
+    let mut group = c.benchmark_group("etl_parse");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(5));
+    group.throughput(Throughput::Bytes(
+        u64::try_from(dump.len()).unwrap_or(u64::MAX),
+    ));
+    group.bench_with_input(
+        BenchmarkId::new("extract_linked_entity_claims", ENTITY_COUNT),
+        &dump,
+        |b, dump| {
+            b.iter(|| {
+                extract_linked_entity_claims_for(
+                    Cursor::new(dump.as_slice()),
+                    &links,
+                    &filter,
+                    ClaimSelection::new(&properties),
+                )
+                .unwrap_or_else(|err| panic!("benchmark dump should parse: {err}"))
+            });
+        },
+    );
+    group.finish();
+}
+
+criterion_group!(benches, bench_extract);
+criterion_main!(benches);
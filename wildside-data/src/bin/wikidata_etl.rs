@@ -25,7 +25,10 @@ async fn main() {
 async fn run(arguments: Arguments) -> Result<(), CliError> {
     let endpoint = arguments.endpoint.clone();
     let user_agent = arguments.user_agent.clone();
-    let source = HttpDumpSource::new(endpoint).with_user_agent(user_agent);
+    let mut source = HttpDumpSource::new(endpoint).with_user_agent(user_agent);
+    if let Some(path) = arguments.manifest_cache.clone() {
+        source = source.with_manifest_cache(path);
+    }
     execute(arguments, source).await
 }
 
@@ -101,6 +104,9 @@ struct Arguments {
     /// Custom HTTP user agent string
     #[arg(long, value_name = "agent", default_value = DEFAULT_USER_AGENT)]
     user_agent: String,
+    /// Cache the resolved status manifest and revalidate it on later runs
+    #[arg(long, value_name = "path")]
+    manifest_cache: Option<PathBuf>,
     /// Overwrite the output file if it exists
     #[arg(long)]
     overwrite: bool,
@@ -174,6 +180,7 @@ mod tests {
         assert_eq!(args.metadata_db, None);
         assert_eq!(args.endpoint, "https://dumps.wikimedia.org");
         assert_eq!(args.user_agent, DEFAULT_USER_AGENT);
+        assert_eq!(args.manifest_cache, None);
         assert!(!args.overwrite);
     }
 
@@ -193,6 +200,8 @@ mod tests {
             "https://mirror.local",
             "--user-agent",
             "agent/1.0",
+            "--manifest-cache",
+            "cache/dumpstatus.json",
             "--overwrite",
         ])
         .expect("arguments should parse");
@@ -200,6 +209,10 @@ mod tests {
         assert_eq!(args.metadata_db.as_deref(), Some(metadata.as_path()));
         assert_eq!(args.endpoint, "https://mirror.local");
         assert_eq!(args.user_agent, "agent/1.0");
+        assert_eq!(
+            args.manifest_cache.as_deref(),
+            Some(Path::new("cache/dumpstatus.json"))
+        );
         assert!(args.overwrite);
     }
 
@@ -226,6 +239,7 @@ mod tests {
             metadata_db: None,
             endpoint: base_url.clone().into_inner(),
             user_agent: DEFAULT_USER_AGENT.to_owned(),
+            manifest_cache: None,
             overwrite: false,
         };
         let source = StubSource::new(base_url, manifest, archive);
@@ -0,0 +1,179 @@
+//! Reading and writing the length-prefixed, digest-checked bundle entries.
+
+use std::{
+    ffi::OsStr,
+    fs::File,
+    io::{self, BufWriter, Read, Write},
+    path::Path,
+};
+
+use sha2::{Digest, Sha256};
+
+use super::{BundleError, DIGEST_LEN};
+
+/// Append the file at `path` to `out` as entry `name`, returning its length.
+pub(super) fn write_entry(
+    out: &mut impl Write,
+    name: &str,
+    path: &Path,
+    bundle_path: &Path,
+) -> Result<u64, BundleError> {
+    let read_error = |source| BundleError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let write_error = |source| BundleError::Io {
+        path: bundle_path.to_path_buf(),
+        source,
+    };
+    let mut file = File::open(path).map_err(read_error)?;
+    let len = file.metadata().map_err(read_error)?.len();
+    let name_len = u16::try_from(name.len()).map_err(|err| write_error(io::Error::other(err)))?;
+    out.write_all(&name_len.to_le_bytes())
+        .map_err(write_error)?;
+    out.write_all(name.as_bytes()).map_err(write_error)?;
+    out.write_all(&len.to_le_bytes()).map_err(write_error)?;
+
+    let mut hashing = HashingWriter::new(&mut *out);
+    let copied = io::copy(&mut (&mut file).take(len), &mut hashing).map_err(read_error)?;
+    if copied != len {
+        return Err(read_error(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "artefact shrank while it was being bundled",
+        )));
+    }
+    let digest = hashing.finish();
+    out.write_all(&digest).map_err(write_error)?;
+    Ok(len)
+}
+
+/// Read an entry name and reject any that could leave the extraction
+/// directory.
+pub(super) fn read_entry_name(
+    reader: &mut impl Read,
+    bundle_path: &Path,
+) -> Result<String, BundleError> {
+    let mut name_len = [0_u8; 2];
+    read_header_field(reader, &mut name_len, bundle_path)?;
+    let mut raw = vec![0_u8; usize::from(u16::from_le_bytes(name_len))];
+    read_header_field(reader, &mut raw, bundle_path)?;
+    let name = String::from_utf8(raw).map_err(|_| BundleError::Corrupt {
+        path: bundle_path.to_path_buf(),
+        reason: "entry name is not valid UTF-8".to_owned(),
+    })?;
+    let is_plain_file_name = !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.contains(['/', '\\', '\0'])
+        && Path::new(&name).file_name() == Some(OsStr::new(&name));
+    if is_plain_file_name {
+        Ok(name)
+    } else {
+        Err(BundleError::UnsafeEntryName {
+            path: bundle_path.to_path_buf(),
+            name,
+        })
+    }
+}
+
+/// Copy the next entry's data into `dir/name` and verify its digest,
+/// returning the entry length.
+pub(super) fn extract_entry(
+    reader: &mut impl Read,
+    name: &str,
+    dir: &Path,
+    bundle_path: &Path,
+) -> Result<u64, BundleError> {
+    let mut len_bytes = [0_u8; 8];
+    read_header_field(reader, &mut len_bytes, bundle_path)?;
+    let len = u64::from_le_bytes(len_bytes);
+
+    let target = dir.join(name);
+    let write_error = |source| BundleError::Io {
+        path: target.clone(),
+        source,
+    };
+    let file = File::create(&target).map_err(write_error)?;
+    let mut hashing = HashingWriter::new(BufWriter::new(file));
+    let copied = io::copy(&mut reader.by_ref().take(len), &mut hashing).map_err(|source| {
+        BundleError::Io {
+            path: bundle_path.to_path_buf(),
+            source,
+        }
+    })?;
+    if copied != len {
+        return Err(BundleError::Corrupt {
+            path: bundle_path.to_path_buf(),
+            reason: format!("entry {name} is truncated"),
+        });
+    }
+    let (mut out, digest) = hashing.into_parts();
+    out.flush().map_err(write_error)?;
+
+    let mut expected = [0_u8; DIGEST_LEN];
+    read_header_field(reader, &mut expected, bundle_path)?;
+    if digest == expected {
+        Ok(len)
+    } else {
+        Err(BundleError::ChecksumMismatch {
+            path: bundle_path.to_path_buf(),
+            name: name.to_owned(),
+        })
+    }
+}
+
+/// Fill `buf` from `reader`, reporting a short read as corruption.
+pub(super) fn read_header_field(
+    reader: &mut impl Read,
+    buf: &mut [u8],
+    bundle_path: &Path,
+) -> Result<(), BundleError> {
+    reader.read_exact(buf).map_err(|source| {
+        if source.kind() == io::ErrorKind::UnexpectedEof {
+            BundleError::Corrupt {
+                path: bundle_path.to_path_buf(),
+                reason: "bundle ends mid-entry".to_owned(),
+            }
+        } else {
+            BundleError::Io {
+                path: bundle_path.to_path_buf(),
+                source,
+            }
+        }
+    })
+}
+
+/// Writer that hashes everything passed through it with SHA-256.
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> HashingWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+
+    fn finish(self) -> [u8; DIGEST_LEN] {
+        self.into_parts().1
+    }
+
+    fn into_parts(self) -> (W, [u8; DIGEST_LEN]) {
+        (self.inner, self.hasher.finalize().into())
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(buf.get(..written).unwrap_or_default());
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
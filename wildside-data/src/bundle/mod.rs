@@ -0,0 +1,295 @@
+//! `.wildside` bundles: one file carrying a whole artefact set.
+//!
+//! Devices consume `pois.db`, `pois.rstar`, `popularity.bin`, and
+//! `manifest.json` together, so shipping them as loose files invites partial
+//! copies. [`write_bundle`] packs an artefact directory into a single
+//! container and [`open_bundle`] unpacks it again, checking every entry's
+//! digest and the bundled manifest before handing the directory back.
+//!
+//! The container is a flat type-length-value stream:
+//!
+//! | Field         | Encoding                                        |
+//! | ------------- | ----------------------------------------------- |
+//! | Magic         | `WSBN`                                          |
+//! | Version       | `u16` little-endian, see [`BUNDLE`]             |
+//! | Entry count   | `u32` little-endian                             |
+//! | Each entry    | `u16` name length, UTF-8 name, `u64` data       |
+//! |               | length, data, 32-byte SHA-256 digest of data    |
+//!
+//! Entries are plain file names. SQLite needs a real file to open, so
+//! bundles are extracted to a directory rather than read in place.
+
+use std::{
+    collections::HashSet,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+use wildside_core::formats::{BUNDLE, FormatVersionError};
+
+use crate::manifest::{ArtefactManifest, MANIFEST_FILE_NAME, ManifestError, load_manifest};
+
+mod entry;
+
+use entry::{extract_entry, read_entry_name, read_header_field, write_entry};
+
+/// File identifier for `.wildside` bundles.
+pub const BUNDLE_MAGIC: [u8; 4] = *b"WSBN";
+
+/// Conventional file extension for bundles.
+pub const BUNDLE_EXTENSION: &str = "wildside";
+
+/// Artefacts every bundle must contain.
+const REQUIRED_FILES: [&str; 2] = ["pois.db", "pois.rstar"];
+
+/// Artefacts packed when present in the source directory.
+const OPTIONAL_FILES: [&str; 7] = [
+    "popularity.bin",
+    "links.filter",
+    "graph.bin",
+    "graph-step-free.bin",
+    "travel_times.bin",
+    "priors.json",
+    MANIFEST_FILE_NAME,
+];
+
+/// Length of the SHA-256 digest trailing each entry.
+const DIGEST_LEN: usize = 32;
+
+/// Errors raised while writing or opening a bundle.
+#[derive(Debug, Error)]
+pub enum BundleError {
+    /// A required artefact is absent from the source directory.
+    #[error("cannot bundle {dir:?}: required artefact {file} is missing")]
+    MissingArtefact {
+        /// Directory being bundled.
+        dir: PathBuf,
+        /// Name of the missing artefact.
+        file: &'static str,
+    },
+    /// Reading or writing a file failed.
+    #[error("failed to access {path:?}: {source}")]
+    Io {
+        /// File being read or written.
+        path: PathBuf,
+        /// Underlying I/O error.
+        #[source]
+        source: io::Error,
+    },
+    /// The file does not start with the bundle magic.
+    #[error("invalid bundle magic: expected {expected:?}, found {found:?}")]
+    InvalidMagic {
+        /// Expected byte sequence identifying a bundle.
+        expected: [u8; 4],
+        /// Sequence read from the file.
+        found: [u8; 4],
+    },
+    /// The bundle's format version is outside the readable range.
+    #[error(transparent)]
+    UnsupportedVersion(#[from] FormatVersionError),
+    /// An entry name could escape the extraction directory or repeats an
+    /// earlier entry.
+    #[error("bundle {path:?} contains unsafe entry name {name:?}")]
+    UnsafeEntryName {
+        /// Bundle being opened.
+        path: PathBuf,
+        /// Offending entry name.
+        name: String,
+    },
+    /// The bundle is truncated or structurally invalid.
+    #[error("bundle {path:?} is corrupt: {reason}")]
+    Corrupt {
+        /// Bundle being opened.
+        path: PathBuf,
+        /// Description of the inconsistency.
+        reason: String,
+    },
+    /// An entry's contents do not match its recorded digest.
+    #[error("bundle {path:?} entry {name} failed its checksum")]
+    ChecksumMismatch {
+        /// Bundle being opened.
+        path: PathBuf,
+        /// Entry whose digest differed.
+        name: String,
+    },
+    /// The bundled manifest is unreadable or incompatible with this build.
+    #[error(transparent)]
+    Manifest(#[from] ManifestError),
+}
+
+/// One file stored in a bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleEntry {
+    /// File name inside the artefact directory.
+    pub name: String,
+    /// Size of the file in bytes.
+    pub len: u64,
+}
+
+/// Artefact directory unpacked by [`open_bundle`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenedBundle {
+    /// Directory the artefacts were extracted into.
+    pub dir: PathBuf,
+    /// Files extracted, in bundle order.
+    pub entries: Vec<BundleEntry>,
+    /// Bundled manifest, or `None` for artefact sets written without one.
+    pub manifest: Option<ArtefactManifest>,
+}
+
+/// Pack the artefacts in `artefacts_dir` into a bundle at `bundle_path`,
+/// returning the entries written.
+///
+/// `pois.db` and `pois.rstar` are required; `popularity.bin`, `links.filter`,
+/// `graph.bin`, `graph-step-free.bin`, `travel_times.bin`, `priors.json`, and
+/// `manifest.json` are included when present. An existing file at
+/// `bundle_path` is replaced.
+///
+/// # Errors
+/// Returns [`BundleError::MissingArtefact`] when a required artefact is absent
+/// and [`BundleError::Io`] when reading an artefact or writing the bundle
+/// fails.
+///
+/// # Examples
+/// ```
+/// use std::fs;
+/// use tempfile::tempdir;
+/// use wildside_data::bundle::{open_bundle, write_bundle};
+///
+/// let dir = tempdir().expect("create temp directory");
+/// let artefacts = dir.path().join("artefacts");
+/// fs::create_dir(&artefacts).expect("create artefact directory");
+/// fs::write(artefacts.join("pois.db"), b"db").expect("write pois.db");
+/// fs::write(artefacts.join("pois.rstar"), b"index").expect("write pois.rstar");
+///
+/// let bundle = dir.path().join("region.wildside");
+/// write_bundle(&artefacts, &bundle).expect("write bundle");
+///
+/// let opened = open_bundle(&bundle, dir.path().join("device")).expect("open bundle");
+/// assert_eq!(opened.entries.len(), 2);
+/// assert_eq!(fs::read(opened.dir.join("pois.db")).expect("read pois.db"), b"db");
+/// ```
+pub fn write_bundle(
+    artefacts_dir: impl AsRef<Path>,
+    bundle_path: impl AsRef<Path>,
+) -> Result<Vec<BundleEntry>, BundleError> {
+    let dir = artefacts_dir.as_ref();
+    let bundle_path = bundle_path.as_ref();
+    let mut sources = Vec::with_capacity(REQUIRED_FILES.len() + OPTIONAL_FILES.len());
+    for file in REQUIRED_FILES {
+        let path = dir.join(file);
+        if !path.is_file() {
+            return Err(BundleError::MissingArtefact {
+                dir: dir.to_path_buf(),
+                file,
+            });
+        }
+        sources.push((file, path));
+    }
+    sources.extend(
+        OPTIONAL_FILES
+            .into_iter()
+            .map(|file| (file, dir.join(file)))
+            .filter(|(_, path)| path.is_file()),
+    );
+
+    let io_error = |source| BundleError::Io {
+        path: bundle_path.to_path_buf(),
+        source,
+    };
+    let mut out = BufWriter::new(File::create(bundle_path).map_err(io_error)?);
+    let count = u32::try_from(sources.len()).map_err(|err| io_error(io::Error::other(err)))?;
+    out.write_all(&BUNDLE_MAGIC).map_err(io_error)?;
+    out.write_all(&BUNDLE.current.to_le_bytes())
+        .map_err(io_error)?;
+    out.write_all(&count.to_le_bytes()).map_err(io_error)?;
+
+    let mut entries = Vec::with_capacity(sources.len());
+    for (name, path) in sources {
+        let len = write_entry(&mut out, name, &path, bundle_path)?;
+        entries.push(BundleEntry {
+            name: name.to_owned(),
+            len,
+        });
+    }
+    out.flush().map_err(io_error)?;
+    Ok(entries)
+}
+
+/// Extract the bundle at `bundle_path` into `extract_dir`, creating the
+/// directory if needed, and load the bundled manifest.
+///
+/// Every entry is checked against its digest as it is written, and the
+/// manifest is checked against the compatibility matrix, so a damaged or
+/// incompatible bundle is rejected before any store is opened. Files already
+/// in `extract_dir` with the same names are replaced, and entries extracted
+/// before a failure are left behind, so extract into a fresh directory.
+///
+/// # Errors
+/// Returns [`BundleError::InvalidMagic`] or
+/// [`BundleError::UnsupportedVersion`] for files that are not readable
+/// bundles, [`BundleError::Corrupt`], [`BundleError::UnsafeEntryName`], or
+/// [`BundleError::ChecksumMismatch`] for damaged ones,
+/// [`BundleError::Manifest`] when the manifest is unreadable or incompatible,
+/// and [`BundleError::Io`] for I/O failures.
+pub fn open_bundle(
+    bundle_path: impl AsRef<Path>,
+    extract_dir: impl AsRef<Path>,
+) -> Result<OpenedBundle, BundleError> {
+    let bundle_path = bundle_path.as_ref();
+    let dir = extract_dir.as_ref();
+    let io_error = |path: &Path| {
+        let path = path.to_path_buf();
+        move |source| BundleError::Io { path, source }
+    };
+    let corrupt = |reason: &str| BundleError::Corrupt {
+        path: bundle_path.to_path_buf(),
+        reason: reason.to_owned(),
+    };
+    let mut reader = BufReader::new(File::open(bundle_path).map_err(io_error(bundle_path))?);
+
+    let mut magic = [0_u8; 4];
+    read_header_field(&mut reader, &mut magic, bundle_path)?;
+    if magic != BUNDLE_MAGIC {
+        return Err(BundleError::InvalidMagic {
+            expected: BUNDLE_MAGIC,
+            found: magic,
+        });
+    }
+    let mut version = [0_u8; 2];
+    read_header_field(&mut reader, &mut version, bundle_path)?;
+    BUNDLE.negotiate(i64::from(u16::from_le_bytes(version)))?;
+    let mut count = [0_u8; 4];
+    read_header_field(&mut reader, &mut count, bundle_path)?;
+
+    fs::create_dir_all(dir).map_err(io_error(dir))?;
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+    for _ in 0..u32::from_le_bytes(count) {
+        let name = read_entry_name(&mut reader, bundle_path)?;
+        if !seen.insert(name.clone()) {
+            return Err(BundleError::UnsafeEntryName {
+                path: bundle_path.to_path_buf(),
+                name,
+            });
+        }
+        let len = extract_entry(&mut reader, &name, dir, bundle_path)?;
+        entries.push(BundleEntry { name, len });
+    }
+    let mut trailing = [0_u8; 1];
+    if reader.read(&mut trailing).map_err(io_error(bundle_path))? != 0 {
+        return Err(corrupt("unexpected data after the last entry"));
+    }
+
+    let manifest = load_manifest(dir)?;
+    Ok(OpenedBundle {
+        dir: dir.to_path_buf(),
+        entries,
+        manifest,
+    })
+}
+
+#[cfg(test)]
+mod tests;
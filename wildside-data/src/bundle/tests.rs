@@ -0,0 +1,158 @@
+//! Unit tests for bundle round trips and rejection of damaged bundles.
+
+use rstest::{fixture, rstest};
+use sha2::{Digest, Sha256};
+use tempfile::TempDir;
+use wildside_core::formats::SPATIAL_INDEX;
+
+use super::*;
+
+#[fixture]
+fn tmp() -> TempDir {
+    TempDir::new().expect("create temp directory")
+}
+
+fn artefact_dir(tmp: &TempDir) -> PathBuf {
+    let dir = tmp.path().join("artefacts");
+    fs::create_dir(&dir).expect("create artefact directory");
+    fs::write(dir.join("pois.db"), b"sqlite bytes").expect("write pois.db");
+    fs::write(dir.join("pois.rstar"), b"index bytes").expect("write pois.rstar");
+    fs::write(dir.join("popularity.bin"), b"scores").expect("write popularity.bin");
+    ArtefactManifest::new("1.2.3")
+        .with_artefact("pois.rstar", SPATIAL_INDEX)
+        .write(&dir)
+        .expect("write manifest");
+    dir
+}
+
+fn bundle(tmp: &TempDir) -> PathBuf {
+    let path = tmp.path().join("region.wildside");
+    write_bundle(artefact_dir(tmp), &path).expect("write bundle");
+    path
+}
+
+#[rstest]
+fn round_trips_artefacts_and_manifest(tmp: TempDir) {
+    let source = artefact_dir(&tmp);
+    let path = tmp.path().join("region.wildside");
+    let written = write_bundle(&source, &path).expect("write bundle");
+
+    let opened = open_bundle(&path, tmp.path().join("device")).expect("open bundle");
+
+    assert_eq!(opened.entries, written);
+    let names: Vec<&str> = opened.entries.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "pois.db",
+            "pois.rstar",
+            "popularity.bin",
+            MANIFEST_FILE_NAME
+        ]
+    );
+    for name in names {
+        assert_eq!(
+            fs::read(opened.dir.join(name)).expect("read extracted file"),
+            fs::read(source.join(name)).expect("read source file"),
+        );
+    }
+    assert_eq!(
+        opened.manifest.map(|manifest| manifest.tool_version),
+        Some("1.2.3".to_owned())
+    );
+}
+
+#[rstest]
+fn rejects_directories_missing_required_artefacts(tmp: TempDir) {
+    let dir = artefact_dir(&tmp);
+    fs::remove_file(dir.join("pois.rstar")).expect("remove pois.rstar");
+
+    let error = write_bundle(&dir, tmp.path().join("region.wildside"))
+        .expect_err("missing index should be rejected");
+    assert!(matches!(
+        error,
+        BundleError::MissingArtefact {
+            file: "pois.rstar",
+            ..
+        }
+    ));
+}
+
+#[rstest]
+fn rejects_flipped_bytes(tmp: TempDir) {
+    let path = bundle(&tmp);
+    let mut bytes = fs::read(&path).expect("read bundle");
+    // The first entry's data follows the header, name length, `pois.db`,
+    // and its data length.
+    let offset = 4 + 2 + 4 + 2 + "pois.db".len() + 8;
+    let byte = bytes.get_mut(offset).expect("entry data present");
+    *byte ^= 0xFF;
+    fs::write(&path, bytes).expect("rewrite bundle");
+
+    let error = open_bundle(&path, tmp.path().join("device"))
+        .expect_err("corrupt entry should be rejected");
+    assert!(matches!(error, BundleError::ChecksumMismatch { name, .. } if name == "pois.db"));
+}
+
+#[rstest]
+#[case(1)]
+#[case(DIGEST_LEN + 1)]
+fn rejects_truncated_bundles(tmp: TempDir, #[case] cut: usize) {
+    let path = bundle(&tmp);
+    let bytes = fs::read(&path).expect("read bundle");
+    let kept = bytes
+        .get(..bytes.len() - cut)
+        .expect("bundle longer than cut");
+    fs::write(&path, kept).expect("rewrite bundle");
+
+    let error = open_bundle(&path, tmp.path().join("device"))
+        .expect_err("truncated bundle should be rejected");
+    assert!(matches!(error, BundleError::Corrupt { .. }));
+}
+
+#[rstest]
+#[case("../escape")]
+#[case("nested/pois.db")]
+#[case("..")]
+fn rejects_entry_names_outside_the_directory(tmp: TempDir, #[case] name: &str) {
+    let path = tmp.path().join("evil.wildside");
+    let mut bytes = BUNDLE_MAGIC.to_vec();
+    bytes.extend_from_slice(&BUNDLE.current.to_le_bytes());
+    bytes.extend_from_slice(&1_u32.to_le_bytes());
+    let name_len = u16::try_from(name.len()).expect("short name");
+    bytes.extend_from_slice(&name_len.to_le_bytes());
+    bytes.extend_from_slice(name.as_bytes());
+    bytes.extend_from_slice(&0_u64.to_le_bytes());
+    bytes.extend_from_slice(&Sha256::digest(b""));
+    fs::write(&path, bytes).expect("write bundle");
+
+    let error =
+        open_bundle(&path, tmp.path().join("device")).expect_err("unsafe name should be rejected");
+    assert!(matches!(error, BundleError::UnsafeEntryName { .. }));
+    assert!(!tmp.path().join("escape").exists());
+}
+
+#[rstest]
+fn rejects_newer_bundle_versions(tmp: TempDir) {
+    let path = bundle(&tmp);
+    let mut bytes = fs::read(&path).expect("read bundle");
+    let newer = (BUNDLE.current + 1).to_le_bytes();
+    bytes
+        .get_mut(4..6)
+        .expect("version field present")
+        .copy_from_slice(&newer);
+    fs::write(&path, bytes).expect("rewrite bundle");
+
+    let error =
+        open_bundle(&path, tmp.path().join("device")).expect_err("newer bundle should be rejected");
+    assert!(matches!(error, BundleError::UnsupportedVersion(_)));
+}
+
+#[rstest]
+fn rejects_other_files(tmp: TempDir) {
+    let path = tmp.path().join("pois.db");
+    fs::write(&path, b"SQLite format 3\0").expect("write file");
+
+    let error = open_bundle(&path, tmp.path().join("device")).expect_err("non-bundle should fail");
+    assert!(matches!(error, BundleError::InvalidMagic { .. }));
+}
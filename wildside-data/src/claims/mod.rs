@@ -0,0 +1,190 @@
+//! Typed read access to the Wikidata claims stored in `pois.db`.
+//!
+//! [`ClaimsStore`] wraps a read-only `SQLite` connection and answers the
+//! questions request-time consumers ask of the claims schema, so callers such
+//! as the scorer never name its tables or views. The schema is written by
+//! [`crate::wikidata::store`] during ingestion; this module only reads it and
+//! sits behind the lightweight `claims-store` feature.
+#![forbid(unsafe_code)]
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, Error as SqliteError, OpenFlags, OptionalExtension};
+use thiserror::Error;
+use wildside_core::formats::{CLAIMS_SCHEMA, FormatVersionError};
+
+mod queries;
+
+use queries::CLAIMS_FOR_POI_SQL;
+
+/// An entity-valued claim reached through one of a POI's linked entities.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PoiClaim {
+    /// The linked entity holding the claim (e.g., `Q64`).
+    pub entity_id: String,
+    /// The Wikidata property identifier (e.g., `P1435`).
+    pub property_id: String,
+    /// The entity the claim points at (e.g., `Q9259`).
+    pub value_entity_id: String,
+}
+
+/// A heritage designation held by an entity.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Designation {
+    /// The designation entity (e.g., `Q9259`).
+    pub entity_id: String,
+    /// The designation's label in the requested language, when resolved.
+    pub label: Option<String>,
+}
+
+/// Read-only query interface over the Wikidata claims in `pois.db`.
+///
+/// # Examples
+/// ```
+/// use rusqlite::Connection;
+/// use wildside_data::claims::{ClaimsStore, PoiClaim};
+///
+/// let connection = Connection::open_in_memory().expect("create in-memory database");
+/// connection
+///     .execute_batch(
+///         "CREATE TABLE poi_wikidata_links (poi_id INTEGER, entity_id TEXT);
+///          CREATE TABLE wikidata_entity_claims (
+///              entity_id TEXT, property_id TEXT, value_entity_id TEXT);
+///          CREATE VIEW poi_wikidata_claims AS
+///              SELECT links.poi_id, claims.entity_id, claims.property_id, claims.value_entity_id
+///              FROM poi_wikidata_links AS links
+///              JOIN wikidata_entity_claims AS claims ON claims.entity_id = links.entity_id;
+///          INSERT INTO poi_wikidata_links VALUES (7, 'Q64');
+///          INSERT INTO wikidata_entity_claims VALUES ('Q64', 'P1435', 'Q9259');",
+///     )
+///     .expect("seed claims");
+/// let store = ClaimsStore::from_connection(connection)?;
+///
+/// assert_eq!(
+///     store.claims_for_poi(7)?,
+///     [PoiClaim {
+///         entity_id: "Q64".into(),
+///         property_id: "P1435".into(),
+///         value_entity_id: "Q9259".into(),
+///     }]
+/// );
+/// assert_eq!(store.entities_with_property("P1435")?, ["Q64"]);
+/// # Ok::<(), wildside_data::claims::ClaimsStoreError>(())
+/// ```
+#[derive(Debug)]
+pub struct ClaimsStore {
+    connection: Connection,
+    has_labels: bool,
+}
+
+impl ClaimsStore {
+    /// Open the claims in the database at `path` read-only.
+    ///
+    /// # Errors
+    /// Returns [`ClaimsStoreError`] when the database cannot be opened, its
+    /// claims schema is newer than this build reads, or it lacks the claims
+    /// tables.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ClaimsStoreError> {
+        let connection =
+            Connection::open_with_flags(path.as_ref(), OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(
+                |source| ClaimsStoreError::Open {
+                    path: path.as_ref().to_path_buf(),
+                    source,
+                },
+            )?;
+        Self::from_connection(connection)
+    }
+
+    /// Wrap an existing connection.
+    ///
+    /// Stores written before a schema version was recorded are accepted; a
+    /// recorded version must be readable by
+    /// [`CLAIMS_SCHEMA`](wildside_core::formats::CLAIMS_SCHEMA). Stores from
+    /// before labels were resolved still answer every query, reporting
+    /// designations without labels.
+    ///
+    /// # Errors
+    /// Returns [`ClaimsStoreError`] when the schema version is unsupported or
+    /// the claim lookup cannot be prepared.
+    pub fn from_connection(connection: Connection) -> Result<Self, ClaimsStoreError> {
+        if table_exists(&connection, "wikidata_schema_version")? {
+            let version: Option<i64> = connection
+                .query_row(
+                    "SELECT MAX(version) FROM wikidata_schema_version",
+                    [],
+                    |row| row.get(0),
+                )
+                .map_err(|source| ClaimsStoreError::Sqlite {
+                    operation: "read claims schema version",
+                    source,
+                })?;
+            if let Some(found) = version {
+                CLAIMS_SCHEMA.negotiate(found)?;
+            }
+        }
+        connection
+            .prepare_cached(CLAIMS_FOR_POI_SQL)
+            .map_err(|source| ClaimsStoreError::Sqlite {
+                operation: "prepare claim lookup",
+                source,
+            })?;
+        let has_labels = table_exists(&connection, "wikidata_entity_labels")?;
+        Ok(Self {
+            connection,
+            has_labels,
+        })
+    }
+
+    fn prepare(
+        &self,
+        sql: &str,
+        operation: &'static str,
+    ) -> Result<rusqlite::CachedStatement<'_>, ClaimsStoreError> {
+        self.connection
+            .prepare_cached(sql)
+            .map_err(|source| ClaimsStoreError::Sqlite { operation, source })
+    }
+}
+
+fn table_exists(connection: &Connection, name: &str) -> Result<bool, ClaimsStoreError> {
+    connection
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1 LIMIT 1",
+            [name],
+            |_| Ok(()),
+        )
+        .optional()
+        .map(|row| row.is_some())
+        .map_err(|source| ClaimsStoreError::Sqlite {
+            operation: "inspect schema",
+            source,
+        })
+}
+
+fn sql_poi_id(poi_id: u64) -> Result<i64, ClaimsStoreError> {
+    i64::try_from(poi_id).map_err(|_| ClaimsStoreError::PoiIdOutOfRange { poi_id })
+}
+
+/// Errors raised when reading Wikidata claims through [`ClaimsStore`].
+#[derive(Debug, Error)]
+pub enum ClaimsStoreError {
+    #[error("failed to open read-only SQLite database at {path:?}")]
+    Open {
+        path: PathBuf,
+        #[source]
+        source: SqliteError,
+    },
+    #[error(transparent)]
+    UnsupportedVersion(#[from] FormatVersionError),
+    #[error("POI id {poi_id} exceeds SQLite i64 range")]
+    PoiIdOutOfRange { poi_id: u64 },
+    #[error("failed to {operation}")]
+    Sqlite {
+        operation: &'static str,
+        #[source]
+        source: SqliteError,
+    },
+}
+
+#[cfg(all(test, feature = "wikidata-etl"))]
+mod tests;
@@ -0,0 +1,239 @@
+//! Claim, designation, and sitelink lookups.
+
+use std::collections::HashMap;
+
+use rusqlite::{OptionalExtension, params_from_iter};
+
+use super::{ClaimsStore, ClaimsStoreError, Designation, PoiClaim, sql_poi_id};
+
+/// `P1435` (heritage designation), whose values [`ClaimsStore::designations_for_entity`]
+/// returns.
+const HERITAGE_PROPERTY: &str = "P1435";
+
+/// `SQLite` limits bound parameters per statement to 999 by default, so
+/// [`ClaimsStore::claims_for_pois`] queries at most this many POIs at once.
+const MAX_POIS_PER_QUERY: usize = 999;
+
+pub(super) const CLAIMS_FOR_POI_SQL: &str = concat!(
+    "SELECT entity_id, property_id, value_entity_id FROM poi_wikidata_claims ",
+    "WHERE poi_id = ?1 ORDER BY entity_id, property_id, value_entity_id"
+);
+const POIS_WITH_CLAIM_SQL: &str = concat!(
+    "SELECT DISTINCT poi_id FROM poi_wikidata_claims ",
+    "WHERE property_id = ?1 AND value_entity_id = ?2 ORDER BY poi_id"
+);
+const ENTITIES_WITH_PROPERTY_SQL: &str = concat!(
+    "SELECT DISTINCT entity_id FROM wikidata_entity_claims ",
+    "WHERE property_id = ?1 ORDER BY entity_id"
+);
+const LABELLED_DESIGNATIONS_SQL: &str = concat!(
+    "SELECT claims.value_entity_id, labels.label FROM wikidata_entity_claims AS claims ",
+    "LEFT JOIN wikidata_entity_labels AS labels ",
+    "ON labels.entity_id = claims.value_entity_id AND labels.language = ?3 ",
+    "WHERE claims.entity_id = ?1 AND claims.property_id = ?2 ",
+    "ORDER BY claims.value_entity_id"
+);
+const UNLABELLED_DESIGNATIONS_SQL: &str = concat!(
+    "SELECT value_entity_id, NULL FROM wikidata_entity_claims ",
+    "WHERE entity_id = ?1 AND property_id = ?2 ",
+    "ORDER BY value_entity_id"
+);
+const SITELINKS_FOR_POI_SQL: &str = concat!(
+    "SELECT sitelinks.sitelink_count FROM poi_wikidata_links AS links ",
+    "JOIN wikidata_entity_sitelinks AS sitelinks ON sitelinks.entity_id = links.entity_id ",
+    "WHERE links.poi_id = ?1 ORDER BY links.entity_id LIMIT 1"
+);
+
+impl ClaimsStore {
+    /// Return every entity-valued claim of the entities linked to `poi_id`,
+    /// sorted by entity, property, and value.
+    ///
+    /// # Errors
+    /// Returns [`ClaimsStoreError`] when `poi_id` exceeds the `SQLite` integer
+    /// range or the query fails.
+    pub fn claims_for_poi(&self, poi_id: u64) -> Result<Vec<PoiClaim>, ClaimsStoreError> {
+        let poi_id = sql_poi_id(poi_id)?;
+        let mut statement = self.prepare(CLAIMS_FOR_POI_SQL, "prepare claim lookup")?;
+        let rows = statement
+            .query_map([poi_id], |row| {
+                Ok(PoiClaim {
+                    entity_id: row.get(0)?,
+                    property_id: row.get(1)?,
+                    value_entity_id: row.get(2)?,
+                })
+            })
+            .map_err(|source| ClaimsStoreError::Sqlite {
+                operation: "query POI claims",
+                source,
+            })?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|source| ClaimsStoreError::Sqlite {
+                operation: "read POI claim row",
+                source,
+            })
+    }
+
+    /// Return the entity-valued claims of the entities linked to each of
+    /// `poi_ids`, keyed by POI and sorted as [`Self::claims_for_poi`] sorts
+    /// them.
+    ///
+    /// POIs without claims are absent from the map, as are ids beyond the
+    /// `SQLite` integer range, which no stored link can reference. One such id
+    /// therefore leaves the rest of the batch intact. Scoring a batch of
+    /// candidates this way costs one query per 999 POIs rather than one per
+    /// POI.
+    ///
+    /// # Errors
+    /// Returns [`ClaimsStoreError`] when a query fails.
+    pub fn claims_for_pois(
+        &self,
+        poi_ids: &[u64],
+    ) -> Result<HashMap<u64, Vec<PoiClaim>>, ClaimsStoreError> {
+        let ids: Vec<i64> = poi_ids
+            .iter()
+            .filter_map(|&poi_id| sql_poi_id(poi_id).ok())
+            .collect();
+        let mut claims: HashMap<u64, Vec<PoiClaim>> = HashMap::new();
+        for chunk in ids.chunks(MAX_POIS_PER_QUERY) {
+            for (poi_id, claim) in self.claims_for_chunk(chunk)? {
+                claims.entry(poi_id).or_default().push(claim);
+            }
+        }
+        Ok(claims)
+    }
+
+    fn claims_for_chunk(&self, poi_ids: &[i64]) -> Result<Vec<(u64, PoiClaim)>, ClaimsStoreError> {
+        let placeholders = vec!["?"; poi_ids.len()].join(", ");
+        let sql = format!(
+            "SELECT poi_id, entity_id, property_id, value_entity_id FROM poi_wikidata_claims \
+             WHERE poi_id IN ({placeholders}) \
+             ORDER BY poi_id, entity_id, property_id, value_entity_id"
+        );
+        let mut statement = self.prepare(&sql, "prepare batch claim lookup")?;
+        let rows = statement
+            .query_map(params_from_iter(poi_ids), |row| {
+                Ok((
+                    row.get(0)?,
+                    PoiClaim {
+                        entity_id: row.get(1)?,
+                        property_id: row.get(2)?,
+                        value_entity_id: row.get(3)?,
+                    },
+                ))
+            })
+            .map_err(|source| ClaimsStoreError::Sqlite {
+                operation: "query POI claims",
+                source,
+            })?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|source| ClaimsStoreError::Sqlite {
+                operation: "read POI claim row",
+                source,
+            })
+    }
+
+    /// Return the POIs linked to an entity claiming `value_entity_id` for
+    /// `property_id`, sorted.
+    ///
+    /// # Errors
+    /// Returns [`ClaimsStoreError`] when the query fails.
+    pub fn pois_with_claim(
+        &self,
+        property_id: &str,
+        value_entity_id: &str,
+    ) -> Result<Vec<u64>, ClaimsStoreError> {
+        let mut statement = self.prepare(POIS_WITH_CLAIM_SQL, "prepare claim holder lookup")?;
+        let rows = statement
+            .query_map([property_id, value_entity_id], |row| row.get(0))
+            .map_err(|source| ClaimsStoreError::Sqlite {
+                operation: "query POIs with claim",
+                source,
+            })?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|source| ClaimsStoreError::Sqlite {
+                operation: "read POI row",
+                source,
+            })
+    }
+
+    /// Return the entities holding at least one entity-valued claim for
+    /// `property_id`, sorted.
+    ///
+    /// # Errors
+    /// Returns [`ClaimsStoreError`] when the query fails.
+    pub fn entities_with_property(
+        &self,
+        property_id: &str,
+    ) -> Result<Vec<String>, ClaimsStoreError> {
+        let mut statement = self.prepare(ENTITIES_WITH_PROPERTY_SQL, "prepare property lookup")?;
+        let rows = statement
+            .query_map([property_id], |row| row.get(0))
+            .map_err(|source| ClaimsStoreError::Sqlite {
+                operation: "query entities with property",
+                source,
+            })?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|source| ClaimsStoreError::Sqlite {
+                operation: "read entity row",
+                source,
+            })
+    }
+
+    /// Return the heritage designations (`P1435` values) of `entity_id`,
+    /// labelled in `language` where a label has been resolved.
+    ///
+    /// # Errors
+    /// Returns [`ClaimsStoreError`] when the query fails.
+    pub fn designations_for_entity(
+        &self,
+        entity_id: &str,
+        language: &str,
+    ) -> Result<Vec<Designation>, ClaimsStoreError> {
+        let sql = if self.has_labels {
+            LABELLED_DESIGNATIONS_SQL
+        } else {
+            UNLABELLED_DESIGNATIONS_SQL
+        };
+        let mut statement = self.prepare(sql, "prepare designation lookup")?;
+        let map_row = |row: &rusqlite::Row<'_>| {
+            Ok(Designation {
+                entity_id: row.get(0)?,
+                label: row.get(1)?,
+            })
+        };
+        let rows = if self.has_labels {
+            statement.query_map((entity_id, HERITAGE_PROPERTY, language), map_row)
+        } else {
+            statement.query_map((entity_id, HERITAGE_PROPERTY), map_row)
+        }
+        .map_err(|source| ClaimsStoreError::Sqlite {
+            operation: "query designations",
+            source,
+        })?;
+        rows.collect::<Result<_, _>>()
+            .map_err(|source| ClaimsStoreError::Sqlite {
+                operation: "read designation row",
+                source,
+            })
+    }
+
+    /// Return the stored sitelink count of the first entity linked to
+    /// `poi_id`, or `None` when no linked entity has one.
+    ///
+    /// # Errors
+    /// Returns [`ClaimsStoreError`] when `poi_id` exceeds the `SQLite` integer
+    /// range or the query fails, including for stores ingested before
+    /// sitelink counts were recorded.
+    pub fn sitelink_count_for_poi(&self, poi_id: u64) -> Result<Option<u32>, ClaimsStoreError> {
+        let poi_id = sql_poi_id(poi_id)?;
+        let mut statement = self.prepare(SITELINKS_FOR_POI_SQL, "prepare sitelink lookup")?;
+        let count: Option<i64> = statement
+            .query_row([poi_id], |row| row.get(0))
+            .optional()
+            .map_err(|source| ClaimsStoreError::Sqlite {
+                operation: "query sitelink count",
+                source,
+            })?;
+        Ok(count.and_then(|count| u32::try_from(count).ok()))
+    }
+}
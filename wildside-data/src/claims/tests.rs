@@ -0,0 +1,227 @@
+//! Tests for typed claim reads against the ingest schema.
+
+use rstest::{fixture, rstest};
+use tempfile::TempDir;
+
+use super::*;
+use crate::wikidata::etl::{Claim, EntityClaims, EntityLabel};
+use crate::wikidata::store::{persist_claims, persist_entity_labels};
+
+fn entity(entity_id: &str, poi_id: u64, claims: Vec<Claim>) -> EntityClaims {
+    EntityClaims {
+        entity_id: entity_id.into(),
+        linked_poi_ids: vec![poi_id],
+        claims,
+        image_files: Vec::new(),
+        sitelink_count: Some(12),
+    }
+}
+
+fn seeded_connection() -> Connection {
+    let mut connection = Connection::open_in_memory().expect("open in-memory database");
+    connection
+        .execute_batch(
+            "CREATE TABLE pois (id INTEGER PRIMARY KEY, lon REAL, lat REAL, tags TEXT);
+             INSERT INTO pois VALUES (7, 13.4, 52.5, '{}');
+             INSERT INTO pois VALUES (8, 13.4, 52.5, '{}');",
+        )
+        .expect("seed POIs");
+    persist_claims(
+        &mut connection,
+        &[
+            entity(
+                "Q64",
+                7,
+                vec![
+                    Claim::entity("P31", "Q515"),
+                    Claim::entity("P1435", "Q9259"),
+                    Claim::entity("P1435", "Q15700818"),
+                    Claim::literal("P571", "+1237-00-00T00:00:00Z"),
+                ],
+            ),
+            entity("Q90", 8, vec![Claim::entity("P31", "Q515")]),
+        ],
+    )
+    .expect("persist claims");
+    persist_entity_labels(
+        &mut connection,
+        &[EntityLabel {
+            entity_id: "Q9259".into(),
+            language: "en".into(),
+            label: "World Heritage Site".into(),
+        }],
+    )
+    .expect("persist labels");
+    connection
+}
+
+#[fixture]
+fn store() -> ClaimsStore {
+    ClaimsStore::from_connection(seeded_connection()).expect("wrap connection")
+}
+
+fn claim(entity_id: &str, property_id: &str, value_entity_id: &str) -> PoiClaim {
+    PoiClaim {
+        entity_id: entity_id.into(),
+        property_id: property_id.into(),
+        value_entity_id: value_entity_id.into(),
+    }
+}
+
+#[rstest]
+fn lists_entity_claims_for_poi(store: ClaimsStore) {
+    assert_eq!(
+        store.claims_for_poi(7).expect("query claims"),
+        [
+            claim("Q64", "P1435", "Q15700818"),
+            claim("Q64", "P1435", "Q9259"),
+            claim("Q64", "P31", "Q515"),
+        ]
+    );
+    assert!(store.claims_for_poi(9).expect("query claims").is_empty());
+}
+
+#[rstest]
+fn batches_claims_for_several_pois(store: ClaimsStore) {
+    let claims = store.claims_for_pois(&[7, 8, 9]).expect("query claims");
+
+    assert_eq!(
+        claims.get(&7).map(Vec::as_slice),
+        Some(store.claims_for_poi(7).expect("query claims").as_slice())
+    );
+    assert_eq!(claims.get(&8), Some(&vec![claim("Q90", "P31", "Q515")]));
+    assert!(!claims.contains_key(&9));
+    assert!(store.claims_for_pois(&[]).expect("query claims").is_empty());
+}
+
+#[rstest]
+fn skips_out_of_range_ids_in_a_batch(store: ClaimsStore) {
+    let claims = store
+        .claims_for_pois(&[u64::MAX, 8])
+        .expect("an out-of-range id should not fail the batch");
+
+    assert_eq!(claims.get(&8), Some(&vec![claim("Q90", "P31", "Q515")]));
+    assert!(!claims.contains_key(&u64::MAX));
+}
+
+#[rstest]
+fn lists_pois_holding_a_claim(store: ClaimsStore) {
+    assert_eq!(
+        store.pois_with_claim("P31", "Q515").expect("query POIs"),
+        [7, 8]
+    );
+    assert_eq!(
+        store.pois_with_claim("P1435", "Q9259").expect("query POIs"),
+        [7]
+    );
+    assert!(
+        store
+            .pois_with_claim("P1435", "Q515")
+            .expect("query POIs")
+            .is_empty()
+    );
+}
+
+#[rstest]
+fn lists_entities_with_property(store: ClaimsStore) {
+    assert_eq!(
+        store.entities_with_property("P31").expect("query entities"),
+        ["Q64", "Q90"]
+    );
+    assert!(
+        store
+            .entities_with_property("P571")
+            .expect("query entities")
+            .is_empty(),
+        "literal claims are not entity-valued"
+    );
+}
+
+#[rstest]
+fn labels_designations_when_resolved(store: ClaimsStore) {
+    assert_eq!(
+        store
+            .designations_for_entity("Q64", "en")
+            .expect("query designations"),
+        [
+            Designation {
+                entity_id: "Q15700818".into(),
+                label: None,
+            },
+            Designation {
+                entity_id: "Q9259".into(),
+                label: Some("World Heritage Site".into()),
+            },
+        ]
+    );
+}
+
+#[rstest]
+fn reads_designations_from_stores_without_labels() {
+    let connection = seeded_connection();
+    connection
+        .execute_batch("DROP VIEW poi_wikidata_claim_labels; DROP TABLE wikidata_entity_labels;")
+        .expect("drop labels");
+    let store = ClaimsStore::from_connection(connection).expect("wrap connection");
+
+    let designations = store
+        .designations_for_entity("Q64", "en")
+        .expect("query designations");
+
+    assert!(
+        designations
+            .iter()
+            .all(|designation| designation.label.is_none())
+    );
+    assert_eq!(designations.len(), 2);
+}
+
+#[rstest]
+fn reads_sitelink_counts(store: ClaimsStore) {
+    assert_eq!(
+        store.sitelink_count_for_poi(7).expect("query sitelinks"),
+        Some(12)
+    );
+    assert_eq!(
+        store.sitelink_count_for_poi(9).expect("query sitelinks"),
+        None
+    );
+}
+
+#[rstest]
+fn rejects_newer_schema_versions() {
+    let connection = seeded_connection();
+    connection
+        .execute(
+            "INSERT INTO wikidata_schema_version (version) VALUES (?1)",
+            [i64::from(CLAIMS_SCHEMA.current) + 1],
+        )
+        .expect("record newer version");
+
+    let err = ClaimsStore::from_connection(connection).expect_err("newer schema should fail");
+
+    assert!(matches!(err, ClaimsStoreError::UnsupportedVersion(_)));
+}
+
+#[rstest]
+fn opens_databases_read_only() {
+    let dir = TempDir::new().expect("create temp dir");
+    let path = dir.path().join("pois.db");
+    let mut connection = Connection::open(&path).expect("open database");
+    connection
+        .execute_batch("CREATE TABLE pois (id INTEGER PRIMARY KEY, lon REAL, lat REAL, tags TEXT);")
+        .expect("create pois table");
+    persist_claims(&mut connection, &[]).expect("initialise schema");
+    drop(connection);
+
+    let store = ClaimsStore::open(&path).expect("open store");
+
+    assert!(store.claims_for_poi(7).expect("query claims").is_empty());
+    assert!(
+        store
+            .connection
+            .execute("DELETE FROM wikidata_entities", [])
+            .is_err(),
+        "store connection should be read-only"
+    );
+}
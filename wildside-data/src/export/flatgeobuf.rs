@@ -0,0 +1,171 @@
+//! FlatGeobuf export.
+//!
+//! The writer buffers every feature so it can sort them along a Hilbert
+//! curve and prepend a packed R-tree, which lets QGIS and GDAL read a
+//! bounding box without scanning the whole file.
+
+use std::{fs::File, io::BufWriter, path::Path};
+
+use ::flatgeobuf::{ColumnType, FgbCrs, FgbWriter, FgbWriterOptions, GeometryType};
+use geo::{Geometry, Point};
+use geozero::{ColumnValue, PropertyProcessor, error::GeozeroError};
+use wildside_core::PointOfInterest;
+
+use super::{ExportError, ExportOptions, ExportRow, io_error};
+
+/// Dataset name recorded in the FlatGeobuf header.
+const DATASET_NAME: &str = "pois";
+
+/// EPSG code of WGS84 longitude and latitude.
+const WGS84_EPSG: i32 = 4326;
+
+/// Write `pois` to a FlatGeobuf file at `path`, replacing any existing file.
+///
+/// Properties follow the table in the [module documentation](super); a null
+/// `name` or `popularity` is left out of the feature. Features are stored in
+/// spatial-index order rather than the order of `pois`.
+///
+/// # Errors
+/// Returns [`ExportError::Io`] when the file cannot be created,
+/// [`ExportError::SerializeTags`] when a tag map cannot be encoded,
+/// [`ExportError::FlatGeobufFeature`] when a POI cannot be encoded, and
+/// [`ExportError::FlatGeobuf`] when the FlatGeobuf writer fails.
+///
+/// # Examples
+/// ```rust
+/// use geo::Coord;
+/// use wildside_core::{PointOfInterest, Tags};
+/// use wildside_data::export::{ExportOptions, write_flatgeobuf};
+///
+/// let dir = tempfile::tempdir().expect("temp dir");
+/// let pois = vec![PointOfInterest::new(
+///     1,
+///     Coord { x: 13.396, y: 52.521 },
+///     Tags::from([("name".into(), "Pergamon Museum".into())]),
+/// )];
+/// write_flatgeobuf(dir.path().join("pois.fgb"), &pois, &ExportOptions::default())
+///     .expect("export");
+/// ```
+pub fn write_flatgeobuf(
+    path: impl AsRef<Path>,
+    pois: &[PointOfInterest],
+    options: &ExportOptions,
+) -> Result<(), ExportError> {
+    let path = path.as_ref();
+    let fgb_error = |source| ExportError::FlatGeobuf {
+        path: path.to_path_buf(),
+        source,
+    };
+    let writer_options = FgbWriterOptions {
+        crs: FgbCrs {
+            code: WGS84_EPSG,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut writer =
+        FgbWriter::create_with_options(DATASET_NAME, GeometryType::Point, writer_options)
+            .map_err(fgb_error)?;
+    writer.add_column("id", ColumnType::ULong, |_, column| {
+        column.nullable = false;
+    });
+    writer.add_column("name", ColumnType::String, |_, _| {});
+    writer.add_column("tags", ColumnType::Json, |_, column| {
+        column.nullable = false;
+    });
+    if options.popularity.is_some() {
+        writer.add_column("popularity", ColumnType::Float, |_, _| {});
+    }
+
+    for poi in pois {
+        let row = ExportRow::new(poi, options)?;
+        let feature_error = |source| ExportError::FlatGeobufFeature { id: row.id, source };
+        let mut failure = None;
+        writer
+            .add_feature_geom(Geometry::Point(Point::new(row.lon, row.lat)), |feature| {
+                failure = write_properties(feature, &row).err();
+            })
+            .map_err(feature_error)?;
+        if let Some(source) = failure {
+            return Err(feature_error(source));
+        }
+    }
+
+    let mut out = BufWriter::new(File::create(path).map_err(io_error(path))?);
+    writer.write(&mut out).map_err(fgb_error)
+}
+
+/// Write `row`'s attributes in the column order declared by
+/// [`write_flatgeobuf`].
+fn write_properties(
+    feature: &mut impl PropertyProcessor,
+    row: &ExportRow<'_>,
+) -> Result<(), GeozeroError> {
+    feature.property(0, "id", &ColumnValue::ULong(row.id))?;
+    if let Some(name) = row.name {
+        feature.property(1, "name", &ColumnValue::String(name))?;
+    }
+    feature.property(2, "tags", &ColumnValue::Json(&row.tags))?;
+    if let Some(popularity) = row.popularity {
+        feature.property(3, "popularity", &ColumnValue::Float(popularity))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for FlatGeobuf export.
+
+    use super::*;
+    use ::flatgeobuf::{FallibleStreamingIterator, FgbReader};
+    use geo::Coord;
+    use geozero::FeatureProperties;
+    use rstest::rstest;
+    use std::{collections::HashMap, io::BufReader};
+    use tempfile::TempDir;
+    use wildside_core::Tags;
+
+    #[rstest]
+    fn writes_features_with_properties() {
+        let dir = TempDir::new().expect("temp dir");
+        let path = dir.path().join("pois.fgb");
+        let pois = vec![
+            PointOfInterest::new(
+                1,
+                Coord {
+                    x: 13.396,
+                    y: 52.521,
+                },
+                Tags::from([("name".into(), "Pergamon Museum".into())]),
+            ),
+            PointOfInterest::with_empty_tags(
+                2,
+                Coord {
+                    x: 13.377,
+                    y: 52.516,
+                },
+            ),
+        ];
+        let options = ExportOptions::default().with_popularity([(1, 0.75)]);
+
+        write_flatgeobuf(&path, &pois, &options).expect("export");
+
+        let mut file = BufReader::new(File::open(&path).expect("open export"));
+        let mut features = FgbReader::open(&mut file)
+            .expect("read header")
+            .select_all()
+            .expect("select features");
+        let mut by_id: HashMap<String, HashMap<String, String>> = HashMap::new();
+        while let Some(feature) = features.next().expect("read feature") {
+            let properties = feature.properties().expect("read properties");
+            by_id.insert(properties["id"].clone(), properties);
+        }
+
+        assert_eq!(by_id.len(), 2);
+        assert_eq!(by_id["1"]["name"], "Pergamon Museum");
+        assert_eq!(by_id["1"]["popularity"], "0.75");
+        assert!(!by_id["2"].contains_key("name"));
+        assert!(!by_id["2"].contains_key("popularity"));
+        assert_eq!(by_id["2"]["tags"], "{}");
+    }
+}
@@ -0,0 +1,270 @@
+//! GeoParquet export.
+//!
+//! Geometries are stored as ISO WKB points and described by the `geo` key in
+//! the file metadata, as GeoParquet 1.1 requires. The CRS is left implicit,
+//! which GeoParquet defines as `OGC:CRS84`: longitude, then latitude.
+
+use std::{fs::File, io::BufWriter, path::Path, sync::Arc};
+
+use arrow_array::{ArrayRef, BinaryArray, Float32Array, RecordBatch, StringArray, UInt64Array};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use parquet::{
+    arrow::ArrowWriter,
+    basic::{Compression, ZstdLevel},
+    errors::ParquetError,
+    file::{metadata::KeyValue, properties::WriterProperties},
+};
+use serde_json::json;
+use wildside_core::PointOfInterest;
+
+use super::{ExportError, ExportOptions, ExportRow, io_error};
+
+/// GeoParquet specification version the metadata follows.
+const GEOPARQUET_VERSION: &str = "1.1.0";
+
+/// POIs converted and written per record batch.
+const BATCH_ROWS: usize = 65_536;
+
+/// Length of a little-endian WKB point: byte order, type, and two doubles.
+const WKB_POINT_LEN: usize = 21;
+
+/// Write `pois` to a GeoParquet file at `path`, replacing any existing file.
+///
+/// Columns follow the table in the [module documentation](super), with the
+/// WKB `geometry` column last. Rows keep the order of `pois`.
+///
+/// # Errors
+/// Returns [`ExportError::Io`] when the file cannot be created,
+/// [`ExportError::SerializeTags`] when a tag map cannot be encoded, and
+/// [`ExportError::GeoParquet`] when the Parquet writer fails.
+///
+/// # Examples
+/// ```rust
+/// use geo::Coord;
+/// use wildside_core::{PointOfInterest, Tags};
+/// use wildside_data::export::{ExportOptions, write_geoparquet};
+///
+/// let dir = tempfile::tempdir().expect("temp dir");
+/// let pois = vec![PointOfInterest::new(
+///     1,
+///     Coord { x: 13.396, y: 52.521 },
+///     Tags::from([("name".into(), "Pergamon Museum".into())]),
+/// )];
+/// let options = ExportOptions::default().with_popularity([(1, 0.9)]);
+/// write_geoparquet(dir.path().join("pois.parquet"), &pois, &options).expect("export");
+/// ```
+pub fn write_geoparquet(
+    path: impl AsRef<Path>,
+    pois: &[PointOfInterest],
+    options: &ExportOptions,
+) -> Result<(), ExportError> {
+    let path = path.as_ref();
+    let parquet_error = |source| ExportError::GeoParquet {
+        path: path.to_path_buf(),
+        source,
+    };
+    let schema = schema(options);
+    let properties = WriterProperties::builder()
+        .set_compression(Compression::ZSTD(ZstdLevel::default()))
+        .build();
+    let file = File::create(path).map_err(io_error(path))?;
+    let mut writer =
+        ArrowWriter::try_new(BufWriter::new(file), Arc::clone(&schema), Some(properties))
+            .map_err(parquet_error)?;
+
+    for chunk in pois.chunks(BATCH_ROWS) {
+        let rows = chunk
+            .iter()
+            .map(|poi| ExportRow::new(poi, options))
+            .collect::<Result<Vec<_>, _>>()?;
+        let batch = record_batch(&schema, &rows, options)
+            .map_err(|err| parquet_error(ParquetError::from(err)))?;
+        writer.write(&batch).map_err(parquet_error)?;
+    }
+    writer.append_key_value_metadata(KeyValue::new(String::from("geo"), geo_metadata(pois)));
+    writer.close().map_err(parquet_error)?;
+    Ok(())
+}
+
+fn schema(options: &ExportOptions) -> SchemaRef {
+    let mut fields = vec![
+        Field::new("id", DataType::UInt64, false),
+        Field::new("name", DataType::Utf8, true),
+        Field::new("tags", DataType::Utf8, false),
+    ];
+    if options.popularity.is_some() {
+        fields.push(Field::new("popularity", DataType::Float32, true));
+    }
+    fields.push(Field::new("geometry", DataType::Binary, false));
+    Arc::new(Schema::new(fields))
+}
+
+fn record_batch(
+    schema: &SchemaRef,
+    rows: &[ExportRow<'_>],
+    options: &ExportOptions,
+) -> Result<RecordBatch, arrow_schema::ArrowError> {
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(UInt64Array::from_iter_values(rows.iter().map(|row| row.id))),
+        Arc::new(rows.iter().map(|row| row.name).collect::<StringArray>()),
+        Arc::new(StringArray::from_iter_values(
+            rows.iter().map(|row| row.tags.as_str()),
+        )),
+    ];
+    if options.popularity.is_some() {
+        columns.push(Arc::new(
+            rows.iter()
+                .map(|row| row.popularity)
+                .collect::<Float32Array>(),
+        ));
+    }
+    columns.push(Arc::new(BinaryArray::from_iter_values(
+        rows.iter().map(|row| wkb_point(row.lon, row.lat)),
+    )));
+    RecordBatch::try_new(Arc::clone(schema), columns)
+}
+
+/// Encode a point as little-endian ISO WKB.
+fn wkb_point(lon: f64, lat: f64) -> [u8; WKB_POINT_LEN] {
+    let mut wkb = [0_u8; WKB_POINT_LEN];
+    wkb[0] = 1;
+    wkb[1..5].copy_from_slice(&1_u32.to_le_bytes());
+    wkb[5..13].copy_from_slice(&lon.to_le_bytes());
+    wkb[13..21].copy_from_slice(&lat.to_le_bytes());
+    wkb
+}
+
+/// GeoParquet file metadata describing the `geometry` column.
+fn geo_metadata(pois: &[PointOfInterest]) -> String {
+    let mut column = json!({
+        "encoding": "WKB",
+        "geometry_types": ["Point"],
+    });
+    let bbox = pois.iter().fold(None, |bbox: Option<[f64; 4]>, poi| {
+        let (x, y) = (poi.location.x, poi.location.y);
+        Some(match bbox {
+            None => [x, y, x, y],
+            Some([min_x, min_y, max_x, max_y]) => [
+                f64::min(min_x, x),
+                f64::min(min_y, y),
+                f64::max(max_x, x),
+                f64::max(max_y, y),
+            ],
+        })
+    });
+    if let Some(bbox) = bbox {
+        column["bbox"] = json!(bbox);
+    }
+    json!({
+        "version": GEOPARQUET_VERSION,
+        "primary_column": "geometry",
+        "columns": { "geometry": column },
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for GeoParquet export.
+
+    use super::*;
+    use arrow_array::Array;
+    use geo::Coord;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use rstest::{fixture, rstest};
+    use tempfile::TempDir;
+    use wildside_core::Tags;
+
+    #[fixture]
+    fn pois() -> Vec<PointOfInterest> {
+        vec![
+            PointOfInterest::new(
+                1,
+                Coord {
+                    x: 13.396,
+                    y: 52.521,
+                },
+                Tags::from([("name".into(), "Pergamon Museum".into())]),
+            ),
+            PointOfInterest::with_empty_tags(
+                2,
+                Coord {
+                    x: 13.377,
+                    y: 52.516,
+                },
+            ),
+        ]
+    }
+
+    fn read_back(path: &Path) -> (RecordBatch, Option<String>) {
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(path).expect("open"))
+            .expect("read metadata");
+        let geo = builder
+            .metadata()
+            .file_metadata()
+            .key_value_metadata()
+            .and_then(|entries| entries.iter().find(|entry| entry.key == "geo"))
+            .and_then(|entry| entry.value.clone());
+        let mut reader = builder.build().expect("build reader");
+        let batch = reader.next().expect("one batch").expect("read batch");
+        (batch, geo)
+    }
+
+    #[rstest]
+    fn writes_columns_and_geo_metadata(pois: Vec<PointOfInterest>) {
+        let dir = TempDir::new().expect("temp dir");
+        let path = dir.path().join("pois.parquet");
+        let options = ExportOptions::default().with_popularity([(1, 0.75)]);
+
+        write_geoparquet(&path, &pois, &options).expect("export");
+
+        let (batch, geo) = read_back(&path);
+        assert_eq!(batch.num_rows(), 2);
+        let names = batch
+            .column_by_name("name")
+            .and_then(|column| column.as_any().downcast_ref::<StringArray>())
+            .expect("name column");
+        assert_eq!(names.value(0), "Pergamon Museum");
+        assert!(names.is_null(1));
+        let popularity = batch
+            .column_by_name("popularity")
+            .and_then(|column| column.as_any().downcast_ref::<Float32Array>())
+            .expect("popularity column");
+        assert_eq!(popularity.value(0), 0.75);
+        assert!(popularity.is_null(1));
+        let geometry = batch
+            .column_by_name("geometry")
+            .and_then(|column| column.as_any().downcast_ref::<BinaryArray>())
+            .expect("geometry column");
+        assert_eq!(geometry.value(0), wkb_point(13.396, 52.521));
+
+        let geo: serde_json::Value =
+            serde_json::from_str(&geo.expect("geo metadata")).expect("parse geo metadata");
+        assert_eq!(geo["primary_column"], "geometry");
+        assert_eq!(geo["columns"]["geometry"]["encoding"], "WKB");
+        assert_eq!(
+            geo["columns"]["geometry"]["bbox"],
+            json!([13.377, 52.516, 13.396, 52.521])
+        );
+    }
+
+    #[rstest]
+    fn omits_popularity_column_without_scores(pois: Vec<PointOfInterest>) {
+        let dir = TempDir::new().expect("temp dir");
+        let path = dir.path().join("pois.parquet");
+
+        write_geoparquet(&path, &pois, &ExportOptions::default()).expect("export");
+
+        let (batch, _) = read_back(&path);
+        assert!(batch.column_by_name("popularity").is_none());
+    }
+
+    #[rstest]
+    fn encodes_points_as_iso_wkb() {
+        let wkb = wkb_point(1.5, -2.0);
+        assert_eq!(wkb[0], 1);
+        assert_eq!(wkb[1..5], 1_u32.to_le_bytes());
+        assert_eq!(wkb[5..13], 1.5_f64.to_le_bytes());
+        assert_eq!(wkb[13..21], (-2.0_f64).to_le_bytes());
+    }
+}
@@ -0,0 +1,151 @@
+//! Export ingested POIs to interchange formats read by geospatial tooling.
+//!
+//! The artefacts in an ingest directory are tuned for the engine: `pois.db`
+//! stores tags as JSON and `pois.rstar` is a private index. The writers here
+//! turn a set of POIs into files that QGIS, GDAL, and `DuckDB` open directly:
+//!
+//! - [`write_geoparquet`] (feature `export-geoparquet`) writes a GeoParquet
+//!   1.1 file with WKB point geometries.
+//! - [`write_flatgeobuf`] (feature `export-flatgeobuf`) writes a FlatGeobuf
+//!   file with a packed Hilbert R-tree, so viewers can stream a region of it.
+//!
+//! Both write the same attributes for every POI:
+//!
+//! | Column       | Contents                                             |
+//! | ------------ | ---------------------------------------------------- |
+//! | `id`         | POI identifier                                       |
+//! | `name`       | `name` tag, null when absent                         |
+//! | `tags`       | every tag as a JSON object                           |
+//! | `popularity` | score from [`ExportOptions::popularity`], if given   |
+//!
+//! Geometries are WGS84 longitude and latitude. `popularity.bin` is owned by
+//! `wildside-scorer`, which depends on this crate, so callers decode it
+//! themselves and pass the scores through [`ExportOptions::with_popularity`].
+
+use std::{
+    collections::BTreeMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+use wildside_core::PointOfInterest;
+
+#[cfg(feature = "export-flatgeobuf")]
+mod flatgeobuf;
+#[cfg(feature = "export-geoparquet")]
+mod geoparquet;
+
+#[cfg(feature = "export-flatgeobuf")]
+#[cfg_attr(docsrs, doc(cfg(feature = "export-flatgeobuf")))]
+pub use self::flatgeobuf::write_flatgeobuf;
+#[cfg(feature = "export-geoparquet")]
+#[cfg_attr(docsrs, doc(cfg(feature = "export-geoparquet")))]
+pub use self::geoparquet::write_geoparquet;
+
+/// Errors raised while exporting POIs.
+#[derive(Debug, Error)]
+pub enum ExportError {
+    /// Creating or writing the output file failed.
+    #[error("failed to write export {path:?}: {source}")]
+    Io {
+        /// Output path.
+        path: PathBuf,
+        /// Underlying I/O error.
+        #[source]
+        source: io::Error,
+    },
+    /// A POI's tags could not be encoded as JSON.
+    #[error("failed to encode tags for POI {id}: {source}")]
+    SerializeTags {
+        /// Identifier of the POI.
+        id: u64,
+        /// JSON encoding failure.
+        #[source]
+        source: serde_json::Error,
+    },
+    /// Building or writing the Parquet file failed.
+    #[cfg(feature = "export-geoparquet")]
+    #[error("failed to write GeoParquet {path:?}: {source}")]
+    GeoParquet {
+        /// Output path.
+        path: PathBuf,
+        /// Error reported by the Parquet writer.
+        #[source]
+        source: parquet::errors::ParquetError,
+    },
+    /// Building or writing the FlatGeobuf file failed.
+    #[cfg(feature = "export-flatgeobuf")]
+    #[error("failed to write FlatGeobuf {path:?}: {source}")]
+    FlatGeobuf {
+        /// Output path.
+        path: PathBuf,
+        /// Error reported by the FlatGeobuf writer.
+        #[source]
+        source: ::flatgeobuf::Error,
+    },
+    /// A POI's geometry or attributes could not be encoded as a FlatGeobuf
+    /// feature.
+    #[cfg(feature = "export-flatgeobuf")]
+    #[error("failed to encode POI {id} as a FlatGeobuf feature: {source}")]
+    FlatGeobufFeature {
+        /// Identifier of the POI.
+        id: u64,
+        /// Error reported by the feature encoder.
+        #[source]
+        source: geozero::error::GeozeroError,
+    },
+}
+
+/// Optional inputs for the export writers.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExportOptions {
+    /// Popularity scores keyed by POI identifier; `None` omits the
+    /// `popularity` column.
+    pub popularity: Option<BTreeMap<u64, f32>>,
+}
+
+impl ExportOptions {
+    /// Add a `popularity` column filled from `scores` while consuming
+    /// `self`. POIs without a score get a null.
+    #[must_use]
+    pub fn with_popularity(mut self, scores: impl IntoIterator<Item = (u64, f32)>) -> Self {
+        self.popularity = Some(scores.into_iter().collect());
+        self
+    }
+}
+
+/// Attributes written for one POI.
+struct ExportRow<'a> {
+    id: u64,
+    lon: f64,
+    lat: f64,
+    name: Option<&'a str>,
+    tags: String,
+    popularity: Option<f32>,
+}
+
+impl<'a> ExportRow<'a> {
+    fn new(poi: &'a PointOfInterest, options: &ExportOptions) -> Result<Self, ExportError> {
+        let tags = serde_json::to_string(&poi.tags)
+            .map_err(|source| ExportError::SerializeTags { id: poi.id, source })?;
+        Ok(Self {
+            id: poi.id,
+            lon: poi.location.x,
+            lat: poi.location.y,
+            name: poi.tags.get("name").map(String::as_str),
+            tags,
+            popularity: options
+                .popularity
+                .as_ref()
+                .and_then(|scores| scores.get(&poi.id).copied()),
+        })
+    }
+}
+
+fn io_error(path: &Path) -> impl Fn(io::Error) -> ExportError + '_ {
+    move |source| ExportError::Io {
+        path: path.to_path_buf(),
+        source,
+    }
+}
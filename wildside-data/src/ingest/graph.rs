@@ -0,0 +1,237 @@
+//! Pedestrian routing graph extraction.
+//!
+//! Walkable ways are read in a parallel first pass that records their node
+//! references, and a second pass hydrates the coordinates of just those nodes.
+//! Consecutive resolved nodes of each way become an edge of the
+//! [`RoutingGraph`]; segments touching a node without coordinates are
+//! dropped.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use log::warn;
+use osmpbf::{Element, ElementReader, Way};
+use wildside_core::travel_time::{RoutingGraph, RoutingGraphBuilder};
+
+use super::OsmIngestError;
+use super::accumulator::validated_coord;
+
+/// `highway` values pedestrians may use unless other tags forbid it.
+const WALKABLE_HIGHWAYS: &[&str] = &[
+    "footway",
+    "path",
+    "pedestrian",
+    "steps",
+    "living_street",
+    "residential",
+    "service",
+    "track",
+    "unclassified",
+    "cycleway",
+    "bridleway",
+    "corridor",
+    "road",
+    "tertiary",
+    "tertiary_link",
+    "secondary",
+    "secondary_link",
+    "primary",
+    "primary_link",
+];
+
+/// Node references of the walkable ways in one part of the file.
+#[derive(Debug, Default)]
+struct WalkableWays {
+    ways: Vec<Vec<i64>>,
+}
+
+impl WalkableWays {
+    fn combine(mut self, other: Self) -> Self {
+        self.ways.extend(other.ways);
+        self
+    }
+}
+
+/// Build the pedestrian routing graph for the OSM PBF file at `path`.
+///
+/// # Examples
+/// ```no_run
+/// use std::path::Path;
+/// use wildside_data::build_routing_graph;
+///
+/// # fn main() -> Result<(), wildside_data::OsmIngestError> {
+/// let graph = build_routing_graph(Path::new("berlin.osm.pbf"))?;
+/// println!("Routing over {} nodes", graph.node_count());
+/// # Ok(())
+/// # }
+/// ```
+pub fn build_routing_graph(path: &Path) -> Result<RoutingGraph, OsmIngestError> {
+    build_graph(path, |way| is_walkable(way.tags()))
+}
+
+/// Build the step-free routing graph for the OSM PBF file at `path`.
+///
+/// The graph holds the walkable ways of [`build_routing_graph`] except
+/// stairs and ways tagged `wheelchair=no`, for routing visitors who cannot
+/// climb steps.
+///
+/// # Examples
+/// ```no_run
+/// use std::path::Path;
+/// use wildside_data::build_step_free_routing_graph;
+///
+/// # fn main() -> Result<(), wildside_data::OsmIngestError> {
+/// let graph = build_step_free_routing_graph(Path::new("berlin.osm.pbf"))?;
+/// println!("Routing step-free over {} nodes", graph.node_count());
+/// # Ok(())
+/// # }
+/// ```
+pub fn build_step_free_routing_graph(path: &Path) -> Result<RoutingGraph, OsmIngestError> {
+    build_graph(path, |way| {
+        is_walkable(way.tags()) && is_step_free(way.tags())
+    })
+}
+
+/// Build a routing graph from the ways in the file at `path` that `include`
+/// accepts.
+fn build_graph(
+    path: &Path,
+    include: impl Fn(&Way<'_>) -> bool + Sync + Send,
+) -> Result<RoutingGraph, OsmIngestError> {
+    let open = || {
+        ElementReader::from_path(path).map_err(|source| OsmIngestError::Open {
+            source,
+            path: path.to_path_buf(),
+        })
+    };
+    let decode_error = |source| OsmIngestError::Decode {
+        source,
+        path: path.to_path_buf(),
+    };
+
+    let walkable = open()?
+        .par_map_reduce(
+            |element| match element {
+                Element::Way(way) if include(&way) => WalkableWays {
+                    ways: vec![way.refs().collect()],
+                },
+                _ => WalkableWays::default(),
+            },
+            WalkableWays::default,
+            WalkableWays::combine,
+        )
+        .map_err(decode_error)?;
+
+    let wanted: HashSet<i64> = walkable.ways.iter().flatten().copied().collect();
+    let mut coords = HashMap::with_capacity(wanted.len());
+    open()?
+        .for_each(|element| {
+            let (id, lon, lat) = match element {
+                Element::Node(node) => (node.id(), node.lon(), node.lat()),
+                Element::DenseNode(node) => (node.id(), node.lon(), node.lat()),
+                Element::Way(_) | Element::Relation(_) => return,
+            };
+            if wanted.contains(&id)
+                && let Some(coord) = validated_coord(lon, lat)
+            {
+                coords.insert(id, coord);
+            }
+        })
+        .map_err(decode_error)?;
+    if coords.len() < wanted.len() {
+        warn!(
+            "Skipped {} walkable way node references without coordinates",
+            wanted.len() - coords.len()
+        );
+    }
+
+    let mut builder = RoutingGraphBuilder::default();
+    let mut nodes: HashMap<i64, u32> = HashMap::with_capacity(coords.len());
+    for refs in &walkable.ways {
+        let mut previous = None;
+        for osm_id in refs {
+            let node = nodes.get(osm_id).copied().or_else(|| {
+                let added = builder.add_node(*coords.get(osm_id)?)?;
+                nodes.insert(*osm_id, added);
+                Some(added)
+            });
+            if let (Some(from), Some(to)) = (previous, node) {
+                builder.add_edge(from, to);
+            }
+            previous = node;
+        }
+    }
+    Ok(builder.build())
+}
+
+/// Report whether a way with `tags` may be walked.
+///
+/// Only `highway` ways are considered. An explicit `foot` tag decides;
+/// otherwise `access=no` or `access=private` excludes the way, and the
+/// `highway` value must be one pedestrians use.
+fn is_walkable<'a>(tags: impl IntoIterator<Item = (&'a str, &'a str)>) -> bool {
+    let (mut highway, mut foot, mut access) = (None, None, None);
+    for (key, value) in tags {
+        match key {
+            "highway" => highway = Some(value),
+            "foot" => foot = Some(value),
+            "access" => access = Some(value),
+            _ => {}
+        }
+    }
+    let Some(highway) = highway else {
+        return false;
+    };
+    match foot {
+        Some("yes" | "designated" | "permissive" | "destination") => return true,
+        Some("no" | "private" | "use_sidepath") => return false,
+        _ => {}
+    }
+    if matches!(access, Some("no" | "private")) {
+        return false;
+    }
+    WALKABLE_HIGHWAYS.contains(&highway)
+}
+
+/// Report whether a way with `tags` can be used without climbing steps.
+///
+/// Stairs and ways explicitly tagged `wheelchair=no` are excluded; other
+/// ways are assumed passable.
+fn is_step_free<'a>(tags: impl IntoIterator<Item = (&'a str, &'a str)>) -> bool {
+    !tags
+        .into_iter()
+        .any(|tag| matches!(tag, ("highway", "steps") | ("wheelchair", "no")))
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for walkable way classification.
+
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(&[("highway", "footway")], true)]
+    #[case(&[("highway", "residential"), ("name", "Unter den Linden")], true)]
+    #[case(&[("highway", "motorway")], false)]
+    #[case(&[("highway", "motorway"), ("foot", "yes")], true)]
+    #[case(&[("highway", "footway"), ("foot", "no")], false)]
+    #[case(&[("highway", "path"), ("access", "private")], false)]
+    #[case(&[("highway", "service"), ("access", "private"), ("foot", "permissive")], true)]
+    #[case(&[("building", "yes")], false)]
+    #[case(&[("railway", "platform"), ("foot", "yes")], false)]
+    fn classifies_walkable_ways(#[case] tags: &[(&str, &str)], #[case] expected: bool) {
+        assert_eq!(is_walkable(tags.iter().copied()), expected);
+    }
+
+    #[rstest]
+    #[case(&[("highway", "footway")], true)]
+    #[case(&[("highway", "footway"), ("wheelchair", "yes")], true)]
+    #[case(&[("highway", "steps")], false)]
+    #[case(&[("highway", "path"), ("wheelchair", "no")], false)]
+    fn classifies_step_free_ways(#[case] tags: &[(&str, &str)], #[case] expected: bool) {
+        assert_eq!(is_step_free(tags.iter().copied()), expected);
+    }
+}
@@ -8,6 +8,9 @@
 //! - [`ingest_osm_pbf`] for a summary only
 //! - [`ingest_osm_pbf_report`] for a summary plus derived POIs
 //! - [`persist_pois_to_sqlite`] to persist POIs to a SQLite database
+//! - [`build_routing_graph`] to extract the pedestrian routing graph
+//! - [`build_step_free_routing_graph`] to extract the same graph without
+//!   stairs
 //!
 //! This module is thread-safe and performs a second pass to hydrate coordinates
 //! for node references required by relevant ways.
@@ -20,10 +23,12 @@ use thiserror::Error;
 use wildside_core::PointOfInterest;
 
 mod accumulator;
+mod graph;
 mod ids;
 mod sqlite;
 mod tags;
 
+pub use graph::{build_routing_graph, build_step_free_routing_graph};
 pub use sqlite::{PersistPoisError, persist_pois_to_sqlite};
 
 use accumulator::OsmPoiAccumulator;
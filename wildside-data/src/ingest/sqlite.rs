@@ -6,6 +6,8 @@ use rusqlite::{Connection, Error as SqliteError, Transaction};
 use serde_json::to_string;
 use thiserror::Error;
 use wildside_core::PointOfInterest;
+use wildside_core::formats::{FormatVersionError, POIS_SCHEMA};
+use wildside_core::store::install_poi_name_search;
 
 /// Errors raised when persisting ingested POIs to SQLite.
 #[derive(Debug, Error)]
@@ -42,13 +44,16 @@ pub enum PersistPoisError {
         #[source]
         source: SqliteError,
     },
-    /// Creating the `pois` table failed.
+    /// Creating the `pois` table or its name index failed.
     #[error("failed to create pois table: {source}")]
     CreateSchema {
         /// Source error returned by `rusqlite`.
         #[source]
         source: SqliteError,
     },
+    /// The existing database uses a `pois` schema this build cannot write.
+    #[error(transparent)]
+    UnsupportedSchema(#[from] FormatVersionError),
     /// A POI identifier could not be represented as an SQLite integer.
     #[error("POI id {poi_id} exceeds SQLite i64 range")]
     PoiIdOutOfRange {
@@ -132,6 +137,11 @@ fn ensure_parent_dir(path: &Utf8Path) -> Result<(), PersistPoisError> {
 }
 
 fn create_schema(transaction: &Transaction<'_>) -> Result<(), PersistPoisError> {
+    let create_error = |source| PersistPoisError::CreateSchema { source };
+    let existing: i64 = transaction
+        .pragma_query_value(None, "user_version", |row| row.get(0))
+        .map_err(create_error)?;
+    POIS_SCHEMA.negotiate(existing)?;
     transaction
         .execute(
             "CREATE TABLE IF NOT EXISTS pois (
@@ -142,8 +152,11 @@ fn create_schema(transaction: &Transaction<'_>) -> Result<(), PersistPoisError>
             )",
             [],
         )
-        .map(|_| ())
-        .map_err(|source| PersistPoisError::CreateSchema { source })
+        .map_err(create_error)?;
+    install_poi_name_search(transaction, existing).map_err(create_error)?;
+    transaction
+        .pragma_update(None, "user_version", POIS_SCHEMA.current)
+        .map_err(create_error)
 }
 
 fn persist_rows(
@@ -226,6 +239,81 @@ mod tests {
         assert!(stored.3.contains("Example"));
     }
 
+    #[rstest]
+    fn indexes_poi_names(temp_dir: TempDir, poi: PointOfInterest) {
+        let db_path =
+            Utf8PathBuf::from_path_buf(temp_dir.path().join("pois.db")).expect("utf-8 path");
+        let unnamed = PointOfInterest::with_empty_tags(8, Coord { x: 1.0, y: 2.0 });
+
+        persist_pois_to_sqlite(&db_path, &[poi, unnamed]).expect("persist POIs");
+
+        let conn = Connection::open(db_path.as_std_path()).expect("open database");
+        let ids: Vec<i64> = conn
+            .prepare("SELECT rowid FROM poi_names WHERE poi_names MATCH 'example'")
+            .expect("prepare search")
+            .query_map([], |row| row.get(0))
+            .expect("search names")
+            .collect::<Result<_, _>>()
+            .expect("read ids");
+        assert_eq!(ids, vec![7]);
+    }
+
+    #[rstest]
+    fn reingest_replaces_indexed_name(temp_dir: TempDir, poi: PointOfInterest) {
+        let db_path =
+            Utf8PathBuf::from_path_buf(temp_dir.path().join("pois.db")).expect("utf-8 path");
+        let renamed = PointOfInterest::new(
+            poi.id,
+            poi.location,
+            Tags::from([("name".into(), "Renamed".into())]),
+        );
+
+        persist_pois_to_sqlite(&db_path, &[poi]).expect("persist POIs");
+        persist_pois_to_sqlite(&db_path, &[renamed]).expect("persist renamed POIs");
+
+        let conn = Connection::open(db_path.as_std_path()).expect("open database");
+        let names: Vec<String> = conn
+            .prepare("SELECT name FROM poi_names")
+            .expect("prepare query")
+            .query_map([], |row| row.get(0))
+            .expect("read names")
+            .collect::<Result<_, _>>()
+            .expect("collect names");
+        assert_eq!(names, vec![String::from("Renamed")]);
+    }
+
+    #[rstest]
+    fn stamps_pois_schema_version(temp_dir: TempDir, poi: PointOfInterest) {
+        let db_path =
+            Utf8PathBuf::from_path_buf(temp_dir.path().join("pois.db")).expect("utf-8 path");
+
+        persist_pois_to_sqlite(&db_path, &[poi]).expect("persist POIs");
+
+        let conn = Connection::open(db_path.as_std_path()).expect("open database");
+        let version: i64 = conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .expect("read schema version");
+        assert_eq!(version, i64::from(POIS_SCHEMA.current));
+    }
+
+    #[rstest]
+    fn rejects_newer_pois_schema(temp_dir: TempDir, poi: PointOfInterest) {
+        let db_path =
+            Utf8PathBuf::from_path_buf(temp_dir.path().join("pois.db")).expect("utf-8 path");
+        let newer = POIS_SCHEMA.current + 1;
+        Connection::open(db_path.as_std_path())
+            .expect("create database")
+            .pragma_update(None, "user_version", newer)
+            .expect("stamp newer version");
+
+        let err = persist_pois_to_sqlite(&db_path, &[poi]).expect_err("newer schema should fail");
+        assert!(matches!(
+            err,
+            PersistPoisError::UnsupportedSchema(FormatVersionError { found, .. })
+                if found == i64::from(newer)
+        ));
+    }
+
     #[rstest]
     fn creates_parent_directory(temp_dir: TempDir, poi: PointOfInterest) {
         let nested =
@@ -0,0 +1,342 @@
+//! Cross-artefact integrity checks for an ingested artefact set.
+//!
+//! Each artefact validates its own header when opened, but nothing checks
+//! that the artefacts still agree with one another: an index rebuilt from a
+//! different extract, a claims table edited by hand, or a stale
+//! `popularity.bin` all open cleanly and fail later, one POI at a time.
+//! [`verify_artifacts`] reads the whole set and returns an
+//! [`IntegrityReport`] listing every kind of disagreement it finds instead of
+//! stopping at the first.
+//!
+//! `popularity.bin` is owned by `wildside-scorer`, which depends on this
+//! crate, so callers decode it themselves and pass its identifiers through
+//! [`VerifyOptions::with_popularity_ids`].
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
+use thiserror::Error;
+use wildside_core::store::{SpatialIndexError, read_spatial_index};
+
+/// Maximum number of offending identifiers kept per issue.
+pub const ISSUE_SAMPLE_SIZE: usize = 20;
+
+const POIS_DB_FILE_NAME: &str = "pois.db";
+const SPATIAL_INDEX_FILE_NAME: &str = "pois.rstar";
+const POI_LOCATIONS_SQL: &str = "SELECT id, lon, lat FROM pois ORDER BY id";
+
+/// Errors that prevent the checks from running at all.
+///
+/// Inconsistencies between artefacts are not errors; they are reported in
+/// the returned [`IntegrityReport`].
+#[derive(Debug, Error)]
+pub enum VerifyError {
+    /// `pois.db` could not be opened.
+    #[error("failed to open POI database {path:?}: {source}")]
+    OpenDatabase {
+        /// Database path.
+        path: PathBuf,
+        /// Error reported by `SQLite`.
+        #[source]
+        source: rusqlite::Error,
+    },
+    /// Querying `pois.db` failed.
+    #[error("failed to query POI database {path:?}: {source}")]
+    QueryDatabase {
+        /// Database path.
+        path: PathBuf,
+        /// Error reported by `SQLite`.
+        #[source]
+        source: rusqlite::Error,
+    },
+    /// `pois.rstar` could not be read.
+    #[error("failed to read spatial index {path:?}: {source}")]
+    ReadSpatialIndex {
+        /// Spatial index path.
+        path: PathBuf,
+        /// Error reported by the index reader.
+        #[source]
+        source: SpatialIndexError,
+    },
+}
+
+/// Optional inputs for [`verify_artifacts_with_options`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyOptions {
+    /// Identifiers scored in `popularity.bin`; `None` skips the coverage
+    /// check.
+    pub popularity_ids: Option<BTreeSet<u64>>,
+}
+
+impl VerifyOptions {
+    /// Check popularity coverage against `ids` while consuming `self`.
+    #[must_use]
+    pub fn with_popularity_ids(mut self, ids: impl IntoIterator<Item = u64>) -> Self {
+        self.popularity_ids = Some(ids.into_iter().collect());
+        self
+    }
+}
+
+/// Number of identifiers affected by an issue, with the first few of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AffectedIds {
+    /// Total number of affected identifiers.
+    pub count: usize,
+    /// Up to [`ISSUE_SAMPLE_SIZE`] affected identifiers, in ascending order.
+    pub sample_ids: Vec<u64>,
+}
+
+impl AffectedIds {
+    fn push(&mut self, id: u64) {
+        self.count += 1;
+        if self.sample_ids.len() < ISSUE_SAMPLE_SIZE {
+            self.sample_ids.push(id);
+        }
+    }
+}
+
+/// One kind of disagreement between artefacts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IntegrityIssue {
+    /// `pois.rstar` indexes POIs that have no row in `pois.db`.
+    IndexedPoisMissing(AffectedIds),
+    /// `pois.db` holds POIs that `pois.rstar` does not index, so spatial
+    /// queries never return them.
+    UnindexedPois(AffectedIds),
+    /// `pois.rstar` places POIs somewhere other than `pois.db` does.
+    LocationMismatches(AffectedIds),
+    /// Rows in `pois.db` reference a parent row that does not exist.
+    ForeignKeyViolations {
+        /// Table holding the dangling references.
+        table: String,
+        /// Table the references should point into.
+        parent: String,
+        /// Number of dangling rows.
+        count: usize,
+    },
+    /// POIs in `pois.db` without a popularity score.
+    UnscoredPois(AffectedIds),
+    /// Popularity scores for POIs absent from `pois.db`.
+    OrphanPopularityScores(AffectedIds),
+}
+
+/// Outcome of [`verify_artifacts`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct IntegrityReport {
+    /// Rows in the `pois` table.
+    pub pois: usize,
+    /// Entries in the spatial index.
+    pub index_entries: usize,
+    /// Scores checked for coverage; `None` when the check was skipped.
+    pub popularity_scores: Option<usize>,
+    /// Disagreements found, empty when the artefacts are consistent.
+    pub issues: Vec<IntegrityIssue>,
+}
+
+impl IntegrityReport {
+    /// Whether every check passed.
+    #[must_use]
+    pub fn is_consistent(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Cross-check `pois.db` and `pois.rstar` in `dir`.
+///
+/// Equivalent to [`verify_artifacts_with_options`] with default options, so
+/// popularity coverage is not checked.
+///
+/// # Errors
+/// Returns [`VerifyError`] when an artefact cannot be read.
+pub fn verify_artifacts(dir: impl AsRef<Path>) -> Result<IntegrityReport, VerifyError> {
+    verify_artifacts_with_options(dir, &VerifyOptions::default())
+}
+
+/// Cross-check the artefacts in `dir`, reporting every inconsistency found.
+///
+/// The spatial index must list exactly the POIs in `pois.db`, at the same
+/// locations; every foreign key in `pois.db`, including the claims tables,
+/// must resolve; and, when [`VerifyOptions::popularity_ids`] is set, the
+/// scored identifiers must match the stored POIs one for one.
+///
+/// # Errors
+/// Returns [`VerifyError`] when an artefact cannot be read.
+///
+/// # Examples
+/// ```
+/// use geo::Coord;
+/// use rusqlite::Connection;
+/// use tempfile::tempdir;
+/// use wildside_core::{PointOfInterest, store::write_spatial_index};
+/// use wildside_data::integrity::{IntegrityIssue, VerifyOptions, verify_artifacts_with_options};
+///
+/// let dir = tempdir().expect("create temp directory");
+/// Connection::open(dir.path().join("pois.db"))
+///     .expect("create database")
+///     .execute_batch(
+///         "CREATE TABLE pois (id INTEGER PRIMARY KEY, lon REAL, lat REAL, tags TEXT);
+///          INSERT INTO pois VALUES (1, 0.0, 0.0, '{}');",
+///     )
+///     .expect("seed database");
+/// let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+/// write_spatial_index(&dir.path().join("pois.rstar"), &[poi]).expect("write index");
+///
+/// let options = VerifyOptions::default().with_popularity_ids([1, 2]);
+/// let report = verify_artifacts_with_options(dir.path(), &options).expect("verify");
+/// assert!(matches!(
+///     report.issues.as_slice(),
+///     [IntegrityIssue::OrphanPopularityScores(ids)] if ids.sample_ids == [2]
+/// ));
+/// ```
+pub fn verify_artifacts_with_options(
+    dir: impl AsRef<Path>,
+    options: &VerifyOptions,
+) -> Result<IntegrityReport, VerifyError> {
+    let dir = dir.as_ref();
+    let db_path = dir.join(POIS_DB_FILE_NAME);
+    let index_path = dir.join(SPATIAL_INDEX_FILE_NAME);
+    let query_error = |source| VerifyError::QueryDatabase {
+        path: db_path.clone(),
+        source,
+    };
+
+    let connection = Connection::open_with_flags(&db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|source| VerifyError::OpenDatabase {
+            path: db_path.clone(),
+            source,
+        })?;
+    let stored = poi_locations(&connection).map_err(query_error)?;
+    let mut indexed =
+        read_spatial_index(&index_path).map_err(|source| VerifyError::ReadSpatialIndex {
+            path: index_path.clone(),
+            source,
+        })?;
+    indexed.sort_unstable_by_key(|poi| poi.id);
+
+    let mut report = IntegrityReport {
+        pois: stored.len(),
+        index_entries: indexed.len(),
+        popularity_scores: options.popularity_ids.as_ref().map(BTreeSet::len),
+        issues: Vec::new(),
+    };
+    compare_index(&stored, &indexed, &mut report.issues);
+    report
+        .issues
+        .extend(foreign_key_violations(&connection).map_err(query_error)?);
+    if let Some(scored) = &options.popularity_ids {
+        compare_popularity(&stored, scored, &mut report.issues);
+    }
+    Ok(report)
+}
+
+/// Identifiers and locations of every stored POI, ordered by identifier.
+fn poi_locations(connection: &Connection) -> rusqlite::Result<Vec<(u64, f64, f64)>> {
+    let mut statement = connection.prepare(POI_LOCATIONS_SQL)?;
+    let rows = statement.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+    rows.collect()
+}
+
+/// Walk the stored and indexed POIs in identifier order, recording entries
+/// present on one side only and entries whose locations differ.
+fn compare_index(
+    stored: &[(u64, f64, f64)],
+    indexed: &[wildside_core::PointOfInterest],
+    issues: &mut Vec<IntegrityIssue>,
+) {
+    let mut missing = AffectedIds::default();
+    let mut unindexed = AffectedIds::default();
+    let mut moved = AffectedIds::default();
+    let mut stored_iter = stored.iter().peekable();
+    let mut indexed_iter = indexed.iter().peekable();
+    loop {
+        match (stored_iter.peek(), indexed_iter.peek()) {
+            (Some(&&(id, lon, lat)), Some(poi)) if id == poi.id => {
+                if poi.location.x != lon || poi.location.y != lat {
+                    moved.push(id);
+                }
+                stored_iter.next();
+                indexed_iter.next();
+            }
+            (Some(&&(id, _, _)), Some(poi)) if id < poi.id => {
+                unindexed.push(id);
+                stored_iter.next();
+            }
+            (_, Some(poi)) => {
+                missing.push(poi.id);
+                indexed_iter.next();
+            }
+            (Some(&&(id, _, _)), None) => {
+                unindexed.push(id);
+                stored_iter.next();
+            }
+            (None, None) => break,
+        }
+    }
+    push_if_any(issues, missing, IntegrityIssue::IndexedPoisMissing);
+    push_if_any(issues, unindexed, IntegrityIssue::UnindexedPois);
+    push_if_any(issues, moved, IntegrityIssue::LocationMismatches);
+}
+
+/// Dangling references reported by `PRAGMA foreign_key_check`, grouped by
+/// child and parent table.
+fn foreign_key_violations(connection: &Connection) -> rusqlite::Result<Vec<IntegrityIssue>> {
+    let mut counts: BTreeMap<(String, String), usize> = BTreeMap::new();
+    let mut statement = connection.prepare("PRAGMA foreign_key_check")?;
+    let mut rows = statement.query([])?;
+    while let Some(row) = rows.next()? {
+        *counts.entry((row.get(0)?, row.get(2)?)).or_default() += 1;
+    }
+    Ok(counts
+        .into_iter()
+        .map(
+            |((table, parent), count)| IntegrityIssue::ForeignKeyViolations {
+                table,
+                parent,
+                count,
+            },
+        )
+        .collect())
+}
+
+/// Record stored POIs without a score and scores without a stored POI.
+fn compare_popularity(
+    stored: &[(u64, f64, f64)],
+    scored: &BTreeSet<u64>,
+    issues: &mut Vec<IntegrityIssue>,
+) {
+    let mut unscored = AffectedIds::default();
+    for &(id, _, _) in stored {
+        if !scored.contains(&id) {
+            unscored.push(id);
+        }
+    }
+    let mut orphans = AffectedIds::default();
+    for &id in scored {
+        if stored
+            .binary_search_by_key(&id, |&(stored_id, _, _)| stored_id)
+            .is_err()
+        {
+            orphans.push(id);
+        }
+    }
+    push_if_any(issues, unscored, IntegrityIssue::UnscoredPois);
+    push_if_any(issues, orphans, IntegrityIssue::OrphanPopularityScores);
+}
+
+fn push_if_any(
+    issues: &mut Vec<IntegrityIssue>,
+    ids: AffectedIds,
+    issue: fn(AffectedIds) -> IntegrityIssue,
+) {
+    if ids.count > 0 {
+        issues.push(issue(ids));
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,151 @@
+//! Unit tests for cross-artefact integrity checks.
+
+use geo::Coord;
+use rstest::{fixture, rstest};
+use tempfile::TempDir;
+use wildside_core::{PointOfInterest, store::write_spatial_index};
+
+use super::*;
+
+#[fixture]
+fn tmp() -> TempDir {
+    TempDir::new().expect("create temp directory")
+}
+
+fn poi(id: u64, x: f64) -> PointOfInterest {
+    PointOfInterest::with_empty_tags(id, Coord { x, y: 0.0 })
+}
+
+fn write_artefacts(tmp: &TempDir, stored: &[PointOfInterest], indexed: &[PointOfInterest]) {
+    let connection = Connection::open(tmp.path().join(POIS_DB_FILE_NAME)).expect("open db");
+    connection
+        .execute_batch(
+            "CREATE TABLE pois (id INTEGER PRIMARY KEY, lon REAL, lat REAL, tags TEXT);
+             CREATE TABLE poi_wikidata_links (
+                 poi_id INTEGER NOT NULL REFERENCES pois(id),
+                 entity_id TEXT NOT NULL
+             );",
+        )
+        .expect("create schema");
+    for stored_poi in stored {
+        connection
+            .execute(
+                "INSERT INTO pois VALUES (?1, ?2, ?3, '{}')",
+                (
+                    i64::try_from(stored_poi.id).expect("id fits in SQLite"),
+                    stored_poi.location.x,
+                    stored_poi.location.y,
+                ),
+            )
+            .expect("insert poi");
+    }
+    write_spatial_index(&tmp.path().join(SPATIAL_INDEX_FILE_NAME), indexed).expect("write index");
+}
+
+#[rstest]
+fn consistent_artefacts_report_no_issues(tmp: TempDir) {
+    let pois = [poi(1, 0.0), poi(2, 1.0)];
+    write_artefacts(&tmp, &pois, &pois);
+
+    let options = VerifyOptions::default().with_popularity_ids([1, 2]);
+    let report = verify_artifacts_with_options(tmp.path(), &options).expect("verify");
+
+    assert!(report.is_consistent(), "unexpected issues: {report:?}");
+    assert_eq!(report.pois, 2);
+    assert_eq!(report.index_entries, 2);
+    assert_eq!(report.popularity_scores, Some(2));
+}
+
+#[rstest]
+fn reports_index_disagreements(tmp: TempDir) {
+    write_artefacts(
+        &tmp,
+        &[poi(1, 0.0), poi(2, 1.0), poi(4, 3.0)],
+        &[poi(2, 1.5), poi(3, 2.0), poi(4, 3.0)],
+    );
+
+    let report = verify_artifacts(tmp.path()).expect("verify");
+
+    let ids = |sample_ids: &[u64]| AffectedIds {
+        count: sample_ids.len(),
+        sample_ids: sample_ids.to_vec(),
+    };
+    assert_eq!(
+        report.issues,
+        [
+            IntegrityIssue::IndexedPoisMissing(ids(&[3])),
+            IntegrityIssue::UnindexedPois(ids(&[1])),
+            IntegrityIssue::LocationMismatches(ids(&[2])),
+        ]
+    );
+}
+
+#[rstest]
+fn reports_dangling_claim_links(tmp: TempDir) {
+    let pois = [poi(1, 0.0)];
+    write_artefacts(&tmp, &pois, &pois);
+    Connection::open(tmp.path().join(POIS_DB_FILE_NAME))
+        .expect("open db")
+        .execute_batch(
+            "PRAGMA foreign_keys = OFF;
+             INSERT INTO poi_wikidata_links VALUES (1, 'Q1'), (7, 'Q2'), (8, 'Q3');",
+        )
+        .expect("insert links");
+
+    let report = verify_artifacts(tmp.path()).expect("verify");
+
+    assert_eq!(
+        report.issues,
+        [IntegrityIssue::ForeignKeyViolations {
+            table: "poi_wikidata_links".to_owned(),
+            parent: "pois".to_owned(),
+            count: 2,
+        }]
+    );
+}
+
+#[rstest]
+fn reports_popularity_coverage_gaps(tmp: TempDir) {
+    let pois = [poi(1, 0.0), poi(2, 1.0)];
+    write_artefacts(&tmp, &pois, &pois);
+
+    let options = VerifyOptions::default().with_popularity_ids([2, 9]);
+    let report = verify_artifacts_with_options(tmp.path(), &options).expect("verify");
+
+    assert_eq!(
+        report.issues,
+        [
+            IntegrityIssue::UnscoredPois(AffectedIds {
+                count: 1,
+                sample_ids: vec![1],
+            }),
+            IntegrityIssue::OrphanPopularityScores(AffectedIds {
+                count: 1,
+                sample_ids: vec![9],
+            }),
+        ]
+    );
+}
+
+#[rstest]
+fn samples_are_capped(tmp: TempDir) {
+    let stored: Vec<PointOfInterest> = (0..50).map(|id| poi(id, 0.0)).collect();
+    write_artefacts(&tmp, &stored, &[]);
+
+    let report = verify_artifacts(tmp.path()).expect("verify");
+
+    let [IntegrityIssue::UnindexedPois(ids)] = report.issues.as_slice() else {
+        panic!("expected unindexed POIs, got {:?}", report.issues);
+    };
+    assert_eq!(ids.count, 50);
+    assert_eq!(ids.sample_ids.len(), ISSUE_SAMPLE_SIZE);
+}
+
+#[rstest]
+fn missing_index_is_an_error(tmp: TempDir) {
+    write_artefacts(&tmp, &[], &[]);
+    std::fs::remove_file(tmp.path().join(SPATIAL_INDEX_FILE_NAME)).expect("remove index");
+
+    let error = verify_artifacts(tmp.path()).expect_err("missing index should fail");
+    assert!(matches!(error, VerifyError::ReadSpatialIndex { .. }));
+}
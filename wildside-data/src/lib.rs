@@ -4,6 +4,10 @@
 //! - Define repository and source traits for ingestion and query.
 //! - Provide adapters for files, HTTP and databases.
 //! - Encapsulate serialization formats and schema evolution.
+//! - Record artefact provenance and format versions in `manifest.json`.
+//! - Pack artefact sets into single-file `.wildside` bundles.
+//! - Cross-check an artefact set for inconsistencies between its files.
+//! - Export ingested POIs to GeoParquet and FlatGeobuf for GIS tooling.
 //!
 //! Boundaries:
 //! - Do not encode domain rules (live in `wildside-core`).
@@ -12,15 +16,54 @@
 //! Invariants:
 //! - Thread-safe by default where feasible.
 //! - No global mutable state.
+//!
+//! Features:
+//! - `osm-ingest` (default): OSM PBF ingestion, `pois.db` persistence, and
+//!   extraction of the pedestrian routing graph.
+//! - `claims-store` (enabled by `wikidata-etl`): read-only typed queries over
+//!   the Wikidata claims in `pois.db` and cross-artefact integrity checks,
+//!   without the ETL dependencies.
+//! - `wikidata-etl` (default): Wikidata dump download, claim extraction, and
+//!   claim persistence.
+//! - `routing-osrm` (default): the OSRM-backed HTTP travel time provider.
+//! - `export-geoparquet` and `export-flatgeobuf`: POI export writers for
+//!   each interchange format.
+//!
+//! Request-time consumers can disable default features to avoid pulling in
+//! `osmpbf`, `bzip2`, and `reqwest`.
+
+#![cfg_attr(docsrs, feature(doc_cfg))]
 
+pub mod bundle;
+#[cfg(feature = "claims-store")]
+#[cfg_attr(docsrs, doc(cfg(feature = "claims-store")))]
+pub mod claims;
+#[cfg(any(feature = "export-geoparquet", feature = "export-flatgeobuf"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "export-geoparquet", feature = "export-flatgeobuf")))
+)]
+pub mod export;
+#[cfg(feature = "osm-ingest")]
 mod ingest;
+#[cfg(feature = "claims-store")]
+#[cfg_attr(docsrs, doc(cfg(feature = "claims-store")))]
+pub mod integrity;
+pub mod manifest;
+#[cfg(feature = "routing-osrm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "routing-osrm")))]
 pub mod routing;
+#[cfg(feature = "wikidata-etl")]
+#[cfg_attr(docsrs, doc(cfg(feature = "wikidata-etl")))]
 pub mod wikidata;
 
+#[cfg(feature = "osm-ingest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "osm-ingest")))]
 pub use crate::ingest::{
-    OsmIngestError, OsmIngestReport, OsmIngestSummary, PersistPoisError, ingest_osm_pbf,
-    ingest_osm_pbf_report, persist_pois_to_sqlite,
+    OsmIngestError, OsmIngestReport, OsmIngestSummary, PersistPoisError, build_routing_graph,
+    build_step_free_routing_graph, ingest_osm_pbf, ingest_osm_pbf_report, persist_pois_to_sqlite,
 };
 
 #[cfg(test)]
+#[cfg(feature = "osm-ingest")]
 mod tests;
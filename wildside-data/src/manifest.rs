@@ -0,0 +1,341 @@
+//! `manifest.json`: provenance and format versions of an artefact set.
+//!
+//! Ingestion writes the manifest beside `pois.db`, `pois.rstar`, and the other
+//! artefacts it produces. It records the bundle and per-artefact format
+//! versions from [`wildside_core::formats`], the SHA-256 digest of every input
+//! file, when the ingest ran, and which tool version produced it. Readers call
+//! [`load_manifest`] before opening the artefacts so an incompatible set is
+//! rejected up front rather than half-way through opening the store and
+//! scorer.
+//!
+//! Artefact sets from older ingests carry no manifest; [`load_manifest`]
+//! returns `None` for them and the per-artefact version checks still apply.
+
+use std::{
+    fmt::Write as _,
+    fs,
+    io::{self, BufReader},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use wildside_core::formats::{ArtefactFormat, ArtefactKind, BUNDLE, FormatVersionError, format_of};
+
+/// File name of the manifest written next to the artefacts it describes.
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Provenance and format versions of one ingest run's artefacts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtefactManifest {
+    /// [`BUNDLE`] format version the artefact set was written with.
+    pub bundle_version: u16,
+    /// Version of the tool that ran the ingest.
+    pub tool_version: String,
+    /// Seconds since the Unix epoch when the manifest was created.
+    pub ingested_at: u64,
+    /// Versioned artefacts in the set.
+    pub artefacts: Vec<ManifestArtefact>,
+    /// Inputs the artefacts were derived from.
+    pub inputs: Vec<ManifestInput>,
+}
+
+/// Format version of one artefact in the set.
+///
+/// A file holding several versioned formats, such as `pois.db` with its POI
+/// and claims schemas, has one entry per format.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestArtefact {
+    /// File name relative to the manifest's directory.
+    pub file: String,
+    /// Versioned format stored in the file.
+    pub kind: ArtefactKind,
+    /// Format version the file was written with.
+    pub version: u16,
+}
+
+/// One input consumed by the ingest run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestInput {
+    /// Role of the input, such as `osm-pbf` or `wikidata-dump`.
+    pub role: String,
+    /// Path or URL the input was read from.
+    pub source: String,
+    /// Lowercase hexadecimal SHA-256 digest of a file input; `None` for
+    /// remote services, which have no stable content to hash.
+    pub sha256: Option<String>,
+}
+
+/// Errors raised while writing, reading, or validating a manifest.
+#[derive(Debug, Error)]
+pub enum ManifestError {
+    /// Hashing an input file failed.
+    #[error("failed to hash ingest input {path:?}")]
+    HashInput {
+        /// Input that could not be read.
+        path: PathBuf,
+        /// Underlying I/O error.
+        #[source]
+        source: io::Error,
+    },
+    /// Writing `manifest.json` failed.
+    #[error("failed to write artefact manifest {path:?}")]
+    Write {
+        /// Destination manifest path.
+        path: PathBuf,
+        /// Underlying I/O error.
+        #[source]
+        source: io::Error,
+    },
+    /// Reading `manifest.json` failed.
+    #[error("failed to read artefact manifest {path:?}")]
+    Read {
+        /// Manifest path.
+        path: PathBuf,
+        /// Underlying I/O error.
+        #[source]
+        source: io::Error,
+    },
+    /// `manifest.json` is not a valid manifest.
+    #[error("failed to parse artefact manifest {path:?}")]
+    Parse {
+        /// Manifest path.
+        path: PathBuf,
+        /// Source error produced by `serde_json`.
+        #[source]
+        source: serde_json::Error,
+    },
+    /// The manifest records a format version this build cannot read.
+    #[error("artefacts described by {path:?} are incompatible with this build")]
+    Incompatible {
+        /// Manifest path.
+        path: PathBuf,
+        /// Version check that failed.
+        #[source]
+        source: FormatVersionError,
+    },
+}
+
+impl ArtefactManifest {
+    /// Start a manifest stamped with the current bundle version and time.
+    #[must_use]
+    pub fn new(tool_version: impl Into<String>) -> Self {
+        let ingested_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Self {
+            bundle_version: BUNDLE.current,
+            tool_version: tool_version.into(),
+            ingested_at,
+            artefacts: Vec::new(),
+            inputs: Vec::new(),
+        }
+    }
+
+    /// Record that `file` was written at the current version of `format`.
+    #[must_use]
+    pub fn with_artefact(mut self, file: impl Into<String>, format: ArtefactFormat) -> Self {
+        self.artefacts.push(ManifestArtefact {
+            file: file.into(),
+            kind: format.kind,
+            version: format.current,
+        });
+        self
+    }
+
+    /// Record the input file at `path`, hashing its contents.
+    ///
+    /// # Errors
+    /// Returns [`ManifestError::HashInput`] when the file cannot be read.
+    pub fn with_input_file(
+        mut self,
+        role: impl Into<String>,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, ManifestError> {
+        let path = path.as_ref();
+        let sha256 = sha256_file(path).map_err(|source| ManifestError::HashInput {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        self.inputs.push(ManifestInput {
+            role: role.into(),
+            source: path.display().to_string(),
+            sha256: Some(sha256),
+        });
+        Ok(self)
+    }
+
+    /// Record a remote input, such as an API endpoint, that has no digest.
+    #[must_use]
+    pub fn with_remote_input(mut self, role: impl Into<String>, url: impl Into<String>) -> Self {
+        self.inputs.push(ManifestInput {
+            role: role.into(),
+            source: url.into(),
+            sha256: None,
+        });
+        self
+    }
+
+    /// Check the bundle version and every artefact version against the
+    /// compatibility matrix.
+    ///
+    /// # Errors
+    /// Returns the first [`FormatVersionError`] encountered.
+    pub fn check_compatibility(&self) -> Result<(), FormatVersionError> {
+        BUNDLE.negotiate(i64::from(self.bundle_version))?;
+        for artefact in &self.artefacts {
+            format_of(artefact.kind).negotiate(i64::from(artefact.version))?;
+        }
+        Ok(())
+    }
+
+    /// Write the manifest to [`MANIFEST_FILE_NAME`] inside `dir`, returning
+    /// the path written.
+    ///
+    /// # Errors
+    /// Returns [`ManifestError::Write`] when the file cannot be written.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempfile::tempdir;
+    /// use wildside_core::formats::SPATIAL_INDEX;
+    /// use wildside_data::manifest::{ArtefactManifest, load_manifest};
+    ///
+    /// let dir = tempdir().expect("create temp directory");
+    /// let manifest = ArtefactManifest::new("0.1.0").with_artefact("pois.rstar", SPATIAL_INDEX);
+    /// manifest.write(dir.path()).expect("write manifest");
+    ///
+    /// let loaded = load_manifest(dir.path()).expect("load manifest");
+    /// assert_eq!(loaded, Some(manifest));
+    /// ```
+    pub fn write(&self, dir: impl AsRef<Path>) -> Result<PathBuf, ManifestError> {
+        let path = dir.as_ref().join(MANIFEST_FILE_NAME);
+        let write_error = |source| ManifestError::Write {
+            path: path.clone(),
+            source,
+        };
+        let json = serde_json::to_vec_pretty(self).map_err(|err| write_error(err.into()))?;
+        fs::write(&path, json).map_err(write_error)?;
+        Ok(path)
+    }
+}
+
+/// Load and validate the manifest in `dir`, if one exists.
+///
+/// Returns `Ok(None)` for artefact sets written before manifests existed.
+///
+/// # Errors
+/// Returns [`ManifestError::Read`] or [`ManifestError::Parse`] when the file
+/// exists but cannot be decoded, and [`ManifestError::Incompatible`] when it
+/// records a bundle or artefact version outside the compatibility matrix.
+pub fn load_manifest(dir: impl AsRef<Path>) -> Result<Option<ArtefactManifest>, ManifestError> {
+    let path = dir.as_ref().join(MANIFEST_FILE_NAME);
+    let file = match fs::File::open(&path) {
+        Ok(file) => file,
+        Err(source) if source.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(source) => return Err(ManifestError::Read { path, source }),
+    };
+    let manifest: ArtefactManifest =
+        serde_json::from_reader(BufReader::new(file)).map_err(|source| ManifestError::Parse {
+            path: path.clone(),
+            source,
+        })?;
+    manifest
+        .check_compatibility()
+        .map_err(|source| ManifestError::Incompatible { path, source })?;
+    Ok(Some(manifest))
+}
+
+/// Hash the file at `path` with SHA-256, streaming its contents.
+fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+    let mut hex = String::with_capacity(64);
+    for byte in hasher.finalize() {
+        write!(hex, "{byte:02x}").map_err(io::Error::other)?;
+    }
+    Ok(hex)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for manifest round trips and compatibility checks.
+
+    use rstest::{fixture, rstest};
+    use tempfile::TempDir;
+    use wildside_core::formats::{CLAIMS_SCHEMA, POIS_SCHEMA, SPATIAL_INDEX};
+
+    use super::*;
+
+    #[fixture]
+    fn tmp() -> TempDir {
+        TempDir::new().expect("create temp directory")
+    }
+
+    #[rstest]
+    fn round_trips_artefacts_and_hashed_inputs(tmp: TempDir) {
+        let input = tmp.path().join("region.osm.pbf");
+        fs::write(&input, b"abc").expect("write input");
+        let manifest = ArtefactManifest::new("1.2.3")
+            .with_artefact("pois.db", POIS_SCHEMA)
+            .with_artefact("pois.db", CLAIMS_SCHEMA)
+            .with_artefact("pois.rstar", SPATIAL_INDEX)
+            .with_input_file("osm-pbf", &input)
+            .expect("hash input")
+            .with_remote_input("wikidata-api", "https://www.wikidata.org/w/api.php");
+
+        manifest.write(tmp.path()).expect("write manifest");
+        let loaded = load_manifest(tmp.path())
+            .expect("load manifest")
+            .expect("manifest present");
+
+        assert_eq!(loaded, manifest);
+        assert_eq!(
+            loaded.inputs[0].sha256.as_deref(),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+        );
+        assert_eq!(loaded.inputs[1].sha256, None);
+    }
+
+    #[rstest]
+    fn missing_manifest_loads_as_none(tmp: TempDir) {
+        assert!(load_manifest(tmp.path()).expect("load manifest").is_none());
+    }
+
+    #[rstest]
+    fn rejects_newer_artefact_versions(tmp: TempDir) {
+        let mut manifest =
+            ArtefactManifest::new("9.9.9").with_artefact("pois.rstar", SPATIAL_INDEX);
+        manifest.artefacts[0].version = SPATIAL_INDEX.current + 1;
+        manifest.write(tmp.path()).expect("write manifest");
+
+        let error = load_manifest(tmp.path()).expect_err("newer version should be rejected");
+        let ManifestError::Incompatible { source, .. } = error else {
+            panic!("expected incompatibility, got {error:?}");
+        };
+        assert_eq!(source.kind, ArtefactKind::SpatialIndex);
+    }
+
+    #[rstest]
+    fn rejects_newer_bundle_versions(tmp: TempDir) {
+        let mut manifest = ArtefactManifest::new("9.9.9");
+        manifest.bundle_version = BUNDLE.current + 1;
+        manifest.write(tmp.path()).expect("write manifest");
+
+        let error = load_manifest(tmp.path()).expect_err("newer bundle should be rejected");
+        assert!(matches!(
+            error,
+            ManifestError::Incompatible { source, .. } if source.kind == ArtefactKind::Bundle
+        ));
+    }
+
+    #[rstest]
+    fn reports_corrupt_manifests(tmp: TempDir) {
+        fs::write(tmp.path().join(MANIFEST_FILE_NAME), b"{").expect("write manifest");
+
+        let error = load_manifest(tmp.path()).expect_err("corrupt manifest should fail");
+        assert!(matches!(error, ManifestError::Parse { .. }));
+    }
+}
@@ -11,6 +11,16 @@
 //! implemented by blocking on async HTTP calls internally, keeping the core
 //! library embeddable in synchronous contexts.
 //!
+//! Transient failures are retried with backoff according to a
+//! [`RetryPolicy`], and a circuit breaker configured by
+//! [`CircuitBreakerPolicy`] makes requests fail fast while the service keeps
+//! failing. A token bucket configured by [`RateLimitPolicy`] spaces requests
+//! out to respect a shared service's usage policy.
+//!
+//! [`HttpTravelTimeProvider::snap_report`] reports how far the service moved
+//! each POI to reach its network, so POIs that are effectively unreachable on
+//! foot can be flagged.
+//!
 //! # Example
 //!
 //! ```no_run
@@ -21,6 +31,7 @@
 //!
 //! // Create a provider with custom configuration
 //! let config = HttpTravelTimeProviderConfig::new("http://localhost:5000")
+//!     .with_profile("cycling")
 //!     .with_timeout(Duration::from_mins(1))
 //!     .with_user_agent("my-app/1.0");
 //! let provider = HttpTravelTimeProvider::with_config(config)?;
@@ -40,10 +51,17 @@
 
 mod osrm;
 mod provider;
+mod rate_limit;
+mod resilience;
+mod snapping;
 
 #[doc(hidden)]
 pub mod test_support;
 
 pub use provider::{
     DEFAULT_USER_AGENT, HttpTravelTimeProvider, HttpTravelTimeProviderConfig, ProviderBuildError,
+    RoutingProfile,
 };
+pub use rate_limit::RateLimitPolicy;
+pub use resilience::{CircuitBreakerPolicy, RetryPolicy};
+pub use snapping::{DEFAULT_MAX_SNAP_DISTANCE_M, SnapReport, WaypointSnap};
@@ -1,10 +1,12 @@
-//! OSRM API response types for the Table service.
+//! OSRM API response types for the Table and Route services.
 //!
 //! This module provides deserialization types for the OSRM Table API response
 //! format. The Table API computes the duration of the fastest route between all
-//! pairs of supplied coordinates.
+//! pairs of supplied coordinates. The Route API traces the fastest route
+//! through coordinates in order, and is used for route geometry.
 //!
-//! See: <http://project-osrm.org/docs/v5.24.0/api/#table-service>
+//! See: <http://project-osrm.org/docs/v5.24.0/api/#table-service> and
+//! <http://project-osrm.org/docs/v5.24.0/api/#route-service>
 
 use serde::Deserialize;
 
@@ -31,6 +33,20 @@ pub struct TableResponse {
     /// `durations[i][j]` is the travel time from the i-th to the j-th
     /// coordinate. Values are `None` when no route exists between a pair.
     pub durations: Option<Vec<Vec<Option<f64>>>>,
+
+    /// Destination coordinates as OSRM matched them to the routing network,
+    /// in column order.
+    pub destinations: Option<Vec<Waypoint>>,
+}
+
+/// A supplied coordinate snapped to the nearest point on the routing network.
+#[derive(Debug, Deserialize)]
+pub struct Waypoint {
+    /// Distance in metres from the supplied coordinate to the snapped one.
+    pub distance: f64,
+
+    /// Snapped coordinate as `[longitude, latitude]`.
+    pub location: [f64; 2],
 }
 
 impl TableResponse {
@@ -41,6 +57,43 @@ impl TableResponse {
     }
 }
 
+/// OSRM Route API response requested with `geometries=geojson`.
+#[derive(Debug, Deserialize)]
+pub struct RouteResponse {
+    /// Status code from OSRM, as for [`TableResponse::code`]; `"NoRoute"`
+    /// means the coordinates cannot be connected.
+    pub code: String,
+
+    /// Optional error message when `code` is not `"Ok"`.
+    pub message: Option<String>,
+
+    /// Candidate routes, best first; absent on failure.
+    #[serde(default)]
+    pub routes: Vec<RouteGeometry>,
+}
+
+impl RouteResponse {
+    /// Check if the response indicates success.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.code == "Ok"
+    }
+}
+
+/// One route from a [`RouteResponse`], reduced to its shape.
+#[derive(Debug, Deserialize)]
+pub struct RouteGeometry {
+    /// GeoJSON `LineString` of the whole route.
+    pub geometry: GeoJsonLineString,
+}
+
+/// A GeoJSON `LineString`, with positions as `[longitude, latitude]`.
+#[derive(Debug, Deserialize)]
+pub struct GeoJsonLineString {
+    /// Positions along the line.
+    pub coordinates: Vec<[f64; 2]>,
+}
+
 #[cfg(test)]
 mod tests {
     //! Tests for Open Source Routing Machine response decoding.
@@ -64,6 +117,28 @@ mod tests {
         assert_eq!(durations[0][1], Some(120.5));
     }
 
+    #[test]
+    fn deserialize_snapped_waypoints() {
+        let json = r#"{
+            "code": "Ok",
+            "durations": [[0.0, 120.5]],
+            "sources": [
+                {"hint": "abc", "distance": 4.2, "name": "High Street", "location": [-0.1, 51.5]}
+            ],
+            "destinations": [
+                {"hint": "abc", "distance": 4.2, "name": "High Street", "location": [-0.1, 51.5]},
+                {"hint": "def", "distance": 310.0, "name": "", "location": [-0.2, 51.6]}
+            ]
+        }"#;
+
+        let response: TableResponse = serde_json::from_str(json).expect("should deserialize");
+
+        let destinations = response.destinations.expect("should have destinations");
+        assert_eq!(destinations.len(), 2);
+        assert_eq!(destinations[1].distance, 310.0);
+        assert_eq!(destinations[1].location, [-0.2, 51.6]);
+    }
+
     #[test]
     fn deserialize_error_response() {
         let json = r#"{
@@ -79,6 +154,40 @@ mod tests {
             Some("Coordinates are invalid".to_string())
         );
         assert!(response.durations.is_none());
+        assert!(response.destinations.is_none());
+    }
+
+    #[test]
+    fn deserialize_route_geometry() {
+        let json = r#"{
+            "code": "Ok",
+            "routes": [{
+                "distance": 812.4,
+                "duration": 584.9,
+                "geometry": {
+                    "type": "LineString",
+                    "coordinates": [[-0.1, 51.5], [-0.15, 51.55], [-0.2, 51.6]]
+                }
+            }],
+            "waypoints": []
+        }"#;
+
+        let response: RouteResponse = serde_json::from_str(json).expect("should deserialize");
+
+        assert!(response.is_ok());
+        let coordinates = &response.routes[0].geometry.coordinates;
+        assert_eq!(coordinates.len(), 3);
+        assert_eq!(coordinates[1], [-0.15, 51.55]);
+    }
+
+    #[test]
+    fn deserialize_route_error_without_routes() {
+        let json = r#"{"code": "NoRoute", "message": "Impossible route between points"}"#;
+
+        let response: RouteResponse = serde_json::from_str(json).expect("should deserialize");
+
+        assert!(!response.is_ok());
+        assert!(response.routes.is_empty());
     }
 
     #[test]
@@ -0,0 +1,213 @@
+//! Configuration for the HTTP travel time provider.
+
+use std::time::Duration;
+
+#[cfg(doc)]
+use super::HttpTravelTimeProvider;
+use crate::routing::rate_limit::RateLimitPolicy;
+use crate::routing::resilience::{CircuitBreakerPolicy, RetryPolicy};
+
+/// Error type for [`HttpTravelTimeProvider`] construction failures.
+#[derive(Debug)]
+pub enum ProviderBuildError {
+    /// Failed to build the HTTP client.
+    HttpClient(reqwest::Error),
+    /// Failed to build the Tokio runtime.
+    Runtime(std::io::Error),
+    /// The routing profile cannot be used as a URL path segment.
+    InvalidProfile(String),
+}
+
+impl std::fmt::Display for ProviderBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::HttpClient(err) => write!(f, "failed to build HTTP client: {err}"),
+            Self::Runtime(err) => write!(f, "failed to build Tokio runtime: {err}"),
+            Self::InvalidProfile(profile) => write!(
+                f,
+                "invalid routing profile {profile:?}: use letters, digits, '-', '_', or '.'"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProviderBuildError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::HttpClient(err) => Some(err),
+            Self::Runtime(err) => Some(err),
+            Self::InvalidProfile(_) => None,
+        }
+    }
+}
+
+/// Default user agent for OSRM requests.
+pub const DEFAULT_USER_AGENT: &str = "wildside-routing/0.1";
+
+/// Default request timeout in seconds.
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// OSRM routing profile named in the table request URL.
+///
+/// Stock OSRM deployments serve one profile per instance and accept any of
+/// the standard names; self-hosted instances behind a gateway may route on a
+/// custom name instead, which [`Self::Custom`] carries verbatim.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum RoutingProfile {
+    /// The `walking` profile.
+    #[default]
+    Walking,
+    /// The `cycling` profile.
+    Cycling,
+    /// The `driving` profile.
+    Driving,
+    /// Any other profile name.
+    Custom(String),
+}
+
+impl RoutingProfile {
+    /// Return the profile name as it appears in the request URL.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Walking => "walking",
+            Self::Cycling => "cycling",
+            Self::Driving => "driving",
+            Self::Custom(name) => name,
+        }
+    }
+
+    /// Report whether the name is safe to splice into a URL path.
+    pub(super) fn is_valid(&self) -> bool {
+        let name = self.as_str();
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.'))
+    }
+}
+
+impl From<&str> for RoutingProfile {
+    fn from(name: &str) -> Self {
+        match name {
+            "walking" => Self::Walking,
+            "cycling" => Self::Cycling,
+            "driving" => Self::Driving,
+            other => Self::Custom(other.to_owned()),
+        }
+    }
+}
+
+impl From<String> for RoutingProfile {
+    fn from(name: String) -> Self {
+        match Self::from(name.as_str()) {
+            Self::Custom(_) => Self::Custom(name),
+            standard => standard,
+        }
+    }
+}
+
+impl std::fmt::Display for RoutingProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Configuration for [`HttpTravelTimeProvider`].
+#[derive(Debug, Clone)]
+pub struct HttpTravelTimeProviderConfig {
+    /// Base URL for the OSRM service (e.g., `"http://localhost:5000"`).
+    pub base_url: String,
+    /// Routing profile requested from the service.
+    pub profile: RoutingProfile,
+    /// Profile avoiding stairs, used for step-free solves.
+    ///
+    /// `None` leaves the provider unable to plan step-free routes.
+    pub step_free_profile: Option<RoutingProfile>,
+    /// Request timeout duration.
+    pub timeout: Duration,
+    /// User agent string for requests.
+    pub user_agent: String,
+    /// Retries for timeouts, network errors, and 429 or 5xx responses.
+    pub retry: RetryPolicy,
+    /// When to stop contacting a service that keeps failing.
+    pub circuit_breaker: CircuitBreakerPolicy,
+    /// Maximum request rate and burst sent to the service.
+    pub rate_limit: RateLimitPolicy,
+}
+
+impl Default for HttpTravelTimeProviderConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "http://localhost:5000".to_string(),
+            profile: RoutingProfile::default(),
+            step_free_profile: None,
+            timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            retry: RetryPolicy::default(),
+            circuit_breaker: CircuitBreakerPolicy::default(),
+            rate_limit: RateLimitPolicy::default(),
+        }
+    }
+}
+
+impl HttpTravelTimeProviderConfig {
+    /// Create a new configuration with the given base URL.
+    #[must_use]
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the routing profile, e.g. `"cycling"` or a custom profile name.
+    #[must_use]
+    pub fn with_profile(mut self, profile: impl Into<RoutingProfile>) -> Self {
+        self.profile = profile.into();
+        self
+    }
+
+    /// Set the profile, served by the same service, that routes around
+    /// stairs for step-free requests, e.g. a custom `"wheelchair"` profile.
+    #[must_use]
+    pub fn with_step_free_profile(mut self, profile: impl Into<RoutingProfile>) -> Self {
+        self.step_free_profile = Some(profile.into());
+        self
+    }
+
+    /// Set the request timeout.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set the user agent string.
+    #[must_use]
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Set the retry policy for transient failures.
+    #[must_use]
+    pub const fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Set the circuit breaker policy.
+    #[must_use]
+    pub const fn with_circuit_breaker(mut self, circuit_breaker: CircuitBreakerPolicy) -> Self {
+        self.circuit_breaker = circuit_breaker;
+        self
+    }
+
+    /// Set the rate limit, e.g. to respect a public instance's usage policy.
+    #[must_use]
+    pub const fn with_rate_limit(mut self, rate_limit: RateLimitPolicy) -> Self {
+        self.rate_limit = rate_limit;
+        self
+    }
+}
@@ -0,0 +1,98 @@
+//! Converting OSRM responses into matrices, snap reports, and route lines.
+
+use std::time::Duration;
+
+use geo::{Coord, LineString};
+use wildside_core::{PointOfInterest, TravelTimeError, TravelTimeMatrix};
+
+use super::HttpTravelTimeProvider;
+use crate::routing::osrm::{RouteResponse, TableResponse};
+use crate::routing::snapping::SnapReport;
+
+impl HttpTravelTimeProvider {
+    /// Convert an OSRM response to a `TravelTimeMatrix`.
+    pub(super) fn convert_response(
+        &self,
+        response: TableResponse,
+    ) -> Result<TravelTimeMatrix, TravelTimeError> {
+        if !response.is_ok() {
+            return Err(TravelTimeError::ServiceError {
+                code: response.code,
+                message: response.message.unwrap_or_default(),
+            });
+        }
+
+        let durations = response
+            .durations
+            .ok_or_else(|| TravelTimeError::ParseError {
+                message: "OSRM response missing durations array".to_string(),
+            })?;
+
+        // Convert f64 seconds to Duration, treating null as Duration::MAX
+        // to indicate unreachable pairs. Invalid values (negative, NaN, infinite)
+        // are also treated as unreachable to avoid panics from Duration::from_secs_f64.
+        let rows: Vec<Vec<Duration>> = durations
+            .into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|cell| {
+                        cell.filter(|&v| v >= 0.0 && v.is_finite())
+                            .map_or(Duration::MAX, Duration::from_secs_f64)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        TravelTimeMatrix::try_from(rows).map_err(|error| TravelTimeError::ParseError {
+            message: error.to_string(),
+        })
+    }
+
+    /// Convert the destination waypoints of an OSRM response to a
+    /// [`SnapReport`] for `pois`.
+    pub(super) fn convert_snapping(
+        pois: &[PointOfInterest],
+        response: TableResponse,
+    ) -> Result<SnapReport, TravelTimeError> {
+        if !response.is_ok() {
+            return Err(TravelTimeError::ServiceError {
+                code: response.code,
+                message: response.message.unwrap_or_default(),
+            });
+        }
+
+        let waypoints = response
+            .destinations
+            .ok_or_else(|| TravelTimeError::ParseError {
+                message: "OSRM response missing destinations array".to_string(),
+            })?;
+        SnapReport::from_waypoints(pois, waypoints)
+    }
+
+    /// Convert the best route of an OSRM Route response to a line.
+    ///
+    /// `NoRoute` means OSRM cannot connect the stops, so there is no shape to
+    /// report rather than a failure.
+    pub(super) fn convert_geometry(
+        response: RouteResponse,
+    ) -> Result<Option<LineString<f64>>, TravelTimeError> {
+        if response.code == "NoRoute" {
+            return Ok(None);
+        }
+        if !response.is_ok() {
+            return Err(TravelTimeError::ServiceError {
+                code: response.code,
+                message: response.message.unwrap_or_default(),
+            });
+        }
+
+        Ok(response.routes.into_iter().next().map(|route| {
+            route
+                .geometry
+                .coordinates
+                .into_iter()
+                .map(|[x, y]| Coord { x, y })
+                .collect()
+        }))
+    }
+}
@@ -0,0 +1,291 @@
+//! HTTP-based `TravelTimeProvider` using OSRM's Table API.
+//!
+//! This module provides [`HttpTravelTimeProvider`], an implementation of the
+//! [`TravelTimeProvider`] trait that fetches travel time matrices from an OSRM
+//! routing service via HTTP, and route geometry from its Route API.
+//!
+//! # Architecture
+//!
+//! The [`TravelTimeProvider`] trait is synchronous to keep the core library
+//! embeddable in synchronous contexts. This provider bridges the async HTTP
+//! calls to the sync interface by blocking on a Tokio runtime internally.
+//! Async callers should use its [`AsyncTravelTimeProvider`] implementation
+//! instead, which awaits the requests on the caller's runtime. Solving from
+//! async code goes through [`wildside_core::AsyncEngine`], which awaits the
+//! candidates' matrix through that implementation and runs the search on a
+//! blocking executor, so no solve blocks the runtime.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use wildside_data::routing::HttpTravelTimeProvider;
+//! use wildside_core::{PointOfInterest, TravelTimeProvider};
+//! use geo::Coord;
+//!
+//! let provider = HttpTravelTimeProvider::new("http://localhost:5000")?;
+//! let pois = vec![
+//!     PointOfInterest::with_empty_tags(1, Coord { x: -0.1, y: 51.5 }),
+//!     PointOfInterest::with_empty_tags(2, Coord { x: -0.2, y: 51.6 }),
+//! ];
+//!
+//! let matrix = provider.get_travel_time_matrix(&pois)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use std::sync::Arc;
+
+use geo::{Coord, LineString};
+use reqwest::Client;
+#[cfg(doc)]
+use tokio::runtime::{Handle, RuntimeFlavor};
+#[cfg(doc)]
+use wildside_core::AsyncTravelTimeProvider;
+use wildside_core::{PointOfInterest, TravelTimeError, TravelTimeMatrix, TravelTimeProvider};
+
+use super::rate_limit::RateLimiter;
+use super::resilience::CircuitBreaker;
+use super::snapping::SnapReport;
+
+mod config;
+mod convert;
+mod requests;
+mod runtime;
+
+pub use config::{
+    DEFAULT_USER_AGENT, HttpTravelTimeProviderConfig, ProviderBuildError, RoutingProfile,
+};
+use runtime::OwnedRuntime;
+
+/// HTTP-based travel time provider using OSRM Table API.
+///
+/// This provider implements the synchronous [`TravelTimeProvider`] trait
+/// by internally blocking on asynchronous HTTP requests. It owns a Tokio
+/// runtime that is reused across calls, avoiding the overhead of creating
+/// a new runtime per request.
+///
+/// # Runtime behaviour
+///
+/// When called from outside any Tokio runtime, the provider uses its own
+/// stored runtime. When called from within an existing multi-threaded Tokio
+/// runtime (detected via [`Handle::try_current()`] and
+/// [`RuntimeFlavor::MultiThread`]), it uses that runtime's handle with
+/// [`tokio::task::block_in_place`] to avoid nested runtime panics.
+///
+/// When called from within a `current_thread` Tokio runtime, the provider
+/// falls back to using its own internal runtime. This avoids the panic that
+/// `block_in_place` would cause, but may lead to deadlocks if the caller's
+/// runtime is driving IO or timers that this request depends on.
+///
+/// # Failure handling
+///
+/// Transient failures are retried according to
+/// [`HttpTravelTimeProviderConfig::retry`]; each attempt has its own
+/// timeout, so a request can take up to `max_attempts` timeouts plus the
+/// backoff delays. Requests that still fail count towards the circuit
+/// breaker, and while it is open the provider returns
+/// [`TravelTimeError::CircuitOpen`] without contacting the service. The
+/// step-free profile shares the breaker, since both profiles are served by
+/// the same service.
+///
+/// # Rate limiting
+///
+/// Every HTTP request, retries included, waits for a token from the bucket
+/// configured by [`HttpTravelTimeProviderConfig::rate_limit`]. The default
+/// policy is unlimited; set one with
+/// [`HttpTravelTimeProviderConfig::with_rate_limit`] when a shared service
+/// publishes a usage policy. The bucket belongs to the provider, so the limit
+/// holds across every thread and task that shares it, and across requests
+/// with its step-free profile.
+///
+/// # Supported routing modes
+///
+/// The provider computes an n×n travel time matrix for all provided POIs.
+/// Both round-trip and point-to-point routing are supported; the routing
+/// mode is determined by the caller (solver) which includes synthetic
+/// start/end POIs in the request as needed.
+///
+/// # Snapping diagnostics
+///
+/// OSRM snaps every coordinate to the nearest routable way before routing.
+/// [`Self::snap_report`] reports how far each POI moved, so POIs that sit well
+/// away from the network can be flagged with [`SnapReport::exceeding`].
+pub struct HttpTravelTimeProvider {
+    client: Client,
+    config: HttpTravelTimeProviderConfig,
+    breaker: Arc<CircuitBreaker>,
+    limiter: Arc<RateLimiter>,
+    runtime: OwnedRuntime,
+    step_free: Option<Box<Self>>,
+}
+
+impl std::fmt::Debug for HttpTravelTimeProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpTravelTimeProvider")
+            .field("client", &self.client)
+            .field("config", &self.config)
+            .field("breaker", &self.breaker)
+            .field("limiter", &self.limiter)
+            .field("runtime", &"<tokio::runtime::Runtime>")
+            .field("step_free", &self.step_free)
+            .finish()
+    }
+}
+
+impl HttpTravelTimeProvider {
+    /// Create a new provider with default configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - Base URL for the OSRM service (e.g., `"http://localhost:5000"`)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client or Tokio runtime fails to build.
+    pub fn new(base_url: impl Into<String>) -> Result<Self, ProviderBuildError> {
+        Self::with_config(HttpTravelTimeProviderConfig::new(base_url))
+    }
+
+    /// Create a new provider with explicit configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the routing profile or step-free profile is not
+    /// a plain name of letters, digits, `-`, `_`, or `.`, or if the HTTP
+    /// client or Tokio runtime fails to build.
+    pub fn with_config(config: HttpTravelTimeProviderConfig) -> Result<Self, ProviderBuildError> {
+        let breaker = Arc::new(CircuitBreaker::new(config.circuit_breaker));
+        let limiter = Arc::new(RateLimiter::new(config.rate_limit));
+        Self::with_shared(config, breaker, limiter)
+    }
+
+    /// Build a provider whose requests go through `breaker` and `limiter`.
+    fn with_shared(
+        config: HttpTravelTimeProviderConfig,
+        breaker: Arc<CircuitBreaker>,
+        limiter: Arc<RateLimiter>,
+    ) -> Result<Self, ProviderBuildError> {
+        if !config.profile.is_valid() {
+            return Err(ProviderBuildError::InvalidProfile(
+                config.profile.as_str().to_owned(),
+            ));
+        }
+        // The step-free profile is routed by the same service, so its
+        // requests count towards the same rate limit and circuit breaker.
+        let step_free = config
+            .step_free_profile
+            .clone()
+            .map(|profile| {
+                let step_free_config = HttpTravelTimeProviderConfig {
+                    profile,
+                    step_free_profile: None,
+                    ..config.clone()
+                };
+                Self::with_shared(step_free_config, Arc::clone(&breaker), Arc::clone(&limiter))
+            })
+            .transpose()?
+            .map(Box::new);
+        let client = Client::builder()
+            .user_agent(&config.user_agent)
+            .connect_timeout(config.timeout)
+            .timeout(config.timeout)
+            .build()
+            .map_err(ProviderBuildError::HttpClient)?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(ProviderBuildError::Runtime)?;
+        Ok(Self {
+            client,
+            breaker,
+            limiter,
+            config,
+            runtime: OwnedRuntime::new(runtime),
+            step_free,
+        })
+    }
+
+    /// Report how far the routing service snapped each POI to its network.
+    ///
+    /// Sends one Table API request that routes from the first POI only, so
+    /// the cost grows linearly with `pois.len()`. Retries and the circuit
+    /// breaker apply as for matrix requests, and the same runtime rules as
+    /// [`TravelTimeProvider::get_travel_time_matrix`] hold.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TravelTimeError::EmptyInput`] for an empty slice, and the
+    /// same request and service errors as matrix requests otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use geo::Coord;
+    /// use wildside_core::PointOfInterest;
+    /// use wildside_data::routing::{DEFAULT_MAX_SNAP_DISTANCE_M, HttpTravelTimeProvider};
+    ///
+    /// let provider = HttpTravelTimeProvider::new("http://localhost:5000")?;
+    /// let pois = vec![
+    ///     PointOfInterest::with_empty_tags(1, Coord { x: -0.1, y: 51.5 }),
+    ///     PointOfInterest::with_empty_tags(2, Coord { x: -0.2, y: 51.6 }),
+    /// ];
+    /// let report = provider.snap_report(&pois)?;
+    /// for snap in report.exceeding(DEFAULT_MAX_SNAP_DISTANCE_M) {
+    ///     println!("POI {} is {:.0} m from the network", snap.poi_id, snap.distance_m);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn snap_report(&self, pois: &[PointOfInterest]) -> Result<SnapReport, TravelTimeError> {
+        if pois.is_empty() {
+            return Err(TravelTimeError::EmptyInput);
+        }
+        self.block_on(self.resilient(|| self.fetch_snapping_async(pois)))
+    }
+}
+
+impl TravelTimeProvider for HttpTravelTimeProvider {
+    /// Fetch the travel time matrix for the given POIs.
+    ///
+    /// # Runtime requirements
+    ///
+    /// When called from within an existing Tokio runtime, the runtime must be
+    /// multi-threaded (`flavor = "multi_thread"`). If called from within a
+    /// `current_thread` runtime, the method falls back to using its own
+    /// internal runtime, which may block the caller's runtime and cause
+    /// deadlocks if the caller's runtime is driving IO or timers needed by
+    /// this request.
+    fn get_travel_time_matrix(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> Result<TravelTimeMatrix, TravelTimeError> {
+        if pois.is_empty() {
+            return Err(TravelTimeError::EmptyInput);
+        }
+
+        self.block_on(self.resilient(|| self.fetch_matrix_async(pois)))
+    }
+
+    /// Trace the walking path through `stops` with OSRM's Route API.
+    ///
+    /// Fewer than two stops have no path and return `Ok(None)` without a
+    /// request. Retries, the circuit breaker, and the runtime requirements of
+    /// matrix requests apply.
+    fn route_geometry(
+        &self,
+        stops: &[Coord<f64>],
+    ) -> Result<Option<LineString<f64>>, TravelTimeError> {
+        if stops.len() < 2 {
+            return Ok(None);
+        }
+        self.block_on(self.resilient(|| self.fetch_geometry_async(stops)))
+    }
+
+    /// Route with the configured step-free profile, if any.
+    fn step_free(&self) -> Option<&dyn TravelTimeProvider> {
+        self.step_free
+            .as_deref()
+            .map(|provider| provider as &dyn TravelTimeProvider)
+    }
+}
+
+#[cfg(test)]
+mod tests;
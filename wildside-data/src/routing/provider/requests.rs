@@ -0,0 +1,147 @@
+//! Building OSRM request URLs and sending them through the retry policy,
+//! circuit breaker, and rate limiter.
+
+use std::time::Instant;
+
+use geo::{Coord, LineString};
+use serde::de::DeserializeOwned;
+use wildside_core::{PointOfInterest, TravelTimeError, TravelTimeMatrix};
+
+use super::HttpTravelTimeProvider;
+use crate::routing::resilience::with_retry;
+use crate::routing::snapping::SnapReport;
+
+impl HttpTravelTimeProvider {
+    /// Build the OSRM Table API URL for the given POIs.
+    ///
+    /// The URL format is: `{base_url}/table/v1/{profile}/{coordinates}`
+    /// where coordinates are semicolon-separated `lon,lat` pairs.
+    pub(super) fn build_table_url(&self, pois: &[PointOfInterest]) -> String {
+        self.build_service_url("table", pois.iter().map(|poi| poi.location))
+    }
+
+    /// Build the OSRM Route API URL tracing `stops` in order, asking for the
+    /// full geometry as GeoJSON.
+    pub(super) fn build_route_url(&self, stops: &[Coord<f64>]) -> String {
+        format!(
+            "{}?overview=full&geometries=geojson",
+            self.build_service_url("route", stops.iter().copied())
+        )
+    }
+
+    /// Build `{base_url}/{service}/v1/{profile}/{coordinates}`.
+    fn build_service_url(&self, service: &str, coords: impl Iterator<Item = Coord<f64>>) -> String {
+        let path: String = coords
+            .map(|coord| format!("{},{}", coord.x, coord.y))
+            .collect::<Vec<_>>()
+            .join(";");
+
+        format!(
+            "{}/{}/v1/{}/{}",
+            self.config.base_url.trim_end_matches('/'),
+            service,
+            self.config.profile,
+            path
+        )
+    }
+
+    /// Build a Table API URL that routes from the first POI only.
+    ///
+    /// The response still snaps every coordinate, but its duration matrix has
+    /// a single row, so snapping many POIs stays cheap.
+    pub(super) fn build_snap_url(&self, pois: &[PointOfInterest]) -> String {
+        format!("{}?sources=0", self.build_table_url(pois))
+    }
+
+    /// Run `request`, retrying transient failures and honouring the circuit
+    /// breaker.
+    pub(super) async fn resilient<T, F, Fut>(&self, request: F) -> Result<T, TravelTimeError>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = Result<T, TravelTimeError>>,
+    {
+        let admission = self.breaker.admit(Instant::now()).map_err(|retry_after| {
+            TravelTimeError::CircuitOpen {
+                base_url: self.config.base_url.clone(),
+                retry_after_secs: retry_after.as_secs(),
+            }
+        })?;
+        let result = with_retry(self.config.retry, request).await;
+        admission.record(&result, Instant::now());
+        result
+    }
+
+    /// Fetch the travel time matrix asynchronously.
+    pub(super) async fn fetch_matrix_async(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> Result<TravelTimeMatrix, TravelTimeError> {
+        let response = self.fetch_json(&self.build_table_url(pois)).await?;
+        self.convert_response(response)
+    }
+
+    /// Fetch the snapping distances of `pois` asynchronously.
+    pub(super) async fn fetch_snapping_async(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> Result<SnapReport, TravelTimeError> {
+        let response = self.fetch_json(&self.build_snap_url(pois)).await?;
+        Self::convert_snapping(pois, response)
+    }
+
+    /// Fetch the path through `stops` asynchronously.
+    pub(super) async fn fetch_geometry_async(
+        &self,
+        stops: &[Coord<f64>],
+    ) -> Result<Option<LineString<f64>>, TravelTimeError> {
+        let response = self.fetch_json(&self.build_route_url(stops)).await?;
+        Self::convert_geometry(response)
+    }
+
+    /// Send one API request and decode the response body.
+    async fn fetch_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, TravelTimeError> {
+        let delay = self.limiter.reserve(Instant::now());
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|err| self.convert_reqwest_error(&err, url))?
+            .error_for_status()
+            .map_err(|err| self.convert_reqwest_error(&err, url))?;
+
+        response
+            .json()
+            .await
+            .map_err(|err| TravelTimeError::ParseError {
+                message: err.to_string(),
+            })
+    }
+
+    /// Convert a reqwest error to a `TravelTimeError`.
+    fn convert_reqwest_error(&self, error: &reqwest::Error, url: &str) -> TravelTimeError {
+        if error.is_timeout() {
+            return TravelTimeError::Timeout {
+                url: url.to_owned(),
+                timeout_secs: self.config.timeout.as_secs(),
+            };
+        }
+
+        if let Some(status) = error.status() {
+            return TravelTimeError::HttpError {
+                url: url.to_owned(),
+                status: status.as_u16(),
+                message: error.to_string(),
+            };
+        }
+
+        TravelTimeError::NetworkError {
+            url: url.to_owned(),
+            message: error.to_string(),
+        }
+    }
+}
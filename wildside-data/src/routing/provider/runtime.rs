@@ -0,0 +1,86 @@
+//! Running the provider's requests for synchronous and asynchronous callers.
+
+use tokio::runtime::{Handle, Runtime, RuntimeFlavor};
+use wildside_core::{AsyncTravelTimeProvider, PointOfInterest, TravelTimeError, TravelTimeMatrix};
+
+use super::HttpTravelTimeProvider;
+use crate::routing::snapping::SnapReport;
+
+/// Tokio runtime owned by a provider for its blocking calls.
+///
+/// Dropping a [`Runtime`] from async code panics, which would make the
+/// provider unusable through [`AsyncTravelTimeProvider`]. Shutting it down in
+/// the background instead is safe anywhere; it only ever runs `block_on`, so
+/// no spawned work is cut short.
+pub(super) struct OwnedRuntime(Option<Runtime>);
+
+impl OwnedRuntime {
+    pub(super) const fn new(runtime: Runtime) -> Self {
+        Self(Some(runtime))
+    }
+
+    #[expect(
+        clippy::expect_used,
+        reason = "the runtime is only taken when the provider is dropped"
+    )]
+    fn block_on<F: std::future::Future>(&self, future: F) -> F::Output {
+        self.0
+            .as_ref()
+            .expect("runtime is only taken on drop")
+            .block_on(future)
+    }
+}
+
+impl Drop for OwnedRuntime {
+    fn drop(&mut self) {
+        if let Some(runtime) = self.0.take() {
+            runtime.shutdown_background();
+        }
+    }
+}
+
+impl HttpTravelTimeProvider {
+    /// Drive `future` to completion from synchronous code.
+    ///
+    /// Inside a multi-threaded Tokio runtime this blocks in place on the
+    /// caller's runtime; otherwise, including inside a `current_thread`
+    /// runtime, it uses the provider's own runtime.
+    pub(super) fn block_on<F: std::future::Future>(&self, future: F) -> F::Output {
+        match Handle::try_current() {
+            Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                tokio::task::block_in_place(|| handle.block_on(future))
+            }
+            _ => self.runtime.block_on(future),
+        }
+    }
+
+    /// Report snapping distances on the caller's runtime, which must be
+    /// Tokio with IO and timers enabled.
+    ///
+    /// # Errors
+    ///
+    /// As for [`Self::snap_report`].
+    pub async fn snap_report_async(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> Result<SnapReport, TravelTimeError> {
+        if pois.is_empty() {
+            return Err(TravelTimeError::EmptyInput);
+        }
+        self.resilient(|| self.fetch_snapping_async(pois)).await
+    }
+}
+
+impl AsyncTravelTimeProvider for HttpTravelTimeProvider {
+    /// Fetch the travel time matrix for the given POIs on the caller's
+    /// runtime, which must be Tokio with IO and timers enabled.
+    async fn get_travel_time_matrix_async(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> Result<TravelTimeMatrix, TravelTimeError> {
+        if pois.is_empty() {
+            return Err(TravelTimeError::EmptyInput);
+        }
+        self.resilient(|| self.fetch_matrix_async(pois)).await
+    }
+}
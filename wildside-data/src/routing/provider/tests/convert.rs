@@ -0,0 +1,214 @@
+//! Tests for converting OSRM responses.
+
+use super::*;
+
+#[rstest]
+fn convert_response_handles_success() {
+    let provider =
+        HttpTravelTimeProvider::new("http://localhost:5000").expect("provider should build");
+    let response = TableResponse {
+        code: "Ok".to_string(),
+        message: None,
+        destinations: None,
+        durations: Some(vec![
+            vec![Some(0.0), Some(120.5)],
+            vec![Some(120.5), Some(0.0)],
+        ]),
+    };
+
+    let matrix = provider.convert_response(response).expect("should parse");
+
+    assert_eq!(matrix.len(), 2);
+    assert_eq!(matrix[0][0], Duration::ZERO);
+    assert_eq!(matrix[0][1], Duration::from_secs_f64(120.5));
+    assert_eq!(matrix[1][0], Duration::from_secs_f64(120.5));
+    assert_eq!(matrix[1][1], Duration::ZERO);
+}
+
+#[rstest]
+fn convert_response_handles_null_durations() {
+    let provider =
+        HttpTravelTimeProvider::new("http://localhost:5000").expect("provider should build");
+    let response = TableResponse {
+        code: "Ok".to_string(),
+        message: None,
+        destinations: None,
+        durations: Some(vec![vec![Some(0.0), None], vec![None, Some(0.0)]]),
+    };
+
+    let matrix = provider.convert_response(response).expect("should parse");
+
+    assert_eq!(matrix[0][1], Duration::MAX);
+    assert_eq!(matrix[1][0], Duration::MAX);
+}
+
+#[rstest]
+fn convert_response_handles_invalid_durations() {
+    let provider =
+        HttpTravelTimeProvider::new("http://localhost:5000").expect("provider should build");
+    let response = TableResponse {
+        code: "Ok".to_string(),
+        message: None,
+        destinations: None,
+        durations: Some(vec![
+            vec![Some(0.0), Some(-1.0), Some(f64::NAN)],
+            vec![Some(f64::INFINITY), Some(0.0), Some(f64::NEG_INFINITY)],
+            vec![Some(100.0), Some(200.0), Some(0.0)],
+        ]),
+    };
+
+    let matrix = provider.convert_response(response).expect("should parse");
+
+    // Negative values become Duration::MAX
+    assert_eq!(matrix[0][1], Duration::MAX);
+    // NaN becomes Duration::MAX
+    assert_eq!(matrix[0][2], Duration::MAX);
+    // Positive infinity becomes Duration::MAX
+    assert_eq!(matrix[1][0], Duration::MAX);
+    // Negative infinity becomes Duration::MAX
+    assert_eq!(matrix[1][2], Duration::MAX);
+    // Valid values are converted correctly
+    assert_eq!(matrix[2][0], Duration::from_secs(100));
+    assert_eq!(matrix[2][1], Duration::from_secs(200));
+}
+
+#[rstest]
+fn convert_response_rejects_ragged_durations() {
+    let provider =
+        HttpTravelTimeProvider::new("http://localhost:5000").expect("provider should build");
+    let response = TableResponse {
+        code: "Ok".to_string(),
+        message: None,
+        destinations: None,
+        durations: Some(vec![vec![Some(0.0), Some(60.0)], vec![Some(60.0)]]),
+    };
+
+    let err = provider
+        .convert_response(response)
+        .expect_err("should fail");
+
+    match err {
+        TravelTimeError::ParseError { message } => assert!(message.contains("row 1")),
+        _ => panic!("expected ParseError, got {err:?}"),
+    }
+}
+
+#[rstest]
+fn convert_response_handles_service_error() {
+    let provider =
+        HttpTravelTimeProvider::new("http://localhost:5000").expect("provider should build");
+    let response = TableResponse {
+        code: "InvalidQuery".to_string(),
+        message: Some("Too many coordinates".to_string()),
+        durations: None,
+        destinations: None,
+    };
+
+    let err = provider
+        .convert_response(response)
+        .expect_err("should fail");
+
+    match err {
+        TravelTimeError::ServiceError { code, message } => {
+            assert_eq!(code, "InvalidQuery");
+            assert_eq!(message, "Too many coordinates");
+        }
+        _ => panic!("expected ServiceError, got {err:?}"),
+    }
+}
+
+#[rstest]
+fn convert_response_handles_missing_durations() {
+    let provider =
+        HttpTravelTimeProvider::new("http://localhost:5000").expect("provider should build");
+    let response = TableResponse {
+        code: "Ok".to_string(),
+        message: None,
+        durations: None,
+        destinations: None,
+    };
+
+    let err = provider
+        .convert_response(response)
+        .expect_err("should fail");
+
+    assert!(matches!(err, TravelTimeError::ParseError { .. }));
+}
+
+#[rstest]
+fn convert_snapping_reports_destination_distances(sample_pois: Vec<PointOfInterest>) {
+    let response = TableResponse {
+        code: "Ok".to_string(),
+        message: None,
+        durations: Some(vec![vec![Some(0.0), Some(60.0)]]),
+        destinations: Some(vec![
+            Waypoint {
+                distance: 2.5,
+                location: [-0.1, 51.5],
+            },
+            Waypoint {
+                distance: 480.0,
+                location: [-0.195, 51.6],
+            },
+        ]),
+    };
+
+    let report =
+        HttpTravelTimeProvider::convert_snapping(&sample_pois, response).expect("should parse");
+
+    let flagged: Vec<_> = report.exceeding(250.0).map(|snap| snap.poi_id).collect();
+    assert_eq!(flagged, [2]);
+    assert_eq!(report.waypoints[0].distance_m, 2.5);
+}
+
+#[rstest]
+fn convert_snapping_handles_missing_destinations(sample_pois: Vec<PointOfInterest>) {
+    let response = TableResponse {
+        code: "Ok".to_string(),
+        message: None,
+        durations: Some(vec![vec![Some(0.0), Some(60.0)]]),
+        destinations: None,
+    };
+
+    let err =
+        HttpTravelTimeProvider::convert_snapping(&sample_pois, response).expect_err("should fail");
+
+    assert!(matches!(err, TravelTimeError::ParseError { .. }));
+}
+
+#[rstest]
+fn convert_geometry_takes_the_best_route() {
+    let response: RouteResponse = serde_json::from_str(
+        r#"{"code": "Ok", "routes": [
+            {"geometry": {"type": "LineString", "coordinates": [[-0.1, 51.5], [-0.2, 51.6]]}},
+            {"geometry": {"type": "LineString", "coordinates": [[9.0, 9.0]]}}
+        ]}"#,
+    )
+    .expect("should deserialize");
+
+    let line = HttpTravelTimeProvider::convert_geometry(response).expect("should convert");
+
+    assert_eq!(
+        line,
+        Some(LineString::from(vec![(-0.1, 51.5), (-0.2, 51.6)]))
+    );
+}
+
+#[rstest]
+#[case("NoRoute", Ok(()))]
+#[case("InvalidQuery", Err("InvalidQuery"))]
+fn convert_geometry_handles_failures(#[case] code: &str, #[case] expected: Result<(), &str>) {
+    let response = RouteResponse {
+        code: code.to_owned(),
+        message: None,
+        routes: Vec::new(),
+    };
+
+    match (HttpTravelTimeProvider::convert_geometry(response), expected) {
+        (Ok(line), Ok(())) => assert_eq!(line, None),
+        (Err(TravelTimeError::ServiceError { code: found, .. }), Err(code)) => {
+            assert_eq!(found, code);
+        }
+        (result, _) => panic!("unexpected result: {result:?}"),
+    }
+}
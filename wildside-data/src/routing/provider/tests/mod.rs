@@ -0,0 +1,22 @@
+//! Tests for HTTP routing provider requests and responses.
+
+use super::*;
+use crate::routing::osrm::{RouteResponse, TableResponse, Waypoint};
+use crate::routing::rate_limit::RateLimitPolicy;
+use crate::routing::resilience::{CircuitBreakerPolicy, RetryPolicy};
+use geo::Coord;
+use rstest::{fixture, rstest};
+use std::time::Duration;
+use wildside_core::AsyncTravelTimeProvider;
+
+mod convert;
+mod requests;
+mod urls;
+
+#[fixture]
+fn sample_pois() -> Vec<PointOfInterest> {
+    vec![
+        PointOfInterest::with_empty_tags(1, Coord { x: -0.1, y: 51.5 }),
+        PointOfInterest::with_empty_tags(2, Coord { x: -0.2, y: 51.6 }),
+    ]
+}
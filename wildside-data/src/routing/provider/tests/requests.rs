@@ -0,0 +1,195 @@
+//! Tests for request validation and failure handling.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+use super::*;
+
+/// Answer `requests` OSRM table requests on a local port, returning the base
+/// URL and a handle yielding the profile each request asked for.
+fn stub_osrm(requests: usize) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind stub OSRM");
+    let address = listener.local_addr().expect("stub OSRM address");
+    let server = thread::spawn(move || {
+        listener
+            .incoming()
+            .take(requests)
+            .map(|stream| answer_table(stream.expect("accept request")))
+            .collect()
+    });
+    (format!("http://{address}"), server)
+}
+
+fn answer_table(mut stream: TcpStream) -> String {
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader
+        .read_line(&mut request_line)
+        .expect("read request line");
+    // Skip the headers, which end with an empty line.
+    let mut header = String::new();
+    while reader.read_line(&mut header).expect("read header") > "\r\n".len() {
+        header.clear();
+    }
+    let body = r#"{"code":"Ok","durations":[[0,60],[60,0]]}"#;
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{body}",
+        body.len()
+    )
+    .expect("write response");
+    // "GET /table/v1/<profile>/..." names the profile in its third segment.
+    request_line
+        .split('/')
+        .nth(3)
+        .unwrap_or_default()
+        .to_owned()
+}
+
+#[rstest]
+fn snap_report_rejects_empty_input() {
+    let provider =
+        HttpTravelTimeProvider::new("http://localhost:5000").expect("provider should build");
+
+    let err = provider.snap_report(&[]).expect_err("should fail");
+
+    assert_eq!(err, TravelTimeError::EmptyInput);
+}
+
+#[rstest]
+fn empty_input_returns_error() {
+    let provider =
+        HttpTravelTimeProvider::new("http://localhost:5000").expect("provider should build");
+
+    let err = provider
+        .get_travel_time_matrix(&[])
+        .expect_err("should fail");
+
+    assert_eq!(err, TravelTimeError::EmptyInput);
+}
+
+#[rstest]
+#[tokio::test]
+async fn async_empty_input_returns_error() {
+    let provider =
+        HttpTravelTimeProvider::new("http://localhost:5000").expect("provider should build");
+
+    let err = provider
+        .get_travel_time_matrix_async(&[])
+        .await
+        .expect_err("should fail");
+
+    assert_eq!(err, TravelTimeError::EmptyInput);
+}
+
+#[rstest]
+fn config_builder_pattern() {
+    let config = HttpTravelTimeProviderConfig::new("http://example.com")
+        .with_timeout(Duration::from_mins(1))
+        .with_user_agent("test-agent/1.0")
+        .with_rate_limit(RateLimitPolicy::new(1.0, 5));
+
+    assert_eq!(config.base_url, "http://example.com");
+    assert_eq!(config.rate_limit, RateLimitPolicy::new(1.0, 5));
+    assert_eq!(config.timeout, Duration::from_mins(1));
+    assert_eq!(config.user_agent, "test-agent/1.0");
+}
+
+#[rstest]
+fn open_circuit_fails_fast(sample_pois: Vec<PointOfInterest>) {
+    // Nothing listens on the discard port, so the first request fails
+    // with a network error and opens the single-failure circuit.
+    let config = HttpTravelTimeProviderConfig::new("http://127.0.0.1:9")
+        .with_retry(RetryPolicy::none())
+        .with_circuit_breaker(CircuitBreakerPolicy {
+            failure_threshold: 1,
+            cooldown: Duration::from_mins(1),
+        });
+    let provider = HttpTravelTimeProvider::with_config(config).expect("provider should build");
+
+    let first = provider
+        .get_travel_time_matrix(&sample_pois)
+        .expect_err("nothing is listening");
+    assert!(matches!(first, TravelTimeError::NetworkError { .. }));
+
+    let second = provider
+        .get_travel_time_matrix(&sample_pois)
+        .expect_err("circuit should be open");
+    assert!(matches!(
+        second,
+        TravelTimeError::CircuitOpen { ref base_url, .. } if base_url == "http://127.0.0.1:9"
+    ));
+}
+
+#[rstest]
+fn route_geometry_needs_two_stops() {
+    let provider =
+        HttpTravelTimeProvider::new("http://127.0.0.1:9").expect("provider should build");
+
+    let line = provider
+        .route_geometry(&[Coord { x: -0.1, y: 51.5 }])
+        .expect("no request is sent");
+
+    assert_eq!(line, None);
+}
+
+#[rstest]
+fn profiles_share_one_rate_limit(sample_pois: Vec<PointOfInterest>) {
+    let (base_url, server) = stub_osrm(6);
+    let config = HttpTravelTimeProviderConfig::new(base_url)
+        .with_step_free_profile("wheelchair")
+        .with_rate_limit(RateLimitPolicy::new(10.0, 1));
+    let provider = HttpTravelTimeProvider::with_config(config).expect("provider should build");
+    let step_free = provider.step_free().expect("step-free profile");
+    let started = Instant::now();
+
+    for turn in 0..6 {
+        let profile: &dyn TravelTimeProvider = if turn % 2 == 0 { &provider } else { step_free };
+        profile
+            .get_travel_time_matrix(&sample_pois)
+            .expect("stub OSRM answers");
+    }
+
+    let elapsed = started.elapsed();
+    let profiles = server.join().expect("stub OSRM finishes");
+    assert_eq!(
+        profiles,
+        [
+            "walking",
+            "wheelchair",
+            "walking",
+            "wheelchair",
+            "walking",
+            "wheelchair"
+        ]
+    );
+    // One bucket for both profiles spaces all six requests 100 ms apart;
+    // a bucket each would send them in half the time.
+    assert!(elapsed >= Duration::from_millis(480), "took {elapsed:?}");
+}
+
+#[rstest]
+fn profiles_share_one_circuit_breaker(sample_pois: Vec<PointOfInterest>) {
+    let config = HttpTravelTimeProviderConfig::new("http://127.0.0.1:9")
+        .with_step_free_profile("wheelchair")
+        .with_retry(RetryPolicy::none())
+        .with_circuit_breaker(CircuitBreakerPolicy {
+            failure_threshold: 1,
+            cooldown: Duration::from_mins(1),
+        });
+    let provider = HttpTravelTimeProvider::with_config(config).expect("provider should build");
+
+    provider
+        .get_travel_time_matrix(&sample_pois)
+        .expect_err("nothing is listening");
+    let step_free = provider
+        .step_free()
+        .expect("step-free profile")
+        .get_travel_time_matrix(&sample_pois)
+        .expect_err("circuit should be open");
+
+    assert!(matches!(step_free, TravelTimeError::CircuitOpen { .. }));
+}
@@ -0,0 +1,126 @@
+//! Tests for building OSRM request URLs.
+
+use super::*;
+
+#[rstest]
+fn build_table_url_formats_coordinates(sample_pois: Vec<PointOfInterest>) {
+    let provider =
+        HttpTravelTimeProvider::new("http://osrm.example.com").expect("provider should build");
+
+    let url = provider.build_table_url(&sample_pois);
+
+    assert_eq!(
+        url,
+        "http://osrm.example.com/table/v1/walking/-0.1,51.5;-0.2,51.6"
+    );
+}
+
+#[rstest]
+#[case(RoutingProfile::Cycling, "cycling")]
+#[case(RoutingProfile::from("foot-accessible"), "foot-accessible")]
+fn build_table_url_uses_profile(
+    sample_pois: Vec<PointOfInterest>,
+    #[case] profile: RoutingProfile,
+    #[case] segment: &str,
+) {
+    let config = HttpTravelTimeProviderConfig::new("http://osrm.example.com").with_profile(profile);
+    let provider = HttpTravelTimeProvider::with_config(config).expect("provider should build");
+
+    let url = provider.build_table_url(&sample_pois);
+
+    assert_eq!(
+        url,
+        format!("http://osrm.example.com/table/v1/{segment}/-0.1,51.5;-0.2,51.6")
+    );
+}
+
+#[rstest]
+#[case("")]
+#[case("bike/../admin")]
+#[case("foot?x=1")]
+fn rejects_unsafe_profiles(#[case] profile: &str) {
+    let config = HttpTravelTimeProviderConfig::new("http://osrm.example.com").with_profile(profile);
+
+    let err = HttpTravelTimeProvider::with_config(config).expect_err("profile is unsafe");
+
+    assert!(matches!(err, ProviderBuildError::InvalidProfile(name) if name == profile));
+}
+
+#[rstest]
+fn step_free_requests_use_the_step_free_profile(sample_pois: Vec<PointOfInterest>) {
+    let provider =
+        HttpTravelTimeProvider::new("http://osrm.example.com").expect("provider should build");
+    assert!(provider.step_free().is_none());
+
+    let config = HttpTravelTimeProviderConfig::new("http://osrm.example.com")
+        .with_step_free_profile("wheelchair");
+    let provider = HttpTravelTimeProvider::with_config(config).expect("provider should build");
+    let step_free = provider.step_free.as_deref().expect("step-free provider");
+
+    assert_eq!(
+        step_free.build_table_url(&sample_pois),
+        "http://osrm.example.com/table/v1/wheelchair/-0.1,51.5;-0.2,51.6"
+    );
+    assert!(provider.step_free().is_some());
+}
+
+#[rstest]
+fn rejects_unsafe_step_free_profiles() {
+    let config = HttpTravelTimeProviderConfig::new("http://osrm.example.com")
+        .with_step_free_profile("foot?x=1");
+
+    let err = HttpTravelTimeProvider::with_config(config).expect_err("profile is unsafe");
+
+    assert!(matches!(err, ProviderBuildError::InvalidProfile(name) if name == "foot?x=1"));
+}
+
+#[rstest]
+fn standard_profile_names_parse_to_variants() {
+    assert_eq!(RoutingProfile::from("driving"), RoutingProfile::Driving);
+    assert_eq!(
+        RoutingProfile::from(String::from("bike")),
+        RoutingProfile::Custom(String::from("bike"))
+    );
+}
+
+#[rstest]
+fn build_table_url_strips_trailing_slash(sample_pois: Vec<PointOfInterest>) {
+    let provider =
+        HttpTravelTimeProvider::new("http://osrm.example.com/").expect("provider should build");
+
+    let url = provider.build_table_url(&sample_pois);
+
+    assert!(url.starts_with("http://osrm.example.com/table/"));
+    assert!(!url.contains("//table"));
+}
+
+#[rstest]
+fn build_snap_url_routes_from_first_poi_only(sample_pois: Vec<PointOfInterest>) {
+    let provider =
+        HttpTravelTimeProvider::new("http://osrm.example.com").expect("provider should build");
+
+    let url = provider.build_snap_url(&sample_pois);
+
+    assert_eq!(
+        url,
+        "http://osrm.example.com/table/v1/walking/-0.1,51.5;-0.2,51.6?sources=0"
+    );
+}
+
+#[rstest]
+fn build_route_url_requests_full_geojson_geometry() {
+    let provider =
+        HttpTravelTimeProvider::new("http://osrm.example.com").expect("provider should build");
+    let stops = [
+        Coord { x: -0.1, y: 51.5 },
+        Coord { x: -0.2, y: 51.6 },
+        Coord { x: -0.1, y: 51.5 },
+    ];
+
+    let url = provider.build_route_url(&stops);
+
+    assert_eq!(
+        url,
+        "http://osrm.example.com/route/v1/walking/-0.1,51.5;-0.2,51.6;-0.1,51.5?overview=full&geometries=geojson"
+    );
+}
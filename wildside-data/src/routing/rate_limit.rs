@@ -0,0 +1,187 @@
+//! Client-side rate limiting for routing requests.
+//!
+//! Public OSRM and Valhalla instances publish usage policies, typically a
+//! request rate with some allowance for bursts. [`RateLimitPolicy`] configures
+//! a token bucket that delays outbound requests to stay within such a policy,
+//! so batch jobs need no external throttling.
+
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// How many routing requests may be sent, on average and in a burst.
+///
+/// The bucket holds up to `burst` tokens and refills at
+/// `requests_per_second`. Each HTTP request, retries included, takes one
+/// token, waiting for it when the bucket is empty. A rate that is zero,
+/// negative, or not finite disables the limit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimitPolicy {
+    /// Sustained request rate.
+    pub requests_per_second: f64,
+    /// Requests that may be sent back to back after a quiet spell; `0`
+    /// behaves like `1`.
+    pub burst: u32,
+}
+
+impl RateLimitPolicy {
+    /// A policy that never delays requests.
+    #[must_use]
+    pub const fn unlimited() -> Self {
+        Self {
+            requests_per_second: f64::INFINITY,
+            burst: 1,
+        }
+    }
+
+    /// Allow `requests_per_second` on average with bursts of up to `burst`.
+    #[must_use]
+    pub const fn new(requests_per_second: f64, burst: u32) -> Self {
+        Self {
+            requests_per_second,
+            burst,
+        }
+    }
+
+    /// Report whether the policy ever delays a request.
+    fn is_limited(self) -> bool {
+        self.requests_per_second.is_finite() && self.requests_per_second > 0.0
+    }
+
+    fn capacity(self) -> f64 {
+        f64::from(self.burst.max(1))
+    }
+}
+
+impl Default for RateLimitPolicy {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    /// Tokens available at `updated`; negative while requests are queued.
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token bucket shared by a provider's requests.
+#[derive(Debug)]
+pub(super) struct RateLimiter {
+    policy: RateLimitPolicy,
+    bucket: Mutex<Option<Bucket>>,
+}
+
+impl RateLimiter {
+    pub(super) const fn new(policy: RateLimitPolicy) -> Self {
+        Self {
+            policy,
+            bucket: Mutex::new(None),
+        }
+    }
+
+    /// Take a token at `now` and return how long to wait before sending.
+    ///
+    /// A token is reserved even when the caller has to wait, so concurrent
+    /// callers are spaced out in the order they asked. A rate so slow that
+    /// the wait overflows [`Duration`] yields [`Duration::MAX`].
+    pub(super) fn reserve(&self, now: Instant) -> Duration {
+        if !self.policy.is_limited() {
+            return Duration::ZERO;
+        }
+        let rate = self.policy.requests_per_second;
+        let capacity = self.policy.capacity();
+        let mut bucket = self.bucket.lock().unwrap_or_else(PoisonError::into_inner);
+        let current = bucket.get_or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        let elapsed = now.saturating_duration_since(current.updated).as_secs_f64();
+        current.tokens = (current.tokens + elapsed * rate).min(capacity) - 1.0;
+        current.updated = current.updated.max(now);
+        if current.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::try_from_secs_f64(-current.tokens / rate).unwrap_or(Duration::MAX)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for the routing request token bucket.
+
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    fn unlimited_policy_never_waits() {
+        let limiter = RateLimiter::new(RateLimitPolicy::unlimited());
+        let now = Instant::now();
+        for _ in 0..100 {
+            assert_eq!(limiter.reserve(now), Duration::ZERO);
+        }
+    }
+
+    #[rstest]
+    #[case(0.0)]
+    #[case(-1.0)]
+    #[case(f64::NAN)]
+    fn unusable_rates_disable_the_limit(#[case] rate: f64) {
+        let limiter = RateLimiter::new(RateLimitPolicy::new(rate, 1));
+        let now = Instant::now();
+        assert_eq!(limiter.reserve(now), Duration::ZERO);
+        assert_eq!(limiter.reserve(now), Duration::ZERO);
+    }
+
+    #[rstest]
+    fn burst_is_free_then_requests_are_spaced_out() {
+        let limiter = RateLimiter::new(RateLimitPolicy::new(2.0, 3));
+        let now = Instant::now();
+
+        let delays: Vec<_> = (0..5).map(|_| limiter.reserve(now)).collect();
+
+        assert_eq!(
+            delays,
+            [
+                Duration::ZERO,
+                Duration::ZERO,
+                Duration::ZERO,
+                Duration::from_millis(500),
+                Duration::from_secs(1),
+            ]
+        );
+    }
+
+    #[rstest]
+    fn bucket_refills_over_time_up_to_the_burst() {
+        let limiter = RateLimiter::new(RateLimitPolicy::new(1.0, 2));
+        let start = Instant::now();
+        limiter.reserve(start);
+        limiter.reserve(start);
+        assert_eq!(limiter.reserve(start), Duration::from_secs(1));
+
+        // After a long pause the bucket is full again, but holds no more
+        // than the burst.
+        let later = start + Duration::from_mins(1);
+        assert_eq!(limiter.reserve(later), Duration::ZERO);
+        assert_eq!(limiter.reserve(later), Duration::ZERO);
+        assert_eq!(limiter.reserve(later), Duration::from_secs(1));
+    }
+
+    #[rstest]
+    fn tiny_rates_wait_as_long_as_a_duration_allows() {
+        let limiter = RateLimiter::new(RateLimitPolicy::new(1e-20, 1));
+        let now = Instant::now();
+        assert_eq!(limiter.reserve(now), Duration::ZERO);
+        assert_eq!(limiter.reserve(now), Duration::MAX);
+    }
+
+    #[rstest]
+    fn zero_burst_behaves_like_one() {
+        let limiter = RateLimiter::new(RateLimitPolicy::new(4.0, 0));
+        let now = Instant::now();
+        assert_eq!(limiter.reserve(now), Duration::ZERO);
+        assert_eq!(limiter.reserve(now), Duration::from_millis(250));
+    }
+}
@@ -0,0 +1,247 @@
+//! Retries and circuit breaking for routing requests.
+//!
+//! A routing service that is briefly overloaded answers with 5xx responses or
+//! not at all. [`RetryPolicy`] repeats such requests with exponential backoff,
+//! and a circuit breaker configured by [`CircuitBreakerPolicy`] stops sending
+//! requests for a while once several in a row have failed, so callers fail
+//! fast instead of queueing behind a service that is down.
+
+use std::future::Future;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use log::warn;
+use wildside_core::TravelTimeError;
+
+/// How often and how patiently transient routing failures are retried.
+///
+/// Timeouts, network errors, HTTP 429, and HTTP 5xx responses are retried.
+/// Errors reported in a successful response body, such as an invalid query,
+/// are returned at once because repeating the request cannot fix them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts per request, including the first; `0` behaves like `1`.
+    pub max_attempts: u32,
+    /// Delay before the first retry; each further retry doubles it.
+    pub initial_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// A policy that makes a single attempt.
+    #[must_use]
+    pub const fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: Duration::ZERO,
+        }
+    }
+
+    /// Delay to wait after the given failed attempt (counting from 1).
+    fn backoff(self, attempt: u32) -> Duration {
+        let doublings = attempt.saturating_sub(1).min(16);
+        self.initial_backoff.saturating_mul(1 << doublings)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+        }
+    }
+}
+
+/// When the circuit breaker opens and how long it stays open.
+///
+/// After `failure_threshold` consecutive requests fail with transient errors
+/// (once their retries are spent), further requests fail immediately with
+/// [`TravelTimeError::CircuitOpen`] until `cooldown` has elapsed. The next
+/// request is then let through as a trial: success closes the circuit, and
+/// failure opens it for another `cooldown`. A trial abandoned before it
+/// finishes, say by a caller's timeout, counts as neither, and the next
+/// request becomes the trial.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CircuitBreakerPolicy {
+    /// Consecutive failed requests that open the circuit; `0` disables it.
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before a trial request.
+    pub cooldown: Duration,
+}
+
+impl CircuitBreakerPolicy {
+    /// A policy that never opens the circuit.
+    #[must_use]
+    pub const fn disabled() -> Self {
+        Self {
+            failure_threshold: 0,
+            cooldown: Duration::ZERO,
+        }
+    }
+}
+
+impl Default for CircuitBreakerPolicy {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Report whether a failed request is worth repeating.
+pub(super) const fn is_transient(error: &TravelTimeError) -> bool {
+    match error {
+        TravelTimeError::HttpError { status, .. } => *status == 429 || *status >= 500,
+        TravelTimeError::NetworkError { .. } | TravelTimeError::Timeout { .. } => true,
+        _ => false,
+    }
+}
+
+/// Run `request` until it succeeds, fails permanently, or `policy` is spent.
+pub(super) async fn with_retry<T, F, Fut>(
+    policy: RetryPolicy,
+    mut request: F,
+) -> Result<T, TravelTimeError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, TravelTimeError>>,
+{
+    let mut attempt = 1;
+    loop {
+        match request().await {
+            Err(error) if attempt < policy.max_attempts && is_transient(&error) => {
+                let delay = policy.backoff(attempt);
+                warn!("Routing request attempt {attempt} failed ({error}); retrying in {delay:?}");
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BreakerState {
+    /// Requests flow; counts consecutive transient failures.
+    Closed { failures: u32 },
+    /// Requests are refused until the instant passes.
+    Open { until: Instant },
+    /// One trial request is in flight; others are refused.
+    HalfOpen,
+}
+
+/// Consecutive-failure circuit breaker shared by a provider's requests.
+#[derive(Debug)]
+pub(super) struct CircuitBreaker {
+    policy: CircuitBreakerPolicy,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    pub(super) const fn new(policy: CircuitBreakerPolicy) -> Self {
+        Self {
+            policy,
+            state: Mutex::new(BreakerState::Closed { failures: 0 }),
+        }
+    }
+
+    /// Decide whether a request may be sent at `now`.
+    ///
+    /// Returns the time left until a trial request is allowed when the
+    /// circuit is open, or zero while another trial is in flight.
+    pub(super) fn admit(&self, now: Instant) -> Result<Admission<'_>, Duration> {
+        let admission = |trial| Admission {
+            breaker: self,
+            admitted_at: now,
+            trial,
+            settled: false,
+        };
+        if self.policy.failure_threshold == 0 {
+            return Ok(admission(false));
+        }
+        let mut state = self.lock();
+        match *state {
+            BreakerState::Closed { .. } => Ok(admission(false)),
+            BreakerState::Open { until } if now >= until => {
+                *state = BreakerState::HalfOpen;
+                Ok(admission(true))
+            }
+            BreakerState::Open { until } => Err(until - now),
+            BreakerState::HalfOpen => Err(Duration::ZERO),
+        }
+    }
+
+    /// Record the outcome of an admitted request that finished at `now`.
+    ///
+    /// Only transient failures count against the service; any other outcome
+    /// shows it is answering and closes the circuit.
+    fn record<T>(&self, result: &Result<T, TravelTimeError>, now: Instant) {
+        if self.policy.failure_threshold == 0 {
+            return;
+        }
+        let mut state = self.lock();
+        let failed = result.as_ref().err().is_some_and(is_transient);
+        *state = match (*state, failed) {
+            (_, false) => BreakerState::Closed { failures: 0 },
+            (BreakerState::Closed { failures }, true)
+                if failures + 1 < self.policy.failure_threshold =>
+            {
+                BreakerState::Closed {
+                    failures: failures + 1,
+                }
+            }
+            (_, true) => BreakerState::Open {
+                until: now + self.policy.cooldown,
+            },
+        };
+    }
+
+    /// Reopen the circuit after a trial admitted at `admitted_at` was
+    /// abandoned, with its cool-down already spent so the next request
+    /// becomes the new trial.
+    fn abandon_trial(&self, admitted_at: Instant) {
+        let mut state = self.lock();
+        if *state == BreakerState::HalfOpen {
+            *state = BreakerState::Open { until: admitted_at };
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BreakerState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Permission to send one request, handed out by [`CircuitBreaker::admit`].
+///
+/// Pass the request's outcome to [`Admission::record`]. A trial request
+/// whose future is dropped before it finishes, say by a caller's timeout,
+/// would otherwise leave the circuit half-open for good, so dropping an
+/// unrecorded trial reopens the circuit ready for another.
+#[derive(Debug)]
+#[must_use = "record the outcome of the admitted request"]
+pub(super) struct Admission<'a> {
+    breaker: &'a CircuitBreaker,
+    admitted_at: Instant,
+    trial: bool,
+    settled: bool,
+}
+
+impl Admission<'_> {
+    /// Record the outcome of the admitted request, which finished at `now`.
+    pub(super) fn record<T>(mut self, result: &Result<T, TravelTimeError>, now: Instant) {
+        self.settled = true;
+        self.breaker.record(result, now);
+    }
+}
+
+impl Drop for Admission<'_> {
+    fn drop(&mut self) {
+        if self.trial && !self.settled {
+            self.breaker.abandon_trial(self.admitted_at);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
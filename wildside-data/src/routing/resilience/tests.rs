@@ -0,0 +1,186 @@
+//! Tests for routing retries and circuit breaking.
+
+use super::*;
+use rstest::rstest;
+use std::cell::Cell;
+
+/// Ask `breaker` to admit a request at `now`, releasing the admission at once.
+fn admits(breaker: &CircuitBreaker, now: Instant) -> Result<(), Duration> {
+    breaker.admit(now).map(drop)
+}
+
+fn unavailable() -> TravelTimeError {
+    TravelTimeError::HttpError {
+        url: "http://osrm.invalid/table/v1/walking/0,0".to_string(),
+        status: 503,
+        message: "service unavailable".to_string(),
+    }
+}
+
+fn invalid_query() -> TravelTimeError {
+    TravelTimeError::ServiceError {
+        code: "InvalidQuery".to_string(),
+        message: "Too many coordinates".to_string(),
+    }
+}
+
+fn run_with_retry(
+    policy: RetryPolicy,
+    outcomes: Vec<Result<u32, TravelTimeError>>,
+) -> (Result<u32, TravelTimeError>, usize) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime should build");
+    let calls = Cell::new(0);
+    let result = runtime.block_on(with_retry(policy, || {
+        let call = calls.get();
+        calls.set(call + 1);
+        let outcome = outcomes[call.min(outcomes.len() - 1)].clone();
+        async move { outcome }
+    }));
+    (result, calls.get())
+}
+
+#[rstest]
+#[case(503, true)]
+#[case(429, true)]
+#[case(404, false)]
+fn classifies_http_statuses(#[case] status: u16, #[case] expected: bool) {
+    let error = TravelTimeError::HttpError {
+        url: String::new(),
+        status,
+        message: String::new(),
+    };
+    assert_eq!(is_transient(&error), expected);
+}
+
+#[rstest]
+fn retries_transient_failures_until_success() {
+    let policy = RetryPolicy {
+        max_attempts: 3,
+        initial_backoff: Duration::ZERO,
+    };
+    let (result, calls) = run_with_retry(policy, vec![Err(unavailable()), Ok(7)]);
+    assert_eq!(result, Ok(7));
+    assert_eq!(calls, 2);
+}
+
+#[rstest]
+fn gives_up_after_max_attempts() {
+    let policy = RetryPolicy {
+        max_attempts: 3,
+        initial_backoff: Duration::ZERO,
+    };
+    let (result, calls) = run_with_retry(policy, vec![Err(unavailable())]);
+    assert_eq!(result, Err(unavailable()));
+    assert_eq!(calls, 3);
+}
+
+#[rstest]
+fn does_not_retry_permanent_failures() {
+    let (result, calls) = run_with_retry(RetryPolicy::default(), vec![Err(invalid_query())]);
+    assert_eq!(result, Err(invalid_query()));
+    assert_eq!(calls, 1);
+}
+
+#[rstest]
+fn backoff_doubles_per_attempt() {
+    let policy = RetryPolicy {
+        max_attempts: 4,
+        initial_backoff: Duration::from_millis(100),
+    };
+    assert_eq!(policy.backoff(1), Duration::from_millis(100));
+    assert_eq!(policy.backoff(3), Duration::from_millis(400));
+}
+
+#[rstest]
+fn breaker_opens_after_threshold_and_recovers_after_cooldown() {
+    let breaker = CircuitBreaker::new(CircuitBreakerPolicy {
+        failure_threshold: 2,
+        cooldown: Duration::from_secs(10),
+    });
+    let start = Instant::now();
+    let failure: Result<(), _> = Err(unavailable());
+
+    breaker.record(&failure, start);
+    assert_eq!(admits(&breaker, start), Ok(()));
+    breaker.record(&failure, start);
+    assert_eq!(admits(&breaker, start), Err(Duration::from_secs(10)));
+
+    let later = start + Duration::from_secs(10);
+    let trial = breaker.admit(later).expect("trial should be admitted");
+    assert_eq!(admits(&breaker, later), Err(Duration::ZERO));
+    trial.record(&Ok(()), later);
+    assert_eq!(admits(&breaker, later), Ok(()));
+}
+
+#[rstest]
+fn failed_trial_reopens_breaker() {
+    let breaker = CircuitBreaker::new(CircuitBreakerPolicy {
+        failure_threshold: 1,
+        cooldown: Duration::from_secs(5),
+    });
+    let start = Instant::now();
+    let failure: Result<(), _> = Err(unavailable());
+    breaker.record(&failure, start);
+
+    let later = start + Duration::from_secs(5);
+    let trial = breaker.admit(later).expect("trial should be admitted");
+    trial.record(&failure, later);
+    assert_eq!(admits(&breaker, later), Err(Duration::from_secs(5)));
+}
+
+#[rstest]
+fn dropped_trial_lets_the_next_request_try_again() {
+    let breaker = CircuitBreaker::new(CircuitBreakerPolicy {
+        failure_threshold: 1,
+        cooldown: Duration::from_secs(5),
+    });
+    let start = Instant::now();
+    breaker.record(&Err::<(), _>(unavailable()), start);
+    let later = start + Duration::from_secs(5);
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .expect("runtime should build");
+
+    // The trial hangs until the caller's timeout drops it mid-flight.
+    let probe = async {
+        let trial = breaker.admit(later);
+        assert!(trial.is_ok());
+        std::future::pending::<()>().await;
+        drop(trial);
+    };
+    let outcome =
+        runtime.block_on(async { tokio::time::timeout(Duration::from_millis(10), probe).await });
+    assert!(outcome.is_err());
+
+    let retry = breaker
+        .admit(later)
+        .expect("a new trial should be admitted");
+    assert_eq!(admits(&breaker, later), Err(Duration::ZERO));
+    retry.record(&Ok(()), later);
+    assert_eq!(admits(&breaker, later), Ok(()));
+}
+
+#[rstest]
+fn permanent_failures_do_not_trip_breaker() {
+    let breaker = CircuitBreaker::new(CircuitBreakerPolicy {
+        failure_threshold: 1,
+        cooldown: Duration::from_secs(5),
+    });
+    let now = Instant::now();
+    breaker.record(&Err::<(), _>(invalid_query()), now);
+    assert_eq!(admits(&breaker, now), Ok(()));
+}
+
+#[rstest]
+fn disabled_breaker_always_admits() {
+    let breaker = CircuitBreaker::new(CircuitBreakerPolicy::disabled());
+    let now = Instant::now();
+    for _ in 0..10 {
+        breaker.record(&Err::<(), _>(unavailable()), now);
+    }
+    assert_eq!(admits(&breaker, now), Ok(()));
+}
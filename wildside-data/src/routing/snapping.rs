@@ -0,0 +1,170 @@
+//! How far the routing service moved each POI to reach its network.
+//!
+//! OSRM answers every request by first snapping each coordinate to the
+//! nearest routable way. A POI that sits far from any footpath, such as a
+//! summit marker or a building in a private estate, still snaps somewhere,
+//! and the travel times computed from that point describe a different place.
+//! [`SnapReport`] records the snapping distance per POI so such POIs can be
+//! flagged, typically during ingestion.
+
+use geo::Coord;
+use wildside_core::{PointOfInterest, TravelTimeError};
+
+use super::osrm::Waypoint;
+
+/// Snapping distance beyond which a POI is treated as off the network, in
+/// metres.
+///
+/// This matches the limit the offline routing graph applies when it snaps
+/// POIs to its nodes.
+pub const DEFAULT_MAX_SNAP_DISTANCE_M: f64 = 250.0;
+
+/// Where the routing service placed one POI on its network.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaypointSnap {
+    /// Identifier of the POI.
+    pub poi_id: u64,
+    /// Coordinate supplied for the POI.
+    pub location: Coord<f64>,
+    /// Coordinate the service snapped it to.
+    pub snapped: Coord<f64>,
+    /// Distance between the two, in metres.
+    pub distance_m: f64,
+}
+
+/// Snapping distances for a set of POIs, in request order.
+///
+/// # Examples
+///
+/// ```rust
+/// use geo::Coord;
+/// use wildside_data::routing::{SnapReport, WaypointSnap};
+///
+/// let report = SnapReport {
+///     waypoints: vec![WaypointSnap {
+///         poi_id: 7,
+///         location: Coord { x: -3.2, y: 55.95 },
+///         snapped: Coord { x: -3.195, y: 55.95 },
+///         distance_m: 312.0,
+///     }],
+/// };
+/// let flagged: Vec<u64> = report.exceeding(250.0).map(|snap| snap.poi_id).collect();
+/// assert_eq!(flagged, [7]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SnapReport {
+    /// One entry per requested POI.
+    pub waypoints: Vec<WaypointSnap>,
+}
+
+impl SnapReport {
+    /// Pair `pois` with the waypoints OSRM returned for them.
+    pub(super) fn from_waypoints(
+        pois: &[PointOfInterest],
+        waypoints: Vec<Waypoint>,
+    ) -> Result<Self, TravelTimeError> {
+        if waypoints.len() != pois.len() {
+            return Err(TravelTimeError::ParseError {
+                message: format!(
+                    "OSRM returned {} waypoints for {} coordinates",
+                    waypoints.len(),
+                    pois.len()
+                ),
+            });
+        }
+        let waypoints = pois
+            .iter()
+            .zip(waypoints)
+            .map(|(poi, waypoint)| {
+                let [x, y] = waypoint.location;
+                WaypointSnap {
+                    poi_id: poi.id,
+                    location: poi.location,
+                    snapped: Coord { x, y },
+                    distance_m: waypoint.distance,
+                }
+            })
+            .collect();
+        Ok(Self { waypoints })
+    }
+
+    /// POIs that snapped further than `max_distance_m`, in request order.
+    pub fn exceeding(&self, max_distance_m: f64) -> impl Iterator<Item = &WaypointSnap> {
+        self.waypoints
+            .iter()
+            .filter(move |snap| snap.distance_m > max_distance_m)
+    }
+
+    /// Largest snapping distance in the report, or `None` when it is empty.
+    #[must_use]
+    pub fn max_distance_m(&self) -> Option<f64> {
+        self.waypoints
+            .iter()
+            .map(|snap| snap.distance_m)
+            .max_by(f64::total_cmp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for snapping reports.
+
+    use super::*;
+    use rstest::rstest;
+
+    fn waypoint(distance: f64) -> Waypoint {
+        Waypoint {
+            distance,
+            location: [1.0, 2.0],
+        }
+    }
+
+    fn pois() -> Vec<PointOfInterest> {
+        vec![
+            PointOfInterest::with_empty_tags(10, Coord { x: 0.0, y: 0.0 }),
+            PointOfInterest::with_empty_tags(11, Coord { x: 0.5, y: 0.5 }),
+            PointOfInterest::with_empty_tags(12, Coord { x: 1.0, y: 1.0 }),
+        ]
+    }
+
+    #[rstest]
+    fn pairs_waypoints_with_pois_in_order() {
+        let report = SnapReport::from_waypoints(
+            &pois(),
+            vec![waypoint(3.0), waypoint(400.0), waypoint(0.0)],
+        )
+        .expect("report");
+
+        let ids: Vec<_> = report.waypoints.iter().map(|snap| snap.poi_id).collect();
+        assert_eq!(ids, [10, 11, 12]);
+        assert_eq!(report.waypoints[1].snapped, Coord { x: 1.0, y: 2.0 });
+        assert_eq!(report.waypoints[1].location, Coord { x: 0.5, y: 0.5 });
+        assert_eq!(report.max_distance_m(), Some(400.0));
+    }
+
+    #[rstest]
+    #[case(250.0, vec![11])]
+    #[case(2.0, vec![10, 11])]
+    #[case(400.0, vec![])]
+    fn flags_pois_beyond_the_threshold(#[case] threshold: f64, #[case] expected: Vec<u64>) {
+        let report = SnapReport::from_waypoints(
+            &pois(),
+            vec![waypoint(3.0), waypoint(400.0), waypoint(0.0)],
+        )
+        .expect("report");
+
+        let flagged: Vec<_> = report
+            .exceeding(threshold)
+            .map(|snap| snap.poi_id)
+            .collect();
+        assert_eq!(flagged, expected);
+    }
+
+    #[rstest]
+    fn rejects_mismatched_waypoint_count() {
+        let err = SnapReport::from_waypoints(&pois(), vec![waypoint(1.0)]).expect_err("mismatch");
+        assert!(
+            matches!(err, TravelTimeError::ParseError { message } if message.contains("1 waypoints for 3"))
+        );
+    }
+}
@@ -18,14 +18,15 @@ use wildside_core::{PointOfInterest, TravelTimeError, TravelTimeMatrix, TravelTi
 /// ```
 /// use std::time::Duration;
 /// use wildside_data::routing::test_support::StubTravelTimeProvider;
-/// use wildside_core::{PointOfInterest, TravelTimeProvider, TravelTimeError};
+/// use wildside_core::{PointOfInterest, TravelTimeMatrix, TravelTimeProvider, TravelTimeError};
 /// use geo::Coord;
 ///
 /// // Create a provider that returns a specific matrix
-/// let matrix = vec![
+/// let matrix = TravelTimeMatrix::try_from(vec![
 ///     vec![Duration::ZERO, Duration::from_secs(60)],
 ///     vec![Duration::from_secs(60), Duration::ZERO],
-/// ];
+/// ])
+/// .expect("square matrix");
 /// let provider = StubTravelTimeProvider::with_matrix(matrix);
 ///
 /// let pois = vec![
@@ -85,12 +86,7 @@ impl StubTravelTimeProvider {
 ///
 /// Returns a matrix with zero on the diagonal and one second for off-diagonal.
 fn build_unit_matrix(size: usize) -> TravelTimeMatrix {
-    (0..size).map(|i| build_unit_row(size, i)).collect()
-}
-
-/// Build a single row of a unit travel time matrix.
-fn build_unit_row(size: usize, row_index: usize) -> Vec<Duration> {
-    (0..size).map(|j| unit_duration(row_index, j)).collect()
+    TravelTimeMatrix::from_fn(size, unit_duration)
 }
 
 /// Return the unit duration for cell (i, j): zero on diagonal, one second otherwise.
@@ -134,10 +130,11 @@ mod tests {
 
     #[rstest]
     fn with_matrix_returns_configured_matrix() {
-        let matrix = vec![
+        let matrix = TravelTimeMatrix::try_from(vec![
             vec![Duration::ZERO, Duration::from_secs(60)],
             vec![Duration::from_secs(60), Duration::ZERO],
-        ];
+        ])
+        .expect("square matrix");
         let provider = StubTravelTimeProvider::with_matrix(matrix.clone());
 
         let result = provider
@@ -181,7 +178,7 @@ mod tests {
             .expect("should succeed");
 
         assert_eq!(matrix.len(), 3);
-        for (i, row) in matrix.iter().enumerate() {
+        for (i, row) in matrix.rows().enumerate() {
             assert_eq!(row.len(), 3);
             for (j, &cell) in row.iter().enumerate() {
                 if i == j {
@@ -1,6 +1,7 @@
 //! Tests for Wildside data loading and decoding behaviour.
 
 use super::*;
+use geo::Coord;
 use rstest::{fixture, rstest};
 use std::path::PathBuf;
 use tempfile::TempPath;
@@ -31,6 +32,11 @@ fn poi_pbf(#[from(fixtures_dir)] dir: PathBuf) -> TempPath {
     decode_fixture(&dir, "poi_tags")
 }
 
+#[fixture]
+fn footpaths_pbf(#[from(fixtures_dir)] dir: PathBuf) -> TempPath {
+    decode_fixture(&dir, "footpaths")
+}
+
 #[fixture]
 fn poi_pbf_with_invalid_coords(#[from(fixtures_dir)] dir: PathBuf) -> TempPath {
     decode_fixture(&dir, "invalid_coords")
@@ -176,6 +182,31 @@ fn propagates_open_error(#[from(fixtures_dir)] dir: PathBuf) {
     }
 }
 
+#[rstest]
+fn builds_routing_graph_from_walkable_ways(footpaths_pbf: TempPath) -> Result<(), OsmIngestError> {
+    let graph = build_routing_graph(footpaths_pbf.as_ref())?;
+    // The motorway, the `foot=no` footway, and the private path are skipped,
+    // leaving the footway and residential street through four nodes.
+    assert_eq!(graph.node_count(), 4, "expected four walkable nodes");
+    assert_eq!(graph.edge_count(), 6, "expected three segments each way");
+
+    let (start, _) = graph
+        .nearest_node(Coord {
+            x: 13.388,
+            y: 52.516,
+        })
+        .expect("graph has nodes");
+    let (end, _) = graph
+        .nearest_node(Coord {
+            x: 13.390,
+            y: 52.517,
+        })
+        .expect("graph has nodes");
+    let metres = graph.shortest_distance(start, end).expect("connected");
+    assert!((240.0..250.0).contains(&metres), "got {metres}");
+    Ok(())
+}
+
 #[rstest]
 fn rejects_invalid_payload(invalid_pbf: TempPath) {
     let err = ingest_osm_pbf(invalid_pbf.as_ref())
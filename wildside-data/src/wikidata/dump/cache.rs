@@ -0,0 +1,192 @@
+//! On-disk cache of the descriptor resolved from the last status manifest.
+//!
+//! Automated pipelines call [`resolve_latest_descriptor`](super::resolve_latest_descriptor)
+//! far more often than Wikimedia publishes new dumps. [`ManifestCache`] keeps
+//! the `ETag` and `Last-Modified` validators of the last `dumpstatus.json`
+//! response alongside the descriptor selected from it, so a `304 Not
+//! Modified` answer can be served without downloading or parsing the
+//! manifest again.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use ::log::warn;
+use reqwest::{
+    RequestBuilder,
+    header::{ETAG, HeaderMap, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+};
+use serde::{Deserialize, Serialize};
+
+use super::{DumpDescriptor, DumpFileName, DumpUrl};
+
+/// File-backed store for the last resolved manifest.
+#[derive(Debug, Clone)]
+pub(crate) struct ManifestCache {
+    path: PathBuf,
+}
+
+impl ManifestCache {
+    pub(crate) fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Load the cached entry, treating unreadable or corrupt files as misses.
+    pub(crate) fn load(&self) -> Option<CachedManifest> {
+        let bytes = match fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return None,
+            Err(error) => {
+                warn!(
+                    "ignoring unreadable manifest cache {:?}: {error}",
+                    self.path
+                );
+                return None;
+            }
+        };
+        serde_json::from_slice(&bytes)
+            .inspect_err(|error| warn!("ignoring corrupt manifest cache {:?}: {error}", self.path))
+            .ok()
+    }
+
+    /// Persist `entry`, logging rather than failing when the write is refused.
+    ///
+    /// A failed write only costs a full manifest fetch on the next call, so
+    /// it never aborts dump resolution.
+    pub(crate) fn store(&self, entry: &CachedManifest) {
+        if let Err(error) = self.write(entry) {
+            warn!("failed to update manifest cache {:?}: {error}", self.path);
+        }
+    }
+
+    fn write(&self, entry: &CachedManifest) -> io::Result<()> {
+        let bytes = serde_json::to_vec(entry).map_err(io::Error::other)?;
+        let parent = self
+            .path
+            .parent()
+            .filter(|path| !path.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        fs::create_dir_all(parent)?;
+        let mut temp = tempfile::Builder::new()
+            .prefix("dumpstatus-cache-")
+            .tempfile_in(parent)?;
+        io::Write::write_all(temp.as_file_mut(), &bytes)?;
+        temp.persist(&self.path).map_err(|error| error.error)?;
+        Ok(())
+    }
+}
+
+/// Validators of a manifest response together with the descriptor it yielded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct CachedManifest {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    file_name: String,
+    url: String,
+    size: Option<u64>,
+    sha1: Option<String>,
+}
+
+impl CachedManifest {
+    /// Build an entry from response headers, or `None` when the server sent
+    /// no validators to revalidate against.
+    pub(crate) fn from_response(headers: &HeaderMap, descriptor: &DumpDescriptor) -> Option<Self> {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_owned)
+        };
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+        if etag.is_none() && last_modified.is_none() {
+            return None;
+        }
+        Some(Self {
+            etag,
+            last_modified,
+            file_name: descriptor.file_name.as_ref().to_owned(),
+            url: descriptor.url.as_ref().to_owned(),
+            size: descriptor.size,
+            sha1: descriptor.sha1.clone(),
+        })
+    }
+
+    /// Attach `If-None-Match` and `If-Modified-Since` to `request`.
+    pub(crate) fn apply(&self, request: RequestBuilder) -> RequestBuilder {
+        let request = match &self.etag {
+            Some(etag) => request.header(IF_NONE_MATCH, etag),
+            None => request,
+        };
+        match &self.last_modified {
+            Some(last_modified) => request.header(IF_MODIFIED_SINCE, last_modified),
+            None => request,
+        }
+    }
+
+    /// Descriptor selected from the cached manifest.
+    pub(crate) fn descriptor(&self) -> DumpDescriptor {
+        DumpDescriptor {
+            file_name: DumpFileName::new(self.file_name.clone()),
+            url: DumpUrl::new(self.url.clone()),
+            size: self.size,
+            sha1: self.sha1.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for the manifest cache file.
+
+    use reqwest::header::HeaderValue;
+    use rstest::{fixture, rstest};
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[fixture]
+    fn descriptor() -> DumpDescriptor {
+        DumpDescriptor {
+            file_name: DumpFileName::new("wikidata-20240909-all.json.bz2"),
+            url: DumpUrl::new("https://example.org/wikidata-20240909-all.json.bz2"),
+            size: Some(5),
+            sha1: Some("abc123".to_owned()),
+        }
+    }
+
+    #[fixture]
+    fn tmp() -> TempDir {
+        TempDir::new().expect("create temp directory")
+    }
+
+    #[rstest]
+    fn round_trips_entries(descriptor: DumpDescriptor, tmp: TempDir) {
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, HeaderValue::from_static("\"v1\""));
+        let entry = CachedManifest::from_response(&headers, &descriptor).expect("validators");
+        let cache = ManifestCache::new(tmp.path().join("nested/dumpstatus.json"));
+
+        cache.store(&entry);
+
+        let loaded = cache.load().expect("cached entry");
+        assert_eq!(loaded, entry);
+        assert_eq!(loaded.descriptor(), descriptor);
+    }
+
+    #[rstest]
+    fn skips_responses_without_validators(descriptor: DumpDescriptor) {
+        assert!(CachedManifest::from_response(&HeaderMap::new(), &descriptor).is_none());
+    }
+
+    #[rstest]
+    fn treats_missing_and_corrupt_files_as_misses(tmp: TempDir) {
+        let path = tmp.path().join("dumpstatus.json");
+        let cache = ManifestCache::new(&path);
+        assert!(cache.load().is_none());
+
+        fs::write(&path, b"not json").expect("write corrupt cache");
+        assert!(cache.load().is_none());
+    }
+}
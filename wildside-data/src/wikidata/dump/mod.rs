@@ -1,6 +1,7 @@
 //! Facilities for discovering and downloading Wikidata dump artefacts.
 #![forbid(unsafe_code)]
 
+mod cache;
 mod error;
 mod log;
 mod ops;
@@ -14,6 +15,7 @@ pub mod test_support;
 pub use error::{TransportError, WikidataDumpError};
 pub use log::DownloadLog;
 pub use ops::{download_descriptor, download_latest_dump, resolve_latest_descriptor};
+pub(crate) use source::convert_reqwest_error;
 pub use source::{DEFAULT_USER_AGENT, DumpSource, HttpDumpSource};
 pub use types::{BaseUrl, DownloadOptions, DownloadReport, DumpDescriptor, DumpFileName, DumpUrl};
 
@@ -257,7 +257,10 @@ fn validate_and_record(
 /// Resolve the descriptor describing the latest available dump archive.
 ///
 /// This helper streams the manifest and applies the JSON dump heuristics used
-/// by [`download_latest_dump`].
+/// by [`download_latest_dump`]. Resolution is delegated to
+/// [`DumpSource::latest_descriptor`], so sources configured with a manifest
+/// cache, such as [`HttpDumpSource::with_manifest_cache`](super::HttpDumpSource::with_manifest_cache),
+/// can answer from the cache when the manifest is unchanged.
 ///
 /// # Examples
 /// ```
@@ -294,6 +297,13 @@ fn validate_and_record(
 /// ```
 pub async fn resolve_latest_descriptor<S: DumpSource + ?Sized>(
     source: &S,
+) -> Result<DumpDescriptor, WikidataDumpError> {
+    source.latest_descriptor().await
+}
+
+/// Fetch the full status manifest from `source` and select the latest dump.
+pub(crate) async fn resolve_from_status<S: DumpSource + ?Sized>(
+    source: &S,
 ) -> Result<DumpDescriptor, WikidataDumpError> {
     let mut manifest = source
         .fetch_status()
@@ -1,12 +1,15 @@
 //! Transport abstractions and HTTP client for retrieving Wikidata dumps.
 
 use async_trait::async_trait;
-use reqwest::{Client, Response, header::USER_AGENT};
-use std::io::{self, BufRead, Write};
+use reqwest::{Client, RequestBuilder, Response, StatusCode, header::USER_AGENT};
+use std::io::{self, BufRead, Cursor, Write};
+use std::path::PathBuf;
 use std::time::Duration;
 
+use super::cache::{CachedManifest, ManifestCache};
+use super::ops::{resolve_from_status, select_dump};
 use super::util::{sanitize_base_url, to_blocking_reader, to_sync_reader};
-use super::{BaseUrl, DumpUrl, TransportError};
+use super::{BaseUrl, DumpDescriptor, DumpUrl, TransportError, WikidataDumpError};
 
 pub const DEFAULT_USER_AGENT: &str = "wildside-wikidata-etl/0.1";
 const STATUS_PATH: &str = "/wikidatawiki/entities/dumpstatus.json";
@@ -23,6 +26,14 @@ pub trait DumpSource {
         url: &str,
         sink: &mut dyn Write,
     ) -> Result<u64, TransportError>;
+    /// Resolve the descriptor of the latest completed JSON dump.
+    ///
+    /// The default implementation fetches and parses the full status
+    /// manifest. Sources able to revalidate a cached manifest override it to
+    /// skip both steps when nothing has changed.
+    async fn latest_descriptor(&self) -> Result<DumpDescriptor, WikidataDumpError> {
+        resolve_from_status(self).await
+    }
 }
 
 /// HTTP implementation of [`DumpSource`].
@@ -31,6 +42,7 @@ pub struct HttpDumpSource {
     client: Client,
     base_url: BaseUrl,
     user_agent: String,
+    manifest_cache: Option<ManifestCache>,
 }
 
 impl HttpDumpSource {
@@ -43,6 +55,7 @@ impl HttpDumpSource {
             client,
             base_url: sanitize_base_url(base_url),
             user_agent,
+            manifest_cache: None,
         }
     }
 
@@ -54,6 +67,19 @@ impl HttpDumpSource {
         self
     }
 
+    /// Cache the last resolved manifest at `path` and revalidate it with
+    /// conditional requests.
+    ///
+    /// [`DumpSource::latest_descriptor`] sends the cached `ETag` and
+    /// `Last-Modified` values as `If-None-Match` and `If-Modified-Since`. A
+    /// `304 Not Modified` response returns the cached descriptor without
+    /// downloading or parsing `dumpstatus.json`; any other success replaces
+    /// the cache. Missing or corrupt cache files are treated as misses.
+    pub fn with_manifest_cache(mut self, path: impl Into<PathBuf>) -> Self {
+        self.manifest_cache = Some(ManifestCache::new(path));
+        self
+    }
+
     fn status_url(&self) -> DumpUrl {
         DumpUrl::new(format!("{}{}", self.base_url.as_ref(), STATUS_PATH))
     }
@@ -69,6 +95,56 @@ impl HttpDumpSource {
             .map_err(|err| convert_reqwest_error(err, url))
     }
 
+    fn status_request(&self, url: &str) -> RequestBuilder {
+        self.client
+            .get(url)
+            .timeout(Duration::from_secs(15))
+            .header(USER_AGENT, self.user_agent.as_str())
+    }
+
+    async fn revalidate_descriptor(
+        &self,
+        cache: &ManifestCache,
+    ) -> Result<DumpDescriptor, WikidataDumpError> {
+        let url = self.status_url();
+        let cached = cache.load();
+        let request = self.status_request(url.as_ref());
+        let request = match &cached {
+            Some(entry) => entry.apply(request),
+            None => request,
+        };
+        let response = request
+            .send()
+            .await
+            .map_err(|err| convert_reqwest_error(err, url.as_ref()))
+            .map_err(|source| WikidataDumpError::StatusFetch { source })?;
+        if let Some(entry) = cached
+            && response.status() == StatusCode::NOT_MODIFIED
+        {
+            return Ok(entry.descriptor());
+        }
+        self.refresh_descriptor(cache, response, url.as_ref()).await
+    }
+
+    async fn refresh_descriptor(
+        &self,
+        cache: &ManifestCache,
+        response: Response,
+        url: &str,
+    ) -> Result<DumpDescriptor, WikidataDumpError> {
+        let fetch_error = |err| WikidataDumpError::StatusFetch {
+            source: convert_reqwest_error(err, url),
+        };
+        let response = response.error_for_status().map_err(fetch_error)?;
+        let headers = response.headers().clone();
+        let body = response.bytes().await.map_err(fetch_error)?;
+        let descriptor = select_dump(&mut Cursor::new(body), &self.base_url)?;
+        if let Some(entry) = CachedManifest::from_response(&headers, &descriptor) {
+            cache.store(&entry);
+        }
+        Ok(descriptor)
+    }
+
     fn build_client(user_agent: &str) -> Client {
         Client::builder()
             .user_agent(user_agent)
@@ -87,10 +163,7 @@ impl DumpSource for HttpDumpSource {
     async fn fetch_status(&self) -> Result<Box<dyn BufRead + Send>, TransportError> {
         let url = self.status_url();
         let response = self
-            .client
-            .get(url.as_ref())
-            .timeout(Duration::from_secs(15))
-            .header(USER_AGENT, self.user_agent.as_str())
+            .status_request(url.as_ref())
             .send()
             .await
             .map_err(|err| convert_reqwest_error(err, url.as_ref()))?
@@ -111,9 +184,16 @@ impl DumpSource for HttpDumpSource {
             source,
         })
     }
+
+    async fn latest_descriptor(&self) -> Result<DumpDescriptor, WikidataDumpError> {
+        match &self.manifest_cache {
+            Some(cache) => self.revalidate_descriptor(cache).await,
+            None => resolve_from_status(self).await,
+        }
+    }
 }
 
-fn convert_reqwest_error(error: reqwest::Error, url: &str) -> TransportError {
+pub(crate) fn convert_reqwest_error(error: reqwest::Error, url: &str) -> TransportError {
     if let Some(status) = error.status() {
         return TransportError::Http {
             url: url.to_owned(),
@@ -3,9 +3,17 @@
 use super::ops::{normalize_url, select_dump};
 use super::test_support::{StubSource, block_on_for_tests};
 use super::util::sanitize_base_url;
-use super::{BaseUrl, DownloadLog, DumpUrl, WikidataDumpError, download_latest_dump};
+use super::{
+    BaseUrl, DownloadLog, DumpUrl, HttpDumpSource, WikidataDumpError, download_latest_dump,
+    resolve_latest_descriptor,
+};
 use rstest::{fixture, rstest};
-use std::{fs, io::Cursor};
+use std::{
+    fs,
+    io::{BufRead, BufReader, Cursor, Write},
+    net::TcpListener,
+    thread,
+};
 use tempfile::TempDir;
 use wikidata_rust::{Entity, Lang, WikiId};
 
@@ -192,4 +200,101 @@ fn normalizes_root_relative_urls(base_url: BaseUrl) {
     assert_eq!(absolute, expected);
 }
 
+/// Serve one canned response per connection and return each request head.
+fn serve_responses(responses: Vec<Vec<u8>>) -> (String, thread::JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind loopback listener");
+    let address = listener.local_addr().expect("listener address");
+    let handle = thread::spawn(move || {
+        responses
+            .into_iter()
+            .map(|response| {
+                let (mut stream, _) = listener.accept().expect("accept connection");
+                let reader = BufReader::new(stream.try_clone().expect("clone stream"));
+                let head: String = reader
+                    .lines()
+                    .map(|line| line.expect("read request line"))
+                    .take_while(|line| !line.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                stream.write_all(&response).expect("write response");
+                head.to_ascii_lowercase()
+            })
+            .collect()
+    });
+    (format!("http://{address}"), handle)
+}
+
+fn http_response(status: &str, headers: &str, body: &[u8]) -> Vec<u8> {
+    let mut response = format!(
+        "HTTP/1.1 {status}\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )
+    .into_bytes();
+    response.extend_from_slice(body);
+    response
+}
+
+#[rstest]
+fn manifest_cache_reuses_descriptor_when_not_modified(manifest: Vec<u8>) {
+    let temp_dir = TempDir::new().expect("failed to create temporary directory");
+    let cache_path = temp_dir.path().join("dumpstatus.cache.json");
+    let validators = "ETag: \"v1\"\r\nLast-Modified: Mon, 09 Sep 2024 00:00:00 GMT\r\n";
+    let (base_url, server) = serve_responses(vec![
+        http_response("200 OK", validators, &manifest),
+        http_response("304 Not Modified", validators, b""),
+    ]);
+    let source = HttpDumpSource::new(base_url).with_manifest_cache(&cache_path);
+
+    let fresh = block_on_for_tests(resolve_latest_descriptor(&source)).expect("fresh manifest");
+    let cached = block_on_for_tests(resolve_latest_descriptor(&source)).expect("cached manifest");
+
+    assert_eq!(cached, fresh);
+    assert!(cache_path.exists(), "cache file should be written");
+    let requests = server.join().expect("server thread");
+    let [first, second] = requests.as_slice() else {
+        panic!("expected two requests, got {requests:?}");
+    };
+    assert!(!first.contains("if-none-match"));
+    assert!(second.contains("if-none-match: \"v1\""));
+    assert!(second.contains("if-modified-since: mon, 09 sep 2024 00:00:00 gmt"));
+}
+
+#[rstest]
+fn manifest_cache_is_replaced_when_manifest_changes(manifest: Vec<u8>) {
+    let temp_dir = TempDir::new().expect("failed to create temporary directory");
+    let cache_path = temp_dir.path().join("dumpstatus.cache.json");
+    let newer = String::from_utf8(manifest.clone())
+        .expect("manifest is UTF-8")
+        .replace("20240909", "20240916");
+    let (base_url, server) = serve_responses(vec![
+        http_response("200 OK", "ETag: \"v1\"\r\n", &manifest),
+        http_response("200 OK", "ETag: \"v2\"\r\n", newer.as_bytes()),
+        http_response("304 Not Modified", "", b""),
+    ]);
+    let source = HttpDumpSource::new(base_url).with_manifest_cache(&cache_path);
+
+    let descriptors: Vec<_> = (0..3)
+        .map(|_| block_on_for_tests(resolve_latest_descriptor(&source)).expect("descriptor"))
+        .collect();
+
+    let names: Vec<_> = descriptors
+        .iter()
+        .map(|descriptor| descriptor.file_name.as_ref())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            "wikidatawiki-20240909-all.json.bz2",
+            "wikidatawiki-20240916-all.json.bz2",
+            "wikidatawiki-20240916-all.json.bz2",
+        ]
+    );
+    let requests = server.join().expect("server thread");
+    assert!(
+        requests
+            .last()
+            .is_some_and(|head| head.contains("if-none-match: \"v2\""))
+    );
+}
+
 mod behaviour;
@@ -0,0 +1,360 @@
+//! Entity enrichment through the Wikidata `wbgetentities` API.
+//!
+//! Streaming a full dump is overkill for small regions with a handful of
+//! linked entities. [`WikidataApiSource`] fetches those entities in batches
+//! from the MediaWiki Action API instead. Each entity in the response has the
+//! same JSON shape as a dump line, so it is converted by the dump extractor's
+//! own logic and both paths yield identical [`EntityClaims`]. The same
+//! endpoint resolves the labels of claim value entities.
+//!
+//! Redirects are not followed, mirroring dumps, which omit redirected ids.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io;
+use std::time::Duration;
+
+use reqwest::Client;
+use serde::Deserialize;
+use thiserror::Error;
+use tokio::runtime::Runtime;
+
+use super::labels::RawEntityLabels;
+use super::{
+    ClaimSelection, EntityClaims, EntityLabel, LabelLanguages, PoiEntityLinks, RawEntity,
+    linked_entity_claims, normalize_wikidata_id,
+};
+use crate::wikidata::dump::{DEFAULT_USER_AGENT, TransportError, convert_reqwest_error};
+
+/// Wikidata's MediaWiki Action API endpoint.
+pub const DEFAULT_API_URL: &str = "https://www.wikidata.org/w/api.php";
+
+/// Most ids `wbgetentities` accepts per request from anonymous clients.
+pub const MAX_ENTITY_BATCH: usize = 50;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Errors raised while enriching POIs through the Wikidata API.
+#[derive(Debug, Error)]
+pub enum WikidataApiError {
+    #[error("failed to build HTTP client: {source}")]
+    HttpClient {
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("failed to build Tokio runtime: {source}")]
+    Runtime {
+        #[source]
+        source: io::Error,
+    },
+    #[error("failed to fetch Wikidata entities: {source}")]
+    Transport {
+        #[source]
+        source: TransportError,
+    },
+    #[error("failed to parse wbgetentities response for {ids}")]
+    ParseResponse {
+        #[source]
+        source: simd_json::Error,
+        ids: String,
+    },
+    #[error("Wikidata API rejected request for {ids}: {code}: {info}")]
+    Api {
+        code: String,
+        info: String,
+        ids: String,
+    },
+}
+
+/// Transport performing `wbgetentities` requests.
+///
+/// Implementations return the raw JSON body so tests can substitute canned
+/// responses for HTTP.
+pub trait EntityTransport {
+    /// Fetch claims and sitelinks for `ids`.
+    ///
+    /// # Errors
+    /// Returns [`TransportError`] when the request fails.
+    fn get_entities(&self, ids: &[&str]) -> Result<Vec<u8>, TransportError>;
+
+    /// Fetch the labels of `ids` in `languages`.
+    ///
+    /// Defaults to [`get_entities`](Self::get_entities); label parsing ignores
+    /// the extra fields, so transports only override this to trim responses.
+    ///
+    /// # Errors
+    /// Returns [`TransportError`] when the request fails.
+    fn get_labels(&self, ids: &[&str], languages: &[&str]) -> Result<Vec<u8>, TransportError> {
+        let _ = languages;
+        self.get_entities(ids)
+    }
+}
+
+/// HTTP implementation of [`EntityTransport`].
+///
+/// Requests block on an internal current-thread Tokio runtime, so calls must
+/// not be made from inside another Tokio runtime.
+#[derive(Debug)]
+pub struct HttpEntityTransport {
+    client: Client,
+    runtime: Runtime,
+    endpoint: String,
+}
+
+impl HttpEntityTransport {
+    /// Build a transport for the API at `endpoint` (e.g. [`DEFAULT_API_URL`]).
+    ///
+    /// # Errors
+    /// Returns [`WikidataApiError`] when the HTTP client or runtime cannot be
+    /// built.
+    pub fn new(endpoint: impl Into<String>) -> Result<Self, WikidataApiError> {
+        let client = http_client(REQUEST_TIMEOUT)
+            .map_err(|source| WikidataApiError::HttpClient { source })?;
+        let runtime = blocking_runtime().map_err(|source| WikidataApiError::Runtime { source })?;
+        Ok(Self {
+            client,
+            runtime,
+            endpoint: endpoint.into(),
+        })
+    }
+
+    async fn fetch(&self, query: &[(&str, &str)]) -> Result<Vec<u8>, TransportError> {
+        let url = self.endpoint.as_str();
+        let response = self
+            .client
+            .get(url)
+            .query(&[
+                ("action", "wbgetentities"),
+                ("format", "json"),
+                ("redirects", "no"),
+            ])
+            .query(query)
+            .send()
+            .await
+            .map_err(|err| convert_reqwest_error(err, url))?
+            .error_for_status()
+            .map_err(|err| convert_reqwest_error(err, url))?;
+        let body = response
+            .bytes()
+            .await
+            .map_err(|err| convert_reqwest_error(err, url))?;
+        Ok(body.to_vec())
+    }
+}
+
+impl EntityTransport for HttpEntityTransport {
+    fn get_entities(&self, ids: &[&str]) -> Result<Vec<u8>, TransportError> {
+        let ids = ids.join("|");
+        self.runtime
+            .block_on(self.fetch(&[("props", "claims|sitelinks"), ("ids", &ids)]))
+    }
+
+    fn get_labels(&self, ids: &[&str], languages: &[&str]) -> Result<Vec<u8>, TransportError> {
+        let ids = ids.join("|");
+        let languages = languages.join("|");
+        self.runtime.block_on(self.fetch(&[
+            ("props", "labels"),
+            ("languages", &languages),
+            ("ids", &ids),
+        ]))
+    }
+}
+
+/// Build the HTTP client shared by the Wikidata API and SPARQL transports.
+pub(super) fn http_client(timeout: Duration) -> Result<Client, reqwest::Error> {
+    Client::builder()
+        .user_agent(DEFAULT_USER_AGENT)
+        .connect_timeout(timeout)
+        .timeout(timeout)
+        .build()
+}
+
+/// Build the current-thread runtime that blocking transports drive requests on.
+pub(super) fn blocking_runtime() -> io::Result<Runtime> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+}
+
+/// Fetches linked entities from the Wikidata API in batches.
+///
+/// # Examples
+/// ```
+/// use geo::Coord;
+/// use wildside_core::{PointOfInterest, Tags};
+/// use wildside_data::wikidata::dump::TransportError;
+/// use wildside_data::wikidata::etl::{
+///     ClaimPropertySet, ClaimSelection, EntityTransport, PoiEntityLinks, WikidataApiSource,
+/// };
+///
+/// struct Canned;
+/// impl EntityTransport for Canned {
+///     fn get_entities(&self, _ids: &[&str]) -> Result<Vec<u8>, TransportError> {
+///         Ok(br#"{"entities":{"Q64":{"id":"Q64","claims":{"P1435":[{"mainsnak":{"snaktype":"value","datavalue":{"type":"wikibase-entityid","value":{"id":"Q9259"}}}}]}}}}"#.to_vec())
+///     }
+/// }
+///
+/// let poi = PointOfInterest::new(
+///     1,
+///     Coord { x: 13.4, y: 52.5 },
+///     Tags::from([("wikidata".into(), "Q64".into())]),
+/// );
+/// let links = PoiEntityLinks::from_pois([&poi]);
+/// let properties = ClaimPropertySet::default();
+/// let source = WikidataApiSource::with_transport(Canned);
+/// let claims = source.extract_linked_entity_claims(&links, ClaimSelection::new(&properties))?;
+///
+/// assert_eq!(claims[0].entity_values("P1435").collect::<Vec<_>>(), ["Q9259"]);
+/// # Ok::<(), wildside_data::wikidata::etl::WikidataApiError>(())
+/// ```
+#[derive(Debug)]
+pub struct WikidataApiSource<T = HttpEntityTransport> {
+    transport: T,
+    batch_size: usize,
+}
+
+impl WikidataApiSource {
+    /// Build an HTTP-backed source for the API at `endpoint`.
+    ///
+    /// # Errors
+    /// Returns [`WikidataApiError`] when the HTTP transport cannot be built.
+    pub fn new(endpoint: impl Into<String>) -> Result<Self, WikidataApiError> {
+        HttpEntityTransport::new(endpoint).map(Self::with_transport)
+    }
+}
+
+impl<T: EntityTransport> WikidataApiSource<T> {
+    /// Build a source over a custom transport.
+    pub const fn with_transport(transport: T) -> Self {
+        Self {
+            transport,
+            batch_size: MAX_ENTITY_BATCH,
+        }
+    }
+
+    /// Set how many ids each request carries, clamped to
+    /// `1..=`[`MAX_ENTITY_BATCH`].
+    #[must_use]
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.clamp(1, MAX_ENTITY_BATCH);
+        self
+    }
+
+    /// Fetch every entity in `links` and extract the claims in `selection`.
+    ///
+    /// Entities the API reports as missing are skipped. Results are sorted by
+    /// entity id, matching [`extract_linked_entity_claims_for`](super::extract_linked_entity_claims_for).
+    ///
+    /// # Errors
+    /// Returns [`WikidataApiError`] when a request fails, a response cannot be
+    /// parsed, or the API reports an error.
+    pub fn extract_linked_entity_claims(
+        &self,
+        links: &PoiEntityLinks,
+        selection: ClaimSelection<'_>,
+    ) -> Result<Vec<EntityClaims>, WikidataApiError> {
+        let ids: Vec<&str> = links.entity_ids().collect();
+        let mut extracted = Vec::new();
+        for batch in ids.chunks(self.batch_size) {
+            let mut body = self.fetch_batch(batch)?;
+            let response: RawApiResponse<'_, RawEntity<'_>> = parse_response(&mut body, batch)?;
+            extracted.extend(
+                response
+                    .entities
+                    .values()
+                    .filter(|entity| entity.missing.is_none())
+                    .filter_map(|entity| linked_entity_claims(entity, links, selection)),
+            );
+        }
+        extracted.sort_unstable_by(|a, b| a.entity_id.cmp(&b.entity_id));
+        Ok(extracted)
+    }
+
+    /// Fetch the labels of `entity_ids` in `languages`.
+    ///
+    /// Use this to name claim value entities, such as the heritage
+    /// designations collected by
+    /// [`designation_entity_ids`](crate::wikidata::store::designation_entity_ids).
+    /// Missing entities and entities without a label in `languages` yield
+    /// nothing. Results are sorted by entity id and language, matching
+    /// [`extract_entity_labels`](super::extract_entity_labels).
+    ///
+    /// # Errors
+    /// Returns [`WikidataApiError`] when a request fails, a response cannot be
+    /// parsed, or the API reports an error.
+    pub fn extract_entity_labels(
+        &self,
+        entity_ids: &[String],
+        languages: &LabelLanguages,
+    ) -> Result<Vec<EntityLabel>, WikidataApiError> {
+        let ids: Vec<&str> = entity_ids.iter().map(String::as_str).collect();
+        let codes: Vec<&str> = languages.iter().collect();
+        let mut labels = Vec::new();
+        for batch in ids.chunks(self.batch_size) {
+            let mut body = self
+                .transport
+                .get_labels(batch, &codes)
+                .map_err(|source| WikidataApiError::Transport { source })?;
+            let response: RawApiResponse<'_, RawEntityLabels<'_>> =
+                parse_response(&mut body, batch)?;
+            labels.extend(
+                response
+                    .entities
+                    .values()
+                    .filter(|entity| entity.missing.is_none())
+                    .filter_map(|entity| {
+                        normalize_wikidata_id(&entity.id).map(|entity_id| (entity, entity_id))
+                    })
+                    .flat_map(|(entity, entity_id)| entity.labels_in(entity_id, languages)),
+            );
+        }
+        labels.sort_unstable();
+        Ok(labels)
+    }
+
+    fn fetch_batch(&self, batch: &[&str]) -> Result<Vec<u8>, WikidataApiError> {
+        self.transport
+            .get_entities(batch)
+            .map_err(|source| WikidataApiError::Transport { source })
+    }
+}
+
+/// Parse a `wbgetentities` response in place, borrowing entity strings from
+/// `body`.
+fn parse_response<'a, E: Deserialize<'a>>(
+    body: &'a mut [u8],
+    batch: &[&str],
+) -> Result<RawApiResponse<'a, E>, WikidataApiError> {
+    let response: RawApiResponse<'a, E> =
+        simd_json::from_slice(body).map_err(|source| WikidataApiError::ParseResponse {
+            source,
+            ids: batch.join("|"),
+        })?;
+    match response.error {
+        Some(RawApiError { code, info }) => Err(WikidataApiError::Api {
+            code,
+            info,
+            ids: batch.join("|"),
+        }),
+        None => Ok(response),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(bound(deserialize = "E: Deserialize<'de>"))]
+struct RawApiResponse<'a, E> {
+    #[serde(default, borrow)]
+    entities: BTreeMap<Cow<'a, str>, E>,
+    #[serde(default)]
+    error: Option<RawApiError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawApiError {
+    code: String,
+    #[serde(default)]
+    info: String,
+}
+
+#[cfg(test)]
+mod tests;
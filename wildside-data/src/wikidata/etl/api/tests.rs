@@ -0,0 +1,154 @@
+//! Unit tests for batched API enrichment.
+
+use std::cell::RefCell;
+use std::io::Cursor;
+
+use geo::Coord;
+use rstest::{fixture, rstest};
+use wildside_core::{PointOfInterest, Tags};
+
+use super::*;
+use crate::wikidata::etl::{ClaimPropertySet, extract_linked_entity_claims};
+
+const BERLIN: &str = r#"{"id":"Q64","claims":{"P1435":[{"mainsnak":{"snaktype":"value","datavalue":{"type":"wikibase-entityid","value":{"id":"Q9259"}}}}]},"sitelinks":{"enwiki":{"site":"enwiki","title":"Berlin"}}}"#;
+
+const WORLD_HERITAGE_SITE: &str = r#"{"id":"Q9259","labels":{"en":{"language":"en","value":"World Heritage Site"},"de":{"language":"de","value":"Welterbe"}},"claims":{}}"#;
+
+/// Answers from canned entity records and records each requested batch.
+struct StubTransport {
+    records: BTreeMap<&'static str, &'static str>,
+    requests: RefCell<Vec<Vec<String>>>,
+}
+
+impl EntityTransport for StubTransport {
+    fn get_entities(&self, ids: &[&str]) -> Result<Vec<u8>, TransportError> {
+        self.requests
+            .borrow_mut()
+            .push(ids.iter().map(|id| (*id).to_owned()).collect());
+        let entities: Vec<String> = ids
+            .iter()
+            .map(|id| {
+                let record = self.records.get(id).map_or_else(
+                    || format!(r#"{{"id":"{id}","missing":""}}"#),
+                    |record| (*record).to_owned(),
+                );
+                format!(r#""{id}":{record}"#)
+            })
+            .collect();
+        Ok(format!(r#"{{"entities":{{{}}},"success":1}}"#, entities.join(",")).into_bytes())
+    }
+}
+
+fn poi(id: u64, entity_id: &str) -> PointOfInterest {
+    PointOfInterest::new(
+        id,
+        Coord { x: 0.0, y: 0.0 },
+        Tags::from([("wikidata".into(), entity_id.into())]),
+    )
+}
+
+#[fixture]
+fn links() -> PoiEntityLinks {
+    PoiEntityLinks::from_pois([&poi(1, "Q64"), &poi(2, "Q90"), &poi(3, "Q1")])
+}
+
+#[fixture]
+fn transport() -> StubTransport {
+    StubTransport {
+        records: BTreeMap::from([
+            ("Q64", BERLIN),
+            ("Q90", r#"{"id":"Q90","claims":{}}"#),
+            ("Q9259", WORLD_HERITAGE_SITE),
+        ]),
+        requests: RefCell::new(Vec::new()),
+    }
+}
+
+#[rstest]
+fn fetches_entities_in_batches(links: PoiEntityLinks, transport: StubTransport) {
+    let properties = ClaimPropertySet::default();
+    let source = WikidataApiSource::with_transport(transport).with_batch_size(2);
+
+    let claims = source
+        .extract_linked_entity_claims(&links, ClaimSelection::new(&properties))
+        .expect("extraction should succeed");
+
+    let ids: Vec<&str> = claims
+        .iter()
+        .map(|claim| claim.entity_id.as_str())
+        .collect();
+    assert_eq!(ids, ["Q64", "Q90"]);
+    assert_eq!(
+        *source.transport.requests.borrow(),
+        [
+            vec!["Q1".to_owned(), "Q64".to_owned()],
+            vec!["Q90".to_owned()]
+        ]
+    );
+}
+
+#[rstest]
+fn matches_dump_extraction(transport: StubTransport) {
+    let links = PoiEntityLinks::from_pois([&poi(7, "Q64")]);
+    let properties = ClaimPropertySet::default();
+    let source = WikidataApiSource::with_transport(transport);
+
+    let from_api = source
+        .extract_linked_entity_claims(&links, ClaimSelection::new(&properties))
+        .expect("API extraction should succeed");
+    let from_dump = extract_linked_entity_claims(Cursor::new(BERLIN), &links)
+        .expect("dump extraction should succeed");
+
+    assert_eq!(from_api, from_dump);
+    assert_eq!(from_api[0].sitelink_count, Some(1));
+}
+
+#[rstest]
+fn surfaces_api_errors(links: PoiEntityLinks) {
+    struct Failing;
+    impl EntityTransport for Failing {
+        fn get_entities(&self, _ids: &[&str]) -> Result<Vec<u8>, TransportError> {
+            Ok(br#"{"error":{"code":"maxlag","info":"Waiting for a database server"}}"#.to_vec())
+        }
+    }
+    let properties = ClaimPropertySet::default();
+    let source = WikidataApiSource::with_transport(Failing);
+
+    let err = source
+        .extract_linked_entity_claims(&links, ClaimSelection::new(&properties))
+        .expect_err("API error should surface");
+
+    assert!(
+        matches!(&err, WikidataApiError::Api { code, .. } if code == "maxlag"),
+        "expected Api error, found {err:?}"
+    );
+}
+
+#[rstest]
+fn resolves_labels_of_present_entities(transport: StubTransport) {
+    let source = WikidataApiSource::with_transport(transport).with_batch_size(1);
+    let ids = ["Q9259".to_owned(), "Q1".to_owned(), "Q90".to_owned()];
+
+    let labels = source
+        .extract_entity_labels(&ids, &LabelLanguages::default())
+        .expect("label resolution should succeed");
+
+    assert_eq!(
+        labels,
+        [EntityLabel {
+            entity_id: "Q9259".into(),
+            language: "en".into(),
+            label: "World Heritage Site".into(),
+        }]
+    );
+    assert_eq!(source.transport.requests.borrow().len(), 3);
+}
+
+#[rstest]
+#[case(0, 1)]
+#[case(10, 10)]
+#[case(500, MAX_ENTITY_BATCH)]
+fn clamps_batch_size(transport: StubTransport, #[case] requested: usize, #[case] expected: usize) {
+    let source = WikidataApiSource::with_transport(transport).with_batch_size(requested);
+    assert_eq!(source.batch_size, expected);
+}
@@ -0,0 +1,306 @@
+//! Parallel decompression for multi-stream bzip2 Wikidata dumps.
+//!
+//! Some dumps are a concatenation of independent bzip2 streams. Each stream
+//! starts on a byte boundary with the `BZh` file header followed by the block
+//! magic, so the compressed input can be split without decoding it.
+//! [`ParallelBz2Reader`] hands those streams to a pool of worker threads and
+//! reassembles their output in order behind a plain [`Read`] implementation,
+//! letting the line-oriented claim extractor consume it unchanged.
+//!
+//! Each stream is buffered whole before a worker decodes it, so the splitter
+//! looks ahead at most a bounded number of bytes for the next boundary. A
+//! stream longer than that, such as the only stream of a single-stream
+//! archive, ends the parallel phase: it and everything after it are decoded
+//! sequentially as they are read.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::{self, Read},
+    num::NonZeroUsize,
+    sync::{
+        Arc, Mutex,
+        mpsc::{Receiver, SyncSender, sync_channel},
+    },
+    thread,
+};
+
+use bzip2::read::{BzDecoder, MultiBzDecoder};
+
+/// Size of each read from the compressed source.
+const READ_CHUNK_BYTES: usize = 1 << 20;
+
+/// Compressed bytes a stream may span before decoding falls back to a
+/// sequential decoder.
+pub const DEFAULT_MAX_STREAM_BYTES: usize = 16 << 20;
+
+/// Length of the stream signature: `BZh`, a block-size digit, and the
+/// 48-bit block magic `0x314159265359`.
+const STREAM_SIGNATURE_LEN: usize = 10;
+
+/// Stream segments queued per worker before the splitter blocks.
+const QUEUE_DEPTH_PER_WORKER: usize = 2;
+
+type Segment = (u64, Vec<u8>);
+type SegmentResult = (u64, io::Result<Decoded>);
+
+/// Output for one position in the decoded sequence.
+enum Decoded {
+    /// A whole stream, decoded by a worker.
+    Stream(Vec<u8>),
+    /// Everything from here on, decoded sequentially as it is read.
+    Rest(Box<dyn Read + Send>),
+}
+
+/// [`Read`] adapter that decompresses bzip2 streams on a worker pool.
+///
+/// A splitter thread scans the compressed input for stream boundaries and
+/// queues each stream for decompression. Workers decode streams concurrently,
+/// and the reader yields their output in the original order. A stream that
+/// spans more than the look-ahead limit is not buffered further: it and the
+/// rest of the input are decoded sequentially instead. Dropping the reader
+/// early disconnects the channels, which stops the background threads once
+/// their current stream completes.
+///
+/// # Examples
+/// ```
+/// use std::io::{Cursor, Read, Write};
+/// use bzip2::{Compression, write::BzEncoder};
+/// use wildside_data::wikidata::etl::ParallelBz2Reader;
+///
+/// let mut archive = Vec::new();
+/// for part in ["first\n", "second\n"] {
+///     let mut encoder = BzEncoder::new(Vec::new(), Compression::fast());
+///     encoder.write_all(part.as_bytes())?;
+///     archive.extend(encoder.finish()?);
+/// }
+///
+/// let mut text = String::new();
+/// ParallelBz2Reader::new(Cursor::new(archive)).read_to_string(&mut text)?;
+/// assert_eq!(text, "first\nsecond\n");
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct ParallelBz2Reader {
+    results: Receiver<SegmentResult>,
+    pending: BTreeMap<u64, io::Result<Decoded>>,
+    next_sequence: u64,
+    current: Box<dyn Read + Send>,
+    finished: bool,
+}
+
+impl ParallelBz2Reader {
+    /// Decompress `source` using one worker per available CPU.
+    #[must_use]
+    pub fn new<R>(source: R) -> Self
+    where
+        R: Read + Send + 'static,
+    {
+        let workers = thread::available_parallelism().unwrap_or(NonZeroUsize::MIN);
+        Self::with_workers(source, workers)
+    }
+
+    /// Decompress `source` using exactly `workers` decoder threads.
+    #[must_use]
+    pub fn with_workers<R>(source: R, workers: NonZeroUsize) -> Self
+    where
+        R: Read + Send + 'static,
+    {
+        Self::with_limits(source, workers, DEFAULT_MAX_STREAM_BYTES)
+    }
+
+    /// Decompress `source` using exactly `workers` decoder threads, decoding
+    /// sequentially from the first stream spanning more than
+    /// `max_stream_bytes` compressed bytes.
+    #[must_use]
+    pub fn with_limits<R>(source: R, workers: NonZeroUsize, max_stream_bytes: usize) -> Self
+    where
+        R: Read + Send + 'static,
+    {
+        let queue_depth = workers.get().saturating_mul(QUEUE_DEPTH_PER_WORKER);
+        let (segment_tx, segment_rx) = sync_channel::<Segment>(queue_depth);
+        let (result_tx, result_rx) = sync_channel::<SegmentResult>(queue_depth);
+        let segment_rx = Arc::new(Mutex::new(segment_rx));
+
+        for _ in 0..workers.get() {
+            let segments = Arc::clone(&segment_rx);
+            let results = result_tx.clone();
+            thread::spawn(move || decompress_segments(&segments, &results));
+        }
+        thread::spawn(move || split_streams(source, max_stream_bytes, &segment_tx, &result_tx));
+
+        Self {
+            results: result_rx,
+            pending: BTreeMap::new(),
+            next_sequence: 0,
+            current: Box::new(io::empty()),
+            finished: false,
+        }
+    }
+
+    /// Advance to the next decoded stream, waiting for workers as needed.
+    ///
+    /// Returns `Ok(false)` once every stream has been consumed.
+    fn advance(&mut self) -> io::Result<bool> {
+        loop {
+            if let Some(decoded) = self.pending.remove(&self.next_sequence) {
+                self.next_sequence += 1;
+                self.current = match decoded? {
+                    Decoded::Stream(text) => Box::new(io::Cursor::new(text)),
+                    Decoded::Rest(reader) => reader,
+                };
+                return Ok(true);
+            }
+            if self.finished {
+                return Ok(false);
+            }
+            let Ok((sequence, decoded)) = self.results.recv() else {
+                self.finished = true;
+                return self.drained();
+            };
+            self.pending.insert(sequence, decoded);
+        }
+    }
+
+    /// Report whether the workers delivered every stream before exiting.
+    fn drained(&self) -> io::Result<bool> {
+        if self.pending.is_empty() {
+            Ok(false)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "bzip2 decoder workers exited before every stream was decoded",
+            ))
+        }
+    }
+}
+
+impl fmt::Debug for ParallelBz2Reader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParallelBz2Reader")
+            .field("next_sequence", &self.next_sequence)
+            .field("pending", &self.pending.len())
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Read for ParallelBz2Reader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let read = self.current.read(buf)?;
+            if read > 0 || buf.is_empty() {
+                return Ok(read);
+            }
+            if !self.advance()? {
+                return Ok(0);
+            }
+        }
+    }
+}
+
+/// Split the compressed input into independent bzip2 streams.
+///
+/// Once the stream being gathered spans more than `max_stream_bytes`, the
+/// buffered bytes and the rest of `source` are handed on to be decoded
+/// sequentially. Read errors are forwarded as a failed segment so the reader
+/// surfaces them in order.
+fn split_streams<R>(
+    mut source: R,
+    max_stream_bytes: usize,
+    segments: &SyncSender<Segment>,
+    results: &SyncSender<SegmentResult>,
+) where
+    R: Read + Send + 'static,
+{
+    let mut buffer = Vec::new();
+    let mut chunk = vec![0_u8; READ_CHUNK_BYTES.min(max_stream_bytes).max(1)];
+    let mut sequence = 0_u64;
+    let mut scan_from = 0_usize;
+
+    loop {
+        let read = match source.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => {
+                // A closed channel means the reader was dropped; nothing to report.
+                let _ignored = results.send((sequence, Err(error)));
+                return;
+            }
+        };
+        buffer.extend_from_slice(chunk.get(..read).unwrap_or_default());
+
+        while let Some(boundary) = find_stream_start(&buffer, scan_from) {
+            let rest = buffer.split_off(boundary);
+            let segment = std::mem::replace(&mut buffer, rest);
+            if segments.send((sequence, segment)).is_err() {
+                return;
+            }
+            sequence += 1;
+            scan_from = 1;
+        }
+        if buffer.len() > max_stream_bytes {
+            let rest = MultiBzDecoder::new(io::Cursor::new(buffer).chain(source));
+            let _ignored = results.send((sequence, Ok(Decoded::Rest(Box::new(rest)))));
+            return;
+        }
+        // Re-scan the tail in case a signature straddles the next chunk.
+        scan_from = buffer.len().saturating_sub(STREAM_SIGNATURE_LEN - 1).max(1);
+    }
+
+    if !buffer.is_empty() {
+        let _ignored = segments.send((sequence, buffer));
+    }
+}
+
+/// Locate the next stream signature at or after `from`.
+///
+/// Offset zero is never reported because it marks the current stream.
+fn find_stream_start(buffer: &[u8], from: usize) -> Option<usize> {
+    let start = from.max(1);
+    buffer
+        .get(start..)?
+        .windows(STREAM_SIGNATURE_LEN)
+        .position(is_stream_signature)
+        .map(|offset| start + offset)
+}
+
+fn is_stream_signature(window: &[u8]) -> bool {
+    matches!(
+        window,
+        [
+            b'B',
+            b'Z',
+            b'h',
+            b'1'..=b'9',
+            0x31,
+            0x41,
+            0x59,
+            0x26,
+            0x53,
+            0x59
+        ]
+    )
+}
+
+fn decompress_segments(segments: &Mutex<Receiver<Segment>>, results: &SyncSender<SegmentResult>) {
+    loop {
+        let next = match segments.lock() {
+            Ok(receiver) => receiver.recv(),
+            Err(_) => return,
+        };
+        let Ok((sequence, compressed)) = next else {
+            return;
+        };
+        let mut decoded = Vec::with_capacity(compressed.len().saturating_mul(4));
+        let outcome = BzDecoder::new(compressed.as_slice())
+            .read_to_end(&mut decoded)
+            .map(|_| Decoded::Stream(decoded));
+        if results.send((sequence, outcome)).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,151 @@
+//! Unit tests for parallel bzip2 decompression.
+
+use super::*;
+use bzip2::{Compression, write::BzEncoder};
+use rstest::rstest;
+use std::io::{Cursor, Write};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Source that counts the bytes taken from it.
+struct Counting {
+    inner: Cursor<Vec<u8>>,
+    consumed: Arc<AtomicUsize>,
+}
+
+impl Read for Counting {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.consumed.fetch_add(read, Ordering::SeqCst);
+        Ok(read)
+    }
+}
+
+/// Lines of pseudo-random hex, which bzip2 cannot shrink much.
+fn incompressible(lines: usize) -> Vec<u8> {
+    let mut state = 0x2545_f491_u64;
+    (0..lines)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            format!("{state:016x}{:016x}\n", state.rotate_left(29))
+        })
+        .collect::<String>()
+        .into_bytes()
+}
+
+fn compress_streams(parts: &[&[u8]]) -> Vec<u8> {
+    parts
+        .iter()
+        .flat_map(|part| {
+            let mut encoder = BzEncoder::new(Vec::new(), Compression::fast());
+            encoder.write_all(part).expect("compress part");
+            encoder.finish().expect("finish stream")
+        })
+        .collect()
+}
+
+fn workers(count: usize) -> NonZeroUsize {
+    NonZeroUsize::new(count).expect("non-zero worker count")
+}
+
+#[rstest]
+#[case(1)]
+#[case(4)]
+fn preserves_stream_order(#[case] worker_count: usize) {
+    let parts: Vec<Vec<u8>> = (0..32)
+        .map(|index| {
+            format!("{{\"id\":\"Q{index}\"}}\n")
+                .repeat(index + 1)
+                .into_bytes()
+        })
+        .collect();
+    let part_refs: Vec<&[u8]> = parts.iter().map(Vec::as_slice).collect();
+    let archive = compress_streams(&part_refs);
+
+    let mut decoded = Vec::new();
+    ParallelBz2Reader::with_workers(Cursor::new(archive), workers(worker_count))
+        .read_to_end(&mut decoded)
+        .expect("decode archive");
+
+    assert_eq!(decoded, parts.concat());
+}
+
+#[rstest]
+fn decodes_single_stream() {
+    let archive = compress_streams(&[b"only stream\n"]);
+    let mut decoded = String::new();
+    ParallelBz2Reader::with_workers(Cursor::new(archive), workers(2))
+        .read_to_string(&mut decoded)
+        .expect("decode archive");
+    assert_eq!(decoded, "only stream\n");
+}
+
+#[rstest]
+fn single_stream_decodes_without_buffering_the_archive() {
+    let text = incompressible(8192);
+    let archive = compress_streams(&[&text]);
+    let archive_len = archive.len();
+    let consumed = Arc::new(AtomicUsize::new(0));
+    let source = Counting {
+        inner: Cursor::new(archive),
+        consumed: Arc::clone(&consumed),
+    };
+    let mut reader = ParallelBz2Reader::with_limits(source, workers(2), 4096);
+
+    let mut first_line = [0_u8; 33];
+    reader
+        .read_exact(&mut first_line)
+        .expect("decode first line");
+    let consumed_early = consumed.load(Ordering::SeqCst);
+    assert!(
+        consumed_early < archive_len / 2,
+        "read {consumed_early} of {archive_len} compressed bytes for one line"
+    );
+
+    let mut rest = Vec::new();
+    reader.read_to_end(&mut rest).expect("decode the rest");
+    assert_eq!([first_line.as_slice(), &rest].concat(), text);
+}
+
+#[rstest]
+fn long_streams_fall_back_to_sequential_decoding() {
+    let long = incompressible(1024);
+    let parts: [&[u8]; 5] = [b"first\n", b"second\n", &long, b"fourth\n", b"fifth\n"];
+    let archive = compress_streams(&parts);
+
+    let mut decoded = Vec::new();
+    ParallelBz2Reader::with_limits(Cursor::new(archive), workers(2), 4096)
+        .read_to_end(&mut decoded)
+        .expect("decode archive");
+
+    assert_eq!(decoded, parts.concat());
+}
+
+#[rstest]
+fn empty_input_yields_no_bytes() {
+    let mut decoded = Vec::new();
+    ParallelBz2Reader::with_workers(Cursor::new(Vec::new()), workers(2))
+        .read_to_end(&mut decoded)
+        .expect("empty input decodes");
+    assert!(decoded.is_empty());
+}
+
+#[rstest]
+fn reports_corrupt_streams() {
+    let mut archive = compress_streams(&[b"valid\n", b"also valid\n"]);
+    archive.truncate(archive.len() - 8);
+    let mut decoded = Vec::new();
+    let result =
+        ParallelBz2Reader::with_workers(Cursor::new(archive), workers(2)).read_to_end(&mut decoded);
+    assert!(result.is_err(), "truncated stream should fail to decode");
+}
+
+#[rstest]
+#[case(&[0; 12], None)]
+#[case(b"BZh91AY&SY..", None)]
+#[case(b"xxBZh91AY&SY", Some(2))]
+#[case(b"xxBZh01AY&SY", None)]
+fn finds_stream_boundaries(#[case] buffer: &[u8], #[case] expected: Option<usize>) {
+    assert_eq!(find_stream_start(buffer, 0), expected);
+}
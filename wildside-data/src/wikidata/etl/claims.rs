@@ -0,0 +1,147 @@
+//! Claims extracted for each linked entity.
+
+use super::ClaimRank;
+
+/// Value of a single extracted claim.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ClaimValue {
+    /// Another Wikidata entity, such as `Q9259` for a heritage designation.
+    Entity(String),
+    /// A literal rendered as text: strings, timestamps (e.g. `+1791-00-00T00:00:00Z`)
+    /// and quantity amounts keep their Wikidata encoding.
+    Literal(String),
+}
+
+/// A `(property, value)` qualifier refining a claim, such as an end time.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Qualifier {
+    /// The qualifying property identifier (e.g., `P582` for end time).
+    pub property_id: String,
+    /// The qualifier's value.
+    pub value: ClaimValue,
+}
+
+/// A generic `(property, value)` claim attached to an entity.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Claim {
+    /// The Wikidata property identifier (e.g., `P31`).
+    pub property_id: String,
+    /// The claim's main value.
+    pub value: ClaimValue,
+    /// The statement's rank; [`ClaimRank::Normal`] when the dump omits it.
+    pub rank: ClaimRank,
+    /// Qualifiers with supported values, sorted by property and value.
+    pub qualifiers: Vec<Qualifier>,
+}
+
+impl Claim {
+    /// Build an entity-valued claim.
+    #[must_use]
+    pub fn entity(property_id: impl Into<String>, value_entity_id: impl Into<String>) -> Self {
+        Self::new(property_id, ClaimValue::Entity(value_entity_id.into()))
+    }
+
+    /// Build a literal-valued claim.
+    #[must_use]
+    pub fn literal(property_id: impl Into<String>, value: impl Into<String>) -> Self {
+        Self::new(property_id, ClaimValue::Literal(value.into()))
+    }
+
+    fn new(property_id: impl Into<String>, value: ClaimValue) -> Self {
+        Self {
+            property_id: property_id.into(),
+            value,
+            rank: ClaimRank::default(),
+            qualifiers: Vec::new(),
+        }
+    }
+
+    /// Set the statement rank.
+    ///
+    /// # Examples
+    /// ```
+    /// use wildside_data::wikidata::etl::{Claim, ClaimRank};
+    ///
+    /// let claim = Claim::entity("P31", "Q33506").with_rank(ClaimRank::Preferred);
+    /// assert_eq!(claim.rank, ClaimRank::Preferred);
+    /// ```
+    #[must_use]
+    pub fn with_rank(mut self, rank: ClaimRank) -> Self {
+        self.rank = rank;
+        self
+    }
+
+    /// Attach a qualifier, keeping qualifiers sorted.
+    #[must_use]
+    pub fn with_qualifier(mut self, property_id: impl Into<String>, value: ClaimValue) -> Self {
+        self.qualifiers.push(Qualifier {
+            property_id: property_id.into(),
+            value,
+        });
+        self.qualifiers.sort_unstable();
+        self
+    }
+}
+
+/// Claims extracted for an entity referenced by one or more POIs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityClaims {
+    /// The Wikidata entity identifier (e.g., `Q64`).
+    pub entity_id: String,
+    /// POIs that reference this entity via the `wikidata` tag.
+    pub linked_poi_ids: Vec<u64>,
+    /// Claims for the selected properties, sorted and de-duplicated.
+    pub claims: Vec<Claim>,
+    /// Wikimedia Commons file names from `P18` (image) claims.
+    pub image_files: Vec<String>,
+    /// Number of sitelinks (Wikipedia and sister-project articles) for the
+    /// entity, or `None` when the dump record omits the `sitelinks` object.
+    pub sitelink_count: Option<u32>,
+}
+
+impl EntityClaims {
+    pub(super) fn new(entity_id: String, linked_poi_ids: Vec<u64>, claims: Vec<Claim>) -> Self {
+        Self {
+            entity_id,
+            linked_poi_ids,
+            claims,
+            image_files: Vec::new(),
+            sitelink_count: None,
+        }
+    }
+
+    pub(super) fn with_image_files(mut self, image_files: Vec<String>) -> Self {
+        self.image_files = image_files;
+        self
+    }
+
+    pub(super) fn with_sitelink_count(mut self, sitelink_count: Option<u32>) -> Self {
+        self.sitelink_count = sitelink_count;
+        self
+    }
+
+    /// Iterate over entity-valued targets of `property_id` claims.
+    ///
+    /// # Examples
+    /// ```
+    /// use wildside_data::wikidata::etl::{Claim, EntityClaims, HERITAGE_PROPERTY};
+    ///
+    /// let entity = EntityClaims {
+    ///     entity_id: "Q64".into(),
+    ///     linked_poi_ids: vec![7],
+    ///     claims: vec![Claim::entity(HERITAGE_PROPERTY, "Q9259")],
+    ///     image_files: Vec::new(),
+    ///     sitelink_count: None,
+    /// };
+    /// assert_eq!(entity.entity_values(HERITAGE_PROPERTY).collect::<Vec<_>>(), ["Q9259"]);
+    /// ```
+    pub fn entity_values<'a>(&'a self, property_id: &'a str) -> impl Iterator<Item = &'a str> {
+        self.claims
+            .iter()
+            .filter(move |claim| claim.property_id == property_id)
+            .filter_map(|claim| match &claim.value {
+                ClaimValue::Entity(id) => Some(id.as_str()),
+                ClaimValue::Literal(_) => None,
+            })
+    }
+}
@@ -0,0 +1,246 @@
+//! Compression detection for Wikidata dump files.
+//!
+//! Wikidata publishes both `.json.bz2` and `.json.gz` dumps, and many mirrors
+//! only serve the gzip variant. [`DumpCompression`] picks a decoder from the
+//! file name so the line-oriented extractor always sees plain JSON.
+
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::str::FromStr;
+
+use bzip2::read::MultiBzDecoder;
+use flate2::read::MultiGzDecoder;
+use thiserror::Error;
+
+use super::ParallelBz2Reader;
+
+/// Compression applied to a Wikidata dump.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpCompression {
+    /// Uncompressed JSON, such as `latest-all.json`.
+    Plain,
+    /// gzip, such as `latest-all.json.gz`.
+    Gzip,
+    /// bzip2, such as `latest-all.json.bz2`.
+    Bzip2,
+}
+
+/// How bzip2 dumps are decompressed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Decompression {
+    /// Decode independent streams on a worker pool with
+    /// [`ParallelBz2Reader`], falling back to sequential decoding for streams
+    /// longer than [`DEFAULT_MAX_STREAM_BYTES`](super::DEFAULT_MAX_STREAM_BYTES).
+    #[default]
+    Parallel,
+    /// Decode every stream on the reading thread.
+    Sequential,
+}
+
+/// Error raised when a decompression mode is neither `parallel` nor
+/// `sequential`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid decompression mode {0:?}; expected parallel or sequential")]
+pub struct DecompressionError(pub String);
+
+impl FromStr for Decompression {
+    type Err = DecompressionError;
+
+    /// Parse `parallel` or `sequential`, ignoring case.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.trim().to_ascii_lowercase().as_str() {
+            "parallel" => Ok(Self::Parallel),
+            "sequential" => Ok(Self::Sequential),
+            _ => Err(DecompressionError(input.to_owned())),
+        }
+    }
+}
+
+/// Decoder wrapped around a dump, as chosen by [`DumpCompression::decoder`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpDecoder {
+    /// No decoding.
+    Plain,
+    /// [`MultiGzDecoder`], reading every gzip member in turn.
+    Gzip,
+    /// [`MultiBzDecoder`], reading every bzip2 stream in turn.
+    Bzip2,
+    /// [`ParallelBz2Reader`], decoding bzip2 streams on a worker pool.
+    ParallelBzip2,
+}
+
+impl DumpDecoder {
+    /// Wrap `source` in this decoder.
+    pub fn wrap<R>(self, source: R) -> Box<dyn Read + Send>
+    where
+        R: Read + Send + 'static,
+    {
+        match self {
+            Self::Plain => Box::new(source),
+            Self::Gzip => Box::new(MultiGzDecoder::new(BufReader::new(source))),
+            Self::Bzip2 => Box::new(MultiBzDecoder::new(source)),
+            Self::ParallelBzip2 => Box::new(ParallelBz2Reader::new(source)),
+        }
+    }
+}
+
+impl DumpCompression {
+    /// Detect the compression from the final extension of `path`.
+    ///
+    /// `.gz`/`.gzip` and `.bz2` are matched case-insensitively; any other
+    /// extension, including `.json`, is treated as plain JSON.
+    ///
+    /// # Examples
+    /// ```
+    /// use wildside_data::wikidata::etl::DumpCompression;
+    ///
+    /// assert_eq!(DumpCompression::from_path("latest-all.json.gz"), DumpCompression::Gzip);
+    /// assert_eq!(DumpCompression::from_path("latest-all.JSON.BZ2"), DumpCompression::Bzip2);
+    /// assert_eq!(DumpCompression::from_path("latest-all.json"), DumpCompression::Plain);
+    /// ```
+    #[must_use]
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        let extension = path
+            .as_ref()
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("gz" | "gzip") => Self::Gzip,
+            Some("bz2") => Self::Bzip2,
+            _ => Self::Plain,
+        }
+    }
+
+    /// Choose the decoder for this compression.
+    ///
+    /// bzip2 dumps are decoded by [`ParallelBz2Reader`] unless
+    /// `decompression` asks for [`Decompression::Sequential`].
+    ///
+    /// # Examples
+    /// ```
+    /// use wildside_data::wikidata::etl::{Decompression, DumpCompression, DumpDecoder};
+    ///
+    /// let bzip2 = DumpCompression::from_path("latest-all.json.bz2");
+    /// assert_eq!(bzip2.decoder(Decompression::default()), DumpDecoder::ParallelBzip2);
+    /// assert_eq!(bzip2.decoder(Decompression::Sequential), DumpDecoder::Bzip2);
+    /// ```
+    #[must_use]
+    pub const fn decoder(self, decompression: Decompression) -> DumpDecoder {
+        match (self, decompression) {
+            (Self::Plain, _) => DumpDecoder::Plain,
+            (Self::Gzip, _) => DumpDecoder::Gzip,
+            (Self::Bzip2, Decompression::Parallel) => DumpDecoder::ParallelBzip2,
+            (Self::Bzip2, Decompression::Sequential) => DumpDecoder::Bzip2,
+        }
+    }
+
+    /// Wrap `source` in the matching decoder.
+    ///
+    /// gzip and bzip2 input may hold several concatenated members or
+    /// streams, as produced by parallel compressors; all of them are decoded.
+    /// bzip2 streams are decompressed on a worker pool via
+    /// [`ParallelBz2Reader`], which decodes a single-stream archive
+    /// sequentially instead.
+    pub fn decode<R>(self, source: R) -> Box<dyn Read + Send>
+    where
+        R: Read + Send + 'static,
+    {
+        self.decoder(Decompression::default()).wrap(source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for dump compression detection and decoding.
+
+    use std::io::{Cursor, Write};
+
+    use bzip2::write::BzEncoder;
+    use flate2::{Compression, write::GzEncoder};
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("dump.json", DumpCompression::Plain)]
+    #[case("dump", DumpCompression::Plain)]
+    #[case("dumpgz", DumpCompression::Plain)]
+    #[case("dump.json.gz", DumpCompression::Gzip)]
+    #[case("dump.GZ", DumpCompression::Gzip)]
+    #[case("dump.json.gzip", DumpCompression::Gzip)]
+    #[case("dump.json.bz2", DumpCompression::Bzip2)]
+    #[case("dump.BZ2", DumpCompression::Bzip2)]
+    #[case("dump-multistream.json.bz2", DumpCompression::Bzip2)]
+    #[case("dump-multistream.json.gz", DumpCompression::Gzip)]
+    fn detects_compression_from_extension(#[case] path: &str, #[case] expected: DumpCompression) {
+        assert_eq!(DumpCompression::from_path(path), expected);
+    }
+
+    #[rstest]
+    #[case(DumpCompression::Plain, Decompression::Parallel, DumpDecoder::Plain)]
+    #[case(DumpCompression::Gzip, Decompression::Parallel, DumpDecoder::Gzip)]
+    #[case(
+        DumpCompression::Bzip2,
+        Decompression::Parallel,
+        DumpDecoder::ParallelBzip2
+    )]
+    #[case(DumpCompression::Bzip2, Decompression::Sequential, DumpDecoder::Bzip2)]
+    #[case(DumpCompression::Gzip, Decompression::Sequential, DumpDecoder::Gzip)]
+    fn chooses_decoder(
+        #[case] compression: DumpCompression,
+        #[case] decompression: Decompression,
+        #[case] expected: DumpDecoder,
+    ) {
+        assert_eq!(compression.decoder(decompression), expected);
+    }
+
+    #[rstest]
+    #[case("parallel", Ok(Decompression::Parallel))]
+    #[case(" Sequential ", Ok(Decompression::Sequential))]
+    #[case("threaded", Err(DecompressionError("threaded".to_owned())))]
+    fn parses_decompression_modes(
+        #[case] input: &str,
+        #[case] expected: Result<Decompression, DecompressionError>,
+    ) {
+        assert_eq!(input.parse::<Decompression>(), expected);
+    }
+
+    #[rstest]
+    fn decodes_concatenated_gzip_members() {
+        let mut archive = Vec::new();
+        for part in ["first\n", "second\n"] {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+            encoder.write_all(part.as_bytes()).expect("compress part");
+            archive.extend(encoder.finish().expect("finish member"));
+        }
+
+        let mut text = String::new();
+        DumpCompression::Gzip
+            .decode(Cursor::new(archive))
+            .read_to_string(&mut text)
+            .expect("decode gzip");
+
+        assert_eq!(text, "first\nsecond\n");
+    }
+
+    #[rstest]
+    #[case(DumpDecoder::Bzip2)]
+    #[case(DumpDecoder::ParallelBzip2)]
+    fn decodes_concatenated_bzip2_streams(#[case] decoder: DumpDecoder) {
+        let mut archive = Vec::new();
+        for part in ["first\n", "second\n"] {
+            let mut encoder = BzEncoder::new(Vec::new(), bzip2::Compression::fast());
+            encoder.write_all(part.as_bytes()).expect("compress part");
+            archive.extend(encoder.finish().expect("finish stream"));
+        }
+
+        let mut text = String::new();
+        decoder
+            .wrap(Cursor::new(archive))
+            .read_to_string(&mut text)
+            .expect("decode bzip2");
+
+        assert_eq!(text, "first\nsecond\n");
+    }
+}
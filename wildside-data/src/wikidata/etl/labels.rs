@@ -0,0 +1,332 @@
+//! Label resolution for entities referenced by claim values.
+//!
+//! Claims such as `P1435` (heritage designation) point at other entities, so
+//! the persisted values are opaque Q-ids. This module resolves the labels of
+//! such value entities in a chosen set of languages, either from a dump or
+//! through [`WikidataApiSource::extract_entity_labels`](super::WikidataApiSource::extract_entity_labels),
+//! so reports can show names like "Grade I listed building" instead.
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    io::{BufRead, BufReader, Read},
+    str::FromStr,
+};
+
+use serde::{Deserialize, de::IgnoredAny};
+use thiserror::Error;
+
+use super::{WikidataEtlError, entity_span, normalize_wikidata_id, peek_entity_id};
+
+/// Language resolved when no explicit set is supplied.
+pub const DEFAULT_LABEL_LANGUAGE: &str = "en";
+
+/// A human-readable entity label in one language.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct EntityLabel {
+    /// The labelled entity (e.g., `Q9259`).
+    pub entity_id: String,
+    /// The Wikimedia language code of the label (e.g., `en`).
+    pub language: String,
+    /// The label text.
+    pub label: String,
+}
+
+/// Ordered set of Wikimedia language codes whose labels are resolved.
+///
+/// # Examples
+/// ```
+/// use wildside_data::wikidata::etl::LabelLanguages;
+///
+/// let languages: LabelLanguages = "en, DE,zh-hans".parse()?;
+/// assert!(languages.contains("de"));
+/// assert_eq!(languages.to_string(), "de,en,zh-hans");
+/// # Ok::<(), wildside_data::wikidata::etl::LanguageCodeError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelLanguages {
+    languages: BTreeSet<String>,
+}
+
+/// Error raised when a language code contains characters other than ASCII
+/// letters, digits, and hyphens.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid Wikimedia language code {0:?}; expected letters, digits, and hyphens")]
+pub struct LanguageCodeError(pub String);
+
+impl LabelLanguages {
+    /// Build a set from language codes, normalizing them to lowercase.
+    ///
+    /// # Errors
+    /// Returns [`LanguageCodeError`] for empty or malformed codes.
+    pub fn try_from_codes<I, S>(codes: I) -> Result<Self, LanguageCodeError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let languages = codes
+            .into_iter()
+            .map(|code| normalize_language_code(code.as_ref()))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { languages })
+    }
+
+    /// Report whether labels in `language` should be resolved.
+    #[must_use]
+    pub fn contains(&self, language: &str) -> bool {
+        self.languages.contains(language)
+    }
+
+    /// Iterate over the selected language codes in sorted order.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.languages.iter().map(String::as_str)
+    }
+
+    /// Return whether no languages are selected.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.languages.is_empty()
+    }
+}
+
+impl Default for LabelLanguages {
+    fn default() -> Self {
+        Self {
+            languages: BTreeSet::from([DEFAULT_LABEL_LANGUAGE.to_owned()]),
+        }
+    }
+}
+
+impl FromStr for LabelLanguages {
+    type Err = LanguageCodeError;
+
+    /// Parse a comma-separated list such as `en,de`.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::try_from_codes(
+            input
+                .split(',')
+                .map(str::trim)
+                .filter(|code| !code.is_empty()),
+        )
+    }
+}
+
+impl fmt::Display for LabelLanguages {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let joined: Vec<&str> = self.iter().collect();
+        f.write_str(&joined.join(","))
+    }
+}
+
+fn normalize_language_code(input: &str) -> Result<String, LanguageCodeError> {
+    let trimmed = input.trim();
+    let valid = !trimmed.is_empty()
+        && trimmed
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if valid {
+        Ok(trimmed.to_ascii_lowercase())
+    } else {
+        Err(LanguageCodeError(input.to_owned()))
+    }
+}
+
+/// Resolve labels for `entity_ids` from a Wikidata dump.
+///
+/// Only the `id` and `labels` of matching entities are parsed; every other
+/// line is skipped after peeking its id. Reading stops once each requested
+/// entity has been seen, so ids clustered early in the dump are cheap to
+/// resolve. Entities without a label in any of `languages` yield nothing.
+/// Results are sorted by entity id and language.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use wildside_data::wikidata::etl::{EntityLabel, LabelLanguages, extract_entity_labels};
+///
+/// let dump = Cursor::new(
+///     "[\n{\"id\":\"Q9259\",\"labels\":{\"en\":{\"language\":\"en\",\"value\":\"World Heritage Site\"}},\"claims\":{}}\n]\n",
+/// );
+/// let labels = extract_entity_labels(dump, &["Q9259".to_owned()], &LabelLanguages::default())?;
+///
+/// assert_eq!(
+///     labels,
+///     vec![EntityLabel {
+///         entity_id: "Q9259".into(),
+///         language: "en".into(),
+///         label: "World Heritage Site".into(),
+///     }]
+/// );
+/// # Ok::<(), wildside_data::wikidata::etl::WikidataEtlError>(())
+/// ```
+pub fn extract_entity_labels<R>(
+    reader: R,
+    entity_ids: &[String],
+    languages: &LabelLanguages,
+) -> Result<Vec<EntityLabel>, WikidataEtlError>
+where
+    R: Read,
+{
+    let mut pending: BTreeSet<&str> = entity_ids.iter().map(String::as_str).collect();
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+    let mut line_number = 0;
+    let mut labels = Vec::new();
+    while !pending.is_empty() {
+        line.clear();
+        let read =
+            reader
+                .read_until(b'\n', &mut line)
+                .map_err(|source| WikidataEtlError::ReadLine {
+                    source,
+                    line: line_number + 1,
+                })?;
+        if read == 0 {
+            break;
+        }
+        line_number += 1;
+        let Some(json) = entity_span(&line).and_then(|span| line.get_mut(span)) else {
+            continue;
+        };
+        if peek_entity_id(json).is_some_and(|id| !pending.contains(id)) {
+            continue;
+        }
+        let entity: RawEntityLabels<'_> =
+            simd_json::from_slice(json).map_err(|source| WikidataEtlError::ParseEntity {
+                source,
+                line: line_number,
+            })?;
+        if let Some(entity_id) = normalize_wikidata_id(&entity.id)
+            && pending.remove(entity_id.as_str())
+        {
+            labels.extend(entity.labels_in(entity_id, languages));
+        }
+    }
+    labels.sort_unstable();
+    Ok(labels)
+}
+
+/// Entity record reduced to its id and labels.
+#[derive(Debug, Deserialize)]
+pub(super) struct RawEntityLabels<'a> {
+    #[serde(borrow)]
+    pub(super) id: Cow<'a, str>,
+    #[serde(default, borrow)]
+    labels: BTreeMap<Cow<'a, str>, RawLabel<'a>>,
+    /// Set by `wbgetentities` for ids that do not exist; absent from dumps.
+    #[serde(default)]
+    pub(super) missing: Option<IgnoredAny>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawLabel<'a> {
+    #[serde(borrow)]
+    value: Cow<'a, str>,
+}
+
+impl RawEntityLabels<'_> {
+    /// Labels in `languages`, skipping blank values.
+    pub(super) fn labels_in<'s>(
+        &'s self,
+        entity_id: String,
+        languages: &'s LabelLanguages,
+    ) -> impl Iterator<Item = EntityLabel> + 's {
+        self.labels
+            .iter()
+            .filter(|(language, _)| languages.contains(language))
+            .filter_map(move |(language, label)| {
+                let text = label.value.trim();
+                (!text.is_empty()).then(|| EntityLabel {
+                    entity_id: entity_id.clone(),
+                    language: language.to_string(),
+                    label: text.to_owned(),
+                })
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for label language parsing and dump label extraction.
+
+    use std::io::Cursor;
+
+    use rstest::rstest;
+
+    use super::*;
+
+    const DUMP: &str = concat!(
+        "[\n",
+        r#"{"id":"Q9259","labels":{"en":{"language":"en","value":"World Heritage Site"},"de":{"language":"de","value":"Welterbe"},"fr":{"language":"fr","value":" "}},"claims":{}},"#,
+        "\n",
+        r#"{"id":"Q64","labels":{"en":{"language":"en","value":"Berlin"}},"claims":{}},"#,
+        "\n",
+        r#"{"id":"Q15700818","labels":{"en":{"language":"en","value":"Grade I listed building"}},"claims":{}}"#,
+        "\n]\n",
+    );
+
+    fn label(entity_id: &str, language: &str, text: &str) -> EntityLabel {
+        EntityLabel {
+            entity_id: entity_id.into(),
+            language: language.into(),
+            label: text.into(),
+        }
+    }
+
+    #[rstest]
+    #[case("en", "en")]
+    #[case(" DE , en ", "de,en")]
+    #[case("zh-Hans,,zh-hans", "zh-hans")]
+    #[case("", "")]
+    fn parses_language_lists(#[case] input: &str, #[case] expected: &str) {
+        let languages: LabelLanguages = input.parse().expect("valid language list");
+        assert_eq!(languages.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("en_GB")]
+    #[case("fr;de")]
+    fn rejects_malformed_language_codes(#[case] input: &str) {
+        let err = input
+            .parse::<LabelLanguages>()
+            .expect_err("malformed code should fail");
+        assert_eq!(err, LanguageCodeError(input.to_owned()));
+    }
+
+    #[rstest]
+    fn extracts_labels_in_selected_languages() {
+        let languages: LabelLanguages = "en,de,fr".parse().expect("valid languages");
+        let ids = ["Q9259".to_owned(), "Q15700818".to_owned()];
+
+        let labels =
+            extract_entity_labels(Cursor::new(DUMP), &ids, &languages).expect("labels resolve");
+
+        assert_eq!(
+            labels,
+            [
+                label("Q15700818", "en", "Grade I listed building"),
+                label("Q9259", "de", "Welterbe"),
+                label("Q9259", "en", "World Heritage Site"),
+            ]
+        );
+    }
+
+    #[rstest]
+    fn stops_reading_once_every_entity_is_seen() {
+        let dump = format!("{DUMP}{{\"id\":\"Q1\",\"labels\":\n");
+        let ids = ["Q9259".to_owned()];
+
+        let labels = extract_entity_labels(Cursor::new(dump), &ids, &LabelLanguages::default())
+            .expect("malformed trailing line is never read");
+
+        assert_eq!(labels, [label("Q9259", "en", "World Heritage Site")]);
+    }
+
+    #[rstest]
+    fn empty_id_list_reads_nothing() {
+        let labels = extract_entity_labels(Cursor::new("{"), &[], &LabelLanguages::default())
+            .expect("nothing to resolve");
+        assert!(labels.is_empty());
+    }
+}
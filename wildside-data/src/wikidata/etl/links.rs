@@ -0,0 +1,126 @@
+//! Mapping between Wikidata entities and the POIs that link to them.
+
+use std::collections::BTreeMap;
+
+use wildside_core::{PointOfInterest, store::LinkFilter};
+
+use super::normalize_wikidata_id;
+
+/// Mapping between Wikidata entity identifiers and linked POI ids.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PoiEntityLinks {
+    links: BTreeMap<String, Vec<u64>>,
+}
+
+impl PoiEntityLinks {
+    /// Build the mapping from an iterator of [`PointOfInterest`] references.
+    ///
+    /// # Examples
+    /// ```
+    /// use geo::Coord;
+    /// use wildside_core::{PointOfInterest, Tags};
+    /// use wildside_data::wikidata::etl::PoiEntityLinks;
+    ///
+    /// let poi = PointOfInterest::new(
+    ///     1,
+    ///     Coord { x: 0.0, y: 0.0 },
+    ///     Tags::from([("wikidata".into(), "Q64".into())]),
+    /// );
+    /// let links = PoiEntityLinks::from_pois([&poi]);
+    ///
+    /// assert!(links.contains("Q64"));
+    /// assert_eq!(links.linked_poi_ids("Q64"), Some(&[1][..]));
+    /// ```
+    pub fn from_pois<'a, I>(pois: I) -> Self
+    where
+        I: IntoIterator<Item = &'a PointOfInterest>,
+    {
+        let mut links: BTreeMap<String, Vec<u64>> = BTreeMap::new();
+        for poi in pois {
+            if let Some(raw) = poi.tags.get("wikidata")
+                && let Some(entity_id) = normalize_wikidata_id(raw)
+            {
+                links.entry(entity_id).or_default().push(poi.id);
+            }
+        }
+        for poi_ids in links.values_mut() {
+            poi_ids.sort_unstable();
+            poi_ids.dedup();
+        }
+        Self { links }
+    }
+
+    /// Report whether the mapping contains the provided entity identifier.
+    #[must_use]
+    pub fn contains(&self, entity_id: &str) -> bool {
+        self.links.contains_key(entity_id)
+    }
+
+    /// Retrieve the POI identifiers linked to the entity.
+    #[must_use]
+    pub fn linked_poi_ids(&self, entity_id: &str) -> Option<&[u64]> {
+        self.links.get(entity_id).map(Vec::as_slice)
+    }
+
+    /// Iterate over the linked entity identifiers in sorted order.
+    pub fn entity_ids(&self) -> impl Iterator<Item = &str> {
+        self.links.keys().map(String::as_str)
+    }
+
+    /// Keep only the entities in `entity_ids`, with their linked POIs.
+    ///
+    /// # Examples
+    /// ```
+    /// use geo::Coord;
+    /// use wildside_core::{PointOfInterest, Tags};
+    /// use wildside_data::wikidata::etl::PoiEntityLinks;
+    ///
+    /// let poi = |id, entity: &str| {
+    ///     PointOfInterest::new(
+    ///         id,
+    ///         Coord { x: 0.0, y: 0.0 },
+    ///         Tags::from([("wikidata".into(), entity.into())]),
+    ///     )
+    /// };
+    /// let links = PoiEntityLinks::from_pois([&poi(1, "Q64"), &poi(2, "Q90")]);
+    /// let subset = links.restricted_to(["Q90", "Q1"]);
+    ///
+    /// assert_eq!(subset.entity_ids().collect::<Vec<_>>(), ["Q90"]);
+    /// assert_eq!(subset.linked_poi_ids("Q90"), Some(&[2][..]));
+    /// ```
+    #[must_use]
+    pub fn restricted_to<'a, I>(&self, entity_ids: I) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let links = entity_ids
+            .into_iter()
+            .filter_map(|entity_id| {
+                self.links
+                    .get_key_value(entity_id)
+                    .map(|(key, poi_ids)| (key.clone(), poi_ids.clone()))
+            })
+            .collect();
+        Self { links }
+    }
+
+    /// Return whether the mapping is empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+
+    /// Build a [`LinkFilter`] recording every entity and POI in the mapping.
+    #[must_use]
+    pub fn to_link_filter(&self) -> LinkFilter {
+        let poi_count: usize = self.links.values().map(Vec::len).sum();
+        let mut filter = LinkFilter::with_capacity(self.links.len() + poi_count);
+        for (entity_id, poi_ids) in &self.links {
+            filter.insert_entity(entity_id);
+            for poi_id in poi_ids {
+                filter.insert_poi(*poi_id);
+            }
+        }
+        filter
+    }
+}
@@ -1,110 +1,64 @@
 //! Wikidata entity extraction from dump files.
 //!
 //! Streams the JSON dump, filters for entities linked from previously ingested
-//! OpenStreetMap POIs, and extracts claims that will later populate the local
-//! semantic store. The parser is deliberately incremental: it avoids loading the
-//! full dump into memory and only yields entities referenced by the OSM ingest
-//! report.
+//! OpenStreetMap POIs, and extracts claims for a configurable set of properties
+//! that will later populate the local semantic store. Statement ranks and
+//! qualifiers are parsed alongside each value, and a [`RankFilter`] can limit
+//! extraction to truthy statements. The parser is
+//! deliberately incremental: it avoids loading the full dump into memory and
+//! only yields entities referenced by the OSM ingest report.
 #![forbid(unsafe_code)]
 
-use std::{
-    collections::BTreeMap,
-    io::{BufRead, Read},
-};
+use std::{io::Read, ops::Range};
 
-use serde::Deserialize;
 use thiserror::Error;
-use wildside_core::PointOfInterest;
-
-pub(crate) const HERITAGE_PROPERTY: &str = "P1435";
-
-/// Mapping between Wikidata entity identifiers and linked POI ids.
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
-pub struct PoiEntityLinks {
-    links: BTreeMap<String, Vec<u64>>,
-}
-
-impl PoiEntityLinks {
-    /// Build the mapping from an iterator of [`PointOfInterest`] references.
-    ///
-    /// # Examples
-    /// ```
-    /// use geo::Coord;
-    /// use wildside_core::{PointOfInterest, Tags};
-    /// use wildside_data::wikidata::etl::PoiEntityLinks;
-    ///
-    /// let poi = PointOfInterest::new(
-    ///     1,
-    ///     Coord { x: 0.0, y: 0.0 },
-    ///     Tags::from([("wikidata".into(), "Q64".into())]),
-    /// );
-    /// let links = PoiEntityLinks::from_pois([&poi]);
-    ///
-    /// assert!(links.contains("Q64"));
-    /// assert_eq!(links.linked_poi_ids("Q64"), Some(&[1][..]));
-    /// ```
-    pub fn from_pois<'a, I>(pois: I) -> Self
-    where
-        I: IntoIterator<Item = &'a PointOfInterest>,
-    {
-        let mut links: BTreeMap<String, Vec<u64>> = BTreeMap::new();
-        for poi in pois {
-            if let Some(raw) = poi.tags.get("wikidata")
-                && let Some(entity_id) = normalize_wikidata_id(raw)
-            {
-                links.entry(entity_id).or_default().push(poi.id);
-            }
-        }
-        for poi_ids in links.values_mut() {
-            poi_ids.sort_unstable();
-            poi_ids.dedup();
-        }
-        Self { links }
-    }
-
-    /// Report whether the mapping contains the provided entity identifier.
-    #[must_use]
-    pub fn contains(&self, entity_id: &str) -> bool {
-        self.links.contains_key(entity_id)
-    }
-
-    /// Retrieve the POI identifiers linked to the entity.
-    #[must_use]
-    pub fn linked_poi_ids(&self, entity_id: &str) -> Option<&[u64]> {
-        self.links.get(entity_id).map(Vec::as_slice)
-    }
-
-    /// Return whether the mapping is empty.
-    #[must_use]
-    pub fn is_empty(&self) -> bool {
-        self.links.is_empty()
-    }
-}
-
-/// Claims extracted for an entity referenced by one or more POIs.
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct EntityClaims {
-    /// The Wikidata entity identifier (e.g., `Q64`).
-    pub entity_id: String,
-    /// POIs that reference this entity via the `wikidata` tag.
-    pub linked_poi_ids: Vec<u64>,
-    /// Heritage designation entity identifiers (`P1435` claim targets).
-    pub heritage_designations: Vec<String>,
-}
+use wildside_core::store::LinkFilter;
+
+mod api;
+mod bz2;
+mod claims;
+mod compression;
+mod labels;
+mod links;
+mod parallel;
+mod properties;
+mod rank;
+mod raw;
+mod revisions;
+mod sparql;
+mod stream;
+
+pub use api::{
+    DEFAULT_API_URL, EntityTransport, HttpEntityTransport, MAX_ENTITY_BATCH, WikidataApiError,
+    WikidataApiSource,
+};
+pub use bz2::{DEFAULT_MAX_STREAM_BYTES, ParallelBz2Reader};
+pub use claims::{Claim, ClaimValue, EntityClaims, Qualifier};
+pub use compression::{Decompression, DecompressionError, DumpCompression, DumpDecoder};
+pub use labels::{
+    DEFAULT_LABEL_LANGUAGE, EntityLabel, LabelLanguages, LanguageCodeError, extract_entity_labels,
+};
+pub use links::PoiEntityLinks;
+pub use parallel::ParallelLinkedEntityClaims;
+pub use properties::{
+    ARCHITECTURAL_STYLE_PROPERTY, ClaimPropertySet, DEFAULT_CLAIM_PROPERTIES, INCEPTION_PROPERTY,
+    INSTANCE_OF_PROPERTY, PropertyIdError,
+};
+pub use rank::{ClaimRank, RankFilter};
+use raw::RawEntity;
+use raw::non_empty;
+pub use revisions::{
+    EntityChanges, HttpRevisionTransport, RevisionTransport, WikidataRevisionSource,
+};
+pub use sparql::{
+    DEFAULT_SPARQL_BATCH, DEFAULT_SPARQL_PAGE_SIZE, DEFAULT_SPARQL_URL, HttpSparqlTransport,
+    RetryPolicy, SparqlTransport, WikidataSparqlError, WikidataSparqlSource,
+};
+pub use stream::{LinkedEntityClaims, stream_linked_entity_claims};
 
-impl EntityClaims {
-    fn new(
-        entity_id: String,
-        linked_poi_ids: Vec<u64>,
-        heritage_designations: Vec<String>,
-    ) -> Self {
-        Self {
-            entity_id,
-            linked_poi_ids,
-            heritage_designations,
-        }
-    }
-}
+/// `P1435` (heritage designation).
+pub const HERITAGE_PROPERTY: &str = "P1435";
+pub(crate) const IMAGE_PROPERTY: &str = "P18";
 
 /// Errors that can occur while extracting claims from a Wikidata dump.
 #[derive(Debug, Error)]
@@ -127,8 +81,9 @@ pub enum WikidataEtlError {
 ///
 /// The function streams through the dump, ignoring unrelated entities and only
 /// returning records that correspond to `wikidata` tags discovered during OSM
-/// ingestion. Currently only the heritage designation claim (`P1435`) is
-/// captured, but the structure leaves space for additional properties.
+/// ingestion. Claims for [`ClaimPropertySet::default`], image file names
+/// (`P18`) and sitelink counts are captured; use [`extract_linked_entity_claims_for`] to choose
+/// the properties.
 ///
 /// # Examples
 /// ```
@@ -147,7 +102,7 @@ pub enum WikidataEtlError {
 /// let claims = extract_linked_entity_claims(dump, &links)?;
 ///
 /// assert_eq!(claims[0].entity_id, "Q64");
-/// assert_eq!(claims[0].heritage_designations, vec!["Q9259"]);
+/// assert_eq!(claims[0].entity_values("P1435").collect::<Vec<_>>(), ["Q9259"]);
 /// # Ok::<(), wildside_data::wikidata::etl::WikidataEtlError>(())
 /// ```
 pub fn extract_linked_entity_claims<R>(
@@ -157,106 +112,229 @@ pub fn extract_linked_entity_claims<R>(
 where
     R: Read,
 {
-    if links.is_empty() {
-        return Ok(Vec::new());
-    }
-
-    let mut buffered = std::io::BufReader::new(reader);
-    let mut line = String::new();
-    let mut line_number = 0usize;
-    let mut extracted = Vec::new();
-    let mut parse_buf = Vec::new();
+    extract_linked_entity_claims_with_filter(reader, links, &links.to_link_filter())
+}
 
-    loop {
-        line.clear();
-        match buffered.read_line(&mut line) {
-            Ok(0) => break,
-            Ok(_) => {
-                line_number += 1;
-            }
-            Err(source) => {
-                return Err(WikidataEtlError::ReadLine {
-                    source,
-                    line: line_number + 1,
-                });
-            }
-        }
+/// Extract claims, using `filter` to skip entities before JSON parsing.
+///
+/// Each line's top-level `id` is peeked from the raw text; entities the filter
+/// rules out are discarded without a full parse. Lines whose id cannot be
+/// peeked cheaply are parsed as usual, so the filter only ever saves work.
+/// The filter may be loaded from a previous ingest's `links.filter` artefact
+/// or derived via [`PoiEntityLinks::to_link_filter`].
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use geo::Coord;
+/// use wildside_core::{PointOfInterest, Tags};
+/// use wildside_data::wikidata::etl::{
+///     PoiEntityLinks, extract_linked_entity_claims_with_filter,
+/// };
+///
+/// let poi = PointOfInterest::new(
+///     1,
+///     Coord { x: 13.4, y: 52.5 },
+///     Tags::from([("wikidata".into(), "Q64".into())]),
+/// );
+/// let links = PoiEntityLinks::from_pois([&poi]);
+/// let filter = links.to_link_filter();
+/// let dump = Cursor::new("{\"id\":\"Q1\",\"claims\":{}}\n{\"id\":\"Q64\",\"claims\":{}}\n");
+/// let claims = extract_linked_entity_claims_with_filter(dump, &links, &filter)?;
+///
+/// assert_eq!(claims.len(), 1);
+/// assert_eq!(claims[0].entity_id, "Q64");
+/// # Ok::<(), wildside_data::wikidata::etl::WikidataEtlError>(())
+/// ```
+pub fn extract_linked_entity_claims_with_filter<R>(
+    reader: R,
+    links: &PoiEntityLinks,
+    filter: &LinkFilter,
+) -> Result<Vec<EntityClaims>, WikidataEtlError>
+where
+    R: Read,
+{
+    let properties = ClaimPropertySet::default();
+    extract_linked_entity_claims_for(reader, links, filter, ClaimSelection::new(&properties))
+}
 
-        let Some(preprocessed) = preprocess_json_line(&line) else {
-            continue;
-        };
+/// Which claims an extraction keeps: the properties and the statement ranks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClaimSelection<'a> {
+    /// Properties whose claims are extracted.
+    pub properties: &'a ClaimPropertySet,
+    /// Statements kept for each selected property.
+    pub ranks: RankFilter,
+}
 
-        if let Some(claims) =
-            process_entity_claims(preprocessed, links, line_number, &mut parse_buf)?
-        {
-            extracted.push(claims);
+impl<'a> ClaimSelection<'a> {
+    /// Select `properties`, keeping statements of every rank.
+    #[must_use]
+    pub const fn new(properties: &'a ClaimPropertySet) -> Self {
+        Self {
+            properties,
+            ranks: RankFilter::All,
         }
     }
 
+    /// Replace the rank filter.
+    #[must_use]
+    pub const fn with_ranks(mut self, ranks: RankFilter) -> Self {
+        self.ranks = ranks;
+        self
+    }
+}
+
+/// Extract the claims chosen by `selection`, pre-filtering with `filter`.
+///
+/// Every selected property yields generic `(property, value)` pairs in
+/// [`EntityClaims::claims`]: entity targets become [`ClaimValue::Entity`] and
+/// strings, times, and quantities become [`ClaimValue::Literal`]. The
+/// selection's [`RankFilter`] decides which statements of each property are
+/// kept; it applies to `P18` image file names as well.
+/// Results are sorted by entity id; use [`stream_linked_entity_claims`] to
+/// consume entities in dump order without collecting them.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use geo::Coord;
+/// use wildside_core::{PointOfInterest, Tags};
+/// use wildside_data::wikidata::etl::{
+///     Claim, ClaimPropertySet, ClaimSelection, PoiEntityLinks, RankFilter,
+///     extract_linked_entity_claims_for,
+/// };
+///
+/// let poi = PointOfInterest::new(
+///     1,
+///     Coord { x: 13.4, y: 52.5 },
+///     Tags::from([("wikidata".into(), "Q64".into())]),
+/// );
+/// let links = PoiEntityLinks::from_pois([&poi]);
+/// let properties: ClaimPropertySet = "P31".parse()?;
+/// let dump = Cursor::new(r#"{"id":"Q64","claims":{"P31":[{"mainsnak":{"snaktype":"value","datavalue":{"type":"wikibase-entityid","value":{"id":"Q515"}}}}]}}"#);
+/// let selection = ClaimSelection::new(&properties).with_ranks(RankFilter::Truthy);
+/// let claims = extract_linked_entity_claims_for(dump, &links, &links.to_link_filter(), selection)?;
+///
+/// assert_eq!(claims[0].claims, vec![Claim::entity("P31", "Q515")]);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn extract_linked_entity_claims_for<R>(
+    reader: R,
+    links: &PoiEntityLinks,
+    filter: &LinkFilter,
+    selection: ClaimSelection<'_>,
+) -> Result<Vec<EntityClaims>, WikidataEtlError>
+where
+    R: Read,
+{
+    let mut extracted = stream_linked_entity_claims(reader, links, filter, selection)
+        .collect::<Result<Vec<_>, _>>()?;
     extracted.sort_unstable_by(|a, b| a.entity_id.cmp(&b.entity_id));
     Ok(extracted)
 }
 
-fn preprocess_json_line(line: &str) -> Option<&str> {
-    let trimmed = line.trim();
-    if is_structural_line(trimmed) {
-        return None;
-    }
-    let trimmed = trimmed.trim_start_matches(',').trim();
-    let trimmed = if trimmed.ends_with(',') {
-        let without_comma = trimmed.strip_suffix(',').expect(
-            "preprocess_json_line: expected trailing comma after entity object when \
-                 trimmed.ends_with(',') was true",
-        );
-        without_comma.trim()
-    } else {
-        trimmed
-    };
-    if trimmed.is_empty() || is_structural_line(trimmed) {
-        None
-    } else {
-        Some(trimmed)
-    }
+/// Per-line inputs shared by entity processing.
+struct EntityContext<'a> {
+    links: &'a PoiEntityLinks,
+    selection: ClaimSelection<'a>,
+    line_number: usize,
+}
+
+/// Locate the entity object on a dump line.
+///
+/// Dumps wrap one entity per line in a JSON array, so lines carry leading or
+/// trailing commas and the array brackets sit on lines of their own. Returns
+/// the byte range of the object, or `None` for purely structural lines.
+fn entity_span(line: &[u8]) -> Option<Range<usize>> {
+    let start = line
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace() && *byte != b',')?;
+    let body = line.get(start..)?.trim_ascii_end();
+    let body = body.strip_suffix(b",").map_or(body, <[u8]>::trim_ascii_end);
+    (!is_structural_line(body)).then(|| start..start + body.len())
+}
+
+fn is_filtered_out(json_slice: &[u8], filter: &LinkFilter) -> bool {
+    peek_entity_id(json_slice)
+        .and_then(normalize_wikidata_id)
+        .is_some_and(|entity_id| !filter.may_contain_entity(&entity_id))
+}
+
+/// Read the top-level `id` value without parsing the whole entity.
+///
+/// Only the flat prefix before the first nested object or array is searched,
+/// so ids nested inside claims can never be mistaken for the entity id. Dumps
+/// emit `id` near the start of each entity, which keeps the scan short.
+fn peek_entity_id(json_slice: &[u8]) -> Option<&str> {
+    let body = json_slice.strip_prefix(b"{")?;
+    let flat_end = body
+        .iter()
+        .position(|byte| matches!(byte, b'{' | b'['))
+        .unwrap_or(body.len());
+    let flat = body.get(..flat_end)?;
+    let key = flat.windows(4).position(|window| window == b"\"id\"")?;
+    let after_colon = flat
+        .get(key + 4..)?
+        .trim_ascii_start()
+        .strip_prefix(b":")?
+        .trim_ascii_start();
+    let value = after_colon.strip_prefix(b"\"")?;
+    let end = value.iter().position(|byte| matches!(byte, b'"' | b'\\'))?;
+    std::str::from_utf8(value.get(..end)?).ok()
 }
 
-fn is_structural_line(line: &str) -> bool {
-    line.is_empty() || line == "[" || line == "]"
+fn is_structural_line(line: &[u8]) -> bool {
+    matches!(line, b"" | b"[" | b"]")
 }
 
+/// Parse one entity in place and extract its claims when it is linked.
+///
+/// `simd_json` rewrites `json_slice` while parsing, and strings without escape
+/// sequences are borrowed from it rather than copied.
 fn process_entity_claims(
-    json_slice: &str,
-    links: &PoiEntityLinks,
-    line_number: usize,
-    parse_buf: &mut Vec<u8>,
+    json_slice: &mut [u8],
+    context: &EntityContext<'_>,
 ) -> Result<Option<EntityClaims>, WikidataEtlError> {
-    parse_buf.clear();
-    parse_buf.extend_from_slice(json_slice.as_bytes());
-    let entity: RawEntity = simd_json::from_slice(parse_buf.as_mut_slice()).map_err(|source| {
-        WikidataEtlError::ParseEntity {
+    let entity: RawEntity<'_> =
+        simd_json::from_slice(json_slice).map_err(|source| WikidataEtlError::ParseEntity {
             source,
-            line: line_number,
-        }
-    })?;
-    let Some(normalized_id) = normalize_wikidata_id(&entity.id) else {
-        return Ok(None);
-    };
+            line: context.line_number,
+        })?;
+    Ok(linked_entity_claims(
+        &entity,
+        context.links,
+        context.selection,
+    ))
+}
+
+/// Convert a parsed entity into [`EntityClaims`] when `links` references it.
+fn linked_entity_claims(
+    entity: &RawEntity<'_>,
+    links: &PoiEntityLinks,
+    selection: ClaimSelection<'_>,
+) -> Option<EntityClaims> {
+    let normalized_id = normalize_wikidata_id(&entity.id)?;
     if !links.contains(&normalized_id) {
-        return Ok(None);
+        return None;
     }
-    let mut heritage_designations = entity.heritage_designations();
-    heritage_designations.sort_unstable();
-    heritage_designations.dedup();
+    let mut claims = entity.selected_claims(selection);
+    claims.sort_unstable();
+    claims.dedup();
+    let mut image_files = entity.image_files(selection.ranks);
+    image_files.sort_unstable();
+    image_files.dedup();
 
     let linked_poi_ids = links
         .linked_poi_ids(&normalized_id)
         .map(|ids| ids.to_vec())
         .unwrap_or_default();
 
-    Ok(Some(EntityClaims::new(
-        normalized_id,
-        linked_poi_ids,
-        heritage_designations,
-    )))
+    Some(
+        EntityClaims::new(normalized_id, linked_poi_ids, claims)
+            .with_image_files(image_files)
+            .with_sitelink_count(entity.sitelink_count()),
+    )
 }
 
 fn normalize_wikidata_id(input: &str) -> Option<String> {
@@ -282,77 +360,5 @@ fn normalize_wikidata_id(input: &str) -> Option<String> {
     Some(format!("Q{digits}"))
 }
 
-#[derive(Debug, Deserialize)]
-struct RawEntity {
-    id: String,
-    #[serde(default)]
-    claims: BTreeMap<String, Vec<RawClaim>>,
-}
-
-impl RawEntity {
-    fn heritage_designations(&self) -> Vec<String> {
-        self.claims
-            .get(HERITAGE_PROPERTY)
-            .into_iter()
-            .flatten()
-            .filter_map(RawClaim::heritage_target)
-            .collect()
-    }
-}
-
-#[derive(Debug, Deserialize)]
-struct RawClaim {
-    #[serde(rename = "mainsnak")]
-    main_snak: RawSnak,
-}
-
-impl RawClaim {
-    fn heritage_target(&self) -> Option<String> {
-        self.main_snak.entity_target()
-    }
-}
-
-#[derive(Debug, Deserialize)]
-struct RawSnak {
-    #[serde(rename = "snaktype")]
-    snak_type: RawSnakType,
-    #[serde(rename = "datavalue")]
-    data_value: Option<RawDataValue>,
-}
-
-impl RawSnak {
-    fn entity_target(&self) -> Option<String> {
-        if self.snak_type != RawSnakType::Value {
-            return None;
-        }
-        let RawDataValue::Entity { value } = self.data_value.as_ref()? else {
-            return None;
-        };
-        normalize_wikidata_id(&value.id)
-    }
-}
-
-#[derive(Debug, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
-enum RawSnakType {
-    Value,
-    Somevalue,
-    Novalue,
-}
-
-#[derive(Debug, Deserialize)]
-#[serde(tag = "type")]
-enum RawDataValue {
-    #[serde(rename = "wikibase-entityid")]
-    Entity { value: RawEntityId },
-    #[serde(other)]
-    Unsupported,
-}
-
-#[derive(Debug, Deserialize)]
-struct RawEntityId {
-    id: String,
-}
-
 #[cfg(test)]
 mod tests;
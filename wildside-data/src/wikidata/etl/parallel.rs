@@ -0,0 +1,465 @@
+//! Parse dump lines on a pool of worker threads.
+//!
+//! [`ParallelLinkedEntityClaims`] yields the same entities as
+//! [`LinkedEntityClaims`](super::LinkedEntityClaims), in the same order. A
+//! reader thread cuts the dump into numbered batches of lines and queues them
+//! on a channel shared by the workers. Each worker parses its batch and sends
+//! the claims back, and the iterator reassembles the batches in order. Paired
+//! with [`ParallelBz2Reader`](super::ParallelBz2Reader), neither
+//! decompression nor parsing is confined to one core.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::{self, BufRead},
+    num::NonZeroUsize,
+    sync::{
+        Arc, Mutex,
+        mpsc::{Receiver, SyncSender, sync_channel},
+    },
+    thread, vec,
+};
+
+use wildside_core::store::LinkFilter;
+
+use super::{
+    ClaimPropertySet, ClaimSelection, EntityClaims, EntityContext, PoiEntityLinks, RankFilter,
+    WikidataEtlError, entity_span, is_filtered_out, process_entity_claims,
+};
+
+/// Dump lines handed to a worker at once.
+const BATCH_LINES: usize = 256;
+
+/// Batches queued per worker before the reader blocks.
+const QUEUE_DEPTH_PER_WORKER: usize = 2;
+
+/// Consecutive dump lines, the first of them numbered `first_line`.
+struct Batch {
+    sequence: u64,
+    first_line: usize,
+    lines: Vec<Vec<u8>>,
+}
+
+/// Claims parsed from one batch, and the error that cut it short, if any.
+struct Parsed {
+    claims: Vec<EntityClaims>,
+    error: Option<WikidataEtlError>,
+}
+
+impl Parsed {
+    const fn failed(error: WikidataEtlError) -> Self {
+        Self {
+            claims: Vec::new(),
+            error: Some(error),
+        }
+    }
+}
+
+type ParsedBatch = (u64, Parsed);
+
+/// What every worker needs to parse a batch, owned so it can be shared
+/// across threads.
+pub(super) struct Extraction {
+    pub(super) links: PoiEntityLinks,
+    pub(super) filter: LinkFilter,
+    pub(super) properties: ClaimPropertySet,
+    pub(super) ranks: RankFilter,
+}
+
+impl Extraction {
+    /// Parse `batch`, stopping at the first malformed entity.
+    fn parse(&self, batch: Batch) -> Parsed {
+        let selection = ClaimSelection::new(&self.properties).with_ranks(self.ranks);
+        let mut claims = Vec::new();
+        for (line_number, mut line) in (batch.first_line..).zip(batch.lines) {
+            let Some(json) = entity_span(&line).and_then(|span| line.get_mut(span)) else {
+                continue;
+            };
+            if is_filtered_out(json, &self.filter) {
+                continue;
+            }
+            let context = EntityContext {
+                links: &self.links,
+                selection,
+                line_number,
+            };
+            match process_entity_claims(json, &context) {
+                Ok(entity) => claims.extend(entity),
+                Err(error) => {
+                    return Parsed {
+                        claims,
+                        error: Some(error),
+                    };
+                }
+            }
+        }
+        Parsed {
+            claims,
+            error: None,
+        }
+    }
+}
+
+/// Iterator over the claims of linked entities, parsed on a worker pool.
+///
+/// Built by [`LinkedEntityClaims::parallel`](super::LinkedEntityClaims::parallel).
+/// Entities are yielded in dump order. The iterator stops after the first
+/// error, which it yields once, after the entities that precede it. Dropping
+/// the iterator early disconnects the channels, which stops the background
+/// threads once their current batch completes.
+pub struct ParallelLinkedEntityClaims {
+    results: Option<Receiver<ParsedBatch>>,
+    pending: BTreeMap<u64, Parsed>,
+    next_sequence: u64,
+    current: vec::IntoIter<EntityClaims>,
+    error: Option<WikidataEtlError>,
+}
+
+impl ParallelLinkedEntityClaims {
+    /// An iterator that yields nothing, for dumps with no linked entities.
+    pub(super) fn empty() -> Self {
+        Self {
+            results: None,
+            pending: BTreeMap::new(),
+            next_sequence: 0,
+            current: Vec::new().into_iter(),
+            error: None,
+        }
+    }
+
+    /// Parse the lines of `reader` after `line_number` on `workers` threads.
+    pub(super) fn spawn<R>(
+        reader: R,
+        extraction: Extraction,
+        line_number: usize,
+        workers: NonZeroUsize,
+    ) -> Self
+    where
+        R: BufRead + Send + 'static,
+    {
+        let queue_depth = workers.get().saturating_mul(QUEUE_DEPTH_PER_WORKER);
+        let (batch_tx, batch_rx) = sync_channel::<Batch>(queue_depth);
+        let (result_tx, result_rx) = sync_channel::<ParsedBatch>(queue_depth);
+        let batch_rx = Arc::new(Mutex::new(batch_rx));
+        let extraction = Arc::new(extraction);
+
+        for _ in 0..workers.get() {
+            let batches = Arc::clone(&batch_rx);
+            let results = result_tx.clone();
+            let shared = Arc::clone(&extraction);
+            thread::spawn(move || parse_batches(&shared, &batches, &results));
+        }
+        thread::spawn(move || read_batches(reader, line_number, &batch_tx, &result_tx));
+
+        Self {
+            results: Some(result_rx),
+            ..Self::empty()
+        }
+    }
+
+    /// Take the next batch in dump order, waiting for workers as needed.
+    ///
+    /// Returns `None` once every batch has been consumed.
+    fn next_batch(&mut self) -> Option<Parsed> {
+        loop {
+            if let Some(parsed) = self.pending.remove(&self.next_sequence) {
+                self.next_sequence += 1;
+                return Some(parsed);
+            }
+            let results = self.results.as_ref()?;
+            let Ok((sequence, parsed)) = results.recv() else {
+                self.results = None;
+                return self.drained();
+            };
+            self.pending.insert(sequence, parsed);
+        }
+    }
+
+    /// Report whether the workers delivered every batch before exiting.
+    fn drained(&self) -> Option<Parsed> {
+        (!self.pending.is_empty()).then(|| {
+            Parsed::failed(WikidataEtlError::ReadLine {
+                source: io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "claim parser workers exited before every line was parsed",
+                ),
+                line: 0,
+            })
+        })
+    }
+}
+
+impl fmt::Debug for ParallelLinkedEntityClaims {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParallelLinkedEntityClaims")
+            .field("next_sequence", &self.next_sequence)
+            .field("pending", &self.pending.len())
+            .field("finished", &self.results.is_none())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Iterator for ParallelLinkedEntityClaims {
+    type Item = Result<EntityClaims, WikidataEtlError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(claims) = self.current.next() {
+                return Some(Ok(claims));
+            }
+            if let Some(error) = self.error.take() {
+                self.results = None;
+                self.pending.clear();
+                return Some(Err(error));
+            }
+            let parsed = self.next_batch()?;
+            self.current = parsed.claims.into_iter();
+            self.error = parsed.error;
+        }
+    }
+}
+
+/// Cut `reader` into batches of lines, numbering them from `line_number + 1`.
+///
+/// A read error is forwarded as a failed batch so the iterator surfaces it
+/// after every line read before it.
+fn read_batches<R: BufRead>(
+    mut reader: R,
+    mut line_number: usize,
+    batches: &SyncSender<Batch>,
+    results: &SyncSender<ParsedBatch>,
+) {
+    for sequence in 0_u64.. {
+        let first_line = line_number + 1;
+        let (lines, failure) = read_batch(&mut reader, &mut line_number);
+        let exhausted = lines.len() < BATCH_LINES;
+        if let Some(error) = failure {
+            // A closed channel means the iterator was dropped; nothing to report.
+            let _ignored = batches.send(Batch {
+                sequence,
+                first_line,
+                lines,
+            });
+            let _ignored = results.send((sequence + 1, Parsed::failed(error)));
+            return;
+        }
+        let batch = Batch {
+            sequence,
+            first_line,
+            lines,
+        };
+        if batches.send(batch).is_err() || exhausted {
+            return;
+        }
+    }
+}
+
+/// Read up to [`BATCH_LINES`] lines, stopping early at end of input or on
+/// the first read error.
+fn read_batch<R: BufRead>(
+    reader: &mut R,
+    line_number: &mut usize,
+) -> (Vec<Vec<u8>>, Option<WikidataEtlError>) {
+    let mut lines = Vec::with_capacity(BATCH_LINES);
+    while lines.len() < BATCH_LINES {
+        let mut line = Vec::new();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => break,
+            Ok(_) => {
+                *line_number += 1;
+                lines.push(line);
+            }
+            Err(source) => {
+                let error = WikidataEtlError::ReadLine {
+                    source,
+                    line: *line_number + 1,
+                };
+                return (lines, Some(error));
+            }
+        }
+    }
+    (lines, None)
+}
+
+fn parse_batches(
+    extraction: &Extraction,
+    batches: &Mutex<Receiver<Batch>>,
+    results: &SyncSender<ParsedBatch>,
+) {
+    loop {
+        let next = match batches.lock() {
+            Ok(receiver) => receiver.recv(),
+            Err(_) => return,
+        };
+        let Ok(batch) = next else {
+            return;
+        };
+        let sequence = batch.sequence;
+        if results.send((sequence, extraction.parse(batch))).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for parsing dump lines on a worker pool.
+
+    use std::io::{self, Cursor, Read};
+
+    use geo::Coord;
+    use rstest::rstest;
+    use wildside_core::{PointOfInterest, Tags};
+
+    use super::*;
+    use crate::wikidata::etl::stream_linked_entity_claims;
+
+    fn links(entity_ids: &[String]) -> PoiEntityLinks {
+        let pois: Vec<_> = (1_u64..)
+            .zip(entity_ids)
+            .map(|(id, entity)| {
+                PointOfInterest::new(
+                    id,
+                    Coord { x: 0.0, y: 0.0 },
+                    Tags::from([("wikidata".into(), entity.clone())]),
+                )
+            })
+            .collect();
+        PoiEntityLinks::from_pois(pois.iter())
+    }
+
+    fn workers(count: usize) -> NonZeroUsize {
+        NonZeroUsize::new(count).expect("worker count is positive")
+    }
+
+    /// A dump spanning several batches, linking every third entity.
+    fn dump(entities: usize) -> (String, Vec<String>) {
+        let mut text = String::from("[\n");
+        let mut linked = Vec::new();
+        for index in 0..entities {
+            let id = format!("Q{}", index + 1);
+            text.push_str(&format!(
+                "{{\"id\":\"{id}\",\"claims\":{{\"P31\":[{{\"mainsnak\":{{\"snaktype\":\"value\",\"datavalue\":{{\"type\":\"wikibase-entityid\",\"value\":{{\"id\":\"Q{index}\"}}}}}}}}]}}}},\n"
+            ));
+            if index % 3 == 0 {
+                linked.push(id);
+            }
+        }
+        text.push_str("]\n");
+        (text, linked)
+    }
+
+    /// Fails once `limit` bytes have been read.
+    struct Failing {
+        inner: Cursor<Vec<u8>>,
+        limit: u64,
+    }
+
+    impl Read for Failing {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.inner.position() >= self.limit {
+                return Err(io::Error::other("disk went away"));
+            }
+            let remaining = usize::try_from(self.limit - self.inner.position()).unwrap_or(0);
+            let end = buf.len().min(remaining);
+            self.inner.read(buf.get_mut(..end).unwrap_or_default())
+        }
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(4)]
+    fn matches_sequential_extraction(#[case] worker_count: usize) {
+        let (text, linked) = dump(BATCH_LINES * 3 + 17);
+        let links = links(&linked);
+        let filter = links.to_link_filter();
+        let properties = ClaimPropertySet::default();
+        let selection = ClaimSelection::new(&properties);
+
+        let sequential: Vec<EntityClaims> =
+            stream_linked_entity_claims(Cursor::new(text.clone()), &links, &filter, selection)
+                .collect::<Result<_, _>>()
+                .expect("sequential extraction");
+        let parallel: Vec<EntityClaims> =
+            stream_linked_entity_claims(Cursor::new(text), &links, &filter, selection)
+                .parallel_with_workers(workers(worker_count))
+                .collect::<Result<_, _>>()
+                .expect("parallel extraction");
+
+        assert_eq!(parallel.len(), linked.len());
+        assert_eq!(parallel, sequential);
+    }
+
+    #[rstest]
+    fn yields_preceding_entities_before_a_parse_error() {
+        let (mut text, linked) = dump(BATCH_LINES + 10);
+        text.push_str("{\"id\":\"Q1\",\"claims\":\n{\"id\":\"Q1\",\"claims\":{}}\n");
+        let links = links(&linked);
+        let filter = links.to_link_filter();
+        let properties = ClaimPropertySet::default();
+
+        let results: Vec<_> = stream_linked_entity_claims(
+            Cursor::new(text),
+            &links,
+            &filter,
+            ClaimSelection::new(&properties),
+        )
+        .parallel_with_workers(workers(3))
+        .collect();
+
+        let (last, entities) = results.split_last().expect("results");
+        assert_eq!(entities.len(), linked.len());
+        assert!(entities.iter().all(Result::is_ok));
+        assert!(matches!(
+            last,
+            Err(WikidataEtlError::ParseEntity { line, .. }) if *line == BATCH_LINES + 13
+        ));
+    }
+
+    #[rstest]
+    fn reports_read_errors_with_their_line() {
+        let (text, linked) = dump(BATCH_LINES * 2);
+        let limit = text
+            .lines()
+            .take(BATCH_LINES + 5)
+            .map(|line| line.len() + 1)
+            .sum::<usize>();
+        let reader = Failing {
+            inner: Cursor::new(text.into_bytes()),
+            limit: u64::try_from(limit).expect("small dump"),
+        };
+        let links = links(&linked);
+        let filter = links.to_link_filter();
+        let properties = ClaimPropertySet::default();
+
+        let results: Vec<_> =
+            stream_linked_entity_claims(reader, &links, &filter, ClaimSelection::new(&properties))
+                .parallel_with_workers(workers(2))
+                .collect();
+
+        let (last, entities) = results.split_last().expect("results");
+        assert_eq!(entities.len(), (BATCH_LINES + 4).div_ceil(3));
+        assert!(matches!(
+            last,
+            Err(WikidataEtlError::ReadLine { line, .. }) if *line == BATCH_LINES + 6
+        ));
+    }
+
+    #[rstest]
+    fn yields_nothing_without_links() {
+        let links = PoiEntityLinks::default();
+        let filter = links.to_link_filter();
+        let properties = ClaimPropertySet::default();
+        let (text, _) = dump(10);
+
+        let mut stream = stream_linked_entity_claims(
+            Cursor::new(text),
+            &links,
+            &filter,
+            ClaimSelection::new(&properties),
+        )
+        .parallel();
+
+        assert!(stream.next().is_none());
+    }
+}
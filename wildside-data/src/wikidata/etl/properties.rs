@@ -0,0 +1,153 @@
+//! Selection of the Wikidata properties captured during claim extraction.
+//!
+//! The extractor only keeps claims whose property appears in a
+//! [`ClaimPropertySet`]. The default set covers the properties the scorer's
+//! theme mappings currently understand; callers can widen or narrow it to suit
+//! their own mappings without touching the parser.
+
+use std::{collections::BTreeSet, fmt, str::FromStr};
+
+use thiserror::Error;
+
+use super::HERITAGE_PROPERTY;
+
+/// `P31` (instance of): the entity's class, such as museum or park.
+pub const INSTANCE_OF_PROPERTY: &str = "P31";
+/// `P149` (architectural style).
+pub const ARCHITECTURAL_STYLE_PROPERTY: &str = "P149";
+/// `P571` (inception): when the entity was founded or built.
+pub const INCEPTION_PROPERTY: &str = "P571";
+
+/// Properties extracted when no explicit set is supplied.
+pub const DEFAULT_CLAIM_PROPERTIES: [&str; 4] = [
+    HERITAGE_PROPERTY,
+    INSTANCE_OF_PROPERTY,
+    ARCHITECTURAL_STYLE_PROPERTY,
+    INCEPTION_PROPERTY,
+];
+
+/// Ordered set of Wikidata property identifiers to extract.
+///
+/// # Examples
+/// ```
+/// use wildside_data::wikidata::etl::ClaimPropertySet;
+///
+/// let properties: ClaimPropertySet = "P31, p149".parse()?;
+/// assert!(properties.contains("P31"));
+/// assert!(properties.contains("P149"));
+/// assert!(!properties.contains("P1435"));
+/// # Ok::<(), wildside_data::wikidata::etl::PropertyIdError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClaimPropertySet {
+    properties: BTreeSet<String>,
+}
+
+/// Error raised when a property identifier is not of the form `P<digits>`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("invalid Wikidata property id {0:?}; expected P followed by digits")]
+pub struct PropertyIdError(pub String);
+
+impl ClaimPropertySet {
+    /// Build a set from property identifiers, normalizing their case.
+    ///
+    /// # Errors
+    /// Returns [`PropertyIdError`] for identifiers that are not `P<digits>`.
+    pub fn try_from_ids<I, S>(ids: I) -> Result<Self, PropertyIdError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let properties = ids
+            .into_iter()
+            .map(|id| normalize_property_id(id.as_ref()))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { properties })
+    }
+
+    /// Report whether claims for `property_id` should be extracted.
+    #[must_use]
+    pub fn contains(&self, property_id: &str) -> bool {
+        self.properties.contains(property_id)
+    }
+
+    /// Iterate over the selected property identifiers in sorted order.
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.properties.iter().map(String::as_str)
+    }
+
+    /// Return whether no properties are selected.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+    }
+}
+
+impl Default for ClaimPropertySet {
+    fn default() -> Self {
+        Self {
+            properties: DEFAULT_CLAIM_PROPERTIES.map(str::to_owned).into(),
+        }
+    }
+}
+
+impl FromStr for ClaimPropertySet {
+    type Err = PropertyIdError;
+
+    /// Parse a comma-separated list such as `P31,P149`.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::try_from_ids(input.split(',').map(str::trim).filter(|id| !id.is_empty()))
+    }
+}
+
+impl fmt::Display for ClaimPropertySet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let joined: Vec<&str> = self.iter().collect();
+        f.write_str(&joined.join(","))
+    }
+}
+
+fn normalize_property_id(input: &str) -> Result<String, PropertyIdError> {
+    let trimmed = input.trim();
+    let digits = trimmed
+        .strip_prefix(['P', 'p'])
+        .filter(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+        .ok_or_else(|| PropertyIdError(input.to_owned()))?;
+    Ok(format!("P{digits}"))
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for property set parsing.
+
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    fn default_set_includes_heritage() {
+        let properties = ClaimPropertySet::default();
+        assert!(properties.contains(HERITAGE_PROPERTY));
+        assert_eq!(properties.to_string(), "P1435,P149,P31,P571");
+    }
+
+    #[rstest]
+    #[case("P31", "P31")]
+    #[case(" p571 ", "P571")]
+    #[case("P31,,P31", "P31")]
+    #[case("", "")]
+    fn parses_property_lists(#[case] input: &str, #[case] expected: &str) {
+        let properties: ClaimPropertySet = input.parse().expect("valid property list");
+        assert_eq!(properties.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case("Q31")]
+    #[case("P")]
+    #[case("P3a")]
+    fn rejects_malformed_ids(#[case] input: &str) {
+        let err = input
+            .parse::<ClaimPropertySet>()
+            .expect_err("malformed id should fail");
+        assert_eq!(err, PropertyIdError(input.to_owned()));
+    }
+}
@@ -0,0 +1,66 @@
+//! Statement ranks and the truthy-claim filter.
+//!
+//! Wikidata marks each statement as preferred, normal, or deprecated. The
+//! "truthy" statements for a property are those with the best non-deprecated
+//! rank present: the preferred ones when any exist, otherwise the normal ones.
+//! [`RankFilter::Truthy`] applies that rule during extraction so deprecated or
+//! superseded values never reach the claims tables.
+
+use serde::Deserialize;
+
+/// Rank assigned to a Wikidata statement.
+///
+/// Variants are ordered from worst to best, so the maximum rank among a
+/// property's statements is its best rank.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClaimRank {
+    /// The statement is known to be wrong or outdated.
+    Deprecated,
+    /// The default rank for statements.
+    #[default]
+    Normal,
+    /// The statement is the most current or accurate value.
+    Preferred,
+}
+
+/// Which statements of a property are kept during extraction.
+///
+/// # Examples
+/// ```
+/// use wildside_data::wikidata::etl::{ClaimRank, RankFilter};
+///
+/// let ranks = [ClaimRank::Normal, ClaimRank::Preferred, ClaimRank::Deprecated];
+/// let best = RankFilter::Truthy.best_rank(ranks);
+/// assert!(RankFilter::Truthy.keeps(ClaimRank::Preferred, best));
+/// assert!(!RankFilter::Truthy.keeps(ClaimRank::Normal, best));
+/// assert!(RankFilter::All.keeps(ClaimRank::Deprecated, best));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RankFilter {
+    /// Keep every statement regardless of rank.
+    #[default]
+    All,
+    /// Keep only the best-ranked, non-deprecated statements of each property.
+    Truthy,
+}
+
+impl RankFilter {
+    /// Return the best rank among a property's statement `ranks`.
+    pub fn best_rank<I>(self, ranks: I) -> ClaimRank
+    where
+        I: IntoIterator<Item = ClaimRank>,
+    {
+        ranks.into_iter().max().unwrap_or_default()
+    }
+
+    /// Report whether a statement of `rank` is kept when the property's best
+    /// rank is `best`.
+    #[must_use]
+    pub fn keeps(self, rank: ClaimRank, best: ClaimRank) -> bool {
+        match self {
+            Self::All => true,
+            Self::Truthy => rank != ClaimRank::Deprecated && rank == best,
+        }
+    }
+}
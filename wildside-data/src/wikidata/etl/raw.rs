@@ -0,0 +1,196 @@
+//! Borrowed deserialisation targets for dump and API entity records.
+
+use std::{borrow::Cow, collections::BTreeMap};
+
+use serde::{Deserialize, de::IgnoredAny};
+
+use super::{
+    Claim, ClaimRank, ClaimSelection, ClaimValue, IMAGE_PROPERTY, Qualifier, RankFilter,
+    normalize_wikidata_id,
+};
+
+/// Entity record borrowing its strings from the parse buffer where possible.
+#[derive(Debug, Deserialize)]
+pub(super) struct RawEntity<'a> {
+    #[serde(borrow)]
+    pub(super) id: Cow<'a, str>,
+    #[serde(default, borrow)]
+    claims: BTreeMap<Cow<'a, str>, Vec<RawClaim<'a>>>,
+    /// Sitelinks keyed by site (e.g. `enwiki`); only the count is retained.
+    #[serde(default, borrow)]
+    sitelinks: Option<BTreeMap<Cow<'a, str>, IgnoredAny>>,
+    /// Set by `wbgetentities` for ids that do not exist; absent from dumps.
+    #[serde(default)]
+    pub(super) missing: Option<IgnoredAny>,
+}
+
+impl RawEntity<'_> {
+    pub(super) fn selected_claims(&self, selection: ClaimSelection<'_>) -> Vec<Claim> {
+        self.claims
+            .iter()
+            .filter(|(property_id, _)| selection.properties.contains(property_id))
+            .flat_map(|(property_id, claims)| {
+                ranked_claims(claims, selection.ranks).filter_map(move |claim| {
+                    claim.main_snak.claim_value().map(|value| Claim {
+                        property_id: property_id.to_string(),
+                        value,
+                        rank: claim.rank,
+                        qualifiers: claim.qualifiers(),
+                    })
+                })
+            })
+            .collect()
+    }
+
+    pub(super) fn sitelink_count(&self) -> Option<u32> {
+        self.sitelinks
+            .as_ref()
+            .map(|sitelinks| u32::try_from(sitelinks.len()).unwrap_or(u32::MAX))
+    }
+
+    pub(super) fn image_files(&self, ranks: RankFilter) -> Vec<String> {
+        self.claims
+            .get(IMAGE_PROPERTY)
+            .into_iter()
+            .flat_map(|claims| ranked_claims(claims, ranks))
+            .filter_map(|claim| claim.main_snak.string_value())
+            .collect()
+    }
+}
+
+/// Statements of one property that survive the rank filter.
+///
+/// The best rank is taken over every statement, including `novalue` and
+/// `somevalue` snaks, matching Wikidata's own truthy definition.
+fn ranked_claims<'c, 'a>(
+    claims: &'c [RawClaim<'a>],
+    ranks: RankFilter,
+) -> impl Iterator<Item = &'c RawClaim<'a>> {
+    let best = ranks.best_rank(claims.iter().map(|claim| claim.rank));
+    claims
+        .iter()
+        .filter(move |claim| ranks.keeps(claim.rank, best))
+}
+
+#[derive(Debug, Deserialize)]
+struct RawClaim<'a> {
+    #[serde(rename = "mainsnak", borrow)]
+    main_snak: RawSnak<'a>,
+    #[serde(default)]
+    rank: ClaimRank,
+    #[serde(default, borrow)]
+    qualifiers: BTreeMap<Cow<'a, str>, Vec<RawSnak<'a>>>,
+}
+
+impl RawClaim<'_> {
+    fn qualifiers(&self) -> Vec<Qualifier> {
+        let mut qualifiers: Vec<Qualifier> = self
+            .qualifiers
+            .iter()
+            .flat_map(|(property_id, snaks)| {
+                snaks.iter().filter_map(move |snak| {
+                    snak.claim_value().map(|value| Qualifier {
+                        property_id: property_id.to_string(),
+                        value,
+                    })
+                })
+            })
+            .collect();
+        qualifiers.sort_unstable();
+        qualifiers.dedup();
+        qualifiers
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RawSnak<'a> {
+    #[serde(rename = "snaktype")]
+    snak_type: RawSnakType,
+    #[serde(rename = "datavalue", borrow)]
+    data_value: Option<RawDataValue<'a>>,
+}
+
+impl RawSnak<'_> {
+    fn claim_value(&self) -> Option<ClaimValue> {
+        if self.snak_type != RawSnakType::Value {
+            return None;
+        }
+        match self.data_value.as_ref()? {
+            RawDataValue::Entity { value } => {
+                normalize_wikidata_id(&value.id).map(ClaimValue::Entity)
+            }
+            RawDataValue::String { value } => non_empty(value).map(ClaimValue::Literal),
+            RawDataValue::Time { value } => non_empty(&value.time).map(ClaimValue::Literal),
+            RawDataValue::Quantity { value } => non_empty(&value.amount).map(ClaimValue::Literal),
+            RawDataValue::Unsupported => None,
+        }
+    }
+
+    fn string_value(&self) -> Option<String> {
+        if self.snak_type != RawSnakType::Value {
+            return None;
+        }
+        let RawDataValue::String { value } = self.data_value.as_ref()? else {
+            return None;
+        };
+        non_empty(value)
+    }
+}
+
+pub(super) fn non_empty(value: &str) -> Option<String> {
+    let trimmed = value.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_owned())
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum RawSnakType {
+    Value,
+    Somevalue,
+    Novalue,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+enum RawDataValue<'a> {
+    #[serde(rename = "wikibase-entityid")]
+    Entity {
+        #[serde(borrow)]
+        value: RawEntityId<'a>,
+    },
+    #[serde(rename = "string")]
+    String {
+        #[serde(borrow)]
+        value: Cow<'a, str>,
+    },
+    #[serde(rename = "time")]
+    Time {
+        #[serde(borrow)]
+        value: RawTime<'a>,
+    },
+    #[serde(rename = "quantity")]
+    Quantity {
+        #[serde(borrow)]
+        value: RawQuantity<'a>,
+    },
+    #[serde(other)]
+    Unsupported,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawEntityId<'a> {
+    #[serde(borrow)]
+    id: Cow<'a, str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawTime<'a> {
+    #[serde(borrow)]
+    time: Cow<'a, str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawQuantity<'a> {
+    #[serde(borrow)]
+    amount: Cow<'a, str>,
+}
@@ -0,0 +1,379 @@
+//! Change detection for linked entities through the Wikidata Action API.
+//!
+//! [`WikidataRevisionSource`] asks `action=query&prop=revisions` for the
+//! latest revision timestamp of each linked entity, 50 titles per request,
+//! and reports the entities edited after a watermark. Checking the linked set
+//! directly costs one request per batch, whereas scanning the global
+//! `list=recentchanges` feed would page through every edit made to Wikidata
+//! and only covers the last 30 days.
+
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use reqwest::Client;
+use serde::Deserialize;
+use tokio::runtime::Runtime;
+
+use super::api::{blocking_runtime, http_client};
+use super::{MAX_ENTITY_BATCH, PoiEntityLinks, WikidataApiError};
+use crate::wikidata::dump::{TransportError, convert_reqwest_error};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Transport performing `prop=revisions` queries.
+///
+/// Implementations return the raw JSON body so tests can substitute canned
+/// responses for HTTP.
+pub trait RevisionTransport {
+    /// Fetch the latest revision timestamp of each title in `ids`.
+    ///
+    /// # Errors
+    /// Returns [`TransportError`] when the request fails.
+    fn latest_revisions(&self, ids: &[&str]) -> Result<Vec<u8>, TransportError>;
+}
+
+/// HTTP implementation of [`RevisionTransport`].
+///
+/// Requests block on an internal current-thread Tokio runtime, so calls must
+/// not be made from inside another Tokio runtime.
+#[derive(Debug)]
+pub struct HttpRevisionTransport {
+    client: Client,
+    runtime: Runtime,
+    endpoint: String,
+}
+
+impl HttpRevisionTransport {
+    /// Build a transport for the API at `endpoint` (e.g.
+    /// [`DEFAULT_API_URL`](super::DEFAULT_API_URL)).
+    ///
+    /// # Errors
+    /// Returns [`WikidataApiError`] when the HTTP client or runtime cannot be
+    /// built.
+    pub fn new(endpoint: impl Into<String>) -> Result<Self, WikidataApiError> {
+        let client = http_client(REQUEST_TIMEOUT)
+            .map_err(|source| WikidataApiError::HttpClient { source })?;
+        let runtime = blocking_runtime().map_err(|source| WikidataApiError::Runtime { source })?;
+        Ok(Self {
+            client,
+            runtime,
+            endpoint: endpoint.into(),
+        })
+    }
+
+    async fn fetch(&self, titles: &str) -> Result<Vec<u8>, TransportError> {
+        let url = self.endpoint.as_str();
+        let response = self
+            .client
+            .get(url)
+            .query(&[
+                ("action", "query"),
+                ("format", "json"),
+                ("formatversion", "2"),
+                ("prop", "revisions"),
+                ("rvprop", "timestamp"),
+                ("titles", titles),
+            ])
+            .send()
+            .await
+            .map_err(|err| convert_reqwest_error(err, url))?
+            .error_for_status()
+            .map_err(|err| convert_reqwest_error(err, url))?;
+        let body = response
+            .bytes()
+            .await
+            .map_err(|err| convert_reqwest_error(err, url))?;
+        Ok(body.to_vec())
+    }
+}
+
+impl RevisionTransport for HttpRevisionTransport {
+    fn latest_revisions(&self, ids: &[&str]) -> Result<Vec<u8>, TransportError> {
+        self.runtime.block_on(self.fetch(&ids.join("|")))
+    }
+}
+
+/// Linked entities edited after a watermark.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EntityChanges {
+    /// Entities whose latest revision is newer than the watermark, sorted.
+    pub changed: Vec<String>,
+    /// Latest revision timestamp seen across every checked entity, in
+    /// MediaWiki's `YYYY-MM-DDTHH:MM:SSZ` form.
+    pub latest_edit: Option<String>,
+}
+
+/// Reports which linked entities changed since a watermark.
+///
+/// # Examples
+/// ```
+/// use geo::Coord;
+/// use wildside_core::{PointOfInterest, Tags};
+/// use wildside_data::wikidata::dump::TransportError;
+/// use wildside_data::wikidata::etl::{
+///     PoiEntityLinks, RevisionTransport, WikidataRevisionSource,
+/// };
+///
+/// struct Canned;
+/// impl RevisionTransport for Canned {
+///     fn latest_revisions(&self, _ids: &[&str]) -> Result<Vec<u8>, TransportError> {
+///         Ok(br#"{"query":{"pages":[{"ns":0,"title":"Q64","revisions":[{"timestamp":"2024-09-10T08:00:00Z"}]}]}}"#.to_vec())
+///     }
+/// }
+///
+/// let poi = PointOfInterest::new(
+///     1,
+///     Coord { x: 13.4, y: 52.5 },
+///     Tags::from([("wikidata".into(), "Q64".into())]),
+/// );
+/// let links = PoiEntityLinks::from_pois([&poi]);
+/// let source = WikidataRevisionSource::with_transport(Canned);
+/// let changes = source.changed_since(&links, Some("2024-09-09T00:00:00Z"))?;
+///
+/// assert_eq!(changes.changed, ["Q64"]);
+/// assert_eq!(changes.latest_edit.as_deref(), Some("2024-09-10T08:00:00Z"));
+/// # Ok::<(), wildside_data::wikidata::etl::WikidataApiError>(())
+/// ```
+#[derive(Debug)]
+pub struct WikidataRevisionSource<T = HttpRevisionTransport> {
+    transport: T,
+    batch_size: usize,
+}
+
+impl WikidataRevisionSource {
+    /// Build an HTTP-backed source for the API at `endpoint`.
+    ///
+    /// # Errors
+    /// Returns [`WikidataApiError`] when the HTTP transport cannot be built.
+    pub fn new(endpoint: impl Into<String>) -> Result<Self, WikidataApiError> {
+        HttpRevisionTransport::new(endpoint).map(Self::with_transport)
+    }
+}
+
+impl<T: RevisionTransport> WikidataRevisionSource<T> {
+    /// Build a source over a custom transport.
+    pub const fn with_transport(transport: T) -> Self {
+        Self {
+            transport,
+            batch_size: MAX_ENTITY_BATCH,
+        }
+    }
+
+    /// Set how many titles each request carries, clamped to
+    /// `1..=`[`MAX_ENTITY_BATCH`].
+    #[must_use]
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.clamp(1, MAX_ENTITY_BATCH);
+        self
+    }
+
+    /// Report the entities in `links` edited strictly after `since`.
+    ///
+    /// Without a watermark every existing entity counts as changed. Deleted
+    /// entities are skipped, so their stored claims are left untouched.
+    /// Timestamps have one-second resolution; an edit landing in the same
+    /// second as the watermark is picked up by the next change after it.
+    ///
+    /// # Errors
+    /// Returns [`WikidataApiError`] when a request fails, a response cannot be
+    /// parsed, or the API reports an error.
+    pub fn changed_since(
+        &self,
+        links: &PoiEntityLinks,
+        since: Option<&str>,
+    ) -> Result<EntityChanges, WikidataApiError> {
+        let ids: Vec<&str> = links.entity_ids().collect();
+        let mut revisions = Vec::new();
+        for batch in ids.chunks(self.batch_size) {
+            revisions.extend(self.fetch_batch(batch)?);
+        }
+        let latest_edit = revisions
+            .iter()
+            .map(|(_, timestamp)| timestamp)
+            .max()
+            .cloned();
+        let changed: BTreeSet<String> = revisions
+            .into_iter()
+            .filter(|(_, timestamp)| since.is_none_or(|watermark| timestamp.as_str() > watermark))
+            .map(|(title, _)| title)
+            .collect();
+        Ok(EntityChanges {
+            changed: changed.into_iter().collect(),
+            latest_edit,
+        })
+    }
+
+    fn fetch_batch(&self, batch: &[&str]) -> Result<Vec<(String, String)>, WikidataApiError> {
+        let mut body = self
+            .transport
+            .latest_revisions(batch)
+            .map_err(|source| WikidataApiError::Transport { source })?;
+        let response: RawQueryResponse =
+            simd_json::from_slice(&mut body).map_err(|source| WikidataApiError::ParseResponse {
+                source,
+                ids: batch.join("|"),
+            })?;
+        if let Some(RawQueryError { code, info }) = response.error {
+            return Err(WikidataApiError::Api {
+                code,
+                info,
+                ids: batch.join("|"),
+            });
+        }
+        Ok(response
+            .query
+            .pages
+            .into_iter()
+            .filter_map(|page| {
+                let revision = page.revisions.into_iter().next()?;
+                Some((page.title, revision.timestamp))
+            })
+            .collect())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RawQueryResponse {
+    #[serde(default)]
+    query: RawQuery,
+    #[serde(default)]
+    error: Option<RawQueryError>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawQuery {
+    #[serde(default)]
+    pages: Vec<RawPage>,
+}
+
+/// A page in a `formatversion=2` response; missing pages have no revisions.
+#[derive(Debug, Deserialize)]
+struct RawPage {
+    title: String,
+    #[serde(default)]
+    revisions: Vec<RawRevision>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawRevision {
+    timestamp: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawQueryError {
+    code: String,
+    #[serde(default)]
+    info: String,
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for revision-based change detection.
+
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+
+    use geo::Coord;
+    use rstest::{fixture, rstest};
+    use wildside_core::{PointOfInterest, Tags};
+
+    use super::*;
+
+    /// Answers from canned timestamps and records each requested batch.
+    struct StubTransport {
+        timestamps: BTreeMap<&'static str, &'static str>,
+        requests: RefCell<Vec<Vec<String>>>,
+    }
+
+    impl StubTransport {
+        fn page(&self, id: &str) -> String {
+            match self.timestamps.get(id) {
+                Some(timestamp) => format!(
+                    r#"{{"ns":0,"title":"{id}","revisions":[{{"timestamp":"{timestamp}"}}]}}"#
+                ),
+                None => format!(r#"{{"ns":0,"title":"{id}","missing":true}}"#),
+            }
+        }
+    }
+
+    impl RevisionTransport for StubTransport {
+        fn latest_revisions(&self, ids: &[&str]) -> Result<Vec<u8>, TransportError> {
+            self.requests
+                .borrow_mut()
+                .push(ids.iter().map(|id| (*id).to_owned()).collect());
+            let pages: Vec<String> = ids.iter().map(|id| self.page(id)).collect();
+            Ok(format!(r#"{{"query":{{"pages":[{}]}}}}"#, pages.join(",")).into_bytes())
+        }
+    }
+
+    fn poi(id: u64, entity_id: &str) -> PointOfInterest {
+        PointOfInterest::new(
+            id,
+            Coord { x: 0.0, y: 0.0 },
+            Tags::from([("wikidata".into(), entity_id.into())]),
+        )
+    }
+
+    #[fixture]
+    fn links() -> PoiEntityLinks {
+        PoiEntityLinks::from_pois([&poi(1, "Q64"), &poi(2, "Q90"), &poi(3, "Q1")])
+    }
+
+    #[fixture]
+    fn transport() -> StubTransport {
+        StubTransport {
+            timestamps: BTreeMap::from([
+                ("Q64", "2024-09-12T10:00:00Z"),
+                ("Q90", "2024-09-01T10:00:00Z"),
+            ]),
+            requests: RefCell::new(Vec::new()),
+        }
+    }
+
+    #[rstest]
+    fn reports_entities_edited_after_the_watermark(
+        links: PoiEntityLinks,
+        transport: StubTransport,
+    ) {
+        let source = WikidataRevisionSource::with_transport(transport).with_batch_size(2);
+
+        let changes = source
+            .changed_since(&links, Some("2024-09-09T00:00:00Z"))
+            .expect("change detection should succeed");
+
+        assert_eq!(changes.changed, ["Q64"]);
+        assert_eq!(changes.latest_edit.as_deref(), Some("2024-09-12T10:00:00Z"));
+        assert_eq!(source.transport.requests.borrow().len(), 2);
+    }
+
+    #[rstest]
+    fn treats_every_entity_as_changed_without_a_watermark(
+        links: PoiEntityLinks,
+        transport: StubTransport,
+    ) {
+        let source = WikidataRevisionSource::with_transport(transport);
+
+        let changes = source
+            .changed_since(&links, None)
+            .expect("change detection should succeed");
+
+        assert_eq!(changes.changed, ["Q64", "Q90"]);
+    }
+
+    #[rstest]
+    fn surfaces_api_errors(links: PoiEntityLinks) {
+        struct Failing;
+        impl RevisionTransport for Failing {
+            fn latest_revisions(&self, _ids: &[&str]) -> Result<Vec<u8>, TransportError> {
+                Ok(br#"{"error":{"code":"maxlag","info":"Waiting"}}"#.to_vec())
+            }
+        }
+        let source = WikidataRevisionSource::with_transport(Failing);
+
+        let err = source
+            .changed_since(&links, None)
+            .expect_err("API error should surface");
+
+        assert!(matches!(&err, WikidataApiError::Api { code, .. } if code == "maxlag"));
+    }
+}
@@ -0,0 +1,315 @@
+//! Claim enrichment through the Wikidata Query Service (WDQS).
+//!
+//! Re-extracting claims for an already linked entity set does not need a full
+//! dump pass. [`WikidataSparqlSource`] asks a SPARQL endpoint for the selected
+//! statements of those entities instead, batching ids into `VALUES` blocks and
+//! paging through each batch's results with `LIMIT`/`OFFSET`. Transient
+//! failures (HTTP 429, 5xx, and network errors) are retried with exponential
+//! backoff according to a [`RetryPolicy`].
+//!
+//! Results use the same [`EntityClaims`] shape as dump extraction, with two
+//! differences inherent to WDQS's simple values: qualifiers are not fetched,
+//! and times are rendered at day precision (`+1791-01-01T00:00:00Z` rather
+//! than the dump's `+1791-00-00T00:00:00Z`).
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+use std::thread;
+use std::time::Duration;
+
+use log::warn;
+use thiserror::Error;
+
+#[cfg(doc)]
+use super::RankFilter;
+use super::{ClaimSelection, EntityClaims, IMAGE_PROPERTY, PoiEntityLinks};
+use crate::wikidata::dump::TransportError;
+
+mod results;
+mod transport;
+
+use results::{RawSparqlResults, SparqlEntity};
+pub use transport::{HttpSparqlTransport, SparqlTransport};
+
+/// The public Wikidata Query Service endpoint.
+pub const DEFAULT_SPARQL_URL: &str = "https://query.wikidata.org/sparql";
+
+/// Entity ids sent per query unless overridden.
+pub const DEFAULT_SPARQL_BATCH: usize = 200;
+
+/// Result rows requested per page unless overridden.
+pub const DEFAULT_SPARQL_PAGE_SIZE: usize = 5_000;
+
+/// WDQS aborts queries after 60 seconds; allow a little longer for transfer.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(65);
+
+const ENTITY_PREFIX: &str = "http://www.wikidata.org/entity/";
+const COMMONS_FILE_PREFIX: &str = "http://commons.wikimedia.org/wiki/Special:FilePath/";
+const RANK_PREFIX: &str = "http://wikiba.se/ontology#";
+const XSD_PREFIX: &str = "http://www.w3.org/2001/XMLSchema#";
+const SPARQL_RESULTS_JSON: &str = "application/sparql-results+json";
+
+/// Errors raised while enriching POIs through a SPARQL endpoint.
+#[derive(Debug, Error)]
+pub enum WikidataSparqlError {
+    #[error("failed to build HTTP client: {source}")]
+    HttpClient {
+        #[source]
+        source: reqwest::Error,
+    },
+    #[error("failed to build Tokio runtime: {source}")]
+    Runtime {
+        #[source]
+        source: io::Error,
+    },
+    #[error("SPARQL query failed after {attempts} attempt(s): {source}")]
+    Transport {
+        #[source]
+        source: TransportError,
+        attempts: u32,
+    },
+    #[error("failed to parse SPARQL results for {ids}")]
+    ParseResponse {
+        #[source]
+        source: simd_json::Error,
+        ids: String,
+    },
+}
+
+/// How often and how patiently failed queries are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts per query, including the first; `0` behaves like `1`.
+    pub max_attempts: u32,
+    /// Delay before the first retry; each further retry doubles it.
+    pub initial_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Delay to wait after the given failed attempt (counting from 1).
+    fn backoff(self, attempt: u32) -> Duration {
+        let doublings = attempt.saturating_sub(1).min(16);
+        self.initial_backoff.saturating_mul(1 << doublings)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Fetches claims for linked entities from a SPARQL endpoint.
+///
+/// # Examples
+/// ```
+/// use geo::Coord;
+/// use wildside_core::{PointOfInterest, Tags};
+/// use wildside_data::wikidata::dump::TransportError;
+/// use wildside_data::wikidata::etl::{
+///     ClaimPropertySet, ClaimSelection, PoiEntityLinks, SparqlTransport, WikidataSparqlSource,
+/// };
+///
+/// struct Canned;
+/// impl SparqlTransport for Canned {
+///     fn query(&self, _sparql: &str) -> Result<Vec<u8>, TransportError> {
+///         Ok(br#"{"results":{"bindings":[{
+///             "item":{"type":"uri","value":"http://www.wikidata.org/entity/Q64"},
+///             "sitelinks":{"type":"literal","value":"230"},
+///             "property":{"type":"uri","value":"http://www.wikidata.org/entity/P1435"},
+///             "statement":{"type":"uri","value":"http://www.wikidata.org/entity/statement/Q64-1"},
+///             "rank":{"type":"uri","value":"http://wikiba.se/ontology#NormalRank"},
+///             "value":{"type":"uri","value":"http://www.wikidata.org/entity/Q9259"}
+///         }]}}"#.to_vec())
+///     }
+/// }
+///
+/// let poi = PointOfInterest::new(
+///     1,
+///     Coord { x: 13.4, y: 52.5 },
+///     Tags::from([("wikidata".into(), "Q64".into())]),
+/// );
+/// let links = PoiEntityLinks::from_pois([&poi]);
+/// let properties = ClaimPropertySet::default();
+/// let source = WikidataSparqlSource::with_transport(Canned);
+/// let claims = source.extract_linked_entity_claims(&links, ClaimSelection::new(&properties))?;
+///
+/// assert_eq!(claims[0].entity_values("P1435").collect::<Vec<_>>(), ["Q9259"]);
+/// assert_eq!(claims[0].sitelink_count, Some(230));
+/// # Ok::<(), wildside_data::wikidata::etl::WikidataSparqlError>(())
+/// ```
+#[derive(Debug)]
+pub struct WikidataSparqlSource<T = HttpSparqlTransport> {
+    transport: T,
+    batch_size: usize,
+    page_size: usize,
+    retry: RetryPolicy,
+}
+
+impl WikidataSparqlSource {
+    /// Build an HTTP-backed source for the SPARQL endpoint at `endpoint`.
+    ///
+    /// # Errors
+    /// Returns [`WikidataSparqlError`] when the HTTP transport cannot be built.
+    pub fn new(endpoint: impl Into<String>) -> Result<Self, WikidataSparqlError> {
+        HttpSparqlTransport::new(endpoint).map(Self::with_transport)
+    }
+}
+
+impl<T: SparqlTransport> WikidataSparqlSource<T> {
+    /// Build a source over a custom transport.
+    pub fn with_transport(transport: T) -> Self {
+        Self {
+            transport,
+            batch_size: DEFAULT_SPARQL_BATCH,
+            page_size: DEFAULT_SPARQL_PAGE_SIZE,
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    /// Set how many entity ids each query carries (at least one).
+    #[must_use]
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Set how many result rows each page requests (at least one).
+    #[must_use]
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Replace the retry policy for failed queries.
+    #[must_use]
+    pub const fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Query every entity in `links` and extract the claims in `selection`.
+    ///
+    /// Entities the endpoint does not know are skipped. The selection's
+    /// [`RankFilter`] is applied per property, including `P18` image file
+    /// names, and results are sorted by entity id.
+    ///
+    /// # Errors
+    /// Returns [`WikidataSparqlError`] when a query still fails after the
+    /// retry policy is exhausted or a response cannot be parsed.
+    pub fn extract_linked_entity_claims(
+        &self,
+        links: &PoiEntityLinks,
+        selection: ClaimSelection<'_>,
+    ) -> Result<Vec<EntityClaims>, WikidataSparqlError> {
+        let ids: Vec<&str> = links.entity_ids().collect();
+        let mut entities = BTreeMap::new();
+        for batch in ids.chunks(self.batch_size) {
+            self.fetch_batch(batch, selection, &mut entities)?;
+        }
+        Ok(entities
+            .into_iter()
+            .filter_map(|(entity_id, entity)| entity.into_claims(entity_id, links, selection))
+            .collect())
+    }
+
+    fn fetch_batch(
+        &self,
+        batch: &[&str],
+        selection: ClaimSelection<'_>,
+        entities: &mut BTreeMap<String, SparqlEntity>,
+    ) -> Result<(), WikidataSparqlError> {
+        let query = batch_query(batch, selection);
+        let mut offset = 0;
+        loop {
+            let page = format!(
+                "{query}ORDER BY ?item ?statement\nLIMIT {}\nOFFSET {offset}\n",
+                self.page_size
+            );
+            let mut body = self.query_with_retry(&page)?;
+            let results: RawSparqlResults = simd_json::from_slice(&mut body).map_err(|source| {
+                WikidataSparqlError::ParseResponse {
+                    source,
+                    ids: batch.join("|"),
+                }
+            })?;
+            let rows = results.results.bindings.len();
+            for binding in results.results.bindings {
+                binding.record(entities);
+            }
+            if rows < self.page_size {
+                return Ok(());
+            }
+            offset += rows;
+        }
+    }
+
+    fn query_with_retry(&self, sparql: &str) -> Result<Vec<u8>, WikidataSparqlError> {
+        let mut attempt = 1;
+        loop {
+            match self.transport.query(sparql) {
+                Ok(body) => return Ok(body),
+                Err(source) if attempt < self.retry.max_attempts && is_retryable(&source) => {
+                    let delay = self.retry.backoff(attempt);
+                    warn!(
+                        "SPARQL query attempt {attempt} failed ({source}); retrying in {delay:?}"
+                    );
+                    thread::sleep(delay);
+                    attempt += 1;
+                }
+                Err(source) => {
+                    return Err(WikidataSparqlError::Transport {
+                        source,
+                        attempts: attempt,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Report whether a failed query is worth repeating.
+const fn is_retryable(error: &TransportError) -> bool {
+    match error {
+        TransportError::Http { status, .. } => *status == 429 || *status >= 500,
+        TransportError::Network { .. } => true,
+    }
+}
+
+/// Build the query for one batch, without ordering or paging clauses.
+///
+/// Entity and property ids are normalized before they get here, so they can
+/// be interpolated safely. `P18` is always requested so image file names are
+/// captured as in dump extraction.
+fn batch_query(ids: &[&str], selection: ClaimSelection<'_>) -> String {
+    let items: Vec<String> = ids.iter().map(|id| format!("wd:{id}")).collect();
+    let properties: BTreeSet<&str> = selection
+        .properties
+        .iter()
+        .chain([IMAGE_PROPERTY])
+        .collect();
+    let properties: Vec<String> = properties.iter().map(|id| format!("wd:{id}")).collect();
+    format!(
+        "PREFIX wd: <{ENTITY_PREFIX}>\n\
+         PREFIX wikibase: <{RANK_PREFIX}>\n\
+         SELECT ?item ?sitelinks ?property ?statement ?rank ?value WHERE {{\n  \
+           VALUES ?item {{ {items} }}\n  \
+           ?item wikibase:sitelinks ?sitelinks .\n  \
+           OPTIONAL {{\n    \
+             VALUES ?property {{ {properties} }}\n    \
+             ?property wikibase:claim ?claim ; wikibase:statementProperty ?statementValue .\n    \
+             ?item ?claim ?statement .\n    \
+             ?statement wikibase:rank ?rank .\n    \
+             OPTIONAL {{ ?statement ?statementValue ?value . }}\n  \
+           }}\n\
+         }}\n",
+        items = items.join(" "),
+        properties = properties.join(" "),
+    )
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,213 @@
+//! Decoding SPARQL JSON results into entity claims.
+
+use std::collections::BTreeMap;
+
+use percent_encoding::percent_decode_str;
+use serde::Deserialize;
+
+use super::super::{
+    Claim, ClaimRank, ClaimSelection, ClaimValue, EntityClaims, IMAGE_PROPERTY, PoiEntityLinks,
+    RankFilter, non_empty, normalize_wikidata_id,
+};
+use super::{COMMONS_FILE_PREFIX, ENTITY_PREFIX, RANK_PREFIX, XSD_PREFIX};
+
+/// Statements gathered for one entity across result pages.
+#[derive(Debug, Default)]
+pub(super) struct SparqlEntity {
+    sitelinks: Option<u32>,
+    /// Keyed by statement IRI so rows repeated across pages collapse.
+    statements: BTreeMap<String, SparqlStatement>,
+}
+
+#[derive(Debug)]
+struct SparqlStatement {
+    property_id: String,
+    rank: ClaimRank,
+    /// `None` for `novalue`, `somevalue`, and unsupported value types.
+    value: Option<ClaimValue>,
+}
+
+impl SparqlEntity {
+    pub(super) fn into_claims(
+        self,
+        entity_id: String,
+        links: &PoiEntityLinks,
+        selection: ClaimSelection<'_>,
+    ) -> Option<EntityClaims> {
+        let linked_poi_ids = links.linked_poi_ids(&entity_id)?.to_vec();
+        let kept = self.kept_statements(selection.ranks);
+        let mut claims: Vec<Claim> = kept
+            .iter()
+            .filter(|statement| selection.properties.contains(&statement.property_id))
+            .filter_map(|statement| {
+                statement.value.clone().map(|value| Claim {
+                    property_id: statement.property_id.clone(),
+                    value,
+                    rank: statement.rank,
+                    qualifiers: Vec::new(),
+                })
+            })
+            .collect();
+        claims.sort_unstable();
+        claims.dedup();
+        let mut image_files: Vec<String> = kept
+            .iter()
+            .filter(|statement| statement.property_id == IMAGE_PROPERTY)
+            .filter_map(|statement| match &statement.value {
+                Some(ClaimValue::Literal(file)) => Some(file.clone()),
+                _ => None,
+            })
+            .collect();
+        image_files.sort_unstable();
+        image_files.dedup();
+        Some(
+            EntityClaims::new(entity_id, linked_poi_ids, claims)
+                .with_image_files(image_files)
+                .with_sitelink_count(self.sitelinks),
+        )
+    }
+
+    /// Statements that survive `ranks`, judged per property over every
+    /// statement including those without a usable value.
+    fn kept_statements(&self, ranks: RankFilter) -> Vec<&SparqlStatement> {
+        let mut by_property: BTreeMap<&str, Vec<&SparqlStatement>> = BTreeMap::new();
+        for statement in self.statements.values() {
+            by_property
+                .entry(statement.property_id.as_str())
+                .or_default()
+                .push(statement);
+        }
+        by_property
+            .into_values()
+            .flat_map(|statements| {
+                let best = ranks.best_rank(statements.iter().map(|statement| statement.rank));
+                statements
+                    .into_iter()
+                    .filter(move |statement| ranks.keeps(statement.rank, best))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct RawSparqlResults {
+    pub(super) results: RawBindings,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct RawBindings {
+    #[serde(default)]
+    pub(super) bindings: Vec<RawBinding>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(super) struct RawBinding {
+    item: RawTerm,
+    #[serde(default)]
+    sitelinks: Option<RawTerm>,
+    #[serde(default)]
+    property: Option<RawTerm>,
+    #[serde(default)]
+    statement: Option<RawTerm>,
+    #[serde(default)]
+    rank: Option<RawTerm>,
+    #[serde(default)]
+    value: Option<RawTerm>,
+}
+
+impl RawBinding {
+    pub(super) fn record(self, entities: &mut BTreeMap<String, SparqlEntity>) {
+        let Some(entity_id) = normalize_wikidata_id(&self.item.value) else {
+            return;
+        };
+        let entity = entities.entry(entity_id).or_default();
+        if let Some(sitelinks) = self.sitelinks {
+            entity.sitelinks = sitelinks.value.trim().parse().ok();
+        }
+        let (Some(property), Some(statement), Some(rank)) =
+            (self.property, self.statement, self.rank)
+        else {
+            return;
+        };
+        let Some(property_id) = property.value.strip_prefix(ENTITY_PREFIX) else {
+            return;
+        };
+        entity
+            .statements
+            .entry(statement.value)
+            .or_insert_with(|| SparqlStatement {
+                property_id: property_id.to_owned(),
+                rank: parse_rank(&rank.value),
+                value: self.value.as_ref().and_then(RawTerm::claim_value),
+            });
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RawTerm {
+    #[serde(rename = "type")]
+    kind: String,
+    value: String,
+    #[serde(default)]
+    datatype: Option<String>,
+    #[serde(default, rename = "xml:lang")]
+    lang: Option<String>,
+}
+
+impl RawTerm {
+    /// Convert a statement value, mirroring the value types dumps support.
+    fn claim_value(&self) -> Option<ClaimValue> {
+        match self.kind.as_str() {
+            "uri" => uri_value(&self.value),
+            // Monolingual text carries a language tag; dumps skip it too.
+            "literal" if self.lang.is_none() => {
+                literal_value(&self.value, self.datatype.as_deref())
+            }
+            _ => None,
+        }
+    }
+}
+
+fn uri_value(uri: &str) -> Option<ClaimValue> {
+    if let Some(id) = uri.strip_prefix(ENTITY_PREFIX) {
+        return normalize_wikidata_id(id).map(ClaimValue::Entity);
+    }
+    if let Some(file) = uri.strip_prefix(COMMONS_FILE_PREFIX) {
+        return non_empty(&percent_decode_str(file).decode_utf8_lossy()).map(ClaimValue::Literal);
+    }
+    if uri.contains("/.well-known/genid/") {
+        // Skolemized blank node standing in for a `somevalue` snak.
+        return None;
+    }
+    non_empty(uri).map(ClaimValue::Literal)
+}
+
+fn literal_value(value: &str, datatype: Option<&str>) -> Option<ClaimValue> {
+    let Some(datatype) = datatype else {
+        return non_empty(value).map(ClaimValue::Literal);
+    };
+    match datatype.strip_prefix(XSD_PREFIX)? {
+        "string" => non_empty(value).map(ClaimValue::Literal),
+        // Wikidata encodes times and amounts with an explicit sign.
+        "dateTime" | "decimal" | "integer" => {
+            non_empty(value).map(|value| ClaimValue::Literal(signed(value)))
+        }
+        _ => None,
+    }
+}
+
+fn signed(value: String) -> String {
+    if value.starts_with(['+', '-']) {
+        value
+    } else {
+        format!("+{value}")
+    }
+}
+
+fn parse_rank(iri: &str) -> ClaimRank {
+    match iri.strip_prefix(RANK_PREFIX) {
+        Some("PreferredRank") => ClaimRank::Preferred,
+        Some("DeprecatedRank") => ClaimRank::Deprecated,
+        _ => ClaimRank::Normal,
+    }
+}
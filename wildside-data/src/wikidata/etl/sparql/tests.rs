@@ -0,0 +1,250 @@
+//! Unit tests for SPARQL enrichment.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+
+use geo::Coord;
+use rstest::{fixture, rstest};
+use wildside_core::{PointOfInterest, Tags};
+
+use super::*;
+use crate::wikidata::etl::{Claim, ClaimPropertySet, ClaimRank, RankFilter};
+
+/// Replays canned responses in order and records each query.
+struct StubTransport {
+    responses: RefCell<VecDeque<Result<String, TransportError>>>,
+    queries: RefCell<Vec<String>>,
+}
+
+impl StubTransport {
+    fn new(responses: impl IntoIterator<Item = Result<String, TransportError>>) -> Self {
+        Self {
+            responses: RefCell::new(responses.into_iter().collect()),
+            queries: RefCell::new(Vec::new()),
+        }
+    }
+}
+
+impl SparqlTransport for StubTransport {
+    fn query(&self, sparql: &str) -> Result<Vec<u8>, TransportError> {
+        self.queries.borrow_mut().push(sparql.to_owned());
+        self.responses
+            .borrow_mut()
+            .pop_front()
+            .expect("unexpected extra query")
+            .map(String::into_bytes)
+    }
+}
+
+fn term(kind: &str, value: &str) -> String {
+    format!(r#"{{"type":"{kind}","value":"{value}"}}"#)
+}
+
+/// Binding for a statement of `Q64`, the only entity the fixtures return.
+fn row(statement: &str, property: &str, rank: &str, value: &str) -> String {
+    format!(
+        r#"{{"item":{},"sitelinks":{},"property":{},"statement":{},"rank":{},"value":{value}}}"#,
+        term("uri", &format!("{ENTITY_PREFIX}Q64")),
+        term("literal", "230"),
+        term("uri", &format!("{ENTITY_PREFIX}{property}")),
+        term("uri", &format!("{ENTITY_PREFIX}statement/{statement}")),
+        term("uri", &format!("{RANK_PREFIX}{rank}")),
+    )
+}
+
+fn page(rows: &[String]) -> String {
+    format!(r#"{{"results":{{"bindings":[{}]}}}}"#, rows.join(","))
+}
+
+fn http_error(status: u16) -> TransportError {
+    TransportError::Http {
+        url: DEFAULT_SPARQL_URL.to_owned(),
+        status,
+        message: "stub".to_owned(),
+    }
+}
+
+fn no_wait() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 3,
+        initial_backoff: Duration::ZERO,
+    }
+}
+
+#[fixture]
+fn links() -> PoiEntityLinks {
+    let poi = PointOfInterest::new(
+        7,
+        Coord { x: 0.0, y: 0.0 },
+        Tags::from([("wikidata".into(), "Q64".into())]),
+    );
+    PoiEntityLinks::from_pois([&poi])
+}
+
+#[fixture]
+fn properties() -> ClaimPropertySet {
+    "P1435,P571".parse().expect("valid properties")
+}
+
+fn berlin_rows() -> Vec<String> {
+    vec![
+        row(
+            "s1",
+            "P1435",
+            "PreferredRank",
+            &term("uri", &format!("{ENTITY_PREFIX}Q9259")),
+        ),
+        row(
+            "s2",
+            "P1435",
+            "NormalRank",
+            &term("uri", &format!("{ENTITY_PREFIX}Q1")),
+        ),
+        row(
+            "s3",
+            "P18",
+            "NormalRank",
+            &term(
+                "uri",
+                &format!("{COMMONS_FILE_PREFIX}Brandenburger%20Tor.jpg"),
+            ),
+        ),
+        row(
+            "s4",
+            "P571",
+            "NormalRank",
+            &format!(
+                r#"{{"type":"literal","value":"1237-01-01T00:00:00Z","datatype":"{XSD_PREFIX}dateTime"}}"#
+            ),
+        ),
+    ]
+}
+
+#[rstest]
+fn converts_bindings_into_entity_claims(links: PoiEntityLinks, properties: ClaimPropertySet) {
+    let transport = StubTransport::new([Ok(page(&berlin_rows()))]);
+    let source = WikidataSparqlSource::with_transport(transport);
+    let selection = ClaimSelection::new(&properties).with_ranks(RankFilter::Truthy);
+
+    let claims = source
+        .extract_linked_entity_claims(&links, selection)
+        .expect("extraction should succeed");
+
+    assert_eq!(
+        claims,
+        [EntityClaims::new(
+            "Q64".to_owned(),
+            vec![7],
+            vec![
+                Claim::entity("P1435", "Q9259").with_rank(ClaimRank::Preferred),
+                Claim::literal("P571", "+1237-01-01T00:00:00Z"),
+            ],
+        )
+        .with_image_files(vec!["Brandenburger Tor.jpg".to_owned()])
+        .with_sitelink_count(Some(230))]
+    );
+    let queries = source.transport.queries.borrow();
+    let query = queries.first().expect("one query");
+    assert!(query.contains("VALUES ?item { wd:Q64 }"));
+    assert!(query.contains("VALUES ?property { wd:P1435 wd:P18 wd:P571 }"));
+}
+
+#[rstest]
+fn pages_until_a_short_page(links: PoiEntityLinks, properties: ClaimPropertySet) {
+    let rows = berlin_rows();
+    let (first, rest) = rows.split_at(2);
+    let transport = StubTransport::new([Ok(page(first)), Ok(page(rest)), Ok(page(&[]))]);
+    let source = WikidataSparqlSource::with_transport(transport).with_page_size(2);
+
+    let claims = source
+        .extract_linked_entity_claims(&links, ClaimSelection::new(&properties))
+        .expect("extraction should succeed");
+
+    let [berlin] = claims.as_slice() else {
+        panic!("expected one entity, found {claims:?}");
+    };
+    assert_eq!(berlin.claims.len(), 3);
+    let queries = source.transport.queries.borrow();
+    assert_eq!(queries.len(), 3, "a full final page needs one more probe");
+    assert!(queries[0].contains("LIMIT 2\nOFFSET 0"));
+    assert!(queries[1].contains("OFFSET 2"));
+    assert!(queries[2].contains("OFFSET 4"));
+}
+
+#[rstest]
+fn retries_transient_failures(links: PoiEntityLinks, properties: ClaimPropertySet) {
+    let transport = StubTransport::new([
+        Err(http_error(429)),
+        Err(http_error(503)),
+        Ok(page(&berlin_rows())),
+    ]);
+    let source = WikidataSparqlSource::with_transport(transport).with_retry(no_wait());
+
+    let claims = source
+        .extract_linked_entity_claims(&links, ClaimSelection::new(&properties))
+        .expect("third attempt should succeed");
+
+    assert_eq!(claims.len(), 1);
+    assert_eq!(source.transport.queries.borrow().len(), 3);
+}
+
+#[rstest]
+#[case::client_error(vec![Err(http_error(400))], 1)]
+#[case::exhausted(vec![Err(http_error(500)), Err(http_error(502)), Err(http_error(504))], 3)]
+fn gives_up_on_persistent_failures(
+    links: PoiEntityLinks,
+    properties: ClaimPropertySet,
+    #[case] responses: Vec<Result<String, TransportError>>,
+    #[case] expected_attempts: u32,
+) {
+    let source =
+        WikidataSparqlSource::with_transport(StubTransport::new(responses)).with_retry(no_wait());
+
+    let err = source
+        .extract_linked_entity_claims(&links, ClaimSelection::new(&properties))
+        .expect_err("query should fail");
+
+    assert!(
+        matches!(err, WikidataSparqlError::Transport { attempts, .. } if attempts == expected_attempts),
+        "expected Transport error after {expected_attempts} attempts, found {err:?}"
+    );
+}
+
+#[rstest]
+fn skips_unknown_entities_and_batches_ids(properties: ClaimPropertySet) {
+    let pois: Vec<PointOfInterest> = ["Q1", "Q64", "Q90"]
+        .into_iter()
+        .zip(1..)
+        .map(|(entity_id, id)| {
+            PointOfInterest::new(
+                id,
+                Coord { x: 0.0, y: 0.0 },
+                Tags::from([("wikidata".into(), entity_id.into())]),
+            )
+        })
+        .collect();
+    let links = PoiEntityLinks::from_pois(&pois);
+    let transport = StubTransport::new([Ok(page(&berlin_rows())), Ok(page(&[]))]);
+    let source = WikidataSparqlSource::with_transport(transport).with_batch_size(2);
+
+    let claims = source
+        .extract_linked_entity_claims(&links, ClaimSelection::new(&properties))
+        .expect("extraction should succeed");
+
+    let ids: Vec<&str> = claims
+        .iter()
+        .map(|claim| claim.entity_id.as_str())
+        .collect();
+    assert_eq!(ids, ["Q64"]);
+    let queries = source.transport.queries.borrow();
+    assert!(queries[0].contains("VALUES ?item { wd:Q1 wd:Q64 }"));
+    assert!(queries[1].contains("VALUES ?item { wd:Q90 }"));
+}
+
+#[rstest]
+#[case(1, Duration::from_secs(2))]
+#[case(2, Duration::from_secs(4))]
+#[case(3, Duration::from_secs(8))]
+fn backoff_doubles_per_attempt(#[case] attempt: u32, #[case] expected: Duration) {
+    assert_eq!(RetryPolicy::default().backoff(attempt), expected);
+}
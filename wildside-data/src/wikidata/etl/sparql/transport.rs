@@ -0,0 +1,80 @@
+//! Sending SPARQL queries to an endpoint.
+
+use reqwest::Client;
+use reqwest::header::ACCEPT;
+use tokio::runtime::Runtime;
+
+use super::super::api::{blocking_runtime, http_client};
+#[cfg(doc)]
+use super::DEFAULT_SPARQL_URL;
+use super::{QUERY_TIMEOUT, SPARQL_RESULTS_JSON, WikidataSparqlError};
+use crate::wikidata::dump::{TransportError, convert_reqwest_error};
+
+/// Transport executing SPARQL queries.
+///
+/// Implementations return the raw SPARQL JSON results body so tests can
+/// substitute canned responses for HTTP.
+pub trait SparqlTransport {
+    /// Run `sparql` and return the `application/sparql-results+json` body.
+    ///
+    /// # Errors
+    /// Returns [`TransportError`] when the request fails.
+    fn query(&self, sparql: &str) -> Result<Vec<u8>, TransportError>;
+}
+
+/// HTTP implementation of [`SparqlTransport`].
+///
+/// Queries are sent as form-encoded `POST` requests, which keeps long `VALUES`
+/// blocks clear of URL length limits. Requests block on an internal
+/// current-thread Tokio runtime, so calls must not be made from inside another
+/// Tokio runtime.
+#[derive(Debug)]
+pub struct HttpSparqlTransport {
+    client: Client,
+    runtime: Runtime,
+    endpoint: String,
+}
+
+impl HttpSparqlTransport {
+    /// Build a transport for the endpoint at `endpoint` (e.g. [`DEFAULT_SPARQL_URL`]).
+    ///
+    /// # Errors
+    /// Returns [`WikidataSparqlError`] when the HTTP client or runtime cannot
+    /// be built.
+    pub fn new(endpoint: impl Into<String>) -> Result<Self, WikidataSparqlError> {
+        let client = http_client(QUERY_TIMEOUT)
+            .map_err(|source| WikidataSparqlError::HttpClient { source })?;
+        let runtime =
+            blocking_runtime().map_err(|source| WikidataSparqlError::Runtime { source })?;
+        Ok(Self {
+            client,
+            runtime,
+            endpoint: endpoint.into(),
+        })
+    }
+
+    async fn fetch(&self, sparql: &str) -> Result<Vec<u8>, TransportError> {
+        let url = self.endpoint.as_str();
+        let response = self
+            .client
+            .post(url)
+            .header(ACCEPT, SPARQL_RESULTS_JSON)
+            .form(&[("query", sparql)])
+            .send()
+            .await
+            .map_err(|err| convert_reqwest_error(err, url))?
+            .error_for_status()
+            .map_err(|err| convert_reqwest_error(err, url))?;
+        let body = response
+            .bytes()
+            .await
+            .map_err(|err| convert_reqwest_error(err, url))?;
+        Ok(body.to_vec())
+    }
+}
+
+impl SparqlTransport for HttpSparqlTransport {
+    fn query(&self, sparql: &str) -> Result<Vec<u8>, TransportError> {
+        self.runtime.block_on(self.fetch(sparql))
+    }
+}
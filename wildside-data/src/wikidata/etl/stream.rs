@@ -0,0 +1,273 @@
+//! Lazily yield linked entity claims from a dump.
+//!
+//! [`LinkedEntityClaims`] performs the same line-by-line extraction as
+//! [`extract_linked_entity_claims_for`](super::extract_linked_entity_claims_for)
+//! but hands each entity to the caller as soon as it is parsed, so consumers
+//! such as [`persist_claims_iter`](crate::wikidata::store::persist_claims_iter)
+//! never hold the whole extraction in memory.
+//! [`LinkedEntityClaims::parallel`] moves the parsing onto a worker pool.
+
+use std::{
+    io::{BufRead, BufReader, Read},
+    num::NonZeroUsize,
+    thread,
+};
+
+use wildside_core::store::LinkFilter;
+
+use super::{
+    ClaimSelection, EntityClaims, EntityContext, PoiEntityLinks, WikidataEtlError, entity_span,
+    is_filtered_out,
+    parallel::{Extraction, ParallelLinkedEntityClaims},
+    process_entity_claims,
+};
+
+/// Iterator over the claims of linked entities in a Wikidata dump.
+///
+/// Entities are yielded in dump order. The iterator stops after the first
+/// error, which it yields once.
+pub struct LinkedEntityClaims<'a, R> {
+    reader: BufReader<R>,
+    links: &'a PoiEntityLinks,
+    filter: &'a LinkFilter,
+    selection: ClaimSelection<'a>,
+    line: Vec<u8>,
+    line_number: usize,
+    finished: bool,
+}
+
+impl<R> std::fmt::Debug for LinkedEntityClaims<'_, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LinkedEntityClaims")
+            .field("line_number", &self.line_number)
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Stream the claims chosen by `selection`, pre-filtering with `filter`.
+///
+/// # Examples
+/// ```
+/// use std::io::Cursor;
+/// use geo::Coord;
+/// use wildside_core::{PointOfInterest, Tags};
+/// use wildside_data::wikidata::etl::{
+///     ClaimPropertySet, ClaimSelection, PoiEntityLinks, stream_linked_entity_claims,
+/// };
+///
+/// let poi = PointOfInterest::new(
+///     1,
+///     Coord { x: 13.4, y: 52.5 },
+///     Tags::from([("wikidata".into(), "Q64".into())]),
+/// );
+/// let links = PoiEntityLinks::from_pois([&poi]);
+/// let filter = links.to_link_filter();
+/// let properties = ClaimPropertySet::default();
+/// let dump = Cursor::new("{\"id\":\"Q1\",\"claims\":{}}\n{\"id\":\"Q64\",\"claims\":{}}\n");
+///
+/// let mut stream =
+///     stream_linked_entity_claims(dump, &links, &filter, ClaimSelection::new(&properties));
+/// assert_eq!(stream.next().transpose()?.map(|claims| claims.entity_id), Some("Q64".into()));
+/// assert!(stream.next().is_none());
+/// # Ok::<(), wildside_data::wikidata::etl::WikidataEtlError>(())
+/// ```
+pub fn stream_linked_entity_claims<'a, R>(
+    reader: R,
+    links: &'a PoiEntityLinks,
+    filter: &'a LinkFilter,
+    selection: ClaimSelection<'a>,
+) -> LinkedEntityClaims<'a, R>
+where
+    R: Read,
+{
+    LinkedEntityClaims {
+        reader: BufReader::new(reader),
+        links,
+        filter,
+        selection,
+        line: Vec::new(),
+        line_number: 0,
+        finished: links.is_empty(),
+    }
+}
+
+impl<R: Read> LinkedEntityClaims<'_, R> {
+    /// Read the next line, returning `false` at end of input.
+    fn read_line(&mut self) -> Result<bool, WikidataEtlError> {
+        self.line.clear();
+        let read = self
+            .reader
+            .read_until(b'\n', &mut self.line)
+            .map_err(|source| WikidataEtlError::ReadLine {
+                source,
+                line: self.line_number + 1,
+            })?;
+        self.line_number += 1;
+        Ok(read != 0)
+    }
+
+    /// Parse the current line in place, returning claims when it holds a
+    /// linked entity.
+    fn current_entity(&mut self) -> Result<Option<EntityClaims>, WikidataEtlError> {
+        let Some(span) = entity_span(&self.line) else {
+            return Ok(None);
+        };
+        let Some(json) = self.line.get_mut(span) else {
+            return Ok(None);
+        };
+        if is_filtered_out(json, self.filter) {
+            return Ok(None);
+        }
+        let context = EntityContext {
+            links: self.links,
+            selection: self.selection,
+            line_number: self.line_number,
+        };
+        process_entity_claims(json, &context)
+    }
+
+    fn advance(&mut self) -> Result<Option<EntityClaims>, WikidataEtlError> {
+        while self.read_line()? {
+            if let Some(claims) = self.current_entity()? {
+                return Ok(Some(claims));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<R> LinkedEntityClaims<'_, R>
+where
+    R: Read + Send + 'static,
+{
+    /// Parse the rest of the dump using one worker per available CPU.
+    #[must_use]
+    pub fn parallel(self) -> ParallelLinkedEntityClaims {
+        let workers = thread::available_parallelism().unwrap_or(NonZeroUsize::MIN);
+        self.parallel_with_workers(workers)
+    }
+
+    /// Parse the rest of the dump using exactly `workers` parser threads.
+    ///
+    /// A reader thread batches the remaining lines onto a channel shared by
+    /// the workers, and the returned iterator yields the same entities, in
+    /// the same order, as this one would.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::{io::Cursor, num::NonZeroUsize};
+    /// use geo::Coord;
+    /// use wildside_core::{PointOfInterest, Tags};
+    /// use wildside_data::wikidata::etl::{
+    ///     ClaimPropertySet, ClaimSelection, PoiEntityLinks, stream_linked_entity_claims,
+    /// };
+    ///
+    /// let poi = PointOfInterest::new(
+    ///     1,
+    ///     Coord { x: 13.4, y: 52.5 },
+    ///     Tags::from([("wikidata".into(), "Q64".into())]),
+    /// );
+    /// let links = PoiEntityLinks::from_pois([&poi]);
+    /// let filter = links.to_link_filter();
+    /// let properties = ClaimPropertySet::default();
+    /// let dump = Cursor::new("{\"id\":\"Q1\",\"claims\":{}}\n{\"id\":\"Q64\",\"claims\":{}}\n");
+    ///
+    /// let mut stream =
+    ///     stream_linked_entity_claims(dump, &links, &filter, ClaimSelection::new(&properties))
+    ///         .parallel_with_workers(NonZeroUsize::new(2).expect("non-zero"));
+    /// assert_eq!(stream.next().transpose()?.map(|claims| claims.entity_id), Some("Q64".into()));
+    /// assert!(stream.next().is_none());
+    /// # Ok::<(), wildside_data::wikidata::etl::WikidataEtlError>(())
+    /// ```
+    #[must_use]
+    pub fn parallel_with_workers(self, workers: NonZeroUsize) -> ParallelLinkedEntityClaims {
+        if self.finished {
+            return ParallelLinkedEntityClaims::empty();
+        }
+        let extraction = Extraction {
+            links: self.links.clone(),
+            filter: self.filter.clone(),
+            properties: self.selection.properties.clone(),
+            ranks: self.selection.ranks,
+        };
+        ParallelLinkedEntityClaims::spawn(self.reader, extraction, self.line_number, workers)
+    }
+}
+
+impl<R: Read> Iterator for LinkedEntityClaims<'_, R> {
+    type Item = Result<EntityClaims, WikidataEtlError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let next = self.advance().transpose();
+        if !matches!(next, Some(Ok(_))) {
+            self.finished = true;
+        }
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for streaming claim extraction.
+
+    use std::io::Cursor;
+
+    use geo::Coord;
+    use rstest::rstest;
+    use wildside_core::{PointOfInterest, Tags};
+
+    use super::*;
+    use crate::wikidata::etl::ClaimPropertySet;
+
+    fn links(entity_ids: &[&str]) -> PoiEntityLinks {
+        let pois: Vec<_> = (1_u64..)
+            .zip(entity_ids)
+            .map(|(id, entity)| {
+                PointOfInterest::new(
+                    id,
+                    Coord { x: 0.0, y: 0.0 },
+                    Tags::from([("wikidata".into(), (*entity).into())]),
+                )
+            })
+            .collect();
+        PoiEntityLinks::from_pois(pois.iter())
+    }
+
+    #[rstest]
+    fn yields_linked_entities_in_dump_order() {
+        let links = links(&["Q64", "Q1"]);
+        let filter = links.to_link_filter();
+        let properties = ClaimPropertySet::default();
+        let dump = Cursor::new(
+            "[\n{\"id\":\"Q64\",\"claims\":{}},\n{\"id\":\"Q2\",\"claims\":{}},\n{\"id\":\"Q1\",\"claims\":{}}\n]\n",
+        );
+
+        let ids: Vec<String> =
+            stream_linked_entity_claims(dump, &links, &filter, ClaimSelection::new(&properties))
+                .map(|claims| claims.expect("valid entity").entity_id)
+                .collect();
+
+        assert_eq!(ids, ["Q64", "Q1"]);
+    }
+
+    #[rstest]
+    fn stops_after_first_error() {
+        let links = links(&["Q64"]);
+        let filter = links.to_link_filter();
+        let properties = ClaimPropertySet::default();
+        let dump = Cursor::new("{\"id\":\"Q64\",\"claims\":\n{\"id\":\"Q64\",\"claims\":{}}\n");
+
+        let mut stream =
+            stream_linked_entity_claims(dump, &links, &filter, ClaimSelection::new(&properties));
+
+        assert!(matches!(
+            stream.next(),
+            Some(Err(WikidataEtlError::ParseEntity { line: 1, .. }))
+        ));
+        assert!(stream.next().is_none());
+    }
+}
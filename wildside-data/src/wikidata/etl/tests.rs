@@ -3,13 +3,15 @@
 mod behaviour;
 
 use super::{
-    EntityClaims, PoiEntityLinks, WikidataEtlError, extract_linked_entity_claims,
-    normalize_wikidata_id,
+    Claim, ClaimPropertySet, ClaimRank, ClaimSelection, ClaimValue, EntityClaims, PoiEntityLinks,
+    RankFilter, WikidataEtlError, entity_span, extract_linked_entity_claims,
+    extract_linked_entity_claims_for, extract_linked_entity_claims_with_filter,
+    normalize_wikidata_id, peek_entity_id,
 };
 use geo::Coord;
 use rstest::{fixture, rstest};
 use std::io::Cursor;
-use wildside_core::{PointOfInterest, Tags};
+use wildside_core::{PointOfInterest, Tags, store::LinkFilter};
 
 #[fixture]
 fn poi_with_wikidata() -> PointOfInterest {
@@ -66,7 +68,7 @@ fn extract_claims_for_linked_entity(poi_with_wikidata: PointOfInterest) {
         vec![EntityClaims::new(
             "Q64".into(),
             vec![7],
-            vec!["Q9259".into()]
+            vec![Claim::entity("P1435", "Q9259")]
         )]
     );
 }
@@ -109,3 +111,236 @@ fn reports_parse_errors(poi_with_wikidata: PointOfInterest) {
     };
     assert_eq!(line, 1, "malformed JSON should be flagged on line 1");
 }
+
+#[rstest]
+#[case(r#"{"type":"item","id":"Q64","claims":{}}"#, Some("Q64"))]
+#[case(r#"{ "id" : "Q42" }"#, Some("Q42"))]
+#[case(r#"{"claims":{"P1435":[{"id":"Q1"}]},"id":"Q64"}"#, None)]
+#[case(r#"{"labels":["id"],"id":"Q64"}"#, None)]
+#[case(r#"["id"]"#, None)]
+fn peeks_top_level_entity_id(#[case] line: &str, #[case] expected: Option<&str>) {
+    assert_eq!(peek_entity_id(line.as_bytes()), expected);
+}
+
+#[rstest]
+#[case("[\n", None)]
+#[case("]\n", None)]
+#[case("  \n", None)]
+#[case("{\"id\":\"Q64\"},\n", Some(0..12))]
+#[case(",{\"id\":\"Q64\"}\n", Some(1..13))]
+#[case("  {\"id\":\"Q64\"} , \r\n", Some(2..14))]
+fn locates_entity_span(#[case] line: &str, #[case] expected: Option<std::ops::Range<usize>>) {
+    assert_eq!(entity_span(line.as_bytes()), expected);
+}
+
+#[rstest]
+fn decodes_escaped_strings(poi_with_wikidata: PointOfInterest) {
+    let links = PoiEntityLinks::from_pois([&poi_with_wikidata]);
+    let dump = Cursor::new(concat!(
+        r#"{"id":"Q64","claims":{"P18":["#,
+        r#"{"mainsnak":{"snaktype":"value","datavalue":{"type":"string","value":"Caf\u00e9 \"Einstein\".jpg"}}}"#,
+        r#"]}}"#,
+    ));
+
+    let claims = extract_linked_entity_claims(dump, &links).expect("parsing should succeed");
+
+    assert_eq!(claims[0].image_files, ["Caf\u{e9} \"Einstein\".jpg"]);
+}
+
+#[rstest]
+fn builds_link_filter_from_links(poi_with_wikidata: PointOfInterest) {
+    let filter = PoiEntityLinks::from_pois([&poi_with_wikidata]).to_link_filter();
+
+    assert!(filter.may_contain_entity("Q64"));
+    assert!(filter.may_contain_poi(7));
+}
+
+#[rstest]
+fn filter_skips_unlinked_entities_without_parsing(poi_with_wikidata: PointOfInterest) {
+    let links = PoiEntityLinks::from_pois([&poi_with_wikidata]);
+    let filter = links.to_link_filter();
+    // The first entity is malformed; the filter must discard it unparsed.
+    let dump = Cursor::new(concat!(
+        r#"{"id":"Q1","claims": ["#,
+        "\n",
+        r#"{"id":"Q64","claims":{}}"#,
+    ));
+
+    let claims = extract_linked_entity_claims_with_filter(dump, &links, &filter)
+        .expect("filtered entity should not be parsed");
+
+    assert_eq!(
+        claims,
+        vec![EntityClaims::new("Q64".into(), vec![7], Vec::new())]
+    );
+}
+
+#[rstest]
+fn empty_filter_skips_all_peekable_entities(poi_with_wikidata: PointOfInterest) {
+    let links = PoiEntityLinks::from_pois([&poi_with_wikidata]);
+    let filter = LinkFilter::with_capacity(1);
+    let dump = Cursor::new(r#"{"id":"Q64","claims":{}}"#);
+
+    let claims = extract_linked_entity_claims_with_filter(dump, &links, &filter)
+        .expect("parsing should succeed");
+
+    assert!(claims.is_empty());
+}
+
+#[rstest]
+fn extracts_image_file_names(poi_with_wikidata: PointOfInterest) {
+    let links = PoiEntityLinks::from_pois([&poi_with_wikidata]);
+    let dump = Cursor::new(concat!(
+        r#"{"id":"Q64","claims":{"P18":["#,
+        r#"{"mainsnak":{"snaktype":"value","datavalue":{"type":"string","value":" Skyline.jpg "}}},"#,
+        r#"{"mainsnak":{"snaktype":"value","datavalue":{"type":"string","value":"Gate.jpg"}}},"#,
+        r#"{"mainsnak":{"snaktype":"value","datavalue":{"type":"string","value":"Gate.jpg"}}},"#,
+        r#"{"mainsnak":{"snaktype":"somevalue"}}"#,
+        r#"]}}"#,
+    ));
+
+    let claims = extract_linked_entity_claims(dump, &links).expect("parsing should succeed");
+
+    assert_eq!(
+        claims,
+        vec![
+            EntityClaims::new("Q64".into(), vec![7], Vec::new())
+                .with_image_files(vec!["Gate.jpg".into(), "Skyline.jpg".into()])
+        ]
+    );
+}
+
+#[rstest]
+#[case::counted(r#"{"id":"Q64","sitelinks":{"enwiki":{"site":"enwiki","title":"Berlin"},"dewiki":{"site":"dewiki","title":"Berlin"}}}"#, Some(2))]
+#[case::empty(r#"{"id":"Q64","sitelinks":{}}"#, Some(0))]
+#[case::absent(r#"{"id":"Q64"}"#, None)]
+fn extracts_sitelink_counts(
+    poi_with_wikidata: PointOfInterest,
+    #[case] record: &'static str,
+    #[case] expected: Option<u32>,
+) {
+    let links = PoiEntityLinks::from_pois([&poi_with_wikidata]);
+
+    let claims =
+        extract_linked_entity_claims(Cursor::new(record), &links).expect("parsing should succeed");
+
+    assert_eq!(
+        claims,
+        vec![EntityClaims::new("Q64".into(), vec![7], Vec::new()).with_sitelink_count(expected)]
+    );
+}
+
+#[rstest]
+fn extracts_configured_properties(poi_with_wikidata: PointOfInterest) {
+    let links = PoiEntityLinks::from_pois([&poi_with_wikidata]);
+    let properties: ClaimPropertySet = "P31,P571".parse().expect("valid properties");
+    let dump = Cursor::new(concat!(
+        r#"{"id":"Q64","claims":{"#,
+        r#""P31":[{"mainsnak":{"snaktype":"value","datavalue":{"type":"wikibase-entityid","value":{"id":"Q515"}}}}],"#,
+        r#""P571":[{"mainsnak":{"snaktype":"value","datavalue":{"type":"time","value":{"time":"+1237-00-00T00:00:00Z","precision":9}}}}],"#,
+        r#""P1435":[{"mainsnak":{"snaktype":"value","datavalue":{"type":"wikibase-entityid","value":{"id":"Q9259"}}}}]"#,
+        r#"}}"#,
+    ));
+
+    let claims = extract_linked_entity_claims_for(
+        dump,
+        &links,
+        &links.to_link_filter(),
+        ClaimSelection::new(&properties),
+    )
+    .expect("parsing should succeed");
+
+    assert_eq!(
+        claims,
+        vec![EntityClaims::new(
+            "Q64".into(),
+            vec![7],
+            vec![
+                Claim::entity("P31", "Q515"),
+                Claim::literal("P571", "+1237-00-00T00:00:00Z"),
+            ]
+        )]
+    );
+}
+
+/// `P31` statements of every rank; `P18` images with a deprecated file.
+const RANKED_ENTITY: &str = concat!(
+    r#"{"id":"Q64","claims":{"#,
+    r#""P31":["#,
+    r#"{"mainsnak":{"snaktype":"value","datavalue":{"type":"wikibase-entityid","value":{"id":"Q515"}}},"rank":"preferred","#,
+    r#""qualifiers":{"P580":[{"snaktype":"value","property":"P580","datavalue":{"type":"time","value":{"time":"+1990-10-03T00:00:00Z","precision":11}}}]}},"#,
+    r#"{"mainsnak":{"snaktype":"value","datavalue":{"type":"wikibase-entityid","value":{"id":"Q1549591"}}},"rank":"normal"},"#,
+    r#"{"mainsnak":{"snaktype":"value","datavalue":{"type":"wikibase-entityid","value":{"id":"Q42744322"}}},"rank":"deprecated"}"#,
+    r#"],"#,
+    r#""P18":["#,
+    r#"{"mainsnak":{"snaktype":"value","datavalue":{"type":"string","value":"Old.jpg"}},"rank":"deprecated"},"#,
+    r#"{"mainsnak":{"snaktype":"value","datavalue":{"type":"string","value":"Skyline.jpg"}},"rank":"normal"}"#,
+    r#"]}}"#,
+);
+
+fn ranked_claims(ranks: RankFilter) -> Vec<EntityClaims> {
+    let poi = poi_with_wikidata();
+    let links = PoiEntityLinks::from_pois([&poi]);
+    let properties: ClaimPropertySet = "P31".parse().expect("valid properties");
+    extract_linked_entity_claims_for(
+        Cursor::new(RANKED_ENTITY),
+        &links,
+        &links.to_link_filter(),
+        ClaimSelection::new(&properties).with_ranks(ranks),
+    )
+    .expect("parsing should succeed")
+}
+
+fn preferred_city() -> Claim {
+    Claim::entity("P31", "Q515")
+        .with_rank(ClaimRank::Preferred)
+        .with_qualifier("P580", ClaimValue::Literal("+1990-10-03T00:00:00Z".into()))
+}
+
+#[rstest]
+fn extracts_ranks_and_qualifiers_for_all_statements() {
+    let claims = ranked_claims(RankFilter::All);
+
+    assert_eq!(
+        claims,
+        vec![
+            EntityClaims::new(
+                "Q64".into(),
+                vec![7],
+                vec![
+                    Claim::entity("P31", "Q1549591"),
+                    Claim::entity("P31", "Q42744322").with_rank(ClaimRank::Deprecated),
+                    preferred_city(),
+                ]
+            )
+            .with_image_files(vec!["Old.jpg".into(), "Skyline.jpg".into()])
+        ]
+    );
+}
+
+#[rstest]
+fn truthy_filter_keeps_best_ranked_statements() {
+    let claims = ranked_claims(RankFilter::Truthy);
+
+    assert_eq!(
+        claims,
+        vec![
+            EntityClaims::new("Q64".into(), vec![7], vec![preferred_city()])
+                .with_image_files(vec!["Skyline.jpg".into()])
+        ]
+    );
+}
+
+#[rstest]
+#[case::preferred_wins(&[ClaimRank::Normal, ClaimRank::Preferred], ClaimRank::Normal, false)]
+#[case::normal_without_preferred(&[ClaimRank::Normal, ClaimRank::Deprecated], ClaimRank::Normal, true)]
+#[case::deprecated_only(&[ClaimRank::Deprecated], ClaimRank::Deprecated, false)]
+fn truthy_filter_follows_best_rank(
+    #[case] ranks: &[ClaimRank],
+    #[case] rank: ClaimRank,
+    #[case] kept: bool,
+) {
+    let best = RankFilter::Truthy.best_rank(ranks.iter().copied());
+
+    assert_eq!(RankFilter::Truthy.keeps(rank, best), kept);
+}
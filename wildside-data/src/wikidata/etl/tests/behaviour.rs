@@ -1,10 +1,17 @@
 //! Behavioural coverage for extracting linked Wikidata claims.
 
-use super::super::{EntityClaims, PoiEntityLinks, WikidataEtlError, extract_linked_entity_claims};
+use super::super::{
+    Claim, DumpCompression, EntityClaims, PoiEntityLinks, WikidataEtlError,
+    extract_linked_entity_claims,
+};
+use flate2::{Compression, write::GzEncoder};
 use geo::Coord;
 use rstest::fixture;
 use rstest_bdd_macros::{given, scenario, then, when};
-use std::{cell::RefCell, io::Cursor};
+use std::{
+    cell::{Cell, RefCell},
+    io::{Cursor, Write},
+};
 use wildside_core::{PointOfInterest, Tags};
 
 #[fixture]
@@ -17,6 +24,11 @@ pub fn dump_bytes() -> RefCell<Vec<u8>> {
     RefCell::new(Vec::new())
 }
 
+#[fixture]
+pub fn dump_compression() -> Cell<DumpCompression> {
+    Cell::new(DumpCompression::Plain)
+}
+
 #[fixture]
 pub fn extraction_result() -> RefCell<Option<Result<Vec<EntityClaims>, WikidataEtlError>>> {
     RefCell::new(None)
@@ -44,6 +56,22 @@ fn dump_with_heritage(#[from(dump_bytes)] cell: &RefCell<Vec<u8>>) {
     *cell.borrow_mut() = br#"{"id":"Q64","claims":{"P1435":[{"mainsnak":{"snaktype":"value","datavalue":{"type":"wikibase-entityid","value":{"id":"Q9259"}}}}]}}"#.to_vec();
 }
 
+#[given("a gzip-compressed dump containing a heritage claim for the linked entity")]
+fn gzip_dump_with_heritage(
+    #[from(dump_bytes)] cell: &RefCell<Vec<u8>>,
+    #[from(dump_compression)] compression: &Cell<DumpCompression>,
+) {
+    dump_with_heritage(cell);
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(&cell.borrow())
+        .unwrap_or_else(|err| panic!("compress dump: {err}"));
+    *cell.borrow_mut() = encoder
+        .finish()
+        .unwrap_or_else(|err| panic!("finish gzip stream: {err}"));
+    compression.set(DumpCompression::Gzip);
+}
+
 #[given("a dump with malformed JSON for the linked entity")]
 fn dump_with_error(#[from(dump_bytes)] cell: &RefCell<Vec<u8>>) {
     *cell.borrow_mut() = br#"{"id":"Q64","claims": ["#.to_vec();
@@ -53,6 +81,7 @@ fn dump_with_error(#[from(dump_bytes)] cell: &RefCell<Vec<u8>>) {
 fn extract_claims(
     #[from(poi_links)] links_cell: &RefCell<Option<PoiEntityLinks>>,
     #[from(dump_bytes)] bytes_cell: &RefCell<Vec<u8>>,
+    #[from(dump_compression)] compression: &Cell<DumpCompression>,
     #[from(extraction_result)] result_cell: &RefCell<
         Option<Result<Vec<EntityClaims>, WikidataEtlError>>,
     >,
@@ -63,8 +92,8 @@ fn extract_claims(
         .cloned()
         .unwrap_or_else(|| panic!("POI links must be initialized"));
     let bytes = bytes_cell.borrow().clone();
-    let cursor = Cursor::new(bytes);
-    let outcome = extract_linked_entity_claims(cursor, &links);
+    let reader = compression.get().decode(Cursor::new(bytes));
+    let outcome = extract_linked_entity_claims(reader, &links);
     *result_cell.borrow_mut() = Some(outcome);
 }
 
@@ -85,7 +114,7 @@ fn heritage_recorded(
     let expected = vec![EntityClaims::new(
         "Q64".into(),
         vec![11],
-        vec!["Q9259".into()],
+        vec![Claim::entity("P1435", "Q9259")],
     )];
     assert_eq!(claims, &expected);
 }
@@ -113,16 +142,28 @@ fn parse_error(
 fn extract_heritage_claims(
     poi_links: RefCell<Option<PoiEntityLinks>>,
     dump_bytes: RefCell<Vec<u8>>,
+    dump_compression: Cell<DumpCompression>,
     extraction_result: RefCell<Option<Result<Vec<EntityClaims>, WikidataEtlError>>>,
 ) {
-    let _ = (poi_links, dump_bytes, extraction_result);
+    let _ = (poi_links, dump_bytes, dump_compression, extraction_result);
 }
 
 #[scenario(path = "tests/features/extract_wikidata_claims.feature", index = 1)]
 fn report_parse_failure(
     poi_links: RefCell<Option<PoiEntityLinks>>,
     dump_bytes: RefCell<Vec<u8>>,
+    dump_compression: Cell<DumpCompression>,
+    extraction_result: RefCell<Option<Result<Vec<EntityClaims>, WikidataEtlError>>>,
+) {
+    let _ = (poi_links, dump_bytes, dump_compression, extraction_result);
+}
+
+#[scenario(path = "tests/features/extract_wikidata_claims.feature", index = 2)]
+fn extract_claims_from_gzip_dump(
+    poi_links: RefCell<Option<PoiEntityLinks>>,
+    dump_bytes: RefCell<Vec<u8>>,
+    dump_compression: Cell<DumpCompression>,
     extraction_result: RefCell<Option<Result<Vec<EntityClaims>, WikidataEtlError>>>,
 ) {
-    let _ = (poi_links, dump_bytes, extraction_result);
+    let _ = (poi_links, dump_bytes, dump_compression, extraction_result);
 }
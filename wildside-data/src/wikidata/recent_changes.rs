@@ -0,0 +1,331 @@
+//! Incremental enrichment of linked entities edited since the last ingest.
+//!
+//! A full dump import is the baseline; [`RecentChangesSync`] keeps it fresh
+//! between dumps. It reads the watermark stored in `pois.db`, asks the
+//! Wikidata API which linked entities have been edited after it, re-fetches
+//! only those entities, and replaces their stored claims. The new watermark
+//! is written in the same transaction as the claims, so a failed run is
+//! simply retried from the previous watermark.
+#![forbid(unsafe_code)]
+
+use rusqlite::Connection;
+use thiserror::Error;
+
+use crate::wikidata::etl::{
+    ClaimSelection, EntityTransport, HttpEntityTransport, HttpRevisionTransport, PoiEntityLinks,
+    RevisionTransport, WikidataApiError, WikidataApiSource, WikidataRevisionSource,
+};
+use crate::wikidata::store::{
+    ClaimsQueryError, PersistClaimsError, initialise_schema, replace_claims, sync_watermark,
+};
+
+/// Outcome of a [`RecentChangesSync::run`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecentChangesReport {
+    /// Linked entities whose latest revision was checked.
+    pub checked: usize,
+    /// Entities whose claims were re-fetched and replaced.
+    pub refreshed: usize,
+    /// Watermark stored after the run, if any entity has been synced.
+    pub synced_through: Option<String>,
+}
+
+/// Refreshes the stored claims of linked entities edited upstream.
+///
+/// # Examples
+/// ```
+/// use geo::Coord;
+/// use rusqlite::Connection;
+/// use wildside_core::{PointOfInterest, Tags};
+/// use wildside_data::wikidata::dump::TransportError;
+/// use wildside_data::wikidata::etl::{
+///     ClaimPropertySet, ClaimSelection, EntityTransport, PoiEntityLinks, RevisionTransport,
+///     WikidataApiSource, WikidataRevisionSource,
+/// };
+/// use wildside_data::wikidata::recent_changes::RecentChangesSync;
+///
+/// struct Revisions;
+/// impl RevisionTransport for Revisions {
+///     fn latest_revisions(&self, _ids: &[&str]) -> Result<Vec<u8>, TransportError> {
+///         Ok(br#"{"query":{"pages":[{"ns":0,"title":"Q64","revisions":[{"timestamp":"2024-09-10T08:00:00Z"}]}]}}"#.to_vec())
+///     }
+/// }
+/// struct Entities;
+/// impl EntityTransport for Entities {
+///     fn get_entities(&self, _ids: &[&str]) -> Result<Vec<u8>, TransportError> {
+///         Ok(br#"{"entities":{"Q64":{"id":"Q64","claims":{"P1435":[{"mainsnak":{"snaktype":"value","datavalue":{"type":"wikibase-entityid","value":{"id":"Q9259"}}}}]}}}}"#.to_vec())
+///     }
+/// }
+///
+/// let mut conn = Connection::open_in_memory().expect("create in-memory database");
+/// conn.execute(
+///     "CREATE TABLE pois (id INTEGER PRIMARY KEY, lon REAL, lat REAL, tags TEXT)",
+///     [],
+/// )
+/// .expect("create pois table");
+/// conn.execute("INSERT INTO pois VALUES (1, 13.4, 52.5, '{}')", [])
+///     .expect("insert POI row");
+/// let poi = PointOfInterest::new(
+///     1,
+///     Coord { x: 13.4, y: 52.5 },
+///     Tags::from([("wikidata".into(), "Q64".into())]),
+/// );
+/// let links = PoiEntityLinks::from_pois([&poi]);
+/// let properties = ClaimPropertySet::default();
+/// let sync = RecentChangesSync::with_sources(
+///     WikidataRevisionSource::with_transport(Revisions),
+///     WikidataApiSource::with_transport(Entities),
+/// );
+///
+/// let report = sync.run(&mut conn, &links, ClaimSelection::new(&properties))?;
+///
+/// assert_eq!(report.refreshed, 1);
+/// assert_eq!(report.synced_through.as_deref(), Some("2024-09-10T08:00:00Z"));
+/// # Ok::<(), wildside_data::wikidata::recent_changes::RecentChangesError>(())
+/// ```
+#[derive(Debug)]
+pub struct RecentChangesSync<R = HttpRevisionTransport, E = HttpEntityTransport> {
+    revisions: WikidataRevisionSource<R>,
+    entities: WikidataApiSource<E>,
+    initial_since: Option<String>,
+}
+
+impl RecentChangesSync {
+    /// Build an HTTP-backed sync against the API at `endpoint`.
+    ///
+    /// # Errors
+    /// Returns [`WikidataApiError`] when either HTTP transport cannot be
+    /// built.
+    pub fn new(endpoint: impl Into<String>) -> Result<Self, WikidataApiError> {
+        let endpoint = endpoint.into();
+        Ok(Self::with_sources(
+            WikidataRevisionSource::new(endpoint.as_str())?,
+            WikidataApiSource::new(endpoint)?,
+        ))
+    }
+}
+
+impl<R: RevisionTransport, E: EntityTransport> RecentChangesSync<R, E> {
+    /// Build a sync over custom revision and entity sources.
+    pub const fn with_sources(
+        revisions: WikidataRevisionSource<R>,
+        entities: WikidataApiSource<E>,
+    ) -> Self {
+        Self {
+            revisions,
+            entities,
+            initial_since: None,
+        }
+    }
+
+    /// Watermark to use when the database has none yet, typically the
+    /// timestamp of the dump that seeded it.
+    ///
+    /// Without it the first run re-fetches every linked entity.
+    #[must_use]
+    pub fn with_initial_since(mut self, since: impl Into<String>) -> Self {
+        self.initial_since = Some(since.into());
+        self
+    }
+
+    /// Refresh the claims of every entity in `links` edited after the stored
+    /// watermark.
+    ///
+    /// # Errors
+    /// Returns [`RecentChangesError`] when the watermark cannot be read, the
+    /// API requests fail, or the refreshed claims cannot be persisted.
+    pub fn run(
+        &self,
+        connection: &mut Connection,
+        links: &PoiEntityLinks,
+        selection: ClaimSelection<'_>,
+    ) -> Result<RecentChangesReport, RecentChangesError> {
+        initialise_schema(connection).map_err(PersistClaimsError::from)?;
+        let since = sync_watermark(connection)?.or_else(|| self.initial_since.clone());
+        let changes = self.revisions.changed_since(links, since.as_deref())?;
+        let changed = links.restricted_to(changes.changed.iter().map(String::as_str));
+        let claims = self
+            .entities
+            .extract_linked_entity_claims(&changed, selection)?;
+        let synced_through = since.into_iter().chain(changes.latest_edit).max();
+        replace_claims(connection, &claims, synced_through.as_deref())?;
+        Ok(RecentChangesReport {
+            checked: links.entity_ids().count(),
+            refreshed: claims.len(),
+            synced_through,
+        })
+    }
+}
+
+/// Errors raised by [`RecentChangesSync::run`].
+#[derive(Debug, Error)]
+pub enum RecentChangesError {
+    /// Reading the stored watermark failed.
+    #[error("failed to read the Wikidata sync watermark")]
+    Query(#[from] ClaimsQueryError),
+    /// Checking revisions or fetching entities failed.
+    #[error(transparent)]
+    Api(#[from] WikidataApiError),
+    /// Writing the refreshed claims failed.
+    #[error("failed to persist refreshed Wikidata claims")]
+    Persist(#[from] PersistClaimsError),
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for incremental enrichment.
+
+    use geo::Coord;
+    use rstest::{fixture, rstest};
+    use wildside_core::{PointOfInterest, Tags};
+
+    use super::*;
+    use crate::wikidata::dump::TransportError;
+    use crate::wikidata::etl::{Claim, ClaimPropertySet, EntityClaims};
+    use crate::wikidata::store::persist_claims;
+
+    struct Revisions(&'static str);
+
+    impl Revisions {
+        fn page(&self, id: &str) -> String {
+            let timestamp = if id == "Q64" {
+                self.0
+            } else {
+                "2024-01-01T00:00:00Z"
+            };
+            format!(r#"{{"ns":0,"title":"{id}","revisions":[{{"timestamp":"{timestamp}"}}]}}"#)
+        }
+    }
+
+    impl RevisionTransport for Revisions {
+        fn latest_revisions(&self, ids: &[&str]) -> Result<Vec<u8>, TransportError> {
+            let pages: Vec<String> = ids.iter().map(|id| self.page(id)).collect();
+            Ok(format!(r#"{{"query":{{"pages":[{}]}}}}"#, pages.join(",")).into_bytes())
+        }
+    }
+
+    /// Serves `Q64` with a single `P1435` value and fails for anything else.
+    struct Entities(&'static str);
+
+    impl EntityTransport for Entities {
+        fn get_entities(&self, ids: &[&str]) -> Result<Vec<u8>, TransportError> {
+            assert_eq!(ids, ["Q64"], "only changed entities should be fetched");
+            Ok(format!(
+                r#"{{"entities":{{"Q64":{{"id":"Q64","claims":{{"P1435":[{{"mainsnak":{{"snaktype":"value","datavalue":{{"type":"wikibase-entityid","value":{{"id":"{}"}}}}}}}}]}}}}}}}}"#,
+                self.0
+            )
+            .into_bytes())
+        }
+    }
+
+    fn poi(id: u64, entity_id: &str) -> PointOfInterest {
+        PointOfInterest::new(
+            id,
+            Coord { x: 0.0, y: 0.0 },
+            Tags::from([("wikidata".into(), entity_id.into())]),
+        )
+    }
+
+    fn heritage(entity_id: &str, poi_id: u64, value: &str) -> EntityClaims {
+        EntityClaims {
+            entity_id: entity_id.into(),
+            linked_poi_ids: vec![poi_id],
+            claims: vec![Claim::entity("P1435", value)],
+            image_files: Vec::new(),
+            sitelink_count: None,
+        }
+    }
+
+    fn stored_values(connection: &Connection) -> Vec<(String, String)> {
+        let mut statement = connection
+            .prepare("SELECT entity_id, value_entity_id FROM wikidata_entity_claims ORDER BY 1")
+            .expect("prepare claims query");
+        statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .expect("query claims")
+            .collect::<Result<_, _>>()
+            .expect("read claims")
+    }
+
+    #[fixture]
+    fn links() -> PoiEntityLinks {
+        PoiEntityLinks::from_pois([&poi(1, "Q64"), &poi(2, "Q90")])
+    }
+
+    /// Database seeded from a dump, with stale claims for both entities.
+    #[fixture]
+    fn connection() -> Connection {
+        let mut connection = Connection::open_in_memory().expect("open database");
+        connection
+            .execute_batch(
+                "CREATE TABLE pois (id INTEGER PRIMARY KEY, lon REAL, lat REAL, tags TEXT);
+                 INSERT INTO pois VALUES (1, 0.0, 0.0, '{}'), (2, 0.0, 0.0, '{}');",
+            )
+            .expect("seed POIs");
+        persist_claims(
+            &mut connection,
+            &[heritage("Q64", 1, "Q9259"), heritage("Q90", 2, "Q9259")],
+        )
+        .expect("seed claims");
+        connection
+    }
+
+    #[rstest]
+    fn replaces_claims_of_entities_edited_since_the_seed_dump(
+        mut connection: Connection,
+        links: PoiEntityLinks,
+    ) {
+        let properties = ClaimPropertySet::default();
+        let sync = RecentChangesSync::with_sources(
+            WikidataRevisionSource::with_transport(Revisions("2024-09-10T08:00:00Z")),
+            WikidataApiSource::with_transport(Entities("Q811165")),
+        )
+        .with_initial_since("2024-09-01T00:00:00Z");
+
+        let report = sync
+            .run(&mut connection, &links, ClaimSelection::new(&properties))
+            .expect("sync should succeed");
+
+        assert_eq!(report.checked, 2);
+        assert_eq!(report.refreshed, 1);
+        assert_eq!(
+            stored_values(&connection),
+            [
+                ("Q64".to_owned(), "Q811165".to_owned()),
+                ("Q90".to_owned(), "Q9259".to_owned()),
+            ]
+        );
+        assert_eq!(
+            sync_watermark(&connection)
+                .expect("read watermark")
+                .as_deref(),
+            Some("2024-09-10T08:00:00Z")
+        );
+    }
+
+    #[rstest]
+    fn stored_watermark_takes_precedence_and_never_regresses(
+        mut connection: Connection,
+        links: PoiEntityLinks,
+    ) {
+        replace_claims(&mut connection, &[], Some("2024-09-20T00:00:00Z"))
+            .expect("record watermark");
+        let properties = ClaimPropertySet::default();
+        let sync = RecentChangesSync::with_sources(
+            WikidataRevisionSource::with_transport(Revisions("2024-09-10T08:00:00Z")),
+            WikidataApiSource::with_transport(Entities("Q811165")),
+        )
+        .with_initial_since("2024-09-01T00:00:00Z");
+
+        let report = sync
+            .run(&mut connection, &links, ClaimSelection::new(&properties))
+            .expect("sync should succeed");
+
+        assert_eq!(report.refreshed, 0);
+        assert_eq!(
+            report.synced_through.as_deref(),
+            Some("2024-09-20T00:00:00Z")
+        );
+        assert_eq!(stored_values(&connection).len(), 2);
+    }
+}
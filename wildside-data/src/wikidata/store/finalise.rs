@@ -0,0 +1,136 @@
+//! Prepare a fully ingested `pois.db` for read-mostly distribution.
+//!
+//! Ingestion writes POIs, claims, and labels with insert-friendly settings.
+//! Once the last write has landed, [`finalise_artifacts`] builds the read-side
+//! indexes, records planner statistics, and rewrites the file with a larger
+//! page size and a rollback journal so readers can open it from read-only
+//! storage.
+#![forbid(unsafe_code)]
+
+use std::path::Path;
+
+use rusqlite::Connection;
+
+use super::persistence::PersistClaimsError;
+use super::schema::initialise_schema;
+
+/// Page size, in bytes, of finalised artefacts.
+///
+/// Larger pages keep the claim B-trees shallower for the scorer's point
+/// lookups; the database is rarely written after ingest, so the extra write
+/// amplification does not matter.
+pub const ARTEFACT_PAGE_SIZE: u32 = 8_192;
+
+/// Index only readers need, built once after bulk loading rather than
+/// maintained across every literal insert.
+const LITERAL_PROPERTY_INDEX: &str =
+    "CREATE INDEX IF NOT EXISTS idx_wikidata_entity_literals_property
+    ON wikidata_entity_literals(property_id, value, entity_id)";
+
+/// Optimise an ingested database held by `connection` for reading.
+///
+/// The claims schema is initialised first, so its covering indexes on
+/// `wikidata_entity_claims(property_id, value_entity_id, entity_id)` and
+/// `poi_wikidata_links(entity_id, poi_id)` exist alongside the literal
+/// property index added here. `ANALYZE` then records statistics for the query
+/// planner, the journal switches to `DELETE` mode so no `-wal` or `-shm`
+/// files are needed beside the artefact, and `VACUUM` rewrites the file at
+/// [`ARTEFACT_PAGE_SIZE`] without the free pages left by chunked loads.
+///
+/// Finalising is idempotent; re-running it after an incremental refresh
+/// refreshes the statistics and compacts the file again.
+///
+/// # Examples
+/// ```
+/// use rusqlite::Connection;
+/// use wildside_data::wikidata::store::finalise_database;
+///
+/// let mut conn = Connection::open_in_memory().expect("create in-memory database");
+/// conn.execute(
+///     "CREATE TABLE pois (id INTEGER PRIMARY KEY, lon REAL, lat REAL, tags TEXT)",
+///     [],
+/// )
+/// .expect("create pois table");
+/// finalise_database(&mut conn).expect("finalise database");
+///
+/// let indexed: i64 = conn
+///     .query_row(
+///         "SELECT COUNT(*) FROM sqlite_master
+///          WHERE type = 'index' AND name = 'idx_wikidata_entity_literals_property'",
+///         [],
+///         |row| row.get(0),
+///     )
+///     .expect("query indexes");
+/// assert_eq!(indexed, 1);
+/// ```
+pub fn finalise_database(connection: &mut Connection) -> Result<(), PersistClaimsError> {
+    initialise_schema(connection)?;
+    connection
+        .execute(LITERAL_PROPERTY_INDEX, [])
+        .map_err(|source| PersistClaimsError::Sqlite {
+            operation: "literal property index",
+            source,
+        })?;
+    connection
+        .execute_batch("ANALYZE")
+        .map_err(|source| PersistClaimsError::Sqlite {
+            operation: "query planner statistics",
+            source,
+        })?;
+    connection
+        .pragma_update_and_check(None, "journal_mode", "DELETE", |row| {
+            row.get::<_, String>(0)
+        })
+        .map_err(|source| PersistClaimsError::Sqlite {
+            operation: "journal mode",
+            source,
+        })?;
+    connection
+        .pragma_update(None, "page_size", ARTEFACT_PAGE_SIZE)
+        .map_err(|source| PersistClaimsError::Sqlite {
+            operation: "page size",
+            source,
+        })?;
+    connection
+        .execute_batch("VACUUM")
+        .map_err(|source| PersistClaimsError::Sqlite {
+            operation: "compacted database",
+            source,
+        })
+}
+
+/// Finalise the database file at `db_path`; see [`finalise_database`].
+///
+/// The ingest command calls this once every POI, claim, and label has been
+/// written.
+///
+/// # Examples
+/// ```
+/// use rusqlite::Connection;
+/// use tempfile::NamedTempFile;
+/// use wildside_data::wikidata::store::{ARTEFACT_PAGE_SIZE, finalise_artifacts};
+///
+/// let temp = NamedTempFile::new().expect("create temp file");
+/// let conn = Connection::open(temp.path()).expect("open database");
+/// conn.execute(
+///     "CREATE TABLE pois (id INTEGER PRIMARY KEY, lon REAL, lat REAL, tags TEXT)",
+///     [],
+/// )
+/// .expect("create pois table");
+/// drop(conn);
+///
+/// finalise_artifacts(temp.path()).expect("finalise artefacts");
+/// let conn = Connection::open(temp.path()).expect("reopen database");
+/// let page_size: u32 = conn
+///     .query_row("PRAGMA page_size", [], |row| row.get(0))
+///     .expect("read page size");
+/// assert_eq!(page_size, ARTEFACT_PAGE_SIZE);
+/// ```
+pub fn finalise_artifacts<P: AsRef<Path>>(db_path: P) -> Result<(), PersistClaimsError> {
+    let mut connection =
+        Connection::open(db_path.as_ref()).map_err(|source| PersistClaimsError::Open {
+            path: db_path.as_ref().to_path_buf(),
+            source,
+        })?;
+    finalise_database(&mut connection)
+}
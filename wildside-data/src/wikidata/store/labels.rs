@@ -0,0 +1,211 @@
+//! Store and read human-readable labels for claim value entities.
+//!
+//! Labels live in `wikidata_entity_labels`, keyed by entity and language, and
+//! are joined onto POI claims by the `poi_wikidata_claim_labels` view. The
+//! label pass runs after claims are persisted: [`designation_entity_ids`]
+//! lists the heritage designations to resolve, and
+//! [`persist_entity_labels`] records whatever the dump or API returned.
+#![forbid(unsafe_code)]
+
+use std::path::Path;
+
+use rusqlite::{Connection, OptionalExtension};
+
+use crate::wikidata::etl::{EntityLabel, HERITAGE_PROPERTY};
+
+use super::persistence::PersistClaimsError;
+use super::query::ClaimsQueryError;
+use super::schema::initialise_schema;
+
+/// Return the distinct heritage designation entities (`P1435` values) in
+/// the store, sorted.
+///
+/// The schema must have been initialised, for example by
+/// [`super::persist_claims`].
+///
+/// # Examples
+/// ```
+/// use rusqlite::Connection;
+/// use wildside_data::wikidata::etl::{Claim, EntityClaims};
+/// use wildside_data::wikidata::store::{designation_entity_ids, persist_claims};
+///
+/// let mut conn = Connection::open_in_memory().expect("create in-memory database");
+/// conn.execute(
+///     "CREATE TABLE pois (id INTEGER PRIMARY KEY, lon REAL, lat REAL, tags TEXT)",
+///     [],
+/// )
+/// .expect("create pois table");
+/// conn.execute("INSERT INTO pois VALUES (7, 13.4, 52.5, '{}')", [])
+///     .expect("insert POI row");
+/// let claims = vec![EntityClaims {
+///     entity_id: "Q64".into(),
+///     linked_poi_ids: vec![7],
+///     claims: vec![Claim::entity("P1435", "Q9259"), Claim::entity("P31", "Q515")],
+///     image_files: Vec::new(),
+///     sitelink_count: None,
+/// }];
+/// persist_claims(&mut conn, &claims).expect("persist claims");
+///
+/// assert_eq!(designation_entity_ids(&conn).expect("query designations"), ["Q9259"]);
+/// ```
+pub fn designation_entity_ids(connection: &Connection) -> Result<Vec<String>, ClaimsQueryError> {
+    let mut statement = connection
+        .prepare_cached(concat!(
+            "SELECT DISTINCT value_entity_id FROM wikidata_entity_claims ",
+            "WHERE property_id = ?1 ORDER BY value_entity_id",
+        ))
+        .map_err(|source| ClaimsQueryError::Sqlite {
+            operation: "prepare designation lookup",
+            source,
+        })?;
+    let rows = statement
+        .query_map([HERITAGE_PROPERTY], |row| row.get(0))
+        .map_err(|source| ClaimsQueryError::Sqlite {
+            operation: "query designations",
+            source,
+        })?;
+    rows.collect::<Result<_, _>>()
+        .map_err(|source| ClaimsQueryError::Sqlite {
+            operation: "read designation row",
+            source,
+        })
+}
+
+/// Read [`designation_entity_ids`] from a database file on disk.
+pub fn designation_entity_ids_for_path<P: AsRef<Path>>(
+    path: P,
+) -> Result<Vec<String>, ClaimsQueryError> {
+    let connection = Connection::open(path.as_ref()).map_err(|source| ClaimsQueryError::Open {
+        path: path.as_ref().to_path_buf(),
+        source,
+    })?;
+    designation_entity_ids(&connection)
+}
+
+/// Return the stored label of `entity_id` in `language`, if any.
+///
+/// # Examples
+/// ```
+/// use rusqlite::Connection;
+/// use wildside_data::wikidata::etl::EntityLabel;
+/// use wildside_data::wikidata::store::{entity_label, persist_entity_labels};
+///
+/// let mut conn = Connection::open_in_memory().expect("create in-memory database");
+/// conn.execute(
+///     "CREATE TABLE pois (id INTEGER PRIMARY KEY, lon REAL, lat REAL, tags TEXT)",
+///     [],
+/// )
+/// .expect("create pois table");
+/// let label = EntityLabel {
+///     entity_id: "Q9259".into(),
+///     language: "en".into(),
+///     label: "World Heritage Site".into(),
+/// };
+/// persist_entity_labels(&mut conn, &[label]).expect("persist labels");
+///
+/// assert_eq!(
+///     entity_label(&conn, "Q9259", "en").expect("read label").as_deref(),
+///     Some("World Heritage Site"),
+/// );
+/// assert_eq!(entity_label(&conn, "Q9259", "de").expect("read label"), None);
+/// ```
+pub fn entity_label(
+    connection: &Connection,
+    entity_id: &str,
+    language: &str,
+) -> Result<Option<String>, ClaimsQueryError> {
+    connection
+        .query_row(
+            "SELECT label FROM wikidata_entity_labels WHERE entity_id = ?1 AND language = ?2",
+            [entity_id, language],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|source| ClaimsQueryError::Sqlite {
+            operation: "read entity label",
+            source,
+        })
+}
+
+/// Record entity labels, returning the number written.
+///
+/// The schema is initialised first. Labelled entities are added to
+/// `wikidata_entities` when absent, and a label already stored for the same
+/// entity and language is replaced, so re-running against a newer dump picks
+/// up renamed designations. All labels are written in one transaction.
+///
+/// # Errors
+/// Returns [`PersistClaimsError`] when the schema cannot be initialised or a
+/// write fails.
+pub fn persist_entity_labels(
+    connection: &mut Connection,
+    labels: &[EntityLabel],
+) -> Result<usize, PersistClaimsError> {
+    initialise_schema(connection)?;
+    let transaction = connection
+        .transaction()
+        .map_err(|source| PersistClaimsError::Sqlite {
+            operation: "begin label transaction",
+            source,
+        })?;
+    {
+        let mut insert_entity = transaction
+            .prepare_cached(concat!(
+                "INSERT INTO wikidata_entities (entity_id) VALUES (?1) ",
+                "ON CONFLICT(entity_id) DO NOTHING",
+            ))
+            .map_err(|source| PersistClaimsError::Sqlite {
+                operation: "prepare insert labelled entity",
+                source,
+            })?;
+        let mut upsert_label = transaction
+            .prepare_cached(concat!(
+                "INSERT INTO wikidata_entity_labels (entity_id, language, label) ",
+                "VALUES (?1, ?2, ?3) ",
+                "ON CONFLICT(entity_id, language) DO UPDATE SET label = excluded.label",
+            ))
+            .map_err(|source| PersistClaimsError::Sqlite {
+                operation: "prepare upsert label",
+                source,
+            })?;
+        for label in labels {
+            insert_entity
+                .execute([label.entity_id.as_str()])
+                .map_err(|source| PersistClaimsError::Sqlite {
+                    operation: "insert labelled entity",
+                    source,
+                })?;
+            upsert_label
+                .execute((
+                    label.entity_id.as_str(),
+                    label.language.as_str(),
+                    label.label.as_str(),
+                ))
+                .map_err(|source| PersistClaimsError::Sqlite {
+                    operation: "upsert entity label",
+                    source,
+                })?;
+        }
+    }
+    transaction
+        .commit()
+        .map_err(|source| PersistClaimsError::Sqlite {
+            operation: "commit label transaction",
+            source,
+        })?;
+    Ok(labels.len())
+}
+
+/// Record entity labels in a database file on disk; see
+/// [`persist_entity_labels`].
+pub fn persist_entity_labels_to_path<P: AsRef<Path>>(
+    path: P,
+    labels: &[EntityLabel],
+) -> Result<usize, PersistClaimsError> {
+    let mut connection =
+        Connection::open(path.as_ref()).map_err(|source| PersistClaimsError::Open {
+            path: path.as_ref().to_path_buf(),
+            source,
+        })?;
+    persist_entity_labels(&mut connection, labels)
+}
@@ -0,0 +1,100 @@
+//! Membership filters over the persisted POI links.
+
+use std::path::Path;
+
+use rusqlite::Connection;
+use wildside_core::store::LinkFilter;
+
+use super::persistence::PersistClaimsError;
+use super::schema::initialise_schema;
+
+/// Build a [`LinkFilter`] from the persisted `poi_wikidata_links` table.
+///
+/// Every linked POI id and entity id is recorded so downstream readers can
+/// skip SQLite lookups for the unlinked majority. The schema is initialised
+/// first, so an empty database yields an empty filter.
+///
+/// # Examples
+/// ```
+/// use rusqlite::Connection;
+/// use wildside_data::wikidata::store::build_link_filter;
+///
+/// let mut conn = Connection::open_in_memory().expect("create in-memory database");
+/// conn.execute(
+///     "CREATE TABLE pois (id INTEGER PRIMARY KEY, lon REAL, lat REAL, tags TEXT)",
+///     [],
+/// )
+/// .expect("create pois table");
+///
+/// let filter = build_link_filter(&mut conn).expect("build filter");
+/// assert!(!filter.may_contain_poi(7));
+/// ```
+pub fn build_link_filter(connection: &mut Connection) -> Result<LinkFilter, PersistClaimsError> {
+    initialise_schema(connection)?;
+    let sqlite_error = |operation| move |source| PersistClaimsError::Sqlite { operation, source };
+    let link_count: i64 = connection
+        .query_row("SELECT COUNT(*) FROM poi_wikidata_links", [], |row| {
+            row.get(0)
+        })
+        .map_err(sqlite_error("count POI links"))?;
+    // Each link contributes a POI key and an entity key.
+    let capacity = usize::try_from(link_count).unwrap_or(0).saturating_mul(2);
+    let mut filter = LinkFilter::with_capacity(capacity);
+
+    let mut statement = connection
+        .prepare("SELECT poi_id, entity_id FROM poi_wikidata_links")
+        .map_err(sqlite_error("prepare link scan"))?;
+    let mut rows = statement
+        .query([])
+        .map_err(sqlite_error("scan POI links"))?;
+    while let Some(row) = rows.next().map_err(sqlite_error("read POI link"))? {
+        let poi_id: i64 = row.get(0).map_err(sqlite_error("decode POI link"))?;
+        let entity_id: String = row.get(1).map_err(sqlite_error("decode POI link"))?;
+        // Ids were range-checked on insert; the cast restores the original u64.
+        filter.insert_poi(poi_id.cast_unsigned());
+        filter.insert_entity(&entity_id);
+    }
+    Ok(filter)
+}
+
+/// Build the link filter for the database at `db_path` and write it to
+/// `filter_path`.
+///
+/// # Examples
+/// ```
+/// use rusqlite::Connection;
+/// use tempfile::TempDir;
+/// use wildside_core::store::LinkFilter;
+/// use wildside_data::wikidata::store::write_link_filter_for_path;
+///
+/// let dir = TempDir::new().expect("create temp dir");
+/// let db_path = dir.path().join("pois.db");
+/// let conn = Connection::open(&db_path).expect("open database");
+/// conn.execute(
+///     "CREATE TABLE pois (id INTEGER PRIMARY KEY, lon REAL, lat REAL, tags TEXT)",
+///     [],
+/// )
+/// .expect("create pois table");
+/// drop(conn);
+///
+/// let filter_path = dir.path().join("links.filter");
+/// write_link_filter_for_path(&db_path, &filter_path).expect("write filter");
+/// assert!(LinkFilter::load(&filter_path).is_ok());
+/// ```
+pub fn write_link_filter_for_path<P, Q>(
+    db_path: P,
+    filter_path: Q,
+) -> Result<LinkFilter, PersistClaimsError>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let mut connection =
+        Connection::open(db_path.as_ref()).map_err(|source| PersistClaimsError::Open {
+            path: db_path.as_ref().to_path_buf(),
+            source,
+        })?;
+    let filter = build_link_filter(&mut connection)?;
+    filter.write(filter_path.as_ref())?;
+    Ok(filter)
+}
@@ -1,14 +1,40 @@
 //! Persistence layer for Wikidata claims in the `pois.db` SQLite database.
 //!
-//! The module is split into two focused submodules:
+//! The module is split into focused submodules:
 //! - [`schema`] materializes the SQLite structures that back the POI metadata.
-//! - [`persistence`] writes extracted claims into those tables.
+//! - [`persistence`] writes extracted claims into those tables, gathering
+//!   each chunk's rows in [`rows`] first.
+//! - [`refresh`] replaces the claims of entities changed upstream.
+//! - [`link_filter`] derives the link membership filter consulted by readers.
+//! - [`query`] reads derived metadata, such as image file names and the
+//!   incremental sync watermark, back out.
+//! - [`labels`] stores and reads the labels of claim value entities, such as
+//!   heritage designations.
+//! - [`finalise`] indexes, analyses, and compacts the database once ingest
+//!   has written everything.
 #![forbid(unsafe_code)]
 
+mod finalise;
+mod labels;
+mod link_filter;
 mod persistence;
+mod query;
+mod refresh;
+mod rows;
 mod schema;
 
-pub use persistence::{PersistClaimsError, persist_claims, persist_claims_to_path};
+pub use finalise::{ARTEFACT_PAGE_SIZE, finalise_artifacts, finalise_database};
+pub use labels::{
+    designation_entity_ids, designation_entity_ids_for_path, entity_label, persist_entity_labels,
+    persist_entity_labels_to_path,
+};
+pub use link_filter::{build_link_filter, write_link_filter_for_path};
+pub use persistence::{
+    DEFAULT_CLAIMS_CHUNK_SIZE, PersistClaimsError, persist_claims, persist_claims_iter,
+    persist_claims_iter_to_path, persist_claims_to_path,
+};
+pub use query::{ClaimsQueryError, poi_image_files, representative_image, sync_watermark};
+pub use refresh::replace_claims;
 pub use schema::{ClaimsSchemaError, SCHEMA_VERSION, initialise_schema};
 
 #[cfg(test)]
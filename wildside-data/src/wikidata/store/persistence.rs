@@ -1,158 +1,45 @@
-//! Persist Wikidata entities, POI links, and heritage claims into SQLite using
-//! a single transaction with idempotent statement execution. The helpers in
-//! this module encapsulate the cached statement lifecycle so callers can load
-//! batches of claims without duplicating insert guards or foreign key checks.
+//! Persist Wikidata entities, POI links, generic property claims, image file
+//! names, and sitelink counts into SQLite using a single transaction with idempotent statement execution.
+//! Rows are gathered per table, de-duplicated, and sorted into primary key
+//! order, then written with multi-row `INSERT` statements of a few hundred
+//! rows each, so one statement step replaces hundreds of per-row executions.
+//! Streamed loads split the same writes into chunked transactions so memory
+//! use stays bounded by the chunk size.
+//! Incremental refreshes in the sibling `refresh` module reuse the same
+//! batch writer.
+//! The helpers in this module encapsulate the cached statement lifecycle so
+//! callers can load batches of claims without duplicating insert guards or
+//! foreign key checks.
 #![forbid(unsafe_code)]
 
 use std::{
-    collections::HashSet,
+    num::NonZeroUsize,
     path::{Path, PathBuf},
 };
 
-use rusqlite::{CachedStatement, Connection, Error as SqliteError, OptionalExtension, Transaction};
+use rusqlite::{Connection, Error as SqliteError};
 use thiserror::Error;
+use wildside_core::store::LinkFilterError;
 
-use crate::wikidata::etl::{EntityClaims, HERITAGE_PROPERTY};
+use crate::wikidata::etl::EntityClaims;
 
+use super::rows::persist_batch;
 use super::schema::{ClaimsSchemaError, initialise_schema};
 
-struct PreparedStatements<'conn> {
-    insert_entity: CachedStatement<'conn>,
-    insert_link: CachedStatement<'conn>,
-    insert_claim: CachedStatement<'conn>,
-    check_poi: CachedStatement<'conn>,
-}
-
-impl<'conn> PreparedStatements<'conn> {
-    fn prepare(transaction: &'conn Transaction<'conn>) -> Result<Self, PersistClaimsError> {
-        let insert_entity = transaction
-            .prepare_cached(concat!(
-                "INSERT INTO wikidata_entities (entity_id) VALUES (?1) ",
-                "ON CONFLICT(entity_id) DO NOTHING",
-            ))
-            .map_err(|source| PersistClaimsError::Sqlite {
-                operation: "prepare insert entity",
-                source,
-            })?;
-        let insert_link = transaction
-            .prepare_cached(concat!(
-                "INSERT INTO poi_wikidata_links (poi_id, entity_id) VALUES (?1, ?2) ",
-                "ON CONFLICT(poi_id, entity_id) DO NOTHING",
-            ))
-            .map_err(|source| PersistClaimsError::Sqlite {
-                operation: "prepare link POI",
-                source,
-            })?;
-        let insert_claim = transaction
-            .prepare_cached(concat!(
-                "INSERT INTO wikidata_entity_claims (\n",
-                "    entity_id,\n",
-                "    property_id,\n",
-                "    value_entity_id\n",
-                ") VALUES (?1, ?2, ?3)\n",
-                "ON CONFLICT(entity_id, property_id, value_entity_id) DO NOTHING",
-            ))
-            .map_err(|source| PersistClaimsError::Sqlite {
-                operation: "prepare insert claim",
-                source,
-            })?;
-        let check_poi = transaction
-            .prepare_cached("SELECT 1 FROM pois WHERE id = ?1 LIMIT 1")
-            .map_err(|source| PersistClaimsError::Sqlite {
-                operation: "prepare POI lookup",
-                source,
-            })?;
-
-        Ok(Self {
-            insert_entity,
-            insert_link,
-            insert_claim,
-            check_poi,
-        })
-    }
-}
-
-fn persist_entity(
-    statement: &mut CachedStatement<'_>,
-    entity_id: &str,
-    operation: &'static str,
-) -> Result<(), PersistClaimsError> {
-    statement
-        .execute([entity_id])
-        .map(|_| ())
-        .map_err(|source| PersistClaimsError::Sqlite { operation, source })
-}
-
-fn persist_heritage_designations(
-    statements: &mut PreparedStatements<'_>,
-    entity_id: &str,
-    designations: &[String],
-) -> Result<(), PersistClaimsError> {
-    for designation in designations {
-        persist_entity(
-            &mut statements.insert_entity,
-            designation.as_str(),
-            "insert designation entity",
-        )?;
-        statements
-            .insert_claim
-            .execute((entity_id, HERITAGE_PROPERTY, designation.as_str()))
-            .map_err(|source| PersistClaimsError::Sqlite {
-                operation: "insert heritage claim",
-                source,
-            })?;
-    }
-    Ok(())
-}
-
-fn persist_poi_links(
-    statements: &mut PreparedStatements<'_>,
-    entity_id: &str,
-    poi_ids: &[u64],
-    known_pois: &mut HashSet<u64>,
-) -> Result<(), PersistClaimsError> {
-    for poi_id in poi_ids {
-        let poi_id_i64 = i64::try_from(*poi_id)
-            .map_err(|_| PersistClaimsError::PoiIdOutOfRange { poi_id: *poi_id })?;
-        if !known_pois.contains(poi_id) {
-            let exists = statements
-                .check_poi
-                .query_row([poi_id_i64], |_| Ok(()))
-                .optional()
-                .map_err(|source| PersistClaimsError::Sqlite {
-                    operation: "verify POI presence",
-                    source,
-                })?
-                .is_some();
-            if !exists {
-                return Err(PersistClaimsError::MissingPoi {
-                    poi_id: *poi_id,
-                    entity_id: entity_id.to_owned(),
-                });
-            }
-            known_pois.insert(*poi_id);
-        }
-        statements
-            .insert_link
-            .execute((poi_id_i64, entity_id))
-            .map_err(|source| PersistClaimsError::Sqlite {
-                operation: "link POI to entity",
-                source,
-            })?;
-    }
-    Ok(())
-}
-
 /// Persist the supplied claims into an initialized SQLite connection.
 ///
 /// The function ensures the schema is present, validates that every referenced
-/// POI id exists in the `pois` table, and performs idempotent inserts for both
-/// entity metadata and claim values.
+/// POI id exists in the `pois` table, and performs idempotent inserts for
+/// entity metadata, claim values, and image file names. Entity-valued claims
+/// land in `wikidata_entity_claims`; literal values such as `P571` inception
+/// timestamps land in `wikidata_entity_literals`. Sitelink counts are written
+/// to `wikidata_entity_sitelinks`, where the popularity scorer reads them;
+/// re-running with a newer dump replaces the stored count.
 ///
 /// # Examples
 /// ```
 /// use rusqlite::Connection;
-/// use wildside_data::wikidata::etl::EntityClaims;
+/// use wildside_data::wikidata::etl::{Claim, EntityClaims};
 /// use wildside_data::wikidata::store::persist_claims;
 ///
 /// let mut conn = Connection::open_in_memory().expect("create in-memory database");
@@ -174,7 +61,9 @@ fn persist_poi_links(
 /// let claims = vec![EntityClaims {
 ///     entity_id: "Q64".into(),
 ///     linked_poi_ids: vec![7],
-///     heritage_designations: vec!["Q9259".into()],
+///     claims: vec![Claim::entity("P1435", "Q9259")],
+///     image_files: Vec::new(),
+///     sitelink_count: None,
 /// }];
 ///
 /// persist_claims(&mut conn, &claims).expect("persist claims");
@@ -195,46 +84,99 @@ pub fn persist_claims(
     if claims.is_empty() {
         return Ok(());
     }
+    persist_chunk(connection, claims)
+}
+
+/// Entities written per transaction by [`persist_claims_iter`] when callers
+/// have no better estimate.
+pub const DEFAULT_CLAIMS_CHUNK_SIZE: NonZeroUsize = NonZeroUsize::new(1_000).unwrap();
+
+/// Persist claims drawn from an iterator in transactions of `chunk_size`
+/// entities, returning the number of entities written.
+///
+/// Only one chunk is held in memory at a time, so extractions far larger than
+/// RAM can be loaded by passing a lazy source such as
+/// [`stream_linked_entity_claims`](crate::wikidata::etl::stream_linked_entity_claims)
+/// or the receiving end of a channel. Each chunk commits independently: if a
+/// later chunk fails, earlier chunks remain persisted. Writes are idempotent,
+/// so re-running the load after a failure is safe. The schema is initialised
+/// even when the iterator is empty.
+///
+/// # Examples
+/// ```
+/// use std::num::NonZeroUsize;
+/// use std::sync::mpsc;
+/// use rusqlite::Connection;
+/// use wildside_data::wikidata::etl::{Claim, EntityClaims};
+/// use wildside_data::wikidata::store::persist_claims_iter;
+///
+/// let mut conn = Connection::open_in_memory().expect("create in-memory database");
+/// conn.execute(
+///     "CREATE TABLE pois (id INTEGER PRIMARY KEY, lon REAL, lat REAL, tags TEXT)",
+///     [],
+/// )
+/// .expect("create pois table");
+/// conn.execute("INSERT INTO pois VALUES (7, 13.4, 52.5, '{}')", [])
+///     .expect("insert POI row");
+///
+/// let (sender, receiver) = mpsc::channel();
+/// for entity_id in ["Q64", "Q1731"] {
+///     sender
+///         .send(EntityClaims {
+///             entity_id: entity_id.into(),
+///             linked_poi_ids: vec![7],
+///             claims: vec![Claim::entity("P1435", "Q9259")],
+///             image_files: Vec::new(),
+///             sitelink_count: None,
+///         })
+///         .expect("queue claims");
+/// }
+/// drop(sender);
+///
+/// let chunk_size = NonZeroUsize::new(1).expect("non-zero chunk size");
+/// let written = persist_claims_iter(&mut conn, receiver, chunk_size).expect("persist claims");
+/// assert_eq!(written, 2);
+/// ```
+pub fn persist_claims_iter<I>(
+    connection: &mut Connection,
+    claims: I,
+    chunk_size: NonZeroUsize,
+) -> Result<usize, PersistClaimsError>
+where
+    I: IntoIterator<Item = EntityClaims>,
+{
+    initialise_schema(connection)?;
+    let mut claims = claims.into_iter();
+    let mut chunk = Vec::new();
+    let mut persisted = 0;
+    loop {
+        chunk.clear();
+        chunk.extend(claims.by_ref().take(chunk_size.get()));
+        if chunk.is_empty() {
+            return Ok(persisted);
+        }
+        persist_chunk(connection, &chunk)?;
+        persisted += chunk.len();
+    }
+}
 
+fn persist_chunk(
+    connection: &mut Connection,
+    claims: &[EntityClaims],
+) -> Result<(), PersistClaimsError> {
     let transaction = connection
         .transaction()
         .map_err(|source| PersistClaimsError::Sqlite {
             operation: "begin persistence transaction",
             source,
         })?;
-
-    {
-        let mut statements = PreparedStatements::prepare(&transaction)?;
-        let mut known_pois = HashSet::new();
-
-        for claim in claims {
-            persist_entity(
-                &mut statements.insert_entity,
-                claim.entity_id.as_str(),
-                "insert entity",
-            )?;
-            persist_heritage_designations(
-                &mut statements,
-                claim.entity_id.as_str(),
-                &claim.heritage_designations,
-            )?;
-            persist_poi_links(
-                &mut statements,
-                claim.entity_id.as_str(),
-                &claim.linked_poi_ids,
-                &mut known_pois,
-            )?;
-        }
-    }
-
+    persist_batch(&transaction, claims)?;
     transaction
         .commit()
         .map_err(|source| PersistClaimsError::Sqlite {
             operation: "commit persistence transaction",
             source,
-        })?;
-
-    Ok(())
+        })
 }
 
 /// Convenience helper to persist claims to a database file on disk.
@@ -243,7 +185,7 @@ pub fn persist_claims(
 /// ```
 /// use rusqlite::Connection;
 /// use tempfile::NamedTempFile;
-/// use wildside_data::wikidata::etl::EntityClaims;
+/// use wildside_data::wikidata::etl::{Claim, EntityClaims};
 /// use wildside_data::wikidata::store::persist_claims_to_path;
 ///
 /// let temp = NamedTempFile::new().expect("create temp file");
@@ -268,7 +210,9 @@ pub fn persist_claims(
 /// let claims = vec![EntityClaims {
 ///     entity_id: "Q42".into(),
 ///     linked_poi_ids: vec![11],
-///     heritage_designations: vec!["Q9259".into()],
+///     claims: vec![Claim::entity("P1435", "Q9259")],
+///     image_files: Vec::new(),
+///     sitelink_count: None,
 /// }];
 ///
 /// persist_claims_to_path(temp.path(), &claims).expect("persist claims to disk");
@@ -294,6 +238,24 @@ pub fn persist_claims_to_path<P: AsRef<Path>>(
     persist_claims(&mut connection, claims)
 }
 
+/// Stream claims into a database file on disk; see [`persist_claims_iter`].
+pub fn persist_claims_iter_to_path<P, I>(
+    path: P,
+    claims: I,
+    chunk_size: NonZeroUsize,
+) -> Result<usize, PersistClaimsError>
+where
+    P: AsRef<Path>,
+    I: IntoIterator<Item = EntityClaims>,
+{
+    let mut connection =
+        Connection::open(path.as_ref()).map_err(|source| PersistClaimsError::Open {
+            path: path.as_ref().to_path_buf(),
+            source,
+        })?;
+    persist_claims_iter(&mut connection, claims, chunk_size)
+}
+
 /// Errors raised when persisting Wikidata claims.
 #[derive(Debug, Error)]
 pub enum PersistClaimsError {
@@ -309,6 +271,8 @@ pub enum PersistClaimsError {
     PoiIdOutOfRange { poi_id: u64 },
     #[error("POI id {poi_id} referenced by entity {entity_id} is missing from the pois table")]
     MissingPoi { poi_id: u64, entity_id: String },
+    #[error("failed to write link filter")]
+    LinkFilter(#[from] LinkFilterError),
     #[error("failed to persist {operation}")]
     Sqlite {
         operation: &'static str,
@@ -0,0 +1,136 @@
+//! Read helpers for Wikidata-derived POI metadata stored in `pois.db`.
+//!
+//! These queries sit on top of the views created by [`super::initialise_schema`]
+//! so callers do not need to know how entities, links, and claims are joined.
+#![forbid(unsafe_code)]
+
+use std::path::PathBuf;
+
+use rusqlite::{Connection, Error as SqliteError, OptionalExtension};
+use thiserror::Error;
+
+const POI_IMAGES_SQL: &str = concat!(
+    "SELECT file_name FROM poi_wikidata_images WHERE poi_id = ?1 ",
+    "ORDER BY entity_id, file_name"
+);
+
+/// Return every Commons image file name linked to the POI.
+///
+/// Results are ordered by entity id and then file name so repeated calls are
+/// stable.
+///
+/// # Examples
+/// ```
+/// use rusqlite::Connection;
+/// use wildside_data::wikidata::etl::EntityClaims;
+/// use wildside_data::wikidata::store::{persist_claims, poi_image_files};
+///
+/// let mut conn = Connection::open_in_memory().expect("create in-memory database");
+/// conn.execute(
+///     "CREATE TABLE pois (id INTEGER PRIMARY KEY, lon REAL, lat REAL, tags TEXT)",
+///     [],
+/// )
+/// .expect("create pois table");
+/// conn.execute("INSERT INTO pois VALUES (7, 13.4, 52.5, '{}')", [])
+///     .expect("insert POI row");
+/// let claims = vec![EntityClaims {
+///     entity_id: "Q64".into(),
+///     linked_poi_ids: vec![7],
+///     claims: Vec::new(),
+///     image_files: vec!["Berlin skyline.jpg".into()],
+///     sitelink_count: None,
+/// }];
+/// persist_claims(&mut conn, &claims).expect("persist claims");
+///
+/// let images = poi_image_files(&conn, 7).expect("query images");
+/// assert_eq!(images, vec!["Berlin skyline.jpg"]);
+/// ```
+pub fn poi_image_files(
+    connection: &Connection,
+    poi_id: u64,
+) -> Result<Vec<String>, ClaimsQueryError> {
+    let poi_id_i64 =
+        i64::try_from(poi_id).map_err(|_| ClaimsQueryError::PoiIdOutOfRange { poi_id })?;
+    let mut statement = connection
+        .prepare_cached(POI_IMAGES_SQL)
+        .map_err(|source| ClaimsQueryError::Sqlite {
+            operation: "prepare image lookup",
+            source,
+        })?;
+    let rows = statement
+        .query_map([poi_id_i64], |row| row.get(0))
+        .map_err(|source| ClaimsQueryError::Sqlite {
+            operation: "query images",
+            source,
+        })?;
+    rows.collect::<Result<_, _>>()
+        .map_err(|source| ClaimsQueryError::Sqlite {
+            operation: "read image row",
+            source,
+        })
+}
+
+/// Return a single representative Commons image for the POI, if any.
+///
+/// The first file from [`poi_image_files`] is chosen, which keeps the choice
+/// deterministic across runs.
+pub fn representative_image(
+    connection: &Connection,
+    poi_id: u64,
+) -> Result<Option<String>, ClaimsQueryError> {
+    Ok(poi_image_files(connection, poi_id)?.into_iter().next())
+}
+
+/// Return the latest revision timestamp already applied by an incremental
+/// refresh, or `None` when no refresh has completed.
+///
+/// The schema must have been initialised, for example by
+/// [`super::initialise_schema`] or [`super::persist_claims`].
+///
+/// # Examples
+/// ```
+/// use rusqlite::Connection;
+/// use wildside_data::wikidata::store::{initialise_schema, sync_watermark};
+///
+/// let mut conn = Connection::open_in_memory().expect("create in-memory database");
+/// conn.execute(
+///     "CREATE TABLE pois (id INTEGER PRIMARY KEY, lon REAL, lat REAL, tags TEXT)",
+///     [],
+/// )
+/// .expect("create pois table");
+/// initialise_schema(&mut conn).expect("create Wikidata schema");
+///
+/// assert_eq!(sync_watermark(&conn).expect("read watermark"), None);
+/// ```
+pub fn sync_watermark(connection: &Connection) -> Result<Option<String>, ClaimsQueryError> {
+    connection
+        .query_row(
+            "SELECT synced_through FROM wikidata_sync_state WHERE id = 1",
+            [],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|source| ClaimsQueryError::Sqlite {
+            operation: "read sync watermark",
+            source,
+        })
+}
+
+/// Errors raised when reading Wikidata-derived metadata.
+#[derive(Debug, Error)]
+pub enum ClaimsQueryError {
+    #[error("failed to open SQLite database at {path:?}")]
+    Open {
+        path: PathBuf,
+        #[source]
+        source: SqliteError,
+    },
+    #[error("POI id {poi_id} exceeds SQLite i64 range")]
+    PoiIdOutOfRange { poi_id: u64 },
+    #[error("failed to {operation}")]
+    Sqlite {
+        operation: &'static str,
+        #[source]
+        source: SqliteError,
+    },
+}
@@ -0,0 +1,118 @@
+//! Incremental refreshes that replace the stored claims of changed entities.
+
+use rusqlite::{Connection, Transaction};
+
+use crate::wikidata::etl::EntityClaims;
+
+use super::persistence::PersistClaimsError;
+use super::rows::persist_batch;
+use super::schema::initialise_schema;
+
+/// Replace the stored claims of each entity in `claims` and record
+/// `synced_through` as the sync watermark.
+///
+/// Entity-valued claims, literal claims, and image file names previously
+/// stored for these entities are deleted before the fresh values are written,
+/// so statements removed upstream disappear locally. Entities absent from
+/// `claims` are untouched. The watermark, when supplied, is written in the
+/// same transaction, so an interrupted refresh is retried from the previous
+/// watermark.
+///
+/// # Examples
+/// ```
+/// use rusqlite::Connection;
+/// use wildside_data::wikidata::etl::{Claim, EntityClaims};
+/// use wildside_data::wikidata::store::{persist_claims, replace_claims, sync_watermark};
+///
+/// let mut conn = Connection::open_in_memory().expect("create in-memory database");
+/// conn.execute(
+///     "CREATE TABLE pois (id INTEGER PRIMARY KEY, lon REAL, lat REAL, tags TEXT)",
+///     [],
+/// )
+/// .expect("create pois table");
+/// conn.execute("INSERT INTO pois VALUES (7, 13.4, 52.5, '{}')", [])
+///     .expect("insert POI row");
+/// let entity = |value: &str| EntityClaims {
+///     entity_id: "Q64".into(),
+///     linked_poi_ids: vec![7],
+///     claims: vec![Claim::entity("P1435", value)],
+///     image_files: Vec::new(),
+///     sitelink_count: None,
+/// };
+/// persist_claims(&mut conn, &[entity("Q9259")]).expect("persist claims");
+///
+/// replace_claims(&mut conn, &[entity("Q811165")], Some("2024-09-10T08:00:00Z"))
+///     .expect("replace claims");
+///
+/// let value: String = conn
+///     .query_row("SELECT value_entity_id FROM poi_wikidata_claims", [], |row| row.get(0))
+///     .expect("query claims");
+/// assert_eq!(value, "Q811165");
+/// assert_eq!(
+///     sync_watermark(&conn).expect("read watermark").as_deref(),
+///     Some("2024-09-10T08:00:00Z"),
+/// );
+/// ```
+pub fn replace_claims(
+    connection: &mut Connection,
+    claims: &[EntityClaims],
+    synced_through: Option<&str>,
+) -> Result<(), PersistClaimsError> {
+    initialise_schema(connection)?;
+    let transaction = connection
+        .transaction()
+        .map_err(|source| PersistClaimsError::Sqlite {
+            operation: "begin replacement transaction",
+            source,
+        })?;
+    for claim in claims {
+        clear_entity_claims(&transaction, claim.entity_id.as_str())?;
+    }
+    persist_batch(&transaction, claims)?;
+    if let Some(watermark) = synced_through {
+        transaction
+            .execute(
+                concat!(
+                    "INSERT INTO wikidata_sync_state (id, synced_through) VALUES (1, ?1) ",
+                    "ON CONFLICT(id) DO UPDATE SET synced_through = excluded.synced_through",
+                ),
+                [watermark],
+            )
+            .map_err(|source| PersistClaimsError::Sqlite {
+                operation: "record sync watermark",
+                source,
+            })?;
+    }
+    transaction
+        .commit()
+        .map_err(|source| PersistClaimsError::Sqlite {
+            operation: "commit replacement transaction",
+            source,
+        })
+}
+
+fn clear_entity_claims(
+    transaction: &Transaction<'_>,
+    entity_id: &str,
+) -> Result<(), PersistClaimsError> {
+    for (operation, sql) in [
+        (
+            "clear entity claims",
+            "DELETE FROM wikidata_entity_claims WHERE entity_id = ?1",
+        ),
+        (
+            "clear literal claims",
+            "DELETE FROM wikidata_entity_literals WHERE entity_id = ?1",
+        ),
+        (
+            "clear image claims",
+            "DELETE FROM wikidata_entity_images WHERE entity_id = ?1",
+        ),
+    ] {
+        transaction
+            .prepare_cached(sql)
+            .and_then(|mut statement| statement.execute([entity_id]))
+            .map_err(|source| PersistClaimsError::Sqlite { operation, source })?;
+    }
+    Ok(())
+}
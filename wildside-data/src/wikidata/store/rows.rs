@@ -0,0 +1,264 @@
+//! Rows of one persisted chunk, gathered per table and written with
+//! multi-row `INSERT` statements.
+
+use std::collections::HashSet;
+
+use rusqlite::{CachedStatement, OptionalExtension, ToSql, Transaction, params_from_iter};
+
+use crate::wikidata::etl::{Claim, ClaimValue, EntityClaims};
+
+use super::persistence::PersistClaimsError;
+
+/// Rows written by one multi-row `INSERT` statement.
+///
+/// At three columns per row this stays well inside `SQLite`'s historical
+/// limit of 999 bound parameters per statement.
+const INSERT_BATCH_ROWS: usize = 256;
+
+/// Idempotent multi-row insert into one claims table.
+struct TableInsert {
+    /// Statement text up to and including `VALUES`.
+    head: &'static str,
+    /// Placeholder group for a single row.
+    row: &'static str,
+    /// Conflict clause appended after the last row.
+    conflict: &'static str,
+    operation: &'static str,
+}
+
+const ENTITY_INSERT: TableInsert = TableInsert {
+    head: "INSERT INTO wikidata_entities (entity_id) VALUES ",
+    row: "(?)",
+    conflict: "ON CONFLICT(entity_id) DO NOTHING",
+    operation: "insert entity",
+};
+const CLAIM_INSERT: TableInsert = TableInsert {
+    head: "INSERT INTO wikidata_entity_claims (entity_id, property_id, value_entity_id) VALUES ",
+    row: "(?, ?, ?)",
+    conflict: "ON CONFLICT(entity_id, property_id, value_entity_id) DO NOTHING",
+    operation: "insert entity claim",
+};
+const LITERAL_INSERT: TableInsert = TableInsert {
+    head: "INSERT INTO wikidata_entity_literals (entity_id, property_id, value) VALUES ",
+    row: "(?, ?, ?)",
+    conflict: "ON CONFLICT(entity_id, property_id, value) DO NOTHING",
+    operation: "insert literal claim",
+};
+const IMAGE_INSERT: TableInsert = TableInsert {
+    head: "INSERT INTO wikidata_entity_images (entity_id, file_name) VALUES ",
+    row: "(?, ?)",
+    conflict: "ON CONFLICT(entity_id, file_name) DO NOTHING",
+    operation: "insert image claim",
+};
+const SITELINK_UPSERT: TableInsert = TableInsert {
+    head: "INSERT INTO wikidata_entity_sitelinks (entity_id, sitelink_count) VALUES ",
+    row: "(?, ?)",
+    conflict: "ON CONFLICT(entity_id) DO UPDATE SET sitelink_count = excluded.sitelink_count",
+    operation: "upsert sitelink count",
+};
+const LINK_INSERT: TableInsert = TableInsert {
+    head: "INSERT INTO poi_wikidata_links (poi_id, entity_id) VALUES ",
+    row: "(?, ?)",
+    conflict: "ON CONFLICT(poi_id, entity_id) DO NOTHING",
+    operation: "link POI to entity",
+};
+
+impl TableInsert {
+    /// Statement text inserting `rows` rows at once.
+    fn sql(&self, rows: usize) -> String {
+        let mut sql = String::with_capacity(
+            self.head.len() + rows * (self.row.len() + 2) + self.conflict.len(),
+        );
+        sql.push_str(self.head);
+        for index in 0..rows {
+            if index > 0 {
+                sql.push_str(", ");
+            }
+            sql.push_str(self.row);
+        }
+        sql.push(' ');
+        sql.push_str(self.conflict);
+        sql
+    }
+
+    /// Write `rows` in statements of up to [`INSERT_BATCH_ROWS`] rows.
+    ///
+    /// Full batches share one cached statement. The shorter trailing batch
+    /// varies in length between chunks, so it is prepared uncached rather than
+    /// evicting the full-batch statements from the connection cache.
+    fn insert<T: ToSql, const N: usize>(
+        &self,
+        transaction: &Transaction<'_>,
+        rows: &[[T; N]],
+    ) -> Result<(), PersistClaimsError> {
+        let sqlite_error = |source| PersistClaimsError::Sqlite {
+            operation: self.operation,
+            source,
+        };
+        let mut batches = rows.chunks_exact(INSERT_BATCH_ROWS);
+        if rows.len() >= INSERT_BATCH_ROWS {
+            let mut statement = transaction
+                .prepare_cached(&self.sql(INSERT_BATCH_ROWS))
+                .map_err(sqlite_error)?;
+            for batch in batches.by_ref() {
+                statement
+                    .execute(params_from_iter(batch.iter().flatten()))
+                    .map_err(sqlite_error)?;
+            }
+        }
+        let remainder = batches.remainder();
+        if !remainder.is_empty() {
+            transaction
+                .prepare(&self.sql(remainder.len()))
+                .and_then(|mut statement| {
+                    statement.execute(params_from_iter(remainder.iter().flatten()))
+                })
+                .map_err(sqlite_error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Rows of one persisted chunk, gathered per table so each can be written in
+/// key order.
+#[derive(Default)]
+struct ChunkRows<'a> {
+    entities: Vec<[&'a str; 1]>,
+    seen_entities: HashSet<&'a str>,
+    claims: Vec<[&'a str; 3]>,
+    literals: Vec<[&'a str; 3]>,
+    images: Vec<[&'a str; 2]>,
+    sitelinks: Vec<(&'a str, u32)>,
+    links: Vec<(i64, &'a str)>,
+    known_pois: HashSet<u64>,
+}
+
+impl<'a> ChunkRows<'a> {
+    /// Record `entity_id` once per chunk.
+    fn add_entity(&mut self, entity_id: &'a str) {
+        if self.seen_entities.insert(entity_id) {
+            self.entities.push([entity_id]);
+        }
+    }
+
+    fn add_claims(&mut self, entity_id: &'a str, claims: &'a [Claim]) {
+        for claim in claims {
+            let property_id = claim.property_id.as_str();
+            match &claim.value {
+                ClaimValue::Entity(value_entity_id) => {
+                    self.add_entity(value_entity_id);
+                    self.claims.push([entity_id, property_id, value_entity_id]);
+                }
+                ClaimValue::Literal(value) => {
+                    self.literals.push([entity_id, property_id, value]);
+                }
+            }
+        }
+    }
+
+    fn add_entity_claims(&mut self, claims: &'a EntityClaims) {
+        let entity_id = claims.entity_id.as_str();
+        self.add_entity(entity_id);
+        self.add_claims(entity_id, &claims.claims);
+        self.images.extend(
+            claims
+                .image_files
+                .iter()
+                .map(|file_name| [entity_id, file_name.as_str()]),
+        );
+        if let Some(count) = claims.sitelink_count {
+            self.sitelinks.push((entity_id, count));
+        }
+    }
+
+    /// Queue links from `entity_id` to `poi_ids`, verifying each POI exists.
+    fn add_poi_links(
+        &mut self,
+        check_poi: &mut CachedStatement<'_>,
+        entity_id: &'a str,
+        poi_ids: &[u64],
+    ) -> Result<(), PersistClaimsError> {
+        for &poi_id in poi_ids {
+            let poi_id_i64 = i64::try_from(poi_id)
+                .map_err(|_| PersistClaimsError::PoiIdOutOfRange { poi_id })?;
+            if !self.known_pois.contains(&poi_id) {
+                verify_poi(check_poi, poi_id, entity_id)?;
+                self.known_pois.insert(poi_id);
+            }
+            self.links.push((poi_id_i64, entity_id));
+        }
+        Ok(())
+    }
+
+    /// Write every gathered row, entities first so claim rows satisfy their
+    /// foreign keys.
+    fn write(&mut self, transaction: &Transaction<'_>) -> Result<(), PersistClaimsError> {
+        self.entities.sort_unstable();
+        self.claims.sort_unstable();
+        self.literals.sort_unstable();
+        self.images.sort_unstable();
+        // A stable sort keeps the last count of a repeated entity winning.
+        self.sitelinks.sort_by_key(|(entity_id, _)| *entity_id);
+        self.links.sort_unstable();
+        ENTITY_INSERT.insert(transaction, &self.entities)?;
+        CLAIM_INSERT.insert(transaction, &self.claims)?;
+        LITERAL_INSERT.insert(transaction, &self.literals)?;
+        IMAGE_INSERT.insert(transaction, &self.images)?;
+        let sitelinks: Vec<[&dyn ToSql; 2]> = self
+            .sitelinks
+            .iter()
+            .map(|(entity_id, count)| [entity_id as &dyn ToSql, count])
+            .collect();
+        SITELINK_UPSERT.insert(transaction, &sitelinks)?;
+        let links: Vec<[&dyn ToSql; 2]> = self
+            .links
+            .iter()
+            .map(|(poi_id, entity_id)| [poi_id as &dyn ToSql, entity_id])
+            .collect();
+        LINK_INSERT.insert(transaction, &links)
+    }
+}
+
+/// Fail with [`PersistClaimsError::MissingPoi`] unless `poi_id` is in the
+/// `pois` table.
+fn verify_poi(
+    check_poi: &mut CachedStatement<'_>,
+    poi_id: u64,
+    entity_id: &str,
+) -> Result<(), PersistClaimsError> {
+    // Callers range-check ids first, so the cast preserves the value.
+    let exists = check_poi
+        .query_row([poi_id.cast_signed()], |_| Ok(()))
+        .optional()
+        .map_err(|source| PersistClaimsError::Sqlite {
+            operation: "verify POI presence",
+            source,
+        })?
+        .is_some();
+    if exists {
+        Ok(())
+    } else {
+        Err(PersistClaimsError::MissingPoi {
+            poi_id,
+            entity_id: entity_id.to_owned(),
+        })
+    }
+}
+
+pub(super) fn persist_batch(
+    transaction: &Transaction<'_>,
+    claims: &[EntityClaims],
+) -> Result<(), PersistClaimsError> {
+    let mut check_poi = transaction
+        .prepare_cached("SELECT 1 FROM pois WHERE id = ?1 LIMIT 1")
+        .map_err(|source| PersistClaimsError::Sqlite {
+            operation: "prepare POI lookup",
+            source,
+        })?;
+    let mut rows = ChunkRows::default();
+    for claim in claims {
+        rows.add_entity_claims(claim);
+        rows.add_poi_links(&mut check_poi, &claim.entity_id, &claim.linked_poi_ids)?;
+    }
+    rows.write(transaction)
+}
@@ -7,15 +7,19 @@
 
 use rusqlite::{Connection, Error as SqliteError, OptionalExtension, Transaction};
 use thiserror::Error;
+use wildside_core::formats::{CLAIMS_SCHEMA, FormatVersionError, Negotiated};
 
-pub const SCHEMA_VERSION: i64 = 1;
+/// Claims schema version recorded by [`initialise_schema`], as declared in
+/// [`wildside_core::formats::CLAIMS_SCHEMA`].
+pub const SCHEMA_VERSION: i64 = CLAIMS_SCHEMA.current as i64;
 
 /// Initialize the Wikidata claims schema inside an existing SQLite database.
 ///
 /// The function enables foreign keys, creates the supporting tables, indexes
-/// and views, and records the schema version. Existing installations must
-/// already match the expected version; mismatches are rejected so migrations
-/// can be applied explicitly.
+/// and views, and records the schema version. Installations at an older
+/// version are upgraded in place because every change so far only adds tables
+/// and views; newer versions are rejected so migrations can be applied
+/// explicitly.
 ///
 /// # Examples
 /// ```
@@ -37,7 +41,7 @@ pub const SCHEMA_VERSION: i64 = 1;
 ///         |row| row.get(0),
 ///     )
 ///     .expect("read schema version");
-/// assert_eq!(version, 1);
+/// assert_eq!(version, 6);
 /// ```
 pub fn initialise_schema(connection: &mut Connection) -> Result<(), ClaimsSchemaError> {
     connection
@@ -96,6 +100,55 @@ fn create_core_tables(transaction: &Transaction<'_>) -> Result<(), ClaimsSchemaE
             FOREIGN KEY (entity_id) REFERENCES wikidata_entities(entity_id) ON DELETE CASCADE,
             FOREIGN KEY (value_entity_id) REFERENCES wikidata_entities(entity_id) ON DELETE CASCADE
         ) WITHOUT ROWID",
+    )?;
+    run_migration_step(
+        transaction,
+        "create wikidata_entity_images",
+        "CREATE TABLE IF NOT EXISTS wikidata_entity_images (
+            entity_id TEXT NOT NULL,
+            file_name TEXT NOT NULL CHECK (length(trim(file_name)) > 0),
+            PRIMARY KEY (entity_id, file_name),
+            FOREIGN KEY (entity_id) REFERENCES wikidata_entities(entity_id) ON DELETE CASCADE
+        ) WITHOUT ROWID",
+    )?;
+    run_migration_step(
+        transaction,
+        "create wikidata_entity_literals",
+        "CREATE TABLE IF NOT EXISTS wikidata_entity_literals (
+            entity_id TEXT NOT NULL,
+            property_id TEXT NOT NULL,
+            value TEXT NOT NULL CHECK (length(trim(value)) > 0),
+            PRIMARY KEY (entity_id, property_id, value),
+            FOREIGN KEY (entity_id) REFERENCES wikidata_entities(entity_id) ON DELETE CASCADE
+        ) WITHOUT ROWID",
+    )?;
+    run_migration_step(
+        transaction,
+        "create wikidata_entity_sitelinks",
+        "CREATE TABLE IF NOT EXISTS wikidata_entity_sitelinks (
+            entity_id TEXT PRIMARY KEY,
+            sitelink_count INTEGER NOT NULL CHECK (sitelink_count >= 0),
+            FOREIGN KEY (entity_id) REFERENCES wikidata_entities(entity_id) ON DELETE CASCADE
+        ) WITHOUT ROWID",
+    )?;
+    run_migration_step(
+        transaction,
+        "create wikidata_entity_labels",
+        "CREATE TABLE IF NOT EXISTS wikidata_entity_labels (
+            entity_id TEXT NOT NULL,
+            language TEXT NOT NULL CHECK (length(trim(language)) > 0),
+            label TEXT NOT NULL CHECK (length(trim(label)) > 0),
+            PRIMARY KEY (entity_id, language),
+            FOREIGN KEY (entity_id) REFERENCES wikidata_entities(entity_id) ON DELETE CASCADE
+        ) WITHOUT ROWID",
+    )?;
+    run_migration_step(
+        transaction,
+        "create wikidata_sync_state",
+        "CREATE TABLE IF NOT EXISTS wikidata_sync_state (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            synced_through TEXT NOT NULL CHECK (length(trim(synced_through)) > 0)
+        )",
     )
 }
 
@@ -127,6 +180,46 @@ fn create_views(transaction: &Transaction<'_>) -> Result<(), ClaimsSchemaError>
             FROM poi_wikidata_links AS links
             JOIN wikidata_entity_claims AS claims
                 ON claims.entity_id = links.entity_id",
+    )?;
+    run_migration_step(
+        transaction,
+        "create poi_wikidata_images view",
+        "CREATE VIEW IF NOT EXISTS poi_wikidata_images AS
+            SELECT
+                links.poi_id AS poi_id,
+                images.entity_id AS entity_id,
+                images.file_name AS file_name
+            FROM poi_wikidata_links AS links
+            JOIN wikidata_entity_images AS images
+                ON images.entity_id = links.entity_id",
+    )?;
+    run_migration_step(
+        transaction,
+        "create poi_wikidata_literals view",
+        "CREATE VIEW IF NOT EXISTS poi_wikidata_literals AS
+            SELECT
+                links.poi_id AS poi_id,
+                literals.entity_id AS entity_id,
+                literals.property_id AS property_id,
+                literals.value AS value
+            FROM poi_wikidata_links AS links
+            JOIN wikidata_entity_literals AS literals
+                ON literals.entity_id = links.entity_id",
+    )?;
+    run_migration_step(
+        transaction,
+        "create poi_wikidata_claim_labels view",
+        "CREATE VIEW IF NOT EXISTS poi_wikidata_claim_labels AS
+            SELECT
+                claims.poi_id AS poi_id,
+                claims.entity_id AS entity_id,
+                claims.property_id AS property_id,
+                claims.value_entity_id AS value_entity_id,
+                labels.language AS language,
+                labels.label AS label
+            FROM poi_wikidata_claims AS claims
+            JOIN wikidata_entity_labels AS labels
+                ON labels.entity_id = claims.value_entity_id",
     )
 }
 
@@ -152,26 +245,21 @@ fn ensure_schema_version(transaction: &Transaction<'_>) -> Result<(), ClaimsSche
             source,
         })?;
 
-    match existing_version {
-        Some(version) if version == SCHEMA_VERSION => {}
-        Some(found) => {
-            return Err(ClaimsSchemaError::VersionMismatch {
-                expected: SCHEMA_VERSION,
-                found,
-            });
-        }
-        None => {
-            transaction
-                .execute(
-                    "INSERT INTO wikidata_schema_version (version) VALUES (?1)",
-                    [SCHEMA_VERSION],
-                )
-                .map_err(|source| ClaimsSchemaError::Migration {
-                    step: "record schema version",
-                    source,
-                })?;
-        }
+    if let Some(found) = existing_version
+        && CLAIMS_SCHEMA.negotiate(found)? == Negotiated::Current
+    {
+        return Ok(());
     }
+    // Older schemas were upgraded by the additive steps above.
+    transaction
+        .execute(
+            "INSERT INTO wikidata_schema_version (version) VALUES (?1)",
+            [SCHEMA_VERSION],
+        )
+        .map_err(|source| ClaimsSchemaError::Migration {
+            step: "record schema version",
+            source,
+        })?;
 
     Ok(())
 }
@@ -201,8 +289,6 @@ pub enum ClaimsSchemaError {
         #[source]
         source: SqliteError,
     },
-    #[error(
-        "expected Wikidata schema version {expected} but found {found}; apply migrations before retrying"
-    )]
-    VersionMismatch { expected: i64, found: i64 },
+    #[error(transparent)]
+    UnsupportedVersion(#[from] FormatVersionError),
 }
@@ -1,11 +1,18 @@
 //! Unit tests for the Wikidata claims persistence layer.
 
 mod behaviour;
+mod finalise;
+mod labels;
+mod refresh;
+mod schema;
+
+use std::num::NonZeroUsize;
 
 use super::{
-    ClaimsSchemaError, PersistClaimsError, SCHEMA_VERSION, initialise_schema, persist_claims,
+    PersistClaimsError, SCHEMA_VERSION, build_link_filter, persist_claims, persist_claims_iter,
+    poi_image_files, representative_image,
 };
-use crate::wikidata::etl::EntityClaims;
+use crate::wikidata::etl::{Claim, EntityClaims};
 use rstest::{fixture, rstest};
 use rusqlite::Connection;
 
@@ -37,39 +44,6 @@ fn connection() -> Connection {
     Connection::open_in_memory().expect("open in-memory database")
 }
 
-#[rstest]
-fn initializes_schema_records_version(mut connection: Connection) -> Result<(), ClaimsSchemaError> {
-    create_pois_table(&connection);
-
-    initialise_schema(&mut connection)?;
-
-    let version: i64 = connection
-        .query_row(
-            "SELECT version FROM wikidata_schema_version LIMIT 1",
-            [],
-            |row| row.get(0),
-        )
-        .expect("schema version present");
-    assert_eq!(version, SCHEMA_VERSION);
-
-    let table_count: i64 = connection
-        .query_row(
-            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name IN (
-                'wikidata_entities',
-                'poi_wikidata_links',
-                'wikidata_entity_claims'
-            )",
-            [],
-            |row| row.get(0),
-        )
-        .expect("query tables");
-    assert_eq!(
-        table_count, 3,
-        "expected three Wikidata tables to be created"
-    );
-    Ok(())
-}
-
 #[rstest]
 fn persists_claims_for_linked_poi(mut connection: Connection) -> Result<(), PersistClaimsError> {
     create_pois_table(&connection);
@@ -78,7 +52,9 @@ fn persists_claims_for_linked_poi(mut connection: Connection) -> Result<(), Pers
     let claims = vec![EntityClaims {
         entity_id: "Q64".into(),
         linked_poi_ids: vec![7],
-        heritage_designations: vec!["Q9259".into()],
+        claims: vec![Claim::entity("P1435", "Q9259")],
+        image_files: Vec::new(),
+        sitelink_count: None,
     }];
 
     persist_claims(&mut connection, &claims)?;
@@ -103,7 +79,9 @@ fn rejects_missing_poi(mut connection: Connection) {
     let claims = vec![EntityClaims {
         entity_id: "Q64".into(),
         linked_poi_ids: vec![42],
-        heritage_designations: vec!["Q9259".into()],
+        claims: vec![Claim::entity("P1435", "Q9259")],
+        image_files: Vec::new(),
+        sitelink_count: None,
     }];
 
     let err = persist_claims(&mut connection, &claims).expect_err("missing POI should error");
@@ -124,7 +102,9 @@ fn inserts_idempotently(mut connection: Connection) -> Result<(), PersistClaimsE
     let claims = vec![EntityClaims {
         entity_id: "Q42".into(),
         linked_poi_ids: vec![11],
-        heritage_designations: vec!["Q9259".into()],
+        claims: vec![Claim::entity("P1435", "Q9259")],
+        image_files: Vec::new(),
+        sitelink_count: None,
     }];
 
     persist_claims(&mut connection, &claims)?;
@@ -141,3 +121,246 @@ fn inserts_idempotently(mut connection: Connection) -> Result<(), PersistClaimsE
     );
     Ok(())
 }
+
+fn linked_entity(entity_id: &str, poi_id: u64) -> EntityClaims {
+    EntityClaims {
+        entity_id: entity_id.into(),
+        linked_poi_ids: vec![poi_id],
+        claims: vec![Claim::entity("P1435", "Q9259")],
+        image_files: Vec::new(),
+        sitelink_count: None,
+    }
+}
+
+#[rstest]
+fn persists_claims_iter_across_chunks(mut connection: Connection) {
+    create_pois_table(&connection);
+    for id in 1..=5 {
+        insert_poi(&connection, id);
+    }
+    let claims = (1_u64..=5).map(|id| linked_entity(&format!("Q{id}"), id));
+    let chunk_size = NonZeroUsize::new(2).expect("non-zero chunk size");
+
+    let written = persist_claims_iter(&mut connection, claims, chunk_size).expect("persist claims");
+
+    assert_eq!(written, 5);
+    let count: i64 = connection
+        .query_row("SELECT COUNT(*) FROM poi_wikidata_claims", [], |row| {
+            row.get(0)
+        })
+        .expect("count rows");
+    assert_eq!(count, 5);
+}
+
+#[rstest]
+fn persists_large_chunks_idempotently(mut connection: Connection) {
+    create_pois_table(&connection);
+    for id in 1..=700 {
+        insert_poi(&connection, id);
+    }
+    let claims: Vec<_> = (1_u64..=700)
+        .map(|id| EntityClaims {
+            sitelink_count: Some(3),
+            ..linked_entity(&format!("Q{id}"), id)
+        })
+        .collect();
+
+    persist_claims(&mut connection, &claims).expect("persist claims");
+    persist_claims(&mut connection, &claims).expect("re-persist claims");
+
+    let count = |table: &str| -> i64 {
+        connection
+            .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })
+            .expect("count rows")
+    };
+    assert_eq!(count("poi_wikidata_claims"), 700);
+    assert_eq!(count("poi_wikidata_links"), 700);
+    assert_eq!(count("wikidata_entity_sitelinks"), 700);
+}
+
+#[rstest]
+fn persist_claims_iter_keeps_committed_chunks_on_failure(mut connection: Connection) {
+    create_pois_table(&connection);
+    insert_poi(&connection, 1);
+    insert_poi(&connection, 2);
+    let claims = vec![
+        linked_entity("Q1", 1),
+        linked_entity("Q2", 2),
+        linked_entity("Q3", 3),
+    ];
+    let chunk_size = NonZeroUsize::new(2).expect("non-zero chunk size");
+
+    let err = persist_claims_iter(&mut connection, claims, chunk_size)
+        .expect_err("missing POI should error");
+
+    assert!(matches!(
+        err,
+        PersistClaimsError::MissingPoi { poi_id: 3, .. }
+    ));
+    let linked: i64 = connection
+        .query_row("SELECT COUNT(*) FROM poi_wikidata_links", [], |row| {
+            row.get(0)
+        })
+        .expect("count links");
+    assert_eq!(linked, 2, "the first chunk should stay committed");
+}
+
+#[rstest]
+fn persist_claims_iter_initialises_schema_for_empty_input(mut connection: Connection) {
+    create_pois_table(&connection);
+
+    let written = persist_claims_iter(
+        &mut connection,
+        std::iter::empty(),
+        NonZeroUsize::new(8).expect("non-zero chunk size"),
+    )
+    .expect("persist nothing");
+
+    assert_eq!(written, 0);
+    let version: i64 = connection
+        .query_row(
+            "SELECT MAX(version) FROM wikidata_schema_version",
+            [],
+            |row| row.get(0),
+        )
+        .expect("read schema version");
+    assert_eq!(version, SCHEMA_VERSION);
+}
+
+#[rstest]
+fn link_filter_covers_persisted_links(
+    mut connection: Connection,
+) -> Result<(), PersistClaimsError> {
+    create_pois_table(&connection);
+    insert_poi(&connection, 7);
+    insert_poi(&connection, 8);
+
+    let claims = vec![EntityClaims {
+        entity_id: "Q64".into(),
+        linked_poi_ids: vec![7],
+        claims: vec![Claim::entity("P1435", "Q9259")],
+        image_files: Vec::new(),
+        sitelink_count: None,
+    }];
+    persist_claims(&mut connection, &claims)?;
+
+    let filter = build_link_filter(&mut connection)?;
+
+    assert!(filter.may_contain_poi(7));
+    assert!(filter.may_contain_entity("Q64"));
+    assert!(
+        !filter.may_contain_poi(8),
+        "unlinked POI should be excluded"
+    );
+    Ok(())
+}
+
+#[rstest]
+fn persists_and_queries_image_files(mut connection: Connection) {
+    create_pois_table(&connection);
+    insert_poi(&connection, 7);
+    insert_poi(&connection, 8);
+
+    let claims = vec![EntityClaims {
+        entity_id: "Q64".into(),
+        linked_poi_ids: vec![7],
+        claims: Vec::new(),
+        image_files: vec!["Zoo.jpg".into(), "Brandenburg Gate.jpg".into()],
+        sitelink_count: None,
+    }];
+    persist_claims(&mut connection, &claims).expect("persist claims");
+    persist_claims(&mut connection, &claims).expect("persist claims twice");
+
+    let images = poi_image_files(&connection, 7).expect("query images");
+    assert_eq!(images, vec!["Brandenburg Gate.jpg", "Zoo.jpg"]);
+    assert_eq!(
+        representative_image(&connection, 7).expect("query representative image"),
+        Some("Brandenburg Gate.jpg".to_owned())
+    );
+    assert_eq!(
+        representative_image(&connection, 8).expect("query unlinked POI"),
+        None
+    );
+}
+
+#[rstest]
+fn persists_generic_entity_and_literal_claims(mut connection: Connection) {
+    create_pois_table(&connection);
+    insert_poi(&connection, 7);
+
+    let claims = vec![EntityClaims {
+        entity_id: "Q64".into(),
+        linked_poi_ids: vec![7],
+        claims: vec![
+            Claim::entity("P31", "Q515"),
+            Claim::entity("P149", "Q46261"),
+            Claim::literal("P571", "+1237-00-00T00:00:00Z"),
+        ],
+        image_files: Vec::new(),
+        sitelink_count: None,
+    }];
+    persist_claims(&mut connection, &claims).expect("persist claims");
+
+    let mut statement = connection
+        .prepare(
+            "SELECT property_id, value_entity_id FROM poi_wikidata_claims ORDER BY property_id",
+        )
+        .expect("prepare claim select");
+    let entity_rows: Vec<(String, String)> = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .expect("map claim rows")
+        .collect::<Result<_, _>>()
+        .expect("collect claim rows");
+    assert_eq!(
+        entity_rows,
+        vec![
+            ("P149".to_owned(), "Q46261".to_owned()),
+            ("P31".to_owned(), "Q515".to_owned()),
+        ]
+    );
+
+    let literal: (i64, String, String) = connection
+        .query_row(
+            "SELECT poi_id, property_id, value FROM poi_wikidata_literals",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .expect("literal claim present");
+    assert_eq!(
+        literal,
+        (7, "P571".to_owned(), "+1237-00-00T00:00:00Z".to_owned())
+    );
+}
+
+#[rstest]
+fn persists_and_refreshes_sitelink_counts(mut connection: Connection) {
+    create_pois_table(&connection);
+    insert_poi(&connection, 7);
+    insert_poi(&connection, 8);
+
+    let entity = |entity_id: &str, poi_id, sitelink_count| EntityClaims {
+        entity_id: entity_id.into(),
+        linked_poi_ids: vec![poi_id],
+        claims: Vec::new(),
+        image_files: Vec::new(),
+        sitelink_count,
+    };
+    persist_claims(
+        &mut connection,
+        &[entity("Q64", 7, Some(12)), entity("Q1", 8, None)],
+    )
+    .expect("persist claims");
+    persist_claims(&mut connection, &[entity("Q64", 7, Some(15))]).expect("persist refresh");
+
+    let mut statement = connection
+        .prepare("SELECT entity_id, sitelink_count FROM wikidata_entity_sitelinks")
+        .expect("prepare sitelink select");
+    let rows: Vec<(String, i64)> = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .expect("map sitelink rows")
+        .collect::<Result<_, _>>()
+        .expect("collect sitelink rows");
+    assert_eq!(rows, vec![("Q64".to_owned(), 15)]);
+}
@@ -1,7 +1,7 @@
 //! Behavioural tests for persisting Wikidata claims using rstest-bdd.
 
 use super::super::{PersistClaimsError, persist_claims_to_path};
-use crate::wikidata::etl::EntityClaims;
+use crate::wikidata::etl::{Claim, EntityClaims};
 use rstest::fixture;
 use rstest_bdd_macros::{given, scenario, then, when};
 use rusqlite::Connection;
@@ -88,7 +88,9 @@ fn extracted_claims(claims: &RefCell<Option<Vec<EntityClaims>>>) {
     *claims.borrow_mut() = Some(vec![EntityClaims {
         entity_id: "Q64".into(),
         linked_poi_ids: vec![11],
-        heritage_designations: vec!["Q9259".into()],
+        claims: vec![Claim::entity("P1435", "Q9259")],
+        image_files: Vec::new(),
+        sitelink_count: None,
     }]);
 }
 
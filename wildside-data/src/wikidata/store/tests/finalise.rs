@@ -0,0 +1,46 @@
+//! Tests for finalising the database once ingest has written everything.
+
+use super::super::{ARTEFACT_PAGE_SIZE, finalise_artifacts, persist_claims};
+use super::{create_pois_table, insert_poi, linked_entity};
+use rstest::rstest;
+use rusqlite::Connection;
+
+#[rstest]
+fn finalise_artifacts_indexes_analyses_and_compacts() {
+    let temp = tempfile::NamedTempFile::new().expect("create temp file");
+    let mut connection = Connection::open(temp.path()).expect("open database");
+    create_pois_table(&connection);
+    insert_poi(&connection, 1);
+    persist_claims(&mut connection, &[linked_entity("Q64", 1)]).expect("persist claims");
+    connection
+        .pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))
+        .expect("enable WAL");
+    drop(connection);
+
+    finalise_artifacts(temp.path()).expect("finalise artefacts");
+    finalise_artifacts(temp.path()).expect("finalise artefacts twice");
+
+    let connection = Connection::open(temp.path()).expect("reopen database");
+    let page_size: u32 = connection
+        .query_row("PRAGMA page_size", [], |row| row.get(0))
+        .expect("read page size");
+    assert_eq!(page_size, ARTEFACT_PAGE_SIZE);
+    let journal_mode: String = connection
+        .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+        .expect("read journal mode");
+    assert_eq!(journal_mode, "delete");
+    let statistics: i64 = connection
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_stat1 WHERE tbl = 'wikidata_entity_claims'",
+            [],
+            |row| row.get(0),
+        )
+        .expect("read planner statistics");
+    assert!(statistics > 0, "ANALYZE should record claim statistics");
+    let count: i64 = connection
+        .query_row("SELECT COUNT(*) FROM poi_wikidata_claims", [], |row| {
+            row.get(0)
+        })
+        .expect("count claims");
+    assert_eq!(count, 1, "finalising must keep persisted claims");
+}
@@ -0,0 +1,77 @@
+//! Tests for persisting and reading the labels of claim value entities.
+
+use super::super::{designation_entity_ids, entity_label, persist_claims, persist_entity_labels};
+use super::{connection, create_pois_table, insert_poi, linked_entity};
+use crate::wikidata::etl::{Claim, EntityLabel};
+use rstest::rstest;
+use rusqlite::Connection;
+
+fn label(entity_id: &str, language: &str, text: &str) -> EntityLabel {
+    EntityLabel {
+        entity_id: entity_id.into(),
+        language: language.into(),
+        label: text.into(),
+    }
+}
+
+#[rstest]
+fn labels_designations_of_linked_pois(mut connection: Connection) {
+    create_pois_table(&connection);
+    insert_poi(&connection, 7);
+    let mut entity = linked_entity("Q64", 7);
+    entity.claims = vec![
+        Claim::entity("P1435", "Q9259"),
+        Claim::entity("P1435", "Q15700818"),
+        Claim::entity("P31", "Q515"),
+    ];
+    persist_claims(&mut connection, &[entity]).expect("persist claims");
+
+    let designations = designation_entity_ids(&connection).expect("query designations");
+    assert_eq!(designations, ["Q15700818", "Q9259"]);
+
+    let written = persist_entity_labels(
+        &mut connection,
+        &[
+            label("Q9259", "en", "World Heritage Site"),
+            label("Q9259", "de", "Welterbe"),
+        ],
+    )
+    .expect("persist labels");
+    assert_eq!(written, 2);
+
+    let mut statement = connection
+        .prepare(
+            "SELECT poi_id, value_entity_id, label FROM poi_wikidata_claim_labels \
+             WHERE language = 'en'",
+        )
+        .expect("prepare view query");
+    let rows: Vec<(i64, String, String)> = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .expect("query view")
+        .collect::<Result<_, _>>()
+        .expect("read view rows");
+    assert_eq!(
+        rows,
+        [(7, "Q9259".to_owned(), "World Heritage Site".to_owned())]
+    );
+}
+
+#[rstest]
+fn persisting_labels_replaces_earlier_text(mut connection: Connection) {
+    create_pois_table(&connection);
+
+    persist_entity_labels(&mut connection, &[label("Q9259", "en", "World Heritage")])
+        .expect("persist first label");
+    persist_entity_labels(
+        &mut connection,
+        &[label("Q9259", "en", "World Heritage Site")],
+    )
+    .expect("persist renamed label");
+
+    assert_eq!(
+        entity_label(&connection, "Q9259", "en")
+            .expect("read label")
+            .as_deref(),
+        Some("World Heritage Site")
+    );
+}
@@ -0,0 +1,76 @@
+//! Tests for replacing the claims of refreshed entities.
+
+use super::super::{persist_claims, poi_image_files, replace_claims, sync_watermark};
+use super::{connection, create_pois_table, insert_poi};
+use crate::wikidata::etl::{Claim, EntityClaims};
+use rstest::rstest;
+use rusqlite::Connection;
+
+#[rstest]
+fn replace_claims_clears_stale_values_of_refreshed_entities(mut connection: Connection) {
+    create_pois_table(&connection);
+    insert_poi(&connection, 7);
+    insert_poi(&connection, 8);
+    let entity = |entity_id: &str, poi_id, claims, image_files| EntityClaims {
+        entity_id: entity_id.into(),
+        linked_poi_ids: vec![poi_id],
+        claims,
+        image_files,
+        sitelink_count: None,
+    };
+    persist_claims(
+        &mut connection,
+        &[
+            entity(
+                "Q64",
+                7,
+                vec![
+                    Claim::entity("P1435", "Q9259"),
+                    Claim::literal("P571", "+1237"),
+                ],
+                vec!["Old.jpg".into()],
+            ),
+            entity("Q90", 8, vec![Claim::entity("P1435", "Q9259")], Vec::new()),
+        ],
+    )
+    .expect("seed claims");
+
+    replace_claims(
+        &mut connection,
+        &[entity(
+            "Q64",
+            7,
+            vec![Claim::entity("P1435", "Q811165")],
+            Vec::new(),
+        )],
+        Some("2024-09-10T08:00:00Z"),
+    )
+    .expect("replace claims");
+
+    let mut statement = connection
+        .prepare("SELECT poi_id, value_entity_id FROM poi_wikidata_claims ORDER BY poi_id")
+        .expect("prepare claims query");
+    let claims: Vec<(i64, String)> = statement
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .expect("query claims")
+        .collect::<Result<_, _>>()
+        .expect("read claims");
+    assert_eq!(claims, [(7, "Q811165".to_owned()), (8, "Q9259".to_owned())]);
+    let literals: i64 = connection
+        .query_row("SELECT COUNT(*) FROM wikidata_entity_literals", [], |row| {
+            row.get(0)
+        })
+        .expect("count literals");
+    assert_eq!(literals, 0);
+    assert!(
+        poi_image_files(&connection, 7)
+            .expect("query images")
+            .is_empty()
+    );
+    assert_eq!(
+        sync_watermark(&connection)
+            .expect("read watermark")
+            .as_deref(),
+        Some("2024-09-10T08:00:00Z")
+    );
+}
@@ -0,0 +1,93 @@
+//! Tests for creating and upgrading the claims schema.
+
+use super::super::{ClaimsSchemaError, SCHEMA_VERSION, initialise_schema};
+use super::{connection, create_pois_table};
+use rstest::rstest;
+use rusqlite::Connection;
+use wildside_core::formats::FormatVersionError;
+
+#[rstest]
+fn initializes_schema_records_version(mut connection: Connection) -> Result<(), ClaimsSchemaError> {
+    create_pois_table(&connection);
+
+    initialise_schema(&mut connection)?;
+
+    let version: i64 = connection
+        .query_row(
+            "SELECT version FROM wikidata_schema_version LIMIT 1",
+            [],
+            |row| row.get(0),
+        )
+        .expect("schema version present");
+    assert_eq!(version, SCHEMA_VERSION);
+
+    let table_count: i64 = connection
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name IN (
+                'wikidata_entities',
+                'poi_wikidata_links',
+                'wikidata_entity_claims'
+            )",
+            [],
+            |row| row.get(0),
+        )
+        .expect("query tables");
+    assert_eq!(
+        table_count, 3,
+        "expected three Wikidata tables to be created"
+    );
+    Ok(())
+}
+
+#[rstest]
+fn upgrades_version_one_schema(mut connection: Connection) -> Result<(), ClaimsSchemaError> {
+    create_pois_table(&connection);
+    connection
+        .execute_batch(
+            "CREATE TABLE wikidata_schema_version (
+                version INTEGER PRIMARY KEY,
+                applied_at TEXT NOT NULL DEFAULT ''
+            ) WITHOUT ROWID;
+            INSERT INTO wikidata_schema_version (version) VALUES (1);",
+        )
+        .expect("seed version one schema");
+
+    initialise_schema(&mut connection)?;
+
+    let version: i64 = connection
+        .query_row(
+            "SELECT MAX(version) FROM wikidata_schema_version",
+            [],
+            |row| row.get(0),
+        )
+        .expect("schema version present");
+    assert_eq!(version, SCHEMA_VERSION);
+    let images_table: i64 = connection
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE name = 'wikidata_entity_images'",
+            [],
+            |row| row.get(0),
+        )
+        .expect("query tables");
+    assert_eq!(images_table, 1, "upgrade should add the images table");
+    Ok(())
+}
+
+#[rstest]
+fn rejects_newer_schema_versions(mut connection: Connection) {
+    create_pois_table(&connection);
+    initialise_schema(&mut connection).expect("initialise schema");
+    connection
+        .execute(
+            "INSERT INTO wikidata_schema_version (version) VALUES (?1)",
+            [SCHEMA_VERSION + 1],
+        )
+        .expect("record future version");
+
+    let err = initialise_schema(&mut connection).expect_err("newer schema should be rejected");
+    assert!(matches!(
+        err,
+        ClaimsSchemaError::UnsupportedVersion(FormatVersionError { found, .. })
+            if found == SCHEMA_VERSION + 1
+    ));
+}
@@ -11,3 +11,9 @@ Feature: Extract linked Wikidata claims
     And a dump with malformed JSON for the linked entity
     When I extract the linked claims
     Then a parse error is reported
+
+  Scenario: Extract claims from a gzip-compressed dump
+    Given an OSM ingest report containing linked POIs
+    And a gzip-compressed dump containing a heritage claim for the linked entity
+    When I extract the linked claims
+    Then the UNESCO heritage designation is recorded
@@ -21,7 +21,7 @@ fn provider() -> RefCell<Option<StubTravelTimeProvider>> {
 
 #[fixture]
 fn result() -> ResultCell {
-    RefCell::new(Ok(Vec::new()))
+    RefCell::new(Ok(TravelTimeMatrix::default()))
 }
 
 fn sample_pois(count: usize) -> Vec<PointOfInterest> {
@@ -39,17 +39,19 @@ fn sample_pois(count: usize) -> Vec<PointOfInterest> {
 }
 
 fn sample_matrix() -> TravelTimeMatrix {
-    vec![
+    TravelTimeMatrix::try_from(vec![
         vec![Duration::ZERO, Duration::from_mins(2)],
         vec![Duration::from_mins(2), Duration::ZERO],
-    ]
+    ])
+    .expect("sample matrix should be square")
 }
 
 fn matrix_with_nulls() -> TravelTimeMatrix {
-    vec![
+    TravelTimeMatrix::try_from(vec![
         vec![Duration::ZERO, Duration::MAX],
         vec![Duration::MAX, Duration::ZERO],
-    ]
+    ])
+    .expect("matrix with nulls should be square")
 }
 
 // --- Given steps ---
@@ -130,7 +132,7 @@ fn then_matrix(#[from(result)] result: &ResultCell) {
     let matrix = borrowed.as_ref().expect("expected Ok result");
     assert_eq!(matrix.len(), 2, "expected 2 rows");
     assert!(
-        matrix.iter().all(|row| row.len() == 2),
+        matrix.rows().all(|row| row.len() == 2),
         "expected 2 columns"
     );
     assert_eq!(matrix[0][0], Duration::ZERO, "diagonal should be zero");
@@ -0,0 +1,145 @@
+//! Criterion benchmarks for concurrent user relevance scoring.
+//!
+//! Scores the same batch of POIs from 1, 2, and 4 threads sharing one
+//! `UserRelevanceScorer`, so changes to how claim lookups share `pois.db`
+//! connections show up as throughput differences.
+//!
+//! Run benchmarks with:
+//! ```bash
+//! cargo bench --package wildside-scorer
+//! ```
+
+use std::{thread, time::Duration};
+
+use camino::Utf8PathBuf;
+use criterion::{BenchmarkId, Criterion, Throughput, black_box};
+use geo::Coord;
+use rusqlite::Connection;
+use tempfile::TempDir;
+use wildside_core::{InterestProfile, PointOfInterest, Scorer, Theme};
+use wildside_scorer::{PopularitySource, ScoreWeights, ThemeClaimMapping, UserRelevanceScorer};
+
+/// POIs scored by each thread per iteration.
+const BATCH_SIZE: u64 = 2_000;
+
+/// Thread counts to benchmark.
+const THREAD_COUNTS: &[usize] = &[1, 2, 4];
+
+/// Write a claims database linking every POI to a heritage-listed entity.
+#[expect(clippy::expect_used, reason = "benchmark setup should fail fast")]
+fn seed_database(dir: &TempDir) -> Utf8PathBuf {
+    let path = Utf8PathBuf::from_path_buf(dir.path().join("pois.db")).expect("utf8 path");
+    let mut connection = Connection::open(path.as_std_path()).expect("open database");
+    connection
+        .execute_batch(
+            "CREATE TABLE poi_wikidata_links (poi_id INTEGER NOT NULL, entity_id TEXT NOT NULL);
+             CREATE TABLE wikidata_entity_claims (
+                 entity_id TEXT NOT NULL,
+                 property_id TEXT NOT NULL,
+                 value_entity_id TEXT NOT NULL
+             );
+             CREATE INDEX idx_links_poi ON poi_wikidata_links (poi_id);
+             CREATE INDEX idx_claims_entity ON wikidata_entity_claims (entity_id);
+             CREATE VIEW poi_wikidata_claims AS
+                 SELECT links.poi_id, claims.entity_id, claims.property_id,
+                        claims.value_entity_id
+                 FROM poi_wikidata_links AS links
+                 JOIN wikidata_entity_claims AS claims
+                     ON claims.entity_id = links.entity_id;",
+        )
+        .expect("create claims schema");
+    let transaction = connection.transaction().expect("begin transaction");
+    for id in 0..BATCH_SIZE {
+        let entity = format!("Q{id}");
+        transaction
+            .execute(
+                "INSERT INTO poi_wikidata_links (poi_id, entity_id) VALUES (?1, ?2)",
+                (id, &entity),
+            )
+            .expect("link POI");
+        transaction
+            .execute(
+                "INSERT INTO wikidata_entity_claims VALUES (?1, 'P1435', 'Q9259')",
+                [&entity],
+            )
+            .expect("insert claim");
+    }
+    transaction.commit().expect("commit claims");
+    path
+}
+
+/// Score `pois` once on each of `threads` threads sharing `scorer`.
+#[expect(
+    clippy::expect_used,
+    reason = "a panicking worker should abort the benchmark"
+)]
+fn score_concurrently(
+    scorer: &UserRelevanceScorer,
+    pois: &[PointOfInterest],
+    profile: &InterestProfile,
+    threads: usize,
+) {
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    pois.iter()
+                        .map(|poi| scorer.score(poi, profile))
+                        .sum::<f32>()
+                })
+            })
+            .collect();
+        for worker in workers {
+            black_box(worker.join().expect("join scoring thread"));
+        }
+    });
+}
+
+/// Benchmark scoring throughput as the number of scoring threads grows.
+#[expect(clippy::expect_used, reason = "benchmark setup should fail fast")]
+fn bench_concurrent_scoring(c: &mut Criterion) {
+    let dir = TempDir::new().expect("create temp dir");
+    let database = seed_database(&dir);
+    let scorer = UserRelevanceScorer::from_source(
+        &database,
+        PopularitySource::None,
+        ThemeClaimMapping::default(),
+        ScoreWeights::default(),
+    )
+    .expect("construct scorer");
+    let profile = InterestProfile::new().with_weight(Theme::History, 1.0);
+    let pois: Vec<_> = (0..BATCH_SIZE)
+        .map(|id| PointOfInterest::with_empty_tags(id, Coord { x: 0.0, y: 0.0 }))
+        .collect();
+
+    let mut group = c.benchmark_group("concurrent_scoring");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(3));
+    for &threads in THREAD_COUNTS {
+        #[expect(
+            clippy::as_conversions,
+            reason = "thread counts are tiny and fit in u64"
+        )]
+        let elements = BATCH_SIZE * threads as u64;
+        group.throughput(Throughput::Elements(elements));
+        group.bench_with_input(BenchmarkId::new("threads", threads), &threads, |b, &n| {
+            b.iter(|| score_concurrently(&scorer, &pois, &profile, n));
+        });
+    }
+    group.finish();
+}
+
+// Private module containing the criterion_group! macro invocation.
+mod group {
+    use super::bench_concurrent_scoring;
+    use criterion::criterion_group;
+
+    criterion_group!(benches, bench_concurrent_scoring);
+
+    // Re-export the generated function for use by criterion_main!
+    pub use self::benches as run;
+}
+
+// The criterion_main! macro must be invoked at crate level to generate main().
+use criterion::criterion_main;
+criterion_main!(group::run);
@@ -8,6 +8,7 @@ use thiserror::Error;
 #[derive(Debug, Error)]
 pub enum PopularityError {
     /// Opening the `SQLite` database failed.
+    #[cfg(feature = "scorer-sqlite")]
     #[error("failed to open SQLite database at {path}")]
     OpenDatabase {
         /// Requested database path.
@@ -17,6 +18,7 @@ pub enum PopularityError {
         source: rusqlite::Error,
     },
     /// Preparing or executing a database query failed.
+    #[cfg(feature = "scorer-sqlite")]
     #[error("failed to query {operation}")]
     Query {
         /// Description of the failed operation.
@@ -56,6 +58,15 @@ pub enum PopularityError {
         /// Raw JSON payload describing the invalid value.
         raw_json: String,
     },
+    /// Hashing the database to record its digest failed.
+    #[error("failed to hash database at {path}")]
+    HashDatabase {
+        /// Database path.
+        path: Utf8PathBuf,
+        /// Source error from std I/O.
+        #[source]
+        source: std::io::Error,
+    },
     /// Creating the parent directory for the output file failed.
     #[error("failed to create parent directory {path}")]
     CreateParent {
@@ -74,6 +85,19 @@ pub enum PopularityError {
         #[source]
         source: std::io::Error,
     },
+    /// Loading `popularity.bin` for an export failed.
+    #[cfg(feature = "scorer-sqlite")]
+    #[error("failed to load popularity scores for export")]
+    LoadPopularity(#[source] Box<crate::UserRelevanceError>),
+    /// Encoding a popularity export as JSON failed.
+    #[error("failed to encode popularity export into {path}")]
+    EncodeExport {
+        /// Target file path.
+        path: Utf8PathBuf,
+        /// Source error from `serde_json`.
+        #[source]
+        source: serde_json::Error,
+    },
     /// Serializing the scores to `bincode` failed.
     #[error("failed to serialize popularity scores into {path}")]
     Serialise {
@@ -0,0 +1,72 @@
+//! Explanations that break a user relevance score into cited components.
+//!
+//! [`UserRelevanceScorer::explain`](crate::UserRelevanceScorer::explain)
+//! reports each component of a score together with the data that produced
+//! it: the sitelink count, heritage entity, and boosting tags behind the
+//! popularity score, the artefact that supplied it, and the claim selector
+//! that matched each theme. When a POI scores unexpectedly high or low, the
+//! explanation points at the row or artefact to investigate.
+#![forbid(unsafe_code)]
+
+use camino::Utf8PathBuf;
+use wildside_core::Theme;
+
+use crate::ClaimSelector;
+
+/// Score for a single POI, broken into its cited components.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoreExplanation {
+    /// Identifier of the explained POI.
+    pub poi_id: u64,
+    /// Final blended score, identical to [`Scorer::score`](wildside_core::Scorer::score).
+    pub score: f32,
+    /// Popularity component, absent when the scorer runs interest-only.
+    pub popularity: Option<PopularityComponent>,
+    /// Themes whose selectors matched, in [`Theme::ALL`] order.
+    pub interests: Vec<InterestComponent>,
+}
+
+/// Global popularity contribution and the signals behind it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PopularityComponent {
+    /// Normalized popularity score for the POI.
+    pub score: f32,
+    /// Weight applied to popularity when blending.
+    pub weight: f32,
+    /// Artefact the popularity score was read from.
+    pub artefact: PopularityArtefact,
+    /// Sitelink count recorded for the POI's linked entity, if known.
+    pub sitelinks: Option<u32>,
+    /// Linked entity carrying the UNESCO World Heritage designation, if any.
+    pub heritage_entity: Option<String>,
+    /// OpenStreetMap tags earning a [`TagBoosts`](crate::TagBoosts) bonus, as
+    /// `(key, value)` pairs in key order.
+    pub boosting_tags: Vec<(String, String)>,
+}
+
+/// Origin of the popularity scores used by a scorer.
+///
+/// Artefacts are identified by the path they were loaded from; their header's
+/// [`PopularityProvenance`](crate::PopularityProvenance) records when and from
+/// which database they were generated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PopularityArtefact {
+    /// Scores decoded from a `popularity.bin` file.
+    File(Utf8PathBuf),
+    /// Scores supplied in memory by the caller.
+    InMemory,
+}
+
+/// Interest contribution from a theme whose claim selector matched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterestComponent {
+    /// Theme that matched.
+    pub theme: Theme,
+    /// Visitor's interest weight for the theme, negative when the visitor
+    /// dislikes it.
+    pub weight: f32,
+    /// Selector that fired.
+    pub selector: ClaimSelector,
+    /// Linked Wikidata entity holding the matching claim.
+    pub entity_id: String,
+}
@@ -0,0 +1,215 @@
+//! Export popularity scores alongside POI names and coordinates.
+//!
+//! `popularity.bin` only stores scores keyed by POI id, which is awkward to
+//! sanity-check by eye. These helpers join the scores with each POI's `name`
+//! tag and position from `pois.db`, rank them from most to least popular, and
+//! write the result as CSV or JSON for a spreadsheet or GIS tool.
+#![forbid(unsafe_code)]
+
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+use camino::Utf8Path;
+use rusqlite::{Connection, OpenFlags};
+use serde::Serialize;
+use wildside_fs::ensure_parent_dir;
+
+use crate::{PopularityError, PopularityScores, load_popularity};
+
+const CSV_HEADER: &str = "rank,poi_id,name,longitude,latitude,popularity";
+
+/// One ranked POI in a popularity export.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct PopularityExportRow {
+    rank: usize,
+    poi_id: u64,
+    name: Option<String>,
+    longitude: f64,
+    latitude: f64,
+    popularity: f32,
+}
+
+impl PopularityExportRow {
+    fn write_csv(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{}",
+            self.rank,
+            self.poi_id,
+            csv_field(self.name.as_deref().unwrap_or_default()),
+            self.longitude,
+            self.latitude,
+            self.popularity
+        )
+    }
+}
+
+/// Write ranked popularity scores with POI names and coordinates as CSV.
+///
+/// Columns are `rank,poi_id,name,longitude,latitude,popularity`, ordered from
+/// most to least popular with ties broken by POI id. POIs without a score in
+/// `popularity_path` are omitted, and POIs without a `name` tag leave the
+/// column empty. The parent directory of `output_path` is created when
+/// missing.
+///
+/// # Errors
+/// Returns [`PopularityError::LoadPopularity`] when the scores cannot be read,
+/// the database variants when `db_path` cannot be queried, and
+/// [`PopularityError::WriteFile`] when the CSV cannot be written.
+pub fn export_popularity_csv(
+    db_path: &Utf8Path,
+    popularity_path: &Utf8Path,
+    output_path: &Utf8Path,
+) -> Result<(), PopularityError> {
+    let rows = ranked_rows(db_path, popularity_path)?;
+    let mut writer = create_output(output_path)?;
+    let write_error = |source| PopularityError::WriteFile {
+        path: output_path.to_path_buf(),
+        source,
+    };
+    writeln!(writer, "{CSV_HEADER}").map_err(write_error)?;
+    for row in &rows {
+        row.write_csv(&mut writer).map_err(write_error)?;
+    }
+    writer.flush().map_err(write_error)
+}
+
+/// Write ranked popularity scores with POI names and coordinates as JSON.
+///
+/// The output is an array of objects with the fields `rank`, `poi_id`,
+/// `name`, `longitude`, `latitude`, and `popularity`, in the order described
+/// for [`export_popularity_csv`]. A missing `name` is written as `null`.
+///
+/// # Errors
+/// Returns the errors of [`export_popularity_csv`], plus
+/// [`PopularityError::EncodeExport`] when the JSON cannot be encoded.
+pub fn export_popularity_json(
+    db_path: &Utf8Path,
+    popularity_path: &Utf8Path,
+    output_path: &Utf8Path,
+) -> Result<(), PopularityError> {
+    let rows = ranked_rows(db_path, popularity_path)?;
+    let mut writer = create_output(output_path)?;
+    serde_json::to_writer_pretty(&mut writer, &rows).map_err(|source| {
+        PopularityError::EncodeExport {
+            path: output_path.to_path_buf(),
+            source,
+        }
+    })?;
+    writer.flush().map_err(|source| PopularityError::WriteFile {
+        path: output_path.to_path_buf(),
+        source,
+    })
+}
+
+/// Join the scores in `popularity_path` with the POIs in `db_path`, ranked by
+/// descending popularity.
+fn ranked_rows(
+    db_path: &Utf8Path,
+    popularity_path: &Utf8Path,
+) -> Result<Vec<PopularityExportRow>, PopularityError> {
+    let scores = load_popularity(popularity_path)
+        .map_err(|source| PopularityError::LoadPopularity(Box::new(source)))?;
+    let connection =
+        Connection::open_with_flags(db_path.as_std_path(), OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|source| PopularityError::OpenDatabase {
+                path: db_path.to_path_buf(),
+                source,
+            })?;
+    let mut rows = scored_pois(&connection, &scores)?;
+    rows.sort_by(|left, right| {
+        right
+            .popularity
+            .partial_cmp(&left.popularity)
+            .unwrap_or(Ordering::Equal)
+            .then(left.poi_id.cmp(&right.poi_id))
+    });
+    for (index, row) in rows.iter_mut().enumerate() {
+        row.rank = index.saturating_add(1);
+    }
+    Ok(rows)
+}
+
+/// Read every POI that has a score, leaving its rank unset.
+fn scored_pois(
+    connection: &Connection,
+    scores: &PopularityScores,
+) -> Result<Vec<PopularityExportRow>, PopularityError> {
+    let mut statement = connection
+        .prepare("SELECT id, lon, lat, tags FROM pois ORDER BY id")
+        .map_err(|source| PopularityError::Query {
+            operation: "prepare POI export",
+            source,
+        })?;
+    let pois = statement
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, f64>(1)?,
+                row.get::<_, f64>(2)?,
+                row.get::<_, String>(3)?,
+            ))
+        })
+        .map_err(|source| PopularityError::Query {
+            operation: "query POIs for export",
+            source,
+        })?;
+
+    let mut rows = Vec::new();
+    for poi in pois {
+        let (poi_id_raw, longitude, latitude, tags) =
+            poi.map_err(|source| PopularityError::Query {
+                operation: "read POI export row",
+                source,
+            })?;
+        let poi_id = u64::try_from(poi_id_raw)
+            .map_err(|_| PopularityError::PoiIdOutOfRange { poi_id: poi_id_raw })?;
+        let Some(popularity) = scores.get(poi_id) else {
+            continue;
+        };
+        rows.push(PopularityExportRow {
+            rank: 0,
+            poi_id,
+            name: poi_name(&tags, poi_id)?,
+            longitude,
+            latitude,
+            popularity,
+        });
+    }
+    Ok(rows)
+}
+
+/// Return the `name` tag from a POI's JSON tags.
+fn poi_name(tags: &str, poi_id: u64) -> Result<Option<String>, PopularityError> {
+    let parsed: serde_json::Value = serde_json::from_str(tags)
+        .map_err(|source| PopularityError::ParseTags { poi_id, source })?;
+    Ok(parsed
+        .get("name")
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_owned))
+}
+
+/// Create `path`, and its parent directory when missing, for buffered writes.
+fn create_output(path: &Utf8Path) -> Result<BufWriter<File>, PopularityError> {
+    ensure_parent_dir(path).map_err(|source| PopularityError::CreateParent {
+        path: path
+            .parent()
+            .map_or_else(|| Utf8Path::new(".").to_path_buf(), Utf8Path::to_path_buf),
+        source,
+    })?;
+    let file = File::create(path.as_std_path()).map_err(|source| PopularityError::WriteFile {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    Ok(BufWriter::new(file))
+}
+
+/// Quote a CSV field when it holds a delimiter, quote, or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
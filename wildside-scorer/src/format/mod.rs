@@ -0,0 +1,251 @@
+//! Versioned header for `popularity.bin`.
+//!
+//! From format version 2 the artefact starts with the [`POPULARITY_MAGIC`]
+//! bytes and a little-endian `u16` version, followed by the `bincode` payload.
+//! Version 3 adds one byte after the version recording the
+//! [`PopularityNormalization`] code the scores were produced with; version 2
+//! scores were always divided by the maximum. Version 4 follows the
+//! normalization byte with the [`PopularityProvenance`]: a little-endian `u64`
+//! generation time in seconds since the Unix epoch and the 32-byte SHA-256
+//! digest of the `pois.db` the scores were computed from. Version 5 inserts a
+//! byte between the normalization code and the provenance recording the
+//! [`PopularityScope`] code; older scores were normalized globally. Version 1
+//! files are the bare payload; readers still accept them while
+//! [`wildside_core::formats::POPULARITY`] lists version 1 as readable.
+
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+use wildside_core::formats::{FormatVersionError, POPULARITY};
+
+use crate::{PopularityNormalization, PopularityScope};
+
+/// File identifier for versioned popularity artefacts.
+pub const POPULARITY_MAGIC: [u8; 4] = *b"WSPP";
+
+/// Length of the SHA-256 digest recorded in the header.
+pub const SOURCE_DIGEST_LEN: usize = 32;
+
+/// Version assigned to headerless popularity files.
+const HEADERLESS_VERSION: u16 = 1;
+
+/// First version whose header records the normalization mode.
+const NORMALIZATION_VERSION: u16 = 3;
+
+/// First version whose header records the [`PopularityProvenance`].
+const PROVENANCE_VERSION: u16 = 4;
+
+/// First version whose header records the [`PopularityScope`].
+const SCOPE_VERSION: u16 = 5;
+
+/// Fields recorded ahead of a popularity artefact's payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PopularityHeader {
+    /// Format version of the artefact.
+    pub version: u16,
+    /// Normalization the scores were produced with.
+    pub normalization: PopularityNormalization,
+    /// Which POIs each score was normalized against.
+    pub scope: PopularityScope,
+    /// When and from which database the scores were computed; `None` for
+    /// files older than version 4.
+    pub provenance: Option<PopularityProvenance>,
+}
+
+/// When a popularity artefact was generated and from which database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PopularityProvenance {
+    /// Seconds since the Unix epoch when the scores were computed.
+    pub generated_at: u64,
+    /// SHA-256 digest of the `pois.db` the scores were computed from.
+    pub source_sha256: [u8; SOURCE_DIGEST_LEN],
+}
+
+impl PopularityProvenance {
+    /// Stamp the current time and the digest of the database at `source`.
+    ///
+    /// # Errors
+    /// Returns the I/O error raised while reading `source`.
+    pub fn for_source(source: impl AsRef<Path>) -> io::Result<Self> {
+        let generated_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Ok(Self {
+            generated_at,
+            source_sha256: source_digest(source)?,
+        })
+    }
+
+    /// Report whether the scores were computed from the database at `source`
+    /// as it is now.
+    ///
+    /// # Errors
+    /// Returns the I/O error raised while reading `source`.
+    pub fn matches_source(&self, source: impl AsRef<Path>) -> io::Result<bool> {
+        source_digest(source).map(|digest| digest == self.source_sha256)
+    }
+}
+
+/// Hash the file at `path` with SHA-256, streaming its contents.
+fn source_digest(path: impl AsRef<Path>) -> io::Result<[u8; SOURCE_DIGEST_LEN]> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(hasher.finalize().into())
+}
+
+/// Errors raised while reading a popularity artefact header.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PopularityHeaderError {
+    /// The recorded format version is not readable by this build.
+    #[error(transparent)]
+    Version(#[from] FormatVersionError),
+    /// The header ends before the fields its version requires.
+    #[error("popularity header ends before its version {version} fields")]
+    Truncated {
+        /// Format version read from the header.
+        version: u16,
+    },
+    /// The header records a normalization this build does not know.
+    #[error("unknown popularity normalization code {code}")]
+    UnknownNormalization {
+        /// Code read from the header.
+        code: u8,
+    },
+    /// The header records a normalization scope this build does not know.
+    #[error("unknown popularity scope code {code}")]
+    UnknownScope {
+        /// Code read from the header.
+        code: u8,
+    },
+}
+
+/// Write the magic bytes, current format version, `normalization` and
+/// `scope` codes, and `provenance` ahead of the payload.
+///
+/// # Errors
+/// Propagates I/O errors from `writer`.
+#[expect(
+    clippy::little_endian_bytes,
+    reason = "artefact headers are little-endian like the spatial index"
+)]
+pub fn write_popularity_header<W: Write>(
+    writer: &mut W,
+    normalization: PopularityNormalization,
+    scope: PopularityScope,
+    provenance: PopularityProvenance,
+) -> io::Result<()> {
+    writer.write_all(&POPULARITY_MAGIC)?;
+    writer.write_all(&POPULARITY.current.to_le_bytes())?;
+    writer.write_all(&[normalization.code(), scope.code()])?;
+    writer.write_all(&provenance.generated_at.to_le_bytes())?;
+    writer.write_all(&provenance.source_sha256)
+}
+
+/// Read the header of a popularity artefact and return it with the payload.
+///
+/// Bytes without the magic prefix are treated as a headerless version 1 file
+/// and returned unchanged. Files older than version 3 report
+/// [`PopularityNormalization::Max`], the only mode they were written with,
+/// files older than version 4 carry no provenance, and files older than
+/// version 5 report [`PopularityScope::Global`].
+///
+/// # Errors
+/// Returns [`PopularityHeaderError`] when the recorded version is not
+/// readable by this build, the header is cut short, or the normalization or
+/// scope code is unknown.
+#[expect(
+    clippy::little_endian_bytes,
+    reason = "artefact headers are little-endian like the spatial index"
+)]
+pub fn read_popularity_header(
+    bytes: &[u8],
+) -> Result<(PopularityHeader, &[u8]), PopularityHeaderError> {
+    let (version, fields) = bytes
+        .split_first_chunk::<4>()
+        .filter(|(magic, _)| **magic == POPULARITY_MAGIC)
+        .and_then(|(_, rest)| rest.split_first_chunk::<2>())
+        .map_or((HEADERLESS_VERSION, bytes), |(version, payload)| {
+            (u16::from_le_bytes(*version), payload)
+        });
+    POPULARITY.negotiate(i64::from(version))?;
+    let (normalization, rest) = read_normalization(version, fields)?;
+    let (scope, remainder) = read_scope(version, rest)?;
+    let (provenance, payload) = read_provenance(version, remainder)?;
+    let header = PopularityHeader {
+        version,
+        normalization,
+        scope,
+        provenance,
+    };
+    Ok((header, payload))
+}
+
+fn read_normalization(
+    version: u16,
+    bytes: &[u8],
+) -> Result<(PopularityNormalization, &[u8]), PopularityHeaderError> {
+    if version < NORMALIZATION_VERSION {
+        return Ok((PopularityNormalization::Max, bytes));
+    }
+    let (&code, rest) = bytes
+        .split_first()
+        .ok_or(PopularityHeaderError::Truncated { version })?;
+    let normalization = PopularityNormalization::from_code(code)
+        .ok_or(PopularityHeaderError::UnknownNormalization { code })?;
+    Ok((normalization, rest))
+}
+
+fn read_scope(
+    version: u16,
+    bytes: &[u8],
+) -> Result<(PopularityScope, &[u8]), PopularityHeaderError> {
+    if version < SCOPE_VERSION {
+        return Ok((PopularityScope::Global, bytes));
+    }
+    let (&code, rest) = bytes
+        .split_first()
+        .ok_or(PopularityHeaderError::Truncated { version })?;
+    let scope =
+        PopularityScope::from_code(code).ok_or(PopularityHeaderError::UnknownScope { code })?;
+    Ok((scope, rest))
+}
+
+#[expect(
+    clippy::little_endian_bytes,
+    reason = "artefact headers are little-endian like the spatial index"
+)]
+fn read_provenance(
+    version: u16,
+    bytes: &[u8],
+) -> Result<(Option<PopularityProvenance>, &[u8]), PopularityHeaderError> {
+    if version < PROVENANCE_VERSION {
+        return Ok((None, bytes));
+    }
+    let (generated_at, rest) = bytes
+        .split_first_chunk::<8>()
+        .ok_or(PopularityHeaderError::Truncated { version })?;
+    let (source_sha256, payload) = rest
+        .split_first_chunk::<SOURCE_DIGEST_LEN>()
+        .ok_or(PopularityHeaderError::Truncated { version })?;
+    let provenance = PopularityProvenance {
+        generated_at: u64::from_le_bytes(*generated_at),
+        source_sha256: *source_sha256,
+    };
+    Ok((Some(provenance), payload))
+}
+
+/// Check the header of a popularity artefact and return its payload.
+///
+/// # Errors
+/// Returns [`PopularityHeaderError`] under the same conditions as
+/// [`read_popularity_header`].
+pub fn popularity_payload(bytes: &[u8]) -> Result<&[u8], PopularityHeaderError> {
+    read_popularity_header(bytes).map(|(_, payload)| payload)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,173 @@
+//! Tests for the popularity artefact header.
+
+use super::*;
+use rstest::rstest;
+
+const PROVENANCE: PopularityProvenance = PopularityProvenance {
+    generated_at: 1_792_000_000,
+    source_sha256: [7; SOURCE_DIGEST_LEN],
+};
+
+#[rstest]
+#[case(PopularityNormalization::Max)]
+#[case(PopularityNormalization::Log)]
+#[case(PopularityNormalization::PercentileRank)]
+#[case(PopularityNormalization::ZScore)]
+fn header_round_trips_payload_normalization_and_scope(
+    #[case] normalization: PopularityNormalization,
+) {
+    for scope in PopularityScope::ALL {
+        let mut bytes = Vec::new();
+        write_popularity_header(&mut bytes, normalization, scope, PROVENANCE)
+            .expect("write header");
+        bytes.extend_from_slice(b"payload");
+
+        let (header, payload) = read_popularity_header(&bytes).expect("read header");
+        assert_eq!(payload, b"payload");
+        assert_eq!(header.version, POPULARITY.current);
+        assert_eq!(header.normalization, normalization);
+        assert_eq!(header.scope, scope);
+        assert_eq!(header.provenance, Some(PROVENANCE));
+    }
+}
+
+#[rstest]
+fn provenance_matches_only_the_hashed_source() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let source = dir.path().join("pois.db");
+    std::fs::write(&source, b"abc").expect("write source");
+
+    let provenance = PopularityProvenance::for_source(&source).expect("hash source");
+    assert_eq!(
+        provenance.source_sha256.first_chunk::<4>(),
+        Some(&[0xba, 0x78, 0x16, 0xbf])
+    );
+    assert!(provenance.matches_source(&source).expect("rehash source"));
+
+    std::fs::write(&source, b"abd").expect("rewrite source");
+    assert!(!provenance.matches_source(&source).expect("rehash source"));
+}
+
+#[rstest]
+#[expect(
+    clippy::little_endian_bytes,
+    reason = "tests build little-endian headers"
+)]
+fn version_three_headers_carry_no_provenance() {
+    let mut bytes = POPULARITY_MAGIC.to_vec();
+    bytes.extend_from_slice(&NORMALIZATION_VERSION.to_le_bytes());
+    bytes.push(PopularityNormalization::Log.code());
+    bytes.extend_from_slice(b"payload");
+
+    let (header, payload) = read_popularity_header(&bytes).expect("read header");
+    assert_eq!(payload, b"payload");
+    assert_eq!(header.normalization, PopularityNormalization::Log);
+    assert_eq!(header.provenance, None);
+}
+
+#[rstest]
+fn truncated_provenance_is_rejected() {
+    let mut bytes = Vec::new();
+    write_popularity_header(
+        &mut bytes,
+        PopularityNormalization::Max,
+        PopularityScope::Global,
+        PROVENANCE,
+    )
+    .expect("write header");
+    bytes.pop();
+
+    assert_eq!(
+        popularity_payload(&bytes),
+        Err(PopularityHeaderError::Truncated {
+            version: POPULARITY.current
+        })
+    );
+}
+
+#[rstest]
+#[expect(
+    clippy::little_endian_bytes,
+    reason = "tests build little-endian headers"
+)]
+fn version_four_headers_were_normalized_globally() {
+    let mut bytes = POPULARITY_MAGIC.to_vec();
+    bytes.extend_from_slice(&PROVENANCE_VERSION.to_le_bytes());
+    bytes.push(PopularityNormalization::Log.code());
+    bytes.extend_from_slice(&PROVENANCE.generated_at.to_le_bytes());
+    bytes.extend_from_slice(&PROVENANCE.source_sha256);
+    bytes.extend_from_slice(b"payload");
+
+    let (header, payload) = read_popularity_header(&bytes).expect("read header");
+    assert_eq!(payload, b"payload");
+    assert_eq!(header.scope, PopularityScope::Global);
+    assert_eq!(header.provenance, Some(PROVENANCE));
+}
+
+#[rstest]
+#[expect(
+    clippy::little_endian_bytes,
+    reason = "tests build little-endian headers"
+)]
+fn unknown_scope_codes_are_rejected() {
+    let mut bytes = POPULARITY_MAGIC.to_vec();
+    bytes.extend_from_slice(&SCOPE_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&[PopularityNormalization::Max.code(), u8::MAX]);
+
+    assert_eq!(
+        popularity_payload(&bytes),
+        Err(PopularityHeaderError::UnknownScope { code: u8::MAX })
+    );
+}
+
+#[rstest]
+fn headerless_bytes_are_read_as_version_one() {
+    assert_eq!(popularity_payload(b"legacy"), Ok(&b"legacy"[..]));
+}
+
+#[rstest]
+#[expect(
+    clippy::little_endian_bytes,
+    reason = "tests build little-endian headers"
+)]
+fn version_two_headers_were_max_normalized() {
+    let mut bytes = POPULARITY_MAGIC.to_vec();
+    bytes.extend_from_slice(&2_u16.to_le_bytes());
+    bytes.extend_from_slice(b"payload");
+
+    let (header, payload) = read_popularity_header(&bytes).expect("read header");
+    assert_eq!(payload, b"payload");
+    assert_eq!(header.normalization, PopularityNormalization::Max);
+}
+
+#[rstest]
+#[expect(
+    clippy::little_endian_bytes,
+    reason = "tests build little-endian headers"
+)]
+fn unknown_normalization_codes_are_rejected() {
+    let mut bytes = POPULARITY_MAGIC.to_vec();
+    bytes.extend_from_slice(&NORMALIZATION_VERSION.to_le_bytes());
+    bytes.push(u8::MAX);
+
+    assert_eq!(
+        popularity_payload(&bytes),
+        Err(PopularityHeaderError::UnknownNormalization { code: u8::MAX })
+    );
+}
+
+#[rstest]
+#[expect(
+    clippy::little_endian_bytes,
+    reason = "tests build little-endian headers"
+)]
+fn newer_versions_are_rejected() {
+    let mut bytes = POPULARITY_MAGIC.to_vec();
+    bytes.extend_from_slice(&(POPULARITY.current + 1).to_le_bytes());
+
+    let error = popularity_payload(&bytes).expect_err("newer version should fail");
+    let PopularityHeaderError::Version(version) = error else {
+        panic!("expected a version error, got {error}");
+    };
+    assert_eq!(version.found, i64::from(POPULARITY.current + 1));
+}
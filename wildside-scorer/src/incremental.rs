@@ -0,0 +1,112 @@
+//! Refresh popularity scores after small changes to `pois.db`.
+//!
+//! Normalization depends on every POI's raw score, so normalized scores alone
+//! cannot be patched. [`RawPopularityScores`] keeps the raw values from
+//! [`compute_raw_popularity_scores`](crate::compute_raw_popularity_scores),
+//! letting [`update_popularity_scores`] re-read only the POIs an OSM diff or
+//! claims refresh touched and normalize again in memory.
+#![forbid(unsafe_code)]
+
+use std::collections::{BTreeSet, HashMap};
+
+use camino::Utf8Path;
+use wildside_core::Theme;
+
+use crate::normalize::normalize_within_categories;
+use crate::{PopularityError, PopularityScores, PopularityWeights, open_database, read_raw_scores};
+
+/// POIs read per query, keeping the heritage parameters and one parameter per
+/// POI within `SQLite`'s default limit of 999 variables.
+const MAX_POIS_PER_QUERY: usize = 997;
+
+/// Raw popularity scores and the weights that produced them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawPopularityScores {
+    weights: PopularityWeights,
+    scores: HashMap<u64, (f32, Option<Theme>)>,
+}
+
+impl RawPopularityScores {
+    pub(crate) const fn new(
+        weights: PopularityWeights,
+        scores: HashMap<u64, (f32, Option<Theme>)>,
+    ) -> Self {
+        Self { weights, scores }
+    }
+
+    /// Return the weights used to compute the scores.
+    #[must_use]
+    pub const fn weights(&self) -> PopularityWeights {
+        self.weights
+    }
+
+    /// Return the raw score for a POI, if present.
+    #[must_use]
+    pub fn get(&self, poi_id: u64) -> Option<f32> {
+        self.scores.get(&poi_id).map(|&(score, _)| score)
+    }
+
+    /// Return the number of scored POIs.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    /// Report whether any scores are present.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    /// Normalize the raw scores as the weights' normalization and scope
+    /// select.
+    #[must_use]
+    pub fn normalized(&self) -> PopularityScores {
+        PopularityScores::new(normalize_within_categories(
+            &self.scores,
+            self.weights.normalization,
+        ))
+    }
+}
+
+/// Recompute the raw scores of `changed_ids` in `existing`, then return every
+/// POI's normalized score.
+///
+/// Only the changed POIs are read from `db_path`, using the weights `existing`
+/// was computed with. Changed POIs no longer in the database are dropped, and
+/// new ones are added. The result matches [`compute_popularity_scores`] over
+/// the updated database provided every changed POI is listed.
+///
+/// [`compute_popularity_scores`]: crate::compute_popularity_scores
+///
+/// # Errors
+/// Returns [`PopularityError`] when the database cannot be opened or queried,
+/// or when a changed POI's tags are invalid. `existing` is left untouched on
+/// error.
+pub fn update_popularity_scores(
+    db_path: &Utf8Path,
+    existing: &mut RawPopularityScores,
+    changed_ids: &[u64],
+) -> Result<PopularityScores, PopularityError> {
+    let connection = open_database(db_path)?;
+    // SQLite rowids are signed, so larger identifiers can only be removals.
+    let (queried, removed): (BTreeSet<u64>, BTreeSet<u64>) = changed_ids
+        .iter()
+        .partition(|&&poi_id| i64::try_from(poi_id).is_ok());
+    let ids: Vec<i64> = queried
+        .iter()
+        .filter_map(|&poi_id| i64::try_from(poi_id).ok())
+        .collect();
+
+    let mut fresh = HashMap::new();
+    for chunk in ids.chunks(MAX_POIS_PER_QUERY) {
+        fresh.extend(read_raw_scores(&connection, existing.weights, Some(chunk))?);
+    }
+    for poi_id in queried.into_iter().chain(removed) {
+        match fresh.remove(&poi_id) {
+            Some(score) => existing.scores.insert(poi_id, score),
+            None => existing.scores.remove(&poi_id),
+        };
+    }
+    Ok(existing.normalized())
+}
@@ -2,20 +2,32 @@
 //!
 //! The crate provides two complementary capabilities:
 //! - **Offline popularity computation** walks a `pois.db` `SQLite` database,
-//!   extracts popularity signals, normalizes them into the `0.0..=1.0` range,
-//!   and optionally serializes the resulting scores to `popularity.bin` via
-//!   `bincode`. Popularity is derived from two signals: Wikidata sitelink
-//!   counts per linked entity, and UNESCO World Heritage designation
-//!   (`P1435=Q9259`).
+//!   extracts popularity signals, normalizes them into the `0.0..=1.0` range
+//!   with a selectable [`PopularityNormalization`], and optionally serializes
+//!   the resulting scores to `popularity.bin` via `bincode`. Popularity is
+//!   derived from Wikidata sitelink counts per linked entity, UNESCO World
+//!   Heritage designation (`P1435=Q9259`), and [`TagBoosts`] read from the
+//!   POI's own OpenStreetMap tags.
 //! - **Request-time user relevance scoring** combines per-theme interests from
 //!   an [`InterestProfile`](wildside_core::InterestProfile) with fast, indexed
 //!   lookups against `pois.db` and the pre-computed popularity scores. It
 //!   implements the [`Scorer`](wildside_core::Scorer) trait so callers can
 //!   plug the scorer into route solvers.
+//! - **Tag-driven theme relevance** through [`TagThemeScorer`], which reads
+//!   themes from OpenStreetMap tags alone for regions with sparse Wikidata
+//!   coverage.
+//!
+//! Both `SQLite` capabilities sit behind the default `scorer-sqlite` feature.
+//! Without it the crate only exposes the popularity artefact types
+//! ([`PopularityScores`], [`PopularityWeights`], [`TagBoosts`]), their
+//! `bincode` options, and [`TagThemeScorer`], so consumers that load
+//! `popularity.bin` elsewhere avoid linking `SQLite`.
 //!
 //! # Examples
 //!
 //! ```no_run
+//! # #[cfg(feature = "scorer-sqlite")]
+//! # fn main() {
 //! use camino::Utf8Path;
 //! use wildside_scorer::{PopularityWeights, write_popularity_file};
 //!
@@ -23,36 +35,88 @@
 //! let output = Utf8Path::new("artifacts/popularity.bin");
 //! let weights = PopularityWeights::default();
 //! write_popularity_file(db_path, output, weights).expect("persist popularity scores");
+//! # }
+//! # #[cfg(not(feature = "scorer-sqlite"))]
+//! # fn main() {}
 //! ```
 
 #![forbid(unsafe_code)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+#[cfg(feature = "scorer-sqlite")]
 use std::collections::HashMap;
+#[cfg(feature = "scorer-sqlite")]
 use std::fs::File;
+#[cfg(feature = "scorer-sqlite")]
 use std::io::BufWriter;
 
+#[cfg(feature = "scorer-sqlite")]
 use bincode::Options;
+#[cfg(feature = "scorer-sqlite")]
 use camino::Utf8Path;
-use rusqlite::Connection;
+#[cfg(feature = "scorer-sqlite")]
+use rusqlite::{Connection, ToSql, params_from_iter};
+#[cfg(feature = "scorer-sqlite")]
+use wildside_core::{Tags, Theme};
+#[cfg(feature = "scorer-sqlite")]
 use wildside_fs::ensure_parent_dir;
 
 mod error;
+#[cfg(feature = "scorer-sqlite")]
+mod explain;
+#[cfg(feature = "scorer-sqlite")]
+mod export;
+mod format;
+#[cfg(feature = "scorer-sqlite")]
+mod incremental;
+#[cfg(feature = "scorer-sqlite")]
+mod mapping;
+#[cfg(feature = "scorer-sqlite")]
+mod normalize;
+#[cfg(feature = "scorer-sqlite")]
 pub(crate) mod resolver;
+mod tag_theme;
 mod types;
+#[cfg(feature = "scorer-sqlite")]
 mod user;
 
 pub use error::PopularityError;
-pub use types::{PopularityScores, PopularityWeights};
+#[cfg(feature = "scorer-sqlite")]
+#[cfg_attr(docsrs, doc(cfg(feature = "scorer-sqlite")))]
+pub use explain::{InterestComponent, PopularityArtefact, PopularityComponent, ScoreExplanation};
+#[cfg(feature = "scorer-sqlite")]
+#[cfg_attr(docsrs, doc(cfg(feature = "scorer-sqlite")))]
+pub use export::{export_popularity_csv, export_popularity_json};
+pub use format::{
+    POPULARITY_MAGIC, PopularityHeader, PopularityHeaderError, PopularityProvenance,
+    SOURCE_DIGEST_LEN, popularity_payload, read_popularity_header, write_popularity_header,
+};
+#[cfg(feature = "scorer-sqlite")]
+#[cfg_attr(docsrs, doc(cfg(feature = "scorer-sqlite")))]
+pub use incremental::{RawPopularityScores, update_popularity_scores};
+#[cfg(feature = "scorer-sqlite")]
+#[cfg_attr(docsrs, doc(cfg(feature = "scorer-sqlite")))]
+pub use mapping::ThemeMappingError;
+pub use tag_theme::{TagRule, TagThemeError, TagThemeRules, TagThemeScorer};
+pub use types::{
+    PopularityNormalization, PopularityScope, PopularityScores, PopularityWeights, TagBoosts,
+};
+#[cfg(feature = "scorer-sqlite")]
+#[cfg_attr(docsrs, doc(cfg(feature = "scorer-sqlite")))]
 pub use user::{
-    ClaimSelector, ScoreWeights, ThemeClaimMapping, UserRelevanceError, UserRelevanceScorer,
+    ClaimSelector, PopularitySource, ScoreWeights, ThemeClaimMapping, UserRelevanceError,
+    UserRelevanceScorer, load_popularity, load_popularity_with_header,
 };
 
+#[cfg(feature = "scorer-sqlite")]
 use resolver::SitelinkResolver;
 
+#[cfg(feature = "scorer-sqlite")]
 pub(crate) const HERITAGE_PROPERTY: &str = "P1435";
+#[cfg(feature = "scorer-sqlite")]
 pub(crate) const SITELINK_TABLE: &str = "wikidata_entity_sitelinks";
-const UNESCO_WORLD_HERITAGE: &str = "Q9259";
+#[cfg(feature = "scorer-sqlite")]
+pub(crate) const UNESCO_WORLD_HERITAGE: &str = "Q9259";
 
 /// Bincode options used for serializing and deserializing popularity scores.
 pub(crate) fn bincode_options() -> impl bincode::Options {
@@ -67,38 +131,72 @@ pub fn popularity_bincode_options() -> impl bincode::Options {
 
 /// Compute normalized popularity scores for all POIs in a `pois.db` database.
 ///
+/// Raw scores are mapped into `0.0..=1.0` with `weights.normalization`, across
+/// every POI or within each POI's category as `weights.scope` selects.
+///
 /// # Errors
 /// Returns [`PopularityError`] when the `SQLite` database cannot be opened,
 /// queried, or when tag payloads contain invalid sitelink values.
+#[cfg(feature = "scorer-sqlite")]
+#[cfg_attr(docsrs, doc(cfg(feature = "scorer-sqlite")))]
 pub fn compute_popularity_scores(
     db_path: &Utf8Path,
     weights: PopularityWeights,
 ) -> Result<PopularityScores, PopularityError> {
-    let mut connection = Connection::open(db_path.as_std_path()).map_err(|source| {
-        PopularityError::OpenDatabase {
-            path: db_path.to_path_buf(),
-            source,
-        }
-    })?;
+    compute_raw_popularity_scores(db_path, weights).map(|raw| raw.normalized())
+}
 
-    let raw = read_raw_scores(&mut connection, weights)?;
-    let normalized = normalize_scores(&raw);
-    Ok(PopularityScores::new(normalized))
+/// Compute the raw, unnormalized popularity scores for all POIs in a
+/// `pois.db` database.
+///
+/// Keep the result to refresh scores with [`update_popularity_scores`] after
+/// a small change to the database rather than re-reading every POI.
+///
+/// # Errors
+/// Returns the same errors as [`compute_popularity_scores`].
+#[cfg(feature = "scorer-sqlite")]
+#[cfg_attr(docsrs, doc(cfg(feature = "scorer-sqlite")))]
+pub fn compute_raw_popularity_scores(
+    db_path: &Utf8Path,
+    weights: PopularityWeights,
+) -> Result<RawPopularityScores, PopularityError> {
+    let connection = open_database(db_path)?;
+    let raw = read_raw_scores(&connection, weights, None)?;
+    Ok(RawPopularityScores::new(weights, raw))
+}
+
+#[cfg(feature = "scorer-sqlite")]
+pub(crate) fn open_database(db_path: &Utf8Path) -> Result<Connection, PopularityError> {
+    Connection::open(db_path.as_std_path()).map_err(|source| PopularityError::OpenDatabase {
+        path: db_path.to_path_buf(),
+        source,
+    })
 }
 
 /// Compute popularity scores and persist them to `popularity.bin`.
 ///
-/// The parent directory is created when missing. The function returns the
-/// in-memory scores as well as writing them to disk.
+/// The parent directory is created when missing, and the header records the
+/// normalization mode and scope, the generation time, and the SHA-256 digest of
+/// `db_path`, so scorers can reject the file once the database changes. The
+/// function returns the in-memory scores as well as writing them to disk.
 ///
 /// # Errors
 /// Propagates errors from [`compute_popularity_scores`] and from filesystem
-/// interactions when creating the output file or serializing the scores.
+/// interactions when hashing the database, creating the output file, or
+/// serializing the scores.
+#[cfg(feature = "scorer-sqlite")]
+#[cfg_attr(docsrs, doc(cfg(feature = "scorer-sqlite")))]
 pub fn write_popularity_file(
     db_path: &Utf8Path,
     output_path: &Utf8Path,
     weights: PopularityWeights,
 ) -> Result<PopularityScores, PopularityError> {
+    let provenance = PopularityProvenance::for_source(db_path).map_err(|source| {
+        PopularityError::HashDatabase {
+            path: db_path.to_path_buf(),
+            source,
+        }
+    })?;
     let scores = compute_popularity_scores(db_path, weights)?;
     ensure_parent_dir(output_path).map_err(|source| PopularityError::CreateParent {
         path: output_path
@@ -111,7 +209,17 @@ pub fn write_popularity_file(
             path: output_path.to_path_buf(),
             source,
         })?;
-    let writer = BufWriter::new(file);
+    let mut writer = BufWriter::new(file);
+    write_popularity_header(
+        &mut writer,
+        weights.normalization,
+        weights.scope,
+        provenance,
+    )
+    .map_err(|source| PopularityError::WriteFile {
+        path: output_path.to_path_buf(),
+        source,
+    })?;
     bincode_options()
         .serialize_into(writer, &scores)
         .map_err(|source| PopularityError::Serialise {
@@ -121,43 +229,66 @@ pub fn write_popularity_file(
     Ok(scores)
 }
 
-fn read_raw_scores(
-    connection: &mut Connection,
+/// Select each POI's tags, linked entity, and UNESCO designation.
+#[cfg(feature = "scorer-sqlite")]
+const RAW_SCORE_SQL: &str = "SELECT
+        pois.id,
+        pois.tags,
+        links.entity_id,
+        CASE
+            WHEN links.entity_id IS NULL THEN 0
+            ELSE EXISTS(
+                SELECT 1 FROM wikidata_entity_claims AS claims
+                WHERE claims.entity_id = links.entity_id
+                  AND claims.property_id = ?1
+                  AND claims.value_entity_id = ?2
+            )
+        END AS is_heritage
+     FROM pois
+     LEFT JOIN poi_wikidata_links AS links ON links.poi_id = pois.id";
+
+/// Read each POI's raw score, paired with its category when `weights.scope`
+/// normalizes within categories.
+///
+/// Every POI is read when `poi_ids` is `None`; otherwise only the listed POIs
+/// that still exist.
+#[cfg(feature = "scorer-sqlite")]
+pub(crate) fn read_raw_scores(
+    connection: &Connection,
     weights: PopularityWeights,
-) -> Result<HashMap<u64, f32>, PopularityError> {
+    poi_ids: Option<&[i64]>,
+) -> Result<HashMap<u64, (f32, Option<Theme>)>, PopularityError> {
     let mut resolver = SitelinkResolver::new(connection)?;
+    let query = poi_ids.map_or_else(
+        || RAW_SCORE_SQL.to_owned(),
+        |ids| {
+            let placeholders = vec!["?"; ids.len()].join(", ");
+            format!("{RAW_SCORE_SQL} WHERE pois.id IN ({placeholders})")
+        },
+    );
     let mut statement = connection
-        .prepare(
-            "SELECT
-                pois.id,
-                pois.tags,
-                links.entity_id,
-                CASE
-                    WHEN links.entity_id IS NULL THEN 0
-                    ELSE EXISTS(
-                        SELECT 1 FROM wikidata_entity_claims AS claims
-                        WHERE claims.entity_id = links.entity_id
-                          AND claims.property_id = ?1
-                          AND claims.value_entity_id = ?2
-                    )
-                END AS is_heritage
-             FROM pois
-             LEFT JOIN poi_wikidata_links AS links ON links.poi_id = pois.id",
-        )
+        .prepare(&query)
         .map_err(|source| PopularityError::Query {
             operation: "prepare POI selection",
             source,
         })?;
-
+    let heritage_claim: [&dyn ToSql; 2] = [&HERITAGE_PROPERTY, &UNESCO_WORLD_HERITAGE];
+    let ids = poi_ids
+        .unwrap_or_default()
+        .iter()
+        .map(|id| id as &dyn ToSql);
     let rows = statement
-        .query_map([HERITAGE_PROPERTY, UNESCO_WORLD_HERITAGE], |row| {
-            let poi_id_raw: i64 = row.get(0)?;
-            let tags: String = row.get(1)?;
-            let entity_id: Option<String> = row.get(2)?;
-            let heritage: bool = row.get(3)?;
+        .query_map(
+            params_from_iter(heritage_claim.into_iter().chain(ids)),
+            |row| {
+                let poi_id_raw: i64 = row.get(0)?;
+                let tags: String = row.get(1)?;
+                let entity_id: Option<String> = row.get(2)?;
+                let heritage: bool = row.get(3)?;
 
-            Ok((poi_id_raw, tags, entity_id, heritage))
-        })
+                Ok((poi_id_raw, tags, entity_id, heritage))
+            },
+        )
         .map_err(|source| PopularityError::Query {
             operation: "query POIs",
             source,
@@ -173,19 +304,30 @@ fn read_raw_scores(
         let poi_id = u64::try_from(poi_id_raw)
             .map_err(|_| PopularityError::PoiIdOutOfRange { poi_id: poi_id_raw })?;
         let sitelinks = resolver.sitelink_count(entity_id.as_deref(), &tags, poi_id)?;
-        let score = score_signals(sitelinks, heritage, weights);
-        raw_scores.insert(poi_id, score);
+        let tag_bonus = tag_bonus(&tags, poi_id, weights.tag_boosts)?;
+        let score = score_signals(sitelinks, heritage, tag_bonus, weights);
+        let category = match weights.scope {
+            PopularityScope::Global => None,
+            PopularityScope::Category => tag_category(&tags, poi_id)?,
+        };
+        raw_scores.insert(poi_id, (score, category));
     }
 
     Ok(raw_scores)
 }
 
+#[cfg(feature = "scorer-sqlite")]
 #[expect(
     clippy::float_arithmetic,
     clippy::cast_precision_loss,
     reason = "popularity scoring requires floating-point weighting with bounded casts"
 )]
-fn score_signals(sitelinks: u32, heritage: bool, weights: PopularityWeights) -> f32 {
+fn score_signals(
+    sitelinks: u32,
+    heritage: bool,
+    tag_bonus: f32,
+    weights: PopularityWeights,
+) -> f32 {
     let sitelinks_f32 = sitelinks as f32;
     let sitelink_component = weights.sitelink_weight * sitelinks_f32;
     let heritage_component = if heritage {
@@ -193,22 +335,41 @@ fn score_signals(sitelinks: u32, heritage: bool, weights: PopularityWeights) ->
     } else {
         0.0_f32
     };
-    (sitelink_component + heritage_component).max(0.0_f32)
+    (sitelink_component + heritage_component + tag_bonus).max(0.0_f32)
 }
 
-#[expect(
-    clippy::float_arithmetic,
-    reason = "normalizing scores divides by the maximum raw value"
-)]
-pub(crate) fn normalize_scores(raw: &HashMap<u64, f32>) -> std::collections::BTreeMap<u64, f32> {
-    let max = raw.values().copied().fold(0.0_f32, f32::max);
-    if max == 0.0_f32 {
-        return raw.keys().map(|&id| (id, 0.0_f32)).collect();
-    }
-    raw.iter()
-        .map(|(&id, value)| (id, (value / max).clamp(0.0_f32, 1.0_f32)))
-        .collect()
+/// Sum the [`TagBoosts`] earned by the string values in a POI's JSON tags.
+#[cfg(feature = "scorer-sqlite")]
+fn tag_bonus(tags: &str, poi_id: u64, boosts: TagBoosts) -> Result<f32, PopularityError> {
+    let parsed: serde_json::Value = serde_json::from_str(tags)
+        .map_err(|source| PopularityError::ParseTags { poi_id, source })?;
+    Ok(parsed.as_object().map_or(0.0_f32, |object| {
+        boosts.bonus(
+            object
+                .iter()
+                .filter_map(|(key, value)| Some((key.as_str(), value.as_str()?))),
+        )
+    }))
+}
+
+/// Category of a POI with the given JSON tags under
+/// [`PopularityScope::Category`].
+#[cfg(feature = "scorer-sqlite")]
+fn tag_category(tags: &str, poi_id: u64) -> Result<Option<Theme>, PopularityError> {
+    let parsed: serde_json::Value = serde_json::from_str(tags)
+        .map_err(|source| PopularityError::ParseTags { poi_id, source })?;
+    let string_tags: Tags = parsed
+        .as_object()
+        .map(|object| {
+            object
+                .iter()
+                .filter_map(|(key, value)| Some((key.clone(), value.as_str()?.to_owned())))
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(Theme::primary_for(&string_tags))
 }
 
 #[cfg(test)]
+#[cfg(feature = "scorer-sqlite")]
 mod tests;
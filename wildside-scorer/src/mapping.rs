@@ -0,0 +1,281 @@
+//! Load [`ThemeClaimMapping`] rules from TOML or JSON configuration files.
+//!
+//! A mapping file is a table keyed by theme name. Each theme lists the claim
+//! selectors that mark a POI as matching it:
+//!
+//! ```toml
+//! history = [{ property_id = "P1435", value_entity_id = "Q9259" }]
+//! art = [
+//!     { property_id = "P31", value_entity_id = "Q33506" },
+//!     { property_id = "P31", value_entity_id = "Q207694" },
+//! ]
+//! ```
+//!
+//! Theme names are matched case-insensitively. Unknown themes, themes without
+//! selectors, and selectors with empty identifiers are rejected.
+#![forbid(unsafe_code)]
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use wildside_core::Theme;
+
+use crate::{ClaimSelector, ThemeClaimMapping};
+
+/// Errors raised while loading a [`ThemeClaimMapping`] from configuration.
+#[derive(Debug, Error)]
+pub enum ThemeMappingError {
+    /// Reading the mapping file failed.
+    #[error("failed to read theme mapping at {path}")]
+    Read {
+        /// Path to the mapping file.
+        path: Utf8PathBuf,
+        /// Source error from std I/O.
+        #[source]
+        source: std::io::Error,
+    },
+    /// The mapping file extension names neither TOML nor JSON.
+    #[error("theme mapping at {path} must have a .toml or .json extension")]
+    UnsupportedFormat {
+        /// Path to the mapping file.
+        path: Utf8PathBuf,
+    },
+    /// The TOML document could not be decoded.
+    #[error("failed to parse TOML theme mapping")]
+    ParseToml(#[source] Box<toml::de::Error>),
+    /// The JSON document could not be decoded.
+    #[error("failed to parse JSON theme mapping")]
+    ParseJson(#[source] serde_json::Error),
+    /// A key did not name a [`Theme`].
+    #[error("unknown theme `{name}` in theme mapping")]
+    UnknownTheme {
+        /// Key found in the mapping.
+        name: String,
+    },
+    /// A theme listed no claim selectors.
+    #[error("theme {theme} lists no claim selectors")]
+    EmptySelectors {
+        /// Theme without selectors.
+        theme: Theme,
+    },
+    /// A selector was missing its property or value identifier.
+    #[error("theme {theme} has a claim selector with an empty identifier")]
+    InvalidSelector {
+        /// Theme holding the selector.
+        theme: Theme,
+    },
+}
+
+/// Serialized form of a [`ThemeClaimMapping`]: selectors keyed by theme name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct RawThemeClaimMapping(BTreeMap<String, Vec<RawClaimSelector>>);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawClaimSelector {
+    property_id: String,
+    value_entity_id: String,
+}
+
+impl RawClaimSelector {
+    fn validate(self, theme: &Theme) -> Result<ClaimSelector, ThemeMappingError> {
+        ClaimSelector::new(self.property_id, self.value_entity_id).map_err(|_| {
+            ThemeMappingError::InvalidSelector {
+                theme: theme.clone(),
+            }
+        })
+    }
+}
+
+impl TryFrom<RawThemeClaimMapping> for ThemeClaimMapping {
+    type Error = ThemeMappingError;
+
+    fn try_from(raw: RawThemeClaimMapping) -> Result<Self, Self::Error> {
+        let mut mapping = Self::new();
+        for (name, selectors) in raw.0 {
+            let Ok(theme) = Theme::from_str(&name) else {
+                return Err(ThemeMappingError::UnknownTheme { name });
+            };
+            if selectors.is_empty() {
+                return Err(ThemeMappingError::EmptySelectors { theme });
+            }
+            for selector in selectors {
+                mapping.insert(theme.clone(), selector.validate(&theme)?);
+            }
+        }
+        Ok(mapping)
+    }
+}
+
+impl From<ThemeClaimMapping> for RawThemeClaimMapping {
+    fn from(mapping: ThemeClaimMapping) -> Self {
+        Self(
+            mapping
+                .iter()
+                .map(|(theme, selectors)| {
+                    let raw = selectors
+                        .iter()
+                        .map(|selector| RawClaimSelector {
+                            property_id: selector.property_id().to_owned(),
+                            value_entity_id: selector.value_entity_id().to_owned(),
+                        })
+                        .collect();
+                    (theme.as_str().to_owned(), raw)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl ThemeClaimMapping {
+    /// Decode and validate a mapping from a TOML document.
+    ///
+    /// # Examples
+    /// ```
+    /// use wildside_scorer::ThemeClaimMapping;
+    ///
+    /// let mapping = ThemeClaimMapping::from_toml(
+    ///     r#"nature = [{ property_id = "P31", value_entity_id = "Q22698" }]"#,
+    /// )?;
+    /// # let _ = mapping;
+    /// # Ok::<(), wildside_scorer::ThemeMappingError>(())
+    /// ```
+    ///
+    /// # Errors
+    /// Returns [`ThemeMappingError::ParseToml`] for malformed documents and
+    /// the validation variants for unknown themes or empty selectors.
+    pub fn from_toml(document: &str) -> Result<Self, ThemeMappingError> {
+        let raw: RawThemeClaimMapping = toml::from_str(document)
+            .map_err(|source| ThemeMappingError::ParseToml(Box::new(source)))?;
+        Self::try_from(raw)
+    }
+
+    /// Decode and validate a mapping from a JSON document.
+    ///
+    /// # Errors
+    /// Returns [`ThemeMappingError::ParseJson`] for malformed documents and
+    /// the validation variants for unknown themes or empty selectors.
+    pub fn from_json(document: &str) -> Result<Self, ThemeMappingError> {
+        let raw: RawThemeClaimMapping =
+            serde_json::from_str(document).map_err(ThemeMappingError::ParseJson)?;
+        Self::try_from(raw)
+    }
+
+    /// Load a mapping file, decoding it as TOML or JSON by its extension.
+    ///
+    /// # Errors
+    /// Returns [`ThemeMappingError::Read`] when the file is unreadable,
+    /// [`ThemeMappingError::UnsupportedFormat`] for other extensions, and the
+    /// errors of [`Self::from_toml`] or [`Self::from_json`] otherwise.
+    pub fn from_path(path: &Utf8Path) -> Result<Self, ThemeMappingError> {
+        let extension = path.extension().map(str::to_ascii_lowercase);
+        let decode = match extension.as_deref() {
+            Some("toml") => Self::from_toml,
+            Some("json") => Self::from_json,
+            _ => {
+                return Err(ThemeMappingError::UnsupportedFormat {
+                    path: path.to_path_buf(),
+                });
+            }
+        };
+        let document = std::fs::read_to_string(path.as_std_path()).map_err(|source| {
+            ThemeMappingError::Read {
+                path: path.to_path_buf(),
+                source,
+            }
+        })?;
+        decode(&document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for decoding and validating theme mapping files.
+
+    use rstest::rstest;
+    use wildside_core::Theme;
+
+    use super::ThemeMappingError;
+    use crate::{ClaimSelector, ThemeClaimMapping};
+
+    const TOML_MAPPING: &str = r#"
+        History = [{ property_id = "P1435", value_entity_id = "Q9259" }]
+        art = [
+            { property_id = "P31", value_entity_id = "Q33506" },
+            { property_id = "P31", value_entity_id = "Q207694" },
+        ]
+    "#;
+
+    #[rstest]
+    fn decodes_toml_selectors_per_theme() {
+        let mapping = ThemeClaimMapping::from_toml(TOML_MAPPING).expect("decode mapping");
+
+        let art = mapping.selectors(&Theme::Art).expect("art selectors");
+        assert_eq!(
+            art.iter()
+                .map(ClaimSelector::value_entity_id)
+                .collect::<Vec<_>>(),
+            ["Q33506", "Q207694"]
+        );
+        assert!(mapping.selectors(&Theme::History).is_some());
+        assert!(mapping.selectors(&Theme::Food).is_none());
+    }
+
+    #[rstest]
+    fn json_round_trips_through_serde() {
+        let mapping = ThemeClaimMapping::from_toml(TOML_MAPPING).expect("decode mapping");
+        let json = serde_json::to_string(&mapping).expect("encode mapping");
+        let decoded = ThemeClaimMapping::from_json(&json).expect("decode mapping");
+
+        assert_eq!(
+            decoded.selectors(&Theme::Art),
+            mapping.selectors(&Theme::Art)
+        );
+        assert_eq!(
+            decoded.selectors(&Theme::History),
+            mapping.selectors(&Theme::History)
+        );
+    }
+
+    #[rstest]
+    #[case::empty_selectors("art = []", |err: &ThemeMappingError| {
+        matches!(err, ThemeMappingError::EmptySelectors { theme: Theme::Art })
+    })]
+    #[case::blank_property(
+        r#"art = [{ property_id = " ", value_entity_id = "Q1" }]"#,
+        |err: &ThemeMappingError| {
+            matches!(err, ThemeMappingError::InvalidSelector { theme: Theme::Art })
+        }
+    )]
+    #[case::unknown_field(
+        r#"art = [{ property = "P31", value_entity_id = "Q1" }]"#,
+        |err: &ThemeMappingError| matches!(err, ThemeMappingError::ParseToml(_))
+    )]
+    fn rejects_invalid_mappings(
+        #[case] document: &str,
+        #[case] expected: fn(&ThemeMappingError) -> bool,
+    ) {
+        let err = ThemeClaimMapping::from_toml(document).expect_err("invalid mapping");
+        assert!(expected(&err), "unexpected error: {err}");
+    }
+
+    #[rstest]
+    fn names_the_unknown_theme() {
+        let err = ThemeClaimMapping::from_json(r#"{"sci-fi": []}"#).expect_err("unknown theme");
+        assert!(
+            matches!(&err, ThemeMappingError::UnknownTheme { name } if name == "sci-fi"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[rstest]
+    fn rejects_unsupported_extensions() {
+        let err = ThemeClaimMapping::from_path(camino::Utf8Path::new("mapping.yaml"))
+            .expect_err("unsupported extension");
+        assert!(matches!(err, ThemeMappingError::UnsupportedFormat { .. }));
+    }
+}
@@ -0,0 +1,126 @@
+//! Map raw popularity scores into `0.0..=1.0`.
+#![forbid(unsafe_code)]
+
+use std::collections::{BTreeMap, HashMap};
+
+use wildside_core::Theme;
+
+use crate::PopularityNormalization;
+
+/// Standard deviations above the mean that normalize to `1.0` under
+/// [`PopularityNormalization::ZScore`].
+const Z_SCORE_CEILING: f32 = 3.0_f32;
+
+/// Normalize every raw score with `mode`.
+///
+/// Scores that are all zero normalize to zero under every mode. Ranks and
+/// z-scores also normalize a run of equal scores to zero, since no POI stands
+/// out from the others.
+pub(crate) fn normalize_scores(
+    raw: &HashMap<u64, f32>,
+    mode: PopularityNormalization,
+) -> BTreeMap<u64, f32> {
+    match mode {
+        PopularityNormalization::Max => apply(raw, by_max(raw)),
+        PopularityNormalization::Log => apply(raw, by_log(raw)),
+        PopularityNormalization::PercentileRank => apply(raw, by_rank(raw)),
+        PopularityNormalization::ZScore => apply(raw, by_z_score(raw)),
+    }
+}
+
+/// Normalize raw scores with `mode` separately within each category.
+///
+/// Every entry of `raw` pairs a score with its POI's category; POIs without
+/// one are normalized together.
+pub(crate) fn normalize_within_categories(
+    raw: &HashMap<u64, (f32, Option<Theme>)>,
+    mode: PopularityNormalization,
+) -> BTreeMap<u64, f32> {
+    let mut groups: HashMap<Option<&Theme>, HashMap<u64, f32>> = HashMap::new();
+    for (&id, (value, category)) in raw {
+        groups
+            .entry(category.as_ref())
+            .or_default()
+            .insert(id, *value);
+    }
+    groups
+        .values()
+        .flat_map(|group| normalize_scores(group, mode))
+        .collect()
+}
+
+fn apply(raw: &HashMap<u64, f32>, normalize: impl Fn(f32) -> f32) -> BTreeMap<u64, f32> {
+    raw.iter()
+        .map(|(&id, &value)| (id, normalize(value).clamp(0.0_f32, 1.0_f32)))
+        .collect()
+}
+
+fn maximum(raw: &HashMap<u64, f32>) -> f32 {
+    raw.values().copied().fold(0.0_f32, f32::max)
+}
+
+#[expect(
+    clippy::float_arithmetic,
+    reason = "max normalization divides by the highest raw value"
+)]
+fn by_max(raw: &HashMap<u64, f32>) -> impl Fn(f32) -> f32 {
+    let max = maximum(raw);
+    move |value| if max > 0.0_f32 { value / max } else { 0.0_f32 }
+}
+
+#[expect(
+    clippy::float_arithmetic,
+    reason = "log normalization divides logarithms of raw values"
+)]
+fn by_log(raw: &HashMap<u64, f32>) -> impl Fn(f32) -> f32 {
+    let ceiling = maximum(raw).ln_1p();
+    move |value| {
+        if ceiling > 0.0_f32 {
+            value.max(0.0_f32).ln_1p() / ceiling
+        } else {
+            0.0_f32
+        }
+    }
+}
+
+#[expect(
+    clippy::float_arithmetic,
+    clippy::cast_precision_loss,
+    reason = "percentile ranks divide POI counts"
+)]
+fn by_rank(raw: &HashMap<u64, f32>) -> impl Fn(f32) -> f32 {
+    let mut sorted: Vec<f32> = raw.values().copied().collect();
+    sorted.sort_unstable_by(f32::total_cmp);
+    let others = sorted.len().saturating_sub(1);
+    move |value| {
+        let lower = sorted.partition_point(|other| *other < value);
+        if others > 0 {
+            lower as f32 / others as f32
+        } else {
+            0.0_f32
+        }
+    }
+}
+
+#[expect(
+    clippy::float_arithmetic,
+    clippy::cast_precision_loss,
+    reason = "z-scores divide deviations from the mean by the standard deviation"
+)]
+fn by_z_score(raw: &HashMap<u64, f32>) -> impl Fn(f32) -> f32 {
+    let count = raw.len().max(1) as f32;
+    let mean = raw.values().sum::<f32>() / count;
+    let variance = raw
+        .values()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f32>()
+        / count;
+    let deviation = variance.sqrt();
+    move |value| {
+        if deviation > 0.0_f32 {
+            ((value - mean) / deviation).clamp(0.0_f32, Z_SCORE_CEILING) / Z_SCORE_CEILING
+        } else {
+            0.0_f32
+        }
+    }
+}
@@ -0,0 +1,308 @@
+//! Theme relevance read from OpenStreetMap tags alone.
+//!
+//! [`TagThemeScorer`] personalizes scores where Wikidata coverage is sparse.
+//! A [`TagThemeRules`] table lists, for each theme, tag patterns and how
+//! strongly a match marks a POI as on-theme. Rules serialize as a table keyed
+//! by theme name, so they can be tuned from configuration:
+//!
+//! ```toml
+//! history = [
+//!     { key = "historic" },
+//!     { key = "heritage", relevance = 0.8 },
+//! ]
+//! food = [{ key = "amenity", value = "restaurant" }]
+//! ```
+#![forbid(unsafe_code)]
+
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use wildside_core::{InterestProfile, PointOfInterest, Scorer, Tags, Theme};
+
+/// Relevance of rules that do not set one.
+const FULL_RELEVANCE: f32 = 1.0;
+
+/// Errors raised while building tag rules.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum TagThemeError {
+    /// A rule's tag key was empty or whitespace.
+    #[error("tag rule key must not be empty")]
+    EmptyKey,
+    /// A rule's relevance was outside `0.0..=1.0` or not finite.
+    #[error("tag rule relevance {relevance} must be finite and within 0.0..=1.0")]
+    InvalidRelevance {
+        /// Rejected relevance.
+        relevance: f32,
+    },
+    /// A key did not name a [`Theme`].
+    #[error("unknown theme `{name}` in tag rules")]
+    UnknownTheme {
+        /// Key found in the rules table.
+        name: String,
+    },
+    /// A theme listed no rules.
+    #[error("theme {theme} lists no tag rules")]
+    EmptyRules {
+        /// Theme without rules.
+        theme: Theme,
+    },
+}
+
+/// An OpenStreetMap tag pattern and the theme relevance of a match.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawTagRule", into = "RawTagRule")]
+pub struct TagRule {
+    key: String,
+    value: Option<String>,
+    relevance: f32,
+}
+
+impl TagRule {
+    /// Match POIs carrying `key` with any value.
+    ///
+    /// # Errors
+    /// Returns [`TagThemeError::EmptyKey`] for a blank key and
+    /// [`TagThemeError::InvalidRelevance`] when `relevance` is outside
+    /// `0.0..=1.0` or not finite.
+    pub fn any_value(key: impl Into<String>, relevance: f32) -> Result<Self, TagThemeError> {
+        Self::build(key.into(), None, relevance)
+    }
+
+    /// Match POIs carrying `key` set to `value`.
+    ///
+    /// # Errors
+    /// As for [`Self::any_value`].
+    pub fn with_value(
+        key: impl Into<String>,
+        value: impl Into<String>,
+        relevance: f32,
+    ) -> Result<Self, TagThemeError> {
+        Self::build(key.into(), Some(value.into()), relevance)
+    }
+
+    fn build(key: String, value: Option<String>, relevance: f32) -> Result<Self, TagThemeError> {
+        if key.trim().is_empty() {
+            return Err(TagThemeError::EmptyKey);
+        }
+        if !relevance.is_finite() || !(0.0..=FULL_RELEVANCE).contains(&relevance) {
+            return Err(TagThemeError::InvalidRelevance { relevance });
+        }
+        Ok(Self {
+            key,
+            value,
+            relevance,
+        })
+    }
+
+    /// Return the tag key the rule inspects.
+    #[must_use]
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Return the value the tag must carry, or `None` to accept any value.
+    #[must_use]
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// Return the theme relevance of a match, in `0.0..=1.0`.
+    #[must_use]
+    pub const fn relevance(&self) -> f32 {
+        self.relevance
+    }
+
+    fn matches(&self, tags: &Tags) -> bool {
+        tags.get(&self.key)
+            .is_some_and(|found| self.value.as_ref().is_none_or(|expected| found == expected))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawTagRule {
+    key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+    #[serde(default = "full_relevance")]
+    relevance: f32,
+}
+
+const fn full_relevance() -> f32 {
+    FULL_RELEVANCE
+}
+
+impl TryFrom<RawTagRule> for TagRule {
+    type Error = TagThemeError;
+
+    fn try_from(raw: RawTagRule) -> Result<Self, Self::Error> {
+        Self::build(raw.key, raw.value, raw.relevance)
+    }
+}
+
+impl From<TagRule> for RawTagRule {
+    fn from(rule: TagRule) -> Self {
+        Self {
+            key: rule.key,
+            value: rule.value,
+            relevance: rule.relevance,
+        }
+    }
+}
+
+/// Tag rules for each theme.
+///
+/// The default table turns every [`Theme::osm_tags`] entry into a rule of
+/// full relevance.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    try_from = "BTreeMap<String, Vec<TagRule>>",
+    into = "BTreeMap<String, Vec<TagRule>>"
+)]
+pub struct TagThemeRules {
+    rules: HashMap<Theme, Vec<TagRule>>,
+}
+
+impl TagThemeRules {
+    /// Create a table without rules.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            rules: HashMap::new(),
+        }
+    }
+
+    /// Add a rule for `theme`.
+    pub fn insert(&mut self, theme: Theme, rule: TagRule) {
+        self.rules.entry(theme).or_default().push(rule);
+    }
+
+    /// Add a rule while consuming `self`, enabling chaining.
+    #[must_use]
+    pub fn with_rule(mut self, theme: Theme, rule: TagRule) -> Self {
+        self.insert(theme, rule);
+        self
+    }
+
+    /// Return the rules of `theme`, empty when it has none.
+    #[must_use]
+    pub fn rules(&self, theme: &Theme) -> &[TagRule] {
+        self.rules.get(theme).map_or(&[], Vec::as_slice)
+    }
+
+    /// Return the highest relevance among the rules of `theme` that `tags`
+    /// match, or `0.0` when none match.
+    fn relevance(&self, theme: &Theme, tags: &Tags) -> f32 {
+        self.rules(theme)
+            .iter()
+            .filter(|rule| rule.matches(tags))
+            .map(TagRule::relevance)
+            .fold(0.0_f32, f32::max)
+    }
+}
+
+impl Default for TagThemeRules {
+    fn default() -> Self {
+        let mut rules = Self::new();
+        for theme in Theme::ALL {
+            for &(key, value) in theme.osm_tags() {
+                let rule = TagRule {
+                    key: key.to_owned(),
+                    value: value.map(str::to_owned),
+                    relevance: FULL_RELEVANCE,
+                };
+                rules.insert(theme.clone(), rule);
+            }
+        }
+        rules
+    }
+}
+
+impl TryFrom<BTreeMap<String, Vec<TagRule>>> for TagThemeRules {
+    type Error = TagThemeError;
+
+    fn try_from(raw: BTreeMap<String, Vec<TagRule>>) -> Result<Self, Self::Error> {
+        let mut rules = Self::new();
+        for (name, theme_rules) in raw {
+            let Ok(theme) = Theme::from_str(&name) else {
+                return Err(TagThemeError::UnknownTheme { name });
+            };
+            if theme_rules.is_empty() {
+                return Err(TagThemeError::EmptyRules { theme });
+            }
+            rules.rules.entry(theme).or_default().extend(theme_rules);
+        }
+        Ok(rules)
+    }
+}
+
+impl From<TagThemeRules> for BTreeMap<String, Vec<TagRule>> {
+    fn from(rules: TagThemeRules) -> Self {
+        rules
+            .rules
+            .into_iter()
+            .map(|(theme, theme_rules)| (theme.as_str().to_owned(), theme_rules))
+            .collect()
+    }
+}
+
+/// Scorer that reads theme relevance from a POI's OpenStreetMap tags.
+///
+/// A POI's score sums, over the visitor's weighted themes, the weight times
+/// the best relevance among the theme's matching rules, then clamps the sum
+/// to `0.0..=1.0`. Negative weights subtract, as they do for
+/// the claims-based user relevance scorer. Unlike the test-only
+/// `TagScorer` in `wildside-core`, rules match tag values as well as keys.
+///
+/// # Examples
+/// ```
+/// use geo::Coord;
+/// use wildside_core::{InterestProfile, PointOfInterest, Scorer, Tags, Theme};
+/// use wildside_scorer::TagThemeScorer;
+///
+/// let poi = PointOfInterest::new(
+///     1,
+///     Coord { x: 0.0, y: 0.0 },
+///     Tags::from([("tourism".into(), "museum".into())]),
+/// );
+/// let profile = InterestProfile::new().with_weight(Theme::Culture, 0.8);
+/// assert_eq!(TagThemeScorer::default().score(&poi, &profile), 0.8);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TagThemeScorer {
+    rules: TagThemeRules,
+}
+
+impl TagThemeScorer {
+    /// Construct a scorer over `rules`.
+    #[must_use]
+    pub const fn new(rules: TagThemeRules) -> Self {
+        Self { rules }
+    }
+
+    /// Return the scorer's rules table.
+    #[must_use]
+    pub const fn rules(&self) -> &TagThemeRules {
+        &self.rules
+    }
+}
+
+impl Scorer for TagThemeScorer {
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "theme relevance sums weighted rule matches"
+    )]
+    fn score(&self, poi: &PointOfInterest, profile: &InterestProfile) -> f32 {
+        let relevance: f32 = profile
+            .iter()
+            .filter(|(_, weight)| weight.is_finite())
+            .map(|(theme, weight)| weight * self.rules.relevance(theme, &poi.tags))
+            .sum();
+        Self::sanitise(relevance)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,121 @@
+//! Tests for tag rule matching, validation, and scoring.
+
+use geo::Coord;
+use rstest::rstest;
+use wildside_core::{InterestProfile, PointOfInterest, Scorer, Tags, Theme};
+
+use super::{TagRule, TagThemeError, TagThemeRules, TagThemeScorer};
+
+fn poi_with(pairs: &[(&str, &str)]) -> PointOfInterest {
+    let tags: Tags = pairs
+        .iter()
+        .map(|&(key, value)| (key.to_owned(), value.to_owned()))
+        .collect();
+    PointOfInterest::new(1, Coord { x: 0.0, y: 0.0 }, tags)
+}
+
+fn approx(actual: f32, expected: f32) -> bool {
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "tests compare floating point values"
+    )]
+    let error = (actual - expected).abs();
+    error < 0.000_1_f32
+}
+
+#[rstest]
+#[case(&[("tourism", "museum")], &[(Theme::Culture, 0.7)], 0.7)]
+#[case(&[("tourism", "museum")], &[(Theme::Food, 0.7)], 0.0)]
+#[case(&[("historic", "castle")], &[(Theme::History, 0.5), (Theme::Architecture, 0.3)], 0.8)]
+#[case(&[("amenity", "cafe"), ("shop", "books")], &[(Theme::Food, 0.6), (Theme::Shopping, -0.4)], 0.2)]
+#[case(&[("shop", "books")], &[(Theme::Shopping, -1.0)], 0.0)]
+#[case(&[("amenity", "bench")], &[(Theme::Food, 1.0)], 0.0)]
+fn default_rules_score_matching_themes(
+    #[case] tags: &[(&str, &str)],
+    #[case] weights: &[(Theme, f32)],
+    #[case] expected: f32,
+) {
+    let profile = weights
+        .iter()
+        .fold(InterestProfile::new(), |profile, (theme, weight)| {
+            profile.with_weight(theme.clone(), *weight)
+        });
+
+    let score = TagThemeScorer::default().score(&poi_with(tags), &profile);
+    assert!(approx(score, expected), "got {score}, want {expected}");
+}
+
+#[rstest]
+fn best_matching_rule_sets_relevance() {
+    let rules = TagThemeRules::new()
+        .with_rule(
+            Theme::Nature,
+            TagRule::any_value("natural", 0.4).expect("valid rule"),
+        )
+        .with_rule(
+            Theme::Nature,
+            TagRule::with_value("leisure", "park", 0.9).expect("valid rule"),
+        );
+    let scorer = TagThemeScorer::new(rules);
+    let profile = InterestProfile::new().with_weight(Theme::Nature, 1.0);
+
+    let park = poi_with(&[("leisure", "park"), ("natural", "wood")]);
+    let garden = poi_with(&[("leisure", "garden")]);
+    assert!(approx(scorer.score(&park, &profile), 0.9));
+    assert!(approx(scorer.score(&garden, &profile), 0.0));
+}
+
+#[rstest]
+fn rules_decode_from_toml() {
+    let rules: TagThemeRules = toml::from_str(
+        r#"
+        History = [{ key = "historic" }, { key = "heritage", relevance = 0.8 }]
+        food = [{ key = "amenity", value = "restaurant" }]
+        "#,
+    )
+    .expect("decode rules");
+
+    assert_eq!(
+        rules.rules(&Theme::History),
+        [
+            TagRule::any_value("historic", 1.0).expect("valid rule"),
+            TagRule::any_value("heritage", 0.8).expect("valid rule"),
+        ]
+    );
+    assert_eq!(
+        rules.rules(&Theme::Food).first().and_then(TagRule::value),
+        Some("restaurant")
+    );
+    assert!(rules.rules(&Theme::Art).is_empty());
+}
+
+#[rstest]
+fn default_rules_round_trip_through_json() {
+    let rules = TagThemeRules::default();
+    let json = serde_json::to_string(&rules).expect("encode rules");
+    let decoded: TagThemeRules = serde_json::from_str(&json).expect("decode rules");
+    assert_eq!(decoded, rules);
+}
+
+#[rstest]
+#[case(r#"sci-fi = [{ key = "shop" }]"#, "unknown theme `sci-fi`")]
+#[case("art = []", "lists no tag rules")]
+#[case(r#"art = [{ key = " " }]"#, "must not be empty")]
+#[case(r#"art = [{ key = "shop", relevance = 1.5 }]"#, "within 0.0..=1.0")]
+fn rejects_invalid_rules(#[case] document: &str, #[case] message: &str) {
+    let err = toml::from_str::<TagThemeRules>(document).expect_err("invalid rules");
+    assert!(err.to_string().contains(message), "unexpected error: {err}");
+}
+
+#[rstest]
+fn rule_constructors_validate() {
+    assert_eq!(TagRule::any_value(" ", 1.0), Err(TagThemeError::EmptyKey));
+    assert!(matches!(
+        TagRule::with_value("shop", "books", f32::NAN),
+        Err(TagThemeError::InvalidRelevance { .. })
+    ));
+    assert!(matches!(
+        TagRule::with_value("shop", "books", -0.1),
+        Err(TagThemeError::InvalidRelevance { .. })
+    ));
+}
@@ -6,113 +6,112 @@ use camino::Utf8PathBuf;
 use rstest::rstest;
 use rusqlite::Connection;
 use tempfile::TempDir;
+use wildside_core::Theme;
 
 use crate::{
-    PopularityError, PopularityScores, PopularityWeights, bincode_options,
-    compute_popularity_scores, normalize_scores, resolver::SitelinkResolver,
-    resolver::parse_sitelinks_from_tags, write_popularity_file,
+    PopularityError, PopularityNormalization, PopularityScope, PopularityScores, PopularityWeights,
+    TagBoosts, bincode_options, compute_popularity_scores, compute_raw_popularity_scores,
+    export_popularity_csv, export_popularity_json, read_popularity_header,
+    resolver::SitelinkResolver, tag_bonus, tag_category, update_popularity_scores,
+    write_popularity_file,
 };
 
-#[rstest]
-#[expect(
-    clippy::float_arithmetic,
-    reason = "test uses float maths for assertions"
-)]
-fn normalizes_scores() {
-    let mut raw = std::collections::HashMap::new();
-    raw.insert(1, 10.0_f32);
-    raw.insert(2, 5.0_f32);
-
-    let normalized = normalize_scores(&raw);
-
-    assert_eq!(normalized.get(&1), Some(&1.0_f32));
-    let value = normalized.get(&2).expect("score for poi 2");
-    let delta = (value - 0.5_f32).abs();
-    assert!(
-        delta < 0.000_1_f32,
-        "expected approximately 0.5, got {value}"
-    );
-}
+mod normalize;
+mod sitelinks;
 
 #[rstest]
-fn normalizes_zero_scores_to_zero() {
-    let mut raw = std::collections::HashMap::new();
-    raw.insert(1, 0.0_f32);
-    raw.insert(2, 0.0_f32);
-
-    let normalized = normalize_scores(&raw);
-
-    assert_eq!(normalized.get(&1), Some(&0.0_f32));
-    assert_eq!(normalized.get(&2), Some(&0.0_f32));
+#[case::museum(r#"{"tourism":"museum","sitelinks":40}"#, Some(Theme::Culture))]
+#[case::viewpoint(r#"{"tourism":"viewpoint"}"#, Some(Theme::Nature))]
+#[case::uncategorised(r#"{"tourism":"attraction"}"#, None)]
+fn tag_category_reads_the_primary_theme(#[case] tags: &str, #[case] expected: Option<Theme>) {
+    assert_eq!(tag_category(tags, 1).expect("parse tags"), expected);
 }
 
 #[rstest]
-fn parses_numeric_sitelinks_from_tags() {
-    let tags = r#"{"wikidata":"Q64","sitelinks":42}"#;
-
-    let parsed = parse_sitelinks_from_tags(tags, 1).expect("parse sitelinks");
-
-    assert_eq!(parsed, Some(42));
-}
-
-#[rstest]
-fn parses_string_sitelinks_from_tags() {
-    let tags = r#"{"wikidata":"Q64","sitelinks":"17"}"#;
-
-    let parsed = parse_sitelinks_from_tags(tags, 1).expect("parse sitelinks");
-
-    assert_eq!(parsed, Some(17));
-}
-
-#[rstest]
-fn missing_sitelinks_returns_none() {
-    let tags = r#"{"wikidata":"Q64"}"#;
-
-    let parsed = parse_sitelinks_from_tags(tags, 1).expect("parse sitelinks");
-
-    assert!(parsed.is_none());
-}
-
-#[rstest]
-fn null_sitelinks_returns_none() {
-    let tags = r#"{"wikidata":"Q64","sitelinks":null}"#;
-
-    let parsed = parse_sitelinks_from_tags(tags, 1).expect("parse sitelinks");
-
-    assert!(parsed.is_none());
+fn category_scope_lifts_niche_categories() {
+    let temp = TempDir::new().expect("tempdir");
+    let db_path = Utf8PathBuf::from_path_buf(temp.path().join("pois.db")).expect("utf8 path");
+    seed_database(&db_path);
+    let connection = Connection::open(db_path.as_std_path()).expect("reopen database");
+    connection
+        .execute(
+            "INSERT INTO pois (id, lon, lat, tags) VALUES (2, 0.0, 0.0, '{\"leisure\":\"park\",\"tourism\":\"attraction\"}')",
+            [],
+        )
+        .expect("insert park");
+
+    let global =
+        compute_popularity_scores(&db_path, PopularityWeights::default()).expect("compute scores");
+    let by_category = compute_popularity_scores(
+        &db_path,
+        PopularityWeights {
+            scope: PopularityScope::Category,
+            ..PopularityWeights::default()
+        },
+    )
+    .expect("compute scores");
+
+    assert!(global.get(2).is_some_and(|score| score < 0.5_f32));
+    assert_eq!(by_category.get(1), Some(1.0_f32));
+    assert_eq!(by_category.get(2), Some(1.0_f32));
 }
 
 #[rstest]
-fn sitelink_count_alias_is_supported() {
-    let tags = r#"{"wikidata":"Q64","sitelink_count":5}"#;
-
-    let parsed = parse_sitelinks_from_tags(tags, 1).expect("parse sitelinks");
-
-    assert_eq!(parsed, Some(5));
+#[case::attraction(r#"{"tourism":"attraction"}"#, 5.0_f32)]
+#[case::wikipedia(r#"{"wikipedia":"en:Old Bridge"}"#, 10.0_f32)]
+#[case::national_heritage(r#"{"heritage":"2"}"#, 7.5_f32)]
+#[case::combined(
+    r#"{"tourism":"attraction","wikipedia":"en:Castle","heritage":"1"}"#,
+    30.0_f32
+)]
+#[case::unlisted_heritage(r#"{"heritage":"yes","wikipedia":" "}"#, 0.0_f32)]
+#[case::numeric_values(r#"{"sitelinks":4,"tourism":"museum"}"#, 0.0_f32)]
+fn tag_boosts_read_osm_tags(#[case] tags: &str, #[case] expected: f32) {
+    let bonus = tag_bonus(tags, 1, TagBoosts::default()).expect("parse tags");
+
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "test uses float maths for assertions"
+    )]
+    let delta = (bonus - expected).abs();
+    assert!(delta < 0.000_1_f32, "expected {expected}, got {bonus}");
 }
 
 #[rstest]
-fn empty_string_returns_none() {
-    let tags = r#"{"wikidata":"Q64","sitelinks":"   "}"#;
+fn disabled_tag_boosts_earn_nothing() {
+    let tags = r#"{"tourism":"attraction","wikipedia":"en:Castle","heritage":"1"}"#;
 
-    let parsed = parse_sitelinks_from_tags(tags, 1).expect("parse sitelinks");
+    let bonus = tag_bonus(tags, 1, TagBoosts::disabled()).expect("parse tags");
 
-    assert!(parsed.is_none());
+    assert!(bonus <= 0.0_f32, "expected no bonus, got {bonus}");
 }
 
 #[rstest]
-fn invalid_array_sitelinks_returns_error() {
-    let tags = r#"{"wikidata":"Q64","sitelinks":[]}"#;
-
-    let err = match parse_sitelinks_from_tags(tags, 1) {
-        Ok(value) => panic!("expected error, got {value:?}"),
-        Err(err) => err,
-    };
-
-    match err {
-        PopularityError::InvalidSitelinkCountJson { poi_id, .. } => assert_eq!(poi_id, 1),
-        other => panic!("unexpected error: {other}"),
-    }
+fn tagged_pois_without_links_earn_popularity() {
+    let temp = TempDir::new().expect("tempdir");
+    let db_path = Utf8PathBuf::from_path_buf(temp.path().join("pois.db")).expect("utf8 path");
+    seed_database(&db_path);
+    let connection = Connection::open(db_path.as_std_path()).expect("reopen database");
+    connection
+        .execute(
+            "INSERT INTO pois (id, lon, lat, tags) VALUES (2, 0.0, 0.0, '{\"tourism\":\"attraction\"}')",
+            [],
+        )
+        .expect("insert tagged poi");
+
+    let boosted =
+        compute_popularity_scores(&db_path, PopularityWeights::default()).expect("compute scores");
+    let unboosted = compute_popularity_scores(
+        &db_path,
+        PopularityWeights {
+            tag_boosts: TagBoosts::disabled(),
+            ..PopularityWeights::default()
+        },
+    )
+    .expect("compute scores");
+
+    assert!(boosted.get(2).is_some_and(|score| score > 0.0_f32));
+    assert_eq!(unboosted.get(2), Some(0.0_f32));
 }
 
 #[rstest]
@@ -147,7 +146,11 @@ fn write_popularity_file_round_trips_scores() {
     let temp = TempDir::new().expect("tempdir");
     let db_path = Utf8PathBuf::from_path_buf(temp.path().join("pois.db")).expect("utf8 path");
     seed_database_with_sitelinks(&db_path);
-    let weights = PopularityWeights::default();
+    let weights = PopularityWeights {
+        normalization: PopularityNormalization::Log,
+        scope: PopularityScope::Category,
+        ..PopularityWeights::default()
+    };
     let expected = compute_popularity_scores(&db_path, weights).expect("compute scores");
 
     let nested = temp.path().join("nested/dir/popularity.bin");
@@ -156,13 +159,157 @@ fn write_popularity_file_round_trips_scores() {
     write_popularity_file(&db_path, &output, weights).expect("write popularity file");
 
     let bytes = std::fs::read(output.as_std_path()).expect("read popularity file");
+    let (header, payload) = read_popularity_header(&bytes).expect("versioned popularity header");
+    assert_eq!(header.normalization, PopularityNormalization::Log);
+    assert_eq!(header.scope, PopularityScope::Category);
     let decoded: PopularityScores = bincode_options()
-        .deserialize(&bytes)
+        .deserialize(payload)
         .expect("decode popularity file");
 
     assert_eq!(decoded, expected, "scores should round-trip via bincode");
 }
 
+#[rstest]
+fn exports_ranked_scores_with_names_and_coordinates() {
+    let temp = TempDir::new().expect("tempdir");
+    let db_path = Utf8PathBuf::from_path_buf(temp.path().join("pois.db")).expect("utf8 path");
+    seed_database_with_sitelinks(&db_path);
+    let connection = Connection::open(db_path.as_std_path()).expect("reopen database");
+    connection
+        .execute(
+            "INSERT INTO pois (id, lon, lat, tags)
+             VALUES (2, 13.4, 52.5, '{\"name\":\"Cafe \\\"Kranzler\\\", Berlin\",\"tourism\":\"attraction\"}')",
+            [],
+        )
+        .expect("insert named poi");
+    let popularity = Utf8PathBuf::from_path_buf(temp.path().join("popularity.bin"))
+        .expect("utf8 popularity path");
+    write_popularity_file(&db_path, &popularity, PopularityWeights::default())
+        .expect("write popularity file");
+    connection
+        .execute(
+            "INSERT INTO pois (id, lon, lat, tags) VALUES (3, 1.0, 1.0, '{\"name\":\"Unscored\"}')",
+            [],
+        )
+        .expect("insert unscored poi");
+
+    let csv_path =
+        Utf8PathBuf::from_path_buf(temp.path().join("export/popularity.csv")).expect("csv path");
+    export_popularity_csv(&db_path, &popularity, &csv_path).expect("export csv");
+    let csv = std::fs::read_to_string(csv_path.as_std_path()).expect("read csv");
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 3, "unexpected csv: {csv}");
+    assert_eq!(
+        lines.first(),
+        Some(&"rank,poi_id,name,longitude,latitude,popularity")
+    );
+    assert_eq!(lines.get(1), Some(&"1,1,,0,0,1"));
+    assert!(
+        lines.get(2).is_some_and(
+            |line| line.starts_with("2,2,\"Cafe \"\"Kranzler\"\", Berlin\",13.4,52.5,")
+        ),
+        "unexpected csv: {csv}"
+    );
+
+    let json_path =
+        Utf8PathBuf::from_path_buf(temp.path().join("popularity.json")).expect("json path");
+    export_popularity_json(&db_path, &popularity, &json_path).expect("export json");
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(json_path.as_std_path()).expect("read json"))
+            .expect("decode json");
+    let rows = json.as_array().expect("json array");
+    assert_eq!(rows.len(), 2);
+    assert_eq!(
+        rows.first(),
+        Some(&serde_json::json!({
+            "rank": 1, "poi_id": 1, "name": null,
+            "longitude": 0.0, "latitude": 0.0, "popularity": 1.0
+        }))
+    );
+    assert_eq!(
+        rows.get(1).and_then(|row| row.get("name")),
+        Some(&serde_json::json!("Cafe \"Kranzler\", Berlin"))
+    );
+}
+
+#[rstest]
+fn export_reports_a_missing_popularity_file() {
+    let temp = TempDir::new().expect("tempdir");
+    let db_path = Utf8PathBuf::from_path_buf(temp.path().join("pois.db")).expect("utf8 path");
+    seed_database(&db_path);
+    let missing = Utf8PathBuf::from_path_buf(temp.path().join("missing.bin")).expect("utf8 path");
+    let output = Utf8PathBuf::from_path_buf(temp.path().join("out.csv")).expect("utf8 path");
+
+    let err = export_popularity_csv(&db_path, &missing, &output).expect_err("missing scores");
+    assert!(matches!(err, PopularityError::LoadPopularity(_)));
+}
+
+#[rstest]
+#[case(PopularityNormalization::Max, PopularityScope::Global)]
+#[case(PopularityNormalization::Log, PopularityScope::Category)]
+fn incremental_updates_match_a_full_recompute(
+    #[case] normalization: PopularityNormalization,
+    #[case] scope: PopularityScope,
+) {
+    let temp = TempDir::new().expect("tempdir");
+    let db_path = Utf8PathBuf::from_path_buf(temp.path().join("pois.db")).expect("utf8 path");
+    seed_database_with_sitelinks(&db_path);
+    let connection = Connection::open(db_path.as_std_path()).expect("reopen database");
+    connection
+        .execute_batch(
+            "INSERT INTO pois (id, lon, lat, tags) VALUES
+                (2, 0.0, 0.0, '{\"tourism\":\"museum\",\"wikipedia\":\"en:Museum\"}'),
+                (3, 0.0, 0.0, '{\"leisure\":\"park\",\"tourism\":\"attraction\"}'),
+                (4, 0.0, 0.0, '{\"amenity\":\"cafe\"}');",
+        )
+        .expect("insert pois");
+    let weights = PopularityWeights {
+        normalization,
+        scope,
+        ..PopularityWeights::default()
+    };
+    let mut raw = compute_raw_popularity_scores(&db_path, weights).expect("compute raw scores");
+    assert_eq!(raw.len(), 4);
+
+    connection
+        .execute_batch(
+            "UPDATE pois SET tags = '{\"amenity\":\"cafe\",\"tourism\":\"attraction\"}'
+                WHERE id = 4;
+             DELETE FROM pois WHERE id = 3;
+             INSERT INTO pois (id, lon, lat, tags) VALUES
+                (5, 0.0, 0.0, '{\"leisure\":\"park\",\"heritage\":\"2\"}');",
+        )
+        .expect("apply diff");
+
+    let updated =
+        update_popularity_scores(&db_path, &mut raw, &[4, 3, 5, u64::MAX]).expect("update scores");
+
+    let expected = compute_popularity_scores(&db_path, weights).expect("recompute scores");
+    assert_eq!(updated, expected);
+    assert_eq!(raw.len(), 4);
+    assert!(raw.get(3).is_none());
+    assert!(raw.get(5).is_some_and(|score| score > 0.0_f32));
+}
+
+#[rstest]
+fn incremental_updates_keep_scores_on_error() {
+    let temp = TempDir::new().expect("tempdir");
+    let db_path = Utf8PathBuf::from_path_buf(temp.path().join("pois.db")).expect("utf8 path");
+    seed_database(&db_path);
+    let mut raw = compute_raw_popularity_scores(&db_path, PopularityWeights::default())
+        .expect("compute raw scores");
+    let before = raw.clone();
+    let connection = Connection::open(db_path.as_std_path()).expect("reopen database");
+    connection
+        .execute("UPDATE pois SET tags = 'not json' WHERE id = 1", [])
+        .expect("corrupt tags");
+
+    let err = update_popularity_scores(&db_path, &mut raw, &[1]).expect_err("invalid tags");
+
+    assert!(matches!(err, PopularityError::ParseTags { poi_id: 1, .. }));
+    assert_eq!(raw, before);
+}
+
 fn seed_database(path: &Utf8PathBuf) {
     let connection = Connection::open(path.as_std_path()).expect("open database");
     connection
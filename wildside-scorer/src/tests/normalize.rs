@@ -0,0 +1,142 @@
+//! Tests for normalising raw popularity scores.
+
+use rstest::rstest;
+use wildside_core::Theme;
+
+use crate::{
+    PopularityNormalization,
+    normalize::{normalize_scores, normalize_within_categories},
+};
+
+#[rstest]
+#[expect(
+    clippy::float_arithmetic,
+    reason = "test uses float maths for assertions"
+)]
+fn normalizes_scores() {
+    let mut raw = std::collections::HashMap::new();
+    raw.insert(1, 10.0_f32);
+    raw.insert(2, 5.0_f32);
+
+    let normalized = normalize_scores(&raw, PopularityNormalization::Max);
+
+    assert_eq!(normalized.get(&1), Some(&1.0_f32));
+    let value = normalized.get(&2).expect("score for poi 2");
+    let delta = (value - 0.5_f32).abs();
+    assert!(
+        delta < 0.000_1_f32,
+        "expected approximately 0.5, got {value}"
+    );
+}
+
+#[rstest]
+fn normalizes_zero_scores_to_zero() {
+    let mut raw = std::collections::HashMap::new();
+    raw.insert(1, 0.0_f32);
+    raw.insert(2, 0.0_f32);
+
+    let normalized = normalize_scores(&raw, PopularityNormalization::Max);
+
+    assert_eq!(normalized.get(&1), Some(&0.0_f32));
+    assert_eq!(normalized.get(&2), Some(&0.0_f32));
+}
+
+fn mega_landmark_scores() -> std::collections::HashMap<u64, f32> {
+    [(1, 1_000.0_f32), (2, 10.0_f32), (3, 5.0_f32), (4, 0.0_f32)]
+        .into_iter()
+        .collect()
+}
+
+#[rstest]
+#[case(PopularityNormalization::Log)]
+#[case(PopularityNormalization::PercentileRank)]
+#[case(PopularityNormalization::ZScore)]
+fn alternative_normalizations_stay_in_range_and_keep_order(#[case] mode: PopularityNormalization) {
+    let normalized = normalize_scores(&mega_landmark_scores(), mode);
+
+    let values: Vec<f32> = [1, 2, 3, 4]
+        .iter()
+        .map(|id| *normalized.get(id).expect("normalized score"))
+        .collect();
+    assert!(
+        values
+            .iter()
+            .all(|value| (0.0_f32..=1.0_f32).contains(value))
+    );
+    assert!(
+        values.windows(2).all(|pair| pair.first() >= pair.last()),
+        "{mode:?} should keep raw order, got {values:?}"
+    );
+}
+
+#[rstest]
+fn log_normalization_lifts_scores_flattened_by_a_landmark() {
+    let raw = mega_landmark_scores();
+    let by_max = normalize_scores(&raw, PopularityNormalization::Max);
+    let by_log = normalize_scores(&raw, PopularityNormalization::Log);
+
+    let flattened = *by_max.get(&2).expect("max score");
+    let lifted = *by_log.get(&2).expect("log score");
+    assert!(flattened < 0.02_f32, "expected near zero, got {flattened}");
+    assert!(lifted > 0.3_f32, "expected a visible score, got {lifted}");
+}
+
+#[rstest]
+#[expect(
+    clippy::float_arithmetic,
+    reason = "test uses float maths for assertions"
+)]
+fn percentile_rank_spaces_scores_evenly() {
+    let normalized = normalize_scores(
+        &mega_landmark_scores(),
+        PopularityNormalization::PercentileRank,
+    );
+
+    let delta = normalized
+        .get(&3)
+        .map(|value| (value - 1.0_f32 / 3.0_f32).abs())
+        .expect("rank score");
+    assert!(delta < 0.000_1_f32, "expected one third, got {delta}");
+    assert_eq!(normalized.get(&4), Some(&0.0_f32));
+}
+
+#[rstest]
+fn z_score_clamps_below_average_scores_to_zero() {
+    let normalized = normalize_scores(&mega_landmark_scores(), PopularityNormalization::ZScore);
+
+    assert_eq!(normalized.get(&2), Some(&0.0_f32));
+    assert_eq!(normalized.get(&4), Some(&0.0_f32));
+}
+
+#[rstest]
+#[case(PopularityNormalization::PercentileRank)]
+#[case(PopularityNormalization::ZScore)]
+fn relative_normalizations_score_equal_values_as_zero(#[case] mode: PopularityNormalization) {
+    let raw = [(1, 7.0_f32), (2, 7.0_f32)].into_iter().collect();
+
+    let normalized = normalize_scores(&raw, mode);
+
+    assert_eq!(normalized.get(&1), Some(&0.0_f32));
+    assert_eq!(normalized.get(&2), Some(&0.0_f32));
+}
+
+#[rstest]
+fn category_normalization_scores_each_category_leader_highly() {
+    let raw = [
+        (1, (1_000.0_f32, Some(Theme::Culture))),
+        (2, (500.0_f32, Some(Theme::Culture))),
+        (3, (20.0_f32, Some(Theme::Nature))),
+        (4, (10.0_f32, Some(Theme::Nature))),
+        (5, (4.0_f32, None)),
+    ]
+    .into_iter()
+    .collect();
+
+    let normalized = normalize_within_categories(&raw, PopularityNormalization::Max);
+
+    assert_eq!(normalized.get(&1), Some(&1.0_f32));
+    assert_eq!(normalized.get(&2), Some(&0.5_f32));
+    assert_eq!(normalized.get(&3), Some(&1.0_f32));
+    assert_eq!(normalized.get(&4), Some(&0.5_f32));
+    assert_eq!(normalized.get(&5), Some(&1.0_f32));
+}
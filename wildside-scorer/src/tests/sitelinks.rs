@@ -0,0 +1,74 @@
+//! Tests for reading sitelink counts from OSM tags.
+
+use rstest::rstest;
+
+use crate::{PopularityError, resolver::parse_sitelinks_from_tags};
+
+#[rstest]
+fn parses_numeric_sitelinks_from_tags() {
+    let tags = r#"{"wikidata":"Q64","sitelinks":42}"#;
+
+    let parsed = parse_sitelinks_from_tags(tags, 1).expect("parse sitelinks");
+
+    assert_eq!(parsed, Some(42));
+}
+
+#[rstest]
+fn parses_string_sitelinks_from_tags() {
+    let tags = r#"{"wikidata":"Q64","sitelinks":"17"}"#;
+
+    let parsed = parse_sitelinks_from_tags(tags, 1).expect("parse sitelinks");
+
+    assert_eq!(parsed, Some(17));
+}
+
+#[rstest]
+fn missing_sitelinks_returns_none() {
+    let tags = r#"{"wikidata":"Q64"}"#;
+
+    let parsed = parse_sitelinks_from_tags(tags, 1).expect("parse sitelinks");
+
+    assert!(parsed.is_none());
+}
+
+#[rstest]
+fn null_sitelinks_returns_none() {
+    let tags = r#"{"wikidata":"Q64","sitelinks":null}"#;
+
+    let parsed = parse_sitelinks_from_tags(tags, 1).expect("parse sitelinks");
+
+    assert!(parsed.is_none());
+}
+
+#[rstest]
+fn sitelink_count_alias_is_supported() {
+    let tags = r#"{"wikidata":"Q64","sitelink_count":5}"#;
+
+    let parsed = parse_sitelinks_from_tags(tags, 1).expect("parse sitelinks");
+
+    assert_eq!(parsed, Some(5));
+}
+
+#[rstest]
+fn empty_string_returns_none() {
+    let tags = r#"{"wikidata":"Q64","sitelinks":"   "}"#;
+
+    let parsed = parse_sitelinks_from_tags(tags, 1).expect("parse sitelinks");
+
+    assert!(parsed.is_none());
+}
+
+#[rstest]
+fn invalid_array_sitelinks_returns_error() {
+    let tags = r#"{"wikidata":"Q64","sitelinks":[]}"#;
+
+    let err = match parse_sitelinks_from_tags(tags, 1) {
+        Ok(value) => panic!("expected error, got {value:?}"),
+        Err(err) => err,
+    };
+
+    match err {
+        PopularityError::InvalidSitelinkCountJson { poi_id, .. } => assert_eq!(poi_id, 1),
+        other => panic!("unexpected error: {other}"),
+    }
+}
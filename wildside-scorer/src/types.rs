@@ -4,6 +4,7 @@
 use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
+use wildside_core::store::PopularityLookup;
 
 /// Tunable weights applied to raw popularity signals.
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
@@ -12,6 +13,15 @@ pub struct PopularityWeights {
     pub sitelink_weight: f32,
     /// Additive bonus applied when a POI is a UNESCO World Heritage Site.
     pub heritage_bonus: f32,
+    /// Bonuses read from the POI's own OpenStreetMap tags.
+    #[serde(default)]
+    pub tag_boosts: TagBoosts,
+    /// How raw scores are mapped into `0.0..=1.0`.
+    #[serde(default)]
+    pub normalization: PopularityNormalization,
+    /// Which POIs each raw score is normalized against.
+    #[serde(default)]
+    pub scope: PopularityScope,
 }
 
 impl Default for PopularityWeights {
@@ -19,10 +29,229 @@ impl Default for PopularityWeights {
         Self {
             sitelink_weight: 1.0_f32,
             heritage_bonus: 25.0_f32,
+            tag_boosts: TagBoosts::default(),
+            normalization: PopularityNormalization::default(),
+            scope: PopularityScope::default(),
         }
     }
 }
 
+/// Strategy for mapping raw popularity scores into `0.0..=1.0`.
+///
+/// Dividing by the maximum lets one landmark with thousands of sitelinks
+/// flatten every other POI towards zero. The other modes spread scores more
+/// evenly. The mode used is recorded in the `popularity.bin` header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PopularityNormalization {
+    /// Divide each score by the highest score.
+    #[default]
+    Max,
+    /// Divide `ln(1 + score)` by `ln(1 + max)`, compressing the top end.
+    Log,
+    /// The fraction of other POIs that score strictly lower.
+    PercentileRank,
+    /// Standard deviations above the mean, clamped to `0.0..=3.0` and divided
+    /// by three, so scores at or below the mean normalize to zero.
+    ZScore,
+}
+
+impl PopularityNormalization {
+    /// Every mode, in header code order.
+    pub const ALL: [Self; 4] = [Self::Max, Self::Log, Self::PercentileRank, Self::ZScore];
+
+    /// Code identifying the mode in the `popularity.bin` header.
+    #[must_use]
+    pub const fn code(self) -> u8 {
+        match self {
+            Self::Max => 0,
+            Self::Log => 1,
+            Self::PercentileRank => 2,
+            Self::ZScore => 3,
+        }
+    }
+
+    /// Mode recorded under a header `code`, or `None` when it is unknown.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wildside_scorer::PopularityNormalization;
+    ///
+    /// for mode in PopularityNormalization::ALL {
+    ///     assert_eq!(PopularityNormalization::from_code(mode.code()), Some(mode));
+    /// }
+    /// assert_eq!(PopularityNormalization::from_code(9), None);
+    /// ```
+    #[must_use]
+    pub const fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(Self::Max),
+            1 => Some(Self::Log),
+            2 => Some(Self::PercentileRank),
+            3 => Some(Self::ZScore),
+            _ => None,
+        }
+    }
+}
+
+/// Which POIs each raw popularity score is normalized against.
+///
+/// Normalizing across every POI lets blockbuster museums drown out the best
+/// park or viewpoint. [`Self::Category`] normalizes within each POI's
+/// category instead, so every category's leaders score highly. A POI's
+/// category is its first [`Theme`](wildside_core::Theme) in
+/// [`Theme::ALL`](wildside_core::Theme::ALL) whose OSM tags it carries, and
+/// POIs matching no theme form one further category. The scope used is
+/// recorded in the `popularity.bin` header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PopularityScope {
+    /// Normalize against every POI in the database.
+    #[default]
+    Global,
+    /// Normalize against the POIs sharing the POI's category.
+    Category,
+}
+
+impl PopularityScope {
+    /// Every scope, in header code order.
+    pub const ALL: [Self; 2] = [Self::Global, Self::Category];
+
+    /// Code identifying the scope in the `popularity.bin` header.
+    #[must_use]
+    pub const fn code(self) -> u8 {
+        match self {
+            Self::Global => 0,
+            Self::Category => 1,
+        }
+    }
+
+    /// Scope recorded under a header `code`, or `None` when it is unknown.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use wildside_scorer::PopularityScope;
+    ///
+    /// for scope in PopularityScope::ALL {
+    ///     assert_eq!(PopularityScope::from_code(scope.code()), Some(scope));
+    /// }
+    /// assert_eq!(PopularityScope::from_code(2), None);
+    /// ```
+    #[must_use]
+    pub const fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => Some(Self::Global),
+            1 => Some(Self::Category),
+            _ => None,
+        }
+    }
+}
+
+/// Additive popularity bonuses for OpenStreetMap tags.
+///
+/// Sitelinks and heritage claims only reach POIs linked to Wikidata. These
+/// bonuses read the POI's own tags, so well-known places without a link still
+/// earn some popularity. Bonuses are in the same raw units as the sitelink
+/// count and are normalized with it.
+///
+/// # Examples
+///
+/// ```rust
+/// use wildside_scorer::TagBoosts;
+///
+/// let boosts = TagBoosts::default();
+/// let listed = boosts.bonus([("heritage", "2"), ("name", "Old Bridge")]);
+/// assert_eq!(listed, boosts.heritage / 2.0);
+/// assert_eq!(TagBoosts::disabled().bonus([("tourism", "attraction")]), 0.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TagBoosts {
+    /// Added for `tourism=attraction`.
+    pub attraction: f32,
+    /// Added when the POI has a non-empty `wikipedia` tag.
+    pub wikipedia: f32,
+    /// Added for `heritage=1`; `heritage=N` adds this divided by `N`, so
+    /// international listings outrank regional ones. Non-numeric levels earn
+    /// nothing.
+    pub heritage: f32,
+}
+
+impl Default for TagBoosts {
+    fn default() -> Self {
+        Self {
+            attraction: 5.0_f32,
+            wikipedia: 10.0_f32,
+            heritage: 15.0_f32,
+        }
+    }
+}
+
+impl TagBoosts {
+    /// Bonuses that leave popularity to Wikidata signals alone.
+    #[must_use]
+    pub const fn disabled() -> Self {
+        Self {
+            attraction: 0.0_f32,
+            wikipedia: 0.0_f32,
+            heritage: 0.0_f32,
+        }
+    }
+
+    /// Total bonus earned by a POI with the given `(key, value)` tags.
+    #[must_use]
+    pub fn bonus<'a>(&self, tags: impl IntoIterator<Item = (&'a str, &'a str)>) -> f32 {
+        tags.into_iter()
+            .filter_map(|(key, value)| TagSignal::read(key, value))
+            .map(|signal| self.signal_bonus(signal))
+            .sum()
+    }
+
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "heritage bonuses shrink with the listing level"
+    )]
+    fn signal_bonus(&self, signal: TagSignal) -> f32 {
+        match signal {
+            TagSignal::Attraction => self.attraction,
+            TagSignal::Wikipedia => self.wikipedia,
+            TagSignal::Heritage(level) => self.heritage / f32::from(level),
+        }
+    }
+}
+
+/// A tag that earns a [`TagBoosts`] bonus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TagSignal {
+    Attraction,
+    Wikipedia,
+    Heritage(u8),
+}
+
+impl TagSignal {
+    fn read(key: &str, value: &str) -> Option<Self> {
+        let trimmed = value.trim();
+        match key {
+            "tourism" if trimmed == "attraction" => Some(Self::Attraction),
+            "wikipedia" if !trimmed.is_empty() => Some(Self::Wikipedia),
+            "heritage" => trimmed
+                .parse()
+                .ok()
+                .filter(|level| *level > 0)
+                .map(Self::Heritage),
+            _ => None,
+        }
+    }
+}
+
+/// Report whether a tag earns a [`TagBoosts`] bonus.
+#[cfg(feature = "scorer-sqlite")]
+pub(crate) fn is_boosting_tag(key: &str, value: &str) -> bool {
+    TagSignal::read(key, value).is_some()
+}
+
 /// Normalized popularity scores keyed by POI identifier.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PopularityScores {
@@ -64,3 +293,9 @@ impl PopularityScores {
         self.scores
     }
 }
+
+impl PopularityLookup for PopularityScores {
+    fn popularity(&self, id: u64) -> Option<f32> {
+        self.get(id)
+    }
+}
@@ -0,0 +1,92 @@
+//! Fetching claims for many POIs at once, or for all of them up front.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use log::warn;
+use wildside_core::PointOfInterest;
+use wildside_data::claims::{ClaimsStore, ClaimsStoreError, PoiClaim};
+
+use super::{ClaimSelector, ThemeClaimMapping, UserRelevanceError, UserRelevanceScorer};
+
+/// The POIs holding each claim named by a theme mapping, held in memory.
+#[derive(Debug, Default)]
+pub(super) struct PrefetchedClaims {
+    holders: HashMap<ClaimSelector, HashSet<u64>>,
+}
+
+impl PrefetchedClaims {
+    /// Load the holders of every selector in `mapping` from `store`.
+    fn load(store: &ClaimsStore, mapping: &ThemeClaimMapping) -> Result<Self, ClaimsStoreError> {
+        let mut holders = HashMap::new();
+        for selector in mapping.iter().flat_map(|(_, selectors)| selectors) {
+            if holders.contains_key(selector) {
+                continue;
+            }
+            let pois = store.pois_with_claim(selector.property_id(), selector.value_entity_id())?;
+            holders.insert(selector.clone(), pois.into_iter().collect());
+        }
+        Ok(Self { holders })
+    }
+
+    pub(super) fn holds(&self, selector: &ClaimSelector, poi_id: u64) -> bool {
+        self.holders
+            .get(selector)
+            .is_some_and(|pois| pois.contains(&poi_id))
+    }
+}
+
+impl UserRelevanceScorer {
+    /// Hold the claims named by the theme mapping in memory, so scoring no
+    /// longer queries `SQLite`.
+    ///
+    /// Only the POIs holding a mapped claim are loaded, which keeps the
+    /// prefetch small enough for interactive solves. Explanations still read
+    /// their provenance from the database.
+    ///
+    /// # Errors
+    /// Returns [`UserRelevanceError::PrefetchClaims`] when the claims cannot
+    /// be read.
+    pub fn with_prefetched_claims(mut self) -> Result<Self, UserRelevanceError> {
+        let prefetched = self
+            .claims
+            .with(|claims_store| PrefetchedClaims::load(claims_store, &self.mapping))
+            .and_then(|loaded| loaded)
+            .map_err(|source| UserRelevanceError::PrefetchClaims { source })?;
+        self.prefetched = Some(Arc::new(prefetched));
+        Ok(self)
+    }
+
+    /// Fetch the claims of `poi_id`, logging and yielding `None` on failure.
+    pub(super) fn poi_claims(&self, poi_id: u64) -> Option<Vec<PoiClaim>> {
+        self.claims
+            .with(|claims_store| claims_store.claims_for_poi(poi_id))
+            .map_err(|err| warn!("user relevance scoring skipped: claims store unavailable: {err}"))
+            .ok()?
+            .map_err(|err| warn!("claim query failed for POI {poi_id}: {err}"))
+            .ok()
+    }
+
+    /// Fetch the claims of every linked POI in `pois` in one batch, logging
+    /// and yielding no claims on failure.
+    pub(super) fn batch_claims(&self, pois: &[PointOfInterest]) -> HashMap<u64, Vec<PoiClaim>> {
+        let linked: Vec<u64> = pois
+            .iter()
+            .map(|poi| poi.id)
+            .filter(|poi_id| !self.is_unlinked(*poi_id))
+            .collect();
+        if linked.is_empty() {
+            return HashMap::new();
+        }
+        self.claims
+            .with(|claims_store| claims_store.claims_for_pois(&linked))
+            .map_err(|err| warn!("user relevance scoring skipped: claims store unavailable: {err}"))
+            .ok()
+            .and_then(|claims| {
+                claims
+                    .map_err(|err| warn!("batch claim query failed: {err}"))
+                    .ok()
+            })
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,223 @@
+//! How the user relevance scorer matches claims to themes and weighs its
+//! components.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use wildside_core::Theme;
+use wildside_data::claims::PoiClaim;
+
+use super::UserRelevanceError;
+use crate::mapping::RawThemeClaimMapping;
+
+const DEFAULT_HISTORY_PROPERTY: &str = "P1435";
+const DEFAULT_HISTORY_VALUE: &str = "Q9259";
+
+/// Declarative mapping from a theme to one or more Wikidata property/value
+/// pairs.
+///
+/// Mappings serialize as a table of selectors keyed by theme name and can be
+/// loaded from configuration with [`Self::from_path`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "RawThemeClaimMapping", into = "RawThemeClaimMapping")]
+pub struct ThemeClaimMapping {
+    pub(super) map: HashMap<Theme, Vec<ClaimSelector>>,
+}
+
+impl ThemeClaimMapping {
+    /// Create an empty mapping.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            map: HashMap::new(),
+        }
+    }
+
+    /// Insert a claim selector for the given theme.
+    pub fn insert(&mut self, theme: Theme, selector: ClaimSelector) {
+        self.map.entry(theme).or_default().push(selector);
+    }
+
+    /// Add a selector while consuming `self`, enabling chaining.
+    #[must_use]
+    pub fn with_selector(mut self, theme: Theme, selector: ClaimSelector) -> Self {
+        self.insert(theme, selector);
+        self
+    }
+
+    /// Retrieve selectors for a theme, if present (test-only helper).
+    #[cfg(test)]
+    pub(crate) fn selectors(&self, theme: &Theme) -> Option<&[ClaimSelector]> {
+        self.map.get(theme).map(Vec::as_slice)
+    }
+
+    /// Iterate over all configured selectors grouped by theme.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&Theme, &[ClaimSelector])> {
+        self.map
+            .iter()
+            .map(|(theme, selectors)| (theme, selectors.as_slice()))
+    }
+}
+
+impl Default for ThemeClaimMapping {
+    fn default() -> Self {
+        let selector = ClaimSelector::new(DEFAULT_HISTORY_PROPERTY, DEFAULT_HISTORY_VALUE)
+            .unwrap_or_else(|_| ClaimSelector {
+                property_id: DEFAULT_HISTORY_PROPERTY.to_owned(),
+                value_entity_id: DEFAULT_HISTORY_VALUE.to_owned(),
+            });
+        Self::new().with_selector(Theme::History, selector)
+    }
+}
+
+/// Identify a Wikidata claim by property and value.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClaimSelector {
+    property_id: String,
+    value_entity_id: String,
+}
+
+impl ClaimSelector {
+    /// Build a selector from property and value identifiers.
+    ///
+    /// # Errors
+    /// Returns [`UserRelevanceError::InvalidSelector`] when either identifier
+    /// is empty or whitespace.
+    pub fn new(
+        property_id: impl Into<String>,
+        value_entity_id: impl Into<String>,
+    ) -> Result<Self, UserRelevanceError> {
+        let property = property_id.into();
+        let value = value_entity_id.into();
+        if property.trim().is_empty() || value.trim().is_empty() {
+            return Err(UserRelevanceError::InvalidSelector);
+        }
+        Ok(Self {
+            property_id: property,
+            value_entity_id: value,
+        })
+    }
+
+    /// Return the Wikidata property identifier, such as `P1435`.
+    #[must_use]
+    pub fn property_id(&self) -> &str {
+        &self.property_id
+    }
+
+    /// Return the Wikidata value entity identifier, such as `Q9259`.
+    #[must_use]
+    pub fn value_entity_id(&self) -> &str {
+        &self.value_entity_id
+    }
+
+    /// Return the linked entity holding this claim among a POI's `claims`.
+    pub(super) fn matched_by<'a>(&self, claims: &'a [PoiClaim]) -> Option<&'a str> {
+        matching_entity(claims, &self.property_id, &self.value_entity_id)
+    }
+}
+
+/// Relative weighting between global popularity and user relevance.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ScoreWeights {
+    /// Multiplier applied to the global popularity component.
+    pub popularity: f32,
+    /// Multiplier applied to the user relevance component.
+    pub user_relevance: f32,
+}
+
+impl ScoreWeights {
+    /// Validate the weights and return a copy.
+    ///
+    /// # Errors
+    /// Returns [`UserRelevanceError::InvalidWeights`] when either value is not
+    /// finite or the total weight is zero.
+    pub const fn validate(self) -> Result<Self, UserRelevanceError> {
+        if self.is_valid() {
+            Ok(self)
+        } else {
+            Err(UserRelevanceError::InvalidWeights)
+        }
+    }
+
+    #[expect(
+        clippy::trivially_copy_pass_by_ref,
+        reason = "ScoreWeights is a tiny Copy type; pass-by-ref keeps the signature consistent"
+    )]
+    const fn is_valid(&self) -> bool {
+        self.has_finite_values() && self.has_non_negative_values() && self.has_non_zero_total()
+    }
+
+    #[expect(
+        clippy::trivially_copy_pass_by_ref,
+        reason = "ScoreWeights is Copy; borrowing avoids repeated copies"
+    )]
+    const fn has_finite_values(&self) -> bool {
+        self.popularity.is_finite() && self.user_relevance.is_finite()
+    }
+
+    #[expect(
+        clippy::trivially_copy_pass_by_ref,
+        reason = "ScoreWeights is Copy; borrowing avoids repeated copies"
+    )]
+    const fn has_non_negative_values(&self) -> bool {
+        self.popularity >= 0.0_f32 && self.user_relevance >= 0.0_f32
+    }
+
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "validation sums weights to ensure a non-zero total"
+    )]
+    #[expect(
+        clippy::trivially_copy_pass_by_ref,
+        reason = "ScoreWeights is Copy; borrowing avoids repeated copies"
+    )]
+    const fn has_non_zero_total(&self) -> bool {
+        (self.popularity + self.user_relevance) != 0.0_f32
+    }
+
+    /// Drop the popularity component when no scores are available.
+    pub(super) const fn without_popularity(self) -> Self {
+        Self {
+            popularity: 0.0_f32,
+            ..self
+        }
+    }
+
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "score blending requires weighted averages"
+    )]
+    pub(super) fn blend(self, popularity: f32, user_relevance: f32) -> f32 {
+        let user_weight = if user_relevance == 0.0_f32 {
+            0.0_f32
+        } else {
+            self.user_relevance
+        };
+        let total = self.popularity + user_weight;
+        if total == 0.0 {
+            return 0.0;
+        }
+        (popularity * self.popularity + user_relevance * user_weight) / total
+    }
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self {
+            popularity: 0.5_f32,
+            user_relevance: 0.5_f32,
+        }
+    }
+}
+
+/// Return the first linked entity, in entity order, holding the claim.
+pub(super) fn matching_entity<'a>(
+    claims: &'a [PoiClaim],
+    property_id: &str,
+    value_entity_id: &str,
+) -> Option<&'a str> {
+    claims
+        .iter()
+        .find(|claim| claim.property_id == property_id && claim.value_entity_id == value_entity_id)
+        .map(|claim| claim.entity_id.as_str())
+}
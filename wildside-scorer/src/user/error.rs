@@ -0,0 +1,102 @@
+//! Errors raised by the user relevance scorer.
+
+use camino::Utf8PathBuf;
+use thiserror::Error;
+use wildside_core::store::LinkFilterError;
+use wildside_data::claims::ClaimsStoreError;
+
+use crate::PopularityHeaderError;
+
+/// Errors raised when initializing or configuring the user relevance scorer.
+#[derive(Debug, Error)]
+pub enum UserRelevanceError {
+    /// Opening the `SQLite` database failed.
+    #[error("failed to open read-only SQLite database at {path}")]
+    OpenDatabase {
+        /// Requested database path.
+        path: Utf8PathBuf,
+        /// Source error from `rusqlite`.
+        #[source]
+        source: rusqlite::Error,
+    },
+    /// The database's Wikidata claims could not be read.
+    #[error("failed to read Wikidata claims from {path}")]
+    Claims {
+        /// Requested database path.
+        path: Utf8PathBuf,
+        /// Source error from the claims store.
+        #[source]
+        source: ClaimsStoreError,
+    },
+    /// Reading the popularity artefact failed.
+    #[error("failed to read popularity file at {path}")]
+    ReadPopularity {
+        /// Path to the popularity artefact.
+        path: Utf8PathBuf,
+        /// Source error from std I/O.
+        #[source]
+        source: std::io::Error,
+    },
+    /// Decoding the popularity artefact failed.
+    #[error("failed to decode popularity file at {path}")]
+    DecodePopularity {
+        /// Path to the popularity artefact.
+        path: Utf8PathBuf,
+        /// Source error from `bincode`.
+        #[source]
+        source: bincode::Error,
+    },
+    /// The popularity artefact uses an unsupported format version or
+    /// normalization.
+    #[error("unsupported popularity file at {path}")]
+    PopularityVersion {
+        /// Path to the popularity artefact.
+        path: Utf8PathBuf,
+        /// Header negotiation failure.
+        #[source]
+        source: PopularityHeaderError,
+    },
+    /// The popularity artefact was computed from a different database.
+    #[error(
+        "popularity file at {path} (generated at {generated_at}) was not computed from {database}"
+    )]
+    StalePopularity {
+        /// Path to the popularity artefact.
+        path: Utf8PathBuf,
+        /// Database the scorer was opened with.
+        database: Utf8PathBuf,
+        /// Seconds since the Unix epoch when the artefact was generated.
+        generated_at: u64,
+    },
+    /// Hashing the database to check popularity provenance failed.
+    #[error("failed to hash database at {path}")]
+    HashDatabase {
+        /// Requested database path.
+        path: Utf8PathBuf,
+        /// Source error from std I/O.
+        #[source]
+        source: std::io::Error,
+    },
+    /// Loading mapped claims into memory failed.
+    #[error("failed to prefetch Wikidata claims")]
+    PrefetchClaims {
+        /// Source error from the claims store.
+        #[source]
+        source: ClaimsStoreError,
+    },
+    /// Loading the link membership filter failed.
+    #[error("failed to load link filter at {path}")]
+    LoadLinkFilter {
+        /// Path to the link filter artefact.
+        path: Utf8PathBuf,
+        /// Source error from the filter reader.
+        #[source]
+        source: LinkFilterError,
+    },
+    /// Provided weights were unusable.
+    #[error("weights must be finite and sum to a positive value")]
+    InvalidWeights,
+    /// A claim selector was missing identifiers.
+    #[error("claim selector must include non-empty property and value identifiers")]
+    InvalidSelector,
+}
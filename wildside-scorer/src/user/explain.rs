@@ -0,0 +1,165 @@
+//! Explaining a score by citing the data behind each component.
+
+use log::warn;
+use wildside_core::{InterestProfile, PointOfInterest, Scorer, Theme};
+use wildside_data::claims::{ClaimsStore, PoiClaim};
+
+use super::UserRelevanceScorer;
+use super::config::matching_entity;
+use super::popularity::LoadedPopularity;
+use crate::{
+    HERITAGE_PROPERTY, InterestComponent, PopularityComponent, ScoreExplanation,
+    UNESCO_WORLD_HERITAGE, types::is_boosting_tag,
+};
+
+/// Stored data an explanation cites for one POI.
+struct PoiEvidence {
+    claims: Vec<PoiClaim>,
+    sitelinks: Option<u32>,
+}
+
+impl UserRelevanceScorer {
+    /// Break the score of `poi` into components that cite their data.
+    ///
+    /// [`ScoreExplanation::score`] always equals [`Scorer::score`]. The
+    /// provenance lookups make this slower than scoring, so reserve it for
+    /// diagnostics rather than candidate ranking.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use camino::Utf8Path;
+    /// use geo::Coord;
+    /// use wildside_core::{InterestProfile, PointOfInterest, Theme};
+    /// use wildside_scorer::UserRelevanceScorer;
+    ///
+    /// let scorer = UserRelevanceScorer::with_defaults(
+    ///     Utf8Path::new("artifacts/pois.db"),
+    ///     Utf8Path::new("artifacts/popularity.bin"),
+    /// )?;
+    /// let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+    /// let profile = InterestProfile::new().with_weight(Theme::History, 1.0);
+    /// let explanation = scorer.explain(&poi, &profile);
+    /// for interest in &explanation.interests {
+    ///     println!("{} matched via {}", interest.theme, interest.entity_id);
+    /// }
+    /// # Ok::<(), wildside_scorer::UserRelevanceError>(())
+    /// ```
+    #[must_use]
+    pub fn explain(&self, poi: &PointOfInterest, profile: &InterestProfile) -> ScoreExplanation {
+        let score = self.score(poi, profile);
+        let Some(evidence) = self.evidence(poi) else {
+            return ScoreExplanation {
+                poi_id: poi.id,
+                score,
+                popularity: None,
+                interests: Vec::new(),
+            };
+        };
+        ScoreExplanation {
+            poi_id: poi.id,
+            score,
+            popularity: self
+                .popularity
+                .as_ref()
+                .map(|loaded| self.popularity_component(poi, loaded, &evidence)),
+            interests: self.interest_components(&evidence.claims, poi, profile),
+        }
+    }
+
+    /// Gather the stored claims and sitelink count cited by an explanation.
+    fn evidence(&self, poi: &PointOfInterest) -> Option<PoiEvidence> {
+        self.claims
+            .with(|claims_store| {
+                let claims = claims_store.claims_for_poi(poi.id).unwrap_or_else(|err| {
+                    warn!("claim provenance query failed for POI {}: {err}", poi.id);
+                    Vec::new()
+                });
+                PoiEvidence {
+                    claims,
+                    sitelinks: sitelink_count(claims_store, poi.id).or_else(|| tag_sitelinks(poi)),
+                }
+            })
+            .map_err(|err| warn!("score explanation skipped: claims store unavailable: {err}"))
+            .ok()
+    }
+
+    fn popularity_component(
+        &self,
+        poi: &PointOfInterest,
+        loaded: &LoadedPopularity,
+        evidence: &PoiEvidence,
+    ) -> PopularityComponent {
+        PopularityComponent {
+            score: loaded.score(poi.id),
+            weight: self.weights.popularity,
+            artefact: loaded.artefact.clone(),
+            sitelinks: evidence.sitelinks,
+            heritage_entity: matching_entity(
+                &evidence.claims,
+                HERITAGE_PROPERTY,
+                UNESCO_WORLD_HERITAGE,
+            )
+            .map(str::to_owned),
+            boosting_tags: boosting_tags(poi),
+        }
+    }
+
+    fn interest_components(
+        &self,
+        claims: &[PoiClaim],
+        poi: &PointOfInterest,
+        profile: &InterestProfile,
+    ) -> Vec<InterestComponent> {
+        if self.is_unlinked(poi.id) {
+            return Vec::new();
+        }
+        Theme::ALL
+            .iter()
+            .filter_map(|theme| {
+                let weight = profile
+                    .weight(theme)
+                    .filter(|weight| *weight != 0.0_f32 && weight.is_finite())?;
+                let (selector, entity_id) =
+                    self.mapping.map.get(theme)?.iter().find_map(|selector| {
+                        selector
+                            .matched_by(claims)
+                            .map(|entity_id| (selector, entity_id))
+                    })?;
+                Some(InterestComponent {
+                    theme: theme.clone(),
+                    weight,
+                    selector: selector.clone(),
+                    entity_id: entity_id.to_owned(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Look up the stored sitelink count for the POI's linked entity.
+///
+/// Artefacts from older ingests lack the sitelink table, so a failed lookup
+/// yields `None` and the caller falls back to POI tags, mirroring the
+/// popularity computation.
+fn sitelink_count(store: &ClaimsStore, poi_id: u64) -> Option<u32> {
+    store.sitelink_count_for_poi(poi_id).ok().flatten()
+}
+
+/// Tags of `poi` that earn a popularity bonus, sorted by key.
+fn boosting_tags(poi: &PointOfInterest) -> Vec<(String, String)> {
+    let mut tags: Vec<(String, String)> = poi
+        .tags
+        .iter()
+        .filter(|(key, value)| is_boosting_tag(key, value))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    tags.sort_unstable();
+    tags
+}
+
+fn tag_sitelinks(poi: &PointOfInterest) -> Option<u32> {
+    ["sitelinks", "sitelink_count"]
+        .into_iter()
+        .find_map(|key| poi.tags.get(key))
+        .and_then(|raw| raw.trim().parse().ok())
+}
@@ -0,0 +1,257 @@
+//! User relevance scoring that combines personalized interests with
+//! pre-computed popularity, returning a normalized value in `0.0..=1.0` via
+//! the `Scorer` trait.
+//!
+//! The scorer inspects Wikidata claims stored in `pois.db` to determine whether
+//! a point of interest matches the visitor's declared themes. It blends these
+//! matches with the global popularity score loaded from `popularity.bin`,
+//! returning a normalized value in `0.0..=1.0` via the `Scorer` trait.
+
+#![forbid(unsafe_code)]
+
+use std::sync::Arc;
+
+use camino::Utf8Path;
+use rusqlite::{Connection, OpenFlags};
+use wildside_core::{
+    InterestProfile, PointOfInterest, Scorer,
+    store::{DEFAULT_MAX_IDLE, LinkFilter, ReadPool},
+};
+use wildside_data::claims::{ClaimsStore, ClaimsStoreError, PoiClaim};
+
+mod batch;
+mod config;
+mod error;
+mod explain;
+mod popularity;
+
+pub use config::{ClaimSelector, ScoreWeights, ThemeClaimMapping};
+pub use error::UserRelevanceError;
+pub use popularity::{PopularitySource, load_popularity, load_popularity_with_header};
+
+use batch::PrefetchedClaims;
+use popularity::LoadedPopularity;
+
+/// Scorer that blends per-user interests with global popularity.
+#[derive(Debug, Clone)]
+pub struct UserRelevanceScorer {
+    claims: Arc<ReadPool<ClaimsStore, ClaimsStoreError>>,
+    mapping: ThemeClaimMapping,
+    weights: ScoreWeights,
+    popularity: Option<LoadedPopularity>,
+    link_filter: Option<Arc<LinkFilter>>,
+    prefetched: Option<Arc<PrefetchedClaims>>,
+}
+
+impl UserRelevanceScorer {
+    /// Construct a scorer from artefact paths using default mapping and weights.
+    ///
+    /// # Errors
+    /// Propagates filesystem, decoding, and database preparation failures.
+    pub fn with_defaults(
+        database_path: &Utf8Path,
+        popularity_path: &Utf8Path,
+    ) -> Result<Self, UserRelevanceError> {
+        Self::from_paths(
+            database_path,
+            popularity_path,
+            ThemeClaimMapping::default(),
+            ScoreWeights::default(),
+        )
+    }
+
+    /// Construct a scorer from artefact paths, mapping, and weights.
+    ///
+    /// # Errors
+    /// Returns [`UserRelevanceError`] when artefacts are unreadable, the
+    /// mapping is invalid, the database's claims schema is unreadable, or
+    /// the popularity file was computed from a different database.
+    pub fn from_paths(
+        database_path: &Utf8Path,
+        popularity_path: &Utf8Path,
+        mapping: ThemeClaimMapping,
+        weights: ScoreWeights,
+    ) -> Result<Self, UserRelevanceError> {
+        let popularity = LoadedPopularity::from_file(popularity_path.to_path_buf())?;
+        Self::open(database_path, Some(popularity), mapping, weights)
+    }
+
+    /// Construct a scorer whose popularity component comes from `popularity`.
+    ///
+    /// Unlike [`Self::from_paths`], a missing `popularity.bin` does not abort
+    /// construction when supplied via [`PopularitySource::Path`]; the scorer
+    /// instead ranks POIs on user relevance alone.
+    ///
+    /// # Errors
+    /// Returns [`UserRelevanceError`] when the database cannot be opened, the
+    /// weights are invalid, or an existing popularity file cannot be decoded
+    /// or was computed from a different database.
+    pub fn from_source(
+        database_path: &Utf8Path,
+        popularity: PopularitySource,
+        mapping: ThemeClaimMapping,
+        weights: ScoreWeights,
+    ) -> Result<Self, UserRelevanceError> {
+        Self::open(database_path, popularity.resolve()?, mapping, weights)
+    }
+
+    fn open(
+        database_path: &Utf8Path,
+        popularity: Option<LoadedPopularity>,
+        mapping: ThemeClaimMapping,
+        weights: ScoreWeights,
+    ) -> Result<Self, UserRelevanceError> {
+        let validated_weights = weights.validate()?;
+        if let Some(loaded) = &popularity {
+            loaded.check_source(database_path)?;
+        }
+        let connection = Connection::open_with_flags(
+            database_path.as_std_path(),
+            OpenFlags::SQLITE_OPEN_READ_ONLY,
+        )
+        .map_err(|source| UserRelevanceError::OpenDatabase {
+            path: database_path.to_path_buf(),
+            source,
+        })?;
+        let claims = ClaimsStore::from_connection(connection).map_err(|source| {
+            UserRelevanceError::Claims {
+                path: database_path.to_path_buf(),
+                source,
+            }
+        })?;
+
+        // Each scoring thread borrows its own read-only connection, so solver
+        // threads do not queue behind one another for claim lookups.
+        let path = database_path.to_path_buf();
+        let pool =
+            ReadPool::new(DEFAULT_MAX_IDLE, move || ClaimsStore::open(&path)).with_idle(claims);
+
+        Ok(Self {
+            claims: Arc::new(pool),
+            mapping,
+            weights: validated_weights,
+            popularity,
+            link_filter: None,
+            prefetched: None,
+        })
+    }
+
+    /// Skip claim lookups for POIs the filter reports as unlinked.
+    ///
+    /// POIs without a Wikidata link cannot match any claim selector, so a
+    /// negative filter answer lets the scorer avoid a `SQLite` round trip.
+    #[must_use]
+    pub fn with_link_filter(mut self, filter: LinkFilter) -> Self {
+        self.link_filter = Some(Arc::new(filter));
+        self
+    }
+
+    /// Load a persisted link filter and attach it to the scorer.
+    ///
+    /// # Errors
+    /// Returns [`UserRelevanceError::LoadLinkFilter`] when the artefact is
+    /// missing or malformed.
+    pub fn with_link_filter_path(self, path: &Utf8Path) -> Result<Self, UserRelevanceError> {
+        let filter = LinkFilter::load(path.as_std_path()).map_err(|source| {
+            UserRelevanceError::LoadLinkFilter {
+                path: path.to_path_buf(),
+                source,
+            }
+        })?;
+        Ok(self.with_link_filter(filter))
+    }
+
+    fn user_relevance(&self, poi: &PointOfInterest, profile: &InterestProfile) -> f32 {
+        if self.is_unlinked(poi.id) {
+            return 0.0;
+        }
+        if let Some(prefetched) = &self.prefetched {
+            return self.matched_relevance(profile, |selector| prefetched.holds(selector, poi.id));
+        }
+        self.poi_claims(poi.id)
+            .map_or(0.0_f32, |claims| self.claims_relevance(&claims, profile))
+    }
+
+    /// Sum the interest weights of the themes whose selectors `claims` match.
+    ///
+    /// Disliked themes carry negative weights, so the sum falls below zero
+    /// when they outweigh the liked themes that match.
+    fn claims_relevance(&self, claims: &[PoiClaim], profile: &InterestProfile) -> f32 {
+        self.matched_relevance(profile, |selector| selector.matched_by(claims).is_some())
+    }
+
+    /// Sum the interest weights of the themes with a selector that `matches`.
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "relevance scoring sums matching theme weights"
+    )]
+    fn matched_relevance(
+        &self,
+        profile: &InterestProfile,
+        matches: impl Fn(&ClaimSelector) -> bool,
+    ) -> f32 {
+        let mut relevance = 0.0_f32;
+        for (theme, selectors) in self.mapping.iter() {
+            let Some(weight) = profile.weight(theme) else {
+                continue;
+            };
+            if weight == 0.0_f32 || !weight.is_finite() {
+                continue;
+            }
+            if selectors.iter().any(&matches) {
+                relevance += weight;
+            }
+        }
+
+        if relevance.is_finite() {
+            relevance.clamp(-1.0_f32, 1.0_f32)
+        } else {
+            0.0_f32
+        }
+    }
+
+    /// Blend `user_relevance` with the popularity of `poi_id`.
+    fn blend(&self, poi_id: u64, user_relevance: f32) -> f32 {
+        let blended = self.popularity.as_ref().map_or_else(
+            || {
+                self.weights
+                    .without_popularity()
+                    .blend(0.0_f32, user_relevance)
+            },
+            |loaded| self.weights.blend(loaded.score(poi_id), user_relevance),
+        );
+        <Self as Scorer>::sanitise(blended)
+    }
+
+    fn is_unlinked(&self, poi_id: u64) -> bool {
+        self.link_filter
+            .as_ref()
+            .is_some_and(|filter| !filter.may_contain_poi(poi_id))
+    }
+}
+
+impl Scorer for UserRelevanceScorer {
+    fn score(&self, poi: &PointOfInterest, profile: &InterestProfile) -> f32 {
+        self.blend(poi.id, self.user_relevance(poi, profile))
+    }
+
+    /// Score `pois` with one claims query per 999 POIs rather than one per
+    /// POI, or with no queries once claims are prefetched.
+    fn score_many(&self, pois: &[PointOfInterest], profile: &InterestProfile) -> Vec<f32> {
+        if self.prefetched.is_some() {
+            return pois.iter().map(|poi| self.score(poi, profile)).collect();
+        }
+        let claims = self.batch_claims(pois);
+        pois.iter()
+            .map(|poi| {
+                let user_relevance = claims
+                    .get(&poi.id)
+                    .map_or(0.0_f32, |found| self.claims_relevance(found, profile));
+                self.blend(poi.id, user_relevance)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests;
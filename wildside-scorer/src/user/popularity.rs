@@ -0,0 +1,137 @@
+//! Popularity scores and the artefacts they are loaded from.
+
+use bincode::Options;
+use camino::{Utf8Path, Utf8PathBuf};
+use log::warn;
+use wildside_core::Scorer;
+
+use super::{UserRelevanceError, UserRelevanceScorer};
+use crate::{
+    PopularityArtefact, PopularityHeader, PopularityProvenance, PopularityScores, bincode_options,
+    read_popularity_header,
+};
+
+/// Where the scorer obtains global popularity scores.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PopularitySource {
+    /// Score on user relevance alone.
+    None,
+    /// Load `popularity.bin` from the path, degrading to interest-only
+    /// scoring with a logged warning when the file does not exist.
+    Path(Utf8PathBuf),
+    /// Use scores already held in memory.
+    Store(PopularityScores),
+}
+
+impl PopularitySource {
+    pub(super) fn resolve(self) -> Result<Option<LoadedPopularity>, UserRelevanceError> {
+        match self {
+            Self::None => Ok(None),
+            Self::Store(scores) => Ok(Some(LoadedPopularity {
+                scores,
+                artefact: PopularityArtefact::InMemory,
+                provenance: None,
+            })),
+            Self::Path(path) => match LoadedPopularity::from_file(path.clone()) {
+                Err(UserRelevanceError::ReadPopularity { source, .. })
+                    if source.kind() == std::io::ErrorKind::NotFound =>
+                {
+                    warn!(
+                        "popularity file {path} not found; falling back to interest-only scoring"
+                    );
+                    Ok(None)
+                }
+                other => other.map(Some),
+            },
+        }
+    }
+}
+
+/// Popularity scores together with the artefact they came from.
+#[derive(Debug, Clone)]
+pub(super) struct LoadedPopularity {
+    scores: PopularityScores,
+    pub(super) artefact: PopularityArtefact,
+    provenance: Option<PopularityProvenance>,
+}
+
+impl LoadedPopularity {
+    pub(super) fn from_file(path: Utf8PathBuf) -> Result<Self, UserRelevanceError> {
+        let (header, scores) = load_popularity_with_header(&path)?;
+        Ok(Self {
+            scores,
+            artefact: PopularityArtefact::File(path),
+            provenance: header.provenance,
+        })
+    }
+
+    /// Reject scores computed from a different `pois.db` than `database_path`.
+    ///
+    /// Files older than format version 4 record no source digest and are
+    /// accepted unchecked.
+    pub(super) fn check_source(&self, database_path: &Utf8Path) -> Result<(), UserRelevanceError> {
+        let (Some(provenance), PopularityArtefact::File(path)) = (self.provenance, &self.artefact)
+        else {
+            return Ok(());
+        };
+        let matches = provenance.matches_source(database_path).map_err(|source| {
+            UserRelevanceError::HashDatabase {
+                path: database_path.to_path_buf(),
+                source,
+            }
+        })?;
+        if matches {
+            Ok(())
+        } else {
+            Err(UserRelevanceError::StalePopularity {
+                path: path.clone(),
+                database: database_path.to_path_buf(),
+                generated_at: provenance.generated_at,
+            })
+        }
+    }
+
+    pub(super) fn score(&self, poi_id: u64) -> f32 {
+        <UserRelevanceScorer as Scorer>::sanitise(self.scores.get(poi_id).unwrap_or(0.0_f32))
+    }
+}
+
+/// Read and decode a `popularity.bin` artefact.
+///
+/// # Errors
+/// Returns [`UserRelevanceError::ReadPopularity`],
+/// [`UserRelevanceError::PopularityVersion`], or
+/// [`UserRelevanceError::DecodePopularity`] when the file cannot be read, is
+/// in an unreadable version or normalization, or does not decode.
+pub fn load_popularity(path: &Utf8Path) -> Result<PopularityScores, UserRelevanceError> {
+    load_popularity_with_header(path).map(|(_, scores)| scores)
+}
+
+/// Read and decode a `popularity.bin` artefact together with its header.
+///
+/// Callers can compare [`PopularityHeader::provenance`] against the database
+/// they pair the scores with to detect a stale artefact.
+///
+/// # Errors
+/// Returns the same errors as [`load_popularity`].
+pub fn load_popularity_with_header(
+    path: &Utf8Path,
+) -> Result<(PopularityHeader, PopularityScores), UserRelevanceError> {
+    let bytes =
+        std::fs::read(path.as_std_path()).map_err(|source| UserRelevanceError::ReadPopularity {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    let (header, payload) =
+        read_popularity_header(&bytes).map_err(|source| UserRelevanceError::PopularityVersion {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    let scores = bincode_options().deserialize(payload).map_err(|source| {
+        UserRelevanceError::DecodePopularity {
+            path: path.to_path_buf(),
+            source,
+        }
+    })?;
+    Ok((header, scores))
+}
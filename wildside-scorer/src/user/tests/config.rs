@@ -0,0 +1,26 @@
+//! Tests for claim selectors, theme mappings, and score weights.
+
+use super::*;
+
+#[rstest]
+fn defaults_include_history_mapping() {
+    let mapping = ThemeClaimMapping::default();
+    assert!(mapping.selectors(&Theme::History).is_some());
+}
+
+#[rstest]
+fn selector_rejects_empty_fields() {
+    let err = ClaimSelector::new("", TEST_VALUE).expect_err("empty property should error");
+    assert!(matches!(err, UserRelevanceError::InvalidSelector));
+}
+
+#[rstest]
+fn weights_reject_zero_total() {
+    let err = ScoreWeights {
+        popularity: 0.0,
+        user_relevance: 0.0,
+    }
+    .validate()
+    .expect_err("zero weights should be invalid");
+    assert!(matches!(err, UserRelevanceError::InvalidWeights));
+}
@@ -0,0 +1,112 @@
+//! Tests for score explanations.
+
+use super::*;
+
+#[rstest]
+fn explain_cites_popularity_and_interest_sources(
+    seeded_db_path: (TempDir, Utf8PathBuf),
+    popularity_fixture: (TempDir, PopularityFixture),
+) {
+    let (_pop_temp_dir, pop_fixture) = popularity_fixture;
+    let popularity_path = pop_fixture.with_score(1, 0.25_f32);
+    let (_db_temp_dir, db_path) = seeded_db_path;
+    let selector = ClaimSelector::new(TEST_PROPERTY, TEST_VALUE).expect("valid selector");
+    let scorer = UserRelevanceScorer::from_paths(
+        &db_path,
+        &popularity_path,
+        ThemeClaimMapping::new().with_selector(Theme::Art, selector.clone()),
+        ScoreWeights::default(),
+    )
+    .expect("construct scorer");
+    let poi = PointOfInterest::new(
+        1,
+        Coord { x: 0.0, y: 0.0 },
+        Tags::from([
+            ("sitelinks".into(), "12".into()),
+            ("tourism".into(), "attraction".into()),
+            ("name".into(), "Gallery".into()),
+        ]),
+    );
+    let profile = InterestProfile::new()
+        .with_weight(Theme::Art, 0.8_f32)
+        .with_weight(Theme::Food, 0.5_f32);
+
+    let explanation = scorer.explain(&poi, &profile);
+
+    assert_eq!(
+        explanation.score.to_bits(),
+        scorer.score(&poi, &profile).to_bits()
+    );
+    let popularity = explanation.popularity.expect("popularity component");
+    assert_eq!(
+        popularity.artefact,
+        PopularityArtefact::File(popularity_path)
+    );
+    assert_eq!(popularity.sitelinks, Some(12));
+    assert_eq!(popularity.heritage_entity.as_deref(), Some("Q_ART"));
+    assert_eq!(
+        popularity.boosting_tags,
+        vec![("tourism".to_owned(), "attraction".to_owned())]
+    );
+    assert_eq!(
+        explanation.interests,
+        vec![InterestComponent {
+            theme: Theme::Art,
+            weight: 0.8_f32,
+            selector,
+            entity_id: "Q_ART".to_owned(),
+        }]
+    );
+}
+
+#[rstest]
+fn explain_prefers_stored_sitelink_counts(seeded_db_path: (TempDir, Utf8PathBuf)) {
+    let (_db_temp_dir, db_path) = seeded_db_path;
+    let connection = Connection::open(db_path.as_std_path()).expect("open sqlite database");
+    connection
+        .execute_batch(concat!(
+            "CREATE TABLE wikidata_entity_sitelinks (",
+            "entity_id TEXT PRIMARY KEY, sitelink_count INTEGER NOT NULL);",
+            "INSERT INTO wikidata_entity_sitelinks VALUES ('Q_ART', 87);"
+        ))
+        .expect("seed sitelinks");
+    let scores = PopularityScores::new(BTreeMap::from([(1, 0.9_f32)]));
+    let scorer = UserRelevanceScorer::from_source(
+        &db_path,
+        PopularitySource::Store(scores),
+        ThemeClaimMapping::default(),
+        ScoreWeights::default(),
+    )
+    .expect("construct scorer");
+    let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+
+    let explanation = scorer.explain(&poi, &InterestProfile::new());
+
+    let popularity = explanation.popularity.expect("popularity component");
+    assert_eq!(popularity.artefact, PopularityArtefact::InMemory);
+    assert_eq!(popularity.sitelinks, Some(87));
+    assert!(explanation.interests.is_empty());
+}
+
+#[rstest]
+fn explain_omits_popularity_when_unavailable(seeded_db_path: (TempDir, Utf8PathBuf)) {
+    let (_db_temp_dir, db_path) = seeded_db_path;
+    let scorer = UserRelevanceScorer::from_source(
+        &db_path,
+        PopularitySource::None,
+        ThemeClaimMapping::default(),
+        ScoreWeights::default(),
+    )
+    .expect("construct scorer");
+    let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+    let profile = InterestProfile::new().with_weight(Theme::History, 1.0_f32);
+
+    let explanation = scorer.explain(&poi, &profile);
+
+    assert!(explanation.popularity.is_none());
+    let [interest] = explanation.interests.as_slice() else {
+        panic!("expected one interest component");
+    };
+    assert_eq!(interest.selector.property_id(), "P1435");
+    assert_eq!(interest.selector.value_entity_id(), "Q9259");
+}
@@ -0,0 +1,78 @@
+//! Tests for skipping claim lookups with a link filter.
+
+use super::*;
+
+fn score_with_link_filter(
+    (_db_temp_dir, db_path): (TempDir, Utf8PathBuf),
+    (_pop_temp_dir, pop_fixture): (TempDir, PopularityFixture),
+    filter: LinkFilter,
+) -> f32 {
+    let popularity_path = pop_fixture.with_score(2, 0.0_f32);
+    let scorer = UserRelevanceScorer::from_paths(
+        &db_path,
+        &popularity_path,
+        ThemeClaimMapping::default(),
+        ScoreWeights {
+            popularity: 0.3_f32,
+            user_relevance: 0.7_f32,
+        },
+    )
+    .expect("construct scorer")
+    .with_link_filter(filter);
+    let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+    let profile = InterestProfile::new().with_weight(Theme::History, 1.0_f32);
+    scorer.score(&poi, &profile)
+}
+
+#[rstest]
+#[expect(
+    clippy::float_arithmetic,
+    reason = "tests compare floating point values"
+)]
+fn link_filter_keeps_linked_poi_relevance(
+    seeded_db_path: (TempDir, Utf8PathBuf),
+    popularity_fixture: (TempDir, PopularityFixture),
+) {
+    let mut filter = LinkFilter::with_capacity(1);
+    filter.insert_poi(1);
+
+    let score = score_with_link_filter(seeded_db_path, popularity_fixture, filter);
+
+    assert!(
+        (score - 0.7_f32).abs() < 0.000_1_f32,
+        "linked POIs should still be looked up"
+    );
+}
+
+#[rstest]
+fn link_filter_skips_unlinked_poi_lookup(
+    seeded_db_path: (TempDir, Utf8PathBuf),
+    popularity_fixture: (TempDir, PopularityFixture),
+) {
+    let filter = LinkFilter::with_capacity(1);
+
+    let score = score_with_link_filter(seeded_db_path, popularity_fixture, filter);
+
+    assert!(
+        score.abs() < 0.000_1_f32,
+        "filtered POIs should contribute no user relevance"
+    );
+}
+
+#[rstest]
+fn missing_link_filter_file_is_reported(
+    seeded_db_path: (TempDir, Utf8PathBuf),
+    popularity_fixture: (TempDir, PopularityFixture),
+) {
+    let (_pop_temp_dir, pop_fixture) = popularity_fixture;
+    let popularity_path = pop_fixture.with_score(1, 0.5_f32);
+    let (_db_temp_dir, db_path) = seeded_db_path;
+    let missing = pop_fixture.dir.join("links.filter");
+
+    let err = UserRelevanceScorer::with_defaults(&db_path, &popularity_path)
+        .expect("construct scorer")
+        .with_link_filter_path(&missing)
+        .expect_err("missing filter should error");
+
+    assert!(matches!(err, UserRelevanceError::LoadLinkFilter { .. }));
+}
@@ -0,0 +1,140 @@
+//! Unit coverage for user relevance scoring.
+
+use std::collections::BTreeMap;
+
+use bincode::Options;
+use camino::Utf8PathBuf;
+use geo::Coord;
+use rstest::{fixture, rstest};
+use rusqlite::Connection;
+use tempfile::TempDir;
+use wildside_core::{InterestProfile, PointOfInterest, Scorer, Tags, Theme, store::LinkFilter};
+
+use super::{
+    ClaimSelector, PopularitySource, ScoreWeights, ThemeClaimMapping, UserRelevanceError,
+    UserRelevanceScorer,
+};
+use crate::{
+    InterestComponent, PopularityArtefact, PopularityNormalization, PopularityProvenance,
+    PopularityScope, PopularityScores, popularity_bincode_options, write_popularity_header,
+};
+
+const TEST_PROPERTY: &str = "P999";
+const TEST_VALUE: &str = "Q_TEST_ART";
+
+mod config;
+mod explain;
+mod link_filter;
+mod popularity;
+mod scoring;
+
+#[fixture]
+fn seeded_db_path() -> (TempDir, Utf8PathBuf) {
+    let temp_dir = TempDir::new().expect("tempdir");
+    let path = Utf8PathBuf::from_path_buf(temp_dir.path().join("pois.db")).expect("utf8 db path");
+    seed_claims_database(&path);
+    (temp_dir, path)
+}
+
+#[derive(Clone)]
+struct PopularityFixture {
+    dir: Utf8PathBuf,
+}
+
+impl PopularityFixture {
+    fn with_score(&self, poi_id: u64, score: f32) -> Utf8PathBuf {
+        let popularity = PopularityScores::new(BTreeMap::from([(poi_id, score)]));
+        let path = self.dir.join("popularity.bin");
+        let bytes = popularity_bincode_options()
+            .serialize(&popularity)
+            .expect("serialize popularity");
+        std::fs::write(path.as_std_path(), bytes).expect("write popularity fixture");
+        path
+    }
+
+    fn computed_from(&self, source: &Utf8PathBuf) -> Utf8PathBuf {
+        let popularity = PopularityScores::new(BTreeMap::from([(1, 0.5_f32)]));
+        let provenance = PopularityProvenance::for_source(source).expect("hash source");
+        let mut bytes = Vec::new();
+        write_popularity_header(
+            &mut bytes,
+            PopularityNormalization::Max,
+            PopularityScope::Global,
+            provenance,
+        )
+        .expect("write popularity header");
+        popularity_bincode_options()
+            .serialize_into(&mut bytes, &popularity)
+            .expect("serialize popularity");
+        let path = self.dir.join("popularity.bin");
+        std::fs::write(path.as_std_path(), bytes).expect("write popularity fixture");
+        path
+    }
+}
+
+#[fixture]
+fn popularity_fixture() -> (TempDir, PopularityFixture) {
+    let temp_dir = TempDir::new().expect("tempdir");
+    let dir = Utf8PathBuf::from_path_buf(temp_dir.path().to_path_buf()).expect("utf8 dir path");
+    (temp_dir, PopularityFixture { dir })
+}
+
+fn seed_claims_database(path: &Utf8PathBuf) {
+    let connection = Connection::open(path.as_std_path()).expect("open sqlite database");
+    connection
+        .execute(
+            concat!(
+                "CREATE TABLE poi_wikidata_links (",
+                "poi_id INTEGER NOT NULL, ",
+                "entity_id TEXT NOT NULL",
+                ")"
+            ),
+            [],
+        )
+        .expect("create links table");
+    connection
+        .execute(
+            concat!(
+                "CREATE TABLE wikidata_entity_claims (",
+                "entity_id TEXT NOT NULL, ",
+                "property_id TEXT NOT NULL, ",
+                "value_entity_id TEXT NOT NULL",
+                ")"
+            ),
+            [],
+        )
+        .expect("create claims table");
+    connection
+        .execute(
+            concat!(
+                "CREATE VIEW poi_wikidata_claims AS ",
+                "SELECT links.poi_id AS poi_id, ",
+                "claims.entity_id AS entity_id, ",
+                "claims.property_id AS property_id, ",
+                "claims.value_entity_id AS value_entity_id ",
+                "FROM poi_wikidata_links AS links ",
+                "JOIN wikidata_entity_claims AS claims ",
+                "ON claims.entity_id = links.entity_id"
+            ),
+            [],
+        )
+        .expect("create claims view");
+    connection
+        .execute(
+            "INSERT INTO poi_wikidata_links (poi_id, entity_id) VALUES (1, 'Q_ART')",
+            [],
+        )
+        .expect("insert link");
+    connection
+        .execute(
+            "INSERT INTO wikidata_entity_claims (entity_id, property_id, value_entity_id) VALUES ('Q_ART', ?1, ?2)",
+            (TEST_PROPERTY, TEST_VALUE),
+        )
+        .expect("insert claim");
+    connection
+        .execute(
+            "INSERT INTO wikidata_entity_claims (entity_id, property_id, value_entity_id) VALUES ('Q_ART', 'P1435', 'Q9259')",
+            [],
+        )
+        .expect("insert heritage claim");
+}
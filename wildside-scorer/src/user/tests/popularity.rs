@@ -0,0 +1,130 @@
+//! Tests for where popularity scores come from.
+
+use super::*;
+
+#[rstest]
+fn accepts_popularity_computed_from_the_database(
+    seeded_db_path: (TempDir, Utf8PathBuf),
+    popularity_fixture: (TempDir, PopularityFixture),
+) {
+    let (_pop_temp_dir, pop_fixture) = popularity_fixture;
+    let (_db_temp_dir, db_path) = seeded_db_path;
+    let popularity_path = pop_fixture.computed_from(&db_path);
+
+    UserRelevanceScorer::with_defaults(&db_path, &popularity_path)
+        .expect("matching popularity should load");
+}
+
+#[rstest]
+fn rejects_popularity_computed_from_another_database(
+    seeded_db_path: (TempDir, Utf8PathBuf),
+    popularity_fixture: (TempDir, PopularityFixture),
+) {
+    let (_pop_temp_dir, pop_fixture) = popularity_fixture;
+    let (_db_temp_dir, db_path) = seeded_db_path;
+    let other = pop_fixture.dir.join("other.db");
+    std::fs::write(other.as_std_path(), b"another database").expect("write other db");
+    let popularity_path = pop_fixture.computed_from(&other);
+
+    let err = UserRelevanceScorer::from_source(
+        &db_path,
+        PopularitySource::Path(popularity_path.clone()),
+        ThemeClaimMapping::default(),
+        ScoreWeights::default(),
+    )
+    .expect_err("stale popularity should be rejected");
+
+    assert!(
+        matches!(
+            &err,
+            UserRelevanceError::StalePopularity { path, database, .. }
+                if *path == popularity_path && *database == db_path
+        ),
+        "unexpected error: {err}"
+    );
+}
+
+fn score_with_source(db_path: &Utf8PathBuf, source: PopularitySource) -> f32 {
+    let scorer = UserRelevanceScorer::from_source(
+        db_path,
+        source,
+        ThemeClaimMapping::default(),
+        ScoreWeights::default(),
+    )
+    .expect("construct scorer");
+    let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+    let profile = InterestProfile::new().with_weight(Theme::History, 0.6_f32);
+    scorer.score(&poi, &profile)
+}
+
+#[rstest]
+#[expect(
+    clippy::float_arithmetic,
+    reason = "tests compare floating point values"
+)]
+fn missing_popularity_file_degrades_to_interest_only(
+    seeded_db_path: (TempDir, Utf8PathBuf),
+    popularity_fixture: (TempDir, PopularityFixture),
+) {
+    let (_pop_temp_dir, pop_fixture) = popularity_fixture;
+    let (_db_temp_dir, db_path) = seeded_db_path;
+    let missing = pop_fixture.dir.join("absent.bin");
+
+    let score = score_with_source(&db_path, PopularitySource::Path(missing));
+
+    assert!(
+        (score - 0.6_f32).abs() < 0.000_1_f32,
+        "interest-only scoring should ignore the popularity weight"
+    );
+}
+
+#[rstest]
+#[expect(
+    clippy::float_arithmetic,
+    reason = "tests compare floating point values"
+)]
+fn none_source_matches_missing_file(seeded_db_path: (TempDir, Utf8PathBuf)) {
+    let (_db_temp_dir, db_path) = seeded_db_path;
+
+    let score = score_with_source(&db_path, PopularitySource::None);
+
+    assert!((score - 0.6_f32).abs() < 0.000_1_f32);
+}
+
+#[rstest]
+#[expect(
+    clippy::float_arithmetic,
+    reason = "tests compare floating point values"
+)]
+fn store_source_uses_in_memory_scores(seeded_db_path: (TempDir, Utf8PathBuf)) {
+    let (_db_temp_dir, db_path) = seeded_db_path;
+    let scores = PopularityScores::new(BTreeMap::from([(1, 0.2_f32)]));
+
+    let score = score_with_source(&db_path, PopularitySource::Store(scores));
+
+    assert!(
+        (score - f32::midpoint(0.2_f32, 0.6_f32)).abs() < 0.000_1_f32,
+        "in-memory popularity should blend as usual"
+    );
+}
+
+#[rstest]
+fn path_source_still_rejects_corrupt_files(
+    seeded_db_path: (TempDir, Utf8PathBuf),
+    popularity_fixture: (TempDir, PopularityFixture),
+) {
+    let (_pop_temp_dir, pop_fixture) = popularity_fixture;
+    let (_db_temp_dir, db_path) = seeded_db_path;
+    let corrupt = pop_fixture.dir.join("popularity.bin");
+    std::fs::write(corrupt.as_std_path(), b"\xff").expect("write corrupt popularity");
+
+    let err = UserRelevanceScorer::from_source(
+        &db_path,
+        PopularitySource::Path(corrupt),
+        ThemeClaimMapping::default(),
+        ScoreWeights::default(),
+    )
+    .expect_err("corrupt popularity should error");
+
+    assert!(matches!(err, UserRelevanceError::DecodePopularity { .. }));
+}
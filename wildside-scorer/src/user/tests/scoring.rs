@@ -0,0 +1,199 @@
+//! Tests for blending popularity with interest matches.
+
+use super::*;
+
+#[rstest]
+#[expect(
+    clippy::float_arithmetic,
+    reason = "tests compare floating point values"
+)]
+fn scoring_blends_popularity_and_interest(
+    seeded_db_path: (TempDir, Utf8PathBuf),
+    popularity_fixture: (TempDir, PopularityFixture),
+) {
+    let (_pop_temp_dir, pop_fixture) = popularity_fixture;
+    let popularity_path = pop_fixture.with_score(1, 0.25_f32);
+    let (_db_temp_dir, db_path) = seeded_db_path;
+
+    let mut mapping = ThemeClaimMapping::new();
+    mapping.insert(
+        Theme::Art,
+        ClaimSelector::new(TEST_PROPERTY, TEST_VALUE).expect("valid selector"),
+    );
+    let scorer = UserRelevanceScorer::from_paths(
+        &db_path,
+        &popularity_path,
+        mapping,
+        ScoreWeights::default(),
+    )
+    .expect("construct scorer");
+
+    let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+    let profile = InterestProfile::new().with_weight(Theme::Art, 0.8_f32);
+
+    let score = scorer.score(&poi, &profile);
+
+    let expected = f32::midpoint(0.25_f32, 0.8_f32);
+    assert!(
+        (score - expected).abs() < 0.000_1_f32,
+        "score should blend components"
+    );
+}
+
+#[rstest]
+#[expect(
+    clippy::float_arithmetic,
+    reason = "tests compare floating point values"
+)]
+fn non_matching_interest_yields_popularity_only(
+    seeded_db_path: (TempDir, Utf8PathBuf),
+    popularity_fixture: (TempDir, PopularityFixture),
+) {
+    let (_pop_temp_dir, pop_fixture) = popularity_fixture;
+    let popularity_path = pop_fixture.with_score(1, 0.6_f32);
+    let (_db_temp_dir, db_path) = seeded_db_path;
+
+    let scorer = UserRelevanceScorer::with_defaults(&db_path, &popularity_path)
+        .expect("construct scorer with defaults");
+    let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+    let profile = InterestProfile::new().with_weight(Theme::Art, 1.0_f32);
+
+    let score = scorer.score(&poi, &profile);
+
+    assert!(
+        (score - 0.6_f32).abs() < 0.000_1_f32,
+        "non matching interest falls back to popularity"
+    );
+}
+
+#[rstest]
+#[case(-1.0_f32, 0.0_f32)]
+#[case(-0.2_f32, 0.2_f32)]
+fn disliked_theme_lowers_popularity(
+    seeded_db_path: (TempDir, Utf8PathBuf),
+    popularity_fixture: (TempDir, PopularityFixture),
+    #[case] weight: f32,
+    #[case] expected: f32,
+) {
+    let (_pop_temp_dir, pop_fixture) = popularity_fixture;
+    let popularity_path = pop_fixture.with_score(1, 0.6_f32);
+    let (_db_temp_dir, db_path) = seeded_db_path;
+
+    let scorer = UserRelevanceScorer::with_defaults(&db_path, &popularity_path)
+        .expect("construct scorer with defaults");
+    let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+    let profile = InterestProfile::new().with_weight(Theme::History, weight);
+
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "tests compare floating point values"
+    )]
+    let error = (scorer.score(&poi, &profile) - expected).abs();
+    assert!(error < 0.000_1_f32, "dislike should subtract relevance");
+    let explanation = scorer.explain(&poi, &profile);
+    assert_eq!(
+        explanation
+            .interests
+            .first()
+            .map(|interest| interest.weight),
+        Some(weight)
+    );
+}
+
+#[rstest]
+#[expect(
+    clippy::float_arithmetic,
+    reason = "tests compare floating point values"
+)]
+fn missing_popularity_falls_back_to_interest(
+    seeded_db_path: (TempDir, Utf8PathBuf),
+    popularity_fixture: (TempDir, PopularityFixture),
+) {
+    let (_pop_temp_dir, pop_fixture) = popularity_fixture;
+    let popularity_path = pop_fixture.with_score(2, 0.0_f32);
+    let (_db_temp_dir, db_path) = seeded_db_path;
+
+    let mapping = ThemeClaimMapping::default();
+    let scorer = UserRelevanceScorer::from_paths(
+        &db_path,
+        &popularity_path,
+        mapping.clone(),
+        ScoreWeights {
+            popularity: 0.3_f32,
+            user_relevance: 0.7_f32,
+        },
+    )
+    .expect("construct scorer");
+    let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+    let profile = InterestProfile::new().with_weight(Theme::History, 1.0_f32);
+
+    let score = scorer.score(&poi, &profile);
+
+    assert!(
+        (score - 0.7_f32).abs() < 0.000_1_f32,
+        "interest match should contribute even without popularity"
+    );
+}
+
+#[rstest]
+fn score_many_matches_scoring_each_poi(
+    seeded_db_path: (TempDir, Utf8PathBuf),
+    popularity_fixture: (TempDir, PopularityFixture),
+) {
+    let (_pop_temp_dir, pop_fixture) = popularity_fixture;
+    let popularity_path = pop_fixture.with_score(2, 0.4_f32);
+    let (_db_temp_dir, db_path) = seeded_db_path;
+    let mapping = ThemeClaimMapping::new().with_selector(
+        Theme::Art,
+        ClaimSelector::new(TEST_PROPERTY, TEST_VALUE).expect("valid selector"),
+    );
+    let scorer = UserRelevanceScorer::from_paths(
+        &db_path,
+        &popularity_path,
+        mapping,
+        ScoreWeights::default(),
+    )
+    .expect("construct scorer");
+    let pois: Vec<PointOfInterest> = (1..=3)
+        .map(|id| PointOfInterest::with_empty_tags(id, Coord { x: 0.0, y: 0.0 }))
+        .collect();
+    let profile = InterestProfile::new().with_weight(Theme::Art, 0.9_f32);
+
+    let batched = scorer.score_many(&pois, &profile);
+    let individual: Vec<f32> = pois.iter().map(|poi| scorer.score(poi, &profile)).collect();
+
+    assert_eq!(batched, individual);
+    assert!(batched.iter().take(2).all(|score| *score > 0.0_f32));
+    assert_eq!(batched.last(), Some(&0.0_f32));
+}
+
+#[rstest]
+fn prefetched_claims_score_like_database_lookups(seeded_db_path: (TempDir, Utf8PathBuf)) {
+    let (_db_temp_dir, db_path) = seeded_db_path;
+    let mapping = ThemeClaimMapping::default().with_selector(
+        Theme::Art,
+        ClaimSelector::new(TEST_PROPERTY, TEST_VALUE).expect("valid selector"),
+    );
+    let scorer = UserRelevanceScorer::from_source(
+        &db_path,
+        PopularitySource::None,
+        mapping,
+        ScoreWeights::default(),
+    )
+    .expect("construct scorer");
+    let prefetched = scorer
+        .clone()
+        .with_prefetched_claims()
+        .expect("prefetch claims");
+    let pois: Vec<PointOfInterest> = (1..=3)
+        .map(|id| PointOfInterest::with_empty_tags(id, Coord { x: 0.0, y: 0.0 }))
+        .collect();
+    let profile = InterestProfile::new()
+        .with_weight(Theme::History, 0.6_f32)
+        .with_weight(Theme::Art, 0.3_f32);
+
+    let scores = prefetched.score_many(&pois, &profile);
+
+    assert_eq!(scores, scorer.score_many(&pois, &profile));
+    assert!(scores.first().is_some_and(|score| *score > 0.0_f32));
+}
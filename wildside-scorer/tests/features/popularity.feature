@@ -19,3 +19,8 @@ Feature: Compute global popularity
     Given a SQLite POI database with sitelink counts
     When I write the popularity file to a nested path
     Then the popularity file round-trips the scores
+
+  Scenario: Unlinked POIs with notable tags earn popularity
+    Given a SQLite POI database with sitelink counts
+    When I compute popularity scores
+    Then the tagged unlinked POI has a positive normalised score
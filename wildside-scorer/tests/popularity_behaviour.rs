@@ -1,5 +1,7 @@
 //! Behavioural coverage for computing global popularity scores.
 
+mod popularity_support;
+
 use std::cell::RefCell;
 
 use bincode::Options;
@@ -11,9 +13,11 @@ use rusqlite::Connection;
 use tempfile::TempDir;
 use wildside_scorer::{
     PopularityError, PopularityScores, PopularityWeights, compute_popularity_scores,
-    write_popularity_file,
+    popularity_payload, write_popularity_file,
 };
 
+use popularity_support::{create_schema, insert_heritage_claim, insert_poi, link_entity};
+
 /// Temporary directory for each scenario.
 #[fixture]
 pub fn temp_dir() -> TempDir {
@@ -59,6 +63,7 @@ fn sqlite_with_sitelinks(temp_dir: &TempDir, db_path: &RefCell<Option<Utf8PathBu
     insert_poi(&connection, 1, "Q1", "{\"wikidata\":\"Q1\"}");
     insert_poi(&connection, 2, "Q2", "{\"wikidata\":\"Q2\"}");
     insert_poi(&connection, 3, "Q3", "{}");
+    insert_poi(&connection, 4, "Q4", "{\"tourism\":\"attraction\"}");
     link_entity(&connection, 1, "Q1");
     link_entity(&connection, 2, "Q2");
     insert_heritage_claim(&connection, "Q1");
@@ -215,6 +220,28 @@ fn unlinked_poi_scores_zero(
     }
 }
 
+#[then("the tagged unlinked POI has a positive normalised score")]
+fn tagged_unlinked_poi_scores_positive(
+    compute_result: &RefCell<Option<Result<PopularityScores, PopularityError>>>,
+) {
+    let binding = compute_result.borrow();
+    let result = binding
+        .as_ref()
+        .unwrap_or_else(|| panic!("computation result must be recorded"));
+    match result {
+        Ok(scores) => {
+            let Some(q4) = scores.get(4) else {
+                panic!("score for tagged poi")
+            };
+            assert!(
+                q4 > 0.0_f32 && q4 < 1.0_f32,
+                "tagged POI should score above zero and below the heritage POI (got {q4})"
+            );
+        }
+        Err(err) => panic!("popularity computation should succeed, got {err}"),
+    }
+}
+
 #[then("the popularity file round-trips the scores")]
 fn popularity_file_round_trips(
     db_path: &RefCell<Option<Utf8PathBuf>>,
@@ -235,8 +262,10 @@ fn popularity_file_round_trips(
         .unwrap_or_else(|err| panic!("compute expected scores: {err}"));
     let bytes = std::fs::read(output.as_std_path())
         .unwrap_or_else(|err| panic!("read popularity file: {err}"));
+    let payload =
+        popularity_payload(&bytes).unwrap_or_else(|err| panic!("read popularity header: {err}"));
     let decoded: PopularityScores = bincode::DefaultOptions::new()
-        .deserialize(&bytes)
+        .deserialize(payload)
         .unwrap_or_else(|err| panic!("decode popularity scores: {err}"));
     assert_eq!(
         decoded, expected,
@@ -244,71 +273,6 @@ fn popularity_file_round_trips(
     );
 }
 
-#[expect(
-    clippy::expect_used,
-    reason = "schema setup should panic when the database is unavailable"
-)]
-fn create_schema(connection: &Connection) {
-    connection
-        .execute(
-            "CREATE TABLE pois (
-                id INTEGER PRIMARY KEY,
-                lon REAL NOT NULL,
-                lat REAL NOT NULL,
-                tags TEXT NOT NULL
-            )",
-            [],
-        )
-        .expect("create pois table");
-    connection
-        .execute(
-            "CREATE TABLE poi_wikidata_links (
-                poi_id INTEGER NOT NULL,
-                entity_id TEXT NOT NULL,
-                PRIMARY KEY (poi_id, entity_id)
-            )",
-            [],
-        )
-        .expect("create links table");
-    connection
-        .execute(
-            "CREATE TABLE wikidata_entity_claims (
-                entity_id TEXT NOT NULL,
-                property_id TEXT NOT NULL,
-                value_entity_id TEXT NOT NULL
-            )",
-            [],
-        )
-        .expect("create claims table");
-}
-
-fn insert_poi(connection: &Connection, id: i64, entity: &str, tags: &str) {
-    connection
-        .execute(
-            "INSERT INTO pois (id, lon, lat, tags) VALUES (?1, 0.0, 0.0, ?2)",
-            (&id, tags),
-        )
-        .unwrap_or_else(|err| panic!("insert poi {id} for entity {entity}: {err}"));
-}
-
-fn link_entity(connection: &Connection, poi_id: i64, entity: &str) {
-    connection
-        .execute(
-            "INSERT INTO poi_wikidata_links (poi_id, entity_id) VALUES (?1, ?2)",
-            (poi_id, entity),
-        )
-        .unwrap_or_else(|err| panic!("link poi {poi_id} to {entity}: {err}"));
-}
-
-fn insert_heritage_claim(connection: &Connection, entity: &str) {
-    connection
-        .execute(
-            "INSERT INTO wikidata_entity_claims (entity_id, property_id, value_entity_id) VALUES (?1, 'P1435', 'Q9259')",
-            [entity],
-        )
-        .unwrap_or_else(|err| panic!("insert heritage claim for {entity}: {err}"));
-}
-
 #[scenario(path = "tests/features/popularity.feature", index = 0)]
 #[expect(
     clippy::too_many_arguments,
@@ -368,3 +332,18 @@ fn popularity_file_roundtrip_scenario(
 ) {
     let _ = (temp_dir, db_path, weights, compute_result, popularity_path);
 }
+
+#[scenario(path = "tests/features/popularity.feature", index = 4)]
+#[expect(
+    clippy::too_many_arguments,
+    reason = "fixtures are dictated by rstest-bdd API"
+)]
+fn tagged_unlinked_poi_scores_positive_scenario(
+    temp_dir: TempDir,
+    db_path: RefCell<Option<Utf8PathBuf>>,
+    weights: PopularityWeights,
+    compute_result: RefCell<Option<Result<PopularityScores, PopularityError>>>,
+    popularity_path: RefCell<Option<Utf8PathBuf>>,
+) {
+    let _ = (temp_dir, db_path, weights, compute_result, popularity_path);
+}
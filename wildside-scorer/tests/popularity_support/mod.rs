@@ -0,0 +1,72 @@
+//! Database fixtures for the popularity behaviour tests.
+
+use rusqlite::Connection;
+
+/// Create the tables popularity scoring reads from.
+#[expect(
+    clippy::expect_used,
+    reason = "schema setup should panic when the database is unavailable"
+)]
+pub fn create_schema(connection: &Connection) {
+    connection
+        .execute(
+            "CREATE TABLE pois (
+                id INTEGER PRIMARY KEY,
+                lon REAL NOT NULL,
+                lat REAL NOT NULL,
+                tags TEXT NOT NULL
+            )",
+            [],
+        )
+        .expect("create pois table");
+    connection
+        .execute(
+            "CREATE TABLE poi_wikidata_links (
+                poi_id INTEGER NOT NULL,
+                entity_id TEXT NOT NULL,
+                PRIMARY KEY (poi_id, entity_id)
+            )",
+            [],
+        )
+        .expect("create links table");
+    connection
+        .execute(
+            "CREATE TABLE wikidata_entity_claims (
+                entity_id TEXT NOT NULL,
+                property_id TEXT NOT NULL,
+                value_entity_id TEXT NOT NULL
+            )",
+            [],
+        )
+        .expect("create claims table");
+}
+
+/// Insert a POI at the origin with the given JSON tags.
+pub fn insert_poi(connection: &Connection, id: i64, entity: &str, tags: &str) {
+    connection
+        .execute(
+            "INSERT INTO pois (id, lon, lat, tags) VALUES (?1, 0.0, 0.0, ?2)",
+            (&id, tags),
+        )
+        .unwrap_or_else(|err| panic!("insert poi {id} for entity {entity}: {err}"));
+}
+
+/// Link a POI to a Wikidata entity.
+pub fn link_entity(connection: &Connection, poi_id: i64, entity: &str) {
+    connection
+        .execute(
+            "INSERT INTO poi_wikidata_links (poi_id, entity_id) VALUES (?1, ?2)",
+            (poi_id, entity),
+        )
+        .unwrap_or_else(|err| panic!("link poi {poi_id} to {entity}: {err}"));
+}
+
+/// Record a heritage designation claim for an entity.
+pub fn insert_heritage_claim(connection: &Connection, entity: &str) {
+    connection
+        .execute(
+            "INSERT INTO wikidata_entity_claims (entity_id, property_id, value_entity_id) VALUES (?1, 'P1435', 'Q9259')",
+            [entity],
+        )
+        .unwrap_or_else(|err| panic!("insert heritage claim for {entity}: {err}"));
+}
@@ -0,0 +1,246 @@
+//! Cheapest-insertion construction ranked by score per minute.
+//!
+//! The route grows one stop at a time. Every unvisited candidate is tried at
+//! every position along the route, and the move adding the most score per
+//! minute of extra walking and visiting wins. Required candidates are placed
+//! before any optional one. Construction stops once no candidate fits the
+//! budget.
+//!
+//! When candidates have opening hours, every stop's arrival is worked out
+//! from the start: a visit begins on arrival, or once the POI opens, and an
+//! insertion that would leave any stop closed is not made.
+//!
+//! With a leg limit, an insertion is only made when both legs to and from the
+//! new stop keep within it.
+
+use std::ops::Range;
+use std::time::Duration;
+
+use wildside_core::{PointOfInterest, RouteLeg, RouteStop, TravelTimeMatrix};
+
+/// Costs and rewards of a single-walker orienteering instance.
+///
+/// Locations follow the matrix layout: the start is location `0`, candidate
+/// `i` is location `i + 1`, and `end` is the location the route finishes at.
+pub(crate) struct Instance<'a> {
+    pub(crate) matrix: &'a TravelTimeMatrix,
+    /// Score of each candidate.
+    pub(crate) scores: &'a [f32],
+    /// Whether each candidate must be visited; missing entries are optional.
+    pub(crate) required: &'a [bool],
+    /// Time spent at each visited candidate.
+    pub(crate) dwell: Duration,
+    /// Offsets at which each candidate's visit may begin; `None` or missing
+    /// entries leave a candidate unconstrained.
+    pub(crate) windows: &'a [Option<Vec<Range<Duration>>>],
+    /// Longest walk allowed between consecutive stops, if any.
+    pub(crate) max_leg: Option<Duration>,
+    pub(crate) budget: Duration,
+    pub(crate) end: usize,
+}
+
+/// Candidate indices of the constructed route, in visiting order, and its
+/// duration including visits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Plan {
+    pub(crate) stops: Vec<usize>,
+    pub(crate) duration: Duration,
+}
+
+/// One way of extending the route.
+struct Insertion {
+    candidate: usize,
+    position: usize,
+    added: Duration,
+    required: bool,
+    value: f32,
+}
+
+impl Insertion {
+    /// Report whether `self` is a better move than `other`: required stops
+    /// first, then more score per minute, then the lower candidate index,
+    /// then the later position so that ties extend the route forwards.
+    fn beats(&self, other: &Self) -> bool {
+        self.required
+            .cmp(&other.required)
+            .then_with(|| self.value.total_cmp(&other.value))
+            .then_with(|| other.candidate.cmp(&self.candidate))
+            .then_with(|| self.position.cmp(&other.position))
+            .is_gt()
+    }
+}
+
+impl Instance<'_> {
+    /// Build a route by repeatedly making the best feasible insertion.
+    ///
+    /// Optional candidates scoring nothing are never visited.
+    pub(crate) fn plan(&self) -> Plan {
+        let mut plan = Plan {
+            stops: Vec::new(),
+            duration: self.travel(0, self.end).unwrap_or_default(),
+        };
+        while let Some(best) = self.best_insertion(&plan) {
+            plan.stops.insert(best.position, best.candidate);
+            plan.duration += best.added;
+        }
+        plan
+    }
+
+    /// Break `plan` into legs from the start, through each of `route_pois`
+    /// in turn, to the end.
+    ///
+    /// `route_pois` are the candidates at the plan's stops. A round trip
+    /// that visits nothing has no legs.
+    pub(crate) fn legs(&self, plan: &Plan, route_pois: &[PointOfInterest]) -> Vec<RouteLeg> {
+        let mut legs = Vec::with_capacity(route_pois.len() + 1);
+        let mut elapsed = Duration::ZERO;
+        let mut from = (RouteStop::Start, 0);
+        for (stop, poi) in plan.stops.iter().zip(route_pois) {
+            let travel_duration = self.travel(from.1, stop + 1).unwrap_or_default();
+            let arrival = elapsed + travel_duration;
+            let wait = self
+                .visit_start(*stop, arrival)
+                .map_or(Duration::ZERO, |start| start.saturating_sub(arrival));
+            elapsed = arrival + wait + self.dwell;
+            legs.push(RouteLeg {
+                from: from.0,
+                to: RouteStop::Poi(poi.id),
+                travel_duration,
+                dwell: Some(wait + self.dwell),
+                cumulative_duration: elapsed,
+                arrival_at: None,
+            });
+            from = (RouteStop::Poi(poi.id), stop + 1);
+        }
+        if legs.is_empty() && self.end == 0 {
+            return legs;
+        }
+        let travel_duration = self.travel(from.1, self.end).unwrap_or_default();
+        legs.push(RouteLeg {
+            from: from.0,
+            to: RouteStop::End,
+            travel_duration,
+            dwell: None,
+            cumulative_duration: elapsed + travel_duration,
+            arrival_at: None,
+        });
+        legs
+    }
+
+    fn best_insertion(&self, plan: &Plan) -> Option<Insertion> {
+        self.scores
+            .iter()
+            .enumerate()
+            .filter(|(candidate, _)| !plan.stops.contains(candidate))
+            .flat_map(|(candidate, score)| {
+                let required = self.required.get(candidate).copied().unwrap_or(false);
+                let worthwhile = required || *score > 0.0;
+                (0..=plan.stops.len())
+                    .filter(move |_| worthwhile)
+                    .filter_map(move |position| {
+                        let added = self.added(plan, candidate, position)?;
+                        (plan.duration.saturating_add(added) <= self.budget).then(|| Insertion {
+                            candidate,
+                            position,
+                            added,
+                            required,
+                            value: score_per_minute(*score, added),
+                        })
+                    })
+            })
+            .reduce(|best, next| if next.beats(&best) { next } else { best })
+    }
+
+    /// Extra time spent by visiting `candidate` just before the stop at
+    /// `position` of `plan`, or `None` when a leg is missing, unroutable, or
+    /// too long, or a stop would be closed.
+    ///
+    /// Without opening hours only the neighbouring legs change. Otherwise
+    /// the visit may delay every later stop, so the whole route is timed
+    /// again.
+    fn added(&self, plan: &Plan, candidate: usize, position: usize) -> Option<Duration> {
+        if self.windows.is_empty() {
+            return self.added_duration(&plan.stops, candidate, position);
+        }
+        let mut stops = plan.stops.clone();
+        stops.insert(position, candidate);
+        Some(self.finish(&stops)?.saturating_sub(plan.duration))
+    }
+
+    /// When a route visiting `stops` in turn ends, or `None` when a leg is
+    /// missing, unroutable, or too long, or a stop is closed on arrival and
+    /// stays closed.
+    fn finish(&self, stops: &[usize]) -> Option<Duration> {
+        let mut clock = Duration::ZERO;
+        let mut at = 0;
+        for stop in stops {
+            let arrival = clock.checked_add(self.leg(at, stop + 1)?)?;
+            clock = self.visit_start(*stop, arrival)?.checked_add(self.dwell)?;
+            at = stop + 1;
+        }
+        clock.checked_add(self.leg(at, self.end)?)
+    }
+
+    /// When a visit to `candidate`, reached at `arrival`, can begin: at once,
+    /// or when it next opens. `None` when it does not open again in time.
+    fn visit_start(&self, candidate: usize, arrival: Duration) -> Option<Duration> {
+        self.windows
+            .get(candidate)
+            .and_then(Option::as_ref)
+            .map_or(Some(arrival), |windows| {
+                windows
+                    .iter()
+                    .filter(|window| window.end >= arrival)
+                    .map(|window| window.start.max(arrival))
+                    .min()
+            })
+    }
+
+    /// Extra time spent by visiting `candidate` just before the stop at
+    /// `position`, ignoring opening hours, or `None` when a leg is missing,
+    /// unroutable, or too long.
+    fn added_duration(
+        &self,
+        stops: &[usize],
+        candidate: usize,
+        position: usize,
+    ) -> Option<Duration> {
+        let previous = position
+            .checked_sub(1)
+            .and_then(|index| stops.get(index))
+            .map_or(0, |stop| stop + 1);
+        let next = stops.get(position).map_or(self.end, |stop| stop + 1);
+        let location = candidate + 1;
+        let detour = self
+            .leg(previous, location)?
+            .checked_add(self.dwell)?
+            .checked_add(self.leg(location, next)?)?;
+        Some(detour.saturating_sub(self.travel(previous, next)?))
+    }
+
+    fn travel(&self, from: usize, to: usize) -> Option<Duration> {
+        self.matrix
+            .get(from, to)
+            .filter(|duration| *duration != Duration::MAX)
+    }
+
+    /// Travel time of a leg the route may take: routable and within the leg
+    /// limit.
+    fn leg(&self, from: usize, to: usize) -> Option<Duration> {
+        self.travel(from, to)
+            .filter(|duration| self.max_leg.is_none_or(|limit| *duration <= limit))
+    }
+}
+
+/// Score gained per minute added, treating moves shorter than a second as a
+/// second long.
+#[expect(
+    clippy::float_arithmetic,
+    reason = "insertions are ranked by a floating-point score rate"
+)]
+fn score_per_minute(score: f32, added: Duration) -> f32 {
+    score / added.as_secs_f32().max(1.0) * 60.0
+}
+
+#[cfg(test)]
+mod tests;
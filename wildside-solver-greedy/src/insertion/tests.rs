@@ -0,0 +1,153 @@
+//! Tests for cheapest-insertion construction.
+
+use super::*;
+use rstest::rstest;
+
+/// Start and candidates on a line, a minute apart.
+fn line(size: usize) -> TravelTimeMatrix {
+    TravelTimeMatrix::from_fn(size, |from, to| {
+        Duration::from_mins(from.abs_diff(to).try_into().unwrap_or(u64::MAX))
+    })
+}
+
+fn instance<'a>(matrix: &'a TravelTimeMatrix, scores: &'a [f32], budget: Duration) -> Instance<'a> {
+    Instance {
+        matrix,
+        scores,
+        required: &[],
+        dwell: Duration::ZERO,
+        windows: &[],
+        max_leg: None,
+        budget,
+        end: 0,
+    }
+}
+
+#[rstest]
+fn everything_fits_a_generous_budget() {
+    let matrix = line(4);
+    let plan = instance(&matrix, &[1.0, 1.0, 1.0], Duration::from_hours(1)).plan();
+    assert_eq!(plan.stops, vec![0, 1, 2]);
+    assert_eq!(plan.duration, Duration::from_mins(6));
+}
+
+#[rstest]
+fn score_per_minute_beats_raw_score() {
+    // With a minute at each stop, the near one earns 1 point for 3
+    // minutes and the far one 2 points for 7 minutes; both take 8.
+    let matrix = line(4);
+    let plan = Instance {
+        dwell: Duration::from_mins(1),
+        ..instance(&matrix, &[1.0, 0.0, 2.0], Duration::from_mins(7))
+    }
+    .plan();
+    assert_eq!(plan.stops, vec![0]);
+    assert_eq!(plan.duration, Duration::from_mins(3));
+}
+
+#[rstest]
+fn required_stops_come_first() {
+    let matrix = line(4);
+    let plan = Instance {
+        required: &[false, false, true],
+        ..instance(&matrix, &[1.0, 1.0, 0.0], Duration::from_mins(6))
+    }
+    .plan();
+    assert_eq!(plan.stops, vec![2, 1, 0]);
+}
+
+#[rstest]
+fn dwell_counts_against_the_budget() {
+    let matrix = line(3);
+    let plan = Instance {
+        dwell: Duration::from_mins(3),
+        ..instance(&matrix, &[1.0, 1.0], Duration::from_mins(6))
+    }
+    .plan();
+    assert_eq!(plan.stops, vec![0]);
+    assert_eq!(plan.duration, Duration::from_mins(5));
+}
+
+#[rstest]
+fn legs_accumulate_travel_and_dwell() {
+    let matrix = line(3);
+    let route_instance = Instance {
+        dwell: Duration::from_mins(1),
+        end: 2,
+        ..instance(&matrix, &[1.0], Duration::from_hours(1))
+    };
+    let plan = route_instance.plan();
+    let stop = PointOfInterest::with_empty_tags(7, geo::Coord { x: 0.0, y: 0.0 });
+    let legs = route_instance.legs(&plan, &[stop]);
+    let cumulative: Vec<Duration> = legs.iter().map(|leg| leg.cumulative_duration).collect();
+    assert_eq!(
+        cumulative,
+        vec![Duration::from_mins(2), Duration::from_mins(3)]
+    );
+    assert_eq!(legs.last().map(|leg| leg.from), Some(RouteStop::Poi(7)));
+    assert_eq!(plan.duration, Duration::from_mins(3));
+}
+
+#[rstest]
+fn closed_candidates_are_visited_once_open() {
+    // The far candidate opens five minutes in, so the near one is
+    // visited first while it waits.
+    let matrix = line(3);
+    let windows = [
+        Some(vec![Duration::ZERO..Duration::from_mins(2)]),
+        Some(vec![Duration::from_mins(5)..Duration::from_mins(9)]),
+    ];
+    let route_instance = Instance {
+        windows: &windows,
+        ..instance(&matrix, &[1.0, 1.0], Duration::from_mins(10))
+    };
+    let plan = route_instance.plan();
+    assert_eq!(plan.stops, vec![0, 1]);
+    assert_eq!(plan.duration, Duration::from_mins(7));
+    let stops =
+        [0, 1].map(|id| PointOfInterest::with_empty_tags(id, geo::Coord { x: 0.0, y: 0.0 }));
+    let legs = route_instance.legs(&plan, &stops);
+    let dwells: Vec<Option<Duration>> = legs.iter().map(|leg| leg.dwell).collect();
+    assert_eq!(
+        dwells,
+        vec![Some(Duration::ZERO), Some(Duration::from_mins(3)), None]
+    );
+}
+
+#[rstest]
+fn candidates_closed_on_arrival_are_skipped() {
+    let matrix = line(3);
+    let windows = [None, Some(vec![Duration::ZERO..Duration::from_mins(1)])];
+    let plan = Instance {
+        windows: &windows,
+        ..instance(&matrix, &[1.0, 1.0], Duration::from_hours(1))
+    }
+    .plan();
+    assert_eq!(plan.stops, vec![0]);
+}
+
+#[rstest]
+fn insertions_keep_every_leg_within_the_limit() {
+    // The far candidate is two minutes from the start, so a round trip
+    // through it walks further than a minute on some leg.
+    let matrix = line(3);
+    let plan = Instance {
+        max_leg: Some(Duration::from_mins(1)),
+        ..instance(&matrix, &[1.0, 5.0], Duration::from_hours(1))
+    }
+    .plan();
+    assert_eq!(plan.stops, vec![0]);
+}
+
+#[rstest]
+fn unroutable_candidates_are_skipped() {
+    let matrix = TravelTimeMatrix::from_fn(2, |from, to| {
+        if from == to {
+            Duration::ZERO
+        } else {
+            Duration::MAX
+        }
+    });
+    let plan = instance(&matrix, &[5.0], Duration::from_hours(1)).plan();
+    assert!(plan.stops.is_empty());
+}
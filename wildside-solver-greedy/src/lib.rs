@@ -0,0 +1,18 @@
+//! Greedy heuristic solver for Wildside.
+//!
+//! This crate provides [`GreedySolver`], a lightweight implementation of the
+//! [`Solver`](wildside_core::Solver) trait with no `vrp-core` dependency. It
+//! selects candidates within walking reach of the request's endpoints, fetches
+//! one travel-time matrix, and builds a route by inserting the POI that adds
+//! the most score per extra minute until nothing else fits the budget.
+//!
+//! Routes are found in milliseconds and are fully deterministic, which makes
+//! the solver a fast fallback, a choice for constrained devices, and a
+//! lower-bound baseline when benchmarking other solvers.
+
+#![forbid(unsafe_code)]
+
+mod insertion;
+mod solver;
+
+pub use solver::{GreedySolver, GreedySolverConfig};
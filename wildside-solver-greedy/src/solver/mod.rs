@@ -0,0 +1,225 @@
+//! `GreedySolver` implementation.
+//!
+//! Candidates are the POIs within walking reach of the start or end. The
+//! route is then built by [cheapest insertion](crate::insertion) over a
+//! single travel-time matrix, so a solve costs one store query, one matrix
+//! request, and a polynomial amount of arithmetic.
+
+use std::ops::Range;
+use std::time::{Duration, Instant};
+
+use wildside_core::{
+    Diagnostics, OpeningHours, PoiStore, PointOfInterest, Route, Scorer, Shortfall, SolveError,
+    SolveRequest, SolveResponse, Solver, TravelTimeProvider,
+};
+
+use crate::insertion::{Instance, Plan};
+
+/// Configuration for [`GreedySolver`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GreedySolverConfig {
+    /// Average walking speed used to derive the candidate search radius.
+    pub average_speed_kmh: f64,
+    /// Time spent at each visited POI, counted against the duration budget.
+    pub dwell: Duration,
+}
+
+impl Default for GreedySolverConfig {
+    fn default() -> Self {
+        Self {
+            average_speed_kmh: 5.0,
+            dwell: Duration::ZERO,
+        }
+    }
+}
+
+/// Fast heuristic solver building routes by greedy insertion.
+///
+/// Stops are added one at a time, each time choosing the POI and position
+/// that add the most score per extra minute while keeping the route within
+/// the budget. Required POIs are inserted before any optional one. Routes
+/// usually score below those of the `vrp-core` backed `VrpSolver`, but a
+/// solve needs no metaheuristic search, which suits fallbacks, constrained
+/// devices, and baselines in benchmarks.
+///
+/// When the request has a start time, visits begin on arrival or once the POI
+/// opens, and stops that would be closed are left out. Rest breaks in
+/// [`SolveRequest::rest_break`] are not reserved. Stops are only inserted
+/// where both neighbouring legs keep within [`SolveRequest::max_leg_minutes`],
+/// and a point-to-point walk whose direct leg is too long fails with
+/// [`SolveError::Infeasible`] when no stop breaks it up. Requests carrying
+/// [`SolveRequest::visited`] fail with [`SolveError::NotImplemented`].
+pub struct GreedySolver<S, T, C>
+where
+    S: PoiStore,
+    T: TravelTimeProvider,
+    C: Scorer,
+{
+    store: S,
+    travel_time_provider: T,
+    scorer: C,
+    config: GreedySolverConfig,
+}
+
+impl<S, T, C> GreedySolver<S, T, C>
+where
+    S: PoiStore,
+    T: TravelTimeProvider,
+    C: Scorer,
+{
+    /// Construct a solver using default configuration.
+    pub fn new(store: S, travel_time_provider: T, scorer: C) -> Self {
+        Self::with_config(
+            store,
+            travel_time_provider,
+            scorer,
+            GreedySolverConfig::default(),
+        )
+    }
+
+    /// Construct a solver with explicit configuration.
+    pub const fn with_config(
+        store: S,
+        travel_time_provider: T,
+        scorer: C,
+        config: GreedySolverConfig,
+    ) -> Self {
+        Self {
+            store,
+            travel_time_provider,
+            scorer,
+            config,
+        }
+    }
+}
+
+impl<S, T, C> Solver for GreedySolver<S, T, C>
+where
+    S: PoiStore + Send + Sync,
+    T: TravelTimeProvider + Send + Sync,
+    C: Scorer + Send + Sync,
+{
+    fn solve(&self, request: &SolveRequest) -> Result<SolveResponse, SolveError> {
+        let started_at = Instant::now();
+        request.validate()?;
+        if request.visited.is_some() {
+            return Err(SolveError::NotImplemented);
+        }
+        let (candidates, scores): (Vec<PointOfInterest>, Vec<f32>) = request
+            .walking_candidates(&self.store, &self.scorer, self.config.average_speed_kmh)?
+            .into_iter()
+            .unzip();
+        request.ensure_required(&candidates)?;
+        let locations = request.matrix_locations(&candidates);
+        let provider = request.accessibility.provider(&self.travel_time_provider)?;
+        let matrix = provider.get_travel_time_matrix(&locations)?;
+        let required: Vec<bool> = candidates
+            .iter()
+            .map(|poi| request.required_poi_ids.contains(&poi.id))
+            .collect();
+        let end = request.end.map_or(0, |_| locations.len() - 1);
+        let budget = request.detour_budget(matrix.get(0, end).unwrap_or(Duration::MAX));
+        let windows = visit_windows(request, &candidates, budget, self.config.dwell);
+        let instance = Instance {
+            matrix: &matrix,
+            scores: &scores,
+            required: &required,
+            dwell: self.config.dwell,
+            windows: &windows,
+            max_leg: request.max_leg_duration(),
+            budget,
+            end,
+        };
+        let plan = instance.plan();
+        let route_pois: Vec<PointOfInterest> = plan
+            .stops
+            .iter()
+            .filter_map(|index| candidates.get(*index).cloned())
+            .collect();
+        request.ensure_required(&route_pois)?;
+
+        let legs = instance.legs(&plan, &route_pois);
+        let route_end = request.end.unwrap_or(request.start);
+        let mut route = Route::with_endpoints(request.start, route_end, route_pois, plan.duration)
+            .with_legs(legs);
+        if let Some(start) = request.start_at {
+            route = route.with_start_at(start);
+        }
+        let geometry = route.trace(provider);
+        let response = ensure_leg_limit(
+            SolveResponse {
+                route,
+                score: plan_score(&plan, &scores),
+                diagnostics: Diagnostics {
+                    solve_time: started_at.elapsed(),
+                    candidates_evaluated: candidates.len() as u64,
+                    candidates_pruned: 0,
+                },
+                geometry,
+            },
+            request.max_leg_duration(),
+        )?;
+        request.ensure_minimums(response)
+    }
+}
+
+/// Offsets from the request's start at which a visit to each candidate may
+/// begin, or nothing when the request has no start time.
+///
+/// Candidates without usable opening hours are unconstrained.
+fn visit_windows(
+    request: &SolveRequest,
+    candidates: &[PointOfInterest],
+    budget: Duration,
+    dwell: Duration,
+) -> Vec<Option<Vec<Range<Duration>>>> {
+    let Some(start) = request.start_week_time() else {
+        return Vec::new();
+    };
+    candidates
+        .iter()
+        .map(|poi| {
+            let hours = OpeningHours::from_tags(&poi.tags)?
+                .inspect_err(|error| {
+                    log::debug!("Ignoring opening hours of POI {}: {error}", poi.id);
+                })
+                .ok()?;
+            Some(hours.visit_windows(start, budget, dwell))
+        })
+        .collect()
+}
+
+/// Pass `response` through unless one of its legs walks further than
+/// `max_leg`, in which case fail with [`SolveError::Infeasible`].
+///
+/// Insertion keeps every leg it creates within the limit, so only the direct
+/// walk of a point-to-point route that visits nothing can break it.
+fn ensure_leg_limit(
+    response: SolveResponse,
+    max_leg: Option<Duration>,
+) -> Result<SolveResponse, SolveError> {
+    let Some(limit) = max_leg else {
+        return Ok(response);
+    };
+    if response
+        .route
+        .legs()
+        .iter()
+        .any(|leg| leg.travel_duration > limit)
+    {
+        log::debug!("A leg of the route exceeds the {limit:?} limit");
+        return Err(SolveError::Infeasible(Shortfall::of(&response)));
+    }
+    Ok(response)
+}
+
+/// Total score of the candidates `plan` visits.
+fn plan_score(plan: &Plan, scores: &[f32]) -> f32 {
+    plan.stops
+        .iter()
+        .filter_map(|index| scores.get(*index))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,357 @@
+//! Tests for the `GreedySolver`.
+
+use std::error::Error as _;
+
+use super::*;
+use geo::Coord;
+use rstest::{fixture, rstest};
+use wildside_core::test_support::{MemoryStore, TagScorer, UnitTravelTimeProvider};
+use wildside_core::travel_time::StepFreeTravelTimeProvider;
+use wildside_core::{
+    AccessibilityRequirements, InterestProfile, RouteStop, Tags, Theme, TravelTimeError,
+    TravelTimeMatrix, VisitedPrefix, WheelchairAccess,
+};
+
+fn poi(id: u64, x: f64, theme: &str) -> PointOfInterest {
+    PointOfInterest::new(
+        id,
+        Coord { x, y: 0.0 },
+        Tags::from([(theme.to_owned(), String::new())]),
+    )
+}
+
+#[fixture]
+fn solver() -> GreedySolver<MemoryStore, UnitTravelTimeProvider, TagScorer> {
+    let pois = vec![
+        poi(1, 0.0, "art"),
+        poi(2, 0.001, "history"),
+        poi(3, 0.002, "nature"),
+    ];
+    GreedySolver::new(
+        MemoryStore::with_pois(pois),
+        UnitTravelTimeProvider,
+        TagScorer,
+    )
+}
+
+#[fixture]
+fn request() -> SolveRequest {
+    SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes: 10,
+        interests: InterestProfile::new()
+            .with_weight(Theme::Art, 0.5)
+            .with_weight(Theme::History, 0.25),
+        seed: 1,
+        ..Default::default()
+    }
+}
+
+fn ids(response: &SolveResponse) -> Vec<u64> {
+    response.route.pois().iter().map(|poi| poi.id).collect()
+}
+
+#[rstest]
+#[expect(
+    clippy::float_cmp,
+    reason = "scores are exact sums of binary fractions"
+)]
+fn visits_every_scoring_poi_that_fits(
+    solver: GreedySolver<MemoryStore, UnitTravelTimeProvider, TagScorer>,
+    request: SolveRequest,
+) {
+    let response = solver.solve(&request).expect("solve");
+    assert_eq!(ids(&response), vec![1, 2]);
+    assert_eq!(response.score, 0.75);
+    assert_eq!(response.diagnostics.candidates_evaluated, 2);
+    assert_eq!(response.route.total_duration(), Duration::from_secs(3));
+    assert_eq!(
+        response
+            .route
+            .legs()
+            .last()
+            .map(|leg| leg.cumulative_duration),
+        Some(Duration::from_secs(3))
+    );
+}
+
+#[rstest]
+fn dwell_limits_the_stops_visited(
+    solver: GreedySolver<MemoryStore, UnitTravelTimeProvider, TagScorer>,
+    request: SolveRequest,
+) {
+    let dwelling = GreedySolver {
+        config: GreedySolverConfig {
+            dwell: Duration::from_mins(6),
+            ..GreedySolverConfig::default()
+        },
+        ..solver
+    };
+    let response = dwelling.solve(&request).expect("solve");
+    assert_eq!(ids(&response), vec![1]);
+}
+
+#[rstest]
+fn required_pois_are_visited_even_without_score(
+    solver: GreedySolver<MemoryStore, UnitTravelTimeProvider, TagScorer>,
+    request: SolveRequest,
+) {
+    let required = SolveRequest {
+        required_poi_ids: vec![3],
+        max_nodes: Some(1),
+        ..request
+    };
+    let response = solver.solve(&required).expect("solve");
+    assert_eq!(ids(&response), vec![3]);
+}
+
+#[rstest]
+fn excluded_required_pois_are_infeasible(
+    solver: GreedySolver<MemoryStore, UnitTravelTimeProvider, TagScorer>,
+    request: SolveRequest,
+) {
+    let conflicting = SolveRequest {
+        required_poi_ids: vec![2],
+        excluded_poi_ids: vec![2],
+        ..request
+    };
+    assert!(matches!(
+        solver.solve(&conflicting),
+        Err(SolveError::InfeasibleRequirement)
+    ));
+}
+
+#[rstest]
+fn point_to_point_routes_end_at_the_destination(
+    solver: GreedySolver<MemoryStore, UnitTravelTimeProvider, TagScorer>,
+    request: SolveRequest,
+) {
+    let destination = Coord { x: 0.003, y: 0.0 };
+    let one_way = SolveRequest {
+        end: Some(destination),
+        ..request
+    };
+    let response = solver.solve(&one_way).expect("solve");
+    assert_eq!(response.route.end(), destination);
+    assert_eq!(
+        response.route.legs().last().map(|leg| leg.to),
+        Some(RouteStop::End)
+    );
+}
+
+#[rstest]
+fn detour_limits_cap_point_to_point_routes(
+    solver: GreedySolver<MemoryStore, UnitTravelTimeProvider, TagScorer>,
+    request: SolveRequest,
+) {
+    // The direct walk takes a second, so each stop doubles it and more.
+    let commute = SolveRequest {
+        end: Some(Coord { x: 0.003, y: 0.0 }),
+        max_detour: Some(2.0),
+        ..request
+    };
+    let response = solver.solve(&commute).expect("solve");
+    assert_eq!(ids(&response), vec![1]);
+    assert_eq!(response.route.total_duration(), Duration::from_secs(2));
+}
+
+#[rstest]
+fn tours_under_way_are_not_supported(
+    solver: GreedySolver<MemoryStore, UnitTravelTimeProvider, TagScorer>,
+    request: SolveRequest,
+) {
+    let under_way = SolveRequest {
+        visited: Some(VisitedPrefix {
+            poi_ids: vec![1],
+            position: Coord { x: 0.0, y: 0.0 },
+            elapsed_minutes: 1,
+        }),
+        ..request
+    };
+    assert!(matches!(
+        solver.solve(&under_way),
+        Err(SolveError::NotImplemented)
+    ));
+}
+
+#[rstest]
+fn routes_short_of_the_minimums_are_infeasible(
+    solver: GreedySolver<MemoryStore, UnitTravelTimeProvider, TagScorer>,
+    request: SolveRequest,
+) {
+    let demanding = SolveRequest {
+        min_pois: Some(3),
+        ..request
+    };
+    let Err(SolveError::Infeasible(shortfall)) = solver.solve(&demanding) else {
+        panic!("two scoring POIs cannot make three stops");
+    };
+    assert_eq!(shortfall.pois, 2);
+    assert_eq!(shortfall.diagnostics.candidates_evaluated, 2);
+}
+
+/// Provider whose routing service cannot be reached.
+struct Offline;
+
+impl TravelTimeProvider for Offline {
+    fn get_travel_time_matrix(
+        &self,
+        _pois: &[PointOfInterest],
+    ) -> Result<TravelTimeMatrix, TravelTimeError> {
+        Err(TravelTimeError::NetworkError {
+            url: "http://localhost:5000".to_owned(),
+            message: "connection refused".to_owned(),
+        })
+    }
+}
+
+#[rstest]
+fn travel_time_failures_keep_their_cause(request: SolveRequest) {
+    let offline = GreedySolver::new(
+        MemoryStore::with_pois(vec![poi(1, 0.0, "art")]),
+        Offline,
+        TagScorer,
+    );
+    let error = offline.solve(&request).expect_err("routing is offline");
+    assert!(matches!(
+        error,
+        SolveError::TravelTime(TravelTimeError::NetworkError { .. })
+    ));
+    assert!(error.source().is_some());
+}
+
+/// Provider placing every location ten minutes from every other.
+struct Distant;
+
+impl TravelTimeProvider for Distant {
+    fn get_travel_time_matrix(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> Result<TravelTimeMatrix, TravelTimeError> {
+        Ok(TravelTimeMatrix::from_fn(pois.len(), |from, to| {
+            if from == to {
+                Duration::ZERO
+            } else {
+                Duration::from_mins(10)
+            }
+        }))
+    }
+}
+
+fn distant_solver() -> GreedySolver<MemoryStore, Distant, TagScorer> {
+    GreedySolver::new(
+        MemoryStore::with_pois(vec![poi(1, 0.0, "art")]),
+        Distant,
+        TagScorer,
+    )
+}
+
+#[rstest]
+fn stops_beyond_the_leg_limit_are_left_off_the_route(request: SolveRequest) {
+    let limited = SolveRequest {
+        duration_minutes: 60,
+        max_leg_minutes: Some(5),
+        ..request
+    };
+    let response = distant_solver().solve(&limited).expect("solve");
+    assert!(response.route.pois().is_empty());
+}
+
+#[rstest]
+fn direct_walks_beyond_the_leg_limit_are_infeasible(request: SolveRequest) {
+    let limited = SolveRequest {
+        end: Some(Coord { x: 0.003, y: 0.0 }),
+        duration_minutes: 60,
+        max_leg_minutes: Some(5),
+        ..request
+    };
+    assert!(matches!(
+        distant_solver().solve(&limited),
+        Err(SolveError::Infeasible(_))
+    ));
+}
+
+#[rstest]
+fn closed_pois_are_skipped_and_arrivals_stamped(request: SolveRequest) {
+    let gallery = PointOfInterest::new(
+        1,
+        Coord { x: 0.0, y: 0.0 },
+        Tags::from([
+            ("art".to_owned(), String::new()),
+            ("opening_hours".to_owned(), "Sa 12:00-18:00".to_owned()),
+        ]),
+    );
+    let solver = GreedySolver::new(
+        MemoryStore::with_pois(vec![gallery, poi(2, 0.001, "history")]),
+        UnitTravelTimeProvider,
+        TagScorer,
+    );
+    let timed = SolveRequest {
+        start_at: Some("2026-10-17T10:00:00+02:00".parse().expect("valid start")),
+        ..request
+    };
+    let response = solver.solve(&timed).expect("solve");
+    assert_eq!(ids(&response), vec![2]);
+    let arrivals: Vec<String> = response
+        .route
+        .legs()
+        .iter()
+        .filter_map(|leg| leg.arrival_at.map(|at| at.to_string()))
+        .collect();
+    assert_eq!(
+        arrivals,
+        ["2026-10-17T10:00:01+02:00", "2026-10-17T10:00:02+02:00"]
+    );
+}
+
+#[rstest]
+fn wheelchair_users_visit_only_accessible_pois(request: SolveRequest) {
+    let ramped = PointOfInterest::new(
+        2,
+        Coord { x: 0.001, y: 0.0 },
+        Tags::from([
+            ("history".to_owned(), String::new()),
+            ("wheelchair".to_owned(), "yes".to_owned()),
+        ]),
+    );
+    let solver = GreedySolver::new(
+        MemoryStore::with_pois(vec![poi(1, 0.0, "art"), ramped]),
+        UnitTravelTimeProvider,
+        TagScorer,
+    );
+    let accessible = SolveRequest {
+        accessibility: AccessibilityRequirements {
+            wheelchair: Some(WheelchairAccess::Limited),
+            step_free: false,
+        },
+        ..request
+    };
+    let response = solver.solve(&accessible).expect("solve");
+    assert_eq!(ids(&response), vec![2]);
+}
+
+#[rstest]
+fn step_free_solves_route_with_the_step_free_profile(
+    solver: GreedySolver<MemoryStore, UnitTravelTimeProvider, TagScorer>,
+    request: SolveRequest,
+) {
+    let step_free = SolveRequest {
+        accessibility: AccessibilityRequirements {
+            wheelchair: None,
+            step_free: true,
+        },
+        ..request
+    };
+    assert!(matches!(
+        solver.solve(&step_free),
+        Err(SolveError::TravelTime(TravelTimeError::StepFreeUnavailable))
+    ));
+
+    let paired = GreedySolver::new(
+        MemoryStore::with_pois(vec![poi(1, 0.0, "art")]),
+        StepFreeTravelTimeProvider::new(Offline, UnitTravelTimeProvider),
+        TagScorer,
+    );
+    let response = paired.solve(&step_free).expect("solve");
+    assert_eq!(ids(&response), vec![1]);
+}
@@ -1,50 +1,25 @@
 //! Optional OR-Tools-based solver implementation.
 //!
-//! This crate currently provides a stub solver that compiles behind the
-//! `solver-ortools` feature flag. It reserves the API surface for a future
-//! CP-SAT implementation without pulling native OR-Tools dependencies yet.
+//! This crate provides [`OrtoolsSolver`], an exact implementation of the
+//! [`Solver`](wildside_core::Solver) trait that models each request as an
+//! Orienteering Problem for the OR-Tools CP-SAT constraint solver. It selects
+//! candidates within walking reach of the request's endpoints, fetches one
+//! travel-time matrix, and maximizes the collected score within the budget,
+//! less the diversity and backtracking penalties `VrpSolver` also charges.
+//! Visits keep opening hours and per-POI dwell times, and a requested rest
+//! break is taken at a suitable venue, as with `VrpSolver`.
+//!
+//! The model is compiled behind the `cp-sat` feature, which the workspace's
+//! `solver-ortools` feature enables. Building it links the native OR-Tools
+//! C++ library, found through the `ORTOOLS_PREFIX` environment variable, and
+//! needs `protoc`. Without the feature the solver validates requests and
+//! otherwise fails with
+//! [`SolveError::NotImplemented`](wildside_core::SolveError::NotImplemented).
 
 #![forbid(unsafe_code)]
 
-use wildside_core::TravelTimeProvider;
-use wildside_core::{PoiStore, Scorer, SolveError, SolveRequest, SolveResponse, Solver};
-
-/// Placeholder solver for the optional OR-Tools backend.
-#[derive(Debug)]
-pub struct OrtoolsSolver<S, T, C>
-where
-    S: PoiStore,
-    T: TravelTimeProvider,
-    C: Scorer,
-{
-    _store: S,
-    _travel_time_provider: T,
-    _scorer: C,
-}
-
-impl<S, T, C> OrtoolsSolver<S, T, C>
-where
-    S: PoiStore,
-    T: TravelTimeProvider,
-    C: Scorer,
-{
-    /// Construct a placeholder OR-Tools solver.
-    pub const fn new(store: S, travel_time_provider: T, scorer: C) -> Self {
-        Self {
-            _store: store,
-            _travel_time_provider: travel_time_provider,
-            _scorer: scorer,
-        }
-    }
-}
+#[cfg(feature = "cp-sat")]
+mod model;
+mod solver;
 
-impl<S, T, C> Solver for OrtoolsSolver<S, T, C>
-where
-    S: PoiStore + Send + Sync,
-    T: TravelTimeProvider + Send + Sync,
-    C: Scorer + Send + Sync,
-{
-    fn solve(&self, _request: &SolveRequest) -> Result<SolveResponse, SolveError> {
-        Err(SolveError::NotImplemented)
-    }
-}
+pub use solver::{OrtoolsSolver, OrtoolsSolverConfig};
@@ -0,0 +1,409 @@
+//! CP-SAT model of the Orienteering Problem.
+//!
+//! Every location is a node of a single circuit: the start is node `0`,
+//! candidate `i` is node `i + 1`, and a point-to-point route's end is the last
+//! node. Each candidate carries a self-loop literal that is true when the
+//! route skips it, so `AddCircuit` selects the visited candidates and orders
+//! them at once without extra subtour constraints. The end of a
+//! point-to-point route closes the circuit through a fixed arc back to the
+//! start; a round trip's start keeps its own self-loop only when nothing is
+//! visited, so the circuit always passes through it. A linear constraint
+//! keeps travel, dwell and rest time within the budget, and the objective
+//! maximizes the collected score less any diversity and backtracking
+//! penalties (see [`penalty`]). Arcs longer than the leg limit are left out
+//! of the circuit, so no route can walk them. When candidates keep opening
+//! hours or the route reserves a rest break, every visit is also given a
+//! start time (see [`schedule`]).
+
+mod penalty;
+mod schedule;
+
+use std::time::Duration;
+
+use cp_sat::builder::{BoolVar, CpModelBuilder, LinearExpr};
+use cp_sat::ffi;
+use cp_sat::proto::constraint_proto::Constraint;
+use cp_sat::proto::{
+    CircuitConstraintProto, ConstraintProto, CpSolverResponse, CpSolverStatus, SatParameters,
+};
+use wildside_core::{
+    PointOfInterest, RouteLeg, RouteStop, Shortfall, SolveError, TravelTimeMatrix,
+};
+
+pub(crate) use penalty::Penalties;
+pub(crate) use schedule::Schedule;
+
+/// Fixed-point factor turning floating-point scores into CP-SAT coefficients.
+///
+/// Scores are rounded to three decimal places, which leaves ample headroom in
+/// `i64` for the summed objective.
+const SCORE_SCALE: f32 = 1000.0;
+
+/// Objective bonus, in score units, for taking the rest break.
+///
+/// It outweighs any achievable score, so the search gives up optional POIs
+/// to fit the break in, as the VRP solver does. Required POIs are
+/// constraints rather than rewards and are never given up.
+const REST_BREAK_BONUS: f32 = 1_000_000.0;
+
+/// Costs and rewards of a single-walker orienteering instance.
+pub(crate) struct Orienteering<'a> {
+    pub(crate) matrix: &'a TravelTimeMatrix,
+    /// Score of each candidate.
+    pub(crate) scores: &'a [f32],
+    /// Whether each candidate must be visited; missing entries are optional.
+    pub(crate) required: &'a [bool],
+    /// Time spent at each visited candidate; missing entries spend none.
+    pub(crate) dwell: &'a [Duration],
+    /// Longest walk allowed between consecutive stops, if any.
+    pub(crate) max_leg: Option<Duration>,
+    pub(crate) budget: Duration,
+    /// Node the route finishes at: the start for round trips.
+    pub(crate) end: usize,
+    /// When candidates may be visited and where the rest break may be taken.
+    pub(crate) schedule: Schedule<'a>,
+    /// Penalties for repeated themes and routes that double back.
+    pub(crate) penalties: Penalties<'a>,
+}
+
+/// How long and with which seed CP-SAT may search.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SearchLimits {
+    pub(crate) seed: u64,
+    pub(crate) time_limit: Option<Duration>,
+}
+
+/// An arc the route may take, with its travel time in whole seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Arc {
+    pub(crate) from: usize,
+    pub(crate) to: usize,
+    pub(crate) seconds: i64,
+}
+
+/// The route a search found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Found {
+    /// Candidate indices visited, in order.
+    pub(crate) stops: Vec<usize>,
+    /// Candidate at which the rest break is taken, if any.
+    pub(crate) rest_stop: Option<usize>,
+}
+
+/// The model's decision variables.
+struct Decisions {
+    /// Arcs the route may take, each with a literal true when it does.
+    arcs: Vec<(Arc, Literal)>,
+    /// A literal per candidate, true when the route visits it.
+    visits: Vec<Literal>,
+    /// Candidates where the rest break may be taken, each with a literal
+    /// true when it is.
+    rests: Vec<(usize, Literal)>,
+}
+
+impl Orienteering<'_> {
+    /// Search for the best route and return the candidates it visits.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::InfeasibleRequirement`] when no route visits
+    /// every required POI within the budget, [`SolveError::Infeasible`] when
+    /// no route reaches the end within it, [`SolveError::Timeout`] when the
+    /// time limit passes before any route is found, and
+    /// [`SolveError::Internal`] when CP-SAT rejects the model.
+    pub(crate) fn solve(&self, limits: SearchLimits) -> Result<Found, SolveError> {
+        let mut model = CpModelBuilder::default();
+        let decisions = self.decide(&mut model)?;
+        let circuit = self.circuit(&mut model, &decisions)?;
+        for (visit, required) in decisions.visits.iter().zip(self.required) {
+            if *required {
+                model.add_eq(visit.var, 1_i64);
+            }
+        }
+        let enforced = self.schedule_visits(&mut model, &decisions)?;
+        model.add_le(self.elapsed(&decisions)?, seconds(self.budget)?);
+        let penalties = self.penalize(&mut model, &decisions)?;
+        model.maximize(self.collected(&decisions) - penalties);
+
+        let mut proto = model.proto().clone();
+        enforced.apply(&mut proto);
+        proto.constraints.push(circuit);
+        let response = ffi::solve_with_parameters(&proto, &parameters(limits));
+        match response.status() {
+            CpSolverStatus::Optimal | CpSolverStatus::Feasible => {
+                Ok(decisions.found(&response, self.end))
+            }
+            CpSolverStatus::Infeasible if self.required.contains(&true) => {
+                Err(SolveError::InfeasibleRequirement)
+            }
+            CpSolverStatus::Infeasible => Err(SolveError::Infeasible(Shortfall::default())),
+            CpSolverStatus::Unknown => Err(SolveError::Timeout),
+            CpSolverStatus::ModelInvalid => {
+                Err(SolveError::internal("CP-SAT rejected the model as invalid"))
+            }
+        }
+    }
+
+    /// Add a literal for each arc, visit, and rest venue to `model`.
+    fn decide(&self, model: &mut CpModelBuilder) -> Result<Decisions, SolveError> {
+        let arcs = self
+            .arcs()
+            .into_iter()
+            .map(|arc| Ok((arc, Literal::new(model)?)))
+            .collect::<Result<_, SolveError>>()?;
+        let visits: Vec<Literal> = self
+            .scores
+            .iter()
+            .map(|_| Literal::new(model))
+            .collect::<Result<_, _>>()?;
+        let rests = self.rest_stops(model, &visits)?;
+        Ok(Decisions {
+            arcs,
+            visits,
+            rests,
+        })
+    }
+
+    /// Circuit constraint over every node: the route's arcs, a self-loop per
+    /// candidate that is true when it is skipped, and either the fixed arc
+    /// from the end back to the start or, for round trips, a self-loop
+    /// letting the start stay home.
+    ///
+    /// The start may only stay home when nothing is visited; otherwise the
+    /// circuit could close a subtour through the candidates alone and leave
+    /// the route without a way out of the start.
+    fn circuit(
+        &self,
+        model: &mut CpModelBuilder,
+        decisions: &Decisions,
+    ) -> Result<ConstraintProto, SolveError> {
+        let mut circuit = CircuitConstraintProto::default();
+        let mut push = |from: i32, to: i32, literal: i32| {
+            circuit.tails.push(from);
+            circuit.heads.push(to);
+            circuit.literals.push(literal);
+        };
+        for (arc, used) in &decisions.arcs {
+            push(node(arc.from)?, node(arc.to)?, used.index);
+        }
+        for (index, visit) in decisions.visits.iter().enumerate() {
+            let candidate = node(index + 1)?;
+            push(candidate, candidate, visit.negated());
+        }
+        let closing = Literal::new(model)?;
+        if self.end == 0 {
+            push(0, 0, closing.index);
+            for visit in &decisions.visits {
+                model.add_at_most_one([closing.var, visit.var]);
+            }
+        } else {
+            model.add_eq(closing.var, 1_i64);
+            push(node(self.end)?, 0, closing.index);
+        }
+        Ok(ConstraintProto {
+            constraint: Some(Constraint::Circuit(circuit)),
+            ..ConstraintProto::default()
+        })
+    }
+
+    /// Seconds the route spends walking, visiting, and resting; waiting for
+    /// opening hours is bounded by [`Self::schedule_visits`] instead.
+    fn elapsed(&self, decisions: &Decisions) -> Result<LinearExpr, SolveError> {
+        let mut elapsed: LinearExpr = decisions
+            .arcs
+            .iter()
+            .map(|(arc, used)| (arc.seconds, used.var))
+            .collect();
+        for (candidate, visit) in decisions.visits.iter().enumerate() {
+            elapsed += (seconds(self.dwell(candidate))?, visit.var);
+        }
+        let rest = seconds(self.schedule.rest_break)?;
+        elapsed.extend(decisions.rests.iter().map(|(_, taken)| (rest, taken.var)));
+        Ok(elapsed)
+    }
+
+    /// Score collected by the visits, plus the bonus for taking the break.
+    fn collected(&self, decisions: &Decisions) -> LinearExpr {
+        let mut collected: LinearExpr = decisions
+            .visits
+            .iter()
+            .zip(self.scores)
+            .map(|(visit, score)| (weight(*score), visit.var))
+            .collect();
+        collected.extend(
+            decisions
+                .rests
+                .iter()
+                .map(|(_, taken)| (weight(REST_BREAK_BONUS), taken.var)),
+        );
+        collected
+    }
+
+    /// Arcs between distinct locations that the route may take.
+    ///
+    /// Arcs into the start and out of a point-to-point route's end are left
+    /// out, as [`Self::circuit`] closes the route itself, as are arcs the
+    /// travel-time matrix marks unroutable and arcs longer than the leg
+    /// limit.
+    pub(crate) fn arcs(&self) -> Vec<Arc> {
+        let last = self.scores.len() + usize::from(self.end != 0);
+        let mut arcs = Vec::new();
+        for from in (0..=last).filter(|from| *from == 0 || *from != self.end) {
+            arcs.extend(
+                (1..=last)
+                    .filter(|to| *to != from)
+                    .chain((self.end == 0 && from != 0).then_some(0))
+                    .filter_map(|to| {
+                        let travel = self.matrix.get(from, to)?;
+                        let seconds = i64::try_from(travel.as_secs()).ok()?;
+                        let allowed = travel != Duration::MAX
+                            && self.max_leg.is_none_or(|limit| travel <= limit);
+                        allowed.then_some(Arc { from, to, seconds })
+                    }),
+            );
+        }
+        arcs
+    }
+
+    /// Break the `found` route, through the candidates at `route_pois`, into
+    /// legs from the start to the end.
+    ///
+    /// Each visit begins as early as its opening hours allow, and any wait
+    /// for them or rest break taken there counts as dwell. A round trip that
+    /// visits nothing has no legs.
+    pub(crate) fn legs(&self, found: &Found, route_pois: &[PointOfInterest]) -> Vec<RouteLeg> {
+        let mut legs = Vec::with_capacity(route_pois.len() + 1);
+        let mut elapsed = Duration::ZERO;
+        let mut from = (RouteStop::Start, 0);
+        for (candidate, poi) in found.stops.iter().zip(route_pois) {
+            let travel_duration = self.matrix.get(from.1, candidate + 1).unwrap_or_default();
+            let arrival = elapsed + travel_duration;
+            elapsed = self.schedule.visit_start(*candidate, arrival) + self.dwell(*candidate);
+            if found.rest_stop == Some(*candidate) {
+                elapsed = self.schedule.rest_end(*candidate, elapsed);
+            }
+            legs.push(RouteLeg {
+                from: from.0,
+                to: RouteStop::Poi(poi.id),
+                travel_duration,
+                dwell: Some(elapsed.saturating_sub(arrival)),
+                cumulative_duration: elapsed,
+                arrival_at: None,
+            });
+            from = (RouteStop::Poi(poi.id), candidate + 1);
+        }
+        if legs.is_empty() && self.end == 0 {
+            return legs;
+        }
+        let travel_duration = self.matrix.get(from.1, self.end).unwrap_or_default();
+        legs.push(RouteLeg {
+            from: from.0,
+            to: RouteStop::End,
+            travel_duration,
+            dwell: None,
+            cumulative_duration: elapsed + travel_duration,
+            arrival_at: None,
+        });
+        legs
+    }
+
+    /// Time spent at `candidate` when it is visited.
+    fn dwell(&self, candidate: usize) -> Duration {
+        self.dwell.get(candidate).copied().unwrap_or_default()
+    }
+}
+
+impl Decisions {
+    /// Read the route `response` found, finishing at `end`.
+    fn found(&self, response: &CpSolverResponse, end: usize) -> Found {
+        let used: Vec<Arc> = self
+            .arcs
+            .iter()
+            .filter(|(_, used)| used.var.solution_value(response))
+            .map(|(arc, _)| *arc)
+            .collect();
+        Found {
+            stops: tour(&used, end),
+            rest_stop: self
+                .rests
+                .iter()
+                .find(|(_, taken)| taken.var.solution_value(response))
+                .map(|(candidate, _)| *candidate),
+        }
+    }
+}
+
+/// A Boolean variable together with its index in the model proto.
+///
+/// `CpModelBuilder` has no circuit constraint, so [`Orienteering::circuit`]
+/// writes one into the proto directly, referring to literals by index.
+#[derive(Debug, Clone, Copy)]
+struct Literal {
+    var: BoolVar,
+    index: i32,
+}
+
+impl Literal {
+    /// Add a Boolean variable to `model`.
+    fn new(model: &mut CpModelBuilder) -> Result<Self, SolveError> {
+        let var = model.new_bool_var();
+        let index = model.proto().variables.len().saturating_sub(1);
+        Ok(Self {
+            var,
+            index: node(index)?,
+        })
+    }
+
+    /// Proto index of the variable's negation.
+    const fn negated(self) -> i32 {
+        -self.index - 1
+    }
+}
+
+/// Candidate indices visited by following `used` arcs from the start until
+/// the route reaches `end`.
+pub(crate) fn tour(used: &[Arc], end: usize) -> Vec<usize> {
+    let mut stops = Vec::new();
+    let mut at = 0;
+    while let Some(arc) = used.iter().find(|arc| arc.from == at) {
+        if arc.to == end || stops.len() > used.len() {
+            break;
+        }
+        stops.push(arc.to - 1);
+        at = arc.to;
+    }
+    stops
+}
+
+/// Solver parameters for a single-threaded, seeded, optionally time-limited
+/// search, so identical requests return identical routes when the search
+/// completes.
+fn parameters(limits: SearchLimits) -> SatParameters {
+    SatParameters {
+        random_seed: Some(i32::try_from(limits.seed & 0x7fff_ffff).unwrap_or_default()),
+        num_search_workers: Some(1),
+        max_time_in_seconds: limits.time_limit.map(|limit| limit.as_secs_f64()),
+        ..SatParameters::default()
+    }
+}
+
+/// Objective coefficient for `score`.
+#[expect(
+    clippy::float_arithmetic,
+    clippy::cast_possible_truncation,
+    reason = "scores are scaled to fixed point and rounded for CP-SAT"
+)]
+pub(crate) fn weight(score: f32) -> i64 {
+    (score.max(0.0) * SCORE_SCALE).round() as i64
+}
+
+fn node(index: usize) -> Result<i32, SolveError> {
+    i32::try_from(index).map_err(SolveError::internal)
+}
+
+fn seconds(duration: Duration) -> Result<i64, SolveError> {
+    i64::try_from(duration.as_secs()).map_err(SolveError::internal)
+}
+
+#[cfg(test)]
+mod tests;
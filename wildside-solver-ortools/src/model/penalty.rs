@@ -0,0 +1,147 @@
+//! Penalties for repeated themes and routes that double back.
+//!
+//! Each theme shared by several candidates gets an integer variable counting
+//! its visits beyond the first, and every arc between two candidates of the
+//! same theme costs the consecutive penalty. A turn is a pair of arcs into
+//! and out of a candidate, so each pair [`OverlapPolicy`] would penalize gets
+//! a literal that must hold when the route takes both.
+
+use cp_sat::builder::{CpModelBuilder, LinearExpr};
+use geo::Coord;
+use wildside_core::{DiversityPolicy, OverlapPolicy, SolveError, Theme};
+
+use super::{Arc, Decisions, Orienteering, SCORE_SCALE};
+
+/// Penalties the objective charges, as configured for the route.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Penalties<'a> {
+    pub(crate) diversity: DiversityPolicy,
+    /// Theme of each candidate; missing entries have none.
+    pub(crate) themes: &'a [Option<Theme>],
+    pub(crate) overlap: OverlapPolicy,
+    /// Position of each node; turns at nodes without one cost nothing.
+    pub(crate) positions: &'a [Coord<f64>],
+}
+
+impl Penalties<'_> {
+    /// Theme of the candidate at `node`, if any.
+    fn theme(&self, node: usize) -> Option<&Theme> {
+        self.themes.get(node.checked_sub(1)?)?.as_ref()
+    }
+
+    /// Objective cost of arriving at a candidate along `into` and leaving
+    /// along `out`.
+    fn turn(&self, into: Arc, out: Arc) -> i64 {
+        let (Some(from), Some(via), Some(to)) = (
+            self.positions.get(into.from),
+            self.positions.get(into.to),
+            self.positions.get(out.to),
+        ) else {
+            return 0;
+        };
+        penalty_weight(self.overlap.turn_penalty(*from, *via, *to))
+    }
+}
+
+impl Orienteering<'_> {
+    /// Add the variables the penalties need to `model` and return their
+    /// total cost.
+    pub(super) fn penalize(
+        &self,
+        model: &mut CpModelBuilder,
+        decisions: &Decisions,
+    ) -> Result<LinearExpr, SolveError> {
+        let mut penalties = self.repeats(model, decisions)?;
+        penalties += self.consecutive(decisions);
+        penalties += self.turns(model, decisions);
+        Ok(penalties)
+    }
+
+    /// Cost of visiting each theme more than once.
+    fn repeats(
+        &self,
+        model: &mut CpModelBuilder,
+        decisions: &Decisions,
+    ) -> Result<LinearExpr, SolveError> {
+        let cost = penalty_weight(self.penalties.diversity.repeat_penalty);
+        let mut repeats = LinearExpr::default();
+        let mut themes: Vec<&Theme> = Vec::new();
+        for theme in self.penalties.themes.iter().flatten() {
+            if cost == 0 || themes.contains(&theme) {
+                continue;
+            }
+            themes.push(theme);
+            let visits: LinearExpr = decisions
+                .visits
+                .iter()
+                .zip(self.penalties.themes)
+                .filter(|(_, candidate)| candidate.as_ref() == Some(theme))
+                .map(|(visit, _)| visit.var)
+                .collect();
+            let sharing = self
+                .penalties
+                .themes
+                .iter()
+                .filter(|candidate| candidate.as_ref() == Some(theme))
+                .count();
+            let most = i64::try_from(sharing.saturating_sub(1)).map_err(SolveError::internal)?;
+            let repeated = model.new_int_var([(0, most)]);
+            model.add_ge(LinearExpr::from(repeated) - visits, -1_i64);
+            repeats += (cost, repeated);
+        }
+        Ok(repeats)
+    }
+
+    /// Cost of walking straight between two candidates of the same theme.
+    fn consecutive(&self, decisions: &Decisions) -> LinearExpr {
+        let cost = penalty_weight(self.penalties.diversity.consecutive_penalty);
+        decisions
+            .arcs
+            .iter()
+            .filter(|(arc, _)| {
+                cost > 0
+                    && self
+                        .penalties
+                        .theme(arc.from)
+                        .is_some_and(|theme| self.penalties.theme(arc.to) == Some(theme))
+            })
+            .map(|(_, used)| (cost, used.var))
+            .collect()
+    }
+
+    /// Cost of the turns taken at each candidate.
+    fn turns(&self, model: &mut CpModelBuilder, decisions: &Decisions) -> LinearExpr {
+        let mut turns = LinearExpr::default();
+        if !self.penalties.overlap.is_enabled() {
+            return turns;
+        }
+        let pairs = decisions.arcs.iter().flat_map(|into| {
+            decisions
+                .arcs
+                .iter()
+                .filter(move |out| into.0.to != 0 && out.0.from == into.0.to)
+                .map(move |out| (into, out))
+        });
+        for ((into, arrives), (out, leaves)) in pairs {
+            let cost = self.penalties.turn(*into, *out);
+            if cost == 0 {
+                continue;
+            }
+            let turned = model.new_bool_var();
+            model.add_le(LinearExpr::from(arrives.var) + leaves.var - turned, 1_i64);
+            turns += (cost, turned);
+        }
+        turns
+    }
+}
+
+/// Objective cost of `penalty`, in the fixed point of
+/// [`weight`](super::weight).
+#[expect(
+    clippy::float_arithmetic,
+    clippy::cast_possible_truncation,
+    reason = "penalties are scaled to fixed point and rounded for CP-SAT"
+)]
+fn penalty_weight(penalty: f64) -> i64 {
+    (penalty.max(0.0) * f64::from(SCORE_SCALE)).round() as i64
+}
@@ -0,0 +1,291 @@
+//! Start times for visits bound by opening hours or a rest break.
+//!
+//! Each candidate is given a start-time variable within the budget. Every arc
+//! the route takes begins the next visit no earlier than the previous one
+//! ends, rest break included, plus the walk between them, and the route must
+//! reach its end within the budget, waits and all. A candidate with opening
+//! windows chooses one of them exactly when it is visited, and its visit
+//! begins inside it; the rest break chooses one of its venue's windows in
+//! the same way, beginning as the visit there ends.
+//!
+//! `CpModelBuilder` cannot make a constraint conditional, so the literals
+//! each one depends on are collected in [`Enforced`] and written into the
+//! model proto before solving.
+
+use std::ops::Range;
+use std::time::Duration;
+
+use cp_sat::builder::{CpModelBuilder, IntVar, LinearExpr};
+use cp_sat::proto::CpModelProto;
+use wildside_core::SolveError;
+
+use super::{Decisions, Literal, Orienteering, seconds};
+
+/// When candidates may be visited and where the rest break may be taken,
+/// as offsets from the start of the walk.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Schedule<'a> {
+    /// Windows in which a visit to each candidate may begin; `None`, or a
+    /// missing entry, leaves the candidate unconstrained.
+    pub(crate) windows: &'a [Option<Vec<Range<Duration>>>],
+    /// Length of the rest break, if the route reserves one.
+    pub(crate) rest_break: Duration,
+    /// Windows in which the rest break may begin at each candidate; `None`,
+    /// a missing entry, or no windows at all rule the candidate out.
+    pub(crate) rest_windows: &'a [Option<Vec<Range<Duration>>>],
+}
+
+impl Schedule<'_> {
+    /// Whether any visit needs a start time.
+    fn is_timed(&self) -> bool {
+        self.windows
+            .iter()
+            .chain(self.rest_windows)
+            .any(Option::is_some)
+    }
+
+    fn windows(&self, candidate: usize) -> Option<&[Range<Duration>]> {
+        self.windows.get(candidate)?.as_deref()
+    }
+
+    fn rest_windows(&self, candidate: usize) -> Option<&[Range<Duration>]> {
+        self.rest_windows.get(candidate)?.as_deref()
+    }
+
+    /// When a visit to `candidate`, reached at `arrival`, begins: at once, or
+    /// as its next window opens.
+    pub(crate) fn visit_start(&self, candidate: usize, arrival: Duration) -> Duration {
+        earliest(self.windows(candidate), arrival)
+    }
+
+    /// When a rest break at `candidate`, which may begin at `ready`, ends.
+    pub(crate) fn rest_end(&self, candidate: usize, ready: Duration) -> Duration {
+        earliest(self.rest_windows(candidate), ready) + self.rest_break
+    }
+}
+
+/// Constraints that hold only while a literal does, by index in the model
+/// proto.
+#[derive(Debug, Default)]
+pub(super) struct Enforced(Vec<(usize, i32)>);
+
+impl Enforced {
+    /// Enforce the constraint last added to `model` only while `literal`
+    /// holds.
+    fn last(&mut self, model: &CpModelBuilder, literal: Literal) {
+        if let Some(index) = model.proto().constraints.len().checked_sub(1) {
+            self.0.push((index, literal.index));
+        }
+    }
+
+    /// Write the enforcement literals into `proto`, built from the model
+    /// they were recorded against.
+    pub(super) fn apply(&self, proto: &mut CpModelProto) {
+        for (index, literal) in &self.0 {
+            if let Some(constraint) = proto.constraints.get_mut(*index) {
+                constraint.enforcement_literal.push(*literal);
+            }
+        }
+    }
+}
+
+/// Start-time variables of a timed route and the constraints enforced on
+/// them.
+struct Timetable {
+    /// When each candidate's visit begins.
+    starts: Vec<IntVar>,
+    /// When the route reaches its end.
+    finish: IntVar,
+    enforced: Enforced,
+}
+
+impl Orienteering<'_> {
+    /// Literals for taking the rest break at each venue, of which at most one
+    /// holds, and only at a visited venue.
+    pub(super) fn rest_stops(
+        &self,
+        model: &mut CpModelBuilder,
+        visits: &[Literal],
+    ) -> Result<Vec<(usize, Literal)>, SolveError> {
+        let mut rests = Vec::new();
+        for (candidate, visit) in visits.iter().enumerate() {
+            if self
+                .schedule
+                .rest_windows(candidate)
+                .is_some_and(|windows| !windows.is_empty())
+            {
+                let taken = Literal::new(model)?;
+                model.add_le(taken.var, visit.var);
+                rests.push((candidate, taken));
+            }
+        }
+        if rests.len() > 1 {
+            model.add_at_most_one(rests.iter().map(|(_, taken)| taken.var));
+        }
+        Ok(rests)
+    }
+
+    /// Give each visit a start time when the schedule needs one, returning
+    /// the constraints that hold only while a literal does.
+    pub(super) fn schedule_visits(
+        &self,
+        model: &mut CpModelBuilder,
+        decisions: &Decisions,
+    ) -> Result<Enforced, SolveError> {
+        if !self.schedule.is_timed() {
+            return Ok(Enforced::default());
+        }
+        let horizon = seconds(self.budget)?;
+        let mut timetable = Timetable {
+            starts: decisions
+                .visits
+                .iter()
+                .map(|_| model.new_int_var([(0, horizon)]))
+                .collect(),
+            finish: model.new_int_var([(0, horizon)]),
+            enforced: Enforced::default(),
+        };
+        self.time_arcs(model, decisions, &mut timetable)?;
+        self.open_visit_windows(model, decisions, &mut timetable)?;
+        self.open_rest_windows(model, decisions, &mut timetable)?;
+        Ok(timetable.enforced)
+    }
+
+    /// Begin each visit, or finish the route, no earlier than the walk along
+    /// the arc used to reach it allows.
+    fn time_arcs(
+        &self,
+        model: &mut CpModelBuilder,
+        decisions: &Decisions,
+        timetable: &mut Timetable,
+    ) -> Result<(), SolveError> {
+        for (arc, used) in &decisions.arcs {
+            let reached = if arc.to == self.end {
+                Some(timetable.finish)
+            } else {
+                arc.to
+                    .checked_sub(1)
+                    .and_then(|candidate| timetable.starts.get(candidate))
+                    .copied()
+            };
+            let Some(arrival) = reached else {
+                continue;
+            };
+            let departure = self.departure(arc.from, decisions, timetable)?;
+            model.add_ge(LinearExpr::from(arrival) - departure, arc.seconds);
+            timetable.enforced.last(model, *used);
+        }
+        Ok(())
+    }
+
+    /// When the route leaves `node`: at once from the start, otherwise as the
+    /// visit there, and any rest break taken there, ends.
+    fn departure(
+        &self,
+        node: usize,
+        decisions: &Decisions,
+        timetable: &Timetable,
+    ) -> Result<LinearExpr, SolveError> {
+        let Some((candidate, begins)) = node
+            .checked_sub(1)
+            .and_then(|candidate| Some((candidate, *timetable.starts.get(candidate)?)))
+        else {
+            return Ok(LinearExpr::default());
+        };
+        let mut leaves = LinearExpr::from(begins) + seconds(self.dwell(candidate))?;
+        let rest = seconds(self.schedule.rest_break)?;
+        leaves.extend(
+            decisions
+                .rests
+                .iter()
+                .filter(|(venue, _)| *venue == candidate)
+                .map(|(_, taken)| (rest, taken.var)),
+        );
+        Ok(leaves)
+    }
+
+    /// Begin each visit to a candidate keeping opening hours within one of
+    /// its windows.
+    fn open_visit_windows(
+        &self,
+        model: &mut CpModelBuilder,
+        decisions: &Decisions,
+        timetable: &mut Timetable,
+    ) -> Result<(), SolveError> {
+        for (candidate, visit) in decisions.visits.iter().enumerate() {
+            let (Some(windows), Some(begins)) = (
+                self.schedule.windows(candidate),
+                timetable.starts.get(candidate).copied(),
+            ) else {
+                continue;
+            };
+            let chosen = choose_window(
+                model,
+                &mut timetable.enforced,
+                &LinearExpr::from(begins),
+                windows,
+            )?;
+            model.add_eq(chosen, visit.var);
+        }
+        Ok(())
+    }
+
+    /// Begin the rest break, as the visit to its venue ends, within one of
+    /// the venue's windows.
+    fn open_rest_windows(
+        &self,
+        model: &mut CpModelBuilder,
+        decisions: &Decisions,
+        timetable: &mut Timetable,
+    ) -> Result<(), SolveError> {
+        for (candidate, taken) in &decisions.rests {
+            let (Some(windows), Some(begins)) = (
+                self.schedule.rest_windows(*candidate),
+                timetable.starts.get(*candidate).copied(),
+            ) else {
+                continue;
+            };
+            let ready = LinearExpr::from(begins) + seconds(self.dwell(*candidate))?;
+            let chosen = choose_window(model, &mut timetable.enforced, &ready, windows)?;
+            model.add_eq(chosen, taken.var);
+        }
+        Ok(())
+    }
+}
+
+/// Add a literal per window, each placing `begins` inside its window while
+/// it holds, and return their sum so the caller can tie the choice to a
+/// visit.
+///
+/// With no windows the sum is zero, ruling the visit out.
+fn choose_window(
+    model: &mut CpModelBuilder,
+    enforced: &mut Enforced,
+    begins: &LinearExpr,
+    windows: &[Range<Duration>],
+) -> Result<LinearExpr, SolveError> {
+    let mut chosen = LinearExpr::default();
+    for window in windows {
+        let within = Literal::new(model)?;
+        model.add_linear_constraint(
+            begins.clone(),
+            [(seconds(window.start)?, seconds(window.end)?)],
+        );
+        enforced.last(model, within);
+        chosen += within.var;
+    }
+    Ok(chosen)
+}
+
+/// The earliest moment from `at` within one of `windows`, or `at` itself
+/// when there are none to keep.
+fn earliest(windows: Option<&[Range<Duration>]>, at: Duration) -> Duration {
+    windows
+        .and_then(|open| {
+            open.iter()
+                .filter(|window| window.end >= at)
+                .map(|window| window.start.max(at))
+                .min()
+        })
+        .unwrap_or(at)
+}
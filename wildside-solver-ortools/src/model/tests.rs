@@ -0,0 +1,300 @@
+//! Tests for the CP-SAT orienteering model.
+
+use super::*;
+use geo::Coord;
+use rstest::{fixture, rstest};
+use std::ops::Range;
+use wildside_core::{DiversityPolicy, OverlapPolicy, Theme};
+
+/// Start and candidates on a line, a minute apart.
+#[fixture]
+fn line() -> TravelTimeMatrix {
+    TravelTimeMatrix::from_fn(4, |from, to| {
+        Duration::from_mins(from.abs_diff(to).try_into().unwrap_or(u64::MAX))
+    })
+}
+
+fn instance<'a>(
+    matrix: &'a TravelTimeMatrix,
+    scores: &'a [f32],
+    budget: Duration,
+) -> Orienteering<'a> {
+    Orienteering {
+        matrix,
+        scores,
+        required: &[],
+        dwell: &[],
+        max_leg: None,
+        budget,
+        end: 0,
+        schedule: Schedule::default(),
+        penalties: Penalties::default(),
+    }
+}
+
+fn limits() -> SearchLimits {
+    SearchLimits {
+        seed: 1,
+        time_limit: Some(Duration::from_secs(5)),
+    }
+}
+
+#[rstest]
+fn round_trips_may_return_to_the_start_from_any_candidate(line: TravelTimeMatrix) {
+    let arcs = instance(&line, &[1.0, 1.0], Duration::from_hours(1)).arcs();
+    assert!(arcs.contains(&Arc {
+        from: 2,
+        to: 0,
+        seconds: 120,
+    }));
+    assert!(!arcs.iter().any(|arc| arc.from == arc.to));
+}
+
+#[rstest]
+fn point_to_point_routes_leave_the_end_only_through_the_circuit(line: TravelTimeMatrix) {
+    let arcs = Orienteering {
+        end: 3,
+        ..instance(&line, &[1.0, 1.0], Duration::from_hours(1))
+    }
+    .arcs();
+    assert!(arcs.iter().all(|arc| arc.from != 3 && arc.to != 0));
+    assert!(arcs.contains(&Arc {
+        from: 0,
+        to: 3,
+        seconds: 180,
+    }));
+}
+
+#[rstest]
+fn arcs_beyond_the_leg_limit_are_left_out(line: TravelTimeMatrix) {
+    let arcs = Orienteering {
+        max_leg: Some(Duration::from_mins(1)),
+        ..instance(&line, &[1.0, 1.0, 1.0], Duration::from_hours(1))
+    }
+    .arcs();
+    assert!(!arcs.is_empty());
+    assert!(arcs.iter().all(|arc| arc.seconds <= 60));
+}
+
+#[rstest]
+fn point_to_point_routes_beyond_the_leg_limit_are_infeasible() {
+    let distant = TravelTimeMatrix::from_fn(3, |from, to| {
+        if from == to {
+            Duration::ZERO
+        } else {
+            Duration::from_mins(2)
+        }
+    });
+    let result = Orienteering {
+        end: 2,
+        max_leg: Some(Duration::from_mins(1)),
+        ..instance(&distant, &[1.0], Duration::from_hours(1))
+    }
+    .solve(limits());
+    assert!(matches!(result, Err(SolveError::Infeasible(_))));
+}
+
+#[rstest]
+fn tours_follow_the_used_arcs_to_the_end() {
+    let arc = |from, to| Arc {
+        from,
+        to,
+        seconds: 60,
+    };
+    let used = [arc(2, 1), arc(0, 2), arc(1, 3)];
+    assert_eq!(tour(&used, 3), vec![1, 0]);
+}
+
+#[rstest]
+fn weights_keep_three_decimal_places() {
+    assert_eq!(weight(0.1234), 123);
+    assert_eq!(weight(-1.0), 0);
+}
+
+#[rstest]
+fn the_best_scoring_route_within_the_budget_is_chosen(line: TravelTimeMatrix) {
+    // With a minute at each stop, the far stop alone takes 7 minutes and
+    // outscores both near ones together.
+    let stops = Orienteering {
+        dwell: &[Duration::from_mins(1); 3],
+        ..instance(&line, &[1.0, 1.0, 3.0], Duration::from_mins(7))
+    }
+    .solve(limits())
+    .expect("solve")
+    .stops;
+    assert_eq!(stops, vec![2]);
+}
+
+#[rstest]
+fn required_stops_are_visited(line: TravelTimeMatrix) {
+    let mut stops = Orienteering {
+        required: &[false, true, false],
+        ..instance(&line, &[5.0, 0.0, 0.0], Duration::from_mins(4))
+    }
+    .solve(limits())
+    .expect("solve")
+    .stops;
+    stops.sort_unstable();
+    assert_eq!(stops, vec![0, 1]);
+}
+
+#[rstest]
+fn unreachable_requirements_are_infeasible(line: TravelTimeMatrix) {
+    let result = Orienteering {
+        required: &[false, false, true],
+        ..instance(&line, &[1.0, 1.0, 1.0], Duration::from_mins(5))
+    }
+    .solve(limits());
+    assert!(matches!(result, Err(SolveError::InfeasibleRequirement)));
+}
+
+#[rstest]
+fn unreachable_ends_are_infeasible(line: TravelTimeMatrix) {
+    let result = Orienteering {
+        end: 3,
+        ..instance(&line, &[1.0, 1.0], Duration::from_mins(2))
+    }
+    .solve(limits());
+    assert!(matches!(result, Err(SolveError::Infeasible(_))));
+}
+
+#[rstest]
+fn point_to_point_routes_visit_stops_on_the_way(line: TravelTimeMatrix) {
+    let stops = Orienteering {
+        end: 3,
+        ..instance(&line, &[1.0, 1.0], Duration::from_mins(3))
+    }
+    .solve(limits())
+    .expect("solve")
+    .stops;
+    assert_eq!(stops, vec![0, 1]);
+}
+
+/// Start and candidates all a minute apart.
+#[fixture]
+fn uniform() -> TravelTimeMatrix {
+    TravelTimeMatrix::from_fn(4, |from, to| {
+        if from == to {
+            Duration::ZERO
+        } else {
+            Duration::from_mins(1)
+        }
+    })
+}
+
+#[rstest]
+fn visits_wait_for_their_window(line: TravelTimeMatrix) {
+    // The far stop opens only after the ten-minute budget runs out.
+    let windows = [
+        None,
+        None,
+        Some(vec![Duration::from_mins(20)..Duration::from_mins(30)]),
+    ];
+    let mut stops = Orienteering {
+        schedule: Schedule {
+            windows: &windows,
+            ..Schedule::default()
+        },
+        ..instance(&line, &[1.0, 1.0, 5.0], Duration::from_mins(10))
+    }
+    .solve(limits())
+    .expect("solve")
+    .stops;
+    stops.sort_unstable();
+    assert_eq!(stops, vec![0, 1]);
+}
+
+#[rstest]
+fn waits_count_as_dwell(line: TravelTimeMatrix) {
+    let windows = [Some(vec![Duration::from_mins(5)..Duration::from_mins(10)])];
+    let legs = Orienteering {
+        schedule: Schedule {
+            windows: &windows,
+            ..Schedule::default()
+        },
+        ..instance(&line, &[1.0], Duration::from_mins(10))
+    }
+    .legs(
+        &Found {
+            stops: vec![0],
+            rest_stop: None,
+        },
+        &[PointOfInterest::with_empty_tags(
+            1,
+            Coord { x: 0.0, y: 0.0 },
+        )],
+    );
+    let dwell: Vec<Option<Duration>> = legs.iter().map(|leg| leg.dwell).collect();
+    assert_eq!(dwell, vec![Some(Duration::from_mins(4)), None]);
+    assert_eq!(
+        legs.last().map(|leg| leg.cumulative_duration),
+        Some(Duration::from_mins(6))
+    );
+}
+
+#[rstest]
+fn rest_breaks_outweigh_optional_stops(uniform: TravelTimeMatrix) {
+    // Resting at the second stop leaves no time for the first.
+    let rest_windows: [Option<Vec<Range<Duration>>>; 2] =
+        [None, Some(vec![Duration::ZERO..Duration::from_hours(1)])];
+    let found = Orienteering {
+        schedule: Schedule {
+            rest_break: Duration::from_mins(10),
+            rest_windows: &rest_windows,
+            ..Schedule::default()
+        },
+        ..instance(&uniform, &[1.0, 0.5], Duration::from_mins(12))
+    }
+    .solve(limits())
+    .expect("solve");
+    assert_eq!(
+        found,
+        Found {
+            stops: vec![1],
+            rest_stop: Some(1),
+        }
+    );
+}
+
+#[rstest]
+fn repeat_penalties_trade_a_theme_for_another(uniform: TravelTimeMatrix) {
+    let themes = [Some(Theme::Art), Some(Theme::Art), Some(Theme::Food)];
+    let mut stops = Orienteering {
+        penalties: Penalties {
+            diversity: DiversityPolicy::new(0.5, 0.0),
+            themes: &themes,
+            ..Penalties::default()
+        },
+        ..instance(&uniform, &[1.0, 1.0, 0.8], Duration::from_mins(3))
+    }
+    .solve(limits())
+    .expect("solve")
+    .stops;
+    stops.sort_unstable();
+    assert_eq!(stops.len(), 2);
+    assert!(stops.contains(&2), "stops {stops:?}");
+}
+
+#[rstest]
+fn backtracking_penalties_prefer_loops(uniform: TravelTimeMatrix) {
+    // Walking out to the second stop and back turns straight round there.
+    let positions = [
+        Coord { x: 0.0, y: 0.0 },
+        Coord { x: 0.001, y: 0.0 },
+        Coord { x: 0.002, y: 0.0 },
+        Coord { x: 0.001, y: 0.001 },
+    ];
+    let mut stops = Orienteering {
+        penalties: Penalties {
+            overlap: OverlapPolicy::new(1.0),
+            positions: &positions,
+            ..Penalties::default()
+        },
+        ..instance(&uniform, &[1.1, 1.0, 0.95], Duration::from_mins(3))
+    }
+    .solve(limits())
+    .expect("solve")
+    .stops;
+    stops.sort_unstable();
+    assert_eq!(stops, vec![0, 2]);
+}
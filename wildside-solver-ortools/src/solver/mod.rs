@@ -0,0 +1,411 @@
+//! `OrtoolsSolver` implementation.
+//!
+//! Candidates are the POIs within walking reach of the start or end, as for
+//! the greedy solver, less any closed for the whole walk. With the `cp-sat`
+//! feature the route is then chosen by an exact [CP-SAT model](crate::model)
+//! over a single travel-time matrix; without it every valid request fails
+//! with [`SolveError::NotImplemented`].
+
+#[cfg(feature = "cp-sat")]
+use std::ops::Range;
+use std::time::Duration;
+#[cfg(feature = "cp-sat")]
+use std::time::Instant;
+
+#[cfg(feature = "cp-sat")]
+use geo::Coord;
+#[cfg(feature = "cp-sat")]
+use wildside_core::{
+    Diagnostics, OpeningHours, PointOfInterest, RestBreak, Route, Shortfall, Theme, WeekTime,
+};
+use wildside_core::{
+    DiversityPolicy, DwellTimePolicy, OverlapPolicy, PoiStore, Scorer, SolveError, SolveRequest,
+    SolveResponse, Solver, TravelTimeProvider,
+};
+
+#[cfg(feature = "cp-sat")]
+use crate::model::{Found, Orienteering, Penalties, Schedule, SearchLimits};
+
+/// Configuration for [`OrtoolsSolver`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrtoolsSolverConfig {
+    /// Average walking speed used to derive the candidate search radius.
+    pub average_speed_kmh: f64,
+    /// Time spent at each visited POI, counted against the duration budget.
+    pub dwell: DwellTimePolicy,
+    /// Penalties discouraging routes that keep visiting the same theme.
+    pub diversity: DiversityPolicy,
+    /// Penalties discouraging routes that double back on themselves.
+    pub overlap: OverlapPolicy,
+    /// Wall-clock limit on the CP-SAT search.
+    ///
+    /// When the limit passes the best route found so far is returned, which
+    /// may not be optimal. When a request sets
+    /// [`SolveRequest::max_solve_ms`], the tighter of the two applies. `None`
+    /// searches until optimality is proven.
+    pub max_solve_duration: Option<Duration>,
+}
+
+impl Default for OrtoolsSolverConfig {
+    fn default() -> Self {
+        Self {
+            average_speed_kmh: 5.0,
+            dwell: DwellTimePolicy::default(),
+            diversity: DiversityPolicy::default(),
+            overlap: OverlapPolicy::default(),
+            max_solve_duration: Some(Duration::from_secs(10)),
+        }
+    }
+}
+
+/// Exact solver backed by the OR-Tools CP-SAT constraint solver.
+///
+/// Each request becomes an Orienteering Problem: every candidate is an
+/// optional node of a single circuit from the start to the end, travel and
+/// visit time must fit the budget, and the objective maximizes the summed
+/// score less the configured diversity and backtracking penalties, as in the
+/// `vrp-core` backed `VrpSolver`. Visits last as long as
+/// [`OrtoolsSolverConfig::dwell`] says. Required POIs are forced onto the
+/// route, and arcs longer than [`SolveRequest::max_leg_minutes`] are left out
+/// of the circuit. Searches are single-threaded and seeded from
+/// [`SolveRequest::seed`].
+///
+/// When the request has a start time, POIs are visited only while open,
+/// waiting for them to open if need be, and a requested
+/// [`SolveRequest::rest_break`] is taken at a café or similar venue on the
+/// route, extending the visit there, whenever one fits; like the VRP solver,
+/// the search gives up optional POIs to make room for it. Requests carrying
+/// [`SolveRequest::visited`] re-plan the rest of the tour and return the
+/// whole of it, visited stops first.
+///
+/// The model is compiled only with the `cp-sat` feature, which links the
+/// native OR-Tools library.
+#[cfg_attr(
+    not(feature = "cp-sat"),
+    expect(
+        dead_code,
+        reason = "the solver's inputs are only read by the CP-SAT model"
+    )
+)]
+pub struct OrtoolsSolver<S, T, C>
+where
+    S: PoiStore,
+    T: TravelTimeProvider,
+    C: Scorer,
+{
+    store: S,
+    travel_time_provider: T,
+    scorer: C,
+    config: OrtoolsSolverConfig,
+}
+
+impl<S, T, C> OrtoolsSolver<S, T, C>
+where
+    S: PoiStore,
+    T: TravelTimeProvider,
+    C: Scorer,
+{
+    /// Construct a solver using default configuration.
+    pub fn new(store: S, travel_time_provider: T, scorer: C) -> Self {
+        Self::with_config(
+            store,
+            travel_time_provider,
+            scorer,
+            OrtoolsSolverConfig::default(),
+        )
+    }
+
+    /// Construct a solver with explicit configuration.
+    pub const fn with_config(
+        store: S,
+        travel_time_provider: T,
+        scorer: C,
+        config: OrtoolsSolverConfig,
+    ) -> Self {
+        Self {
+            store,
+            travel_time_provider,
+            scorer,
+            config,
+        }
+    }
+}
+
+impl<S, T, C> Solver for OrtoolsSolver<S, T, C>
+where
+    S: PoiStore + Send + Sync,
+    T: TravelTimeProvider + Send + Sync,
+    C: Scorer + Send + Sync,
+{
+    #[cfg(feature = "cp-sat")]
+    fn solve(&self, request: &SolveRequest) -> Result<SolveResponse, SolveError> {
+        let started_at = Instant::now();
+        request.validate()?;
+        let response = match request.remainder() {
+            Some(remainder) => {
+                let planned = self.solve_planned(&remainder, started_at)?;
+                self.lock_prefix(request, planned)?
+            }
+            None => self.solve_planned(request, started_at)?,
+        };
+        request.ensure_minimums(response)
+    }
+
+    #[cfg(not(feature = "cp-sat"))]
+    fn solve(&self, request: &SolveRequest) -> Result<SolveResponse, SolveError> {
+        request.validate()?;
+        Err(SolveError::NotImplemented)
+    }
+}
+
+#[cfg(feature = "cp-sat")]
+impl<S, T, C> OrtoolsSolver<S, T, C>
+where
+    S: PoiStore,
+    T: TravelTimeProvider,
+    C: Scorer,
+{
+    /// Plan the route for `request`, ignoring any stops already visited.
+    fn solve_planned(
+        &self,
+        request: &SolveRequest,
+        started_at: Instant,
+    ) -> Result<SolveResponse, SolveError> {
+        let (candidates, scores) = self.candidates(request)?;
+        request.ensure_required(&candidates)?;
+        let provider = request.accessibility.provider(&self.travel_time_provider)?;
+        if candidates.is_empty() && request.end.is_none() {
+            return Ok(stay_at_start(request, started_at));
+        }
+        let locations = request.matrix_locations(&candidates);
+        let matrix = provider.get_travel_time_matrix(&locations)?;
+        let required: Vec<bool> = candidates
+            .iter()
+            .map(|poi| request.required_poi_ids.contains(&poi.id))
+            .collect();
+        let dwell: Vec<Duration> = candidates
+            .iter()
+            .map(|poi| self.config.dwell.dwell_time(poi))
+            .collect();
+        let end = request.end.map_or(0, |_| locations.len() - 1);
+        let budget = request.detour_budget(matrix.get(0, end).unwrap_or(Duration::MAX));
+        let (windows, rest_windows) = self.windows(request, &candidates, budget);
+        let (themes, positions) = self.penalized(&candidates, &locations);
+        let instance = Orienteering {
+            matrix: &matrix,
+            scores: &scores,
+            required: &required,
+            dwell: &dwell,
+            max_leg: request.max_leg_duration(),
+            budget,
+            end,
+            schedule: Schedule {
+                windows: &windows,
+                rest_break: request
+                    .rest_break
+                    .map_or(Duration::ZERO, |rest_break| rest_break.duration()),
+                rest_windows: &rest_windows,
+            },
+            penalties: Penalties {
+                diversity: self.config.diversity,
+                themes: &themes,
+                overlap: self.config.overlap,
+                positions: &positions,
+            },
+        };
+        let found = instance
+            .solve(self.limits(request, started_at))
+            .map_err(|error| with_diagnostics(error, diagnostics(started_at, candidates.len())))?;
+        let route = route(request, &instance, &found, &candidates);
+        let geometry = route.trace(provider);
+        Ok(SolveResponse {
+            route,
+            score: found
+                .stops
+                .iter()
+                .filter_map(|index| scores.get(*index))
+                .sum(),
+            diagnostics: diagnostics(started_at, candidates.len()),
+            geometry,
+        })
+    }
+
+    /// Candidates within walking reach of `request`, less those closed for
+    /// the whole walk, with their scores.
+    fn candidates(
+        &self,
+        request: &SolveRequest,
+    ) -> Result<(Vec<PointOfInterest>, Vec<f32>), SolveError> {
+        let mut scored =
+            request.walking_candidates(&self.store, &self.scorer, self.config.average_speed_kmh)?;
+        if let Some(start) = request.start_week_time() {
+            let budget = Duration::from_mins(u64::from(request.duration_minutes));
+            scored.retain(|(poi, _)| {
+                visit_windows(poi, start, budget, self.config.dwell.dwell_time(poi))
+                    .is_none_or(|windows| !windows.is_empty())
+            });
+        }
+        Ok(scored.into_iter().unzip())
+    }
+
+    /// Offsets from the start of the walk, within `budget`, at which a visit
+    /// to each candidate may begin, and at which `request`'s rest break may
+    /// begin there.
+    ///
+    /// Both are empty when the request has no start time.
+    fn windows(
+        &self,
+        request: &SolveRequest,
+        candidates: &[PointOfInterest],
+        budget: Duration,
+    ) -> (CandidateWindows, CandidateWindows) {
+        let Some(start) = request.start_week_time() else {
+            return (Vec::new(), Vec::new());
+        };
+        let windows = candidates
+            .iter()
+            .map(|poi| visit_windows(poi, start, budget, self.config.dwell.dwell_time(poi)))
+            .collect();
+        let rest_windows = candidates
+            .iter()
+            .map(|poi| {
+                request
+                    .rest_break
+                    .filter(|_| RestBreak::is_venue(poi))
+                    .map(|rest_break| rest_break.venue_windows(poi, start, budget))
+                    .filter(|open| !open.is_empty())
+            })
+            .collect();
+        (windows, rest_windows)
+    }
+
+    /// Theme of each candidate and position of each matrix location, for
+    /// whichever penalties are enabled.
+    fn penalized(
+        &self,
+        candidates: &[PointOfInterest],
+        locations: &[PointOfInterest],
+    ) -> (Vec<Option<Theme>>, Vec<Coord<f64>>) {
+        let themes = if self.config.diversity.is_enabled() {
+            candidates.iter().map(DiversityPolicy::category).collect()
+        } else {
+            Vec::new()
+        };
+        let positions = if self.config.overlap.is_enabled() {
+            locations.iter().map(|location| location.location).collect()
+        } else {
+            Vec::new()
+        };
+        (themes, positions)
+    }
+
+    /// Prefix `response`, planned for the rest of `request`, with the stops
+    /// already visited and trace the whole route.
+    fn lock_prefix(
+        &self,
+        request: &SolveRequest,
+        response: SolveResponse,
+    ) -> Result<SolveResponse, SolveError> {
+        let locked = request.lock_prefix(&self.store, &self.scorer, response)?;
+        let provider = request.accessibility.provider(&self.travel_time_provider)?;
+        let geometry = locked.route.trace(provider);
+        Ok(SolveResponse { geometry, ..locked })
+    }
+
+    /// Search limits for `request`: its seed, and whatever remains of the
+    /// tighter of the configured and requested time limits.
+    fn limits(&self, request: &SolveRequest, started_at: Instant) -> SearchLimits {
+        let requested_limit = request
+            .max_solve_ms
+            .map(|millis| Duration::from_millis(u64::from(millis)));
+        let limit = match (self.config.max_solve_duration, requested_limit) {
+            (Some(configured), Some(requested)) => Some(configured.min(requested)),
+            (configured, requested) => configured.or(requested),
+        };
+        SearchLimits {
+            seed: request.seed,
+            time_limit: limit.map(|total| total.saturating_sub(started_at.elapsed())),
+        }
+    }
+}
+
+/// Windows per candidate, in the form [`Schedule`] takes them.
+#[cfg(feature = "cp-sat")]
+type CandidateWindows = Vec<Option<Vec<Range<Duration>>>>;
+
+/// Offsets from `start` at which a visit to `poi` lasting `dwell` may begin
+/// within `budget`, or `None` when it keeps no usable opening hours.
+#[cfg(feature = "cp-sat")]
+fn visit_windows(
+    poi: &PointOfInterest,
+    start: WeekTime,
+    budget: Duration,
+    dwell: Duration,
+) -> Option<Vec<Range<Duration>>> {
+    let hours = OpeningHours::from_tags(&poi.tags)?.ok()?;
+    Some(hours.visit_windows(start, budget, dwell))
+}
+
+/// The route `instance` `found` through `candidates` for `request`.
+#[cfg(feature = "cp-sat")]
+fn route(
+    request: &SolveRequest,
+    instance: &Orienteering<'_>,
+    found: &Found,
+    candidates: &[PointOfInterest],
+) -> Route {
+    let route_pois: Vec<PointOfInterest> = found
+        .stops
+        .iter()
+        .filter_map(|index| candidates.get(*index).cloned())
+        .collect();
+    let legs = instance.legs(found, &route_pois);
+    let duration = legs
+        .last()
+        .map_or(Duration::ZERO, |leg| leg.cumulative_duration);
+    let route_end = request.end.unwrap_or(request.start);
+    let route =
+        Route::with_endpoints(request.start, route_end, route_pois, duration).with_legs(legs);
+    match request.start_at {
+        Some(start) => route.with_start_at(start),
+        None => route,
+    }
+}
+
+/// Response for a round trip with no candidates: an empty route that never
+/// leaves the start, found without a travel-time request.
+#[cfg(feature = "cp-sat")]
+fn stay_at_start(request: &SolveRequest, started_at: Instant) -> SolveResponse {
+    SolveResponse {
+        route: Route::with_endpoints(request.start, request.start, Vec::new(), Duration::ZERO),
+        score: 0.0,
+        diagnostics: diagnostics(started_at, 0),
+        geometry: None,
+    }
+}
+
+/// Telemetry for a solve that started at `started_at` and modelled
+/// `evaluated` candidates.
+#[cfg(feature = "cp-sat")]
+fn diagnostics(started_at: Instant, evaluated: usize) -> Diagnostics {
+    Diagnostics {
+        solve_time: started_at.elapsed(),
+        candidates_evaluated: evaluated as u64,
+        candidates_pruned: 0,
+    }
+}
+
+/// Attach the solve's telemetry to an infeasible model's [`Shortfall`].
+#[cfg(feature = "cp-sat")]
+fn with_diagnostics(error: SolveError, diagnostics: Diagnostics) -> SolveError {
+    match error {
+        SolveError::Infeasible(shortfall) => SolveError::Infeasible(Shortfall {
+            diagnostics,
+            ..shortfall
+        }),
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,289 @@
+//! Tests for the `OrtoolsSolver`.
+
+use super::*;
+use geo::Coord;
+use rstest::{fixture, rstest};
+use wildside_core::test_support::{MemoryStore, TagScorer, UnitTravelTimeProvider};
+use wildside_core::{InterestProfile, PointOfInterest, Tags, Theme};
+
+fn poi(id: u64, x: f64, theme: &str) -> PointOfInterest {
+    PointOfInterest::new(
+        id,
+        Coord { x, y: 0.0 },
+        Tags::from([(theme.to_owned(), String::new())]),
+    )
+}
+
+#[fixture]
+fn solver() -> OrtoolsSolver<MemoryStore, UnitTravelTimeProvider, TagScorer> {
+    let pois = vec![
+        poi(1, 0.0, "art"),
+        poi(2, 0.001, "history"),
+        poi(3, 0.002, "nature"),
+    ];
+    OrtoolsSolver::new(
+        MemoryStore::with_pois(pois),
+        UnitTravelTimeProvider,
+        TagScorer,
+    )
+}
+
+#[fixture]
+fn request() -> SolveRequest {
+    SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes: 10,
+        interests: InterestProfile::new()
+            .with_weight(Theme::Art, 0.5)
+            .with_weight(Theme::History, 0.25),
+        seed: 1,
+        ..Default::default()
+    }
+}
+
+#[rstest]
+fn invalid_requests_are_rejected(
+    solver: OrtoolsSolver<MemoryStore, UnitTravelTimeProvider, TagScorer>,
+    request: SolveRequest,
+) {
+    let invalid = SolveRequest {
+        duration_minutes: 0,
+        ..request
+    };
+    assert!(matches!(
+        solver.solve(&invalid),
+        Err(SolveError::InvalidRequest)
+    ));
+}
+
+#[cfg(not(feature = "cp-sat"))]
+#[rstest]
+fn valid_requests_need_the_cp_sat_feature(
+    solver: OrtoolsSolver<MemoryStore, UnitTravelTimeProvider, TagScorer>,
+    request: SolveRequest,
+) {
+    assert!(matches!(
+        solver.solve(&request),
+        Err(SolveError::NotImplemented)
+    ));
+}
+
+#[cfg(feature = "cp-sat")]
+mod cp_sat {
+    //! Tests solving with the CP-SAT model.
+
+    use super::*;
+    use wildside_core::{RestBreak, RouteStop, VisitedPrefix};
+
+    fn ids(response: &SolveResponse) -> Vec<u64> {
+        response.route.pois().iter().map(|poi| poi.id).collect()
+    }
+
+    #[rstest]
+    #[expect(
+        clippy::float_cmp,
+        reason = "scores are exact sums of binary fractions"
+    )]
+    fn visits_every_scoring_poi_that_fits(
+        solver: OrtoolsSolver<MemoryStore, UnitTravelTimeProvider, TagScorer>,
+        request: SolveRequest,
+    ) {
+        let response = solver.solve(&request).expect("solve");
+        let mut visited = ids(&response);
+        visited.sort_unstable();
+        assert_eq!(visited, vec![1, 2]);
+        assert_eq!(response.score, 0.75);
+        assert_eq!(response.diagnostics.candidates_evaluated, 2);
+        assert_eq!(response.route.total_duration(), Duration::from_secs(3));
+    }
+
+    #[rstest]
+    fn dwell_limits_the_stops_visited(
+        solver: OrtoolsSolver<MemoryStore, UnitTravelTimeProvider, TagScorer>,
+        request: SolveRequest,
+    ) {
+        let dwelling = OrtoolsSolver {
+            config: OrtoolsSolverConfig {
+                dwell: DwellTimePolicy::new(Duration::from_mins(6)),
+                ..OrtoolsSolverConfig::default()
+            },
+            ..solver
+        };
+        let response = dwelling.solve(&request).expect("solve");
+        assert_eq!(ids(&response), vec![1]);
+    }
+
+    #[rstest]
+    fn required_pois_are_visited_even_without_score(
+        solver: OrtoolsSolver<MemoryStore, UnitTravelTimeProvider, TagScorer>,
+        request: SolveRequest,
+    ) {
+        let required = SolveRequest {
+            required_poi_ids: vec![3],
+            max_nodes: Some(1),
+            ..request
+        };
+        let response = solver.solve(&required).expect("solve");
+        assert_eq!(ids(&response), vec![3]);
+    }
+
+    #[rstest]
+    fn point_to_point_routes_end_at_the_destination(
+        solver: OrtoolsSolver<MemoryStore, UnitTravelTimeProvider, TagScorer>,
+        request: SolveRequest,
+    ) {
+        let destination = Coord { x: 0.003, y: 0.0 };
+        let one_way = SolveRequest {
+            end: Some(destination),
+            ..request
+        };
+        let response = solver.solve(&one_way).expect("solve");
+        assert_eq!(response.route.end(), destination);
+        assert_eq!(
+            response.route.legs().last().map(|leg| leg.to),
+            Some(RouteStop::End)
+        );
+    }
+
+    #[rstest]
+    fn tours_under_way_keep_their_visited_stops(
+        solver: OrtoolsSolver<MemoryStore, UnitTravelTimeProvider, TagScorer>,
+        request: SolveRequest,
+    ) {
+        let under_way = SolveRequest {
+            visited: Some(VisitedPrefix {
+                poi_ids: vec![1],
+                position: Coord { x: 0.0, y: 0.0 },
+                elapsed_minutes: 1,
+            }),
+            ..request
+        };
+        let response = solver.solve(&under_way).expect("solve");
+        assert_eq!(ids(&response), vec![1, 2]);
+        assert_eq!(
+            response.route.legs().first().map(|leg| leg.from),
+            Some(RouteStop::Poi(1))
+        );
+        assert!(response.route.total_duration() > Duration::from_mins(1));
+    }
+
+    #[rstest]
+    #[case(Some("Sa 10:00"), vec![1])]
+    #[case(None, vec![1, 2])]
+    fn closed_pois_are_skipped_when_the_start_time_is_known(
+        request: SolveRequest,
+        #[case] start_time: Option<&str>,
+        #[case] expected: Vec<u64>,
+    ) {
+        let mut afternoon_gallery = poi(2, 0.001, "art");
+        afternoon_gallery
+            .tags
+            .insert("opening_hours".to_owned(), "Sa 12:00-18:00".to_owned());
+        let solver = OrtoolsSolver::new(
+            MemoryStore::with_pois(vec![poi(1, 0.0, "art"), afternoon_gallery]),
+            UnitTravelTimeProvider,
+            TagScorer,
+        );
+        let timed = SolveRequest {
+            duration_minutes: 60,
+            start_time: start_time.map(|time| time.parse().expect("valid start time")),
+            ..request
+        };
+        let mut visited = ids(&solver.solve(&timed).expect("solve"));
+        visited.sort_unstable();
+        assert_eq!(visited, expected);
+    }
+
+    #[rstest]
+    fn visits_wait_for_opening_time(request: SolveRequest) {
+        let mut gallery = poi(1, 0.0, "art");
+        gallery
+            .tags
+            .insert("opening_hours".to_owned(), "Sa 10:30-18:00".to_owned());
+        let solver = OrtoolsSolver::new(
+            MemoryStore::with_pois(vec![gallery]),
+            UnitTravelTimeProvider,
+            TagScorer,
+        );
+        let timed = SolveRequest {
+            duration_minutes: 60,
+            start_time: Some("Sa 10:00".parse().expect("valid start time")),
+            ..request
+        };
+        let response = solver.solve(&timed).expect("solve");
+        let wait = response.route.legs().first().and_then(|leg| leg.dwell);
+        assert!(
+            wait.is_some_and(|dwell| dwell >= Duration::from_mins(29)),
+            "waited {wait:?}"
+        );
+    }
+
+    #[rstest]
+    #[case::no_break(None, false)]
+    #[case::lunch(Some(RestBreak::lunch()), true)]
+    fn rest_breaks_stop_at_a_cafe_during_their_window(
+        request: SolveRequest,
+        #[case] rest_break: Option<RestBreak>,
+        #[case] rests: bool,
+    ) {
+        let cafe = PointOfInterest::new(
+            2,
+            Coord { x: 0.001, y: 0.0 },
+            Tags::from([("amenity".to_owned(), "cafe".to_owned())]),
+        );
+        let solver = OrtoolsSolver::new(
+            MemoryStore::with_pois(vec![poi(1, 0.0, "art"), cafe]),
+            UnitTravelTimeProvider,
+            TagScorer,
+        );
+        let lunch = SolveRequest {
+            duration_minutes: 120,
+            start_time: Some("Sa 11:00".parse().expect("valid start time")),
+            rest_break,
+            ..request
+        };
+        let response = solver.solve(&lunch).expect("solve");
+        let rest = response
+            .route
+            .legs()
+            .iter()
+            .find(|leg| leg.to == RouteStop::Poi(2));
+        assert_eq!(rest.is_some(), rests);
+        if let Some(leg) = rest {
+            // Lunch may not begin before 11:30, half an hour into the walk.
+            assert!(
+                leg.cumulative_duration >= Duration::from_mins(60),
+                "break ended after {:?}",
+                leg.cumulative_duration
+            );
+        }
+        assert!(response.route.pois().iter().any(|poi| poi.id == 1));
+    }
+
+    #[rstest]
+    fn diversity_penalties_mix_themes(request: SolveRequest) {
+        // Two stops fit; without penalties both galleries would be visited.
+        let categorized = |id, x, theme: &str, tag: (&str, &str)| {
+            let mut categorized = poi(id, x, theme);
+            categorized.tags.insert(tag.0.to_owned(), tag.1.to_owned());
+            categorized
+        };
+        let solver = OrtoolsSolver::with_config(
+            MemoryStore::with_pois(vec![
+                categorized(1, 0.0, "art", ("tourism", "gallery")),
+                categorized(2, 0.0005, "art", ("tourism", "gallery")),
+                categorized(3, 0.001, "history", ("historic", "monument")),
+            ]),
+            UnitTravelTimeProvider,
+            TagScorer,
+            OrtoolsSolverConfig {
+                dwell: DwellTimePolicy::new(Duration::from_mins(4)),
+                diversity: DiversityPolicy::new(0.4, 0.0),
+                ..OrtoolsSolverConfig::default()
+            },
+        );
+        let visited = ids(&solver.solve(&request).expect("solve"));
+        assert_eq!(visited.len(), 2);
+        assert!(visited.contains(&3), "visited {visited:?}");
+    }
+}
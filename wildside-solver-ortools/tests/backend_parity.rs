@@ -0,0 +1,207 @@
+//! Golden route parity between the CP-SAT and VRP backends.
+//!
+//! Each golden route fixture of the VRP solver is solved by both backends
+//! over the same fixed travel-time matrix. The instances are small enough
+//! for CP-SAT to prove optimality, so the VRP solver's routes must match its
+//! objective: the same POIs and the same score.
+
+#![cfg(feature = "cp-sat")]
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use geo::Coord;
+use rstest::rstest;
+use serde::Deserialize;
+use wildside_core::test_support::{MemoryStore, TagScorer};
+use wildside_core::{
+    DwellTimePolicy, InterestProfile, PointOfInterest, SolveRequest, SolveResponse, Solver, Theme,
+};
+use wildside_solver_ortools::{OrtoolsSolver, OrtoolsSolverConfig};
+use wildside_solver_vrp::test_support::FixedMatrixTravelTimeProvider;
+use wildside_solver_vrp::{VrpSolver, VrpSolverConfig};
+
+/// Largest score difference accepted between the backends.
+const SCORE_TOLERANCE: f32 = 1e-3;
+
+/// The parts of a VRP golden route fixture both backends consume.
+#[derive(Debug, Deserialize)]
+struct GoldenRoute {
+    pois: Vec<PoiSpec>,
+    travel_time_matrix_seconds: Vec<Vec<u64>>,
+    request: RequestSpec,
+}
+
+#[derive(Debug, Deserialize)]
+struct PoiSpec {
+    id: u64,
+    x: f64,
+    y: f64,
+    tags: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RequestSpec {
+    start: CoordSpec,
+    end: Option<CoordSpec>,
+    duration_minutes: u16,
+    interests: HashMap<String, f32>,
+    seed: u64,
+    max_nodes: Option<u16>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy)]
+struct CoordSpec {
+    x: f64,
+    y: f64,
+}
+
+impl From<CoordSpec> for Coord<f64> {
+    fn from(spec: CoordSpec) -> Self {
+        Self {
+            x: spec.x,
+            y: spec.y,
+        }
+    }
+}
+
+fn load_golden_route(name: &str) -> GoldenRoute {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../wildside-solver-vrp/tests/golden_routes/data")
+        .join(format!("{name}.json"));
+    let content = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()));
+    serde_json::from_str(&content)
+        .unwrap_or_else(|e| panic!("failed to parse {}: {e}", path.display()))
+}
+
+fn pois(golden: &GoldenRoute) -> Vec<PointOfInterest> {
+    golden
+        .pois
+        .iter()
+        .map(|spec| {
+            PointOfInterest::new(
+                spec.id,
+                Coord {
+                    x: spec.x,
+                    y: spec.y,
+                },
+                spec.tags.clone().into_iter().collect(),
+            )
+        })
+        .collect()
+}
+
+fn request(spec: &RequestSpec) -> SolveRequest {
+    let mut interests = InterestProfile::new();
+    for (theme, weight) in &spec.interests {
+        let parsed: Theme = theme
+            .parse()
+            .unwrap_or_else(|_| panic!("golden route contains invalid theme: {theme}"));
+        interests.set_weight(parsed, *weight);
+    }
+    SolveRequest {
+        start: spec.start.into(),
+        end: spec.end.map(Coord::from),
+        duration_minutes: spec.duration_minutes,
+        interests,
+        seed: spec.seed,
+        max_nodes: spec.max_nodes,
+        ..Default::default()
+    }
+}
+
+fn solve(solver: &impl Solver, request: &SolveRequest, backend: &str) -> SolveResponse {
+    solver
+        .solve(request)
+        .unwrap_or_else(|e| panic!("{backend} should solve the golden route: {e:?}"))
+}
+
+fn poi_ids(response: &SolveResponse) -> BTreeSet<u64> {
+    response.route.pois().iter().map(|poi| poi.id).collect()
+}
+
+#[expect(
+    clippy::float_arithmetic,
+    reason = "scores are compared within a tolerance"
+)]
+fn scores_match(actual: f32, expected: f32) -> bool {
+    (actual - expected).abs() <= SCORE_TOLERANCE
+}
+
+#[rstest]
+#[case("trivial_single_poi")]
+#[case("linear_three_poi")]
+#[case("budget_constrained")]
+#[case("point_to_point")]
+#[case("max_nodes_pruning")]
+#[case("empty_candidates")]
+fn backends_agree_on_golden_routes(#[case] name: &str) {
+    let golden = load_golden_route(name);
+    let request = request(&golden.request);
+    let vrp = VrpSolver::new(
+        MemoryStore::with_pois(pois(&golden)),
+        FixedMatrixTravelTimeProvider::from_seconds(golden.travel_time_matrix_seconds.clone()),
+        TagScorer,
+    );
+    let cp_sat = OrtoolsSolver::new(
+        MemoryStore::with_pois(pois(&golden)),
+        FixedMatrixTravelTimeProvider::from_seconds(golden.travel_time_matrix_seconds.clone()),
+        TagScorer,
+    );
+
+    let expected = solve(&vrp, &request, "VrpSolver");
+    let actual = solve(&cp_sat, &request, "OrtoolsSolver");
+
+    assert_eq!(
+        poi_ids(&actual),
+        poi_ids(&expected),
+        "{name}: POI sets differ"
+    );
+    assert!(
+        scores_match(actual.score, expected.score),
+        "{name}: CP-SAT scored {} but VRP scored {}",
+        actual.score,
+        expected.score
+    );
+    let budget = Duration::from_mins(u64::from(request.duration_minutes));
+    assert!(
+        actual.route.total_duration() <= budget,
+        "{name}: CP-SAT route exceeds the budget"
+    );
+}
+
+#[rstest]
+fn backends_agree_under_a_dwell_policy() {
+    // Visits of three minutes and three quarters leave room for the two
+    // nearest POIs only.
+    let golden = load_golden_route("linear_three_poi");
+    let request = request(&golden.request);
+    let dwell = DwellTimePolicy::new(Duration::from_secs(225));
+    let vrp = VrpSolver::with_config(
+        MemoryStore::with_pois(pois(&golden)),
+        FixedMatrixTravelTimeProvider::from_seconds(golden.travel_time_matrix_seconds.clone()),
+        TagScorer,
+        VrpSolverConfig {
+            dwell: dwell.clone(),
+            ..VrpSolverConfig::default()
+        },
+    );
+    let cp_sat = OrtoolsSolver::with_config(
+        MemoryStore::with_pois(pois(&golden)),
+        FixedMatrixTravelTimeProvider::from_seconds(golden.travel_time_matrix_seconds.clone()),
+        TagScorer,
+        OrtoolsSolverConfig {
+            dwell,
+            ..OrtoolsSolverConfig::default()
+        },
+    );
+
+    let expected = solve(&vrp, &request, "VrpSolver");
+    let actual = solve(&cp_sat, &request, "OrtoolsSolver");
+
+    assert_eq!(poi_ids(&expected), BTreeSet::from([1, 2]));
+    assert_eq!(poi_ids(&actual), poi_ids(&expected));
+}
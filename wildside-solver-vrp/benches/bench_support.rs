@@ -53,7 +53,6 @@ pub const BENCHMARK_START: Coord<f64> = Coord { x: 0.05, y: 0.05 };
 pub fn build_benchmark_request(seed: u64) -> SolveRequest {
     SolveRequest {
         start: BENCHMARK_START,
-        end: None,
         duration_minutes: DURATION_MINUTES,
         interests: InterestProfile::new()
             .with_weight(Theme::Art, 0.8)
@@ -61,7 +60,7 @@ pub fn build_benchmark_request(seed: u64) -> SolveRequest {
             .with_weight(Theme::Nature, 0.3)
             .with_weight(Theme::Culture, 0.2),
         seed,
-        max_nodes: None,
+        ..Default::default()
     }
 }
 
@@ -0,0 +1,198 @@
+//! City-scale benchmark scenarios for the VRP solver.
+//!
+//! Scenarios place 500 to 5,000 candidates in the clustered layout produced
+//! by [`generate_clustered_pois`] and pair each candidate count with a
+//! solver configuration. Travel times are recorded once per scenario from
+//! [`StreetTravelTimeProvider`], which estimates walks along a street grid,
+//! and replayed with [`ReplayTravelTimeProvider`] while solves are timed, so
+//! measurements cover the solver rather than the provider.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use geo::{Distance, Haversine, Point};
+use wildside_core::test_support::{MemoryStore, TagScorer};
+use wildside_core::travel_time::{
+    RecordingTravelTimeProvider, ReplayTravelTimeProvider, TravelTimeRecording,
+};
+use wildside_core::{
+    PointOfInterest, SolveError, SolveRequest, SolveResponse, Solver, TravelTimeError,
+    TravelTimeMatrix, TravelTimeProvider,
+};
+use wildside_solver_vrp::{
+    ClusteringPolicy, DiversityPolicy, OverlapPolicy, VrpSolver, VrpSolverConfig,
+};
+
+use crate::bench_support::{build_benchmark_request, generate_clustered_pois};
+
+/// Candidate counts of the city-scale benchmarks.
+pub const SCALE_SIZES: [usize; 3] = [500, 2_000, 5_000];
+
+/// Candidates routed individually before the clustered profile groups them.
+const CLUSTER_THRESHOLD: usize = 200;
+
+/// Best-scoring candidates searched by the capped profiles.
+const CANDIDATE_CAP: u16 = 150;
+
+/// Best-scoring candidates searched by the clustered profile.
+const CLUSTERED_CANDIDATE_CAP: u16 = 1_000;
+
+/// Walking speed of benchmark travel times, in metres per second.
+const WALKING_SPEED_MPS: f64 = 1.4;
+
+/// Ratio of a walk along streets to the straight line between its ends.
+const DETOUR_FACTOR: f64 = 1.3;
+
+/// A solver configuration benchmarked at every candidate count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolverProfile {
+    /// Default settings over the best-scoring candidates.
+    Capped,
+    /// Capped candidates with diversity and backtracking penalties.
+    Penalised,
+    /// A larger set of candidates, clustered into grid cells for routing.
+    Clustered,
+}
+
+impl SolverProfile {
+    /// Every profile, in reporting order.
+    pub const ALL: [Self; 3] = [Self::Capped, Self::Penalised, Self::Clustered];
+
+    /// Name used in benchmark identifiers.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Capped => "capped",
+            Self::Penalised => "penalised",
+            Self::Clustered => "clustered",
+        }
+    }
+
+    /// Solver configuration for the profile.
+    #[must_use]
+    pub fn config(self) -> VrpSolverConfig {
+        match self {
+            Self::Capped => VrpSolverConfig::default(),
+            Self::Penalised => VrpSolverConfig {
+                diversity: DiversityPolicy::new(0.2, 0.5),
+                overlap: OverlapPolicy::new(0.3),
+                ..VrpSolverConfig::default()
+            },
+            Self::Clustered => VrpSolverConfig {
+                clustering: ClusteringPolicy {
+                    threshold: Some(CLUSTER_THRESHOLD),
+                    ..ClusteringPolicy::default()
+                },
+                ..VrpSolverConfig::default()
+            },
+        }
+    }
+
+    /// Cap on the candidates searched.
+    #[must_use]
+    pub const fn max_nodes(self) -> u16 {
+        match self {
+            Self::Capped | Self::Penalised => CANDIDATE_CAP,
+            Self::Clustered => CLUSTERED_CANDIDATE_CAP,
+        }
+    }
+}
+
+/// Estimates walking times along a street grid from straight-line distance.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StreetTravelTimeProvider;
+
+impl StreetTravelTimeProvider {
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "walking time scales distance by detour and pace"
+    )]
+    fn travel_time(from: &PointOfInterest, to: &PointOfInterest) -> Duration {
+        let metres = Haversine.distance(Point::from(from.location), Point::from(to.location));
+        Duration::from_secs_f64((metres * DETOUR_FACTOR / WALKING_SPEED_MPS).round())
+    }
+}
+
+impl TravelTimeProvider for StreetTravelTimeProvider {
+    fn get_travel_time_matrix(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> Result<TravelTimeMatrix, TravelTimeError> {
+        if pois.is_empty() {
+            return Err(TravelTimeError::EmptyInput);
+        }
+        Ok(TravelTimeMatrix::from_fn(pois.len(), |from, to| {
+            pois.get(from)
+                .zip(pois.get(to))
+                .map_or(Duration::MAX, |(lhs, rhs)| Self::travel_time(lhs, rhs))
+        }))
+    }
+}
+
+/// One benchmark case: a candidate set, a request, and recorded travel times.
+#[derive(Debug)]
+pub struct Scenario {
+    /// Configuration the scenario is solved with.
+    pub profile: SolverProfile,
+    /// Candidates held by the store.
+    pub pois: Vec<PointOfInterest>,
+    /// Request solved by every iteration.
+    pub request: SolveRequest,
+    /// Travel times the solver requested while recording.
+    pub recording: TravelTimeRecording,
+    /// Route found while recording.
+    pub response: SolveResponse,
+}
+
+impl Scenario {
+    /// Generate `size` candidates and record the travel times a solve under
+    /// `profile` requests from [`StreetTravelTimeProvider`].
+    ///
+    /// # Errors
+    ///
+    /// Returns the recording solve's error when it fails.
+    pub fn record(profile: SolverProfile, size: usize, seed: u64) -> Result<Self, SolveError> {
+        let pois = generate_clustered_pois(size, seed);
+        let request = SolveRequest {
+            max_nodes: Some(profile.max_nodes()),
+            ..build_benchmark_request(seed)
+        };
+        let recorder = Arc::new(RecordingTravelTimeProvider::new(StreetTravelTimeProvider));
+        let response = VrpSolver::with_config(
+            MemoryStore::with_pois(pois.clone()),
+            Arc::clone(&recorder),
+            TagScorer,
+            profile.config(),
+        )
+        .solve(&request)?;
+        Ok(Self {
+            profile,
+            pois,
+            request,
+            recording: recorder.recording(),
+            response,
+        })
+    }
+
+    /// A solver over the scenario's candidates that replays its recording.
+    #[must_use]
+    pub fn replay_solver(&self) -> VrpSolver<MemoryStore, ReplayTravelTimeProvider, TagScorer> {
+        VrpSolver::with_config(
+            MemoryStore::with_pois(self.pois.clone()),
+            ReplayTravelTimeProvider::new(self.recording.clone()),
+            TagScorer,
+            self.profile.config(),
+        )
+    }
+}
+
+/// Summarise the quality of `response` on one line for benchmark logs.
+#[must_use]
+pub fn describe_quality(response: &SolveResponse) -> String {
+    let minutes = response.route.total_duration().as_secs().div_ceil(60);
+    format!(
+        "score {:.2} over {} stops in {minutes} min",
+        response.score,
+        response.route.pois().len(),
+    )
+}
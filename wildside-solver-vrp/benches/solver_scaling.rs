@@ -0,0 +1,95 @@
+//! City-scale criterion benchmarks for the VRP solver.
+//!
+//! Measures solve latency for 500, 2,000, and 5,000 candidates under each
+//! [`SolverProfile`], replaying travel times recorded once per scenario. The
+//! score, stop count, and duration of each scenario's route are logged before
+//! it is timed, so quality regressions show up beside latency regressions.
+//!
+//! Run benchmarks with:
+//! ```bash
+//! cargo bench --package wildside-solver-vrp --bench solver_scaling
+//! ```
+
+use std::time::Duration;
+
+use criterion::measurement::WallTime;
+use criterion::{BenchmarkGroup, BenchmarkId, Criterion, Throughput};
+use wildside_core::Solver;
+
+#[expect(dead_code, reason = "bench_support exports items not all used here")]
+mod bench_support;
+mod scale_support;
+
+use bench_support::BENCHMARK_SEED;
+use scale_support::{SCALE_SIZES, Scenario, SolverProfile, describe_quality};
+
+/// Benchmark solve latency for every profile at every candidate count.
+///
+/// Large solves take seconds, so the group keeps the criterion minimum of 10
+/// samples with a longer measurement window.
+fn bench_city_scale(c: &mut Criterion) {
+    let mut group = c.benchmark_group("city_scale");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(20));
+
+    for profile in SolverProfile::ALL {
+        for size in SCALE_SIZES {
+            bench_scenario(&mut group, profile, size);
+        }
+    }
+
+    group.finish();
+}
+
+/// Record one scenario, log its route quality, and time replayed solves.
+///
+/// Recording happens outside the measurement loop, so only the replayed
+/// solves are timed.
+#[expect(
+    clippy::print_stderr,
+    reason = "route quality is reported beside criterion's timings"
+)]
+fn bench_scenario(group: &mut BenchmarkGroup<'_, WallTime>, profile: SolverProfile, size: usize) {
+    #[expect(
+        clippy::expect_used,
+        reason = "benchmark scenarios must solve to be measured"
+    )]
+    let scenario = Scenario::record(profile, size, BENCHMARK_SEED).expect("record scenario");
+    eprintln!(
+        "city_scale/{}/{size}: {}",
+        profile.name(),
+        describe_quality(&scenario.response)
+    );
+
+    let solver = scenario.replay_solver();
+    #[expect(
+        clippy::as_conversions,
+        reason = "Safe conversion for benchmark problem sizes"
+    )]
+    let throughput_size = size as u64;
+    group.throughput(Throughput::Elements(throughput_size));
+    group.bench_with_input(BenchmarkId::new(profile.name(), size), &size, |b, _| {
+        b.iter(|| {
+            #[expect(
+                clippy::let_underscore_must_use,
+                reason = "Benchmarking solve performance, result is intentionally discarded"
+            )]
+            let _ = solver.solve(&scenario.request);
+        });
+    });
+}
+
+// Private module containing the criterion_group! macro invocation.
+mod group {
+    use super::bench_city_scale;
+    use criterion::criterion_group;
+
+    criterion_group!(benches, bench_city_scale);
+
+    // Re-export the generated function for use by criterion_main!
+    pub use self::benches as run;
+}
+
+// The criterion_main! macro must be invoked at crate level to generate main().
+use criterion::criterion_main;
+criterion_main!(group::run);
@@ -0,0 +1,177 @@
+//! Restarts that look for several distinct routes for one request.
+//!
+//! Re-running the search with a new seed tends to rediscover the best route.
+//! [`AlternativesPolicy`] steers later restarts elsewhere by discounting the
+//! scores of POIs that accepted routes already visit, then keeps only routes
+//! that share few enough POIs with those accepted before them.
+
+use wildside_core::{PointOfInterest, Route};
+
+/// How [`VrpSolver`](crate::VrpSolver) searches for alternative routes.
+///
+/// Distinctness is measured with [`Route::overlap`]: a route is accepted only
+/// when it shares at most `max_overlap` of its POIs with each route accepted
+/// before it. Discounted scores only steer the search; returned routes report
+/// their undiscounted score.
+///
+/// # Examples
+///
+/// ```rust
+/// use wildside_solver_vrp::{AlternativesPolicy, VrpSolverConfig};
+///
+/// let config = VrpSolverConfig {
+///     alternatives: AlternativesPolicy {
+///         max_overlap: 0.25,
+///         ..AlternativesPolicy::default()
+///     },
+///     ..VrpSolverConfig::default()
+/// };
+/// assert_eq!(config.alternatives.restarts_per_route, 3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlternativesPolicy {
+    /// Largest share of POIs an accepted route may have in common with any
+    /// other accepted route.
+    pub max_overlap: f64,
+    /// Searches allowed per requested route before giving up on finding more.
+    pub restarts_per_route: usize,
+    /// Fraction of its score a POI keeps in later searches once an accepted
+    /// route visits it.
+    pub reused_score_factor: f32,
+}
+
+impl Default for AlternativesPolicy {
+    fn default() -> Self {
+        Self {
+            max_overlap: 0.5,
+            restarts_per_route: 3,
+            reused_score_factor: 0.5,
+        }
+    }
+}
+
+impl AlternativesPolicy {
+    /// Number of searches to run when looking for `count` routes.
+    pub(crate) const fn restarts(&self, count: usize) -> usize {
+        count.saturating_mul(self.restarts_per_route)
+    }
+
+    /// Candidate scores for the next search, discounting candidates that
+    /// `accepted` routes already visit.
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "discounts scale floating-point scores"
+    )]
+    pub(crate) fn discounted_scores(
+        &self,
+        candidates: &[PointOfInterest],
+        scores: &[f32],
+        accepted: &[Route],
+    ) -> Vec<f32> {
+        candidates
+            .iter()
+            .zip(scores)
+            .map(|(candidate, score)| {
+                let visits = accepted
+                    .iter()
+                    .filter(|route| route.pois().iter().any(|poi| poi.id == candidate.id))
+                    .count();
+                let exponent = i32::try_from(visits).unwrap_or(i32::MAX);
+                score * self.reused_score_factor.powi(exponent)
+            })
+            .collect()
+    }
+
+    /// Report whether `route` differs enough from every `accepted` route.
+    pub(crate) fn is_distinct<'a>(
+        &self,
+        route: &Route,
+        mut accepted: impl Iterator<Item = &'a Route>,
+    ) -> bool {
+        accepted.all(|other| route.overlap(other) <= self.max_overlap)
+    }
+}
+
+/// Undiscounted score of visiting `pois`, looked up among `candidates`.
+pub(crate) fn route_score(
+    candidates: &[PointOfInterest],
+    scores: &[f32],
+    pois: &[PointOfInterest],
+) -> f32 {
+    candidates
+        .iter()
+        .zip(scores)
+        .filter(|(candidate, _)| pois.iter().any(|poi| poi.id == candidate.id))
+        .map(|(_, score)| score)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for alternative route selection.
+
+    use std::time::Duration;
+
+    use super::*;
+    use geo::Coord;
+    use rstest::rstest;
+
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "scores are compared within a tolerance"
+    )]
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    fn poi(id: u64) -> PointOfInterest {
+        PointOfInterest::with_empty_tags(id, Coord { x: 0.0, y: 0.0 })
+    }
+
+    fn route(ids: &[u64]) -> Route {
+        Route::new(
+            ids.iter().copied().map(poi).collect(),
+            Duration::from_mins(10),
+        )
+    }
+
+    #[rstest]
+    fn scores_are_discounted_per_accepted_visit() {
+        let policy = AlternativesPolicy::default();
+        let candidates = vec![poi(1), poi(2), poi(3)];
+        let accepted = [route(&[1, 2]), route(&[2])];
+        let scores = policy.discounted_scores(&candidates, &[4.0, 4.0, 4.0], &accepted);
+        assert_eq!(scores.len(), 3);
+        for (actual, expected) in scores.into_iter().zip([2.0, 1.0, 4.0]) {
+            assert_close(actual, expected);
+        }
+    }
+
+    #[rstest]
+    #[case::disjoint(&[3, 4], true)]
+    #[case::one_shared(&[1, 3], false)]
+    #[case::identical(&[2, 1], false)]
+    fn distinct_routes_stay_under_the_overlap_limit(#[case] ids: &[u64], #[case] expected: bool) {
+        let policy = AlternativesPolicy {
+            max_overlap: 0.25,
+            ..AlternativesPolicy::default()
+        };
+        let accepted = [route(&[1, 2])];
+        assert_eq!(policy.is_distinct(&route(ids), accepted.iter()), expected);
+    }
+
+    #[rstest]
+    fn route_score_ignores_discounts_and_unknown_pois() {
+        let candidates = vec![poi(1), poi(2), poi(3)];
+        let score = route_score(&candidates, &[1.0, 2.0, 4.0], &[poi(3), poi(1), poi(9)]);
+        assert_close(score, 5.0);
+    }
+
+    #[rstest]
+    fn restarts_scale_with_the_requested_count() {
+        assert_eq!(AlternativesPolicy::default().restarts(2), 6);
+    }
+}
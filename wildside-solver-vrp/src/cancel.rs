@@ -0,0 +1,39 @@
+//! Cancellation of `vrp-core` searches.
+//!
+//! [`with_cancellation`] wraps the configured termination so the search stops
+//! at the first generation boundary after the caller cancels its
+//! [`CancellationToken`]. The solver then discards the route and reports
+//! [`SolveError::Cancelled`](wildside_core::SolveError::Cancelled).
+
+use vrp_core::prelude::{Float, GoalContext};
+use vrp_core::rosomaxa::prelude::Termination;
+use vrp_core::solver::RefinementContext;
+use wildside_core::CancellationToken;
+
+use crate::progress::VrpTermination;
+
+/// Wrap `inner` so the search also stops once `cancel` is cancelled.
+pub(crate) fn with_cancellation(
+    inner: VrpTermination,
+    cancel: CancellationToken,
+) -> VrpTermination {
+    Box::new(CancelTermination { inner, cancel })
+}
+
+struct CancelTermination {
+    inner: VrpTermination,
+    cancel: CancellationToken,
+}
+
+impl Termination for CancelTermination {
+    type Context = RefinementContext;
+    type Objective = GoalContext;
+
+    fn is_termination(&self, heuristic_ctx: &mut Self::Context) -> bool {
+        self.inner.is_termination(heuristic_ctx) || self.cancel.is_cancelled()
+    }
+
+    fn estimate(&self, heuristic_ctx: &Self::Context) -> Float {
+        self.inner.estimate(heuristic_ctx)
+    }
+}
@@ -0,0 +1,265 @@
+//! Clustering of dense candidate sets to shrink travel-time requests.
+//!
+//! A travel-time matrix grows with the square of the locations it covers, so
+//! thousands of candidates in a city centre make for slow routing requests
+//! and searches. [`ClusteringPolicy`] groups candidates into square grid
+//! cells, asks the provider only for travel times between one representative
+//! per cell and the route's endpoints, and expands each cell locally with
+//! straight-line walking estimates.
+
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use geo::{Coord, Distance, Haversine, Point};
+use wildside_core::{PointOfInterest, TravelTimeMatrix};
+
+/// When and how [`VrpSolver`](crate::VrpSolver) clusters candidates.
+///
+/// Once more than `threshold` candidates are selected, they are grouped into
+/// cells of `cell_size_m` metres. The highest-scoring candidate of each cell
+/// represents it in the travel-time request. A walk between two cells takes
+/// the routed time between their representatives, plus straight-line walks at
+/// the solver's average speed from and to the representatives; walks within a
+/// cell are straight-line estimates. Travel times to and from the start and
+/// end stay exact for representatives. The default policy never clusters.
+///
+/// # Examples
+///
+/// ```rust
+/// use wildside_solver_vrp::{ClusteringPolicy, VrpSolverConfig};
+///
+/// let config = VrpSolverConfig {
+///     clustering: ClusteringPolicy {
+///         threshold: Some(500),
+///         ..ClusteringPolicy::default()
+///     },
+///     ..VrpSolverConfig::default()
+/// };
+/// assert_eq!(config.clustering.cell_size_m, 150.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClusteringPolicy {
+    /// Largest number of candidates routed individually; `None` never
+    /// clusters.
+    pub threshold: Option<usize>,
+    /// Side of each grid cell, in metres.
+    pub cell_size_m: f64,
+}
+
+impl Default for ClusteringPolicy {
+    fn default() -> Self {
+        Self {
+            threshold: None,
+            cell_size_m: 150.0,
+        }
+    }
+}
+
+impl ClusteringPolicy {
+    /// Group the `candidates` of `locations` into cells, when there are more
+    /// than the threshold allows.
+    ///
+    /// Other locations, such as the start and end, are kept on their own.
+    /// Cells are laid out around the first location, and each is represented
+    /// by its first member, so candidates sorted best first are represented
+    /// by their best.
+    pub(crate) fn clusters(
+        &self,
+        locations: &[PointOfInterest],
+        candidates: RangeInclusive<usize>,
+    ) -> Option<Clusters> {
+        let threshold = self.threshold?;
+        if candidates.clone().count() <= threshold || !self.cell_size_m.is_normal() {
+            return None;
+        }
+        let origin = locations.first()?.location;
+        let mut cells: HashMap<(i64, i64), usize> = HashMap::new();
+        let mut representatives = Vec::new();
+        let mut anchors = Vec::with_capacity(locations.len());
+        for (index, poi) in locations.iter().enumerate() {
+            let mut represent = || {
+                representatives.push(index);
+                representatives.len() - 1
+            };
+            let anchor = if candidates.contains(&index) {
+                *cells
+                    .entry(self.cell(origin, poi.location))
+                    .or_insert_with(represent)
+            } else {
+                represent()
+            };
+            anchors.push(anchor);
+        }
+        Some(Clusters {
+            anchors,
+            representatives,
+        })
+    }
+
+    /// Grid cell containing `location`, on an equirectangular projection
+    /// around `origin`.
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "grid cells are derived from projected coordinates"
+    )]
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "cell indices of walkable distances fit in i64"
+    )]
+    fn cell(&self, origin: Coord<f64>, location: Coord<f64>) -> (i64, i64) {
+        let metres_per_degree = Haversine.radius().to_radians();
+        let east = (location.x - origin.x) * origin.y.to_radians().cos() * metres_per_degree;
+        let north = (location.y - origin.y) * metres_per_degree;
+        (
+            (east / self.cell_size_m).floor() as i64,
+            (north / self.cell_size_m).floor() as i64,
+        )
+    }
+}
+
+/// Locations grouped into cells, each routed through a representative.
+#[derive(Debug)]
+pub(crate) struct Clusters {
+    /// Cell of each location, as an index into `representatives`.
+    anchors: Vec<usize>,
+    /// Location representing each cell.
+    representatives: Vec<usize>,
+}
+
+impl Clusters {
+    /// The locations to request travel times between: one per cell.
+    pub(crate) fn representatives(&self, locations: &[PointOfInterest]) -> Vec<PointOfInterest> {
+        self.representatives
+            .iter()
+            .filter_map(|index| locations.get(*index).cloned())
+            .collect()
+    }
+
+    /// Travel times between every pair of `locations`, from the `routed`
+    /// times between representatives and straight-line walks at `speed_kmh`
+    /// within cells.
+    pub(crate) fn expand(
+        &self,
+        locations: &[PointOfInterest],
+        routed: &TravelTimeMatrix,
+        speed_kmh: f64,
+    ) -> TravelTimeMatrix {
+        let walk = |from: usize, to: usize| {
+            locations
+                .get(from)
+                .zip(locations.get(to))
+                .map_or(Duration::MAX, |(lhs, rhs)| {
+                    walking_time(lhs.location, rhs.location, speed_kmh)
+                })
+        };
+        let representative = |cell: usize| self.representatives.get(cell).copied();
+        TravelTimeMatrix::from_fn(locations.len(), |from, to| {
+            if from == to {
+                return Duration::ZERO;
+            }
+            let (Some(&source), Some(&target)) = (self.anchors.get(from), self.anchors.get(to))
+            else {
+                return Duration::MAX;
+            };
+            if source == target {
+                return walk(from, to);
+            }
+            let leave = representative(source).map_or(Duration::MAX, |index| walk(from, index));
+            let join = representative(target).map_or(Duration::MAX, |index| walk(index, to));
+            routed
+                .get(source, target)
+                .unwrap_or(Duration::MAX)
+                .saturating_add(leave)
+                .saturating_add(join)
+        })
+    }
+}
+
+/// Straight-line walking time from `from` to `to` at `speed_kmh`.
+#[expect(
+    clippy::float_arithmetic,
+    reason = "walking times divide distance by speed"
+)]
+fn walking_time(from: Coord<f64>, to: Coord<f64>, speed_kmh: f64) -> Duration {
+    let metres = Haversine.distance(Point::from(from), Point::from(to));
+    Duration::try_from_secs_f64(metres * 3.6 / speed_kmh).unwrap_or(Duration::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for candidate clustering.
+
+    use super::*;
+    use rstest::rstest;
+
+    fn location(id: u64, x: f64) -> PointOfInterest {
+        PointOfInterest::with_empty_tags(id, Coord { x, y: 0.0 })
+    }
+
+    /// The start, three candidates with the first two sharing a cell, then
+    /// the end.
+    fn locations() -> Vec<PointOfInterest> {
+        vec![
+            location(0, 0.0),
+            location(1, 0.0100),
+            location(2, 0.0101),
+            location(3, 0.0200),
+            location(4, 0.0300),
+        ]
+    }
+
+    fn policy(threshold: Option<usize>) -> ClusteringPolicy {
+        ClusteringPolicy {
+            threshold,
+            cell_size_m: 100.0,
+        }
+    }
+
+    #[rstest]
+    #[case::disabled(None)]
+    #[case::below_threshold(Some(3))]
+    fn small_candidate_sets_are_not_clustered(#[case] threshold: Option<usize>) {
+        assert!(policy(threshold).clusters(&locations(), 1..=3).is_none());
+    }
+
+    #[rstest]
+    fn nearby_candidates_share_a_representative() {
+        let pois = locations();
+        let clusters = policy(Some(2))
+            .clusters(&pois, 1..=3)
+            .expect("three candidates exceed the threshold");
+
+        let ids: Vec<u64> = clusters
+            .representatives(&pois)
+            .iter()
+            .map(|poi| poi.id)
+            .collect();
+        assert_eq!(ids, [0, 1, 3, 4]);
+    }
+
+    #[rstest]
+    fn expansion_routes_between_cells_through_representatives() {
+        let pois = locations();
+        let clusters = policy(Some(2))
+            .clusters(&pois, 1..=3)
+            .expect("three candidates exceed the threshold");
+        let routed = TravelTimeMatrix::from_fn(4, |from, to| {
+            Duration::from_secs(if from == to { 0 } else { 600 })
+        });
+
+        let matrix = clusters.expand(&pois, &routed, 3.6);
+
+        // At 1 m/s the 0.0001° between candidates 1 and 2 takes about 11 s.
+        let local = walking_time(
+            Coord { x: 0.0100, y: 0.0 },
+            Coord { x: 0.0101, y: 0.0 },
+            3.6,
+        );
+        assert_eq!(matrix.get(1, 2), Some(local));
+        assert_eq!(matrix.get(0, 1), Some(Duration::from_secs(600)));
+        assert_eq!(matrix.get(0, 2), Some(Duration::from_secs(600) + local));
+        assert_eq!(matrix.get(2, 3), Some(Duration::from_secs(600) + local));
+        assert_eq!(matrix.get(2, 2), Some(Duration::ZERO));
+    }
+}
@@ -0,0 +1,120 @@
+//! Wall-clock limits for `vrp-core` searches.
+//!
+//! `vrp-core` can bound a search in whole seconds only, which is too coarse
+//! for interactive callers that want an answer within a few hundred
+//! milliseconds. [`with_deadline`] wraps the configured termination so the
+//! search also stops at the first generation boundary after a deadline, and
+//! returns the best route found so far.
+
+use std::time::Instant;
+
+use vrp_core::prelude::{Float, GoalContext};
+use vrp_core::rosomaxa::prelude::Termination;
+use vrp_core::solver::RefinementContext;
+
+use crate::progress::VrpTermination;
+
+/// Wrap `inner` so the search also stops once `deadline` has passed.
+///
+/// The deadline usually includes time spent before the search started, such
+/// as fetching the travel-time matrix, so a search may stop after its first
+/// generation.
+pub(crate) fn with_deadline(inner: VrpTermination, deadline: Instant) -> VrpTermination {
+    Box::new(DeadlineTermination {
+        inner,
+        started_at: Instant::now(),
+        deadline,
+    })
+}
+
+struct DeadlineTermination {
+    inner: VrpTermination,
+    started_at: Instant,
+    deadline: Instant,
+}
+
+impl DeadlineTermination {
+    /// Share of the time between wrapping and the deadline already spent.
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "progress is a ratio of elapsed to allowed time"
+    )]
+    fn elapsed_fraction(&self, now: Instant) -> Float {
+        let allowed = self.deadline.saturating_duration_since(self.started_at);
+        if allowed.is_zero() {
+            return 1.0;
+        }
+        let elapsed = now.saturating_duration_since(self.started_at);
+        (elapsed.as_secs_f64() / allowed.as_secs_f64()).min(1.0)
+    }
+}
+
+impl Termination for DeadlineTermination {
+    type Context = RefinementContext;
+    type Objective = GoalContext;
+
+    fn is_termination(&self, heuristic_ctx: &mut Self::Context) -> bool {
+        self.inner.is_termination(heuristic_ctx) || Instant::now() >= self.deadline
+    }
+
+    fn estimate(&self, heuristic_ctx: &Self::Context) -> Float {
+        self.inner
+            .estimate(heuristic_ctx)
+            .max(self.elapsed_fraction(Instant::now()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for wall-clock search limits.
+
+    use std::time::Duration;
+
+    use super::*;
+    use rstest::rstest;
+
+    /// A termination that never fires and reports no progress.
+    struct Never;
+
+    impl Termination for Never {
+        type Context = RefinementContext;
+        type Objective = GoalContext;
+
+        fn is_termination(&self, _heuristic_ctx: &mut Self::Context) -> bool {
+            false
+        }
+
+        fn estimate(&self, _heuristic_ctx: &Self::Context) -> Float {
+            0.0
+        }
+    }
+
+    fn termination(allowed: Duration) -> DeadlineTermination {
+        let started_at = Instant::now();
+        DeadlineTermination {
+            inner: Box::new(Never),
+            started_at,
+            deadline: started_at + allowed,
+        }
+    }
+
+    #[rstest]
+    #[case::start(Duration::ZERO, 0.0)]
+    #[case::halfway(Duration::from_millis(250), 0.5)]
+    #[case::deadline(Duration::from_millis(500), 1.0)]
+    #[case::overrun(Duration::from_secs(2), 1.0)]
+    fn estimate_tracks_elapsed_time(#[case] elapsed: Duration, #[case] expected: Float) {
+        let limited = termination(Duration::from_millis(500));
+        let fraction = limited.elapsed_fraction(limited.started_at + elapsed);
+        assert_eq!(fraction.to_bits(), expected.to_bits());
+    }
+
+    #[rstest]
+    fn expired_deadline_is_complete() {
+        let limited = termination(Duration::ZERO);
+        assert_eq!(
+            limited.elapsed_fraction(limited.started_at).to_bits(),
+            Float::to_bits(1.0)
+        );
+    }
+}
@@ -0,0 +1,152 @@
+//! Limits on the walk between consecutive stops.
+//!
+//! A visitor who needs to rest often can cap each leg of a route with
+//! [`SolveRequest::max_leg_minutes`] or [`VrpSolverConfig::max_leg_duration`].
+//! [`max_leg_feature`] turns the limit into a `vrp-core` constraint that
+//! rejects any insertion placing a stop further than the limit from its
+//! neighbours, so the search never builds such a leg.
+//!
+//! [`SolveRequest::max_leg_minutes`]: wildside_core::SolveRequest::max_leg_minutes
+//! [`VrpSolverConfig::max_leg_duration`]: crate::VrpSolverConfig::max_leg_duration
+
+use std::time::Duration;
+
+use vrp_core::models::common::Location;
+use vrp_core::prelude::*;
+use wildside_core::TravelTimeMatrix;
+
+/// Violation code reported for insertions that would create an over-long leg.
+const MAX_LEG_CODE: ViolationCode = ViolationCode(101);
+
+/// A feature whose constraint keeps every leg within `limit` according to
+/// `matrix`.
+pub(crate) fn max_leg_feature(
+    matrix: &TravelTimeMatrix,
+    limit: Duration,
+) -> GenericResult<Feature> {
+    FeatureBuilder::default()
+        .with_name("max-leg-duration")
+        .with_constraint(MaxLegConstraint::new(matrix, limit))
+        .build()
+}
+
+/// Rejects insertions that walk further than a limit to or from the new
+/// stop.
+///
+/// Locations index the travel-time matrix directly, as in the VRP model.
+struct MaxLegConstraint {
+    /// Whether the leg between each pair of locations is within the limit.
+    allowed: Vec<Vec<bool>>,
+}
+
+impl MaxLegConstraint {
+    fn new(matrix: &TravelTimeMatrix, limit: Duration) -> Self {
+        let allowed = matrix
+            .rows()
+            .map(|row| row.iter().map(|travel| *travel <= limit).collect())
+            .collect();
+        Self { allowed }
+    }
+
+    fn allows(&self, from: Location, to: Location) -> bool {
+        self.allowed
+            .get(from)
+            .and_then(|row| row.get(to))
+            .copied()
+            .unwrap_or(false)
+    }
+}
+
+impl FeatureConstraint for MaxLegConstraint {
+    fn evaluate(&self, move_ctx: &MoveContext<'_>) -> Option<ConstraintViolation> {
+        let MoveContext::Activity { activity_ctx, .. } = move_ctx else {
+            return None;
+        };
+        let target = activity_ctx.target.place.location;
+        let inbound = self.allows(activity_ctx.prev.place.location, target);
+        let outbound = activity_ctx
+            .next
+            .is_none_or(|next| self.allows(target, next.place.location));
+        if inbound && outbound {
+            None
+        } else {
+            // Another position in the route may still keep both legs short.
+            ConstraintViolation::skip(MAX_LEG_CODE)
+        }
+    }
+
+    fn merge(&self, source: Job, _candidate: Job) -> Result<Job, ViolationCode> {
+        Ok(source)
+    }
+}
+
+/// Whether the leg from location `from` to `to` keeps to `limit`, if there
+/// is one.
+pub(crate) fn within_limit(
+    matrix: &TravelTimeMatrix,
+    limit: Option<Duration>,
+    from: usize,
+    to: usize,
+) -> bool {
+    limit.is_none_or(|max_leg| matrix.get(from, to).is_some_and(|travel| travel <= max_leg))
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for the leg limit.
+
+    use super::*;
+    use rstest::{fixture, rstest};
+
+    /// Depot and two candidates: the first a minute away, the second ten.
+    #[fixture]
+    fn matrix() -> TravelTimeMatrix {
+        TravelTimeMatrix::try_from(vec![
+            vec![
+                Duration::ZERO,
+                Duration::from_mins(1),
+                Duration::from_mins(10),
+            ],
+            vec![
+                Duration::from_mins(1),
+                Duration::ZERO,
+                Duration::from_mins(9),
+            ],
+            vec![
+                Duration::from_mins(10),
+                Duration::from_mins(9),
+                Duration::ZERO,
+            ],
+        ])
+        .expect("test matrix should be square")
+    }
+
+    #[rstest]
+    fn constraint_allows_only_short_legs(matrix: TravelTimeMatrix) {
+        let constraint = MaxLegConstraint::new(&matrix, Duration::from_mins(9));
+        assert!(constraint.allows(0, 1));
+        assert!(constraint.allows(1, 2));
+        assert!(!constraint.allows(0, 2));
+        assert!(!constraint.allows(0, 3));
+    }
+
+    /// A leg checked against an optional limit.
+    struct LegCase {
+        limit: Option<Duration>,
+        from: usize,
+        to: usize,
+    }
+
+    #[rstest]
+    #[case(LegCase { limit: None, from: 0, to: 2 }, true)]
+    #[case(LegCase { limit: Some(Duration::from_mins(10)), from: 0, to: 2 }, true)]
+    #[case(LegCase { limit: Some(Duration::from_mins(9)), from: 0, to: 2 }, false)]
+    #[case(LegCase { limit: Some(Duration::from_mins(9)), from: 2, to: 1 }, true)]
+    fn legs_are_checked_against_the_limit(
+        matrix: TravelTimeMatrix,
+        #[case] leg: LegCase,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(within_limit(&matrix, leg.limit, leg.from, leg.to), expected);
+    }
+}
@@ -8,16 +8,33 @@
 //! The current implementation is intentionally small and deterministic at the API
 //! boundary: it selects candidates synchronously from a [`PoiStore`], queries a
 //! [`TravelTimeProvider`] for a routing matrix, then invokes `vrp-core` to search
-//! for a good route. Any modelling errors are mapped to
-//! [`SolveError::InvalidRequest`].
+//! for a good route. Modelling and search errors surface as
+//! [`SolveError::Internal`](wildside_core::SolveError::Internal).
 
 #![forbid(unsafe_code)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+mod alternatives;
+mod cancel;
+mod cluster;
+mod deadline;
+mod leg;
+mod opening;
+mod progress;
+mod random;
+mod region;
+mod search;
 mod solver;
 mod vrp;
+mod warm_start;
 
+pub use alternatives::AlternativesPolicy;
+pub use cluster::ClusteringPolicy;
+pub use region::CandidateRegionStrategy;
 pub use solver::{VrpSolver, VrpSolverConfig};
+pub use wildside_core::{
+    DiversityPolicy, DwellSelector, DwellTimePolicy, DwellTimeRule, OverlapPolicy,
+};
 
 #[cfg(any(test, feature = "test-support"))]
 pub mod test_support;
@@ -0,0 +1,105 @@
+//! Opening-hours time windows for route candidates.
+//!
+//! When a request carries a start time, a candidate tagged with
+//! `opening_hours` may only be visited while it is open. Windows are offsets
+//! from the start of the walk so they line up with the `vrp-core` schedule,
+//! which starts at zero.
+
+use std::ops::Range;
+use std::time::Duration;
+
+use wildside_core::{OpeningHours, PointOfInterest, WeekTime};
+
+/// Offsets from `start` at which a visit to `poi` may begin.
+///
+/// Each opening window within `budget` is shortened by `dwell` so a visit
+/// that begins inside it ends before closing; windows too short for a visit
+/// are dropped, so an empty list means the POI cannot be visited. Returns
+/// `None` when the POI has no usable opening hours and may be visited at any
+/// time.
+pub(crate) fn visit_windows(
+    poi: &PointOfInterest,
+    start: WeekTime,
+    budget: Duration,
+    dwell: Duration,
+) -> Option<Vec<Range<Duration>>> {
+    let hours = OpeningHours::from_tags(&poi.tags)?
+        .inspect_err(|error| {
+            log::debug!("Ignoring opening hours of POI {}: {error}", poi.id);
+        })
+        .ok()?;
+    Some(hours.visit_windows(start, budget, dwell))
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for candidate visit windows.
+
+    use super::*;
+    use geo::Coord;
+    use rstest::rstest;
+    use wildside_core::{Tags, Weekday};
+
+    fn poi_with_hours(hours: &str) -> PointOfInterest {
+        PointOfInterest::new(
+            1,
+            Coord { x: 0.0, y: 0.0 },
+            Tags::from([("opening_hours".to_owned(), hours.to_owned())]),
+        )
+    }
+
+    fn saturday_at(hour: u8) -> WeekTime {
+        WeekTime::new(Weekday::Saturday, hour, 0).expect("valid time")
+    }
+
+    #[rstest]
+    fn untagged_pois_are_unconstrained() {
+        let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+        let windows = visit_windows(
+            &poi,
+            saturday_at(9),
+            Duration::from_hours(2),
+            Duration::ZERO,
+        );
+        assert_eq!(windows, None);
+    }
+
+    #[rstest]
+    fn unparseable_hours_are_unconstrained() {
+        let poi = poi_with_hours("sunrise-sunset");
+        let windows = visit_windows(
+            &poi,
+            saturday_at(9),
+            Duration::from_hours(2),
+            Duration::ZERO,
+        );
+        assert_eq!(windows, None);
+    }
+
+    #[rstest]
+    fn windows_end_early_enough_to_finish_the_visit() {
+        let poi = poi_with_hours("Sa 10:00-12:00");
+        let windows = visit_windows(
+            &poi,
+            saturday_at(9),
+            Duration::from_hours(4),
+            Duration::from_mins(30),
+        );
+        assert_eq!(
+            windows,
+            Some(vec![Duration::from_hours(1)..Duration::from_mins(150)])
+        );
+    }
+
+    #[rstest]
+    fn windows_shorter_than_the_visit_are_dropped() {
+        let poi = poi_with_hours("Sa 10:00-10:20; Su off");
+        let windows = visit_windows(
+            &poi,
+            saturday_at(9),
+            Duration::from_hours(4),
+            Duration::from_mins(30),
+        );
+        assert_eq!(windows, Some(Vec::new()));
+    }
+}
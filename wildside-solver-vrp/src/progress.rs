@@ -0,0 +1,127 @@
+//! Progress reporting for `vrp-core` searches.
+//!
+//! `vrp-core` polls its termination criteria once per generation, which makes
+//! a wrapping termination the natural hook: [`ProgressReporter`] forwards a
+//! snapshot of the best individual over a channel and stops the search early
+//! once the caller's observer asks to accept the current best route. The
+//! search runs on a worker thread, so the observer itself never has to be
+//! `Send`; [`ProgressReceiver::forward`] invokes it on the calling thread.
+
+use std::ops::ControlFlow;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Instant;
+
+use vrp_core::prelude::{Float, GoalContext};
+use vrp_core::rosomaxa::prelude::{HeuristicContext, Termination};
+use vrp_core::solver::RefinementContext;
+use wildside_core::{ProgressObserver, SolveProgress};
+
+use crate::vrp::{solution_score, solution_stops};
+
+pub(crate) type VrpTermination =
+    Box<dyn Termination<Context = RefinementContext, Objective = GoalContext>>;
+
+/// Sentinel meaning no generation has been reported yet.
+const NOT_REPORTED: usize = usize::MAX;
+
+/// Create a connected reporter/receiver pair for a solve started at `started_at`.
+pub(crate) fn progress_channel(started_at: Instant) -> (ProgressReporter, ProgressReceiver) {
+    let (sender, receiver) = mpsc::channel();
+    let stop = Arc::new(AtomicBool::new(false));
+    (
+        ProgressReporter {
+            sender,
+            stop: Arc::clone(&stop),
+            started_at,
+        },
+        ProgressReceiver { receiver, stop },
+    )
+}
+
+/// Search-side half of a progress channel.
+pub(crate) struct ProgressReporter {
+    sender: Sender<SolveProgress>,
+    stop: Arc<AtomicBool>,
+    started_at: Instant,
+}
+
+impl ProgressReporter {
+    /// Wrap `inner` so each new generation is reported before it is checked.
+    pub(crate) fn wrap(self, inner: VrpTermination) -> VrpTermination {
+        Box::new(ProgressTermination {
+            inner,
+            reporter: self,
+            last_generation: AtomicUsize::new(NOT_REPORTED),
+        })
+    }
+}
+
+/// Caller-side half of a progress channel.
+pub(crate) struct ProgressReceiver {
+    receiver: Receiver<SolveProgress>,
+    stop: Arc<AtomicBool>,
+}
+
+impl ProgressReceiver {
+    /// Pass every snapshot to `on_progress` until the search finishes.
+    ///
+    /// Once the observer breaks, the search is asked to stop and later
+    /// snapshots are discarded.
+    pub(crate) fn forward(self, on_progress: &mut ProgressObserver<'_>) {
+        let mut accepted = false;
+        for progress in self.receiver {
+            if !accepted && on_progress(&progress).is_break() {
+                accepted = true;
+                self.stop.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+struct ProgressTermination {
+    inner: VrpTermination,
+    reporter: ProgressReporter,
+    last_generation: AtomicUsize,
+}
+
+impl ProgressTermination {
+    fn report(&self, heuristic_ctx: &RefinementContext) -> ControlFlow<()> {
+        let generation = heuristic_ctx.statistics().generation;
+        if self.last_generation.swap(generation, Ordering::Relaxed) != generation {
+            let best = heuristic_ctx.ranked().next();
+            let best_score = best.map_or(0.0, solution_score);
+            let best_stops = best.map_or(0, solution_stops);
+            // A dropped receiver only means nobody is listening any more.
+            self.reporter
+                .sender
+                .send(SolveProgress {
+                    generation,
+                    best_score,
+                    best_stops,
+                    elapsed: self.reporter.started_at.elapsed(),
+                })
+                .ok();
+        }
+        if self.reporter.stop.load(Ordering::Relaxed) {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+}
+
+impl Termination for ProgressTermination {
+    type Context = RefinementContext;
+    type Objective = GoalContext;
+
+    fn is_termination(&self, heuristic_ctx: &mut Self::Context) -> bool {
+        let accepted = self.report(heuristic_ctx).is_break();
+        self.inner.is_termination(heuristic_ctx) || accepted
+    }
+
+    fn estimate(&self, heuristic_ctx: &Self::Context) -> Float {
+        self.inner.estimate(heuristic_ctx)
+    }
+}
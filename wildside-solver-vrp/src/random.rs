@@ -0,0 +1,157 @@
+//! Seeded random numbers for the `vrp-core` search.
+//!
+//! `vrp-core` draws every mutation, selection, and acceptance decision from
+//! the [`Random`] in its [`Environment`]. The default draws from the operating
+//! system, so two identical requests could return different routes.
+//! [`SeededRandom`] replays the same sequence for the same
+//! [`SolveRequest::seed`](wildside_core::SolveRequest::seed).
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use vrp_core::construction::heuristics::{RouteContext, SolutionContext};
+use vrp_core::models::FeatureState;
+use vrp_core::models::problem::{Job, JobIdDimension};
+use vrp_core::rosomaxa::utils::{Environment, Parallelism, Random, RandomGen};
+
+/// A [`Random`] whose sequence is fixed by a seed.
+///
+/// `vrp-core` also shuffles through [`RandomGen`], which cannot be seeded:
+/// its repeatable generator is thread-local and starts from the same state on
+/// every new thread. Each search therefore runs on a fresh single-thread pool,
+/// so shuffles replay too.
+pub(crate) struct SeededRandom {
+    rng: Mutex<ChaCha8Rng>,
+}
+
+impl SeededRandom {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self {
+            rng: Mutex::new(ChaCha8Rng::seed_from_u64(seed)),
+        }
+    }
+
+    fn draw<R>(&self, sample: impl FnOnce(&mut ChaCha8Rng) -> R) -> R {
+        let mut rng = self.rng.lock().unwrap_or_else(PoisonError::into_inner);
+        sample(&mut rng)
+    }
+}
+
+impl Random for SeededRandom {
+    fn uniform_int(&self, min: i32, max: i32) -> i32 {
+        if min >= max {
+            return min;
+        }
+        self.draw(|rng| rng.gen_range(min..=max))
+    }
+
+    fn uniform_real(&self, min: f64, max: f64) -> f64 {
+        if min >= max {
+            return min;
+        }
+        self.draw(|rng| rng.gen_range(min..max))
+    }
+
+    fn is_head_not_tails(&self) -> bool {
+        self.draw(|rng| rng.gen_bool(0.5))
+    }
+
+    fn is_hit(&self, probability: f64) -> bool {
+        let clamped = probability.clamp(0.0, 1.0);
+        self.draw(|rng| rng.gen_bool(clamped))
+    }
+
+    fn weighted(&self, weights: &[usize]) -> usize {
+        WeightedIndex::new(weights)
+            .map_or(0, |distribution| self.draw(|rng| distribution.sample(rng)))
+    }
+
+    fn get_rng(&self) -> RandomGen {
+        RandomGen::new_repeatable()
+    }
+}
+
+/// A single-threaded `vrp-core` environment drawing from `seed`.
+///
+/// Parallel evolution merges results in whatever order threads finish, so
+/// the search runs on one thread to stay reproducible.
+pub(crate) fn seeded_environment(seed: u64) -> Arc<Environment> {
+    Arc::new(Environment {
+        random: Arc::new(SeededRandom::new(seed)),
+        parallelism: Parallelism::new_with_cpus(1),
+        ..Environment::default()
+    })
+}
+
+/// Keeps the jobs awaiting insertion in job id order.
+///
+/// `vrp-core` keeps unassigned jobs in a map keyed by allocation address and
+/// returns them to the insertion queue in the map's iteration order, which
+/// changes from run to run. Sorting the queue before the seeded shuffles see
+/// it lets those shuffles replay.
+pub(crate) struct CanonicalJobOrder;
+
+impl FeatureState for CanonicalJobOrder {
+    fn accept_insertion(
+        &self,
+        _solution_ctx: &mut SolutionContext,
+        _route_index: usize,
+        _job: &Job,
+    ) {
+    }
+
+    fn accept_route_state(&self, _route_ctx: &mut RouteContext) {}
+
+    fn accept_solution_state(&self, solution_ctx: &mut SolutionContext) {
+        solution_ctx
+            .required
+            .sort_by(|left, right| left.dimens().get_job_id().cmp(&right.dimens().get_job_id()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for the seeded random source.
+
+    use super::*;
+    use rstest::rstest;
+
+    fn sequence(random: &SeededRandom) -> Vec<i32> {
+        (0..16).map(|_| random.uniform_int(0, 1_000)).collect()
+    }
+
+    #[rstest]
+    fn same_seed_replays_the_sequence() {
+        assert_eq!(
+            sequence(&SeededRandom::new(7)),
+            sequence(&SeededRandom::new(7))
+        );
+    }
+
+    #[rstest]
+    fn different_seeds_diverge() {
+        assert_ne!(
+            sequence(&SeededRandom::new(7)),
+            sequence(&SeededRandom::new(8))
+        );
+    }
+
+    #[rstest]
+    #[case(3, 3, 3)]
+    #[case(5, 2, 5)]
+    fn degenerate_ranges_return_the_minimum(
+        #[case] min: i32,
+        #[case] max: i32,
+        #[case] expected: i32,
+    ) {
+        assert_eq!(SeededRandom::new(1).uniform_int(min, max), expected);
+    }
+
+    #[rstest]
+    fn weighted_choice_skips_zero_weights() {
+        let random = SeededRandom::new(1);
+        assert!((0..32).all(|_| random.weighted(&[0, 4, 0]) == 1));
+    }
+}
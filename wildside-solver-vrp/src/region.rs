@@ -0,0 +1,261 @@
+//! Candidate regions bounding the POIs considered for a solve.
+//!
+//! A [`CandidateRegionStrategy`] turns a request's endpoints and walking
+//! budget into a region on the Earth's surface. The solver queries the store
+//! with the region's bounding rectangle and keeps only the POIs the region
+//! contains. Distances are great-circle metres on a spherical Earth, so the
+//! longitude span of a region widens with latitude instead of assuming a
+//! fixed number of kilometres per degree.
+
+use geo::{Coord, Rect};
+use wildside_core::SolveRequest;
+use wildside_core::geometry::{cap_bounds, great_circle_m};
+
+/// Model used to decide which POIs are close enough to visit.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CandidateRegionStrategy {
+    /// [`Ellipse`](Self::Ellipse) for point-to-point requests, so candidates
+    /// lie in the corridor towards the destination, and
+    /// [`WalkingRadius`](Self::WalkingRadius) for round trips.
+    #[default]
+    Corridor,
+    /// Circles around the start and end whose radius is the distance
+    /// walkable within the budget at the configured speed.
+    WalkingRadius,
+    /// Circles of a fixed radius in metres around the start and end,
+    /// regardless of the budget.
+    FixedRadius {
+        /// Radius of each circle in metres.
+        radius_m: f64,
+    },
+    /// Ellipse with the start and end as foci, containing every point whose
+    /// detour via the start and end fits the walkable distance. Round trips
+    /// degenerate to a circle of half the walkable distance.
+    Ellipse,
+    /// [`Corridor`](Self::Corridor) candidates pruned to those the
+    /// travel-time provider reports as reachable and returnable within the
+    /// budget.
+    ///
+    /// This issues an extra travel-time matrix request over the pre-filtered
+    /// candidates before the solve, trading latency for a tighter candidate
+    /// set in areas where the street network diverges from straight lines.
+    Isochrone,
+}
+
+/// Region resolved for a single request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct CandidateRegion {
+    start: Coord<f64>,
+    end: Coord<f64>,
+    shape: RegionShape,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RegionShape {
+    Circles { radius_m: f64 },
+    Ellipse { major_m: f64 },
+}
+
+impl CandidateRegion {
+    /// Resolve `strategy` for `request` at `speed_kmh`.
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "the walkable distance is derived from the budget and speed"
+    )]
+    pub(crate) fn new(
+        strategy: CandidateRegionStrategy,
+        request: &SolveRequest,
+        speed_kmh: f64,
+    ) -> Self {
+        let reach_m = f64::from(request.duration_minutes) / 60.0 * speed_kmh * 1000.0;
+        let start = request.start;
+        let end = request.end.unwrap_or(start);
+        let ellipse = || RegionShape::Ellipse {
+            major_m: reach_m.max(great_circle_m(start, end)),
+        };
+        let shape = match strategy {
+            CandidateRegionStrategy::Corridor | CandidateRegionStrategy::Isochrone
+                if request.end.is_some() =>
+            {
+                ellipse()
+            }
+            CandidateRegionStrategy::Corridor
+            | CandidateRegionStrategy::WalkingRadius
+            | CandidateRegionStrategy::Isochrone => RegionShape::Circles { radius_m: reach_m },
+            CandidateRegionStrategy::FixedRadius { radius_m } => RegionShape::Circles { radius_m },
+            CandidateRegionStrategy::Ellipse => ellipse(),
+        };
+        Self { start, end, shape }
+    }
+
+    /// Rectangle, in degrees, enclosing the whole region.
+    ///
+    /// Every point of an ellipse lies within half its major axis of one of
+    /// the foci, so both shapes are bounded by circles around the endpoints.
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "an ellipse is bounded by circles of half its major axis"
+    )]
+    pub(crate) fn bounds(&self) -> Rect<f64> {
+        let radius_m = match self.shape {
+            RegionShape::Circles { radius_m } => radius_m,
+            RegionShape::Ellipse { major_m } => major_m / 2.0,
+        };
+        let start = cap_bounds(self.start, radius_m);
+        let end = cap_bounds(self.end, radius_m);
+        Rect::new(
+            Coord {
+                x: start.min().x.min(end.min().x),
+                y: start.min().y.min(end.min().y),
+            },
+            Coord {
+                x: start.max().x.max(end.max().x),
+                y: start.max().y.max(end.max().y),
+            },
+        )
+    }
+
+    /// Whether `location` lies inside the region.
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "ellipse membership sums the distances to both foci"
+    )]
+    pub(crate) fn contains(&self, location: Coord<f64>) -> bool {
+        let from_start = great_circle_m(self.start, location);
+        let to_end = great_circle_m(location, self.end);
+        match self.shape {
+            RegionShape::Circles { radius_m } => from_start.min(to_end) <= radius_m,
+            RegionShape::Ellipse { major_m } => from_start + to_end <= major_m,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for candidate region geometry.
+
+    use rstest::rstest;
+    use wildside_core::InterestProfile;
+
+    use super::*;
+
+    fn request(start: Coord<f64>, end: Option<Coord<f64>>) -> SolveRequest {
+        SolveRequest {
+            start,
+            end,
+            duration_minutes: 60,
+            interests: InterestProfile::new(),
+            seed: 1,
+            ..Default::default()
+        }
+    }
+
+    fn width_m(bounds: Rect<f64>, latitude: f64) -> f64 {
+        great_circle_m(
+            Coord {
+                x: bounds.min().x,
+                y: latitude,
+            },
+            Coord {
+                x: bounds.max().x,
+                y: latitude,
+            },
+        )
+    }
+
+    #[rstest]
+    #[case(0.0)]
+    #[case(51.5)]
+    #[case(69.6)]
+    fn walking_radius_spans_the_same_distance_at_any_latitude(#[case] latitude: f64) {
+        let centre = Coord {
+            x: 10.0,
+            y: latitude,
+        };
+        let region = CandidateRegion::new(
+            CandidateRegionStrategy::WalkingRadius,
+            &request(centre, None),
+            5.0,
+        );
+
+        let width = width_m(region.bounds(), latitude);
+
+        assert!((9_975.0..10_025.0).contains(&width), "width was {width} m");
+    }
+
+    #[rstest]
+    fn caps_reaching_a_pole_span_every_longitude() {
+        let bounds = cap_bounds(Coord { x: 20.0, y: 89.99 }, 5_000.0);
+
+        assert!(bounds.min().x <= -180.0);
+        assert!(bounds.max().x >= 180.0);
+        assert!(bounds.max().y >= 90.0);
+    }
+
+    #[rstest]
+    fn fixed_radius_ignores_the_budget() {
+        let start = Coord { x: 0.0, y: 0.0 };
+        let region = CandidateRegion::new(
+            CandidateRegionStrategy::FixedRadius { radius_m: 500.0 },
+            &request(start, None),
+            5.0,
+        );
+
+        assert!(region.contains(Coord { x: 0.004, y: 0.0 }));
+        assert!(!region.contains(Coord { x: 0.005, y: 0.0 }));
+    }
+
+    #[rstest]
+    fn ellipse_excludes_points_beside_the_direct_line() {
+        let start = Coord { x: 0.0, y: 0.0 };
+        let end = Coord { x: 0.04, y: 0.0 };
+        let ellipse = CandidateRegion::new(
+            CandidateRegionStrategy::Ellipse,
+            &request(start, Some(end)),
+            5.0,
+        );
+        let circles = CandidateRegion::new(
+            CandidateRegionStrategy::WalkingRadius,
+            &request(start, Some(end)),
+            5.0,
+        );
+        let midway = Coord { x: 0.02, y: 0.01 };
+        let behind_start = Coord { x: -0.03, y: 0.0 };
+
+        assert!(ellipse.contains(midway));
+        assert!(!ellipse.contains(behind_start));
+        assert!(circles.contains(behind_start));
+    }
+
+    #[rstest]
+    fn corridor_uses_circles_for_round_trips_and_ellipses_otherwise() {
+        let start = Coord { x: 0.0, y: 0.0 };
+        let end = Coord { x: 0.04, y: 0.0 };
+        let round_trip = request(start, None);
+        let point_to_point = request(start, Some(end));
+
+        for (request, strategy) in [
+            (&round_trip, CandidateRegionStrategy::WalkingRadius),
+            (&point_to_point, CandidateRegionStrategy::Ellipse),
+        ] {
+            assert_eq!(
+                CandidateRegion::new(CandidateRegionStrategy::Corridor, request, 5.0),
+                CandidateRegion::new(strategy, request, 5.0),
+            );
+        }
+    }
+
+    #[rstest]
+    fn ellipse_keeps_the_direct_line_when_the_end_is_out_of_reach() {
+        let start = Coord { x: 0.0, y: 0.0 };
+        let end = Coord { x: 1.0, y: 0.0 };
+        let region = CandidateRegion::new(
+            CandidateRegionStrategy::Ellipse,
+            &request(start, Some(end)),
+            5.0,
+        );
+
+        assert!(region.contains(Coord { x: 0.5, y: 0.0 }));
+        assert!(!region.contains(Coord { x: 0.5, y: 0.1 }));
+    }
+}
@@ -0,0 +1,240 @@
+//! A `vrp-core` search that replays for a seed.
+//!
+//! The default `vrp-core` heuristics weight their operators by how long they
+//! take, and several operators walk a tour's job set, whose order follows
+//! allocation addresses. Either makes two solves of the same request diverge.
+//! [`seeded_heuristic`] keeps the static weighting and swaps the ruin and
+//! local-search operators for ones that walk tours in visiting order, so every
+//! choice is drawn from the seeded [`Environment`].
+
+use std::sync::Arc;
+
+use vrp_core::construction::heuristics::{
+    InsertionContext, RouteContext, SolutionContext, UnassignmentInfo,
+};
+use vrp_core::models::problem::Job;
+use vrp_core::models::solution::Activity;
+use vrp_core::prelude::*;
+use vrp_core::rosomaxa::HeuristicSolution;
+use vrp_core::rosomaxa::hyper::StaticSelective;
+use vrp_core::rosomaxa::utils::Random;
+use vrp_core::solver::search::{
+    CompositeLocalOperator, ExchangeSequence, LocalOperator, LocalSearch, RecreateWithBlinks,
+    RecreateWithCheapest, RecreateWithFarthest, RecreateWithGaps, RecreateWithNearestNeighbor,
+    RecreateWithPerturbation, RecreateWithRegret, RecreateWithSkipBest, RecreateWithSkipRandom,
+    RecreateWithSlice, RemovalLimits, Ruin, RuinAndRecreate, WeightedHeuristicOperator,
+    WeightedRecreate, WeightedRuin,
+};
+use vrp_core::solver::{
+    RefinementContext, TargetHeuristic, TargetSearchOperator, create_scalar_operator_probability,
+};
+
+use crate::vrp::is_worthless;
+
+/// A static heuristic whose every decision comes from `environment.random`.
+pub(crate) fn seeded_heuristic(
+    problem: &Arc<Problem>,
+    environment: &Arc<Environment>,
+) -> TargetHeuristic {
+    let random = &environment.random;
+    let limits = RemovalLimits::new(problem);
+    let search_group = vec![
+        (
+            ruin_and_recreate(&limits, random),
+            create_scalar_operator_probability(1.0, random.clone()),
+        ),
+        (
+            local_search(Arc::new(ExchangeSequence::default())),
+            create_scalar_operator_probability(0.05, random.clone()),
+        ),
+        (
+            local_search(Arc::new(WorthlessStopRemoval)),
+            create_scalar_operator_probability(0.5, random.clone()),
+        ),
+    ];
+    let diversify = Arc::new(WeightedHeuristicOperator::new(
+        vec![
+            ruin_and_recreate(&limits, random),
+            local_search(Arc::new(ExchangeSequence::new(8, 0.5, 0.1))),
+        ],
+        vec![10, 2],
+    ));
+    Box::new(StaticSelective::new(search_group, vec![diversify]))
+}
+
+/// Ruin a seeded share of the route, then rebuild it with one of the
+/// `vrp-core` recreate methods.
+fn ruin_and_recreate(limits: &RemovalLimits, random: &Arc<dyn Random>) -> TargetSearchOperator {
+    let strings: Arc<dyn Ruin> = Arc::new(StringRemoval {
+        limits: limits.clone(),
+    });
+    let scattered: Arc<dyn Ruin> = Arc::new(ScatteredRemoval {
+        limits: limits.clone(),
+    });
+    let ruin = WeightedRuin::new(vec![
+        (vec![(strings, 1.0), (scattered.clone(), 0.1)], 100),
+        (vec![(scattered, 1.0)], 10),
+    ]);
+    let recreate = WeightedRecreate::new(vec![
+        (
+            Arc::new(RecreateWithSkipBest::new(1, 2, random.clone())),
+            50,
+        ),
+        (Arc::new(RecreateWithRegret::new(2, 3, random.clone())), 20),
+        (Arc::new(RecreateWithCheapest::new(random.clone())), 20),
+        (
+            Arc::new(RecreateWithPerturbation::new_with_defaults(random.clone())),
+            10,
+        ),
+        (Arc::new(RecreateWithSkipBest::new(3, 4, random.clone())), 5),
+        (Arc::new(RecreateWithGaps::new(2, 20, random.clone())), 5),
+        (
+            Arc::new(RecreateWithBlinks::new_with_defaults(random.clone())),
+            5,
+        ),
+        (Arc::new(RecreateWithFarthest::new(random.clone())), 2),
+        (
+            Arc::new(RecreateWithNearestNeighbor::new(random.clone())),
+            1,
+        ),
+        (Arc::new(RecreateWithSlice::new(random.clone())), 1),
+        (
+            Arc::new(RecreateWithSkipRandom::default_explorative_phased(
+                Arc::new(RecreateWithCheapest::new(random.clone())),
+                random.clone(),
+            )),
+            1,
+        ),
+    ]);
+    Arc::new(RuinAndRecreate::new(Arc::new(ruin), Arc::new(recreate)))
+}
+
+fn local_search(operator: Arc<dyn LocalOperator>) -> TargetSearchOperator {
+    Arc::new(LocalSearch::new(Arc::new(CompositeLocalOperator::new(
+        vec![(operator, 1)],
+        1,
+        2,
+    ))))
+}
+
+/// Removes a run of consecutive stops from a random route.
+struct StringRemoval {
+    limits: RemovalLimits,
+}
+
+impl Ruin for StringRemoval {
+    fn run(&self, _: &RefinementContext, mut insertion_ctx: InsertionContext) -> InsertionContext {
+        let random = insertion_ctx.environment.random.clone();
+        let Some((route_idx, jobs)) = random_route(&insertion_ctx.solution, random.as_ref()) else {
+            return insertion_ctx;
+        };
+        let count = removal_count(&self.limits, random.as_ref()).min(jobs.len());
+        let start = pick(random.as_ref(), jobs.len() - count + 1).unwrap_or_default();
+        let run = jobs.into_iter().skip(start).take(count);
+        remove_jobs(&mut insertion_ctx.solution, route_idx, run);
+        insertion_ctx
+    }
+}
+
+/// Removes stops chosen at random from a random route.
+struct ScatteredRemoval {
+    limits: RemovalLimits,
+}
+
+impl Ruin for ScatteredRemoval {
+    fn run(&self, _: &RefinementContext, mut insertion_ctx: InsertionContext) -> InsertionContext {
+        let random = insertion_ctx.environment.random.clone();
+        let Some((route_idx, mut jobs)) = random_route(&insertion_ctx.solution, random.as_ref())
+        else {
+            return insertion_ctx;
+        };
+        let count = removal_count(&self.limits, random.as_ref()).min(jobs.len());
+        let removed: Vec<Job> = (0..count)
+            .filter_map(|_| pick(random.as_ref(), jobs.len()).map(|index| jobs.swap_remove(index)))
+            .collect();
+        remove_jobs(&mut insertion_ctx.solution, route_idx, removed);
+        insertion_ctx
+    }
+}
+
+/// Drops optional stops that score nothing.
+///
+/// Recreating a route inserts every stop that fits, so without this a stop
+/// that only adds walking is rarely left out.
+struct WorthlessStopRemoval;
+
+impl LocalOperator for WorthlessStopRemoval {
+    fn explore(
+        &self,
+        refinement_ctx: &RefinementContext,
+        insertion_ctx: &InsertionContext,
+    ) -> Option<InsertionContext> {
+        let mut refined = insertion_ctx.deep_copy();
+        let solution = &mut refined.solution;
+        let mut dropped = Vec::new();
+        for route_ctx in &mut solution.routes {
+            let worthless = visited_jobs(route_ctx)
+                .into_iter()
+                .filter(|job| is_worthless(job) && !solution.locked.contains(job));
+            dropped.extend(worthless.filter(|job| route_ctx.route_mut().tour.remove(job)));
+        }
+        if dropped.is_empty() {
+            return None;
+        }
+        solution.unassigned.extend(
+            dropped
+                .into_iter()
+                .map(|job| (job, UnassignmentInfo::Unknown)),
+        );
+        refinement_ctx.problem.goal.accept_solution_state(solution);
+        Some(refined)
+    }
+}
+
+/// A random route's index and its jobs in visiting order.
+fn random_route(solution: &SolutionContext, random: &dyn Random) -> Option<(usize, Vec<Job>)> {
+    let route_idx = pick(random, solution.routes.len())?;
+    let route_ctx = solution.routes.get(route_idx)?;
+    Some((route_idx, visited_jobs(route_ctx)))
+}
+
+/// Jobs of a route in visiting order.
+///
+/// `Tour::jobs` iterates a set hashed by address, so it cannot be replayed.
+fn visited_jobs(route_ctx: &RouteContext) -> Vec<Job> {
+    route_ctx
+        .route()
+        .tour
+        .all_activities()
+        .filter_map(Activity::retrieve_job)
+        .collect()
+}
+
+/// Move unlocked `jobs` from route `route_idx` back to the insertion queue.
+fn remove_jobs(
+    solution: &mut SolutionContext,
+    route_idx: usize,
+    jobs: impl IntoIterator<Item = Job>,
+) {
+    let Some(route_ctx) = solution.routes.get_mut(route_idx) else {
+        return;
+    };
+    for job in jobs {
+        if !solution.locked.contains(&job) && route_ctx.route_mut().tour.remove(&job) {
+            solution.required.push(job);
+        }
+    }
+}
+
+/// How many stops a ruin removes, within `limits`.
+fn removal_count(limits: &RemovalLimits, random: &dyn Random) -> usize {
+    let range = &limits.removed_activities_range;
+    let span = range.end.saturating_sub(range.start).max(1);
+    range.start + pick(random, span).unwrap_or_default()
+}
+
+/// A uniform index below `len`, or `None` when `len` is zero.
+fn pick(random: &dyn Random, len: usize) -> Option<usize> {
+    let max = i32::try_from(len.checked_sub(1)?).unwrap_or(i32::MAX);
+    usize::try_from(random.uniform_int(0, max)).ok()
+}
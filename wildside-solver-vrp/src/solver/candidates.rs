@@ -0,0 +1,159 @@
+//! Selecting the candidates a solve searches.
+//!
+//! Candidates come from the configured region around the request, less the
+//! POIs it excludes or that are closed for the whole walk, plus any required
+//! POIs the region left out. Only the best-scoring ones are routed.
+
+use std::time::Duration;
+
+use wildside_core::{
+    PoiStore, PointOfInterest, Scorer, SolveError, SolveRequest, TravelTimeProvider,
+};
+
+use super::prune::RoutingInputs;
+use super::routing::end_index;
+use super::{DEPOT_POI_ID, END_POI_ID, Prepared, SearchSpace, SolveControl, VrpSolver};
+use crate::opening::visit_windows;
+use crate::region::{CandidateRegion, CandidateRegionStrategy};
+
+impl<S, T, C> VrpSolver<S, T, C>
+where
+    S: PoiStore,
+    T: TravelTimeProvider,
+    C: Scorer,
+{
+    pub(super) fn select_candidates(
+        &self,
+        request: &SolveRequest,
+    ) -> Result<Vec<(PointOfInterest, f32)>, SolveError> {
+        let region =
+            CandidateRegion::new(self.config.region, request, self.config.average_speed_kmh);
+        let mut pois: Vec<PointOfInterest> = self
+            .store
+            .get_pois_in_bbox(&region.bounds())?
+            .filter(|poi| region.contains(poi.location) && !request.excludes(poi))
+            .collect();
+        if self.config.region == CandidateRegionStrategy::Isochrone {
+            pois = self.within_isochrone(request, pois);
+        }
+        request.add_required(&self.store, &mut pois)?;
+        if let Some(start_time) = request.start_week_time() {
+            let budget = Duration::from_mins(u64::from(request.duration_minutes));
+            pois.retain(|poi| {
+                visit_windows(poi, start_time, budget, self.config.dwell.dwell_time(poi))
+                    .is_none_or(|windows| !windows.is_empty())
+            });
+        }
+
+        let relevance = self.scorer.score_many(&pois, &request.interests);
+        Ok(request.rank_candidates(pois.into_iter().zip(relevance).collect()))
+    }
+
+    /// Keep the POIs that can be reached, visited, and left for the end
+    /// within the budget according to the travel-time provider.
+    ///
+    /// Candidates are kept unpruned when the provider fails, leaving the
+    /// error to surface from the solve's own matrix request.
+    fn within_isochrone(
+        &self,
+        request: &SolveRequest,
+        pois: Vec<PointOfInterest>,
+    ) -> Vec<PointOfInterest> {
+        if pois.is_empty() {
+            return pois;
+        }
+        let end = request.end.unwrap_or(request.start);
+        let mut probe = Vec::with_capacity(pois.len() + 2);
+        probe.push(PointOfInterest::with_empty_tags(
+            DEPOT_POI_ID,
+            request.start,
+        ));
+        probe.extend(pois.iter().cloned());
+        probe.push(PointOfInterest::with_empty_tags(END_POI_ID, end));
+        let matrix = match self
+            .provider_for(request)
+            .and_then(|provider| provider.get_travel_time_matrix(&probe))
+        {
+            Ok(matrix) => matrix,
+            Err(error) => {
+                log::warn!("Isochrone pruning skipped; travel-time matrix failed: {error}");
+                return pois;
+            }
+        };
+        let budget = Duration::from_mins(u64::from(request.duration_minutes));
+        let end_index = probe.len() - 1;
+        pois.into_iter()
+            .zip(1..)
+            .filter(|(poi, index)| {
+                let outbound = matrix.get(0, *index);
+                let onward = matrix.get(*index, end_index);
+                outbound
+                    .zip(onward)
+                    .and_then(|(there, back)| there.checked_add(back))
+                    .and_then(|round_trip| {
+                        round_trip.checked_add(self.config.dwell.dwell_time(poi))
+                    })
+                    .is_some_and(|round_trip| round_trip <= budget)
+            })
+            .map(|(poi, _)| poi)
+            .collect()
+    }
+}
+
+impl<S, T, C> VrpSolver<S, T, C>
+where
+    S: PoiStore + Send + Sync,
+    T: TravelTimeProvider + Send + Sync,
+    C: Scorer + Send + Sync,
+{
+    /// Validate the request, select candidates, and fetch their travel
+    /// times, answering directly when nothing is left to search.
+    pub(super) fn prepare(
+        &self,
+        request: &SolveRequest,
+        control: &SolveControl,
+    ) -> Result<Prepared, SolveError> {
+        request.validate()?;
+        // Checked up front so a request the provider cannot route fails even
+        // when no candidate is left to route.
+        self.provider_for(request)?;
+        control.cancel.check()?;
+
+        let scored_candidates = self.select_candidates(request)?;
+        request.ensure_required(scored_candidates.iter().map(|(poi, _)| poi))?;
+        if scored_candidates.is_empty() {
+            return self
+                .handle_empty_candidates(request, control, 0)
+                .map(Prepared::Answered);
+        }
+
+        control.cancel.check()?;
+        let routing = self.routing_inputs(request, scored_candidates)?;
+        self.search_space(request, control, routing)
+    }
+
+    /// Drop the candidates of `routing` that cannot be reached, answering
+    /// directly when none are left.
+    pub(super) fn search_space(
+        &self,
+        request: &SolveRequest,
+        control: &SolveControl,
+        mut routing: RoutingInputs,
+    ) -> Result<Prepared, SolveError> {
+        let pruned = routing.prune_unreachable(end_index(request, &routing.all_pois)) as u64;
+        if pruned > 0 {
+            log::debug!("Pruned {pruned} candidates unreachable in the travel-time matrix");
+            request.ensure_required(&routing.candidates)?;
+        }
+        if routing.candidates.is_empty() {
+            return self
+                .handle_empty_candidates(request, control, pruned)
+                .map(Prepared::Answered);
+        }
+        Ok(Prepared::Search(SearchSpace {
+            routing,
+            pruned,
+            started_at: control.started_at,
+        }))
+    }
+}
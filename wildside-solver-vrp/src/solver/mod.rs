@@ -11,33 +11,26 @@
 //! - [`DEPOT_POI_ID`]: ID `0`, used for the start location (depot).
 //! - [`END_POI_ID`]: ID `u64::MAX - 1`, used for the end location in point-to-point routes.
 //!
-//! These IDs are chosen to avoid collision with real POI IDs from the data layer.
-//! Note: `u64::MAX` is rejected by the sqlite persistence layer, so we use
-//! `u64::MAX - 1` for the end location to remain within valid bounds should these
-//! POIs ever need to be persisted (though currently they are not).
+//! Both are shared with the other backends through `wildside-core`, which
+//! chooses them to avoid collision with real POI IDs from the data layer.
 
+use std::panic;
+use std::thread::{self, ScopedJoinHandle};
 use std::time::{Duration, Instant};
 
-/// Synthetic POI ID for the depot (start location).
-///
-/// This ID is used internally for travel time matrix computation and is never
-/// included in the returned route.
-const DEPOT_POI_ID: u64 = 0;
-
-/// Synthetic POI ID for the end location in point-to-point routes.
-///
-/// Uses `u64::MAX - 1` to avoid collision with real POI IDs and to remain within
-/// the valid range for the sqlite persistence layer (which rejects `u64::MAX`).
-const END_POI_ID: u64 = u64::MAX - 1;
-
-use geo::{Coord, Rect};
+use geo::Coord;
+use wildside_core::START_POI_ID as DEPOT_POI_ID;
 use wildside_core::{
-    Diagnostics, PoiStore, PointOfInterest, Route, Scorer, SolveError, SolveRequest, SolveResponse,
-    Solver, TravelTimeProvider,
+    CancellationToken, DiversityPolicy, DwellTimePolicy, END_POI_ID, OverlapPolicy, PoiStore,
+    ProgressObserver, Scorer, SolveError, SolveRequest, SolveResponse, Solver, StartChoice,
+    TravelTimeProvider,
 };
 
-use crate::vrp::VrpInstance;
-use crate::vrp::VrpSolveContext;
+use crate::alternatives::AlternativesPolicy;
+use crate::cluster::ClusteringPolicy;
+use crate::progress::{ProgressReporter, progress_channel};
+use crate::region::CandidateRegionStrategy;
+use prune::RoutingInputs;
 
 /// Configuration for [`VrpSolver`].
 #[derive(Debug, Clone)]
@@ -46,6 +39,33 @@ pub struct VrpSolverConfig {
     pub average_speed_kmh: f64,
     /// Upper bound on `vrp-core` generations.
     pub max_generations: usize,
+    /// Wall-clock limit on each solve, measured from the call and including
+    /// candidate selection and the travel-time matrix request.
+    ///
+    /// The search stops at the first generation boundary after the limit and
+    /// returns the best route found so far. When a request sets
+    /// [`SolveRequest::max_solve_ms`], the tighter of the two applies. `None`
+    /// bounds the search by generations only.
+    pub max_solve_duration: Option<Duration>,
+    /// Longest walk allowed between consecutive stops, including the legs
+    /// from the start and to the end.
+    ///
+    /// When a request sets [`SolveRequest::max_leg_minutes`], the tighter of
+    /// the two applies. `None` leaves legs bounded by the budget only.
+    pub max_leg_duration: Option<Duration>,
+    /// Model deciding which POIs around the endpoints become candidates.
+    pub region: CandidateRegionStrategy,
+    /// Time spent at each visited POI, counted against the duration budget.
+    pub dwell: DwellTimePolicy,
+    /// Penalties discouraging routes that keep visiting the same theme.
+    pub diversity: DiversityPolicy,
+    /// Penalties discouraging routes that double back on themselves.
+    pub overlap: OverlapPolicy,
+    /// When to route dense candidate sets through cluster representatives.
+    pub clustering: ClusteringPolicy,
+    /// How [`Solver::solve_alternatives`] restarts the search for distinct
+    /// routes.
+    pub alternatives: AlternativesPolicy,
 }
 
 impl Default for VrpSolverConfig {
@@ -53,6 +73,14 @@ impl Default for VrpSolverConfig {
         Self {
             average_speed_kmh: 5.0,
             max_generations: 50,
+            max_solve_duration: None,
+            max_leg_duration: None,
+            region: CandidateRegionStrategy::default(),
+            dwell: DwellTimePolicy::default(),
+            diversity: DiversityPolicy::default(),
+            overlap: OverlapPolicy::default(),
+            clustering: ClusteringPolicy::default(),
+            alternatives: AlternativesPolicy::default(),
         }
     }
 }
@@ -105,44 +133,46 @@ where
     }
 }
 
-impl<S, T, C> VrpSolver<S, T, C>
-where
-    S: PoiStore + Send + Sync,
-    T: TravelTimeProvider + Send + Sync,
-    C: Scorer + Send + Sync,
-{
-    fn handle_empty_candidates(
-        &self,
-        request: &SolveRequest,
-        started_at: Instant,
-    ) -> Result<SolveResponse, SolveError> {
-        if let Some(end_coord) = request.end {
-            let start = PointOfInterest::with_empty_tags(DEPOT_POI_ID, request.start);
-            let end_poi = PointOfInterest::with_empty_tags(END_POI_ID, end_coord);
-            let all_pois = vec![start, end_poi];
-            let matrix = self
-                .travel_time_provider
-                .get_travel_time_matrix(&all_pois)
-                .map_err(|_| SolveError::InvalidRequest)?;
-            let total_duration = final_leg_duration(0, 1, &matrix);
-            return Ok(SolveResponse {
-                route: Route::with_endpoints(request.start, end_coord, Vec::new(), total_duration),
-                score: 0.0,
-                diagnostics: Diagnostics {
-                    solve_time: started_at.elapsed(),
-                    candidates_evaluated: 0,
-                },
-            });
+/// When a solve started, and how its caller observes or stops it.
+struct SolveControl {
+    started_at: Instant,
+    progress: Option<ProgressReporter>,
+    cancel: CancellationToken,
+}
+
+impl SolveControl {
+    /// Start the clock for a solve that reports no progress.
+    fn new(cancel: CancellationToken) -> Self {
+        Self {
+            started_at: Instant::now(),
+            progress: None,
+            cancel,
         }
-        Ok(SolveResponse {
-            route: Route::with_endpoints(request.start, request.start, Vec::new(), Duration::ZERO),
-            score: 0.0,
-            diagnostics: Diagnostics {
-                solve_time: started_at.elapsed(),
-                candidates_evaluated: 0,
-            },
-        })
     }
+
+    /// Control for a further search within the same solve, without progress
+    /// reporting.
+    fn restart(&self) -> Self {
+        Self {
+            started_at: self.started_at,
+            progress: None,
+            cancel: self.cancel.clone(),
+        }
+    }
+}
+
+/// Outcome of preparing a solve: either candidates to search or a response
+/// that needs no search.
+enum Prepared {
+    Search(SearchSpace),
+    Answered(SolveResponse),
+}
+
+/// Reachable candidates and their travel times, ready to search.
+struct SearchSpace {
+    routing: RoutingInputs,
+    pruned: u64,
+    started_at: Instant,
 }
 
 impl<S, T, C> Solver for VrpSolver<S, T, C>
@@ -151,182 +181,159 @@ where
     T: TravelTimeProvider + Send + Sync,
     C: Scorer + Send + Sync,
 {
+    /// Solve on a scoped worker thread.
+    ///
+    /// `vrp-core` shuffles with a thread-local generator that starts from the
+    /// same state on each new thread, so a fresh thread lets identical
+    /// requests replay identical searches.
     fn solve(&self, request: &SolveRequest) -> Result<SolveResponse, SolveError> {
-        request.validate()?;
-        let started_at = Instant::now();
+        let control = SolveControl::new(CancellationToken::new());
+        thread::scope(|scope| join(scope.spawn(move || self.solve_reporting(request, control))))
+    }
 
-        let scored_candidates = self.select_candidates(request);
-        let route_end = request.end.unwrap_or(request.start);
+    /// Solve while streaming one [`SolveProgress`](wildside_core::SolveProgress)
+    /// per `vrp-core` generation.
+    ///
+    /// The search runs on a scoped worker thread while `on_progress` is called
+    /// on the caller's thread. Returning [`ControlFlow::Break`](std::ops::ControlFlow::Break)
+    /// stops the search at the next generation boundary and returns the best
+    /// route found so far. Requests without candidates return without
+    /// reporting any progress.
+    fn solve_with_progress(
+        &self,
+        request: &SolveRequest,
+        on_progress: &mut ProgressObserver<'_>,
+    ) -> Result<SolveResponse, SolveError> {
+        let mut control = SolveControl::new(CancellationToken::new());
+        let (reporter, receiver) = progress_channel(control.started_at);
+        control.progress = Some(reporter);
+        thread::scope(|scope| {
+            let worker = scope.spawn(move || self.solve_reporting(request, control));
+            receiver.forward(on_progress);
+            join(worker)
+        })
+    }
 
-        if scored_candidates.is_empty() {
-            return self.handle_empty_candidates(request, started_at);
-        }
+    /// Solve on a scoped worker thread until `cancel` is cancelled.
+    ///
+    /// The token is checked before each travel-time request and at every
+    /// `vrp-core` generation boundary.
+    fn solve_with_cancel(
+        &self,
+        request: &SolveRequest,
+        cancel: &CancellationToken,
+    ) -> Result<SolveResponse, SolveError> {
+        let control = SolveControl::new(cancel.clone());
+        thread::scope(|scope| join(scope.spawn(move || self.solve_reporting(request, control))))
+    }
 
-        let (candidates, scores): (Vec<PointOfInterest>, Vec<f32>) =
-            scored_candidates.into_iter().unzip();
-        let depot = PointOfInterest::with_empty_tags(DEPOT_POI_ID, request.start);
-        let end_poi = request
-            .end
-            .map(|end_coord| PointOfInterest::with_empty_tags(END_POI_ID, end_coord));
-        let mut all_pois =
-            Vec::with_capacity(candidates.len() + 1 + usize::from(end_poi.is_some()));
-        all_pois.push(depot);
-        all_pois.extend(candidates.iter().cloned());
-        if let Some(end_poi_value) = end_poi.clone() {
-            all_pois.push(end_poi_value);
+    /// Restart the search with new seeds until `count` distinct routes are
+    /// found or [`AlternativesPolicy::restarts_per_route`] restarts per route
+    /// have run.
+    ///
+    /// Each restart discounts the scores of POIs already on accepted routes,
+    /// as configured by [`VrpSolverConfig::alternatives`]. Candidates and
+    /// travel times are fetched once and shared by every restart, and the
+    /// solve time limit covers all of them.
+    fn solve_alternatives(
+        &self,
+        request: &SolveRequest,
+        count: usize,
+    ) -> Result<Vec<SolveResponse>, SolveError> {
+        if count == 0 {
+            request.validate()?;
+            return Ok(Vec::new());
         }
+        let control = SolveControl::new(CancellationToken::new());
+        thread::scope(|scope| {
+            join(scope.spawn(move || self.find_alternatives(request, count, &control)))
+        })
+    }
 
-        let matrix = self
-            .travel_time_provider
-            .get_travel_time_matrix(&all_pois)
-            .map_err(|_| SolveError::InvalidRequest)?;
-
-        let end_location = end_poi.as_ref().map_or(0, |_| all_pois.len() - 1);
-        let budget_seconds = Duration::from_mins(u64::from(request.duration_minutes));
-        let context = VrpSolveContext::new(&self.config);
-        let instance = VrpInstance::new(&candidates, &scores, &matrix, budget_seconds);
-        let (route_pois, total_score) = context.solve(&instance, end_location)?;
-
-        let total_duration = route_duration(&route_pois, &all_pois, &matrix, end_location);
-        let diagnostics = Diagnostics {
-            solve_time: started_at.elapsed(),
-            candidates_evaluated: candidates.len() as u64,
-        };
+    /// Select candidates and fetch travel times once, for the longest budget,
+    /// then search them once per budget.
+    ///
+    /// Every search uses the request's seed, and the solve time limit covers
+    /// all of them. Budgets too short to visit the required POIs are left off
+    /// the frontier.
+    fn solve_frontier(
+        &self,
+        request: &SolveRequest,
+        budgets: &[u16],
+    ) -> Result<Vec<SolveResponse>, SolveError> {
+        let budgeted = request.with_budgets(budgets)?;
+        let control = SolveControl::new(CancellationToken::new());
+        thread::scope(|scope| join(scope.spawn(move || self.find_frontier(&budgeted, &control))))
+    }
 
-        Ok(SolveResponse {
-            route: Route::with_endpoints(request.start, route_end, route_pois, total_duration),
-            score: total_score,
-            diagnostics,
-        })
+    /// Select each start's candidates, fetch one travel-time matrix covering
+    /// every start and candidate, then search each start over its own
+    /// candidates.
+    ///
+    /// Starts near one another, such as the transit stops around a park,
+    /// share most candidates, so the shared matrix is much smaller than one
+    /// per start. The solve time limit covers every search.
+    fn solve_from_starts(
+        &self,
+        request: &SolveRequest,
+        starts: &[Coord<f64>],
+    ) -> Result<StartChoice, SolveError> {
+        let moved = request.with_starts(starts)?;
+        let control = SolveControl::new(CancellationToken::new());
+        thread::scope(|scope| join(scope.spawn(move || self.find_best_start(&moved, &control))))
     }
 }
 
-#[expect(
-    clippy::float_arithmetic,
-    reason = "candidate selection uses floating-point score and distance heuristics"
-)]
-fn bounding_box(
-    start: Coord<f64>,
-    end_coord: Option<Coord<f64>>,
-    duration_minutes: u16,
-    speed_kmh: f64,
-) -> Rect<f64> {
-    let duration_hours = f64::from(duration_minutes) / 60.0;
-    let distance_km = duration_hours * speed_kmh;
-    let radius_deg = distance_km / 111.0;
-    let min_x = end_coord.map_or(start.x, |end| start.x.min(end.x));
-    let max_x = end_coord.map_or(start.x, |end| start.x.max(end.x));
-    let min_y = end_coord.map_or(start.y, |end| start.y.min(end.y));
-    let max_y = end_coord.map_or(start.y, |end| start.y.max(end.y));
-    Rect::new(
-        Coord {
-            x: min_x - radius_deg,
-            y: min_y - radius_deg,
-        },
-        Coord {
-            x: max_x + radius_deg,
-            y: max_y + radius_deg,
-        },
-    )
+/// Wait for a solve worker, resuming any panic on the calling thread.
+fn join<R>(worker: ScopedJoinHandle<'_, R>) -> R {
+    worker
+        .join()
+        .unwrap_or_else(|payload| panic::resume_unwind(payload))
 }
 
 impl<S, T, C> VrpSolver<S, T, C>
 where
-    S: PoiStore,
-    T: TravelTimeProvider,
-    C: Scorer,
+    S: PoiStore + Send + Sync,
+    T: TravelTimeProvider + Send + Sync,
+    C: Scorer + Send + Sync,
 {
-    fn select_candidates(&self, request: &SolveRequest) -> Vec<(PointOfInterest, f32)> {
-        let bbox = bounding_box(
-            request.start,
-            request.end,
-            request.duration_minutes,
-            self.config.average_speed_kmh,
-        );
-
-        let mut scored: Vec<(PointOfInterest, f32)> = self
-            .store
-            .get_pois_in_bbox(&bbox)
-            .map(|poi| {
-                let score = self.scorer.score(&poi, &request.interests);
-                (poi, score)
-            })
-            .collect();
-
-        scored.sort_unstable_by(|(lhs_poi, lhs_score), (rhs_poi, rhs_score)| {
-            rhs_score
-                .partial_cmp(lhs_score)
-                .unwrap_or(std::cmp::Ordering::Equal)
-                .then_with(|| lhs_poi.id.cmp(&rhs_poi.id))
-        });
-
-        if let Some(max_nodes) = request.max_nodes {
-            let max = usize::from(max_nodes);
-            scored.truncate(max);
-        }
-
-        scored
+    /// Solve `request`, re-planning the rest of any tour under way, and hold
+    /// the whole route to the request's minimums.
+    fn solve_reporting(
+        &self,
+        request: &SolveRequest,
+        control: SolveControl,
+    ) -> Result<SolveResponse, SolveError> {
+        request.validate()?;
+        let response = match request.remainder() {
+            Some(remainder) => {
+                let response = self.solve_planned(&remainder, control)?;
+                self.lock_prefix(request, response)?
+            }
+            None => self.solve_planned(request, control)?,
+        };
+        request.ensure_minimums(response)
     }
-}
 
-fn build_poi_index(all_pois: &[PointOfInterest]) -> std::collections::HashMap<u64, usize> {
-    all_pois
-        .iter()
-        .enumerate()
-        .map(|(idx, poi)| (poi.id, idx))
-        .collect()
-}
-
-fn final_leg_duration(from_index: usize, end_index: usize, matrix: &[Vec<Duration>]) -> Duration {
-    if from_index == end_index {
-        return Duration::ZERO;
+    /// Solve `request` from its start, ignoring any tour under way.
+    fn solve_planned(
+        &self,
+        request: &SolveRequest,
+        control: SolveControl,
+    ) -> Result<SolveResponse, SolveError> {
+        let prepared = self.prepare(request, &control)?;
+        self.search_prepared(request, prepared, control)
     }
-
-    let Some(duration) = matrix
-        .get(from_index)
-        .and_then(|row| row.get(end_index))
-        .copied()
-    else {
-        log::warn!(
-            "Matrix access failed for final leg from index {from_index} to index {end_index}; falling back to zero duration"
-        );
-        debug_assert!(
-            false,
-            "Matrix access failed for final leg from index {from_index} to index {end_index}"
-        );
-        return Duration::ZERO;
-    };
-    duration
 }
 
-fn route_duration(
-    route_pois: &[PointOfInterest],
-    all_pois: &[PointOfInterest],
-    matrix: &[Vec<Duration>],
-    end_index: usize,
-) -> Duration {
-    let mut duration = Duration::ZERO;
-    let mut prev_index = 0_usize;
-    let poi_index = build_poi_index(all_pois);
-    for poi in route_pois {
-        let poi_id = poi.id;
-        let looked_up = poi_index.get(&poi_id).copied();
-        debug_assert!(looked_up.is_some(), "POI {poi_id} not found in index");
-        if looked_up.is_none() {
-            log::warn!(
-                "POI {poi_id} not found in POI index; falling back to previous index {prev_index}"
-            );
-        }
-        let next_index = looked_up.unwrap_or(prev_index);
-        if let Some(row) = matrix.get(prev_index)
-            && let Some(edge) = row.get(next_index)
-        {
-            duration += *edge;
-        }
-        prev_index = next_index;
-    }
-    duration + final_leg_duration(prev_index, end_index, matrix)
-}
+mod candidates;
+mod prune;
+mod replan;
+mod respond;
+mod routing;
+mod search;
+mod starts;
+mod variants;
 
 #[cfg(test)]
 mod tests;
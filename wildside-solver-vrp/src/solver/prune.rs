@@ -0,0 +1,170 @@
+//! Removal of candidates the travel-time matrix marks as unreachable.
+//!
+//! Providers report missing routes as [`Duration::MAX`]. Leaving such
+//! candidates in the VRP instance inflates the search space and feeds
+//! saturated costs into the objective, so they are dropped once the matrix
+//! is known.
+
+use std::time::Duration;
+
+use wildside_core::{PointOfInterest, TravelTimeMatrix};
+
+/// Locations and costs handed to the VRP model.
+///
+/// `all_pois` holds the depot at index `0`, then `candidates` in order, then
+/// the optional end location; `matrix` is indexed the same way and `scores`
+/// runs parallel to `candidates`.
+pub(super) struct RoutingInputs {
+    pub(super) all_pois: Vec<PointOfInterest>,
+    pub(super) candidates: Vec<PointOfInterest>,
+    pub(super) scores: Vec<f32>,
+    pub(super) matrix: TravelTimeMatrix,
+}
+
+impl RoutingInputs {
+    /// Drop candidates that the depot cannot reach or that cannot reach the
+    /// route end at `end_index`, returning how many were removed.
+    ///
+    /// The depot and end location are always kept, so the end location stays
+    /// the last entry of `all_pois` for point-to-point routes.
+    pub(super) fn prune_unreachable(&mut self, end_index: usize) -> usize {
+        let candidate_range = 1..=self.candidates.len();
+        let keep: Vec<bool> = (0..self.all_pois.len())
+            .map(|index| !candidate_range.contains(&index) || self.is_reachable(index, end_index))
+            .collect();
+        let pruned = keep.iter().filter(|kept| !**kept).count();
+        if pruned == 0 {
+            return 0;
+        }
+
+        let candidate_keep = keep.get(candidate_range).unwrap_or_default();
+        self.candidates = retain_by_mask(std::mem::take(&mut self.candidates), candidate_keep);
+        self.scores = retain_by_mask(std::mem::take(&mut self.scores), candidate_keep);
+        self.all_pois = retain_by_mask(std::mem::take(&mut self.all_pois), &keep);
+        self.matrix
+            .retain(|index| keep.get(index).copied().unwrap_or(true));
+        pruned
+    }
+
+    fn is_reachable(&self, index: usize, end_index: usize) -> bool {
+        let reachable = |from: usize, to: usize| {
+            self.matrix
+                .get(from, to)
+                .is_some_and(|duration| duration != Duration::MAX)
+        };
+        reachable(0, index) && reachable(index, end_index)
+    }
+}
+
+fn retain_by_mask<T>(items: Vec<T>, keep: &[bool]) -> Vec<T> {
+    items
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(item, kept)| kept.then_some(item))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    //! Unit tests for unreachable-candidate pruning.
+
+    use geo::Coord;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn location(id: u64) -> PointOfInterest {
+        PointOfInterest::with_empty_tags(id, Coord { x: 0.0, y: 0.0 })
+    }
+
+    fn inputs(matrix: Vec<Vec<u64>>, candidate_ids: &[u64], has_end: bool) -> RoutingInputs {
+        let candidates: Vec<_> = candidate_ids.iter().copied().map(location).collect();
+        let mut all_pois = vec![location(0)];
+        all_pois.extend(candidates.iter().cloned());
+        if has_end {
+            all_pois.push(location(u64::MAX - 1));
+        }
+        RoutingInputs {
+            all_pois,
+            scores: candidate_ids.iter().map(|_| 1.0).collect(),
+            candidates,
+            matrix: TravelTimeMatrix::try_from(
+                matrix
+                    .into_iter()
+                    .map(|row| row.into_iter().map(duration).collect())
+                    .collect::<Vec<_>>(),
+            )
+            .expect("test matrix should be square"),
+        }
+    }
+
+    fn duration(secs: u64) -> Duration {
+        if secs == X {
+            Duration::MAX
+        } else {
+            Duration::from_secs(secs)
+        }
+    }
+
+    fn ids(pois: &[PointOfInterest]) -> Vec<u64> {
+        pois.iter().map(|poi| poi.id).collect()
+    }
+
+    const X: u64 = u64::MAX;
+
+    #[rstest]
+    fn keeps_fully_connected_inputs() {
+        let mut routing = inputs(
+            vec![vec![0, 5, 5], vec![5, 0, 5], vec![5, 5, 0]],
+            &[1, 2],
+            false,
+        );
+
+        assert_eq!(routing.prune_unreachable(0), 0);
+        assert_eq!(ids(&routing.candidates), [1, 2]);
+    }
+
+    #[rstest]
+    fn drops_candidates_cut_off_from_the_depot() {
+        let mut routing = inputs(
+            vec![
+                vec![0, 5, X, 5],
+                vec![5, 0, 5, 5],
+                vec![5, 5, 0, 5],
+                vec![X, 5, 5, 0],
+            ],
+            &[1, 2, 3],
+            false,
+        );
+
+        assert_eq!(routing.prune_unreachable(0), 2);
+        assert_eq!(ids(&routing.candidates), [1]);
+        assert_eq!(ids(&routing.all_pois), [0, 1]);
+        assert_eq!(routing.scores.len(), 1);
+        assert_eq!(
+            routing.matrix.into_rows(),
+            [
+                [Duration::ZERO, Duration::from_secs(5)],
+                [Duration::from_secs(5), Duration::ZERO],
+            ]
+        );
+    }
+
+    #[rstest]
+    fn drops_candidates_that_cannot_reach_the_end() {
+        let mut routing = inputs(
+            vec![
+                vec![0, 5, 5, 9],
+                vec![5, 0, 5, X],
+                vec![5, 5, 0, 5],
+                vec![9, 5, 5, 0],
+            ],
+            &[1, 2],
+            true,
+        );
+
+        assert_eq!(routing.prune_unreachable(3), 1);
+        assert_eq!(ids(&routing.all_pois), [0, 2, u64::MAX - 1]);
+        assert_eq!(routing.matrix.len(), 3);
+    }
+}
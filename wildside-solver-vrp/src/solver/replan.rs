@@ -0,0 +1,36 @@
+//! Re-planning the rest of a tour already under way.
+//!
+//! When a request carries [`SolveRequest::visited`], the solver searches
+//! [`SolveRequest::remainder`] and then puts the visited stops back in front
+//! of the result, so the response describes the whole tour.
+
+use wildside_core::{
+    PoiStore, Scorer, SolveError, SolveRequest, SolveResponse, TravelTimeProvider,
+};
+
+use super::VrpSolver;
+
+impl<S, T, C> VrpSolver<S, T, C>
+where
+    S: PoiStore + Send + Sync,
+    T: TravelTimeProvider + Send + Sync,
+    C: Scorer + Send + Sync,
+{
+    /// Prefix `response`, planned for the remainder of `request`, with the
+    /// stops already visited, and trace the whole route.
+    ///
+    /// See [`SolveRequest::lock_prefix`]. Responses to requests without
+    /// [`SolveRequest::visited`] are returned unchanged.
+    pub(super) fn lock_prefix(
+        &self,
+        request: &SolveRequest,
+        response: SolveResponse,
+    ) -> Result<SolveResponse, SolveError> {
+        if request.visited.is_none() {
+            return Ok(response);
+        }
+        let locked = request.lock_prefix(&self.store, &self.scorer, response)?;
+        let geometry = self.route_geometry(request, &locked.route);
+        Ok(SolveResponse { geometry, ..locked })
+    }
+}
@@ -0,0 +1,311 @@
+//! Laying a found route out as legs and a response.
+
+use std::collections::HashMap;
+
+use std::ops::Range;
+use std::time::Duration;
+
+use geo::LineString;
+use wildside_core::{
+    Diagnostics, PoiStore, PointOfInterest, Route, RouteLeg, RouteStop, Scorer, Shortfall,
+    SolveError, SolveRequest, SolveResponse, TravelTimeMatrix, TravelTimeProvider,
+};
+
+use super::prune::RoutingInputs;
+use super::routing::end_index;
+use super::{DEPOT_POI_ID, END_POI_ID, SearchSpace, SolveControl, VrpSolver};
+use crate::opening::visit_windows;
+use crate::vrp::Found;
+
+impl<S, T, C> VrpSolver<S, T, C>
+where
+    S: PoiStore + Send + Sync,
+    T: TravelTimeProvider + Send + Sync,
+    C: Scorer + Send + Sync,
+{
+    pub(super) fn handle_empty_candidates(
+        &self,
+        request: &SolveRequest,
+        control: &SolveControl,
+        candidates_pruned: u64,
+    ) -> Result<SolveResponse, SolveError> {
+        let started_at = control.started_at;
+        if let Some(end_coord) = request.end {
+            control.cancel.check()?;
+            let start = PointOfInterest::with_empty_tags(DEPOT_POI_ID, request.start);
+            let end_poi = PointOfInterest::with_empty_tags(END_POI_ID, end_coord);
+            let all_pois = vec![start, end_poi];
+            let matrix = self
+                .provider_for(request)?
+                .get_travel_time_matrix(&all_pois)?;
+            let legs = route_legs(&[], &all_pois, &matrix, 1);
+            let total_duration = legs_duration(&legs);
+            let mut route =
+                Route::with_endpoints(request.start, end_coord, Vec::new(), total_duration)
+                    .with_legs(legs);
+            if let Some(start_at) = request.start_at {
+                route = route.with_start_at(start_at);
+            }
+            let response = SolveResponse {
+                route,
+                score: 0.0,
+                diagnostics: Diagnostics {
+                    solve_time: started_at.elapsed(),
+                    candidates_evaluated: 0,
+                    candidates_pruned,
+                },
+                geometry: None,
+            };
+            let limited = ensure_leg_limit(response, self.max_leg(request))?;
+            let geometry = self.route_geometry(request, &limited.route);
+            return Ok(SolveResponse {
+                geometry,
+                ..limited
+            });
+        }
+        Ok(SolveResponse {
+            route: Route::with_endpoints(request.start, request.start, Vec::new(), Duration::ZERO),
+            score: 0.0,
+            diagnostics: Diagnostics {
+                solve_time: started_at.elapsed(),
+                candidates_evaluated: 0,
+                candidates_pruned,
+            },
+            geometry: None,
+        })
+    }
+
+    /// Lay `found` out as a route with legs and geometry.
+    ///
+    /// Fails with [`SolveError::Infeasible`] when a leg breaks the leg
+    /// limit, which the search cannot prevent for the direct walk of a
+    /// point-to-point route without stops.
+    pub(super) fn respond(
+        &self,
+        request: &SolveRequest,
+        space: &SearchSpace,
+        found: Found,
+    ) -> Result<SolveResponse, SolveError> {
+        let routing = &space.routing;
+        let legs = timed_legs(request, routing, &found);
+        let total_duration = legs_duration(&legs);
+        let diagnostics = Diagnostics {
+            solve_time: space.started_at.elapsed(),
+            candidates_evaluated: routing.candidates.len() as u64,
+            candidates_pruned: space.pruned,
+        };
+
+        let route_end = request.end.unwrap_or(request.start);
+        let mut route = Route::with_endpoints(request.start, route_end, found.pois, total_duration)
+            .with_legs(legs);
+        if let Some(start_at) = request.start_at {
+            route = route.with_start_at(start_at);
+        }
+        let response = SolveResponse {
+            route,
+            score: found.score,
+            diagnostics,
+            geometry: None,
+        };
+        let limited = ensure_leg_limit(response, self.max_leg(request))?;
+        let geometry = self.route_geometry(request, &limited.route);
+        Ok(SolveResponse {
+            geometry,
+            ..limited
+        })
+    }
+
+    /// Trace `route` through the travel-time provider `request` calls for.
+    ///
+    /// Geometry is decoration for display, so a provider failure is logged
+    /// and the route is returned without it.
+    pub(super) fn route_geometry(
+        &self,
+        request: &SolveRequest,
+        route: &Route,
+    ) -> Option<LineString<f64>> {
+        self.provider_for(request)
+            .inspect_err(|error| log::warn!("Returning route without geometry: {error}"))
+            .ok()
+            .and_then(|provider| route.trace(provider))
+    }
+}
+
+/// Pass `response` through unless one of its legs walks further than
+/// `max_leg`, in which case fail with [`SolveError::Infeasible`].
+fn ensure_leg_limit(
+    response: SolveResponse,
+    max_leg: Option<Duration>,
+) -> Result<SolveResponse, SolveError> {
+    let Some(limit) = max_leg else {
+        return Ok(response);
+    };
+    match response
+        .route
+        .legs()
+        .iter()
+        .find(|leg| leg.travel_duration > limit)
+    {
+        Some(leg) => {
+            log::debug!(
+                "Leg from {:?} to {:?} exceeds the {limit:?} limit",
+                leg.from,
+                leg.to
+            );
+            Err(SolveError::Infeasible(Shortfall::of(&response)))
+        }
+        None => Ok(response),
+    }
+}
+
+/// Break the route `found` into legs.
+///
+/// When the request has a start time, a stop reached before it opens is
+/// visited once it does, and the rest break begins no earlier than its
+/// window allows, as in the `vrp-core` schedule; the wait counts as time
+/// spent there.
+fn timed_legs(request: &SolveRequest, routing: &RoutingInputs, found: &Found) -> Vec<RouteLeg> {
+    let visits: Vec<(u64, Duration)> = found
+        .pois
+        .iter()
+        .zip(&found.dwell_times)
+        .map(|(poi, dwell)| (poi.id, *dwell))
+        .collect();
+    let mut legs = route_legs(
+        &visits,
+        &routing.all_pois,
+        &routing.matrix,
+        end_index(request, &routing.all_pois),
+    );
+    if let Some(start_time) = request.start_week_time() {
+        let budget = Duration::from_mins(u64::from(request.duration_minutes));
+        let windows: Vec<_> = found
+            .pois
+            .iter()
+            .zip(&found.dwell_times)
+            .enumerate()
+            .map(|(stop, (poi, dwell))| match request.rest_break {
+                Some(rest_break) if found.rest_stop == Some(stop) => {
+                    Some(rest_break.venue_windows(poi, start_time, budget))
+                }
+                _ => visit_windows(poi, start_time, budget, *dwell),
+            })
+            .collect();
+        wait_for_opening(&mut legs, &windows);
+    }
+    legs
+}
+
+/// Break the route into legs from the start, through each visited POI id
+/// with the time spent there, to the route end at `end_index`.
+///
+/// A round trip that visits nothing has no legs.
+pub(super) fn route_legs(
+    visits: &[(u64, Duration)],
+    all_pois: &[PointOfInterest],
+    matrix: &TravelTimeMatrix,
+    end_index: usize,
+) -> Vec<RouteLeg> {
+    let mut legs = Vec::with_capacity(visits.len() + 1);
+    let mut elapsed = Duration::ZERO;
+    let mut prev_index = 0_usize;
+    let mut from = RouteStop::Start;
+    let poi_index = build_poi_index(all_pois);
+    for &(poi_id, visit) in visits {
+        let looked_up = poi_index.get(&poi_id).copied();
+        debug_assert!(looked_up.is_some(), "POI {poi_id} not found in index");
+        if looked_up.is_none() {
+            log::warn!(
+                "POI {poi_id} not found in POI index; falling back to previous index {prev_index}"
+            );
+        }
+        let next_index = looked_up.unwrap_or(prev_index);
+        let travel_duration = matrix.get(prev_index, next_index).unwrap_or_default();
+        elapsed += travel_duration + visit;
+        legs.push(RouteLeg {
+            from,
+            to: RouteStop::Poi(poi_id),
+            travel_duration,
+            dwell: Some(visit),
+            cumulative_duration: elapsed,
+            arrival_at: None,
+        });
+        from = RouteStop::Poi(poi_id);
+        prev_index = next_index;
+    }
+    if visits.is_empty() && end_index == 0 {
+        return legs;
+    }
+    let travel_duration = final_leg_duration(prev_index, end_index, matrix);
+    legs.push(RouteLeg {
+        from,
+        to: RouteStop::End,
+        travel_duration,
+        dwell: None,
+        cumulative_duration: elapsed + travel_duration,
+        arrival_at: None,
+    });
+    legs
+}
+
+/// Count the wait for each stop of `legs` to open as time spent there,
+/// delaying every later leg.
+///
+/// `windows` holds the offsets at which a visit to each POI of the route may
+/// begin, in visiting order; `None` leaves a POI unconstrained. A visit
+/// begins on arrival or at the start of the next window.
+pub(super) fn wait_for_opening(legs: &mut [RouteLeg], windows: &[Option<Vec<Range<Duration>>>]) {
+    let mut pending = windows.iter();
+    let mut delay = Duration::ZERO;
+    for leg in legs {
+        leg.cumulative_duration += delay;
+        if !matches!(leg.to, RouteStop::Poi(_)) {
+            continue;
+        }
+        let Some(Some(open)) = pending.next() else {
+            continue;
+        };
+        let arrival = leg.arrival();
+        let wait = open
+            .iter()
+            .filter(|window| window.end >= arrival)
+            .map(|window| window.start.saturating_sub(arrival))
+            .min()
+            .unwrap_or_default();
+        leg.dwell = leg.dwell.map(|dwell| dwell + wait);
+        leg.cumulative_duration += wait;
+        delay += wait;
+    }
+}
+
+/// Total duration of a route broken into `legs`.
+pub(super) fn legs_duration(legs: &[RouteLeg]) -> Duration {
+    legs.last()
+        .map_or(Duration::ZERO, |leg| leg.cumulative_duration)
+}
+
+fn build_poi_index(all_pois: &[PointOfInterest]) -> HashMap<u64, usize> {
+    all_pois
+        .iter()
+        .enumerate()
+        .map(|(idx, poi)| (poi.id, idx))
+        .collect()
+}
+
+fn final_leg_duration(from_index: usize, end_index: usize, matrix: &TravelTimeMatrix) -> Duration {
+    if from_index == end_index {
+        return Duration::ZERO;
+    }
+
+    let Some(duration) = matrix.get(from_index, end_index) else {
+        log::warn!(
+            "Matrix access failed for final leg from index {from_index} to index {end_index}; falling back to zero duration"
+        );
+        debug_assert!(
+            false,
+            "Matrix access failed for final leg from index {from_index} to index {end_index}"
+        );
+        return Duration::ZERO;
+    };
+    duration
+}
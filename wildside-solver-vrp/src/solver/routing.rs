@@ -0,0 +1,106 @@
+//! Laying candidates out for routing and fetching their travel times.
+
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use wildside_core::{
+    PoiStore, PointOfInterest, Scorer, SolveError, SolveRequest, TravelTimeError, TravelTimeMatrix,
+    TravelTimeProvider,
+};
+
+use super::prune::RoutingInputs;
+use super::{DEPOT_POI_ID, END_POI_ID, VrpSolver};
+
+impl<S, T, C> VrpSolver<S, T, C>
+where
+    S: PoiStore,
+    T: TravelTimeProvider,
+    C: Scorer,
+{
+    /// Lay out the depot, candidates, and optional end, then fetch their
+    /// travel-time matrix.
+    pub(super) fn routing_inputs(
+        &self,
+        request: &SolveRequest,
+        scored_candidates: Vec<(PointOfInterest, f32)>,
+    ) -> Result<RoutingInputs, SolveError> {
+        let (candidates, scores): (Vec<PointOfInterest>, Vec<f32>) =
+            scored_candidates.into_iter().unzip();
+        let all_pois = route_layout(request, &candidates);
+        let matrix = self.travel_times(request, &all_pois, 1..=candidates.len())?;
+        Ok(RoutingInputs {
+            all_pois,
+            candidates,
+            scores,
+            matrix,
+        })
+    }
+
+    /// The travel-time provider to route `request` with: the configured one,
+    /// or its step-free profile when the request needs one.
+    pub(super) fn provider_for(
+        &self,
+        request: &SolveRequest,
+    ) -> Result<&dyn TravelTimeProvider, TravelTimeError> {
+        request.accessibility.provider(&self.travel_time_provider)
+    }
+
+    /// Travel times between `locations` for `request`, clustering the
+    /// `candidates` among them when there are too many to route one by one.
+    pub(super) fn travel_times(
+        &self,
+        request: &SolveRequest,
+        locations: &[PointOfInterest],
+        candidates: RangeInclusive<usize>,
+    ) -> Result<TravelTimeMatrix, SolveError> {
+        let provider = self.provider_for(request)?;
+        let Some(clusters) = self.config.clustering.clusters(locations, candidates) else {
+            return Ok(provider.get_travel_time_matrix(locations)?);
+        };
+        let representatives = clusters.representatives(locations);
+        log::debug!(
+            "Routing {} locations through {} cluster representatives",
+            locations.len(),
+            representatives.len()
+        );
+        let routed = provider.get_travel_time_matrix(&representatives)?;
+        Ok(clusters.expand(locations, &routed, self.config.average_speed_kmh))
+    }
+}
+
+/// The depot, `candidates`, then the end of a point-to-point `request`, as
+/// laid out in the travel-time matrix.
+pub(super) fn route_layout(
+    request: &SolveRequest,
+    candidates: &[PointOfInterest],
+) -> Vec<PointOfInterest> {
+    let mut all_pois = Vec::with_capacity(candidates.len() + 2);
+    all_pois.push(PointOfInterest::with_empty_tags(
+        DEPOT_POI_ID,
+        request.start,
+    ));
+    all_pois.extend(candidates.iter().cloned());
+    if let Some(end_coord) = request.end {
+        all_pois.push(PointOfInterest::with_empty_tags(END_POI_ID, end_coord));
+    }
+    all_pois
+}
+
+/// Time the walk may take: the request's budget, capped by its detour limit
+/// over the direct walk from start to end in `routing`.
+pub(super) fn walk_budget(request: &SolveRequest, routing: &RoutingInputs) -> Duration {
+    let direct = routing
+        .matrix
+        .get(0, end_index(request, &routing.all_pois))
+        .unwrap_or(Duration::MAX);
+    request.detour_budget(direct)
+}
+
+/// Index of the route end in `all_pois`: the end location for
+/// point-to-point requests, otherwise the depot.
+pub(super) const fn end_index(request: &SolveRequest, all_pois: &[PointOfInterest]) -> usize {
+    match request.end {
+        Some(_) => all_pois.len() - 1,
+        None => 0,
+    }
+}
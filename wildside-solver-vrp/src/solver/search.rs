@@ -0,0 +1,153 @@
+//! Searching prepared candidates with `vrp-core`.
+
+use std::time::{Duration, Instant};
+
+use wildside_core::{
+    DiversityPolicy, PoiStore, PointOfInterest, RestBreak, Scorer, SolveError, SolveRequest,
+    SolveResponse, TravelTimeProvider,
+};
+
+use super::prune::RoutingInputs;
+use super::routing::{end_index, walk_budget};
+use super::{Prepared, SolveControl, VrpSolver};
+use crate::opening::visit_windows;
+use crate::vrp::{Found, RestVenue, VrpInstance, VrpSolveContext};
+
+/// Inputs that vary between searches of the same [`SearchSpace`](super::SearchSpace).
+pub(super) struct SearchPlan<'a> {
+    pub(super) scores: &'a [f32],
+    pub(super) seed: u64,
+    pub(super) control: SolveControl,
+}
+
+impl<S, T, C> VrpSolver<S, T, C>
+where
+    S: PoiStore + Send + Sync,
+    T: TravelTimeProvider + Send + Sync,
+    C: Scorer + Send + Sync,
+{
+    /// Search the candidates `prepared` for `request`, unless it is already
+    /// answered.
+    pub(super) fn search_prepared(
+        &self,
+        request: &SolveRequest,
+        prepared: Prepared,
+        control: SolveControl,
+    ) -> Result<SolveResponse, SolveError> {
+        let space = match prepared {
+            Prepared::Search(space) => space,
+            Prepared::Answered(response) => return Ok(response),
+        };
+        let cancel = control.cancel.clone();
+        let plan = SearchPlan {
+            scores: &space.routing.scores,
+            seed: request.seed,
+            control,
+        };
+        let found = self.search(request, &space.routing, plan)?;
+        cancel.check()?;
+        request.ensure_required(&found.pois)?;
+        self.respond(request, &space, found)
+    }
+
+    /// Model the candidates as a `vrp-core` problem and search it.
+    pub(super) fn search(
+        &self,
+        request: &SolveRequest,
+        routing: &RoutingInputs,
+        plan: SearchPlan<'_>,
+    ) -> Result<Found, SolveError> {
+        let candidates = &routing.candidates;
+        let budget_seconds = walk_budget(request, routing);
+        let dwell_times: Vec<Duration> = candidates
+            .iter()
+            .map(|poi| self.config.dwell.dwell_time(poi))
+            .collect();
+        let time_windows: Vec<_> = request
+            .start_week_time()
+            .map_or_else(Vec::new, |start_time| {
+                candidates
+                    .iter()
+                    .zip(&dwell_times)
+                    .map(|(poi, dwell)| visit_windows(poi, start_time, budget_seconds, *dwell))
+                    .collect()
+            });
+        let rest_venues = rest_venues(request, candidates, budget_seconds);
+        let themes: Vec<_> = if self.config.diversity.is_enabled() {
+            candidates.iter().map(DiversityPolicy::category).collect()
+        } else {
+            Vec::new()
+        };
+        let positions: Vec<_> = if self.config.overlap.is_enabled() {
+            routing.all_pois.iter().map(|poi| poi.location).collect()
+        } else {
+            Vec::new()
+        };
+        let context = VrpSolveContext::new(&self.config, plan.seed)
+            .with_deadline(self.deadline(request, plan.control.started_at))
+            .with_cancellation(plan.control.cancel);
+        let instance = VrpInstance::new(candidates, plan.scores, &routing.matrix, budget_seconds)
+            .with_dwell_times(&dwell_times)
+            .with_time_windows(&time_windows)
+            .with_required_ids(&request.required_poi_ids)
+            .with_themes(&themes)
+            .with_positions(&positions)
+            .with_initial_route(&request.initial_route)
+            .with_rest_break(
+                request
+                    .rest_break
+                    .map_or(Duration::ZERO, |rest_break| rest_break.duration()),
+                &rest_venues,
+            )
+            .with_max_leg(self.max_leg(request));
+        context.solve(
+            &instance,
+            end_index(request, &routing.all_pois),
+            plan.control.progress,
+        )
+    }
+
+    /// When the search must stop: the tighter of the configured and requested
+    /// limits after `started_at`.
+    pub(super) fn deadline(&self, request: &SolveRequest, started_at: Instant) -> Option<Instant> {
+        let limit = match (self.config.max_solve_duration, request.max_solve_duration()) {
+            (Some(configured), Some(requested)) => Some(configured.min(requested)),
+            (configured, requested) => configured.or(requested),
+        };
+        limit.and_then(|duration| started_at.checked_add(duration))
+    }
+
+    /// The longest walk allowed between stops: the tighter of the
+    /// configured and requested limits.
+    pub(super) fn max_leg(&self, request: &SolveRequest) -> Option<Duration> {
+        match (self.config.max_leg_duration, request.max_leg_duration()) {
+            (Some(configured), Some(requested)) => Some(configured.min(requested)),
+            (configured, requested) => configured.or(requested),
+        }
+    }
+}
+
+/// Candidates where `request`'s rest break may be taken within `budget`,
+/// with the offsets at which it may begin there.
+///
+/// Empty when the request reserves no break.
+fn rest_venues(
+    request: &SolveRequest,
+    candidates: &[PointOfInterest],
+    budget: Duration,
+) -> Vec<RestVenue> {
+    let (Some(rest_break), Some(start_time)) = (request.rest_break, request.start_week_time())
+    else {
+        return Vec::new();
+    };
+    candidates
+        .iter()
+        .enumerate()
+        .filter(|(_, poi)| RestBreak::is_venue(poi))
+        .map(|(index, poi)| RestVenue {
+            index,
+            windows: rest_break.venue_windows(poi, start_time, budget),
+        })
+        .filter(|venue| !venue.windows.is_empty())
+        .collect()
+}
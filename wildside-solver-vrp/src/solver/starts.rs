@@ -0,0 +1,162 @@
+//! Choosing the best of several candidate starts.
+//!
+//! [`Solver::solve_from_starts`] solves one request from each of several
+//! starts. Each start searches the candidates a solve from it alone would
+//! select, but travel times come from one matrix over every start and the
+//! union of their candidates, so candidates that nearby starts share are
+//! routed once.
+//!
+//! [`Solver::solve_from_starts`]: wildside_core::Solver::solve_from_starts
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use wildside_core::{
+    PoiStore, PointOfInterest, Scorer, SolveError, SolveRequest, SolveResponse, StartChoice,
+    TravelTimeMatrix, TravelTimeProvider,
+};
+
+use super::prune::RoutingInputs;
+use super::routing::route_layout;
+use super::{DEPOT_POI_ID, END_POI_ID, Prepared, SolveControl, VrpSolver};
+
+/// Candidates selected for one start, with their scores.
+type Scored = Vec<(PointOfInterest, f32)>;
+
+/// One travel-time matrix over every start, then each pooled candidate once,
+/// then the end of a point-to-point route.
+struct SharedMatrix {
+    /// Index of each pooled candidate in `matrix`, by identifier.
+    positions: HashMap<u64, usize>,
+    /// Index of the route end in `matrix`, for point-to-point routes.
+    end: Option<usize>,
+    matrix: TravelTimeMatrix,
+}
+
+impl SharedMatrix {
+    /// Routing inputs for the start at `index`, laid out for `request` over
+    /// its own `pool` of scored candidates.
+    fn routing_inputs(&self, index: usize, request: &SolveRequest, pool: Scored) -> RoutingInputs {
+        let (candidates, scores): (Vec<PointOfInterest>, Vec<f32>) = pool.into_iter().unzip();
+        let rows: Vec<Option<usize>> = std::iter::once(Some(index))
+            .chain(
+                candidates
+                    .iter()
+                    .map(|poi| self.positions.get(&poi.id).copied()),
+            )
+            .chain(request.end.map(|_| self.end))
+            .collect();
+        let shared_row = |position: usize| rows.get(position).copied().flatten();
+        let matrix = TravelTimeMatrix::from_fn(rows.len(), |from, to| {
+            shared_row(from)
+                .zip(shared_row(to))
+                .and_then(|(row, column)| self.matrix.get(row, column))
+                .unwrap_or(Duration::MAX)
+        });
+        RoutingInputs {
+            all_pois: route_layout(request, &candidates),
+            candidates,
+            scores,
+            matrix,
+        }
+    }
+}
+
+impl<S, T, C> VrpSolver<S, T, C>
+where
+    S: PoiStore + Send + Sync,
+    T: TravelTimeProvider + Send + Sync,
+    C: Scorer + Send + Sync,
+{
+    /// Solve each of `moved`, one request per candidate start, and pick the
+    /// best route.
+    ///
+    /// Tours under way re-plan from the visitor's position whichever start
+    /// they left from, so they are solved one start at a time.
+    pub(super) fn find_best_start(
+        &self,
+        moved: &[SolveRequest],
+        control: &SolveControl,
+    ) -> Result<StartChoice, SolveError> {
+        if moved.iter().any(|request| request.visited.is_some()) {
+            return StartChoice::best(
+                moved
+                    .iter()
+                    .map(|request| self.solve_reporting(request, control.restart())),
+            );
+        }
+        let selections = moved
+            .iter()
+            .map(|request| {
+                control.cancel.check()?;
+                self.select_candidates(request)
+            })
+            .collect::<Result<Vec<Scored>, SolveError>>()?;
+        control.cancel.check()?;
+        let shared = if selections.iter().all(Vec::is_empty) {
+            None
+        } else {
+            Some(self.shared_matrix(moved, &selections)?)
+        };
+        StartChoice::best(moved.iter().zip(selections).enumerate().map(
+            |(index, (request, scored))| {
+                request.ensure_required(scored.iter().map(|(poi, _)| poi))?;
+                let routing = shared
+                    .as_ref()
+                    .filter(|_| !scored.is_empty())
+                    .map(|matrix| matrix.routing_inputs(index, request, scored));
+                self.solve_start(request, routing, control)
+            },
+        ))
+    }
+
+    /// Fetch one travel-time matrix over the starts of `moved` and every
+    /// candidate in `selections`.
+    fn shared_matrix(
+        &self,
+        moved: &[SolveRequest],
+        selections: &[Scored],
+    ) -> Result<SharedMatrix, SolveError> {
+        let Some(first) = moved.first() else {
+            return Err(SolveError::InvalidRequest);
+        };
+        let mut locations: Vec<PointOfInterest> = moved
+            .iter()
+            .map(|request| PointOfInterest::with_empty_tags(DEPOT_POI_ID, request.start))
+            .collect();
+        let mut positions = HashMap::new();
+        for (poi, _) in selections.iter().flatten() {
+            positions.entry(poi.id).or_insert_with(|| {
+                locations.push(poi.clone());
+                locations.len() - 1
+            });
+        }
+        let pooled = moved.len()..=locations.len().saturating_sub(1);
+        let end = first.end.map(|end_coord| {
+            locations.push(PointOfInterest::with_empty_tags(END_POI_ID, end_coord));
+            locations.len() - 1
+        });
+        let matrix = self.travel_times(first, &locations, pooled)?;
+        Ok(SharedMatrix {
+            positions,
+            end,
+            matrix,
+        })
+    }
+
+    /// Solve `request` over `routing`, or answer it directly when it has no
+    /// candidates, and hold the route to the request's minimums.
+    fn solve_start(
+        &self,
+        request: &SolveRequest,
+        routing: Option<RoutingInputs>,
+        control: &SolveControl,
+    ) -> Result<SolveResponse, SolveError> {
+        let prepared = match routing {
+            Some(inputs) => self.search_space(request, control, inputs)?,
+            None => Prepared::Answered(self.handle_empty_candidates(request, control, 0)?),
+        };
+        let response = self.search_prepared(request, prepared, control.restart())?;
+        request.ensure_minimums(response)
+    }
+}
@@ -0,0 +1,270 @@
+//! Tests for candidate selection, required POIs, and pruning.
+
+use super::*;
+
+#[rstest]
+fn candidate_selection_respects_max_nodes() {
+    let pois = vec![
+        poi(1, 0.0, 0.0, "art"),
+        poi(2, 0.001, 0.0, "history"),
+        poi(3, 0.002, 0.0, "nature"),
+    ];
+    let store = MemoryStore::with_pois(pois);
+    let solver = VrpSolver::new(store, UnitTravelTimeProvider, TagScorer);
+    let interests = InterestProfile::new()
+        .with_weight(Theme::Art, 0.9)
+        .with_weight(Theme::History, 0.4)
+        .with_weight(Theme::Nature, 0.1);
+
+    let request = SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes: 10,
+        interests,
+        seed: 1,
+        max_nodes: Some(2),
+        ..Default::default()
+    };
+
+    let candidates = solver
+        .select_candidates(&request)
+        .expect("select candidates");
+    assert_eq!(candidates.len(), 2);
+    let first = candidates
+        .first()
+        .map(|(poi, _)| poi)
+        .expect("expected first candidate");
+    assert_eq!(first.id, 1);
+    let second = candidates
+        .get(1)
+        .map(|(poi, _)| poi)
+        .expect("expected second candidate");
+    assert_eq!(second.id, 2);
+}
+
+#[rstest]
+fn candidate_selection_skips_excluded_and_avoided_pois() {
+    let pois = vec![
+        poi(1, 0.0, 0.0, "art"),
+        poi(2, 0.001, 0.0, "art"),
+        poi(3, 0.002, 0.0, "art"),
+    ];
+    let solver = VrpSolver::new(
+        MemoryStore::with_pois(pois),
+        UnitTravelTimeProvider,
+        TagScorer,
+    );
+    let construction_zone = Rect::new(
+        Coord {
+            x: 0.0015,
+            y: -0.001,
+        },
+        Coord {
+            x: 0.0025,
+            y: 0.001,
+        },
+    )
+    .to_polygon();
+    let request = SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes: 10,
+        interests: InterestProfile::new().with_weight(Theme::Art, 1.0),
+        seed: 1,
+        excluded_poi_ids: vec![1],
+        avoid_areas: vec![construction_zone],
+        ..Default::default()
+    };
+
+    let candidates = solver
+        .select_candidates(&request)
+        .expect("select candidates");
+
+    let ids: Vec<u64> = candidates.iter().map(|(poi, _)| poi.id).collect();
+    assert_eq!(ids, [2]);
+}
+
+#[rstest]
+fn isochrone_region_prunes_candidates_beyond_the_budget() {
+    let pois = vec![poi(1, 0.001, 0.0, "art"), poi(2, 0.002, 0.0, "art")];
+    let provider = FixedMatrixTravelTimeProvider::from_seconds(vec![
+        vec![0, 60, 400, 0],
+        vec![60, 0, 340, 60],
+        vec![400, 340, 0, 400],
+        vec![0, 60, 400, 0],
+    ]);
+    let solver = VrpSolver::with_config(
+        MemoryStore::with_pois(pois),
+        provider,
+        TagScorer,
+        VrpSolverConfig {
+            region: CandidateRegionStrategy::Isochrone,
+            ..VrpSolverConfig::default()
+        },
+    );
+    let request = SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes: 10,
+        interests: InterestProfile::new().with_weight(Theme::Art, 1.0),
+        seed: 1,
+        ..Default::default()
+    };
+
+    let ids: Vec<u64> = solver
+        .select_candidates(&request)
+        .expect("select candidates")
+        .into_iter()
+        .map(|(poi, _)| poi.id)
+        .collect();
+
+    assert_eq!(ids, [1]);
+}
+
+#[rstest]
+fn walking_radius_excludes_bounding_box_corners() {
+    // 10 minutes at 5 km/h reaches about 833 m, or 0.0075 degrees at the
+    // equator; the corner POI is within the box but about 1 km away.
+    let pois = vec![poi(1, 0.005, 0.0, "art"), poi(2, 0.0065, 0.0065, "art")];
+    let solver = VrpSolver::new(
+        MemoryStore::with_pois(pois),
+        UnitTravelTimeProvider,
+        TagScorer,
+    );
+    let request = SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes: 10,
+        interests: InterestProfile::new().with_weight(Theme::Art, 1.0),
+        seed: 1,
+        ..Default::default()
+    };
+
+    let ids: Vec<u64> = solver
+        .select_candidates(&request)
+        .expect("select candidates")
+        .into_iter()
+        .map(|(poi, _)| poi.id)
+        .collect();
+
+    assert_eq!(ids, [1]);
+}
+
+#[rstest]
+fn point_to_point_candidates_follow_the_corridor() {
+    let pois = vec![poi(1, 0.003, 0.001, "art"), poi(2, -0.004, 0.0, "art")];
+    let solver = VrpSolver::new(
+        MemoryStore::with_pois(pois),
+        UnitTravelTimeProvider,
+        TagScorer,
+    );
+    let request = SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        end: Some(Coord { x: 0.006, y: 0.0 }),
+        duration_minutes: 10,
+        interests: InterestProfile::new().with_weight(Theme::Art, 1.0),
+        seed: 1,
+        ..Default::default()
+    };
+
+    let ids: Vec<u64> = solver
+        .select_candidates(&request)
+        .expect("select candidates")
+        .into_iter()
+        .map(|(poi, _)| poi.id)
+        .collect();
+
+    assert_eq!(ids, [1]);
+}
+
+#[rstest]
+fn required_pois_are_visited_outside_the_region_and_node_limit() {
+    // POI 2 scores nothing and lies far beyond the walking radius, but the
+    // unit travel times make it cheap to reach.
+    let pois = vec![poi(1, 0.0, 0.0, "art"), poi(2, 1.0, 1.0, "amenity")];
+    let solver = VrpSolver::new(
+        MemoryStore::with_pois(pois),
+        UnitTravelTimeProvider,
+        TagScorer,
+    );
+
+    let response = solver
+        .solve(&required_request(vec![2]))
+        .expect("solve should succeed");
+
+    let ids: Vec<u64> = response.route.pois().iter().map(|poi| poi.id).collect();
+    assert_eq!(ids, [2]);
+}
+
+#[rstest]
+#[case::unknown_poi(vec![9], Duration::ZERO)]
+#[case::visit_exceeds_budget(vec![1], Duration::from_mins(20))]
+fn unsatisfiable_requirements_are_reported(
+    #[case] required_poi_ids: Vec<u64>,
+    #[case] dwell: Duration,
+) {
+    let solver = VrpSolver::with_config(
+        MemoryStore::with_pois(vec![poi(1, 0.0, 0.0, "art")]),
+        UnitTravelTimeProvider,
+        TagScorer,
+        VrpSolverConfig {
+            dwell: DwellTimePolicy::new(dwell),
+            ..VrpSolverConfig::default()
+        },
+    );
+
+    let err = solver
+        .solve(&required_request(required_poi_ids))
+        .expect_err("requirement cannot be met");
+
+    assert!(matches!(err, SolveError::InfeasibleRequirement));
+}
+
+#[rstest]
+fn candidate_selection_keeps_only_accessible_pois(progress_request: SolveRequest) {
+    let mut ramped = poi(2, 0.001, 0.0, "history");
+    ramped
+        .tags
+        .insert("wheelchair".to_owned(), "limited".to_owned());
+    let solver = VrpSolver::new(
+        MemoryStore::with_pois(vec![poi(1, 0.0, 0.0, "art"), ramped]),
+        UnitTravelTimeProvider,
+        TagScorer,
+    );
+    let request = SolveRequest {
+        accessibility: AccessibilityRequirements {
+            wheelchair: Some(WheelchairAccess::Limited),
+            step_free: false,
+        },
+        ..progress_request
+    };
+
+    let candidates = solver
+        .select_candidates(&request)
+        .expect("select candidates");
+
+    let ids: Vec<u64> = candidates.iter().map(|(poi, _)| poi.id).collect();
+    assert_eq!(ids, vec![2]);
+}
+
+#[rstest]
+fn solve_prunes_candidates_unreachable_from_the_depot() {
+    let pois = vec![poi(1, 0.0, 0.0, "art"), poi(2, 0.001, 0.0, "art")];
+    let secs = Duration::from_secs;
+    let provider = FixedMatrixTravelTimeProvider::new(vec![
+        vec![Duration::ZERO, secs(30), Duration::MAX],
+        vec![secs(30), Duration::ZERO, secs(30)],
+        vec![Duration::MAX, secs(30), Duration::ZERO],
+    ]);
+    let solver = VrpSolver::new(MemoryStore::with_pois(pois), provider, TagScorer);
+    let request = SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes: 10,
+        interests: InterestProfile::new().with_weight(Theme::Art, 1.0),
+        seed: 1,
+        ..Default::default()
+    };
+
+    let response = solver.solve(&request).expect("solve should succeed");
+
+    assert_eq!(response.diagnostics.candidates_pruned, 1);
+    assert_eq!(response.diagnostics.candidates_evaluated, 1);
+    let ids: Vec<u64> = response.route.pois().iter().map(|poi| poi.id).collect();
+    assert_eq!(ids, [1]);
+}
@@ -0,0 +1,124 @@
+//! Tests for cancellation and progress reporting.
+
+use super::*;
+
+#[rstest]
+fn cancelled_token_stops_before_fetching_travel_times() {
+    let solver = VrpSolver::new(
+        MemoryStore::with_pois(vec![poi(1, 0.0, 0.0, "art")]),
+        TracingProvider { fail: true },
+        TagScorer,
+    );
+    let token = CancellationToken::new();
+    token.cancel();
+
+    let err = solver
+        .solve_with_cancel(&required_request(Vec::new()), &token)
+        .expect_err("cancelled solve should fail");
+
+    assert!(matches!(err, SolveError::Cancelled));
+}
+
+#[rstest]
+fn cancelling_mid_search_abandons_the_route() {
+    let solver = VrpSolver::with_config(
+        MemoryStore::with_pois(vec![poi(1, 0.0, 0.0, "art"), poi(2, 0.001, 0.0, "art")]),
+        UnitTravelTimeProvider,
+        TagScorer,
+        VrpSolverConfig {
+            max_generations: 1_000_000,
+            ..VrpSolverConfig::default()
+        },
+    );
+    let token = CancellationToken::new();
+    let canceller = token.clone();
+
+    let started_at = Instant::now();
+    let outcome = thread::scope(|scope| {
+        scope.spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            canceller.cancel();
+        });
+        solver.solve_with_cancel(&required_request(Vec::new()), &token)
+    });
+
+    assert!(matches!(outcome, Err(SolveError::Cancelled)));
+    assert!(started_at.elapsed() < Duration::from_secs(10));
+}
+
+#[rstest]
+fn solve_with_progress_reports_each_generation(progress_request: SolveRequest) {
+    let solver = progress_solver();
+    let mut snapshots = Vec::new();
+
+    let response = solver
+        .solve_with_progress(&progress_request, &mut |progress| {
+            snapshots.push(*progress);
+            ControlFlow::Continue(())
+        })
+        .expect("solve should succeed");
+
+    assert!(!snapshots.is_empty());
+    assert!(snapshots.windows(2).all(
+        |pair| matches!(pair, [lhs, rhs] if lhs.generation < rhs.generation
+                && lhs.elapsed <= rhs.elapsed)
+    ));
+    let last = snapshots.last().expect("expected a final snapshot");
+    assert!(last.generation <= 20);
+    assert!(last.best_score > 0.0);
+    assert_eq!(last.best_stops, response.route.pois().len());
+    assert!(response.score > 0.0);
+}
+
+#[rstest]
+fn solve_with_progress_stops_when_observer_accepts(progress_request: SolveRequest) {
+    let solver = progress_solver();
+    let mut generations = Vec::new();
+
+    let response = solver
+        .solve_with_progress(&progress_request, &mut |progress| {
+            generations.push(progress.generation);
+            if progress.generation >= 1 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        })
+        .expect("solve should succeed");
+
+    assert_eq!(generations.last().copied(), Some(1));
+    assert!(!response.route.pois().is_empty());
+}
+
+#[rstest]
+fn solve_handle_finishes_with_best_so_far(progress_request: SolveRequest) {
+    let handle = SolveHandle::spawn(Arc::new(progress_solver()), progress_request);
+    while handle
+        .best_so_far()
+        .is_none_or(|progress| progress.generation == 0)
+        && !handle.is_finished()
+    {
+        std::thread::yield_now();
+    }
+
+    let response = handle.finish_now().expect("solve should succeed");
+
+    assert!(!response.route.pois().is_empty());
+    assert!(response.score > 0.0);
+}
+
+#[rstest]
+fn solve_with_progress_skips_reporting_without_candidates(progress_request: SolveRequest) {
+    let solver = VrpSolver::new(MemoryStore::default(), UnitTravelTimeProvider, TagScorer);
+    let mut reported = 0_usize;
+
+    let response = solver
+        .solve_with_progress(&progress_request, &mut |_| {
+            reported += 1;
+            ControlFlow::Continue(())
+        })
+        .expect("solve should succeed");
+
+    assert_eq!(reported, 0);
+    assert!(response.route.pois().is_empty());
+}
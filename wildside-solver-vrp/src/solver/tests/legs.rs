@@ -0,0 +1,165 @@
+//! Tests for laying routes out as legs.
+
+use super::*;
+use crate::solver::respond::{legs_duration, route_legs, wait_for_opening};
+
+#[rstest]
+fn route_legs_add_final_leg_to_end_location() {
+    let start = PointOfInterest::with_empty_tags(0, Coord { x: 0.0, y: 0.0 });
+    let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+    let end = PointOfInterest::with_empty_tags(u64::MAX, Coord { x: 1.0, y: 1.0 });
+    let all_pois = vec![start, poi.clone(), end];
+    let matrix = TravelTimeMatrix::try_from(vec![
+        vec![
+            Duration::ZERO,
+            Duration::from_secs(5),
+            Duration::from_secs(3),
+        ],
+        vec![
+            Duration::from_secs(11),
+            Duration::ZERO,
+            Duration::from_secs(7),
+        ],
+        vec![
+            Duration::from_secs(13),
+            Duration::from_secs(17),
+            Duration::ZERO,
+        ],
+    ])
+    .expect("matrix should be square");
+
+    let legs = route_legs(&[(poi.id, Duration::ZERO)], &all_pois, &matrix, 2);
+    assert_eq!(legs_duration(&legs), Duration::from_secs(12));
+    let stops: Vec<_> = legs.iter().map(|leg| (leg.from, leg.to)).collect();
+    assert_eq!(
+        stops,
+        [
+            (RouteStop::Start, RouteStop::Poi(1)),
+            (RouteStop::Poi(1), RouteStop::End),
+        ]
+    );
+}
+
+#[rstest]
+fn route_legs_return_to_start_when_end_is_depot() {
+    let start = PointOfInterest::with_empty_tags(0, Coord { x: 0.0, y: 0.0 });
+    let poi = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+    let all_pois = vec![start, poi.clone()];
+    let matrix = TravelTimeMatrix::try_from(vec![
+        vec![Duration::ZERO, Duration::from_secs(5)],
+        vec![Duration::from_secs(11), Duration::ZERO],
+    ])
+    .expect("matrix should be square");
+
+    let legs = route_legs(&[(poi.id, Duration::ZERO)], &all_pois, &matrix, 0);
+    assert_eq!(legs_duration(&legs), Duration::from_secs(16));
+}
+
+#[rstest]
+fn route_legs_accumulate_travel_and_dwell() {
+    let start = PointOfInterest::with_empty_tags(0, Coord { x: 0.0, y: 0.0 });
+    let first = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+    let second = PointOfInterest::with_empty_tags(2, Coord { x: 0.0, y: 0.0 });
+    let all_pois = vec![start, first.clone(), second.clone()];
+    let matrix = FixedMatrixTravelTimeProvider::from_seconds(vec![
+        vec![0, 60, 120],
+        vec![60, 0, 30],
+        vec![120, 30, 0],
+    ])
+    .get_travel_time_matrix(&all_pois)
+    .expect("matrix");
+    let dwell = Duration::from_mins(10);
+
+    let legs = route_legs(
+        &[(first.id, dwell), (second.id, dwell)],
+        &all_pois,
+        &matrix,
+        0,
+    );
+
+    assert_eq!(
+        legs,
+        [
+            RouteLeg {
+                from: RouteStop::Start,
+                to: RouteStop::Poi(1),
+                travel_duration: Duration::from_secs(60),
+                dwell: Some(Duration::from_mins(10)),
+                cumulative_duration: Duration::from_secs(660),
+                arrival_at: None,
+            },
+            RouteLeg {
+                from: RouteStop::Poi(1),
+                to: RouteStop::Poi(2),
+                travel_duration: Duration::from_secs(30),
+                dwell: Some(Duration::from_mins(10)),
+                cumulative_duration: Duration::from_secs(1290),
+                arrival_at: None,
+            },
+            RouteLeg {
+                from: RouteStop::Poi(2),
+                to: RouteStop::End,
+                travel_duration: Duration::from_secs(120),
+                dwell: None,
+                cumulative_duration: Duration::from_secs(1410),
+                arrival_at: None,
+            },
+        ]
+    );
+}
+
+#[rstest]
+fn waits_for_opening_delay_later_legs() {
+    let start = PointOfInterest::with_empty_tags(0, Coord { x: 0.0, y: 0.0 });
+    let first = PointOfInterest::with_empty_tags(1, Coord { x: 0.0, y: 0.0 });
+    let second = PointOfInterest::with_empty_tags(2, Coord { x: 0.0, y: 0.0 });
+    let all_pois = vec![start, first.clone(), second.clone()];
+    let matrix = FixedMatrixTravelTimeProvider::from_seconds(vec![
+        vec![0, 60, 120],
+        vec![60, 0, 30],
+        vec![120, 30, 0],
+    ])
+    .get_travel_time_matrix(&all_pois)
+    .expect("matrix");
+    let dwell = Duration::from_mins(10);
+    let mut legs = route_legs(
+        &[(first.id, dwell), (second.id, dwell)],
+        &all_pois,
+        &matrix,
+        0,
+    );
+
+    // The first stop opens a minute after it is reached.
+    let windows = [
+        Some(vec![Duration::from_secs(120)..Duration::from_secs(600)]),
+        None,
+    ];
+    wait_for_opening(&mut legs, &windows);
+
+    let timings: Vec<(Option<Duration>, Duration)> = legs
+        .iter()
+        .map(|leg| (leg.dwell, leg.cumulative_duration))
+        .collect();
+    assert_eq!(
+        timings,
+        [
+            (Some(Duration::from_secs(660)), Duration::from_secs(720)),
+            (Some(dwell), Duration::from_secs(1350)),
+            (None, Duration::from_secs(1470)),
+        ]
+    );
+    assert_eq!(
+        legs.first().map(RouteLeg::arrival),
+        Some(Duration::from_secs(60))
+    );
+}
+
+#[rstest]
+fn round_trips_without_stops_have_no_legs() {
+    let all_pois = vec![PointOfInterest::with_empty_tags(
+        0,
+        Coord { x: 0.0, y: 0.0 },
+    )];
+    let matrix = TravelTimeMatrix::try_from(vec![vec![Duration::ZERO]]).expect("matrix");
+    assert!(route_legs(&[], &all_pois, &matrix, 0).is_empty());
+}
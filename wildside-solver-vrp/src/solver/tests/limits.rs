@@ -0,0 +1,220 @@
+//! Tests for request validation and the time, leg, and detour limits.
+
+use super::*;
+
+#[rstest]
+fn invalid_request_is_rejected() {
+    let store = MemoryStore::default();
+    let solver = VrpSolver::new(store, UnitTravelTimeProvider, TagScorer);
+    let request = SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes: 0,
+        interests: InterestProfile::new(),
+        seed: 1,
+        ..Default::default()
+    };
+
+    let err = solver
+        .solve(&request)
+        .expect_err("expected invalid request error");
+    assert!(matches!(err, SolveError::InvalidRequest));
+}
+
+#[rstest]
+#[case::unlimited(None, None, None)]
+#[case::configured(Some(300), None, Some(300))]
+#[case::requested(None, Some(200), Some(200))]
+#[case::tighter_request(Some(300), Some(200), Some(200))]
+#[case::tighter_config(Some(100), Some(200), Some(100))]
+fn deadline_uses_the_tighter_limit(
+    #[case] configured_ms: Option<u64>,
+    #[case] requested_ms: Option<u32>,
+    #[case] expected_ms: Option<u64>,
+) {
+    let solver = VrpSolver::with_config(
+        MemoryStore::default(),
+        UnitTravelTimeProvider,
+        TagScorer,
+        VrpSolverConfig {
+            max_solve_duration: configured_ms.map(Duration::from_millis),
+            ..VrpSolverConfig::default()
+        },
+    );
+    let request = SolveRequest {
+        max_solve_ms: requested_ms,
+        ..required_request(Vec::new())
+    };
+    let started_at = Instant::now();
+
+    let deadline = solver.deadline(&request, started_at);
+
+    assert_eq!(
+        deadline.map(|instant| instant.duration_since(started_at)),
+        expected_ms.map(Duration::from_millis)
+    );
+}
+
+#[rstest]
+#[case::unlimited(None, None, None)]
+#[case::configured(Some(5), None, Some(5))]
+#[case::requested(None, Some(4), Some(4))]
+#[case::tighter_request(Some(5), Some(4), Some(4))]
+#[case::tighter_config(Some(3), Some(4), Some(3))]
+fn leg_limit_uses_the_tighter_limit(
+    #[case] configured_mins: Option<u64>,
+    #[case] requested_mins: Option<u16>,
+    #[case] expected_mins: Option<u64>,
+) {
+    let solver = VrpSolver::with_config(
+        MemoryStore::default(),
+        UnitTravelTimeProvider,
+        TagScorer,
+        VrpSolverConfig {
+            max_leg_duration: configured_mins.map(Duration::from_mins),
+            ..VrpSolverConfig::default()
+        },
+    );
+    let request = SolveRequest {
+        max_leg_minutes: requested_mins,
+        min_pois: None,
+        min_score: None,
+        ..required_request(Vec::new())
+    };
+
+    assert_eq!(
+        solver.max_leg(&request),
+        expected_mins.map(Duration::from_mins)
+    );
+}
+
+#[rstest]
+fn stops_beyond_the_leg_limit_are_left_off_the_route() {
+    // POI 2 scores as well as POI 1 but is ten minutes from everything.
+    let pois = vec![poi(1, 0.0, 0.0, "art"), poi(2, 0.001, 0.0, "art")];
+    let provider = FixedMatrixTravelTimeProvider::from_seconds(vec![
+        vec![0, 60, 600],
+        vec![60, 0, 600],
+        vec![600, 600, 0],
+    ]);
+    let solver = VrpSolver::new(MemoryStore::with_pois(pois), provider, TagScorer);
+    let request = SolveRequest {
+        duration_minutes: 30,
+        max_nodes: None,
+        max_leg_minutes: Some(5),
+        min_pois: None,
+        min_score: None,
+        ..required_request(Vec::new())
+    };
+
+    let response = solver.solve(&request).expect("solve should succeed");
+
+    let ids: Vec<u64> = response.route.pois().iter().map(|poi| poi.id).collect();
+    assert_eq!(ids, [1]);
+    assert!(
+        response
+            .route
+            .legs()
+            .iter()
+            .all(|leg| leg.travel_duration <= Duration::from_mins(5))
+    );
+}
+
+#[rstest]
+fn direct_walks_beyond_the_leg_limit_are_infeasible() {
+    let provider = FixedMatrixTravelTimeProvider::from_seconds(vec![vec![0, 600], vec![600, 0]]);
+    let solver = VrpSolver::new(MemoryStore::default(), provider, TagScorer);
+    let request = SolveRequest {
+        end: Some(Coord { x: 0.01, y: 0.0 }),
+        duration_minutes: 30,
+        max_leg_minutes: Some(5),
+        min_pois: None,
+        min_score: None,
+        ..required_request(Vec::new())
+    };
+
+    let err = solver
+        .solve(&request)
+        .expect_err("no route keeps every leg short");
+
+    assert!(matches!(err, SolveError::Infeasible(_)));
+}
+
+#[rstest]
+#[case::unlimited(None, 1)]
+#[case::direct(Some(1.5), 0)]
+fn detour_limits_cap_point_to_point_walks(
+    #[case] max_detour: Option<f32>,
+    #[case] expected_stops: usize,
+) {
+    let solver = VrpSolver::new(
+        MemoryStore::with_pois(vec![poi(1, 0.0001, 0.0, "art")]),
+        UnitTravelTimeProvider,
+        TagScorer,
+    );
+    // The direct walk takes a second, so any stop doubles it.
+    let request = SolveRequest {
+        end: Some(Coord { x: 0.0002, y: 0.0 }),
+        max_detour,
+        ..required_request(Vec::new())
+    };
+
+    let response = solver.solve(&request).expect("solve should succeed");
+
+    assert_eq!(response.route.pois().len(), expected_stops);
+}
+
+#[rstest]
+fn routes_short_of_the_minimums_are_infeasible() {
+    let solver = VrpSolver::new(
+        MemoryStore::with_pois(vec![poi(1, 0.0, 0.0, "art")]),
+        UnitTravelTimeProvider,
+        TagScorer,
+    );
+    let request = SolveRequest {
+        min_pois: Some(2),
+        ..required_request(Vec::new())
+    };
+
+    let err = solver
+        .solve(&request)
+        .expect_err("one POI cannot make two stops");
+
+    let SolveError::Infeasible(shortfall) = err else {
+        panic!("expected Infeasible, got {err:?}");
+    };
+    assert_eq!(shortfall.pois, 1);
+    assert_eq!(shortfall.diagnostics.candidates_evaluated, 1);
+}
+
+#[rstest]
+fn solve_returns_the_best_route_when_time_runs_out() {
+    let pois = vec![
+        poi(1, 0.0, 0.0, "art"),
+        poi(2, 0.001, 0.0, "history"),
+        poi(3, 0.002, 0.0, "art"),
+        poi(4, 0.0, 0.001, "history"),
+    ];
+    let solver = VrpSolver::with_config(
+        MemoryStore::with_pois(pois),
+        UnitTravelTimeProvider,
+        TagScorer,
+        VrpSolverConfig {
+            max_generations: 1_000_000,
+            ..VrpSolverConfig::default()
+        },
+    );
+    let request = SolveRequest {
+        max_nodes: None,
+        max_solve_ms: Some(50),
+        max_leg_minutes: None,
+        min_pois: None,
+        min_score: None,
+        ..required_request(Vec::new())
+    };
+
+    let started_at = Instant::now();
+    let response = solver.solve(&request).expect("solve should succeed");
+
+    assert!(started_at.elapsed() < Duration::from_secs(10));
+    assert!(!response.route.pois().is_empty());
+}
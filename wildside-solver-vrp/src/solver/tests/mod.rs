@@ -0,0 +1,101 @@
+//! Tests for the `VrpSolver`.
+
+use std::ops::ControlFlow;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use super::*;
+use geo::{Coord, LineString, Rect};
+use rstest::{fixture, rstest};
+use wildside_core::test_support::{MemoryStore, TagScorer, UnitTravelTimeProvider};
+use wildside_core::{
+    AccessibilityRequirements, InterestProfile, PointOfInterest, RestBreak, RouteLeg, RouteStop,
+    SolveHandle, Tags, Theme, TravelTimeError, TravelTimeMatrix, VisitedPrefix, WheelchairAccess,
+};
+
+use crate::test_support::{FixedMatrixTravelTimeProvider, poi};
+use crate::{CandidateRegionStrategy, ClusteringPolicy, DiversityPolicy, OverlapPolicy};
+
+mod candidates;
+mod control;
+mod legs;
+mod limits;
+mod penalties;
+mod providers;
+mod replan;
+mod schedule;
+mod variants;
+
+#[fixture]
+fn progress_request() -> SolveRequest {
+    SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes: 10,
+        interests: InterestProfile::new()
+            .with_weight(Theme::Art, 0.8)
+            .with_weight(Theme::History, 0.5),
+        seed: 1,
+        ..Default::default()
+    }
+}
+
+fn progress_solver() -> VrpSolver<MemoryStore, UnitTravelTimeProvider, TagScorer> {
+    let pois = vec![poi(1, 0.0, 0.0, "art"), poi(2, 0.001, 0.0, "history")];
+    VrpSolver::with_config(
+        MemoryStore::with_pois(pois),
+        UnitTravelTimeProvider,
+        TagScorer,
+        VrpSolverConfig {
+            max_generations: 20,
+            ..VrpSolverConfig::default()
+        },
+    )
+}
+
+/// Unit travel times whose geometry joins the stops with straight lines, or
+/// fails when `fail` is set.
+struct TracingProvider {
+    fail: bool,
+}
+
+impl TravelTimeProvider for TracingProvider {
+    fn get_travel_time_matrix(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> Result<TravelTimeMatrix, TravelTimeError> {
+        UnitTravelTimeProvider.get_travel_time_matrix(pois)
+    }
+
+    fn route_geometry(
+        &self,
+        stops: &[Coord<f64>],
+    ) -> Result<Option<LineString<f64>>, TravelTimeError> {
+        if self.fail {
+            return Err(TravelTimeError::EmptyInput);
+        }
+        Ok(Some(LineString::from(stops.to_vec())))
+    }
+}
+
+fn required_request(required_poi_ids: Vec<u64>) -> SolveRequest {
+    SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes: 10,
+        interests: InterestProfile::new().with_weight(Theme::Art, 1.0),
+        seed: 1,
+        max_nodes: Some(1),
+        required_poi_ids,
+        ..Default::default()
+    }
+}
+
+fn themed_poi(id: u64, x: f64, interest: &str, (key, value): (&str, &str)) -> PointOfInterest {
+    PointOfInterest::new(
+        id,
+        Coord { x, y: 0.0 },
+        Tags::from([
+            (interest.to_owned(), String::new()),
+            (key.to_owned(), value.to_owned()),
+        ]),
+    )
+}
@@ -0,0 +1,78 @@
+//! Tests for the theme diversity and route overlap penalties.
+
+use super::*;
+
+#[rstest]
+#[case::scores_only(DiversityPolicy::default(), false)]
+#[case::penalise_repeats(DiversityPolicy::new(0.8, 0.2), true)]
+fn diversity_penalties_mix_themes(#[case] diversity: DiversityPolicy, #[case] visits_nature: bool) {
+    let pois = vec![
+        themed_poi(1, 0.0, "history", ("historic", "church")),
+        themed_poi(2, 0.0001, "history", ("historic", "church")),
+        themed_poi(3, 0.0002, "history", ("historic", "church")),
+        themed_poi(4, 0.0003, "nature", ("natural", "tree")),
+    ];
+    // Four-minute visits leave room for two stops in the ten-minute budget.
+    let solver = VrpSolver::with_config(
+        MemoryStore::with_pois(pois),
+        UnitTravelTimeProvider,
+        TagScorer,
+        VrpSolverConfig {
+            dwell: DwellTimePolicy::new(Duration::from_mins(4)),
+            diversity,
+            ..VrpSolverConfig::default()
+        },
+    );
+    let request = SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes: 10,
+        interests: InterestProfile::new()
+            .with_weight(Theme::History, 1.0)
+            .with_weight(Theme::Nature, 0.5),
+        seed: 1,
+        ..Default::default()
+    };
+
+    let response = solver.solve(&request).expect("solve should succeed");
+
+    // Two churches score 2.0; with penalties they net 1.0, below the 1.5 of
+    // a church and the tree.
+    let stops = response.route.pois();
+    assert_eq!(stops.len(), 2);
+    assert_eq!(stops.iter().any(|poi| poi.id == 4), visits_nature);
+}
+
+#[rstest]
+fn overlap_penalties_turn_out_and_back_routes_into_loops() {
+    // POIs 1 and 2 lie on one line east of the start, so visiting both
+    // means walking back over the same ground; POI 3 lies north of POI 1.
+    let pois = vec![
+        poi(1, 0.001, 0.0, "art"),
+        poi(2, 0.002, 0.0, "art"),
+        poi(3, 0.001, 0.001, "art"),
+    ];
+    // Four-minute visits leave room for two stops in the ten-minute budget.
+    let solver = VrpSolver::with_config(
+        MemoryStore::with_pois(pois),
+        UnitTravelTimeProvider,
+        TagScorer,
+        VrpSolverConfig {
+            dwell: DwellTimePolicy::new(Duration::from_mins(4)),
+            overlap: OverlapPolicy::new(1.0),
+            ..VrpSolverConfig::default()
+        },
+    );
+    let request = SolveRequest {
+        max_nodes: None,
+        ..required_request(Vec::new())
+    };
+
+    let response = solver.solve(&request).expect("solve should succeed");
+
+    // Out to POI 2 and back costs a full U-turn; any route through POI 3
+    // turns back at most 135 degrees.
+    let stops = response.route.pois();
+    assert_eq!(stops.len(), 2);
+    assert!(stops.iter().any(|poi| poi.id == 3));
+    assert!(response.score > 1.9);
+}
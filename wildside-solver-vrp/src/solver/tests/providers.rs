@@ -0,0 +1,96 @@
+//! Tests for how the solver uses its travel-time provider.
+
+use super::*;
+
+/// Unit travel times that record the size of every matrix requested.
+#[derive(Default)]
+struct RecordingProvider {
+    sizes: Mutex<Vec<usize>>,
+}
+
+impl TravelTimeProvider for RecordingProvider {
+    fn get_travel_time_matrix(
+        &self,
+        pois: &[PointOfInterest],
+    ) -> Result<TravelTimeMatrix, TravelTimeError> {
+        self.sizes.lock().expect("sizes lock").push(pois.len());
+        UnitTravelTimeProvider.get_travel_time_matrix(pois)
+    }
+}
+
+#[rstest]
+fn dense_candidates_are_routed_through_cluster_representatives() {
+    // Two pairs of POIs a metre apart, some 110 m from one another.
+    let pois = vec![
+        poi(1, 0.001, 0.0, "art"),
+        poi(2, 0.001_01, 0.0, "art"),
+        poi(3, 0.002, 0.0, "art"),
+        poi(4, 0.002_01, 0.0, "art"),
+    ];
+    let solver = VrpSolver::with_config(
+        MemoryStore::with_pois(pois),
+        RecordingProvider::default(),
+        TagScorer,
+        VrpSolverConfig {
+            clustering: ClusteringPolicy {
+                threshold: Some(2),
+                cell_size_m: 50.0,
+            },
+            ..VrpSolverConfig::default()
+        },
+    );
+    let request = SolveRequest {
+        max_nodes: None,
+        ..required_request(Vec::new())
+    };
+
+    let response = solver.solve(&request).expect("solve should succeed");
+
+    assert_eq!(response.route.pois().len(), 4);
+    let sizes = solver
+        .travel_time_provider
+        .sizes
+        .lock()
+        .expect("sizes lock")
+        .clone();
+    assert_eq!(sizes, [3]);
+}
+
+#[rstest]
+#[case::traced(false)]
+#[case::provider_failure(true)]
+fn geometry_traces_the_route_through_the_provider(#[case] fail: bool) {
+    let solver = VrpSolver::new(
+        MemoryStore::with_pois(vec![poi(1, 0.001, 0.0, "art")]),
+        TracingProvider { fail },
+        TagScorer,
+    );
+
+    let response = solver
+        .solve(&required_request(Vec::new()))
+        .expect("solve should succeed");
+
+    let expected = (!fail).then(|| {
+        LineString::from(vec![
+            Coord { x: 0.0, y: 0.0 },
+            Coord { x: 0.001, y: 0.0 },
+            Coord { x: 0.0, y: 0.0 },
+        ])
+    });
+    assert_eq!(response.geometry, expected);
+}
+
+#[rstest]
+fn step_free_solves_need_a_step_free_profile(progress_request: SolveRequest) {
+    let request = SolveRequest {
+        accessibility: AccessibilityRequirements::wheelchair_user(),
+        ..progress_request
+    };
+    let err = progress_solver()
+        .solve(&request)
+        .expect_err("the unit provider cannot avoid stairs");
+    assert!(matches!(
+        err,
+        SolveError::TravelTime(TravelTimeError::StepFreeUnavailable)
+    ));
+}
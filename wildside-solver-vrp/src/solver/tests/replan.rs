@@ -0,0 +1,108 @@
+//! Tests for warm starts and re-planning a tour under way.
+
+use super::*;
+
+#[rstest]
+fn warm_start_keeps_the_previous_route() {
+    let pois = vec![
+        themed_poi(1, 0.0, "history", ("historic", "church")),
+        themed_poi(2, 0.0001, "nature", ("natural", "tree")),
+        themed_poi(3, 0.0002, "history", ("historic", "castle")),
+        themed_poi(4, 0.0003, "nature", ("natural", "tree")),
+    ];
+    let config = VrpSolverConfig {
+        dwell: DwellTimePolicy::new(Duration::from_mins(4)),
+        ..VrpSolverConfig::default()
+    };
+    let request = SolveRequest {
+        interests: InterestProfile::new()
+            .with_weight(Theme::History, 1.0)
+            .with_weight(Theme::Nature, 0.5),
+        max_nodes: None,
+        ..required_request(Vec::new())
+    };
+    let previous = VrpSolver::with_config(
+        MemoryStore::with_pois(pois.clone()),
+        UnitTravelTimeProvider,
+        TagScorer,
+        config.clone(),
+    )
+    .solve(&request)
+    .expect("solve should succeed");
+
+    // A single generation is too short to rediscover the route unaided, and
+    // the unknown POI 99 is ignored.
+    let hurried = VrpSolver::with_config(
+        MemoryStore::with_pois(pois),
+        UnitTravelTimeProvider,
+        TagScorer,
+        VrpSolverConfig {
+            max_generations: 1,
+            ..config
+        },
+    );
+    let warm_request = SolveRequest {
+        initial_route: previous
+            .route
+            .pois()
+            .iter()
+            .map(|poi| poi.id)
+            .chain([99])
+            .collect(),
+        ..request
+    };
+    let response = hurried
+        .solve(&warm_request)
+        .expect("warm solve should succeed");
+
+    assert!(response.score >= previous.score);
+}
+
+#[rstest]
+fn replanning_keeps_the_visited_stops_first() {
+    let pois = vec![
+        poi(1, 0.0, 0.0, "art"),
+        poi(2, 0.0001, 0.0, "art"),
+        poi(3, 0.0002, 0.0, "art"),
+        poi(4, 0.0003, 0.0, "art"),
+    ];
+    let solver = VrpSolver::with_config(
+        MemoryStore::with_pois(pois),
+        UnitTravelTimeProvider,
+        TagScorer,
+        VrpSolverConfig {
+            dwell: DwellTimePolicy::new(Duration::from_mins(4)),
+            ..VrpSolverConfig::default()
+        },
+    );
+    // Fifteen minutes remain, enough for the three unvisited stops.
+    let request = SolveRequest {
+        duration_minutes: 20,
+        max_nodes: None,
+        visited: Some(VisitedPrefix {
+            poi_ids: vec![1],
+            position: Coord { x: 0.0, y: 0.0 },
+            elapsed_minutes: 5,
+        }),
+        ..required_request(Vec::new())
+    };
+
+    let response = solver.solve(&request).expect("replan should succeed");
+
+    let ids: Vec<u64> = response.route.pois().iter().map(|poi| poi.id).collect();
+    assert_eq!(ids.first(), Some(&1));
+    let mut sorted = ids.clone();
+    sorted.sort_unstable();
+    assert_eq!(sorted, vec![1, 2, 3, 4]);
+    assert!(
+        (3.999..=4.001).contains(&response.score),
+        "replanned score {} should count every stop",
+        response.score
+    );
+    assert_eq!(response.route.start(), request.start);
+    assert!(response.route.total_duration() > Duration::from_mins(5));
+    assert_eq!(
+        response.route.legs().first().map(|leg| leg.from),
+        Some(RouteStop::Poi(1))
+    );
+}
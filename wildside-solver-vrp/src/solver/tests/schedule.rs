@@ -0,0 +1,171 @@
+//! Tests for dwell times, opening hours, and rest breaks.
+
+use super::*;
+
+#[rstest]
+fn solve_returns_route_with_positive_score() {
+    let pois = vec![poi(1, 0.0, 0.0, "art"), poi(2, 0.001, 0.0, "history")];
+    let store = MemoryStore::with_pois(pois);
+    let solver = VrpSolver::new(store, UnitTravelTimeProvider, TagScorer);
+    let interests = InterestProfile::new()
+        .with_weight(Theme::Art, 0.8)
+        .with_weight(Theme::History, 0.5);
+    let request = SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes: 10,
+        interests,
+        seed: 1,
+        ..Default::default()
+    };
+
+    let response = solver.solve(&request).expect("solve should succeed");
+    assert!(!response.route.pois().is_empty());
+    assert!(response.score > 0.0);
+    assert!(response.route.total_duration() <= Duration::from_mins(10));
+}
+
+#[rstest]
+fn dwell_time_counts_against_the_budget() {
+    let pois = vec![poi(1, 0.0, 0.0, "art"), poi(2, 0.001, 0.0, "art")];
+    let solver = VrpSolver::with_config(
+        MemoryStore::with_pois(pois),
+        UnitTravelTimeProvider,
+        TagScorer,
+        VrpSolverConfig {
+            dwell: DwellTimePolicy::new(Duration::from_mins(6)),
+            ..VrpSolverConfig::default()
+        },
+    );
+    let request = SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes: 10,
+        interests: InterestProfile::new().with_weight(Theme::Art, 1.0),
+        seed: 1,
+        ..Default::default()
+    };
+
+    let response = solver.solve(&request).expect("solve should succeed");
+
+    // Walking between the POIs takes seconds, but two six-minute visits do
+    // not fit in ten minutes.
+    assert_eq!(response.route.pois().len(), 1);
+    assert!(response.route.total_duration() >= Duration::from_mins(6));
+    assert!(response.route.total_duration() <= Duration::from_mins(10));
+}
+
+#[rstest]
+#[case(Some("Sa 10:00"), vec![1])]
+#[case(None, vec![1, 2])]
+fn closed_pois_are_skipped_when_the_start_time_is_known(
+    #[case] start_time: Option<&str>,
+    #[case] expected: Vec<u64>,
+) {
+    let mut afternoon_gallery = poi(2, 0.001, 0.0, "art");
+    afternoon_gallery
+        .tags
+        .insert("opening_hours".to_owned(), "Sa 12:00-18:00".to_owned());
+    let pois = vec![poi(1, 0.0, 0.0, "art"), afternoon_gallery];
+    let solver = VrpSolver::new(
+        MemoryStore::with_pois(pois),
+        UnitTravelTimeProvider,
+        TagScorer,
+    );
+    let request = SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes: 60,
+        interests: InterestProfile::new().with_weight(Theme::Art, 1.0),
+        seed: 1,
+        start_time: start_time.map(|time| time.parse().expect("valid start time")),
+        ..Default::default()
+    };
+
+    let response = solver.solve(&request).expect("solve should succeed");
+
+    let mut ids: Vec<u64> = response.route.pois().iter().map(|poi| poi.id).collect();
+    ids.sort_unstable();
+    assert_eq!(ids, expected);
+}
+
+#[rstest]
+fn absolute_start_times_check_hours_and_stamp_arrivals() {
+    let mut afternoon_gallery = poi(2, 0.001, 0.0, "art");
+    afternoon_gallery
+        .tags
+        .insert("opening_hours".to_owned(), "Sa 12:00-18:00".to_owned());
+    let pois = vec![poi(1, 0.0, 0.0, "art"), afternoon_gallery];
+    let solver = VrpSolver::new(
+        MemoryStore::with_pois(pois),
+        UnitTravelTimeProvider,
+        TagScorer,
+    );
+    let request = SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes: 60,
+        interests: InterestProfile::new().with_weight(Theme::Art, 1.0),
+        seed: 1,
+        start_at: Some("2026-10-17T10:00:00+02:00".parse().expect("valid start")),
+        ..Default::default()
+    };
+
+    let response = solver.solve(&request).expect("solve should succeed");
+
+    let ids: Vec<u64> = response.route.pois().iter().map(|poi| poi.id).collect();
+    assert_eq!(ids, vec![1]);
+    let arrivals: Vec<String> = response
+        .route
+        .legs()
+        .iter()
+        .filter_map(|leg| leg.arrival_at.map(|at| at.to_string()))
+        .collect();
+    assert_eq!(
+        arrivals,
+        ["2026-10-17T10:00:01+02:00", "2026-10-17T10:00:02+02:00"]
+    );
+}
+
+#[rstest]
+#[case::no_break(None, false)]
+#[case::lunch(Some(RestBreak::lunch()), true)]
+fn rest_breaks_stop_at_a_cafe_during_their_window(
+    #[case] rest_break: Option<RestBreak>,
+    #[case] rests: bool,
+) {
+    let cafe = PointOfInterest::new(
+        2,
+        Coord { x: 0.001, y: 0.0 },
+        Tags::from([("amenity".to_owned(), "cafe".to_owned())]),
+    );
+    let pois = vec![poi(1, 0.0, 0.0, "art"), cafe];
+    let solver = VrpSolver::new(
+        MemoryStore::with_pois(pois),
+        UnitTravelTimeProvider,
+        TagScorer,
+    );
+    let request = SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        duration_minutes: 120,
+        interests: InterestProfile::new().with_weight(Theme::Art, 1.0),
+        seed: 1,
+        start_time: Some("Sa 11:00".parse().expect("valid start time")),
+        rest_break,
+        ..Default::default()
+    };
+
+    let response = solver.solve(&request).expect("solve should succeed");
+
+    let rest = response
+        .route
+        .legs()
+        .iter()
+        .find(|leg| leg.to == RouteStop::Poi(2));
+    assert_eq!(rest.is_some(), rests);
+    if let Some(leg) = rest {
+        // Lunch may not begin before 11:30, half an hour into the walk.
+        assert!(
+            leg.cumulative_duration >= Duration::from_mins(60),
+            "break ended after {:?}",
+            leg.cumulative_duration
+        );
+    }
+    assert!(response.route.pois().iter().any(|poi| poi.id == 1));
+}
@@ -0,0 +1,136 @@
+//! Tests for alternative routes, budget frontiers, and multiple starts.
+
+use super::*;
+
+#[rstest]
+fn alternatives_visit_different_pois() {
+    let pois = vec![
+        poi(1, 0.0, 0.0, "art"),
+        poi(2, 0.0001, 0.0, "art"),
+        poi(3, 0.0002, 0.0, "art"),
+        poi(4, 0.0003, 0.0, "art"),
+    ];
+    // Four-minute visits leave room for two stops in the ten-minute budget.
+    let solver = VrpSolver::with_config(
+        MemoryStore::with_pois(pois),
+        UnitTravelTimeProvider,
+        TagScorer,
+        VrpSolverConfig {
+            dwell: DwellTimePolicy::new(Duration::from_mins(4)),
+            ..VrpSolverConfig::default()
+        },
+    );
+    let request = SolveRequest {
+        max_nodes: None,
+        ..required_request(Vec::new())
+    };
+
+    let responses = solver
+        .solve_alternatives(&request, 2)
+        .expect("alternatives should solve");
+
+    let [first, second] = responses.as_slice() else {
+        panic!("expected two alternatives, got {}", responses.len());
+    };
+    assert_eq!(first.route.pois().len(), 2);
+    let overlap = first.route.overlap(&second.route);
+    assert!(
+        overlap <= 0.0,
+        "alternatives share stops: overlap {overlap}"
+    );
+    assert!(first.score >= second.score);
+}
+
+#[rstest]
+fn zero_alternatives_only_validate_the_request() {
+    let solver = VrpSolver::new(
+        MemoryStore::with_pois(vec![poi(1, 0.0, 0.0, "art")]),
+        TracingProvider { fail: true },
+        TagScorer,
+    );
+
+    let responses = solver
+        .solve_alternatives(&required_request(Vec::new()), 0)
+        .expect("validation should pass");
+
+    assert!(responses.is_empty());
+}
+
+#[rstest]
+fn frontier_keeps_budgets_that_add_stops() {
+    let pois = vec![
+        poi(1, 0.0, 0.0, "art"),
+        poi(2, 0.0001, 0.0, "art"),
+        poi(3, 0.0002, 0.0, "art"),
+        poi(4, 0.0003, 0.0, "art"),
+    ];
+    let solver = VrpSolver::with_config(
+        MemoryStore::with_pois(pois),
+        UnitTravelTimeProvider,
+        TagScorer,
+        VrpSolverConfig {
+            dwell: DwellTimePolicy::new(Duration::from_mins(4)),
+            ..VrpSolverConfig::default()
+        },
+    );
+    let request = SolveRequest {
+        max_nodes: None,
+        ..required_request(Vec::new())
+    };
+
+    // Twenty minutes already fit all four stops, so thirty adds nothing.
+    let frontier = solver
+        .solve_frontier(&request, &[30, 5, 10, 20])
+        .expect("frontier should solve");
+
+    let stops: Vec<usize> = frontier
+        .iter()
+        .map(|response| response.route.pois().len())
+        .collect();
+    assert_eq!(stops, vec![1, 2, 4]);
+}
+
+#[rstest]
+fn frontier_rejects_zero_budgets() {
+    let solver = VrpSolver::new(
+        MemoryStore::with_pois(vec![poi(1, 0.0, 0.0, "art")]),
+        UnitTravelTimeProvider,
+        TagScorer,
+    );
+
+    let err = solver
+        .solve_frontier(&required_request(Vec::new()), &[10, 0])
+        .expect_err("zero budget should be rejected");
+
+    assert!(matches!(err, SolveError::InvalidRequest));
+}
+
+#[rstest]
+fn multi_start_picks_the_start_with_the_best_route() {
+    let solver = VrpSolver::new(
+        MemoryStore::with_pois(vec![poi(1, 0.0, 0.0, "art")]),
+        UnitTravelTimeProvider,
+        TagScorer,
+    );
+    let far = Coord { x: 10.0, y: 10.0 };
+    let near = Coord { x: 0.0, y: 0.0 };
+
+    let choice = solver
+        .solve_from_starts(&required_request(Vec::new()), &[far, near])
+        .expect("some start should solve");
+
+    assert_eq!(choice.start_index, 1);
+    assert_eq!(choice.response.route.start(), near);
+    assert_eq!(choice.response.route.pois().len(), 1);
+}
+
+#[rstest]
+fn multi_start_rejects_an_empty_start_list() {
+    let solver = progress_solver();
+
+    let err = solver
+        .solve_from_starts(&required_request(Vec::new()), &[])
+        .expect_err("no starts should be rejected");
+
+    assert!(matches!(err, SolveError::InvalidRequest));
+}
@@ -0,0 +1,125 @@
+//! Searching one set of candidates several times over.
+//!
+//! Alternative routes restart the search with new seeds and discounted
+//! scores; budget frontiers search once per budget. Both fetch candidates
+//! and travel times once and share them between searches.
+
+use wildside_core::{
+    PoiStore, Route, Scorer, SolveError, SolveRequest, SolveResponse, TravelTimeProvider,
+    pareto_frontier,
+};
+
+use super::search::SearchPlan;
+use super::{Prepared, SolveControl, VrpSolver};
+use crate::alternatives::route_score;
+use crate::vrp::Found;
+
+impl<S, T, C> VrpSolver<S, T, C>
+where
+    S: PoiStore + Send + Sync,
+    T: TravelTimeProvider + Send + Sync,
+    C: Scorer + Send + Sync,
+{
+    /// Search `count` times over, keeping routes distinct from those already
+    /// accepted, and return them best first.
+    ///
+    /// Tours under way look for alternative remainders.
+    pub(super) fn find_alternatives(
+        &self,
+        request: &SolveRequest,
+        count: usize,
+        control: &SolveControl,
+    ) -> Result<Vec<SolveResponse>, SolveError> {
+        request.validate()?;
+        if let Some(remainder) = request.remainder() {
+            return self
+                .find_alternatives(&remainder, count, control)?
+                .into_iter()
+                .map(|response| self.lock_prefix(request, response))
+                .collect();
+        }
+        let space = match self.prepare(request, control)? {
+            Prepared::Search(space) => space,
+            Prepared::Answered(response) => return Ok(vec![response]),
+        };
+        let policy = self.config.alternatives;
+        let routing = &space.routing;
+        let mut accepted: Vec<SolveResponse> = Vec::new();
+        let mut accepted_routes: Vec<Route> = Vec::new();
+        for attempt in (0_u64..).take(policy.restarts(count)) {
+            if accepted.len() == count {
+                break;
+            }
+            let scores =
+                policy.discounted_scores(&routing.candidates, &routing.scores, &accepted_routes);
+            let plan = SearchPlan {
+                scores: &scores,
+                seed: request.seed.wrapping_add(attempt),
+                control: control.restart(),
+            };
+            let found = self.search(request, routing, plan)?;
+            control.cancel.check()?;
+            if request.ensure_required(&found.pois).is_err() {
+                continue;
+            }
+            let score = route_score(&routing.candidates, &routing.scores, &found.pois);
+            let Ok(response) = self.respond(request, &space, Found { score, ..found }) else {
+                continue;
+            };
+            if policy.is_distinct(&response.route, accepted_routes.iter()) {
+                accepted_routes.push(response.route.clone());
+                accepted.push(response);
+            }
+        }
+        if accepted.is_empty() {
+            return Err(SolveError::InfeasibleRequirement);
+        }
+        accepted.sort_by(|lhs, rhs| rhs.score.total_cmp(&lhs.score));
+        Ok(accepted)
+    }
+
+    /// Search each of the `budgeted` requests, which differ only in duration,
+    /// and keep the Pareto frontier.
+    ///
+    /// Tours under way re-plan the remainder of each budget.
+    pub(super) fn find_frontier(
+        &self,
+        budgeted: &[SolveRequest],
+        control: &SolveControl,
+    ) -> Result<Vec<SolveResponse>, SolveError> {
+        let planned: Vec<SolveRequest> = budgeted
+            .iter()
+            .map(|request| request.remainder().unwrap_or_else(|| request.clone()))
+            .collect();
+        let Some((longest, whole)) = planned
+            .iter()
+            .zip(budgeted)
+            .max_by_key(|(request, _)| request.duration_minutes)
+        else {
+            return Ok(Vec::new());
+        };
+        let space = match self.prepare(longest, control)? {
+            Prepared::Search(space) => space,
+            Prepared::Answered(response) => return Ok(vec![self.lock_prefix(whole, response)?]),
+        };
+        let mut responses = Vec::with_capacity(budgeted.len());
+        for (request, original) in planned.iter().zip(budgeted) {
+            let plan = SearchPlan {
+                scores: &space.routing.scores,
+                seed: request.seed,
+                control: control.restart(),
+            };
+            let found = self.search(request, &space.routing, plan)?;
+            control.cancel.check()?;
+            if request.ensure_required(&found.pois).is_ok()
+                && let Ok(response) = self.respond(request, &space, found)
+            {
+                responses.push(self.lock_prefix(original, response)?);
+            }
+        }
+        if responses.is_empty() {
+            return Err(SolveError::InfeasibleRequirement);
+        }
+        Ok(pareto_frontier(responses))
+    }
+}
@@ -30,9 +30,11 @@ pub fn poi(id: u64, x: f64, y: f64, theme: &str) -> PointOfInterest {
 /// A [`TravelTimeProvider`] returning a fixed, pre-defined matrix.
 ///
 /// This provider enables fully deterministic golden route tests by returning
-/// a caller-supplied travel time matrix verbatim. The matrix must match the
-/// number of POIs passed to [`get_travel_time_matrix`]; dimension mismatches
-/// produce a [`TravelTimeError::ServiceError`] with code `DIMENSION_MISMATCH`.
+/// a caller-supplied travel time matrix verbatim. The rows are checked when
+/// the matrix is requested rather than on construction, so fixtures can
+/// exercise shape errors: the matrix must be square and match the number of
+/// POIs passed to [`get_travel_time_matrix`], and mismatches produce a
+/// [`TravelTimeError::ServiceError`] with code `DIMENSION_MISMATCH`.
 ///
 /// # Examples
 ///
@@ -57,14 +59,14 @@ pub fn poi(id: u64, x: f64, y: f64, theme: &str) -> PointOfInterest {
 /// ```
 #[derive(Debug, Clone)]
 pub struct FixedMatrixTravelTimeProvider {
-    matrix: TravelTimeMatrix,
+    rows: Vec<Vec<Duration>>,
 }
 
 impl FixedMatrixTravelTimeProvider {
-    /// Construct a provider from a pre-built travel time matrix.
+    /// Construct a provider from pre-built travel time rows.
     #[must_use]
-    pub const fn new(matrix: TravelTimeMatrix) -> Self {
-        Self { matrix }
+    pub const fn new(rows: Vec<Vec<Duration>>) -> Self {
+        Self { rows }
     }
 
     /// Build from integer seconds for convenience in test fixtures.
@@ -82,11 +84,11 @@ impl FixedMatrixTravelTimeProvider {
     /// ```
     #[must_use]
     pub fn from_seconds(seconds: Vec<Vec<u64>>) -> Self {
-        let matrix = seconds
+        let rows = seconds
             .into_iter()
             .map(|row| row.into_iter().map(Duration::from_secs).collect())
             .collect();
-        Self { matrix }
+        Self { rows }
     }
 }
 
@@ -99,31 +101,22 @@ impl TravelTimeProvider for FixedMatrixTravelTimeProvider {
             return Err(TravelTimeError::EmptyInput);
         }
         let expected_dim = pois.len();
-        if self.matrix.len() != expected_dim {
+        if self.rows.len() != expected_dim {
             return Err(TravelTimeError::ServiceError {
                 code: "DIMENSION_MISMATCH".to_owned(),
                 message: format!(
                     "matrix has {} rows but {} POIs provided",
-                    self.matrix.len(),
+                    self.rows.len(),
                     expected_dim
                 ),
             });
         }
-        // Validate each row has the correct number of columns.
-        for (row_idx, row) in self.matrix.iter().enumerate() {
-            if row.len() != expected_dim {
-                return Err(TravelTimeError::ServiceError {
-                    code: "DIMENSION_MISMATCH".to_owned(),
-                    message: format!(
-                        "row {} has {} columns but {} expected (matrix must be square)",
-                        row_idx,
-                        row.len(),
-                        expected_dim
-                    ),
-                });
+        TravelTimeMatrix::try_from(self.rows.clone()).map_err(|error| {
+            TravelTimeError::ServiceError {
+                code: "DIMENSION_MISMATCH".to_owned(),
+                message: error.to_string(),
             }
-        }
-        Ok(self.matrix.clone())
+        })
     }
 }
 
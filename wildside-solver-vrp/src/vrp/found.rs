@@ -0,0 +1,96 @@
+//! Translates a `vrp-core` solution back into the stops of a route.
+
+use std::time::Duration;
+
+use vrp_core::models::Solution;
+use wildside_core::PointOfInterest;
+
+use super::{JobRestBreakDimension, VrpInstance};
+
+/// The stops of a route found by the search.
+#[derive(Debug, Default)]
+pub(crate) struct Found {
+    /// POIs visited, in order.
+    pub(crate) pois: Vec<PointOfInterest>,
+    /// Time spent at each of `pois`, including any rest break taken there.
+    pub(crate) dwell_times: Vec<Duration>,
+    /// Position in `pois` of the stop where the rest break is taken, if any.
+    pub(crate) rest_stop: Option<usize>,
+    /// Total score of `pois`.
+    pub(crate) score: f32,
+}
+
+impl Found {
+    /// The stops `solution` visits, in order.
+    pub(super) fn from_solution(instance: &VrpInstance<'_>, solution: &Solution) -> Self {
+        let mut found = Self::default();
+        for activity in solution
+            .routes
+            .iter()
+            .flat_map(|route| route.tour.all_activities())
+        {
+            // The depot and route end carry no job.
+            let Some(single) = activity.job.as_deref() else {
+                continue;
+            };
+            let Some(idx) = activity.place.location.checked_sub(1) else {
+                continue;
+            };
+            let Some(poi) = instance.candidates.get(idx) else {
+                continue;
+            };
+            let score = instance.scores.get(idx).copied().unwrap_or(0.0_f32);
+            if single.dimens.get_job_rest_break().copied().unwrap_or(false) {
+                found.rest_at(poi, score, instance.rest_break);
+            } else {
+                let dwell = instance.dwell_times.get(idx).copied().unwrap_or_default();
+                found.visit(poi, score, dwell);
+            }
+        }
+
+        found
+    }
+
+    /// Visit `poi`, collecting `score` and spending `dwell` there.
+    fn visit(&mut self, poi: &PointOfInterest, score: f32, dwell: Duration) {
+        if self.extend_last(poi, dwell).is_none() {
+            self.push(poi, score, dwell);
+        }
+    }
+
+    /// Take the rest break, lasting `duration`, at `poi`.
+    ///
+    /// A break at the POI just visited extends that stop rather than
+    /// returning to it. Otherwise the break is a stop of its own, and the
+    /// venue's score is collected even though it was not worth a visit.
+    fn rest_at(&mut self, poi: &PointOfInterest, score: f32, duration: Duration) {
+        let stop = self
+            .extend_last(poi, duration)
+            .unwrap_or_else(|| self.push(poi, score, duration));
+        self.rest_stop = Some(stop);
+    }
+
+    /// Append a stop at `poi`, returning its position.
+    #[expect(
+        clippy::float_arithmetic,
+        reason = "route scores sum floating-point POI scores"
+    )]
+    fn push(&mut self, poi: &PointOfInterest, score: f32, dwell: Duration) -> usize {
+        self.pois.push(poi.clone());
+        self.dwell_times.push(dwell);
+        self.score += score;
+        self.pois.len() - 1
+    }
+
+    /// Add `dwell` to the last stop when it is at `poi`, returning its
+    /// position.
+    fn extend_last(&mut self, poi: &PointOfInterest, dwell: Duration) -> Option<usize> {
+        let last = self.pois.len().checked_sub(1)?;
+        if self.pois.get(last)?.id != poi.id {
+            return None;
+        }
+        let spent = self.dwell_times.get_mut(last)?;
+        *spent += dwell;
+        Some(last)
+    }
+}
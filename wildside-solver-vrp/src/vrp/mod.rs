@@ -0,0 +1,316 @@
+//! `vrp-core` modelling helpers for `VrpSolver`.
+//!
+//! This module converts scored POI candidates and a travel-time matrix into a
+//! `vrp-core` problem, runs the solver, and translates the resulting tour back
+//! into Wildside types.
+
+mod found;
+mod objective;
+mod problem;
+
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use geo::Coord;
+use vrp_core::models::Solution;
+use vrp_core::models::common::Location;
+use vrp_core::prelude::*;
+use vrp_core::rosomaxa::utils::ThreadPool;
+use wildside_core::{CancellationToken, PointOfInterest, SolveError, Theme, TravelTimeMatrix};
+
+use crate::cancel::with_cancellation;
+use crate::deadline::with_deadline;
+use crate::leg::max_leg_feature;
+use crate::progress::ProgressReporter;
+use crate::random::seeded_environment;
+use crate::search::seeded_heuristic;
+use crate::solver::VrpSolverConfig;
+use crate::warm_start::{TourCosts, hinted_tour, initial_solution};
+pub(crate) use found::Found;
+use objective::{ScoreObjective, define_goal};
+pub(crate) use objective::{is_worthless, solution_score, solution_stops};
+use problem::{ProblemSpec, TravelTimeTransportCost, define_problem};
+
+custom_dimension!(JobScore typeof Cost);
+custom_dimension!(JobRequired typeof bool);
+custom_dimension!(JobTheme typeof Theme);
+custom_dimension!(JobRestBreak typeof bool);
+
+/// Context for running a `vrp-core` solve with shared inputs.
+pub(super) struct VrpSolveContext<'a> {
+    config: &'a VrpSolverConfig,
+    /// Seed for every random decision the search makes.
+    seed: u64,
+    /// Instant after which the search stops at the next generation.
+    deadline: Option<Instant>,
+    /// Token whose cancellation stops the search at the next generation.
+    cancel: Option<CancellationToken>,
+}
+
+pub(super) struct VrpInstance<'a> {
+    candidates: &'a [PointOfInterest],
+    scores: &'a [f32],
+    /// Time spent visiting each candidate, parallel to `candidates`; missing
+    /// entries count as zero.
+    dwell_times: &'a [Duration],
+    /// Offsets from the start at which each candidate's visit may begin,
+    /// parallel to `candidates`; `None` or missing entries leave a candidate
+    /// unconstrained.
+    time_windows: &'a [Option<Vec<Range<Duration>>>],
+    /// Identifiers of candidates the route must visit.
+    required_ids: &'a [u64],
+    /// Category of each candidate for diversity penalties, parallel to
+    /// `candidates`; `None` or missing entries are uncategorised.
+    themes: &'a [Option<Theme>],
+    /// Coordinates of each location in `matrix` for backtracking penalties;
+    /// empty when they are disabled.
+    positions: &'a [Coord<f64>],
+    /// Identifiers of a previous route's stops, in order, to start from.
+    initial_route: &'a [u64],
+    /// Length of the rest break, taken at one of `rest_venues`.
+    rest_break: Duration,
+    /// Candidates where the rest break may be taken; empty when the route
+    /// takes none.
+    rest_venues: &'a [RestVenue],
+    matrix: &'a TravelTimeMatrix,
+    budget_seconds: Duration,
+    /// Longest walk allowed between consecutive stops, if any.
+    max_leg: Option<Duration>,
+}
+
+impl<'a> VrpInstance<'a> {
+    pub(super) const fn new(
+        candidates: &'a [PointOfInterest],
+        scores: &'a [f32],
+        matrix: &'a TravelTimeMatrix,
+        budget_seconds: Duration,
+    ) -> Self {
+        Self {
+            candidates,
+            scores,
+            dwell_times: &[],
+            time_windows: &[],
+            required_ids: &[],
+            themes: &[],
+            positions: &[],
+            initial_route: &[],
+            rest_break: Duration::ZERO,
+            rest_venues: &[],
+            matrix,
+            budget_seconds,
+            max_leg: None,
+        }
+    }
+
+    /// Set the visit duration of each candidate, while consuming `self`.
+    pub(super) const fn with_dwell_times(mut self, dwell_times: &'a [Duration]) -> Self {
+        self.dwell_times = dwell_times;
+        self
+    }
+
+    /// Restrict when each candidate's visit may begin, while consuming
+    /// `self`.
+    pub(super) const fn with_time_windows(
+        mut self,
+        time_windows: &'a [Option<Vec<Range<Duration>>>],
+    ) -> Self {
+        self.time_windows = time_windows;
+        self
+    }
+
+    /// Mark the candidates the route must visit, while consuming `self`.
+    pub(super) const fn with_required_ids(mut self, required_ids: &'a [u64]) -> Self {
+        self.required_ids = required_ids;
+        self
+    }
+
+    /// Set the category of each candidate, while consuming `self`.
+    pub(super) const fn with_themes(mut self, themes: &'a [Option<Theme>]) -> Self {
+        self.themes = themes;
+        self
+    }
+
+    /// Set the coordinates of each location in the matrix, while consuming
+    /// `self`.
+    pub(super) const fn with_positions(mut self, positions: &'a [Coord<f64>]) -> Self {
+        self.positions = positions;
+        self
+    }
+
+    /// Start the search from a previous route's stops, while consuming
+    /// `self`.
+    pub(super) const fn with_initial_route(mut self, initial_route: &'a [u64]) -> Self {
+        self.initial_route = initial_route;
+        self
+    }
+
+    /// Bias the route towards a rest break lasting `duration` at one of
+    /// `venues`, while consuming `self`.
+    pub(super) const fn with_rest_break(
+        mut self,
+        duration: Duration,
+        venues: &'a [RestVenue],
+    ) -> Self {
+        self.rest_break = duration;
+        self.rest_venues = venues;
+        self
+    }
+
+    /// Keep every leg of the route within `max_leg`, while consuming `self`.
+    pub(super) const fn with_max_leg(mut self, max_leg: Option<Duration>) -> Self {
+        self.max_leg = max_leg;
+        self
+    }
+
+    /// A solution visiting the stops of the initial route that still fit,
+    /// or `None` when none do.
+    fn warm_start(
+        &self,
+        problem: &Arc<Problem>,
+        environment: &Arc<Environment>,
+        end_location: Location,
+    ) -> Option<InsertionContext> {
+        let costs = TourCosts {
+            matrix: self.matrix,
+            dwell_times: self.dwell_times,
+            time_windows: self.time_windows,
+            budget: self.budget_seconds,
+            max_leg: self.max_leg,
+            end_location,
+        };
+        let tour = hinted_tour(self.initial_route, self.candidates, &costs);
+        if tour.is_empty() {
+            return None;
+        }
+        initial_solution(problem, environment.clone(), &tour)
+    }
+}
+
+impl<'a> VrpSolveContext<'a> {
+    /// Create a new solve context whose search replays `seed`.
+    pub(super) const fn new(config: &'a VrpSolverConfig, seed: u64) -> Self {
+        Self {
+            config,
+            seed,
+            deadline: None,
+            cancel: None,
+        }
+    }
+
+    /// Stop the search once `cancel` is cancelled, while consuming `self`.
+    pub(super) fn with_cancellation(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Stop the search once `deadline` passes, while consuming `self`.
+    pub(super) const fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+        self.deadline = deadline;
+        self
+    }
+
+    /// Solve the VRP instance using the provided candidates and matrix.
+    ///
+    /// When `progress` is set, each generation is reported through it and the
+    /// search stops early once the receiving observer accepts the best route.
+    pub(super) fn solve(
+        &self,
+        instance: &VrpInstance<'_>,
+        end_location: Location,
+        progress: Option<ProgressReporter>,
+    ) -> Result<Found, SolveError> {
+        let transport = Arc::new(TravelTimeTransportCost::new(instance.matrix));
+        let leg_limit = instance
+            .max_leg
+            .map(|max_leg| max_leg_feature(instance.matrix, max_leg))
+            .transpose()
+            .map_err(|error| SolveError::internal(error.to_string()))?;
+        let objective = ScoreObjective {
+            diversity: self.config.diversity,
+            overlap: self.config.overlap,
+            positions: instance.positions.to_vec(),
+        };
+        let goal = define_goal(transport.clone(), objective, leg_limit)
+            .map_err(|error| SolveError::internal(error.to_string()))?;
+        let problem_spec = ProblemSpec {
+            candidates: instance.candidates,
+            scores: instance.scores,
+            dwell_times: instance.dwell_times,
+            time_windows: instance.time_windows,
+            required_ids: instance.required_ids,
+            themes: instance.themes,
+            rest_break: instance.rest_break,
+            rest_venues: instance.rest_venues,
+            transport,
+            goal,
+            budget_seconds: instance.budget_seconds,
+            end_location,
+        };
+        let problem = Arc::new(
+            define_problem(problem_spec)
+                .map_err(|error| SolveError::internal(error.to_string()))?,
+        );
+
+        // Populations search on rayon's current pool, whose threads keep their
+        // shuffling state between solves. A fresh single-thread pool replays
+        // the same draws for every solve with the same seed.
+        let solution = ThreadPool::new(1).execute(|| {
+            let environment = seeded_environment(self.seed);
+            let initial = instance.warm_start(&problem, &environment, end_location);
+            self.search(problem, initial, progress)
+        })?;
+
+        Ok(Found::from_solution(instance, &solution))
+    }
+
+    /// Search `problem`, starting from `initial` when the previous route
+    /// still fits.
+    ///
+    /// The search draws from the warm start's seeded environment, or from a
+    /// fresh one when there is no warm start.
+    fn search(
+        &self,
+        problem: Arc<Problem>,
+        initial: Option<InsertionContext>,
+        progress: Option<ProgressReporter>,
+    ) -> Result<Solution, SolveError> {
+        let environment = initial.as_ref().map_or_else(
+            || seeded_environment(self.seed),
+            |insertion_ctx| insertion_ctx.environment.clone(),
+        );
+        let heuristic = seeded_heuristic(&problem, &environment);
+        let mut vrp_config = VrpConfigBuilder::new(problem.clone())
+            .set_environment(environment)
+            .set_heuristic(heuristic)
+            .prebuild()
+            .map_err(|error| SolveError::internal(error.to_string()))?
+            .with_init_solutions(initial.into_iter().collect(), None)
+            .with_max_generations(Some(self.config.max_generations))
+            .build()
+            .map_err(|error| SolveError::internal(error.to_string()))?;
+        // `EvolutionConfigBuilder` discards custom terminations, so wrap the
+        // built one instead.
+        if let Some(deadline) = self.deadline {
+            vrp_config.termination = with_deadline(vrp_config.termination, deadline);
+        }
+        if let Some(cancel) = &self.cancel {
+            vrp_config.termination = with_cancellation(vrp_config.termination, cancel.clone());
+        }
+        if let Some(reporter) = progress {
+            vrp_config.termination = reporter.wrap(vrp_config.termination);
+        }
+
+        vrp_core::solver::Solver::new(problem, vrp_config)
+            .solve()
+            .map_err(|error| SolveError::internal(error.to_string()))
+    }
+}
+
+/// A candidate where the rest break may be taken.
+pub(super) struct RestVenue {
+    /// Position of the venue among the candidates.
+    pub(super) index: usize,
+    /// Offsets from the start at which the break may begin there.
+    pub(super) windows: Vec<Range<Duration>>,
+}
@@ -0,0 +1,242 @@
+//! The objective `vrp-core` minimises: collected score, negated, plus
+//! diversity and backtracking penalties.
+
+use std::sync::Arc;
+
+use geo::Coord;
+use vrp_core::models::solution::{Activity, Tour};
+use vrp_core::prelude::*;
+use wildside_core::{DiversityPolicy, OverlapPolicy, Theme};
+
+use super::{JobRequiredDimension, JobRestBreakDimension, JobScoreDimension, JobThemeDimension};
+use crate::random::CanonicalJobOrder;
+
+/// Objective bonus for visiting a required POI.
+///
+/// It dwarfs any achievable score, so the search trades every optional POI
+/// for a required one.
+const REQUIRED_JOB_BONUS: Cost = 1_000_000_000.0;
+
+/// Objective bonus for a route's first rest break; further breaks earn
+/// nothing.
+///
+/// It outweighs any achievable score but not a required POI, so the search
+/// gives up optional POIs to take the break and never a required one.
+const REST_BREAK_BONUS: Cost = 1_000_000.0;
+
+/// Collected score, negated so `vrp-core` can minimise it, plus diversity
+/// and backtracking penalties.
+pub(super) struct ScoreObjective {
+    pub(super) diversity: DiversityPolicy,
+    pub(super) overlap: OverlapPolicy,
+    /// Coordinates of each matrix location for backtracking penalties;
+    /// empty when they are disabled.
+    pub(super) positions: Vec<Coord<f64>>,
+}
+
+#[expect(
+    clippy::float_arithmetic,
+    reason = "objective cost combines floating-point scores and penalties"
+)]
+impl FeatureObjective for ScoreObjective {
+    fn fitness(&self, solution: &InsertionContext) -> Cost {
+        solution
+            .solution
+            .routes
+            .iter()
+            .map(|route_ctx| {
+                let tour = &route_ctx.route().tour;
+                // Summed in visiting order: `Tour::jobs` follows allocation
+                // addresses, and a different order rounds differently.
+                let collected: Cost = tour
+                    .all_activities()
+                    .filter_map(Activity::retrieve_job)
+                    .filter(|job| !is_rest_break(job))
+                    .map(|job| estimate_job_cost(&job))
+                    .sum();
+                let rest = if tour.jobs().any(is_rest_break) {
+                    -REST_BREAK_BONUS
+                } else {
+                    0.0
+                };
+                let themes = tour
+                    .all_activities()
+                    .filter(|activity| activity.job.is_some())
+                    .map(activity_theme);
+                let turns = self.overlap.route_penalty(
+                    tour.all_activities()
+                        .filter_map(|activity| self.position(activity)),
+                );
+                collected + rest + self.diversity.route_penalty(themes) + turns
+            })
+            .sum()
+    }
+
+    fn estimate(&self, move_ctx: &MoveContext<'_>) -> Cost {
+        match move_ctx {
+            MoveContext::Route { route_ctx, job, .. } => {
+                self.insertion_cost(&route_ctx.route().tour, job)
+            }
+            MoveContext::Activity { activity_ctx, .. } => {
+                let (prev, target, next) =
+                    (activity_ctx.prev, activity_ctx.target, activity_ctx.next);
+                self.theme_cost(prev, target, next) + self.turn_cost(prev, target, next)
+            }
+        }
+    }
+}
+
+#[expect(
+    clippy::float_arithmetic,
+    reason = "objective cost combines floating-point scores and penalties"
+)]
+impl ScoreObjective {
+    /// Objective cost of adding `job` to `tour`.
+    fn insertion_cost(&self, tour: &Tour, job: &Job) -> Cost {
+        if is_rest_break(job) {
+            // Only the first break of a route earns the bonus.
+            return if tour.jobs().any(is_rest_break) {
+                0.0
+            } else {
+                -REST_BREAK_BONUS
+            };
+        }
+        let theme = job.dimens().get_job_theme();
+        let repeats = theme.is_some_and(|current| {
+            tour.jobs()
+                .any(|other| other.dimens().get_job_theme() == Some(current))
+        });
+        let repeat_cost = if repeats {
+            self.diversity.repeat_penalty
+        } else {
+            0.0
+        };
+        estimate_job_cost(job) + repeat_cost
+    }
+
+    /// Consecutive-theme penalty change from visiting `target` between
+    /// `prev` and `next`.
+    fn theme_cost(&self, prev: &Activity, target: &Activity, next: Option<&Activity>) -> Cost {
+        let Some(current) = activity_theme(target) else {
+            return 0.0;
+        };
+        let before = activity_theme(prev);
+        let after = next.and_then(activity_theme);
+        let joined = u8::from(before == Some(current)) + u8::from(after == Some(current));
+        // Inserting between two stops of one theme separates them.
+        let separated = u8::from(before.is_some() && before == after);
+        (f64::from(joined) - f64::from(separated)) * self.diversity.consecutive_penalty
+    }
+
+    /// Backtracking penalty for turning at `target` between `prev` and
+    /// `next`.
+    ///
+    /// Inserting a stop also changes the turns at its neighbours, but those
+    /// depend on stops further along the tour, so the estimate leaves them
+    /// to the route's fitness.
+    fn turn_cost(&self, prev: &Activity, target: &Activity, next: Option<&Activity>) -> Cost {
+        match (
+            self.position(prev),
+            self.position(target),
+            next.and_then(|activity| self.position(activity)),
+        ) {
+            (Some(from), Some(via), Some(to)) => self.overlap.turn_penalty(from, via, to),
+            _ => 0.0,
+        }
+    }
+
+    /// Coordinates of the location `activity` visits, when backtracking is
+    /// penalised.
+    fn position(&self, activity: &Activity) -> Option<Coord<f64>> {
+        self.positions.get(activity.place.location).copied()
+    }
+}
+
+/// Theme of the POI an activity visits, if it visits a categorised POI.
+fn activity_theme(activity: &Activity) -> Option<&Theme> {
+    activity
+        .job
+        .as_deref()
+        .and_then(|single| single.dimens.get_job_theme())
+}
+
+#[expect(
+    clippy::float_arithmetic,
+    reason = "objective cost uses floating-point POI scores"
+)]
+fn estimate_job_cost(job: &Job) -> Cost {
+    let dimens = job.dimens();
+    let score = dimens.get_job_score().copied().unwrap_or(0.0);
+    if dimens.get_job_required().copied().unwrap_or(false) {
+        -(score + REQUIRED_JOB_BONUS)
+    } else {
+        -score
+    }
+}
+
+/// Whether `job` is a rest break rather than a POI visit.
+pub(super) fn is_rest_break(job: &Job) -> bool {
+    job.dimens().get_job_rest_break().copied().unwrap_or(false)
+}
+
+/// Whether visiting `job` only adds walking: an optional POI that scores
+/// nothing.
+pub(crate) fn is_worthless(job: &Job) -> bool {
+    !is_rest_break(job) && estimate_job_cost(job) >= 0.0
+}
+
+/// Total POI score collected by a `vrp-core` solution.
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "solution scores are narrowed to the public f32 score"
+)]
+pub(crate) fn solution_score(solution: &InsertionContext) -> f32 {
+    solution
+        .solution
+        .routes
+        .iter()
+        .flat_map(|route_ctx| route_ctx.route().tour.all_activities())
+        .filter_map(Activity::retrieve_job)
+        .filter_map(|job| job.dimens().get_job_score().copied())
+        .sum::<Cost>() as f32
+}
+
+/// Number of POI visits in a `vrp-core` solution, not counting rest breaks.
+pub(crate) fn solution_stops(solution: &InsertionContext) -> usize {
+    solution
+        .solution
+        .routes
+        .iter()
+        .map(|route_ctx| {
+            route_ctx
+                .route()
+                .tour
+                .jobs()
+                .filter(|job| !is_rest_break(job))
+                .count()
+        })
+        .sum()
+}
+
+/// Goal maximizing score, then minimizing travel time, with an optional
+/// leg-limit constraint.
+pub(super) fn define_goal(
+    transport: Arc<dyn TransportCost>,
+    objective: ScoreObjective,
+    leg_limit: Option<Feature>,
+) -> GenericResult<GoalContext> {
+    let transport_feature = TransportFeatureBuilder::new("min-travel-time")
+        .set_transport_cost(transport)
+        .set_time_constrained(true)
+        .build_minimize_distance()?;
+
+    let score_feature = FeatureBuilder::default()
+        .with_name("maximize-score")
+        .with_objective(objective)
+        .with_state(CanonicalJobOrder)
+        .build()?;
+
+    let mut features = vec![score_feature, transport_feature];
+    features.extend(leg_limit);
+    GoalContextBuilder::with_features(&features)?.build()
+}
@@ -0,0 +1,197 @@
+//! Builds the `vrp-core` problem: one job per candidate, rest break jobs,
+//! and travel times from the matrix.
+
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+
+use vrp_core::models::common::{Location, Profile, TimeWindow};
+use vrp_core::models::problem::TravelTime;
+use vrp_core::models::solution::Route as VrpRoute;
+use vrp_core::prelude::*;
+use wildside_core::{PointOfInterest, Theme, TravelTimeMatrix};
+
+use super::{
+    JobRequiredDimension, JobRestBreakDimension, JobScoreDimension, JobThemeDimension, RestVenue,
+};
+
+pub(super) struct ProblemSpec<'a> {
+    pub(super) candidates: &'a [PointOfInterest],
+    pub(super) scores: &'a [f32],
+    pub(super) dwell_times: &'a [Duration],
+    pub(super) time_windows: &'a [Option<Vec<Range<Duration>>>],
+    pub(super) required_ids: &'a [u64],
+    pub(super) themes: &'a [Option<Theme>],
+    pub(super) rest_break: Duration,
+    pub(super) rest_venues: &'a [RestVenue],
+    pub(super) transport: Arc<dyn TransportCost>,
+    pub(super) goal: GoalContext,
+    pub(super) budget_seconds: Duration,
+    pub(super) end_location: Location,
+}
+
+pub(super) fn define_problem(spec: ProblemSpec<'_>) -> GenericResult<Problem> {
+    let ProblemSpec {
+        candidates,
+        scores,
+        dwell_times,
+        time_windows,
+        required_ids,
+        themes,
+        rest_break,
+        rest_venues,
+        transport,
+        goal,
+        budget_seconds,
+        end_location,
+    } = spec;
+
+    debug_assert_eq!(
+        candidates.len(),
+        scores.len(),
+        "VRP problem invariant violated: candidates.len() != scores.len()"
+    );
+    if candidates.len() != scores.len() {
+        return Err("VRP problem invariant violated: candidates.len() != scores.len()".into());
+    }
+
+    let mut jobs = candidates
+        .iter()
+        .zip(scores.iter())
+        .enumerate()
+        .map(|(idx, (poi, score))| {
+            let location = idx + 1;
+            let dwell = dwell_times.get(idx).copied().unwrap_or_default();
+            let job = SingleBuilder::default()
+                .id(format!("poi{}", poi.id).as_str())
+                .dimension(|dimens| {
+                    dimens.set_job_score(Cost::from(*score));
+                    dimens.set_job_required(required_ids.contains(&poi.id));
+                    if let Some(theme) = themes.get(idx).cloned().flatten() {
+                        dimens.set_job_theme(theme);
+                    }
+                })
+                .location(location)?
+                .duration(dwell.as_secs_f64())?;
+            match time_windows.get(idx).and_then(Option::as_ref) {
+                Some(windows) => job.times(windows.iter().map(to_time_window).collect())?,
+                None => job,
+            }
+            .build_as_job()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    // Breaks follow the POI jobs so job `i` still visits candidate `i`.
+    jobs.extend(rest_break_jobs(candidates, rest_break, rest_venues)?);
+
+    let budget = budget_seconds.as_secs_f64();
+    let vehicle = VehicleBuilder::default()
+        .id("walker")
+        .add_detail(
+            VehicleDetailBuilder::default()
+                .set_start_location(0)
+                .set_start_time(0.0)
+                .set_end_location(end_location)
+                .set_end_time(budget)
+                .build()?,
+        )
+        .build()?;
+
+    ProblemBuilder::default()
+        .add_jobs(jobs.into_iter())
+        .add_vehicles(std::iter::once(vehicle))
+        .with_goal(goal)
+        .with_transport_cost(transport)
+        .build()
+}
+
+/// One job per venue for a rest break lasting `duration`, of which the
+/// objective rewards only the first a route takes.
+fn rest_break_jobs(
+    candidates: &[PointOfInterest],
+    duration: Duration,
+    venues: &[RestVenue],
+) -> GenericResult<Vec<Job>> {
+    venues
+        .iter()
+        .filter_map(|venue| candidates.get(venue.index).map(|poi| (poi, venue)))
+        .map(|(poi, venue)| {
+            SingleBuilder::default()
+                .id(format!("rest{}", poi.id).as_str())
+                .dimension(|dimens| {
+                    dimens.set_job_rest_break(true);
+                })
+                .location(venue.index + 1)?
+                .duration(duration.as_secs_f64())?
+                .times(venue.windows.iter().map(to_time_window).collect())?
+                .build_as_job()
+        })
+        .collect()
+}
+
+fn to_time_window(window: &Range<Duration>) -> TimeWindow {
+    TimeWindow::new(window.start.as_secs_f64(), window.end.as_secs_f64())
+}
+
+pub(super) struct TravelTimeTransportCost {
+    durations: Vec<Vec<f64>>,
+}
+
+impl TravelTimeTransportCost {
+    pub(super) fn new(matrix: &TravelTimeMatrix) -> Self {
+        let durations = matrix
+            .rows()
+            .map(|row| row.iter().map(Duration::as_secs_f64).collect())
+            .collect();
+        Self { durations }
+    }
+
+    fn duration_seconds(&self, from: Location, to: Location) -> f64 {
+        let from_idx = from;
+        let to_idx = to;
+        let result = self
+            .durations
+            .get(from_idx)
+            .and_then(|row| row.get(to_idx))
+            .copied();
+        debug_assert!(
+            result.is_some(),
+            "Matrix lookup failed: from={from_idx}, to={to_idx}"
+        );
+        result.unwrap_or(0.0)
+    }
+}
+
+impl TransportCost for TravelTimeTransportCost {
+    // `distance` and `duration` implement `vrp-core`'s `TransportCost` trait.
+    // The trait signature includes `route` and `departure` parameters even
+    // though this matrix-backed implementation does not use them. Other
+    // `TransportCost` implementations may have route-dependent or
+    // time-dependent costs, so these parameters are part of the shared API.
+    fn distance(
+        &self,
+        _route: &VrpRoute,
+        from: Location,
+        to: Location,
+        _departure: TravelTime,
+    ) -> Cost {
+        self.duration_seconds(from, to)
+    }
+
+    fn duration(
+        &self,
+        _route: &VrpRoute,
+        from: Location,
+        to: Location,
+        _departure: TravelTime,
+    ) -> f64 {
+        self.duration_seconds(from, to)
+    }
+
+    fn distance_approx(&self, profile: &Profile, from: usize, to: usize) -> f64 {
+        self.duration_approx(profile, from, to)
+    }
+
+    fn duration_approx(&self, _profile: &Profile, from: usize, to: usize) -> f64 {
+        self.duration_seconds(from, to)
+    }
+}
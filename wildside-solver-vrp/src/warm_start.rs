@@ -0,0 +1,291 @@
+//! Warm starts from a previous route.
+//!
+//! A request may carry [`SolveRequest::initial_route`], the stops of a route
+//! solved before a small change to the request. Walking those stops again
+//! under the new request's costs keeps the ones that still fit; `vrp-core`
+//! then starts its population from that tour instead of building every
+//! initial solution from scratch.
+//!
+//! [`SolveRequest::initial_route`]: wildside_core::SolveRequest::initial_route
+
+use std::ops::Range;
+use std::sync::Arc;
+use std::time::Duration;
+
+use vrp_core::models::common::{Schedule, TimeWindow};
+use vrp_core::models::problem::Single;
+use vrp_core::models::solution::{Activity, Place};
+use vrp_core::prelude::*;
+use wildside_core::{PointOfInterest, TravelTimeMatrix};
+
+use crate::leg::within_limit;
+
+/// Travel, visit, leg, and opening constraints a hinted tour is walked
+/// against.
+///
+/// Locations follow the VRP layout: the depot is location `0` and candidate
+/// `i` is location `i + 1`.
+pub(crate) struct TourCosts<'a> {
+    pub(crate) matrix: &'a TravelTimeMatrix,
+    /// Time spent at each candidate; missing entries count as zero.
+    pub(crate) dwell_times: &'a [Duration],
+    /// Offsets at which each candidate's visit may begin; `None` or missing
+    /// entries leave a candidate unconstrained.
+    pub(crate) time_windows: &'a [Option<Vec<Range<Duration>>>],
+    pub(crate) budget: Duration,
+    /// Longest walk allowed between consecutive stops, if any.
+    pub(crate) max_leg: Option<Duration>,
+    pub(crate) end_location: usize,
+}
+
+/// A stop of a hinted tour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct HintedStop {
+    /// Position of the stop among the candidates.
+    pub(crate) index: usize,
+    /// Offset from the start at which the visit begins.
+    pub(crate) start: Duration,
+}
+
+impl TourCosts<'_> {
+    /// When a visit to candidate `index` begins and ends, having left `from`
+    /// at `clock`, or `None` when it cannot be reached, is too far, or is
+    /// closed.
+    fn visit(&self, from: usize, index: usize, clock: Duration) -> Option<Range<Duration>> {
+        if !within_limit(self.matrix, self.max_leg, from, index + 1) {
+            return None;
+        }
+        let arrival = clock.checked_add(self.matrix.get(from, index + 1)?)?;
+        let start = match self.time_windows.get(index).and_then(Option::as_ref) {
+            Some(windows) => windows
+                .iter()
+                .filter(|window| window.end >= arrival)
+                .map(|window| window.start.max(arrival))
+                .min()?,
+            None => arrival,
+        };
+        let dwell = self.dwell_times.get(index).copied().unwrap_or_default();
+        Some(start..start.checked_add(dwell)?)
+    }
+
+    /// Report whether the route can still finish on time, within the leg
+    /// limit, after leaving `from` at `clock`.
+    fn can_finish(&self, from: usize, clock: Duration) -> bool {
+        within_limit(self.matrix, self.max_leg, from, self.end_location)
+            && self
+                .matrix
+                .get(from, self.end_location)
+                .and_then(|travel| clock.checked_add(travel))
+                .is_some_and(|finish| finish <= self.budget)
+    }
+}
+
+/// The `hint` stops that still make a feasible route, in hint order.
+///
+/// Stops that are no longer candidates, repeat an earlier stop, lie beyond
+/// the leg limit, or would miss their opening hours or the end of the budget
+/// are skipped, and the walk continues from the last stop kept.
+pub(crate) fn hinted_tour(
+    hint: &[u64],
+    candidates: &[PointOfInterest],
+    costs: &TourCosts<'_>,
+) -> Vec<HintedStop> {
+    let mut tour: Vec<HintedStop> = Vec::new();
+    let mut clock = Duration::ZERO;
+    let mut at = 0;
+    for id in hint {
+        let Some(index) = candidates.iter().position(|poi| poi.id == *id) else {
+            continue;
+        };
+        if tour.iter().any(|stop| stop.index == index) {
+            continue;
+        }
+        let Some(visit) = costs.visit(at, index, clock) else {
+            continue;
+        };
+        if costs.can_finish(index + 1, visit.end) {
+            tour.push(HintedStop {
+                index,
+                start: visit.start,
+            });
+            clock = visit.end;
+            at = index + 1;
+        }
+    }
+    tour
+}
+
+/// A `vrp-core` solution whose single route visits the jobs of `tour` in
+/// order, leaving every other job unassigned.
+///
+/// Jobs are indexed as the problem was built, one per candidate. Returns
+/// `None` when the problem has no vehicle or a stop has no located job.
+pub(crate) fn initial_solution(
+    problem: &Arc<Problem>,
+    environment: Arc<Environment>,
+    tour: &[HintedStop],
+) -> Option<InsertionContext> {
+    let mut insertion_ctx = InsertionContext::new_empty(problem.clone(), environment);
+    let mut route_ctx = insertion_ctx
+        .solution
+        .registry
+        .next_route()
+        .next()?
+        .deep_copy();
+    let jobs = problem.jobs.all();
+    let mut visited: Vec<Job> = Vec::with_capacity(tour.len());
+    for (position, stop) in tour.iter().enumerate() {
+        let job = jobs.get(stop.index)?;
+        let activity = visit_activity(job.as_single()?, stop.start)?;
+        route_ctx.route_mut().tour.insert_at(activity, position + 1);
+        visited.push(job.clone());
+    }
+    insertion_ctx.solution.registry.use_route(&route_ctx);
+    insertion_ctx.solution.routes.push(route_ctx);
+    insertion_ctx
+        .solution
+        .required
+        .retain(|job| !visited.contains(job));
+    insertion_ctx.restore();
+    Some(insertion_ctx)
+}
+
+/// An activity performing `single` at its location, in the opening window
+/// that holds `start`.
+///
+/// [`Activity::new_with_job`] leaves the place at the depot, which the route
+/// schedule and the returned route would then report.
+fn visit_activity(single: &Arc<Single>, start: Duration) -> Option<Activity> {
+    let place = single.places.first()?;
+    let begins = start.as_secs_f64();
+    let time = if place.times.is_empty() {
+        TimeWindow::max()
+    } else {
+        place
+            .times
+            .iter()
+            .map(|span| span.to_time_window(0.0))
+            .find(|window| window.contains(begins))?
+    };
+    Some(Activity {
+        place: Place {
+            idx: 0,
+            location: place.location?,
+            duration: place.duration,
+            time,
+        },
+        schedule: Schedule::new(begins, begins),
+        job: Some(single.clone()),
+        commute: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for walking hinted tours.
+
+    use super::*;
+    use geo::Coord;
+    use rstest::{fixture, rstest};
+
+    fn poi(id: u64) -> PointOfInterest {
+        PointOfInterest::with_empty_tags(id, Coord { x: 0.0, y: 0.0 })
+    }
+
+    /// Depot and three candidates, each a minute's walk from the others.
+    #[fixture]
+    fn matrix() -> TravelTimeMatrix {
+        TravelTimeMatrix::from_fn(4, |from, to| {
+            if from == to {
+                Duration::ZERO
+            } else {
+                Duration::from_mins(1)
+            }
+        })
+    }
+
+    fn indices(tour: &[HintedStop]) -> Vec<usize> {
+        tour.iter().map(|stop| stop.index).collect()
+    }
+
+    fn costs(matrix: &TravelTimeMatrix, budget: Duration) -> TourCosts<'_> {
+        TourCosts {
+            matrix,
+            dwell_times: &[],
+            time_windows: &[],
+            budget,
+            max_leg: None,
+            end_location: 0,
+        }
+    }
+
+    #[rstest]
+    fn hint_order_is_kept(matrix: TravelTimeMatrix) {
+        let candidates = [poi(10), poi(20), poi(30)];
+        let tour = hinted_tour(
+            &[30, 10, 20],
+            &candidates,
+            &costs(&matrix, Duration::from_hours(1)),
+        );
+        assert_eq!(indices(&tour), vec![2, 0, 1]);
+    }
+
+    #[rstest]
+    fn unknown_and_repeated_stops_are_skipped(matrix: TravelTimeMatrix) {
+        let candidates = [poi(10), poi(20), poi(30)];
+        let tour = hinted_tour(
+            &[10, 99, 10, 20],
+            &candidates,
+            &costs(&matrix, Duration::from_hours(1)),
+        );
+        assert_eq!(indices(&tour), vec![0, 1]);
+    }
+
+    #[rstest]
+    fn stops_past_the_budget_are_dropped(matrix: TravelTimeMatrix) {
+        let candidates = [poi(10), poi(20), poi(30)];
+        let tour = hinted_tour(
+            &[10, 20, 30],
+            &candidates,
+            &costs(&matrix, Duration::from_mins(3)),
+        );
+        assert_eq!(indices(&tour), vec![0, 1]);
+    }
+
+    #[rstest]
+    fn stops_beyond_the_leg_limit_are_skipped() {
+        // Candidate 30 is ten minutes' walk from everywhere else.
+        let matrix = TravelTimeMatrix::from_fn(4, |from, to| match (from, to) {
+            _ if from == to => Duration::ZERO,
+            (3, _) | (_, 3) => Duration::from_mins(10),
+            _ => Duration::from_mins(1),
+        });
+        let candidates = [poi(10), poi(20), poi(30)];
+        let tour_costs = TourCosts {
+            max_leg: Some(Duration::from_mins(5)),
+            ..costs(&matrix, Duration::from_hours(1))
+        };
+        let tour = hinted_tour(&[10, 30, 20], &candidates, &tour_costs);
+        assert_eq!(indices(&tour), vec![0, 1]);
+    }
+
+    #[rstest]
+    fn closed_stops_are_skipped_and_open_ones_wait(matrix: TravelTimeMatrix) {
+        let candidates = [poi(10), poi(20), poi(30)];
+        let time_windows = [
+            Some(vec![Duration::from_mins(5)..Duration::from_mins(6)]),
+            Some(Vec::new()),
+            None,
+        ];
+        let tour_costs = TourCosts {
+            time_windows: &time_windows,
+            ..costs(&matrix, Duration::from_mins(7))
+        };
+        let tour = hinted_tour(&[10, 20, 30], &candidates, &tour_costs);
+        assert_eq!(indices(&tour), vec![0, 2]);
+        assert_eq!(
+            tour.first().map(|stop| stop.start),
+            Some(Duration::from_mins(5))
+        );
+    }
+}
@@ -0,0 +1,76 @@
+//! Tests for the city-scale benchmark scenarios.
+//!
+//! These tests check the benchmark profiles and travel-time estimates, and that
+//! replaying a scenario's recorded travel times reproduces its route.
+
+use std::time::Duration;
+
+use geo::Coord;
+use rstest::rstest;
+use wildside_core::{PointOfInterest, Solver, TravelTimeProvider};
+
+/// Include the benchmark support modules from the benches directory.
+#[expect(dead_code, reason = "bench_support exports items not all used here")]
+#[path = "../benches/bench_support.rs"]
+mod bench_support;
+#[path = "../benches/scale_support.rs"]
+mod scale_support;
+
+use bench_support::BENCHMARK_SEED;
+use scale_support::{
+    SCALE_SIZES, Scenario, SolverProfile, StreetTravelTimeProvider, describe_quality,
+};
+
+#[rstest]
+fn scale_sizes_span_realistic_candidate_sets() {
+    assert_eq!(SCALE_SIZES.first(), Some(&500));
+    assert_eq!(SCALE_SIZES.last(), Some(&5_000));
+}
+
+#[rstest]
+fn clustered_profile_searches_more_candidates_than_it_routes() {
+    let profile = SolverProfile::Clustered;
+    let threshold = profile
+        .config()
+        .clustering
+        .threshold
+        .expect("clustered profile sets a threshold");
+    assert!(usize::from(profile.max_nodes()) > threshold);
+}
+
+#[rstest]
+fn street_travel_times_are_symmetric_walks() {
+    let pois = [
+        PointOfInterest::with_empty_tags(1, Coord { x: 0.05, y: 0.05 }),
+        PointOfInterest::with_empty_tags(2, Coord { x: 0.05, y: 0.06 }),
+    ];
+    let matrix = StreetTravelTimeProvider
+        .get_travel_time_matrix(&pois)
+        .expect("street travel times");
+
+    // 0.01° of latitude is about 1.1 km, or about 17 minutes with detours.
+    let walk = matrix.get(0, 1).expect("walk between the POIs");
+    assert!(walk > Duration::from_mins(15) && walk < Duration::from_mins(19));
+    assert_eq!(matrix.get(1, 0), Some(walk));
+    assert_eq!(matrix.get(0, 0), Some(Duration::ZERO));
+}
+
+#[rstest]
+fn replayed_scenarios_reproduce_the_recorded_route() {
+    for profile in SolverProfile::ALL {
+        let name = profile.name();
+        let scenario = Scenario::record(profile, 40, BENCHMARK_SEED)
+            .unwrap_or_else(|err| panic!("{name}: record scenario: {err:?}"));
+        assert!(!scenario.recording.is_empty(), "{name}: nothing recorded");
+
+        let replayed = scenario
+            .replay_solver()
+            .solve(&scenario.request)
+            .unwrap_or_else(|err| panic!("{name}: replay scenario: {err:?}"));
+        assert_eq!(
+            replayed.route, scenario.response.route,
+            "{name}: routes differ"
+        );
+        assert!(describe_quality(&replayed).starts_with("score "));
+    }
+}
@@ -9,13 +9,13 @@ Feature: VRP solver
     Then a route is returned containing in-bbox POIs
     And the route score is positive
 
-  Scenario: Travel time failures are surfaced as invalid request
+  Scenario: Travel time failures are surfaced as travel time errors
     Given a memory POI store with points near the origin
     And a failing travel time provider
     And a tag scorer
     And a valid solve request with interests
     When the VRP solver runs
-    Then the solve fails with InvalidRequest
+    Then the solve fails with a travel time error
 
   Scenario: No candidates yields empty route
     Given a memory POI store with no points near the origin
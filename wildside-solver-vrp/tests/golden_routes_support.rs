@@ -158,5 +158,6 @@ pub fn build_request(spec: &RequestSpec) -> SolveRequest {
         interests,
         seed: spec.seed,
         max_nodes: spec.max_nodes,
+        ..Default::default()
     }
 }
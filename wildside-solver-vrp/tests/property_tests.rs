@@ -15,6 +15,7 @@
 //!   core invariants and have correctly set start/end coordinates.
 //! - **Empty candidates:** When no candidates match, an empty route with zero score
 //!   is returned.
+//! - **Determinism:** Identical requests return identical routes.
 
 #![expect(
     clippy::cast_precision_loss,
@@ -29,35 +30,14 @@ use std::time::Duration;
 use geo::Coord;
 use proptest::prelude::*;
 use wildside_core::test_support::{MemoryStore, TagScorer, UnitTravelTimeProvider};
-use wildside_core::{InterestProfile, Scorer, Solver, Theme};
+use wildside_core::{Scorer, Solver};
 use wildside_solver_vrp::VrpSolver;
 
 use proptest_support::{
-    assert_no_duplicate_poi_ids, euclidean_distance, generate_pois_near_origin, poi_set_strategy,
+    assert_no_duplicate_poi_ids, build_request, euclidean_distance, generate_pois_near_origin,
+    poi_set_strategy,
 };
 
-/// Build a standard solve request for property tests.
-fn build_request(
-    duration_minutes: u16,
-    seed: u64,
-    max_nodes: Option<u16>,
-    end: Option<Coord<f64>>,
-) -> wildside_core::SolveRequest {
-    let interests = InterestProfile::new()
-        .with_weight(Theme::Art, 0.8)
-        .with_weight(Theme::History, 0.5)
-        .with_weight(Theme::Nature, 0.3)
-        .with_weight(Theme::Culture, 0.2);
-    wildside_core::SolveRequest {
-        start: Coord { x: 0.0, y: 0.0 },
-        end,
-        duration_minutes,
-        interests,
-        seed,
-        max_nodes,
-    }
-}
-
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(100))]
 
@@ -349,6 +329,36 @@ proptest! {
         }
     }
 
+    /// Property: Identical requests return identical routes.
+    ///
+    /// The request seed fixes every random decision in the search, so solving
+    /// twice, even with the candidates stored in a different order, must give
+    /// the same stops, score, and duration.
+    #[test]
+    fn identical_requests_return_identical_routes(
+        seed in any::<u64>(),
+        pois in poi_set_strategy(3, 15),
+    ) {
+        let mut reversed = pois.clone();
+        reversed.reverse();
+        let request = build_request(30, seed, None, None);
+
+        let first =
+            VrpSolver::new(MemoryStore::with_pois(pois), UnitTravelTimeProvider, TagScorer)
+                .solve(&request)
+                .expect("solve should succeed");
+        let second =
+            VrpSolver::new(MemoryStore::with_pois(reversed), UnitTravelTimeProvider, TagScorer)
+                .solve(&request)
+                .expect("solve should succeed");
+
+        let first_ids: Vec<u64> = first.route.pois().iter().map(|poi| poi.id).collect();
+        let second_ids: Vec<u64> = second.route.pois().iter().map(|poi| poi.id).collect();
+        prop_assert_eq!(first_ids, second_ids);
+        prop_assert_eq!(first.score.to_bits(), second.score.to_bits());
+        prop_assert_eq!(first.route.total_duration(), second.route.total_duration());
+    }
+
     /// Property: Empty candidate sets produce empty routes with zero score.
     ///
     /// When no POIs match the query, the solver should return an empty route
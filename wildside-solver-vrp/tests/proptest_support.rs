@@ -8,7 +8,7 @@ use std::collections::HashSet;
 
 use geo::Coord;
 use proptest::prelude::*;
-use wildside_core::{PointOfInterest, Tags, Theme};
+use wildside_core::{InterestProfile, PointOfInterest, SolveRequest, Tags, Theme};
 
 // Note: FixedMatrixTravelTimeProvider functions were removed since all property
 // tests now use UnitTravelTimeProvider for dynamic matrix generation.
@@ -132,3 +132,27 @@ pub fn assert_no_duplicate_poi_ids(
     );
     Ok(())
 }
+
+/// Build a standard solve request for property tests.
+#[must_use]
+pub fn build_request(
+    duration_minutes: u16,
+    seed: u64,
+    max_nodes: Option<u16>,
+    end: Option<Coord<f64>>,
+) -> SolveRequest {
+    let interests = InterestProfile::new()
+        .with_weight(Theme::Art, 0.8)
+        .with_weight(Theme::History, 0.5)
+        .with_weight(Theme::Nature, 0.3)
+        .with_weight(Theme::Culture, 0.2);
+    SolveRequest {
+        start: Coord { x: 0.0, y: 0.0 },
+        end,
+        duration_minutes,
+        interests,
+        seed,
+        max_nodes,
+        ..Default::default()
+    }
+}
@@ -1,6 +1,6 @@
 //! Behavioural tests for `VrpSolver` using rstest-bdd.
 
-use std::cell::RefCell;
+use std::cell::{Ref, RefCell};
 
 use geo::Coord;
 use rstest::fixture;
@@ -52,11 +52,10 @@ impl VrpWorld {
             provider: RefCell::new(ProviderChoice::default()),
             request: RefCell::new(SolveRequest {
                 start: Coord { x: 0.0, y: 0.0 },
-                end: None,
                 duration_minutes: 10,
                 interests: InterestProfile::new(),
                 seed: 1,
-                max_nodes: None,
+                ..Default::default()
             }),
             outcome: RefCell::new(None),
         }
@@ -66,12 +65,12 @@ impl VrpWorld {
         clippy::expect_used,
         reason = "behaviour tests use expect for readable failures"
     )]
-    fn expect_outcome(&self) -> Result<SolveResponse, SolveError> {
-        self.outcome
-            .borrow()
-            .as_ref()
-            .cloned()
-            .expect("outcome should be recorded before assertions")
+    fn expect_outcome(&self) -> Ref<'_, Result<SolveResponse, SolveError>> {
+        Ref::map(self.outcome.borrow(), |outcome| {
+            outcome
+                .as_ref()
+                .expect("outcome should be recorded before assertions")
+        })
     }
 }
 
@@ -116,11 +115,10 @@ fn given_valid_request(world: &VrpWorld) {
         .with_weight(Theme::History, 0.5);
     world.request.replace(SolveRequest {
         start: Coord { x: 0.0, y: 0.0 },
-        end: None,
         duration_minutes: 10,
         interests,
         seed: 1,
-        max_nodes: None,
+        ..Default::default()
     });
 }
 
@@ -140,7 +138,8 @@ fn when_solver_runs(world: &VrpWorld) {
     reason = "behaviour tests use expect for readable failures"
 )]
 fn then_route_returned(world: &VrpWorld) {
-    let response = world.expect_outcome().expect("expected solve success");
+    let outcome = world.expect_outcome();
+    let response = outcome.as_ref().expect("expected solve success");
     assert!(!response.route.pois().is_empty());
 }
 
@@ -150,20 +149,20 @@ fn then_route_returned(world: &VrpWorld) {
     reason = "behaviour tests use expect for readable failures"
 )]
 fn then_score_positive(world: &VrpWorld) {
-    let response = world.expect_outcome().expect("expected solve success");
+    let outcome = world.expect_outcome();
+    let response = outcome.as_ref().expect("expected solve success");
     assert!(response.score > 0.0);
 }
 
-#[then("the solve fails with InvalidRequest")]
+#[then("the solve fails with a travel time error")]
 #[expect(
     clippy::expect_used,
     reason = "behaviour tests use expect for readable failures"
 )]
-fn then_invalid_request(world: &VrpWorld) {
-    let err = world
-        .expect_outcome()
-        .expect_err("expected InvalidRequest error");
-    assert!(matches!(err, SolveError::InvalidRequest));
+fn then_travel_time_error(world: &VrpWorld) {
+    let outcome = world.expect_outcome();
+    let err = outcome.as_ref().expect_err("expected travel time error");
+    assert!(matches!(err, SolveError::TravelTime(_)));
 }
 
 #[then("an empty route is returned")]
@@ -173,7 +172,8 @@ fn then_invalid_request(world: &VrpWorld) {
     reason = "behaviour tests use expect and strict float checks for clarity"
 )]
 fn then_empty_route(world: &VrpWorld) {
-    let response = world.expect_outcome().expect("expected solve success");
+    let outcome = world.expect_outcome();
+    let response = outcome.as_ref().expect("expected solve success");
     assert!(response.route.pois().is_empty());
     assert_eq!(response.score, 0.0);
 }